@@ -0,0 +1,128 @@
+//! Measure compiling and running an interpreter-style dispatch loop over a
+//! large `br_table`, with and without `Config::compiler_br_table_range_split`.
+
+use criterion::*;
+use wasmtime::*;
+
+criterion_main!(benches);
+criterion_group!(benches, bench_br_table);
+
+/// Number of entries in the dispatch `br_table`.
+const TABLE_SIZE: u32 = 4096;
+
+/// Number of distinct opcode handlers.
+const HANDLERS: u32 = 16;
+
+/// Number of opcodes in the interpreted program.
+const PROGRAM_LEN: u32 = 1024;
+
+fn bench_br_table(c: &mut Criterion) {
+    let wat = dispatch_loop();
+    let configs = [("dense", 0), ("split-16", 16), ("split-64", 64)];
+
+    let mut group = c.benchmark_group("br_table/compile");
+    for (name, threshold) in configs {
+        let engine = engine(threshold);
+        group.bench_function(name, |b| {
+            b.iter(|| Module::new(&engine, &wat).unwrap());
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("br_table/run");
+    group.throughput(Throughput::Elements(u64::from(PROGRAM_LEN)));
+    for (name, threshold) in configs {
+        let engine = engine(threshold);
+        let module = Module::new(&engine, &wat).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        let run = instance
+            .get_typed_func::<i32, i32>(&mut store, "run")
+            .unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| run.call(&mut store, 1).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn engine(threshold: u32) -> Engine {
+    let mut config = Config::new();
+    config.compiler_br_table_range_split(threshold);
+    Engine::new(&config).unwrap()
+}
+
+/// Returns the handler an opcode dispatches to, or `None` for invalid opcodes.
+///
+/// Like the dispatch tables of real interpreters most entries are invalid and
+/// branch to the same place, with a dense range of opcodes at the start of the
+/// table and a small range of extension opcodes further in.
+fn handler(opcode: u32) -> Option<u32> {
+    match opcode {
+        0..256 => Some(opcode % HANDLERS),
+        1024..1040 => Some(opcode % 4),
+        _ => None,
+    }
+}
+
+/// Generates a module whose `run` export interprets a program of
+/// `PROGRAM_LEN` 16-bit opcodes stored in memory as many times as its argument.
+fn dispatch_loop() -> String {
+    let labels = (0..TABLE_SIZE)
+        .map(|op| match handler(op) {
+            Some(h) => format!("$h{h}"),
+            None => "$bad".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // A fixed pseudo-random program over the valid opcodes.
+    let mut state = 0x2545_f491_u32;
+    let mut program = String::new();
+    for _ in 0..PROGRAM_LEN {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let op = if state.is_multiple_of(8) {
+            1024 + (state >> 3) % 16
+        } else {
+            (state >> 3) % 256
+        };
+        program.push_str(&format!("\\{:02x}\\{:02x}", op & 0xff, op >> 8));
+    }
+
+    let mut body = format!(
+        "(block $bad (br_table {labels} $bad (i32.load16_u (local.get $pc))))\n\
+         unreachable\n"
+    );
+    for h in 0..HANDLERS {
+        body = format!(
+            "(block $h{h}\n{body})\n\
+             (local.set $acc (i32.add (i32.mul (local.get $acc) (i32.const {mul})) (i32.const {h})))\n\
+             (br $next)\n",
+            mul = 2 * h + 1,
+        );
+    }
+
+    format!(
+        r#"
+            (module
+                (memory 1)
+                (data (i32.const 0) "{program}")
+                (func (export "run") (param $n i32) (result i32)
+                    (local $pc i32) (local $acc i32)
+                    (loop $top
+                        (block $next
+                            {body}
+                        )
+                        (local.set $pc (i32.add (local.get $pc) (i32.const 2)))
+                        (if (i32.eq (local.get $pc) (i32.const {end}))
+                            (then
+                                (local.set $pc (i32.const 0))
+                                (local.set $n (i32.sub (local.get $n) (i32.const 1)))))
+                        (br_if $top (local.get $n)))
+                    (local.get $acc)))
+        "#,
+        end = PROGRAM_LEN * 2,
+    )
+}
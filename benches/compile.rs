@@ -1,11 +1,16 @@
 //! Measure the compilation time of files in `benches/compile`.
 //!
 //! Drop in new `*.wasm` or `*.wat` files in `benches/compile` to add
-//! benchmarks. To try new compilation configurations, modify [`Scenario`].
+//! benchmarks, for example modules from Sightglass. To try new compilation
+//! configurations, modify [`Scenario`]. Throughput is measured in bytes of
+//! compiled artifact, so criterion's reports compare the artifact sizes of
+//! scenarios along with their compilation times.
 
 use core::fmt;
 use criterion::measurement::WallTime;
-use criterion::{BenchmarkGroup, BenchmarkId, Criterion, criterion_group, criterion_main};
+use criterion::{
+    BenchmarkGroup, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main,
+};
 use std::path::Path;
 use wasmtime::*;
 
@@ -14,6 +19,7 @@ use wasmtime::*;
 struct Scenario {
     compiler: Strategy,
     opt_level: Option<OptLevel>,
+    native_unwind_info: bool,
 }
 
 impl fmt::Display for Scenario {
@@ -31,7 +37,11 @@ impl fmt::Display for Scenario {
             None => "none",
             _ => unreachable!(),
         };
-        write!(f, "[{compiler}:{opt_level}]")
+        write!(f, "[{compiler}:{opt_level}")?;
+        if !self.native_unwind_info {
+            write!(f, ":no_unwind_info")?;
+        }
+        write!(f, "]")
     }
 }
 
@@ -40,6 +50,14 @@ impl Scenario {
         Scenario {
             compiler,
             opt_level,
+            native_unwind_info: true,
+        }
+    }
+
+    fn without_native_unwind_info(self) -> Self {
+        Scenario {
+            native_unwind_info: false,
+            ..self
         }
     }
 
@@ -48,6 +66,7 @@ impl Scenario {
             Scenario::new(Strategy::Cranelift, Some(OptLevel::None)),
             Scenario::new(Strategy::Cranelift, Some(OptLevel::Speed)),
             Scenario::new(Strategy::Cranelift, Some(OptLevel::SpeedAndSize)),
+            Scenario::new(Strategy::Cranelift, Some(OptLevel::Speed)).without_native_unwind_info(),
             Scenario::new(Strategy::Winch, None),
         ]
     }
@@ -58,6 +77,7 @@ impl Scenario {
         if let Some(opt_level) = self.opt_level {
             config.cranelift_opt_level(opt_level);
         }
+        config.native_unwind_info(self.native_unwind_info);
         config
     }
 }
@@ -68,6 +88,10 @@ fn compile(group: &mut BenchmarkGroup<WallTime>, path: &Path, scenario: Scenario
     let bytes = std::fs::read(path).expect("failed to read file");
     let config = scenario.to_config();
     let engine = Engine::new(&config).expect("failed to create engine");
+    let artifact = engine
+        .precompile_module(&bytes)
+        .expect("failed to compile file");
+    group.throughput(Throughput::Bytes(artifact.len() as u64));
     group.bench_function(id, |b| {
         b.iter(|| Module::new(&engine, &bytes).unwrap());
     });
@@ -9,6 +9,7 @@ fn bench_traps(c: &mut Criterion) {
     bench_many_modules_registered_traps(c);
     bench_many_stack_frames_traps(c);
     bench_host_wasm_frames_traps(c);
+    bench_native_unwind_info_traps(c);
 }
 
 fn bench_multi_threaded_traps(c: &mut Criterion) {
@@ -212,6 +213,46 @@ fn bench_host_wasm_frames_traps(c: &mut Criterion) {
     group.finish()
 }
 
+fn bench_native_unwind_info_traps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("native-unwind-info-traps");
+
+    for native_unwind_info in [true, false] {
+        let mut config = Config::new();
+        config.native_unwind_info(native_unwind_info);
+        let engine = Engine::new(&config).unwrap();
+        let name = if native_unwind_info {
+            "with-unwind-info"
+        } else {
+            "without-unwind-info"
+        };
+
+        for num_stack_frames in vec![1, 64, 512] {
+            group.throughput(Throughput::Elements(num_stack_frames));
+            group.bench_with_input(
+                BenchmarkId::new(name, num_stack_frames),
+                &num_stack_frames,
+                |b, &num_stack_frames| {
+                    let module = module(&engine, num_stack_frames).unwrap();
+
+                    b.iter_custom(|iters| {
+                        let mut store = Store::new(&engine, ());
+                        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+                        let f = instance.get_typed_func::<(), ()>(&mut store, "").unwrap();
+
+                        let start = std::time::Instant::now();
+                        for _ in 0..iters {
+                            assert!(f.call(&mut store, ()).is_err());
+                        }
+                        start.elapsed()
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish()
+}
+
 fn module(engine: &Engine, num_funcs: u64) -> Result<Module> {
     let mut wat = String::new();
     wat.push_str("(module\n");
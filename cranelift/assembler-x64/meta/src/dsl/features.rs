@@ -92,6 +92,7 @@ pub enum Feature {
     avx512f,
     avx512vl,
     avx512dq,
+    avx512bw,
     avx512bitalg,
     avx512vbmi,
     cmpxchg16b,
@@ -123,6 +124,7 @@ pub const ALL_FEATURES: &[Feature] = &[
     Feature::avx512f,
     Feature::avx512vl,
     Feature::avx512dq,
+    Feature::avx512bw,
     Feature::avx512bitalg,
     Feature::avx512vbmi,
     Feature::cmpxchg16b,
@@ -66,5 +66,9 @@ pub fn list() -> Vec<Inst> {
         // * cvttps2pi
 
         inst("vcvtudq2ps", fmt("A", [w(xmm1), r(xmm_m128)]), evex(L128, Full)._f2()._0f().w0().op(0x7A).r(), _64b | avx512vl | avx512f),
+        inst("vcvttps2udq", fmt("A", [w(xmm1), r(xmm_m128)]), evex(L128, Full)._0f().w0().op(0x78).r(), (_64b | compat) & avx512vl & avx512f),
+        inst("vcvttpd2udq", fmt("A", [w(xmm1), r(xmm_m128)]), evex(L128, Full)._0f().w1().op(0x78).r(), (_64b | compat) & avx512vl & avx512f),
+        inst("vcvtqq2pd", fmt("A", [w(xmm1), r(xmm_m128)]), evex(L128, Full)._f3()._0f().w1().op(0xE6).r(), (_64b | compat) & avx512vl & avx512dq),
+        inst("vcvtuqq2pd", fmt("A", [w(xmm1), r(xmm_m128)]), evex(L128, Full)._f3()._0f().w1().op(0x7A).r(), (_64b | compat) & avx512vl & avx512dq),
     ]
 }
@@ -16,5 +16,13 @@ pub(crate) fn define() -> TargetIsa {
         "Whether this is a big-endian target",
         false,
     );
+    settings.add_bool(
+        "enable_peephole",
+        "Enable emission-time peephole optimizations",
+        "Rewrites short instruction sequences into Pulley's fused macro-ops \
+         and elides dead moves while encoding bytecode, reducing the number \
+         of instructions the interpreter has to dispatch.",
+        false,
+    );
     TargetIsa::new("pulley", settings.build())
 }
@@ -3253,6 +3253,20 @@ pub(crate) fn define(
         .operands_out(vec![Operand::new("a", IntTo)]),
     );
 
+    ig.push(
+        Inst::new(
+            "x86_cvtt2udq",
+            r#"
+        A float-to-integer conversion instruction for vectors-of-floats which
+        has the same semantics as `vcvttp{s,d}2udq` on x86. This specifically
+        returns `UINT_MAX` for NaN or out-of-bounds lanes.
+        "#,
+            &formats.unary,
+        )
+        .operands_in(vec![Operand::new("x", Float)])
+        .operands_out(vec![Operand::new("a", IntTo)]),
+    );
+
     let Int = &TypeVar::new(
         "Int",
         "A scalar or vector integer type",
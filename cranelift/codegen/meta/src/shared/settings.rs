@@ -67,6 +67,61 @@ pub(crate) fn define() -> SettingGroup {
         true,
     );
 
+    settings.add_bool(
+        "enable_bounds_check_elimination",
+        "Remove bounds checks that are implied by other bounds checks.",
+        r#"
+            This enables a pass that recognizes the explicit bounds checks that guard
+            sandboxed memory accesses, removes checks implied by a dominating check on
+            the same index, and performs a single check for a run of accesses at
+            constant offsets from the same index within a block.
+
+            The latter may report an out-of-bounds trap at an earlier access than the
+            one that is actually out of bounds; the trap code is the same and no
+            side effects are skipped. Only effective when `opt_level` is `speed` or
+            `speed_and_size`.
+        "#,
+        false,
+    );
+
+    settings.add_bool(
+        "enable_switch_recognition",
+        "Turn chains of conditional branches on the same value into jump tables.",
+        r#"
+            This enables a pass that recognizes chains of conditional branches that test
+            the same integer value against different constants, as emitted by frontends
+            that lower `switch` statements to sequences of equality comparisons, and
+            replaces each of them with a jump table when the constants are dense enough,
+            or with a binary search over the constants otherwise.
+
+            This makes dispatch to the cases at the end of a chain cheaper, but it no
+            longer tests the cases in their original order. Frontends often put the
+            most frequent cases first, and dispatch to those becomes more expensive.
+
+            Only effective when `opt_level` is `speed` or `speed_and_size`.
+        "#,
+        false,
+    );
+
+    settings.add_bool(
+        "enable_loop_unrolling",
+        "Unroll small loops with a constant trip count.",
+        r#"
+            This enables a pass that recognizes single-block loops whose exit condition
+            compares an induction variable, which starts at a constant and is incremented
+            by a constant, with a constant. Loops which are small enough in total are
+            fully unrolled. Otherwise loops with a small body have it repeated a few times
+            per iteration, with the iterations that don't divide evenly peeled off ahead
+            of the loop.
+
+            This grows the code of every loop it applies to, which only pays off when
+            the loop's branch or induction variable is a significant part of its cost.
+
+            Only effective when `opt_level` is `speed` or `speed_and_size`.
+        "#,
+        false,
+    );
+
     settings.add_bool(
         "enable_verifier",
         "Run the Cranelift IR verifier at strategic times during compilation.",
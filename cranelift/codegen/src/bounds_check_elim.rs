@@ -0,0 +1,571 @@
+//! Redundant bounds-check elimination.
+//!
+//! Frontends that compile sandboxed memory accesses (such as Wasmtime) guard
+//! each access with an explicit bounds check. The check comes in one of two
+//! shapes: either a `trapnz` on an out-of-bounds condition, or a
+//! `select_spectre_guard` that replaces the address with null when the
+//! condition holds, so that the (trapping) access itself faults:
+//!
+//! ```clif
+//! v10 = iconst.i64 8
+//! v11 = uadd_overflow_trap v0, v10, heap_oob
+//! v12 = icmp ugt v11, v1
+//! v13 = iconst.i64 0
+//! v14 = select_spectre_guard v12, v13, v2
+//! v15 = load.i32 heap_oob v14
+//! ```
+//!
+//! The out-of-bounds conditions recognized here all have the form
+//! `index + k > bound`, where `bound` is either a dynamic value or zero and
+//! `k` is a constant. (Comparisons against `bound - k` are not recognized,
+//! since nothing guarantees that the subtraction doesn't wrap.) Once an access
+//! guarded by such a condition has executed without trapping, any later access
+//! with the same `index` and `bound` and a `k` that is no larger is known to
+//! be in bounds. This pass performs two rewrites based on that fact:
+//!
+//! * **Subsumption.** When a check is dominated by an at-least-as-strong check
+//!   on the same index and bound, a `trapnz` is removed outright and a
+//!   `select_spectre_guard` is rewritten to use the dominating condition. The
+//!   new guard still has a data dependency on a real bounds check, so no new
+//!   opportunity for speculation is introduced, but it no longer needs its
+//!   own comparison. Accesses of the same address whose guards are rewritten
+//!   to the same condition share a single new guard. A `uadd_overflow_trap`
+//!   on the same index that cannot overflow given a dominating check becomes
+//!   a plain `iadd`.
+//!
+//! * **Widening.** Within a block, a run of accesses on the same index and
+//!   bound that trap with the same code -- for example the sequential,
+//!   constant-stride accesses of an unrolled loop body -- is guarded by the
+//!   strongest check of the run, performed at the run's first access. Every
+//!   other access in the run is then subsumed. This only moves a trap earlier
+//!   when nothing observable happens between the two program points: the run
+//!   is cut at calls, stores and instructions that may trap with a different
+//!   code, and a store can only be the last access of a run.
+//!
+//! Nothing is hoisted out of loops. The pass does not reason about
+//! loop-invariant addresses or induction variables, so every iteration of a
+//! loop still performs its own checks, and it does not remove the checks of
+//! one iteration based on the previous iteration's checks.
+
+use crate::cursor::{Cursor, FuncCursor};
+use crate::dominator_tree::DominatorTree;
+use crate::inst_predicates::{is_constant_64bit, is_pure_for_egraph};
+use crate::ir::condcodes::{CondCode, IntCC};
+use crate::ir::{
+    Block, Function, Inst, InstBuilder, InstructionData, Opcode, TrapCode, Value, ValueDef,
+};
+use crate::timing;
+use crate::{FxHashMap, trace};
+use alloc::vec::Vec;
+use cranelift_entity::SecondaryMap;
+use smallvec::SmallVec;
+
+/// An out-of-bounds condition of the form `index + k > bound`.
+///
+/// When `bound` is `None` the bound is zero, which is how comparisons against
+/// a constant are represented: `index > C` becomes `index + (-C) > 0`.
+#[derive(Clone, Copy, Debug)]
+struct Check {
+    index: Value,
+    bound: Option<Value>,
+    k: i128,
+    /// The `uadd_overflow_trap` computing `index + k`, and its trap code, if
+    /// any.
+    overflow: Option<(Inst, TrapCode)>,
+}
+
+/// How a memory access is protected by its bounds check.
+#[derive(Clone, Copy, Debug)]
+enum Guard {
+    /// The access's address is `select_spectre_guard cond, zero, addr`.
+    Spectre { zero: Value, addr: Value },
+    /// The access is preceded by (and represented as) `trapnz cond`.
+    Trap,
+}
+
+/// A bounds-checked access.
+#[derive(Clone, Copy, Debug)]
+struct Access {
+    /// The load, store or `trapnz` that traps if the check fails.
+    inst: Inst,
+    /// The out-of-bounds condition value.
+    cond: Value,
+    check: Check,
+    code: TrapCode,
+    guard: Guard,
+    is_store: bool,
+}
+
+impl Access {
+    /// Accesses with equal keys may share a single bounds check.
+    fn run_key(&self) -> (Value, Option<Value>, TrapCode) {
+        (self.check.index, self.check.bound, self.code)
+    }
+}
+
+/// A previously executed check that later checks may rely on.
+#[derive(Clone, Copy)]
+struct Fact {
+    inst: Inst,
+    k: i128,
+    cond: Value,
+}
+
+/// Eliminate bounds checks that are implied by other bounds checks.
+pub fn do_bounds_check_elimination(func: &mut Function, domtree: &DominatorTree) {
+    let _tt = timing::bounds_check_elimination();
+
+    // Guards built by `set_condition`, so that accesses of the same address
+    // whose checks are replaced by the same check share one guard.
+    let mut guards: FxHashMap<(Value, Value, Value), SmallVec<[Value; 2]>> = FxHashMap::default();
+
+    let blocks: Vec<Block> = domtree.cfg_rpo().copied().collect();
+    for &block in &blocks {
+        widen_block(func, block, &mut guards);
+    }
+
+    // Facts are recorded in reverse post-order, so every fact that dominates
+    // an instruction has been recorded by the time that instruction is
+    // visited.
+    let mut checks: FxHashMap<(Value, Option<Value>), SmallVec<[Fact; 4]>> = FxHashMap::default();
+    let mut no_overflow: FxHashMap<Value, SmallVec<[(Inst, u64); 4]>> = FxHashMap::default();
+    // Overflow checks are only simplified at the end, so that the bounds
+    // checks using them are still recognized until then.
+    let mut cannot_overflow = Vec::new();
+
+    for &block in &blocks {
+        let insts: SmallVec<[Inst; 16]> = func.layout.block_insts(block).collect();
+        for inst in insts {
+            if let Some((index, k)) = overflow_check(func, inst) {
+                let facts = no_overflow.entry(index).or_default();
+                if facts
+                    .iter()
+                    .any(|&(at, max)| k <= max && domtree.dominates(at, inst, &func.layout))
+                {
+                    trace!("bounds-check elim: {inst} cannot overflow");
+                    cannot_overflow.push(inst);
+                } else {
+                    facts.push((inst, k));
+                }
+                continue;
+            }
+
+            let Some(access) = analyze_access(func, inst) else {
+                continue;
+            };
+            let key = (access.check.index, access.check.bound);
+            let facts = checks.entry(key).or_default();
+            let dominating = facts.iter().find(|fact| {
+                fact.k >= access.check.k && domtree.dominates(fact.inst, inst, &func.layout)
+            });
+            if let Some(fact) = dominating {
+                trace!(
+                    "bounds-check elim: check of {inst} is implied by {}",
+                    fact.inst
+                );
+                let cond = fact.cond;
+                match access.guard {
+                    Guard::Trap => func.layout.remove_inst(inst),
+                    Guard::Spectre { zero, addr } => {
+                        let built = guards.entry((cond, zero, addr)).or_default();
+                        let existing = built.iter().copied().find(|&guard| {
+                            let def = value_def_inst(func, guard).unwrap();
+                            domtree.dominates(def, inst, &func.layout)
+                        });
+                        match existing {
+                            Some(guard) => set_address(func, &access, guard),
+                            None => built.push(set_condition(func, &access, cond)),
+                        }
+                    }
+                }
+                continue;
+            }
+            facts.push(Fact {
+                inst,
+                k: access.check.k,
+                cond: access.cond,
+            });
+            if let Some(max) = headroom(func, &access.check) {
+                no_overflow
+                    .entry(access.check.index)
+                    .or_default()
+                    .push((inst, max));
+            }
+        }
+    }
+
+    for inst in cannot_overflow {
+        let InstructionData::IntAddTrap { args: [x, y], .. } = func.dfg.insts[inst] else {
+            unreachable!()
+        };
+        func.replace(inst).iadd(x, y);
+    }
+
+    remove_dead_code(func, &blocks);
+}
+
+/// Remove the pure instructions, such as superseded comparisons and guards,
+/// that no longer have any uses.
+fn remove_dead_code(func: &mut Function, blocks: &[Block]) {
+    let mut uses: SecondaryMap<Value, u32> = SecondaryMap::new();
+    for &block in blocks {
+        for inst in func.layout.block_insts(block) {
+            for arg in func.dfg.inst_values(inst) {
+                uses[arg] += 1;
+            }
+        }
+    }
+
+    let is_dead = |func: &Function, uses: &SecondaryMap<Value, u32>, inst: Inst| {
+        func.layout.inst_block(inst).is_some()
+            && is_pure_for_egraph(func, inst)
+            && func.dfg.inst_results(inst).iter().all(|&v| uses[v] == 0)
+    };
+    let mut worklist: Vec<Inst> = blocks
+        .iter()
+        .flat_map(|&block| func.layout.block_insts(block))
+        .filter(|&inst| is_dead(func, &uses, inst))
+        .collect();
+    while let Some(inst) = worklist.pop() {
+        if !is_dead(func, &uses, inst) {
+            continue;
+        }
+        func.layout.remove_inst(inst);
+        for arg in func.dfg.inst_values(inst) {
+            uses[arg] -= 1;
+            if let Some(def) = value_def_inst(func, arg) {
+                if is_dead(func, &uses, def) {
+                    worklist.push(def);
+                }
+            }
+        }
+    }
+}
+
+/// Hoist the strongest check of each run of related accesses in `block` to the
+/// run's first access.
+fn widen_block(
+    func: &mut Function,
+    block: Block,
+    guards: &mut FxHashMap<(Value, Value, Value), SmallVec<[Value; 2]>>,
+) {
+    let mut open: SmallVec<[SmallVec<[Access; 4]>; 4]> = SmallVec::new();
+    let mut runs: SmallVec<[SmallVec<[Access; 4]>; 4]> = SmallVec::new();
+
+    for inst in func.layout.block_insts(block) {
+        let access = analyze_access(func, inst);
+        let mut i = 0;
+        while i < open.len() {
+            let run = &open[i];
+            let member = access.is_some_and(|a| a.run_key() == run[0].run_key());
+            if member || may_trap_before(func, inst, run[0].code) {
+                i += 1;
+            } else {
+                runs.push(open.remove(i));
+            }
+        }
+        let Some(access) = access else {
+            continue;
+        };
+        let run = match open
+            .iter()
+            .position(|run| run[0].run_key() == access.run_key())
+        {
+            Some(i) => {
+                open[i].push(access);
+                i
+            }
+            None => {
+                open.push(SmallVec::from_elem(access, 1));
+                open.len() - 1
+            }
+        };
+        if access.is_store {
+            // The store must happen if none of the checks before it fail, so
+            // no check may be moved ahead of it.
+            runs.push(open.remove(run));
+        }
+    }
+    runs.extend(open);
+
+    for run in runs {
+        let first = run[0];
+        let widest = run
+            .iter()
+            .copied()
+            .max_by_key(|access| access.check.k)
+            .unwrap();
+        if widest.check.k <= first.check.k {
+            continue;
+        }
+        if let Some((_, code)) = widest.check.overflow {
+            if code != first.code {
+                continue;
+            }
+        }
+        // Compute the widened index in place of the first access's own
+        // overflow check when possible; that check is then known not to trap
+        // and can be simplified.
+        let overflow_at = match first.check.overflow {
+            Some((inst, code))
+                if code == first.code
+                    && func.layout.inst_block(inst) == Some(block)
+                    && insts_between(func, inst, first.inst)
+                        .all(|i| may_trap_before(func, i, first.code)) =>
+            {
+                inst
+            }
+            _ => first.inst,
+        };
+        trace!(
+            "bounds-check elim: performing check of {} at {}",
+            widest.inst, first.inst
+        );
+        let cond = emit_check(func, overflow_at, first.inst, &widest.check, first.code);
+        let guard = set_condition(func, &first, cond);
+        if let Guard::Spectre { zero, addr } = first.guard {
+            guards.entry((cond, zero, addr)).or_default().push(guard);
+        }
+    }
+}
+
+/// Can a trap with `code` be moved from just after `inst` to just before it
+/// without an observable difference?
+fn may_trap_before(func: &Function, inst: Inst, code: TrapCode) -> bool {
+    let data = &func.dfg.insts[inst];
+    let opcode = data.opcode();
+    if opcode.is_call()
+        || opcode.is_branch()
+        || opcode.is_terminator()
+        || opcode.can_store()
+        || opcode.other_side_effects()
+    {
+        return false;
+    }
+    match data
+        .trap_code()
+        .or_else(|| data.memflags_trap_code(&func.dfg))
+    {
+        Some(c) => c == code,
+        None => !opcode.can_trap(),
+    }
+}
+
+/// The instructions strictly between `from` and `to` in the same block.
+fn insts_between(func: &Function, from: Inst, to: Inst) -> impl Iterator<Item = Inst> + '_ {
+    core::iter::successors(func.layout.next_inst(from), |&i| func.layout.next_inst(i))
+        .take_while(move |&i| i != to)
+}
+
+/// Recognize a memory access guarded by a bounds check, or a bounds check
+/// performed with `trapnz`.
+fn analyze_access(func: &Function, inst: Inst) -> Option<Access> {
+    let dfg = &func.dfg;
+    let (cond, code, guard, is_store) = match dfg.insts[inst] {
+        InstructionData::CondTrap {
+            opcode: Opcode::Trapnz,
+            arg,
+            code,
+        } => (arg, code, Guard::Trap, false),
+        InstructionData::Load { arg, offset, .. } if i32::from(offset) == 0 => {
+            let (cond, guard) = spectre_guard(func, arg)?;
+            let code = dfg.insts[inst].memflags_trap_code(dfg)?;
+            (cond, code, guard, false)
+        }
+        InstructionData::Store {
+            args: [_, addr],
+            offset,
+            ..
+        } if i32::from(offset) == 0 => {
+            let (cond, guard) = spectre_guard(func, addr)?;
+            let code = dfg.insts[inst].memflags_trap_code(dfg)?;
+            (cond, code, guard, true)
+        }
+        _ => return None,
+    };
+    let check = analyze_condition(func, cond)?;
+    Some(Access {
+        inst,
+        cond,
+        check,
+        code,
+        guard,
+        is_store,
+    })
+}
+
+/// Match `select_spectre_guard cond, 0, addr`.
+fn spectre_guard(func: &Function, addr: Value) -> Option<(Value, Guard)> {
+    let inst = func.dfg.value_def(addr).inst()?;
+    match func.dfg.insts[inst] {
+        InstructionData::Ternary {
+            opcode: Opcode::SelectSpectreGuard,
+            args: [cond, zero, addr],
+        } if constant(func, zero) == Some(0) => Some((cond, Guard::Spectre { zero, addr })),
+        _ => None,
+    }
+}
+
+/// Match an out-of-bounds condition of the form `index + k > bound`.
+fn analyze_condition(func: &Function, cond: Value) -> Option<Check> {
+    let inst = func.dfg.value_def(cond).inst()?;
+    let (cc, lhs, rhs) = match func.dfg.insts[inst] {
+        InstructionData::IntCompare {
+            opcode: Opcode::Icmp,
+            cond,
+            args: [x, y],
+        } => match cond {
+            IntCC::UnsignedGreaterThan | IntCC::UnsignedGreaterThanOrEqual => (cond, x, y),
+            IntCC::UnsignedLessThan | IntCC::UnsignedLessThanOrEqual => (cond.swap_args(), y, x),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let strict = cc == IntCC::UnsignedGreaterThan;
+    if let Some(c) = constant(func, rhs) {
+        // `index > C` and `index >= C`.
+        let k = -i128::from(c) + i128::from(!strict);
+        return Some(Check {
+            index: lhs,
+            bound: None,
+            k,
+            overflow: None,
+        });
+    }
+    if let Some(add) = value_def_inst(func, lhs).filter(|_| strict) {
+        if let Some((index, k)) = overflow_check(func, add) {
+            // `index + k > bound`, where the addition traps on overflow.
+            return Some(Check {
+                index,
+                bound: Some(rhs),
+                k: i128::from(k),
+                overflow: Some((add, func.dfg.insts[add].trap_code()?)),
+            });
+        }
+    }
+    Some(Check {
+        index: lhs,
+        bound: Some(rhs),
+        k: i128::from(!strict),
+        overflow: None,
+    })
+}
+
+/// Match `uadd_overflow_trap index, k` with a constant `k`.
+fn overflow_check(func: &Function, inst: Inst) -> Option<(Value, u64)> {
+    match func.dfg.insts[inst] {
+        InstructionData::IntAddTrap {
+            opcode: Opcode::UaddOverflowTrap,
+            args: [x, y],
+            ..
+        } => Some((x, constant(func, y)?)),
+        _ => None,
+    }
+}
+
+/// The largest `k` for which `index + k` is known not to overflow once `check`
+/// has passed.
+fn headroom(func: &Function, check: &Check) -> Option<u64> {
+    match check.bound {
+        // `index + k <= bound` was computed without overflow.
+        Some(_) => u64::try_from(check.k).ok(),
+        // `index <= -k`.
+        None => {
+            let max = u64::MAX >> (64 - func.dfg.value_type(check.index).bits().min(64));
+            u64::try_from(-check.k).ok().map(|c| max.saturating_sub(c))
+        }
+    }
+}
+
+/// Emit instructions before `inst` computing the out-of-bounds condition of
+/// `check`. If the index computation may overflow, it is emitted before
+/// `overflow_at` instead and traps with `code`.
+fn emit_check(
+    func: &mut Function,
+    overflow_at: Inst,
+    inst: Inst,
+    check: &Check,
+    code: TrapCode,
+) -> Value {
+    let srcloc = func.srcloc(inst);
+    let mut pos = FuncCursor::new(func)
+        .at_inst(overflow_at)
+        .with_srcloc(srcloc);
+    let ty = pos.func.dfg.value_type(check.index);
+    let index = check.index;
+    let sum = match (check.bound, check.k) {
+        (Some(_), k) if k > 1 => {
+            let k = pos.ins().iconst(ty, k as u64 as i64);
+            Some(pos.ins().uadd_overflow_trap(index, k, code))
+        }
+        _ => None,
+    };
+    pos.goto_inst(inst);
+    match (check.bound, check.k, sum) {
+        (Some(bound), _, Some(sum)) => pos.ins().icmp(IntCC::UnsignedGreaterThan, sum, bound),
+        (None, k, _) if k <= 0 => {
+            let c = pos.ins().iconst(ty, (-k) as i64);
+            pos.ins().icmp(IntCC::UnsignedGreaterThan, index, c)
+        }
+        (None, k, _) => {
+            let c = pos.ins().iconst(ty, (1 - k) as i64);
+            pos.ins().icmp(IntCC::UnsignedGreaterThanOrEqual, index, c)
+        }
+        (Some(bound), 0, _) => pos.ins().icmp(IntCC::UnsignedGreaterThan, index, bound),
+        (Some(bound), _, _) => pos
+            .ins()
+            .icmp(IntCC::UnsignedGreaterThanOrEqual, index, bound),
+    }
+}
+
+/// Make `access` use `cond` as its out-of-bounds condition, returning the
+/// condition or the newly built guard that `access` now uses.
+fn set_condition(func: &mut Function, access: &Access, cond: Value) -> Value {
+    match access.guard {
+        Guard::Trap => {
+            func.dfg.inst_args_mut(access.inst)[0] = cond;
+            cond
+        }
+        Guard::Spectre { zero, addr } => {
+            // The original guard may be shared with other accesses, or placed
+            // before `cond` is available, so build a new one right before the
+            // access.
+            let srcloc = func.srcloc(access.inst);
+            let mut pos = FuncCursor::new(func)
+                .at_inst(access.inst)
+                .with_srcloc(srcloc);
+            let guarded = pos.ins().select_spectre_guard(cond, zero, addr);
+            set_address(pos.func, access, guarded);
+            guarded
+        }
+    }
+}
+
+/// Make the guarded `access` use `guarded` as its address.
+fn set_address(func: &mut Function, access: &Access, guarded: Value) {
+    let index = if access.is_store { 1 } else { 0 };
+    func.dfg.inst_args_mut(access.inst)[index] = guarded;
+}
+
+fn value_def_inst(func: &Function, value: Value) -> Option<Inst> {
+    match func.dfg.value_def(value) {
+        ValueDef::Result(inst, 0) => Some(inst),
+        _ => None,
+    }
+}
+
+/// The value of an integer constant, zero-extended from its type.
+fn constant(func: &Function, value: Value) -> Option<u64> {
+    let inst = value_def_inst(func, value)?;
+    if func.dfg.insts[inst].opcode() != Opcode::Iconst {
+        return None;
+    }
+    let bits = is_constant_64bit(func, inst)?;
+    let width = func.dfg.value_type(value).bits();
+    Some(if width >= 64 {
+        bits
+    } else {
+        bits & ((1 << width) - 1)
+    })
+}
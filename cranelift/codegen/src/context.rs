@@ -10,6 +10,7 @@
 //! single ISA instance.
 
 use crate::alias_analysis::AliasAnalysis;
+use crate::bounds_check_elim::do_bounds_check_elimination;
 use crate::dominator_tree::DominatorTree;
 use crate::egraph::EgraphPass;
 use crate::flowgraph::ControlFlowGraph;
@@ -17,11 +18,13 @@ use crate::inline::{Inline, do_inlining};
 use crate::ir::Function;
 use crate::isa::TargetIsa;
 use crate::loop_analysis::LoopAnalysis;
+use crate::loop_unrolling::do_loop_unrolling;
 use crate::machinst::{CompiledCode, CompiledCodeStencil};
 use crate::nan_canonicalization::do_nan_canonicalization;
 use crate::remove_constant_phis::do_remove_constant_phis;
 use crate::result::{CodegenResult, CompileResult};
 use crate::settings::{FlagsOrIsa, OptLevel};
+use crate::switch_recognition::do_switch_recognition;
 use crate::trace;
 use crate::unreachable_code::eliminate_unreachable_code;
 use crate::verifier::{VerifierErrors, VerifierResult, verify_context};
@@ -192,7 +195,16 @@ impl Context {
         self.func.dfg.resolve_all_aliases();
 
         if opt_level != OptLevel::None {
+            if isa.flags().enable_loop_unrolling() {
+                self.unroll_loops(isa)?;
+            }
             self.egraph_pass(isa, ctrl_plane)?;
+            if isa.flags().enable_bounds_check_elimination() {
+                self.eliminate_redundant_bounds_checks(isa)?;
+            }
+            if isa.flags().enable_switch_recognition() {
+                self.recognize_switches(isa)?;
+            }
         }
 
         Ok(())
@@ -336,6 +348,46 @@ impl Context {
         self.verify_if(fisa)
     }
 
+    /// Remove bounds checks that are implied by other bounds checks.
+    pub fn eliminate_redundant_bounds_checks<'a, FOI>(&mut self, fisa: FOI) -> CodegenResult<()>
+    where
+        FOI: Into<FlagsOrIsa<'a>>,
+    {
+        do_bounds_check_elimination(&mut self.func, &self.domtree);
+        self.verify_if(fisa)
+    }
+
+    /// Unroll and peel small loops with a constant trip count.
+    pub fn unroll_loops<'a, FOI>(&mut self, fisa: FOI) -> CodegenResult<()>
+    where
+        FOI: Into<FlagsOrIsa<'a>>,
+    {
+        let fisa = fisa.into();
+        self.compute_loop_analysis();
+        if do_loop_unrolling(&mut self.func, &self.cfg, &self.loop_analysis) {
+            self.compute_cfg();
+            self.compute_domtree();
+            // Parameters of unrolled loops which are now only passed constants
+            // become constants themselves.
+            self.remove_constant_phis(fisa)?;
+            self.func.dfg.resolve_all_aliases();
+        }
+        self.verify_if(fisa)
+    }
+
+    /// Replace chains of conditional branches testing the same value against
+    /// constants with jump tables and binary searches.
+    pub fn recognize_switches<'a, FOI>(&mut self, fisa: FOI) -> CodegenResult<()>
+    where
+        FOI: Into<FlagsOrIsa<'a>>,
+    {
+        if do_switch_recognition(&mut self.func, &self.cfg, &self.domtree) {
+            self.compute_cfg();
+            self.compute_domtree();
+        }
+        self.verify_if(fisa)
+    }
+
     /// Replace all redundant loads with the known values in
     /// memory. These are loads whose values were already loaded by
     /// other loads earlier, as well as loads whose values were stored
@@ -144,7 +144,8 @@ fn enc_arith_rrrr(top11: u32, rm: Reg, bit15: u32, ra: Reg, rn: Reg, rd: Writabl
         | machreg_to_gpr(rd.to_reg())
 }
 
-fn enc_jump26(op_31_26: u32, off_26_0: u32) -> u32 {
+/// Encode an unconditional branch instruction with a 26-bit offset.
+pub fn enc_jump26(op_31_26: u32, off_26_0: u32) -> u32 {
     assert!(off_26_0 < (1 << 26));
     (op_31_26 << 26) | off_26_0
 }
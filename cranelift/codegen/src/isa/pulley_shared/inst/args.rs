@@ -282,6 +282,17 @@ impl Cond {
                 collector.reg_use(src1);
                 let _: &mut u32 = src2;
             }
+
+            Cond::IfVanytrue { src }
+            | Cond::IfNotVanytrue { src }
+            | Cond::IfValltrue8x16 { src }
+            | Cond::IfValltrue16x8 { src }
+            | Cond::IfValltrue32x4 { src }
+            | Cond::IfValltrue64x2 { src }
+            | Cond::IfNotValltrue8x16 { src }
+            | Cond::IfNotValltrue16x8 { src }
+            | Cond::IfNotValltrue32x4 { src }
+            | Cond::IfNotValltrue64x2 { src } => collector.reg_use(src),
         }
     }
 
@@ -387,6 +398,17 @@ impl Cond {
                 Ok(src2) => encode::br_if_xugteq64_u8(sink, src1, src2, rel),
                 Err(_) => encode::br_if_xugteq64_u32(sink, src1, src2, rel),
             },
+
+            Cond::IfVanytrue { src } => encode::br_if_vanytrue(sink, src, rel),
+            Cond::IfNotVanytrue { src } => encode::br_if_not_vanytrue(sink, src, rel),
+            Cond::IfValltrue8x16 { src } => encode::br_if_valltrue8x16(sink, src, rel),
+            Cond::IfValltrue16x8 { src } => encode::br_if_valltrue16x8(sink, src, rel),
+            Cond::IfValltrue32x4 { src } => encode::br_if_valltrue32x4(sink, src, rel),
+            Cond::IfValltrue64x2 { src } => encode::br_if_valltrue64x2(sink, src, rel),
+            Cond::IfNotValltrue8x16 { src } => encode::br_if_not_valltrue8x16(sink, src, rel),
+            Cond::IfNotValltrue16x8 { src } => encode::br_if_not_valltrue16x8(sink, src, rel),
+            Cond::IfNotValltrue32x4 { src } => encode::br_if_not_valltrue32x4(sink, src, rel),
+            Cond::IfNotValltrue64x2 { src } => encode::br_if_not_valltrue64x2(sink, src, rel),
         }
     }
 
@@ -456,6 +478,17 @@ impl Cond {
             Cond::IfXsgteq64I32 { src1, src2 } => Cond::IfXslt64I32 { src1, src2 },
             Cond::IfXugt64I32 { src1, src2 } => Cond::IfXulteq64I32 { src1, src2 },
             Cond::IfXugteq64I32 { src1, src2 } => Cond::IfXult64I32 { src1, src2 },
+
+            Cond::IfVanytrue { src } => Cond::IfNotVanytrue { src },
+            Cond::IfNotVanytrue { src } => Cond::IfVanytrue { src },
+            Cond::IfValltrue8x16 { src } => Cond::IfNotValltrue8x16 { src },
+            Cond::IfValltrue16x8 { src } => Cond::IfNotValltrue16x8 { src },
+            Cond::IfValltrue32x4 { src } => Cond::IfNotValltrue32x4 { src },
+            Cond::IfValltrue64x2 { src } => Cond::IfNotValltrue64x2 { src },
+            Cond::IfNotValltrue8x16 { src } => Cond::IfValltrue8x16 { src },
+            Cond::IfNotValltrue16x8 { src } => Cond::IfValltrue16x8 { src },
+            Cond::IfNotValltrue32x4 { src } => Cond::IfValltrue32x4 { src },
+            Cond::IfNotValltrue64x2 { src } => Cond::IfValltrue64x2 { src },
         }
     }
 }
@@ -561,6 +594,16 @@ impl fmt::Display for Cond {
             Cond::IfXugteq64I32 { src1, src2 } => {
                 write!(f, "if_xugteq64_i32 {}, {src2}", reg_name(**src1))
             }
+            Cond::IfVanytrue { src } => write!(f, "if_vanytrue {}", reg_name(**src)),
+            Cond::IfNotVanytrue { src } => write!(f, "if_not_vanytrue {}", reg_name(**src)),
+            Cond::IfValltrue8x16 { src } => write!(f, "if_valltrue8x16 {}", reg_name(**src)),
+            Cond::IfValltrue16x8 { src } => write!(f, "if_valltrue16x8 {}", reg_name(**src)),
+            Cond::IfValltrue32x4 { src } => write!(f, "if_valltrue32x4 {}", reg_name(**src)),
+            Cond::IfValltrue64x2 { src } => write!(f, "if_valltrue64x2 {}", reg_name(**src)),
+            Cond::IfNotValltrue8x16 { src } => write!(f, "if_not_valltrue8x16 {}", reg_name(**src)),
+            Cond::IfNotValltrue16x8 { src } => write!(f, "if_not_valltrue16x8 {}", reg_name(**src)),
+            Cond::IfNotValltrue32x4 { src } => write!(f, "if_not_valltrue32x4 {}", reg_name(**src)),
+            Cond::IfNotValltrue64x2 { src } => write!(f, "if_not_valltrue64x2 {}", reg_name(**src)),
         }
     }
 }
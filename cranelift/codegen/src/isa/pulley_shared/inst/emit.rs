@@ -32,6 +32,10 @@ impl EmitInfo {
     fn endianness(&self, flags: MemFlagsData) -> Endianness {
         flags.endianness(self.isa_flags.endianness())
     }
+
+    fn peephole(&self) -> bool {
+        self.isa_flags.enable_peephole()
+    }
 }
 
 /// State carried between emissions of a sequence of instructions.
@@ -373,7 +377,11 @@ fn pulley_emit<P>(
 
             if let Some(base) = base {
                 if offset == 0 {
-                    enc::xmov(sink, dst, base);
+                    if !(emit_info.peephole() && dst.to_reg() == base) {
+                        enc::xmov(sink, dst, base);
+                    }
+                } else if emit_info.peephole() {
+                    emit_add_imm::<P>(sink, *dst, base, offset);
                 } else {
                     if let Ok(offset) = i8::try_from(offset) {
                         enc::xconst8(sink, dst, offset);
@@ -417,10 +425,12 @@ fn pulley_emit<P>(
                     E::Big => enc::xload16be_s32_o32(sink, dst, addr),
                 },
                 I32 => match endian {
+                    E::Little if flags.aligned() => enc::xload32le_aligned_o32(sink, dst, addr),
                     E::Little => enc::xload32le_o32(sink, dst, addr),
                     E::Big => enc::xload32be_o32(sink, dst, addr),
                 },
                 I64 => match endian {
+                    E::Little if flags.aligned() => enc::xload64le_aligned_o32(sink, dst, addr),
                     E::Little => enc::xload64le_o32(sink, dst, addr),
                     E::Big => enc::xload64be_o32(sink, dst, addr),
                 },
@@ -443,10 +453,12 @@ fn pulley_emit<P>(
             let endian = emit_info.endianness(*flags);
             match *ty {
                 F32 => match endian {
+                    E::Little if flags.aligned() => enc::fload32le_aligned_o32(sink, dst, addr),
                     E::Little => enc::fload32le_o32(sink, dst, addr),
                     E::Big => enc::fload32be_o32(sink, dst, addr),
                 },
                 F64 => match endian {
+                    E::Little if flags.aligned() => enc::fload64le_aligned_o32(sink, dst, addr),
                     E::Little => enc::fload64le_o32(sink, dst, addr),
                     E::Big => enc::fload64be_o32(sink, dst, addr),
                 },
@@ -491,10 +503,12 @@ fn pulley_emit<P>(
                     E::Big => enc::xstore16be_o32(sink, addr, src),
                 },
                 I32 => match endian {
+                    E::Little if flags.aligned() => enc::xstore32le_aligned_o32(sink, addr, src),
                     E::Little => enc::xstore32le_o32(sink, addr, src),
                     E::Big => enc::xstore32be_o32(sink, addr, src),
                 },
                 I64 => match endian {
+                    E::Little if flags.aligned() => enc::xstore64le_aligned_o32(sink, addr, src),
                     E::Little => enc::xstore64le_o32(sink, addr, src),
                     E::Big => enc::xstore64be_o32(sink, addr, src),
                 },
@@ -517,10 +531,12 @@ fn pulley_emit<P>(
             let endian = emit_info.endianness(*flags);
             match *ty {
                 F32 => match endian {
+                    E::Little if flags.aligned() => enc::fstore32le_aligned_o32(sink, addr, src),
                     E::Little => enc::fstore32le_o32(sink, addr, src),
                     E::Big => enc::fstore32be_o32(sink, addr, src),
                 },
                 F64 => match endian {
+                    E::Little if flags.aligned() => enc::fstore64le_aligned_o32(sink, addr, src),
                     E::Little => enc::fstore64le_o32(sink, addr, src),
                     E::Big => enc::fstore64be_o32(sink, addr, src),
                 },
@@ -586,7 +602,11 @@ fn pulley_emit<P>(
             *start_offset = sink.cur_offset();
         }
 
-        Inst::Raw { raw } => super::generated::emit(raw, sink),
+        Inst::Raw { raw } => {
+            if !(emit_info.peephole() && is_dead_move(raw)) {
+                super::generated::emit(raw, sink)
+            }
+        }
 
         Inst::EmitIsland { space_needed } => {
             if sink.island_needed(*space_needed) {
@@ -707,3 +727,41 @@ fn patch_pc_rel_offset<P>(
     assert_eq!(*chunk, [0, 0, 0, 0]);
     *chunk = (end - start - 4).to_le_bytes();
 }
+
+/// Emits `dst = base + offset` as a single add/sub-with-immediate macro-op
+/// instead of materializing `offset` into `dst` first.
+fn emit_add_imm<P>(
+    sink: &mut MachBuffer<InstAndKind<P>>,
+    dst: WritableXReg,
+    base: XReg,
+    offset: i32,
+) where
+    P: PulleyTargetKind,
+{
+    let magnitude = offset.unsigned_abs();
+    match (P::pointer_width(), offset < 0, u8::try_from(magnitude)) {
+        (PointerWidth::PointerWidth32, false, Ok(imm)) => enc::xadd32_u8(sink, dst, base, imm),
+        (PointerWidth::PointerWidth32, false, Err(_)) => {
+            enc::xadd32_u32(sink, dst, base, magnitude)
+        }
+        (PointerWidth::PointerWidth32, true, Ok(imm)) => enc::xsub32_u8(sink, dst, base, imm),
+        (PointerWidth::PointerWidth32, true, Err(_)) => enc::xsub32_u32(sink, dst, base, magnitude),
+        (PointerWidth::PointerWidth64, false, Ok(imm)) => enc::xadd64_u8(sink, dst, base, imm),
+        (PointerWidth::PointerWidth64, false, Err(_)) => {
+            enc::xadd64_u32(sink, dst, base, magnitude)
+        }
+        (PointerWidth::PointerWidth64, true, Ok(imm)) => enc::xsub64_u8(sink, dst, base, imm),
+        (PointerWidth::PointerWidth64, true, Err(_)) => enc::xsub64_u32(sink, dst, base, magnitude),
+    }
+}
+
+/// Returns whether `raw` is a register-to-register move whose source and
+/// destination are the same register, which can be dropped entirely.
+fn is_dead_move(raw: &RawInst) -> bool {
+    match raw {
+        RawInst::Xmov { dst, src } => dst.to_reg() == *src,
+        RawInst::Fmov { dst, src } => dst.to_reg() == *src,
+        RawInst::Vmov { dst, src } => dst.to_reg() == *src,
+        _ => false,
+    }
+}
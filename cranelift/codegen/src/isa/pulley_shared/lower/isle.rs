@@ -2,7 +2,7 @@
 
 // Pull in the ISLE generated code.
 pub mod generated_code;
-use generated_code::MInst;
+use generated_code::{MInst, VecReduceOp};
 use inst::InstAndKind;
 
 // Types that the generated ISLE code uses via `use super::*`.
@@ -64,6 +64,148 @@ where
     pub(crate) fn dfg(&self) -> &crate::ir::DataFlowGraph {
         &self.lower_ctx.f.dfg
     }
+
+    /// Looks through vector-to-vector bitcasts of `val`, which are no-ops on
+    /// Pulley.
+    fn peel_vec_bitcasts(&self, mut val: Value) -> Value {
+        let dfg = self.dfg();
+        while let Some(inst) = dfg.value_def(val).inst() {
+            match dfg.insts[inst] {
+                InstructionData::LoadNoOffset {
+                    opcode: Opcode::Bitcast,
+                    arg,
+                    ..
+                } if dfg.value_type(arg).is_vector() => val = arg,
+                _ => break,
+            }
+        }
+        val
+    }
+
+    /// Returns the instruction defining `val`, modulo vector bitcasts.
+    fn vec_def_inst(&self, val: Value) -> Option<Inst> {
+        self.dfg().value_def(self.peel_vec_bitcasts(val)).inst()
+    }
+
+    /// Returns whether `a` and `b` are the same vector, modulo bitcasts.
+    fn same_vec(&self, a: Value, b: Value) -> bool {
+        self.peel_vec_bitcasts(a) == self.peel_vec_bitcasts(b)
+    }
+
+    /// Returns the vector `src` such that each lane `i < width` of `val` is
+    /// the reduction with `opcode` of lanes `i`, `i + width`, `i + 2 * width`,
+    /// ... of `src`.
+    ///
+    /// This is the shape of the usual log-step reduction idiom, which combines
+    /// a vector with a shuffle moving its upper half down until one lane is
+    /// left.
+    fn vec_reduce_halves(&self, opcode: Opcode, ty: Type, val: Value, width: u32) -> Option<Value> {
+        if width == ty.lane_count() {
+            return Some(val);
+        }
+        let inst = self.vec_def_inst(val)?;
+        let dfg = self.dfg();
+        if dfg.insts[inst].opcode() != opcode || dfg.ctrl_typevar(inst) != ty {
+            return None;
+        }
+        let [a, b] = *dfg.inst_args(inst) else {
+            return None;
+        };
+        [(a, b), (b, a)].into_iter().find_map(|(y, s)| {
+            if !self.is_vec_upper_shuffle(ty, y, s, width) {
+                return None;
+            }
+            self.vec_reduce_halves(opcode, ty, y, width * 2)
+        })
+    }
+
+    /// Returns whether lanes `i < width` of `shuffle` are lanes `i + width` of
+    /// `src`, where lanes are of type `ty`.
+    fn is_vec_upper_shuffle(&self, ty: Type, src: Value, shuffle: Value, width: u32) -> bool {
+        let Some(inst) = self.vec_def_inst(shuffle) else {
+            return false;
+        };
+        let dfg = self.dfg();
+        let InstructionData::Shuffle {
+            opcode: Opcode::Shuffle,
+            args: [a, b],
+            imm,
+        } = dfg.insts[inst]
+        else {
+            return false;
+        };
+        let base = if self.same_vec(a, src) {
+            0
+        } else if self.same_vec(b, src) {
+            16
+        } else {
+            return false;
+        };
+        let mask = dfg.immediates.get(imm).unwrap().as_slice();
+        let lane_bytes = ty.lane_bits() / 8;
+        (0..width * lane_bytes)
+            .all(|i| u32::from(mask[i as usize]) == base + width * lane_bytes + i)
+    }
+
+    /// Returns the vector `src` such that lane `i < lane_count / group` of
+    /// `val` is the sum of lanes `i * group .. (i + 1) * group` of `src`.
+    ///
+    /// This is the shape of a reduction built from `iadd_pairwise` of a
+    /// vector with itself until one lane is left.
+    fn vec_reduce_pairwise(&self, ty: Type, val: Value, group: u32) -> Option<Value> {
+        if group == 1 {
+            return Some(val);
+        }
+        let inst = self.vec_def_inst(val)?;
+        let dfg = self.dfg();
+        if dfg.insts[inst].opcode() != Opcode::IaddPairwise || dfg.ctrl_typevar(inst) != ty {
+            return None;
+        }
+        let [a, b] = *dfg.inst_args(inst) else {
+            return None;
+        };
+        if !self.same_vec(a, b) {
+            return None;
+        }
+        self.vec_reduce_pairwise(ty, a, group / 2)
+    }
+
+    /// Collects the leaves of a tree of `iadd`s of type `ty` rooted at `val`
+    /// into `lanes`, as `(vector, lane)` pairs if they're `extractlane`s.
+    ///
+    /// A sum of at most 16 lanes can't be more than 15 `iadd`s deep, which
+    /// `depth` bounds the recursion by.
+    fn vec_lane_sum_leaves(
+        &self,
+        ty: Type,
+        val: Value,
+        depth: u32,
+        lanes: &mut SmallVec<[(Value, u8); 16]>,
+    ) -> Option<()> {
+        let dfg = self.dfg();
+        let inst = dfg.value_def(val).inst()?;
+        match dfg.insts[inst] {
+            InstructionData::Binary {
+                opcode: Opcode::Iadd,
+                args: [a, b],
+            } if dfg.ctrl_typevar(inst) == ty && depth < 15 => {
+                self.vec_lane_sum_leaves(ty, a, depth + 1, lanes)?;
+                self.vec_lane_sum_leaves(ty, b, depth + 1, lanes)
+            }
+            InstructionData::BinaryImm8 {
+                opcode: Opcode::Extractlane,
+                arg,
+                imm,
+            } => {
+                if lanes.len() == 16 {
+                    return None;
+                }
+                lanes.push((arg, imm));
+                Some(())
+            }
+            _ => None,
+        }
+    }
 }
 
 impl<P> generated_code::Context for PulleyIsleContext<'_, '_, InstAndKind<P>, PulleyBackend<P>>
@@ -280,11 +422,105 @@ where
         flags.trap_code()
     }
 
+    fn memflags_aligned(&mut self, flags: MemFlagsData) -> bool {
+        flags.aligned()
+    }
+
+    fn atomic_trap_code(&mut self, flags: MemFlagsData) -> TrapCode {
+        flags.trap_code().unwrap_or(TrapCode::HEAP_OUT_OF_BOUNDS)
+    }
+
     fn memflags_is_wasm(&mut self, flags: MemFlagsData) -> bool {
         flags.trap_code() == Some(TrapCode::HEAP_OUT_OF_BOUNDS)
             && self.endianness(flags) == Endianness::Little
     }
 
+    fn shuffle_mask_second_only(&mut self, mask: u128) -> Option<u128> {
+        let bytes = mask.to_le_bytes();
+        if bytes.iter().all(|b| (16..32).contains(b)) {
+            Some(u128::from_le_bytes(bytes.map(|b| b - 16)))
+        } else {
+            None
+        }
+    }
+
+    fn shuffle_dup8_from_mask(&mut self, mask: u128) -> Option<u8> {
+        shuffle_dup_lane(mask, 1)
+    }
+
+    fn shuffle_dup16_from_mask(&mut self, mask: u128) -> Option<u8> {
+        shuffle_dup_lane(mask, 2)
+    }
+
+    fn shuffle_dup32_from_mask(&mut self, mask: u128) -> Option<u8> {
+        shuffle_dup_lane(mask, 4)
+    }
+
+    fn shuffle_dup64_from_mask(&mut self, mask: u128) -> Option<u8> {
+        shuffle_dup_lane(mask, 8)
+    }
+
+    fn swizzle_mask_to_shuffle(&mut self, idx: u128) -> u128 {
+        u128::from_le_bytes(idx.to_le_bytes().map(|b| b.min(16)))
+    }
+
+    fn swizzle_mask_in_bounds(&mut self, idx: u128) -> Option<u128> {
+        if idx.to_le_bytes().iter().all(|b| *b < 16) {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
+    fn vec_reduce(&mut self, val: Value) -> Option<(VecReduceOp, Value)> {
+        let ty = self.value_type(val);
+        if !ty.is_vector() || !ty.lane_type().is_int() || ty.bits() != 128 {
+            return None;
+        }
+        let inst = self.vec_def_inst(val)?;
+        let (op, opcode) = match self.dfg().insts[inst].opcode() {
+            Opcode::IaddPairwise => {
+                let src = self.vec_reduce_pairwise(ty, val, ty.lane_count())?;
+                return Some((VecReduceOp::Add, src));
+            }
+            Opcode::Iadd => (VecReduceOp::Add, Opcode::Iadd),
+            Opcode::Smin => (VecReduceOp::Smin, Opcode::Smin),
+            Opcode::Smax => (VecReduceOp::Smax, Opcode::Smax),
+            Opcode::Umin => (VecReduceOp::Umin, Opcode::Umin),
+            Opcode::Umax => (VecReduceOp::Umax, Opcode::Umax),
+            _ => return None,
+        };
+        // Pulley has no 64-bit lane min/max reductions.
+        if op != VecReduceOp::Add && ty.lane_bits() == 64 {
+            return None;
+        }
+        let src = self.vec_reduce_halves(opcode, ty, val, 1)?;
+        Some((op, src))
+    }
+
+    fn vec_lane_sum(&mut self, ty: Type, a: Value, b: Value) -> Option<Value> {
+        let mut lanes = SmallVec::new();
+        self.vec_lane_sum_leaves(ty, a, 1, &mut lanes)?;
+        self.vec_lane_sum_leaves(ty, b, 1, &mut lanes)?;
+        let (src, _) = lanes[0];
+        let src_ty = self.value_type(src);
+        if src_ty.bits() != 128
+            || src_ty.lane_type() != ty
+            || lanes.len() != src_ty.lane_count() as usize
+        {
+            return None;
+        }
+        // Each lane of `src` must be summed exactly once.
+        let mut seen = 0u16;
+        for (val, lane) in lanes {
+            if val != src || seen & (1 << lane) != 0 {
+                return None;
+            }
+            seen |= 1 << lane;
+        }
+        Some(src)
+    }
+
     fn g32_offset(
         &mut self,
         load_offset: i32,
@@ -301,6 +537,20 @@ where
     }
 }
 
+/// Returns the index of the `size`-byte lane of the first shuffle operand that
+/// `mask` duplicates into every lane, if any.
+fn shuffle_dup_lane(mask: u128, size: u8) -> Option<u8> {
+    let bytes = mask.to_le_bytes();
+    let mut chunks = bytes.chunks(usize::from(size));
+    let first = chunks.next()?;
+    let lane = shuffle_imm_as_le_lane_idx(size, first)?;
+    if lane < 16 / size && chunks.all(|chunk| chunk == first) {
+        Some(lane)
+    } else {
+        None
+    }
+}
+
 /// The main entry point for lowering with ISLE.
 pub(crate) fn lower<P>(
     lower_ctx: &mut Lower<InstAndKind<P>>,
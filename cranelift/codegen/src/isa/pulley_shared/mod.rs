@@ -259,6 +259,10 @@ where
         false
     }
 
+    fn has_x86_cvtt2udq_lowering(&self) -> bool {
+        false
+    }
+
     fn default_argument_extension(&self) -> ir::ArgumentExtension {
         ir::ArgumentExtension::None
     }
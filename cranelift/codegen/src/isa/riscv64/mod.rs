@@ -234,6 +234,10 @@ impl TargetIsa for Riscv64Backend {
         false
     }
 
+    fn has_x86_cvtt2udq_lowering(&self) -> bool {
+        false
+    }
+
     fn default_argument_extension(&self) -> ir::ArgumentExtension {
         // According to https://riscv.org/wp-content/uploads/2024/12/riscv-calling.pdf
         // it says:
@@ -357,6 +357,7 @@ fn test_x64_emit() {
     isa_flag_builder.enable("has_fma").unwrap();
     isa_flag_builder.enable("has_avx").unwrap();
     isa_flag_builder.enable("has_avx512bitalg").unwrap();
+    isa_flag_builder.enable("has_avx512bw").unwrap();
     isa_flag_builder.enable("has_avx512dq").unwrap();
     isa_flag_builder.enable("has_avx512f").unwrap();
     isa_flag_builder.enable("has_avx512vbmi").unwrap();
@@ -1583,6 +1583,10 @@ impl asm::AvailableFeatures for &EmitInfo {
         self.isa_flags.has_avx512dq()
     }
 
+    fn avx512bw(&self) -> bool {
+        self.isa_flags.has_avx512bw()
+    }
+
     fn avx512bitalg(&self) -> bool {
         self.isa_flags.has_avx512bitalg()
     }
@@ -477,6 +477,10 @@ impl Context for IsleContext<'_, '_, MInst, X64Backend> {
         self.backend.x64_flags.has_avx512dq()
     }
 
+    fn has_avx512bw(&mut self) -> bool {
+        self.backend.x64_flags.has_avx512bw()
+    }
+
     #[inline]
     fn has_avx512f(&mut self) -> bool {
         self.backend.x64_flags.has_avx512f()
@@ -82,6 +82,7 @@ pub use crate::machinst::{
 };
 
 mod alias_analysis;
+mod bounds_check_elim;
 mod branch_to_trap;
 mod constant_hash;
 mod context;
@@ -89,6 +90,7 @@ mod ctxhash;
 mod egraph;
 mod inst_predicates;
 mod isle_prelude;
+mod loop_unrolling;
 mod nan_canonicalization;
 mod opts;
 mod ranges;
@@ -101,6 +103,7 @@ mod value_label;
 
 #[cfg(feature = "souper-harvest")]
 mod souper_harvest;
+mod switch_recognition;
 
 pub use crate::result::{CodegenError, CodegenResult, CompileError};
 pub use crate::take_and_replace::TakeAndReplace;
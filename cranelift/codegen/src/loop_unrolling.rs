@@ -0,0 +1,508 @@
+//! Unrolling and peeling of small loops with a constant trip count.
+//!
+//! Frontends often emit loops that run a small, fixed number of times, such
+//! as the lane loops that WebAssembly compilers produce for vector code when
+//! SIMD isn't available:
+//!
+//! ```clif
+//! block0(v0: i64):
+//!     v1 = iconst.i32 0
+//!     jump block1(v1)
+//!
+//! block1(v2: i32):
+//!     ...
+//!     v3 = iconst.i32 1
+//!     v4 = iadd v2, v3
+//!     v5 = iconst.i32 4
+//!     v6 = icmp ult v4, v5
+//!     brif v6, block1(v4), block2
+//! ```
+//!
+//! This pass handles loops consisting of a single block that branches back
+//! to itself, entered through a single edge, whose exit condition compares
+//! an induction variable -- a block parameter that starts at a constant and
+//! is incremented by a constant in every iteration -- with a constant, either
+//! before or after the increment. The number of iterations of such a loop is
+//! computed by stepping through them, and then:
+//!
+//! * If the whole loop is small enough, it is **fully unrolled**: the body is
+//!   repeated once per iteration within the loop's block, which then jumps
+//!   straight to the exit.
+//!
+//! * Otherwise, if the body is small enough, the loop is **partially
+//!   unrolled** by repeating the body a few times before its exit test. The
+//!   tests of the repeated bodies are skipped, which is only correct when the
+//!   repeat count divides the number of iterations, so the remaining
+//!   iterations are **peeled** off into a new block ahead of the loop.
+//!
+//! The repeated bodies refer to the induction variable's value in their own
+//! iteration, which the egraph pass turns into constants after a full unroll
+//! and into constant offsets from the loop's induction variable otherwise.
+//! Uses of the loop's values after the loop are rewritten to their values in
+//! the last repeated body.
+//!
+//! Loops containing calls aren't unrolled, since the cost of the call dwarfs
+//! that of the loop's branch.
+
+use crate::FxHashMap;
+use crate::cursor::{Cursor, FuncCursor};
+use crate::flowgraph::ControlFlowGraph;
+use crate::inst_predicates::is_constant_64bit;
+use crate::ir::condcodes::{CondCode, IntCC};
+use crate::ir::{
+    Block, BlockArg, BlockCall, Function, Inst, InstBuilder, InstructionData, Opcode, Type, Value,
+    ValueDef,
+};
+use crate::loop_analysis::LoopAnalysis;
+use crate::timing;
+use crate::trace;
+use alloc::vec::Vec;
+
+/// The maximum number of instructions in the body of a loop to unroll.
+const MAX_BODY_INSTS: usize = 32;
+
+/// The maximum number of instructions of a fully unrolled loop.
+const MAX_FULLY_UNROLLED_INSTS: usize = 128;
+
+/// The maximum number of times the body of a partially unrolled loop is
+/// repeated.
+const MAX_UNROLL_FACTOR: u64 = 4;
+
+/// The maximum number of instructions in the body of a partially unrolled
+/// loop.
+const MAX_PARTIALLY_UNROLLED_INSTS: usize = 64;
+
+/// The maximum number of iterations stepped through to find a loop's trip
+/// count.
+const MAX_TRIP_COUNT: u64 = 1 << 16;
+
+/// A loop recognized as having a constant trip count.
+struct CountedLoop {
+    header: Block,
+    /// The branch entering the loop from outside of it.
+    entry: Inst,
+    /// The loop's conditional branch back to itself.
+    latch: Inst,
+    /// The loop's instructions other than `latch`.
+    body: Vec<Inst>,
+    /// The destination of `latch` continuing the loop.
+    back: BlockCall,
+    /// The destination of `latch` exiting the loop.
+    exit: BlockCall,
+    trip_count: u64,
+}
+
+/// An induction variable tested by a loop's exit condition.
+struct Induction {
+    ty: Type,
+    init: u64,
+    step: u64,
+    /// Whether the condition tests the incremented value rather than the value
+    /// the iteration started with.
+    tests_next: bool,
+    cc: IntCC,
+    limit: u64,
+    /// Whether the loop continues when the condition holds, rather than when
+    /// it doesn't.
+    continues_if_true: bool,
+}
+
+/// Unroll and peel small loops with a constant trip count.
+///
+/// Returns whether the function was changed, in which case its control flow
+/// graph and dominator tree need to be recomputed.
+pub fn do_loop_unrolling(
+    func: &mut Function,
+    cfg: &ControlFlowGraph,
+    loop_analysis: &LoopAnalysis,
+) -> bool {
+    let _tt = timing::loop_unrolling();
+
+    let candidates: Vec<CountedLoop> = loop_analysis
+        .loops()
+        .filter_map(|lp| recognize(func, cfg, loop_analysis, loop_analysis.loop_header(lp)))
+        .collect();
+
+    let mut changed = false;
+    for lp in candidates {
+        changed |= unroll(func, lp);
+    }
+    changed
+}
+
+/// Recognize the loop headed by `header` if it's a single-block loop with a
+/// constant trip count.
+fn recognize(
+    func: &Function,
+    cfg: &ControlFlowGraph,
+    loop_analysis: &LoopAnalysis,
+    header: Block,
+) -> Option<CountedLoop> {
+    let pool = &func.dfg.value_lists;
+    let latch = func.layout.last_inst(header)?;
+    let InstructionData::Brif {
+        arg: cond,
+        blocks: [then, else_],
+        ..
+    } = func.dfg.insts[latch]
+    else {
+        return None;
+    };
+    let (back, exit, continues_if_true) = match (then.block(pool), else_.block(pool)) {
+        (t, e) if t == header && e != header => (then, else_, true),
+        (t, e) if t != header && e == header => (else_, then, false),
+        _ => return None,
+    };
+
+    // The loop must be entered through a single edge, and its only other
+    // predecessor must be its own branch back.
+    if func.layout.entry_block() == Some(header) {
+        return None;
+    }
+    let lp = loop_analysis.innermost_loop(header)?;
+    let mut entry = None;
+    for pred in cfg.pred_iter(header) {
+        if pred.inst == latch {
+            continue;
+        }
+        if pred.block == header || loop_analysis.is_in_loop(pred.block, lp) || entry.is_some() {
+            return None;
+        }
+        entry = Some(pred.inst);
+    }
+    let entry = entry?;
+    let entry_call = single_call_to(func, entry, header)?;
+
+    let body: Vec<Inst> = func
+        .layout
+        .block_insts(header)
+        .filter(|&inst| inst != latch)
+        .collect();
+    if body.len() + 1 > MAX_BODY_INSTS {
+        return None;
+    }
+    if body.iter().any(|&inst| {
+        let opcode = func.dfg.insts[inst].opcode();
+        opcode.is_call() || opcode.is_branch() || opcode.is_terminator()
+    }) {
+        return None;
+    }
+
+    let back_args = values(back.args(pool))?;
+    let entry_args = values(entry_call.args(pool))?;
+    let iv = induction(
+        func,
+        header,
+        cond,
+        &back_args,
+        &entry_args,
+        continues_if_true,
+    )?;
+    let trip_count = trip_count(&iv)?;
+    trace!("loop unrolling: {header} runs {trip_count} times");
+
+    Some(CountedLoop {
+        header,
+        entry,
+        latch,
+        body,
+        back,
+        exit,
+        trip_count,
+    })
+}
+
+/// Returns the only destination of the branch `inst` that is `block`.
+fn single_call_to(func: &Function, inst: Inst, block: Block) -> Option<BlockCall> {
+    let mut calls = func.dfg.insts[inst]
+        .branch_destination(&func.dfg.jump_tables, &func.dfg.exception_tables)
+        .iter()
+        .filter(|call| call.block(&func.dfg.value_lists) == block);
+    let call = *calls.next()?;
+    match calls.next() {
+        Some(_) => None,
+        None => Some(call),
+    }
+}
+
+fn values(args: impl Iterator<Item = BlockArg>) -> Option<Vec<Value>> {
+    args.map(|arg| arg.as_value()).collect()
+}
+
+/// Find the induction variable that the exit condition `cond` of the loop
+/// headed by `header` tests.
+fn induction(
+    func: &Function,
+    header: Block,
+    cond: Value,
+    back_args: &[Value],
+    entry_args: &[Value],
+    continues_if_true: bool,
+) -> Option<Induction> {
+    // A condition which isn't a comparison with a constant tests whether it's
+    // nonzero.
+    let cond = peel_extends(func, cond);
+    let (x, cc, limit) = compare_with_constant(func, cond).unwrap_or((cond, IntCC::NotEqual, 0));
+    let ty = func.dfg.value_type(x);
+    if !ty.is_int() || ty.bits() > 64 {
+        return None;
+    }
+
+    let params = func.dfg.block_params(header);
+    let (index, tests_next) = match func.dfg.value_def(x) {
+        ValueDef::Param(block, index) if block == header => (index, false),
+        _ => (back_args.iter().position(|&arg| arg == x)?, true),
+    };
+    let param = params[index];
+    let step = increment(func, back_args[index], param)?;
+    let init = constant(func, entry_args[index])?;
+
+    Some(Induction {
+        ty,
+        init: mask(ty, init),
+        step: mask(ty, step),
+        tests_next,
+        cc,
+        limit: mask(ty, limit),
+        continues_if_true,
+    })
+}
+
+/// Match `value` as `param` plus a constant, returning the constant.
+fn increment(func: &Function, value: Value, param: Value) -> Option<u64> {
+    let inst = func.dfg.value_def(value).inst()?;
+    match func.dfg.insts[inst] {
+        InstructionData::Binary {
+            opcode: Opcode::Iadd,
+            args: [a, b],
+        } => match (a == param, b == param) {
+            (true, _) => constant(func, b),
+            (_, true) => constant(func, a),
+            _ => None,
+        },
+        InstructionData::Binary {
+            opcode: Opcode::Isub,
+            args: [a, b],
+        } if a == param => constant(func, b).map(u64::wrapping_neg),
+        _ => None,
+    }
+}
+
+/// Step through the iterations of a loop, returning how many there are.
+fn trip_count(iv: &Induction) -> Option<u64> {
+    let mut value = iv.init;
+    for trips in 1..=MAX_TRIP_COUNT {
+        let next = mask(iv.ty, value.wrapping_add(iv.step));
+        let tested = if iv.tests_next { next } else { value };
+        if holds(iv.cc, iv.ty, tested, iv.limit) != iv.continues_if_true {
+            return Some(trips);
+        }
+        value = next;
+    }
+    None
+}
+
+/// Unroll `lp`, or leave it alone if it's too large. Returns whether the
+/// loop was changed.
+fn unroll(func: &mut Function, lp: CountedLoop) -> bool {
+    let size = lp.body.len() + 1;
+    let (factor, full) =
+        if lp.trip_count.saturating_mul(size as u64) <= MAX_FULLY_UNROLLED_INSTS as u64 {
+            (lp.trip_count, true)
+        } else {
+            match (2..=MAX_UNROLL_FACTOR)
+                .rev()
+                .find(|&f| f as usize * size <= MAX_PARTIALLY_UNROLLED_INSTS)
+            {
+                Some(factor) => (factor, false),
+                None => return false,
+            }
+        };
+    trace!(
+        "loop unrolling: {} {} by {factor}",
+        lp.header,
+        if full { "fully unrolled" } else { "unrolled" }
+    );
+
+    let peeled = match full {
+        true => 0,
+        false => lp.trip_count % factor,
+    };
+    let preheader = (peeled > 0).then(|| peel(func, &lp, peeled));
+
+    // The loop's own body is the first of the repeated bodies.
+    let back_args = back_args(func, &lp);
+    let mut map = FxHashMap::default();
+    let mut args = back_args.clone();
+    for _ in 1..factor {
+        copy_body(func, &lp, &args, &mut map, lp.header);
+        args = back_args.iter().map(|v| lookup(&map, *v)).collect();
+    }
+
+    if full {
+        func.dfg.insts[lp.latch] = InstructionData::Jump {
+            opcode: Opcode::Jump,
+            destination: lp.exit,
+        };
+    }
+    func.dfg
+        .map_inst_values(lp.latch, |value| lookup(&map, value));
+
+    // Values of the loop used after it now come from its last repeated body.
+    if !map.is_empty() {
+        let mut pos = FuncCursor::new(func);
+        while let Some(block) = pos.next_block() {
+            if block == lp.header || Some(block) == preheader {
+                continue;
+            }
+            while let Some(inst) = pos.next_inst() {
+                pos.func
+                    .dfg
+                    .map_inst_values(inst, |value| lookup(&map, value));
+            }
+        }
+    }
+    true
+}
+
+/// Peel `count` iterations of `lp` off into a new block ahead of it, which
+/// the loop's entry branches to instead. Returns the new block.
+fn peel(func: &mut Function, lp: &CountedLoop, count: u64) -> Block {
+    let preheader = func.dfg.make_block();
+    for i in 0..func.dfg.num_block_params(lp.header) {
+        let ty = func.dfg.value_type(func.dfg.block_params(lp.header)[i]);
+        func.dfg.append_block_param(preheader, ty);
+    }
+    func.layout.insert_block(preheader, lp.header);
+
+    let dfg = &mut func.dfg;
+    for call in
+        dfg.insts[lp.entry].branch_destination_mut(&mut dfg.jump_tables, &mut dfg.exception_tables)
+    {
+        if call.block(&dfg.value_lists) == lp.header {
+            call.set_block(preheader, &mut dfg.value_lists);
+        }
+    }
+
+    let back_args = back_args(func, lp);
+    let mut map = FxHashMap::default();
+    let mut args = func.dfg.block_params(preheader).to_vec();
+    for _ in 0..count {
+        copy_body(func, lp, &args, &mut map, preheader);
+        args = back_args.iter().map(|v| lookup(&map, *v)).collect();
+    }
+    let args: Vec<BlockArg> = args.into_iter().map(BlockArg::Value).collect();
+    FuncCursor::new(func)
+        .at_bottom(preheader)
+        .ins()
+        .jump(lp.header, &args);
+    preheader
+}
+
+/// The arguments that the loop's branch passes back to its block.
+///
+/// These are read again when the loop is unrolled, rather than when it's
+/// recognized, since unrolling an earlier loop may have rewritten them.
+fn back_args(func: &Function, lp: &CountedLoop) -> Vec<Value> {
+    values(lp.back.args(&func.dfg.value_lists)).unwrap()
+}
+
+/// Copy the body of `lp` for an iteration starting with `args` as the loop's
+/// parameters into `block`: ahead of the loop's branch within the loop's own
+/// block, and at the end of any other block.
+///
+/// Fills `map` with the values of the loop's block in the copy.
+fn copy_body(
+    func: &mut Function,
+    lp: &CountedLoop,
+    args: &[Value],
+    map: &mut FxHashMap<Value, Value>,
+    block: Block,
+) {
+    map.clear();
+    map.extend(
+        func.dfg
+            .block_params(lp.header)
+            .iter()
+            .copied()
+            .zip(args.iter().copied()),
+    );
+    for &inst in &lp.body {
+        let copy = func.dfg.clone_inst(inst);
+        func.dfg.map_inst_values(copy, |value| lookup(map, value));
+        for i in 0..func.dfg.inst_results(inst).len() {
+            map.insert(
+                func.dfg.inst_results(inst)[i],
+                func.dfg.inst_results(copy)[i],
+            );
+        }
+        func.set_srcloc(copy, func.srcloc(inst));
+        if block == lp.header {
+            func.layout.insert_inst(copy, lp.latch);
+        } else {
+            func.layout.append_inst(copy, block);
+        }
+    }
+}
+
+fn lookup(map: &FxHashMap<Value, Value>, value: Value) -> Value {
+    map.get(&value).copied().unwrap_or(value)
+}
+
+fn peel_extends(func: &Function, mut value: Value) -> Value {
+    while let ValueDef::Result(inst, _) = func.dfg.value_def(value) {
+        match func.dfg.insts[inst] {
+            InstructionData::Unary {
+                opcode: Opcode::Uextend,
+                arg,
+            } => value = arg,
+            _ => break,
+        }
+    }
+    value
+}
+
+/// Match `value` as a comparison of a value with a constant, returning the
+/// value, the condition with the value on its left-hand side, and the
+/// constant.
+fn compare_with_constant(func: &Function, value: Value) -> Option<(Value, IntCC, u64)> {
+    let inst = func.dfg.value_def(value).inst()?;
+    let InstructionData::IntCompare { cond, args, .. } = func.dfg.insts[inst] else {
+        return None;
+    };
+    match (constant(func, args[0]), constant(func, args[1])) {
+        (_, Some(k)) => Some((args[0], cond, k)),
+        (Some(k), None) => Some((args[1], cond.swap_args(), k)),
+        (None, None) => None,
+    }
+}
+
+fn constant(func: &Function, value: Value) -> Option<u64> {
+    let inst = func.dfg.value_def(value).inst()?;
+    match func.dfg.insts[inst].opcode() {
+        Opcode::Iconst => is_constant_64bit(func, inst),
+        _ => None,
+    }
+}
+
+fn mask(ty: Type, k: u64) -> u64 {
+    k & (u64::MAX >> (64 - ty.bits()))
+}
+
+/// Evaluate `a cc b` on values of type `ty`.
+fn holds(cc: IntCC, ty: Type, a: u64, b: u64) -> bool {
+    let shift = 64 - ty.bits();
+    let signed = |x: u64| ((x << shift) as i64) >> shift;
+    match cc {
+        IntCC::Equal => a == b,
+        IntCC::NotEqual => a != b,
+        IntCC::SignedLessThan => signed(a) < signed(b),
+        IntCC::SignedLessThanOrEqual => signed(a) <= signed(b),
+        IntCC::SignedGreaterThan => signed(a) > signed(b),
+        IntCC::SignedGreaterThanOrEqual => signed(a) >= signed(b),
+        IntCC::UnsignedLessThan => a < b,
+        IntCC::UnsignedLessThanOrEqual => a <= b,
+        IntCC::UnsignedGreaterThan => a > b,
+        IntCC::UnsignedGreaterThanOrEqual => a >= b,
+    }
+}
@@ -499,6 +499,9 @@ log2_min_function_alignment = 0
 regalloc_checker = false
 regalloc_verbose_logs = false
 enable_alias_analysis = true
+enable_bounds_check_elimination = false
+enable_switch_recognition = false
+enable_loop_unrolling = false
 enable_verifier = true
 is_pic = false
 use_colocated_libcalls = false
@@ -0,0 +1,489 @@
+//! Recognition of switches written as chains of conditional branches.
+//!
+//! Frontends that can't express a multi-way branch directly -- most notably
+//! WebAssembly produced by LLVM for sparse `switch` statements, where each
+//! case becomes a `br_if` on an `i32.eq` or `i32.eqz` -- compare the same
+//! value against one constant after the other:
+//!
+//! ```clif
+//! block0(v0: i32):
+//!     v1 = iconst.i32 3
+//!     v2 = icmp eq v0, v1
+//!     brif v2, block10, block1
+//!
+//! block1:
+//!     v3 = iconst.i32 7
+//!     v4 = icmp eq v0, v3
+//!     brif v4, block11, block2
+//!
+//! block2:
+//!     brif v0, block3, block12
+//!
+//! block3:
+//!     ...
+//! ```
+//!
+//! Dispatching on the last case of such a chain takes as many branches as
+//! there are cases. This pass collects each chain of at least
+//! [`MIN_CASES`] tests of the same value into a set of cases and a default
+//! destination, and replaces it with a jump table when the cases are dense
+//! enough, or with a binary search over the cases otherwise. Each half of the
+//! search may again use a jump table, and short runs of cases are compared
+//! one after the other as before.
+//!
+//! A block continues the chain only if it is reached from nowhere else,
+//! takes no parameters, and contains nothing but pure instructions and its
+//! test. Those instructions are moved to the head of the chain, where they
+//! can be removed when they were only used by the tests. The pass runs after
+//! the egraph pass, so chains whose conditions were simplified there --
+//! including comparisons wrapped in `i32.eqz` and `uextend` by the wasm
+//! frontend -- are recognized in their simplified form.
+
+use crate::cursor::{Cursor, FuncCursor};
+use crate::dominator_tree::DominatorTree;
+use crate::flowgraph::ControlFlowGraph;
+use crate::inst_predicates::{is_constant_64bit, is_pure_for_egraph};
+use crate::ir::condcodes::IntCC;
+use crate::ir::{
+    Block, BlockArg, BlockCall, Function, Inst, InstBuilder, InstructionData, JumpTableData,
+    Opcode, Type, Value, ValueDef, types,
+};
+use crate::timing;
+use crate::trace;
+use alloc::vec;
+use alloc::vec::Vec;
+use cranelift_entity::{EntitySet, SecondaryMap};
+use smallvec::SmallVec;
+
+/// The minimum number of cases of a chain for it to be rewritten.
+const MIN_CASES: usize = 4;
+
+/// The minimum number of cases dispatched through a jump table.
+const MIN_TABLE_CASES: usize = 4;
+
+/// The maximum number of table entries per case of a jump table.
+const MAX_TABLE_ENTRIES_PER_CASE: u64 = 3;
+
+/// The maximum number of cases compared one after the other within a binary
+/// search.
+const MAX_LINEAR_CASES: usize = 3;
+
+/// A conditional branch testing a value against a constant.
+#[derive(Clone, Copy, Debug)]
+struct Test {
+    value: Value,
+    k: u64,
+    /// Whether the branch is taken when `value == k`, rather than when
+    /// `value != k`.
+    taken_if_equal: bool,
+}
+
+/// A switch recognized from a chain of conditional branches.
+struct Switch {
+    value: Value,
+    /// The cases, sorted by their (unsigned) constant.
+    cases: Vec<(u64, BlockCall)>,
+    default: BlockCall,
+    /// The blocks continuing the chain after its head.
+    blocks: Vec<Block>,
+}
+
+/// Replace chains of conditional branches on the same value with jump tables
+/// and binary searches.
+///
+/// Returns whether the function was changed, in which case its control flow
+/// graph and dominator tree need to be recomputed.
+pub fn do_switch_recognition(
+    func: &mut Function,
+    cfg: &ControlFlowGraph,
+    domtree: &DominatorTree,
+) -> bool {
+    let _tt = timing::switch_recognition();
+
+    let mut absorbed = EntitySet::<Block>::new();
+    let mut candidates = Vec::new();
+    let blocks: Vec<Block> = domtree.cfg_rpo().copied().collect();
+    for head in blocks {
+        if absorbed.contains(head) {
+            continue;
+        }
+        let Some(switch) = recognize(func, cfg, head) else {
+            continue;
+        };
+        trace!(
+            "switch recognition: {head} switches on {} with {} cases",
+            switch.value,
+            switch.cases.len()
+        );
+        for &block in &switch.blocks {
+            absorbed.insert(block);
+        }
+        rewrite(func, head, switch, &mut candidates);
+    }
+
+    if candidates.is_empty() {
+        return false;
+    }
+    remove_dead_insts(func, candidates);
+    true
+}
+
+/// Decode the condition `cond` of a conditional branch as a test of a value
+/// against a constant.
+///
+/// Every condition can be decoded as testing itself against zero, which is
+/// what the result is when it isn't a comparison with a constant.
+fn decode(func: &Function, cond: Value) -> Test {
+    let cond = peel_extends(func, cond);
+    if let Some((value, k, cc)) = compare_with_constant(func, cond) {
+        let taken_if_equal = cc == IntCC::Equal;
+        // `icmp eq c, 0` on a condition `c` negates it, as produced for
+        // `i32.eqz`.
+        if k == 0 && compare_with_constant(func, peel_extends(func, value)).is_some() {
+            let inner = decode(func, value);
+            return Test {
+                taken_if_equal: inner.taken_if_equal != taken_if_equal,
+                ..inner
+            };
+        }
+        return Test {
+            value,
+            k,
+            taken_if_equal,
+        };
+    }
+    Test {
+        value: cond,
+        k: 0,
+        taken_if_equal: false,
+    }
+}
+
+/// Look through extensions of `value`, which don't change whether it's
+/// zero.
+fn peel_extends(func: &Function, mut value: Value) -> Value {
+    while let ValueDef::Result(inst, _) = func.dfg.value_def(value) {
+        match func.dfg.insts[inst] {
+            InstructionData::Unary {
+                opcode: Opcode::Uextend | Opcode::Sextend,
+                arg,
+            } => value = arg,
+            _ => break,
+        }
+    }
+    value
+}
+
+/// Match `value` as an `eq` or `ne` comparison of an integer with a constant.
+fn compare_with_constant(func: &Function, value: Value) -> Option<(Value, u64, IntCC)> {
+    let ValueDef::Result(inst, _) = func.dfg.value_def(value) else {
+        return None;
+    };
+    let (cc, x, k) = match func.dfg.insts[inst] {
+        InstructionData::IntCompare { cond, args, .. } => {
+            match (constant(func, args[0]), constant(func, args[1])) {
+                (_, Some(k)) => (cond, args[0], k),
+                (Some(k), None) => (cond, args[1], k),
+                (None, None) => return None,
+            }
+        }
+        _ => return None,
+    };
+    if !matches!(cc, IntCC::Equal | IntCC::NotEqual) || !is_switchable(func.dfg.value_type(x)) {
+        return None;
+    }
+    Some((x, mask(func.dfg.value_type(x), k), cc))
+}
+
+fn constant(func: &Function, value: Value) -> Option<u64> {
+    let inst = func.dfg.value_def(value).inst()?;
+    match func.dfg.insts[inst].opcode() {
+        Opcode::Iconst => is_constant_64bit(func, inst),
+        _ => None,
+    }
+}
+
+fn is_switchable(ty: Type) -> bool {
+    matches!(ty, types::I8 | types::I16 | types::I32 | types::I64)
+}
+
+fn mask(ty: Type, k: u64) -> u64 {
+    k & (u64::MAX >> (64 - ty.bits()))
+}
+
+/// Match the terminator of `block` as a conditional branch testing `value`,
+/// or any value if `value` is `None`, against a constant.
+///
+/// Returns the test, the destination when the tested value is the constant,
+/// and the destination otherwise.
+fn link(
+    func: &Function,
+    block: Block,
+    value: Option<Value>,
+) -> Option<(Test, BlockCall, BlockCall)> {
+    let term = func.layout.last_inst(block)?;
+    let InstructionData::Brif {
+        arg,
+        blocks: [then, else_],
+        ..
+    } = func.dfg.insts[term]
+    else {
+        return None;
+    };
+    let mut test = decode(func, arg);
+    match value {
+        None => {}
+        Some(value) if value == test.value => {}
+        // A condition on the switched value itself tests it against zero,
+        // even if it's a comparison.
+        Some(value) if value == arg => {
+            test = Test {
+                value,
+                k: 0,
+                taken_if_equal: false,
+            };
+        }
+        Some(_) => return None,
+    }
+    if !is_switchable(func.dfg.value_type(test.value)) {
+        return None;
+    }
+    Some(if test.taken_if_equal {
+        (test, then, else_)
+    } else {
+        (test, else_, then)
+    })
+}
+
+/// Recognize the chain of conditional branches starting with the terminator
+/// of `head`, if it has enough cases.
+fn recognize(func: &Function, cfg: &ControlFlowGraph, head: Block) -> Option<Switch> {
+    let pool = &func.dfg.value_lists;
+    let (test, mut case, mut rest) = link(func, head, None)?;
+    let value = test.value;
+    let mut cases = vec![(test.k, case)];
+    let mut blocks = Vec::new();
+    loop {
+        let next = rest.block(pool);
+        let continues = next != head
+            && next != case.block(pool)
+            && !blocks.contains(&next)
+            && rest.len(pool) == 0
+            && func.dfg.num_block_params(next) == 0
+            && cfg.pred_iter(next).count() == 1
+            && func.layout.block_insts(next).all(|inst| {
+                func.layout.last_inst(next) == Some(inst) || is_pure_for_egraph(func, inst)
+            });
+        if !continues {
+            break;
+        }
+        let Some((test, next_case, next_rest)) = link(func, next, Some(value)) else {
+            break;
+        };
+        blocks.push(next);
+        // Later tests of the same constant are unreachable.
+        if cases.iter().all(|&(k, _)| k != test.k) {
+            cases.push((test.k, next_case));
+        }
+        (case, rest) = (next_case, next_rest);
+    }
+    if cases.len() < MIN_CASES {
+        return None;
+    }
+    cases.sort_by_key(|&(k, _)| k);
+    Some(Switch {
+        value,
+        cases,
+        default: rest,
+        blocks,
+    })
+}
+
+/// Replace the chain starting at `head` with `switch`.
+///
+/// The instructions which may have become dead are pushed to `candidates`.
+fn rewrite(func: &mut Function, head: Block, switch: Switch, candidates: &mut Vec<Inst>) {
+    let term = func.layout.last_inst(head).unwrap();
+
+    // Move the instructions of the chain to its head, and remove the chain.
+    for &block in &switch.blocks {
+        let branch = func.layout.last_inst(block).unwrap();
+        let insts: SmallVec<[Inst; 8]> = func.layout.block_insts(block).collect();
+        for inst in insts {
+            func.layout.remove_inst(inst);
+            if inst != branch {
+                func.layout.insert_inst(inst, term);
+                candidates.push(inst);
+            }
+        }
+        candidates.extend(branch_arg_defs(func, branch));
+        func.layout.remove_block(block);
+    }
+    candidates.extend(branch_arg_defs(func, term));
+    func.layout.remove_inst(term);
+
+    let ty = func.dfg.value_type(switch.value);
+    let srcloc = func.srcloc(term);
+    let mut emitter = Emitter {
+        pos: FuncCursor::new(func).with_srcloc(srcloc),
+        value: switch.value,
+        ty,
+        default: switch.default,
+        last_block: head,
+    };
+    emitter.emit(head, &switch.cases);
+}
+
+fn branch_arg_defs(func: &Function, branch: Inst) -> impl Iterator<Item = Inst> + '_ {
+    func.dfg
+        .inst_values(branch)
+        .filter_map(|arg| func.dfg.value_def(arg).inst())
+}
+
+/// Emits the dispatch of a switch.
+struct Emitter<'a> {
+    pos: FuncCursor<'a>,
+    value: Value,
+    ty: Type,
+    default: BlockCall,
+    /// The last block emitted, after which new blocks are laid out.
+    last_block: Block,
+}
+
+impl Emitter<'_> {
+    /// Emit the dispatch to `cases` at the end of `block`.
+    fn emit(&mut self, block: Block, cases: &[(u64, BlockCall)]) {
+        self.pos.goto_bottom(block);
+        let first = cases[0].0;
+        let span = cases[cases.len() - 1].0 - first + 1;
+        if cases.len() >= MIN_TABLE_CASES && span <= cases.len() as u64 * MAX_TABLE_ENTRIES_PER_CASE
+        {
+            self.emit_table(cases, first, span);
+        } else if cases.len() <= MAX_LINEAR_CASES {
+            self.emit_linear(cases);
+        } else {
+            let (low, high) = cases.split_at(cases.len() / 2);
+            let pivot = self.pos.ins().iconst(self.ty, high[0].0 as i64);
+            let is_low = self
+                .pos
+                .ins()
+                .icmp(IntCC::UnsignedLessThan, self.value, pivot);
+            let low_block = self.new_block();
+            let high_block = self.new_block();
+            self.pos.ins().brif(is_low, low_block, &[], high_block, &[]);
+            self.emit(low_block, low);
+            self.emit(high_block, high);
+        }
+    }
+
+    fn emit_table(&mut self, cases: &[(u64, BlockCall)], first: u64, span: u64) {
+        let mut index = self.value;
+        if first != 0 {
+            let first = self.pos.ins().iconst(self.ty, first as i64);
+            index = self.pos.ins().isub(index, first);
+        }
+        match self.ty.bits() {
+            64 => {
+                // `br_table` takes a 32-bit index, so larger indices are sent to
+                // the default first.
+                let last = self.pos.ins().iconst(self.ty, (span - 1) as i64);
+                let out_of_bounds = self.pos.ins().icmp(IntCC::UnsignedGreaterThan, index, last);
+                let table_block = self.new_block();
+                let (default, args) = self.target(self.default);
+                self.pos
+                    .ins()
+                    .brif(out_of_bounds, default, &args, table_block, &[]);
+                self.pos.goto_bottom(table_block);
+                index = self.pos.ins().ireduce(types::I32, index);
+            }
+            32 => {}
+            _ => index = self.pos.ins().uextend(types::I32, index),
+        }
+
+        let mut entries = Vec::with_capacity(span as usize);
+        let mut cases = cases.iter().peekable();
+        for i in 0..span {
+            let call = match cases.next_if(|&&(k, _)| k - first == i) {
+                Some(&(_, call)) => call,
+                None => self.default,
+            };
+            entries.push(self.copy(call));
+        }
+        let default = self.copy(self.default);
+        let table = self
+            .pos
+            .func
+            .create_jump_table(JumpTableData::new(default, &entries));
+        self.pos.ins().br_table(index, table);
+    }
+
+    fn emit_linear(&mut self, cases: &[(u64, BlockCall)]) {
+        for (i, &(k, call)) in cases.iter().enumerate() {
+            let k = self.pos.ins().iconst(self.ty, k as i64);
+            let is_case = self.pos.ins().icmp(IntCC::Equal, self.value, k);
+            let (case, args) = self.target(call);
+            if i + 1 == cases.len() {
+                let (default, default_args) = self.target(self.default);
+                self.pos
+                    .ins()
+                    .brif(is_case, case, &args, default, &default_args);
+            } else {
+                let next = self.new_block();
+                self.pos.ins().brif(is_case, case, &args, next, &[]);
+                self.pos.goto_bottom(next);
+            }
+        }
+    }
+
+    fn new_block(&mut self) -> Block {
+        let block = self.pos.func.dfg.make_block();
+        self.pos
+            .func
+            .layout
+            .insert_block_after(block, self.last_block);
+        self.last_block = block;
+        block
+    }
+
+    /// Copy `call`, so that no two jump table entries share argument lists.
+    fn copy(&mut self, call: BlockCall) -> BlockCall {
+        let dfg = &mut self.pos.func.dfg;
+        let args: SmallVec<[BlockArg; 4]> = call.args(&dfg.value_lists).collect();
+        dfg.block_call(call.block(&dfg.value_lists), &args)
+    }
+
+    /// Split `call` into its block and arguments, to branch to it.
+    fn target(&self, call: BlockCall) -> (Block, SmallVec<[BlockArg; 4]>) {
+        let pool = &self.pos.func.dfg.value_lists;
+        (call.block(pool), call.args(pool).collect())
+    }
+}
+
+/// Remove the instructions among `candidates`, and those they use, which are
+/// pure and no longer used.
+fn remove_dead_insts(func: &mut Function, mut candidates: Vec<Inst>) {
+    let mut uses = SecondaryMap::<Value, u32>::new();
+    for block in func.layout.blocks() {
+        for inst in func.layout.block_insts(block) {
+            for arg in func.dfg.inst_values(inst) {
+                uses[arg] += 1;
+            }
+        }
+    }
+
+    while let Some(inst) = candidates.pop() {
+        if func.layout.inst_block(inst).is_none()
+            || !is_pure_for_egraph(func, inst)
+            || func.dfg.inst_results(inst).iter().any(|&r| uses[r] > 0)
+        {
+            continue;
+        }
+        func.layout.remove_inst(inst);
+        for arg in func.dfg.inst_values(inst) {
+            uses[arg] -= 1;
+            if uses[arg] == 0 {
+                candidates.extend(func.dfg.value_def(arg).inst());
+            }
+        }
+    }
+}
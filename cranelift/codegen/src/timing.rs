@@ -64,6 +64,9 @@ define_passes! {
     licm: "Loop invariant code motion",
     unreachable_code: "Remove unreachable blocks",
     remove_constant_phis: "Remove constant phi-nodes",
+    bounds_check_elimination: "Redundant bounds-check elimination",
+    switch_recognition: "Switch recognition",
+    loop_unrolling: "Loop unrolling",
 
     vcode_lower: "VCode lowering",
     vcode_emit: "VCode emission",
@@ -41,6 +41,8 @@ type EntryIndex = u128;
 #[derive(Debug, Default)]
 pub struct Switch {
     cases: HashMap<EntryIndex, Block>,
+    range_split_threshold: Option<EntryIndex>,
+    two_level_chunk_size: Option<u32>,
 }
 
 impl Switch {
@@ -48,9 +50,50 @@ impl Switch {
     pub fn new() -> Self {
         Self {
             cases: HashMap::new(),
+            range_split_threshold: None,
+            two_level_chunk_size: None,
         }
     }
 
+    /// Configure how cases are grouped into jump tables.
+    ///
+    /// By default a jump table is emitted for every run of consecutive entry
+    /// indexes, and any gap between entries ends the jump table. With a
+    /// threshold set:
+    ///
+    /// * Gaps of fewer than `threshold` missing entries are filled with the
+    ///   default block rather than ending the jump table.
+    /// * Runs of at least `threshold` consecutive entries which all jump to the
+    ///   same block are split out of jump tables and emitted as a range check.
+    ///
+    /// This keeps jump tables small for switches that are mostly made up of
+    /// long runs, such as large `br_table`s whose entries mostly go to a
+    /// handful of blocks.
+    pub fn set_range_split_threshold(&mut self, threshold: EntryIndex) {
+        self.range_split_threshold = Some(threshold);
+    }
+
+    /// Allow jump tables to be split into two levels of `chunk_size` entries.
+    ///
+    /// The index is split into a high part, which selects a chunk in a
+    /// first-level table, and a low part, which selects the entry within a
+    /// second-level table for that chunk. Chunks whose entries all jump to the
+    /// same block are branched to directly from the first-level table, and
+    /// chunks with identical entries share one second-level table. Two levels
+    /// are only used when that at least halves the number of table entries
+    /// compared to a single dense table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is not a power of two greater than one.
+    pub fn set_two_level_jump_tables(&mut self, chunk_size: u32) {
+        assert!(
+            chunk_size > 1 && chunk_size.is_power_of_two(),
+            "two-level jump table chunk size must be a power of two greater than one"
+        );
+        self.two_level_chunk_size = Some(chunk_size);
+    }
+
     /// Set a switch entry
     pub fn set_entry(&mut self, index: EntryIndex, block: Block) {
         let prev = self.cases.insert(index, block);
@@ -99,12 +142,80 @@ impl Switch {
         contiguous_case_ranges
     }
 
+    /// Turn the `cases` `HashMap` into a list of `ContiguousCaseRange`s, filling
+    /// small gaps with `otherwise` and splitting out long runs of the same
+    /// block as described in `set_range_split_threshold`.
+    ///
+    /// # Postconditions
+    ///
+    /// * Every entry will be represented.
+    /// * The `ContiguousCaseRange`s will not overlap and are sorted.
+    /// * No `ContiguousCaseRange`s will be empty.
+    fn collect_split_case_ranges(
+        self,
+        otherwise: Block,
+        threshold: EntryIndex,
+    ) -> Vec<ContiguousCaseRange> {
+        log::trace!("collect_split_case_ranges before: {:#?}", self.cases);
+        let mut cases = self.cases.into_iter().collect::<Vec<(_, _)>>();
+        cases.sort_by_key(|&(index, _)| index);
+
+        // A run of a single entry is no cheaper as a range check than as an
+        // equality check or a jump table entry.
+        let min_run = threshold.max(2);
+
+        let mut contiguous_case_ranges: Vec<ContiguousCaseRange> = vec![];
+        let mut i = 0;
+        while i < cases.len() {
+            let (first_index, block) = cases[i];
+            let mut j = i + 1;
+            while j < cases.len() && cases[j].0 == cases[j - 1].0 + 1 && cases[j].1 == block {
+                j += 1;
+            }
+            let last_index = cases[j - 1].0;
+
+            if last_index - first_index + 1 >= min_run {
+                contiguous_case_ranges.push(ContiguousCaseRange {
+                    first_index,
+                    blocks: vec![block],
+                    run_end: Some(last_index),
+                });
+            } else {
+                for &(index, block) in &cases[i..j] {
+                    match contiguous_case_ranges.last_mut() {
+                        Some(range)
+                            if range.run_end.is_none()
+                                && index - range.next_index() < threshold =>
+                        {
+                            let gap = index - range.next_index();
+                            range
+                                .blocks
+                                .extend(core::iter::repeat_n(otherwise, gap as usize));
+                            range.blocks.push(block);
+                        }
+                        _ => {
+                            let mut range = ContiguousCaseRange::new(index);
+                            range.blocks.push(block);
+                            contiguous_case_ranges.push(range);
+                        }
+                    }
+                }
+            }
+            i = j;
+        }
+
+        log::trace!("collect_split_case_ranges after: {contiguous_case_ranges:#?}");
+
+        contiguous_case_ranges
+    }
+
     /// Binary search for the right `ContiguousCaseRange`.
     fn build_search_tree<'a>(
         bx: &mut FunctionBuilder,
         val: Value,
         otherwise: Block,
         contiguous_case_ranges: &'a [ContiguousCaseRange],
+        two_level_chunk_size: Option<u32>,
     ) {
         // If no switch cases were added to begin with, we can just emit `jump otherwise`.
         if contiguous_case_ranges.is_empty() {
@@ -114,7 +225,13 @@ impl Switch {
 
         // Avoid allocation in the common case
         if contiguous_case_ranges.len() <= 3 {
-            Self::build_search_branches(bx, val, otherwise, contiguous_case_ranges);
+            Self::build_search_branches(
+                bx,
+                val,
+                otherwise,
+                contiguous_case_ranges,
+                two_level_chunk_size,
+            );
             return;
         }
 
@@ -127,7 +244,13 @@ impl Switch {
             }
 
             if contiguous_case_ranges.len() <= 3 {
-                Self::build_search_branches(bx, val, otherwise, contiguous_case_ranges);
+                Self::build_search_branches(
+                    bx,
+                    val,
+                    otherwise,
+                    contiguous_case_ranges,
+                    two_level_chunk_size,
+                );
             } else {
                 let split_point = contiguous_case_ranges.len() / 2;
                 let (left, right) = contiguous_case_ranges.split_at(split_point);
@@ -156,6 +279,7 @@ impl Switch {
         val: Value,
         otherwise: Block,
         contiguous_case_ranges: &'a [ContiguousCaseRange],
+        two_level_chunk_size: Option<u32>,
     ) {
         for (ix, range) in contiguous_case_ranges.iter().enumerate().rev() {
             let alternate = if ix == 0 {
@@ -167,13 +291,40 @@ impl Switch {
             if range.first_index == 0 {
                 assert_eq!(alternate, otherwise);
 
-                if let Some(block) = range.single_block() {
+                if let Some(last_index) = range.run_end {
+                    let in_range =
+                        icmp_imm_u128(bx, IntCC::UnsignedLessThanOrEqual, val, last_index);
+                    bx.ins()
+                        .brif(in_range, range.blocks[0], &[], otherwise, &[]);
+                } else if let Some(block) = range.single_block() {
                     bx.ins().brif(val, otherwise, &[], block, &[]);
                 } else {
-                    Self::build_jump_table(bx, val, otherwise, 0, &range.blocks);
+                    Self::build_jump_table(
+                        bx,
+                        val,
+                        otherwise,
+                        0,
+                        &range.blocks,
+                        two_level_chunk_size,
+                    );
                 }
             } else {
-                if let Some(block) = range.single_block() {
+                if let Some(last_index) = range.run_end {
+                    let is_good_val = icmp_imm_u128(
+                        bx,
+                        IntCC::UnsignedGreaterThanOrEqual,
+                        val,
+                        range.first_index,
+                    );
+                    let run_block = bx.create_block();
+                    bx.ins().brif(is_good_val, run_block, &[], alternate, &[]);
+                    bx.seal_block(run_block);
+                    bx.switch_to_block(run_block);
+                    let in_range =
+                        icmp_imm_u128(bx, IntCC::UnsignedLessThanOrEqual, val, last_index);
+                    bx.ins()
+                        .brif(in_range, range.blocks[0], &[], otherwise, &[]);
+                } else if let Some(block) = range.single_block() {
                     let is_good_val = icmp_imm_u128(bx, IntCC::Equal, val, range.first_index);
                     bx.ins().brif(is_good_val, block, &[], alternate, &[]);
                 } else {
@@ -187,7 +338,14 @@ impl Switch {
                     bx.ins().brif(is_good_val, jt_block, &[], alternate, &[]);
                     bx.seal_block(jt_block);
                     bx.switch_to_block(jt_block);
-                    Self::build_jump_table(bx, val, otherwise, range.first_index, &range.blocks);
+                    Self::build_jump_table(
+                        bx,
+                        val,
+                        otherwise,
+                        range.first_index,
+                        &range.blocks,
+                        two_level_chunk_size,
+                    );
                 }
             }
 
@@ -204,6 +362,7 @@ impl Switch {
         otherwise: Block,
         first_index: EntryIndex,
         blocks: &[Block],
+        two_level_chunk_size: Option<u32>,
     ) {
         // There are currently no 128bit systems supported by rustc, but once we do ensure that
         // we don't silently ignore a part of the jump table for 128bit integers on 128bit systems.
@@ -212,15 +371,6 @@ impl Switch {
             "Jump tables bigger than 2^32-1 are not yet supported"
         );
 
-        let jt_data = JumpTableData::new(
-            bx.func.dfg.block_call(otherwise, &[]),
-            &blocks
-                .iter()
-                .map(|block| bx.func.dfg.block_call(*block, &[]))
-                .collect::<Vec<_>>(),
-        );
-        let jump_table = bx.create_jump_table(jt_data);
-
         let discr = if first_index == 0 {
             val
         } else {
@@ -255,9 +405,83 @@ impl Switch {
             _ => discr,
         };
 
+        if let Some(chunk_size) = two_level_chunk_size {
+            if Self::build_two_level_jump_table(bx, discr, otherwise, blocks, chunk_size) {
+                return;
+            }
+        }
+
+        let jump_table = create_jump_table(bx, otherwise, blocks);
         bx.ins().br_table(discr, jump_table);
     }
 
+    /// Emit a jump table for `discr` as a first-level table indexed by
+    /// `discr / chunk_size` whose entries are either the single block of a
+    /// uniform chunk or a second-level table indexed by `discr % chunk_size`.
+    ///
+    /// Returns `false` without emitting anything if this wouldn't at least
+    /// halve the number of table entries.
+    fn build_two_level_jump_table(
+        bx: &mut FunctionBuilder,
+        discr: Value,
+        otherwise: Block,
+        blocks: &[Block],
+        chunk_size: u32,
+    ) -> bool {
+        let chunk_size = chunk_size as usize;
+        if blocks.len() <= chunk_size {
+            return false;
+        }
+
+        // Chunks that only jump to one block don't need a second-level table.
+        // A trailing partial chunk always does, so that the entries past the
+        // end of `blocks` go to `otherwise`.
+        let uniform = |chunk: &[Block]| {
+            chunk.len() == chunk_size && chunk.iter().all(|block| *block == chunk[0])
+        };
+        let mut unique_chunks = HashMap::new();
+        for chunk in blocks.chunks(chunk_size) {
+            if !uniform(chunk) {
+                let next = unique_chunks.len();
+                unique_chunks.entry(chunk).or_insert(next);
+            }
+        }
+        let first_level_len = blocks.len().div_ceil(chunk_size);
+        let two_level_len = first_level_len + unique_chunks.len() * chunk_size;
+        if two_level_len * 2 > blocks.len() {
+            return false;
+        }
+
+        let mut second_level = vec![None; unique_chunks.len()];
+        let first_level = blocks
+            .chunks(chunk_size)
+            .map(|chunk| {
+                if uniform(chunk) {
+                    chunk[0]
+                } else {
+                    *second_level[unique_chunks[chunk]].get_or_insert_with(|| bx.create_block())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let chunk_bits = chunk_size.trailing_zeros();
+        let high = bx.ins().ushr_imm_u(discr, i64::from(chunk_bits));
+        let jump_table = create_jump_table(bx, otherwise, &first_level);
+        bx.ins().br_table(high, jump_table);
+
+        let mut chunks = unique_chunks.into_iter().collect::<Vec<_>>();
+        chunks.sort_by_key(|&(_, i)| i);
+        for (chunk, i) in chunks {
+            let block = second_level[i].unwrap();
+            bx.seal_block(block);
+            bx.switch_to_block(block);
+            let low = bx.ins().band_imm_u(discr, (chunk_size - 1) as i64);
+            let jump_table = create_jump_table(bx, otherwise, chunk);
+            bx.ins().br_table(low, jump_table);
+        }
+        true
+    }
+
     /// Build the switch
     ///
     /// # Arguments
@@ -274,11 +498,32 @@ impl Switch {
             panic!("The index type {val_ty} does not fit the maximum switch entry of {max}");
         }
 
-        let contiguous_case_ranges = self.collect_contiguous_case_ranges();
-        Self::build_search_tree(bx, val, otherwise, &contiguous_case_ranges);
+        let two_level_chunk_size = self.two_level_chunk_size;
+        let contiguous_case_ranges = match self.range_split_threshold {
+            Some(threshold) => self.collect_split_case_ranges(otherwise, threshold),
+            None => self.collect_contiguous_case_ranges(),
+        };
+        Self::build_search_tree(
+            bx,
+            val,
+            otherwise,
+            &contiguous_case_ranges,
+            two_level_chunk_size,
+        );
     }
 }
 
+fn create_jump_table(bx: &mut FunctionBuilder, otherwise: Block, blocks: &[Block]) -> JumpTable {
+    let jt_data = JumpTableData::new(
+        bx.func.dfg.block_call(otherwise, &[]),
+        &blocks
+            .iter()
+            .map(|block| bx.func.dfg.block_call(*block, &[]))
+            .collect::<Vec<_>>(),
+    );
+    bx.create_jump_table(jt_data)
+}
+
 fn icmp_imm_u128(bx: &mut FunctionBuilder, cond: IntCC, x: Value, y: u128) -> Value {
     if bx.func.dfg.value_type(x) != types::I128 {
         assert!(u64::try_from(y).is_ok());
@@ -311,6 +556,10 @@ struct ContiguousCaseRange {
 
     /// The blocks to jump to sorted in ascending order of entry index.
     blocks: Vec<Block>,
+
+    /// If set, this range is a run where every entry index from `first_index`
+    /// up to and including `run_end` jumps to the single block in `blocks`.
+    run_end: Option<EntryIndex>,
 }
 
 impl ContiguousCaseRange {
@@ -318,9 +567,15 @@ impl ContiguousCaseRange {
         Self {
             first_index,
             blocks: Vec::new(),
+            run_end: None,
         }
     }
 
+    /// Returns the entry index just past the end of this range's jump table.
+    fn next_index(&self) -> EntryIndex {
+        self.first_index + self.blocks.len() as EntryIndex
+    }
+
     /// Returns `Some` block when there is only a single block in this range.
     fn single_block(&self) -> Option<Block> {
         if self.blocks.len() == 1 {
@@ -335,7 +590,7 @@ impl ContiguousCaseRange {
 mod tests {
     use super::*;
     use crate::frontend::FunctionBuilderContext;
-    use alloc::string::ToString;
+    use alloc::string::{String, ToString};
     use cranelift_codegen::isa::{CallConv, TargetFrontendConfig};
 
     fn systemv_frontend_config() -> TargetFrontendConfig {
@@ -582,6 +837,220 @@ block4:
         }
     }
 
+    fn setup_range_split(typ: Type, threshold: u128, cases: &[(u128, u32)]) -> String {
+        setup_switch(typ, cases, |switch| {
+            switch.set_range_split_threshold(threshold)
+        })
+    }
+
+    fn setup_two_level(typ: Type, chunk_size: u32, cases: &[(u128, u32)]) -> String {
+        setup_switch(typ, cases, |switch| {
+            switch.set_two_level_jump_tables(chunk_size)
+        })
+    }
+
+    fn setup_switch(
+        typ: Type,
+        cases: &[(u128, u32)],
+        configure: impl FnOnce(&mut Switch),
+    ) -> String {
+        let mut func = Function::new();
+        let mut func_ctx = FunctionBuilderContext::new();
+        {
+            let mut bx = FunctionBuilder::new(&mut func, &mut func_ctx);
+            let block0 = bx.create_block();
+            bx.switch_to_block(block0);
+            let val = bx.ins().iconst(typ, 0);
+            let max_block = cases.iter().map(|&(_, block)| block).max().unwrap_or(0);
+            let blocks = (0..=max_block)
+                .map(|_| bx.create_block())
+                .collect::<Vec<_>>();
+            let mut switch = Switch::new();
+            configure(&mut switch);
+            for &(index, block) in cases {
+                switch.set_entry(index, blocks[block as usize]);
+            }
+            switch.emit(&mut bx, val, blocks[0]);
+            bx.seal_block(block0);
+            for block in blocks {
+                bx.seal_block(block);
+                bx.switch_to_block(block);
+                bx.ins().return_(&[]);
+            }
+            bx.finalize(systemv_frontend_config()); // Will panic if some blocks are not sealed
+        }
+        func.to_string()
+            .trim_start_matches("function u0:0() fast {\n")
+            .split("\n\nblock1:")
+            .next()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn switch_range_split() {
+        // Entries 2 through 9 all jump to `block3` and are split out into a
+        // range check, the gap at 10 and 11 is filled with the default block.
+        let cases = [
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 3),
+            (4, 3),
+            (5, 3),
+            (6, 3),
+            (7, 3),
+            (8, 3),
+            (9, 3),
+            (12, 1),
+            (13, 2),
+            (15, 1),
+        ];
+        let func = setup_range_split(types::I32, 4, &cases);
+        assert_eq_output!(
+            func,
+            "block0:
+    v0 = iconst.i32 0
+    v1 = iconst.i32 12
+    v2 = icmp uge v0, v1  ; v0 = 0, v1 = 12
+    brif v2, block6, block5
+
+block6:
+    v3 = iconst.i32 -12
+    v4 = iadd.i32 v0, v3  ; v0 = 0, v3 = -12
+    br_table v4, block1, [block2, block3, block1, block2]
+
+block5:
+    v5 = iconst.i32 2
+    v6 = icmp.i32 uge v0, v5  ; v0 = 0, v5 = 2
+    brif v6, block8, block7
+
+block8:
+    v7 = iconst.i32 9
+    v8 = icmp.i32 ule v0, v7  ; v0 = 0, v7 = 9
+    brif v8, block4, block1
+
+block7:
+    br_table v0, block1, [block2, block3]  ; v0 = 0"
+        );
+    }
+
+    #[test]
+    fn switch_range_split_leading_run() {
+        let cases = [(0, 1), (1, 1), (2, 1), (3, 2), (200, 1)];
+        let func = setup_range_split(types::I8, 2, &cases);
+        assert_eq_output!(
+            func,
+            "block0:
+    v0 = iconst.i8 0
+    v1 = iconst.i8 -56
+    v2 = icmp eq v0, v1  ; v0 = 0, v1 = -56
+    brif v2, block2, block4
+
+block4:
+    v3 = iconst.i8 3
+    v4 = icmp.i8 eq v0, v3  ; v0 = 0, v3 = 3
+    brif v4, block3, block5
+
+block5:
+    v5 = iconst.i8 2
+    v6 = icmp.i8 ule v0, v5  ; v0 = 0, v5 = 2
+    brif v6, block2, block1"
+        );
+    }
+
+    #[test]
+    fn switch_range_split_seal_generated_blocks() {
+        let cases = (0..100)
+            .map(|i| {
+                (
+                    i,
+                    if (20..60).contains(&i) {
+                        1
+                    } else {
+                        2 + (i % 3) as u32
+                    },
+                )
+            })
+            .chain([(1000, 1)])
+            .collect::<Vec<_>>();
+        for typ in [types::I16, types::I32, types::I64, types::I128] {
+            for threshold in [1, 2, 8, 100] {
+                setup_range_split(typ, threshold, &cases);
+            }
+        }
+    }
+
+    #[test]
+    fn switch_two_level() {
+        // Entries 0 through 15 and 32 through 47 alternate between two blocks
+        // and share one second-level table. Entries 16 through 31 and 48
+        // through 63 each jump to a single block straight from the first level.
+        let cases = (0..64)
+            .map(|i| match i {
+                16..32 => (i, 3),
+                48..64 => (i, 4),
+                _ => (i, 1 + (i as u32 % 2)),
+            })
+            .collect::<Vec<_>>();
+        let func = setup_two_level(types::I32, 8, &cases);
+        assert_eq_output!(
+            func,
+            "block0:
+    v0 = iconst.i32 0
+    v1 = iconst.i32 3
+    v2 = ushr v0, v1  ; v0 = 0, v1 = 3
+    br_table v2, block1, [block6, block6, block4, block4, block6, block6, block5, block5]
+
+block6:
+    v3 = iconst.i32 7
+    v4 = band.i32 v0, v3  ; v0 = 0, v3 = 7
+    br_table v4, block1, [block2, block3, block2, block3, block2, block3, block2, block3]"
+        );
+    }
+
+    #[test]
+    fn switch_two_level_not_worth_it() {
+        // Every chunk is different, so a single jump table is kept.
+        let cases = (0..32).map(|i| (i, 1 + i as u32)).collect::<Vec<_>>();
+        let func = setup_two_level(types::I32, 8, &cases);
+        assert_eq_output!(
+            func,
+            "block0:
+    v0 = iconst.i32 0
+    br_table v0, block1, [block2, block3, block4, block5, block6, block7, block8, block9, block10, block11, block12, block13, block14, block15, block16, block17, block18, block19, block20, block21, block22, block23, block24, block25, block26, block27, block28, block29, block30, block31, block32, block33]  ; v0 = 0"
+        );
+    }
+
+    #[test]
+    fn switch_two_level_partial_chunk() {
+        // The trailing chunk is only partially filled and needs a
+        // second-level table even though all of its entries are the same.
+        let cases = (0..34)
+            .map(|i| (i, if i < 4 { 2 } else { 1 }))
+            .collect::<Vec<_>>();
+        let func = setup_two_level(types::I64, 4, &cases);
+        assert_eq_output!(
+            func,
+            "block0:
+    v0 = iconst.i64 0
+    v1 = iconst.i64 0xffff_ffff
+    v2 = icmp ugt v0, v1  ; v0 = 0, v1 = 0xffff_ffff
+    brif v2, block1, block4
+
+block4:
+    v3 = ireduce.i32 v0  ; v0 = 0
+    v4 = iconst.i32 2
+    v5 = ushr v3, v4  ; v4 = 2
+    br_table v5, block1, [block3, block2, block2, block2, block2, block2, block2, block2, block5]
+
+block5:
+    v6 = iconst.i32 3
+    v7 = band.i32 v3, v6  ; v6 = 3
+    br_table v7, block1, [block2, block2]"
+        );
+    }
+
     #[test]
     fn switch_64bit() {
         let mut func = Function::new();
@@ -1302,6 +1302,7 @@ where
         Opcode::X86Pmulhrsw => unimplemented!("X86Pmulhrsw"),
         Opcode::X86Pmaddubsw => unimplemented!("X86Pmaddubsw"),
         Opcode::X86Cvtt2dq => unimplemented!("X86Cvtt2dq"),
+        Opcode::X86Cvtt2udq => unimplemented!("X86Cvtt2udq"),
         Opcode::StackSwitch => unimplemented!("StackSwitch"),
 
         Opcode::TryCall => unimplemented!("TryCall"),
@@ -85,6 +85,9 @@ pub fn infer_native_flags(isa_builder: &mut dyn Configurable) -> Result<(), &'st
         if std::is_x86_feature_detected!("avx512bitalg") {
             isa_builder.enable("has_avx512bitalg").unwrap();
         }
+        if std::is_x86_feature_detected!("avx512bw") {
+            isa_builder.enable("has_avx512bw").unwrap();
+        }
         if std::is_x86_feature_detected!("avx512dq") {
             isa_builder.enable("has_avx512dq").unwrap();
         }
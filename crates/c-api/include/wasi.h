@@ -224,6 +224,12 @@ enum wasi_dir_perms_flags {
    * created within it.
    */
   WASMTIME_WASI_DIR_PERMS_WRITE = 2,
+
+  /**
+   * \brief New files and directories can be created within this directory,
+   * even without WASMTIME_WASI_DIR_PERMS_WRITE.
+   */
+  WASMTIME_WASI_DIR_PERMS_CREATE = 4,
 };
 
 /**
@@ -269,6 +275,7 @@ typedef size_t wasi_file_perms;
  * directory. This argument is a bitmask with the following flag values:
  * - WASMTIME_WASI_DIR_PERMS_READ
  * - WASMTIME_WASI_DIR_PERMS_WRITE
+ * - WASMTIME_WASI_DIR_PERMS_CREATE
  *
  * The `file_perms` argument is similar to `dir_perms` but corresponds to the
  * maximum set of permissions that can be used for any file in this directory.
@@ -222,6 +222,35 @@ public:
                            tables, memories);
   }
 
+  /// \brief Configures callbacks deciding whether memories and tables may
+  /// grow.
+  ///
+  /// Both callbacks receive the current size, the desired size and the
+  /// declared maximum, if any, of the memory (in bytes) or table (in
+  /// elements) that's growing. They return `true` to allow the growth,
+  /// `false` to deny it, or an error to trap.
+  ///
+  /// This replaces any limits configured with `limiter`, and the number of
+  /// instances, tables and memories keep their default limits.
+  template <
+      typename M, typename T,
+      std::enable_if_t<
+          std::is_invocable_r_v<Result<bool>, M, size_t, size_t,
+                                std::optional<size_t>> &&
+              std::is_invocable_r_v<Result<bool>, T, size_t, size_t,
+                                    std::optional<size_t>>,
+          bool> = true>
+  void limiter_callback(M &&memory_growing, T &&table_growing) {
+    using Callbacks =
+        std::pair<std::remove_reference_t<M>, std::remove_reference_t<T>>;
+    wasmtime_store_limiter_callback(
+        ptr.get(), raw_memory_growing<Callbacks>, raw_table_growing<Callbacks>,
+        std::make_unique<Callbacks>(std::forward<M>(memory_growing),
+                                    std::forward<T>(table_growing))
+            .release(),
+        raw_limiter_finalizer<Callbacks>);
+  }
+
   /// \brief Configures epoch deadline callback to C function.
   ///
   /// This function configures a store-local callback function that will be
@@ -274,6 +303,42 @@ private:
   template <typename F> static void raw_epoch_finalizer(void *data) {
     std::unique_ptr<F> _ptr(static_cast<F *>(data));
   }
+
+  template <typename F>
+  static wasmtime_error_t *raw_growing(F &callback, size_t current,
+                                       size_t desired, bool has_maximum,
+                                       size_t maximum, bool *allow) {
+    std::optional<size_t> max;
+    if (has_maximum) {
+      max = maximum;
+    }
+    auto result = callback(current, desired, max);
+    if (!result) {
+      return result.err().capi_release();
+    }
+    *allow = result.ok();
+    return nullptr;
+  }
+
+  template <typename C>
+  static wasmtime_error_t *
+  raw_memory_growing(void *data, size_t current, size_t desired,
+                     bool has_maximum, size_t maximum, bool *allow) {
+    return raw_growing(static_cast<C *>(data)->first, current, desired,
+                       has_maximum, maximum, allow);
+  }
+
+  template <typename C>
+  static wasmtime_error_t *
+  raw_table_growing(void *data, size_t current, size_t desired,
+                    bool has_maximum, size_t maximum, bool *allow) {
+    return raw_growing(static_cast<C *>(data)->second, current, desired,
+                       has_maximum, maximum, allow);
+  }
+
+  template <typename C> static void raw_limiter_finalizer(void *data) {
+    std::unique_ptr<C> _ptr(static_cast<C *>(data));
+  }
 };
 
 } // namespace wasmtime
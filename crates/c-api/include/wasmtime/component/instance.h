@@ -3,9 +3,11 @@
 #ifndef WASMTIME_COMPONENT_INSTANCE_H
 #define WASMTIME_COMPONENT_INSTANCE_H
 
+#include <wasmtime/async.h>
 #include <wasmtime/component/component.h>
 #include <wasmtime/component/func.h>
 #include <wasmtime/conf.h>
+#include <wasmtime/error.h>
 #include <wasmtime/store.h>
 
 #ifdef WASMTIME_FEATURE_COMPONENT_MODEL
@@ -60,6 +62,81 @@ WASM_API_EXTERN bool wasmtime_component_instance_get_func(
     const wasmtime_component_export_index_t *export_index,
     wasmtime_component_func_t *func_out);
 
+/**
+ * \brief A #wasmtime_component_t, pre-instantiation, that is ready to be
+ * instantiated.
+ *
+ * This is created with #wasmtime_component_linker_instantiate_pre and has all
+ * of its imports already resolved and type-checked, which makes instantiating
+ * the same component repeatedly cheaper than going through a linker each
+ * time.
+ *
+ * Must be deleted using #wasmtime_component_instance_pre_delete.
+ *
+ * For more information see the Rust documentation:
+ * https://docs.wasmtime.dev/api/wasmtime/component/struct.InstancePre.html
+ */
+typedef struct wasmtime_component_instance_pre wasmtime_component_instance_pre_t;
+
+/**
+ * \brief Delete a previously created #wasmtime_component_instance_pre_t.
+ */
+WASM_API_EXTERN void wasmtime_component_instance_pre_delete(
+    wasmtime_component_instance_pre_t *instance_pre);
+
+/**
+ * \brief Instantiates a component within the given store.
+ *
+ * \param instance_pre the pre-initialized instance
+ * \param context the #wasmtime_context_t in which the instance should be
+ *        created, which must use the same engine as \p instance_pre
+ * \param instance_out on success, the instantiated
+ *        #wasmtime_component_instance_t
+ *
+ * \return wasmtime_error_t* on success `NULL` is returned, otherwise an error
+ *         is returned which describes why instantiation failed.
+ */
+WASM_API_EXTERN wasmtime_error_t *wasmtime_component_instance_pre_instantiate(
+    const wasmtime_component_instance_pre_t *instance_pre,
+    wasmtime_context_t *context, wasmtime_component_instance_t *instance_out);
+
+/**
+ * \brief Get the component (as a shallow clone) for an instance_pre.
+ *
+ * The returned component is owned by the caller and the caller **must**
+ * delete it via #wasmtime_component_delete.
+ */
+WASM_API_EXTERN wasmtime_component_t *wasmtime_component_instance_pre_component(
+    const wasmtime_component_instance_pre_t *instance_pre);
+
+#ifdef WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
+
+/**
+ * \brief Instantiate a component from \p instance_pre asynchronously.
+ *
+ * This is the same as #wasmtime_component_instance_pre_instantiate except
+ * that it is asynchronous. This is only compatible with stores associated
+ * with an asynchronous config.
+ *
+ * The result is a future that is owned by the caller and must be deleted via
+ * #wasmtime_call_future_delete.
+ *
+ * All parameters to this function must be kept alive and not modified until the
+ * returned #wasmtime_call_future_t is deleted.
+ *
+ * \param instance_pre the pre-initialized instance
+ * \param context the store context
+ * \param instance_out where to store the returned instance
+ * \param error_ret where to store the returned error
+ */
+WASM_API_EXTERN wasmtime_call_future_t *
+wasmtime_component_instance_pre_instantiate_async(
+    const wasmtime_component_instance_pre_t *instance_pre,
+    wasmtime_context_t *context, wasmtime_component_instance_t *instance_out,
+    wasmtime_error_t **error_ret);
+
+#endif // WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
+
 #ifdef __cplusplus
 } // extern "C"
 #endif
@@ -62,6 +62,32 @@ public:
   const wasmtime_component_instance_t *capi() const { return &instance; }
 };
 
+/**
+ * \brief A component which has had its imports resolved and type-checked
+ * ahead of time, ready to be instantiated.
+ *
+ * This is created with `Linker::instantiate_pre`.
+ */
+class InstancePre {
+  WASMTIME_OWN_WRAPPER(InstancePre, wasmtime_component_instance_pre);
+
+  /// \brief Instantiates this component within the given store.
+  Result<Instance> instantiate(Store::Context cx) const {
+    wasmtime_component_instance_t ret;
+    wasmtime_error_t *error =
+        wasmtime_component_instance_pre_instantiate(ptr.get(), cx.capi(), &ret);
+    if (error != nullptr) {
+      return Error(error);
+    }
+    return Instance(ret);
+  }
+
+  /// \brief Returns the component that this will instantiate.
+  Component component() const {
+    return Component(wasmtime_component_instance_pre_component(ptr.get()));
+  }
+};
+
 } // namespace component
 } // namespace wasmtime
 
@@ -77,6 +77,28 @@ WASM_API_EXTERN wasmtime_error_t *wasmtime_component_linker_instantiate(
     const wasmtime_component_t *component,
     wasmtime_component_instance_t *instance_out);
 
+/**
+ * \brief Performs all the type-checking and import resolution necessary to
+ * instantiate \p component ahead of time.
+ *
+ * The returned #wasmtime_component_instance_pre_t can be used to instantiate
+ * \p component many times, in any store of the linker's engine, without
+ * consulting the linker again.
+ *
+ * \param linker the linker to resolve imports with
+ * \param component the #wasmtime_component_t to pre-instantiate
+ * \param instance_pre_out on success, the returned
+ *        #wasmtime_component_instance_pre_t which must be deleted with
+ *        #wasmtime_component_instance_pre_delete
+ *
+ * \return wasmtime_error_t* on success `NULL` is returned, otherwise an error
+ *         is returned which describes why the imports couldn't be resolved.
+ */
+WASM_API_EXTERN wasmtime_error_t *wasmtime_component_linker_instantiate_pre(
+    const wasmtime_component_linker_t *linker,
+    const wasmtime_component_t *component,
+    wasmtime_component_instance_pre_t **instance_pre_out);
+
 /**
  * \brief Defines all unknown imports of `component` as trapping functions.
  */
@@ -199,6 +199,18 @@ class Linker {
     return Instance(ret);
   }
 
+  /// \brief Resolves and type-checks the imports of the given component
+  /// ahead of time so it can be instantiated repeatedly.
+  Result<InstancePre> instantiate_pre(const Component &component) const {
+    wasmtime_component_instance_pre_t *ret = nullptr;
+    wasmtime_error_t *error = wasmtime_component_linker_instantiate_pre(
+        ptr.get(), component.capi(), &ret);
+    if (error != nullptr) {
+      return Error(error);
+    }
+    return InstancePre(ret);
+  }
+
 #ifdef WASMTIME_FEATURE_WASI
   /**
    * \brief Adds WASIp2 API definitions to this linker.
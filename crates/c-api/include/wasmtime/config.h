@@ -464,6 +464,18 @@ WASMTIME_CONFIG_PROP(void, memory_reservation_for_growth, uint64_t)
  */
 WASMTIME_CONFIG_PROP(void, native_unwind_info, bool)
 
+/**
+ * \brief Configures whether compiled code signs return addresses with pointer
+ * authentication on aarch64.
+ *
+ * This option defaults to true when compiling for the host on macOS and false
+ * otherwise.
+ *
+ * For more information see the Rust documentation at
+ * https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.sign_return_address
+ */
+WASMTIME_CONFIG_PROP(void, sign_return_address, bool)
+
 #ifdef WASMTIME_FEATURE_CACHE
 
 /**
@@ -567,6 +567,13 @@ class Config {
     wasmtime_config_native_unwind_info_set(ptr.get(), enable);
   }
 
+  /// \brief Configures whether return addresses are signed on aarch64.
+  ///
+  /// https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.sign_return_address
+  void sign_return_address(bool enable) {
+    wasmtime_config_sign_return_address_set(ptr.get(), enable);
+  }
+
   /// \brief Configures whether mach ports are used on macOS
   ///
   /// https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.macos_use_mach_ports
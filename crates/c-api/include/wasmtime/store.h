@@ -114,6 +114,48 @@ WASM_API_EXTERN void wasmtime_store_limiter(wasmtime_store_t *store,
                                             int64_t instances, int64_t tables,
                                             int64_t memories);
 
+/**
+ * \brief Callback signature for #wasmtime_store_limiter_callback.
+ *
+ * This is invoked when a linear memory or table is about to grow from
+ * `current` to `desired` (bytes for memories, elements for tables).
+ * `has_maximum` indicates whether the memory or table declares a maximum, in
+ * which case it's provided in `maximum`.
+ *
+ * The callback can:
+ * - leave `allow` set to `true` and return NULL to permit the growth.
+ * - set `allow` to `false` and return NULL to deny the growth, which is
+ *   reported to WebAssembly as a failed `memory.grow` or `table.grow` (or as an
+ *   instantiation failure for initial allocations).
+ * - return a #wasmtime_error_t to trap instead.
+ */
+typedef wasmtime_error_t *(*wasmtime_growing_callback_t)(
+    void *data, size_t current, size_t desired, bool has_maximum,
+    size_t maximum, bool *allow);
+
+/**
+ * \brief Configures callbacks which decide whether memories and tables in a
+ * store may grow.
+ *
+ * \param store store where the limiter should be configured.
+ * \param memory_growing callback invoked when a linear memory is about to
+ * grow, or NULL to always allow memories to grow.
+ * \param table_growing callback invoked when a table is about to grow, or
+ * NULL to always allow tables to grow.
+ * \param data user-provided data passed to both callbacks.
+ * \param finalizer optional finalizer for `data`, run when the store is
+ * deleted or the limiter is replaced.
+ *
+ * This replaces any limits previously configured with
+ * #wasmtime_store_limiter, and calling #wasmtime_store_limiter afterwards
+ * replaces these callbacks. The number of instances, tables and memories in
+ * the store keep their default limits of 10,000.
+ */
+WASM_API_EXTERN void wasmtime_store_limiter_callback(
+    wasmtime_store_t *store, wasmtime_growing_callback_t memory_growing,
+    wasmtime_growing_callback_t table_growing, void *data,
+    void (*finalizer)(void *));
+
 /**
  * \brief Deletes a store.
  */
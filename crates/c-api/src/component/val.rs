@@ -344,6 +344,9 @@ impl From<&Val> for wasmtime_component_val_t {
             Val::Char(x) => wasmtime_component_val_t::Char(*x as _),
             Val::String(x) => wasmtime_component_val_t::String(wasm_name_t::from_name(x.clone())),
             Val::List(x) => wasmtime_component_val_t::List(x.as_slice().into()),
+            Val::PackedList(x) => {
+                wasmtime_component_val_t::List(x.iter().collect::<Vec<_>>().as_slice().into())
+            }
             Val::Record(x) => wasmtime_component_val_t::Record(x.as_slice().into()),
             Val::Tuple(x) => wasmtime_component_val_t::Tuple(x.as_slice().into()),
             Val::Variant(discriminant, val) => {
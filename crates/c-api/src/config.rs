@@ -298,6 +298,11 @@ pub extern "C" fn wasmtime_config_native_unwind_info_set(c: &mut wasm_config_t,
     c.config.native_unwind_info(enabled);
 }
 
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_config_sign_return_address_set(c: &mut wasm_config_t, enabled: bool) {
+    c.config.sign_return_address(enabled);
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn wasmtime_config_target_set(
     c: &mut wasm_config_t,
@@ -3,8 +3,8 @@ use std::cell::UnsafeCell;
 use std::ffi::c_void;
 use std::sync::Arc;
 use wasmtime::{
-    AsContext, AsContextMut, Caller, Store, StoreContext, StoreContextMut, StoreLimits,
-    StoreLimitsBuilder, UpdateDeadline, Val,
+    AsContext, AsContextMut, Caller, ResourceLimiter, Result, Store, StoreContext, StoreContextMut,
+    StoreLimits, StoreLimitsBuilder, UpdateDeadline, Val,
 };
 
 // Store-related type aliases for `wasm.h` APIs. Not for use with `wasmtime.h`
@@ -97,6 +97,9 @@ pub struct WasmtimeStoreData {
 
     /// Limits for the store.
     pub store_limits: StoreLimits,
+
+    /// Limiter configured with `wasmtime_store_limiter_callback`, if any.
+    pub limiter_callback: Option<CallbackLimiter>,
 }
 
 #[cfg(all(feature = "component-model", feature = "wasi"))]
@@ -138,6 +141,7 @@ pub extern "C" fn wasmtime_store_new(
                 hostcall_val_storage: Vec::new(),
                 wasm_val_storage: Vec::new(),
                 store_limits: StoreLimits::default(),
+                limiter_callback: None,
             },
         ),
     })
@@ -214,10 +218,93 @@ pub extern "C" fn wasmtime_store_limiter(
     if memories >= 0 {
         limiter = limiter.memories(memories as usize);
     }
-    store.store.data_mut().store_limits = limiter.build();
+    let data = store.store.data_mut();
+    data.store_limits = limiter.build();
+    data.limiter_callback = None;
     store.store.limiter(|data| &mut data.store_limits);
 }
 
+pub type wasmtime_growing_callback_t = extern "C" fn(
+    *mut c_void,
+    usize,
+    usize,
+    bool,
+    usize,
+    &mut bool,
+) -> Option<Box<wasmtime_error_t>>;
+
+/// A `ResourceLimiter` which defers decisions about growing memories and
+/// tables to callbacks provided by the embedder.
+pub struct CallbackLimiter {
+    memory_growing: Option<wasmtime_growing_callback_t>,
+    table_growing: Option<wasmtime_growing_callback_t>,
+    foreign: ForeignData,
+}
+
+impl CallbackLimiter {
+    fn growing(
+        &self,
+        callback: Option<wasmtime_growing_callback_t>,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let Some(callback) = callback else {
+            return Ok(true);
+        };
+        let mut allow = true;
+        match callback(
+            self.foreign.data,
+            current,
+            desired,
+            maximum.is_some(),
+            maximum.unwrap_or(0),
+            &mut allow,
+        ) {
+            Some(err) => Err((*err).into()),
+            None => Ok(allow),
+        }
+    }
+}
+
+impl ResourceLimiter for CallbackLimiter {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.growing(self.memory_growing, current, desired, maximum)
+    }
+
+    fn table_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.growing(self.table_growing, current, desired, maximum)
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_store_limiter_callback(
+    store: &mut wasmtime_store_t,
+    memory_growing: Option<wasmtime_growing_callback_t>,
+    table_growing: Option<wasmtime_growing_callback_t>,
+    data: *mut c_void,
+    finalizer: Option<extern "C" fn(*mut c_void)>,
+) {
+    store.store.data_mut().limiter_callback = Some(CallbackLimiter {
+        memory_growing,
+        table_growing,
+        foreign: ForeignData { data, finalizer },
+    });
+    store
+        .store
+        .limiter(|data| data.limiter_callback.as_mut().unwrap());
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn wasmtime_context_get_data(store: WasmtimeStoreContext<'_>) -> *mut c_void {
     store.data().foreign.data
@@ -21,3 +21,24 @@ TEST(component, instantiate) {
 
   linker.instantiate(context, component).unwrap();
 }
+
+TEST(component, instantiate_pre) {
+  static constexpr auto bytes = std::string_view{
+      R"END(
+      (component
+          (core module)
+      )
+      )END",
+  };
+
+  wasmtime::Engine engine;
+  Component component = Component::compile(engine, bytes).unwrap();
+  Linker linker(engine);
+  InstancePre pre = linker.instantiate_pre(component).unwrap();
+  pre.component();
+
+  wasmtime::Store store1(engine);
+  pre.instantiate(store1.context()).unwrap();
+  wasmtime::Store store2(engine);
+  pre.instantiate(store2.context()).unwrap();
+}
@@ -65,6 +65,7 @@ TEST(Config, Smoke) {
   config.memory_may_move(false);
   config.memory_init_cow(false);
   config.native_unwind_info(false);
+  config.sign_return_address(false);
   config.macos_use_mach_ports(false);
   config.cranelift_flag_enable("foo");
   config.cranelift_flag_set("foo", "bar");
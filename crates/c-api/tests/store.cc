@@ -7,6 +7,9 @@
 #include <wasmtime/instance.hh>
 #include <wasmtime/module.hh>
 
+#include <tuple>
+#include <vector>
+
 using namespace wasmtime;
 
 template <typename T, typename E> T unwrap(Result<T, E> result) {
@@ -31,6 +34,52 @@ TEST(Store, Smoke) {
   store.context().set_epoch_deadline(1);
 }
 
+TEST(Store, LimiterCallback) {
+  Engine engine;
+  Store store(engine);
+
+  std::vector<std::tuple<size_t, size_t, std::optional<size_t>>> memory_grows;
+  size_t table_grows = 0;
+  store.limiter_callback(
+      [&memory_grows](size_t current, size_t desired,
+                      std::optional<size_t> maximum) -> Result<bool> {
+        memory_grows.emplace_back(current, desired, maximum);
+        return desired <= 2 * 65536;
+      },
+      [&table_grows](size_t current, size_t desired,
+                     std::optional<size_t> maximum) -> Result<bool> {
+        table_grows += 1;
+        return Error("table growth denied");
+      });
+
+  Module m = unwrap(Module::compile(engine, R"(
+    (module
+      (memory 1 3)
+      (func (export "grow") (param i32) (result i32)
+        (memory.grow (local.get 0)))
+    )
+  )"));
+  Instance i = unwrap(Instance::create(store, m, {}));
+  auto grow = std::get<Func>(*i.get(store, "grow"));
+
+  auto results = unwrap(grow.call(store, {int32_t(1)}));
+  EXPECT_EQ(results[0].i32(), 1);
+  results = unwrap(grow.call(store, {int32_t(1)}));
+  EXPECT_EQ(results[0].i32(), -1);
+
+  const size_t page = 65536;
+  const std::optional<size_t> max = 3 * page;
+  ASSERT_EQ(memory_grows.size(), 3);
+  EXPECT_EQ(memory_grows[0], std::make_tuple(size_t(0), page, max));
+  EXPECT_EQ(memory_grows[1], std::make_tuple(page, 2 * page, max));
+  EXPECT_EQ(memory_grows[2], std::make_tuple(2 * page, 3 * page, max));
+
+  Module t = unwrap(Module::compile(engine, "(module (table 1 funcref))"));
+  auto result = Instance::create(store, t, {});
+  EXPECT_FALSE(result);
+  EXPECT_EQ(table_grows, 1);
+}
+
 TEST(Store, EpochDeadlineCallback) {
   Config config;
   config.epoch_interruption(true);
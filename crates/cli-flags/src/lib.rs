@@ -106,6 +106,26 @@ wasmtime_option_group! {
         /// effectively disables decommit batching. (default: 1)
         pub pooling_decommit_batch_size: Option<usize>,
 
+        /// The number of bytes to decommit per batch, flushing batches before
+        /// they reach their batch size. (default: unlimited)
+        pub pooling_decommit_batch_bytes: Option<usize>,
+
+        /// The maximum amount of time memory may wait to be decommitted
+        /// before its batch is flushed, e.g. `10ms`. (default: unlimited)
+        pub pooling_decommit_max_delay: Option<Duration>,
+
+        /// How memory is returned to the OS when it's decommitted: `release`
+        /// it immediately, zero it and `lazy-free` it, or `zero` it and keep
+        /// it resident. (default: release)
+        #[serde(default)]
+        #[serde(deserialize_with = "crate::opt::deserialize_cli_parse_wrapper")]
+        #[serde(serialize_with = "crate::opt::serialize_cli_parse_wrapper")]
+        pub pooling_decommit_policy: Option<wasmtime::DecommitPolicy>,
+
+        /// Reset deallocated memories, tables and stacks on a background
+        /// thread instead of the deallocating thread. (default: no)
+        pub pooling_background_decommit: Option<bool>,
+
         /// How many bytes to keep resident between instantiations for the
         /// pooling allocator in linear memories.
         pub pooling_memory_keep_resident: Option<usize>,
@@ -266,9 +286,15 @@ wasmtime_option_group! {
         pub cache_config: Option<String>,
         /// Whether or not to enable parallel compilation of modules.
         pub parallel_compilation: Option<bool>,
+        /// The number of threads to use for parallel compilation, instead of
+        /// one per CPU.
+        pub compilation_threads: Option<usize>,
         /// Controls whether native unwind information is present in compiled
         /// object files.
         pub native_unwind_info: Option<bool>,
+        /// Whether compiled code signs return addresses with pointer
+        /// authentication on aarch64.
+        pub sign_return_address: Option<bool>,
 
         /// Whether to perform function inlining during compilation.
         #[serde(default)]
@@ -351,6 +377,9 @@ wasmtime_option_group! {
         /// Yield when a global epoch counter changes, allowing for async
         /// operation without blocking the executor.
         pub epoch_interruption: Option<bool>,
+        /// Insert an additional epoch check after N operators of
+        /// straight-line code without one.
+        pub epoch_interruption_check_interval: Option<NonZeroU32>,
         /// Maximum stack size, in bytes, that wasm is allowed to consume before a
         /// stack overflow is reported.
         pub max_wasm_stack: Option<usize>,
@@ -507,8 +536,14 @@ wasmtime_option_group! {
         /// Maximum size allowed in a write call to the outgoing body's output-stream.
         /// Default: 1024 * 1024.
         pub http_outgoing_body_chunk_size: Option<usize>,
+        /// Enable WASI APIs marked as: @unstable(feature = informational-outbound-responses)
+        pub http_informational_responses: Option<bool>,
         /// Enable support for WASI config imports (experimental)
         pub config: Option<bool>,
+        /// Enable support for the `wasmtime:correlation` imports and
+        /// propagation of request correlation context (experimental, `serve`
+        /// only)
+        pub correlation: Option<bool>,
         /// Enable support for WASI key-value imports (experimental)
         pub keyvalue: Option<bool>,
         /// Inherit environment variables and file descriptors following the
@@ -935,6 +970,11 @@ impl CommonOptions {
             enable => config.parallel_compilation(enable),
             true => err,
         }
+        match_feature! {
+            ["parallel-compilation" : self.codegen.compilation_threads]
+            threads => config.compilation_threads(threads),
+            _ => err,
+        }
 
         let memory_reservation = self
             .opts
@@ -1006,6 +1046,9 @@ impl CommonOptions {
         if let Some(enable) = self.wasm.epoch_interruption {
             config.epoch_interruption(enable);
         }
+        if let Some(interval) = self.wasm.epoch_interruption_check_interval {
+            config.epoch_interruption_check_interval(Some(interval));
+        }
         if let Some(enable) = self.debug.address_map {
             config.generate_address_map(enable);
         }
@@ -1034,6 +1077,9 @@ impl CommonOptions {
         if let Some(enable) = self.codegen.native_unwind_info {
             config.native_unwind_info(enable);
         }
+        if let Some(enable) = self.codegen.sign_return_address {
+            config.sign_return_address(enable);
+        }
         if let Some(enable) = self.codegen.inlining {
             config.compiler_inlining(enable);
         }
@@ -1105,6 +1151,18 @@ impl CommonOptions {
                     if let Some(size) = self.opts.pooling_decommit_batch_size {
                         cfg.decommit_batch_size(size);
                     }
+                    if let Some(bytes) = self.opts.pooling_decommit_batch_bytes {
+                        cfg.decommit_batch_bytes(bytes);
+                    }
+                    if let Some(delay) = self.opts.pooling_decommit_max_delay {
+                        cfg.decommit_max_delay(Some(delay));
+                    }
+                    if let Some(policy) = self.opts.pooling_decommit_policy {
+                        cfg.decommit_policy(policy);
+                    }
+                    if let Some(enable) = self.opts.pooling_background_decommit {
+                        cfg.background_decommit(enable);
+                    }
                     if let Some(max) = self.opts.pooling_max_unused_warm_slots {
                         cfg.max_unused_warm_slots(max);
                     }
@@ -1353,6 +1411,10 @@ impl CommonOptions {
                 regalloc_algorithm: engine.get_cranelift_regalloc_algorithm(),
                 pooling_allocator: Some(pooling.is_some()),
                 pooling_decommit_batch_size: pooling.map(|c| c.get_decommit_batch_size()),
+                pooling_decommit_batch_bytes: pooling.map(|c| c.get_decommit_batch_bytes()),
+                pooling_decommit_max_delay: pooling.and_then(|c| c.get_decommit_max_delay()),
+                pooling_decommit_policy: pooling.map(|c| c.get_decommit_policy()),
+                pooling_background_decommit: pooling.map(|c| c.get_background_decommit()),
                 pooling_memory_keep_resident: pooling.map(|c| c.get_memory_keep_resident()),
                 pooling_table_keep_resident: pooling.map(|c| c.get_table_keep_resident()),
                 pooling_max_unused_warm_slots: pooling.map(|c| c.get_max_unused_warm_slots()),
@@ -1395,7 +1457,12 @@ impl CommonOptions {
                 cranelift_debug_verifier: engine.get_cranelift_debug_verifier(),
                 inlining: Some(engine.get_compiler_inlining()),
                 native_unwind_info: engine.get_native_unwind_info(),
+                sign_return_address: engine.get_sign_return_address(),
                 parallel_compilation: Some(engine.get_parallel_compilation()),
+                #[cfg(feature = "parallel-compilation")]
+                compilation_threads: engine.get_compilation_threads(),
+                #[cfg(not(feature = "parallel-compilation"))]
+                compilation_threads: None,
                 metadata_for_internal_asserts: Some(engine.get_metadata_for_internal_asserts()),
                 metadata_for_gc_heap_corruption: Some(engine.get_metadata_for_gc_heap_corruption()),
                 cranelift: engine
@@ -1478,6 +1545,7 @@ impl CommonOptions {
                 wide_arithmetic: Some(features.contains(WasmFeatures::WIDE_ARITHMETIC)),
                 concurrency_support: Some(engine.get_concurrency_support()),
                 epoch_interruption: Some(engine.get_epoch_interruption()),
+                epoch_interruption_check_interval: engine.get_epoch_interruption_check_interval(),
                 fuel: if engine.get_consume_fuel() {
                     Some(1)
                 } else {
@@ -599,6 +599,28 @@ impl WasmtimeOptionValue for wasmtime::Enabled {
     }
 }
 
+impl WasmtimeOptionValue for wasmtime::DecommitPolicy {
+    const VAL_HELP: &'static str = "=release|lazy-free|zero";
+    fn parse(val: Option<&str>) -> Result<Self> {
+        match String::parse(val)?.as_str() {
+            "release" => Ok(wasmtime::DecommitPolicy::Release),
+            "lazy-free" => Ok(wasmtime::DecommitPolicy::LazyFree),
+            "zero" => Ok(wasmtime::DecommitPolicy::Zero),
+            other => {
+                bail!("unknown decommit policy `{other}`, only release,lazy-free,zero accepted")
+            }
+        }
+    }
+
+    fn display(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            wasmtime::DecommitPolicy::Release => f.write_str("release"),
+            wasmtime::DecommitPolicy::LazyFree => f.write_str("lazy-free"),
+            wasmtime::DecommitPolicy::Zero => f.write_str("zero"),
+        }
+    }
+}
+
 impl WasmtimeOptionValue for wasmtime::Inlining {
     const VAL_HELP: &'static str = "[=y|n|gc|inter-module|intrinsics]";
     fn parse(val: Option<&str>) -> Result<Self> {
@@ -151,6 +151,10 @@ impl Parse for Config {
                     Opt::Anyhow(val) => {
                         opts.anyhow = val;
                     }
+                    Opt::BlockingWrappers(f) => {
+                        opts.blocking_wrappers = Some(f.into_token_stream().to_string())
+                    }
+                    Opt::Mocks(val) => opts.mocks = val,
                     Opt::IncludeGeneratedCodeFromFile(i) => include_generated_code_from_file = i,
                     Opt::Imports(config, span) => {
                         if imports_configured {
@@ -262,6 +266,8 @@ mod kw {
     syn::custom_keyword!(require_store_data_send);
     syn::custom_keyword!(wasmtime_crate);
     syn::custom_keyword!(anyhow);
+    syn::custom_keyword!(blocking_wrappers);
+    syn::custom_keyword!(mocks);
     syn::custom_keyword!(include_generated_code_from_file);
     syn::custom_keyword!(debug);
     syn::custom_keyword!(imports);
@@ -286,6 +292,8 @@ enum Opt {
     RequireStoreDataSend(bool),
     WasmtimeCrate(syn::Path),
     Anyhow(bool),
+    BlockingWrappers(syn::Path),
+    Mocks(bool),
     IncludeGeneratedCodeFromFile(bool),
     Debug(bool),
     Imports(FunctionConfig, Span),
@@ -424,6 +432,14 @@ impl Parse for Opt {
             input.parse::<kw::anyhow>()?;
             input.parse::<Token![:]>()?;
             Ok(Opt::Anyhow(input.parse::<syn::LitBool>()?.value))
+        } else if l.peek(kw::blocking_wrappers) {
+            input.parse::<kw::blocking_wrappers>()?;
+            input.parse::<Token![:]>()?;
+            Ok(Opt::BlockingWrappers(input.parse()?))
+        } else if l.peek(kw::mocks) {
+            input.parse::<kw::mocks>()?;
+            input.parse::<Token![:]>()?;
+            Ok(Opt::Mocks(input.parse::<syn::LitBool>()?.value))
         } else if l.peek(kw::include_generated_code_from_file) {
             input.parse::<kw::include_generated_code_from_file>()?;
             input.parse::<Token![:]>()?;
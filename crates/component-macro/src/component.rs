@@ -12,6 +12,7 @@ mod kw {
     syn::custom_keyword!(variant);
     syn::custom_keyword!(flags);
     syn::custom_keyword!(name);
+    syn::custom_keyword!(rename_all);
     syn::custom_keyword!(wasmtime_crate);
 }
 
@@ -32,10 +33,26 @@ impl fmt::Display for Style {
     }
 }
 
+/// How the component model names of fields and cases are derived from their
+/// Rust names when they don't have a `#[component(name = "...")]` attribute.
+#[derive(Debug, Copy, Clone)]
+enum RenameAll {
+    KebabCase,
+}
+
+impl RenameAll {
+    fn apply(&self, name: &str) -> String {
+        match self {
+            RenameAll::KebabCase => to_kebab_case(name),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum ComponentAttr {
     Style(Style),
     WasmtimeCrate(syn::Path),
+    RenameAll(RenameAll),
 }
 
 impl Parse for ComponentAttr {
@@ -54,6 +71,17 @@ impl Parse for ComponentAttr {
             input.parse::<kw::wasmtime_crate>()?;
             input.parse::<Token![=]>()?;
             Ok(ComponentAttr::WasmtimeCrate(input.parse()?))
+        } else if lookahead.peek(kw::rename_all) {
+            input.parse::<kw::rename_all>()?;
+            input.parse::<Token![=]>()?;
+            let style = input.parse::<syn::LitStr>()?;
+            match style.value().as_str() {
+                "kebab-case" => Ok(ComponentAttr::RenameAll(RenameAll::KebabCase)),
+                _ => Err(Error::new_spanned(
+                    style,
+                    "unsupported `rename_all` style; only \"kebab-case\" is supported",
+                )),
+            }
         } else if input.peek(kw::flags) {
             Err(input.error(
                 "`flags` not allowed here; \
@@ -91,6 +119,51 @@ fn find_rename(attributes: &[syn::Attribute]) -> Result<Option<syn::LitStr>> {
     Ok(name)
 }
 
+/// Returns the component model name of a field or case, which is either its
+/// explicit `#[component(name = "...")]` or derived from its Rust name.
+fn component_name(
+    attributes: &[syn::Attribute],
+    ident: &syn::Ident,
+    rename_all: Option<RenameAll>,
+) -> Result<syn::LitStr> {
+    if let Some(name) = find_rename(attributes)? {
+        return Ok(name);
+    }
+    let name = ident.to_string();
+    let name = name.strip_prefix("r#").unwrap_or(&name);
+    let name = match rename_all {
+        Some(rename_all) => rename_all.apply(name),
+        None => name.to_string(),
+    };
+    Ok(syn::LitStr::new(&name, ident.span()))
+}
+
+/// Converts a Rust `snake_case` or `UpperCamelCase` name to WIT `kebab-case`.
+fn to_kebab_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut ret = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' {
+            if !ret.is_empty() && !ret.ends_with('-') {
+                ret.push('-');
+            }
+            continue;
+        }
+        if c.is_uppercase() && i > 0 && !ret.ends_with('-') {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                ret.push('-');
+            }
+        }
+        ret.extend(c.to_lowercase());
+    }
+    ret.trim_end_matches('-').to_string()
+}
+
 fn add_trait_bounds(generics: &syn::Generics, bound: syn::TypeParamBound) -> syn::Generics {
     let mut generics = generics.clone();
     for param in &mut generics.params {
@@ -101,8 +174,13 @@ fn add_trait_bounds(generics: &syn::Generics, bound: syn::TypeParamBound) -> syn
     generics
 }
 
+pub struct RecordField<'a> {
+    name: syn::LitStr,
+    field: &'a syn::Field,
+}
+
 pub struct VariantCase<'a> {
-    attrs: &'a [syn::Attribute],
+    name: syn::LitStr,
     ident: &'a syn::Ident,
     ty: Option<&'a syn::Type>,
 }
@@ -112,7 +190,7 @@ pub trait Expander {
         &self,
         name: &syn::Ident,
         generics: &syn::Generics,
-        fields: &[&syn::Field],
+        fields: &[RecordField],
         wasmtime_crate: &syn::Path,
     ) -> Result<TokenStream>;
 
@@ -137,6 +215,7 @@ pub trait Expander {
 pub fn expand(expander: &dyn Expander, input: &DeriveInput) -> Result<TokenStream> {
     let mut wasmtime_crate = None;
     let mut style = None;
+    let mut rename_all = None;
 
     for attribute in &input.attrs {
         if !attribute.path().is_ident("component") {
@@ -144,6 +223,7 @@ pub fn expand(expander: &dyn Expander, input: &DeriveInput) -> Result<TokenStrea
         }
         match attribute.parse_args()? {
             ComponentAttr::WasmtimeCrate(c) => wasmtime_crate = Some(c),
+            ComponentAttr::RenameAll(r) => rename_all = Some(r),
             ComponentAttr::Style(attr_style) => {
                 if style.is_some() {
                     return Err(Error::new_spanned(
@@ -156,11 +236,37 @@ pub fn expand(expander: &dyn Expander, input: &DeriveInput) -> Result<TokenStrea
         }
     }
 
-    let style = style.ok_or_else(|| Error::new_spanned(input, "missing `component` attribute"))?;
+    let style = match style {
+        Some(style) => style,
+        None => infer_style(input)?,
+    };
     let wasmtime_crate = wasmtime_crate.unwrap_or_else(default_wasmtime_crate);
     match style {
-        Style::Record => expand_record(expander, input, &wasmtime_crate),
-        Style::Enum | Style::Variant => expand_variant(expander, input, style, &wasmtime_crate),
+        Style::Record => expand_record(expander, input, rename_all, &wasmtime_crate),
+        Style::Enum | Style::Variant => {
+            expand_variant(expander, input, style, rename_all, &wasmtime_crate)
+        }
+    }
+}
+
+/// Picks the style of a type without a `#[component(record)]`,
+/// `#[component(variant)]`, or `#[component(enum)]` attribute.
+///
+/// Structs are records and enums with a payload in any case are variants.
+/// Enums without any payloads could be either an `enum` or a `variant` in the
+/// component model so they require an explicit attribute.
+fn infer_style(input: &DeriveInput) -> Result<Style> {
+    match &input.data {
+        Data::Struct(_) => Ok(Style::Record),
+        Data::Enum(body) if body.variants.iter().any(|v| !v.fields.is_empty()) => {
+            Ok(Style::Variant)
+        }
+        Data::Enum(_) => Err(Error::new_spanned(
+            input,
+            "missing `component` attribute; use `#[component(enum)]` or \
+             `#[component(variant)]` to specify how this `enum` is bound",
+        )),
+        Data::Union(_) => Err(Error::new_spanned(input, "missing `component` attribute")),
     }
 }
 
@@ -171,6 +277,7 @@ fn default_wasmtime_crate() -> syn::Path {
 fn expand_record(
     expander: &dyn Expander,
     input: &DeriveInput,
+    rename_all: Option<RenameAll>,
     wasmtime_crate: &syn::Path,
 ) -> Result<TokenStream> {
     let name = &input.ident;
@@ -188,7 +295,20 @@ fn expand_record(
         syn::Fields::Named(fields) => expander.expand_record(
             &input.ident,
             &input.generics,
-            &fields.named.iter().collect::<Vec<_>>(),
+            &fields
+                .named
+                .iter()
+                .map(|field| {
+                    Ok(RecordField {
+                        name: component_name(
+                            &field.attrs,
+                            field.ident.as_ref().unwrap(),
+                            rename_all,
+                        )?,
+                        field,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
             wasmtime_crate,
         ),
 
@@ -203,6 +323,7 @@ fn expand_variant(
     expander: &dyn Expander,
     input: &DeriveInput,
     style: Style,
+    rename_all: Option<RenameAll>,
     wasmtime_crate: &syn::Path,
 ) -> Result<TokenStream> {
     let name = &input.ident;
@@ -243,7 +364,7 @@ fn expand_variant(
                  ..
              }| {
                 Ok(VariantCase {
-                    attrs,
+                    name: component_name(attrs, ident, rename_all)?,
                     ident,
                     ty: match fields {
                         syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
@@ -451,7 +572,7 @@ impl Expander for LiftExpander {
         &self,
         name: &syn::Ident,
         generics: &syn::Generics,
-        fields: &[&syn::Field],
+        fields: &[RecordField],
         wt: &syn::Path,
     ) -> Result<TokenStream> {
         let internal = quote!(#wt::component::__internal);
@@ -459,7 +580,8 @@ impl Expander for LiftExpander {
         let mut lifts = TokenStream::new();
         let mut loads = TokenStream::new();
 
-        for (i, syn::Field { ident, ty, .. }) in fields.iter().enumerate() {
+        for (i, RecordField { field, .. }) in fields.iter().enumerate() {
+            let syn::Field { ident, ty, .. } = field;
             let field_ty = quote!(ty.fields[#i].ty);
             lifts.extend(
                 quote!(#ident: <#ty as #wt::component::Lift>::linear_lift_from_flat(
@@ -693,7 +815,7 @@ impl Expander for LowerExpander {
         &self,
         name: &syn::Ident,
         generics: &syn::Generics,
-        fields: &[&syn::Field],
+        fields: &[RecordField],
         wt: &syn::Path,
     ) -> Result<TokenStream> {
         let internal = quote!(#wt::component::__internal);
@@ -701,7 +823,8 @@ impl Expander for LowerExpander {
         let mut lowers = TokenStream::new();
         let mut stores = TokenStream::new();
 
-        for (i, syn::Field { ident, ty, .. }) in fields.iter().enumerate() {
+        for (i, RecordField { field, .. }) in fields.iter().enumerate() {
+            let syn::Field { ident, ty, .. } = field;
             let field_ty = quote!(ty.fields[#i].ty);
             lowers.extend(quote!(#wt::component::Lower::linear_lower_to_flat(
                 &self.#ident, cx, #field_ty, #internal::map_maybe_uninit!(dst.#ident)
@@ -922,29 +1045,21 @@ impl Expander for ComponentTypeExpander {
         &self,
         name: &syn::Ident,
         generics: &syn::Generics,
-        fields: &[&syn::Field],
+        fields: &[RecordField],
         wt: &syn::Path,
     ) -> Result<TokenStream> {
         expand_record_for_component_type(
             name,
             generics,
-            fields,
+            &fields.iter().map(|f| f.field).collect::<Vec<_>>(),
             quote!(typecheck_record),
             fields
                 .iter()
-                .map(
-                    |syn::Field {
-                         attrs, ident, ty, ..
-                     }| {
-                        let name = find_rename(attrs)?.unwrap_or_else(|| {
-                            let ident = ident.as_ref().unwrap();
-                            syn::LitStr::new(&ident.to_string(), ident.span())
-                        });
-
-                        Ok(quote!((#name, <#ty as #wt::component::ComponentType>::typecheck),))
-                    },
-                )
-                .collect::<Result<_>>()?,
+                .map(|RecordField { name, field }| {
+                    let ty = &field.ty;
+                    quote!((#name, <#ty as #wt::component::ComponentType>::typecheck),)
+                })
+                .collect(),
             wt,
         )
     }
@@ -968,11 +1083,7 @@ impl Expander for ComponentTypeExpander {
         let mut unique_types = HashSet::new();
         let mut may_require_realloc = TokenStream::new();
 
-        for (index, VariantCase { attrs, ident, ty }) in cases.iter().enumerate() {
-            let rename = find_rename(attrs)?;
-
-            let name = rename.unwrap_or_else(|| syn::LitStr::new(&ident.to_string(), ident.span()));
-
+        for (index, VariantCase { name, ident, ty }) in cases.iter().enumerate() {
             if let Some(ty) = ty {
                 abi_list.extend(quote!(Some(<#ty as #wt::component::ComponentType>::ABI),));
                 may_require_realloc.extend(quote!(
@@ -1064,11 +1175,7 @@ impl Expander for ComponentTypeExpander {
         let mut case_names = TokenStream::new();
         let mut abi_list = TokenStream::new();
 
-        for VariantCase { attrs, ident, ty } in cases.iter() {
-            let rename = find_rename(attrs)?;
-
-            let name = rename.unwrap_or_else(|| syn::LitStr::new(&ident.to_string(), ident.span()));
-
+        for VariantCase { name, ident, ty } in cases.iter() {
             if ty.is_some() {
                 return Err(Error::new(
                     ident.span(),
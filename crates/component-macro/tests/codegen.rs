@@ -43,10 +43,40 @@ macro_rules! gentest {
                     imports: { default: trappable },
                 });
             }
+            mod blocking_wrappers {
+                wasmtime::component::bindgen!({
+                    path: $path,
+                    imports: { default: async | trappable },
+                    blocking_wrappers: super::super::block_on,
+                });
+            }
+            mod mocks {
+                wasmtime::component::bindgen!({
+                    path: $path,
+                    mocks: true,
+                });
+            }
+            mod mocks_async {
+                wasmtime::component::bindgen!({
+                    path: $path,
+                    imports: { default: async | trappable },
+                    mocks: true,
+                });
+            }
         }
     };
 }
 
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            break result;
+        }
+    }
+}
+
 component_macro_test_helpers::foreach!(gentest);
 
 mod with_key_and_resources {
@@ -194,6 +224,7 @@ mod trappable_errors {
             "demo:pkg/a.b" => MyX,
             "demo:pkg/c.b" => MyX,
         },
+        mocks: true,
     });
 
     type MyX = u32;
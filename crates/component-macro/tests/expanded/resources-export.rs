@@ -464,6 +464,64 @@ pub mod exports {
                         Ok(ret0)
                     }
                 }
+                impl<'a> GuestA<'a> {
+                    /// Wraps the owned `resource` in an [`OwnedA`] which
+                    /// drops it within `store` once it goes out of scope.
+                    pub fn owned<S: wasmtime::AsContextMut>(
+                        &self,
+                        store: S,
+                        resource: A,
+                    ) -> OwnedA<'a, S> {
+                        OwnedA {
+                            funcs: self.funcs,
+                            store,
+                            resource: Some(resource),
+                        }
+                    }
+                }
+                /// An owned [`A`] along with the store it lives in.
+                ///
+                /// The resource is dropped, running the guest's destructor,
+                /// when this value goes out of scope. Use
+                /// [`OwnedA::resource_drop`] to observe errors of the
+                /// destructor instead.
+                pub struct OwnedA<'a, S: wasmtime::AsContextMut> {
+                    funcs: &'a Guest,
+                    store: S,
+                    resource: Option<A>,
+                }
+                impl<S: wasmtime::AsContextMut> OwnedA<'_, S> {
+                    /// Returns the underlying resource handle.
+                    pub fn resource(&self) -> A {
+                        self.resource.unwrap()
+                    }
+                    /// Returns the store this resource lives in.
+                    pub fn store(&mut self) -> &mut S {
+                        &mut self.store
+                    }
+                    /// Releases ownership of the resource without dropping
+                    /// it, which is then up to the caller.
+                    pub fn into_resource(mut self) -> A {
+                        self.resource.take().unwrap()
+                    }
+                    /// Drops the resource, returning any error raised by its
+                    /// destructor.
+                    pub fn resource_drop(mut self) -> wasmtime::Result<()> {
+                        self.resource.take().unwrap().resource_drop(&mut self.store)
+                    }
+                    pub fn method_a(&mut self) -> wasmtime::Result<u32> {
+                        let funcs = GuestA { funcs: self.funcs };
+                        let resource = self.resource();
+                        funcs.call_method_a(&mut self.store, resource)
+                    }
+                }
+                impl<S: wasmtime::AsContextMut> Drop for OwnedA<'_, S> {
+                    fn drop(&mut self) {
+                        if let Some(resource) = self.resource.take() {
+                            let _ = resource.resource_drop(&mut self.store);
+                        }
+                    }
+                }
             }
             #[allow(clippy::all)]
             pub mod export_using_import {
@@ -642,6 +700,67 @@ pub mod exports {
                         Ok(ret0)
                     }
                 }
+                impl<'a> GuestA<'a> {
+                    /// Wraps the owned `resource` in an [`OwnedA`] which
+                    /// drops it within `store` once it goes out of scope.
+                    pub fn owned<S: wasmtime::AsContextMut>(
+                        &self,
+                        store: S,
+                        resource: A,
+                    ) -> OwnedA<'a, S> {
+                        OwnedA {
+                            funcs: self.funcs,
+                            store,
+                            resource: Some(resource),
+                        }
+                    }
+                }
+                /// An owned [`A`] along with the store it lives in.
+                ///
+                /// The resource is dropped, running the guest's destructor,
+                /// when this value goes out of scope. Use
+                /// [`OwnedA::resource_drop`] to observe errors of the
+                /// destructor instead.
+                pub struct OwnedA<'a, S: wasmtime::AsContextMut> {
+                    funcs: &'a Guest,
+                    store: S,
+                    resource: Option<A>,
+                }
+                impl<S: wasmtime::AsContextMut> OwnedA<'_, S> {
+                    /// Returns the underlying resource handle.
+                    pub fn resource(&self) -> A {
+                        self.resource.unwrap()
+                    }
+                    /// Returns the store this resource lives in.
+                    pub fn store(&mut self) -> &mut S {
+                        &mut self.store
+                    }
+                    /// Releases ownership of the resource without dropping
+                    /// it, which is then up to the caller.
+                    pub fn into_resource(mut self) -> A {
+                        self.resource.take().unwrap()
+                    }
+                    /// Drops the resource, returning any error raised by its
+                    /// destructor.
+                    pub fn resource_drop(mut self) -> wasmtime::Result<()> {
+                        self.resource.take().unwrap().resource_drop(&mut self.store)
+                    }
+                    pub fn method_a(
+                        &mut self,
+                        arg1: wasmtime::component::Resource<Y>,
+                    ) -> wasmtime::Result<wasmtime::component::Resource<Y>> {
+                        let funcs = GuestA { funcs: self.funcs };
+                        let resource = self.resource();
+                        funcs.call_method_a(&mut self.store, resource, arg1)
+                    }
+                }
+                impl<S: wasmtime::AsContextMut> Drop for OwnedA<'_, S> {
+                    fn drop(&mut self) {
+                        if let Some(resource) = self.resource.take() {
+                            let _ = resource.resource_drop(&mut self.store);
+                        }
+                    }
+                }
             }
             #[allow(clippy::all)]
             pub mod export_using_export1 {
@@ -741,6 +860,59 @@ pub mod exports {
                         Ok(ret0)
                     }
                 }
+                impl<'a> GuestA<'a> {
+                    /// Wraps the owned `resource` in an [`OwnedA`] which
+                    /// drops it within `store` once it goes out of scope.
+                    pub fn owned<S: wasmtime::AsContextMut>(
+                        &self,
+                        store: S,
+                        resource: A,
+                    ) -> OwnedA<'a, S> {
+                        OwnedA {
+                            funcs: self.funcs,
+                            store,
+                            resource: Some(resource),
+                        }
+                    }
+                }
+                /// An owned [`A`] along with the store it lives in.
+                ///
+                /// The resource is dropped, running the guest's destructor,
+                /// when this value goes out of scope. Use
+                /// [`OwnedA::resource_drop`] to observe errors of the
+                /// destructor instead.
+                pub struct OwnedA<'a, S: wasmtime::AsContextMut> {
+                    funcs: &'a Guest,
+                    store: S,
+                    resource: Option<A>,
+                }
+                impl<S: wasmtime::AsContextMut> OwnedA<'_, S> {
+                    /// Returns the underlying resource handle.
+                    pub fn resource(&self) -> A {
+                        self.resource.unwrap()
+                    }
+                    /// Returns the store this resource lives in.
+                    pub fn store(&mut self) -> &mut S {
+                        &mut self.store
+                    }
+                    /// Releases ownership of the resource without dropping
+                    /// it, which is then up to the caller.
+                    pub fn into_resource(mut self) -> A {
+                        self.resource.take().unwrap()
+                    }
+                    /// Drops the resource, returning any error raised by its
+                    /// destructor.
+                    pub fn resource_drop(mut self) -> wasmtime::Result<()> {
+                        self.resource.take().unwrap().resource_drop(&mut self.store)
+                    }
+                }
+                impl<S: wasmtime::AsContextMut> Drop for OwnedA<'_, S> {
+                    fn drop(&mut self) {
+                        if let Some(resource) = self.resource.take() {
+                            let _ = resource.resource_drop(&mut self.store);
+                        }
+                    }
+                }
             }
             #[allow(clippy::all)]
             pub mod export_using_export2 {
@@ -842,6 +1014,59 @@ pub mod exports {
                         Ok(ret0)
                     }
                 }
+                impl<'a> GuestB<'a> {
+                    /// Wraps the owned `resource` in an [`OwnedB`] which
+                    /// drops it within `store` once it goes out of scope.
+                    pub fn owned<S: wasmtime::AsContextMut>(
+                        &self,
+                        store: S,
+                        resource: B,
+                    ) -> OwnedB<'a, S> {
+                        OwnedB {
+                            funcs: self.funcs,
+                            store,
+                            resource: Some(resource),
+                        }
+                    }
+                }
+                /// An owned [`B`] along with the store it lives in.
+                ///
+                /// The resource is dropped, running the guest's destructor,
+                /// when this value goes out of scope. Use
+                /// [`OwnedB::resource_drop`] to observe errors of the
+                /// destructor instead.
+                pub struct OwnedB<'a, S: wasmtime::AsContextMut> {
+                    funcs: &'a Guest,
+                    store: S,
+                    resource: Option<B>,
+                }
+                impl<S: wasmtime::AsContextMut> OwnedB<'_, S> {
+                    /// Returns the underlying resource handle.
+                    pub fn resource(&self) -> B {
+                        self.resource.unwrap()
+                    }
+                    /// Returns the store this resource lives in.
+                    pub fn store(&mut self) -> &mut S {
+                        &mut self.store
+                    }
+                    /// Releases ownership of the resource without dropping
+                    /// it, which is then up to the caller.
+                    pub fn into_resource(mut self) -> B {
+                        self.resource.take().unwrap()
+                    }
+                    /// Drops the resource, returning any error raised by its
+                    /// destructor.
+                    pub fn resource_drop(mut self) -> wasmtime::Result<()> {
+                        self.resource.take().unwrap().resource_drop(&mut self.store)
+                    }
+                }
+                impl<S: wasmtime::AsContextMut> Drop for OwnedB<'_, S> {
+                    fn drop(&mut self) {
+                        if let Some(resource) = self.resource.take() {
+                            let _ = resource.resource_drop(&mut self.store);
+                        }
+                    }
+                }
             }
         }
     }
@@ -442,7 +442,15 @@ fn bounds_check_field_access(
     //
     //         index + offset + access_size > bound
     //     ==> index > bound - (offset + access_size)
-    if offset_and_size <= heap.memory.minimum_byte_size().unwrap_or(u64::MAX) {
+    //
+    // Cranelift can't know that the subtraction doesn't wrap, though, so it
+    // can't tell that one such check implies another. When Cranelift's
+    // redundant bounds-check elimination is enabled, fall through to the
+    // general case instead so that it can merge the checks of neighboring
+    // accesses.
+    if offset_and_size <= heap.memory.minimum_byte_size().unwrap_or(u64::MAX)
+        && !env.isa().flags().enable_bounds_check_elimination()
+    {
         let bound = get_dynamic_heap_bound(builder, env, heap);
         let adjustment = offset_and_size as i64;
         let adjustment_value = builder.ins().iconst(env.pointer_type(), adjustment);
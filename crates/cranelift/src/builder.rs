@@ -93,6 +93,12 @@ impl CompilerBuilder for Builder {
             "wasmtime_inlining_sum_size_threshold" => {
                 self.tunables.as_mut().unwrap().inlining_sum_size_threshold = value.parse()?;
             }
+            "wasmtime_inlining_adapter_size" => {
+                self.tunables.as_mut().unwrap().inlining_adapter_size = value.parse()?;
+            }
+            "wasmtime_br_table_range_split" => {
+                self.tunables.as_mut().unwrap().br_table_range_split = value.parse()?;
+            }
             "wasmtime_debug_checks" => {
                 self.emit_debug_checks = true;
             }
@@ -8,7 +8,6 @@ use gimli::LineEncoding;
 use gimli::write;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use wasmtime_environ::error::{Context, Error};
 use wasmtime_environ::{
     DebugInfoData, EntityRef, FunctionMetadata, PrimaryMap, StaticModuleIndex, WasmFileInfo,
@@ -107,14 +106,17 @@ fn check_invalid_chars_in_name(s: &str) -> Option<&str> {
     if s.contains('\x00') { None } else { Some(s) }
 }
 
-fn autogenerate_dwarf_wasm_path(di: &DebugInfoData) -> PathBuf {
-    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+/// Generates the path of a module without one.
+///
+/// Note that this must only depend on the module itself for compilation to be
+/// reproducible.
+fn autogenerate_dwarf_wasm_path(module: StaticModuleIndex, di: &DebugInfoData) -> PathBuf {
     let module_name = di
         .name_section
         .module_name
         .and_then(check_invalid_chars_in_name)
         .map(|s| s.to_string())
-        .unwrap_or_else(|| format!("<gen-{}>.wasm", NEXT_ID.fetch_add(1, SeqCst)));
+        .unwrap_or_else(|| format!("<gen-{}>.wasm", module.index()));
     let path = format!("/<wasm-module>/{module_name}");
     PathBuf::from(path)
 }
@@ -297,13 +299,14 @@ pub fn generate_simulated_dwarf(
     isa: &dyn TargetIsa,
 ) -> Result<(), Error> {
     let (wasm_file, path) = {
-        let di = &compilation.translations.iter().next().unwrap().1.debuginfo;
+        let (module, translation) = compilation.translations.iter().next().unwrap();
+        let di = &translation.debuginfo;
         let path = di
             .wasm_file
             .path
             .to_owned()
             .and_then(check_invalid_chars_in_path)
-            .unwrap_or_else(|| autogenerate_dwarf_wasm_path(di));
+            .unwrap_or_else(|| autogenerate_dwarf_wasm_path(module, di));
         (&di.wasm_file, path)
     };
 
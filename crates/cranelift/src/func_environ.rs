@@ -36,9 +36,9 @@ use wasmtime_core::math::f64_cvt_to_int_bounds;
 use wasmtime_environ::{
     BuiltinFunctionIndex, ComponentPC, ConstExpr, ConstOp, DataIndex, DefinedFuncIndex,
     DefinedGlobalIndex, DefinedTableIndex, ElemIndex, EngineOrModuleTypeIndex, FactInlineIntrinsic,
-    FrameStateSlotBuilder, FrameValType, FuncIndex, FuncKey, GlobalConstValue, GlobalIndex,
-    IndexType, KnownFunc, Memory, MemoryIndex, MemoryInit, MemorySegmentOffset, MemoryTunables,
-    Module, ModuleInternedTypeIndex, ModuleTranslation, ModuleTypesBuilder,
+    FrameStateSlotBuilder, FrameValType, FuncIndex, FuncKey, FunctionProfile, GlobalConstValue,
+    GlobalIndex, IndexType, KnownFunc, Memory, MemoryIndex, MemoryInit, MemorySegmentOffset,
+    MemoryTunables, Module, ModuleInternedTypeIndex, ModuleTranslation, ModuleTypesBuilder,
     NUM_COMPONENT_CONTEXT_SLOTS, PassiveElemIndex, PtrSize, RuntimeDataIndex, Table, TableIndex,
     TableInitialValue, TableSegment, TableSegmentElements, TagIndex, Tunables, TypeConvert,
     TypeIndex, VMOffsets, WasmCompositeInnerType, WasmFuncType, WasmHeapTopType, WasmHeapType,
@@ -200,6 +200,11 @@ pub struct FuncEnvironment<'module_environment> {
     /// spill, and this isn't any worse than reloading each time.
     epoch_ptr_var: cranelift_frontend::Variable,
 
+    /// Number of operators translated since the last epoch check, used to
+    /// insert additional checks into long straight-line code when
+    /// `Tunables::epoch_interruption_check_interval` is configured.
+    ops_since_epoch_check: u32,
+
     fuel_consumed: i64,
 
     /// A stack limit for when signals-based traps are disabled.
@@ -238,6 +243,13 @@ pub struct FuncEnvironment<'module_environment> {
     /// Module-relative byte offset of the current function body's start.
     func_body_offset: usize,
 
+    /// The index of the current function, if it has a wasm body.
+    func_index: Option<FuncIndex>,
+
+    /// The recorded execution profile of the current function, if the module
+    /// is being compiled with a profile which recorded it.
+    profile: Option<&'module_environment FunctionProfile>,
+
     /// Cached alias regions for alias analysis.
     pub(crate) alias_regions: AliasRegions<VMOffsets<u8>>,
 }
@@ -260,6 +272,12 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
         let branch_hints = func_index
             .and_then(|func_index| translation.branch_hints(func_index))
             .map(|reader| reader.into_iter().peekable());
+        let profile = func_index.and_then(|func_index| {
+            translation
+                .profile
+                .as_ref()
+                .and_then(|profile| profile.function(func_index))
+        });
 
         // This isn't used during translation, so squash the warning about this
         // being unused from the compiler.
@@ -290,6 +308,7 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
             fuel_var: Variable::reserved_value(),
             epoch_deadline_var: Variable::reserved_value(),
             epoch_ptr_var: Variable::reserved_value(),
+            ops_since_epoch_check: 0,
 
             // Start with at least one fuel being consumed because even empty
             // functions should consume at least some fuel.
@@ -308,6 +327,8 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
 
             branch_hints,
             func_body_offset,
+            func_index,
+            profile,
 
             alias_regions: AliasRegions::new(offsets),
         }
@@ -316,11 +337,24 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
     /// Consume the branch hint for the instruction at module-relative `offset`
     /// (i.e. `builder.srcloc().bits()`), if any. The lazy decoder only moves
     /// forward, making this O(n) over a function body.
+    ///
+    /// Explicit hints take precedence over the profile the module is compiled
+    /// with, if any, which hints branches that always went the same way.
     pub(crate) fn take_branch_hint(&mut self, offset: usize) -> Option<BranchHint> {
+        let rel = u32::try_from(offset.checked_sub(self.func_body_offset)?).ok()?;
+        self.take_explicit_branch_hint(rel).or_else(|| {
+            let taken = self.profile?.branch(rel)?.always()?;
+            Some(BranchHint {
+                func_offset: rel,
+                taken,
+            })
+        })
+    }
+
+    fn take_explicit_branch_hint(&mut self, rel: u32) -> Option<BranchHint> {
         // Fast path: no hints (always so when the proposal is off), and this
         // runs for every `if`/`br_if`.
         let hints = self.branch_hints.as_mut()?;
-        let rel = u32::try_from(offset.checked_sub(self.func_body_offset)?).ok()?;
         loop {
             // Hint bytes were validated when the section was decoded, so an error
             // here is unexpected; treat it like exhaustion (end of hints).
@@ -749,6 +783,49 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
         builder.ins().call(check_free, &[vmctx, ptr]);
     }
 
+    fn epoch_before_op(&mut self, op: &Operator<'_>, builder: &mut FunctionBuilder<'_>) {
+        let Some(interval) = self.tunables.epoch_interruption_check_interval else {
+            return;
+        };
+        self.ops_since_epoch_check = self.ops_since_epoch_check.saturating_add(1);
+        if self.ops_since_epoch_check < interval.get() {
+            return;
+        }
+
+        match op {
+            // Control-flow operators are translated in terms of the current
+            // block and control stack, so defer the check to the next ordinary
+            // operator instead of splitting the block here. Loops perform
+            // their own check at the header anyway.
+            Operator::Block { .. }
+            | Operator::Loop { .. }
+            | Operator::If { .. }
+            | Operator::Else
+            | Operator::End
+            | Operator::Br { .. }
+            | Operator::BrIf { .. }
+            | Operator::BrTable { .. }
+            | Operator::BrOnNull { .. }
+            | Operator::BrOnNonNull { .. }
+            | Operator::BrOnCast { .. }
+            | Operator::BrOnCastFail { .. }
+            | Operator::Return
+            | Operator::Unreachable
+            | Operator::Try { .. }
+            | Operator::TryTable { .. }
+            | Operator::Catch { .. }
+            | Operator::CatchAll
+            | Operator::Delegate { .. }
+            | Operator::Rethrow { .. }
+            | Operator::Throw { .. }
+            | Operator::ThrowRef => {}
+
+            // Otherwise we're in the middle of straight-line code which has
+            // run long enough without a check, so insert one here.
+            _ => self.epoch_check(builder),
+        }
+    }
+
     fn epoch_ptr(&mut self, builder: &mut FunctionBuilder<'_>) -> ir::Value {
         let vmctx = self.vmctx_val(&mut builder.cursor());
         self.alias_regions
@@ -762,6 +839,7 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
     }
 
     fn epoch_check(&mut self, builder: &mut FunctionBuilder<'_>) {
+        self.ops_since_epoch_check = 0;
         let continuation_block = builder.create_block();
 
         // Load new epoch and check against the cached deadline.
@@ -827,6 +905,42 @@ impl<'module_environment> FuncEnvironment<'module_environment> {
         builder.switch_to_block(continuation_block);
     }
 
+    /// Records a call of the current function in the module's profile.
+    fn profile_function_entry(&mut self, builder: &mut FunctionBuilder<'_>) {
+        let Some(func_index) = self.func_index else {
+            return;
+        };
+        let profile_call = self.builtin_functions.profile_call(builder.func);
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let func = builder.ins().iconst(I32, i64::from(func_index.as_u32()));
+        builder.ins().call(profile_call, &[vmctx, func]);
+    }
+
+    /// Records which way the `if` or `br_if` about to be translated goes in
+    /// the module's profile.
+    fn profile_before_op(&mut self, op: &Operator<'_>, builder: &mut FunctionBuilder<'_>) {
+        if !matches!(op, Operator::If { .. } | Operator::BrIf { .. }) {
+            return;
+        }
+        let Some(func_index) = self.func_index else {
+            return;
+        };
+        // Branches are keyed by body-relative offset, like branch hints.
+        let offset = builder.srcloc().bits() as usize - self.func_body_offset;
+        let offset = u32::try_from(offset).unwrap();
+
+        // The condition is still on top of the stack and is consumed by the
+        // branch itself later on.
+        let taken = self.stacks.peek1();
+        let profile_branch = self.builtin_functions.profile_branch(builder.func);
+        let vmctx = self.vmctx_val(&mut builder.cursor());
+        let func = builder.ins().iconst(I32, i64::from(func_index.as_u32()));
+        let offset = builder.ins().iconst(I32, i64::from(offset));
+        builder
+            .ins()
+            .call(profile_branch, &[vmctx, func, offset, taken]);
+    }
+
     /// Get the Memory for the given index.
     fn memory(&self, index: MemoryIndex) -> Memory {
         self.module.memories[index]
@@ -2557,6 +2671,7 @@ impl<'module_environment> TargetEnvironment for FuncEnvironment<'module_environm
 
     fn heap_access_spectre_mitigation(&self) -> bool {
         self.isa.flags().enable_heap_access_spectre_mitigation()
+            && self.module.spectre_mitigations.memory()
     }
 
     fn tunables(&self) -> &Tunables {
@@ -5158,6 +5273,12 @@ impl FuncEnvironment<'_> {
         if self.tunables.consume_fuel {
             self.fuel_before_op(op, builder, self.is_reachable());
         }
+        if self.tunables.epoch_interruption && self.is_reachable() {
+            self.epoch_before_op(op, builder);
+        }
+        if self.tunables.profile_instrumentation && self.is_reachable() {
+            self.profile_before_op(op, builder);
+        }
         if self.is_reachable() && self.state_slot.is_some() {
             let builtin = self.builtin_functions.patchable_breakpoint(builder.func);
             let vmctx = self.vmctx_val(&mut builder.cursor());
@@ -5214,6 +5335,10 @@ impl FuncEnvironment<'_> {
             self.epoch_function_entry(builder);
         }
 
+        if self.tunables.profile_instrumentation {
+            self.profile_function_entry(builder);
+        }
+
         if self.compiler.wmemcheck {
             let func_name = self.current_func_name(builder);
             if func_name == Some("malloc") {
@@ -5364,6 +5489,10 @@ impl FuncEnvironment<'_> {
         self.isa.has_x86_pmaddubsw_lowering()
     }
 
+    pub fn use_x86_cvtt2udq_for_relaxed_trunc(&self) -> bool {
+        self.isa.has_x86_cvtt2udq_lowering()
+    }
+
     pub fn handle_before_return(&mut self, retvals: &[ir::Value], builder: &mut FunctionBuilder) {
         if self.compiler.wmemcheck {
             let func_name = self.current_func_name(builder);
@@ -5820,6 +5949,12 @@ impl FuncEnvironment<'_> {
         self.tunables.signals_based_traps && !self.is_pulley()
     }
 
+    /// Whether to enable Spectre mitigations for table accesses.
+    pub fn table_access_spectre_mitigation(&self) -> bool {
+        self.isa.flags().enable_table_access_spectre_mitigation()
+            && self.module.spectre_mitigations.tables()
+    }
+
     /// Returns whether loads from the null address are allowed as signals of
     /// whether to trap or not.
     pub fn load_from_zero_allowed(&self) -> bool {
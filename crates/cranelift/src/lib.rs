@@ -36,6 +36,7 @@ mod obj;
 pub use obj::*;
 mod compiled_function;
 pub use compiled_function::*;
+pub use translate::get_vmctx_value_label;
 
 mod alias_region;
 mod bounds_checks;
@@ -88,7 +88,7 @@ use cranelift_codegen::ir::{
     types::*,
 };
 use cranelift_codegen::packed_option::ReservedValue;
-use cranelift_frontend::{FunctionBuilder, Variable};
+use cranelift_frontend::{FunctionBuilder, Switch, Variable};
 use itertools::Itertools;
 use smallvec::{SmallVec, ToSmallVec};
 use std::collections::{HashMap, hash_map};
@@ -560,7 +560,7 @@ pub fn translate_operator(
                         frame.set_branched_to_exit();
                         frame.br_destination()
                     };
-                    data.push(builder.func.dfg.block_call(block, &[]));
+                    data.push(block);
                 }
                 let block = {
                     let i = environ.stacks.control_stack.len() - 1 - (default as usize);
@@ -568,9 +568,7 @@ pub fn translate_operator(
                     frame.set_branched_to_exit();
                     frame.br_destination()
                 };
-                let block = builder.func.dfg.block_call(block, &[]);
-                let jt = builder.create_jump_table(JumpTableData::new(block, &data));
-                builder.ins().br_table(val, jt);
+                emit_br_table(builder, environ, val, block, &data);
             } else {
                 // Here we have jump arguments, but Cranelift's br_table doesn't support them
                 // We then proceed to split the edges going out of the br_table
@@ -587,7 +585,7 @@ pub fn translate_operator(
                             *entry.insert(block)
                         }
                     };
-                    data.push(builder.func.dfg.block_call(branch_block, &[]));
+                    data.push(branch_block);
                 }
                 let default_branch_block = match dest_block_map.entry(default as usize) {
                     hash_map::Entry::Occupied(entry) => *entry.get(),
@@ -597,9 +595,7 @@ pub fn translate_operator(
                         *entry.insert(block)
                     }
                 };
-                let default_branch_block = builder.func.dfg.block_call(default_branch_block, &[]);
-                let jt = builder.create_jump_table(JumpTableData::new(default_branch_block, &data));
-                builder.ins().br_table(val, jt);
+                emit_br_table(builder, environ, val, default_branch_block, &data);
                 for (depth, dest_block) in dest_block_sequence {
                     builder.switch_to_block(dest_block);
                     builder.seal_block(dest_block);
@@ -2186,25 +2182,37 @@ pub fn translate_operator(
 
         // FIXME(#5913): the relaxed instructions here are translated the same
         // as the saturating instructions, even when the code generator
-        // configuration allow for different semantics across hosts. On x86,
-        // however, it's theoretically possible to have a slightly more optimal
-        // lowering which accounts for NaN differently, although the lowering is
-        // still not trivial (e.g. one instruction). At this time the
+        // configuration allow for different semantics across hosts, unless
+        // AVX512VL and AVX512F are available on x86. In that case the relaxed
+        // semantics are exactly those of `vcvttp{s,d}2udq`, which return
+        // `UINT_MAX` for NaN and out-of-bounds lanes. Without AVX-512 the
         // more-optimal-but-still-large lowering for x86 is not implemented so
         // the relaxed instructions are listed here instead of down below with
-        // the other relaxed instructions. An x86-specific implementation (or
-        // perhaps for other backends too) should be added and the codegen for
-        // the relaxed instruction should conditionally be different.
+        // the other relaxed instructions.
         Operator::I32x4RelaxedTruncF32x4U | Operator::I32x4TruncSatF32x4U => {
             let a = pop1_with_bitcast(environ, F32X4, builder);
-            environ
-                .stacks
-                .push1(builder.ins().fcvt_to_uint_sat(I32X4, a))
+            environ.stacks.push1(
+                if matches!(op, Operator::I32x4RelaxedTruncF32x4U)
+                    && !environ.relaxed_simd_deterministic()
+                    && environ.use_x86_cvtt2udq_for_relaxed_trunc()
+                {
+                    builder.ins().x86_cvtt2udq(I32X4, a)
+                } else {
+                    builder.ins().fcvt_to_uint_sat(I32X4, a)
+                },
+            )
         }
         Operator::I32x4RelaxedTruncF64x2UZero | Operator::I32x4TruncSatF64x2UZero => {
             let a = pop1_with_bitcast(environ, F64X2, builder);
             let zero_constant = builder.func.dfg.constants.insert(vec![0u8; 16].into());
-            let result = if environ.is_x86() && !environ.isa().has_round() {
+            let result = if matches!(op, Operator::I32x4RelaxedTruncF64x2UZero)
+                && !environ.relaxed_simd_deterministic()
+                && environ.use_x86_cvtt2udq_for_relaxed_trunc()
+            {
+                let converted_a = builder.ins().x86_cvtt2udq(I64X2, a);
+                let zero = builder.ins().vconst(I64X2, zero_constant);
+                builder.ins().uunarrow(converted_a, zero)
+            } else if environ.is_x86() && !environ.isa().has_round() {
                 // On x86 the vector lowering for `fcvt_to_uint_sat` requires
                 // SSE4.1 `round` instructions. If SSE4.1 isn't available it
                 // falls back to a libcall which we don't want in Wasmtime.
@@ -4086,6 +4094,48 @@ fn translate_br_if_args(
     (br_destination, inputs)
 }
 
+/// Emits a `br_table` on `val` with the given `targets` and `default` block.
+///
+/// Large tables are lowered through a `Switch` which splits long runs of the
+/// same target out into range checks when `Tunables::br_table_range_split` is
+/// enabled, rather than emitting a single dense jump table. The jump tables
+/// that remain may additionally be split into two levels of
+/// `BR_TABLE_CHUNK_SIZE` entries each.
+fn emit_br_table(
+    builder: &mut FunctionBuilder,
+    env: &FuncEnvironment<'_>,
+    val: ir::Value,
+    default: ir::Block,
+    targets: &[ir::Block],
+) {
+    let threshold = env.tunables().br_table_range_split;
+    if threshold == 0 || targets.len() < threshold as usize {
+        let default = builder.func.dfg.block_call(default, &[]);
+        let targets = targets
+            .iter()
+            .map(|block| builder.func.dfg.block_call(*block, &[]))
+            .collect::<Vec<_>>();
+        let jt = builder.create_jump_table(JumpTableData::new(default, &targets));
+        builder.ins().br_table(val, jt);
+        return;
+    }
+
+    /// Number of entries in each second-level jump table. Jump table entries
+    /// are 4-byte offsets on most targets, so this keeps a second-level table
+    /// within two cache lines.
+    const BR_TABLE_CHUNK_SIZE: u32 = 32;
+
+    let mut switch = Switch::new();
+    switch.set_range_split_threshold(threshold.into());
+    switch.set_two_level_jump_tables(BR_TABLE_CHUNK_SIZE);
+    for (index, block) in targets.iter().enumerate() {
+        if *block != default {
+            switch.set_entry(index as u128, *block);
+        }
+    }
+    switch.emit(builder, val, default);
+}
+
 /// Determine the returned value type of a WebAssembly operator
 fn type_of(operator: &Operator) -> Type {
     match operator {
@@ -69,8 +69,7 @@ impl TableData {
         let index_ty = pos.func.dfg.value_type(index);
         let addr_ty = env.pointer_type();
         let spectre_mitigations_enabled =
-            env.isa().flags().enable_table_access_spectre_mitigation()
-                && env.clif_memory_traps_enabled();
+            env.table_access_spectre_mitigation() && env.clif_memory_traps_enabled();
 
         // Start with the bounds check. Trap if `index + 1 > bound`.
         let bound = self.bound.bound(pos.cursor(), index_ty);
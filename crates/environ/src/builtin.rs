@@ -170,6 +170,13 @@ macro_rules! foreach_builtin_function {
 
             // Process a debug breakpoint.
             breakpoint(vmctx: vmctx) -> bool;
+
+            // Records a call to `func` in the module's execution profile.
+            profile_call(vmctx: vmctx, func: u32);
+
+            // Records which way the branch at body-relative `offset` in `func`
+            // went in the module's execution profile.
+            profile_branch(vmctx: vmctx, func: u32, offset: u32, taken: u32);
         }
     };
 }
@@ -5,10 +5,12 @@ use crate::WasmChecksum;
 use crate::error::{Result, bail};
 use crate::prelude::*;
 use crate::{
-    CompiledModuleInfo, DebugInfoData, FunctionName, Metadata, ModuleTranslation, Tunables, obj,
+    CompiledModuleInfo, DebugInfoData, FuncIndex, FunctionName, IndirectName, Metadata,
+    ModuleTranslation, Tunables, obj,
 };
 use object::SectionKind;
 use object::write::{Object, SectionId, StandardSegment, WritableBuffer};
+use std::collections::HashMap;
 use std::ops::Range;
 
 /// Helper structure to create an ELF file as a compilation artifact.
@@ -140,30 +142,18 @@ impl<'a> ObjectBuilder<'a> {
         // If any names are present in the module then the `ELF_NAME_DATA` section
         // is create and appended.
         let mut func_names = Vec::new();
-        if debuginfo.name_section.func_names.len() > 0 {
-            let name_id = *self.names.get_or_insert_with(|| {
-                self.obj.add_section(
-                    self.obj.segment_name(StandardSegment::Data).to_vec(),
-                    obj::ELF_NAME_DATA.as_bytes().to_vec(),
-                    SectionKind::ReadOnlyData,
-                )
+        let mut sorted_names = debuginfo.name_section.func_names.iter().collect::<Vec<_>>();
+        sorted_names.sort_by_key(|(idx, _name)| *idx);
+        for (idx, name) in sorted_names {
+            let (offset, len) = self.push_name(name)?;
+            func_names.push(FunctionName {
+                idx: *idx,
+                offset,
+                len,
             });
-            let mut sorted_names = debuginfo.name_section.func_names.iter().collect::<Vec<_>>();
-            sorted_names.sort_by_key(|(idx, _name)| *idx);
-            for (idx, name) in sorted_names {
-                let offset = self.obj.append_section_data(name_id, name.as_bytes(), 1);
-                let offset = match u32::try_from(offset) {
-                    Ok(offset) => offset,
-                    Err(_) => bail!("name section too large (> 4gb)"),
-                };
-                let len = u32::try_from(name.len()).unwrap();
-                func_names.push(FunctionName {
-                    idx: *idx,
-                    offset,
-                    len,
-                });
-            }
         }
+        let local_names = self.push_indirect_names(&debuginfo.name_section.locals_names)?;
+        let label_names = self.push_indirect_names(&debuginfo.name_section.labels_names)?;
 
         // Data offsets for passive data are relative to the start of
         // `translation.runtime_data` which was appended to the data segment
@@ -187,16 +177,66 @@ impl<'a> ObjectBuilder<'a> {
         Ok(CompiledModuleInfo {
             module,
             func_names,
+            local_names,
+            label_names,
             meta: Metadata {
                 has_unparsed_debuginfo,
                 code_section_offset: debuginfo.wasm_file.code_section_offset,
                 has_wasm_debuginfo: self.tunables.parse_wasm_debuginfo,
                 dwarf,
+                recompiled: false,
             },
             checksum: WasmChecksum::from_binary(wasm, self.tunables.recording),
         })
     }
 
+    /// Appends `name` to the `ELF_NAME_DATA` section, creating it if
+    /// necessary, and returns its offset and length within the section.
+    fn push_name(&mut self, name: &str) -> Result<(u32, u32)> {
+        let name_id = *self.names.get_or_insert_with(|| {
+            self.obj.add_section(
+                self.obj.segment_name(StandardSegment::Data).to_vec(),
+                obj::ELF_NAME_DATA.as_bytes().to_vec(),
+                SectionKind::ReadOnlyData,
+            )
+        });
+        let offset = self.obj.append_section_data(name_id, name.as_bytes(), 1);
+        let offset = match u32::try_from(offset) {
+            Ok(offset) => offset,
+            Err(_) => bail!("name section too large (> 4gb)"),
+        };
+        let len = u32::try_from(name.len()).unwrap();
+        Ok((offset, len))
+    }
+
+    /// Appends all of the per-function `names` to the `ELF_NAME_DATA` section,
+    /// returning their descriptors sorted by function and then item index.
+    fn push_indirect_names(
+        &mut self,
+        names: &HashMap<FuncIndex, HashMap<u32, &str>>,
+    ) -> Result<Vec<IndirectName>> {
+        let mut sorted_names = names
+            .iter()
+            .flat_map(|(func, names)| {
+                names
+                    .iter()
+                    .map(move |(index, name)| (*func, *index, *name))
+            })
+            .collect::<Vec<_>>();
+        sorted_names.sort_by_key(|(func, index, _name)| (*func, *index));
+        let mut ret = Vec::with_capacity(sorted_names.len());
+        for (func, index, name) in sorted_names {
+            let (offset, len) = self.push_name(name)?;
+            ret.push(IndirectName {
+                func,
+                index,
+                offset,
+                len,
+            });
+        }
+        Ok(ret)
+    }
+
     fn push_debug<'b, T>(&mut self, dwarf: &mut Vec<(u8, Range<u64>)>, section: &T)
     where
         T: gimli::Section<gimli::EndianSlice<'b, gimli::LittleEndian>>,
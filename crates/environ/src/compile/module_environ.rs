@@ -9,7 +9,7 @@ use crate::{
     MemoryIndex, MemoryInitializer, ModuleInternedTypeIndex, ModuleStartup, ModuleTypesBuilder,
     PanicOnOom as _, PassiveElemIndex, PrimaryMap, RuntimeDataIndex, StaticModuleIndex, TableIndex,
     TableInitialValue, TableInitialization, Tag, TagIndex, Tunables, TypeConvert, TypeIndex,
-    WasmHeapTopType, WasmHeapType, WasmResult, WasmValType, WasmparserTypeConverter,
+    WasmHeapTopType, WasmHeapType, WasmProfile, WasmResult, WasmValType, WasmparserTypeConverter,
 };
 use alloc::borrow::Cow;
 use cranelift_entity::SecondaryMap;
@@ -20,8 +20,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use wasmparser::{
     CustomSectionReader, DataKind, ElementItems, ElementKind, Encoding, ExternalKind,
-    FuncToValidate, FunctionBody, KnownCustom, NameSectionReader, Naming, Parser, Payload, TypeRef,
-    Validator, ValidatorResources, types::Types,
+    FuncToValidate, FunctionBody, IndirectNameMap, KnownCustom, NameSectionReader, Naming, Parser,
+    Payload, TypeRef, Validator, ValidatorResources, types::Types,
 };
 
 /// Object containing the standalone environment information.
@@ -117,6 +117,10 @@ pub struct ModuleTranslation<'data> {
     /// configuration.
     pub has_unparsed_debuginfo: bool,
 
+    /// Whether this module was generated by Wasmtime to hold fused adapters
+    /// between components, as opposed to being provided by the user.
+    pub is_fused_adapter: bool,
+
     /// The desired alignment of `data` in the final data section of the object
     /// file that we'll emit.
     ///
@@ -155,6 +159,10 @@ pub struct ModuleTranslation<'data> {
     /// [`Tunables::branch_hinting`] is enabled.
     branch_hints: HashMap<FuncIndex, BranchHintReader<'data>>,
 
+    /// A profile recorded from earlier executions of this module which
+    /// compilers may use to optimize for the observed behavior.
+    pub profile: Option<WasmProfile>,
+
     /// The WebAssembly `start` function, if defined.
     pub start_func: Option<FuncIndex>,
 
@@ -229,6 +237,7 @@ impl<'data> ModuleTranslation<'data> {
             exported_signatures: Vec::default(),
             debuginfo: DebugInfoData::default(),
             has_unparsed_debuginfo: false,
+            is_fused_adapter: false,
             data_align: None,
             runtime_data: Default::default(),
             code_index: 0,
@@ -236,6 +245,7 @@ impl<'data> ModuleTranslation<'data> {
             runtime_data_map: Default::default(),
             passive_elem_map: Default::default(),
             branch_hints: HashMap::default(),
+            profile: None,
             start_func: None,
             global_initializers: Vec::new(),
             passive_elements: Default::default(),
@@ -296,6 +306,7 @@ pub struct NameSection<'a> {
     pub module_name: Option<&'a str>,
     pub func_names: HashMap<FuncIndex, &'a str>,
     pub locals_names: HashMap<FuncIndex, HashMap<u32, &'a str>>,
+    pub labels_names: HashMap<FuncIndex, HashMap<u32, &'a str>>,
 }
 
 #[derive(Debug, Default)]
@@ -1030,31 +1041,17 @@ and for re-adding support for interface types you can see this issue:
                     }
                 }
                 wasmparser::Name::Local(reader) => {
-                    if !self.tunables.debug_native {
-                        continue;
-                    }
-                    for f in reader {
-                        let f = f?;
-                        // Skip this naming if it's naming a function that
-                        // doesn't actually exist.
-                        if (f.index as usize) >= self.result.module.functions.len() {
-                            continue;
-                        }
-                        for name in f.names {
-                            let Naming { index, name } = name?;
-
-                            self.result
-                                .debuginfo
-                                .name_section
-                                .locals_names
-                                .entry(FuncIndex::from_u32(f.index))
-                                .or_insert(HashMap::new())
-                                .insert(index, name);
-                        }
-                    }
+                    // Like function names these are stored unconditionally
+                    // so they're available at runtime through
+                    // `Module::names` even without native debuginfo.
+                    let names = &mut self.result.debuginfo.name_section.locals_names;
+                    Self::indirect_names(&self.result.module, names, reader)?;
+                }
+                wasmparser::Name::Label(reader) => {
+                    let names = &mut self.result.debuginfo.name_section.labels_names;
+                    Self::indirect_names(&self.result.module, names, reader)?;
                 }
-                wasmparser::Name::Label(_)
-                | wasmparser::Name::Type(_)
+                wasmparser::Name::Type(_)
                 | wasmparser::Name::Table(_)
                 | wasmparser::Name::Global(_)
                 | wasmparser::Name::Memory(_)
@@ -1068,6 +1065,31 @@ and for re-adding support for interface types you can see this issue:
         Ok(())
     }
 
+    /// Parses a per-function name map, such as local or label names, into
+    /// `names`.
+    fn indirect_names(
+        module: &Module,
+        names: &mut HashMap<FuncIndex, HashMap<u32, &'data str>>,
+        reader: IndirectNameMap<'data>,
+    ) -> WasmResult<()> {
+        for f in reader {
+            let f = f?;
+            // Skip this naming if it's naming a function that doesn't
+            // actually exist.
+            if (f.index as usize) >= module.functions.len() {
+                continue;
+            }
+            for name in f.names {
+                let Naming { index, name } = name?;
+                names
+                    .entry(FuncIndex::from_u32(f.index))
+                    .or_insert(HashMap::new())
+                    .insert(index, name);
+            }
+        }
+        Ok(())
+    }
+
     fn require_startup_func(&mut self) {
         self.result.require_startup_func(self.types);
     }
@@ -317,10 +317,43 @@ pub fn alternate_lookup_key(name: &str) -> Option<(&str, Version)> {
     }
 }
 
+/// Splits an interface name into the WIT package it belongs to and the version
+/// of that package, if any.
+///
+/// Some examples are:
+///
+/// * `foo` => `None`
+/// * `foo:bar/baz` => `Some(("foo:bar", None))`
+/// * `foo:bar/baz@1.1.2` => `Some(("foo:bar", Some("1.1.2")))`
+/// * `foo:bar/baz@0.1.0-rc.2` => `Some(("foo:bar", Some("0.1.0-rc.2")))`
+pub fn package_name_and_version(name: &str) -> Option<(&str, Option<&str>)> {
+    let (name, version) = match name.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (name, None),
+    };
+    let (package, _interface) = name.split_once('/')?;
+    if !package.contains(':') {
+        return None;
+    }
+    Some((package, version))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{NameMap, NameMapNoIntern};
 
+    #[test]
+    fn package_name_and_version() {
+        use super::package_name_and_version as pkg;
+
+        assert_eq!(pkg("x"), None);
+        assert_eq!(pkg("x/y"), None);
+        assert_eq!(pkg("x:y"), None);
+        assert_eq!(pkg("x:y/z"), Some(("x:y", None)));
+        assert_eq!(pkg("x:y/z@1.0.0"), Some(("x:y", Some("1.0.0"))));
+        assert_eq!(pkg("x:y/z@0.2.0-rc.1"), Some(("x:y", Some("0.2.0-rc.1"))));
+    }
+
     #[test]
     fn alternate_lookup_key() {
         fn alt(s: &str) -> Option<&str> {
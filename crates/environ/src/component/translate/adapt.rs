@@ -239,7 +239,7 @@ impl<'data> Translator<'_, 'data> {
             // memory.
             self.validator.reset();
             let static_module_index = self.static_modules.next_key();
-            let translation = ModuleEnvironment::new(
+            let mut translation = ModuleEnvironment::new(
                 self.tunables,
                 &mut self.validator,
                 self.types.module_types_builder(),
@@ -247,6 +247,7 @@ impl<'data> Translator<'_, 'data> {
             )
             .translate(Parser::new(0), wasm)
             .expect("invalid adapter module generated");
+            translation.is_fused_adapter = true;
 
             // Record, for each adapter in this adapter module, the module that
             // the adapter was placed within as well as the function index of
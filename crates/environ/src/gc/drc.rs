@@ -26,6 +26,10 @@ pub const HEADER_MARK_BIT: u32 = 1 << 0;
 /// in-the-over-approximated-stack-roots list bit.
 pub const HEADER_IN_OVER_APPROX_LIST_BIT: u32 = 1 << 1;
 
+/// The bit within a `VMDrcHeader`'s reserved bits that is set when the host
+/// holds a weak reference to the object.
+pub const HEADER_WEAK_TARGET_BIT: u32 = 1 << 2;
+
 /// The layout of Wasm GC objects in the deferred reference-counting collector.
 #[derive(Default)]
 pub struct DrcTypeLayouts;
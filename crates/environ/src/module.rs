@@ -170,6 +170,40 @@ impl TableSegmentElements {
     }
 }
 
+/// Tiers of Spectre hardening that the code of a module can be compiled with.
+///
+/// Tiers only ever remove mitigations which are otherwise enabled by the
+/// compiler configuration of the engine; they never add mitigations which are
+/// disabled there.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpectreMitigations {
+    /// All Spectre mitigations enabled for the engine are applied.
+    #[default]
+    Enabled,
+
+    /// Spectre mitigations for linear memory accesses are removed, while
+    /// mitigations for table accesses are kept.
+    ///
+    /// Linear memory bounds checks are by far the most frequently executed
+    /// mitigation, so this tier recovers most of their overhead.
+    TablesOnly,
+
+    /// All Spectre mitigations are removed.
+    Disabled,
+}
+
+impl SpectreMitigations {
+    /// Whether mitigations for linear memory accesses are kept in this tier.
+    pub fn memory(&self) -> bool {
+        matches!(self, SpectreMitigations::Enabled)
+    }
+
+    /// Whether mitigations for table accesses are kept in this tier.
+    pub fn tables(&self) -> bool {
+        !matches!(self, SpectreMitigations::Disabled)
+    }
+}
+
 /// A translated WebAssembly module, excluding the function bodies and
 /// memory initializers.
 #[derive(Debug, Serialize, Deserialize)]
@@ -243,6 +277,9 @@ pub struct Module {
     /// Does this module need a GC heap to run?
     pub needs_gc_heap: bool,
 
+    /// The tier of Spectre mitigations this module's code was compiled with.
+    pub spectre_mitigations: SpectreMitigations,
+
     /// Number of functions that "escape" from this module may need to have a
     /// `VMFuncRef` constructed for them.
     ///
@@ -316,6 +353,7 @@ impl Module {
             num_imported_globals: Default::default(),
             num_imported_tags: Default::default(),
             needs_gc_heap: Default::default(),
+            spectre_mitigations: Default::default(),
             num_escaped_funcs: Default::default(),
             functions: Default::default(),
             tables: Default::default(),
@@ -625,6 +663,7 @@ impl TypeTrace for Module {
             num_imported_tags: _,
             num_escaped_funcs: _,
             needs_gc_heap: _,
+            spectre_mitigations: _,
             functions,
             tables,
             memories: _,
@@ -677,6 +716,7 @@ impl TypeTrace for Module {
             num_imported_tags: _,
             num_escaped_funcs: _,
             needs_gc_heap: _,
+            spectre_mitigations: _,
             functions,
             tables,
             memories: _,
@@ -597,6 +597,14 @@ pub struct CompiledModuleInfo {
     /// Sorted list, by function index, of names we have for this module.
     pub func_names: Vec<FunctionName>,
 
+    /// Sorted list, by function index and then local index, of local names
+    /// we have for this module.
+    pub local_names: Vec<IndirectName>,
+
+    /// Sorted list, by function index and then label index, of label names
+    /// we have for this module.
+    pub label_names: Vec<IndirectName>,
+
     /// Checksum of the source Wasm binary from which this module was compiled.
     pub checksum: WasmChecksum,
 }
@@ -614,6 +622,21 @@ pub struct FunctionName {
     pub len: u32,
 }
 
+/// The name of an item nested within a function, such as a local or a
+/// label, stored in the [`ELF_NAME_DATA`](crate::obj::ELF_NAME_DATA) section.
+#[derive(Serialize, Deserialize)]
+pub struct IndirectName {
+    /// The Wasm function index that the named item belongs to.
+    pub func: FuncIndex,
+    /// The index of the named item within its function.
+    pub index: u32,
+    /// The offset of the name in the
+    /// [`ELF_NAME_DATA`](crate::obj::ELF_NAME_DATA) section.
+    pub offset: u32,
+    /// The length of the name in bytes.
+    pub len: u32,
+}
+
 /// Metadata associated with a compiled ELF artifact.
 #[derive(Serialize, Deserialize)]
 pub struct Metadata {
@@ -634,6 +657,10 @@ pub struct Metadata {
     /// Dwarf sections and the offsets at which they're stored in the
     /// ELF_WASMTIME_DWARF
     pub dwarf: Vec<(u8, Range<u64>)>,
+
+    /// Whether this artifact was compiled by the engine's background
+    /// recompiler rather than its primary compiler.
+    pub recompiled: bool,
 }
 
 /// Value of a configured setting for a [`Compiler`](crate::Compiler)
@@ -658,6 +685,7 @@ impl fmt::Display for FlagValue<'_> {
 }
 
 /// Types of objects that can be created by `Compiler::object`
+#[derive(Copy, Clone)]
 pub enum ObjectKind {
     /// A core wasm compilation artifact
     Module,
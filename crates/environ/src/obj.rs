@@ -94,6 +94,14 @@ pub const ELF_WASM_BTI: &str = ".wasmtime.bti";
 /// double-check that an artifact can be loaded into the current host.
 pub const ELF_WASM_ENGINE: &str = ".wasmtime.engine";
 
+/// A postcard-encoded section containing a list of complete artifacts which
+/// are variants of the artifact containing them compiled with different
+/// ISA-specific flags, such as CPU features.
+///
+/// When an artifact is loaded the first variant compatible with the host is
+/// loaded in its place, if any.
+pub const ELF_WASMTIME_VARIANTS: &str = ".wasmtime.variants";
+
 /// This is the name of the section in the final ELF image which contains
 /// concatenated data segments from the original wasm module.
 ///
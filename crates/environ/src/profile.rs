@@ -0,0 +1,236 @@
+//! Execution profiles of WebAssembly modules used to guide optimization.
+
+use crate::FuncIndex;
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+use serde_derive::{Deserialize, Serialize};
+
+/// Magic bytes at the start of a serialized [`WasmProfile`].
+const MAGIC: &[u8; 8] = b"\0wasmprf";
+
+/// Version of the serialized [`WasmProfile`] format, bumped on incompatible
+/// changes.
+const VERSION: u8 = 1;
+
+/// How often the functions and branches of a WebAssembly module executed.
+///
+/// Profiles are recorded by running a module compiled with profile
+/// instrumentation enabled, and are used when compiling that same module
+/// again to optimize for the recorded behavior: functions which were never
+/// called aren't inlined, hot functions are inlined more eagerly, and the
+/// successors of branches which always went the same way are laid out as
+/// cold code.
+///
+/// Functions are identified by their index in the module's function index
+/// space and branches by the byte offset of their `if` or `br_if`
+/// instruction relative to the start of the function's body, the same
+/// offsets used by the [branch hinting proposal]. A profile is therefore
+/// only meaningful for the exact module binary it was recorded from.
+///
+/// [branch hinting proposal]: https://github.com/WebAssembly/branch-hinting
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmProfile {
+    functions: BTreeMap<FuncIndex, FunctionProfile>,
+}
+
+/// The recorded execution counts of a single function, see [`WasmProfile`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionProfile {
+    calls: u64,
+    branches: BTreeMap<u32, BranchCounts>,
+}
+
+/// How often a conditional branch went either way.
+///
+/// For an `if` the branch is "taken" when its `then` arm executes, and for a
+/// `br_if` when it branches rather than falling through.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchCounts {
+    /// Number of times the branch was taken.
+    pub taken: u64,
+    /// Number of times the branch was not taken.
+    pub not_taken: u64,
+}
+
+impl BranchCounts {
+    /// Returns which way this branch always went, if it executed at all and
+    /// only ever went one way.
+    pub fn always(&self) -> Option<bool> {
+        match (self.taken, self.not_taken) {
+            (0, 0) => None,
+            (_, 0) => Some(true),
+            (0, _) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl WasmProfile {
+    /// Creates a new, empty, profile.
+    pub fn new() -> WasmProfile {
+        WasmProfile::default()
+    }
+
+    /// Records one call to the function `func`.
+    pub fn record_call(&mut self, func: FuncIndex) {
+        let calls = &mut self.functions.entry(func).or_default().calls;
+        *calls = calls.saturating_add(1);
+    }
+
+    /// Records one execution of the branch at body-relative `offset` in the
+    /// function `func`.
+    pub fn record_branch(&mut self, func: FuncIndex, offset: u32, taken: bool) {
+        let counts = self
+            .functions
+            .entry(func)
+            .or_default()
+            .branches
+            .entry(offset)
+            .or_default();
+        let count = if taken {
+            &mut counts.taken
+        } else {
+            &mut counts.not_taken
+        };
+        *count = count.saturating_add(1);
+    }
+
+    /// Returns the recorded counts of the function `func`, if it was recorded
+    /// at all.
+    pub fn function(&self, func: FuncIndex) -> Option<&FunctionProfile> {
+        self.functions.get(&func)
+    }
+
+    /// Returns an iterator over all recorded functions and their counts, in
+    /// order of function index.
+    pub fn functions(&self) -> impl ExactSizeIterator<Item = (FuncIndex, &FunctionProfile)> + '_ {
+        self.functions.iter().map(|(i, f)| (*i, f))
+    }
+
+    /// Returns the number of calls recorded for the function `func`.
+    pub fn calls(&self, func: FuncIndex) -> u64 {
+        self.function(func).map_or(0, |f| f.calls)
+    }
+
+    /// Returns whether nothing has been recorded in this profile.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Adds all counts recorded in `other` to this profile, for example to
+    /// combine the profiles of several runs of the same module.
+    pub fn merge(&mut self, other: &WasmProfile) {
+        for (func, other) in other.functions() {
+            let this = self.functions.entry(func).or_default();
+            this.calls = this.calls.saturating_add(other.calls);
+            for (offset, counts) in other.branches() {
+                let this = this.branches.entry(offset).or_default();
+                this.taken = this.taken.saturating_add(counts.taken);
+                this.not_taken = this.not_taken.saturating_add(counts.not_taken);
+            }
+        }
+    }
+
+    /// Serializes this profile into a compact binary format which can be
+    /// read back with [`WasmProfile::deserialize`].
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        postcard::to_extend(self, bytes).expect("serializing into a `Vec` cannot fail")
+    }
+
+    /// Deserializes a profile previously produced by
+    /// [`WasmProfile::serialize`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` aren't a serialized profile, or if they
+    /// were serialized by an incompatible version of this crate.
+    pub fn deserialize(bytes: &[u8]) -> Result<WasmProfile> {
+        let Some(rest) = bytes.strip_prefix(MAGIC) else {
+            bail!("bytes are not a serialized WebAssembly profile");
+        };
+        match rest.split_first() {
+            Some((&VERSION, rest)) => {
+                postcard::from_bytes(rest).context("failed to deserialize WebAssembly profile")
+            }
+            Some((version, _)) => bail!("unsupported WebAssembly profile version {version}"),
+            None => bail!("truncated WebAssembly profile"),
+        }
+    }
+}
+
+impl FunctionProfile {
+    /// Returns the number of times this function was called.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Returns the counts of the branch at body-relative `offset`, if it was
+    /// executed at all.
+    pub fn branch(&self, offset: u32) -> Option<BranchCounts> {
+        self.branches.get(&offset).copied()
+    }
+
+    /// Returns an iterator over the counts of all executed branches of this
+    /// function, in order of offset.
+    pub fn branches(&self) -> impl ExactSizeIterator<Item = (u32, BranchCounts)> + '_ {
+        self.branches.iter().map(|(o, c)| (*o, *c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_merge() {
+        let f0 = FuncIndex::from_u32(0);
+        let f1 = FuncIndex::from_u32(1);
+
+        let mut a = WasmProfile::new();
+        assert!(a.is_empty());
+        a.record_call(f0);
+        a.record_branch(f0, 4, true);
+        a.record_branch(f0, 4, true);
+        a.record_branch(f0, 9, false);
+
+        let mut b = WasmProfile::new();
+        b.record_call(f0);
+        b.record_call(f1);
+        b.record_branch(f0, 9, true);
+
+        a.merge(&b);
+        assert_eq!(a.calls(f0), 2);
+        assert_eq!(a.calls(f1), 1);
+        assert_eq!(a.calls(FuncIndex::from_u32(2)), 0);
+
+        let f = a.function(f0).unwrap();
+        assert_eq!(f.branch(4).unwrap().always(), Some(true));
+        assert_eq!(
+            f.branch(9),
+            Some(BranchCounts {
+                taken: 1,
+                not_taken: 1
+            })
+        );
+        assert_eq!(f.branch(9).unwrap().always(), None);
+        assert_eq!(f.branch(5), None);
+    }
+
+    #[test]
+    fn serialize_roundtrip() {
+        let mut profile = WasmProfile::new();
+        profile.record_call(FuncIndex::from_u32(3));
+        profile.record_branch(FuncIndex::from_u32(3), 17, false);
+
+        let bytes = profile.serialize();
+        assert_eq!(WasmProfile::deserialize(&bytes).unwrap(), profile);
+
+        assert!(WasmProfile::deserialize(b"garbage").is_err());
+        assert!(WasmProfile::deserialize(&bytes[..MAGIC.len()]).is_err());
+        let mut future = bytes.clone();
+        future[MAGIC.len()] = VERSION + 1;
+        assert!(WasmProfile::deserialize(&future).is_err());
+    }
+}
@@ -76,6 +76,9 @@ macro_rules! generate_trap_type {
         }
 
         impl Trap {
+            /// All trap codes, in order of their byte representation.
+            pub const ALL: &'static [Trap] = &[$(Trap::$name,)*];
+
             /// Converts a byte back into a `Trap` if its in-bounds
             pub fn from_u8(byte: u8) -> Option<Trap> {
                 $(
@@ -99,6 +99,11 @@ define_tunables! {
         /// Whether or not we use epoch-based interruption.
         pub epoch_interruption: bool,
 
+        /// When using epoch-based interruption, the maximum number of operators
+        /// translated in a row without an epoch check, after which an extra
+        /// check is inserted.
+        pub epoch_interruption_check_interval: Option<NonZeroU32>,
+
         /// Whether or not linear memories are allowed to be reallocated after
         /// initial allocation at runtime.
         pub memory_may_move: bool,
@@ -147,6 +152,19 @@ define_tunables! {
         /// sizes, past which we will generally not inline calls anymore.
         pub inlining_sum_size_threshold: u32,
 
+        /// The size of fused component adapters, at or below which calls to
+        /// them are inlined into their callers even when `inlining` would not
+        /// otherwise allow it. A size of zero disables this.
+        pub inlining_adapter_size: u32,
+
+        /// The minimum length of a run of `br_table` entries with the same
+        /// target for the run to be split out of the jump table into a range
+        /// check. Gaps of default targets shorter than this are kept within the
+        /// jump table, and the remaining jump tables may be split into two
+        /// levels. A threshold of zero disables this and emits every
+        /// `br_table` as a single dense jump table.
+        pub br_table_range_split: u32,
+
         /// Whether any component model feature related to concurrency is
         /// enabled.
         pub concurrency_support: bool,
@@ -199,6 +217,10 @@ define_tunables! {
         /// Whether `metadata.code.branch_hint` sections are parsed and used to
         /// mark cold blocks during compilation.
         pub branch_hinting: bool,
+
+        /// Whether compiled code counts the calls of functions and the
+        /// directions of branches to record a `WasmProfile`.
+        pub profile_instrumentation: bool,
     }
 
     pub struct ConfigTunables {
@@ -262,6 +284,7 @@ impl Tunables {
             consume_fuel: false,
             operator_cost: OperatorCostStrategy::Default,
             epoch_interruption: false,
+            epoch_interruption_check_interval: None,
             memory_may_move: true,
             guard_before_linear_memory: true,
             table_lazy_init: true,
@@ -274,6 +297,8 @@ impl Tunables {
             inlining: Inlining::No,
             inlining_small_callee_size: 50,
             inlining_sum_size_threshold: 2000,
+            inlining_adapter_size: 0,
+            br_table_range_split: 0,
             debug_guest: false,
             concurrency_support: true,
             recording: false,
@@ -286,6 +311,7 @@ impl Tunables {
             metadata_for_internal_asserts: false,
             metadata_for_gc_heap_corruption: true,
             branch_hinting: false,
+            profile_instrumentation: false,
             debug_symbols: true,
         }
     }
@@ -642,3 +668,112 @@ macro_rules! define_operator_cost {
 }
 
 wasmparser::for_each_operator!(define_operator_cost);
+
+/// Fuel costs of broad classes of WebAssembly operators.
+///
+/// This is a coarser alternative to configuring an [`OperatorCost`] table
+/// operator-by-operator. Each operator is assigned to one class, and its cost
+/// is the cost configured for that class:
+///
+/// * Operators which are free by default, such as `nop`, `drop`, `block`,
+///   `loop`, `else` and `end`, stay free.
+/// * [`FuelCostTable::call`] - direct, indirect, reference and tail calls.
+/// * [`FuelCostTable::memory`] - all operators accessing linear memory, such
+///   as loads, stores, atomics, `memory.grow` and `memory.copy`, including
+///   SIMD loads and stores.
+/// * [`FuelCostTable::simd`] - all other operators of the SIMD and relaxed
+///   SIMD proposals.
+/// * [`FuelCostTable::other`] - everything else.
+///
+/// All classes cost 1 fuel by default, matching the default cost of
+/// operators.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FuelCostTable {
+    call: u8,
+    memory: u8,
+    simd: u8,
+    other: u8,
+}
+
+impl FuelCostTable {
+    /// Creates a new table where every class of operator costs 1 fuel.
+    pub const fn new() -> FuelCostTable {
+        FuelCostTable {
+            call: 1,
+            memory: 1,
+            simd: 1,
+            other: 1,
+        }
+    }
+
+    /// Configures the cost of call operators.
+    pub fn call(&mut self, cost: u8) -> &mut Self {
+        self.call = cost;
+        self
+    }
+
+    /// Configures the cost of operators accessing linear memory.
+    pub fn memory(&mut self, cost: u8) -> &mut Self {
+        self.memory = cost;
+        self
+    }
+
+    /// Configures the cost of SIMD operators which don't access memory.
+    pub fn simd(&mut self, cost: u8) -> &mut Self {
+        self.simd = cost;
+        self
+    }
+
+    /// Configures the cost of all operators not in any other class.
+    pub fn other(&mut self, cost: u8) -> &mut Self {
+        self.other = cost;
+        self
+    }
+
+    /// Returns the cost of the operator `op` from the `proposal` given, whose
+    /// cost is `default` when using the default costs.
+    fn class_cost(&self, proposal: &str, op: &str, default: u8) -> u8 {
+        if default == 0 {
+            0
+        } else if op.starts_with("Call") || op.starts_with("ReturnCall") {
+            self.call
+        } else if op.starts_with("Memory")
+            || (["I32", "I64", "F32", "F64", "V128"]
+                .iter()
+                .any(|ty| op.starts_with(ty))
+                && (op.contains("Load") || op.contains("Store") || op.contains("Atomic")))
+        {
+            self.memory
+        } else if proposal == "simd" || proposal == "relaxed_simd" {
+            self.simd
+        } else {
+            self.other
+        }
+    }
+}
+
+impl Default for FuelCostTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! define_fuel_cost_table {
+    ($(@$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident ($($ann:tt)*) )*) => {
+        impl From<&FuelCostTable> for OperatorCost {
+            fn from(table: &FuelCostTable) -> OperatorCost {
+                OperatorCost {
+                    $(
+                        $op: table.class_cost(
+                            stringify!($proposal),
+                            stringify!($op),
+                            default_cost!($op),
+                        ),
+                    )*
+                }
+            }
+        }
+    }
+}
+
+wasmparser::for_each_operator!(define_fuel_cost_table);
@@ -0,0 +1,167 @@
+//! Control-flow graphs of CLIF functions, rendered as SVG.
+//!
+//! Blocks are laid out top to bottom in the order they appear in the CLIF.
+//! Edges to the next block are drawn straight down, other forward edges are
+//! routed along the right-hand side and back edges (loops) along the left.
+
+use crate::{AnnotatedClifInstruction, WasmOffset};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const BLOCK_WIDTH: usize = 160;
+const BLOCK_HEIGHT: usize = 36;
+const BLOCK_GAP: usize = 24;
+const LANE_WIDTH: usize = 8;
+const MARGIN: usize = 8;
+
+struct Block<'a> {
+    name: &'a str,
+    header: &'a str,
+    instructions: usize,
+    wasm_offset: Option<WasmOffset>,
+    terminator: &'a str,
+}
+
+/// Renders the control-flow graph of a function's CLIF instructions, or
+/// returns `None` if no blocks were found.
+pub(crate) fn render(func_index: u32, instructions: &[AnnotatedClifInstruction]) -> Option<String> {
+    let mut blocks: Vec<Block<'_>> = Vec::new();
+    for inst in instructions {
+        let line = inst.clif.trim();
+        if line.starts_with("block") && line.ends_with(':') {
+            let name = line[..line.len() - 1].split('(').next().unwrap();
+            blocks.push(Block {
+                name,
+                header: line,
+                instructions: 0,
+                wasm_offset: None,
+                terminator: "",
+            });
+        } else if let Some(block) = blocks.last_mut() {
+            if line.is_empty() || line.starts_with(';') || line == "}" {
+                continue;
+            }
+            block.instructions += 1;
+            block.wasm_offset = block.wasm_offset.or(inst.wasm_offset);
+            block.terminator = line;
+        }
+    }
+    if blocks.is_empty() {
+        return None;
+    }
+
+    // Every block named in a block's terminator is one of its successors.
+    let index_of: HashMap<&str, usize> = blocks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.name, i))
+        .collect();
+    let mut edges = Vec::new();
+    for (from, block) in blocks.iter().enumerate() {
+        let mut succs = Vec::new();
+        for word in block.terminator.split(|c: char| !c.is_ascii_alphanumeric()) {
+            if let Some(&to) = index_of.get(word) {
+                if !succs.contains(&to) {
+                    succs.push(to);
+                }
+            }
+        }
+        edges.extend(succs.into_iter().map(|to| (from, to)));
+    }
+
+    let mut left_lanes = 0;
+    let mut right_lanes = 0;
+    let lanes: Vec<usize> = edges
+        .iter()
+        .map(|&(from, to)| {
+            if to == from + 1 {
+                0
+            } else if to > from {
+                right_lanes += 1;
+                right_lanes
+            } else {
+                left_lanes += 1;
+                left_lanes
+            }
+        })
+        .collect();
+
+    let left = MARGIN + left_lanes * LANE_WIDTH;
+    let right = left + BLOCK_WIDTH;
+    let width = right + right_lanes * LANE_WIDTH + MARGIN;
+    let height = MARGIN * 2 + blocks.len() * (BLOCK_HEIGHT + BLOCK_GAP) - BLOCK_GAP;
+    let top = |i: usize| MARGIN + i * (BLOCK_HEIGHT + BLOCK_GAP);
+    let arrow = format!("cfg-arrow-{func_index}");
+
+    let mut svg = String::new();
+    write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace" font-size="12">"#
+    )
+    .unwrap();
+    write!(
+        svg,
+        r##"<defs><marker id="{arrow}" viewBox="0 0 8 8" refX="8" refY="4" markerWidth="6" markerHeight="6" orient="auto"><path d="M0,0 L8,4 L0,8 z" fill="#555"/></marker></defs>"##
+    )
+    .unwrap();
+
+    for (&(from, to), lane) in edges.iter().zip(lanes) {
+        let path = if lane == 0 {
+            let x = left + BLOCK_WIDTH / 2;
+            format!("M{x},{} V{}", top(from) + BLOCK_HEIGHT, top(to))
+        } else {
+            let (side, x) = if to > from {
+                (right, right + lane * LANE_WIDTH)
+            } else {
+                (left, left - lane * LANE_WIDTH)
+            };
+            format!(
+                "M{side},{} H{x} V{} H{side}",
+                top(from) + BLOCK_HEIGHT * 3 / 4,
+                top(to) + BLOCK_HEIGHT / 4,
+            )
+        };
+        write!(
+            svg,
+            r##"<path d="{path}" fill="none" stroke="#555" marker-end="url(#{arrow})"/>"##
+        )
+        .unwrap();
+    }
+
+    for (i, block) in blocks.iter().enumerate() {
+        let y = top(i);
+        let is_exit = !edges.iter().any(|&(from, _)| from == i);
+        match block.wasm_offset {
+            Some(offset) => write!(svg, r#"<g data-wasm-offset="{}">"#, offset.0),
+            None => write!(svg, "<g>"),
+        }
+        .unwrap();
+        write!(
+            svg,
+            r##"<title>{}</title><rect x="{left}" y="{y}" width="{BLOCK_WIDTH}" height="{BLOCK_HEIGHT}" rx="4" fill="{}" stroke="#555"/>"##,
+            escape(block.header),
+            if is_exit { "#f4e4e4" } else { "#f4f4f4" },
+        )
+        .unwrap();
+        write!(
+            svg,
+            r##"<text x="{}" y="{}">{}</text><text x="{}" y="{}" fill="#777">{} instruction{}</text></g>"##,
+            left + 6,
+            y + 15,
+            escape(block.name),
+            left + 6,
+            y + 29,
+            block.instructions,
+            if block.instructions == 1 { "" } else { "s" },
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>");
+    Some(svg)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
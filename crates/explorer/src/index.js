@@ -233,6 +233,14 @@ if (clifElem) {
     funcHeader.title = `Function ${func.func_index}: ${func_name}`;
     funcElem.appendChild(funcHeader);
 
+    if (func.cfg !== null) {
+      const cfgElem = document.createElement("div");
+      cfgElem.classList.add("cfg");
+      cfgElem.innerHTML = func.cfg;
+      cfgElem.querySelectorAll("[data-wasm-offset]").forEach(linkElements);
+      funcElem.appendChild(cfgElem);
+    }
+
     for (const inst of func.instructions) {
       const instElem = createDivForCode();
       instElem.textContent = `${inst.clif}\n`;
@@ -260,6 +268,14 @@ for (const func of state.asm.functions) {
     func.demangled_name !== null ? func.demangled_name : functionName;
   funcHeader.textContent = `Disassembly of function <${demangledName}>:`;
   funcHeader.title = `Function ${func.func_index}: ${functionName}`;
+  const describeNames = (kind, names) => {
+    if (names.length > 0) {
+      const list = names.map(([index, name]) => `${index}: ${name}`).join(", ");
+      funcHeader.title += `\n${kind}: ${list}`;
+    }
+  };
+  describeNames("Locals", func.locals);
+  describeNames("Labels", func.labels);
   funcElem.appendChild(funcHeader);
 
   let currentBlock = createDivForCode();
@@ -294,6 +310,47 @@ for (const func of state.asm.functions) {
   asmElem.appendChild(funcElem);
 }
 
+// Render the register allocation view (if any).
+const regallocElem = document.getElementById("regalloc");
+if (regallocElem) {
+  const renderRegs = regs => (regs.length == 0 ? "-" : regs.join(","));
+  for (const func of state.asm.functions) {
+    const funcElem = document.createElement("div");
+
+    const funcHeader = document.createElement("h3");
+    let functionName =
+      func.name === null ? `function[${func.func_index}]` : func.name;
+    let demangledName =
+      func.demangled_name !== null ? func.demangled_name : functionName;
+    const { spills, reloads, max_live } = func.regalloc;
+    funcHeader.textContent = `Registers of function <${demangledName}>: ${spills} spills, ${reloads} reloads, at most ${max_live} live`;
+    funcHeader.title = `Function ${func.func_index}: ${functionName}`;
+    funcElem.appendChild(funcHeader);
+
+    for (const inst of func.instructions) {
+      const regs = inst.regs;
+      const instElem = createDivForCode();
+      const stack = regs.stack === null ? "" : regs.stack;
+      instElem.textContent =
+        `${renderAddress(inst.address)}    ${stack.padEnd(6)}    ` +
+        `def ${renderRegs(regs.defs).padEnd(12)}  ` +
+        `use ${renderRegs(regs.uses).padEnd(16)}  ` +
+        `live ${renderRegs(regs.live)}`;
+      if (regs.stack !== null) {
+        instElem.classList.add(regs.stack);
+      }
+      if (inst.wasm_offset !== null) {
+        instElem.dataset.wasmOffset = inst.wasm_offset;
+        adjustColorForOffset(instElem, inst.wasm_offset);
+        linkElements(instElem);
+      }
+      funcElem.appendChild(instElem);
+    }
+
+    regallocElem.appendChild(funcElem);
+  }
+}
+
 // Render the WAT.
 const watElem = document.getElementById("wat");
 for (const chunk of state.wat.chunks) {
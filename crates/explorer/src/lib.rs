@@ -16,10 +16,28 @@ use std::{
 use wasmtime::{Result, ToWasmtimeResult as _};
 use wasmtime_environ::demangle_function_name;
 
+mod cfg;
+mod regalloc;
+
+/// Optional views to include in the explorer output, in addition to the Wat,
+/// CLIF and disassembly.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Views {
+    /// Render the control-flow graph of each function's CLIF as an SVG.
+    ///
+    /// This requires CLIF, so it has no effect without a `clif_dir`.
+    pub cfg: bool,
+    /// Show the registers used, defined and live after each machine
+    /// instruction, along with which instructions spill to or reload from
+    /// stack slots.
+    pub regalloc: bool,
+}
+
 pub fn generate(
     config: &wasmtime::Config,
     target: Option<&str>,
     clif_dir: Option<&Path>,
+    views: Views,
     wasm: &[u8],
     dest: &mut dyn Write,
 ) -> Result<()> {
@@ -30,11 +48,11 @@ pub fn generate(
 
     let wat = annotate_wat(wasm)?;
     let wat_json = serde_json::to_string(&wat)?;
-    let asm = annotate_asm(config, &target, wasm)?;
+    let asm = annotate_asm(config, &target, views, wasm)?;
     let asm_json = serde_json::to_string(&asm)?;
     let clif_json = clif_dir
         .map::<wasmtime::Result<String>, _>(|clif_dir| {
-            let clif = annotate_clif(clif_dir, &asm)?;
+            let clif = annotate_clif(clif_dir, views, &asm)?;
             Ok(serde_json::to_string(&clif)?)
         })
         .transpose()?;
@@ -64,6 +82,14 @@ pub fn generate(
         dest,
         r#"
     <div id="asm"></div>
+        "#
+    )?;
+    if views.regalloc {
+        write!(dest, r#"<div id="regalloc"></div>"#)?;
+    }
+    write!(
+        dest,
+        r#"
     <script>
       window.WAT = {wat_json};
         "#
@@ -129,7 +155,10 @@ struct AnnotatedFunction {
     func_index: u32,
     name: Option<String>,
     demangled_name: Option<String>,
+    locals: Vec<(u32, String)>,
+    labels: Vec<(u32, String)>,
     instructions: Vec<AnnotatedInstruction>,
+    regalloc: Option<regalloc::RegallocSummary>,
 }
 
 #[derive(Serialize, Debug)]
@@ -139,11 +168,13 @@ struct AnnotatedInstruction {
     bytes: Vec<u8>,
     mnemonic: Option<String>,
     operands: Option<String>,
+    regs: Option<regalloc::InstructionRegs>,
 }
 
 fn annotate_asm(
     config: &wasmtime::Config,
     target: &target_lexicon::Triple,
+    views: Views,
     wasm: &[u8],
 ) -> Result<AnnotatedAsm> {
     let engine = wasmtime::Engine::new(config)?;
@@ -208,10 +239,19 @@ fn annotate_asm(
             // additionally is required to skip over trapping instructions on
             // AArch64.
             cs.set_skipdata(true).unwrap();
+            if views.regalloc {
+                cs.set_detail(true).unwrap();
+            }
 
             let instructions = cs
                 .disasm_all(body, function.offset as u64)
                 .map_err(|e| wasmtime::format_err!("{e}"))?;
+            let (mut regs, regalloc) = if views.regalloc {
+                let (regs, summary) = regalloc::analyze(&cs, &target.architecture, &instructions);
+                (Some(regs.into_iter()), Some(summary))
+            } else {
+                (None, None)
+            };
             let instructions = instructions
                 .iter()
                 .map(|inst| {
@@ -223,6 +263,7 @@ fn annotate_asm(
                         bytes: inst.bytes().to_vec(),
                         mnemonic: inst.mnemonic().map(ToString::to_string),
                         operands: inst.op_str().map(ToString::to_string),
+                        regs: regs.as_mut().and_then(|regs| regs.next()),
                     })
                 })
                 .collect::<Result<Vec<_>>>()?;
@@ -238,11 +279,22 @@ fn annotate_asm(
                 None
             };
 
+            let func_index = function.index.as_u32();
+            let names = module.names();
             Ok(AnnotatedFunction {
-                func_index: function.index.as_u32(),
+                func_index,
                 name: function.name,
                 demangled_name,
+                locals: names
+                    .locals(func_index)
+                    .map(|(i, name)| (i, name.to_string()))
+                    .collect(),
+                labels: names
+                    .labels(func_index)
+                    .map(|(i, name)| (i, name.to_string()))
+                    .collect(),
                 instructions,
+                regalloc,
             })
         })
         .collect::<Result<Vec<_>>>()?;
@@ -261,6 +313,7 @@ struct AnnotatedClifFunction {
     name: Option<String>,
     demangled_name: Option<String>,
     instructions: Vec<AnnotatedClifInstruction>,
+    cfg: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -269,20 +322,30 @@ struct AnnotatedClifInstruction {
     clif: String,
 }
 
-fn annotate_clif(clif_dir: &Path, asm: &AnnotatedAsm) -> Result<AnnotatedClif> {
+fn annotate_clif(clif_dir: &Path, views: Views, asm: &AnnotatedAsm) -> Result<AnnotatedClif> {
     let mut clif = AnnotatedClif {
         functions: Vec::new(),
     };
+    let clif_files = std::fs::read_dir(clif_dir)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<_>>>()?;
     for function in &asm.functions {
-        let function_path = clif_dir.join(format!("wasm_func_{}.clif", function.func_index));
-        if !function_path.exists() {
+        // Files are named after the function's symbol, e.g.
+        // `wasm[0]::function[1]::name`, with `::` replaced by `--`.
+        let symbol = format!("wasm[0]--function[{}]", function.func_index);
+        let Some(file_name) = clif_files.iter().find(|name| {
+            name.strip_prefix(&symbol)
+                .is_some_and(|rest| rest == ".clif" || rest.starts_with("--"))
+        }) else {
             continue;
-        }
+        };
+        let function_path = clif_dir.join(file_name);
         let mut clif_function = AnnotatedClifFunction {
             func_index: function.func_index,
             name: function.name.clone(),
             demangled_name: function.demangled_name.clone(),
             instructions: Vec::new(),
+            cfg: None,
         };
         let file = File::open(&function_path)?;
         for mut line in read_to_string(file)?.lines() {
@@ -301,6 +364,9 @@ fn annotate_clif(clif_dir: &Path, asm: &AnnotatedAsm) -> Result<AnnotatedClif> {
                 clif: line.to_string(),
             });
         }
+        if views.cfg {
+            clif_function.cfg = cfg::render(function.func_index, &clif_function.instructions);
+        }
         clif.functions.push(clif_function);
     }
     Ok(clif)
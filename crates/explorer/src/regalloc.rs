@@ -0,0 +1,338 @@
+//! Register usage, liveness and stack slot accesses of disassembled machine
+//! code.
+//!
+//! This is reconstructed from the machine code alone with the help of
+//! capstone, so it is an approximation of what the register allocator did:
+//! registers implicitly used by the ABI, such as arguments to calls and return
+//! values, are not visible, and indirect branches (e.g. jump tables) are
+//! treated as having no known successors.
+
+use capstone::arch::ArchOperand;
+use capstone::arch::arm64::Arm64OperandType;
+use capstone::arch::riscv::RiscVOperand;
+use capstone::arch::sysz::SysZOperand;
+use capstone::arch::x86::X86OperandType;
+use capstone::{Capstone, Insn, InsnGroupType, RegId};
+use serde_derive::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use target_lexicon::Architecture;
+
+/// Registers used, defined and live after a single machine instruction.
+#[derive(Serialize, Debug)]
+pub(crate) struct InstructionRegs {
+    uses: Vec<String>,
+    defs: Vec<String>,
+    live: Vec<String>,
+    stack: Option<StackAccess>,
+}
+
+/// An access to a stack slot, relative to the stack or frame pointer.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum StackAccess {
+    /// A register is stored to a stack slot.
+    Spill,
+    /// A register is loaded from a stack slot.
+    Reload,
+}
+
+/// Register allocation statistics for a whole function.
+#[derive(Serialize, Debug)]
+pub(crate) struct RegallocSummary {
+    spills: usize,
+    reloads: usize,
+    max_live: usize,
+}
+
+struct Inst {
+    uses: BTreeSet<usize>,
+    defs: BTreeSet<usize>,
+    stack: Option<StackAccess>,
+    falls_through: bool,
+    target: Option<u64>,
+}
+
+/// Computes register uses, definitions and liveness for every instruction of
+/// a function.
+///
+/// `cs` must have been configured with detail enabled.
+pub(crate) fn analyze(
+    cs: &Capstone,
+    arch: &Architecture,
+    insns: &[Insn<'_>],
+) -> (Vec<InstructionRegs>, RegallocSummary) {
+    let mut names = Names::default();
+    let reg_name = |reg: RegId| canonical_reg(arch, &cs.reg_name(reg)?);
+
+    let insts = insns
+        .iter()
+        .map(|insn| {
+            let Ok(detail) = cs.insn_detail(insn) else {
+                // Data skipped over by capstone.
+                return Inst {
+                    uses: BTreeSet::new(),
+                    defs: BTreeSet::new(),
+                    stack: None,
+                    falls_through: true,
+                    target: None,
+                };
+            };
+            let uses = detail
+                .regs_read()
+                .iter()
+                .filter_map(|r| Some(names.intern(reg_name(*r)?)))
+                .collect();
+            let mut defs: BTreeSet<_> = detail
+                .regs_write()
+                .iter()
+                .filter_map(|r| Some(names.intern(reg_name(*r)?)))
+                .collect();
+            let in_group = |group| detail.groups().iter().any(|g| u32::from(g.0) == group);
+            let mnemonic = insn.mnemonic().unwrap_or("");
+
+            let mut target = None;
+            let mut stack_slot = false;
+            for op in detail.arch_detail().operands() {
+                match operand(op) {
+                    Operand::Imm(imm) => target = Some(imm),
+                    Operand::Mem(base) => {
+                        stack_slot |= cs
+                            .reg_name(base)
+                            .is_some_and(|name| is_frame_reg(arch, &name));
+                    }
+                    Operand::Other => {}
+                }
+            }
+            let stack = match (stack_slot, defs.is_empty()) {
+                (false, _) => None,
+                // Address computations don't access the stack slot.
+                (true, _) if mnemonic == "lea" => None,
+                (true, true) => Some(StackAccess::Spill),
+                (true, false) => Some(StackAccess::Reload),
+            };
+
+            let jump = in_group(InsnGroupType::CS_GRP_JUMP);
+            let ret = in_group(InsnGroupType::CS_GRP_RET);
+            if in_group(InsnGroupType::CS_GRP_CALL) {
+                // Calls define at least the first return register, without
+                // which its value would appear live since the start of the
+                // function.
+                defs.insert(names.intern(return_reg(arch).to_string()));
+            }
+            Inst {
+                uses,
+                defs,
+                stack,
+                falls_through: !ret && !(jump && is_unconditional_jump(arch, mnemonic)),
+                target: if jump {
+                    target.map(|t| branch_target(arch, insn.address(), t))
+                } else {
+                    None
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Standard backwards dataflow analysis to a fixpoint over the successors
+    // of each instruction.
+    let index_of: HashMap<u64, usize> = insns
+        .iter()
+        .enumerate()
+        .map(|(i, insn)| (insn.address(), i))
+        .collect();
+    let succs: Vec<Vec<usize>> = insts
+        .iter()
+        .enumerate()
+        .map(|(i, inst)| {
+            let fallthrough = (inst.falls_through && i + 1 < insts.len()).then_some(i + 1);
+            let target = inst.target.and_then(|t| index_of.get(&t).copied());
+            fallthrough.into_iter().chain(target).collect()
+        })
+        .collect();
+    let mut live_in = vec![BTreeSet::new(); insts.len()];
+    let mut live_out = vec![BTreeSet::new(); insts.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in (0..insts.len()).rev() {
+            let out: BTreeSet<usize> = succs[i]
+                .iter()
+                .flat_map(|s| live_in[*s].iter().copied())
+                .collect();
+            let inp: BTreeSet<usize> = out
+                .difference(&insts[i].defs)
+                .chain(&insts[i].uses)
+                .copied()
+                .collect();
+            if inp != live_in[i] {
+                live_in[i] = inp;
+                changed = true;
+            }
+            live_out[i] = out;
+        }
+    }
+
+    let named = |regs: &BTreeSet<usize>| regs.iter().map(|r| names.names[*r].clone()).collect();
+    let summary = RegallocSummary {
+        spills: insts
+            .iter()
+            .filter(|i| i.stack == Some(StackAccess::Spill))
+            .count(),
+        reloads: insts
+            .iter()
+            .filter(|i| i.stack == Some(StackAccess::Reload))
+            .count(),
+        max_live: live_out.iter().map(|l| l.len()).max().unwrap_or(0),
+    };
+    let regs = insts
+        .iter()
+        .zip(&live_out)
+        .map(|(inst, live)| InstructionRegs {
+            uses: named(&inst.uses),
+            defs: named(&inst.defs),
+            live: named(live),
+            stack: inst.stack,
+        })
+        .collect();
+    (regs, summary)
+}
+
+/// Interns register names into small integers for the dataflow analysis.
+#[derive(Default)]
+struct Names {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl Names {
+    fn intern(&mut self, name: String) -> usize {
+        let next = self.names.len();
+        *self.ids.entry(name).or_insert_with_key(|name| {
+            self.names.push(name.clone());
+            next
+        })
+    }
+}
+
+enum Operand {
+    Imm(i64),
+    Mem(RegId),
+    Other,
+}
+
+fn operand(op: ArchOperand) -> Operand {
+    match op {
+        ArchOperand::X86Operand(op) => match op.op_type {
+            X86OperandType::Imm(imm) => Operand::Imm(imm),
+            X86OperandType::Mem(mem) => Operand::Mem(mem.base()),
+            _ => Operand::Other,
+        },
+        ArchOperand::Arm64Operand(op) => match op.op_type {
+            Arm64OperandType::Imm(imm) => Operand::Imm(imm),
+            Arm64OperandType::Mem(mem) => Operand::Mem(mem.base()),
+            _ => Operand::Other,
+        },
+        ArchOperand::RiscVOperand(RiscVOperand::Imm(imm)) => Operand::Imm(imm),
+        ArchOperand::RiscVOperand(RiscVOperand::Mem(mem)) => Operand::Mem(mem.base()),
+        ArchOperand::SysZOperand(SysZOperand::Imm(imm)) => Operand::Imm(imm),
+        ArchOperand::SysZOperand(SysZOperand::Mem(mem)) => Operand::Mem(RegId(mem.base().into())),
+        _ => Operand::Other,
+    }
+}
+
+/// Returns the absolute address of a branch's immediate operand.
+fn branch_target(arch: &Architecture, address: u64, imm: i64) -> u64 {
+    match arch {
+        // Capstone reports these as offsets relative to the branch.
+        Architecture::Riscv64(_) | Architecture::S390x => address.wrapping_add_signed(imm),
+        _ => imm as u64,
+    }
+}
+
+/// The register holding a call's first return value.
+fn return_reg(arch: &Architecture) -> &'static str {
+    match arch {
+        Architecture::Aarch64(_) => "x0",
+        Architecture::Riscv64(_) => "a0",
+        Architecture::S390x => "r2",
+        _ => "rax",
+    }
+}
+
+fn is_unconditional_jump(arch: &Architecture, mnemonic: &str) -> bool {
+    match arch {
+        Architecture::X86_64 => mnemonic == "jmp",
+        Architecture::Aarch64(_) => matches!(mnemonic, "b" | "br"),
+        Architecture::Riscv64(_) => matches!(mnemonic, "j" | "jr" | "c.j" | "c.jr"),
+        Architecture::S390x => matches!(mnemonic, "j" | "jg" | "br"),
+        _ => false,
+    }
+}
+
+/// Whether `name` is the stack or frame pointer, which address stack slots.
+fn is_frame_reg(arch: &Architecture, name: &str) -> bool {
+    match arch {
+        Architecture::X86_64 => matches!(name, "rsp" | "rbp"),
+        Architecture::Aarch64(_) => matches!(name, "sp" | "x29" | "fp"),
+        Architecture::Riscv64(_) => matches!(name, "sp" | "s0" | "fp"),
+        Architecture::S390x => matches!(name, "r15"),
+        _ => false,
+    }
+}
+
+/// Maps sub-registers onto the full register they are part of, so that for
+/// example `eax` and `rax` are treated as the same register.
+///
+/// Returns `None` for registers which aren't interesting for register
+/// allocation, such as the stack and frame pointers, flags and zero registers.
+fn canonical_reg(arch: &Architecture, name: &str) -> Option<String> {
+    if is_frame_reg(arch, name) {
+        return None;
+    }
+    let name = match arch {
+        Architecture::X86_64 => {
+            if let Some(n) = name
+                .strip_prefix('r')
+                .filter(|n| n.starts_with(|c: char| c.is_ascii_digit()))
+            {
+                // r8..r15 and their r8d, r8w and r8b sub-registers.
+                format!("r{}", n.trim_end_matches(['d', 'w', 'b']))
+            } else if let Some(n) = name
+                .strip_prefix(['x', 'y', 'z'])
+                .filter(|n| n.starts_with("mm"))
+            {
+                format!("x{n}")
+            } else {
+                let base = match name {
+                    "rax" | "eax" | "ax" | "ah" | "al" => "rax",
+                    "rbx" | "ebx" | "bx" | "bh" | "bl" => "rbx",
+                    "rcx" | "ecx" | "cx" | "ch" | "cl" => "rcx",
+                    "rdx" | "edx" | "dx" | "dh" | "dl" => "rdx",
+                    "rsi" | "esi" | "si" | "sil" => "rsi",
+                    "rdi" | "edi" | "di" | "dil" => "rdi",
+                    "esp" | "sp" | "spl" | "ebp" | "bp" | "bpl" | "rip" | "eip" | "rflags"
+                    | "eflags" | "fpsw" | "mxcsr" => return None,
+                    other => other,
+                };
+                base.to_string()
+            }
+        }
+        Architecture::Aarch64(_) => match name.split_at_checked(1) {
+            Some(("w", n)) if n.parse::<u8>().is_ok() => format!("x{n}"),
+            Some(("b" | "h" | "s" | "d" | "q", n)) if n.parse::<u8>().is_ok() => {
+                format!("v{n}")
+            }
+            _ if matches!(
+                name,
+                "wsp" | "xzr" | "wzr" | "nzcv" | "pc" | "fpcr" | "fpsr"
+            ) =>
+            {
+                return None;
+            }
+            _ => name.to_string(),
+        },
+        Architecture::Riscv64(_) if matches!(name, "zero" | "pc") => return None,
+        _ => name.to_string(),
+    };
+    Some(name)
+}
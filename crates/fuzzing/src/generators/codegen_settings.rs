@@ -123,6 +123,7 @@ impl<'a> Arbitrary<'a> for CodegenSettings {
                     // not a lot of cpus support avx512 so these are weighted
                     // to get enabled much less frequently.
                     std:"avx512bitalg" => clif:"has_avx512bitalg" ratio:1 in 1000,
+                    std:"avx512bw" => clif:"has_avx512bw" ratio: 1 in 1000,
                     std:"avx512dq" => clif:"has_avx512dq" ratio: 1 in 1000,
                     std:"avx512f" => clif:"has_avx512f" ratio: 1 in 1000,
                     std:"avx512vl" => clif:"has_avx512vl" ratio: 1 in 1000,
@@ -664,7 +664,7 @@ impl WasmtimeConfig {
         _u: &mut Unstructured<'_>,
     ) -> arbitrary::Result<()> {
         match self.compiler_strategy {
-            CompilerStrategy::CraneliftNative => {}
+            CompilerStrategy::CraneliftNative | CompilerStrategy::CraneliftPulley => {}
 
             CompilerStrategy::Winch => {
                 // Winch is not complete on non-x64 targets, so just abandon this test
@@ -713,13 +713,8 @@ impl WasmtimeConfig {
                 // Tuning  the following engine options is currently not supported
                 // by Winch.
                 self.signals_based_traps = true;
-                self.table_lazy_init = true;
                 self.debug_info = false;
             }
-
-            CompilerStrategy::CraneliftPulley => {
-                config.config.threads_enabled = false;
-            }
         }
 
         // If using the pooling allocator, constrain the memory and module configurations
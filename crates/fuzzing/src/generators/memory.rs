@@ -78,19 +78,27 @@ impl std::fmt::Debug for HeapImage {
 
 impl<'a> Arbitrary<'a> for HeapImage {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
-        let minimum = u.int_in_range(0..=4)?;
-        let maximum = if u.arbitrary()? {
-            Some(u.int_in_range(minimum..=10)?)
-        } else {
-            None
-        };
-        let memory64 = u.arbitrary()?;
         let page_size_log2 = match u.int_in_range(0..=2)? {
             0 => None,
             1 => Some(0),
             2 => Some(16),
             _ => unreachable!(),
         };
+        // With byte-sized pages, allow sizes of a few host pages so that the
+        // bounds of the memory frequently land in the middle of a host page,
+        // where out-of-bounds accesses can't be caught by guard pages.
+        let max_minimum = if page_size_log2 == Some(0) {
+            0x3_0000
+        } else {
+            4
+        };
+        let minimum = u.int_in_range(0..=max_minimum)?;
+        let maximum = if u.arbitrary()? {
+            Some(u.int_in_range(minimum..=max_minimum.saturating_mul(2).max(10))?)
+        } else {
+            None
+        };
+        let memory64 = u.arbitrary()?;
         let mut segments = vec![];
         if minimum > 0 {
             for _ in 0..u.int_in_range(0..=4)? {
@@ -0,0 +1,301 @@
+//! A harness for differential testing of the WASIp2 and WASIp3 host
+//! implementations in `wasmtime-wasi`.
+//!
+//! Each scenario is written as a pair of guests, one against each version of
+//! the WASI APIs, which perform the same operations and report what they
+//! observe on stdout. [`Harness::record`] runs a guest in a fresh environment
+//! and captures its observable [`Effects`], and [`Harness::compare`] runs both
+//! halves of a scenario and reports any difference between the two.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::path::Path;
+use std::time::Duration;
+use wasmtime::component::{Component, Linker, ResourceTable};
+use wasmtime::{Engine, Result, Store, bail, error::Context as _};
+use wasmtime_wasi::p2::pipe::MemoryOutputPipe;
+use wasmtime_wasi::{
+    DirPerms, FilePerms, HostMonotonicClock, HostWallClock, I32Exit, WasiCtx, WasiCtxBuilder,
+    WasiCtxView, WasiView,
+};
+
+/// Upper bound on the amount of stdout and stderr captured from a guest.
+const MAX_OUTPUT_SIZE: usize = 1 << 20;
+
+/// Which WASI host implementation to run a guest against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stack {
+    /// The `wasi:cli/command@0.2.x` world.
+    P2,
+    /// The `wasi:cli/command@0.3.x` world.
+    P3,
+}
+
+/// The observable effects of running a guest to completion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Effects {
+    /// How the guest exited.
+    pub exit: Exit,
+    /// Everything the guest wrote to stdout.
+    pub stdout: String,
+    /// Everything the guest wrote to stderr.
+    pub stderr: String,
+    /// The contents of the guest's preopened directory once it exited, keyed
+    /// by `/`-separated path relative to that directory.
+    pub files: BTreeMap<String, Entry>,
+}
+
+/// How a guest exited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exit {
+    /// The guest returned from `wasi:cli/run#run` or called `wasi:cli/exit`
+    /// with the given code, where returning an error is code 1.
+    Code(i32),
+    /// The guest trapped.
+    ///
+    /// The trap's message isn't recorded as it's not expected to be identical
+    /// across host implementations.
+    Trap,
+}
+
+/// An entry in a guest's preopened directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// A directory.
+    Directory,
+    /// A regular file with the given contents.
+    File(Vec<u8>),
+    /// A symbolic link to the given target.
+    Symlink(String),
+}
+
+impl Effects {
+    /// Returns a human-readable description of how `other` differs from
+    /// `self`, or `None` if they're the same.
+    pub fn diff(&self, other: &Effects) -> Option<String> {
+        if self == other {
+            return None;
+        }
+        let mut diff = String::new();
+        if self.exit != other.exit {
+            writeln!(diff, "exit: {:?} != {:?}", self.exit, other.exit).unwrap();
+        }
+        for (name, a, b) in [
+            ("stdout", &self.stdout, &other.stdout),
+            ("stderr", &self.stderr, &other.stderr),
+        ] {
+            if a != b {
+                let text = similar::TextDiff::from_lines(a, b);
+                writeln!(diff, "{name}:\n{}", text.unified_diff()).unwrap();
+            }
+        }
+        let paths = self.files.keys().chain(other.files.keys());
+        let mut paths = paths.collect::<Vec<_>>();
+        paths.sort();
+        paths.dedup();
+        for path in paths {
+            match (self.files.get(path), other.files.get(path)) {
+                (a, b) if a == b => {}
+                (a, b) => writeln!(diff, "files: {path}: {a:?} != {b:?}").unwrap(),
+            }
+        }
+        Some(diff)
+    }
+}
+
+impl fmt::Display for Stack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stack::P2 => f.write_str("p2"),
+            Stack::P3 => f.write_str("p3"),
+        }
+    }
+}
+
+/// Runs guests against either WASI host implementation in an identical,
+/// deterministic environment.
+///
+/// Each guest gets:
+///
+/// * an empty stdin, and captured stdout and stderr,
+/// * its file name as its only argument and no environment variables,
+/// * a fresh temporary directory preopened as `.` with all permissions,
+/// * access to the host's network,
+/// * and wall and monotonic clocks which always report the same time.
+pub struct Harness {
+    engine: Engine,
+    p2: Linker<Ctx>,
+    p3: Linker<Ctx>,
+}
+
+impl Harness {
+    /// Creates a new harness compiling guests with `engine`, which must have
+    /// the component model async proposal enabled.
+    pub fn new(engine: &Engine) -> Result<Harness> {
+        let mut p2 = Linker::new(engine);
+        wasmtime_wasi::p2::add_to_linker_async(&mut p2)?;
+
+        // WASIp3 guests are currently still built for `wasm32-wasip1` and use
+        // an adapter to WASIp2 for the standard library, so they need both.
+        let mut p3 = Linker::new(engine);
+        wasmtime_wasi::p2::add_to_linker_async(&mut p3)?;
+        wasmtime_wasi::p3::add_to_linker(&mut p3)?;
+
+        Ok(Harness {
+            engine: engine.clone(),
+            p2,
+            p3,
+        })
+    }
+
+    /// Runs the component at `path` against `stack`, recording its effects.
+    pub async fn record(&self, stack: Stack, path: &Path) -> Result<Effects> {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let workspace = tempfile::Builder::new()
+            .prefix(&format!("wasi_differential_{name}_"))
+            .tempdir()?;
+        let stdout = MemoryOutputPipe::new(MAX_OUTPUT_SIZE);
+        let stderr = MemoryOutputPipe::new(MAX_OUTPUT_SIZE);
+        let wasi = WasiCtxBuilder::new()
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
+            .args(&[name])
+            .preopened_dir(workspace.path(), ".", DirPerms::all(), FilePerms::all())?
+            .inherit_network()
+            .allow_tcp(true)
+            .allow_udp(true)
+            .allow_ip_name_lookup(true)
+            .wall_clock(FixedWallClock)
+            .monotonic_clock(FixedMonotonicClock)
+            .build();
+        let mut store = Store::new(
+            &self.engine,
+            Ctx {
+                wasi,
+                table: ResourceTable::new(),
+            },
+        );
+
+        let component = Component::from_file(&self.engine, path)?;
+        let result = match stack {
+            Stack::P2 => {
+                let command = wasmtime_wasi::p2::bindings::Command::instantiate_async(
+                    &mut store, &component, &self.p2,
+                )
+                .await
+                .context("failed to instantiate `wasi:cli/command@0.2.x`")?;
+                command.wasi_cli_run().call_run(&mut store).await
+            }
+            Stack::P3 => {
+                let command = wasmtime_wasi::p3::bindings::Command::instantiate_async(
+                    &mut store, &component, &self.p3,
+                )
+                .await
+                .context("failed to instantiate `wasi:cli/command@0.3.x`")?;
+                store
+                    .run_concurrent(async move |store| command.wasi_cli_run().call_run(store).await)
+                    .await
+                    .and_then(|result| result)
+            }
+        };
+        let exit = match result {
+            Ok(Ok(())) => Exit::Code(0),
+            Ok(Err(())) => Exit::Code(1),
+            Err(e) => match e.downcast_ref::<I32Exit>() {
+                Some(exit) => Exit::Code(exit.0),
+                None => Exit::Trap,
+            },
+        };
+        drop(store);
+
+        Ok(Effects {
+            exit,
+            stdout: String::from_utf8_lossy(&stdout.contents()).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr.contents()).into_owned(),
+            files: snapshot(workspace.path())?,
+        })
+    }
+
+    /// Runs the WASIp2 guest at `p2` and the WASIp3 guest at `p3`, returning
+    /// their common effects or an error describing how they differ.
+    pub async fn compare(&self, p2: &Path, p3: &Path) -> Result<Effects> {
+        let p2_effects = self.record(Stack::P2, p2).await?;
+        let p3_effects = self.record(Stack::P3, p3).await?;
+        if let Some(diff) = p2_effects.diff(&p3_effects) {
+            bail!(
+                "p2 and p3 hosts disagree (`-` is {}, `+` is {}):\n{diff}",
+                Stack::P2,
+                Stack::P3
+            );
+        }
+        Ok(p2_effects)
+    }
+}
+
+struct Ctx {
+    wasi: WasiCtx,
+    table: ResourceTable,
+}
+
+impl WasiView for Ctx {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView {
+            ctx: &mut self.wasi,
+            table: &mut self.table,
+        }
+    }
+}
+
+struct FixedWallClock;
+
+impl HostWallClock for FixedWallClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_millis(1)
+    }
+
+    fn now(&self) -> Duration {
+        Duration::new(1_700_000_000, 123_456_789)
+    }
+}
+
+struct FixedMonotonicClock;
+
+impl HostMonotonicClock for FixedMonotonicClock {
+    fn resolution(&self) -> u64 {
+        1_000
+    }
+
+    fn now(&self) -> u64 {
+        42_000_000_000
+    }
+}
+
+/// Records the contents of the directory `root`, recursively.
+fn snapshot(root: &Path) -> Result<BTreeMap<String, Entry>> {
+    fn visit(root: &Path, dir: &Path, entries: &mut BTreeMap<String, Entry>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path
+                .strip_prefix(root)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let ty = std::fs::symlink_metadata(&path)?.file_type();
+            let entry = if ty.is_symlink() {
+                Entry::Symlink(std::fs::read_link(&path)?.to_string_lossy().into_owned())
+            } else if ty.is_dir() {
+                visit(root, &path, entries)?;
+                Entry::Directory
+            } else {
+                Entry::File(std::fs::read(&path)?)
+            };
+            entries.insert(name, entry);
+        }
+        Ok(())
+    }
+
+    let mut entries = BTreeMap::new();
+    visit(root, root, &mut entries)?;
+    Ok(entries)
+}
@@ -0,0 +1,118 @@
+//! Runs each differential scenario against both the WASIp2 and WASIp3 hosts,
+//! asserting that they behave identically.
+
+use std::path::Path;
+use test_programs_artifacts::*;
+use wasi_differential_tests::{Entry, Exit, Harness};
+use wasmtime::Result;
+
+macro_rules! assert_test_exists {
+    ($name:ident) => {
+        #[expect(unused_imports, reason = "just here to ensure a name exists")]
+        use self::$name as _;
+    };
+}
+
+foreach_differential!(assert_test_exists);
+
+// Each scenario is named after its WASIp2 guest and also covers its WASIp3
+// counterpart.
+use self::differential_p2_clocks as differential_p3_clocks;
+use self::differential_p2_filesystem as differential_p3_filesystem;
+use self::differential_p2_sockets as differential_p3_sockets;
+
+fn harness() -> Result<Harness> {
+    let engine = test_programs_artifacts::engine(|config| {
+        config.wasm_component_model_async(true);
+    });
+    Harness::new(&engine)
+}
+
+async fn compare(p2: &str, p3: &str) -> Result<wasi_differential_tests::Effects> {
+    harness()?.compare(Path::new(p2), Path::new(p3)).await
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn differential_p2_filesystem() -> Result<()> {
+    let effects = compare(
+        DIFFERENTIAL_P2_FILESYSTEM_COMPONENT,
+        DIFFERENTIAL_P3_FILESYSTEM_COMPONENT,
+    )
+    .await?;
+    assert_eq!(effects.exit, Exit::Code(0));
+    assert_eq!(
+        effects.stdout,
+        "\
+create existing directory: exist
+size after write: 13
+read at offset 7: \"world\\n\"
+size after truncate: 5
+open missing file: no-entry
+create exclusive existing file: exist
+entry: greeting.txt (regular-file)
+entry: nested (directory)
+entry: scratch.txt (regular-file)
+remove non-empty directory: not-empty
+unlink directory: is-directory
+"
+    );
+    assert_eq!(
+        effects.files.into_iter().collect::<Vec<_>>(),
+        [
+            ("dir".to_string(), Entry::Directory),
+            (
+                "dir/greeting.txt".to_string(),
+                Entry::File(b"hello".to_vec())
+            ),
+            ("dir/nested".to_string(), Entry::Directory),
+        ]
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn differential_p2_clocks() -> Result<()> {
+    let effects = compare(
+        DIFFERENTIAL_P2_CLOCKS_COMPONENT,
+        DIFFERENTIAL_P3_CLOCKS_COMPONENT,
+    )
+    .await?;
+    assert_eq!(effects.exit, Exit::Code(0));
+    assert_eq!(
+        effects.stdout,
+        "\
+wall clock now: 1700000000.123456789
+wall clock resolution: 1000000ns
+monotonic clock now: 42000000000
+monotonic clock resolution: 1000ns
+waited for zero duration
+waited for 1ms
+waited until a past instant
+"
+    );
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn differential_p2_sockets() -> Result<()> {
+    let effects = compare(
+        DIFFERENTIAL_P2_SOCKETS_COMPONENT,
+        DIFFERENTIAL_P3_SOCKETS_COMPONENT,
+    )
+    .await?;
+    assert_eq!(effects.exit, Exit::Code(0));
+    assert_eq!(
+        effects.stdout,
+        "\
+listener is listening: true
+listener address is loopback: true
+client remote address matches listener: true
+server remote address matches client: true
+server received: \"ping\"
+client received: \"pong\"
+bind to address in use: address-in-use
+connect to port zero: invalid-argument
+"
+    );
+    Ok(())
+}
@@ -103,9 +103,11 @@ impl Artifacts {
                 s if s.starts_with("dwarf_") => "dwarf",
                 s if s.starts_with("config_") => "config",
                 s if s.starts_with("keyvalue_") => "keyvalue",
+                s if s.starts_with("correlation_") => "correlation",
                 s if s.starts_with("async_") => "async",
                 s if s.starts_with("fuzz_") => "fuzz",
                 s if s.starts_with("wizer_") => "wizer",
+                s if s.starts_with("differential_") => "differential",
                 // If you're reading this because you hit this panic, either add
                 // it to a test suite above or add a new "suite". The purpose of
                 // the categorization above is to have a static assertion that
@@ -128,7 +130,7 @@ impl Artifacts {
             }
             let (adapter, mtime) = match test.name.as_str() {
                 "reactor" => &reactor_adapter,
-                s if s.starts_with("p3_") => &reactor_adapter,
+                s if s.starts_with("p3_") || s.starts_with("differential_p3_") => &reactor_adapter,
                 s if s.starts_with("p2_api_proxy") => &proxy_adapter,
                 _ => &command_adapter,
             };
@@ -0,0 +1,7 @@
+use test_programs::wasmtime::correlation::context;
+
+fn main() {
+    let cx = context::current().unwrap();
+    assert_eq!(cx.request_id, 42);
+    assert_eq!(cx.trace_id.as_deref(), Some("4bf92f3577b34da6"));
+}
@@ -0,0 +1,27 @@
+//! The WASIp2 half of the `clocks` differential scenario, which must behave
+//! identically to `differential_p3_clocks`.
+
+use test_programs::wasi::clocks::{monotonic_clock, wall_clock};
+
+fn main() {
+    let now = wall_clock::now();
+    println!("wall clock now: {}.{:09}", now.seconds, now.nanoseconds);
+    let resolution = wall_clock::resolution();
+    println!(
+        "wall clock resolution: {}ns",
+        resolution.seconds * 1_000_000_000 + u64::from(resolution.nanoseconds)
+    );
+
+    println!("monotonic clock now: {}", monotonic_clock::now());
+    println!(
+        "monotonic clock resolution: {}ns",
+        monotonic_clock::resolution()
+    );
+
+    monotonic_clock::subscribe_duration(0).block();
+    println!("waited for zero duration");
+    monotonic_clock::subscribe_duration(1_000_000).block();
+    println!("waited for 1ms");
+    monotonic_clock::subscribe_instant(0).block();
+    println!("waited until a past instant");
+}
@@ -0,0 +1,108 @@
+//! The WASIp2 half of the `filesystem` differential scenario, which must
+//! behave identically to `differential_p3_filesystem`.
+
+use test_programs::wasi::filesystem::preopens;
+use test_programs::wasi::filesystem::types::{
+    Descriptor, DescriptorFlags, DescriptorType, ErrorCode, OpenFlags, PathFlags,
+};
+
+fn main() {
+    let preopens = preopens::get_directories();
+    let (dir, _) = &preopens[0];
+
+    dir.create_directory_at("dir").unwrap();
+    println!(
+        "create existing directory: {}",
+        error(dir.create_directory_at("dir"))
+    );
+
+    let file = open(dir, "dir/hello.txt", OpenFlags::CREATE).unwrap();
+    file.write(b"hello, world\n", 0).unwrap();
+    println!("size after write: {}", file.stat().unwrap().size);
+    let (data, _) = file.read(100, 7).unwrap();
+    println!("read at offset 7: {:?}", String::from_utf8(data).unwrap());
+    file.set_size(5).unwrap();
+    println!("size after truncate: {}", file.stat().unwrap().size);
+    drop(file);
+
+    dir.rename_at("dir/hello.txt", dir, "dir/greeting.txt")
+        .unwrap();
+    println!(
+        "open missing file: {}",
+        error(open(dir, "dir/hello.txt", OpenFlags::empty()))
+    );
+    println!(
+        "create exclusive existing file: {}",
+        error(open(
+            dir,
+            "dir/greeting.txt",
+            OpenFlags::CREATE | OpenFlags::EXCLUSIVE
+        ))
+    );
+
+    let scratch = open(dir, "dir/scratch.txt", OpenFlags::CREATE).unwrap();
+    scratch.write(b"temporary", 0).unwrap();
+    drop(scratch);
+    dir.create_directory_at("dir/nested").unwrap();
+
+    let mut entries = Vec::new();
+    let stream = open_dir(dir, "dir").read_directory().unwrap();
+    while let Some(entry) = stream.read_directory_entry().unwrap() {
+        entries.push((entry.name, kind(entry.type_)));
+    }
+    entries.sort();
+    for (name, kind) in entries {
+        println!("entry: {name} ({kind})");
+    }
+
+    dir.unlink_file_at("dir/scratch.txt").unwrap();
+    println!(
+        "remove non-empty directory: {}",
+        error(dir.remove_directory_at("dir"))
+    );
+    println!(
+        "unlink directory: {}",
+        error(dir.unlink_file_at("dir/nested"))
+    );
+}
+
+fn open(dir: &Descriptor, path: &str, flags: OpenFlags) -> Result<Descriptor, ErrorCode> {
+    dir.open_at(
+        PathFlags::empty(),
+        path,
+        flags,
+        DescriptorFlags::READ | DescriptorFlags::WRITE,
+    )
+}
+
+fn open_dir(dir: &Descriptor, path: &str) -> Descriptor {
+    dir.open_at(
+        PathFlags::empty(),
+        path,
+        OpenFlags::DIRECTORY,
+        DescriptorFlags::READ,
+    )
+    .unwrap()
+}
+
+fn kind(ty: DescriptorType) -> &'static str {
+    match ty {
+        DescriptorType::Directory => "directory",
+        DescriptorType::RegularFile => "regular-file",
+        DescriptorType::SymbolicLink => "symbolic-link",
+        _ => "other",
+    }
+}
+
+fn error<T>(result: Result<T, ErrorCode>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(ErrorCode::Exist) => "exist",
+        Err(ErrorCode::NoEntry) => "no-entry",
+        Err(ErrorCode::NotEmpty) => "not-empty",
+        Err(ErrorCode::IsDirectory) => "is-directory",
+        Err(ErrorCode::NotPermitted) => "not-permitted",
+        Err(ErrorCode::Access) => "access",
+        Err(_) => "other",
+    }
+}
@@ -0,0 +1,70 @@
+//! The WASIp2 half of the `sockets` differential scenario, which must behave
+//! identically to `differential_p3_sockets`.
+
+use test_programs::wasi::sockets::network::{
+    ErrorCode, IpAddressFamily, IpSocketAddress, Ipv4SocketAddress, Network,
+};
+use test_programs::wasi::sockets::tcp::{ShutdownType, TcpSocket};
+
+fn main() {
+    let net = Network::default();
+    let loopback = IpSocketAddress::Ipv4(Ipv4SocketAddress {
+        port: 0,
+        address: (127, 0, 0, 1),
+    });
+
+    let listener = TcpSocket::new(IpAddressFamily::Ipv4).unwrap();
+    listener.blocking_bind(&net, loopback).unwrap();
+    listener.blocking_listen().unwrap();
+    let addr = listener.local_address().unwrap();
+    println!("listener is listening: {}", listener.is_listening());
+    println!(
+        "listener address is loopback: {}",
+        matches!(addr, IpSocketAddress::Ipv4(a) if a.address == (127, 0, 0, 1) && a.port != 0)
+    );
+
+    let client = TcpSocket::new(IpAddressFamily::Ipv4).unwrap();
+    let (client_input, client_output) = client.blocking_connect(&net, addr).unwrap();
+    let (server, server_input, server_output) = listener.blocking_accept().unwrap();
+    println!(
+        "client remote address matches listener: {}",
+        client.remote_address().unwrap() == addr
+    );
+    println!(
+        "server remote address matches client: {}",
+        server.remote_address().unwrap() == client.local_address().unwrap()
+    );
+
+    client_output.blocking_write_and_flush(b"ping").unwrap();
+    client.shutdown(ShutdownType::Send).unwrap();
+    let data = server_input.blocking_read_to_end().unwrap();
+    println!("server received: {:?}", String::from_utf8(data).unwrap());
+
+    server_output.blocking_write_and_flush(b"pong").unwrap();
+    server.shutdown(ShutdownType::Send).unwrap();
+    let data = client_input.blocking_read_to_end().unwrap();
+    println!("client received: {:?}", String::from_utf8(data).unwrap());
+
+    let socket = TcpSocket::new(IpAddressFamily::Ipv4).unwrap();
+    println!(
+        "bind to address in use: {}",
+        error(socket.blocking_bind(&net, addr))
+    );
+    let socket = TcpSocket::new(IpAddressFamily::Ipv4).unwrap();
+    println!(
+        "connect to port zero: {}",
+        error(socket.blocking_connect(&net, loopback))
+    );
+}
+
+fn error<T>(result: Result<T, ErrorCode>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(ErrorCode::AccessDenied) => "access-denied",
+        Err(ErrorCode::InvalidArgument) => "invalid-argument",
+        Err(ErrorCode::InvalidState) => "invalid-state",
+        Err(ErrorCode::AddressInUse) => "address-in-use",
+        Err(ErrorCode::ConnectionRefused) => "connection-refused",
+        Err(_) => "other",
+    }
+}
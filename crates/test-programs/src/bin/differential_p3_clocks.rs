@@ -0,0 +1,52 @@
+//! The WASIp3 half of the `clocks` differential scenario, which must behave
+//! identically to `differential_p2_clocks`.
+
+use std::fmt::Write;
+use test_programs::p3::wasi::clocks::{monotonic_clock, system_clock};
+use test_programs::p3::write_stdout;
+
+struct Component;
+
+test_programs::p3::export!(Component);
+
+impl test_programs::p3::exports::wasi::cli::run::Guest for Component {
+    async fn run() -> Result<(), ()> {
+        let mut out = String::new();
+
+        let now = system_clock::now();
+        writeln!(
+            out,
+            "wall clock now: {}.{:09}",
+            now.seconds, now.nanoseconds
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "wall clock resolution: {}ns",
+            system_clock::get_resolution()
+        )
+        .unwrap();
+
+        writeln!(out, "monotonic clock now: {}", monotonic_clock::now()).unwrap();
+        writeln!(
+            out,
+            "monotonic clock resolution: {}ns",
+            monotonic_clock::get_resolution()
+        )
+        .unwrap();
+
+        monotonic_clock::wait_for(0).await;
+        writeln!(out, "waited for zero duration").unwrap();
+        monotonic_clock::wait_for(1_000_000).await;
+        writeln!(out, "waited for 1ms").unwrap();
+        monotonic_clock::wait_until(0).await;
+        writeln!(out, "waited until a past instant").unwrap();
+
+        write_stdout(out.into_bytes()).await;
+        Ok(())
+    }
+}
+
+fn main() {
+    unreachable!()
+}
@@ -0,0 +1,178 @@
+//! The WASIp3 half of the `filesystem` differential scenario, which must
+//! behave identically to `differential_p2_filesystem`.
+
+use futures::join;
+use std::fmt::Write;
+use test_programs::p3::wasi::filesystem::preopens;
+use test_programs::p3::wasi::filesystem::types::{
+    Descriptor, DescriptorFlags, DescriptorType, ErrorCode, OpenFlags, PathFlags,
+};
+use test_programs::p3::{wit_stream, write_stdout};
+
+struct Component;
+
+test_programs::p3::export!(Component);
+
+impl test_programs::p3::exports::wasi::cli::run::Guest for Component {
+    async fn run() -> Result<(), ()> {
+        let preopens = preopens::get_directories();
+        let (dir, _) = &preopens[0];
+        let mut out = String::new();
+
+        dir.create_directory_at("dir".to_string()).await.unwrap();
+        writeln!(
+            out,
+            "create existing directory: {}",
+            error(dir.create_directory_at("dir".to_string()).await)
+        )
+        .unwrap();
+
+        let file = open(dir, "dir/hello.txt", OpenFlags::CREATE).await.unwrap();
+        write(&file, b"hello, world\n").await;
+        writeln!(out, "size after write: {}", file.stat().await.unwrap().size).unwrap();
+        let (data, result) = file.read_via_stream(7);
+        let data = data.collect().await;
+        result.await.unwrap();
+        writeln!(
+            out,
+            "read at offset 7: {:?}",
+            String::from_utf8(data).unwrap()
+        )
+        .unwrap();
+        file.set_size(5).await.unwrap();
+        writeln!(
+            out,
+            "size after truncate: {}",
+            file.stat().await.unwrap().size
+        )
+        .unwrap();
+        drop(file);
+
+        dir.rename_at(
+            "dir/hello.txt".to_string(),
+            dir,
+            "dir/greeting.txt".to_string(),
+        )
+        .await
+        .unwrap();
+        writeln!(
+            out,
+            "open missing file: {}",
+            error(open(dir, "dir/hello.txt", OpenFlags::empty()).await)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "create exclusive existing file: {}",
+            error(
+                open(
+                    dir,
+                    "dir/greeting.txt",
+                    OpenFlags::CREATE | OpenFlags::EXCLUSIVE
+                )
+                .await
+            )
+        )
+        .unwrap();
+
+        let scratch = open(dir, "dir/scratch.txt", OpenFlags::CREATE)
+            .await
+            .unwrap();
+        write(&scratch, b"temporary").await;
+        drop(scratch);
+        dir.create_directory_at("dir/nested".to_string())
+            .await
+            .unwrap();
+
+        let (stream, result) = open_dir(dir, "dir").await.read_directory();
+        let mut entries = stream
+            .collect()
+            .await
+            .into_iter()
+            .map(|entry| (entry.name, kind(entry.type_)))
+            .collect::<Vec<_>>();
+        result.await.unwrap();
+        entries.sort();
+        for (name, kind) in entries {
+            writeln!(out, "entry: {name} ({kind})").unwrap();
+        }
+
+        dir.unlink_file_at("dir/scratch.txt".to_string())
+            .await
+            .unwrap();
+        writeln!(
+            out,
+            "remove non-empty directory: {}",
+            error(dir.remove_directory_at("dir".to_string()).await)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "unlink directory: {}",
+            error(dir.unlink_file_at("dir/nested".to_string()).await)
+        )
+        .unwrap();
+
+        write_stdout(out.into_bytes()).await;
+        Ok(())
+    }
+}
+
+fn main() {
+    unreachable!()
+}
+
+async fn open(dir: &Descriptor, path: &str, flags: OpenFlags) -> Result<Descriptor, ErrorCode> {
+    dir.open_at(
+        PathFlags::empty(),
+        path.to_string(),
+        flags,
+        DescriptorFlags::READ | DescriptorFlags::WRITE,
+    )
+    .await
+}
+
+async fn open_dir(dir: &Descriptor, path: &str) -> Descriptor {
+    dir.open_at(
+        PathFlags::empty(),
+        path.to_string(),
+        OpenFlags::DIRECTORY,
+        DescriptorFlags::READ,
+    )
+    .await
+    .unwrap()
+}
+
+async fn write(file: &Descriptor, data: &[u8]) {
+    let (mut tx, rx) = wit_stream::new();
+    join!(
+        async { file.write_via_stream(rx, 0).await.unwrap() },
+        async {
+            let remaining = tx.write_all(data.to_vec()).await;
+            assert!(remaining.is_empty());
+            drop(tx);
+        },
+    );
+}
+
+fn kind(ty: DescriptorType) -> &'static str {
+    match ty {
+        DescriptorType::Directory => "directory",
+        DescriptorType::RegularFile => "regular-file",
+        DescriptorType::SymbolicLink => "symbolic-link",
+        _ => "other",
+    }
+}
+
+fn error<T>(result: Result<T, ErrorCode>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(ErrorCode::Exist) => "exist",
+        Err(ErrorCode::NoEntry) => "no-entry",
+        Err(ErrorCode::NotEmpty) => "not-empty",
+        Err(ErrorCode::IsDirectory) => "is-directory",
+        Err(ErrorCode::NotPermitted) => "not-permitted",
+        Err(ErrorCode::Access) => "access",
+        Err(_) => "other",
+    }
+}
@@ -0,0 +1,119 @@
+//! The WASIp3 half of the `sockets` differential scenario, which must behave
+//! identically to `differential_p2_sockets`.
+
+use futures::join;
+use std::fmt::Write;
+use test_programs::p3::wasi::sockets::types::{
+    ErrorCode, IpAddressFamily, IpSocketAddress, Ipv4SocketAddress, TcpSocket,
+};
+use test_programs::p3::{wit_stream, write_stdout};
+
+struct Component;
+
+test_programs::p3::export!(Component);
+
+impl test_programs::p3::exports::wasi::cli::run::Guest for Component {
+    async fn run() -> Result<(), ()> {
+        let mut out = String::new();
+        let loopback = IpSocketAddress::Ipv4(Ipv4SocketAddress {
+            port: 0,
+            address: (127, 0, 0, 1),
+        });
+
+        let listener = TcpSocket::create(IpAddressFamily::Ipv4).unwrap();
+        listener.bind(loopback).unwrap();
+        let mut accept = listener.listen().unwrap();
+        let addr = listener.get_local_address().unwrap();
+        writeln!(
+            out,
+            "listener is listening: {}",
+            listener.get_is_listening()
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "listener address is loopback: {}",
+            matches!(addr, IpSocketAddress::Ipv4(a) if a.address == (127, 0, 0, 1) && a.port != 0)
+        )
+        .unwrap();
+
+        let client = TcpSocket::create(IpAddressFamily::Ipv4).unwrap();
+        let ((), server) = join!(async { client.connect(addr).await.unwrap() }, async {
+            accept.next().await.unwrap()
+        });
+        writeln!(
+            out,
+            "client remote address matches listener: {}",
+            client.get_remote_address().unwrap() == addr
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "server remote address matches client: {}",
+            server.get_remote_address().unwrap() == client.get_local_address().unwrap()
+        )
+        .unwrap();
+
+        let data = transfer(&client, &server, b"ping").await;
+        writeln!(
+            out,
+            "server received: {:?}",
+            String::from_utf8(data).unwrap()
+        )
+        .unwrap();
+        let data = transfer(&server, &client, b"pong").await;
+        writeln!(
+            out,
+            "client received: {:?}",
+            String::from_utf8(data).unwrap()
+        )
+        .unwrap();
+
+        let socket = TcpSocket::create(IpAddressFamily::Ipv4).unwrap();
+        writeln!(out, "bind to address in use: {}", error(socket.bind(addr))).unwrap();
+        let socket = TcpSocket::create(IpAddressFamily::Ipv4).unwrap();
+        writeln!(
+            out,
+            "connect to port zero: {}",
+            error(socket.connect(loopback).await)
+        )
+        .unwrap();
+
+        write_stdout(out.into_bytes()).await;
+        Ok(())
+    }
+}
+
+fn main() {
+    unreachable!()
+}
+
+/// Sends `data` from `from` to `to`, closing the sending half afterwards, and
+/// returns everything `to` received.
+async fn transfer(from: &TcpSocket, to: &TcpSocket, data: &[u8]) -> Vec<u8> {
+    let (mut tx, rx) = wit_stream::new();
+    let (received, result) = to.receive();
+    let ((), (), received) = join!(
+        async { from.send(rx).await.unwrap() },
+        async {
+            let remaining = tx.write_all(data.to_vec()).await;
+            assert!(remaining.is_empty());
+            drop(tx);
+        },
+        received.collect(),
+    );
+    result.await.unwrap();
+    received
+}
+
+fn error<T>(result: Result<T, ErrorCode>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(ErrorCode::AccessDenied) => "access-denied",
+        Err(ErrorCode::InvalidArgument) => "invalid-argument",
+        Err(ErrorCode::InvalidState) => "invalid-state",
+        Err(ErrorCode::AddressInUse) => "address-in-use",
+        Err(ErrorCode::ConnectionRefused) => "connection-refused",
+        Err(_) => "other",
+    }
+}
@@ -0,0 +1,35 @@
+use test_programs::proxy;
+use test_programs::wasi::http::types::{
+    Fields, IncomingRequest, OutgoingBody, OutgoingResponse, ResponseOutparam,
+};
+
+struct T;
+
+proxy::export!(T);
+
+impl proxy::exports::wasi::http::incoming_handler::Guest for T {
+    fn handle(_: IncomingRequest, outparam: ResponseOutparam) {
+        let hints = Fields::from_list(&[(
+            "link".to_string(),
+            b"</style.css>; rel=preload; as=style".to_vec(),
+        )])
+        .expect("hint fields");
+        outparam
+            .send_informational(103, hints)
+            .expect("sending early hints");
+
+        let resp = OutgoingResponse::new(Fields::new());
+        let body = resp.body().expect("outgoing response");
+
+        ResponseOutparam::set(outparam, Ok(resp));
+
+        let out = body.write().expect("outgoing stream");
+        out.blocking_write_and_flush(b"Hello, WASI!")
+            .expect("writing response");
+
+        drop(out);
+        OutgoingBody::finish(body, None).expect("outgoing-body.finish");
+    }
+}
+
+fn main() {}
@@ -126,15 +126,12 @@ pub fn request(
     }
     http_types::OutgoingBody::finish(outgoing_body, None)?;
 
-    let incoming_response = match future_response.get() {
-        Some(result) => result.map_err(|()| anyhow!("response already taken"))?,
-        None => {
-            let pollable = future_response.subscribe();
-            pollable.block();
-            future_response
-                .get()
-                .expect("incoming response available")
-                .map_err(|()| anyhow!("response already taken"))?
+    // The response may not be available yet even once the pollable is ready,
+    // for example while the host follows a redirect.
+    let incoming_response = loop {
+        match future_response.get() {
+            Some(result) => break result.map_err(|()| anyhow!("response already taken"))?,
+            None => future_response.subscribe().block(),
         }
     }?;
 
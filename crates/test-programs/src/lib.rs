@@ -16,6 +16,7 @@ wit_bindgen::generate!({
             include wasi:config/imports@0.2.0-rc.1;
             include wasi:keyvalue/imports@0.2.0-draft;
             include wasi:tls/imports@0.2.0-draft;
+            include wasmtime:correlation/imports;
         }
     ",
     path: [
@@ -23,9 +24,10 @@ wit_bindgen::generate!({
         "../wasi-config/wit",
         "../wasi-keyvalue/wit",
         "../wasi-tls/wit/deps/tls",
+        "../wasi-correlation/wit",
     ],
     world: "wasmtime:test/test",
-    features: ["cli-exit-with-code", "tls"],
+    features: ["cli-exit-with-code", "tls", "informational-outbound-responses"],
     generate_all,
 });
 
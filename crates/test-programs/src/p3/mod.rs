@@ -49,6 +49,19 @@ pub mod service {
     });
 }
 
+/// Writes all of `bytes` to `wasi:cli/stdout`.
+pub async fn write_stdout(bytes: Vec<u8>) {
+    let (mut tx, rx) = wit_stream::new();
+    futures::join!(
+        async { wasi::cli::stdout::write_via_stream(rx).await.unwrap() },
+        async {
+            let remaining = tx.write_all(bytes).await;
+            assert!(remaining.is_empty());
+            drop(tx);
+        },
+    );
+}
+
 impl std::fmt::Display for wasi::tls::types::Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&self.to_debug_string())
@@ -402,10 +402,8 @@ impl Compiler {
 
             Compiler::Winch => {
                 if config.gc()
-                    || config.tail_call()
                     || config.function_references()
                     || config.gc()
-                    || config.relaxed_simd()
                     || config.gc_types()
                     || config.exceptions()
                     || config.legacy_exceptions()
@@ -416,15 +414,13 @@ impl Compiler {
                 }
 
                 if cfg!(target_arch = "aarch64") {
-                    return config.threads();
+                    return config.threads() || config.relaxed_simd();
                 }
 
                 !cfg!(target_arch = "x86_64")
             }
 
-            Compiler::CraneliftPulley => {
-                config.threads() || config.legacy_exceptions() || config.stack_switching()
-            }
+            Compiler::CraneliftPulley => config.legacy_exceptions() || config.stack_switching(),
         }
     }
 
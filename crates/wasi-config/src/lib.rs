@@ -58,6 +58,36 @@
 //! }
 //! ```
 //!
+//! # Hierarchical keys
+//!
+//! Keys may be hierarchical, with segments separated by `.`, such as
+//! `database.pool.size`. [`WasiConfigVariables::get_prefix`] and
+//! [`WasiConfigVariables::list`] can be used to inspect a subtree of keys on
+//! the host, and [`WasiConfig::with_prefix`] exposes only a subtree of keys to
+//! a component, with the prefix removed from their names:
+//!
+//! ```
+//! use wasmtime_wasi_config::{WasiConfig, WasiConfigVariables};
+//!
+//! let mut vars = WasiConfigVariables::new();
+//! vars.insert("database.pool.size", "8")
+//!     .insert("database.pool.timeout", "30s")
+//!     .insert("database.url", "postgres://localhost")
+//!     .insert("log.level", "info");
+//!
+//! assert_eq!(vars.list("database"), ["pool", "url"]);
+//! assert_eq!(vars.get_prefix("database.pool").get("size"), Some("8"));
+//!
+//! // A component using this view sees `pool.size`, `pool.timeout` and `url`.
+//! let view = WasiConfig::new(&vars).with_prefix("database");
+//! # let _ = view;
+//! ```
+//!
+//! With the `toml` and `json` features enabled, whole TOML or JSON documents
+//! can be mounted as a subtree of keys with
+//! [`WasiConfigVariables::mount_toml`] and
+//! [`WasiConfigVariables::mount_json`].
+//!
 //! [wasi-config]: https://github.com/WebAssembly/wasi-config
 //! [wasi:cli]: https://docs.rs/wasmtime-wasi/latest
 //! [wasi:http]: https://docs.rs/wasmtime-wasi-http/latest
@@ -102,29 +132,174 @@ impl WasiConfigVariables {
         self.0.insert(key.into(), value.into());
         self
     }
+
+    /// Returns the value of `key`, if it's present.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|s| s.as_str())
+    }
+
+    /// Returns a new configuration containing all keys nested under `prefix`,
+    /// with `prefix` and its trailing `.` removed from their names.
+    ///
+    /// For example with a `prefix` of `database` the key `database.pool.size`
+    /// is named `pool.size` in the returned configuration. An empty `prefix`
+    /// returns a copy of the whole configuration.
+    pub fn get_prefix(&self, prefix: &str) -> WasiConfigVariables {
+        Self(
+            self.0
+                .iter()
+                .filter_map(|(k, v)| Some((strip_prefix(k, prefix)?.to_string(), v.clone())))
+                .collect(),
+        )
+    }
+
+    /// Returns the sorted names of the segments directly nested under
+    /// `prefix`, without duplicates.
+    ///
+    /// For example with the keys `database.pool.size` and `database.url`, the
+    /// segments under `database` are `pool` and `url`. An empty `prefix` lists
+    /// the top-level segments.
+    pub fn list(&self, prefix: &str) -> Vec<&str> {
+        let mut names = self
+            .0
+            .keys()
+            .filter_map(|k| strip_prefix(k, prefix))
+            .map(|rest| rest.split('.').next().unwrap())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Parses `toml` as a TOML document and inserts all of its values as keys
+    /// nested under `prefix`.
+    ///
+    /// Tables are flattened into hierarchical keys, so `[pool] size = 8`
+    /// mounted at `database` becomes the key `database.pool.size` with the
+    /// value `8`. Elements of arrays are named by their index. Existing keys
+    /// with the same name are overwritten.
+    #[cfg(feature = "toml")]
+    pub fn mount_toml(&mut self, prefix: &str, toml: &str) -> Result<&mut Self> {
+        let table = toml::from_str::<toml::Table>(toml)
+            .map_err(|e| wasmtime::format_err!("failed to parse TOML config: {e}"))?;
+        self.mount_toml_value(prefix.to_string(), toml::Value::Table(table));
+        Ok(self)
+    }
+
+    #[cfg(feature = "toml")]
+    fn mount_toml_value(&mut self, key: String, value: toml::Value) {
+        match value {
+            toml::Value::Table(table) => {
+                for (k, v) in table {
+                    self.mount_toml_value(join_key(&key, &k), v);
+                }
+            }
+            toml::Value::Array(array) => {
+                for (i, v) in array.into_iter().enumerate() {
+                    self.mount_toml_value(join_key(&key, &i.to_string()), v);
+                }
+            }
+            toml::Value::String(s) => {
+                self.0.insert(key, s);
+            }
+            other => {
+                self.0.insert(key, other.to_string());
+            }
+        }
+    }
+
+    /// Parses `json` as a JSON document and inserts all of its values as keys
+    /// nested under `prefix`.
+    ///
+    /// Objects are flattened into hierarchical keys, so `{"pool": {"size": 8}}`
+    /// mounted at `database` becomes the key `database.pool.size` with the
+    /// value `8`. Elements of arrays are named by their index and `null`
+    /// values are skipped. Existing keys with the same name are overwritten.
+    #[cfg(feature = "json")]
+    pub fn mount_json(&mut self, prefix: &str, json: &str) -> Result<&mut Self> {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|e| wasmtime::format_err!("failed to parse JSON config: {e}"))?;
+        self.mount_json_value(prefix.to_string(), value);
+        Ok(self)
+    }
+
+    #[cfg(feature = "json")]
+    fn mount_json_value(&mut self, key: String, value: serde_json::Value) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (k, v) in object {
+                    self.mount_json_value(join_key(&key, &k), v);
+                }
+            }
+            serde_json::Value::Array(array) => {
+                for (i, v) in array.into_iter().enumerate() {
+                    self.mount_json_value(join_key(&key, &i.to_string()), v);
+                }
+            }
+            serde_json::Value::Null => {}
+            serde_json::Value::String(s) => {
+                self.0.insert(key, s);
+            }
+            other => {
+                self.0.insert(key, other.to_string());
+            }
+        }
+    }
+}
+
+/// Returns the rest of `key` if it's nested under `prefix`.
+fn strip_prefix<'a>(key: &'a str, prefix: &str) -> Option<&'a str> {
+    if prefix.is_empty() {
+        return Some(key);
+    }
+    key.strip_prefix(prefix)?.strip_prefix('.')
+}
+
+#[cfg(any(feature = "toml", feature = "json"))]
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
 }
 
 /// A wrapper capturing the needed internal `wasi-config` state.
 pub struct WasiConfig<'a> {
     vars: &'a WasiConfigVariables,
+    prefix: &'a str,
 }
 
 impl<'a> From<&'a WasiConfigVariables> for WasiConfig<'a> {
     fn from(vars: &'a WasiConfigVariables) -> Self {
-        Self { vars }
+        Self::new(vars)
     }
 }
 
 impl<'a> WasiConfig<'a> {
     /// Create a new view into the `wasi-config` state.
     pub fn new(vars: &'a WasiConfigVariables) -> Self {
-        Self { vars }
+        Self { vars, prefix: "" }
+    }
+
+    /// Restricts this view to the keys nested under `prefix`.
+    ///
+    /// A component using the returned view only sees keys nested under
+    /// `prefix`, with `prefix` and its trailing `.` removed from their names,
+    /// as with [`WasiConfigVariables::get_prefix`].
+    pub fn with_prefix(self, prefix: &'a str) -> Self {
+        Self { prefix, ..self }
     }
 }
 
 impl generated::Host for WasiConfig<'_> {
     fn get(&mut self, key: String) -> Result<Result<Option<String>, generated::Error>> {
-        Ok(Ok(self.vars.0.get(&key).map(|s| s.to_owned())))
+        let value = if self.prefix.is_empty() {
+            self.vars.get(&key)
+        } else {
+            self.vars.get(&format!("{}.{key}", self.prefix))
+        };
+        Ok(Ok(value.map(|s| s.to_owned())))
     }
 
     fn get_all(&mut self) -> Result<Result<Vec<(String, String)>, generated::Error>> {
@@ -132,7 +307,7 @@ impl generated::Host for WasiConfig<'_> {
             .vars
             .0
             .iter()
-            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .filter_map(|(k, v)| Some((strip_prefix(k, self.prefix)?.to_string(), v.to_string())))
             .collect()))
     }
 }
@@ -63,3 +63,65 @@ async fn config_get() -> Result<()> {
     )
     .await
 }
+
+#[test]
+fn hierarchical_keys() {
+    let mut vars = WasiConfigVariables::new();
+    vars.insert("database.pool.size", "8")
+        .insert("database.pool.timeout", "30s")
+        .insert("database.url", "postgres://localhost")
+        .insert("databases", "2")
+        .insert("log.level", "info");
+
+    assert_eq!(vars.list(""), ["database", "databases", "log"]);
+    assert_eq!(vars.list("database"), ["pool", "url"]);
+    assert_eq!(vars.list("database.pool"), ["size", "timeout"]);
+    assert!(vars.list("missing").is_empty());
+
+    let pool = vars.get_prefix("database.pool");
+    assert_eq!(pool.get("size"), Some("8"));
+    assert_eq!(pool.get("timeout"), Some("30s"));
+    assert_eq!(pool.list(""), ["size", "timeout"]);
+    assert_eq!(vars.get_prefix("database").get("pool.size"), Some("8"));
+    assert_eq!(vars.get_prefix("").get("log.level"), Some("info"));
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn mount_toml() -> Result<()> {
+    let mut vars = WasiConfigVariables::new();
+    vars.mount_toml(
+        "app",
+        r#"
+            name = "server"
+            ports = [80, 443]
+
+            [database.pool]
+            size = 8
+            enabled = true
+        "#,
+    )?;
+    assert_eq!(vars.get("app.name"), Some("server"));
+    assert_eq!(vars.get("app.ports.0"), Some("80"));
+    assert_eq!(vars.get("app.ports.1"), Some("443"));
+    assert_eq!(vars.get("app.database.pool.size"), Some("8"));
+    assert_eq!(vars.get("app.database.pool.enabled"), Some("true"));
+    assert!(vars.mount_toml("", "not toml").is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn mount_json() -> Result<()> {
+    let mut vars = WasiConfigVariables::new();
+    vars.mount_json(
+        "",
+        r#"{"database": {"pool": {"size": 8}, "hosts": ["a", "b"], "password": null}}"#,
+    )?;
+    assert_eq!(vars.get("database.pool.size"), Some("8"));
+    assert_eq!(vars.get("database.hosts.0"), Some("a"));
+    assert_eq!(vars.get("database.hosts.1"), Some("b"));
+    assert_eq!(vars.get("database.password"), None);
+    assert!(vars.mount_json("", "{").is_err());
+    Ok(())
+}
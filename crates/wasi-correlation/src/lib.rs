@@ -0,0 +1,245 @@
+//! # Wasmtime's request correlation context
+//!
+//! This crate provides a Wasmtime-specific host interface,
+//! `wasmtime:correlation/context`, which lets components read the correlation
+//! context of the call they're currently handling: a host-assigned, monotonic
+//! request ID and an optional distributed trace ID.
+//!
+//! The context is set by the host through a [`Correlation`] handle. The same
+//! handle can be given to other host implementations so the context is
+//! propagated without any glue in the guest: `wasmtime-wasi-http`, for
+//! example, attaches it to outgoing requests as the [`REQUEST_ID_HEADER`] and
+//! [`TRACE_ID_HEADER`] headers when its `correlation` feature is enabled, and
+//! [`Correlation::span`] creates a [`tracing`] span carrying the context for
+//! host-side work done on behalf of the call.
+//!
+//! # Examples
+//!
+//! ```
+//! use wasmtime::{
+//!     component::{Linker, ResourceTable},
+//!     Engine, Result, Store,
+//! };
+//! use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+//! use wasmtime_wasi_correlation::{
+//!     Correlation, CorrelationContext, RequestIdGenerator, WasiCorrelation,
+//! };
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let engine = Engine::default();
+//!     let request_ids = RequestIdGenerator::new();
+//!
+//!     let mut store = Store::new(&engine, Ctx {
+//!         table: ResourceTable::new(),
+//!         wasi_ctx: WasiCtx::builder().build(),
+//!         correlation: Correlation::new(),
+//!     });
+//!
+//!     let mut linker = Linker::<Ctx>::new(&engine);
+//!     wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+//!     wasmtime_wasi_correlation::add_to_linker(&mut linker, |h: &mut Ctx| {
+//!         WasiCorrelation::from(&h.correlation)
+//!     })?;
+//!
+//!     // ... use `linker` to instantiate within `store` ...
+//!
+//!     // Before each call into the guest, set the context of that call.
+//!     store.data().correlation.set(
+//!         CorrelationContext::new(request_ids.next_id()).with_trace_id("4bf92f3577b34da6"),
+//!     );
+//!
+//!     // ... call the guest ...
+//!
+//!     Ok(())
+//! }
+//!
+//! struct Ctx {
+//!     table: ResourceTable,
+//!     wasi_ctx: WasiCtx,
+//!     correlation: Correlation,
+//! }
+//!
+//! impl WasiView for Ctx {
+//!     fn ctx(&mut self) -> WasiCtxView<'_> {
+//!         WasiCtxView { ctx: &mut self.wasi_ctx, table: &mut self.table }
+//!     }
+//! }
+//! ```
+
+#![deny(missing_docs)]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use wasmtime::Result;
+use wasmtime::component::HasData;
+
+// The generated `wasmtime` module for the `wasmtime:correlation` package
+// shadows the crate within `gen_`, so refer to the crate by its absolute path.
+mod gen_ {
+    ::wasmtime::component::bindgen!({
+        path: "wit",
+        world: "wasmtime:correlation/imports",
+        imports: { default: trappable },
+        wasmtime_crate: ::wasmtime,
+    });
+}
+use self::gen_::wasmtime::correlation::context as generated;
+
+/// Name of the header carrying the request ID of a [`CorrelationContext`].
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Name of the header carrying the trace ID of a [`CorrelationContext`].
+pub const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// Identifiers which correlate the work done by a component with the request
+/// which caused it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorrelationContext {
+    /// Host-assigned identifier of the request, typically produced by a
+    /// [`RequestIdGenerator`].
+    pub request_id: u64,
+    /// Identifier of the distributed trace the request is part of, if any.
+    pub trace_id: Option<String>,
+}
+
+impl CorrelationContext {
+    /// Creates a new context for the request `request_id` which isn't part
+    /// of a trace.
+    pub fn new(request_id: u64) -> Self {
+        Self {
+            request_id,
+            trace_id: None,
+        }
+    }
+
+    /// Sets the trace ID of this context.
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Returns the headers which propagate this context to other services,
+    /// as pairs of header names and values.
+    pub fn headers(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        [
+            Some((REQUEST_ID_HEADER, self.request_id.to_string())),
+            self.trace_id.clone().map(|id| (TRACE_ID_HEADER, id)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Creates a [`tracing`] span recording this context.
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "request",
+            request_id = self.request_id,
+            trace_id = self.trace_id.as_deref(),
+        )
+    }
+}
+
+/// Generator of unique, monotonically increasing request IDs.
+#[derive(Debug, Default)]
+pub struct RequestIdGenerator {
+    next: AtomicU64,
+}
+
+impl RequestIdGenerator {
+    /// Creates a new generator whose first ID is 0.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the next request ID.
+    pub fn next_id(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// A shared handle to the [`CorrelationContext`] of the call currently being
+/// handled.
+///
+/// Clones of a handle all refer to the same context, so a single handle can be
+/// given to every host implementation that should observe it, and the host
+/// only needs to [`set`](Correlation::set) the context once per call.
+#[derive(Clone, Debug, Default)]
+pub struct Correlation(Arc<RwLock<Option<CorrelationContext>>>);
+
+impl Correlation {
+    /// Creates a new handle with no context set.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the context of the call about to be handled.
+    pub fn set(&self, cx: CorrelationContext) {
+        *self.0.write().unwrap() = Some(cx);
+    }
+
+    /// Clears the context, for example once a call has completed.
+    pub fn clear(&self) {
+        *self.0.write().unwrap() = None;
+    }
+
+    /// Returns the current context, if any.
+    pub fn get(&self) -> Option<CorrelationContext> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Creates a [`tracing`] span recording the current context, or a
+    /// disabled span if no context is set.
+    pub fn span(&self) -> tracing::Span {
+        match &*self.0.read().unwrap() {
+            Some(cx) => cx.span(),
+            None => tracing::Span::none(),
+        }
+    }
+}
+
+/// A wrapper capturing the needed internal `wasmtime:correlation` state.
+pub struct WasiCorrelation<'a> {
+    correlation: &'a Correlation,
+}
+
+impl<'a> From<&'a Correlation> for WasiCorrelation<'a> {
+    fn from(correlation: &'a Correlation) -> Self {
+        Self { correlation }
+    }
+}
+
+impl<'a> WasiCorrelation<'a> {
+    /// Create a new view into the `wasmtime:correlation` state.
+    pub fn new(correlation: &'a Correlation) -> Self {
+        Self { correlation }
+    }
+}
+
+impl generated::Host for WasiCorrelation<'_> {
+    fn current(&mut self) -> Result<Option<generated::CorrelationContext>> {
+        Ok(self
+            .correlation
+            .get()
+            .map(|cx| generated::CorrelationContext {
+                request_id: cx.request_id,
+                trace_id: cx.trace_id,
+            }))
+    }
+}
+
+/// Add all the `wasmtime:correlation` world's interfaces to a
+/// [`wasmtime::component::Linker`].
+pub fn add_to_linker<T: 'static>(
+    l: &mut wasmtime::component::Linker<T>,
+    f: fn(&mut T) -> WasiCorrelation<'_>,
+) -> Result<()> {
+    generated::add_to_linker::<T, HasWasiCorrelation>(l, f)?;
+    Ok(())
+}
+
+struct HasWasiCorrelation;
+
+impl HasData for HasWasiCorrelation {
+    type Data<'a> = WasiCorrelation<'a>;
+}
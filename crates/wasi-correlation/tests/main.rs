@@ -0,0 +1,82 @@
+use test_programs_artifacts::{CORRELATION_CURRENT_COMPONENT, foreach_correlation};
+use wasmtime::{
+    Result, Store,
+    component::{Component, Linker, ResourceTable},
+    format_err,
+};
+use wasmtime_wasi::p2::{add_to_linker_async, bindings::Command};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
+use wasmtime_wasi_correlation::{Correlation, CorrelationContext, WasiCorrelation};
+
+struct Ctx {
+    table: ResourceTable,
+    wasi_ctx: WasiCtx,
+    correlation: Correlation,
+}
+
+impl WasiView for Ctx {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView {
+            ctx: &mut self.wasi_ctx,
+            table: &mut self.table,
+        }
+    }
+}
+
+async fn run_wasi(path: &str, ctx: Ctx) -> Result<()> {
+    let engine = test_programs_artifacts::engine(|_config| {});
+    let mut store = Store::new(&engine, ctx);
+    let component = Component::from_file(&engine, path)?;
+
+    let mut linker = Linker::new(&engine);
+    add_to_linker_async(&mut linker)?;
+    wasmtime_wasi_correlation::add_to_linker(&mut linker, |h: &mut Ctx| {
+        WasiCorrelation::from(&h.correlation)
+    })?;
+
+    let command = Command::instantiate_async(&mut store, &component, &linker).await?;
+    command
+        .wasi_cli_run()
+        .call_run(&mut store)
+        .await?
+        .map_err(|()| format_err!("command returned with failing exit status"))
+}
+
+macro_rules! assert_test_exists {
+    ($name:ident) => {
+        #[expect(unused_imports, reason = "only here to ensure name exists")]
+        use self::$name as _;
+    };
+}
+
+foreach_correlation!(assert_test_exists);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn correlation_current() -> Result<()> {
+    let correlation = Correlation::new();
+    correlation.set(CorrelationContext::new(42).with_trace_id("4bf92f3577b34da6"));
+    run_wasi(
+        CORRELATION_CURRENT_COMPONENT,
+        Ctx {
+            table: ResourceTable::new(),
+            wasi_ctx: WasiCtxBuilder::new().build(),
+            correlation,
+        },
+    )
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn correlation_current_unset() -> Result<()> {
+    let result = run_wasi(
+        CORRELATION_CURRENT_COMPONENT,
+        Ctx {
+            table: ResourceTable::new(),
+            wasi_ctx: WasiCtxBuilder::new().build(),
+            correlation: Correlation::new(),
+        },
+    )
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
@@ -0,0 +1,646 @@
+//! Caching of the responses to the outgoing requests of guests.
+//!
+//! A [`ResponseCache`] is configured per [`WasiHttpCtx`](crate::WasiHttpCtx)
+//! with [`set_response_cache`](crate::WasiHttpCtx::set_response_cache).
+//! Requests are looked up in the cache with [`lookup_cached_response`] before
+//! they're sent, and the responses to those which miss are stored as their
+//! bodies are read, subject to the `cache-control` directives of both.
+
+use crate::{Error, WasiBody};
+use bytes::{Bytes, BytesMut};
+use http::header::{AGE, AUTHORIZATION, CACHE_CONTROL, PRAGMA, VARY};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::{BodyExt as _, Full};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, Instant};
+
+/// Storage for the responses to the outgoing requests of guests, configured
+/// with [`WasiHttpCtx::set_response_cache`](crate::WasiHttpCtx::set_response_cache).
+///
+/// Implementations only need to store and retrieve responses; which requests
+/// are looked up, which responses are stored, and whether stored responses
+/// are still fresh is decided by this crate following the `cache-control`
+/// directives of requests and responses. [`InMemoryResponseCache`] is an
+/// implementation which keeps responses in memory.
+///
+/// Only `GET` requests without an `authorization` header are cached, and only
+/// responses with an explicit freshness lifetime, from a `max-age` or
+/// `s-maxage` directive, are stored. Note that the same cache can be shared by
+/// multiple contexts, in which case responses received by one guest may be
+/// returned to another, so caches shouldn't be shared by contexts signing
+/// their requests with different credentials.
+pub trait ResponseCache: Send + Sync + 'static {
+    /// Returns the response stored for `key`, if any.
+    ///
+    /// `key` identifies the requested resource and is currently the absolute
+    /// URI of the request. The returned response may no longer be fresh, in
+    /// which case it's not used.
+    fn get(&self, key: &str) -> Option<Arc<CachedResponse>>;
+
+    /// Stores `response` for `key`, replacing any response already stored.
+    fn insert(&self, key: String, response: Arc<CachedResponse>);
+
+    /// Returns the maximum [`CachedResponse::size`] of responses which are
+    /// stored in this cache.
+    ///
+    /// Responses are buffered in memory while their body is read until they
+    /// can be stored, so this bounds the memory used by each response in
+    /// flight. There's no limit by default.
+    fn max_response_size(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// A response stored in a [`ResponseCache`].
+pub struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+    received: Instant,
+    age: Duration,
+    lifetime: Duration,
+}
+
+impl CachedResponse {
+    /// Returns the status of this response.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the headers of this response.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the body of this response.
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Returns the approximate number of bytes used by this response, that is
+    /// the size of its body and headers.
+    pub fn size(&self) -> usize {
+        self.body.len() + headers_size(&self.headers)
+    }
+
+    /// Returns whether this response is still fresh and may be returned for
+    /// requests without being revalidated.
+    pub fn is_fresh(&self) -> bool {
+        self.current_age() < self.lifetime
+    }
+
+    fn current_age(&self) -> Duration {
+        self.age + self.received.elapsed()
+    }
+
+    /// Returns whether the headers of `request` select this response, as
+    /// determined by the `vary` header of the response.
+    fn matches(&self, request: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| request.get(name) == value.as_ref())
+    }
+
+    fn to_response(&self) -> http::Response<WasiBody> {
+        let mut response = http::Response::new(
+            Full::new(self.body.clone())
+                .map_err(|e| match e {})
+                .boxed_unsync(),
+        );
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+            .headers_mut()
+            .insert(AGE, self.current_age().as_secs().into());
+        response
+    }
+}
+
+impl fmt::Debug for CachedResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .field("body", &self.body.len())
+            .field("age", &self.current_age())
+            .field("lifetime", &self.lifetime)
+            .finish()
+    }
+}
+
+fn headers_size(headers: &HeaderMap) -> usize {
+    headers
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum()
+}
+
+/// A [`ResponseCache`] which keeps responses in memory, evicting the least
+/// recently used ones once its capacity is exceeded.
+pub struct InMemoryResponseCache {
+    capacity: usize,
+    inner: Mutex<LruMap>,
+}
+
+#[derive(Default)]
+struct LruMap {
+    entries: HashMap<String, (Arc<CachedResponse>, u64)>,
+    recency: BTreeMap<u64, String>,
+    next_use: u64,
+    size: usize,
+}
+
+impl InMemoryResponseCache {
+    /// Creates a new cache storing up to `capacity` bytes of responses, as
+    /// measured by [`CachedResponse::size`].
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Returns the number of responses currently stored.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether no responses are currently stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all stored responses.
+    pub fn clear(&self) {
+        *self.inner.lock().unwrap() = LruMap::default();
+    }
+}
+
+impl LruMap {
+    fn touch(&mut self, key: &str) -> Option<Arc<CachedResponse>> {
+        let use_ = self.next_use;
+        let (response, last_use) = self.entries.get_mut(key)?;
+        let key = self.recency.remove(last_use).unwrap();
+        *last_use = use_;
+        self.recency.insert(use_, key);
+        self.next_use += 1;
+        Some(response.clone())
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some((response, last_use)) = self.entries.remove(key) {
+            self.recency.remove(&last_use);
+            self.size -= response.size();
+        }
+    }
+}
+
+impl ResponseCache for InMemoryResponseCache {
+    fn get(&self, key: &str) -> Option<Arc<CachedResponse>> {
+        self.inner.lock().unwrap().touch(key)
+    }
+
+    fn insert(&self, key: String, response: Arc<CachedResponse>) {
+        let size = response.size();
+        if size > self.capacity {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.remove(&key);
+        while inner.size + size > self.capacity {
+            let (_, oldest) = inner.recency.pop_first().unwrap();
+            let (response, _) = inner.entries.remove(&oldest).unwrap();
+            inner.size -= response.size();
+        }
+        let use_ = inner.next_use;
+        inner.next_use += 1;
+        inner.size += size;
+        inner.recency.insert(use_, key.clone());
+        inner.entries.insert(key, (response, use_));
+    }
+
+    fn max_response_size(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl fmt::Debug for InMemoryResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock().unwrap();
+        f.debug_struct("InMemoryResponseCache")
+            .field("capacity", &self.capacity)
+            .field("size", &inner.size)
+            .field("len", &inner.entries.len())
+            .finish()
+    }
+}
+
+/// Extension attached to outgoing requests which are looked up in a cache.
+#[derive(Clone)]
+pub(crate) struct PendingLookup(pub(crate) Arc<dyn ResponseCache>);
+
+/// The result of [`lookup_cached_response`].
+pub enum CacheLookup {
+    /// A fresh response to the request was found in the cache and should be
+    /// returned in place of sending the request.
+    Hit(http::Response<WasiBody>),
+    /// No fresh response was found. The request should be sent and its
+    /// response passed to [`CacheInsert::response`].
+    Miss(http::Request<WasiBody>, CacheInsert),
+}
+
+/// Looks up `request` in the [`ResponseCache`] configured for the
+/// [`WasiHttpCtx`](crate::WasiHttpCtx) it originates from, if any.
+///
+/// This is called by the default implementation of
+/// [`WasiHttpHooks::send_request`](crate::WasiHttpHooks::send_request) and
+/// should be called by custom implementations before
+/// [`sign_request`](crate::sign_request) to make use of the configured cache.
+/// Requests for which no cache is configured, or which can't be cached,
+/// always miss.
+pub fn lookup_cached_response(mut request: http::Request<WasiBody>) -> CacheLookup {
+    let Some(PendingLookup(cache)) = request.extensions_mut().remove::<PendingLookup>() else {
+        return CacheLookup::Miss(request, CacheInsert(None));
+    };
+    if request.method() != Method::GET || request.headers().contains_key(AUTHORIZATION) {
+        return CacheLookup::Miss(request, CacheInsert(None));
+    }
+    let directives = CacheControl::parse(request.headers());
+    if directives.no_store {
+        return CacheLookup::Miss(request, CacheInsert(None));
+    }
+
+    let key = request.uri().to_string();
+    if !directives.no_cache && directives.max_age != Some(0) {
+        if let Some(cached) = cache.get(&key) {
+            if cached.is_fresh() && cached.matches(request.headers()) {
+                return CacheLookup::Hit(cached.to_response());
+            }
+        }
+    }
+    let insert = CacheInsert(Some(Box::new(PendingInsert {
+        cache,
+        key,
+        request_headers: request.headers().clone(),
+    })));
+    CacheLookup::Miss(request, insert)
+}
+
+/// Stores the response to a request which missed in a [`ResponseCache`],
+/// returned by [`lookup_cached_response`].
+pub struct CacheInsert(Option<Box<PendingInsert>>);
+
+struct PendingInsert {
+    cache: Arc<dyn ResponseCache>,
+    key: String,
+    request_headers: HeaderMap,
+}
+
+impl CacheInsert {
+    /// Arranges for `response`, received for the request which missed, to be
+    /// stored in the cache if it's cacheable, returning the response to use in
+    /// its place.
+    ///
+    /// The response is stored once its body has been read to the end, so the
+    /// body of the returned response still streams from the original
+    /// response.
+    pub fn response(self, response: http::Response<WasiBody>) -> http::Response<WasiBody> {
+        let Some(pending) = self.0 else {
+            return response;
+        };
+        let Some(lifetime) = freshness_lifetime(&response) else {
+            return response;
+        };
+        let mut vary = Vec::new();
+        for value in response.headers().get_all(VARY) {
+            let Ok(value) = value.to_str() else {
+                return response;
+            };
+            for name in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let Ok(name) = HeaderName::try_from(name) else {
+                    return response;
+                };
+                let value = pending.request_headers.get(&name).cloned();
+                vary.push((name, value));
+            }
+        }
+        let age = response
+            .headers()
+            .get(AGE)
+            .and_then(|v| v.to_str().ok()?.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+
+        let entry = CachedResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: Bytes::new(),
+            vary,
+            received: Instant::now(),
+            age,
+            lifetime,
+        };
+        if entry.size() > pending.cache.max_response_size() {
+            return response;
+        }
+        response.map(|body| {
+            CachingBody {
+                body,
+                buffer: Some(BytesMut::new()),
+                entry: Some((pending, entry)),
+            }
+            .boxed_unsync()
+        })
+    }
+}
+
+/// Returns how long `response` may be cached for, if it may be cached.
+fn freshness_lifetime(response: &http::Response<WasiBody>) -> Option<Duration> {
+    if !is_cacheable_status(response.status()) {
+        return None;
+    }
+    let directives = CacheControl::parse(response.headers());
+    if directives.no_store || directives.no_cache || directives.private {
+        return None;
+    }
+    if response.headers().get_all(VARY).iter().any(|v| {
+        v.to_str()
+            .is_ok_and(|v| v.split(',').any(|s| s.trim() == "*"))
+    }) {
+        return None;
+    }
+    let lifetime = directives.s_maxage.or(directives.max_age)?;
+    (lifetime > 0).then(|| Duration::from_secs(lifetime))
+}
+
+/// Whether responses with `status` can be stored, as the statuses which are
+/// "heuristically cacheable" per RFC 9110 other than partial content.
+fn is_cacheable_status(status: StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+/// The `cache-control` directives relevant to caching responses.
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    fn parse(headers: &HeaderMap) -> Self {
+        let mut ret = CacheControl::default();
+        for value in headers.get_all(CACHE_CONTROL) {
+            let Ok(value) = value.to_str() else {
+                // Directives which can't be understood prevent caching.
+                ret.no_store = true;
+                continue;
+            };
+            for directive in value.split(',') {
+                let (name, arg) = match directive.split_once('=') {
+                    Some((name, arg)) => (name.trim(), Some(arg.trim().trim_matches('"'))),
+                    None => (directive.trim(), None),
+                };
+                let seconds = || arg.and_then(|a| a.parse().ok()).unwrap_or(0);
+                match name.to_ascii_lowercase().as_str() {
+                    "no-store" => ret.no_store = true,
+                    "no-cache" => ret.no_cache = true,
+                    "private" => ret.private = true,
+                    "max-age" => ret.max_age = Some(seconds()),
+                    "s-maxage" => ret.s_maxage = Some(seconds()),
+                    _ => {}
+                }
+            }
+        }
+        if !headers.contains_key(CACHE_CONTROL)
+            && headers
+                .get_all(PRAGMA)
+                .iter()
+                .any(|v| v.as_bytes().eq_ignore_ascii_case(b"no-cache"))
+        {
+            ret.no_cache = true;
+        }
+        ret
+    }
+}
+
+/// A body which buffers the data read from it to store the response it
+/// belongs to in a cache once it has been read to the end.
+struct CachingBody {
+    body: WasiBody,
+    buffer: Option<BytesMut>,
+    entry: Option<(Box<PendingInsert>, CachedResponse)>,
+}
+
+impl CachingBody {
+    fn abandon(&mut self) {
+        self.buffer = None;
+        self.entry = None;
+    }
+}
+
+impl Body for CachingBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Error>>> {
+        let frame = ready!(Pin::new(&mut self.body).poll_frame(cx));
+        match &frame {
+            Some(Ok(frame)) => match frame.data_ref() {
+                Some(data) => {
+                    let this = &mut *self;
+                    if let (Some(buffer), Some((pending, entry))) = (&mut this.buffer, &this.entry)
+                    {
+                        if entry.size() + buffer.len() + data.len()
+                            > pending.cache.max_response_size()
+                        {
+                            this.abandon();
+                        } else {
+                            buffer.extend_from_slice(data);
+                        }
+                    }
+                }
+                // Responses with trailers aren't stored.
+                None => self.abandon(),
+            },
+            Some(Err(_)) => self.abandon(),
+            None => {
+                if let (Some(buffer), Some((pending, mut entry))) =
+                    (self.buffer.take(), self.entry.take())
+                {
+                    entry.body = buffer.freeze();
+                    pending.cache.insert(pending.key, Arc::new(entry));
+                }
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // Report the end of the stream only once it has been observed by
+        // `poll_frame`, so the response is stored.
+        self.entry.is_none() && self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(data: &'static str) -> WasiBody {
+        Full::new(Bytes::from_static(data.as_bytes()))
+            .map_err(|e| match e {})
+            .boxed_unsync()
+    }
+
+    fn request(
+        cache: &Arc<InMemoryResponseCache>,
+        headers: &[(&str, &str)],
+    ) -> http::Request<WasiBody> {
+        let mut request = http::Request::get("http://example.com/a")
+            .body(body(""))
+            .unwrap();
+        for (name, value) in headers {
+            request.headers_mut().append(
+                HeaderName::try_from(*name).unwrap(),
+                HeaderValue::try_from(*value).unwrap(),
+            );
+        }
+        request
+            .extensions_mut()
+            .insert(PendingLookup(cache.clone()));
+        request
+    }
+
+    fn response(headers: &[(&str, &str)], data: &'static str) -> http::Response<WasiBody> {
+        let mut response = http::Response::new(body(data));
+        for (name, value) in headers {
+            response.headers_mut().append(
+                HeaderName::try_from(*name).unwrap(),
+                HeaderValue::try_from(*value).unwrap(),
+            );
+        }
+        response
+    }
+
+    /// Sends `request` through the cache, using `response` on a miss, and
+    /// returns whether it hit and the body of the response.
+    async fn send(
+        request: http::Request<WasiBody>,
+        response: http::Response<WasiBody>,
+    ) -> (bool, Bytes) {
+        let (hit, response) = match lookup_cached_response(request) {
+            CacheLookup::Hit(response) => (true, response),
+            CacheLookup::Miss(_, insert) => (false, insert.response(response)),
+        };
+        (
+            hit,
+            response.into_body().collect().await.unwrap().to_bytes(),
+        )
+    }
+
+    #[tokio::test]
+    async fn stores_fresh_responses() {
+        let cache = Arc::new(InMemoryResponseCache::new(1 << 20));
+        let fresh = &[("cache-control", "public, max-age=60")];
+        let (hit, body) = send(request(&cache, &[]), response(fresh, "one")).await;
+        assert!(!hit);
+        assert_eq!(body, "one");
+        assert_eq!(cache.len(), 1);
+
+        let (hit, body) = send(request(&cache, &[]), response(fresh, "two")).await;
+        assert!(hit);
+        assert_eq!(body, "one");
+
+        // Requests may ask to bypass or skip the cache.
+        let no_cache = &[("cache-control", "no-cache")];
+        let (hit, body) = send(request(&cache, no_cache), response(fresh, "three")).await;
+        assert!(!hit);
+        assert_eq!(body, "three");
+        let no_store = &[("cache-control", "no-store")];
+        let (hit, _) = send(request(&cache, no_store), response(fresh, "four")).await;
+        assert!(!hit);
+        let (hit, body) = send(request(&cache, &[]), response(fresh, "five")).await;
+        assert!(hit);
+        assert_eq!(body, "three");
+
+        let auth = &[("authorization", "secret")];
+        let (hit, _) = send(request(&cache, auth), response(fresh, "six")).await;
+        assert!(!hit);
+    }
+
+    #[tokio::test]
+    async fn respects_response_directives() {
+        for headers in [
+            &[][..],
+            &[("cache-control", "max-age=0")],
+            &[("cache-control", "no-store, max-age=60")],
+            &[("cache-control", "private, max-age=60")],
+            &[("cache-control", "max-age=60"), ("vary", "*")],
+            &[("cache-control", "max-age=60"), ("age", "60")],
+        ] {
+            let cache = Arc::new(InMemoryResponseCache::new(1 << 20));
+            send(request(&cache, &[]), response(headers, "a")).await;
+            let (hit, _) = send(request(&cache, &[]), response(headers, "b")).await;
+            assert!(!hit, "{headers:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn vary() {
+        let cache = Arc::new(InMemoryResponseCache::new(1 << 20));
+        let headers = &[("cache-control", "max-age=60"), ("vary", "accept")];
+        let json = &[("accept", "application/json")];
+        send(request(&cache, json), response(headers, "json")).await;
+        let (hit, body) = send(request(&cache, json), response(headers, "x")).await;
+        assert!(hit);
+        assert_eq!(body, "json");
+        let (hit, _) = send(request(&cache, &[]), response(headers, "x")).await;
+        assert!(!hit);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        let fresh = &[("cache-control", "max-age=60")];
+        // The size of each response below, with its headers and a 10-byte body.
+        let size = headers_size(response(fresh, "").headers()) + 10;
+        let cache = Arc::new(InMemoryResponseCache::new(2 * size));
+        let get = |path: &'static str| {
+            let mut request = request(&cache, &[]);
+            *request.uri_mut() = format!("http://example.com/{path}").parse().unwrap();
+            request
+        };
+        send(get("a"), response(fresh, "0123456789")).await;
+        send(get("b"), response(fresh, "0123456789")).await;
+        assert_eq!(cache.len(), 2);
+        assert!(send(get("a"), response(fresh, "")).await.0);
+        send(get("c"), response(fresh, "0123456789")).await;
+        assert_eq!(cache.len(), 2);
+        assert!(send(get("a"), response(fresh, "")).await.0);
+        assert!(!send(get("b"), response(fresh, "")).await.0);
+
+        // Responses larger than the whole cache are never stored.
+        cache.clear();
+        let large = "x".repeat(2 * size).leak();
+        send(get("d"), response(fresh, large)).await;
+        assert!(cache.is_empty());
+    }
+}
@@ -2,10 +2,15 @@
 use crate::p2::bindings::http::types as p2;
 #[cfg(feature = "p3")]
 use crate::p3::bindings::http::types as p3;
-use crate::{DEFAULT_FORBIDDEN_HEADERS, Error, RequestOptions, Result};
+use crate::policy::{FollowRedirects, OutgoingPolicy};
+use crate::{
+    DEFAULT_FORBIDDEN_HEADERS, Error, RedirectPolicy, RequestOptions, ResponseCache, Result,
+};
 use bytes::Bytes;
-use http::{HeaderName, uri::Scheme};
+use http::{HeaderName, Method, uri::Scheme};
 use http_body_util::combinators::UnsyncBoxBody;
+use std::fmt;
+use std::sync::Arc;
 use wasmtime::component::{HasData, ResourceTable};
 
 /// A helper struct which implements [`HasData`] for the `wasi:http` APIs.
@@ -109,6 +114,31 @@ pub struct WasiHttpCtxView<'a> {
     pub ctx: &'a mut WasiHttpCtx,
 }
 
+impl WasiHttpCtxView<'_> {
+    /// Sends `request`, built to follow a redirect, the same way as the
+    /// outgoing requests of guests: it is checked against the configured
+    /// limits and policies and is sent through [`WasiHttpHooks::send_request`].
+    pub(crate) fn send_redirect(
+        &mut self,
+        request: http::Request<WasiBody>,
+        options: Option<RequestOptions>,
+    ) -> Result<
+        Box<
+            dyn Future<
+                    Output = Result<(
+                        http::Response<WasiBody>,
+                        Box<dyn Future<Output = Result<(), Error>> + Send>,
+                    )>,
+                > + Send,
+        >,
+    > {
+        let (request, _) = self.ctx.prepare_outgoing_request(request)?;
+        Ok(self
+            .hooks
+            .send_request(request, options, Box::new(async { Ok(()) })))
+    }
+}
+
 /// Default maximum size for the contents of a fields resource.
 ///
 /// Typically, HTTP proxies limit headers to 8k. This number is higher than that
@@ -120,9 +150,16 @@ pub struct WasiHttpCtxView<'a> {
 const DEFAULT_FIELD_SIZE_LIMIT: usize = 128 * 1024;
 
 /// Capture the state necessary for use in the wasi-http API implementation.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct WasiHttpCtx {
     pub(crate) field_size_limit: usize,
+    pub(crate) request_signer: Option<Arc<dyn RequestSigner>>,
+    pub(crate) response_cache: Option<Arc<dyn ResponseCache>>,
+    pub(crate) policy: OutgoingPolicy,
+    #[cfg(feature = "default-send-request")]
+    pub(crate) tls: Option<Arc<crate::tls::TlsPolicy>>,
+    #[cfg(feature = "correlation")]
+    pub(crate) correlation: Option<wasmtime_wasi_correlation::Correlation>,
 }
 
 impl WasiHttpCtx {
@@ -130,6 +167,13 @@ impl WasiHttpCtx {
     pub fn new() -> Self {
         Self {
             field_size_limit: DEFAULT_FIELD_SIZE_LIMIT,
+            request_signer: None,
+            response_cache: None,
+            policy: OutgoingPolicy::default(),
+            #[cfg(feature = "default-send-request")]
+            tls: None,
+            #[cfg(feature = "correlation")]
+            correlation: None,
         }
     }
 
@@ -143,6 +187,231 @@ impl WasiHttpCtx {
     pub fn set_field_size_limit(&mut self, limit: usize) {
         self.field_size_limit = limit;
     }
+
+    /// Set the [`RequestSigner`] used to sign all outgoing requests sent by
+    /// guests using this context.
+    ///
+    /// The signer runs on the host right before a request is transmitted, so
+    /// credentials used to compute signatures never need to be made available
+    /// to the guest. See [`RequestSigner`] for more information.
+    pub fn set_request_signer(&mut self, signer: impl RequestSigner) {
+        self.request_signer = Some(Arc::new(signer));
+    }
+
+    /// Set the [`ResponseCache`] used to cache the responses to outgoing
+    /// requests sent by guests using this context.
+    ///
+    /// Requests are looked up in the cache before they're sent, and fresh
+    /// responses found there are returned to the guest without contacting the
+    /// server. Cacheable responses to the requests which miss are stored
+    /// once the guest has read their body. See [`ResponseCache`] for which
+    /// requests and responses are cached. Nothing is cached by default.
+    ///
+    /// The same cache may be shared by multiple contexts, for example an
+    /// [`InMemoryResponseCache`] shared by all instances of a component.
+    ///
+    /// [`InMemoryResponseCache`]: crate::InMemoryResponseCache
+    pub fn set_response_cache(&mut self, cache: Arc<dyn ResponseCache>) {
+        self.response_cache = Some(cache);
+    }
+
+    /// Set the maximum size, in bytes, of the bodies of outgoing requests sent
+    /// by guests using this context.
+    ///
+    /// Writing more than `limit` bytes to a request body fails the request
+    /// with an `HTTP-request-body-size` error. There's no limit by default.
+    pub fn set_max_outgoing_body_size(&mut self, limit: u64) {
+        self.policy.max_outgoing_body_size = Some(limit);
+    }
+
+    /// Set the maximum size, in bytes, of the bodies of responses received
+    /// for outgoing requests sent by guests using this context.
+    ///
+    /// Reading past `limit` bytes of a response body fails with an
+    /// `HTTP-response-body-size` error. There's no limit by default.
+    pub fn set_max_incoming_body_size(&mut self, limit: u64) {
+        self.policy.max_incoming_body_size = Some(limit);
+    }
+
+    /// Set the maximum number of header fields of outgoing requests sent by
+    /// guests using this context, and of the responses received for them.
+    ///
+    /// Requests with more fields fail with an `HTTP-request-header-section-size`
+    /// error before they're sent, and responses with more fields with an
+    /// `HTTP-response-header-section-size` error. There's no limit by default.
+    pub fn set_max_header_count(&mut self, limit: usize) {
+        self.policy.max_header_count = Some(limit);
+    }
+
+    /// Set the maximum size, in bytes, of a single header field of outgoing
+    /// requests sent by guests using this context, and of the responses
+    /// received for them.
+    ///
+    /// The size of a field is the length of its name plus the length of its
+    /// value. Requests with a larger field fail with an
+    /// `HTTP-request-header-size` error before they're sent, and responses
+    /// with an `HTTP-response-header-size` error. There's no limit by default.
+    pub fn set_max_header_size(&mut self, limit: usize) {
+        self.policy.max_header_size = Some(limit);
+    }
+
+    /// Restrict the methods of outgoing requests sent by guests using this
+    /// context to `methods`.
+    ///
+    /// Requests with any other method fail with an `HTTP-request-denied`
+    /// error before they're sent. All methods are allowed by default.
+    pub fn set_allowed_methods(&mut self, methods: impl IntoIterator<Item = Method>) {
+        self.policy.allowed_methods = Some(methods.into_iter().collect());
+    }
+
+    /// Restrict the URL schemes of outgoing requests sent by guests using this
+    /// context to `schemes`.
+    ///
+    /// Requests with any other scheme fail with an `HTTP-request-denied`
+    /// error before they're sent. This applies in addition to
+    /// [`WasiHttpHooks::is_supported_scheme`], which already restricts the
+    /// schemes accepted from guests. All supported schemes are allowed by
+    /// default.
+    pub fn set_allowed_schemes(&mut self, schemes: impl IntoIterator<Item = Scheme>) {
+        self.policy.allowed_schemes = Some(schemes.into_iter().collect());
+    }
+
+    /// Set how redirect responses to outgoing requests sent by guests using
+    /// this context are handled.
+    ///
+    /// By default redirect responses are returned to the guest. See
+    /// [`RedirectPolicy`] for more information.
+    pub fn set_redirect_policy(&mut self, policy: RedirectPolicy) {
+        self.policy.redirects = policy;
+    }
+
+    /// Set the [`TlsConfig`] used to connect to servers for outgoing HTTPS
+    /// requests sent by guests using this context.
+    ///
+    /// This applies to all servers except those with their own configuration
+    /// set with [`WasiHttpCtx::set_tls_config_for`]. See [`TlsConfig`] for the
+    /// settings used by default.
+    ///
+    /// [`TlsConfig`]: crate::TlsConfig
+    #[cfg(feature = "default-send-request")]
+    pub fn set_tls_config(&mut self, config: crate::TlsConfig) {
+        Arc::make_mut(self.tls.get_or_insert_default()).default = Some(config);
+    }
+
+    /// Set the [`TlsConfig`] used to connect to `authority` for outgoing
+    /// HTTPS requests sent by guests using this context.
+    ///
+    /// `authority` is either a `host`, which applies to all ports of that
+    /// host, or a `host:port`, which takes precedence over the former for
+    /// that port. IPv6 hosts are written in brackets, for example `[::1]`.
+    /// Hosts are matched case-insensitively.
+    ///
+    /// [`TlsConfig`]: crate::TlsConfig
+    #[cfg(feature = "default-send-request")]
+    pub fn set_tls_config_for(&mut self, authority: &str, config: crate::TlsConfig) {
+        Arc::make_mut(self.tls.get_or_insert_default())
+            .authorities
+            .insert(authority.to_ascii_lowercase(), config);
+    }
+
+    /// Set the [`Correlation`] handle whose context is propagated on all
+    /// outgoing requests sent by guests using this context.
+    ///
+    /// While a context is set on `correlation`, outgoing requests which don't
+    /// already carry them get the [`REQUEST_ID_HEADER`] and
+    /// [`TRACE_ID_HEADER`] headers, and are sent within the
+    /// [`Correlation::span`] of the context.
+    ///
+    /// [`Correlation`]: wasmtime_wasi_correlation::Correlation
+    /// [`Correlation::span`]: wasmtime_wasi_correlation::Correlation::span
+    /// [`REQUEST_ID_HEADER`]: wasmtime_wasi_correlation::REQUEST_ID_HEADER
+    /// [`TRACE_ID_HEADER`]: wasmtime_wasi_correlation::TRACE_ID_HEADER
+    #[cfg(feature = "correlation")]
+    pub fn set_correlation(&mut self, correlation: wasmtime_wasi_correlation::Correlation) {
+        self.correlation = Some(correlation);
+    }
+
+    /// Prepares `request`, built from a guest's outgoing request, to be sent.
+    ///
+    /// This checks `request` against the configured limits and policies,
+    /// attaches the configured [`RequestSigner`] and [`ResponseCache`], if
+    /// any, for [`sign_request`] and [`lookup_cached_response`] to pick up,
+    /// attaches the TLS configuration, if any, and adds the headers of the
+    /// correlation context, if any. The returned span is the one the request
+    /// should be sent within.
+    ///
+    /// [`lookup_cached_response`]: crate::lookup_cached_response
+    pub(crate) fn prepare_outgoing_request(
+        &self,
+        request: http::Request<WasiBody>,
+    ) -> Result<(http::Request<WasiBody>, tracing::Span)> {
+        let mut request = self.policy.check_request(request)?;
+
+        if let Some(signer) = &self.request_signer {
+            request
+                .extensions_mut()
+                .insert(PendingSignature(signer.clone()));
+        }
+        if let Some(cache) = &self.response_cache {
+            request
+                .extensions_mut()
+                .insert(crate::cache::PendingLookup(cache.clone()));
+        }
+
+        #[cfg(feature = "default-send-request")]
+        if let Some(tls) = &self.tls {
+            request.extensions_mut().insert(tls.clone());
+        }
+
+        #[cfg(feature = "correlation")]
+        if let Some(cx) = self.correlation.as_ref().and_then(|c| c.get()) {
+            for (name, value) in cx.headers() {
+                let Ok(value) = http::HeaderValue::try_from(value) else {
+                    tracing::warn!("not propagating invalid `{name}` header value");
+                    continue;
+                };
+                request.headers_mut().entry(name).or_insert(value);
+            }
+            return Ok((request, cx.span()));
+        }
+
+        Ok((request, tracing::Span::none()))
+    }
+
+    /// Returns the state with which to follow the redirects of `request`,
+    /// prepared by [`Self::prepare_outgoing_request`], according to the
+    /// configured [`RedirectPolicy`], or `None` if redirects aren't followed.
+    pub(crate) fn follow_redirects<B>(
+        &self,
+        request: &http::Request<B>,
+    ) -> Option<FollowRedirects> {
+        let (remaining, cross_origin) = match self.policy.redirects {
+            RedirectPolicy::None => return None,
+            RedirectPolicy::SameOrigin(max) => (max, false),
+            RedirectPolicy::Any(max) => (max, self.request_signer.is_none()),
+        };
+        Some(FollowRedirects {
+            remaining,
+            cross_origin,
+            allowed_schemes: self.policy.allowed_schemes.clone(),
+            method: request.method().clone(),
+            uri: request.uri().clone(),
+            headers: request.headers().clone(),
+        })
+    }
+}
+
+impl fmt::Debug for WasiHttpCtx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut f = f.debug_struct("WasiHttpCtx");
+        f.field("field_size_limit", &self.field_size_limit)
+            .field("request_signer", &self.request_signer.is_some())
+            .field("response_cache", &self.response_cache.is_some())
+            .field("policy", &self.policy);
+        #[cfg(feature = "default-send-request")]
+        f.field("tls", &self.tls);
+        f.finish()
+    }
 }
 
 impl Default for WasiHttpCtx {
@@ -154,6 +423,80 @@ impl Default for WasiHttpCtx {
 /// Convenience type definition for the bodies used in this crate.
 pub type WasiBody = UnsyncBoxBody<Bytes, Error>;
 
+/// A host hook which signs outgoing requests before they're transmitted,
+/// configured with [`WasiHttpCtx::set_request_signer`].
+///
+/// This allows guests to call APIs which require signed requests without
+/// having access to the credentials used to compute signatures. The signer is
+/// given ownership of the full outgoing request, including its body, and
+/// returns the request to transmit in its place. Signature schemes which cover
+/// the body can collect and hash it before attaching the signature, at the
+/// cost of buffering the body in host memory.
+///
+/// Requests are signed by [`sign_request`], which the default
+/// implementation of [`WasiHttpHooks::send_request`] calls before sending.
+/// Custom implementations of [`WasiHttpHooks::send_request`] need to call
+/// [`sign_request`] themselves.
+///
+/// # Example
+///
+/// ```
+/// use http_body_util::{BodyExt, Full};
+/// use wasmtime_wasi_http::{Error, RequestSigner, WasiBody, WasiHttpCtx};
+///
+/// struct ContentLengthSigner;
+///
+/// impl RequestSigner for ContentLengthSigner {
+///     fn sign(
+///         &self,
+///         request: http::Request<WasiBody>,
+///     ) -> Box<dyn Future<Output = Result<http::Request<WasiBody>, Error>> + Send> {
+///         Box::new(async move {
+///             let (mut parts, body) = request.into_parts();
+///             let body = body.collect().await?.to_bytes();
+///             // A real signer would compute a signature over `parts` and a
+///             // hash of `body` here.
+///             parts.headers.insert("x-signature", body.len().into());
+///             let body = Full::new(body).map_err(|e| match e {}).boxed_unsync();
+///             Ok(http::Request::from_parts(parts, body))
+///         })
+///     }
+/// }
+///
+/// let mut ctx = WasiHttpCtx::new();
+/// ctx.set_request_signer(ContentLengthSigner);
+/// ```
+pub trait RequestSigner: Send + Sync + 'static {
+    /// Signs `request`, returning the request to transmit in its place.
+    ///
+    /// If an error is returned the request isn't sent and the error is
+    /// reported to the guest.
+    fn sign(
+        &self,
+        request: http::Request<WasiBody>,
+    ) -> Box<dyn Future<Output = Result<http::Request<WasiBody>, Error>> + Send>;
+}
+
+/// Extension attached to outgoing requests which still need to be signed.
+#[derive(Clone)]
+struct PendingSignature(Arc<dyn RequestSigner>);
+
+/// Signs `request` with the [`RequestSigner`] configured for the
+/// [`WasiHttpCtx`] it originates from, if any.
+///
+/// This is called by the default implementation of
+/// [`WasiHttpHooks::send_request`] and should be called by custom
+/// implementations before transmitting a request. Requests which have already
+/// been signed, or for which no signer is configured, are returned unchanged.
+pub async fn sign_request(
+    mut request: http::Request<WasiBody>,
+) -> Result<http::Request<WasiBody>, Error> {
+    match request.extensions_mut().remove::<PendingSignature>() {
+        Some(PendingSignature(signer)) => Box::into_pin(signer.sign(request)).await,
+        None => Ok(request),
+    }
+}
+
 /// A trait which provides hooks into internal WASI HTTP operations.
 ///
 /// Note that when using this type if state is needed to implement the methods
@@ -244,6 +587,14 @@ pub trait WasiHttpHooks: Send {
     ///
     /// `Content-Length` of the request passed to this function will be validated, however no
     /// `Content-Length` validation will be performed for the received response.
+    ///
+    /// Implementations should pass the request to [`sign_request`] before
+    /// transmitting it so that the [`RequestSigner`] configured for the
+    /// [`WasiHttpCtx`], if any, is applied. Similarly, they should first look
+    /// the request up with [`lookup_cached_response`] so that the
+    /// [`ResponseCache`] configured for the [`WasiHttpCtx`], if any, is used.
+    ///
+    /// [`lookup_cached_response`]: crate::lookup_cached_response
     #[cfg(feature = "default-send-request")]
     fn send_request(
         &mut self,
@@ -262,9 +613,19 @@ pub trait WasiHttpHooks: Send {
         Box::new(async move {
             use http_body_util::BodyExt;
 
+            let (request, insert) = match crate::lookup_cached_response(request) {
+                crate::CacheLookup::Hit(res) => {
+                    return Ok((
+                        res,
+                        Box::new(async { Ok(()) }) as Box<dyn Future<Output = _> + Send>,
+                    ));
+                }
+                crate::CacheLookup::Miss(request, insert) => (request, insert),
+            };
+            let request = sign_request(request).await?;
             let (res, io) = crate::default_send_request(request, options).await?;
             Ok((
-                res.map(BodyExt::boxed_unsync),
+                insert.response(res.map(BodyExt::boxed_unsync)),
                 Box::new(io) as Box<dyn Future<Output = _> + Send>,
             ))
         })
@@ -286,6 +647,14 @@ pub trait WasiHttpHooks: Send {
     ///
     /// `Content-Length` of the request passed to this function will be validated, however no
     /// `Content-Length` validation will be performed for the received response.
+    ///
+    /// Implementations should pass the request to [`sign_request`] before
+    /// transmitting it so that the [`RequestSigner`] configured for the
+    /// [`WasiHttpCtx`], if any, is applied. Similarly, they should first look
+    /// the request up with [`lookup_cached_response`] so that the
+    /// [`ResponseCache`] configured for the [`WasiHttpCtx`], if any, is used.
+    ///
+    /// [`lookup_cached_response`]: crate::lookup_cached_response
     #[cfg(not(feature = "default-send-request"))]
     fn send_request(
         &mut self,
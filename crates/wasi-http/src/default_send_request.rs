@@ -1,4 +1,5 @@
-use crate::{Error, RequestOptions};
+use crate::tls::TlsPolicy;
+use crate::{Error, RequestOptions, WasiBody};
 use bytes::Bytes;
 use core::future::poll_fn;
 use core::pin::{Pin, pin};
@@ -6,6 +7,8 @@ use core::task::{Poll, ready};
 use http::uri::Scheme;
 use http::{Request, Response};
 use http_body::Body;
+use http_body_util::BodyExt as _;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
@@ -30,7 +33,19 @@ impl<T> TokioStream for T where T: AsyncRead + AsyncWrite + Send + Sync + Unpin
 /// For example, if the request was constructed via `wasi:http/types.request#new`,
 /// a result resolved from it will be forwarded to the guest on the future handle returned.
 ///
+/// Redirect responses are returned as is; redirects are followed by the
+/// `handle` and `send` implementations of this crate according to the
+/// [`RedirectPolicy`] configured for the [`WasiHttpCtx`] the request originates
+/// from, each hop being sent through
+/// [`WasiHttpHooks::send_request`](crate::WasiHttpHooks::send_request) again.
+/// HTTPS connections use the [`TlsConfig`] configured for the server on that
+/// context, if any.
+///
 /// This function performs no `Content-Length` validation.
+///
+/// [`RedirectPolicy`]: crate::RedirectPolicy
+/// [`TlsConfig`]: crate::TlsConfig
+/// [`WasiHttpCtx`]: crate::WasiHttpCtx
 pub async fn default_send_request(
     mut req: Request<impl Body<Data = Bytes, Error = Error> + Send + 'static>,
     options: Option<RequestOptions>,
@@ -40,14 +55,36 @@ pub async fn default_send_request(
         impl Future<Output = Result<(), Error>> + Send,
     ),
     Error,
+> {
+    let tls = req.extensions_mut().remove::<Arc<TlsPolicy>>();
+    send_request(req.map(|body| body.boxed_unsync()), options, tls.as_deref()).await
+}
+
+/// Sends `req` over a new connection.
+async fn send_request(
+    mut req: Request<WasiBody>,
+    options: Option<RequestOptions>,
+    tls: Option<&TlsPolicy>,
+) -> Result<
+    (
+        Response<impl Body<Data = Bytes, Error = Error> + use<>>,
+        impl Future<Output = Result<(), Error>> + Send + use<>,
+    ),
+    Error,
 > {
     let uri = req.uri();
     let authority = uri.authority().ok_or(Error::HttpRequestUriInvalid)?;
     let use_tls = uri.scheme() == Some(&Scheme::HTTPS);
+    let port = authority
+        .port_u16()
+        .unwrap_or(if use_tls { 443 } else { 80 });
+    let tls = tls
+        .and_then(|tls| tls.get(authority.host(), port))
+        .cloned()
+        .unwrap_or_default();
     let authority = if authority.port().is_some() {
         authority.to_string()
     } else {
-        let port = if use_tls { 443 } else { 80 };
         format!("{authority}:{port}")
     };
 
@@ -68,15 +105,11 @@ pub async fn default_send_request(
         Err(..) => return Err(Error::ConnectionTimeout),
     };
     let stream = if use_tls {
-        // derived from https://github.com/rustls/rustls/blob/main/examples/src/bin/simpleclient.rs
-        let root_cert_store = rustls::RootCertStore {
-            roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+        let connector = tls.connector()?;
+        let domain = match tls.server_name() {
+            Some(name) => name.clone(),
+            None => tls_server_name(&authority)?,
         };
-        let config = rustls::ClientConfig::builder()
-            .with_root_certificates(root_cert_store)
-            .with_no_client_auth();
-        let connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(config));
-        let domain = tls_server_name(&authority)?;
         let stream = connector
             .connect(domain, stream)
             .await
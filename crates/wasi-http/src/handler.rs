@@ -28,7 +28,7 @@ use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
 };
 use std::task::{Context, Poll};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 use wasmtime::component::{Accessor, GuestTaskId, Resource, TypedFuncCallConcurrent};
 #[cfg(feature = "p2")]
@@ -948,6 +948,235 @@ where
     }
 }
 
+/// Returned when a request was abandoned because it was still in flight on a
+/// [`ReloadableProxyHandler`] generation which failed to drain within the
+/// timeout passed to [`ReloadableProxyHandler::reload`].
+pub struct DrainTimeoutError;
+
+impl fmt::Display for DrainTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for DrainTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "request abandoned after reload drain timeout")
+    }
+}
+
+impl error::Error for DrainTimeoutError {}
+
+/// A wrapper around [`ProxyHandler`] whose [`HandlerState`] can be replaced
+/// while requests are being served, e.g. to switch to a new version of a
+/// component without downtime.
+///
+/// Each call to [`ReloadableProxyHandler::reload`] starts a new "generation"
+/// with its own [`ProxyHandler`] and instance pool. New requests are routed to
+/// the latest generation immediately, while requests already in flight on
+/// previous generations are allowed to run to completion, subject to a drain
+/// timeout.
+pub struct ReloadableProxyHandler<S: HandlerState>(Arc<ReloadableInner<S>>);
+
+impl<S: HandlerState> Clone for ReloadableProxyHandler<S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+struct ReloadableInner<S: HandlerState> {
+    current: Mutex<Arc<Generation<S>>>,
+    /// The previous generation while it's being drained by `reload`.
+    draining: Mutex<Option<Arc<Generation<S>>>>,
+    status: Mutex<ReloadStatus>,
+    /// Serializes calls to `ReloadableProxyHandler::reload`.
+    reloading: tokio::sync::Mutex<()>,
+}
+
+/// A [`ProxyHandler`] along with the requests currently in flight on it.
+struct Generation<S: HandlerState> {
+    handler: ProxyHandler<S>,
+    in_flight: AtomicUsize,
+    /// Notified when `in_flight` drops to zero.
+    drained: Notify,
+    /// Set, and `abandon` notified, once this generation failed to drain in
+    /// time and its remaining requests should give up.
+    abandoned: AtomicBool,
+    abandon: Notify,
+}
+
+impl<S: HandlerState> Generation<S> {
+    fn new(state: S) -> Self {
+        Self {
+            handler: ProxyHandler::new(state),
+            in_flight: AtomicUsize::new(0),
+            drained: Notify::new(),
+            abandoned: AtomicBool::new(false),
+            abandon: Notify::new(),
+        }
+    }
+
+    async fn drain(&self) {
+        let mut drained = pin!(self.drained.notified());
+        loop {
+            drained.as_mut().enable();
+            if self.in_flight.load(Relaxed) == 0 {
+                return;
+            }
+            drained.as_mut().await;
+            drained.set(self.drained.notified());
+        }
+    }
+
+    async fn abandoned(&self) {
+        let mut abandon = pin!(self.abandon.notified());
+        abandon.as_mut().enable();
+        if !self.abandoned.load(Relaxed) {
+            abandon.await;
+        }
+    }
+}
+
+/// A snapshot of the reload state of a [`ReloadableProxyHandler`], as returned
+/// by [`ReloadableProxyHandler::status`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadStatus {
+    /// Number of the generation new requests are routed to, starting at 0 and
+    /// incremented with each reload.
+    pub generation: u64,
+    /// Number of requests still in flight on the previous generation while
+    /// it's being drained.
+    pub draining: usize,
+    /// The outcome of the most recently completed reload, if any.
+    pub last_reload: Option<ReloadReport>,
+}
+
+/// The outcome of a [`ReloadableProxyHandler::reload`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Number of the generation which was started by the reload.
+    pub generation: u64,
+    /// How long it took for the previous generation to drain, or the drain
+    /// timeout if it didn't drain in time.
+    pub drain_time: Duration,
+    /// Number of requests of the previous generation which were still in
+    /// flight when the drain timeout elapsed and which failed with
+    /// [`DrainTimeoutError`].
+    pub abandoned: usize,
+}
+
+impl<S> ReloadableProxyHandler<S>
+where
+    S: HandlerState,
+{
+    /// Create a new `ReloadableProxyHandler` with the specified initial
+    /// application state.
+    pub fn new(state: S) -> Self {
+        Self(Arc::new(ReloadableInner {
+            current: Mutex::new(Arc::new(Generation::new(state))),
+            draining: Mutex::new(None),
+            status: Mutex::new(ReloadStatus::default()),
+            reloading: tokio::sync::Mutex::new(()),
+        }))
+    }
+
+    /// Handle the specified request using the current generation.
+    ///
+    /// This behaves like [`ProxyHandler::handle`], except that the returned
+    /// error may additionally be downcast to [`DrainTimeoutError`] if the
+    /// request's generation was replaced and didn't drain in time.
+    pub async fn handle(
+        &self,
+        id: <S::WorkerState as WorkerState>::RequestId,
+        request: Request,
+    ) -> Result<Response, wasmtime::Error> {
+        struct InFlight<S: HandlerState>(Arc<Generation<S>>);
+
+        impl<S: HandlerState> Drop for InFlight<S> {
+            fn drop(&mut self) {
+                if self.0.in_flight.fetch_sub(1, Relaxed) == 1 {
+                    self.0.drained.notify_waiters();
+                }
+            }
+        }
+
+        // Count the request while holding the lock so that a concurrent
+        // `reload` can't swap out this generation and find it drained before
+        // the request is accounted for.
+        let in_flight = {
+            let current = self.0.current.lock().unwrap();
+            current.in_flight.fetch_add(1, Relaxed);
+            InFlight(current.clone())
+        };
+        let generation = &in_flight.0;
+        let handle = pin!(generation.handler.handle(id, request));
+        let abandoned = pin!(generation.abandoned());
+        match futures::future::select(handle, abandoned).await {
+            Either::Left((result, _)) => result,
+            Either::Right(((), _)) => Err(DrainTimeoutError.into()),
+        }
+    }
+
+    /// Return a reference to the handler of the current generation.
+    pub fn current(&self) -> ProxyHandler<S> {
+        self.0.current.lock().unwrap().handler.clone()
+    }
+
+    /// Returns the current reload status of this handler.
+    pub fn status(&self) -> ReloadStatus {
+        let mut status = self.0.status.lock().unwrap().clone();
+        if let Some(previous) = &*self.0.draining.lock().unwrap() {
+            status.draining = previous.in_flight.load(Relaxed);
+        }
+        status
+    }
+
+    /// Replaces the application state with `state`, draining the previous
+    /// generation.
+    ///
+    /// New requests are routed to `state` as soon as this function is called.
+    /// This function then waits for the requests in flight on the previous
+    /// generation to finish, for at most `drain_timeout`, after which any
+    /// remaining requests fail with [`DrainTimeoutError`]. Instances of the
+    /// previous generation are dropped once they become idle, as determined
+    /// by their [`WorkerExpiration`].
+    ///
+    /// Concurrent calls to this function are serialized.
+    pub async fn reload(&self, state: S, drain_timeout: Duration) -> ReloadReport {
+        let _reloading = self.0.reloading.lock().await;
+
+        let previous = mem::replace(
+            &mut *self.0.current.lock().unwrap(),
+            Arc::new(Generation::new(state)),
+        );
+        *self.0.draining.lock().unwrap() = Some(previous.clone());
+        let generation = {
+            let mut status = self.0.status.lock().unwrap();
+            status.generation += 1;
+            status.generation
+        };
+
+        let start = Instant::now();
+        let abandoned = match tokio::time::timeout(drain_timeout, previous.drain()).await {
+            Ok(()) => 0,
+            Err(_) => {
+                previous.abandoned.store(true, Relaxed);
+                previous.abandon.notify_waiters();
+                previous.in_flight.load(Relaxed)
+            }
+        };
+
+        let report = ReloadReport {
+            generation,
+            drain_time: start.elapsed().min(drain_timeout),
+            abandoned,
+        };
+        *self.0.draining.lock().unwrap() = None;
+        self.0.status.lock().unwrap().last_reload = Some(report.clone());
+        report
+    }
+}
+
 /// Representation of a "prepared" call for a guest, used to extract the
 /// `GuestTaskId` before actually executing any handlers.
 ///
@@ -1020,17 +1249,29 @@ impl<'a, T: Send> Prepared<'a, T> {
                 // producing a response.
                 let tx = Arc::new(Mutex::new(Some(tx)));
 
+                let mut request = request;
+                let informational = request
+                    .extensions_mut()
+                    .remove::<p2::types::OnInformational>();
                 let request =
                     view(store.data_mut()).new_incoming_request(p2_types::Scheme::Http, request)?;
 
-                let out = view(store.data_mut()).new_response_outparam_from_callback({
+                let send = {
                     let tx = tx.clone();
-                    move |value| {
+                    move |value: Result<_, p2_types::ErrorCode>| {
                         if let Some(tx) = tx.lock().unwrap().take() {
                             _ = tx.send(value.map_err(|e| e.into()));
                         }
                     }
-                })?;
+                };
+                let out = match informational {
+                    Some(informational) => view(store.data_mut())
+                        .new_response_outparam_with_informational(
+                            send,
+                            move |status, headers| informational.call(status, headers),
+                        )?,
+                    None => view(store.data_mut()).new_response_outparam_from_callback(send)?,
+                };
 
                 Ok(Prepared::P2 {
                     guest,
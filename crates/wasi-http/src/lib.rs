@@ -11,6 +11,7 @@
 
 use http::{HeaderName, header};
 
+mod cache;
 mod ctx;
 #[cfg(feature = "default-send-request")]
 mod default_send_request;
@@ -23,14 +24,25 @@ pub mod io;
 pub mod p2;
 #[cfg(feature = "p3")]
 pub mod p3;
+mod policy;
 mod request_options;
+pub mod router;
+#[cfg(feature = "default-send-request")]
+mod tls;
 
+pub use cache::{
+    CacheInsert, CacheLookup, CachedResponse, InMemoryResponseCache, ResponseCache,
+    lookup_cached_response,
+};
 pub use ctx::*;
 #[cfg(feature = "default-send-request")]
 pub use default_send_request::*;
 pub use error::*;
 pub use field_map::*;
+pub use policy::RedirectPolicy;
 pub use request_options::*;
+#[cfg(feature = "default-send-request")]
+pub use tls::TlsConfig;
 
 /// Extract the `Content-Length` header value from a [`http::HeaderMap`], returning `None` if it's not
 /// present. This function will return `Err` if it's not possible to parse the `Content-Length`
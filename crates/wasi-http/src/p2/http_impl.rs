@@ -9,12 +9,17 @@ use crate::p2::{
     },
     error::internal_error,
     http_request_error,
-    types::{HostFutureIncomingResponse, HostOutgoingRequest},
+    types::{
+        FutureIncomingResponseHandle, HostFutureIncomingResponse, HostOutgoingRequest,
+        PendingRedirect,
+    },
 };
+use crate::policy::OutgoingPolicy;
 use bytes::Bytes;
 use http_body_util::{BodyExt, Empty};
 use hyper::Method;
 use std::pin::Pin;
+use tracing::Instrument;
 use wasmtime::component::Resource;
 
 impl outgoing_handler::Host for WasiHttpCtxView<'_> {
@@ -78,11 +83,51 @@ impl outgoing_handler::Host for WasiHttpCtxView<'_> {
         let request = builder
             .body(body)
             .map_err(|err| internal_error(err.to_string()))?;
+        let (request, span) = match self.ctx.prepare_outgoing_request(request) {
+            Ok(prepared) => prepared,
+            Err(e) => return Err(self.error_to_p2(e).into()),
+        };
+        let policy = self.ctx.policy.clone();
+        let redirects = self
+            .ctx
+            .follow_redirects(&request)
+            .map(|redirects| PendingRedirect {
+                redirects,
+                options: opts,
+                span: span.clone(),
+            });
 
         let future = self
             .hooks
             .send_request(request, opts, Box::new(async { Ok(()) }));
-        let future = wasmtime_wasi::runtime::spawn(async move {
+        let future = spawn_send_request(future, policy, redirects, span);
+
+        Ok(self
+            .table
+            .push(HostFutureIncomingResponse::Pending(future))?)
+    }
+}
+
+/// Spawns the `future` returned by `WasiHttpHooks::send_request`, checking the
+/// response it resolves to against `policy`.
+///
+/// The state of following redirects, if any, is attached to the response for
+/// `future-incoming-response.get` to pick up.
+pub(crate) fn spawn_send_request(
+    future: Box<
+        dyn Future<
+                Output = crate::Result<(
+                    http::Response<crate::WasiBody>,
+                    Box<dyn Future<Output = crate::Result<()>> + Send>,
+                )>,
+            > + Send,
+    >,
+    policy: OutgoingPolicy,
+    redirects: Option<PendingRedirect>,
+    span: tracing::Span,
+) -> FutureIncomingResponseHandle {
+    wasmtime_wasi::runtime::spawn(
+        async move {
             let (res, io) = Pin::from(future).await?;
             let io = wasmtime_wasi::runtime::spawn(async move {
                 match Pin::from(io).await {
@@ -92,12 +137,12 @@ impl outgoing_handler::Host for WasiHttpCtxView<'_> {
                     Err(e) => tracing::warn!("dropping error {e}"),
                 }
             });
-            let res = res.map(|b| b.boxed_unsync());
+            let mut res = policy.check_response(res.map(|b| b.boxed_unsync()))?;
+            if let Some(redirects) = redirects {
+                res.extensions_mut().insert(redirects);
+            }
             Ok((res, io))
-        });
-
-        Ok(self
-            .table
-            .push(HostFutureIncomingResponse::Pending(future))?)
-    }
+        }
+        .instrument(span),
+    )
 }
@@ -313,7 +313,19 @@ pub fn add_only_http_to_linker_async<T>(
 where
     T: WasiHttpView + 'static,
 {
-    let options = bindings::LinkOptions::default(); // FIXME: Thread through to the CLI options.
+    let options = bindings::LinkOptions::default();
+    add_only_http_to_linker_with_options_async(l, &options)
+}
+
+/// Similar to [`add_only_http_to_linker_async`], but with the ability to
+/// enable unstable features.
+pub fn add_only_http_to_linker_with_options_async<T>(
+    l: &mut wasmtime::component::Linker<T>,
+    options: &bindings::LinkOptions,
+) -> wasmtime::Result<()>
+where
+    T: WasiHttpView + 'static,
+{
     bindings::http::outgoing_handler::add_to_linker::<_, WasiHttp>(l, T::http)?;
     bindings::http::types::add_to_linker::<_, WasiHttp>(l, &options.into(), T::http)?;
 
@@ -382,7 +394,19 @@ pub fn add_only_http_to_linker_sync<T>(l: &mut Linker<T>) -> wasmtime::Result<()
 where
     T: WasiHttpView + 'static,
 {
-    let options = bindings::LinkOptions::default(); // FIXME: Thread through to the CLI options.
+    let options = bindings::LinkOptions::default();
+    add_only_http_to_linker_with_options_sync(l, &options)
+}
+
+/// Similar to [`add_only_http_to_linker_sync`], but with the ability to
+/// enable unstable features.
+pub fn add_only_http_to_linker_with_options_sync<T>(
+    l: &mut Linker<T>,
+    options: &bindings::LinkOptions,
+) -> wasmtime::Result<()>
+where
+    T: WasiHttpView + 'static,
+{
     bindings::sync::http::outgoing_handler::add_to_linker::<_, WasiHttp>(l, T::http)?;
     bindings::sync::http::types::add_to_linker::<_, WasiHttp>(l, &options.into(), T::http)?;
 
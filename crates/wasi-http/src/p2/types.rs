@@ -5,10 +5,12 @@ use crate::p2::{
     bindings::http::types::{self, Method, Scheme},
     body::{HostIncomingBody, HyperIncomingBody, HyperOutgoingBody},
 };
+use crate::policy::FollowRedirects;
 use crate::{Error, FieldMap, WasiHttpCtxView};
 use bytes::Bytes;
 use http_body_util::BodyExt;
 use hyper::body::Body;
+use std::sync::Arc;
 use wasmtime::component::Resource;
 use wasmtime::{Result, bail};
 use wasmtime_wasi::p2::Pollable;
@@ -112,6 +114,40 @@ pub struct HostResponseOutparam {
     /// The callback sending a response.
     pub send:
         Box<dyn FnOnce(Result<hyper::Response<HyperOutgoingBody>, types::ErrorCode>) + Send + Sync>,
+    /// The callback sending an informational (1xx) response, if supported.
+    ///
+    /// When this is `None` the guest's calls to `send-informational` return
+    /// an `internal-error` indicating that informational responses are not
+    /// supported by this host.
+    pub send_informational: Option<InformationalCallback>,
+}
+
+/// Callback used by [`HostResponseOutparam`] to forward informational
+/// responses, such as `103 Early Hints`, ahead of the final response.
+pub type InformationalCallback = Box<dyn FnMut(http::StatusCode, http::HeaderMap) + Send + Sync>;
+
+/// Request extension which forwards informational (1xx) responses sent by
+/// the guest handling the request.
+///
+/// When a request handled by the `handler` module's `ProxyHandler` carries this extension its `response-outparam` is created with
+/// [`WasiHttpCtxView::new_response_outparam_with_informational`], passing
+/// each informational response to the wrapped callback.
+#[derive(Clone)]
+pub struct OnInformational(Arc<dyn Fn(http::StatusCode, http::HeaderMap) + Send + Sync>);
+
+impl OnInformational {
+    /// Creates a new extension which calls `callback` with each
+    /// informational response.
+    pub fn new(
+        callback: impl Fn(http::StatusCode, http::HeaderMap) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Forwards an informational response to the wrapped callback.
+    pub fn call(&self, status: http::StatusCode, headers: http::HeaderMap) {
+        (self.0)(status, headers)
+    }
 }
 
 impl WasiHttpCtxView<'_> {
@@ -130,6 +166,7 @@ impl WasiHttpCtxView<'_> {
                 // See also: #10784
                 _ = result.send(value)
             }),
+            send_informational: None,
         })?;
         Ok(id)
     }
@@ -144,6 +181,30 @@ impl WasiHttpCtxView<'_> {
     ) -> wasmtime::Result<Resource<HostResponseOutparam>> {
         let id = self.table.push(HostResponseOutparam {
             send: Box::new(callback),
+            send_informational: None,
+        })?;
+        Ok(id)
+    }
+
+    /// Create a new outgoing response from an `FnOnce` for the final response
+    /// and an `FnMut` which receives any informational (1xx) responses sent
+    /// by the guest beforehand.
+    ///
+    /// Informational responses are only reachable by guests when the
+    /// `informational-outbound-responses` unstable feature is enabled in the
+    /// [`LinkOptions`](crate::p2::bindings::LinkOptions) used to link
+    /// `wasi:http/types`.
+    pub fn new_response_outparam_with_informational(
+        &mut self,
+        callback: impl FnOnce(Result<hyper::Response<HyperOutgoingBody>, types::ErrorCode>)
+        + Send
+        + Sync
+        + 'static,
+        informational: impl FnMut(http::StatusCode, http::HeaderMap) + Send + Sync + 'static,
+    ) -> wasmtime::Result<Resource<HostResponseOutparam>> {
+        let id = self.table.push(HostResponseOutparam {
+            send: Box::new(callback),
+            send_informational: Some(Box::new(informational)),
         })?;
         Ok(id)
     }
@@ -225,6 +286,15 @@ pub struct IncomingResponse {
 type SendRequestResult =
     crate::Result<(http::Response<HyperIncomingBody>, AbortOnDropJoinHandle<()>)>;
 
+/// The state of following the redirects of an outgoing request, attached to the
+/// response of each hop for `future-incoming-response.get` to pick up.
+#[derive(Clone)]
+pub(crate) struct PendingRedirect {
+    pub(crate) redirects: FollowRedirects,
+    pub(crate) options: Option<crate::RequestOptions>,
+    pub(crate) span: tracing::Span,
+}
+
 /// The concrete type behind a `wasi:http/types.future-incoming-response` resource.
 pub enum HostFutureIncomingResponse {
     /// A pending response
@@ -232,6 +302,9 @@ pub enum HostFutureIncomingResponse {
     /// The response is ready.
     ///
     /// An outer error will trap while the inner error gets returned to the guest.
+    ///
+    /// If the response is a redirect to be followed, `get` sends the next
+    /// request and moves back to [`Self::Pending`], returning no response yet.
     Ready(SendRequestResult),
     /// The response has been consumed.
     Consumed,
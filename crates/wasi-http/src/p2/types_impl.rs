@@ -2,16 +2,17 @@
 
 use crate::p2::bindings::http::types::{self, Method, Scheme, StatusCode, Trailers};
 use crate::p2::body::{HostFutureTrailers, HostIncomingBody, HostOutgoingBody, StreamContext};
+use crate::p2::http_impl::spawn_send_request;
 use crate::p2::types::{
     HostFutureIncomingResponse, HostIncomingRequest, HostIncomingResponse, HostOutgoingRequest,
-    HostOutgoingResponse, HostResponseOutparam,
+    HostOutgoingResponse, HostResponseOutparam, PendingRedirect,
 };
 use crate::p2::{HeaderError, HeaderResult, HttpError, HttpResult};
 use crate::{FieldMap, WasiHttpCtxView, get_content_length};
 use http::HeaderName;
 use std::str::FromStr;
 use wasmtime::component::Resource;
-use wasmtime::{error::Context as _, format_err};
+use wasmtime::error::Context as _;
 use wasmtime_wasi::p2::{DynInputStream, DynOutputStream, DynPollable};
 
 impl types::Host for WasiHttpCtxView<'_> {
@@ -376,11 +377,26 @@ impl types::HostResponseOutparam for WasiHttpCtxView<'_> {
 
     fn send_informational(
         &mut self,
-        _id: Resource<HostResponseOutparam>,
-        _status: u16,
-        _headers: Resource<FieldMap>,
+        id: Resource<HostResponseOutparam>,
+        status: u16,
+        headers: Resource<FieldMap>,
     ) -> HttpResult<()> {
-        Err(HttpError::trap(format_err!("not implemented")))
+        let headers = self.table.delete(headers)?;
+        if !(100..200).contains(&status) {
+            return Err(types::ErrorCode::HttpProtocolError.into());
+        }
+        let status =
+            http::StatusCode::from_u16(status).map_err(|_| types::ErrorCode::HttpProtocolError)?;
+
+        let outparam = self.table.get_mut(&id)?;
+        let Some(send) = outparam.send_informational.as_mut() else {
+            return Err(types::ErrorCode::InternalError(Some(
+                "informational responses are not supported".to_string(),
+            ))
+            .into());
+        };
+        send(status, headers.into());
+        Ok(())
     }
 }
 
@@ -611,7 +627,7 @@ impl types::HostFutureIncomingResponse for WasiHttpCtxView<'_> {
             HostFutureIncomingResponse::Ready(_) => {}
         }
 
-        let (resp, io) =
+        let (mut resp, io) =
             match std::mem::replace(resp, HostFutureIncomingResponse::Consumed).unwrap_ready() {
                 Ok(pair) => pair,
                 Err(e) => {
@@ -620,6 +636,31 @@ impl types::HostFutureIncomingResponse for WasiHttpCtxView<'_> {
                 }
             };
 
+        if let Some(mut redirect) = resp.extensions_mut().remove::<PendingRedirect>() {
+            let next = redirect
+                .redirects
+                .next_request(&resp)
+                .and_then(|next| match next {
+                    Some(next) => self.send_redirect(next, redirect.options).map(Some),
+                    None => Ok(None),
+                });
+            match next {
+                Ok(Some(future)) => {
+                    drop((resp, io));
+                    let policy = self.ctx.policy.clone();
+                    let span = redirect.span.clone();
+                    let future = spawn_send_request(future, policy, Some(redirect), span);
+                    *self.table.get_mut(&id)? = HostFutureIncomingResponse::Pending(future);
+                    return Ok(None);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    let e = self.error_to_p2(e);
+                    return Ok(Some(Ok(Err(e))));
+                }
+            }
+        }
+
         let (parts, body) = resp.into_parts();
         let headers = FieldMap::new_immutable(self.hooks, parts.headers);
 
@@ -768,3 +809,81 @@ impl types::HostRequestOptions for WasiHttpCtxView<'_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WasiHttpCtx;
+    use std::sync::{Arc, Mutex};
+    use wasmtime::component::ResourceTable;
+
+    #[test]
+    fn send_informational() -> wasmtime::Result<()> {
+        let mut ctx = WasiHttpCtx::new();
+        let mut table = ResourceTable::new();
+        let mut view = WasiHttpCtxView {
+            ctx: &mut ctx,
+            table: &mut table,
+            hooks: Default::default(),
+        };
+
+        // Without an informational callback the host reports that 1xx
+        // responses aren't supported.
+        let out = view.new_response_outparam_from_callback(|_| {})?;
+        let headers = types::HostFields::new(&mut view)?;
+        let err = types::HostResponseOutparam::send_informational(
+            &mut view,
+            Resource::new_borrow(out.rep()),
+            103,
+            headers,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast()?,
+            types::ErrorCode::InternalError(Some(_))
+        ));
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let out = view.new_response_outparam_with_informational(|_| {}, {
+            let sent = sent.clone();
+            move |status, headers| sent.lock().unwrap().push((status, headers))
+        })?;
+
+        // Statuses outside of the 1xx range are rejected.
+        let headers = types::HostFields::new(&mut view)?;
+        let err = types::HostResponseOutparam::send_informational(
+            &mut view,
+            Resource::new_borrow(out.rep()),
+            200,
+            headers,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast()?,
+            types::ErrorCode::HttpProtocolError
+        ));
+
+        // Any number of informational responses may precede the final one.
+        for _ in 0..2 {
+            let headers = types::HostFields::from_list(
+                &mut view,
+                vec![("link".to_string(), b"</style.css>; rel=preload".to_vec())],
+            )
+            .unwrap();
+            types::HostResponseOutparam::send_informational(
+                &mut view,
+                Resource::new_borrow(out.rep()),
+                103,
+                headers,
+            )
+            .unwrap();
+        }
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        for (status, headers) in sent.iter() {
+            assert_eq!(status.as_u16(), 103);
+            assert_eq!(headers["link"], "</style.css>; rel=preload");
+        }
+        Ok(())
+    }
+}
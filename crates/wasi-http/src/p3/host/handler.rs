@@ -9,7 +9,7 @@ use http_body_util::BodyExt as _;
 use std::sync::Arc;
 use tokio::sync::oneshot;
 use tokio::task::{self, JoinHandle};
-use tracing::debug;
+use tracing::{Instrument, debug};
 use wasmtime::component::{Accessor, Resource};
 use wasmtime::error::Context as _;
 
@@ -52,7 +52,7 @@ impl<T> HostWithStore<T> for WasiHttp {
         let (res_result_tx, res_result_rx) = oneshot::channel();
 
         let getter = store.getter();
-        let fut = store.with(|mut store| {
+        let (fut, span, policy, options, mut redirects) = store.with(|mut store| {
             let WasiHttpCtxView { table, .. } = store.get();
             let req = table
                 .delete(req)
@@ -60,9 +60,17 @@ impl<T> HostWithStore<T> for WasiHttp {
                 .map_err(HttpError::trap)?;
             let (req, options) =
                 req.into_http_with_getter(&mut store, io_task_result(io_result_rx), getter)?;
-            HttpResult::Ok(store.get().hooks.send_request(
+            let options = options.as_deref().copied();
+            let mut view = store.get();
+            let (req, span) = view
+                .ctx
+                .prepare_outgoing_request(req)
+                .map_err(|e| view.error_to_p3(&e))?;
+            let policy = view.ctx.policy.clone();
+            let redirects = view.ctx.follow_redirects(&req);
+            let fut = store.get().hooks.send_request(
                 req.map(|body| body.with_state(io_task_rx).boxed_unsync()),
-                options.as_deref().copied(),
+                options,
                 Box::new(async {
                     // Forward the response processing result to `WasiHttpCtx` implementation
                     let Ok(fut) = res_result_rx.await else {
@@ -70,11 +78,29 @@ impl<T> HostWithStore<T> for WasiHttp {
                     };
                     Box::into_pin(fut).await
                 }),
-            ))
+            );
+            HttpResult::Ok((fut, span, policy, options, redirects))
         })?;
-        let (res, io) = Box::into_pin(fut)
-            .await
-            .map_err(|e| store.with(|mut store| store.get().error_to_p3(&e)))?;
+        let (res, io) = async {
+            let (mut res, mut io) = Box::into_pin(fut).await?;
+            loop {
+                let checked = policy.check_response(res)?;
+                let next = match &mut redirects {
+                    Some(redirects) => redirects.next_request(&checked)?,
+                    None => None,
+                };
+                let Some(next) = next else {
+                    return Ok((checked, io));
+                };
+                // Each hop goes through the hooks and the policies like the
+                // original request did.
+                let fut = store.with(|mut store| store.get().send_redirect(next, options))?;
+                (res, io) = Box::into_pin(fut).await?;
+            }
+        }
+        .instrument(span)
+        .await
+        .map_err(|e| store.with(|mut store| store.get().error_to_p3(&e)))?;
         let (
             http::response::Parts {
                 status, headers, ..
@@ -0,0 +1,361 @@
+//! Limits and policies enforced on the outgoing requests of guests.
+//!
+//! These are configured per [`WasiHttpCtx`](crate::WasiHttpCtx), for example
+//! with [`set_max_incoming_body_size`](crate::WasiHttpCtx::set_max_incoming_body_size)
+//! or [`set_allowed_methods`](crate::WasiHttpCtx::set_allowed_methods), and
+//! are enforced by the `handle` and `send` implementations of this crate
+//! regardless of how [`WasiHttpHooks::send_request`](crate::WasiHttpHooks::send_request)
+//! is implemented.
+
+use crate::{Error, WasiBody};
+use bytes::Bytes;
+use http::uri::Scheme;
+use http::{HeaderMap, Method};
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::BodyExt as _;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+/// How redirect responses to the outgoing requests of guests are handled,
+/// configured with
+/// [`WasiHttpCtx::set_redirect_policy`](crate::WasiHttpCtx::set_redirect_policy).
+///
+/// Each request sent to follow a redirect is checked against the other
+/// policies of the context and is sent through
+/// [`WasiHttpHooks::send_request`](crate::WasiHttpHooks::send_request), just
+/// like the request of the guest. Only redirects which can be followed without
+/// resending the request body are followed, that is those resulting in a `GET`
+/// or `HEAD` request; other redirect responses are returned to the guest.
+///
+/// With `wasi:http@0.2`, the next request is sent by
+/// `future-incoming-response.get`, which returns no response yet in that case
+/// even though the pollable of the future was ready.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RedirectPolicy {
+    /// Redirect responses are returned to the guest as is.
+    #[default]
+    None,
+    /// Redirects to the origin of the request are followed, up to the given
+    /// number of times.
+    SameOrigin(usize),
+    /// Redirects to any origin are followed, up to the given number of times.
+    ///
+    /// The `authorization`, `cookie` and `proxy-authorization` headers are
+    /// removed from requests redirected to another origin. Requests signed by
+    /// a [`RequestSigner`](crate::RequestSigner) are only redirected to their
+    /// own origin, as the headers added by the signer aren't known.
+    Any(usize),
+}
+
+/// The limits and policies configured on a `WasiHttpCtx`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OutgoingPolicy {
+    pub(crate) max_outgoing_body_size: Option<u64>,
+    pub(crate) max_incoming_body_size: Option<u64>,
+    pub(crate) max_header_count: Option<usize>,
+    pub(crate) max_header_size: Option<usize>,
+    pub(crate) allowed_methods: Option<Arc<[Method]>>,
+    pub(crate) allowed_schemes: Option<Arc<[Scheme]>>,
+    pub(crate) redirects: RedirectPolicy,
+}
+
+impl OutgoingPolicy {
+    /// Checks the head of an outgoing request against this policy and limits
+    /// the size of its body.
+    pub(crate) fn check_request(
+        &self,
+        request: http::Request<WasiBody>,
+    ) -> Result<http::Request<WasiBody>, Error> {
+        if !self.is_allowed_method(request.method())
+            || !self.is_allowed_scheme(request.uri().scheme())
+        {
+            return Err(Error::HttpRequestDenied);
+        }
+        self.check_headers(request.headers()).map_err(|e| match e {
+            HeaderLimit::Count => Error::HttpRequestHeaderSectionSize(None),
+            HeaderLimit::Size(field_name, field_size) => Error::HttpRequestHeaderSize {
+                field_name: Some(field_name),
+                field_size: Some(field_size),
+            },
+        })?;
+        Ok(match self.max_outgoing_body_size {
+            Some(limit) => request.map(|body| {
+                LimitedBody::new(body, limit, Error::HttpRequestBodySize).boxed_unsync()
+            }),
+            None => request,
+        })
+    }
+
+    /// Checks the head of a response to an outgoing request against this
+    /// policy and limits the size of its body.
+    pub(crate) fn check_response(
+        &self,
+        response: http::Response<WasiBody>,
+    ) -> Result<http::Response<WasiBody>, Error> {
+        self.check_headers(response.headers())
+            .map_err(|e| match e {
+                HeaderLimit::Count => Error::HttpResponseHeaderSectionSize(None),
+                HeaderLimit::Size(field_name, field_size) => Error::HttpResponseHeaderSize {
+                    field_name: Some(field_name),
+                    field_size: Some(field_size),
+                },
+            })?;
+        Ok(match self.max_incoming_body_size {
+            Some(limit) => response.map(|body| {
+                LimitedBody::new(body, limit, Error::HttpResponseBodySize).boxed_unsync()
+            }),
+            None => response,
+        })
+    }
+
+    fn is_allowed_method(&self, method: &Method) -> bool {
+        self.allowed_methods
+            .as_ref()
+            .is_none_or(|methods| methods.contains(method))
+    }
+
+    fn is_allowed_scheme(&self, scheme: Option<&Scheme>) -> bool {
+        match (&self.allowed_schemes, scheme) {
+            (None, _) => true,
+            (Some(schemes), Some(scheme)) => schemes.contains(scheme),
+            (Some(_), None) => false,
+        }
+    }
+
+    fn check_headers(&self, headers: &HeaderMap) -> Result<(), HeaderLimit> {
+        if self.max_header_count.is_some_and(|max| headers.len() > max) {
+            return Err(HeaderLimit::Count);
+        }
+        if let Some(max) = self.max_header_size {
+            for (name, value) in headers {
+                let size = name.as_str().len() + value.len();
+                if size > max {
+                    return Err(HeaderLimit::Size(
+                        name.to_string(),
+                        u32::try_from(size).unwrap_or(u32::MAX),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+enum HeaderLimit {
+    Count,
+    Size(String, u32),
+}
+
+/// A body which fails with `error` once more than `limit` bytes of data have
+/// been read from it.
+struct LimitedBody {
+    body: WasiBody,
+    limit: u64,
+    read: u64,
+    error: fn(Option<u64>) -> Error,
+}
+
+impl LimitedBody {
+    fn new(body: WasiBody, limit: u64, error: fn(Option<u64>) -> Error) -> Self {
+        Self {
+            body,
+            limit,
+            read: 0,
+            error,
+        }
+    }
+}
+
+impl Body for LimitedBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Error>>> {
+        let frame = ready!(Pin::new(&mut self.body).poll_frame(cx));
+        if let Some(data) = frame.as_ref().and_then(|f| f.as_ref().ok()?.data_ref()) {
+            self.read += data.len() as u64;
+            if self.read > self.limit {
+                return Poll::Ready(Some(Err((self.error)(Some(self.read)))));
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+/// The state of following the redirects of an outgoing request, kept by the
+/// `handle` and `send` implementations of this crate between hops.
+#[derive(Clone, Debug)]
+pub(crate) struct FollowRedirects {
+    pub(crate) remaining: usize,
+    pub(crate) cross_origin: bool,
+    pub(crate) allowed_schemes: Option<Arc<[Scheme]>>,
+    /// The head of the request sent last.
+    pub(crate) method: Method,
+    pub(crate) uri: http::Uri,
+    pub(crate) headers: HeaderMap,
+}
+
+impl FollowRedirects {
+    /// Returns the request to send in response to the redirect `response` to
+    /// the request sent last, or `None` if `response` should be returned as
+    /// is.
+    ///
+    /// The returned request is yet to be checked against the policies of the
+    /// context and is to be sent through
+    /// [`WasiHttpHooks::send_request`](crate::WasiHttpHooks::send_request)
+    /// like any other outgoing request.
+    pub(crate) fn next_request<B>(
+        &mut self,
+        response: &http::Response<B>,
+    ) -> Result<Option<http::Request<WasiBody>>, Error> {
+        use http::{StatusCode, header};
+
+        let method = match response.status() {
+            StatusCode::SEE_OTHER if self.method != Method::HEAD => Method::GET,
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND if self.method == Method::POST => {
+                Method::GET
+            }
+            StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::SEE_OTHER
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT => self.method.clone(),
+            _ => return Ok(None),
+        };
+        if method != Method::GET && method != Method::HEAD {
+            return Ok(None);
+        }
+        let Some(uri) = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| resolve_location(&self.uri, location))
+        else {
+            return Ok(None);
+        };
+        let scheme = uri.scheme().expect("resolved URIs are absolute");
+        if *scheme != Scheme::HTTP && *scheme != Scheme::HTTPS {
+            return Ok(None);
+        }
+        let same_origin = origin(&uri) == origin(&self.uri);
+        if !same_origin && !self.cross_origin {
+            return Ok(None);
+        }
+        if let Some(schemes) = &self.allowed_schemes {
+            if !schemes.contains(scheme) {
+                return Err(Error::HttpRequestDenied);
+            }
+        }
+        self.remaining = self.remaining.checked_sub(1).ok_or(Error::LoopDetected)?;
+
+        let mut headers = self.headers.clone();
+        if method != self.method {
+            for name in [
+                header::CONTENT_LENGTH,
+                header::CONTENT_TYPE,
+                header::CONTENT_ENCODING,
+                header::TRANSFER_ENCODING,
+            ] {
+                headers.remove(name);
+            }
+        }
+        if !same_origin {
+            for name in [
+                header::AUTHORIZATION,
+                header::COOKIE,
+                header::PROXY_AUTHORIZATION,
+            ] {
+                headers.remove(name);
+            }
+        }
+        if headers.contains_key(header::HOST) {
+            let authority = uri.authority().expect("resolved URIs are absolute");
+            let host = http::HeaderValue::from_str(authority.as_str())
+                .map_err(|_| Error::HttpRequestUriInvalid)?;
+            headers.insert(header::HOST, host);
+        }
+
+        self.method = method;
+        self.uri = uri;
+        self.headers = headers;
+        let mut next = http::Request::new(
+            http_body_util::Empty::new()
+                .map_err(|e| match e {})
+                .boxed_unsync(),
+        );
+        *next.method_mut() = self.method.clone();
+        *next.uri_mut() = self.uri.clone();
+        *next.headers_mut() = self.headers.clone();
+        Ok(Some(next))
+    }
+}
+
+/// Resolves the `location` of a redirect response against the `base` URI of
+/// the request which was redirected.
+fn resolve_location(base: &http::Uri, location: &str) -> Option<http::Uri> {
+    if location.contains("://") {
+        let uri = location.parse::<http::Uri>().ok()?;
+        return (uri.scheme().is_some() && uri.authority().is_some()).then_some(uri);
+    }
+    let mut parts = http::uri::Parts::default();
+    parts.scheme = base.scheme().cloned();
+    if let Some(rest) = location.strip_prefix("//") {
+        let (authority, path) = rest.split_at(rest.find(['/', '?']).unwrap_or(rest.len()));
+        parts.authority = Some(authority.parse().ok()?);
+        parts.path_and_query = Some(if path.is_empty() { "/" } else { path }.parse().ok()?);
+    } else {
+        parts.authority = base.authority().cloned();
+        let path = if location.starts_with('/') {
+            location.to_string()
+        } else {
+            let dir = &base.path()[..base.path().rfind('/').map_or(0, |i| i + 1)];
+            if location.starts_with('?') {
+                format!("{}{location}", base.path())
+            } else {
+                format!("{dir}{location}")
+            }
+        };
+        parts.path_and_query = Some(path.parse().ok()?);
+    }
+    http::Uri::from_parts(parts).ok()
+}
+
+/// Returns the scheme, host and port of an absolute URI.
+fn origin(uri: &http::Uri) -> (Option<&str>, Option<&str>, Option<u16>) {
+    let port = uri.port_u16().or(match uri.scheme() {
+        Some(scheme) if *scheme == Scheme::HTTP => Some(80),
+        Some(scheme) if *scheme == Scheme::HTTPS => Some(443),
+        _ => None,
+    });
+    (uri.scheme_str(), uri.host(), port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_location;
+
+    #[test]
+    fn resolves_redirect_locations() {
+        let base = "http://example.com/a/b?q".parse().unwrap();
+        let resolve = |location| resolve_location(&base, location).unwrap().to_string();
+        assert_eq!(resolve("https://other.com/c"), "https://other.com/c");
+        assert_eq!(resolve("//other.com/c?d"), "http://other.com/c?d");
+        assert_eq!(resolve("//other.com"), "http://other.com/");
+        assert_eq!(resolve("/c"), "http://example.com/c");
+        assert_eq!(resolve("c?d"), "http://example.com/a/c?d");
+        assert_eq!(resolve("?d"), "http://example.com/a/b?d");
+        assert!(resolve_location(&base, "https://").is_none());
+    }
+}
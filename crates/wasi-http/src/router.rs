@@ -0,0 +1,282 @@
+//! Routing of incoming requests to one of several handlers, for example one
+//! [`ProxyHandler`](crate::handler::ProxyHandler) per component, by their
+//! `Host` header and path.
+
+use http::header;
+use std::fmt;
+use std::str::FromStr;
+use wasmtime::{Error, format_err};
+
+/// The requests matched by an entry of a [`Router`].
+///
+/// A route matches requests with the given path prefix and, if a host is
+/// specified, a `Host` header naming that host. Prefixes match whole path
+/// segments, so `/api` matches `/api` and `/api/users` but not `/apis`.
+///
+/// Routes can be parsed from strings of the form `host`, `/prefix` or
+/// `host/prefix`, such as `example.com/api`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Route {
+    host: Option<String>,
+    path_prefix: String,
+}
+
+impl Route {
+    /// Creates a route matching requests for `host`, if specified, whose path
+    /// starts with `path_prefix`.
+    ///
+    /// Returns an error if `path_prefix` doesn't start with `/` or if `host`
+    /// isn't a valid host name.
+    pub fn new(host: Option<&str>, path_prefix: &str) -> Result<Route, Error> {
+        if !path_prefix.starts_with('/') {
+            return Err(format_err!(
+                "path prefix `{path_prefix}` doesn't start with `/`"
+            ));
+        }
+        let host = match host {
+            Some(host) => {
+                if host.is_empty() || host.parse::<http::uri::Authority>().is_err() {
+                    return Err(format_err!("invalid host `{host}` in route"));
+                }
+                Some(host.to_ascii_lowercase())
+            }
+            None => None,
+        };
+        Ok(Route {
+            host,
+            path_prefix: path_prefix.to_string(),
+        })
+    }
+
+    /// The host matched by this route, if it's limited to one.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// The path prefix matched by this route.
+    pub fn path_prefix(&self) -> &str {
+        &self.path_prefix
+    }
+
+    fn matches(&self, host: Option<&str>, path: &str) -> bool {
+        if let Some(expected) = &self.host {
+            match host {
+                Some(host) if hosts_match(expected, host) => {}
+                _ => return false,
+            }
+        }
+        let prefix = self.path_prefix.trim_end_matches('/');
+        match path.strip_prefix(prefix) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
+/// Compares the host of a route with a request's `Host` header, which may
+/// also include a port.
+fn hosts_match(expected: &str, host: &str) -> bool {
+    if expected.eq_ignore_ascii_case(host) {
+        return true;
+    }
+    let without_port = match host.rsplit_once(':') {
+        Some((name, port)) if !name.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => return false,
+    };
+    expected.eq_ignore_ascii_case(without_port)
+}
+
+impl FromStr for Route {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Route, Error> {
+        match s.find('/') {
+            Some(0) => Route::new(None, s),
+            Some(i) => Route::new(Some(&s[..i]), &s[i..]),
+            None => Route::new(Some(s), "/"),
+        }
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(host) = &self.host {
+            f.write_str(host)?;
+        }
+        f.write_str(&self.path_prefix)
+    }
+}
+
+/// Dispatches requests to one of several handlers by their [`Route`].
+///
+/// When several routes match a request those limited to a host take
+/// precedence, followed by those with the longest path prefix. The path of
+/// the request is passed on to the handler as is.
+///
+/// ```
+/// use wasmtime_wasi_http::router::Router;
+///
+/// # fn main() -> wasmtime::Result<()> {
+/// let mut router = Router::new();
+/// router.add("/".parse()?, "default");
+/// router.add("/api".parse()?, "api");
+/// router.add("admin.example.com".parse()?, "admin");
+///
+/// assert_eq!(router.lookup(Some("example.com"), "/api/users"), Some(&"api"));
+/// assert_eq!(router.lookup(Some("example.com"), "/apis"), Some(&"default"));
+/// assert_eq!(router.lookup(Some("admin.example.com:8080"), "/api"), Some(&"admin"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Router<H> {
+    routes: Vec<(Route, H)>,
+}
+
+impl<H> Default for Router<H> {
+    fn default() -> Self {
+        Self { routes: Vec::new() }
+    }
+}
+
+impl<H> Router<H> {
+    /// Creates a router without any routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dispatches requests matching `route` to `handler`.
+    ///
+    /// If `route` was already added then its handler is replaced.
+    pub fn add(&mut self, route: Route, handler: H) -> &mut Self {
+        match self.routes.iter_mut().find(|(r, _)| *r == route) {
+            Some((_, h)) => *h = handler,
+            None => self.routes.push((route, handler)),
+        }
+        self
+    }
+
+    /// Returns the handler for requests for `host` with the given `path`, or
+    /// `None` if no route matches.
+    pub fn lookup(&self, host: Option<&str>, path: &str) -> Option<&H> {
+        let mut best: Option<&(Route, H)> = None;
+        for entry @ (route, _) in &self.routes {
+            if !route.matches(host, path) {
+                continue;
+            }
+            let key = |r: &Route| (r.host.is_some(), r.path_prefix.trim_end_matches('/').len());
+            if best.is_none_or(|(b, _)| key(route) > key(b)) {
+                best = Some(entry);
+            }
+        }
+        best.map(|(_, h)| h)
+    }
+
+    /// Returns the handler for `request`, or `None` if no route matches.
+    ///
+    /// The host is taken from the `Host` header of the request, or from its
+    /// URI if there's no such header as is the case with HTTP/2.
+    pub fn route<B>(&self, request: &http::Request<B>) -> Option<&H> {
+        let host = request
+            .headers()
+            .get(header::HOST)
+            .and_then(|h| h.to_str().ok())
+            .or_else(|| request.uri().host());
+        self.lookup(host, request.uri().path())
+    }
+
+    /// Returns an iterator over the routes of this router and their handlers,
+    /// in the order they were added.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&Route, &H)> + '_ {
+        self.routes.iter().map(|(r, h)| (r, h))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Route, Router};
+
+    fn router() -> Router<&'static str> {
+        let mut router = Router::new();
+        router.add("/".parse().unwrap(), "root");
+        router.add("/api/".parse().unwrap(), "api");
+        router.add("/api/v2".parse().unwrap(), "api-v2");
+        router.add("Example.com".parse().unwrap(), "example");
+        router.add("example.com/api".parse().unwrap(), "example-api");
+        router
+    }
+
+    #[test]
+    fn parse_routes() {
+        let route: Route = "example.com/api".parse().unwrap();
+        assert_eq!(route.host(), Some("example.com"));
+        assert_eq!(route.path_prefix(), "/api");
+        assert_eq!(route.to_string(), "example.com/api");
+
+        let route: Route = "example.com".parse().unwrap();
+        assert_eq!(route.host(), Some("example.com"));
+        assert_eq!(route.path_prefix(), "/");
+
+        let route: Route = "/static".parse().unwrap();
+        assert_eq!(route.host(), None);
+        assert_eq!(route.path_prefix(), "/static");
+
+        assert!("".parse::<Route>().is_err());
+        assert!("exa mple.com/".parse::<Route>().is_err());
+        assert!(Route::new(None, "api").is_err());
+    }
+
+    #[test]
+    fn lookup_by_path() {
+        let router = router();
+        assert_eq!(router.lookup(None, "/"), Some(&"root"));
+        assert_eq!(router.lookup(None, "/index.html"), Some(&"root"));
+        assert_eq!(router.lookup(None, "/api"), Some(&"api"));
+        assert_eq!(router.lookup(None, "/api/users"), Some(&"api"));
+        assert_eq!(router.lookup(None, "/apis"), Some(&"root"));
+        assert_eq!(router.lookup(None, "/api/v2/users"), Some(&"api-v2"));
+        assert_eq!(router.lookup(None, "/api/v20"), Some(&"api"));
+    }
+
+    #[test]
+    fn lookup_by_host() {
+        let router = router();
+        assert_eq!(router.lookup(Some("example.com"), "/"), Some(&"example"));
+        assert_eq!(router.lookup(Some("EXAMPLE.com:80"), "/"), Some(&"example"));
+        assert_eq!(
+            router.lookup(Some("example.com"), "/api/v2"),
+            Some(&"example-api")
+        );
+        assert_eq!(router.lookup(Some("example.org"), "/api"), Some(&"api"));
+        assert_eq!(router.lookup(Some("example.com.evil"), "/"), Some(&"root"));
+    }
+
+    #[test]
+    fn no_match() {
+        let mut router = Router::new();
+        router.add("example.com/api".parse().unwrap(), ());
+        assert_eq!(router.lookup(None, "/api"), None);
+        assert_eq!(router.lookup(Some("example.com"), "/"), None);
+
+        let request = http::Request::builder()
+            .uri("/api/users")
+            .header("host", "example.com:8080")
+            .body(())
+            .unwrap();
+        assert_eq!(router.route(&request), Some(&()));
+        let request = http::Request::builder()
+            .uri("http://example.com/api")
+            .body(())
+            .unwrap();
+        assert_eq!(router.route(&request), Some(&()));
+    }
+
+    #[test]
+    fn replace_route() {
+        let mut router = Router::new();
+        router.add("/".parse().unwrap(), 1);
+        router.add("/".parse().unwrap(), 2);
+        assert_eq!(router.iter().len(), 1);
+        assert_eq!(router.lookup(None, "/"), Some(&2));
+    }
+}
@@ -0,0 +1,218 @@
+//! TLS configuration for the outgoing HTTPS requests of guests.
+//!
+//! This is configured per [`WasiHttpCtx`](crate::WasiHttpCtx) with
+//! [`set_tls_config`](crate::WasiHttpCtx::set_tls_config) and
+//! [`set_tls_config_for`](crate::WasiHttpCtx::set_tls_config_for), and is
+//! used by [`default_send_request`](crate::default_send_request) when it
+//! connects to a server.
+
+use crate::Error;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+/// TLS settings used when connecting to servers for the outgoing HTTPS
+/// requests of guests.
+///
+/// By default servers are verified against the Mozilla root certificates
+/// bundled in the `webpki-roots` crate, no client certificate is presented
+/// and no ALPN protocols are offered.
+///
+/// These settings are only used by [`default_send_request`], so they have no
+/// effect with custom implementations of
+/// [`WasiHttpHooks::send_request`](crate::WasiHttpHooks::send_request) which
+/// don't use it.
+///
+/// [`default_send_request`]: crate::default_send_request
+///
+/// # Example
+///
+/// ```
+/// use rustls::RootCertStore;
+/// use wasmtime_wasi_http::{TlsConfig, WasiHttpCtx};
+///
+/// # fn internal_ca() -> RootCertStore { RootCertStore::empty() }
+/// let mut tls = TlsConfig::new();
+/// tls.set_root_certificates(internal_ca())
+///     .set_alpn_protocols(["http/1.1"]);
+///
+/// let mut ctx = WasiHttpCtx::new();
+/// ctx.set_tls_config_for("internal.example.com", tls);
+/// ```
+#[derive(Clone, Default)]
+pub struct TlsConfig {
+    root_certificates: Option<Arc<rustls::RootCertStore>>,
+    client_auth: Option<Arc<ClientAuth>>,
+    server_name: Option<ServerName<'static>>,
+    alpn_protocols: Vec<Vec<u8>>,
+}
+
+struct ClientAuth {
+    cert_chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+}
+
+impl TlsConfig {
+    /// Creates a new configuration with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the root certificates that servers are verified against,
+    /// replacing the bundled `webpki-roots` ones.
+    pub fn set_root_certificates(&mut self, roots: rustls::RootCertStore) -> &mut Self {
+        self.root_certificates = Some(Arc::new(roots));
+        self
+    }
+
+    /// Sets the certificate chain and private key presented to servers which
+    /// request client authentication.
+    ///
+    /// The first certificate of `cert_chain` is the client's own, and must
+    /// match `key`. Connecting fails with a TLS error if `key` can't be used.
+    pub fn set_client_auth(
+        &mut self,
+        cert_chain: Vec<CertificateDer<'static>>,
+        key: PrivateKeyDer<'static>,
+    ) -> &mut Self {
+        self.client_auth = Some(Arc::new(ClientAuth { cert_chain, key }));
+        self
+    }
+
+    /// Sets the server name sent with SNI and used to verify the server's
+    /// certificate, instead of the host of the request.
+    pub fn set_server_name(&mut self, name: ServerName<'static>) -> &mut Self {
+        self.server_name = Some(name);
+        self
+    }
+
+    /// Sets the ALPN protocols offered to servers, in order of preference.
+    ///
+    /// Note that requests are always sent using HTTP/1.1 regardless of the
+    /// protocol the server selects.
+    pub fn set_alpn_protocols(
+        &mut self,
+        protocols: impl IntoIterator<Item = impl Into<Vec<u8>>>,
+    ) -> &mut Self {
+        self.alpn_protocols = protocols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the server name set with [`TlsConfig::set_server_name`], if
+    /// any.
+    pub(crate) fn server_name(&self) -> Option<&ServerName<'static>> {
+        self.server_name.as_ref()
+    }
+
+    /// Builds the connector used to establish connections with these
+    /// settings.
+    pub(crate) fn connector(&self) -> Result<tokio_rustls::TlsConnector, Error> {
+        // derived from https://github.com/rustls/rustls/blob/main/examples/src/bin/simpleclient.rs
+        let roots = match &self.root_certificates {
+            Some(roots) => roots.clone(),
+            None => Arc::new(rustls::RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+            }),
+        };
+        let builder = rustls::ClientConfig::builder().with_root_certificates(roots);
+        let mut config = match &self.client_auth {
+            Some(auth) => builder
+                .with_client_auth_cert(auth.cert_chain.clone(), auth.key.clone_key())
+                .map_err(|e| Error::Tls(io::Error::other(e)))?,
+            None => builder.with_no_client_auth(),
+        };
+        config.alpn_protocols = self.alpn_protocols.clone();
+        Ok(tokio_rustls::TlsConnector::from(Arc::new(config)))
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field(
+                "root_certificates",
+                &self.root_certificates.as_ref().map(|r| r.len()),
+            )
+            .field("client_auth", &self.client_auth.is_some())
+            .field("server_name", &self.server_name)
+            .field("alpn_protocols", &self.alpn_protocols)
+            .finish()
+    }
+}
+
+/// The TLS configurations of a `WasiHttpCtx`, attached to its outgoing
+/// requests.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TlsPolicy {
+    pub(crate) default: Option<TlsConfig>,
+    /// Configurations for specific authorities, keyed by lowercase `host` or
+    /// `host:port`.
+    pub(crate) authorities: HashMap<String, TlsConfig>,
+}
+
+impl TlsPolicy {
+    /// Returns the configuration to use when connecting to `host` on `port`.
+    ///
+    /// A configuration for `host:port` takes precedence over one for `host`,
+    /// which takes precedence over the default configuration.
+    pub(crate) fn get(&self, host: &str, port: u16) -> Option<&TlsConfig> {
+        let host = host.to_ascii_lowercase();
+        self.authorities
+            .get(&format!("{host}:{port}"))
+            .or_else(|| self.authorities.get(&host))
+            .or(self.default.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authority_overrides() {
+        let config = |alpn: &str| {
+            let mut config = TlsConfig::new();
+            config.set_alpn_protocols([alpn]);
+            config
+        };
+        let alpn = |config: Option<&TlsConfig>| config.map(|c| c.alpn_protocols[0].clone());
+
+        let mut policy = TlsPolicy::default();
+        assert!(policy.get("example.com", 443).is_none());
+
+        policy.default = Some(config("default"));
+        policy
+            .authorities
+            .insert("example.com".to_string(), config("host"));
+        policy
+            .authorities
+            .insert("example.com:8443".to_string(), config("port"));
+
+        assert_eq!(
+            alpn(policy.get("other.com", 443)),
+            Some(b"default".to_vec())
+        );
+        assert_eq!(alpn(policy.get("example.com", 443)), Some(b"host".to_vec()));
+        assert_eq!(alpn(policy.get("Example.COM", 443)), Some(b"host".to_vec()));
+        assert_eq!(
+            alpn(policy.get("example.com", 8443)),
+            Some(b"port".to_vec())
+        );
+    }
+
+    #[test]
+    fn invalid_client_key() {
+        let mut config = TlsConfig::new();
+        config.set_client_auth(
+            vec![CertificateDer::from(vec![0; 16])],
+            PrivateKeyDer::Pkcs8(vec![0; 16].into()),
+        );
+        assert!(matches!(config.connector(), Err(Error::Tls(_))));
+
+        let mut config = TlsConfig::new();
+        config.set_alpn_protocols(["h2", "http/1.1"]);
+        assert!(config.connector().is_ok());
+    }
+}
@@ -14,8 +14,10 @@ use wasmtime::{
     format_err,
 };
 use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView, p2::pipe::MemoryOutputPipe};
+use wasmtime_wasi_correlation::{Correlation, CorrelationContext};
 use wasmtime_wasi_http::{
-    Error, RequestOptions, WasiBody, WasiHttpCtx, WasiHttpCtxView, WasiHttpHooks, WasiHttpView,
+    Error, RedirectPolicy, RequestOptions, RequestSigner, WasiBody, WasiHttpCtx, WasiHttpCtxView,
+    WasiHttpHooks, WasiHttpView,
     io::TokioIo,
     p2::bindings::http::types::{ErrorCode, Scheme},
 };
@@ -82,6 +84,7 @@ impl WasiHttpHooks for MyHttpHooks {
     > {
         let me = self.clone();
         Box::new(async move {
+            let request = wasmtime_wasi_http::sign_request(request).await?;
             if let Some(rejected_authority) = &me.rejected_authority {
                 let authority = request.uri().authority().map(ToString::to_string).unwrap();
                 if &authority == rejected_authority {
@@ -154,6 +157,29 @@ async fn run_wasi_http(
     rejected_authority: Option<String>,
     early_drop: bool,
     field_size_limit: Option<usize>,
+) -> wasmtime::Result<Result<hyper::Response<Collected<Bytes>>, ErrorCode>> {
+    let mut http = WasiHttpCtx::new();
+    if let Some(limit) = field_size_limit {
+        http.set_field_size_limit(limit);
+    }
+    run_wasi_http_with_ctx(
+        component_filename,
+        req,
+        send_request,
+        rejected_authority,
+        early_drop,
+        http,
+    )
+    .await
+}
+
+async fn run_wasi_http_with_ctx(
+    component_filename: &str,
+    req: hyper::Request<BoxBody<Bytes, hyper::Error>>,
+    send_request: Option<RequestSender>,
+    rejected_authority: Option<String>,
+    early_drop: bool,
+    http: WasiHttpCtx,
 ) -> wasmtime::Result<Result<hyper::Response<Collected<Bytes>>, ErrorCode>> {
     let stdout = MemoryOutputPipe::new(4096);
     let stderr = MemoryOutputPipe::new(4096);
@@ -171,10 +197,6 @@ async fn run_wasi_http(
     builder.stdout(stdout.clone());
     builder.stderr(stderr.clone());
     let wasi = builder.build();
-    let mut http = WasiHttpCtx::new();
-    if let Some(limit) = field_size_limit {
-        http.set_field_size_limit(limit);
-    }
     let ctx = Ctx {
         table,
         wasi,
@@ -615,6 +637,230 @@ async fn wasi_http_without_port() -> Result<()> {
     Ok(())
 }
 
+#[test_log::test(tokio::test)]
+async fn wasi_http_request_signer() -> Result<()> {
+    struct TestSigner;
+
+    impl RequestSigner for TestSigner {
+        fn sign(
+            &self,
+            request: http::Request<WasiBody>,
+        ) -> Box<dyn Future<Output = Result<http::Request<WasiBody>, Error>> + Send> {
+            Box::new(async move {
+                let (mut parts, body) = request.into_parts();
+                let body = body.collect().await?.to_bytes();
+                let mut hasher = Sha256::new();
+                hasher.update(parts.method.as_str());
+                hasher.update(parts.uri.path());
+                hasher.update(&body);
+                use base64::Engine;
+                let signature =
+                    base64::engine::general_purpose::STANDARD_NO_PAD.encode(hasher.finalize());
+                parts
+                    .headers
+                    .insert("x-signature", signature.parse().unwrap());
+                let body = body::full(body).map_err(|e| e.into()).boxed_unsync();
+                Ok(http::Request::from_parts(parts, body))
+            })
+        }
+    }
+
+    let req = hyper::Request::builder()
+        .method(http::Method::GET)
+        .uri("http://example.com:8080/signed");
+
+    let send_request: RequestSender = Arc::new(|request: hyper::Request<WasiBody>, _opts| {
+        let mut hasher = Sha256::new();
+        hasher.update("GET/signed");
+        use base64::Engine;
+        let expected = base64::engine::general_purpose::STANDARD_NO_PAD.encode(hasher.finalize());
+        assert_eq!(
+            request.headers().get("x-signature").unwrap(),
+            expected.as_str()
+        );
+        let resp = hyper::Response::builder()
+            .status(StatusCode::OK)
+            .body(
+                body::full(Bytes::from("ok"))
+                    .map_err(|e| e.into())
+                    .boxed_unsync(),
+            )
+            .unwrap();
+        Ok(resp)
+    });
+
+    let mut http = WasiHttpCtx::new();
+    http.set_request_signer(TestSigner);
+    let response = run_wasi_http_with_ctx(
+        test_programs_artifacts::P2_API_PROXY_FORWARD_REQUEST_COMPONENT,
+        req.body(body::empty())?,
+        Some(send_request),
+        None,
+        false,
+        http,
+    )
+    .await??;
+
+    assert_eq!(StatusCode::OK, response.status());
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn wasi_http_correlation_headers() -> Result<()> {
+    let req = hyper::Request::builder()
+        .method(http::Method::GET)
+        .uri("http://example.com:8080/correlated");
+
+    let send_request: RequestSender = Arc::new(|request: hyper::Request<WasiBody>, _opts| {
+        assert_eq!(request.headers().get("x-request-id").unwrap(), "42");
+        assert_eq!(
+            request.headers().get("x-trace-id").unwrap(),
+            "4bf92f3577b34da6"
+        );
+        let resp = hyper::Response::builder()
+            .status(StatusCode::OK)
+            .body(
+                body::full(Bytes::from("ok"))
+                    .map_err(|e| e.into())
+                    .boxed_unsync(),
+            )
+            .unwrap();
+        Ok(resp)
+    });
+
+    let correlation = Correlation::new();
+    correlation.set(CorrelationContext::new(42).with_trace_id("4bf92f3577b34da6"));
+    let mut http = WasiHttpCtx::new();
+    http.set_correlation(correlation);
+    let response = run_wasi_http_with_ctx(
+        test_programs_artifacts::P2_API_PROXY_FORWARD_REQUEST_COMPONENT,
+        req.body(body::empty())?,
+        Some(send_request),
+        None,
+        false,
+        http,
+    )
+    .await??;
+
+    assert_eq!(StatusCode::OK, response.status());
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn wasi_http_outgoing_policy() -> Result<()> {
+    let request = hyper::Request::builder()
+        .method(http::Method::GET)
+        .uri("http://example.com:8080/hash-all");
+    let request = request.header("url", "http://denied.com/");
+    let request = request.header("url", "https://small.com/");
+    let request = request.header("url", "https://large.com/");
+    let request = request.body(body::empty())?;
+
+    let send_request: RequestSender = Arc::new(|request: hyper::Request<WasiBody>, _opts| {
+        assert_eq!(request.uri().scheme_str(), Some("https"));
+        let body = match request.uri().host() {
+            Some("small.com") => "ok",
+            _ => "way too large",
+        };
+        let resp = hyper::Response::builder()
+            .status(StatusCode::OK)
+            .body(
+                body::full(Bytes::from(body))
+                    .map_err(|e| e.into())
+                    .boxed_unsync(),
+            )
+            .unwrap();
+        Ok(resp)
+    });
+
+    let mut http = WasiHttpCtx::new();
+    http.set_allowed_methods([http::Method::GET]);
+    http.set_allowed_schemes([http::uri::Scheme::HTTPS]);
+    http.set_max_incoming_body_size(4);
+    let response = run_wasi_http_with_ctx(
+        test_programs_artifacts::P2_API_PROXY_STREAMING_COMPONENT,
+        request,
+        Some(send_request),
+        None,
+        false,
+        http,
+    )
+    .await??;
+
+    let body = response.into_body().to_bytes();
+    let body = str::from_utf8(&body).unwrap();
+    let line = |host| body.lines().find(|line| line.contains(host)).unwrap();
+    assert!(line("denied.com").contains("HttpRequestDenied"));
+    assert!(!line("small.com").contains("Http"));
+    assert!(line("large.com").contains("HttpResponseBodySize"));
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn wasi_http_redirect_through_hooks() -> Result<()> {
+    let request = hyper::Request::builder()
+        .method(http::Method::GET)
+        .uri("http://example.com:8080/hash-all");
+    let request = request.header("url", "https://redirect.com/");
+    let request = request.header("url", "https://followed.com/");
+    let request = request.body(body::empty())?;
+
+    let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let send_request: RequestSender = {
+        let sent = sent.clone();
+        Arc::new(move |request: hyper::Request<WasiBody>, _opts| {
+            let host = request.uri().host().unwrap().to_string();
+            sent.lock().unwrap().push(host.clone());
+            let (status, location) = match host.as_str() {
+                "redirect.com" => (StatusCode::FOUND, "https://rejected.com/"),
+                "followed.com" => (StatusCode::FOUND, "/target"),
+                "rejected.com" => return Err(Error::HttpRequestDenied),
+                _ => (StatusCode::OK, ""),
+            };
+            let resp = hyper::Response::builder()
+                .status(status)
+                .header(http::header::LOCATION, location)
+                .body(body::empty().map_err(|e| e.into()).boxed_unsync())
+                .unwrap();
+            Ok(resp)
+        })
+    };
+
+    let mut http = WasiHttpCtx::new();
+    http.set_redirect_policy(RedirectPolicy::Any(5));
+    let response = run_wasi_http_with_ctx(
+        test_programs_artifacts::P2_API_PROXY_STREAMING_COMPONENT,
+        request,
+        Some(send_request),
+        None,
+        false,
+        http,
+    )
+    .await??;
+
+    let body = response.into_body().to_bytes();
+    let body = str::from_utf8(&body).unwrap();
+    let line = |host| body.lines().find(|line| line.contains(host)).unwrap();
+    assert!(line("redirect.com").contains("HttpRequestDenied"));
+    assert!(!line("followed.com").contains("Http"));
+    let mut sent = sent.lock().unwrap().clone();
+    sent.sort();
+    assert_eq!(
+        sent,
+        [
+            "followed.com",
+            "followed.com",
+            "redirect.com",
+            "rejected.com"
+        ]
+    );
+
+    Ok(())
+}
+
 #[test_log::test(tokio::test)]
 async fn wasi_http_no_trap_on_early_drop() -> Result<()> {
     let req = hyper::Request::builder()
@@ -56,6 +56,10 @@ impl poll::Host for ResourceTable {
                     }
                 }
                 if any_ready {
+                    // Futures are polled in resource-table order, so report
+                    // ready pollables in the order the guest listed them to
+                    // keep the result independent of table allocation.
+                    results.sort_unstable();
                     Poll::Ready(results)
                 } else {
                     Poll::Pending
@@ -23,14 +23,15 @@ pub struct DynPollable {
 /// The trait used to implement [`DynPollable`] to create a `pollable`
 /// resource in `wasi:io/poll`.
 ///
-/// This trait is the internal implementation detail of any pollable resource in
-/// this crate's implementation of WASI. The `ready` function is an `async fn`
-/// which resolves when the implementation is ready. Using native `async` Rust
+/// This trait is the implementation detail of any pollable resource in this
+/// crate's implementation of WASI, and it can also be implemented by embedders
+/// for their own event sources. The `ready` function is an `async fn` which
+/// resolves when the implementation is ready. Using native `async` Rust
 /// enables this type's readiness to compose with other types' readiness
 /// throughout the WASI implementation.
 ///
-/// This trait is used in conjunction with [`subscribe`] to create a `pollable`
-/// resource.
+/// This trait is used in conjunction with [`subscribe`] or [`push_pollable`]
+/// to create a `pollable` resource.
 ///
 /// # Example
 ///
@@ -63,6 +64,95 @@ pub struct DynPollable {
 ///     }
 /// }
 /// ```
+///
+/// # Custom event sources
+///
+/// Pollables created from embedder-defined types are indistinguishable from
+/// the built-in ones, so a guest can wait on them with `wasi:io/poll` alongside
+/// streams, timers, and everything else. To hand them out through a custom
+/// WIT interface, alias `wasi:io/poll` to this crate's bindings in
+/// [`bindgen!`](wasmtime::component::bindgen) so that `pollable` is mapped to
+/// [`DynPollable`]:
+///
+/// ```
+/// # // stub out so we don't need a dep to build the doctests:
+/// # mod gpu { pub struct Fence; impl Fence { pub async fn wait(&self) {} pub fn device_idle() -> Fence { Fence } } }
+/// use wasmtime::component::{HasSelf, Linker, Resource, ResourceTable};
+/// use wasmtime::Result;
+/// use wasmtime_wasi_io::{IoView, async_trait};
+/// use wasmtime_wasi_io::poll::{DynPollable, Pollable, push_pollable, subscribe};
+///
+/// wasmtime::component::bindgen!({
+///     path: "wit",
+///     world: "my:gpu/host",
+///     inline: "
+///         package my:gpu;
+///
+///         interface fences {
+///             use wasi:io/poll@0.2.12.{pollable};
+///
+///             resource fence {
+///                 subscribe: func() -> pollable;
+///             }
+///
+///             device-idle: func() -> pollable;
+///         }
+///
+///         world host {
+///             import fences;
+///         }
+///     ",
+///     with: {
+///         "wasi:io/poll": wasmtime_wasi_io::bindings::wasi::io::poll,
+///         "my:gpu/fences.fence": crate::gpu::Fence,
+///     },
+///     imports: { default: trappable },
+///     require_store_data_send: true,
+/// });
+///
+/// #[async_trait]
+/// impl Pollable for gpu::Fence {
+///     async fn ready(&mut self) {
+///         self.wait().await;
+///     }
+/// }
+///
+/// struct MyState {
+///     table: ResourceTable,
+/// }
+///
+/// impl IoView for MyState {
+///     fn table(&mut self) -> &mut ResourceTable {
+///         &mut self.table
+///     }
+/// }
+///
+/// impl my::gpu::fences::Host for MyState {
+///     fn device_idle(&mut self) -> Result<Resource<DynPollable>> {
+///         // The fence isn't otherwise visible to the guest, so the pollable
+///         // takes ownership of it.
+///         push_pollable(&mut self.table, gpu::Fence::device_idle())
+///     }
+/// }
+///
+/// impl my::gpu::fences::HostFence for MyState {
+///     fn subscribe(&mut self, fence: Resource<gpu::Fence>) -> Result<Resource<DynPollable>> {
+///         subscribe(&mut self.table, fence)
+///     }
+///
+///     fn drop(&mut self, fence: Resource<gpu::Fence>) -> Result<()> {
+///         self.table.delete(fence)?;
+///         Ok(())
+///     }
+/// }
+///
+/// fn add_to_linker(linker: &mut Linker<MyState>) -> Result<()> {
+///     wasmtime_wasi_io::add_to_linker_async(linker)?;
+///     my::gpu::fences::add_to_linker::<_, HasSelf<_>>(linker, |s| s)?;
+///     Ok(())
+/// }
+/// # fn main() {}
+/// ```
 #[async_trait::async_trait]
 pub trait Pollable: Send + 'static {
     /// An asynchronous function which resolves when this object's readiness
@@ -80,6 +170,13 @@ pub trait Pollable: Send + 'static {
     async fn ready(&mut self);
 }
 
+#[async_trait::async_trait]
+impl Pollable for Box<dyn Pollable> {
+    async fn ready(&mut self) {
+        (**self).ready().await
+    }
+}
+
 /// Creates a `wasi:io/poll.pollable` resource which is subscribed to the provided
 /// `resource`.
 ///
@@ -117,3 +214,17 @@ where
 
     Ok(table.push_child(pollable, &resource)?)
 }
+
+/// Creates a standalone `wasi:io/poll.pollable` resource from `value`.
+///
+/// This is a shorthand for pushing `value` into `table` and then calling
+/// [`subscribe`] with the owned resource, meaning that `value` is deleted
+/// along with the returned pollable. It's suitable for event sources which
+/// aren't otherwise exposed to the guest as a resource of their own.
+pub fn push_pollable<T>(table: &mut ResourceTable, value: T) -> Result<Resource<DynPollable>>
+where
+    T: Pollable,
+{
+    let resource = table.push(value)?;
+    subscribe(table, resource)
+}
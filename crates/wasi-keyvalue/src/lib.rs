@@ -58,6 +58,25 @@
 //! }
 //! ```
 //!
+//! # Versions and transactions
+//!
+//! Every value stored in a bucket has a version. Each write to a bucket,
+//! whether it's a single `set` or `delete`, an `increment`, or a batch
+//! operation, is applied as one atomic [`Transaction`] and assigns a new
+//! version, greater than any previous version in the bucket, to all of the
+//! values it writes. Values preset with
+//! [`WasiKeyValueCtxBuilder::in_memory_data`] start out at version 0.
+//!
+//! Notably this means that, unlike what the `wasi:keyvalue/batch` interface
+//! permits, batch operations are never partially applied.
+//!
+//! Host functions defined by the embedder can use versions for optimistic
+//! concurrency control: [`WasiKeyValue::get_versioned`] reads a value and its
+//! version, and [`WasiKeyValue::commit`] applies a transaction to multiple
+//! keys only if they are all still at the versions it requires.
+//! [`WasiKeyValue::compare_and_swap`] is a shorthand for a transaction on a
+//! single key.
+//!
 //! [wasi-keyvalue]: https://github.com/WebAssembly/wasi-keyvalue
 //! [wasi:cli]: https://docs.rs/wasmtime-wasi/latest
 //! [wasi:http]: https://docs.rs/wasmtime-wasi-http/latest
@@ -80,6 +99,7 @@ mod generated {
 
 use self::generated::wasi::keyvalue;
 use std::collections::HashMap;
+use std::fmt;
 use wasmtime::Result;
 use wasmtime::component::{HasData, Resource, ResourceTable, ResourceTableError};
 
@@ -98,9 +118,159 @@ impl From<ResourceTableError> for Error {
 
 #[doc(hidden)]
 pub struct Bucket {
-    in_memory_data: HashMap<String, Vec<u8>>,
+    in_memory_data: HashMap<String, Entry>,
+    /// The version assigned to values written by the next transaction.
+    next_version: u64,
+}
+
+struct Entry {
+    value: Vec<u8>,
+    version: u64,
+}
+
+impl Bucket {
+    fn new(data: &HashMap<String, Vec<u8>>) -> Self {
+        Self {
+            in_memory_data: data
+                .iter()
+                .map(|(key, value)| {
+                    let entry = Entry {
+                        value: value.clone(),
+                        version: 0,
+                    };
+                    (key.clone(), entry)
+                })
+                .collect(),
+            next_version: 1,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Vec<u8>> {
+        self.in_memory_data.get(key).map(|entry| &entry.value)
+    }
+
+    fn version(&self, key: &str) -> Option<u64> {
+        self.in_memory_data.get(key).map(|entry| entry.version)
+    }
+
+    /// Atomically applies `transaction`, returning the version assigned to
+    /// the values it wrote.
+    fn commit(&mut self, transaction: Transaction) -> Result<u64, TransactionConflict> {
+        for (key, expected) in &transaction.preconditions {
+            let actual = self.version(key);
+            if actual != *expected {
+                return Err(TransactionConflict {
+                    key: key.clone(),
+                    expected: *expected,
+                    actual,
+                });
+            }
+        }
+
+        let version = self.next_version;
+        self.next_version += 1;
+        for operation in transaction.operations {
+            match operation {
+                Operation::Set(key, value) => {
+                    self.in_memory_data.insert(key, Entry { value, version });
+                }
+                Operation::Delete(key) => {
+                    self.in_memory_data.remove(&key);
+                }
+            }
+        }
+        Ok(version)
+    }
 }
 
+/// A group of writes to a bucket which are applied atomically.
+///
+/// A transaction is applied with [`WasiKeyValue::commit`]. Either all of its
+/// operations are applied, in the order they were added, or, if any of its
+/// preconditions doesn't hold, none of them are.
+#[derive(Clone, Debug, Default)]
+pub struct Transaction {
+    preconditions: Vec<(String, Option<u64>)>,
+    operations: Vec<Operation>,
+}
+
+#[derive(Clone, Debug)]
+enum Operation {
+    Set(String, Vec<u8>),
+    Delete(String),
+}
+
+impl Transaction {
+    /// Creates a new, empty transaction.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Requires `key` to be at `version` when this transaction is committed,
+    /// or to not exist if `version` is `None`.
+    pub fn require_version(&mut self, key: impl Into<String>, version: Option<u64>) -> &mut Self {
+        self.preconditions.push((key.into(), version));
+        self
+    }
+
+    /// Sets `key` to `value`.
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<Vec<u8>>) -> &mut Self {
+        self.operations
+            .push(Operation::Set(key.into(), value.into()));
+        self
+    }
+
+    /// Deletes `key`, if it exists.
+    pub fn delete(&mut self, key: impl Into<String>) -> &mut Self {
+        self.operations.push(Operation::Delete(key.into()));
+        self
+    }
+}
+
+/// The error returned when a precondition of a [`Transaction`] doesn't hold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionConflict {
+    key: String,
+    expected: Option<u64>,
+    actual: Option<u64>,
+}
+
+impl TransactionConflict {
+    /// The key whose version didn't match.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The version the transaction required, or `None` if it required the key
+    /// to not exist.
+    pub fn expected(&self) -> Option<u64> {
+        self.expected
+    }
+
+    /// The current version of the key, or `None` if it doesn't exist.
+    pub fn actual(&self) -> Option<u64> {
+        self.actual
+    }
+}
+
+impl fmt::Display for TransactionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = |v: Option<u64>| match v {
+            Some(v) => format!("version {v}"),
+            None => "no value".to_string(),
+        };
+        write!(
+            f,
+            "transaction conflict on key `{}`: expected {}, found {}",
+            self.key,
+            version(self.expected),
+            version(self.actual),
+        )
+    }
+}
+
+impl std::error::Error for TransactionConflict {}
+
 /// Builder-style structure used to create a [`WasiKeyValueCtx`].
 #[derive(Default)]
 pub struct WasiKeyValueCtxBuilder {
@@ -158,14 +328,68 @@ impl<'a> WasiKeyValue<'a> {
     pub fn new(ctx: &'a WasiKeyValueCtx, table: &'a mut ResourceTable) -> Self {
         Self { ctx, table }
     }
+
+    /// Returns the value of `key` in `bucket` together with its version, or
+    /// `None` if it doesn't exist.
+    pub fn get_versioned(
+        &mut self,
+        bucket: &Resource<Bucket>,
+        key: &str,
+    ) -> Result<Option<(Vec<u8>, u64)>> {
+        let bucket = self.table.get(bucket)?;
+        Ok(bucket
+            .in_memory_data
+            .get(key)
+            .map(|entry| (entry.value.clone(), entry.version)))
+    }
+
+    /// Atomically applies `transaction` to `bucket`.
+    ///
+    /// On success returns the version assigned to all values written by the
+    /// transaction. If any precondition of the transaction doesn't hold,
+    /// nothing is written and the conflict is returned instead.
+    pub fn commit(
+        &mut self,
+        bucket: &Resource<Bucket>,
+        transaction: Transaction,
+    ) -> Result<Result<u64, TransactionConflict>> {
+        let bucket = self.table.get_mut(bucket)?;
+        Ok(bucket.commit(transaction))
+    }
+
+    /// Sets `key` in `bucket` to `value`, or deletes it if `value` is `None`,
+    /// but only if its current version is `expected`.
+    ///
+    /// An `expected` version of `None` requires that `key` doesn't exist. This
+    /// is the same as committing a [`Transaction`] with a single precondition
+    /// and operation.
+    pub fn compare_and_swap(
+        &mut self,
+        bucket: &Resource<Bucket>,
+        key: &str,
+        expected: Option<u64>,
+        value: Option<Vec<u8>>,
+    ) -> Result<Result<u64, TransactionConflict>> {
+        let mut transaction = Transaction::new();
+        transaction.require_version(key, expected);
+        match value {
+            Some(value) => transaction.set(key, value),
+            None => transaction.delete(key),
+        };
+        self.commit(bucket, transaction)
+    }
+}
+
+/// Commits a transaction without preconditions, which can't conflict.
+fn commit_unconditionally(bucket: &mut Bucket, transaction: Transaction) {
+    let result = bucket.commit(transaction);
+    debug_assert!(result.is_ok());
 }
 
 impl keyvalue::store::Host for WasiKeyValue<'_> {
     fn open(&mut self, identifier: String) -> Result<Resource<Bucket>, Error> {
         match identifier.as_str() {
-            "" => Ok(self.table.push(Bucket {
-                in_memory_data: self.ctx.in_memory_data.clone(),
-            })?),
+            "" => Ok(self.table.push(Bucket::new(&self.ctx.in_memory_data))?),
             _ => Err(Error::NoSuchStore),
         }
     }
@@ -182,18 +406,22 @@ impl keyvalue::store::Host for WasiKeyValue<'_> {
 impl keyvalue::store::HostBucket for WasiKeyValue<'_> {
     fn get(&mut self, bucket: Resource<Bucket>, key: String) -> Result<Option<Vec<u8>>, Error> {
         let bucket = self.table.get_mut(&bucket)?;
-        Ok(bucket.in_memory_data.get(&key).cloned())
+        Ok(bucket.get(&key).cloned())
     }
 
     fn set(&mut self, bucket: Resource<Bucket>, key: String, value: Vec<u8>) -> Result<(), Error> {
         let bucket = self.table.get_mut(&bucket)?;
-        bucket.in_memory_data.insert(key, value);
+        let mut transaction = Transaction::new();
+        transaction.set(key, value);
+        commit_unconditionally(bucket, transaction);
         Ok(())
     }
 
     fn delete(&mut self, bucket: Resource<Bucket>, key: String) -> Result<(), Error> {
         let bucket = self.table.get_mut(&bucket)?;
-        bucket.in_memory_data.remove(&key);
+        let mut transaction = Transaction::new();
+        transaction.delete(key);
+        commit_unconditionally(bucket, transaction);
         Ok(())
     }
 
@@ -236,18 +464,19 @@ impl keyvalue::atomics::Host for WasiKeyValue<'_> {
         delta: u64,
     ) -> Result<u64, Error> {
         let bucket = self.table.get_mut(&bucket)?;
-        let value = bucket
-            .in_memory_data
-            .entry(key.clone())
-            .or_insert("0".to_string().into_bytes());
-        let current_value = String::from_utf8(value.clone())
-            .map_err(|e| Error::Other(e.to_string()))?
-            .parse::<u64>()
-            .map_err(|e| Error::Other(e.to_string()))?;
+        let current_value = match bucket.get(&key) {
+            Some(value) => std::str::from_utf8(value)
+                .map_err(|e| Error::Other(e.to_string()))?
+                .parse::<u64>()
+                .map_err(|e| Error::Other(e.to_string()))?,
+            None => 0,
+        };
         let new_value = current_value
             .checked_add(delta)
             .ok_or_else(|| Error::Other("overflow incrementing value".to_string()))?;
-        *value = new_value.to_string().into_bytes();
+        let mut transaction = Transaction::new();
+        transaction.set(key, new_value.to_string());
+        commit_unconditionally(bucket, transaction);
         Ok(new_value)
     }
 }
@@ -261,12 +490,7 @@ impl keyvalue::batch::Host for WasiKeyValue<'_> {
         let bucket = self.table.get_mut(&bucket)?;
         Ok(keys
             .into_iter()
-            .map(|key| {
-                bucket
-                    .in_memory_data
-                    .get(&key)
-                    .map(|value| (key.clone(), value.clone()))
-            })
+            .map(|key| bucket.get(&key).map(|value| (key.clone(), value.clone())))
             .collect())
     }
 
@@ -276,17 +500,21 @@ impl keyvalue::batch::Host for WasiKeyValue<'_> {
         key_values: Vec<(String, Vec<u8>)>,
     ) -> Result<(), Error> {
         let bucket = self.table.get_mut(&bucket)?;
+        let mut transaction = Transaction::new();
         for (key, value) in key_values {
-            bucket.in_memory_data.insert(key, value);
+            transaction.set(key, value);
         }
+        commit_unconditionally(bucket, transaction);
         Ok(())
     }
 
     fn delete_many(&mut self, bucket: Resource<Bucket>, keys: Vec<String>) -> Result<(), Error> {
         let bucket = self.table.get_mut(&bucket)?;
+        let mut transaction = Transaction::new();
         for key in keys {
-            bucket.in_memory_data.remove(&key);
+            transaction.delete(key);
         }
+        commit_unconditionally(bucket, transaction);
         Ok(())
     }
 }
@@ -348,4 +576,68 @@ mod tests {
         };
         assert!(kv.increment(bucket, "c".to_string(), 1).is_err());
     }
+
+    #[test]
+    fn versions_and_transactions() -> Result<()> {
+        let ctx = ctx_with(&[("a", "1")]);
+        let mut table = ResourceTable::new();
+        let mut kv = WasiKeyValue::new(&ctx, &mut table);
+        let bucket = match kv.open(String::new()) {
+            Ok(bucket) => bucket,
+            Err(_) => panic!("open failed"),
+        };
+        let bucket2 = Resource::new_borrow(bucket.rep());
+
+        assert_eq!(kv.get_versioned(&bucket, "a")?, Some((b"1".to_vec(), 0)));
+        assert_eq!(kv.get_versioned(&bucket, "b")?, None);
+
+        // Successful compare-and-swaps bump the version.
+        assert_eq!(
+            kv.compare_and_swap(&bucket, "a", Some(0), Some(b"2".to_vec()))?,
+            Ok(1)
+        );
+        assert_eq!(
+            kv.compare_and_swap(&bucket, "b", None, Some(b"x".to_vec()))?,
+            Ok(2)
+        );
+        assert_eq!(
+            kv.compare_and_swap(&bucket, "a", Some(0), None)?
+                .unwrap_err()
+                .actual(),
+            Some(1)
+        );
+
+        // Writes from guests bump versions too.
+        kv.set(
+            Resource::new_borrow(bucket.rep()),
+            "a".to_string(),
+            b"3".to_vec(),
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(kv.get_versioned(&bucket, "a")?, Some((b"3".to_vec(), 3)));
+
+        // A conflicting transaction writes nothing.
+        let mut transaction = Transaction::new();
+        transaction
+            .require_version("a", Some(3))
+            .require_version("b", Some(1))
+            .set("a", "4")
+            .delete("b")
+            .set("c", "5");
+        let conflict = kv.commit(&bucket2, transaction.clone())?.unwrap_err();
+        assert_eq!(conflict.key(), "b");
+        assert_eq!(conflict.expected(), Some(1));
+        assert_eq!(conflict.actual(), Some(2));
+        assert_eq!(kv.get_versioned(&bucket, "a")?, Some((b"3".to_vec(), 3)));
+        assert_eq!(kv.get_versioned(&bucket, "c")?, None);
+
+        // Once the preconditions hold all writes are applied with one version.
+        transaction.preconditions[1].1 = Some(2);
+        assert_eq!(kv.commit(&bucket, transaction)?, Ok(4));
+        assert_eq!(kv.get_versioned(&bucket, "a")?, Some((b"4".to_vec(), 4)));
+        assert_eq!(kv.get_versioned(&bucket, "b")?, None);
+        assert_eq!(kv.get_versioned(&bucket, "c")?, Some((b"5".to_vec(), 4)));
+        Ok(())
+    }
 }
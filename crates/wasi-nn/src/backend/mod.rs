@@ -80,6 +80,12 @@ pub trait BackendGraph: Send + Sync {
 pub trait BackendExecutionContext: Send + Sync {
     // WITX functions
     fn set_input(&mut self, id: Id, tensor: &Tensor) -> Result<(), BackendError>;
+    /// Like [BackendExecutionContext::set_input] but takes ownership of the
+    /// tensor; backends which hold on to input data until `compute` can
+    /// override this to avoid copying it.
+    fn set_input_owned(&mut self, id: Id, tensor: Tensor) -> Result<(), BackendError> {
+        self.set_input(id, &tensor)
+    }
     fn get_output(&mut self, id: Id) -> Result<Tensor, BackendError>;
 
     // Functions which work for both WIT and WITX
@@ -179,6 +179,16 @@ impl BackendExecutionContext for OnnxExecutionContext {
         Ok(())
     }
 
+    fn set_input_owned(&mut self, id: Id, tensor: Tensor) -> Result<(), BackendError> {
+        let index = self.find(id, &self.inputs)?;
+        let input = &mut self.inputs[index];
+        if let Err(e) = input.shape.matches(&tensor) {
+            return Err(e.into());
+        }
+        input.tensor.replace(tensor);
+        Ok(())
+    }
+
     fn compute(
         &mut self,
         inputs: Option<Vec<NamedTensor>>,
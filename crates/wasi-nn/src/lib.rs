@@ -132,6 +132,10 @@ impl ExecutionContext {
         self.0.set_input(id, tensor)
     }
 
+    pub fn set_input_owned(&mut self, id: Id, tensor: Tensor) -> Result<(), BackendError> {
+        self.0.set_input_owned(id, tensor)
+    }
+
     pub fn compute(&mut self) -> Result<(), BackendError> {
         self.0.compute(None).map(|_| ())
     }
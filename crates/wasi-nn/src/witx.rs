@@ -11,14 +11,22 @@
 //! 3. wrap up with some conversions, i.e., from `generated::*` types to this crate's
 //!    [`types`].
 //!
+//! Besides the `set_input` and `get_output` functions, which transfer whole
+//! tensors at once, this ABI has functions for transferring tensor data in
+//! chunks (see [`stream`]) so that large tensors need not be copied in full
+//! more than once.
+//!
 //! [`types`]: crate::wit::types
 
+mod stream;
+
 use crate::backend::BackendError;
 use crate::backend::Id;
 use crate::wit::GraphEncoding;
 use crate::{Backend, ExecutionContext, Graph, Registry};
 use std::collections::HashMap;
 use std::hash::Hash;
+use stream::{TensorDownload, TensorUpload};
 use thiserror::Error;
 use wiggle::{GuestError, GuestMemory, GuestPtr};
 
@@ -35,6 +43,8 @@ pub struct WasiNnCtx {
     pub(crate) registry: Registry,
     pub(crate) graphs: Table<GraphId, Graph>,
     pub(crate) executions: Table<GraphExecutionContextId, ExecutionContext>,
+    uploads: HashMap<(GraphExecutionContextId, u32), TensorUpload>,
+    downloads: HashMap<(GraphExecutionContextId, u32), TensorDownload>,
 }
 
 impl WasiNnCtx {
@@ -46,7 +56,42 @@ impl WasiNnCtx {
             registry,
             graphs: Table::default(),
             executions: Table::default(),
+            uploads: HashMap::new(),
+            downloads: HashMap::new(),
+        }
+    }
+
+    /// Passes a fully-uploaded input tensor to its execution context.
+    fn finish_input(
+        &mut self,
+        exec_context_id: GraphExecutionContextId,
+        index: u32,
+        upload: TensorUpload,
+    ) -> Result<()> {
+        let tensor = upload.finish()?;
+        if let Some(exec_context) = self.executions.get_mut(exec_context_id) {
+            Ok(exec_context.set_input_owned(Id::Index(index), tensor)?)
+        } else {
+            Err(UsageError::InvalidExecutionContextHandle.into())
+        }
+    }
+
+    /// Returns the output tensor at `index`, retrieving it from the backend
+    /// unless it is already being downloaded.
+    fn download(
+        &mut self,
+        exec_context_id: GraphExecutionContextId,
+        index: u32,
+    ) -> Result<&TensorDownload> {
+        let key = (exec_context_id, index);
+        if !self.downloads.contains_key(&key) {
+            let Some(exec_context) = self.executions.get_mut(exec_context_id) else {
+                return Err(UsageError::InvalidExecutionContextHandle.into());
+            };
+            let tensor = exec_context.get_output(Id::Index(index))?;
+            self.downloads.insert(key, TensorDownload::new(tensor));
         }
+        Ok(&self.downloads[&key])
     }
 }
 
@@ -189,16 +234,19 @@ impl generated::wasi_ephemeral_nn::WasiEphemeralNn for WasiNnCtx {
         index: u32,
         tensor: &generated::types::Tensor,
     ) -> Result<()> {
-        if let Some(exec_context) = self.executions.get_mut(exec_context_id.into()) {
-            let tensor = crate::wit::types::Tensor {
-                dimensions: memory.to_vec(tensor.dimensions)?,
-                ty: tensor.type_.into(),
-                data: memory.to_vec(tensor.data)?,
-            };
-            Ok(exec_context.set_input(Id::Index(index), &tensor)?)
-        } else {
-            Err(UsageError::InvalidGraphHandle.into())
+        if self.executions.get(exec_context_id.into()).is_none() {
+            return Err(UsageError::InvalidGraphHandle.into());
         }
+        // Set the whole tensor as a single chunk. Unlike a streaming upload,
+        // the size of the data is not checked against the dimensions here.
+        let data = memory.as_cow(tensor.data)?;
+        let mut upload = TensorUpload::with_size(
+            memory.to_vec(tensor.dimensions)?,
+            tensor.type_.into(),
+            data.len(),
+        );
+        upload.write(&data)?;
+        self.finish_input(exec_context_id.into(), index, upload)
     }
 
     fn compute(
@@ -207,6 +255,8 @@ impl generated::wasi_ephemeral_nn::WasiEphemeralNn for WasiNnCtx {
         exec_context_id: generated::types::GraphExecutionContext,
     ) -> Result<()> {
         if let Some(exec_context) = self.executions.get_mut(exec_context_id.into()) {
+            let id = GraphExecutionContextId::from(exec_context_id);
+            self.downloads.retain(|(context, _), _| *context != id);
             Ok(exec_context.compute()?)
         } else {
             Err(UsageError::InvalidExecutionContextHandle.into())
@@ -221,23 +271,83 @@ impl generated::wasi_ephemeral_nn::WasiEphemeralNn for WasiNnCtx {
         out_buffer: GuestPtr<u8>,
         out_buffer_max_size: u32,
     ) -> Result<u32> {
-        if let Some(exec_context) = self.executions.get_mut(exec_context_id.into()) {
-            let tensor = exec_context.get_output(Id::Index(index))?;
-            let destination = memory
-                .as_slice_mut(out_buffer.as_array(out_buffer_max_size))?
-                .expect(
-                    "cannot use with shared memories; \
-                     see https://github.com/bytecodealliance/wasmtime/issues/5235 (TODO)",
-                );
-            if tensor.data.len() > destination.len() {
-                Err(WasiNnError::NotEnoughMemory(tensor.data.len()))
-            } else {
-                destination[..tensor.data.len()].copy_from_slice(&tensor.data);
-                Ok(tensor.data.len() as u32)
-            }
-        } else {
-            Err(UsageError::InvalidGraphHandle.into())
+        if self.executions.get(exec_context_id.into()).is_none() {
+            return Err(UsageError::InvalidGraphHandle.into());
+        }
+        // Read the whole tensor as a single chunk, which requires that it fits
+        // in the guest's buffer.
+        let key = (exec_context_id.into(), index);
+        let len = self.download(key.0, key.1)?.len();
+        if len > out_buffer_max_size as usize {
+            return Err(WasiNnError::NotEnoughMemory(len));
+        }
+        let download = self.downloads.remove(&key).unwrap();
+        memory.copy_from_slice(download.read(0, len), out_buffer.as_array(len as u32))?;
+        Ok(len as u32)
+    }
+
+    fn set_input_begin(
+        &mut self,
+        memory: &mut GuestMemory<'_>,
+        exec_context_id: generated::types::GraphExecutionContext,
+        index: u32,
+        dimensions: generated::types::TensorDimensions,
+        type_: generated::types::TensorType,
+    ) -> Result<()> {
+        if self.executions.get(exec_context_id.into()).is_none() {
+            return Err(UsageError::InvalidExecutionContextHandle.into());
+        }
+        let upload = TensorUpload::new(memory.to_vec(dimensions)?, type_.into())?;
+        self.uploads.insert((exec_context_id.into(), index), upload);
+        Ok(())
+    }
+
+    fn set_input_chunk(
+        &mut self,
+        memory: &mut GuestMemory<'_>,
+        exec_context_id: generated::types::GraphExecutionContext,
+        index: u32,
+        chunk: generated::types::TensorData,
+    ) -> Result<()> {
+        let Some(upload) = self.uploads.get_mut(&(exec_context_id.into(), index)) else {
+            return Err(UsageError::NoTensorUpload.into());
+        };
+        upload.write(&memory.as_cow(chunk)?)?;
+        Ok(())
+    }
+
+    fn set_input_end(
+        &mut self,
+        _memory: &mut GuestMemory<'_>,
+        exec_context_id: generated::types::GraphExecutionContext,
+        index: u32,
+    ) -> Result<()> {
+        let Some(upload) = self.uploads.remove(&(exec_context_id.into(), index)) else {
+            return Err(UsageError::NoTensorUpload.into());
+        };
+        self.finish_input(exec_context_id.into(), index, upload)
+    }
+
+    fn get_output_chunk(
+        &mut self,
+        memory: &mut GuestMemory<'_>,
+        exec_context_id: generated::types::GraphExecutionContext,
+        index: u32,
+        offset: u32,
+        out_buffer: GuestPtr<u8>,
+        out_buffer_max_size: u32,
+    ) -> Result<u32> {
+        let key = (exec_context_id.into(), index);
+        let download = self.download(key.0, key.1)?;
+        let chunk = download.read(offset as usize, out_buffer_max_size as usize);
+        let (copied, done) = (chunk.len(), offset as usize + chunk.len() >= download.len());
+        memory.copy_from_slice(chunk, out_buffer.as_array(copied as u32))?;
+        // Release the host's copy of the tensor once the guest has read all of
+        // it; reading it again retrieves it from the backend anew.
+        if done {
+            self.downloads.remove(&key);
         }
+        Ok(copied as u32)
     }
 }
 
@@ -306,4 +416,10 @@ pub enum UsageError {
     InvalidExecutionContextHandle,
     #[error("No graph found with name: {0}")]
     NotFound(String),
+    #[error("Tensor dimensions describe more data than can be held in memory")]
+    InvalidTensorDimensions,
+    #[error("Invalid tensor data size: expected {expected} bytes, got {actual}")]
+    InvalidTensorSize { expected: usize, actual: usize },
+    #[error("No tensor upload in progress; has `set_input_begin` been called?")]
+    NoTensorUpload,
 }
@@ -0,0 +1,133 @@
+//! Chunked transfer of tensor data between guest memory and the host.
+//!
+//! Uploads copy each chunk straight from guest memory into a buffer sized for
+//! the whole tensor up front; that buffer is then handed to the backend by
+//! value. Downloads hold on to a single copy of an output tensor from which
+//! the guest reads successive ranges. In both cases the guest never needs to
+//! hold the whole tensor in one contiguous buffer and the host never creates
+//! more than one copy of it.
+
+use super::UsageError;
+use crate::Tensor;
+use crate::wit::types::TensorType;
+
+/// A tensor whose data is being written to the host in chunks.
+pub(crate) struct TensorUpload {
+    tensor: Tensor,
+    expected: usize,
+}
+
+impl TensorUpload {
+    /// Starts an upload of a tensor whose size is implied by its `dimensions`
+    /// and `ty`.
+    pub fn new(dimensions: Vec<u32>, ty: TensorType) -> Result<Self, UsageError> {
+        let expected = dimensions
+            .iter()
+            .try_fold(element_size(ty), |size, &d| {
+                size.checked_mul(usize::try_from(d).ok()?)
+            })
+            .ok_or(UsageError::InvalidTensorDimensions)?;
+        Ok(Self::with_size(dimensions, ty, expected))
+    }
+
+    /// Starts an upload of exactly `expected` bytes, regardless of what the
+    /// dimensions imply.
+    pub fn with_size(dimensions: Vec<u32>, ty: TensorType, expected: usize) -> Self {
+        Self {
+            tensor: Tensor::new(dimensions, ty, Vec::with_capacity(expected)),
+            expected,
+        }
+    }
+
+    /// Appends `chunk` to the data uploaded so far.
+    pub fn write(&mut self, chunk: &[u8]) -> Result<(), UsageError> {
+        let actual = self.tensor.data.len() + chunk.len();
+        if actual > self.expected {
+            return Err(UsageError::InvalidTensorSize {
+                expected: self.expected,
+                actual,
+            });
+        }
+        self.tensor.data.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    /// Completes the upload, returning the tensor if all of its data has been
+    /// written.
+    pub fn finish(self) -> Result<Tensor, UsageError> {
+        if self.tensor.data.len() != self.expected {
+            return Err(UsageError::InvalidTensorSize {
+                expected: self.expected,
+                actual: self.tensor.data.len(),
+            });
+        }
+        Ok(self.tensor)
+    }
+}
+
+/// An output tensor whose data is being read by the guest in chunks.
+pub(crate) struct TensorDownload {
+    tensor: Tensor,
+}
+
+impl TensorDownload {
+    pub fn new(tensor: Tensor) -> Self {
+        Self { tensor }
+    }
+
+    /// The total size of the tensor's data, in bytes.
+    pub fn len(&self) -> usize {
+        self.tensor.data.len()
+    }
+
+    /// Returns up to `max` bytes of data starting at `offset`; the returned
+    /// slice is empty once `offset` reaches the end of the data.
+    pub fn read(&self, offset: usize, max: usize) -> &[u8] {
+        let rest = self.tensor.data.get(offset..).unwrap_or_default();
+        &rest[..rest.len().min(max)]
+    }
+}
+
+/// The size, in bytes, of a single element of a tensor of type `ty`.
+fn element_size(ty: TensorType) -> usize {
+    match ty {
+        TensorType::U8 => 1,
+        TensorType::Fp16 | TensorType::Bf16 => 2,
+        TensorType::Fp32 | TensorType::I32 => 4,
+        TensorType::Fp64 | TensorType::I64 => 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload() {
+        let mut upload = TensorUpload::new(vec![2, 3], TensorType::Fp16).unwrap();
+        upload.write(&[1; 5]).unwrap();
+        upload.write(&[2; 7]).unwrap();
+        assert!(upload.write(&[3]).is_err());
+        let tensor = upload.finish().unwrap();
+        assert_eq!(tensor.dimensions, [2, 3]);
+        assert_eq!(tensor.data.len(), 12);
+        assert_eq!(tensor.data[4..6], [1, 2]);
+
+        let mut upload = TensorUpload::new(vec![4], TensorType::I64).unwrap();
+        upload.write(&[0; 31]).unwrap();
+        assert!(upload.finish().is_err());
+
+        assert!(TensorUpload::new(vec![u32::MAX; 4], TensorType::Fp32).is_err());
+    }
+
+    #[test]
+    fn download() {
+        let data = (0..10).collect::<Vec<u8>>();
+        let download = TensorDownload::new(Tensor::new(vec![10], TensorType::U8, data));
+        assert_eq!(download.len(), 10);
+        assert_eq!(download.read(0, 4), [0, 1, 2, 3]);
+        assert_eq!(download.read(8, 4), [8, 9]);
+        assert!(download.read(10, 4).is_empty());
+        assert!(download.read(11, 4).is_empty());
+    }
+}
@@ -9,11 +9,16 @@ mod empty;
 mod file;
 mod locked_async;
 mod mem;
+mod process;
 mod stdout;
+mod template;
 mod worker_thread_stdin;
 
 pub use self::file::{InputFile, OutputFile};
 pub use self::locked_async::{AsyncStdinStream, AsyncStdoutStream};
+pub use self::process::{AllowedProcess, ChildProcess};
+pub(crate) use self::process::{ProcessError, ProcessExit, WasiProcessCtx};
+pub use self::template::{CliTemplate, InstanceVars};
 
 // Convenience reexport for stdio types so tokio doesn't have to be imported
 // itself.
@@ -82,6 +87,7 @@ pub struct WasiCliCtx {
     pub(crate) stdin: Box<dyn StdinStream>,
     pub(crate) stdout: Box<dyn StdoutStream>,
     pub(crate) stderr: Box<dyn StdoutStream>,
+    pub(crate) processes: WasiProcessCtx,
 }
 
 impl Default for WasiCliCtx {
@@ -93,6 +99,7 @@ impl Default for WasiCliCtx {
             stdin: Box::new(empty()),
             stdout: Box::new(empty()),
             stderr: Box::new(empty()),
+            processes: WasiProcessCtx::default(),
         }
     }
 }
@@ -139,8 +146,8 @@ pub trait StdinStream: IsTerminal + Send {
 /// This is used both for a guest stdin and a guest stdout.
 ///
 /// Note that there are many built-in implementations of this trait for various
-/// types such as [`tokio::io::Stdout`], [`tokio::io::Empty`], and
-/// [`p2::pipe::MemoryOutputPipe`].
+/// types such as [`tokio::io::Stdout`], [`tokio::io::Empty`],
+/// [`p2::pipe::MemoryOutputPipe`], and [`p2::pipe::LineOutputPipe`].
 pub trait StdoutStream: IsTerminal + Send {
     /// Returns a fresh new stream which can write to this output stream.
     ///
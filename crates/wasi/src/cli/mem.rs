@@ -32,3 +32,18 @@ impl StdoutStream for p2::pipe::MemoryOutputPipe {
         Box::new(self.clone())
     }
 }
+
+// Implementation for p2::pipe::LineOutputPipe
+impl IsTerminal for p2::pipe::LineOutputPipe {
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+impl StdoutStream for p2::pipe::LineOutputPipe {
+    fn p2_stream(&self) -> Box<dyn OutputStream> {
+        Box::new(self.clone())
+    }
+    fn async_stream(&self) -> Box<dyn AsyncWrite + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
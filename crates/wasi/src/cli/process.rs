@@ -0,0 +1,402 @@
+//! Host support for the Wasmtime-specific `wasmtime:wasi/process` interface.
+
+use crate::runtime::{AbortOnDropJoinHandle, with_ambient_tokio_runtime};
+use futures::future::{self, Either};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::process::{ChildStderr, ChildStdin, ChildStdout};
+use tokio::sync::{Notify, watch};
+
+/// A program which guests may spawn through the `wasmtime:wasi/process`
+/// interface, along with the policy to apply when they do.
+///
+/// Guests name the program by the name given to [`AllowedProcess::new`], so
+/// they can't choose which host executable is run. By default guests may not
+/// pass any arguments or environment variables, which can be changed with
+/// [`args`](AllowedProcess::args) and [`allow_env`](AllowedProcess::allow_env).
+/// Spawned processes never inherit the environment of the host process.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use wasmtime_wasi::WasiCtx;
+/// use wasmtime_wasi::cli::AllowedProcess;
+///
+/// let mut git = AllowedProcess::new("git", "/usr/bin/git");
+/// git.args(|args| matches!(args.first().map(|s| s.as_str()), Some("status" | "log")))
+///     .allow_env("GIT_PAGER")
+///     .env("HOME", "/home/guest")
+///     .current_dir("/home/guest/repo")
+///     .timeout(Duration::from_secs(10));
+///
+/// let wasi = WasiCtx::builder().allow_process(git).max_processes(4).build();
+/// ```
+#[derive(Clone)]
+pub struct AllowedProcess {
+    name: String,
+    path: PathBuf,
+    args: Option<Arc<dyn Fn(&[String]) -> bool + Send + Sync>>,
+    allowed_env: Vec<String>,
+    env: Vec<(String, String)>,
+    current_dir: Option<PathBuf>,
+    timeout: Option<Duration>,
+    max_memory: Option<u64>,
+    max_cpu_time: Option<Duration>,
+}
+
+impl AllowedProcess {
+    /// Allows guests to spawn the host executable at `path` under the name
+    /// `name`.
+    ///
+    /// Note that `path` is resolved relative to the host process's working
+    /// directory if it isn't absolute, and isn't looked up in `PATH`.
+    pub fn new(name: impl Into<String>, path: impl AsRef<Path>) -> Self {
+        AllowedProcess {
+            name: name.into(),
+            path: path.as_ref().to_owned(),
+            args: None,
+            allowed_env: Vec::new(),
+            env: Vec::new(),
+            current_dir: None,
+            timeout: None,
+            max_memory: None,
+            max_cpu_time: None,
+        }
+    }
+
+    /// Configures which arguments guests may pass to this program.
+    ///
+    /// `filter` is called with the arguments requested by the guest, not
+    /// including the program name, and spawning is denied unless it returns
+    /// `true`. By default no arguments may be passed.
+    pub fn args(
+        &mut self,
+        filter: impl Fn(&[String]) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.args = Some(Arc::new(filter));
+        self
+    }
+
+    /// Allows guests to set the environment variable `name` for this program.
+    ///
+    /// Spawning is denied if the guest sets any environment variable which
+    /// hasn't been allowed.
+    pub fn allow_env(&mut self, name: impl Into<String>) -> &mut Self {
+        self.allowed_env.push(name.into());
+        self
+    }
+
+    /// Sets the environment variable `key` to `value` for this program.
+    ///
+    /// Variables set here take precedence over those set by the guest.
+    pub fn env(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Configures the host directory this program is run in.
+    ///
+    /// By default the working directory of the host process is used.
+    pub fn current_dir(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.current_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Kills this program if it's still running after `timeout`.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Limits the size of the virtual address space of this program to
+    /// `bytes`, with `RLIMIT_AS`.
+    ///
+    /// This is only supported on Unix, and spawning fails with `unsupported`
+    /// on other platforms.
+    pub fn max_memory(&mut self, bytes: u64) -> &mut Self {
+        self.max_memory = Some(bytes);
+        self
+    }
+
+    /// Limits the CPU time this program may use to `time`, rounded up to
+    /// whole seconds, with `RLIMIT_CPU`.
+    ///
+    /// This is only supported on Unix, and spawning fails with `unsupported`
+    /// on other platforms.
+    pub fn max_cpu_time(&mut self, time: Duration) -> &mut Self {
+        self.max_cpu_time = Some(time);
+        self
+    }
+
+    fn check(&self, args: &[String], env: &[(String, String)]) -> Result<(), ProcessError> {
+        let args_allowed = match &self.args {
+            Some(filter) => filter(args),
+            None => args.is_empty(),
+        };
+        if !args_allowed {
+            return Err(ProcessError::AccessDenied);
+        }
+        if !env
+            .iter()
+            .all(|(key, _)| self.allowed_env.iter().any(|allowed| allowed == key))
+        {
+            return Err(ProcessError::AccessDenied);
+        }
+        Ok(())
+    }
+
+    fn command(&self, args: &[String], env: &[(String, String)]) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new(&self.path);
+        cmd.args(args)
+            .env_clear()
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(dir) = &self.current_dir {
+            cmd.current_dir(dir);
+        }
+        cmd
+    }
+
+    #[cfg(unix)]
+    fn apply_resource_limits(&self, cmd: &mut tokio::process::Command) -> Result<(), ProcessError> {
+        use rustix::process::{Resource, Rlimit, setrlimit};
+
+        let mut limits = Vec::new();
+        if let Some(bytes) = self.max_memory {
+            limits.push((Resource::As, bytes));
+        }
+        if let Some(time) = self.max_cpu_time {
+            let secs = time.as_secs() + u64::from(time.subsec_nanos() > 0);
+            limits.push((Resource::Cpu, secs));
+        }
+        if limits.is_empty() {
+            return Ok(());
+        }
+        // SAFETY: the closure runs in the child between `fork` and `exec`, so
+        // it must only perform async-signal-safe operations. `setrlimit` is a
+        // plain system call, and the list of limits was allocated up front.
+        unsafe {
+            cmd.pre_exec(move || {
+                for (resource, limit) in &limits {
+                    let limit = Rlimit {
+                        current: Some(*limit),
+                        maximum: Some(*limit),
+                    };
+                    setrlimit(*resource, limit)?;
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn apply_resource_limits(
+        &self,
+        _cmd: &mut tokio::process::Command,
+    ) -> Result<(), ProcessError> {
+        if self.max_memory.is_some() || self.max_cpu_time.is_some() {
+            return Err(ProcessError::Unsupported);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for AllowedProcess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AllowedProcess")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("allowed_env", &self.allowed_env)
+            .field("current_dir", &self.current_dir)
+            .field("timeout", &self.timeout)
+            .field("max_memory", &self.max_memory)
+            .field("max_cpu_time", &self.max_cpu_time)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The processes guests may spawn, see [`AllowedProcess`].
+#[derive(Default)]
+pub(crate) struct WasiProcessCtx {
+    pub(crate) allowed: Vec<AllowedProcess>,
+    pub(crate) max_processes: Option<usize>,
+    pub(crate) restricted: bool,
+    running: Arc<AtomicUsize>,
+}
+
+/// Errors which can happen when spawning a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcessError {
+    AccessDenied,
+    NotFound,
+    LimitReached,
+    Unsupported,
+    Other,
+}
+
+impl From<std::io::Error> for ProcessError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => ProcessError::NotFound,
+            std::io::ErrorKind::PermissionDenied => ProcessError::AccessDenied,
+            std::io::ErrorKind::Unsupported => ProcessError::Unsupported,
+            _ => ProcessError::Other,
+        }
+    }
+}
+
+impl WasiProcessCtx {
+    /// Spawns the program `program` on behalf of the guest, if allowed.
+    pub(crate) fn spawn(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> Result<ChildProcess, ProcessError> {
+        if self.restricted {
+            return Err(ProcessError::AccessDenied);
+        }
+        let allowed = self
+            .allowed
+            .iter()
+            .find(|allowed| allowed.name == program)
+            .ok_or(ProcessError::AccessDenied)?;
+        allowed.check(args, env)?;
+
+        let permit = self.acquire()?;
+        let mut cmd = allowed.command(args, env);
+        allowed.apply_resource_limits(&mut cmd)?;
+        let timeout = allowed.timeout;
+        with_ambient_tokio_runtime(|| ChildProcess::spawn(cmd, timeout, permit))
+    }
+
+    fn acquire(&self) -> Result<ProcessPermit, ProcessError> {
+        let max = self.max_processes.unwrap_or(usize::MAX);
+        self.running
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                if n < max { Some(n + 1) } else { None }
+            })
+            .map_err(|_| ProcessError::LimitReached)?;
+        Ok(ProcessPermit(self.running.clone()))
+    }
+}
+
+/// Counts a process towards `WasiProcessCtx::max_processes` until dropped.
+struct ProcessPermit(Arc<AtomicUsize>);
+
+impl Drop for ProcessPermit {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// How a spawned process exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcessExit {
+    Exited(i32),
+    Terminated,
+}
+
+/// A process spawned by a guest through the `wasmtime:wasi/process`
+/// interface.
+///
+/// The process is killed when this is dropped, and counts towards the limit
+/// configured with
+/// [`WasiCtxBuilder::max_processes`](crate::WasiCtxBuilder::max_processes)
+/// until then.
+pub struct ChildProcess {
+    pub(crate) stdin: Option<ChildStdin>,
+    pub(crate) stdout: Option<ChildStdout>,
+    pub(crate) stderr: Option<ChildStderr>,
+    exit: watch::Receiver<Option<ProcessExit>>,
+    kill: Arc<Notify>,
+    _waiter: AbortOnDropJoinHandle<()>,
+    _permit: ProcessPermit,
+}
+
+impl ChildProcess {
+    fn spawn(
+        mut cmd: tokio::process::Command,
+        timeout: Option<Duration>,
+        permit: ProcessPermit,
+    ) -> Result<ChildProcess, ProcessError> {
+        let mut child = cmd.spawn()?;
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        // The process is waited on in the background such that its timeout
+        // is enforced whether or not the guest is waiting for it. Aborting
+        // this task, when the `ChildProcess` is dropped, drops `child` which
+        // kills the process.
+        let (exit_tx, exit) = watch::channel(None);
+        let kill = Arc::new(Notify::new());
+        let waiter = crate::runtime::spawn({
+            let kill = kill.clone();
+            async move {
+                // Stop once the guest asks to kill the process or its timeout
+                // elapses, whichever happens first.
+                let stop = async {
+                    match timeout {
+                        Some(timeout) => {
+                            let _ = tokio::time::timeout(timeout, kill.notified()).await;
+                        }
+                        None => kill.notified().await,
+                    }
+                };
+                let exited = match future::select(pin!(child.wait()), pin!(stop)).await {
+                    Either::Left((status, _)) => Some(status),
+                    Either::Right(((), _)) => None,
+                };
+                let status = match exited {
+                    Some(status) => status,
+                    None => {
+                        let _ = child.start_kill();
+                        child.wait().await
+                    }
+                };
+                let exit = match status.map(|status| status.code()) {
+                    Ok(Some(code)) => ProcessExit::Exited(code),
+                    Ok(None) | Err(_) => ProcessExit::Terminated,
+                };
+                let _ = exit_tx.send(Some(exit));
+            }
+        });
+
+        Ok(ChildProcess {
+            stdin,
+            stdout,
+            stderr,
+            exit,
+            kill,
+            _waiter: waiter,
+            _permit: permit,
+        })
+    }
+
+    /// Returns how the process exited, or `None` if it's still running.
+    pub(crate) fn exit_status(&self) -> Option<ProcessExit> {
+        *self.exit.borrow()
+    }
+
+    /// Kills the process if it's still running, without waiting for it.
+    pub(crate) fn kill(&self) {
+        self.kill.notify_one();
+    }
+
+    /// Waits for the process to exit.
+    pub(crate) async fn exited(&mut self) {
+        let _ = self.exit.wait_for(|exit| exit.is_some()).await;
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+use wasmtime::{Result, bail};
+
+/// A shareable set of environment variables and arguments whose values may
+/// differ between instances.
+///
+/// Besides plain values a template can hold values which are resolved anew
+/// for every instance, either from a template string referencing the
+/// [`InstanceVars`] of the instance or from a callback. Templates are cheap to
+/// clone and can be shared across threads, so a single template can be
+/// configured once and then applied to the [`WasiCtxBuilder`] of each
+/// instance with [`WasiCtxBuilder::cli_template`].
+///
+/// Template strings may contain the placeholders `{instance_id}` and
+/// `{request_id}`, which are replaced with the corresponding value of the
+/// [`InstanceVars`] the template is resolved with. A missing request ID is
+/// replaced with the empty string. Literal braces are written as `{{` and
+/// `}}`.
+///
+/// # Examples
+///
+/// ```
+/// use wasmtime_wasi::WasiCtxBuilder;
+/// use wasmtime_wasi::cli::{CliTemplate, InstanceVars};
+///
+/// # fn main() -> wasmtime::Result<()> {
+/// let mut template = CliTemplate::new();
+/// template
+///     .arg("server.wasm")
+///     .env("MODE", "production")
+///     .env_template("REQUEST_ID", "req-{request_id}")?
+///     .env_with("WORKER", |vars| format!("worker-{}", vars.instance_id() % 4));
+///
+/// for instance_id in 0..3 {
+///     let vars = InstanceVars::new(instance_id).with_request_id(format!("{instance_id:x}"));
+///     let mut builder = WasiCtxBuilder::new();
+///     builder.cli_template(&template, &vars);
+///     let wasi = builder.build();
+///     // ...
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`WasiCtxBuilder`]: crate::WasiCtxBuilder
+/// [`WasiCtxBuilder::cli_template`]: crate::WasiCtxBuilder::cli_template
+#[derive(Clone, Default)]
+pub struct CliTemplate {
+    environment: Vec<(String, Value)>,
+    arguments: Vec<Value>,
+}
+
+/// Per-instance values which [`CliTemplate`] resolves its values with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstanceVars {
+    instance_id: u64,
+    request_id: Option<String>,
+}
+
+#[derive(Clone)]
+enum Value {
+    Static(String),
+    Template(Vec<Segment>),
+    Callback(Arc<dyn Fn(&InstanceVars) -> String + Send + Sync>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    InstanceId,
+    RequestId,
+}
+
+impl CliTemplate {
+    /// Creates a new, empty, template.
+    pub fn new() -> CliTemplate {
+        CliTemplate::default()
+    }
+
+    /// Appends an environment variable with a fixed value.
+    pub fn env(&mut self, k: impl AsRef<str>, v: impl AsRef<str>) -> &mut Self {
+        self.environment
+            .push((k.as_ref().to_owned(), Value::Static(v.as_ref().to_owned())));
+        self
+    }
+
+    /// Appends an environment variable whose value is produced from the
+    /// `template` string for each instance.
+    ///
+    /// See [`CliTemplate`] for the syntax of template strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `template` is malformed or references an unknown
+    /// placeholder.
+    pub fn env_template(&mut self, k: impl AsRef<str>, template: &str) -> Result<&mut Self> {
+        let value = Value::Template(parse(template)?);
+        self.environment.push((k.as_ref().to_owned(), value));
+        Ok(self)
+    }
+
+    /// Appends an environment variable whose value is computed by `f` for
+    /// each instance.
+    pub fn env_with(
+        &mut self,
+        k: impl AsRef<str>,
+        f: impl Fn(&InstanceVars) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.environment
+            .push((k.as_ref().to_owned(), Value::Callback(Arc::new(f))));
+        self
+    }
+
+    /// Appends an argument with a fixed value.
+    pub fn arg(&mut self, arg: impl AsRef<str>) -> &mut Self {
+        self.arguments.push(Value::Static(arg.as_ref().to_owned()));
+        self
+    }
+
+    /// Appends an argument which is produced from the `template` string for
+    /// each instance.
+    ///
+    /// See [`CliTemplate`] for the syntax of template strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `template` is malformed or references an unknown
+    /// placeholder.
+    pub fn arg_template(&mut self, template: &str) -> Result<&mut Self> {
+        self.arguments.push(Value::Template(parse(template)?));
+        Ok(self)
+    }
+
+    /// Appends an argument which is computed by `f` for each instance.
+    pub fn arg_with(
+        &mut self,
+        f: impl Fn(&InstanceVars) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.arguments.push(Value::Callback(Arc::new(f)));
+        self
+    }
+
+    /// Returns the environment variables of this template as resolved for the
+    /// instance described by `vars`.
+    pub fn environment(&self, vars: &InstanceVars) -> Vec<(String, String)> {
+        self.environment
+            .iter()
+            .map(|(k, v)| (k.clone(), v.resolve(vars)))
+            .collect()
+    }
+
+    /// Returns the arguments of this template as resolved for the instance
+    /// described by `vars`.
+    pub fn arguments(&self, vars: &InstanceVars) -> Vec<String> {
+        self.arguments.iter().map(|v| v.resolve(vars)).collect()
+    }
+}
+
+impl fmt::Debug for CliTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CliTemplate")
+            .field("environment", &self.environment)
+            .field("arguments", &self.arguments)
+            .finish()
+    }
+}
+
+impl InstanceVars {
+    /// Creates the variables of the instance identified by `instance_id`.
+    pub fn new(instance_id: u64) -> InstanceVars {
+        InstanceVars {
+            instance_id,
+            request_id: None,
+        }
+    }
+
+    /// Sets the ID of the request the instance is created for.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> InstanceVars {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Returns the ID of the instance.
+    pub fn instance_id(&self) -> u64 {
+        self.instance_id
+    }
+
+    /// Returns the ID of the request the instance is created for, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl Value {
+    fn resolve(&self, vars: &InstanceVars) -> String {
+        match self {
+            Value::Static(s) => s.clone(),
+            Value::Callback(f) => f(vars),
+            Value::Template(segments) => {
+                let mut ret = String::new();
+                for segment in segments {
+                    match segment {
+                        Segment::Literal(s) => ret.push_str(s),
+                        Segment::InstanceId => ret.push_str(&vars.instance_id.to_string()),
+                        Segment::RequestId => {
+                            ret.push_str(vars.request_id.as_deref().unwrap_or(""))
+                        }
+                    }
+                }
+                ret
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Static(s) => s.fmt(f),
+            Value::Template(segments) => f.debug_tuple("Template").field(segments).finish(),
+            Value::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let Some((name, rest)) = chars.as_str().split_once('}') else {
+                    bail!("unterminated placeholder in template `{template}`");
+                };
+                let segment = match name {
+                    "instance_id" => Segment::InstanceId,
+                    "request_id" => Segment::RequestId,
+                    _ => bail!("unknown placeholder `{{{name}}}` in template `{template}`"),
+                };
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(mem::take(&mut literal)));
+                }
+                segments.push(segment);
+                chars = rest.chars();
+            }
+            '}' => bail!("unmatched `}}` in template `{template}`"),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve() -> Result<()> {
+        let mut template = CliTemplate::new();
+        template
+            .env("A", "a")
+            .env_template("B", "{{{instance_id}}}-{request_id}")?
+            .env_with("C", |vars| (vars.instance_id() * 2).to_string())
+            .arg("prog")
+            .arg_template("--id={instance_id}")?
+            .arg_with(|vars| vars.request_id().unwrap_or("none").to_owned());
+
+        let vars = InstanceVars::new(7).with_request_id("r1");
+        assert_eq!(
+            template.environment(&vars),
+            [
+                ("A".to_owned(), "a".to_owned()),
+                ("B".to_owned(), "{7}-r1".to_owned()),
+                ("C".to_owned(), "14".to_owned()),
+            ]
+        );
+        assert_eq!(template.arguments(&vars), ["prog", "--id=7", "r1"]);
+
+        let vars = InstanceVars::new(1);
+        assert_eq!(template.environment(&vars)[1].1, "{1}-");
+        assert_eq!(template.arguments(&vars), ["prog", "--id=1", "none"]);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse("{instance_id").is_err());
+        assert!(parse("{unknown}").is_err());
+        assert!(parse("a}b").is_err());
+        assert_eq!(
+            parse("x{{y}}").unwrap(),
+            [Segment::Literal("x{y}".to_owned())]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use std::error::Error;
 use std::fmt;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::watch;
 use wasmtime::component::{HasData, ResourceTable};
 
 /// A helper struct which implements [`HasData`] for the `wasi:clocks` APIs.
@@ -50,6 +52,8 @@ impl HasData for WasiClocks {
 pub struct WasiClocksCtx {
     pub(crate) wall_clock: Box<dyn HostWallClock + Send>,
     pub(crate) monotonic_clock: Box<dyn HostMonotonicClock + Send>,
+    pub(crate) logical_clock: Option<LogicalClock>,
+    pub(crate) virtual_clock: Option<VirtualClock>,
 }
 
 impl Default for WasiClocksCtx {
@@ -57,6 +61,33 @@ impl Default for WasiClocksCtx {
         Self {
             wall_clock: wall_clock(),
             monotonic_clock: monotonic_clock(),
+            logical_clock: None,
+            virtual_clock: None,
+        }
+    }
+}
+
+impl WasiClocksCtx {
+    /// Returns the [`LogicalClock`] driving both clocks of this context, if
+    /// it was configured with
+    /// [`WasiCtxBuilder::deterministic`](crate::WasiCtxBuilder::deterministic).
+    pub fn logical_clock(&self) -> Option<&LogicalClock> {
+        self.logical_clock.as_ref()
+    }
+
+    /// Returns the [`VirtualClock`] driving both clocks of this context, if
+    /// it was configured with
+    /// [`WasiCtxBuilder::virtual_clock`](crate::WasiCtxBuilder::virtual_clock).
+    pub fn virtual_clock(&self) -> Option<&VirtualClock> {
+        self.virtual_clock.as_ref()
+    }
+
+    /// Converts `duration` of guest monotonic time into the duration of host
+    /// time it takes to elapse, or `None` if it never does.
+    pub(crate) fn host_duration(&self, duration: Duration) -> Option<Duration> {
+        match &self.virtual_clock {
+            Some(clock) => clock.host_duration(duration),
+            None => Some(duration),
         }
     }
 }
@@ -172,6 +203,291 @@ impl HostMonotonicClock for MonotonicClock {
     }
 }
 
+/// A clock whose time only moves when the host explicitly advances it.
+///
+/// A `LogicalClock` implements both [`HostWallClock`] and
+/// [`HostMonotonicClock`]. The monotonic time starts at zero and the wall
+/// time starts at a fixed offset from the Unix epoch; both advance together
+/// through [`LogicalClock::advance`]. Timers subscribed to by the guest while
+/// a logical clock is configured become ready once the logical time reaches
+/// their deadline, not when real time passes.
+///
+/// Clones of a `LogicalClock` share the same time, so the host can keep a
+/// clone to drive a clock that has been handed to a
+/// [`WasiCtxBuilder`](crate::WasiCtxBuilder).
+#[derive(Clone, Debug)]
+pub struct LogicalClock {
+    now: Arc<watch::Sender<u64>>,
+    wall_epoch: Duration,
+}
+
+impl Default for LogicalClock {
+    fn default() -> Self {
+        Self::new(Duration::ZERO)
+    }
+}
+
+impl LogicalClock {
+    /// Creates a new logical clock at monotonic time zero whose wall clock
+    /// reads `wall_epoch` past the Unix epoch.
+    pub fn new(wall_epoch: Duration) -> Self {
+        Self {
+            now: Arc::new(watch::Sender::new(0)),
+            wall_epoch,
+        }
+    }
+
+    /// Returns the current logical time, in nanoseconds.
+    pub fn now(&self) -> u64 {
+        *self.now.borrow()
+    }
+
+    /// Advances the logical time by `duration`, waking any guest timers whose
+    /// deadline has been reached.
+    ///
+    /// Time saturates at `u64::MAX` nanoseconds.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.now.send_modify(|now| *now = now.saturating_add(nanos));
+    }
+
+    /// Waits until the logical time is at least `when` nanoseconds.
+    pub(crate) fn wait_until(&self, when: u64) -> impl Future<Output = ()> + Send + 'static {
+        let mut rx = self.now.subscribe();
+        async move {
+            // The sender lives as long as any clone of this clock, which
+            // includes the one in the `WasiCtx`; if it's gone nothing can
+            // advance time anymore so just wait forever.
+            if rx.wait_for(|now| *now >= when).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+impl HostWallClock for LogicalClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self) -> Duration {
+        self.wall_epoch + Duration::from_nanos(LogicalClock::now(self))
+    }
+}
+
+impl HostMonotonicClock for LogicalClock {
+    fn resolution(&self) -> u64 {
+        1
+    }
+
+    fn now(&self) -> u64 {
+        LogicalClock::now(self)
+    }
+}
+
+/// A clock derived from the host's clocks, with its rate, offset and
+/// resolution adjusted.
+///
+/// A `VirtualClock` implements both [`HostWallClock`] and
+/// [`HostMonotonicClock`]. By default it behaves like the host's clocks: the
+/// monotonic time starts at zero and the wall time at the host's wall time
+/// when the clock is created, and both advance with the host's monotonic
+/// time. Unlike with a fully custom clock, the common adjustments are
+/// configured with a few methods:
+///
+/// * [`VirtualClock::set_scale`] changes the rate at which both clocks
+///   advance relative to host time, which also applies to the timers
+///   subscribed to by the guest.
+/// * [`VirtualClock::set_wall_time`] and [`VirtualClock::advance`] offset the
+///   clocks from the host's.
+/// * [`VirtualClock::set_resolution`] coarsens the time observed by the
+///   guest, for example to resist timing-based fingerprinting.
+///
+/// Clones of a `VirtualClock` share the same configuration, so the host can
+/// keep a clone to adjust a clock at runtime after it has been handed to a
+/// [`WasiCtxBuilder`](crate::WasiCtxBuilder). Adjustments never make the
+/// monotonic clock go backwards.
+#[derive(Clone, Debug)]
+pub struct VirtualClock {
+    state: Arc<Mutex<VirtualClockState>>,
+}
+
+#[derive(Debug)]
+struct VirtualClockState {
+    /// The host time the clocks were last adjusted at.
+    anchor: Instant,
+    /// The monotonic time at `anchor`, in nanoseconds.
+    monotonic: u64,
+    /// The wall time at `anchor`, since the Unix epoch.
+    wall: Duration,
+    scale: f64,
+    resolution: Duration,
+    /// The last monotonic time observed by the guest, which later readings
+    /// never go below.
+    last_monotonic: u64,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualClock {
+    /// Creates a new clock which reads the same as the host's clocks.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(VirtualClockState {
+                anchor: Instant::now(),
+                monotonic: 0,
+                wall: WallClock.now(),
+                scale: 1.0,
+                resolution: Duration::from_nanos(1),
+                last_monotonic: 0,
+            })),
+        }
+    }
+
+    /// Sets the rate at which this clock advances relative to host time.
+    ///
+    /// For example a `scale` of `2.0` makes time pass twice as fast for the
+    /// guest, and guest timers expire after half of the host time. A `scale`
+    /// of `0.0` freezes time, except for explicit adjustments, and guest
+    /// timers that haven't expired never do. Timers which the guest is
+    /// already waiting on keep the rate they were subscribed with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is negative, infinite or NaN.
+    pub fn set_scale(&self, scale: f64) {
+        assert!(
+            scale.is_finite() && scale >= 0.0,
+            "invalid clock scale: {scale}"
+        );
+        let mut state = self.state.lock().unwrap();
+        state.reanchor();
+        state.scale = scale;
+    }
+
+    /// Sets the wall time of this clock to `time`, from which it continues to
+    /// advance.
+    ///
+    /// Times before the Unix epoch are clamped to the epoch. The monotonic
+    /// time isn't affected.
+    pub fn set_wall_time(&self, time: SystemTime) {
+        let mut state = self.state.lock().unwrap();
+        state.reanchor();
+        state.wall = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+    }
+
+    /// Advances both the wall and monotonic times of this clock by
+    /// `duration`.
+    ///
+    /// Guest timers that are already being waited on aren't affected.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.reanchor();
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        state.monotonic = state.monotonic.saturating_add(nanos);
+        state.wall = state.wall.saturating_add(duration);
+    }
+
+    /// Sets the resolution of this clock, which the times read by the guest
+    /// are truncated to a multiple of.
+    ///
+    /// The resolution reported to the guest is the larger of `resolution`
+    /// and the resolution of the host's clocks. The default of one
+    /// nanosecond keeps the host's resolution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is zero.
+    pub fn set_resolution(&self, resolution: Duration) {
+        assert!(!resolution.is_zero(), "clock resolution must be non-zero");
+        self.state.lock().unwrap().resolution = resolution;
+    }
+
+    /// Returns the current monotonic time of this clock, in nanoseconds.
+    pub fn monotonic_now(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let resolution = u64::try_from(state.resolution.as_nanos()).unwrap_or(u64::MAX);
+        let now = state.monotonic.saturating_add(state.elapsed());
+        let now = (now / resolution * resolution).max(state.last_monotonic);
+        state.last_monotonic = now;
+        now
+    }
+
+    /// Returns the current wall time of this clock, since the Unix epoch.
+    pub fn wall_now(&self) -> Duration {
+        let state = self.state.lock().unwrap();
+        let now = state.wall.as_nanos() + u128::from(state.elapsed());
+        let now = now / state.resolution.as_nanos() * state.resolution.as_nanos();
+        Duration::new(
+            u64::try_from(now / 1_000_000_000).unwrap_or(u64::MAX),
+            (now % 1_000_000_000) as u32,
+        )
+    }
+
+    /// Converts `duration` of this clock's time into the duration of host time
+    /// it takes to elapse, or `None` if it never does.
+    pub(crate) fn host_duration(&self, duration: Duration) -> Option<Duration> {
+        let scale = self.state.lock().unwrap().scale;
+        if scale == 1.0 || duration.is_zero() {
+            Some(duration)
+        } else {
+            Duration::try_from_secs_f64(duration.as_secs_f64() / scale).ok()
+        }
+    }
+}
+
+impl VirtualClockState {
+    /// Returns the time elapsed for this clock since `anchor`, in
+    /// nanoseconds.
+    fn elapsed(&self) -> u64 {
+        let host = self.anchor.elapsed().as_nanos();
+        let elapsed = if self.scale == 1.0 {
+            host
+        } else {
+            (host as f64 * self.scale) as u128
+        };
+        u64::try_from(elapsed).unwrap_or(u64::MAX)
+    }
+
+    /// Moves `anchor` to the current host time, so that the configuration can
+    /// change from here on.
+    fn reanchor(&mut self) {
+        let elapsed = self.elapsed();
+        self.anchor = Instant::now();
+        self.monotonic = self.monotonic.saturating_add(elapsed);
+        self.wall = self.wall.saturating_add(Duration::from_nanos(elapsed));
+    }
+}
+
+impl HostWallClock for VirtualClock {
+    fn resolution(&self) -> Duration {
+        let resolution = self.state.lock().unwrap().resolution;
+        WallClock.resolution().max(resolution)
+    }
+
+    fn now(&self) -> Duration {
+        self.wall_now()
+    }
+}
+
+impl HostMonotonicClock for VirtualClock {
+    fn resolution(&self) -> u64 {
+        let resolution = self.state.lock().unwrap().resolution;
+        let resolution = u64::try_from(resolution.as_nanos()).unwrap_or(u64::MAX);
+        MonotonicClock::new().resolution().max(resolution)
+    }
+
+    fn now(&self) -> u64 {
+        self.monotonic_now()
+    }
+}
+
 pub fn monotonic_clock() -> Box<dyn HostMonotonicClock + Send> {
     Box::new(MonotonicClock::default())
 }
@@ -229,3 +545,72 @@ impl From<std::num::TryFromIntError> for DatetimeError {
         DatetimeError
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::task::{Context, Waker};
+
+    #[test]
+    fn logical_clock() {
+        let mut cx = Context::from_waker(Waker::noop());
+        let clock = LogicalClock::new(Duration::from_secs(10));
+        assert_eq!(HostMonotonicClock::now(&clock), 0);
+        assert_eq!(HostWallClock::now(&clock), Duration::from_secs(10));
+
+        let shared = clock.clone();
+        let mut wait = Box::pin(clock.wait_until(100));
+        assert!(wait.as_mut().poll(&mut cx).is_pending());
+
+        shared.advance(Duration::from_nanos(99));
+        assert!(wait.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(HostMonotonicClock::now(&clock), 99);
+
+        shared.advance(Duration::from_nanos(1));
+        assert!(wait.as_mut().poll(&mut cx).is_ready());
+        assert_eq!(
+            HostWallClock::now(&clock),
+            Duration::from_secs(10) + Duration::from_nanos(100)
+        );
+    }
+
+    #[test]
+    fn virtual_clock() {
+        let clock = VirtualClock::new();
+        clock.set_scale(0.0);
+        clock.set_wall_time(SystemTime::UNIX_EPOCH + Duration::from_secs(100));
+        let start = clock.monotonic_now();
+        assert_eq!(clock.wall_now(), Duration::from_secs(100));
+        assert_eq!(clock.host_duration(Duration::from_secs(1)), None);
+
+        // Adjustments through clones are shared.
+        let shared = clock.clone();
+        shared.advance(Duration::from_nanos(1_500));
+        assert_eq!(clock.monotonic_now(), start + 1_500);
+        assert_eq!(
+            clock.wall_now(),
+            Duration::from_secs(100) + Duration::from_nanos(1_500)
+        );
+
+        // Coarser resolutions truncate the time, but never make the monotonic
+        // clock go backwards.
+        shared.set_resolution(Duration::from_micros(1));
+        assert_eq!(
+            clock.wall_now(),
+            Duration::from_secs(100) + Duration::from_micros(1)
+        );
+        assert_eq!(clock.monotonic_now(), start + 1_500);
+        assert!(HostMonotonicClock::resolution(&clock) >= 1_000);
+        assert!(HostWallClock::resolution(&clock) >= Duration::from_micros(1));
+        shared.advance(Duration::from_micros(10));
+        let now = clock.monotonic_now();
+        assert!(now >= start + 11_500 - 1_000 && now % 1_000 == 0);
+
+        shared.set_scale(2.0);
+        assert_eq!(
+            clock.host_duration(Duration::from_secs(1)),
+            Some(Duration::from_millis(500))
+        );
+        assert!(clock.monotonic_now() >= now);
+    }
+}
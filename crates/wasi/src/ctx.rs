@@ -1,16 +1,23 @@
-use crate::cli::{StdinStream, StdoutStream, WasiCliCtx};
-use crate::clocks::{HostMonotonicClock, HostWallClock, WasiClocksCtx};
-use crate::filesystem::{Dir, WasiFilesystemCtx};
+use crate::cli::{
+    AllowedProcess, CliTemplate, InstanceVars, StdinStream, StdoutStream, WasiCliCtx,
+};
+use crate::clocks::{HostMonotonicClock, HostWallClock, LogicalClock, VirtualClock, WasiClocksCtx};
+use crate::filesystem::{Dir, HostFileWatcher, HostPath, WasiFilesystemCtx};
+use crate::limits::{IoLimitBehavior, IoLimitsConfig};
 use crate::random::WasiRandomCtx;
-use crate::sockets::{SocketAddrCheck, SocketAddrUse, WasiSocketsCtx};
-use crate::{DirPerms, FilePerms, OpenMode};
+use crate::runtime::BlockingPool;
+use crate::sockets::ip_name_lookup::HostPattern;
+use crate::sockets::{DnsCache, SocketAddrCheck, SocketAddrUse, WasiSocketsCtx};
+use crate::{DirPerms, FilePerms, OpenMode, SymlinkPolicy};
 use cap_primitives::ambient_authority;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, RngExt as _, SeedableRng as _};
 use std::future::Future;
 use std::mem;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::pin::Pin;
+use std::sync::Arc;
 use tokio::io::{stderr, stdin, stdout};
 use wasmtime::Result;
 
@@ -41,6 +48,7 @@ pub struct WasiCtxBuilder {
     filesystem: WasiFilesystemCtx,
     random: WasiRandomCtx,
     sockets: WasiSocketsCtx,
+    io_limits: IoLimitsConfig,
     built: bool,
 }
 
@@ -59,6 +67,8 @@ impl WasiCtxBuilder {
     ///   quality to satisfy the requirements of WASI APIs.
     /// * TCP/UDP are allowed but all addresses are denied by default.
     /// * `wasi:sockets/ip-name-lookup` is denied by default.
+    /// * No Unix domain sockets may be connected to.
+    /// * No host processes may be spawned.
     ///
     /// These defaults can all be updated via the various builder configuration
     /// methods below.
@@ -164,6 +174,45 @@ impl WasiCtxBuilder {
         self
     }
 
+    /// Configures the [`BlockingPool`] used to run the blocking operations made
+    /// through this `WasiCtx`, such as filesystem accesses and resolving host
+    /// names.
+    ///
+    /// By default these operations are spawned onto Tokio's shared blocking
+    /// pool, which is also used by the rest of the host application. Giving
+    /// guests their own size-capped pool prevents a busy guest from starving
+    /// the host's own blocking tasks and vice versa. The pool is the one
+    /// shared by every context of an [`Engine`](wasmtime::Engine), as
+    /// returned by [`BlockingPool::for_engine`] or configured ahead of time
+    /// with [`BlockingPoolBuilder::install`](crate::runtime::BlockingPoolBuilder::install).
+    ///
+    /// This has no effect on operations which are permitted to block the
+    /// current thread with
+    /// [`allow_blocking_current_thread`](Self::allow_blocking_current_thread).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmtime::Engine;
+    /// use wasmtime_wasi::WasiCtx;
+    /// use wasmtime_wasi::runtime::BlockingPool;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let engine = Engine::default();
+    /// BlockingPool::builder().max_threads(4).install(&engine)?;
+    ///
+    /// let mut wasi = WasiCtx::builder();
+    /// wasi.blocking_pool(BlockingPool::for_engine(&engine)?);
+    /// let wasi: WasiCtx = wasi.build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blocking_pool(&mut self, pool: BlockingPool) -> &mut Self {
+        self.filesystem.blocking_pool = Some(pool.clone());
+        self.sockets.resolver.blocking_pool = Some(pool);
+        self
+    }
+
     /// Appends multiple environment variables at once for this builder.
     ///
     /// All environment variables are appended to the list of environment
@@ -242,6 +291,34 @@ impl WasiCtxBuilder {
         self
     }
 
+    /// Appends the environment variables and arguments of `template`, as
+    /// resolved for the instance described by `vars`.
+    ///
+    /// This enables configuring the environment variables and arguments
+    /// shared by many instances once, with per-instance values being filled in
+    /// here. See [`CliTemplate`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmtime_wasi::WasiCtxBuilder;
+    /// use wasmtime_wasi::cli::{CliTemplate, InstanceVars};
+    ///
+    /// # fn main() -> wasmtime::Result<()> {
+    /// let mut template = CliTemplate::new();
+    /// template.env_template("INSTANCE", "instance-{instance_id}")?;
+    ///
+    /// let mut wasi = WasiCtxBuilder::new();
+    /// wasi.cli_template(&template, &InstanceVars::new(42));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cli_template(&mut self, template: &CliTemplate, vars: &InstanceVars) -> &mut Self {
+        self.cli.environment.extend(template.environment(vars));
+        self.cli.arguments.extend(template.arguments(vars));
+        self
+    }
+
     /// Configures the initial current working directory reported to the guest.
     ///
     /// By default no initial current working directory is configured and
@@ -303,6 +380,56 @@ impl WasiCtxBuilder {
         guest_path: impl AsRef<str>,
         dir_perms: DirPerms,
         file_perms: FilePerms,
+    ) -> Result<&mut Self> {
+        self.preopened_dir_with_symlink_policy(
+            host_path,
+            guest_path,
+            dir_perms,
+            file_perms,
+            SymlinkPolicy::default(),
+        )
+    }
+
+    /// Same as [`WasiCtxBuilder::preopened_dir`], but additionally configures
+    /// how symlinks are treated within the directory.
+    ///
+    /// With [`SymlinkPolicy::NoFollow`] symlinks at the end of paths within
+    /// `guest_path` are never followed, even if the guest requests that they
+    /// are. See [`SymlinkPolicy`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if `host_path` cannot be opened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmtime_wasi::WasiCtxBuilder;
+    /// use wasmtime_wasi::{DirPerms, FilePerms, SymlinkPolicy};
+    ///
+    /// # fn main() {}
+    /// # fn foo() -> wasmtime::Result<()> {
+    /// let mut wasi = WasiCtxBuilder::new();
+    ///
+    /// // Make `./uploads` available in the guest as `/uploads`, without
+    /// // following any symlinks placed within it.
+    /// wasi.preopened_dir_with_symlink_policy(
+    ///     "./uploads",
+    ///     "/uploads",
+    ///     DirPerms::all(),
+    ///     FilePerms::all(),
+    ///     SymlinkPolicy::NoFollow,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn preopened_dir_with_symlink_policy(
+        &mut self,
+        host_path: impl AsRef<Path>,
+        guest_path: impl AsRef<str>,
+        dir_perms: DirPerms,
+        file_perms: FilePerms,
+        symlinks: SymlinkPolicy,
     ) -> Result<&mut Self> {
         let dir = cap_primitives::fs::open_ambient_dir(host_path.as_ref(), ambient_authority())?;
         let mut open_mode = OpenMode::empty();
@@ -312,16 +439,18 @@ impl WasiCtxBuilder {
         if dir_perms.contains(DirPerms::MUTATE) {
             open_mode |= OpenMode::WRITE;
         }
-        self.filesystem.preopens.push((
-            Dir::new(
-                dir,
-                dir_perms,
-                file_perms,
-                open_mode,
-                self.filesystem.allow_blocking_current_thread,
-            ),
-            guest_path.as_ref().to_owned(),
-        ));
+        let mut dir = Dir::new(
+            dir,
+            dir_perms,
+            file_perms,
+            open_mode,
+            self.filesystem.allow_blocking_current_thread,
+        );
+        dir.symlinks = symlinks;
+        dir.host_path = HostPath::preopen(host_path.as_ref());
+        self.filesystem
+            .preopens
+            .push((dir, guest_path.as_ref().to_owned()));
         Ok(self)
     }
 
@@ -372,6 +501,21 @@ impl WasiCtxBuilder {
         self
     }
 
+    /// Configures the backend used to watch directories for changes through
+    /// the `wasmtime:wasi/filesystem-watch` interface.
+    ///
+    /// By default no backend is configured and guests are unable to watch
+    /// directories. With the `notify` feature of this crate enabled,
+    /// [`NotifyFileWatcher`](crate::filesystem::NotifyFileWatcher) can be used
+    /// to watch directories with the host's native change notification APIs.
+    ///
+    /// Only directories within preopens added with
+    /// [`WasiCtxBuilder::preopened_dir`] can be watched.
+    pub fn file_watcher(&mut self, watcher: impl HostFileWatcher + 'static) -> &mut Self {
+        self.filesystem.file_watcher = Some(Arc::new(watcher));
+        self
+    }
+
     /// Configures `wasi:clocks/wall-clock` to use the `clock` specified.
     ///
     /// By default the host's wall clock is used.
@@ -382,9 +526,64 @@ impl WasiCtxBuilder {
 
     /// Configures `wasi:clocks/monotonic-clock` to use the `clock` specified.
     ///
-    /// By default the host's monotonic clock is used.
+    /// By default the host's monotonic clock is used. This replaces any
+    /// [`LogicalClock`] or [`VirtualClock`] configured so far, which guest
+    /// timers then no longer follow.
     pub fn monotonic_clock(&mut self, clock: impl HostMonotonicClock + 'static) -> &mut Self {
         self.clocks.monotonic_clock = Box::new(clock);
+        self.clocks.logical_clock = None;
+        self.clocks.virtual_clock = None;
+        self
+    }
+
+    /// Configures both `wasi:clocks` clocks to use the [`VirtualClock`]
+    /// specified.
+    ///
+    /// Guest timers expire according to the rate of the virtual clock. The
+    /// clock can be adjusted at runtime through a clone of `clock`, or
+    /// through [`WasiClocksCtx::virtual_clock`] after building. See
+    /// [`VirtualClock`] for more information.
+    pub fn virtual_clock(&mut self, clock: VirtualClock) -> &mut Self {
+        self.clocks.wall_clock = Box::new(clock.clone());
+        self.clocks.monotonic_clock = Box::new(clock.clone());
+        self.clocks.logical_clock = None;
+        self.clocks.virtual_clock = Some(clock);
+        self
+    }
+
+    /// Configures this context to behave deterministically.
+    ///
+    /// This replaces the clocks and random number generators configured so
+    /// far such that two guests run with the same `seed` and the same inputs
+    /// observe the same values:
+    ///
+    /// * Both `wasi:clocks` clocks are driven by a single [`LogicalClock`]
+    ///   which starts at zero (the Unix epoch for the wall clock) and only
+    ///   advances when the host calls [`LogicalClock::advance`]. Guest timers
+    ///   become ready based on this logical time rather than real time. The
+    ///   clock can be retrieved after building through
+    ///   [`WasiClocksCtx::logical_clock`].
+    /// * `wasi:random/random`, `wasi:random/insecure`, and
+    ///   `wasi:random/insecure-seed` are all derived from `seed`.
+    ///
+    /// Ready pollables returned from `wasi:io/poll#poll` are always reported
+    /// in the order they were passed in, so with logical time the result of
+    /// polling only depends on the host-driven state of the pollables.
+    ///
+    /// Note that the `wasi:random/random` generator configured here is not
+    /// suitable for any security purpose. Configuring a clock or random
+    /// generator after this method overrides the deterministic one.
+    pub fn deterministic(&mut self, seed: u64) -> &mut Self {
+        let clock = LogicalClock::default();
+        self.clocks.wall_clock = Box::new(clock.clone());
+        self.clocks.monotonic_clock = Box::new(clock.clone());
+        self.clocks.logical_clock = Some(clock);
+        self.clocks.virtual_clock = None;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.random.insecure_random_seed = rng.random();
+        self.random.random = Box::new(StdRng::from_rng(&mut rng));
+        self.random.insecure_random = Box::new(StdRng::from_rng(&mut rng));
         self
     }
 
@@ -416,12 +615,79 @@ impl WasiCtxBuilder {
 
     /// Allow usage of `wasi:sockets/ip-name-lookup`
     ///
-    /// By default this is disabled.
+    /// By default this is disabled. Names are resolved with the host's system
+    /// resolver unless configured otherwise, e.g. with
+    /// [`dns_server`](WasiCtxBuilder::dns_server) or
+    /// [`allow_ip_name_lookup_host`](WasiCtxBuilder::allow_ip_name_lookup_host).
     pub fn allow_ip_name_lookup(&mut self, enable: bool) -> &mut Self {
         self.sockets.allowed_network_uses.ip_name_lookup = enable;
         self
     }
 
+    /// Resolve names for `wasi:sockets/ip-name-lookup` with the DNS server at
+    /// `addr` instead of the host's system resolver.
+    ///
+    /// This can be called multiple times, in which case servers are queried
+    /// in the order they were added until one of them answers. Queries are
+    /// sent over UDP and are not subject to
+    /// [`socket_addr_check`](WasiCtxBuilder::socket_addr_check).
+    pub fn dns_server(&mut self, addr: SocketAddr) -> &mut Self {
+        self.sockets.resolver.servers.push(addr);
+        self
+    }
+
+    /// Adds a search domain for `wasi:sockets/ip-name-lookup`.
+    ///
+    /// Names without any dots are first looked up in each search domain, in
+    /// the order they were added, e.g. `db` is looked up as `db.example.com`
+    /// with a search domain of `example.com`, before being looked up as is.
+    pub fn dns_search_domain(&mut self, domain: &str) -> &mut Self {
+        let domain = domain.trim_matches('.').to_ascii_lowercase();
+        self.sockets.resolver.search_domains.push(domain);
+        self
+    }
+
+    /// Only allow `wasi:sockets/ip-name-lookup` to resolve names matching
+    /// `pattern`.
+    ///
+    /// The pattern is either a host name, matching only that name, `*.` followed
+    /// by a host name, matching all of its subdomains, or `*`, matching any
+    /// name. Patterns are matched case-insensitively against the ASCII form of
+    /// names, so internationalized names need to be given in punycode.
+    ///
+    /// Once any pattern is allowed, names which don't match an allowed pattern
+    /// fail to resolve with `access-denied`. Names can additionally be denied
+    /// with [`deny_ip_name_lookup_host`](WasiCtxBuilder::deny_ip_name_lookup_host).
+    /// Neither applies to IP addresses, which are never looked up.
+    ///
+    /// Note that [`allow_ip_name_lookup`](WasiCtxBuilder::allow_ip_name_lookup)
+    /// must also be enabled for any name to be resolved.
+    pub fn allow_ip_name_lookup_host(&mut self, pattern: &str) -> &mut Self {
+        let pattern = HostPattern::new(pattern);
+        self.sockets.resolver.allowed_hosts.push(pattern);
+        self
+    }
+
+    /// Deny `wasi:sockets/ip-name-lookup` from resolving names matching
+    /// `pattern`, even if they are otherwise allowed.
+    ///
+    /// See [`allow_ip_name_lookup_host`](WasiCtxBuilder::allow_ip_name_lookup_host)
+    /// for the syntax of patterns.
+    pub fn deny_ip_name_lookup_host(&mut self, pattern: &str) -> &mut Self {
+        let pattern = HostPattern::new(pattern);
+        self.sockets.resolver.denied_hosts.push(pattern);
+        self
+    }
+
+    /// Cache names resolved by `wasi:sockets/ip-name-lookup` in `cache`.
+    ///
+    /// By default nothing is cached. See [`DnsCache`] for sharing a cache
+    /// between contexts.
+    pub fn dns_cache(&mut self, cache: DnsCache) -> &mut Self {
+        self.sockets.resolver.cache = Some(cache);
+        self
+    }
+
     /// Allow usage of UDP.
     ///
     /// This is enabled by default, but can be disabled if UDP should be blanket
@@ -440,6 +706,118 @@ impl WasiCtxBuilder {
         self
     }
 
+    /// Allow guests to connect to the Unix domain socket at `path` through the
+    /// `wasmtime:wasi/unix-sockets` interface.
+    ///
+    /// By default no Unix domain sockets may be connected to. Guests must
+    /// name the socket with the same `path`, and connections to any other
+    /// path are denied. Note that the host's permissions still apply, and
+    /// that `path` is resolved relative to the host process's working
+    /// directory if it isn't absolute.
+    ///
+    /// See
+    /// [`add_unix_sockets_to_linker_async`](crate::p2::add_unix_sockets_to_linker_async)
+    /// for making the interface available to guests.
+    pub fn allow_unix_socket(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.sockets
+            .unix_socket_paths
+            .push(path.as_ref().to_owned());
+        self
+    }
+
+    /// Allow guests to spawn the host program described by `process` through
+    /// the `wasmtime:wasi/process` interface.
+    ///
+    /// By default no processes may be spawned. See [`AllowedProcess`] for the
+    /// policy applied to spawned processes, and
+    /// [`add_process_to_linker`](crate::p2::add_process_to_linker) for making
+    /// the interface available to guests.
+    pub fn allow_process(&mut self, process: AllowedProcess) -> &mut Self {
+        self.cli.processes.allowed.push(process);
+        self
+    }
+
+    /// Limits the number of processes guests may have spawned at once to
+    /// `max`.
+    ///
+    /// Processes count towards this limit until the guest drops their handle,
+    /// even once they have exited. Spawning more fails with
+    /// `error-code::limit-reached`. By default the number of processes isn't
+    /// limited.
+    pub fn max_processes(&mut self, max: usize) -> &mut Self {
+        self.cli.processes.max_processes = Some(max);
+        self
+    }
+
+    /// Configures what happens to the guest when it exceeds one of the I/O
+    /// limits configured on this builder.
+    ///
+    /// By default the guest's I/O is delayed until it's within its limits
+    /// again, see [`IoLimitBehavior`].
+    pub fn io_limit_behavior(&mut self, behavior: IoLimitBehavior) -> &mut Self {
+        self.io_limits.behavior = behavior;
+        self
+    }
+
+    /// Limits the rate at which the guest can read from files to
+    /// `bytes_per_sec`.
+    ///
+    /// The limit is shared by all files opened through this context and allows
+    /// bursts of up to one second worth of bytes. By default file reads aren't
+    /// limited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmtime_wasi::{IoLimitBehavior, WasiCtx};
+    ///
+    /// let mut wasi = WasiCtx::builder();
+    /// wasi.max_file_read_rate(10 << 20)
+    ///     .max_file_write_rate(1 << 20)
+    ///     .io_limit_behavior(IoLimitBehavior::Trap);
+    /// let wasi: WasiCtx = wasi.build();
+    /// ```
+    pub fn max_file_read_rate(&mut self, bytes_per_sec: u64) -> &mut Self {
+        self.io_limits.file_read_rate = Some(bytes_per_sec);
+        self
+    }
+
+    /// Same as [`max_file_read_rate`](Self::max_file_read_rate), but for
+    /// writes to files.
+    pub fn max_file_write_rate(&mut self, bytes_per_sec: u64) -> &mut Self {
+        self.io_limits.file_write_rate = Some(bytes_per_sec);
+        self
+    }
+
+    /// Limits the rate at which the guest can receive data from TCP
+    /// connections to `bytes_per_sec`.
+    ///
+    /// The limit is shared by all TCP connections of this context and allows
+    /// bursts of up to one second worth of bytes. By default TCP reads aren't
+    /// limited.
+    pub fn max_socket_read_rate(&mut self, bytes_per_sec: u64) -> &mut Self {
+        self.io_limits.socket_read_rate = Some(bytes_per_sec);
+        self
+    }
+
+    /// Same as [`max_socket_read_rate`](Self::max_socket_read_rate), but for
+    /// sending data over TCP connections.
+    pub fn max_socket_write_rate(&mut self, bytes_per_sec: u64) -> &mut Self {
+        self.io_limits.socket_write_rate = Some(bytes_per_sec);
+        self
+    }
+
+    /// Limits the number of TCP connections the guest can have open at the
+    /// same time to `max`, counting both outgoing and accepted connections.
+    ///
+    /// With [`IoLimitBehavior::Backpressure`] connecting and accepting wait
+    /// for another connection to be closed once the limit is reached. By
+    /// default the number of connections isn't limited.
+    pub fn max_tcp_connections(&mut self, max: usize) -> &mut Self {
+        self.io_limits.tcp_connections = Some(max);
+        self
+    }
+
     /// Uses the configured context so far to construct the final [`WasiCtx`].
     ///
     /// Note that each `WasiCtxBuilder` can only be used to "build" once, and
@@ -456,13 +834,22 @@ impl WasiCtxBuilder {
         let Self {
             cli,
             clocks,
-            filesystem,
+            mut filesystem,
             random,
-            sockets,
+            mut sockets,
+            io_limits,
             built: _,
         } = mem::replace(self, Self::new());
         self.built = true;
 
+        filesystem.rate_limits = io_limits.file_rates();
+        for (dir, _) in filesystem.preopens.iter_mut() {
+            dir.blocking_pool = filesystem.blocking_pool.clone();
+            dir.rate_limits = filesystem.rate_limits.clone();
+        }
+        sockets.rate_limits = io_limits.socket_rates();
+        sockets.tcp_connection_limit = io_limits.tcp_connections();
+
         WasiCtx {
             cli,
             clocks,
@@ -575,4 +962,227 @@ impl WasiCtx {
     pub fn sockets(&mut self) -> &mut WasiSocketsCtx {
         &mut self.sockets
     }
+
+    /// Configures whether the side effects a guest can cause through this
+    /// context are restricted.
+    ///
+    /// While restricted, creating sockets and resolving names fails as if
+    /// networking were disabled, spawning processes is denied, and the
+    /// preopened directories handed to the guest only permit reading.
+    /// Descriptors which were already handed out keep their permissions.
+    ///
+    /// This is intended to be enabled around instantiation, for example in
+    /// conjunction with [`Store::start_function_epoch_deadline`], such that
+    /// start functions can't reach out to the outside world before the
+    /// embedder has finished setting up an instance.
+    ///
+    /// Note that WASIp1 contexts retrieve their preopens once upon first use,
+    /// so restricting them affects the lifetime of the context if the first
+    /// WASI call happens while restricted.
+    ///
+    /// [`Store::start_function_epoch_deadline`]: wasmtime::Store::start_function_epoch_deadline
+    pub fn restrict_side_effects(&mut self, restrict: bool) {
+        self.filesystem.read_only = restrict;
+        self.sockets.allowed_network_uses.restricted = restrict;
+        self.cli.processes.restricted = restrict;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::{Descriptor, WasiFilesystemCtxView};
+    use crate::sockets::ip_name_lookup::resolve_addresses;
+    use wasmtime::component::{Resource, ResourceTable};
+
+    fn preopen_perms(ctx: &mut WasiCtx) -> Result<(DirPerms, FilePerms)> {
+        let mut table = ResourceTable::new();
+        let mut view = WasiFilesystemCtxView {
+            ctx: &mut ctx.filesystem,
+            table: &mut table,
+        };
+        let (fd, _) = view.get_directories()?.remove(0);
+        match table.get(&fd)? {
+            Descriptor::Dir(dir) => Ok((dir.perms, dir.file_perms)),
+            Descriptor::File(_) => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn restrict_side_effects() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut ctx = WasiCtx::builder()
+            .inherit_network()
+            .allow_tcp(true)
+            .allow_udp(true)
+            .allow_ip_name_lookup(true)
+            .allow_process(AllowedProcess::new("true", "/bin/true"))
+            .preopened_dir(dir.path(), ".", DirPerms::all(), FilePerms::all())?
+            .build();
+
+        ctx.restrict_side_effects(true);
+        let network = ctx.sockets.allowed_network_uses;
+        assert!(network.check_allowed_tcp().is_err());
+        assert!(network.check_allowed_udp().is_err());
+        assert!(
+            resolve_addresses(&ctx.sockets, "127.0.0.1".to_string())
+                .await
+                .is_err()
+        );
+        assert!(matches!(
+            ctx.cli.processes.spawn("true", &[], &[]),
+            Err(crate::cli::ProcessError::AccessDenied)
+        ));
+        assert_eq!(preopen_perms(&mut ctx)?, (DirPerms::READ, FilePerms::READ));
+
+        ctx.restrict_side_effects(false);
+        let network = ctx.sockets.allowed_network_uses;
+        assert!(network.check_allowed_tcp().is_ok());
+        assert!(network.check_allowed_udp().is_ok());
+        assert!(
+            resolve_addresses(&ctx.sockets, "127.0.0.1".to_string())
+                .await
+                .is_ok()
+        );
+        if cfg!(unix) {
+            assert!(ctx.cli.processes.spawn("true", &[], &[]).is_ok());
+        }
+        assert_eq!(
+            preopen_perms(&mut ctx)?,
+            (DirPerms::all(), FilePerms::all())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn custom_clocks_replace_logical_clock() {
+        use crate::clocks::{MonotonicClock, WallClock};
+
+        // Only the monotonic clock drives guest timers, so only replacing it
+        // stops the logical clock from doing so.
+        let ctx = WasiCtx::builder()
+            .deterministic(0)
+            .wall_clock(WallClock::new())
+            .build();
+        assert!(ctx.clocks.logical_clock().is_some());
+
+        let ctx = WasiCtx::builder()
+            .deterministic(0)
+            .monotonic_clock(MonotonicClock::new())
+            .build();
+        assert!(ctx.clocks.logical_clock().is_none());
+    }
+
+    #[test]
+    fn deterministic_random() -> Result<()> {
+        use crate::p2::bindings::random::{insecure, insecure_seed, random};
+
+        fn sample(seed: u64) -> Result<(Vec<u8>, u64, Vec<u8>, u64, (u64, u64))> {
+            let mut ctx = WasiCtx::builder().deterministic(seed).build();
+            let rng = &mut ctx.random;
+            Ok((
+                random::Host::get_random_bytes(rng, 32)?,
+                random::Host::get_random_u64(rng)?,
+                insecure::Host::get_insecure_random_bytes(rng, 32)?,
+                insecure::Host::get_insecure_random_u64(rng)?,
+                insecure_seed::Host::insecure_seed(rng)?,
+            ))
+        }
+
+        // The same seed produces the same values, in every interface.
+        assert_eq!(sample(42)?, sample(42)?);
+
+        let (random, random_u64, insecure, insecure_u64, seed) = sample(42)?;
+        let (other_random, other_random_u64, other_insecure, other_insecure_u64, other_seed) =
+            sample(43)?;
+        assert_ne!(random, other_random);
+        assert_ne!(random_u64, other_random_u64);
+        assert_ne!(insecure, other_insecure);
+        assert_ne!(insecure_u64, other_insecure_u64);
+        assert_ne!(seed, other_seed);
+
+        // The secure and insecure generators aren't the same stream.
+        assert_ne!(random, insecure);
+        Ok(())
+    }
+
+    #[test]
+    fn deterministic_time() -> Result<()> {
+        use crate::clocks::WasiClocksCtxView;
+        use crate::p2::bindings::clocks::{monotonic_clock, wall_clock};
+        use crate::p2::bindings::sync::io::poll::HostPollable;
+
+        let mut ctx = WasiCtx::builder().deterministic(0).build();
+        let clock = ctx.clocks.logical_clock().unwrap().clone();
+        let mut table = ResourceTable::new();
+        let mut view = WasiClocksCtxView {
+            ctx: &mut ctx.clocks,
+            table: &mut table,
+        };
+
+        // Both clocks start at zero and stand still as real time passes.
+        let wall_now = |view: &mut WasiClocksCtxView<'_>| -> Result<(u64, u32)> {
+            let now = wall_clock::Host::now(view)?;
+            Ok((now.seconds, now.nanoseconds))
+        };
+        assert_eq!(monotonic_clock::Host::now(&mut view)?, 0);
+        assert_eq!(wall_now(&mut view)?, (0, 0));
+        let sleep = monotonic_clock::Host::subscribe_duration(&mut view, 100)?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(monotonic_clock::Host::now(&mut view)?, 0);
+        assert_eq!(wall_now(&mut view)?, (0, 0));
+        assert!(!view.table.ready(Resource::new_borrow(sleep.rep()))?);
+
+        // Only advancing the logical clock moves time and wakes up timers.
+        clock.advance(std::time::Duration::from_nanos(99));
+        assert_eq!(monotonic_clock::Host::now(&mut view)?, 99);
+        assert_eq!(wall_now(&mut view)?, (0, 99));
+        assert!(!view.table.ready(Resource::new_borrow(sleep.rep()))?);
+
+        clock.advance(std::time::Duration::from_nanos(1));
+        assert_eq!(monotonic_clock::Host::now(&mut view)?, 100);
+        assert_eq!(wall_now(&mut view)?, (0, 100));
+        assert!(view.table.ready(Resource::new_borrow(sleep.rep()))?);
+        Ok(())
+    }
+
+    #[test]
+    fn deterministic_poll_order() -> Result<()> {
+        use crate::clocks::WasiClocksCtxView;
+        use crate::p2::bindings::clocks::monotonic_clock;
+        use crate::p2::bindings::sync::io::poll::Host;
+
+        let mut ctx = WasiCtx::builder().deterministic(0).build();
+        let clock = ctx.clocks.logical_clock().unwrap().clone();
+        let mut table = ResourceTable::new();
+        let mut view = WasiClocksCtxView {
+            ctx: &mut ctx.clocks,
+            table: &mut table,
+        };
+
+        // Create the pollables in the opposite order to how they're polled so
+        // that the resource table's order differs from the guest's.
+        let sleeps = [10, 20, 30]
+            .into_iter()
+            .map(|nanos| monotonic_clock::Host::subscribe_duration(&mut view, nanos))
+            .collect::<Result<Vec<_>>>()?;
+        let poll_list = || {
+            sleeps
+                .iter()
+                .rev()
+                .map(|sleep| Resource::new_borrow(sleep.rep()))
+                .collect::<Vec<_>>()
+        };
+
+        clock.advance(std::time::Duration::from_nanos(10));
+        assert_eq!(view.table.poll(poll_list())?, [2]);
+
+        clock.advance(std::time::Duration::from_nanos(15));
+        assert_eq!(view.table.poll(poll_list())?, [1, 2]);
+        assert_eq!(view.table.poll(poll_list())?, [1, 2]);
+
+        clock.advance(std::time::Duration::from_nanos(5));
+        assert_eq!(view.table.poll(poll_list())?, [0, 1, 2]);
+        Ok(())
+    }
 }
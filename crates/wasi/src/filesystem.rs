@@ -1,9 +1,10 @@
 use crate::clocks::Datetime;
-use crate::runtime::{AbortOnDropJoinHandle, spawn_blocking};
+use crate::limits::RateLimits;
+use crate::runtime::{AbortOnDropJoinHandle, BlockingPool, spawn, spawn_blocking_in};
 use cap_primitives::fs::{DirOptions, FollowSymlinks, Metadata, OpenOptions, SystemTimeSpec};
 use std::collections::hash_map;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tracing::debug;
 use wasmtime::component::{HasData, Resource, ResourceTable};
 use wasmtime::error::Context as _;
@@ -16,6 +17,12 @@ pub(crate) use unix as sys;
 pub(crate) mod windows;
 #[cfg(windows)]
 pub(crate) use windows as sys;
+mod watch;
+
+pub(crate) use self::watch::HostPath;
+#[cfg(feature = "notify")]
+pub use self::watch::NotifyFileWatcher;
+pub use self::watch::{Change, ChangeKind, ChangeSink, HostFileWatcher, Watcher};
 
 /// A helper struct which implements [`HasData`] for the `wasi:filesystem` APIs.
 ///
@@ -64,7 +71,11 @@ impl HasData for WasiFilesystem {
 #[derive(Clone, Default)]
 pub struct WasiFilesystemCtx {
     pub(crate) allow_blocking_current_thread: bool,
+    pub(crate) blocking_pool: Option<BlockingPool>,
+    pub(crate) rate_limits: RateLimits,
     pub(crate) preopens: Vec<(Dir, String)>,
+    pub(crate) read_only: bool,
+    pub(crate) file_watcher: Option<Arc<dyn HostFileWatcher>>,
 }
 
 pub struct WasiFilesystemCtxView<'a> {
@@ -106,9 +117,40 @@ bitflags::bitflags! {
         /// This directory can be mutated, for example by creating new files
         /// within it.
         const MUTATE = 0b10;
+
+        /// New files and directories can be created within this directory,
+        /// even if it can't otherwise be mutated.
+        ///
+        /// Without [`DirPerms::MUTATE`] this doesn't permit modifying or
+        /// removing existing entries. A file created with this permission may
+        /// be written through the descriptor which created it, regardless of
+        /// [`FilePerms`].
+        const CREATE = 0b100;
     }
 }
 
+/// How symlinks are treated when resolving paths within a preopened
+/// directory.
+///
+/// This is configured with
+/// [`WasiCtxBuilder::preopened_dir_with_symlink_policy`](crate::WasiCtxBuilder::preopened_dir_with_symlink_policy)
+/// and applies to all directories opened under the preopened directory as
+/// well. Note that regardless of this policy symlinks are never allowed to
+/// resolve to a path outside of the preopened directory.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Symlinks are followed when the guest asks for them to be, which is the
+    /// default.
+    #[default]
+    Follow,
+    /// Symlinks in the final component of a path are never followed, even if
+    /// the guest asks for them to be. Opening or statting a symlink then
+    /// operates on the symlink itself, or fails if that isn't possible.
+    ///
+    /// Symlinks in intermediate components of a path are still resolved.
+    NoFollow,
+}
+
 bitflags::bitflags! {
     /// Flags determining the method of how paths are resolved.
     #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -674,6 +716,8 @@ pub struct File {
     pub open_mode: OpenMode,
 
     allow_blocking_current_thread: bool,
+    blocking_pool: Option<BlockingPool>,
+    pub(crate) rate_limits: RateLimits,
 }
 
 impl File {
@@ -688,6 +732,8 @@ impl File {
             perms,
             open_mode,
             allow_blocking_current_thread,
+            blocking_pool: None,
+            rate_limits: RateLimits::default(),
         }
     }
 
@@ -697,8 +743,9 @@ impl File {
     /// - Executed directly on the current thread. In this case the `async`
     ///   signature of this method is effectively a lie and the returned
     ///   Future will always be immediately Ready. Or:
-    /// - Spawned on a background thread using [`tokio::task::spawn_blocking`]
-    ///   and immediately awaited.
+    /// - Spawned on a background thread, either of the configured
+    ///   [`BlockingPool`] or using [`tokio::task::spawn_blocking`], and
+    ///   immediately awaited.
     ///
     /// Intentionally blocking the executor thread might seem unorthodox, but is
     /// not actually a problem for specific workloads. See:
@@ -722,7 +769,35 @@ impl File {
         R: Send + 'static,
     {
         let f = self.file.clone();
-        spawn_blocking(move || body(&f))
+        spawn_blocking_in(self.blocking_pool.as_ref(), move || body(&f))
+    }
+
+    /// Same as [`spawn_blocking`](Self::spawn_blocking), but `body` only
+    /// starts running after `delay`, if any.
+    pub(crate) fn spawn_blocking_after<F, R>(
+        &self,
+        delay: Option<Duration>,
+        body: F,
+    ) -> AbortOnDropJoinHandle<R>
+    where
+        F: FnOnce(&std::fs::File) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let Some(delay) = delay else {
+            return self.spawn_blocking(body);
+        };
+        let file = self.clone();
+        spawn(async move {
+            tokio::time::sleep(delay).await;
+            file.spawn_blocking(body).await
+        })
+    }
+
+    /// Returns the pool that blocking operations on this file are spawned
+    /// onto, if one was configured.
+    #[cfg(feature = "p3")]
+    pub(crate) fn blocking_pool(&self) -> Option<&BlockingPool> {
+        self.blocking_pool.as_ref()
     }
 
     /// Returns `Some` when the current thread is allowed to block in filesystem
@@ -786,8 +861,16 @@ pub struct Dir {
     /// cap-primitives doesn't presently provide a cross-platform equivalent
     /// of reading the oflags back out using fcntl.
     pub open_mode: OpenMode,
+    /// How symlinks are treated when resolving paths within this directory,
+    /// inherited by any directories opened under this directory.
+    pub symlinks: SymlinkPolicy,
 
     pub(crate) allow_blocking_current_thread: bool,
+    pub(crate) blocking_pool: Option<BlockingPool>,
+    pub(crate) rate_limits: RateLimits,
+    /// Where this directory is on the host, if known, which is needed to
+    /// watch it for changes.
+    pub(crate) host_path: Option<HostPath>,
 }
 
 impl Dir {
@@ -803,7 +886,20 @@ impl Dir {
             perms,
             file_perms,
             open_mode,
+            symlinks: SymlinkPolicy::default(),
             allow_blocking_current_thread,
+            blocking_pool: None,
+            rate_limits: RateLimits::default(),
+            host_path: None,
+        }
+    }
+
+    /// Returns the `path_flags` to actually resolve paths with, which don't
+    /// follow symlinks if this directory's [`SymlinkPolicy`] forbids it.
+    fn resolve_flags(&self, path_flags: PathFlags) -> PathFlags {
+        match self.symlinks {
+            SymlinkPolicy::Follow => path_flags,
+            SymlinkPolicy::NoFollow => path_flags - PathFlags::SYMLINK_FOLLOW,
         }
     }
 
@@ -813,8 +909,9 @@ impl Dir {
     /// - Executed directly on the current thread. In this case the `async`
     ///   signature of this method is effectively a lie and the returned
     ///   Future will always be immediately Ready. Or:
-    /// - Spawned on a background thread using [`tokio::task::spawn_blocking`]
-    ///   and immediately awaited.
+    /// - Spawned on a background thread, either of the configured
+    ///   [`BlockingPool`] or using [`tokio::task::spawn_blocking`], and
+    ///   immediately awaited.
     ///
     /// Intentionally blocking the executor thread might seem unorthodox, but is
     /// not actually a problem for specific workloads. See:
@@ -830,7 +927,7 @@ impl Dir {
             body(&self.dir)
         } else {
             let d = self.dir.clone();
-            spawn_blocking(move || body(&d)).await
+            spawn_blocking_in(self.blocking_pool.as_ref(), move || body(&d)).await
         }
     }
 
@@ -841,7 +938,7 @@ impl Dir {
     }
 
     pub(crate) async fn create_directory_at(&self, path: String) -> Result<(), ErrorCode> {
-        if !self.perms.contains(DirPerms::MUTATE) {
+        if !self.perms.intersects(DirPerms::MUTATE | DirPerms::CREATE) {
             return Err(ErrorCode::NotPermitted);
         }
         self.run_blocking(move |d| {
@@ -856,6 +953,7 @@ impl Dir {
         path_flags: PathFlags,
         path: String,
     ) -> Result<DescriptorStat, ErrorCode> {
+        let path_flags = self.resolve_flags(path_flags);
         if !self.perms.contains(DirPerms::READ) {
             return Err(ErrorCode::NotPermitted);
         }
@@ -878,6 +976,7 @@ impl Dir {
         atim: Option<SystemTime>,
         mtim: Option<SystemTime>,
     ) -> Result<(), ErrorCode> {
+        let path_flags = self.resolve_flags(path_flags);
         if !self.perms.contains(DirPerms::MUTATE) {
             return Err(ErrorCode::NotPermitted);
         }
@@ -932,15 +1031,25 @@ impl Dir {
         flags: DescriptorFlags,
         allow_blocking_current_thread: bool,
     ) -> Result<Descriptor, ErrorCode> {
+        let path_flags = self.resolve_flags(path_flags);
         if !self.perms.contains(DirPerms::READ) {
             return Err(ErrorCode::NotPermitted);
         }
 
+        // Without `DirPerms::MUTATE`, but with `DirPerms::CREATE`, only new
+        // files may be created. They're always created exclusively so that
+        // existing files can't be written through this path.
+        let create_only = oflags.contains(OpenFlags::CREATE)
+            && !self.perms.contains(DirPerms::MUTATE)
+            && self.perms.contains(DirPerms::CREATE);
+
         if !self.perms.contains(DirPerms::MUTATE) {
-            if oflags.contains(OpenFlags::CREATE) || oflags.contains(OpenFlags::TRUNCATE) {
+            if oflags.contains(OpenFlags::TRUNCATE) {
                 return Err(ErrorCode::NotPermitted);
             }
-            if flags.contains(DescriptorFlags::WRITE) {
+            if !create_only
+                && (oflags.contains(OpenFlags::CREATE) || flags.contains(DescriptorFlags::WRITE))
+            {
                 return Err(ErrorCode::NotPermitted);
             }
         }
@@ -954,7 +1063,7 @@ impl Dir {
         sys::maybe_dir(&mut opts);
 
         if oflags.contains(OpenFlags::CREATE) {
-            if oflags.contains(OpenFlags::EXCLUSIVE) {
+            if oflags.contains(OpenFlags::EXCLUSIVE) || create_only {
                 opts.create_new(true);
             } else {
                 opts.create(true);
@@ -1014,10 +1123,13 @@ impl Dir {
 
         // Now enforce this WasiCtx's permissions before letting the OS have
         // its shot:
-        if !self.perms.contains(DirPerms::MUTATE) && create {
+        if !self.perms.intersects(DirPerms::MUTATE | DirPerms::CREATE) && create {
             return Err(ErrorCode::NotPermitted);
         }
-        if !self.file_perms.contains(FilePerms::WRITE) && open_mode.contains(OpenMode::WRITE) {
+        if !self.file_perms.contains(FilePerms::WRITE)
+            && open_mode.contains(OpenMode::WRITE)
+            && !create_only
+        {
             return Err(ErrorCode::NotPermitted);
         }
 
@@ -1030,6 +1142,7 @@ impl Dir {
             NotDir,
         }
 
+        let host_path = self.host_path.as_ref().map(|p| p.join(&path));
         let opened = self
             .run_blocking::<_, std::io::Result<OpenResult>>(move |d| {
                 let opened = cap_primitives::fs::open(d, path.as_ref(), &opts)?;
@@ -1041,7 +1154,19 @@ impl Dir {
                     Ok(OpenResult::File(opened))
                 }
             })
-            .await?;
+            .await;
+        let opened = match opened {
+            // The guest asked to open an existing file for writing, which it
+            // may not do.
+            Err(e)
+                if create_only
+                    && !oflags.contains(OpenFlags::EXCLUSIVE)
+                    && e.kind() == std::io::ErrorKind::AlreadyExists =>
+            {
+                return Err(ErrorCode::NotPermitted);
+            }
+            opened => opened?,
+        };
 
         match opened {
             // Paper over a divergence between Windows and POSIX, where
@@ -1052,20 +1177,33 @@ impl Dir {
                 Err(ErrorCode::IsDirectory)
             }
 
-            OpenResult::Dir(dir) => Ok(Descriptor::Dir(Dir::new(
-                dir,
-                self.perms,
-                self.file_perms,
-                open_mode,
-                allow_blocking_current_thread,
-            ))),
-
-            OpenResult::File(file) => Ok(Descriptor::File(File::new(
-                file,
-                self.file_perms,
-                open_mode,
-                allow_blocking_current_thread,
-            ))),
+            OpenResult::Dir(dir) => {
+                let mut dir = Dir::new(
+                    dir,
+                    self.perms,
+                    self.file_perms,
+                    open_mode,
+                    allow_blocking_current_thread,
+                );
+                dir.symlinks = self.symlinks;
+                dir.blocking_pool = self.blocking_pool.clone();
+                dir.rate_limits = self.rate_limits.clone();
+                dir.host_path = host_path;
+                Ok(Descriptor::Dir(dir))
+            }
+
+            OpenResult::File(file) => {
+                let file_perms = if create_only {
+                    self.file_perms | FilePerms::WRITE
+                } else {
+                    self.file_perms
+                };
+                let mut file =
+                    File::new(file, file_perms, open_mode, allow_blocking_current_thread);
+                file.blocking_pool = self.blocking_pool.clone();
+                file.rate_limits = self.rate_limits.clone();
+                Ok(Descriptor::File(file))
+            }
 
             OpenResult::NotDir => Err(ErrorCode::NotDirectory),
         }
@@ -1142,6 +1280,7 @@ impl Dir {
         path_flags: PathFlags,
         path: String,
     ) -> Result<MetadataHashValue, ErrorCode> {
+        let path_flags = self.resolve_flags(path_flags);
         // No permissions check on metadata: if dir opened, allowed to stat it
         let follow = if path_flags.contains(PathFlags::SYMLINK_FOLLOW) {
             FollowSymlinks::Yes
@@ -1161,7 +1300,12 @@ impl WasiFilesystemCtxView<'_> {
     ) -> wasmtime::Result<Vec<(Resource<Descriptor>, String)>> {
         let preopens = self.ctx.preopens.clone();
         let mut results = Vec::with_capacity(preopens.len());
-        for (dir, name) in preopens {
+        for (mut dir, name) in preopens {
+            if self.ctx.read_only {
+                dir.perms &= DirPerms::READ;
+                dir.file_perms &= FilePerms::READ;
+                dir.open_mode &= OpenMode::READ;
+            }
             let fd = self
                 .table
                 .push(Descriptor::Dir(dir))
@@ -0,0 +1,317 @@
+//! Support for watching directories for changes, used to implement the
+//! `wasmtime:wasi/filesystem-watch` interface.
+
+use super::{Dir, DirPerms, ErrorCode, WasiFilesystemCtxView};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The maximum number of changes queued for a single watcher.
+///
+/// Once this many changes are queued and unread, the queue is discarded and
+/// the guest is told to rescan the directory instead.
+const MAX_QUEUED_CHANGES: usize = 1024;
+
+/// A backend for watching host directories for changes.
+///
+/// This is configured with
+/// [`WasiCtxBuilder::file_watcher`](crate::WasiCtxBuilder::file_watcher) and
+/// is used to implement `watch` in the `wasmtime:wasi/filesystem-watch`
+/// interface. Without a backend configured guests are unable to watch
+/// directories.
+///
+/// With the `notify` feature of this crate enabled, [`NotifyFileWatcher`]
+/// provides an implementation of this trait using the host's native change
+/// notification APIs.
+pub trait HostFileWatcher: Send + Sync {
+    /// Starts watching the host directory at `path` for changes.
+    ///
+    /// Changes must be reported to `sink` until the returned value is
+    /// dropped. If `recursive` is true then changes within subdirectories of
+    /// `path` must be reported too.
+    ///
+    /// The `path` provided has been canonicalized and is known to be within
+    /// a directory preopened for the guest.
+    fn watch(
+        &self,
+        path: &Path,
+        recursive: bool,
+        sink: ChangeSink,
+    ) -> io::Result<Box<dyn Any + Send + Sync>>;
+}
+
+/// The kind of a [`Change`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// A file or directory was created.
+    Created,
+    /// The contents or metadata of a file or directory were modified.
+    Modified,
+    /// A file or directory was removed.
+    Removed,
+    /// A file or directory was renamed.
+    Renamed,
+    /// Changes were lost and the directory needs to be rescanned.
+    Overflow,
+}
+
+/// A change to a path within a watched directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// What happened to `path`.
+    pub kind: ChangeKind,
+    /// The path that changed, relative to the watched directory and using `/`
+    /// as a separator.
+    pub path: String,
+}
+
+/// The destination that a [`HostFileWatcher`] reports changes to.
+///
+/// Changes are queued here until the guest reads them.
+#[derive(Clone)]
+pub struct ChangeSink {
+    queue: Arc<ChangeQueue>,
+}
+
+impl ChangeSink {
+    /// Reports a change of `kind` to the host path `path`.
+    ///
+    /// Paths outside of the watched directory, and paths which aren't valid
+    /// UTF-8, are ignored.
+    pub fn push(&self, kind: ChangeKind, path: &Path) {
+        let Ok(relative) = path.strip_prefix(&self.queue.root) else {
+            return;
+        };
+        let Some(relative) = relative.to_str() else {
+            return;
+        };
+        #[cfg(windows)]
+        let relative = relative.replace('\\', "/");
+
+        let mut state = self.queue.state.lock().unwrap();
+        if state.overflowed {
+            return;
+        }
+        if state.changes.len() >= MAX_QUEUED_CHANGES {
+            state.changes.clear();
+            state.overflowed = true;
+        } else {
+            state.changes.push_back(Change {
+                kind,
+                path: relative.into(),
+            });
+        }
+        drop(state);
+        self.queue.ready.notify_one();
+    }
+
+    /// Reports that changes were lost, for example because the backend's own
+    /// event queue overflowed.
+    ///
+    /// Any changes which are still queued are discarded and the guest is told
+    /// to rescan the watched directory instead.
+    pub fn overflow(&self) {
+        let mut state = self.queue.state.lock().unwrap();
+        state.changes.clear();
+        state.overflowed = true;
+        drop(state);
+        self.queue.ready.notify_one();
+    }
+}
+
+struct ChangeQueue {
+    root: PathBuf,
+    state: Mutex<QueueState>,
+    ready: tokio::sync::Notify,
+}
+
+#[derive(Default)]
+struct QueueState {
+    changes: VecDeque<Change>,
+    overflowed: bool,
+}
+
+/// An active watch on a directory, created by a [`HostFileWatcher`].
+///
+/// This is the host representation of the `watcher` resource in
+/// `wasmtime:wasi/filesystem-watch`. Dropping it stops the watch.
+pub struct Watcher {
+    queue: Arc<ChangeQueue>,
+    _watch: Box<dyn Any + Send + Sync>,
+}
+
+impl Watcher {
+    /// Returns whether there are any changes available to read.
+    pub(crate) fn has_changes(&self) -> bool {
+        let state = self.queue.state.lock().unwrap();
+        state.overflowed || !state.changes.is_empty()
+    }
+
+    /// Waits until there are changes available to read.
+    pub(crate) async fn ready(&self) {
+        while !self.has_changes() {
+            self.queue.ready.notified().await;
+        }
+    }
+
+    /// Removes and returns up to `max` of the oldest queued changes.
+    pub(crate) fn read_changes(&self, max: usize) -> Vec<Change> {
+        let mut state = self.queue.state.lock().unwrap();
+        let mut changes = Vec::new();
+        if max > 0 && state.overflowed {
+            state.overflowed = false;
+            changes.push(Change {
+                kind: ChangeKind::Overflow,
+                path: String::new(),
+            });
+        }
+        let n = state.changes.len().min(max - changes.len());
+        changes.extend(state.changes.drain(..n));
+        changes
+    }
+}
+
+/// The location on the host of a directory opened by the guest.
+///
+/// This is tracked for directories under preopens so they can be passed to a
+/// [`HostFileWatcher`], which works in terms of host paths rather than file
+/// descriptors.
+#[derive(Clone, Debug)]
+pub(crate) struct HostPath {
+    /// The canonical path of the preopened directory this directory is
+    /// within.
+    root: Arc<Path>,
+    /// The path of this directory, which may not be canonical.
+    path: PathBuf,
+}
+
+impl HostPath {
+    /// Creates the host path of the preopened directory at `path`.
+    pub(crate) fn preopen(path: &Path) -> Option<HostPath> {
+        let root = std::fs::canonicalize(path).ok()?;
+        Some(HostPath {
+            path: root.clone(),
+            root: root.into(),
+        })
+    }
+
+    /// Returns the host path of the directory at the guest path `path`
+    /// relative to this one.
+    ///
+    /// This must only be used for paths which cap-primitives has already
+    /// resolved within the preopen.
+    pub(crate) fn join(&self, path: &str) -> HostPath {
+        HostPath {
+            root: self.root.clone(),
+            path: self.path.join(path),
+        }
+    }
+
+    /// Canonicalizes this path, verifying that it is still within its
+    /// preopen.
+    ///
+    /// The directory may have been moved, or a symlink along the way
+    /// replaced, since it was opened, so this is rechecked each time it's
+    /// used.
+    fn resolve(&self) -> Result<PathBuf, ErrorCode> {
+        let path = std::fs::canonicalize(&self.path)?;
+        if !path.starts_with(&self.root) {
+            return Err(ErrorCode::NotPermitted);
+        }
+        Ok(path)
+    }
+}
+
+impl WasiFilesystemCtxView<'_> {
+    /// Starts watching `dir` for changes with the configured
+    /// [`HostFileWatcher`].
+    pub(crate) fn watch_dir(&self, dir: &Dir, recursive: bool) -> Result<Watcher, ErrorCode> {
+        if !dir.perms.contains(DirPerms::READ) {
+            return Err(ErrorCode::NotPermitted);
+        }
+        let (Some(watcher), Some(host_path)) = (&self.ctx.file_watcher, &dir.host_path) else {
+            return Err(ErrorCode::Unsupported);
+        };
+        let root = host_path.resolve()?;
+        let queue = Arc::new(ChangeQueue {
+            root: root.clone(),
+            state: Mutex::default(),
+            ready: tokio::sync::Notify::new(),
+        });
+        let sink = ChangeSink {
+            queue: queue.clone(),
+        };
+        let watch = watcher.watch(&root, recursive, sink)?;
+        Ok(Watcher {
+            queue,
+            _watch: watch,
+        })
+    }
+}
+
+/// A [`HostFileWatcher`] implemented with the [`notify`] crate.
+///
+/// This uses the host's native change notification API, for example inotify
+/// on Linux, FSEvents on macOS and `ReadDirectoryChangesW` on Windows.
+#[cfg(feature = "notify")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NotifyFileWatcher {
+    _priv: (),
+}
+
+#[cfg(feature = "notify")]
+impl NotifyFileWatcher {
+    /// Creates a new watcher backend.
+    pub fn new() -> NotifyFileWatcher {
+        NotifyFileWatcher::default()
+    }
+}
+
+#[cfg(feature = "notify")]
+impl HostFileWatcher for NotifyFileWatcher {
+    fn watch(
+        &self,
+        path: &Path,
+        recursive: bool,
+        sink: ChangeSink,
+    ) -> io::Result<Box<dyn Any + Send + Sync>> {
+        use notify::event::{EventKind, ModifyKind};
+        use notify::{RecursiveMode, Watcher as _};
+
+        fn to_io(err: notify::Error) -> io::Error {
+            match err.kind {
+                notify::ErrorKind::Io(err) => err,
+                notify::ErrorKind::PathNotFound => io::ErrorKind::NotFound.into(),
+                _ => io::Error::other(err),
+            }
+        }
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let event = match event {
+                    Ok(event) if !event.need_rescan() => event,
+                    _ => return sink.overflow(),
+                };
+                let kind = match event.kind {
+                    EventKind::Create(_) => ChangeKind::Created,
+                    EventKind::Modify(ModifyKind::Name(_)) => ChangeKind::Renamed,
+                    EventKind::Modify(_) | EventKind::Any => ChangeKind::Modified,
+                    EventKind::Remove(_) => ChangeKind::Removed,
+                    EventKind::Access(_) | EventKind::Other => return,
+                };
+                for path in &event.paths {
+                    sink.push(kind, path);
+                }
+            })
+            .map_err(to_io)?;
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher.watch(path, mode).map_err(to_io)?;
+        Ok(Box::new(watcher))
+    }
+}
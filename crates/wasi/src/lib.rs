@@ -35,6 +35,7 @@ pub mod clocks;
 mod ctx;
 mod error;
 pub mod filesystem;
+pub mod limits;
 #[cfg(feature = "p1")]
 pub mod p0;
 #[cfg(feature = "p1")]
@@ -50,10 +51,11 @@ pub mod runtime;
 pub mod sockets;
 mod view;
 
-pub use self::clocks::{HostMonotonicClock, HostWallClock};
+pub use self::clocks::{HostMonotonicClock, HostWallClock, LogicalClock, VirtualClock};
 pub use self::ctx::{WasiCtx, WasiCtxBuilder};
 pub use self::error::{I32Exit, TrappableError};
-pub use self::filesystem::{DirPerms, FilePerms, OpenMode};
+pub use self::filesystem::{DirPerms, FilePerms, OpenMode, SymlinkPolicy};
+pub use self::limits::{IoLimitBehavior, IoLimitExceeded};
 pub use self::random::{Deterministic, thread_rng};
 pub use self::view::{WasiCtxView, WasiView};
 #[doc(no_inline)]
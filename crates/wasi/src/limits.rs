@@ -0,0 +1,298 @@
+//! Rate limits and quotas on the I/O performed by a guest.
+//!
+//! Limits are configured per [`WasiCtx`](crate::WasiCtx) through
+//! [`WasiCtxBuilder`](crate::WasiCtxBuilder), for example with
+//! [`max_file_read_rate`](crate::WasiCtxBuilder::max_file_read_rate) or
+//! [`max_tcp_connections`](crate::WasiCtxBuilder::max_tcp_connections), and
+//! are shared by all files and sockets of that context. What happens to a
+//! guest exceeding its limits is configured with
+//! [`io_limit_behavior`](crate::WasiCtxBuilder::io_limit_behavior).
+
+use crate::runtime::with_ambient_tokio_runtime;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, ready};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// What happens to a guest exceeding one of its I/O limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IoLimitBehavior {
+    /// I/O operations are delayed until the guest is within its limits again.
+    ///
+    /// The guest observes this like any other slow I/O: streams report no
+    /// data or write capacity and become ready once the guest may proceed,
+    /// and blocking operations take longer to complete.
+    #[default]
+    Backpressure,
+    /// The guest traps with an [`IoLimitExceeded`] error.
+    Trap,
+}
+
+/// The error a guest traps with when it exceeds one of its I/O limits with
+/// [`IoLimitBehavior::Trap`].
+#[derive(Debug)]
+pub struct IoLimitExceeded {
+    limit: &'static str,
+}
+
+impl fmt::Display for IoLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} limit exceeded", self.limit)
+    }
+}
+
+impl std::error::Error for IoLimitExceeded {}
+
+/// The configured limits, as recorded by `WasiCtxBuilder`.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct IoLimitsConfig {
+    pub(crate) behavior: IoLimitBehavior,
+    pub(crate) file_read_rate: Option<u64>,
+    pub(crate) file_write_rate: Option<u64>,
+    pub(crate) socket_read_rate: Option<u64>,
+    pub(crate) socket_write_rate: Option<u64>,
+    pub(crate) tcp_connections: Option<usize>,
+}
+
+impl IoLimitsConfig {
+    pub(crate) fn file_rates(&self) -> RateLimits {
+        RateLimits {
+            read: self
+                .file_read_rate
+                .map(|rate| RateLimiter::new("file read rate", rate, self.behavior)),
+            write: self
+                .file_write_rate
+                .map(|rate| RateLimiter::new("file write rate", rate, self.behavior)),
+        }
+    }
+
+    pub(crate) fn socket_rates(&self) -> RateLimits {
+        RateLimits {
+            read: self
+                .socket_read_rate
+                .map(|rate| RateLimiter::new("socket read rate", rate, self.behavior)),
+            write: self
+                .socket_write_rate
+                .map(|rate| RateLimiter::new("socket write rate", rate, self.behavior)),
+        }
+    }
+
+    pub(crate) fn tcp_connections(&self) -> Option<ConnectionLimit> {
+        self.tcp_connections
+            .map(|max| ConnectionLimit::new(max, self.behavior))
+    }
+}
+
+/// The read and write rate limits shared by a class of I/O objects.
+#[derive(Clone, Default)]
+pub(crate) struct RateLimits {
+    pub(crate) read: Option<Arc<RateLimiter>>,
+    pub(crate) write: Option<Arc<RateLimiter>>,
+}
+
+impl RateLimits {
+    /// Waits until reading is within its limit.
+    pub(crate) async fn read_ready(&self) -> Result<(), IoLimitExceeded> {
+        match &self.read {
+            Some(limiter) => limiter.ready().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Waits until writing is within its limit.
+    pub(crate) async fn write_ready(&self) -> Result<(), IoLimitExceeded> {
+        match &self.write {
+            Some(limiter) => limiter.ready().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Returns how long to wait before reading, see [`RateLimiter::check`].
+    pub(crate) fn check_read(&self) -> Result<Option<Duration>, IoLimitExceeded> {
+        self.read
+            .as_ref()
+            .map_or(Ok(None), |limiter| limiter.check())
+    }
+
+    /// Returns how long to wait before writing, see [`RateLimiter::check`].
+    pub(crate) fn check_write(&self) -> Result<Option<Duration>, IoLimitExceeded> {
+        self.write
+            .as_ref()
+            .map_or(Ok(None), |limiter| limiter.check())
+    }
+
+    pub(crate) fn consume_read(&self, bytes: usize) {
+        if let Some(limiter) = &self.read {
+            limiter.consume(bytes);
+        }
+    }
+
+    pub(crate) fn consume_write(&self, bytes: usize) {
+        if let Some(limiter) = &self.write {
+            limiter.consume(bytes);
+        }
+    }
+
+    pub(crate) fn read_throttle(&self) -> Option<Throttle> {
+        self.read.clone().map(Throttle::new)
+    }
+
+    pub(crate) fn write_throttle(&self) -> Option<Throttle> {
+        self.write.clone().map(Throttle::new)
+    }
+}
+
+/// A token bucket limiting a number of bytes per second.
+///
+/// The bucket holds up to one second worth of bytes. Operations are charged
+/// after the fact for the number of bytes they actually transferred, which
+/// may leave the bucket in debt; the guest is over its limit until the debt
+/// has been refilled.
+pub(crate) struct RateLimiter {
+    limit: &'static str,
+    bytes_per_sec: f64,
+    behavior: IoLimitBehavior,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    available: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(
+        limit: &'static str,
+        bytes_per_sec: u64,
+        behavior: IoLimitBehavior,
+    ) -> Arc<RateLimiter> {
+        let bytes_per_sec = bytes_per_sec.max(1) as f64;
+        Arc::new(RateLimiter {
+            limit,
+            bytes_per_sec,
+            behavior,
+            bucket: Mutex::new(Bucket {
+                available: bytes_per_sec,
+                updated: Instant::now(),
+            }),
+        })
+    }
+
+    fn bucket(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * self.bytes_per_sec;
+        bucket.available = (bucket.available + refill).min(self.bytes_per_sec);
+        bucket.updated = now;
+        bucket
+    }
+
+    /// Checks whether the guest is within this limit.
+    ///
+    /// Returns `Ok(None)` if it is, and otherwise either how long to wait
+    /// until it is again or an error if the guest should trap instead.
+    pub(crate) fn check(&self) -> Result<Option<Duration>, IoLimitExceeded> {
+        let available = self.bucket().available;
+        if available >= 0.0 {
+            return Ok(None);
+        }
+        match self.behavior {
+            IoLimitBehavior::Trap => Err(IoLimitExceeded { limit: self.limit }),
+            IoLimitBehavior::Backpressure => {
+                let delay = -available / self.bytes_per_sec;
+                Ok(Some(
+                    Duration::try_from_secs_f64(delay).unwrap_or(Duration::MAX),
+                ))
+            }
+        }
+    }
+
+    /// Charges `bytes` transferred bytes to this limit.
+    pub(crate) fn consume(&self, bytes: usize) {
+        self.bucket().available -= bytes as f64;
+    }
+
+    /// Waits until the guest is within this limit.
+    pub(crate) async fn ready(&self) -> Result<(), IoLimitExceeded> {
+        while let Some(delay) = self.check()? {
+            tokio::time::sleep(delay).await;
+        }
+        Ok(())
+    }
+}
+
+/// A poll-based view of a [`RateLimiter`] for use by a single stream.
+pub(crate) struct Throttle {
+    limiter: Arc<RateLimiter>,
+    delay: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl Throttle {
+    pub(crate) fn new(limiter: Arc<RateLimiter>) -> Throttle {
+        Throttle {
+            limiter,
+            delay: None,
+        }
+    }
+
+    /// Polls until the guest is within the limit, see [`RateLimiter::check`].
+    pub(crate) fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), IoLimitExceeded>> {
+        loop {
+            if let Some(delay) = &mut self.delay {
+                ready!(delay.as_mut().poll(cx));
+                self.delay = None;
+            }
+            match self.limiter.check()? {
+                None => return Poll::Ready(Ok(())),
+                Some(delay) => {
+                    self.delay = Some(Box::pin(with_ambient_tokio_runtime(|| {
+                        tokio::time::sleep(delay)
+                    })));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn consume(&self, bytes: usize) {
+        self.limiter.consume(bytes);
+    }
+}
+
+/// A limit on the number of concurrently open connections.
+#[derive(Clone)]
+pub(crate) struct ConnectionLimit {
+    behavior: IoLimitBehavior,
+    semaphore: Arc<Semaphore>,
+}
+
+impl ConnectionLimit {
+    fn new(max: usize, behavior: IoLimitBehavior) -> ConnectionLimit {
+        ConnectionLimit {
+            behavior,
+            semaphore: Arc::new(Semaphore::new(max)),
+        }
+    }
+
+    /// Returns an error if the guest should trap because it has reached this
+    /// limit.
+    pub(crate) fn check(&self) -> Result<(), IoLimitExceeded> {
+        if self.behavior == IoLimitBehavior::Trap && self.semaphore.available_permits() == 0 {
+            return Err(IoLimitExceeded {
+                limit: "TCP connection",
+            });
+        }
+        Ok(())
+    }
+
+    /// Waits for a connection slot, which is released once the returned
+    /// permit is dropped.
+    pub(crate) async fn acquire(self) -> OwnedSemaphorePermit {
+        self.semaphore
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed")
+    }
+}
@@ -652,6 +652,10 @@ impl WasiP1Ctx {
                 let append = *append;
                 drop(t);
                 let f = self.table.get(&fd)?.file()?;
+                f.rate_limits
+                    .write_ready()
+                    .await
+                    .map_err(|e| StreamError::Trap(e.into()))?;
 
                 let do_write = move |f: &std::fs::File, buf: &[u8]| match (append, write) {
                     // Note that this is implementing Linux semantics of
@@ -676,6 +680,7 @@ impl WasiP1Ctx {
                 };
 
                 let nwritten = nwritten.map_err(|e| StreamError::LastOperationFailed(e.into()))?;
+                f.rate_limits.consume_write(nwritten);
 
                 // If this was a write at the current position then update the
                 // current position with the result, otherwise the current
@@ -1686,6 +1691,10 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiP1Ctx {
                 drop(t);
                 let pos = position.load(Ordering::Relaxed);
                 let file = self.table.get(&fd)?.file()?;
+                file.rate_limits
+                    .read_ready()
+                    .await
+                    .map_err(|e| StreamError::Trap(e.into()))?;
                 let bytes_read = match (file.as_blocking_file(), memory.as_slice_mut(iov)?) {
                     // Try to read directly into wasm memory where possible
                     // when the current thread can block and additionally wasm
@@ -1714,6 +1723,7 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiP1Ctx {
                         buf.len()
                     }
                 };
+                file.rate_limits.consume_read(bytes_read);
 
                 let pos = pos
                     .checked_add(bytes_read.try_into()?)
@@ -2339,6 +2349,8 @@ impl wasi_snapshot_preview1::WasiSnapshotPreview1 for WasiP1Ctx {
                     .flags
                     .contains(types::Subclockflags::SUBSCRIPTION_CLOCK_ABSTIME)
                     && self.wasi.filesystem.allow_blocking_current_thread
+                    && self.wasi.clocks.logical_clock.is_none()
+                    && self.wasi.clocks.virtual_clock.is_none()
                 {
                     std::thread::sleep(std::time::Duration::from_nanos(clocksub.timeout));
                     memory.write(
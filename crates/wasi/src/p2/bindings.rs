@@ -188,6 +188,34 @@ pub mod sync {
     pub use self::generated::exports;
     pub use self::generated::wasi::*;
 
+    mod unix_socks {
+        ::wasmtime::component::bindgen!({
+            // The generated `wasmtime` module for the `wasmtime:wasi` package
+            // would otherwise shadow the `wasmtime` crate.
+            wasmtime_crate: ::wasmtime,
+            path: "src/p2/wit",
+            world: "wasmtime:wasi/unix-sockets-imports",
+            imports: { default: tracing | trappable },
+            trappable_error_type: {
+                "wasi:sockets/network.error-code" => crate::p2::SocketError,
+            },
+            with: {
+                "wasi:io": wasmtime_wasi_io::bindings::wasi::io,
+                "wasi:sockets/network": crate::p2::bindings::sockets::network,
+            },
+            require_store_data_send: true,
+        });
+    }
+
+    /// Synchronous bindings for the Wasmtime-specific
+    /// `wasmtime:wasi/unix-sockets` interface.
+    ///
+    /// See
+    /// [`add_unix_sockets_to_linker_sync`](crate::p2::add_unix_sockets_to_linker_sync)
+    /// for adding it to a linker. For the asynchronous version see
+    /// [`bindings::unix_sockets`](super::unix_sockets).
+    pub use self::unix_socks::wasmtime::wasi::unix_sockets;
+
     /// Synchronous bindings to execute and run a `wasi:cli/command`.
     ///
     /// This structure is automatically generated by `bindgen!`. For the
@@ -545,3 +573,90 @@ pub use self::async_io::Command;
 pub use self::async_io::CommandPre;
 
 pub use self::async_io::CommandIndices;
+
+mod fs_watch {
+    ::wasmtime::component::bindgen!({
+        // The generated `wasmtime` module for the `wasmtime:wasi` package
+        // would otherwise shadow the `wasmtime` crate.
+        wasmtime_crate: ::wasmtime,
+        path: "src/p2/wit",
+        world: "wasmtime:wasi/filesystem-watch-imports",
+        imports: { default: tracing | trappable },
+        trappable_error_type: {
+            "wasi:filesystem/types.error-code" => crate::p2::FsError,
+        },
+        with: {
+            "wasi:io": wasmtime_wasi_io::bindings::wasi::io,
+            "wasi:filesystem/types": crate::p2::bindings::filesystem::types,
+            "wasmtime:wasi/filesystem-watch.watcher": crate::filesystem::Watcher,
+        },
+        require_store_data_send: true,
+    });
+}
+
+/// Bindings for the Wasmtime-specific `wasmtime:wasi/filesystem-watch`
+/// interface, which lets guests watch directories for changes.
+///
+/// This interface isn't part of the `wasi:cli/command` world and isn't added
+/// by [`add_to_linker_async`](crate::p2::add_to_linker_async) or
+/// [`add_to_linker_sync`](crate::p2::add_to_linker_sync). Its functions never
+/// block, so the same bindings are used with both. See
+/// [`add_filesystem_watch_to_linker`](crate::p2::add_filesystem_watch_to_linker)
+/// for adding it to a linker.
+pub use self::fs_watch::wasmtime::wasi::filesystem_watch;
+
+mod unix_socks {
+    ::wasmtime::component::bindgen!({
+        // The generated `wasmtime` module for the `wasmtime:wasi` package
+        // would otherwise shadow the `wasmtime` crate.
+        wasmtime_crate: ::wasmtime,
+        path: "src/p2/wit",
+        world: "wasmtime:wasi/unix-sockets-imports",
+        imports: { default: async | tracing | trappable },
+        trappable_error_type: {
+            "wasi:sockets/network.error-code" => crate::p2::SocketError,
+        },
+        with: {
+            "wasi:io": wasmtime_wasi_io::bindings::wasi::io,
+            "wasi:sockets/network": crate::p2::bindings::sockets::network,
+        },
+        require_store_data_send: true,
+    });
+}
+
+/// Bindings for the Wasmtime-specific `wasmtime:wasi/unix-sockets`
+/// interface, which lets guests connect to Unix domain sockets.
+///
+/// This interface isn't part of the `wasi:cli/command` world and isn't added
+/// by [`add_to_linker_async`](crate::p2::add_to_linker_async). See
+/// [`add_unix_sockets_to_linker_async`](crate::p2::add_unix_sockets_to_linker_async)
+/// for adding it to a linker. For the synchronous version see
+/// [`sync::unix_sockets`].
+pub use self::unix_socks::wasmtime::wasi::unix_sockets;
+
+mod process {
+    ::wasmtime::component::bindgen!({
+        // The generated `wasmtime` module for the `wasmtime:wasi` package
+        // would otherwise shadow the `wasmtime` crate.
+        wasmtime_crate: ::wasmtime,
+        path: "src/p2/wit",
+        world: "wasmtime:wasi/process-imports",
+        imports: { default: tracing | trappable },
+        with: {
+            "wasi:io": wasmtime_wasi_io::bindings::wasi::io,
+            "wasmtime:wasi/process.child": crate::cli::ChildProcess,
+        },
+        require_store_data_send: true,
+    });
+}
+
+/// Bindings for the Wasmtime-specific `wasmtime:wasi/process` interface,
+/// which lets guests spawn host processes.
+///
+/// This interface isn't part of the `wasi:cli/command` world and isn't added
+/// by [`add_to_linker_async`](crate::p2::add_to_linker_async) or
+/// [`add_to_linker_sync`](crate::p2::add_to_linker_sync). Its functions never
+/// block, so the same bindings are used with both. See
+/// [`add_process_to_linker`](crate::p2::add_process_to_linker) for adding it
+/// to a linker.
+pub use self::process::wasmtime::wasi::process as cli_process;
@@ -1,6 +1,7 @@
 use crate::TrappableError;
 use crate::filesystem::File;
 use crate::filesystem::sys;
+use crate::limits::RateLimits;
 use crate::p2::bindings::filesystem::types;
 use crate::p2::{InputStream, OutputStream, Pollable, StreamError, StreamResult};
 use crate::runtime::AbortOnDropJoinHandle;
@@ -84,12 +85,18 @@ impl FileInputStream {
         }
     }
 
-    fn blocking_read(file: &std::fs::File, offset: u64, size: usize) -> ReadState {
+    fn blocking_read(
+        file: &std::fs::File,
+        offset: u64,
+        size: usize,
+        limits: &RateLimits,
+    ) -> ReadState {
         let mut buf = BytesMut::zeroed(size.min(crate::MAX_READ_SIZE_ALLOC));
         loop {
             match sys::read_at_cursor_unspecified(file, &mut buf, offset) {
                 Ok(0) => return ReadState::Closed,
                 Ok(n) => {
+                    limits.consume_read(n);
                     buf.truncate(n);
                     return ReadState::DataAvailable(buf.freeze());
                 }
@@ -120,11 +127,16 @@ impl InputStream for FileInputStream {
                     return Ok(Bytes::new());
                 }
 
+                let delay = self
+                    .file
+                    .rate_limits
+                    .check_read()
+                    .map_err(|e| StreamError::Trap(e.into()))?;
                 let p = self.position;
-                self.state = ReadState::Waiting(
-                    self.file
-                        .spawn_blocking(move |f| Self::blocking_read(f, p, size)),
-                );
+                let limits = self.file.rate_limits.clone();
+                self.state = ReadState::Waiting(self.file.spawn_blocking_after(delay, move |f| {
+                    Self::blocking_read(f, p, size, &limits)
+                }));
                 Ok(Bytes::new())
             }
             ReadState::DataAvailable(b) => {
@@ -151,10 +163,16 @@ impl InputStream for FileInputStream {
 
         // Before we defer to the regular `read`, make sure it has data ready to go:
         if let ReadState::Idle = self.state {
+            self.file
+                .rate_limits
+                .read_ready()
+                .await
+                .map_err(|e| StreamError::Trap(e.into()))?;
             let p = self.position;
+            let limits = self.file.rate_limits.clone();
             self.state = self
                 .file
-                .run_blocking(move |f| Self::blocking_read(f, p, size))
+                .run_blocking(move |f| Self::blocking_read(f, p, size, &limits))
                 .await;
         }
 
@@ -184,14 +202,19 @@ impl Pollable for FileInputStream {
     async fn ready(&mut self) {
         if let ReadState::Idle = self.state {
             // The guest hasn't initiated any read, but is nonetheless waiting
-            // for data to be available. We'll start a read for them:
-
+            // for data to be available. We'll start a read for them.
+            //
+            // If the guest is over its read limit and would trap, leave it to
+            // the next `read` to report that.
             const DEFAULT_READ_SIZE: usize = 4096;
+            let Ok(delay) = self.file.rate_limits.check_read() else {
+                return;
+            };
             let p = self.position;
-            self.state = ReadState::Waiting(
-                self.file
-                    .spawn_blocking(move |f| Self::blocking_read(f, p, DEFAULT_READ_SIZE)),
-            );
+            let limits = self.file.rate_limits.clone();
+            self.state = ReadState::Waiting(self.file.spawn_blocking_after(delay, move |f| {
+                Self::blocking_read(f, p, DEFAULT_READ_SIZE, &limits)
+            }));
         }
 
         self.wait_ready().await
@@ -291,6 +314,7 @@ impl OutputStream for FileOutputStream {
         }
 
         let m = self.mode;
+        self.file.rate_limits.consume_write(buf.len());
         self.state = OutputState::Waiting(
             self.file
                 .spawn_blocking(move |f| Self::blocking_write(f, buf, m)),
@@ -311,8 +335,14 @@ impl OutputStream for FileOutputStream {
             },
             OutputState::Waiting(_) => unreachable!("we've just waited for readiness"),
         }
+        self.file
+            .rate_limits
+            .write_ready()
+            .await
+            .map_err(|e| StreamError::Trap(e.into()))?;
 
         let m = self.mode;
+        self.file.rate_limits.consume_write(buf.len());
         match self
             .file
             .run_blocking(move |f| Self::blocking_write(f, buf, m))
@@ -346,7 +376,11 @@ impl OutputStream for FileOutputStream {
     }
     fn check_write(&mut self) -> Result<usize, StreamError> {
         match self.state {
-            OutputState::Ready => Ok(FILE_WRITE_CAPACITY),
+            OutputState::Ready => match self.file.rate_limits.check_write() {
+                Ok(None) => Ok(FILE_WRITE_CAPACITY),
+                Ok(Some(_)) => Ok(0),
+                Err(e) => Err(StreamError::Trap(e.into())),
+            },
             OutputState::Closed => Err(StreamError::Closed),
             OutputState::Error(_) => match mem::replace(&mut self.state, OutputState::Closed) {
                 OutputState::Error(e) => Err(StreamError::LastOperationFailed(e.into())),
@@ -389,6 +423,10 @@ impl Pollable for FileOutputStream {
                 Err(e) => OutputState::Error(e),
             };
         }
+        if let OutputState::Ready = self.state {
+            // Any error is reported by the next `check_write`.
+            let _ = self.file.rate_limits.write_ready().await;
+        }
     }
 }
 
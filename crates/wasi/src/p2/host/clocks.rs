@@ -1,4 +1,4 @@
-use crate::clocks::WasiClocksCtxView;
+use crate::clocks::{LogicalClock, WasiClocksCtx, WasiClocksCtxView};
 use crate::p2::DynPollable;
 use crate::p2::bindings::{
     clocks::monotonic_clock::{self, Duration as WasiDuration, Instant},
@@ -70,11 +70,26 @@ impl wall_clock::Host for WasiClocksCtxView<'_> {
 
 fn subscribe_to_duration(
     table: &mut wasmtime::component::ResourceTable,
+    clocks: &WasiClocksCtx,
     duration: tokio::time::Duration,
 ) -> wasmtime::Result<Resource<DynPollable>> {
     let sleep = if duration.is_zero() {
         table.push(Deadline::Past { yielded: false })?
-    } else if let Some(deadline) = tokio::time::Instant::now().checked_add(duration) {
+    } else if let Some(clock) = &clocks.logical_clock {
+        match u64::try_from(duration.as_nanos())
+            .ok()
+            .and_then(|nanos| clock.now().checked_add(nanos))
+        {
+            Some(when) => table.push(Deadline::Logical {
+                clock: clock.clone(),
+                when,
+            })?,
+            None => table.push(Deadline::Never)?,
+        }
+    } else if let Some(deadline) = clocks
+        .host_duration(duration)
+        .and_then(|duration| tokio::time::Instant::now().checked_add(duration))
+    {
         // NB: this resource created here is not actually exposed to wasm, it's
         // only an internal implementation detail used to match the signature
         // expected by `subscribe`.
@@ -103,20 +118,21 @@ impl monotonic_clock::Host for WasiClocksCtxView<'_> {
         } else {
             Duration::from_nanos(0)
         };
-        subscribe_to_duration(self.table, duration)
+        subscribe_to_duration(self.table, self.ctx, duration)
     }
 
     fn subscribe_duration(
         &mut self,
         duration: WasiDuration,
     ) -> wasmtime::Result<Resource<DynPollable>> {
-        subscribe_to_duration(self.table, Duration::from_nanos(duration))
+        subscribe_to_duration(self.table, self.ctx, Duration::from_nanos(duration))
     }
 }
 
 enum Deadline {
     Past { yielded: bool },
     Instant(tokio::time::Instant),
+    Logical { clock: LogicalClock, when: u64 },
     Never,
 }
 
@@ -147,6 +163,7 @@ impl Pollable for Deadline {
                 tokio::task::yield_now().await
             }
             Deadline::Instant(instant) => tokio::time::sleep_until(*instant).await,
+            Deadline::Logical { clock, when } => clock.wait_until(*when).await,
             Deadline::Never => std::future::pending().await,
         }
     }
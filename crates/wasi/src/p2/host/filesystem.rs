@@ -111,6 +111,7 @@ impl HostDescriptor for WasiFilesystemCtxView<'_> {
         if !f.perms.contains(FilePerms::READ) {
             return Err(ErrorCode::NotPermitted.into());
         }
+        f.rate_limits.read_ready().await.map_err(FsError::trap)?;
 
         let (mut buffer, r) = f
             .run_blocking(move |f| {
@@ -131,6 +132,7 @@ impl HostDescriptor for WasiFilesystemCtxView<'_> {
         };
 
         buffer.truncate(bytes_read);
+        f.rate_limits.consume_read(bytes_read);
 
         Ok((buffer, state))
     }
@@ -145,10 +147,12 @@ impl HostDescriptor for WasiFilesystemCtxView<'_> {
         if !f.perms.contains(FilePerms::WRITE) {
             return Err(ErrorCode::NotPermitted.into());
         }
+        f.rate_limits.write_ready().await.map_err(FsError::trap)?;
 
         let bytes_written = f
             .run_blocking(move |f| sys::write_at_cursor_unspecified(f, &buf, offset))
             .await?;
+        f.rate_limits.consume_write(bytes_written);
 
         Ok(types::Filesize::try_from(bytes_written).expect("usize fits in Filesize"))
     }
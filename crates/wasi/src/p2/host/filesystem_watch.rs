@@ -0,0 +1,61 @@
+use crate::filesystem::{ChangeKind, WasiFilesystemCtxView, Watcher};
+use crate::p2::FsResult;
+use crate::p2::bindings::filesystem::types::Descriptor;
+use crate::p2::bindings::filesystem_watch::{self, Change, HostWatcher};
+use wasmtime::component::Resource;
+use wasmtime_wasi_io::poll::{DynPollable, Pollable, subscribe};
+
+impl filesystem_watch::Host for WasiFilesystemCtxView<'_> {
+    fn watch(&mut self, dir: Resource<Descriptor>, recursive: bool) -> FsResult<Resource<Watcher>> {
+        let dir = self.table.get(&dir)?.dir()?;
+        let watcher = self.watch_dir(dir, recursive)?;
+        Ok(self.table.push(watcher)?)
+    }
+}
+
+impl HostWatcher for WasiFilesystemCtxView<'_> {
+    fn subscribe(&mut self, watcher: Resource<Watcher>) -> wasmtime::Result<Resource<DynPollable>> {
+        subscribe(self.table, watcher)
+    }
+
+    fn read_changes(
+        &mut self,
+        watcher: Resource<Watcher>,
+        max: u32,
+    ) -> wasmtime::Result<Vec<Change>> {
+        let watcher = self.table.get(&watcher)?;
+        let max = usize::try_from(max).unwrap_or(usize::MAX);
+        Ok(watcher
+            .read_changes(max)
+            .into_iter()
+            .map(|change| Change {
+                kind: change.kind.into(),
+                path: change.path,
+            })
+            .collect())
+    }
+
+    fn drop(&mut self, watcher: Resource<Watcher>) -> wasmtime::Result<()> {
+        self.table.delete(watcher)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Pollable for Watcher {
+    async fn ready(&mut self) {
+        Watcher::ready(self).await
+    }
+}
+
+impl From<ChangeKind> for filesystem_watch::ChangeKind {
+    fn from(kind: ChangeKind) -> Self {
+        match kind {
+            ChangeKind::Created => Self::Created,
+            ChangeKind::Modified => Self::Modified,
+            ChangeKind::Removed => Self::Removed,
+            ChangeKind::Renamed => Self::Renamed,
+            ChangeKind::Overflow => Self::Overflow,
+        }
+    }
+}
@@ -2,11 +2,14 @@ mod clocks;
 mod env;
 mod exit;
 pub(crate) mod filesystem;
+mod filesystem_watch;
 mod instance_network;
 mod io;
 pub(crate) mod network;
+mod process;
 mod random;
 mod tcp;
 mod tcp_create_socket;
 mod udp;
 mod udp_create_socket;
+mod unix_sockets;
@@ -0,0 +1,115 @@
+use crate::cli::{ChildProcess, ProcessError, ProcessExit, WasiCliCtxView};
+use crate::p2::bindings::cli_process::{self, Command, ErrorCode, ExitStatus, HostChild};
+use crate::p2::pipe::{AsyncReadStream, AsyncWriteStream};
+use crate::p2::{DynInputStream, DynOutputStream};
+use wasmtime::component::Resource;
+use wasmtime_wasi_io::poll::{DynPollable, Pollable, subscribe};
+
+impl cli_process::Host for WasiCliCtxView<'_> {
+    fn spawn(
+        &mut self,
+        command: Command,
+    ) -> wasmtime::Result<Result<Resource<ChildProcess>, ErrorCode>> {
+        let child = match self
+            .ctx
+            .processes
+            .spawn(&command.program, &command.args, &command.env)
+        {
+            Ok(child) => child,
+            Err(e) => return Ok(Err(e.into())),
+        };
+        Ok(Ok(self.table.push(child)?))
+    }
+}
+
+impl HostChild for WasiCliCtxView<'_> {
+    fn take_stdin(
+        &mut self,
+        child: Resource<ChildProcess>,
+    ) -> wasmtime::Result<Option<Resource<DynOutputStream>>> {
+        let Some(stdin) = self.table.get_mut(&child)?.stdin.take() else {
+            return Ok(None);
+        };
+        let stream: DynOutputStream =
+            Box::new(AsyncWriteStream::new(crate::MAX_READ_SIZE_ALLOC, stdin));
+        Ok(Some(self.table.push_child(stream, &child)?))
+    }
+
+    fn take_stdout(
+        &mut self,
+        child: Resource<ChildProcess>,
+    ) -> wasmtime::Result<Option<Resource<DynInputStream>>> {
+        let Some(stdout) = self.table.get_mut(&child)?.stdout.take() else {
+            return Ok(None);
+        };
+        let stream: DynInputStream = Box::new(AsyncReadStream::new(stdout));
+        Ok(Some(self.table.push_child(stream, &child)?))
+    }
+
+    fn take_stderr(
+        &mut self,
+        child: Resource<ChildProcess>,
+    ) -> wasmtime::Result<Option<Resource<DynInputStream>>> {
+        let Some(stderr) = self.table.get_mut(&child)?.stderr.take() else {
+            return Ok(None);
+        };
+        let stream: DynInputStream = Box::new(AsyncReadStream::new(stderr));
+        Ok(Some(self.table.push_child(stream, &child)?))
+    }
+
+    fn subscribe(
+        &mut self,
+        child: Resource<ChildProcess>,
+    ) -> wasmtime::Result<Resource<DynPollable>> {
+        subscribe(self.table, child)
+    }
+
+    fn exit_status(
+        &mut self,
+        child: Resource<ChildProcess>,
+    ) -> wasmtime::Result<Option<ExitStatus>> {
+        Ok(self
+            .table
+            .get(&child)?
+            .exit_status()
+            .map(|exit| exit.into()))
+    }
+
+    fn kill(&mut self, child: Resource<ChildProcess>) -> wasmtime::Result<()> {
+        self.table.get(&child)?.kill();
+        Ok(())
+    }
+
+    fn drop(&mut self, child: Resource<ChildProcess>) -> wasmtime::Result<()> {
+        self.table.delete(child)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Pollable for ChildProcess {
+    async fn ready(&mut self) {
+        self.exited().await
+    }
+}
+
+impl From<ProcessError> for ErrorCode {
+    fn from(err: ProcessError) -> Self {
+        match err {
+            ProcessError::AccessDenied => Self::AccessDenied,
+            ProcessError::NotFound => Self::NotFound,
+            ProcessError::LimitReached => Self::LimitReached,
+            ProcessError::Unsupported => Self::Unsupported,
+            ProcessError::Other => Self::Other,
+        }
+    }
+}
+
+impl From<ProcessExit> for ExitStatus {
+    fn from(exit: ProcessExit) -> Self {
+        match exit {
+            ProcessExit::Exited(code) => Self::Exited(code),
+            ProcessExit::Terminated => Self::Terminated,
+        }
+    }
+}
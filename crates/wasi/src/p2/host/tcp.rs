@@ -1,4 +1,4 @@
-use crate::p2::{Pollable, SocketResult, tcp::TcpSocket};
+use crate::p2::{Pollable, SocketError, SocketResult, tcp::TcpSocket};
 use crate::p2::{
     bindings::sockets::{
         network::{ErrorCode, IpAddressFamily, IpSocketAddress, Network},
@@ -66,6 +66,10 @@ impl crate::p2::host::tcp::tcp::HostTcpSocket for WasiSocketsCtxView<'_> {
             return Err(ErrorCode::ConcurrencyConflict.into());
         }
 
+        socket
+            .inner
+            .check_connection_limit()
+            .map_err(SocketError::trap)?;
         socket.inner.start_connect(remote_address)?;
         socket.in_progress_operation = Some(AsyncOperation::Connect);
         Ok(())
@@ -133,6 +137,9 @@ impl crate::p2::host::tcp::tcp::HostTcpSocket for WasiSocketsCtxView<'_> {
         let Some(listener) = &mut socket.listener else {
             return Err(ErrorCode::InvalidState.into());
         };
+        listener
+            .check_connection_limit()
+            .map_err(SocketError::trap)?;
 
         let accepted = match listener.poll_accept(&mut noop_cx()) {
             Poll::Pending => return Err(ErrorCode::WouldBlock.into()),
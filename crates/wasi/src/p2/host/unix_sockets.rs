@@ -0,0 +1,40 @@
+use crate::p2::bindings::{sync, unix_sockets};
+use crate::p2::{DynInputStream, DynOutputStream, SocketResult};
+use crate::runtime::in_tokio;
+use crate::sockets::WasiSocketsCtxView;
+use wasmtime::component::Resource;
+
+impl unix_sockets::Host for WasiSocketsCtxView<'_> {
+    #[cfg(unix)]
+    async fn connect(
+        &mut self,
+        path: String,
+    ) -> SocketResult<(Resource<DynInputStream>, Resource<DynOutputStream>)> {
+        use crate::p2::pipe::{AsyncReadStream, AsyncWriteStream};
+
+        let (reader, writer) = self.ctx.connect_unix(&path).await?.into_split();
+        let input: DynInputStream = Box::new(AsyncReadStream::new(reader));
+        let output: DynOutputStream =
+            Box::new(AsyncWriteStream::new(crate::MAX_READ_SIZE_ALLOC, writer));
+        let input = self.table.push(input)?;
+        let output = self.table.push(output)?;
+        Ok((input, output))
+    }
+
+    #[cfg(not(unix))]
+    async fn connect(
+        &mut self,
+        path: String,
+    ) -> SocketResult<(Resource<DynInputStream>, Resource<DynOutputStream>)> {
+        match self.ctx.connect_unix(&path).await? {}
+    }
+}
+
+impl sync::unix_sockets::Host for WasiSocketsCtxView<'_> {
+    fn connect(
+        &mut self,
+        path: String,
+    ) -> SocketResult<(Resource<DynInputStream>, Resource<DynOutputStream>)> {
+        in_tokio(unix_sockets::Host::connect(self, path))
+    }
+}
@@ -472,6 +472,140 @@ pub fn add_to_linker_with_options_sync<T: WasiView>(
     Ok(())
 }
 
+/// Add the Wasmtime-specific `wasmtime:wasi/filesystem-watch` interface to the
+/// `linker` provided.
+///
+/// This interface lets guests watch directories for changes and is not part
+/// of WASI, so it isn't added by [`add_to_linker_async`] or
+/// [`add_to_linker_sync`]. It can be used alongside either of them. Watching
+/// directories additionally requires configuring a backend with
+/// [`WasiCtxBuilder::file_watcher`](crate::WasiCtxBuilder::file_watcher),
+/// otherwise guests will receive `error-code::unsupported`.
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::{Engine, Result};
+/// use wasmtime::component::{Linker, ResourceTable};
+/// use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+///
+/// fn main() -> Result<()> {
+///     let engine = Engine::default();
+///
+///     let mut linker = Linker::<MyState>::new(&engine);
+///     wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+///     wasmtime_wasi::p2::add_filesystem_watch_to_linker(&mut linker)?;
+///     Ok(())
+/// }
+///
+/// struct MyState {
+///     ctx: WasiCtx,
+///     table: ResourceTable,
+/// }
+///
+/// impl WasiView for MyState {
+///     fn ctx(&mut self) -> WasiCtxView<'_> {
+///         WasiCtxView { ctx: &mut self.ctx, table: &mut self.table }
+///     }
+/// }
+/// ```
+pub fn add_filesystem_watch_to_linker<T: WasiView>(linker: &mut Linker<T>) -> wasmtime::Result<()> {
+    bindings::filesystem_watch::add_to_linker::<T, WasiFilesystem>(linker, T::filesystem)
+}
+
+/// Add the Wasmtime-specific `wasmtime:wasi/unix-sockets` interface to the
+/// `linker` provided.
+///
+/// This interface lets guests connect to Unix domain sockets and is not part
+/// of WASI, so it isn't added by [`add_to_linker_async`]. It can be used
+/// alongside it, and [`add_unix_sockets_to_linker_sync`] is the counterpart
+/// to use alongside [`add_to_linker_sync`]. Guests may only connect to the
+/// paths allowed with
+/// [`WasiCtxBuilder::allow_unix_socket`](crate::WasiCtxBuilder::allow_unix_socket),
+/// and receive `error-code::not-supported` on hosts without Unix domain
+/// sockets.
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::{Engine, Result};
+/// use wasmtime::component::{Linker, ResourceTable};
+/// use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+///
+/// fn main() -> Result<()> {
+///     let engine = Engine::default();
+///
+///     let mut linker = Linker::<MyState>::new(&engine);
+///     wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+///     wasmtime_wasi::p2::add_unix_sockets_to_linker_async(&mut linker)?;
+///     Ok(())
+/// }
+///
+/// struct MyState {
+///     ctx: WasiCtx,
+///     table: ResourceTable,
+/// }
+///
+/// impl WasiView for MyState {
+///     fn ctx(&mut self) -> WasiCtxView<'_> {
+///         WasiCtxView { ctx: &mut self.ctx, table: &mut self.table }
+///     }
+/// }
+/// ```
+pub fn add_unix_sockets_to_linker_async<T: WasiView>(
+    linker: &mut Linker<T>,
+) -> wasmtime::Result<()> {
+    bindings::unix_sockets::add_to_linker::<T, WasiSockets>(linker, T::sockets)
+}
+
+/// Synchronous version of [`add_unix_sockets_to_linker_async`], to be used
+/// alongside [`add_to_linker_sync`].
+pub fn add_unix_sockets_to_linker_sync<T: WasiView>(
+    linker: &mut Linker<T>,
+) -> wasmtime::Result<()> {
+    bindings::sync::unix_sockets::add_to_linker::<T, WasiSockets>(linker, T::sockets)
+}
+
+/// Add the Wasmtime-specific `wasmtime:wasi/process` interface to the
+/// `linker` provided.
+///
+/// This interface lets guests spawn host processes and is not part of WASI,
+/// so it isn't added by [`add_to_linker_async`] or [`add_to_linker_sync`]. It
+/// can be used alongside either of them. Guests may only spawn the programs
+/// allowed with
+/// [`WasiCtxBuilder::allow_process`](crate::WasiCtxBuilder::allow_process).
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::{Engine, Result};
+/// use wasmtime::component::{Linker, ResourceTable};
+/// use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+///
+/// fn main() -> Result<()> {
+///     let engine = Engine::default();
+///
+///     let mut linker = Linker::<MyState>::new(&engine);
+///     wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+///     wasmtime_wasi::p2::add_process_to_linker(&mut linker)?;
+///     Ok(())
+/// }
+///
+/// struct MyState {
+///     ctx: WasiCtx,
+///     table: ResourceTable,
+/// }
+///
+/// impl WasiView for MyState {
+///     fn ctx(&mut self) -> WasiCtxView<'_> {
+///         WasiCtxView { ctx: &mut self.ctx, table: &mut self.table }
+///     }
+/// }
+/// ```
+pub fn add_process_to_linker<T: WasiView>(linker: &mut Linker<T>) -> wasmtime::Result<()> {
+    bindings::cli_process::add_to_linker::<T, WasiCli>(linker, T::cli)
+}
+
 /// Shared functionality of [`add_to_linker_sync`]` and
 /// [`add_to_linker_proxy_interfaces_sync`].
 fn add_sync_wasi_io<T: WasiView>(
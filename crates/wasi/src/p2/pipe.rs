@@ -8,9 +8,11 @@
 //! but the virtual pipes can be instantiated with any `Read` or `Write` type.
 //!
 use bytes::Bytes;
+use std::fmt;
 use std::pin::{Pin, pin};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::SystemTime;
 use tokio::io::{self, AsyncRead, AsyncWrite};
 use tokio::sync::mpsc;
 use wasmtime::format_err;
@@ -146,6 +148,197 @@ impl AsyncWrite for MemoryOutputPipe {
     }
 }
 
+/// A line of output captured by a [`LineOutputPipe`].
+#[derive(Debug, Clone)]
+pub struct CapturedLine {
+    /// The time at which the line's terminating newline was written.
+    pub timestamp: SystemTime,
+    /// The contents of the line, not including its terminating newline.
+    pub line: Bytes,
+}
+
+type LineCallback = Arc<dyn Fn(&CapturedLine) + Send + Sync>;
+
+/// An output pipe which captures what's written to it as a sequence of
+/// timestamped lines.
+///
+/// This is similar to [`MemoryOutputPipe`] except that output is split on
+/// newlines as it's written and each line is recorded along with the time it
+/// was completed. A callback can additionally be provided with
+/// [`LineOutputPipe::with_callback`] to stream lines to the host as they
+/// arrive, for example to forward a guest's stdout to a log.
+///
+/// At most `capacity` bytes, including newlines, are captured. Unlike
+/// [`MemoryOutputPipe`], writing beyond the capacity isn't an error for the
+/// guest: the excess output is discarded and [`LineOutputPipe::truncated`]
+/// will return `true`.
+///
+/// Output following the last newline is buffered until another newline is
+/// written or [`LineOutputPipe::finish`] is called.
+#[derive(Clone)]
+pub struct LineOutputPipe {
+    capacity: usize,
+    state: Arc<Mutex<LineOutputState>>,
+    on_line: Option<LineCallback>,
+}
+
+#[derive(Default)]
+struct LineOutputState {
+    lines: Vec<CapturedLine>,
+    partial: bytes::BytesMut,
+    consumed: usize,
+    truncated: bool,
+}
+
+impl LineOutputPipe {
+    /// Creates a new pipe which captures at most `capacity` bytes of output.
+    pub fn new(capacity: usize) -> Self {
+        LineOutputPipe {
+            capacity,
+            state: Default::default(),
+            on_line: None,
+        }
+    }
+
+    /// Creates a new pipe which captures at most `capacity` bytes of output
+    /// and invokes `on_line` with each line as it's completed.
+    ///
+    /// The callback is invoked on whichever thread is writing to the pipe and
+    /// must not block.
+    pub fn with_callback(
+        capacity: usize,
+        on_line: impl Fn(&CapturedLine) + Send + Sync + 'static,
+    ) -> Self {
+        LineOutputPipe {
+            on_line: Some(Arc::new(on_line)),
+            ..LineOutputPipe::new(capacity)
+        }
+    }
+
+    /// Returns the lines which have been completed so far.
+    pub fn lines(&self) -> Vec<CapturedLine> {
+        self.state.lock().unwrap().lines.clone()
+    }
+
+    /// Returns output written after the last newline which hasn't yet been
+    /// recorded as a line.
+    pub fn partial_line(&self) -> Bytes {
+        self.state.lock().unwrap().partial.clone().freeze()
+    }
+
+    /// Returns whether output was discarded because it exceeded this pipe's
+    /// capacity.
+    pub fn truncated(&self) -> bool {
+        self.state.lock().unwrap().truncated
+    }
+
+    /// Records any output written after the last newline as a final line.
+    ///
+    /// This is intended to be called once the guest has finished running so
+    /// output which doesn't end in a newline isn't lost.
+    pub fn finish(&self) {
+        let line = {
+            let mut state = self.state.lock().unwrap();
+            if state.partial.is_empty() {
+                return;
+            }
+            let line = CapturedLine {
+                timestamp: SystemTime::now(),
+                line: state.partial.split().freeze(),
+            };
+            state.lines.push(line.clone());
+            line
+        };
+        if let Some(on_line) = &self.on_line {
+            on_line(&line);
+        }
+    }
+
+    fn append(&self, mut bytes: &[u8]) {
+        let completed = {
+            let mut state = self.state.lock().unwrap();
+            let remaining = self.capacity - state.consumed;
+            if bytes.len() > remaining {
+                bytes = &bytes[..remaining];
+                state.truncated = true;
+            }
+            state.consumed += bytes.len();
+
+            let start = state.lines.len();
+            while let Some(i) = bytes.iter().position(|b| *b == b'\n') {
+                state.partial.extend_from_slice(&bytes[..i]);
+                bytes = &bytes[i + 1..];
+                let line = state.partial.split().freeze();
+                state.lines.push(CapturedLine {
+                    timestamp: SystemTime::now(),
+                    line,
+                });
+            }
+            state.partial.extend_from_slice(bytes);
+
+            // Invoke the callback after the lock is released so it's free to
+            // inspect this pipe.
+            match &self.on_line {
+                Some(_) => state.lines[start..].to_vec(),
+                None => Vec::new(),
+            }
+        };
+        if let Some(on_line) = &self.on_line {
+            for line in completed.iter() {
+                on_line(line);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for LineOutputPipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineOutputPipe")
+            .field("capacity", &self.capacity)
+            .field("has_callback", &self.on_line.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl OutputStream for LineOutputPipe {
+    fn write(&mut self, bytes: Bytes) -> Result<(), StreamError> {
+        self.append(&bytes);
+        Ok(())
+    }
+    fn flush(&mut self) -> Result<(), StreamError> {
+        // This stream is always flushed
+        Ok(())
+    }
+    fn check_write(&mut self) -> Result<usize, StreamError> {
+        // Output past the capacity is discarded, so this stream is always
+        // ready for writing.
+        Ok(usize::MAX)
+    }
+}
+
+#[async_trait::async_trait]
+impl Pollable for LineOutputPipe {
+    async fn ready(&mut self) {}
+}
+
+impl AsyncWrite for LineOutputPipe {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.append(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
 /// Provides a [`InputStream`] impl from a [`tokio::io::AsyncRead`] impl
 pub struct AsyncReadStream {
     closed: bool,
@@ -904,4 +1097,58 @@ mod test {
             .expect("ready is ok");
         assert_eq!(permit, 1024);
     }
+
+    #[test]
+    fn line_output_pipe_splits_lines() {
+        let mut pipe = LineOutputPipe::new(1024);
+        OutputStream::write(&mut pipe, Bytes::from_static(b"hello\nwor")).unwrap();
+        OutputStream::write(&mut pipe, Bytes::from_static(b"ld\n\npartial")).unwrap();
+
+        let lines = pipe.lines();
+        let lines = lines.iter().map(|l| &l.line[..]).collect::<Vec<_>>();
+        assert_eq!(lines, [&b"hello"[..], b"world", b""]);
+        assert_eq!(&pipe.partial_line()[..], b"partial");
+        assert!(!pipe.truncated());
+
+        pipe.finish();
+        assert_eq!(&pipe.lines()[3].line[..], b"partial");
+        assert!(pipe.partial_line().is_empty());
+    }
+
+    #[test]
+    fn line_output_pipe_capacity() {
+        let mut pipe = LineOutputPipe::new(8);
+        OutputStream::write(&mut pipe, Bytes::from_static(b"abc\ndefgh\nij\n")).unwrap();
+        assert!(pipe.truncated());
+        assert_eq!(OutputStream::check_write(&mut pipe).unwrap(), usize::MAX);
+        OutputStream::write(&mut pipe, Bytes::from_static(b"more\n")).unwrap();
+
+        let lines = pipe.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(&lines[0].line[..], b"abc");
+        assert_eq!(&pipe.partial_line()[..], b"defg");
+    }
+
+    #[tokio::test]
+    async fn line_output_pipe_callback() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let pipe = LineOutputPipe::with_callback(1024, {
+            let seen = seen.clone();
+            move |line| seen.lock().unwrap().push(line.line.clone())
+        });
+
+        let mut writer = pipe.clone();
+        writer.write_all(b"one\ntwo").await.unwrap();
+        assert_eq!(*seen.lock().unwrap(), [Bytes::from_static(b"one")]);
+        writer.write_all(b"\nthree").await.unwrap();
+        pipe.finish();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                Bytes::from_static(b"one"),
+                Bytes::from_static(b"two"),
+                Bytes::from_static(b"three"),
+            ]
+        );
+    }
 }
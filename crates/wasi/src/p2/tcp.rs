@@ -78,6 +78,11 @@ impl ReadState {
         if size == 0 {
             return Ok(bytes::Bytes::new());
         }
+        match stream.poll_limit(&mut noop_cx()) {
+            Poll::Pending => return Ok(bytes::Bytes::new()),
+            Poll::Ready(Err(e)) => return Err(StreamError::Trap(e.into())),
+            Poll::Ready(Ok(())) => {}
+        }
         let mut buf = bytes::BytesMut::zeroed(size.min(crate::MAX_READ_SIZE_ALLOC));
         let n = match stream.poll_read(&mut noop_cx(), &mut buf) {
             Poll::Pending => 0,
@@ -102,7 +107,11 @@ impl ReadState {
 
     fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<()> {
         match self {
-            Self::Open(stream) => stream.poll_ready(cx),
+            Self::Open(stream) => {
+                // A limit error is reported by the next `read`.
+                let _ = ready!(stream.poll_limit(cx));
+                stream.poll_ready(cx)
+            }
             Self::Closed => Poll::Ready(()),
         }
     }
@@ -272,10 +281,15 @@ impl WriteState {
         }
 
         match self {
-            WriteState::Ready(stream, permit) => match stream.poll_ready(cx) {
-                Poll::Ready(()) => Poll::Ready(Ok((stream, permit))),
-                Poll::Pending => Poll::Pending,
-            },
+            WriteState::Ready(stream, permit) => {
+                if let Err(e) = ready!(stream.poll_limit(cx)) {
+                    return Poll::Ready(Err(StreamError::Trap(e.into())));
+                }
+                match stream.poll_ready(cx) {
+                    Poll::Ready(()) => Poll::Ready(Ok((stream, permit))),
+                    Poll::Pending => Poll::Pending,
+                }
+            }
             WriteState::Writing(..) | WriteState::Closing(..) => Poll::Pending,
             WriteState::Closed(e) => Poll::Ready(Err(e.clone().into())),
         }
@@ -27,19 +27,40 @@ impl<U> monotonic_clock::HostWithStore<U> for WasiClocks {
         store: &Accessor<U, Self>,
         when: monotonic_clock::Mark,
     ) -> wasmtime::Result<()> {
-        let clock_now = store.with(|mut view| view.get().ctx.monotonic_clock.now());
+        let (clock_now, logical_clock, delay) = store.with(|mut view| {
+            let ctx = view.get().ctx;
+            let clock_now = ctx.monotonic_clock.now();
+            let delay = ctx.host_duration(Duration::from_nanos(when.saturating_sub(clock_now)));
+            (clock_now, ctx.logical_clock.clone(), delay)
+        });
         if when > clock_now {
-            sleep(Duration::from_nanos(when - clock_now)).await;
+            match (logical_clock, delay) {
+                (Some(clock), _) => clock.wait_until(when).await,
+                (None, Some(delay)) => sleep(delay).await,
+                (None, None) => std::future::pending().await,
+            }
         };
         Ok(())
     }
 
     async fn wait_for(
-        _store: &Accessor<U, Self>,
+        store: &Accessor<U, Self>,
         duration: types::Duration,
     ) -> wasmtime::Result<()> {
         if duration > 0 {
-            sleep(Duration::from_nanos(duration)).await;
+            let (logical_clock, delay) = store.with(|mut view| {
+                let ctx = view.get().ctx;
+                let delay = ctx.host_duration(Duration::from_nanos(duration));
+                (ctx.logical_clock.clone(), delay)
+            });
+            match (logical_clock, delay) {
+                (Some(clock), _) => match clock.now().checked_add(duration) {
+                    Some(when) => clock.wait_until(when).await,
+                    None => std::future::pending().await,
+                },
+                (None, Some(delay)) => sleep(delay).await,
+                (None, None) => std::future::pending().await,
+            }
         }
         Ok(())
     }
@@ -1,5 +1,6 @@
 use crate::filesystem::sys;
 use crate::filesystem::{Descriptor, Dir, File, WasiFilesystem, WasiFilesystemCtxView};
+use crate::limits::Throttle;
 use crate::p3::bindings::clocks::system_clock;
 use crate::p3::bindings::filesystem::types::{
     self, Advice, DescriptorFlags, DescriptorStat, DescriptorType, DirectoryEntry, ErrorCode,
@@ -7,6 +8,7 @@ use crate::p3::bindings::filesystem::types::{
 };
 use crate::p3::filesystem::{FilesystemError, FilesystemResult, preopens};
 use crate::p3::{DEFAULT_BUFFER_CAPACITY, FallibleIteratorProducer};
+use crate::runtime::{BlockingPool, spawn_blocking_raw_in};
 use crate::{DirPerms, FilePerms};
 use bytes::BytesMut;
 use core::pin::Pin;
@@ -16,7 +18,7 @@ use std::io;
 use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::sync::{mpsc, oneshot};
-use tokio::task::{JoinHandle, spawn_blocking};
+use tokio::task::JoinHandle;
 use wasmtime::StoreContextMut;
 use wasmtime::component::{
     Access, Accessor, Destination, FutureReader, Resource, ResourceTable, Source, StreamConsumer,
@@ -125,6 +127,7 @@ struct ReadStreamProducer {
     offset: u64,
     result: Option<oneshot::Sender<Result<(), ErrorCode>>>,
     task: Option<JoinHandle<std::io::Result<BytesMut>>>,
+    throttle: Option<Throttle>,
 }
 
 impl Drop for ReadStreamProducer {
@@ -166,6 +169,17 @@ impl<D> StreamProducer<D> for ReadStreamProducer {
         mut dst: Destination<'a, Self::Item, Self::Buffer>,
         finish: bool,
     ) -> Poll<wasmtime::Result<StreamResult>> {
+        // Don't start another read until the guest is within its read limit.
+        let me = &mut *self;
+        if let (None, Some(throttle)) = (&me.task, &mut me.throttle) {
+            match throttle.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                Poll::Pending if finish => return Poll::Ready(Ok(StreamResult::Cancelled)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
         if let Some(file) = self.file.as_blocking_file() {
             // Once a blocking file, always a blocking file, so assert as such.
             assert!(self.task.is_none());
@@ -180,6 +194,7 @@ impl<D> StreamProducer<D> for ReadStreamProducer {
                     Poll::Ready(Ok(StreamResult::Dropped))
                 }
                 Ok(n) => {
+                    self.file.rate_limits.consume_read(n);
                     dst.mark_written(n);
                     Poll::Ready(Ok(self.complete_read(n)))
                 }
@@ -197,7 +212,7 @@ impl<D> StreamProducer<D> for ReadStreamProducer {
             buf.resize(DEFAULT_BUFFER_CAPACITY, 0);
             let file = Arc::clone(me.file.as_file());
             let offset = me.offset;
-            spawn_blocking(move || {
+            spawn_blocking_raw_in(me.file.blocking_pool(), move || {
                 sys::read_at_cursor_unspecified(&file, &mut buf, offset).map(|n| {
                     buf.truncate(n);
                     buf
@@ -226,6 +241,7 @@ impl<D> StreamProducer<D> for ReadStreamProducer {
             }
             Ok(Ok(buf)) => {
                 let n = buf.len();
+                self.file.rate_limits.consume_read(n);
                 dst.set_buffer(buf);
                 Poll::Ready(Ok(self.complete_read(n)))
             }
@@ -285,11 +301,12 @@ struct ReadDirStream {
 impl ReadDirStream {
     fn new(
         dir: Arc<std::fs::File>,
+        pool: Option<&BlockingPool>,
         result: oneshot::Sender<Result<(), ErrorCode>>,
     ) -> ReadDirStream {
         let (tx, rx) = mpsc::channel(1);
         ReadDirStream {
-            task: spawn_blocking(move || {
+            task: spawn_blocking_raw_in(pool, move || {
                 let entries = cap_primitives::fs::read_base_dir(&dir)?;
                 for entry in entries {
                     if let Some(entry) = map_dir_entry(entry)? {
@@ -374,6 +391,7 @@ struct WriteStreamConsumer {
     result: Option<oneshot::Sender<Result<(), ErrorCode>>>,
     buffer: BytesMut,
     task: Option<JoinHandle<std::io::Result<(BytesMut, usize)>>>,
+    throttle: Option<Throttle>,
 }
 
 #[derive(Copy, Clone)]
@@ -385,6 +403,7 @@ enum WriteLocation {
 impl WriteStreamConsumer {
     fn new_at(file: File, offset: u64, result: oneshot::Sender<Result<(), ErrorCode>>) -> Self {
         Self {
+            throttle: file.rate_limits.write_throttle(),
             file,
             location: WriteLocation::Offset(offset),
             result: Some(result),
@@ -395,6 +414,7 @@ impl WriteStreamConsumer {
 
     fn new_append(file: File, result: oneshot::Sender<Result<(), ErrorCode>>) -> Self {
         Self {
+            throttle: file.rate_limits.write_throttle(),
             file,
             location: WriteLocation::End,
             result: Some(result),
@@ -446,12 +466,24 @@ impl<D> StreamConsumer<D> for WriteStreamConsumer {
         src: Source<Self::Item>,
         finish: bool,
     ) -> Poll<wasmtime::Result<StreamResult>> {
+        // Don't start another write until the guest is within its write limit.
+        let me = &mut *self;
+        if let (None, Some(throttle)) = (&me.task, &mut me.throttle) {
+            match throttle.poll_ready(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+                Poll::Pending if finish => return Poll::Ready(Ok(StreamResult::Cancelled)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
         let mut src = src.as_direct(store);
         if let Some(file) = self.file.as_blocking_file() {
             // Once a blocking file, always a blocking file, so assert as such.
             assert!(self.task.is_none());
             return match self.location.write(file, src.remaining()) {
                 Ok(n) => {
+                    self.file.rate_limits.consume_write(n);
                     src.mark_read(n);
                     Poll::Ready(Ok(self.complete_write(n)))
                 }
@@ -468,7 +500,9 @@ impl<D> StreamConsumer<D> for WriteStreamConsumer {
             let buf = mem::take(&mut me.buffer);
             let file = Arc::clone(me.file.as_file());
             let location = me.location;
-            spawn_blocking(move || location.write(&file, &buf).map(|n| (buf, n)))
+            spawn_blocking_raw_in(me.file.blocking_pool(), move || {
+                location.write(&file, &buf).map(|n| (buf, n))
+            })
         });
         let result = match Pin::new(&mut *task).poll(cx) {
             // If cancellation is requested, then flag that to Tokio. Note that
@@ -483,6 +517,7 @@ impl<D> StreamConsumer<D> for WriteStreamConsumer {
         self.task = None;
         match result {
             Ok(Ok((buf, n))) => {
+                self.file.rate_limits.consume_write(n);
                 src.mark_read(n);
                 self.buffer = buf;
                 self.buffer.clear();
@@ -538,6 +573,7 @@ impl<U> types::HostDescriptorWithStore<U> for WasiFilesystem {
             StreamReader::new(
                 &mut store,
                 ReadStreamProducer {
+                    throttle: file.rate_limits.read_throttle(),
                     file,
                     offset,
                     result: Some(result_tx),
@@ -679,6 +715,7 @@ impl<U> types::HostDescriptorWithStore<U> for WasiFilesystem {
         }) {
             Ok(dir) => {
                 let allow_blocking_current_thread = dir.allow_blocking_current_thread;
+                let pool = dir.blocking_pool.clone();
                 let dir = Arc::clone(dir.as_dir());
                 if allow_blocking_current_thread {
                     match cap_primitives::fs::read_base_dir(&dir) {
@@ -695,7 +732,10 @@ impl<U> types::HostDescriptorWithStore<U> for WasiFilesystem {
                         }
                     }
                 } else {
-                    StreamReader::new(&mut store, ReadDirStream::new(dir, result_tx))?
+                    StreamReader::new(
+                        &mut store,
+                        ReadDirStream::new(dir, pool.as_ref(), result_tx),
+                    )?
                 }
             }
             Err(err) => {
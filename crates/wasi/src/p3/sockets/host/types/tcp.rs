@@ -66,6 +66,7 @@ where
             };
         }
 
+        self.listener.check_connection_limit()?;
         let socket = match self.listener.poll_accept(cx) {
             Poll::Ready(socket) => socket,
             Poll::Pending if finish => return Poll::Ready(Ok(StreamResult::Cancelled)),
@@ -112,6 +113,13 @@ impl<D> StreamProducer<D> for ReceiveStreamProducer {
             return Poll::Ready(Ok(StreamResult::Dropped));
         };
 
+        match stream.poll_limit(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+            Poll::Pending if finish => return Poll::Ready(Ok(StreamResult::Cancelled)),
+            Poll::Pending => return Poll::Pending,
+        }
+
         // 0-length read is a readiness check.
         if dst.remaining(store.as_context_mut()) == Some(0) {
             return match stream.poll_ready(cx) {
@@ -172,6 +180,13 @@ impl<D> StreamConsumer<D> for SendStreamConsumer {
             return Poll::Ready(Ok(StreamResult::Dropped));
         };
 
+        match stream.poll_limit(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err.into())),
+            Poll::Pending if finish => return Poll::Ready(Ok(StreamResult::Cancelled)),
+            Poll::Pending => return Poll::Pending,
+        }
+
         let mut src = src.as_direct(store);
 
         // A 0-length write is a readiness check.
@@ -209,6 +224,7 @@ impl<T: Send> HostTcpSocketWithStore<T> for WasiSockets {
 
         store.with(|mut store| {
             let socket = get_socket_mut(store.get().table, &socket)?;
+            socket.check_connection_limit().map_err(SocketError::trap)?;
             let socket = socket.start_connect(remote_address)?;
             SocketResult::Ok(socket)
         })?;
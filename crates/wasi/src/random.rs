@@ -130,6 +130,21 @@ mod test {
             assert_eq!(*b, (ix % 4) as u8 + 1)
         }
     }
+
+    #[test]
+    fn deterministic_ctx() {
+        fn sample(seed: u64) -> (Vec<u8>, Vec<u8>, u128) {
+            let mut ctx = crate::WasiCtx::builder().deterministic(seed).build();
+            let random = ctx.random();
+            let mut secure = vec![0; 32];
+            random.random.fill_bytes(&mut secure);
+            let mut insecure = vec![0; 32];
+            random.insecure_random.fill_bytes(&mut insecure);
+            (secure, insecure, random.insecure_random_seed)
+        }
+        assert_eq!(sample(1), sample(1));
+        assert_ne!(sample(1), sample(2));
+    }
 }
 
 pub fn thread_rng() -> Box<dyn Rng + Send> {
@@ -16,13 +16,20 @@
 //! [`spawn_blocking`] over tokio's. so we wanted the type name to stick out
 //! if someone misses it.
 //!
+//! Blocking operations may additionally be isolated from the embedder's own
+//! blocking tasks by running them on the dedicated [`BlockingPool`] of an
+//! [`Engine`], see
+//! [`WasiCtxBuilder::blocking_pool`](crate::WasiCtxBuilder::blocking_pool).
+//!
 //! Each of these facilities should be used by dependencies of wasmtime-wasi
 //! which when implementing component bindings.
 
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
 use std::task::{Context, Poll, Waker};
+use wasmtime::Engine;
 
 pub(crate) static RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
     tokio::runtime::Builder::new_multi_thread()
@@ -170,6 +177,327 @@ pub fn with_ambient_tokio_runtime<R>(f: impl FnOnce() -> R) -> R {
     }
 }
 
+/// Policy applied by a [`BlockingPool`] when a blocking operation is submitted
+/// while the pool's queue is already full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlockingPoolRejection {
+    /// Run the rejected operation directly on the thread that submitted it.
+    ///
+    /// Operations are submitted from within asynchronous WASI
+    /// implementations, so this runs blocking work on a thread of the async
+    /// executor, stalling every other task scheduled on that thread until the
+    /// operation finishes. This provides backpressure but should only be used
+    /// when the executor can tolerate blocked threads, for example when it
+    /// has more threads than guests that might be rejected at once.
+    CallerRuns,
+    /// Hand the rejected operation to Tokio's shared blocking pool, the same
+    /// one used when no [`BlockingPool`] is configured. This is the default.
+    #[default]
+    Ambient,
+}
+
+/// A snapshot of the activity of a [`BlockingPool`], as returned by
+/// [`BlockingPool::metrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockingPoolMetrics {
+    /// Number of operations waiting for a thread to become available.
+    pub queued: usize,
+    /// Number of operations currently executing on a pool thread.
+    pub running: usize,
+    /// Total number of operations which ran to completion on the pool.
+    pub completed: u64,
+    /// Total number of operations which were rejected because the queue was
+    /// full, and were handled according to the [`BlockingPoolRejection`]
+    /// policy instead.
+    pub rejected: u64,
+}
+
+/// Builder for a [`BlockingPool`].
+#[derive(Debug, Clone)]
+pub struct BlockingPoolBuilder {
+    max_threads: usize,
+    max_queued: Option<usize>,
+    rejection: BlockingPoolRejection,
+    thread_name: String,
+}
+
+impl BlockingPoolBuilder {
+    /// Sets the maximum number of threads that the pool will spawn to run
+    /// blocking operations.
+    ///
+    /// Defaults to 16 and must be at least 1.
+    pub fn max_threads(&mut self, max: usize) -> &mut Self {
+        self.max_threads = max;
+        self
+    }
+
+    /// Sets the maximum number of operations that can wait for a thread to
+    /// become available before new operations are rejected according to
+    /// [`BlockingPoolBuilder::rejection`].
+    ///
+    /// Defaults to `None`, meaning that the queue is unbounded and operations
+    /// are never rejected.
+    pub fn max_queued(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_queued = max;
+        self
+    }
+
+    /// Sets the policy applied to operations submitted while the queue is
+    /// full.
+    pub fn rejection(&mut self, policy: BlockingPoolRejection) -> &mut Self {
+        self.rejection = policy;
+        self
+    }
+
+    /// Sets the name of the threads spawned by this pool.
+    ///
+    /// Defaults to `"wasi-blocking"`.
+    pub fn thread_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.thread_name = name.into();
+        self
+    }
+
+    /// Creates the [`BlockingPool`] and installs it as the pool shared by all
+    /// WASI contexts configured for `engine`, see [`BlockingPool::for_engine`].
+    ///
+    /// Returns an error if `engine` already has a pool, either because this
+    /// was called before or because [`BlockingPool::for_engine`] already
+    /// created one with the default settings.
+    pub fn install(&self, engine: &Engine) -> std::io::Result<BlockingPool> {
+        let mut created = false;
+        let pool = engine.get_or_try_init_extension(|| {
+            created = true;
+            self.build().map(EnginePool)
+        })?;
+        if !created {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "a blocking pool was already created for this engine",
+            ));
+        }
+        Ok(pool.0.clone())
+    }
+
+    /// Creates the [`BlockingPool`], spawning its dedicated Tokio runtime.
+    ///
+    /// Most embedders want a single pool per [`Engine`] which is created with
+    /// [`BlockingPoolBuilder::install`] instead.
+    pub fn build(&self) -> std::io::Result<BlockingPool> {
+        if self.max_threads == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "a blocking pool requires at least one thread",
+            ));
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .max_blocking_threads(self.max_threads)
+            .thread_name(self.thread_name.clone())
+            .build()?;
+        Ok(BlockingPool {
+            inner: Arc::new(BlockingPoolInner {
+                runtime: Some(runtime),
+                max_threads: self.max_threads,
+                max_queued: self.max_queued,
+                rejection: self.rejection,
+                queued: AtomicUsize::new(0),
+                running: AtomicUsize::new(0),
+                completed: AtomicU64::new(0),
+                rejected: AtomicU64::new(0),
+            }),
+        })
+    }
+}
+
+impl Default for BlockingPoolBuilder {
+    fn default() -> Self {
+        BlockingPoolBuilder {
+            max_threads: 16,
+            max_queued: None,
+            rejection: BlockingPoolRejection::default(),
+            thread_name: "wasi-blocking".to_string(),
+        }
+    }
+}
+
+/// A private, size-capped pool of threads used to run the blocking operations
+/// of WASI implementations, such as filesystem accesses and name resolution.
+///
+/// By default blocking operations are run on Tokio's shared blocking pool
+/// which is also used by the embedder's own workload. A `BlockingPool` isolates
+/// guests from the host application (and vice versa) by running these
+/// operations on dedicated threads instead.
+///
+/// Each [`Engine`] has at most one pool, obtained with
+/// [`BlockingPool::for_engine`] or configured with
+/// [`BlockingPoolBuilder::install`], which is shared by all of the
+/// [`WasiCtx`](crate::WasiCtx)s configured with
+/// [`WasiCtxBuilder::blocking_pool`](crate::WasiCtxBuilder::blocking_pool).
+/// The pool is owned by the engine, cheaply cloneable, and its threads are
+/// shut down once the engine and all contexts using it are dropped.
+#[derive(Clone)]
+pub struct BlockingPool {
+    inner: Arc<BlockingPoolInner>,
+}
+
+struct BlockingPoolInner {
+    runtime: Option<tokio::runtime::Runtime>,
+    max_threads: usize,
+    max_queued: Option<usize>,
+    rejection: BlockingPoolRejection,
+    queued: AtomicUsize,
+    running: AtomicUsize,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl BlockingPool {
+    /// Returns a builder used to configure a new pool.
+    pub fn builder() -> BlockingPoolBuilder {
+        BlockingPoolBuilder::default()
+    }
+
+    /// Returns the pool shared by all WASI contexts of `engine`.
+    ///
+    /// The pool is created with the default settings of [`BlockingPoolBuilder`]
+    /// the first time this is called for `engine`, unless one was already
+    /// installed with [`BlockingPoolBuilder::install`].
+    pub fn for_engine(engine: &Engine) -> std::io::Result<BlockingPool> {
+        let pool =
+            engine.get_or_try_init_extension(|| BlockingPool::builder().build().map(EnginePool))?;
+        Ok(pool.0.clone())
+    }
+
+    /// Returns a snapshot of the current activity of this pool.
+    pub fn metrics(&self) -> BlockingPoolMetrics {
+        let inner = &*self.inner;
+        BlockingPoolMetrics {
+            queued: inner.queued.load(Ordering::Relaxed),
+            running: inner.running.load(Ordering::Relaxed),
+            completed: inner.completed.load(Ordering::Relaxed),
+            rejected: inner.rejected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Runs the blocking function `f` on this pool.
+    pub fn spawn_blocking<F, R>(&self, f: F) -> AbortOnDropJoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        AbortOnDropJoinHandle(self.spawn_blocking_raw(f))
+    }
+
+    pub(crate) fn spawn_blocking_raw<F, R>(&self, f: F) -> tokio::task::JoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let inner = &*self.inner;
+        let runtime = inner.runtime.as_ref().unwrap();
+
+        // Reserve a slot in the queue, unless it's already full in which case
+        // the rejection policy takes over.
+        let reserved = inner.queued.fetch_add(1, Ordering::Relaxed);
+        let in_flight = reserved + inner.running.load(Ordering::Relaxed);
+        if let Some(max_queued) = inner.max_queued {
+            if in_flight >= inner.max_threads + max_queued {
+                inner.queued.fetch_sub(1, Ordering::Relaxed);
+                inner.rejected.fetch_add(1, Ordering::Relaxed);
+                return match inner.rejection {
+                    BlockingPoolRejection::CallerRuns => {
+                        let result = f();
+                        runtime.spawn(async move { result })
+                    }
+                    BlockingPoolRejection::Ambient => {
+                        with_ambient_tokio_runtime(|| tokio::task::spawn_blocking(f))
+                    }
+                };
+            }
+        }
+
+        // The queue slot is released either once a thread picks up `f` or,
+        // if the task is aborted before that happens, when `f` is dropped.
+        let pool = self.clone();
+        let dequeue = OnDrop(move || {
+            pool.inner.queued.fetch_sub(1, Ordering::Relaxed);
+        });
+        let pool = self.clone();
+        runtime.spawn_blocking(move || {
+            drop(dequeue);
+            let inner = &*pool.inner;
+            inner.running.fetch_add(1, Ordering::Relaxed);
+            let _finished = OnDrop(|| {
+                inner.running.fetch_sub(1, Ordering::Relaxed);
+                inner.completed.fetch_add(1, Ordering::Relaxed);
+            });
+            f()
+        })
+    }
+}
+
+impl std::fmt::Debug for BlockingPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingPool")
+            .field("max_threads", &self.inner.max_threads)
+            .field("max_queued", &self.inner.max_queued)
+            .field("rejection", &self.inner.rejection)
+            .field("metrics", &self.metrics())
+            .finish()
+    }
+}
+
+impl Drop for BlockingPoolInner {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks until its threads have exited which isn't
+        // allowed within an asynchronous context, so let the threads wind down
+        // in the background instead.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// The pool of an engine, attached to it by [`BlockingPool::for_engine`] or
+/// [`BlockingPoolBuilder::install`].
+struct EnginePool(BlockingPool);
+
+/// Runs the wrapped closure when dropped.
+struct OnDrop<F: FnMut()>(F);
+
+impl<F: FnMut()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)();
+    }
+}
+
+/// Like [`spawn_blocking`], but runs `f` on `pool` when one is configured.
+pub(crate) fn spawn_blocking_in<F, R>(pool: Option<&BlockingPool>, f: F) -> AbortOnDropJoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    AbortOnDropJoinHandle(spawn_blocking_raw_in(pool, f))
+}
+
+/// Like [`spawn_blocking_in`], but returns Tokio's own [`JoinHandle`] for
+/// callers which need to observe cancellation of the task.
+///
+/// [`JoinHandle`]: tokio::task::JoinHandle
+pub(crate) fn spawn_blocking_raw_in<F, R>(
+    pool: Option<&BlockingPool>,
+    f: F,
+) -> tokio::task::JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    match pool {
+        Some(pool) => pool.spawn_blocking_raw(f),
+        None => with_ambient_tokio_runtime(|| tokio::task::spawn_blocking(f)),
+    }
+}
+
 /// Attempts to get the result of a `future`.
 ///
 /// This function does not block and will poll the provided future once. If the
@@ -187,3 +515,29 @@ where
         Poll::Pending => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocking_pool_is_owned_by_engine() {
+        let engine = Engine::default();
+        let pool = BlockingPool::builder()
+            .max_threads(2)
+            .install(&engine)
+            .unwrap();
+        assert!(BlockingPool::builder().install(&engine).is_err());
+        let shared = BlockingPool::for_engine(&engine).unwrap();
+        assert!(Arc::ptr_eq(&pool.inner, &shared.inner));
+        let other = BlockingPool::for_engine(&Engine::default()).unwrap();
+        assert!(!Arc::ptr_eq(&pool.inner, &other.inner));
+
+        // The engine keeps its pool alive until it's dropped itself.
+        let weak = Arc::downgrade(&pool.inner);
+        drop((pool, shared));
+        assert!(weak.upgrade().is_some());
+        drop(engine);
+        assert!(weak.upgrade().is_none());
+    }
+}
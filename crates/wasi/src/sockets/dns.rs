@@ -0,0 +1,202 @@
+//! A minimal DNS stub resolver, used by `wasi:sockets/ip-name-lookup` when
+//! DNS servers are configured with
+//! [`WasiCtxBuilder::dns_server`](crate::WasiCtxBuilder::dns_server).
+//!
+//! This only sends recursive `A` and `AAAA` queries over UDP and collects the
+//! addresses in the answers, it relies on the server for everything else.
+
+use crate::sockets::ip_name_lookup::ErrorCode;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+/// How long to wait for a server's response before trying the next one.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+const RCODE_NOERROR: u16 = 0;
+const RCODE_SERVFAIL: u16 = 2;
+const RCODE_NXDOMAIN: u16 = 3;
+
+/// The addresses a server resolved a name to, along with how long they may be
+/// cached for.
+#[derive(Debug)]
+pub(crate) struct Answer {
+    pub(crate) addrs: Vec<IpAddr>,
+    pub(crate) ttl: Duration,
+}
+
+/// Resolves `name` with each of `servers` in turn until one of them answers.
+pub(crate) async fn lookup(servers: &[SocketAddr], name: &str) -> Result<Answer, ErrorCode> {
+    let mut error = ErrorCode::TemporaryResolverFailure;
+    for server in servers {
+        match lookup_with(*server, name).await {
+            Ok(answer) => return Ok(answer),
+            // The name doesn't exist, there's no point in asking elsewhere.
+            Err(ErrorCode::NameUnresolvable) => return Err(ErrorCode::NameUnresolvable),
+            Err(e) => {
+                debug!("DNS server {server} failed to resolve `{name}`: {e:?}");
+                error = e;
+            }
+        }
+    }
+    Err(error)
+}
+
+async fn lookup_with(server: SocketAddr, name: &str) -> Result<Answer, ErrorCode> {
+    let bind: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind)
+        .await
+        .map_err(|_| ErrorCode::TemporaryResolverFailure)?;
+    socket
+        .connect(server)
+        .await
+        .map_err(|_| ErrorCode::TemporaryResolverFailure)?;
+
+    let id = rand::random::<u16>();
+    let queries = [(id, TYPE_A), (id.wrapping_add(1), TYPE_AAAA)];
+    for (id, ty) in queries {
+        let query = encode_query(id, name, ty).ok_or(ErrorCode::InvalidArgument)?;
+        socket
+            .send(&query)
+            .await
+            .map_err(|_| ErrorCode::TemporaryResolverFailure)?;
+    }
+
+    let mut answer = Answer {
+        addrs: Vec::new(),
+        ttl: Duration::MAX,
+    };
+    let mut pending = queries.to_vec();
+    let mut buf = [0; 1500];
+    tokio::time::timeout(TIMEOUT, async {
+        while !pending.is_empty() {
+            let len = socket
+                .recv(&mut buf)
+                .await
+                .map_err(|_| ErrorCode::TemporaryResolverFailure)?;
+            // Ignore anything which isn't a response to one of our queries.
+            let Some(response) = parse_response(&buf[..len]) else {
+                continue;
+            };
+            let Some(i) = pending.iter().position(|(id, _)| *id == response.id) else {
+                continue;
+            };
+            pending.swap_remove(i);
+            match response.rcode {
+                RCODE_NOERROR => {
+                    answer.addrs.extend(response.addrs);
+                    answer.ttl = answer.ttl.min(response.ttl);
+                }
+                // The name doesn't exist, which is reported below once all
+                // responses are in.
+                RCODE_NXDOMAIN => {}
+                RCODE_SERVFAIL => return Err(ErrorCode::TemporaryResolverFailure),
+                _ => return Err(ErrorCode::PermanentResolverFailure),
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|_| ErrorCode::TemporaryResolverFailure)??;
+
+    if answer.addrs.is_empty() {
+        // Either the name doesn't exist or it has no addresses, which guests
+        // can't tell apart either way.
+        return Err(ErrorCode::NameUnresolvable);
+    }
+    Ok(answer)
+}
+
+/// Encodes a recursive query for records of type `ty` of `name`, or returns
+/// `None` if `name` isn't a valid domain name.
+fn encode_query(id: u16, name: &str, ty: u16) -> Option<Vec<u8>> {
+    let mut msg = Vec::with_capacity(18 + name.len());
+    msg.extend(id.to_be_bytes());
+    // Flags: a standard query with recursion desired.
+    msg.extend(0x0100u16.to_be_bytes());
+    // One question, no answer, authority or additional records.
+    msg.extend([0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+        msg.push(label.len() as u8);
+        msg.extend(label.as_bytes());
+    }
+    msg.push(0);
+    msg.extend(ty.to_be_bytes());
+    msg.extend(CLASS_IN.to_be_bytes());
+    Some(msg)
+}
+
+struct Response {
+    id: u16,
+    rcode: u16,
+    addrs: Vec<IpAddr>,
+    ttl: Duration,
+}
+
+/// Parses a response, returning `None` if it is malformed.
+fn parse_response(msg: &[u8]) -> Option<Response> {
+    let u16_at = |pos: usize| Some(u16::from_be_bytes(msg.get(pos..pos + 2)?.try_into().ok()?));
+    let u32_at = |pos: usize| Some(u32::from_be_bytes(msg.get(pos..pos + 4)?.try_into().ok()?));
+    let skip_name = |mut pos: usize| loop {
+        let len = *msg.get(pos)?;
+        match len {
+            0 => return Some(pos + 1),
+            // A pointer to a name elsewhere in the message ends the name.
+            _ if len & 0xc0 == 0xc0 => return Some(pos + 2),
+            _ => pos += 1 + usize::from(len),
+        }
+    };
+
+    let id = u16_at(0)?;
+    let flags = u16_at(2)?;
+    if flags & 0x8000 == 0 {
+        return None;
+    }
+    let questions = u16_at(4)?;
+    let answers = u16_at(6)?;
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(pos)? + 4;
+    }
+    let mut addrs = Vec::new();
+    let mut ttl = Duration::MAX;
+    for _ in 0..answers {
+        pos = skip_name(pos)?;
+        let ty = u16_at(pos)?;
+        let class = u16_at(pos + 2)?;
+        let record_ttl = u32_at(pos + 4)?;
+        let len = usize::from(u16_at(pos + 8)?);
+        let data = msg.get(pos + 10..pos + 10 + len)?;
+        pos += 10 + len;
+        if class != CLASS_IN {
+            continue;
+        }
+        let addr = match (ty, data.len()) {
+            (TYPE_A, 4) => IpAddr::from(<[u8; 4]>::try_from(data).unwrap()),
+            (TYPE_AAAA, 16) => IpAddr::from(<[u8; 16]>::try_from(data).unwrap()),
+            // Other records, such as the `CNAME`s leading to the addresses.
+            _ => continue,
+        };
+        addrs.push(addr.to_canonical());
+        ttl = ttl.min(Duration::from_secs(record_ttl.into()));
+    }
+
+    Some(Response {
+        id,
+        rcode: flags & 0xf,
+        addrs,
+        ttl,
+    })
+}
@@ -1,6 +1,11 @@
 use tracing::debug;
 
-use crate::sockets::WasiSocketsCtx;
+use crate::runtime::{BlockingPool, spawn_blocking_in};
+use crate::sockets::{WasiSocketsCtx, dns};
+use std::collections::HashMap;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::{net::IpAddr, str::FromStr};
 
 #[derive(Debug, Clone)]
@@ -14,11 +19,211 @@ pub enum ErrorCode {
     Other,
 }
 
+/// How host names are resolved, as configured through `WasiCtxBuilder`.
+#[derive(Clone, Default)]
+pub(crate) struct ResolverConfig {
+    /// DNS servers to query instead of the system resolver.
+    pub(crate) servers: Vec<SocketAddr>,
+    /// Domains appended to names without any dots.
+    pub(crate) search_domains: Vec<String>,
+    /// If non-empty, only names matching one of these may be resolved.
+    pub(crate) allowed_hosts: Vec<HostPattern>,
+    /// Names matching any of these may not be resolved.
+    pub(crate) denied_hosts: Vec<HostPattern>,
+    pub(crate) cache: Option<DnsCache>,
+    /// Where lookups through the system resolver, which block, are run.
+    pub(crate) blocking_pool: Option<BlockingPool>,
+}
+
+impl ResolverConfig {
+    fn is_allowed(&self, name: &str) -> bool {
+        let matches = |patterns: &[HostPattern]| patterns.iter().any(|p| p.matches(name));
+        !matches(&self.denied_hosts)
+            && (self.allowed_hosts.is_empty() || matches(&self.allowed_hosts))
+    }
+
+    /// Returns the names to try in order to resolve `domain`.
+    fn candidates(&self, domain: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        if !domain.contains('.') {
+            names.extend(
+                self.search_domains
+                    .iter()
+                    .map(|search| format!("{domain}.{search}")),
+            );
+        }
+        names.push(domain.to_string());
+        names
+    }
+
+    async fn resolve(&self, name: &str) -> Result<Vec<IpAddr>, ErrorCode> {
+        if let Some(addrs) = self.cache.as_ref().and_then(|c| c.get(name)) {
+            return Ok(addrs);
+        }
+        let (addrs, ttl) = if self.servers.is_empty() {
+            let host = name.to_string();
+            let lookup = move || (host.as_str(), 0).to_socket_addrs();
+            let addrs = spawn_blocking_in(self.blocking_pool.as_ref(), lookup)
+                .await
+                .map_err(|e| {
+                    debug!("DNS resolution of `{}` failed because: {}", name, e);
+                    // If/when we use `getaddrinfo` directly, map the error properly.
+                    ErrorCode::NameUnresolvable
+                })?;
+            (addrs.map(|addr| addr.ip().to_canonical()).collect(), None)
+        } else {
+            let answer = dns::lookup(&self.servers, name).await?;
+            (answer.addrs, Some(answer.ttl))
+        };
+        if let Some(cache) = &self.cache {
+            cache.insert(name, &addrs, ttl);
+        }
+        Ok(addrs)
+    }
+}
+
+/// A host name, or a wildcard matching all subdomains of one, used to allow or
+/// deny resolving names.
+#[derive(Clone, Debug)]
+pub(crate) enum HostPattern {
+    /// Matches this name exactly.
+    Exact(String),
+    /// Matches any name ending in `.{suffix}`, i.e. `*.{suffix}`.
+    Subdomains(String),
+    /// Matches any name, i.e. `*`.
+    Any,
+}
+
+impl HostPattern {
+    pub(crate) fn new(pattern: &str) -> HostPattern {
+        let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+        if pattern == "*" {
+            HostPattern::Any
+        } else if let Some(suffix) = pattern.strip_prefix("*.") {
+            HostPattern::Subdomains(suffix.to_string())
+        } else {
+            HostPattern::Exact(pattern)
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            HostPattern::Exact(host) => name == host,
+            HostPattern::Subdomains(suffix) => name
+                .strip_suffix(suffix.as_str())
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+            HostPattern::Any => true,
+        }
+    }
+}
+
+/// A cache of resolved host names for `wasi:sockets/ip-name-lookup`.
+///
+/// A cache is configured with
+/// [`WasiCtxBuilder::dns_cache`](crate::WasiCtxBuilder::dns_cache). It is
+/// cheap to clone, and clones share the same entries, so one cache can be
+/// configured for many [`WasiCtx`](crate::WasiCtx)s, e.g. all instances of a
+/// service, to avoid resolving the same names over and over. Only contexts
+/// with the same resolver configuration should share a cache.
+///
+/// Entries are kept for as long as the DNS records' TTL when resolved with
+/// [`dns_server`](crate::WasiCtxBuilder::dns_server)s. The system resolver
+/// doesn't report TTLs, so names it resolves are kept for the default TTL of
+/// the cache instead.
+#[derive(Clone)]
+pub struct DnsCache {
+    inner: Arc<Mutex<CacheInner>>,
+}
+
+struct CacheInner {
+    max_entries: usize,
+    default_ttl: Duration,
+    entries: HashMap<String, CacheEntry>,
+}
+
+struct CacheEntry {
+    addrs: Vec<IpAddr>,
+    expires: Instant,
+}
+
+impl DnsCache {
+    /// Creates a cache holding at most `max_entries` names, which keeps names
+    /// resolved by the system resolver for `default_ttl`.
+    pub fn new(max_entries: usize, default_ttl: Duration) -> DnsCache {
+        DnsCache {
+            inner: Arc::new(Mutex::new(CacheInner {
+                max_entries,
+                default_ttl,
+                entries: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Removes all entries from this cache.
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().entries.clear();
+    }
+
+    /// Returns the number of names in this cache, including any which have
+    /// expired but not been evicted yet.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether this cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, name: &str) -> Option<Vec<IpAddr>> {
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.entries.get(name)?;
+        if entry.expires > Instant::now() {
+            return Some(entry.addrs.clone());
+        }
+        inner.entries.remove(name);
+        None
+    }
+
+    fn insert(&self, name: &str, addrs: &[IpAddr], ttl: Option<Duration>) {
+        let mut inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+        let Some(expires) = now.checked_add(ttl.unwrap_or(inner.default_ttl)) else {
+            return;
+        };
+        if expires <= now || inner.max_entries == 0 {
+            return;
+        }
+        if inner.entries.len() >= inner.max_entries && !inner.entries.contains_key(name) {
+            inner.entries.retain(|_, entry| entry.expires > now);
+            if inner.entries.len() >= inner.max_entries {
+                // Evict whichever entry would expire first.
+                let oldest = inner
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires)
+                    .map(|(name, _)| name.clone());
+                if let Some(oldest) = oldest {
+                    inner.entries.remove(&oldest);
+                }
+            }
+        }
+        inner.entries.insert(
+            name.to_string(),
+            CacheEntry {
+                addrs: addrs.to_vec(),
+                expires,
+            },
+        );
+    }
+}
+
 pub(crate) fn resolve_addresses(
     ctx: &WasiSocketsCtx,
     name: String,
 ) -> impl Future<Output = Result<Vec<IpAddr>, ErrorCode>> + Send + use<> {
-    let allowed = ctx.allowed_network_uses.ip_name_lookup;
+    let allowed = ctx.allowed_network_uses.ip_name_lookup && !ctx.allowed_network_uses.restricted;
+    let resolver = ctx.resolver.clone();
 
     async move {
         if !allowed {
@@ -38,15 +243,177 @@ pub(crate) fn resolve_addresses(
             }
             Err(_) => return Err(ErrorCode::InvalidArgument),
         };
+        let domain = domain.trim_end_matches('.');
+
+        let mut result = Err(ErrorCode::AccessDenied);
+        for candidate in resolver.candidates(domain) {
+            if !resolver.is_allowed(&candidate) {
+                continue;
+            }
+            result = resolver.resolve(&candidate).await;
+            if !matches!(result, Err(ErrorCode::NameUnresolvable)) {
+                break;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    use tokio::net::UdpSocket;
+
+    fn ctx(resolver: ResolverConfig) -> WasiSocketsCtx {
+        let mut ctx = WasiSocketsCtx::default();
+        ctx.allowed_network_uses.ip_name_lookup = true;
+        ctx.resolver = resolver;
+        ctx
+    }
+
+    #[test]
+    fn host_patterns() {
+        let exact = HostPattern::new("Example.COM.");
+        assert!(exact.matches("example.com"));
+        assert!(!exact.matches("www.example.com"));
+
+        let wildcard = HostPattern::new("*.example.com");
+        assert!(wildcard.matches("www.example.com"));
+        assert!(wildcard.matches("a.b.example.com"));
+        assert!(!wildcard.matches("example.com"));
+        assert!(!wildcard.matches("badexample.com"));
+
+        assert!(HostPattern::new("*").matches("anything.at.all"));
+    }
 
-        let addrs = tokio::net::lookup_host((domain.as_str(), 0))
+    #[tokio::test]
+    async fn allow_and_deny_lists() {
+        let resolver = ResolverConfig {
+            allowed_hosts: vec![HostPattern::new("*.example.com")],
+            denied_hosts: vec![HostPattern::new("secret.example.com")],
+            ..ResolverConfig::default()
+        };
+        let cx = ctx(resolver);
+        let denied = |name: &str| {
+            let lookup = resolve_addresses(&cx, name.to_string());
+            async move { matches!(lookup.await, Err(ErrorCode::AccessDenied)) }
+        };
+        assert!(denied("example.org").await);
+        assert!(denied("example.com").await);
+        assert!(denied("secret.example.com").await);
+        assert!(denied("SECRET.example.com.").await);
+
+        // Addresses don't need to be resolved, so they are always allowed.
+        let addrs = resolve_addresses(&cx, "127.0.0.1".to_string())
             .await
-            .map_err(|e| {
-                debug!("DNS resolution of `{}` failed because: {}", domain, e);
-                // If/when we use `getaddrinfo` directly, map the error properly.
-                ErrorCode::NameUnresolvable
-            })?;
+            .unwrap();
+        assert_eq!(addrs, [IpAddr::V4(Ipv4Addr::LOCALHOST)]);
+    }
+
+    #[test]
+    fn cache_evicts_and_expires() {
+        let cache = DnsCache::new(2, Duration::from_secs(60));
+        let addr = |i| vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, i))];
+        cache.insert("a", &addr(1), Some(Duration::from_secs(10)));
+        cache.insert("b", &addr(2), None);
+        assert_eq!(cache.get("a"), Some(addr(1)));
+
+        // "a" expires first, so it is evicted to make room.
+        cache.insert("c", &addr(3), Some(Duration::from_secs(30)));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(addr(2)));
+
+        // Records with a TTL of zero are not cached at all.
+        cache.insert("d", &addr(4), Some(Duration::ZERO));
+        assert_eq!(cache.get("d"), None);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    /// Answers `A` queries for `host.test` with 192.0.2.1 and `AAAA` queries
+    /// with 2001:db8::1, and everything else with `NXDOMAIN`.
+    async fn fake_dns_server() -> (SocketAddr, tokio::task::JoinHandle<usize>) {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let task = tokio::spawn(async move {
+            let mut queries = 0;
+            let mut buf = [0; 512];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+                queries += 1;
+                let query = &buf[..len];
+                let question_end = len - 4;
+                let name = &query[12..question_end];
+                let ty = u16::from_be_bytes([query[question_end], query[question_end + 1]]);
+                let known = name == b"\x04host\x04test\x00";
+
+                let mut response = query.to_vec();
+                response[2] = 0x81;
+                response[3] = if known { 0x80 } else { 0x83 };
+                if known {
+                    response[7] = 1;
+                    // A pointer to the name in the question.
+                    response.extend([0xc0, 12]);
+                    response.extend(ty.to_be_bytes());
+                    response.extend([0, 1]);
+                    response.extend(300u32.to_be_bytes());
+                    if ty == 1 {
+                        response.extend([0, 4, 192, 0, 2, 1]);
+                    } else {
+                        response.extend([0, 16]);
+                        response.extend(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).octets());
+                    }
+                }
+                socket.send_to(&response, peer).await.unwrap();
+                if queries == 6 {
+                    break;
+                }
+            }
+            queries
+        });
+        (addr, task)
+    }
+
+    #[tokio::test]
+    async fn dns_servers_search_domains_and_cache() {
+        let (server, task) = fake_dns_server().await;
+        let cache = DnsCache::new(16, Duration::from_secs(60));
+        let resolver = ResolverConfig {
+            servers: vec![server],
+            search_domains: vec!["missing".to_string(), "test".to_string()],
+            cache: Some(cache.clone()),
+            ..ResolverConfig::default()
+        };
+        let cx = ctx(resolver);
+
+        // `host.missing` doesn't exist, then `host.test` is found.
+        let mut addrs = resolve_addresses(&cx, "host".to_string()).await.unwrap();
+        addrs.sort();
+        let expected = [
+            IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)),
+            IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+        ];
+        assert_eq!(addrs, expected);
+        assert_eq!(cache.len(), 1);
+
+        // Now answered from the cache, even by another context.
+        let other = ctx(ResolverConfig {
+            servers: vec![server],
+            cache: Some(cache.clone()),
+            ..ResolverConfig::default()
+        });
+        let mut addrs = resolve_addresses(&other, "host.test".to_string())
+            .await
+            .unwrap();
+        addrs.sort();
+        assert_eq!(addrs, expected);
+
+        let result = resolve_addresses(&cx, "nope.test".to_string()).await;
+        assert!(matches!(result, Err(ErrorCode::NameUnresolvable)));
 
-        Ok(addrs.map(|addr| addr.ip().to_canonical()).collect())
+        // Two queries each for `host.missing`, `host.test` and `nope.test`.
+        assert_eq!(task.await.unwrap(), 6);
     }
 }
@@ -1,3 +1,4 @@
+use crate::limits::{ConnectionLimit, RateLimits};
 use core::fmt;
 use core::future::Future;
 use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
@@ -5,15 +6,19 @@ use core::ops::Deref;
 use rustix::fd::AsFd;
 use rustix::io::Errno;
 use rustix::net::sockopt;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Poll;
 use tracing::debug;
 use wasmtime::component::{HasData, ResourceTable};
 
+mod dns;
 pub(crate) mod ip_name_lookup;
 mod tcp;
 mod udp;
+mod unix;
+pub use ip_name_lookup::DnsCache;
 pub use tcp::TcpSocket;
 pub(crate) use tcp::{TcpListenStream, TcpReceiveStream, TcpSendStream};
 pub use udp::UdpSocket;
@@ -66,6 +71,10 @@ impl HasData for WasiSockets {
 pub struct WasiSocketsCtx {
     pub(crate) socket_addr_check: SocketAddrCheck,
     pub(crate) allowed_network_uses: AllowedNetworkUses,
+    pub(crate) resolver: ip_name_lookup::ResolverConfig,
+    pub(crate) rate_limits: RateLimits,
+    pub(crate) tcp_connection_limit: Option<ConnectionLimit>,
+    pub(crate) unix_socket_paths: Vec<PathBuf>,
 }
 
 pub struct WasiSocketsCtxView<'a> {
@@ -82,11 +91,14 @@ pub(crate) struct AllowedNetworkUses {
     pub(crate) ip_name_lookup: bool,
     pub(crate) udp: bool,
     pub(crate) tcp: bool,
+    /// Temporarily denies all network uses regardless of the above, see
+    /// `WasiCtx::restrict_side_effects`.
+    pub(crate) restricted: bool,
 }
 
 impl AllowedNetworkUses {
     pub(crate) fn check_allowed_udp(&self) -> std::io::Result<()> {
-        if !self.udp {
+        if !self.udp || self.restricted {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::PermissionDenied,
                 "UDP is not allowed",
@@ -97,7 +109,7 @@ impl AllowedNetworkUses {
     }
 
     pub(crate) fn check_allowed_tcp(&self) -> std::io::Result<()> {
-        if !self.tcp {
+        if !self.tcp || self.restricted {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::PermissionDenied,
                 "TCP is not allowed",
@@ -219,6 +231,20 @@ pub enum SocketAddrUse {
     /// that is being received. If the check fails, the datagram will be
     /// silently dropped before reaching the guest.
     UdpReceive,
+
+    /// Joining a multicast group on a UDP socket.
+    ///
+    /// The address passed to the check is the multicast group address that
+    /// the socket is about to join, with a port of 0.
+    UdpJoinMulticast,
+
+    /// Enabling broadcast on a UDP socket.
+    ///
+    /// The address passed to the check is the IPv4 limited broadcast address
+    /// (`255.255.255.255`) with a port of 0. Sending to a broadcast address
+    /// is additionally subject to the regular [`SocketAddrUse::UdpSend`]
+    /// check.
+    UdpBroadcast,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -519,6 +545,69 @@ pub(crate) fn set_unicast_hop_limit(
     Ok(())
 }
 
+pub(crate) fn get_multicast_hop_limit(
+    fd: impl AsFd,
+    family: SocketAddressFamily,
+) -> Result<u8, ErrorCode> {
+    match family {
+        SocketAddressFamily::Ipv4 => {
+            let v = sockopt::ip_multicast_ttl(fd)?;
+            let Ok(v) = v.try_into() else {
+                return Err(ErrorCode::NotSupported);
+            };
+            Ok(v)
+        }
+        SocketAddressFamily::Ipv6 => {
+            let v = sockopt::ipv6_multicast_hops(fd)?;
+            let Ok(v) = v.try_into() else {
+                return Err(ErrorCode::NotSupported);
+            };
+            Ok(v)
+        }
+    }
+}
+
+pub(crate) fn set_multicast_hop_limit(
+    fd: impl AsFd,
+    family: SocketAddressFamily,
+    value: u8,
+) -> Result<(), ErrorCode> {
+    // Unlike the unicast hop limit, 0 is a meaningful value here: it restricts
+    // multicast datagrams to the local host.
+    match family {
+        SocketAddressFamily::Ipv4 => {
+            sockopt::set_ip_multicast_ttl(fd, value.into())?;
+        }
+        SocketAddressFamily::Ipv6 => {
+            sockopt::set_ipv6_multicast_hops(fd, value.into())?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn get_multicast_loopback(
+    fd: impl AsFd,
+    family: SocketAddressFamily,
+) -> Result<bool, ErrorCode> {
+    let v = match family {
+        SocketAddressFamily::Ipv4 => sockopt::ip_multicast_loop(fd)?,
+        SocketAddressFamily::Ipv6 => sockopt::ipv6_multicast_loop(fd)?,
+    };
+    Ok(v)
+}
+
+pub(crate) fn set_multicast_loopback(
+    fd: impl AsFd,
+    family: SocketAddressFamily,
+    value: bool,
+) -> Result<(), ErrorCode> {
+    match family {
+        SocketAddressFamily::Ipv4 => sockopt::set_ip_multicast_loop(fd, value)?,
+        SocketAddressFamily::Ipv6 => sockopt::set_ipv6_multicast_loop(fd, value)?,
+    }
+    Ok(())
+}
+
 pub(crate) fn get_receive_buffer_size(fd: impl AsFd) -> Result<u64, ErrorCode> {
     let v = sockopt::socket_recv_buffer_size(fd)?;
     Ok(normalize_get_buffer_size(v).try_into().unwrap_or(u64::MAX))
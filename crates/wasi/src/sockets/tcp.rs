@@ -1,3 +1,4 @@
+use crate::limits::{ConnectionLimit, IoLimitExceeded, RateLimits, Throttle};
 use crate::runtime::with_ambient_tokio_runtime;
 use crate::sockets::{
     ErrorCode, MaybeReady, SocketAddrCheck, SocketAddrUse, SocketAddressFamily, WasiSocketsCtx,
@@ -15,6 +16,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::task::{Poll, ready};
 use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
 
 /// Value taken from rust std library.
 const DEFAULT_BACKLOG: u32 = 128;
@@ -45,7 +47,7 @@ enum TcpState {
     /// for the eventual result of the connect.
     ///
     /// From here a socket can transition to `Connected` or `Closed`.
-    Connecting(MaybeReady<Result<tokio::net::TcpStream, ErrorCode>>),
+    Connecting(MaybeReady<Result<Connection, ErrorCode>>),
 
     /// A connection has been established.
     ///
@@ -55,6 +57,7 @@ enum TcpState {
     /// A socket will not transition out of this state.
     Connected {
         stream: Arc<tokio::net::TcpStream>,
+        permit: Option<Arc<OwnedSemaphorePermit>>,
         receive_taken: bool,
         send_taken: bool,
     },
@@ -62,10 +65,15 @@ enum TcpState {
     /// The socket is closed and no more operations can be performed.
     Closed(ErrorCode),
 }
+/// An established connection and its slot in the context's connection limit,
+/// if the number of connections is limited.
+type Connection = (tokio::net::TcpStream, Option<OwnedSemaphorePermit>);
+
 impl TcpState {
-    fn connected(stream: tokio::net::TcpStream) -> Self {
+    fn connected((stream, permit): Connection) -> Self {
         TcpState::Connected {
             stream: Arc::new(stream),
+            permit: permit.map(Arc::new),
             receive_taken: false,
             send_taken: false,
         }
@@ -106,6 +114,12 @@ pub struct TcpSocket {
     /// Cached value of whether the socket is bound. Various methods use the
     /// `.is_bound()` method, so we cache it to avoid redundant syscalls.
     is_bound: bool,
+
+    /// The rate limits of the streams of this socket's connection.
+    rate_limits: RateLimits,
+
+    /// The limit on the number of concurrent connections, if any.
+    connection_limit: Option<ConnectionLimit>,
 }
 
 impl TcpSocket {
@@ -125,9 +139,20 @@ impl TcpSocket {
             is_bound: false,
             listener_options: Default::default(),
             permissions: ctx.socket_addr_check.clone(),
+            rate_limits: ctx.rate_limits.clone(),
+            connection_limit: ctx.tcp_connection_limit.clone(),
         })
     }
 
+    /// Returns an error if the guest should trap because it can't open any
+    /// more connections.
+    pub(crate) fn check_connection_limit(&self) -> Result<(), IoLimitExceeded> {
+        match &self.connection_limit {
+            Some(limit) => limit.check(),
+            None => Ok(()),
+        }
+    }
+
     fn as_fd(&self) -> Result<rustix::fd::BorrowedFd<'_>, ErrorCode> {
         match &self.tcp_state {
             TcpState::Default(socket) => Ok(socket.as_fd()),
@@ -177,6 +202,7 @@ impl TcpSocket {
         };
 
         let permissions = self.permissions.clone();
+        let connection_limit = self.connection_limit.clone();
         let family = self.family;
         let already_bound = self.is_bound();
 
@@ -211,8 +237,12 @@ impl TcpSocket {
                 permissions.check(addr, SocketAddrUse::TcpConnect).await?;
             }
 
+            let permit = match connection_limit {
+                Some(limit) => Some(limit.acquire().await),
+                None => None,
+            };
             let stream = sock.connect(addr).await?;
-            Ok(stream)
+            Ok((stream, permit))
         }));
 
         Ok(())
@@ -235,8 +265,8 @@ impl TcpSocket {
         };
 
         match connect.unwrap_ready() {
-            Ok(stream) => {
-                self.tcp_state = TcpState::connected(stream);
+            Ok(connection) => {
+                self.tcp_state = TcpState::connected(connection);
                 Poll::Ready(Ok(()))
             }
             Err(err) => {
@@ -329,6 +359,8 @@ impl TcpSocket {
             listener_options: self.listener_options.clone(),
             family: self.family,
             permissions: self.permissions.clone(),
+            rate_limits: self.rate_limits.clone(),
+            connection_limit: self.connection_limit.clone(),
             pending_accept: None,
         })
     }
@@ -336,11 +368,16 @@ impl TcpSocket {
     pub(crate) fn take_send_stream(&mut self) -> Result<TcpSendStream, ErrorCode> {
         match &mut self.tcp_state {
             TcpState::Connected {
-                stream, send_taken, ..
+                stream,
+                permit,
+                send_taken,
+                ..
             } if !*send_taken => {
                 *send_taken = true;
                 Ok(TcpSendStream {
                     inner: stream.clone(),
+                    throttle: self.rate_limits.write_throttle(),
+                    _permit: permit.clone(),
                 })
             }
             TcpState::Closed(err) => Err(*err),
@@ -352,12 +389,15 @@ impl TcpSocket {
         match &mut self.tcp_state {
             TcpState::Connected {
                 stream,
+                permit,
                 receive_taken,
                 ..
             } if !*receive_taken => {
                 *receive_taken = true;
                 Ok(TcpReceiveStream {
                     inner: stream.clone(),
+                    throttle: self.rate_limits.read_throttle(),
+                    _permit: permit.clone(),
                 })
             }
             TcpState::Closed(err) => Err(*err),
@@ -542,17 +582,28 @@ pub(crate) struct TcpListenStream {
     family: SocketAddressFamily,
     listener_options: NonInheritedOptions,
     permissions: SocketAddrCheck,
-    pending_accept: Option<MaybeReady<Result<tokio::net::TcpStream, ErrorCode>>>,
+    rate_limits: RateLimits,
+    connection_limit: Option<ConnectionLimit>,
+    pending_accept: Option<MaybeReady<Result<Connection, ErrorCode>>>,
 }
 impl TcpListenStream {
+    /// Returns an error if the guest should trap because it can't accept any
+    /// more connections.
+    pub(crate) fn check_connection_limit(&self) -> Result<(), IoLimitExceeded> {
+        match &self.connection_limit {
+            Some(limit) => limit.check(),
+            None => Ok(()),
+        }
+    }
+
     pub(crate) fn poll_accept(&mut self, cx: &mut std::task::Context<'_>) -> Poll<TcpSocket> {
         ready!(self.poll_ready(cx));
         let result = self.pending_accept.take().unwrap().unwrap_ready();
         Poll::Ready(TcpSocket {
             tcp_state: match result {
-                Ok(client) => {
+                Ok((client, permit)) => {
                     self.listener_options.apply(self.family, &client);
-                    TcpState::connected(client)
+                    TcpState::connected((client, permit))
                 }
                 Err(err) => TcpState::Closed(err),
             },
@@ -561,6 +612,8 @@ impl TcpListenStream {
             is_bound: true,
             listener_options: Default::default(),
             permissions: self.permissions.clone(),
+            rate_limits: self.rate_limits.clone(),
+            connection_limit: self.connection_limit.clone(),
         })
     }
 
@@ -568,6 +621,7 @@ impl TcpListenStream {
         if self.pending_accept.is_none() {
             let listener = self.inner.clone();
             let permissions = self.permissions.clone();
+            let connection_limit = self.connection_limit.clone();
 
             self.pending_accept = Some(MaybeReady::new(async move {
                 loop {
@@ -578,7 +632,13 @@ impl TcpListenStream {
                                 .await
                                 .is_ok()
                             {
-                                return Ok(client);
+                                // Hold on to the connection until there's a
+                                // free slot for it.
+                                let permit = match connection_limit {
+                                    Some(limit) => Some(limit.acquire().await),
+                                    None => None,
+                                };
+                                return Ok((client, permit));
                             } else {
                                 reset(client);
                                 continue;
@@ -604,8 +664,21 @@ impl TcpListenStream {
 
 pub(crate) struct TcpSendStream {
     inner: Arc<tokio::net::TcpStream>,
+    throttle: Option<Throttle>,
+    _permit: Option<Arc<OwnedSemaphorePermit>>,
 }
 impl TcpSendStream {
+    /// Polls until the guest is within its socket write limit, if any.
+    pub(crate) fn poll_limit(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<(), IoLimitExceeded>> {
+        match &mut self.throttle {
+            Some(throttle) => throttle.poll_ready(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
     pub(crate) fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<()> {
         self.inner.poll_write_ready(cx).map(|_| ())
     }
@@ -617,7 +690,12 @@ impl TcpSendStream {
     ) -> Poll<Result<usize, ErrorCode>> {
         loop {
             return match self.inner.try_write(buf) {
-                Ok(n) => Poll::Ready(Ok(n)),
+                Ok(n) => {
+                    if let Some(throttle) = &self.throttle {
+                        throttle.consume(n);
+                    }
+                    Poll::Ready(Ok(n))
+                }
                 Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     match self.inner.poll_write_ready(cx) {
                         Poll::Ready(Ok(())) => continue,
@@ -648,8 +726,21 @@ impl Drop for TcpSendStream {
 
 pub(crate) struct TcpReceiveStream {
     inner: Arc<tokio::net::TcpStream>,
+    throttle: Option<Throttle>,
+    _permit: Option<Arc<OwnedSemaphorePermit>>,
 }
 impl TcpReceiveStream {
+    /// Polls until the guest is within its socket read limit, if any.
+    pub(crate) fn poll_limit(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Result<(), IoLimitExceeded>> {
+        match &mut self.throttle {
+            Some(throttle) => throttle.poll_ready(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
     pub(crate) fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> Poll<()> {
         self.inner.poll_read_ready(cx).map(|_| ())
     }
@@ -665,7 +756,12 @@ impl TcpReceiveStream {
         loop {
             return match self.inner.try_read(buf) {
                 Ok(0) => Poll::Ready(Ok(0)),
-                Ok(n) => Poll::Ready(Ok(n)),
+                Ok(n) => {
+                    if let Some(throttle) = &self.throttle {
+                        throttle.consume(n);
+                    }
+                    Poll::Ready(Ok(n))
+                }
                 Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                     match self.inner.poll_read_ready(cx) {
                         Poll::Ready(Ok(())) => continue,
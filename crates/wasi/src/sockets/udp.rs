@@ -1,13 +1,15 @@
 use crate::runtime::with_ambient_tokio_runtime;
 use crate::sockets::{
     ErrorCode, SocketAddrCheck, SocketAddrUse, SocketAddressFamily, WasiSocketsCtx,
-    get_receive_buffer_size, get_send_buffer_size, get_unicast_hop_limit, is_valid_address_family,
-    is_valid_remote_address, set_receive_buffer_size, set_send_buffer_size, set_unicast_hop_limit,
-    unspecified_addr,
+    get_multicast_hop_limit, get_multicast_loopback, get_receive_buffer_size, get_send_buffer_size,
+    get_unicast_hop_limit, is_valid_address_family, is_valid_remote_address,
+    set_multicast_hop_limit, set_multicast_loopback, set_receive_buffer_size, set_send_buffer_size,
+    set_unicast_hop_limit, unspecified_addr,
 };
 use rustix::fd::AsFd;
 use rustix::io::Errno;
-use std::net::SocketAddr;
+use rustix::net::sockopt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
 use tracing::debug;
 
@@ -297,6 +299,133 @@ impl UdpSocket {
         set_send_buffer_size(&self.socket, value)?;
         Ok(())
     }
+
+    /// Joins the IPv4 multicast group `group` on the local interface with
+    /// address `interface`, or on an interface chosen by the OS if
+    /// `interface` is [`Ipv4Addr::UNSPECIFIED`].
+    ///
+    /// `wasi:sockets` doesn't expose multicast to guests, so this is intended
+    /// for embedders which provide their own interface for it. The group is
+    /// checked with [`SocketAddrUse::UdpJoinMulticast`] before it's joined.
+    ///
+    /// Returns [`ErrorCode::InvalidArgument`] if this isn't an IPv4 socket or
+    /// `group` isn't a multicast address.
+    pub async fn join_multicast_v4(
+        &mut self,
+        group: Ipv4Addr,
+        interface: Ipv4Addr,
+    ) -> Result<(), ErrorCode> {
+        self.check_multicast_group(group.into())?;
+        self.permissions
+            .check(
+                SocketAddr::new(group.into(), 0),
+                SocketAddrUse::UdpJoinMulticast,
+            )
+            .await?;
+        sockopt::set_ip_add_membership(&self.socket, &group, &interface)?;
+        Ok(())
+    }
+
+    /// Joins the IPv6 multicast group `group` on the interface with index
+    /// `interface`, or on an interface chosen by the OS if `interface` is 0.
+    ///
+    /// See [`UdpSocket::join_multicast_v4`] for more information.
+    pub async fn join_multicast_v6(
+        &mut self,
+        group: Ipv6Addr,
+        interface: u32,
+    ) -> Result<(), ErrorCode> {
+        self.check_multicast_group(group.into())?;
+        self.permissions
+            .check(
+                SocketAddr::new(group.into(), 0),
+                SocketAddrUse::UdpJoinMulticast,
+            )
+            .await?;
+        sockopt::set_ipv6_add_membership(&self.socket, &group, interface)?;
+        Ok(())
+    }
+
+    /// Leaves an IPv4 multicast group previously joined with
+    /// [`UdpSocket::join_multicast_v4`].
+    pub fn leave_multicast_v4(
+        &mut self,
+        group: Ipv4Addr,
+        interface: Ipv4Addr,
+    ) -> Result<(), ErrorCode> {
+        self.check_multicast_group(group.into())?;
+        sockopt::set_ip_drop_membership(&self.socket, &group, &interface)?;
+        Ok(())
+    }
+
+    /// Leaves an IPv6 multicast group previously joined with
+    /// [`UdpSocket::join_multicast_v6`].
+    pub fn leave_multicast_v6(&mut self, group: Ipv6Addr, interface: u32) -> Result<(), ErrorCode> {
+        self.check_multicast_group(group.into())?;
+        sockopt::set_ipv6_drop_membership(&self.socket, &group, interface)?;
+        Ok(())
+    }
+
+    fn check_multicast_group(&self, group: IpAddr) -> Result<(), ErrorCode> {
+        if !is_valid_address_family(group, self.family) || !group.is_multicast() {
+            return Err(ErrorCode::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    /// Returns the hop limit (TTL) of outgoing multicast datagrams.
+    pub fn multicast_hop_limit(&self) -> Result<u8, ErrorCode> {
+        let n = get_multicast_hop_limit(&self.socket, self.family)?;
+        Ok(n)
+    }
+
+    /// Sets the hop limit (TTL) of outgoing multicast datagrams.
+    ///
+    /// A value of 0 restricts multicast datagrams to the local host.
+    pub fn set_multicast_hop_limit(&mut self, value: u8) -> Result<(), ErrorCode> {
+        set_multicast_hop_limit(&self.socket, self.family, value)?;
+        Ok(())
+    }
+
+    /// Returns whether outgoing multicast datagrams are looped back to this
+    /// host.
+    pub fn multicast_loopback(&self) -> Result<bool, ErrorCode> {
+        let v = get_multicast_loopback(&self.socket, self.family)?;
+        Ok(v)
+    }
+
+    /// Sets whether outgoing multicast datagrams are looped back to this host.
+    pub fn set_multicast_loopback(&mut self, value: bool) -> Result<(), ErrorCode> {
+        set_multicast_loopback(&self.socket, self.family, value)?;
+        Ok(())
+    }
+
+    /// Returns whether this socket is permitted to send broadcast datagrams.
+    pub fn broadcast(&self) -> Result<bool, ErrorCode> {
+        let v = sockopt::socket_broadcast(&self.socket)?;
+        Ok(v)
+    }
+
+    /// Sets whether this socket is permitted to send broadcast datagrams.
+    ///
+    /// Enabling broadcast is checked with [`SocketAddrUse::UdpBroadcast`].
+    /// Broadcast only exists in IPv4, so [`ErrorCode::NotSupported`] is
+    /// returned for IPv6 sockets.
+    pub async fn set_broadcast(&mut self, value: bool) -> Result<(), ErrorCode> {
+        if self.family != SocketAddressFamily::Ipv4 {
+            return Err(ErrorCode::NotSupported);
+        }
+        if value {
+            self.permissions
+                .check(
+                    SocketAddr::new(Ipv4Addr::BROADCAST.into(), 0),
+                    SocketAddrUse::UdpBroadcast,
+                )
+                .await?;
+        }
+        sockopt::set_socket_broadcast(&self.socket, value)?;
+        Ok(())
+    }
 }
 
 /// Creates a non-blocking/cloexec UDP socket.
@@ -409,3 +538,94 @@ fn disconnect(sockfd: impl AsFd) -> Result<(), Errno> {
         r => r,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ctx(check: fn(SocketAddr, SocketAddrUse) -> bool) -> WasiSocketsCtx {
+        let mut ctx = WasiSocketsCtx::default();
+        ctx.allowed_network_uses.udp = true;
+        ctx.socket_addr_check = SocketAddrCheck::new(move |addr, usage| {
+            let allowed = check(addr, usage);
+            Box::pin(async move { allowed })
+        });
+        ctx
+    }
+
+    #[tokio::test]
+    async fn multicast_options() {
+        let cx = ctx(|_, _| true);
+        let mut socket = UdpSocket::new(&cx, SocketAddressFamily::Ipv4)
+            .await
+            .unwrap();
+
+        let group = Ipv4Addr::new(239, 255, 0, 1);
+        socket
+            .join_multicast_v4(group, Ipv4Addr::LOCALHOST)
+            .await
+            .unwrap();
+        socket
+            .leave_multicast_v4(group, Ipv4Addr::LOCALHOST)
+            .unwrap();
+
+        socket.set_multicast_hop_limit(0).unwrap();
+        assert_eq!(socket.multicast_hop_limit().unwrap(), 0);
+        socket.set_multicast_hop_limit(42).unwrap();
+        assert_eq!(socket.multicast_hop_limit().unwrap(), 42);
+
+        socket.set_multicast_loopback(false).unwrap();
+        assert!(!socket.multicast_loopback().unwrap());
+        socket.set_multicast_loopback(true).unwrap();
+        assert!(socket.multicast_loopback().unwrap());
+
+        socket.set_broadcast(true).await.unwrap();
+        assert!(socket.broadcast().unwrap());
+        socket.set_broadcast(false).await.unwrap();
+        assert!(!socket.broadcast().unwrap());
+    }
+
+    #[tokio::test]
+    async fn multicast_invalid_group() {
+        let cx = ctx(|_, _| true);
+        let mut socket = UdpSocket::new(&cx, SocketAddressFamily::Ipv4)
+            .await
+            .unwrap();
+
+        let result = socket
+            .join_multicast_v4(Ipv4Addr::LOCALHOST, Ipv4Addr::UNSPECIFIED)
+            .await;
+        assert!(matches!(result, Err(ErrorCode::InvalidArgument)));
+        let result = socket
+            .join_multicast_v6(Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1), 0)
+            .await;
+        assert!(matches!(result, Err(ErrorCode::InvalidArgument)));
+    }
+
+    #[tokio::test]
+    async fn multicast_and_broadcast_checked() {
+        let cx = ctx(|addr, usage| match usage {
+            SocketAddrUse::UdpJoinMulticast => addr.ip() == Ipv4Addr::new(239, 255, 0, 1),
+            _ => false,
+        });
+        let mut socket = UdpSocket::new(&cx, SocketAddressFamily::Ipv4)
+            .await
+            .unwrap();
+
+        let result = socket
+            .join_multicast_v4(Ipv4Addr::new(239, 255, 0, 2), Ipv4Addr::LOCALHOST)
+            .await;
+        assert!(matches!(result, Err(ErrorCode::AccessDenied)));
+
+        let result = socket.set_broadcast(true).await;
+        assert!(matches!(result, Err(ErrorCode::AccessDenied)));
+        assert!(!socket.broadcast().unwrap());
+        socket.set_broadcast(false).await.unwrap();
+
+        let mut socket = UdpSocket::new(&cx, SocketAddressFamily::Ipv6)
+            .await
+            .unwrap();
+        let result = socket.set_broadcast(true).await;
+        assert!(matches!(result, Err(ErrorCode::NotSupported)));
+    }
+}
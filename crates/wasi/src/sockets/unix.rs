@@ -0,0 +1,47 @@
+//! Host support for the Wasmtime-specific `wasmtime:wasi/unix-sockets`
+//! interface.
+
+use crate::sockets::{ErrorCode, WasiSocketsCtx};
+use std::path::Path;
+
+impl WasiSocketsCtx {
+    /// Connects to the Unix domain socket at `path` on behalf of the guest.
+    ///
+    /// Only paths added with
+    /// [`WasiCtxBuilder::allow_unix_socket`](crate::WasiCtxBuilder::allow_unix_socket)
+    /// may be connected to, and they must be spelled exactly as they were
+    /// added, modulo redundant separators and `.` components.
+    #[cfg(unix)]
+    pub(crate) async fn connect_unix(
+        &self,
+        path: &str,
+    ) -> Result<tokio::net::UnixStream, ErrorCode> {
+        let path = self.check_unix_socket_path(path)?;
+        tokio::net::UnixStream::connect(path)
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ErrorCode::ConnectionRefused,
+                _ => e.into(),
+            })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) async fn connect_unix(
+        &self,
+        path: &str,
+    ) -> Result<core::convert::Infallible, ErrorCode> {
+        self.check_unix_socket_path(path)?;
+        Err(ErrorCode::NotSupported)
+    }
+
+    fn check_unix_socket_path<'a>(&self, path: &'a str) -> Result<&'a Path, ErrorCode> {
+        if self.allowed_network_uses.restricted {
+            return Err(ErrorCode::AccessDenied);
+        }
+        let path = Path::new(path);
+        if !self.unix_socket_paths.iter().any(|allowed| allowed == path) {
+            return Err(ErrorCode::AccessDenied);
+        }
+        Ok(path)
+    }
+}
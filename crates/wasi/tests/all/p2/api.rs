@@ -198,3 +198,363 @@ async fn p2_api_reactor() -> Result<()> {
 
     Ok(())
 }
+
+#[test_log::test(tokio::test)]
+async fn p2_api_custom_pollable() -> Result<()> {
+    use tokio::sync::oneshot;
+    use wasmtime_wasi::p2::Pollable;
+    use wasmtime_wasi::p2::bindings::io::poll::{Host, HostPollable};
+    use wasmtime_wasi_io::poll::push_pollable;
+
+    struct Message(Option<oneshot::Receiver<()>>);
+
+    #[wasmtime_wasi::async_trait]
+    impl Pollable for Message {
+        async fn ready(&mut self) {
+            if let Some(rx) = &mut self.0 {
+                let _ = rx.await;
+                self.0 = None;
+            }
+        }
+    }
+
+    struct Never;
+
+    #[wasmtime_wasi::async_trait]
+    impl Pollable for Never {
+        async fn ready(&mut self) {
+            std::future::pending().await
+        }
+    }
+
+    let mut table = ResourceTable::new();
+    let (tx, rx) = oneshot::channel();
+    let message = push_pollable(&mut table, Message(Some(rx)))?;
+    let never = push_pollable(&mut table, Box::new(Never) as Box<dyn Pollable>)?;
+
+    let dup =
+        |p: &wasmtime::component::Resource<_>| wasmtime::component::Resource::new_borrow(p.rep());
+    assert!(!HostPollable::ready(&mut table, dup(&message)).await?);
+
+    tx.send(()).unwrap();
+    let ready = Host::poll(&mut table, vec![dup(&never), dup(&message)]).await?;
+    assert_eq!(ready, [1]);
+    assert!(HostPollable::ready(&mut table, dup(&message)).await?);
+    assert!(!HostPollable::ready(&mut table, dup(&never)).await?);
+
+    // Dropping the pollables also deletes the event sources they own.
+    HostPollable::drop(&mut table, message)?;
+    HostPollable::drop(&mut table, never)?;
+    assert!(table.is_empty());
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test)]
+async fn p2_api_filesystem_watch() -> Result<()> {
+    use std::any::Any;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use wasmtime::component::Resource;
+    use wasmtime_wasi::filesystem::{ChangeKind, ChangeSink, HostFileWatcher, WasiFilesystemView};
+    use wasmtime_wasi::p2::bindings::filesystem::preopens::Host as _;
+    use wasmtime_wasi::p2::bindings::filesystem_watch::{self, HostWatcher};
+    use wasmtime_wasi::p2::bindings::io::poll::HostPollable;
+
+    #[derive(Clone, Default)]
+    struct FakeWatcher(Arc<Mutex<Option<(PathBuf, ChangeSink)>>>);
+
+    impl HostFileWatcher for FakeWatcher {
+        fn watch(
+            &self,
+            path: &Path,
+            recursive: bool,
+            sink: ChangeSink,
+        ) -> std::io::Result<Box<dyn Any + Send + Sync>> {
+            assert!(recursive);
+            *self.0.lock().unwrap() = Some((path.to_owned(), sink));
+            Ok(Box::new(()))
+        }
+    }
+
+    fn dup<T: 'static>(r: &Resource<T>) -> Resource<T> {
+        Resource::new_borrow(r.rep())
+    }
+
+    let dir = tempfile::tempdir()?;
+
+    // Without a backend configured watching isn't supported.
+    let mut ctx = CommandCtx {
+        table: ResourceTable::new(),
+        wasi: WasiCtxBuilder::new()
+            .preopened_dir(dir.path(), "/", DirPerms::all(), FilePerms::all())?
+            .build(),
+    };
+    let mut fs = ctx.filesystem();
+    let (preopen, _) = fs.get_directories()?.pop().unwrap();
+    let err = filesystem_watch::Host::watch(&mut fs, dup(&preopen), true).unwrap_err();
+    assert_eq!(err.downcast()?, filesystem::ErrorCode::Unsupported);
+
+    let backend = FakeWatcher::default();
+    let mut ctx = CommandCtx {
+        table: ResourceTable::new(),
+        wasi: WasiCtxBuilder::new()
+            .preopened_dir(dir.path(), "/", DirPerms::all(), FilePerms::all())?
+            .file_watcher(backend.clone())
+            .build(),
+    };
+    let mut fs = ctx.filesystem();
+    let (preopen, _) = fs.get_directories()?.pop().unwrap();
+    let watcher = filesystem_watch::Host::watch(&mut fs, dup(&preopen), true)?;
+    let (root, sink) = backend.0.lock().unwrap().take().unwrap();
+    assert_eq!(root, dir.path().canonicalize()?);
+
+    let pollable = HostWatcher::subscribe(&mut fs, dup(&watcher))?;
+    assert!(!HostPollable::ready(&mut *fs.table, dup(&pollable)).await?);
+
+    // Changes outside of the watched directory are ignored.
+    sink.push(ChangeKind::Removed, &root.with_file_name("elsewhere"));
+    assert!(!HostPollable::ready(&mut *fs.table, dup(&pollable)).await?);
+
+    sink.push(ChangeKind::Created, &root.join("a.txt"));
+    sink.push(ChangeKind::Modified, &root.join("a.txt"));
+    assert!(HostPollable::ready(&mut *fs.table, dup(&pollable)).await?);
+    let changes = HostWatcher::read_changes(&mut fs, dup(&watcher), 1)?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, filesystem_watch::ChangeKind::Created);
+    assert_eq!(changes[0].path, "a.txt");
+    let changes = HostWatcher::read_changes(&mut fs, dup(&watcher), 10)?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, filesystem_watch::ChangeKind::Modified);
+    assert!(HostWatcher::read_changes(&mut fs, dup(&watcher), 10)?.is_empty());
+
+    // Lost changes are reported as a single overflow.
+    sink.push(ChangeKind::Created, &root.join("b.txt"));
+    sink.overflow();
+    sink.push(ChangeKind::Removed, &root.join("b.txt"));
+    let changes = HostWatcher::read_changes(&mut fs, dup(&watcher), 10)?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, filesystem_watch::ChangeKind::Overflow);
+    assert_eq!(changes[0].path, "");
+
+    HostPollable::drop(&mut *fs.table, pollable)?;
+    HostWatcher::drop(&mut fs, watcher)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test_log::test(tokio::test)]
+async fn p2_api_unix_sockets() -> Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use wasmtime::component::Resource;
+    use wasmtime_wasi::p2::bindings::io::streams::{HostInputStream, HostOutputStream};
+    use wasmtime_wasi::p2::bindings::sockets::network::ErrorCode;
+    use wasmtime_wasi::p2::bindings::unix_sockets::Host;
+    use wasmtime_wasi::sockets::WasiSocketsView;
+
+    fn dup<T: 'static>(r: &Resource<T>) -> Resource<T> {
+        Resource::new_borrow(r.rep())
+    }
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("echo.sock");
+    let path_str = path.to_str().unwrap().to_string();
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await?;
+        stream.write_all(&buf).await?;
+        std::io::Result::Ok(())
+    });
+
+    // Sockets which haven't been allowed can't be connected to.
+    let mut ctx = CommandCtx {
+        table: ResourceTable::new(),
+        wasi: WasiCtxBuilder::new().build(),
+    };
+    let err = Host::connect(&mut ctx.sockets(), path_str.clone())
+        .await
+        .unwrap_err();
+    assert_eq!(err.downcast()?, ErrorCode::AccessDenied);
+
+    let mut ctx = CommandCtx {
+        table: ResourceTable::new(),
+        wasi: WasiCtxBuilder::new()
+            .allow_unix_socket(&path)
+            .allow_unix_socket(dir.path().join("missing.sock"))
+            .build(),
+    };
+    let missing = dir.path().join("missing.sock");
+    let err = Host::connect(&mut ctx.sockets(), missing.to_str().unwrap().to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(err.downcast()?, ErrorCode::ConnectionRefused);
+
+    let mut sockets = ctx.sockets();
+    let (input, output) = Host::connect(&mut sockets, path_str).await?;
+    HostOutputStream::blocking_write_and_flush(
+        &mut *sockets.table,
+        dup(&output),
+        b"hello".to_vec(),
+    )
+    .await?;
+    // Dropping the output stream signals the end of the request to the peer.
+    HostOutputStream::drop(&mut *sockets.table, output).await?;
+    let mut echoed = Vec::new();
+    while echoed.len() < 5 {
+        echoed.extend(HostInputStream::blocking_read(&mut *sockets.table, dup(&input), 5).await?);
+    }
+    assert_eq!(echoed, b"hello");
+    HostInputStream::drop(&mut *sockets.table, input).await?;
+    server.await??;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test_log::test(tokio::test)]
+async fn p2_api_process() -> Result<()> {
+    use wasmtime::component::Resource;
+    use wasmtime_wasi::cli::{AllowedProcess, ChildProcess, WasiCliView};
+    use wasmtime_wasi::p2::StreamError;
+    use wasmtime_wasi::p2::bindings::cli_process::{
+        Command, ErrorCode, ExitStatus, Host, HostChild,
+    };
+    use wasmtime_wasi::p2::bindings::io::poll::HostPollable;
+    use wasmtime_wasi::p2::bindings::io::streams::{HostInputStream, HostOutputStream};
+
+    fn dup<T: 'static>(r: &Resource<T>) -> Resource<T> {
+        Resource::new_borrow(r.rep())
+    }
+
+    fn command(program: &str, args: &[&str], env: &[(&str, &str)]) -> Command {
+        Command {
+            program: program.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    let mut sh = AllowedProcess::new("sh", "/bin/sh");
+    sh.args(|args| args.len() == 2 && args[0] == "-c")
+        .allow_env("GREETING")
+        .env("NAME", "host");
+    let mut sleep = AllowedProcess::new("sleep", "/bin/sleep");
+    sleep
+        .args(|args| args == ["10"])
+        .timeout(Duration::from_millis(100));
+    let mut ctx = CommandCtx {
+        table: ResourceTable::new(),
+        wasi: WasiCtxBuilder::new()
+            .allow_process(AllowedProcess::new("cat", "/bin/cat"))
+            .allow_process(AllowedProcess::new("missing", "/nonexistent/program"))
+            .allow_process(sh)
+            .allow_process(sleep)
+            .max_processes(2)
+            .build(),
+    };
+    let mut cli = ctx.cli();
+
+    // Only allowed programs, arguments and environment variables may be used.
+    for denied in [
+        command("ls", &[], &[]),
+        command("/bin/cat", &[], &[]),
+        command("cat", &["/etc/passwd"], &[]),
+        command("sh", &["-c", "true"], &[("PATH", "/bin")]),
+    ] {
+        let err = Host::spawn(&mut cli, denied)?.unwrap_err();
+        assert_eq!(err, ErrorCode::AccessDenied);
+    }
+    let err = Host::spawn(&mut cli, command("missing", &[], &[]))?.unwrap_err();
+    assert_eq!(err, ErrorCode::NotFound);
+
+    async fn read_to_end(
+        table: &mut ResourceTable,
+        stream: Resource<wasmtime_wasi::p2::DynInputStream>,
+    ) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        loop {
+            match HostInputStream::blocking_read(table, dup(&stream), 1024).await {
+                Ok(bytes) => output.extend(bytes),
+                Err(StreamError::Closed) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        HostInputStream::drop(table, stream).await?;
+        Ok(output)
+    }
+
+    async fn wait(table: &mut ResourceTable, child: &Resource<ChildProcess>) -> Result<()> {
+        let pollable = wasmtime_wasi::p2::subscribe(table, dup(child))?;
+        HostPollable::block(table, dup(&pollable)).await?;
+        HostPollable::drop(table, pollable)?;
+        Ok(())
+    }
+
+    // Standard input and output of processes are connected to streams.
+    let cat = Host::spawn(&mut cli, command("cat", &[], &[]))?.unwrap();
+    let stdin = HostChild::take_stdin(&mut cli, dup(&cat))?.unwrap();
+    assert!(HostChild::take_stdin(&mut cli, dup(&cat))?.is_none());
+    let stdout = HostChild::take_stdout(&mut cli, dup(&cat))?.unwrap();
+    HostOutputStream::blocking_write_and_flush(&mut *cli.table, dup(&stdin), b"hello".to_vec())
+        .await?;
+    HostOutputStream::drop(&mut *cli.table, stdin).await?;
+    assert_eq!(read_to_end(cli.table, stdout).await?, b"hello");
+    wait(cli.table, &cat).await?;
+    assert!(matches!(
+        HostChild::exit_status(&mut cli, dup(&cat))?,
+        Some(ExitStatus::Exited(0))
+    ));
+
+    // The environment only contains the variables set by the guest and host.
+    let sh = Host::spawn(
+        &mut cli,
+        command(
+            "sh",
+            &["-c", "printf '%s %s' \"$GREETING\" \"$NAME\"; exit 3"],
+            &[("GREETING", "hi")],
+        ),
+    )?
+    .unwrap();
+    let stdout = HostChild::take_stdout(&mut cli, dup(&sh))?.unwrap();
+    assert_eq!(read_to_end(cli.table, stdout).await?, b"hi host");
+    wait(cli.table, &sh).await?;
+    assert!(matches!(
+        HostChild::exit_status(&mut cli, dup(&sh))?,
+        Some(ExitStatus::Exited(3))
+    ));
+
+    // Processes count towards the limit until they're dropped.
+    let err = Host::spawn(&mut cli, command("cat", &[], &[]))?.unwrap_err();
+    assert_eq!(err, ErrorCode::LimitReached);
+    HostChild::drop(&mut cli, cat)?;
+    HostChild::drop(&mut cli, sh)?;
+
+    // Processes can be killed by the guest, and are killed by the host once
+    // their timeout elapses.
+    let cat = Host::spawn(&mut cli, command("cat", &[], &[]))?.unwrap();
+    assert!(HostChild::exit_status(&mut cli, dup(&cat))?.is_none());
+    HostChild::kill(&mut cli, dup(&cat))?;
+    wait(cli.table, &cat).await?;
+    assert!(matches!(
+        HostChild::exit_status(&mut cli, dup(&cat))?,
+        Some(ExitStatus::Terminated)
+    ));
+    let sleep = Host::spawn(&mut cli, command("sleep", &["10"], &[]))?.unwrap();
+    wait(cli.table, &sleep).await?;
+    assert!(matches!(
+        HostChild::exit_status(&mut cli, dup(&sleep))?,
+        Some(ExitStatus::Terminated)
+    ));
+    HostChild::drop(&mut cli, cat)?;
+    HostChild::drop(&mut cli, sleep)?;
+
+    // Spawning is denied while side effects are restricted.
+    ctx.wasi.restrict_side_effects(true);
+    let err = Host::spawn(&mut ctx.cli(), command("cat", &[], &[]))?.unwrap_err();
+    assert_eq!(err, ErrorCode::AccessDenied);
+    Ok(())
+}
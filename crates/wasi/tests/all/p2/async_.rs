@@ -1,8 +1,8 @@
 use crate::store::{Ctx, MyWasiCtx};
 use std::path::Path;
 use test_programs_artifacts::*;
-use wasmtime::Result;
 use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Result};
 use wasmtime_wasi::WasiCtxBuilder;
 use wasmtime_wasi::p2::add_to_linker_async;
 use wasmtime_wasi::p2::bindings::Command;
@@ -15,18 +15,27 @@ async fn run_with_workspace_setup(
     path: &str,
     setup: impl FnOnce(&Path) -> Result<()>,
     with_builder: impl FnOnce(&mut WasiCtxBuilder),
+) -> Result<()> {
+    let engine = test_programs_artifacts::engine(|_config| {});
+    run_with_engine(&engine, path, setup, with_builder).await
+}
+
+async fn run_with_engine(
+    engine: &Engine,
+    path: &str,
+    setup: impl FnOnce(&Path) -> Result<()>,
+    with_builder: impl FnOnce(&mut WasiCtxBuilder),
 ) -> Result<()> {
     let path = Path::new(path);
     let name = path.file_stem().unwrap().to_str().unwrap();
-    let engine = test_programs_artifacts::engine(|_config| {});
-    let mut linker = Linker::new(&engine);
+    let mut linker = Linker::new(engine);
     add_to_linker_async(&mut linker)?;
 
-    let (mut store, _td) = Ctx::new_with_workspace_setup(&engine, name, setup, |builder| {
+    let (mut store, _td) = Ctx::new_with_workspace_setup(engine, name, setup, |builder| {
         with_builder(builder);
         MyWasiCtx::new(builder.build())
     })?;
-    let component = Component::from_file(&engine, path)?;
+    let component = Component::from_file(engine, path)?;
     let command = Command::instantiate_async(&mut store, &component, &linker).await?;
     command
         .wasi_cli_run()
@@ -414,6 +423,84 @@ async fn p2_file_read_write() {
     run(P2_FILE_READ_WRITE_COMPONENT, |_| {}).await.unwrap()
 }
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn p2_file_read_write_blocking_pool() {
+    let engine = test_programs_artifacts::engine(|_config| {});
+    let pool = wasmtime_wasi::runtime::BlockingPool::builder()
+        .max_threads(2)
+        .install(&engine)
+        .unwrap();
+    // Every context of the engine shares the pool installed above.
+    for _ in 0..2 {
+        run_with_engine(
+            &engine,
+            P2_FILE_READ_WRITE_COMPONENT,
+            |_| Ok(()),
+            |builder| {
+                let pool = wasmtime_wasi::runtime::BlockingPool::for_engine(&engine).unwrap();
+                builder.blocking_pool(pool);
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let metrics = pool.metrics();
+    assert!(metrics.completed > 0);
+    assert_eq!(metrics.queued, 0);
+    assert_eq!(metrics.running, 0);
+    assert_eq!(metrics.rejected, 0);
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn p2_file_read_write_rate_limited() {
+    let start = std::time::Instant::now();
+    run(P2_FILE_READ_WRITE_COMPONENT, |builder| {
+        builder.max_file_read_rate(16).max_file_write_rate(8);
+    })
+    .await
+    .unwrap();
+    // The guest reads the 18-byte file twice, which exceeds its initial
+    // budget of 16 bytes by more than a second's worth of bytes.
+    assert!(start.elapsed() >= std::time::Duration::from_secs(1));
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn p2_file_read_write_rate_limit_traps() {
+    let e = run(P2_FILE_READ_WRITE_COMPONENT, |builder| {
+        builder
+            .max_file_write_rate(4)
+            .io_limit_behavior(wasmtime_wasi::IoLimitBehavior::Trap);
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(
+        format!("{}", e.source().expect("trap source")),
+        "file write rate limit exceeded"
+    )
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn p2_tcp_sample_application_rate_limited() {
+    run(P2_TCP_SAMPLE_APPLICATION_COMPONENT, |builder| {
+        builder
+            .max_socket_read_rate(64)
+            .max_socket_write_rate(64)
+            .max_tcp_connections(1);
+    })
+    .await
+    .unwrap()
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn p2_tcp_connection_limit_traps() {
+    let e = run(P2_TCP_SAMPLE_APPLICATION_COMPONENT, |builder| {
+        builder
+            .max_tcp_connections(0)
+            .io_limit_behavior(wasmtime_wasi::IoLimitBehavior::Trap);
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(
+        format!("{}", e.source().expect("trap source")),
+        "TCP connection limit exceeded"
+    )
+}
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
 async fn p2_udp_send_too_much() {
     let e = run(P2_UDP_SEND_TOO_MUCH_COMPONENT, |_| {})
         .await
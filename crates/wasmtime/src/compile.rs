@@ -31,7 +31,8 @@ use wasmtime_environ::{
     Abi, CompiledFunctionBody, CompiledFunctionsTable, CompiledFunctionsTableBuilder,
     CompiledModuleInfo, Compiler, DefinedFuncIndex, FilePos, FinishedObject, FuncKey,
     FunctionBodyData, Inlining, InliningCompiler, ModuleEnvironment, ModuleTranslation,
-    ModuleTypes, ModuleTypesBuilder, ObjectKind, PrimaryMap, StaticModuleIndex, Tunables,
+    ModuleTypes, ModuleTypesBuilder, ObjectKind, PrimaryMap, SpectreMitigations, StaticModuleIndex,
+    Tunables, WasmProfile,
     graphs::{EntityGraph, Graph as _},
 };
 #[cfg(feature = "component-model")]
@@ -59,14 +60,18 @@ mod runtime;
 /// type information found within.
 pub(crate) fn build_module_artifacts<T: FinishedObject>(
     engine: &Engine,
+    recompile: bool,
     wasm: &[u8],
     dwarf_package: Option<&[u8]>,
+    spectre_mitigations: SpectreMitigations,
+    profile: Option<&WasmProfile>,
+    variants: &[Vec<u8>],
     obj_state: &T::State,
 ) -> Result<(
     T,
     Option<(CompiledModuleInfo, CompiledFunctionsTable, ModuleTypes)>,
 )> {
-    let compiler = engine.try_compiler()?;
+    let compiler = engine.try_compiler_for(recompile)?;
     let tunables = engine.tunables();
 
     // First a `ModuleEnvironment` is created which records type information
@@ -85,11 +90,13 @@ pub(crate) fn build_module_artifacts<T: FinishedObject>(
     )
     .translate(parser, wasm)
     .context("failed to parse WebAssembly module")?;
+    translation.module.spectre_mitigations = spectre_mitigations;
+    translation.profile = profile.cloned();
     prepare_translation(engine, compiler, &mut translation, &mut types);
     let functions = mem::take(&mut translation.function_body_inputs);
 
     let compile_inputs = CompileInputs::for_module(&types, &translation, functions);
-    let unlinked_compile_outputs = compile_inputs.compile(engine, &types)?;
+    let unlinked_compile_outputs = compile_inputs.compile(engine, compiler, &types)?;
     let PreLinkOutput {
         needs_gc_heap,
         compiled_funcs,
@@ -111,10 +118,12 @@ pub(crate) fn build_module_artifacts<T: FinishedObject>(
     // it's left as an exercise for later.
     engine.append_compiler_info(&mut object)?;
     engine.append_bti(&mut object);
+    engine.append_variants(&mut object, variants);
 
     let (mut object, compilation_artifacts) = indices.link_and_append_code(
         object,
         engine,
+        compiler,
         compiled_funcs,
         std::iter::once(translation).collect(),
         dwarf_package,
@@ -124,7 +133,8 @@ pub(crate) fn build_module_artifacts<T: FinishedObject>(
         object.append_wasm_bytecode(std::iter::once(wasm));
     }
 
-    let (info, index) = compilation_artifacts.unwrap_as_module_info();
+    let (mut info, index) = compilation_artifacts.unwrap_as_module_info();
+    info.meta.recompiled = recompile;
     let types = types.finish();
     object.serialize_info(&(&info, &index, &types));
     let result = T::finish_object(object, obj_state)?;
@@ -142,9 +152,12 @@ pub(crate) fn build_module_artifacts<T: FinishedObject>(
 #[cfg(feature = "component-model")]
 pub(crate) fn build_component_artifacts<T: FinishedObject>(
     engine: &Engine,
+    recompile: bool,
     binary: &[u8],
     _dwarf_package: Option<&[u8]>,
     unsafe_intrinsics_import: Option<&str>,
+    spectre_mitigations: SpectreMitigations,
+    variants: &[Vec<u8>],
     obj_state: &T::State,
 ) -> Result<(T, Option<wasmtime_environ::component::ComponentArtifacts>)> {
     use wasmtime_environ::ScopeVec;
@@ -152,7 +165,7 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
         CompiledComponentInfo, ComponentArtifacts, ComponentTypesBuilder,
     };
 
-    let compiler = engine.try_compiler()?;
+    let compiler = engine.try_compiler_for(recompile)?;
     let tunables = engine.tunables();
 
     let scope = ScopeVec::new();
@@ -167,6 +180,7 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
         .context("failed to parse WebAssembly module")?;
 
     for (_, translation) in module_translations.iter_mut() {
+        translation.module.spectre_mitigations = spectre_mitigations;
         prepare_translation(
             engine,
             compiler,
@@ -184,7 +198,8 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
             (i, &*translation, functions)
         }),
     );
-    let unlinked_compile_outputs = compile_inputs.compile(&engine, types.module_types_builder())?;
+    let unlinked_compile_outputs =
+        compile_inputs.compile(engine, compiler, types.module_types_builder())?;
 
     let PreLinkOutput {
         needs_gc_heap,
@@ -208,14 +223,19 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
     let mut object = compiler.object(ObjectKind::Component)?;
     engine.append_compiler_info(&mut object)?;
     engine.append_bti(&mut object);
+    engine.append_variants(&mut object, variants);
 
-    let (mut object, compilation_artifacts) = indices.link_and_append_code(
+    let (mut object, mut compilation_artifacts) = indices.link_and_append_code(
         object,
         engine,
+        compiler,
         compiled_funcs,
         module_translations,
         None, // TODO: Support dwarf packages for components.
     )?;
+    for (_, module) in compilation_artifacts.modules.iter_mut() {
+        module.meta.recompiled = recompile;
+    }
 
     if tunables.debug_guest {
         object.append_wasm_bytecode(module_wasms);
@@ -275,15 +295,29 @@ struct CompileOutput<'a> {
     func_body: Option<wasmparser::FunctionBody<'a>>,
 }
 
+impl CompileOutput<'_> {
+    /// Returns how many times this function was called according to the
+    /// profile its module is compiled with, if any.
+    fn profiled_calls(&self) -> Option<u64> {
+        let translation = self.translation?;
+        let profile = translation.profile.as_ref()?;
+        let (_, def_func_index) = self.key.unwrap_defined_wasm_function();
+        Some(profile.calls(translation.module.func_index(def_func_index)))
+    }
+}
+
 /// Inputs to our inlining heuristics.
 struct InlineHeuristicParams<'a> {
     tunables: &'a Tunables,
     caller_size: u32,
     caller_key: FuncKey,
     caller_needs_gc_heap: bool,
+    caller_calls: Option<u64>,
     callee_size: u32,
     callee_key: FuncKey,
     callee_needs_gc_heap: bool,
+    callee_is_fused_adapter: bool,
+    callee_calls: Option<u64>,
 }
 
 /// The collection of things we need to compile for a Wasm module or component.
@@ -594,10 +628,9 @@ impl<'a> CompileInputs<'a> {
     fn compile(
         self,
         engine: &Engine,
+        compiler: &dyn Compiler,
         types: &'a ModuleTypesBuilder,
     ) -> Result<UnlinkedCompileOutputs<'a>> {
-        let compiler = engine.try_compiler()?;
-
         if self.inputs.len() > 0 && cfg!(miri) {
             bail!(
                 "\
@@ -617,7 +650,8 @@ the use case.
         }
 
         let mut raw_outputs = if let Some(inlining_compiler) = compiler.inlining_compiler() {
-            if engine.tunables().inlining != Inlining::No {
+            let tunables = engine.tunables();
+            if tunables.inlining != Inlining::No || tunables.inlining_adapter_size > 0 {
                 self.compile_with_inlining(engine, compiler, inlining_compiler)?
             } else {
                 // Inlining compiler but inlining is disabled: compile each
@@ -653,7 +687,7 @@ the use case.
         // wasmtime-builtin functions are necessary. If so those need to be
         // collected and then those trampolines additionally need to be
         // compiled.
-        compile_required_builtins(engine, types, &mut raw_outputs)?;
+        compile_required_builtins(engine, compiler, types, &mut raw_outputs)?;
 
         // Bucket the outputs by kind.
         let mut outputs: BTreeMap<FuncKey, CompileOutput> = BTreeMap::new();
@@ -754,6 +788,7 @@ the use case.
                     let caller_key = output.key;
                     let caller_needs_gc_heap =
                         output.translation.is_some_and(|t| t.module.needs_gc_heap);
+                    let caller_calls = output.profiled_calls();
                     let caller = &mut output.function;
 
                     let mut caller_size = inlining_compiler.size(caller);
@@ -778,15 +813,21 @@ the use case.
                         let callee_needs_gc_heap = callee_output
                             .translation
                             .is_some_and(|t| t.module.needs_gc_heap);
+                        let callee_is_fused_adapter = callee_output
+                            .translation
+                            .is_some_and(|t| t.is_fused_adapter);
 
                         if Self::should_inline(InlineHeuristicParams {
                             tunables: engine.tunables(),
                             caller_size,
                             caller_key,
                             caller_needs_gc_heap,
+                            caller_calls,
                             callee_size,
                             callee_key,
                             callee_needs_gc_heap,
+                            callee_is_fused_adapter,
+                            callee_calls: callee_output.profiled_calls(),
                         }) {
                             caller_size = caller_size.saturating_add(callee_size);
                             Some(callee)
@@ -843,9 +884,12 @@ the use case.
             caller_size,
             caller_key,
             caller_needs_gc_heap,
+            caller_calls,
             callee_size,
             callee_key,
             callee_needs_gc_heap,
+            callee_is_fused_adapter,
+            callee_calls,
         }: InlineHeuristicParams,
     ) -> bool {
         log::trace!(
@@ -853,13 +897,16 @@ the use case.
              \tcaller = {caller_key:?}\n\
              \t\tsize = {caller_size}\n\
              \t\tneeds_gc_heap = {caller_needs_gc_heap}\n\
+             \t\tprofiled_calls = {caller_calls:?}\n\
              \tcallee = {callee_key:?}\n\
              \t\tsize = {callee_size}\n\
-             \t\tneeds_gc_heap = {callee_needs_gc_heap}"
+             \t\tneeds_gc_heap = {callee_needs_gc_heap}\n\
+             \t\tis_fused_adapter = {callee_is_fused_adapter}\n\
+             \t\tprofiled_calls = {callee_calls:?}"
         );
 
         debug_assert!(
-            tunables.inlining != Inlining::No,
+            tunables.inlining != Inlining::No || tunables.inlining_adapter_size > 0,
             "shouldn't even call this method if we aren't configured for inlining"
         );
         debug_assert_ne!(caller_key, callee_key, "we never inline recursion");
@@ -886,6 +933,42 @@ the use case.
             return false;
         }
 
+        // Fused adapters between components frequently do little more than
+        // shuffle arguments around, so small ones are inlined regardless of
+        // the configured inlining mode to remove the overhead of the extra
+        // call on each cross-component call.
+        if callee_is_fused_adapter && callee_size <= tunables.inlining_adapter_size {
+            log::trace!(
+                "  --> inlining: callee is a fused adapter no larger than the \
+                 adapter-inlining size: {callee_size} <= {}",
+                tunables.inlining_adapter_size
+            );
+            return true;
+        }
+        if tunables.inlining == Inlining::No {
+            log::trace!("  --> not inlining: only small fused adapters are inlined");
+            return false;
+        }
+
+        // A profile of the module tells us which functions actually run.
+        // Functions which never ran aren't worth growing code for, while
+        // callees which ran at least as often as their caller are presumably
+        // on its hot path.
+        let callee_is_hot = match (caller_calls, callee_calls) {
+            (Some(0), _) => {
+                log::trace!("  --> not inlining: the profile shows the caller was never called");
+                return false;
+            }
+            (_, Some(0)) => {
+                log::trace!("  --> not inlining: the profile shows the callee was never called");
+                return false;
+            }
+            (Some(caller_calls), Some(callee_calls)) => {
+                caller_calls > 0 && callee_calls >= caller_calls
+            }
+            _ => false,
+        };
+
         // Consider whether this is an intra-module call.
         //
         // Inlining within a single core module has most often already been done
@@ -900,6 +983,14 @@ the use case.
             ) => match tunables.inlining {
                 Inlining::Yes => {}
 
+                // The reasoning above doesn't apply when a profile shows the
+                // callee is hot, which is information the toolchain didn't
+                // necessarily have.
+                Inlining::InterModuleAndIntraGc | Inlining::InterModule if callee_is_hot => {
+                    log::trace!("  --> inlining: the profile shows the callee is hot");
+                    return true;
+                }
+
                 Inlining::InterModuleAndIntraGc => {
                     if caller_module == callee_module && !caller_needs_gc_heap {
                         log::trace!("  --> not inlining: intra-module call where GC is not used");
@@ -991,10 +1082,10 @@ fn inlining_functions<'a>(
 
 fn compile_required_builtins<'a>(
     engine: &Engine,
+    compiler: &dyn Compiler,
     types: &'a ModuleTypesBuilder,
     raw_outputs: &mut Vec<CompileOutput<'a>>,
 ) -> Result<()> {
-    let compiler = engine.try_compiler()?;
     let mut builtins = HashSet::new();
     let mut new_inputs: Vec<CompileInput<'_>> = Vec::new();
 
@@ -1124,6 +1215,7 @@ impl FunctionIndices {
         self,
         mut obj: object::write::Object<'static>,
         engine: &'a Engine,
+        compiler: &dyn Compiler,
         compiled_funcs: Vec<(String, FuncKey, Box<dyn Any + Send + Sync>)>,
         translations: PrimaryMap<StaticModuleIndex, ModuleTranslation<'_>>,
         dwarf_package_bytes: Option<&[u8]>,
@@ -1133,7 +1225,6 @@ impl FunctionIndices {
         // The result is a vector parallel to `compiled_funcs` where
         // `symbol_ids_and_locs[i]` is the symbol ID and function location of
         // `compiled_funcs[i]`.
-        let compiler = engine.try_compiler()?;
         let tunables = engine.tunables();
         let symbol_ids_and_locs = compiler.append_code(
             &mut obj,
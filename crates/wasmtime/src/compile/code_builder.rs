@@ -2,6 +2,7 @@ use crate::Engine;
 use crate::prelude::*;
 use std::borrow::Cow;
 use std::path::Path;
+use wasmtime_environ::{ObjectKind, SpectreMitigations, WasmProfile};
 
 #[cfg(feature = "compile-time-builtins")]
 use crate::hash_map::HashMap;
@@ -47,6 +48,15 @@ pub struct CodeBuilder<'a> {
     dwarf_package: Option<Cow<'a, [u8]>>,
     dwarf_package_path: Option<Cow<'a, Path>>,
     unsafe_intrinsics_import: Option<String>,
+    spectre_mitigations: SpectreMitigations,
+    profile: Option<WasmProfile>,
+
+    /// Whether to compile with the engine's optimizing background recompiler
+    /// rather than its primary compiler.
+    pub(super) recompile: bool,
+
+    /// Engines to additionally compile variants of serialized artifacts with.
+    pub(super) variants: Vec<&'a Engine>,
 
     /// A map from import name to the Wasm bytes of the associated compile-time
     /// builtin and its file path, if any.
@@ -79,11 +89,38 @@ impl<'a> CodeBuilder<'a> {
             dwarf_package: None,
             dwarf_package_path: None,
             unsafe_intrinsics_import: None,
+            spectre_mitigations: SpectreMitigations::Enabled,
+            profile: None,
+            recompile: false,
+            variants: Vec::new(),
             #[cfg(feature = "compile-time-builtins")]
             compile_time_builtins: HashMap::default(),
         }
     }
 
+    /// Returns a builder for the same input as this one which compiles with
+    /// `engine` instead, without any variants.
+    fn with_engine<'b>(&'b self, engine: &'b Engine) -> CodeBuilder<'b> {
+        CodeBuilder {
+            engine,
+            wasm: self.wasm.as_deref().map(Cow::Borrowed),
+            wasm_path: self.wasm_path.as_deref().map(Cow::Borrowed),
+            dwarf_package: self.dwarf_package.as_deref().map(Cow::Borrowed),
+            dwarf_package_path: self.dwarf_package_path.as_deref().map(Cow::Borrowed),
+            unsafe_intrinsics_import: self.unsafe_intrinsics_import.clone(),
+            spectre_mitigations: self.spectre_mitigations,
+            profile: self.profile.clone(),
+            recompile: self.recompile,
+            variants: Vec::new(),
+            #[cfg(feature = "compile-time-builtins")]
+            compile_time_builtins: self
+                .compile_time_builtins
+                .iter()
+                .map(|(name, wasm)| (Cow::Borrowed(&**name), Cow::Borrowed(&**wasm)))
+                .collect(),
+        }
+    }
+
     /// Configures the WebAssembly binary that is being compiled.
     ///
     /// The `wasm_bytes` parameter must be a binary WebAssembly file.
@@ -707,6 +744,55 @@ impl<'a> CodeBuilder<'a> {
         self
     }
 
+    /// Configures the tier of Spectre mitigations that the code being compiled
+    /// is hardened with.
+    ///
+    /// By default all Spectre mitigations configured for the [`Engine`] are
+    /// applied to all code it compiles. This method allows removing some or
+    /// all of them from just the code compiled by this builder, for example
+    /// for trusted first-party code sharing an engine with untrusted code.
+    /// Other modules and components compiled by the same engine are
+    /// unaffected. See [`SpectreMitigations`] for the available tiers, and
+    /// note that a tier never re-enables mitigations which were disabled for
+    /// the whole engine.
+    ///
+    /// The tier a module was compiled with is recorded in its compiled, and
+    /// serialized, artifact and can be queried with
+    /// [`Module::spectre_mitigations`](crate::Module::spectre_mitigations).
+    ///
+    /// # Unsafety
+    ///
+    /// Removing Spectre mitigations allows the compiled code to speculatively
+    /// read memory outside of its linear memories and tables, which includes
+    /// memory belonging to the host and to other instances. Code compiled with
+    /// anything other than [`SpectreMitigations::Enabled`] must only be code
+    /// that is trusted not to attempt such attacks.
+    pub unsafe fn spectre_mitigations(&mut self, mitigations: SpectreMitigations) -> &mut Self {
+        self.spectre_mitigations = mitigations;
+        self
+    }
+
+    /// Configures an execution profile of the module being compiled to
+    /// optimize it for.
+    ///
+    /// Profiles are recorded by running the same module compiled with
+    /// [`Config::profile_instrumentation`](crate::Config::profile_instrumentation)
+    /// enabled, see [`Module::profile`](crate::Module::profile). Cranelift
+    /// uses the profile to lay out the successors of branches which always
+    /// went the same way as cold code and, when
+    /// [`Config::compiler_inlining`](crate::Config::compiler_inlining) is
+    /// enabled, to avoid inlining functions which were never called and to
+    /// inline frequently called functions more eagerly. Winch ignores
+    /// profiles.
+    ///
+    /// Profiles never affect the behavior of the compiled code, only its
+    /// performance. They are only meaningful for the exact module binary they
+    /// were recorded from and can't be used when compiling components.
+    pub fn profile(&mut self, profile: WasmProfile) -> &mut Self {
+        self.profile = Some(profile);
+        self
+    }
+
     /// Explicitly specify DWARF `.dwp` path.
     ///
     /// # Errors
@@ -782,6 +868,50 @@ impl<'a> CodeBuilder<'a> {
         }
     }
 
+    /// Additionally compiles the input with `engine`, embedding the result in
+    /// the serialized artifact as a variant to load in its place on hosts
+    /// which support it.
+    ///
+    /// This is intended for artifacts distributed to hosts with differing CPU
+    /// features. For example an artifact for x86-64 hosts can be compiled
+    /// with an engine targeting AVX2 and carry a variant compiled by an
+    /// engine which additionally enables the `has_avx512*` Cranelift flags.
+    /// When the artifact is loaded the first of its variants, in the order
+    /// they were added, which is compatible with the host is used. If no
+    /// variant is compatible then the artifact's own code is used.
+    ///
+    /// The `engine` must be configured the same as this builder's engine
+    /// except for ISA-specific flags, such as CPU features, which is checked
+    /// when compiling.
+    ///
+    /// Variants can only be used when producing serialized artifacts, with
+    /// [`CodeBuilder::compile_module_serialized`] or
+    /// [`CodeBuilder::compile_component_serialized`].
+    pub fn variant(&mut self, engine: &'a Engine) -> &mut Self {
+        self.variants.push(engine);
+        self
+    }
+
+    /// Compiles each variant added with [`CodeBuilder::variant`] with
+    /// `compile`, checking that it can be embedded in this builder's
+    /// artifact.
+    fn compile_variants(
+        &self,
+        kind: ObjectKind,
+        compile: impl Fn(&CodeBuilder<'_>) -> Result<Vec<u8>>,
+    ) -> Result<Vec<Vec<u8>>> {
+        self.variants
+            .iter()
+            .map(|engine| {
+                let variant = compile(&self.with_engine(engine))?;
+                self.engine
+                    .check_variant(&variant, kind)
+                    .context("variant is incompatible with the engine it's embedded for")?;
+                Ok(variant)
+            })
+            .collect()
+    }
+
     /// Finishes this compilation and produces a serialized list of bytes.
     ///
     /// This method requires that either [`CodeBuilder::wasm_binary`] or
@@ -809,10 +939,20 @@ impl<'a> CodeBuilder<'a> {
             "compile-time builtins can only be used with components"
         );
 
+        let variants =
+            self.compile_variants(ObjectKind::Module, |b| b.compile_module_serialized())?;
         let wasm = self.get_wasm()?;
         let dwarf_package = self.get_dwarf_package();
-        let (v, _) =
-            super::build_module_artifacts(self.engine, &wasm, dwarf_package.as_deref(), &())?;
+        let (v, _) = super::build_module_artifacts(
+            self.engine,
+            self.recompile,
+            &wasm,
+            dwarf_package.as_deref(),
+            self.spectre_mitigations,
+            self.profile.as_ref(),
+            &variants,
+            &(),
+        )?;
         Ok(v)
     }
 
@@ -821,12 +961,21 @@ impl<'a> CodeBuilder<'a> {
     /// instead of a module.
     #[cfg(feature = "component-model")]
     pub fn compile_component_serialized(&self) -> Result<Vec<u8>> {
+        ensure!(
+            self.profile.is_none(),
+            "`CodeBuilder::profile` can only be used with modules"
+        );
+        let variants =
+            self.compile_variants(ObjectKind::Component, |b| b.compile_component_serialized())?;
         let wasm = self.get_wasm()?;
         let (v, _) = super::build_component_artifacts(
             self.engine,
+            self.recompile,
             &wasm,
             None,
             self.get_unsafe_intrinsics_import(),
+            self.spectre_mitigations,
+            &variants,
             &(),
         )?;
         Ok(v)
@@ -835,6 +984,23 @@ impl<'a> CodeBuilder<'a> {
     pub(super) fn get_unsafe_intrinsics_import(&self) -> Option<&str> {
         self.unsafe_intrinsics_import.as_deref()
     }
+
+    pub(super) fn get_spectre_mitigations(&self) -> SpectreMitigations {
+        self.spectre_mitigations
+    }
+
+    pub(super) fn get_profile(&self) -> Option<&WasmProfile> {
+        self.profile.as_ref()
+    }
+
+    /// Configures this builder to compile with the engine's background
+    /// recompiler, see
+    /// [`Config::background_recompilation`](crate::Config::background_recompilation).
+    #[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+    pub(crate) fn recompile(&mut self) -> &mut Self {
+        self.recompile = true;
+        self
+    }
 }
 
 /// This is a helper struct used when caching to hash the state of an `Engine`
@@ -1,20 +1,25 @@
+#[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+use crate::RecompilingModule;
 #[cfg(feature = "component-model")]
 use crate::component::Component;
 use crate::prelude::*;
 use crate::runtime::vm::MmapVec;
-use crate::{CodeBuilder, CodeMemory, Engine, Module};
+use crate::{CodeBuilder, CodeMemory, CodeOrigin, Engine, Module};
 use object::write::WritableBuffer;
 use std::sync::Arc;
-use wasmtime_environ::{FinishedObject, ObjectBuilder};
+use wasmtime_environ::{FinishedObject, ObjectBuilder, SpectreMitigations, WasmProfile};
 
 impl<'a> CodeBuilder<'a> {
     fn compile_cached<T, S>(
         &self,
         build_artifacts: fn(
             &Engine,
+            bool,
             &[u8],
             Option<&[u8]>,
             Option<&str>,
+            SpectreMitigations,
+            Option<&WasmProfile>,
             &S,
         ) -> Result<(MmapVecWrapper, Option<T>)>,
         state: &S,
@@ -22,6 +27,8 @@ impl<'a> CodeBuilder<'a> {
         let wasm = self.get_wasm()?;
         let dwarf_package = self.get_dwarf_package();
         let unsafe_intrinsics_import = self.get_unsafe_intrinsics_import();
+        let spectre_mitigations = self.get_spectre_mitigations();
+        let profile = self.get_profile();
 
         self.engine
             .check_compatible_with_native_host()
@@ -34,6 +41,11 @@ impl<'a> CodeBuilder<'a> {
                 &wasm,
                 &dwarf_package,
                 &unsafe_intrinsics_import,
+                spectre_mitigations,
+                profile,
+                // Only which compiler is used needs to be hashed, as the
+                // engine's settings for both are hashed above.
+                self.recompile,
                 // Don't hash this as it's just its own "pure" function pointer.
                 NotHashed(build_artifacts),
                 // Don't hash the FinishedObject state: this contains
@@ -51,32 +63,41 @@ impl<'a> CodeBuilder<'a> {
                             wasm,
                             dwarf_package,
                             unsafe_intrinsics_import,
+                            spectre_mitigations,
+                            profile,
+                            recompile,
                             build_artifacts,
                             state,
                         )|
                          -> Result<_> {
                             let (mmap, info) = (build_artifacts.0)(
                                 engine.0,
+                                *recompile,
                                 wasm,
                                 dwarf_package.as_deref(),
                                 **unsafe_intrinsics_import,
+                                *spectre_mitigations,
+                                *profile,
                                 state.0,
                             )?;
                             let code = publish_mmap(engine.0, mmap.0)?;
                             Ok((code, info))
                         },
                         // Implementation of how to serialize artifacts
-                        |(_engine, _wasm, _, _, _, _), (code, _info_and_types)| {
+                        |(_engine, _wasm, _, _, _, _, _, _, _), (code, _info_and_types)| {
                             Some(code.mmap().to_vec())
                         },
                         // Cache hit, deserialize the provided artifacts
-                        |(engine, wasm, _, _, _, _), serialized_bytes| {
+                        |(engine, wasm, _, _, _, _, _, _, _), serialized_bytes| {
                             let kind = if wasmparser::Parser::is_component(&wasm) {
                                 wasmtime_environ::ObjectKind::Component
                             } else {
                                 wasmtime_environ::ObjectKind::Module
                             };
-                            let code = engine.0.load_code_bytes(&serialized_bytes, kind).ok()?;
+                            let code = engine
+                                .0
+                                .load_code_bytes(&serialized_bytes, kind, CodeOrigin::Cache)
+                                .ok()?;
                             Some((code, None))
                         },
                     )?;
@@ -93,9 +114,12 @@ impl<'a> CodeBuilder<'a> {
         {
             let (mmap, info_and_types) = build_artifacts(
                 self.engine,
+                self.recompile,
                 &wasm,
                 dwarf_package.as_deref(),
                 unsafe_intrinsics_import,
+                spectre_mitigations,
+                profile,
                 state,
             )?;
             let code = publish_mmap(self.engine, mmap.0)?;
@@ -109,6 +133,10 @@ impl<'a> CodeBuilder<'a> {
     /// Note that this method will cache compilations if the `cache` feature is
     /// enabled and turned on in [`Config`](crate::Config).
     pub fn compile_module(&self) -> Result<Module> {
+        ensure!(
+            self.variants.is_empty(),
+            "`CodeBuilder::variant` can only be used with serialized artifacts"
+        );
         ensure!(
             self.get_unsafe_intrinsics_import().is_none(),
             "`CodeBuilder::expose_unsafe_intrinsics` can only be used with components"
@@ -122,27 +150,97 @@ impl<'a> CodeBuilder<'a> {
 
         let custom_alignment = self.custom_alignment();
         let (code, info_and_types) = self.compile_cached(
-            |engine, wasm, dwarf, unsafe_intrinsics_import, state| {
+            |engine,
+             recompile,
+             wasm,
+             dwarf,
+             unsafe_intrinsics_import,
+             spectre_mitigations,
+             profile,
+             state| {
                 assert!(unsafe_intrinsics_import.is_none());
-                super::build_module_artifacts(engine, wasm, dwarf, state)
+                super::build_module_artifacts(
+                    engine,
+                    recompile,
+                    wasm,
+                    dwarf,
+                    spectre_mitigations,
+                    profile,
+                    &[],
+                    state,
+                )
             },
             &custom_alignment,
         )?;
         Module::from_parts(self.engine, code, info_and_types)
     }
 
+    /// Same as [`CodeBuilder::compile_module`] except that a
+    /// [`RecompilingModule`] is produced, which is additionally recompiled
+    /// with optimizations in the background.
+    ///
+    /// This requires [`Config::background_recompilation`](crate::Config::background_recompilation)
+    /// to be enabled.
+    #[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+    pub fn compile_recompiling_module(&self) -> Result<RecompilingModule> {
+        let engine = self.engine.clone();
+        engine.try_recompiler()?;
+        let baseline = self.compile_module()?;
+
+        let wasm = self.get_wasm()?.into_owned();
+        let dwarf_package = self.get_dwarf_package().map(|d| d.to_vec());
+        let spectre_mitigations = self.get_spectre_mitigations();
+        let profile = self.get_profile().cloned();
+        RecompilingModule::spawn(baseline, move || {
+            let mut builder = CodeBuilder::new(&engine);
+            builder.wasm_binary(&wasm, None)?;
+            if let Some(dwarf_package) = &dwarf_package {
+                builder.dwarf_package(dwarf_package)?;
+            }
+            // SAFETY: these are the same mitigations that the baseline module
+            // was compiled with.
+            unsafe {
+                builder.spectre_mitigations(spectre_mitigations);
+            }
+            if let Some(profile) = profile {
+                builder.profile(profile);
+            }
+            builder.recompile().compile_module()
+        })
+    }
+
     /// Same as [`CodeBuilder::compile_module`] except that it compiles a
     /// [`Component`] instead of a module.
     #[cfg(feature = "component-model")]
     pub fn compile_component(&self) -> Result<Component> {
+        ensure!(
+            self.variants.is_empty(),
+            "`CodeBuilder::variant` can only be used with serialized artifacts"
+        );
+        ensure!(
+            self.get_profile().is_none(),
+            "`CodeBuilder::profile` can only be used with modules"
+        );
+
         let custom_alignment = self.custom_alignment();
         let (code, artifacts) = self.compile_cached(
-            |engine, wasm, dwarf, unsafe_intrinsics_import, state| {
+            |engine,
+             recompile,
+             wasm,
+             dwarf,
+             unsafe_intrinsics_import,
+             spectre_mitigations,
+             profile,
+             state| {
+                assert!(profile.is_none());
                 super::build_component_artifacts(
                     engine,
+                    recompile,
                     wasm,
                     dwarf,
                     unsafe_intrinsics_import,
+                    spectre_mitigations,
+                    &[],
                     state,
                 )
             },
@@ -164,6 +262,7 @@ impl<'a> CodeBuilder<'a> {
 
 fn publish_mmap(engine: &Engine, mmap: MmapVec) -> Result<Arc<CodeMemory>> {
     let mut code = CodeMemory::new(engine, mmap)?;
+    code.set_origin(CodeOrigin::Compiled);
     code.publish()?;
     Ok(Arc::new(code))
 }
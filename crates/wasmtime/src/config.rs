@@ -5,12 +5,15 @@ use bitflags::Flags;
 use core::fmt;
 use core::num::{NonZeroU32, NonZeroUsize};
 use core::str::FromStr;
+use core::time::Duration;
 #[cfg(any(feature = "cranelift", feature = "winch"))]
 use std::path::Path;
 pub use wasmparser::WasmFeatures;
 #[cfg(any(feature = "cranelift", feature = "winch"))]
 use wasmtime_environ::FlagValue;
-use wasmtime_environ::{ConfigTunables, OperatorCost, OperatorCostStrategy, TripleExt, Tunables};
+use wasmtime_environ::{
+    ConfigTunables, FuelCostTable, OperatorCost, OperatorCostStrategy, TripleExt, Tunables,
+};
 
 #[cfg(feature = "runtime")]
 use crate::memory::MemoryCreator;
@@ -171,6 +174,8 @@ pub struct Config {
     pub(crate) mem_creator: Option<Arc<dyn RuntimeMemoryCreator>>,
     #[cfg(feature = "runtime")]
     pub(crate) custom_code_memory: Option<Arc<dyn CustomCodeMemory>>,
+    #[cfg(feature = "runtime")]
+    pub(crate) code_event_hook: Option<crate::runtime::CodeEventHook>,
     pub(crate) allocation_strategy: InstanceAllocationStrategy,
     pub(crate) max_wasm_stack: usize,
     /// Explicitly enabled features via `Config::wasm_*` methods. This is a
@@ -185,17 +190,28 @@ pub struct Config {
     pub(crate) wasm_backtrace_details_env_used: bool,
     pub(crate) wasm_backtrace_max_frames: Option<NonZeroUsize>,
     pub(crate) native_unwind_info: Option<bool>,
+    pub(crate) sign_return_address: Option<bool>,
     pub(crate) async_stack_size: usize,
     pub(crate) async_stack_zeroing: bool,
     #[cfg(feature = "async")]
     pub(crate) stack_creator: Option<Arc<dyn RuntimeFiberStackCreator>>,
     pub(crate) module_version: ModuleVersionStrategy,
     pub(crate) parallel_compilation: bool,
+    #[cfg(feature = "parallel-compilation")]
+    pub(crate) compilation_threads: Option<usize>,
+    #[cfg(feature = "parallel-compilation")]
+    pub(crate) compilation_thread_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    #[cfg(feature = "parallel-compilation")]
+    pub(crate) compilation_executor: Option<Arc<dyn crate::CompilationExecutor>>,
     pub(crate) memory_guaranteed_dense_image_size: u64,
     pub(crate) force_memory_init_memfd: bool,
     pub(crate) wmemcheck: bool,
     #[cfg(feature = "coredump")]
     pub(crate) coredump_on_trap: bool,
+    #[cfg(feature = "coredump")]
+    pub(crate) coredump_on_trap_path: Option<std::path::PathBuf>,
+    #[cfg(all(feature = "crash-dump", unix))]
+    pub(crate) crash_dump_fd: Option<Arc<std::os::fd::OwnedFd>>,
     pub(crate) macos_use_mach_ports: bool,
     pub(crate) detect_host_feature: Option<fn(&str) -> Option<bool>>,
     pub(crate) x86_float_abi_ok: Option<bool>,
@@ -214,6 +230,7 @@ struct CompilerConfig {
     cache_store: Option<Arc<dyn CacheStore>>,
     clif_dir: Option<std::path::PathBuf>,
     wmemcheck: bool,
+    background_recompilation: bool,
 }
 
 #[cfg(any(feature = "cranelift", feature = "winch"))]
@@ -234,6 +251,7 @@ impl CompilerConfig {
             cache_store: None,
             clif_dir: None,
             wmemcheck: false,
+            background_recompilation: false,
         }
     }
 
@@ -283,6 +301,8 @@ impl Config {
             mem_creator: None,
             #[cfg(feature = "runtime")]
             custom_code_memory: None,
+            #[cfg(feature = "runtime")]
+            code_event_hook: None,
             allocation_strategy: InstanceAllocationStrategy::OnDemand,
             // 512k of stack -- note that this is chosen currently to not be too
             // big, not be too small, and be a good default for most platforms.
@@ -296,6 +316,7 @@ impl Config {
             wasm_backtrace_details_env_used: false,
             wasm_backtrace_max_frames: Some(DEFAULT_WASM_BACKTRACE_MAX_FRAMES),
             native_unwind_info: None,
+            sign_return_address: None,
             enabled_features: WasmFeatures::empty(),
             disabled_features: WasmFeatures::empty(),
             async_stack_size: 2 << 20,
@@ -304,11 +325,21 @@ impl Config {
             stack_creator: None,
             module_version: ModuleVersionStrategy::default(),
             parallel_compilation: !cfg!(miri),
+            #[cfg(feature = "parallel-compilation")]
+            compilation_threads: None,
+            #[cfg(feature = "parallel-compilation")]
+            compilation_thread_start: None,
+            #[cfg(feature = "parallel-compilation")]
+            compilation_executor: None,
             memory_guaranteed_dense_image_size: 16 << 20,
             force_memory_init_memfd: false,
             wmemcheck: false,
             #[cfg(feature = "coredump")]
             coredump_on_trap: false,
+            #[cfg(feature = "coredump")]
+            coredump_on_trap_path: None,
+            #[cfg(all(feature = "crash-dump", unix))]
+            crash_dump_fd: None,
             macos_use_mach_ports: !cfg!(miri),
             #[cfg(feature = "std")]
             detect_host_feature: Some(detect_host_feature),
@@ -439,7 +470,6 @@ impl Config {
     /// guest WebAssembly programs.
     ///
     /// By default this option is `false`.
-    /// **Note** Enabling this option is not compatible with the Winch compiler.
     pub fn debug_info(&mut self, enable: bool) -> &mut Self {
         self.tunables.debug_native = Some(enable);
         self
@@ -618,12 +648,53 @@ impl Config {
     /// information which can greatly slow down the module loading/unloading
     /// process.
     ///
+    /// Unwind information can also make up a large fraction of the size of
+    /// compiled artifacts, particularly on targets such as aarch64 and riscv64,
+    /// and disabling it removes it entirely. Wasmtime itself only ever walks
+    /// Wasm frames using frame pointers, which are always preserved, so traps,
+    /// [`WasmBacktrace`], garbage collection, and host panics propagating
+    /// through Wasm are unaffected. Tools unwinding through Wasm frames on
+    /// their own, however, need to fall back to frame pointers as well: for
+    /// example `perf record --call-graph=fp` still works while
+    /// `--call-graph=dwarf` stops at the first Wasm frame, and native
+    /// debuggers may fail to print backtraces through Wasm frames.
+    ///
     /// [`WasmBacktrace`]: crate::WasmBacktrace
     pub fn native_unwind_info(&mut self, enable: bool) -> &mut Self {
         self.native_unwind_info = Some(enable);
         self
     }
 
+    /// Configures whether compiled code signs return addresses using pointer
+    /// authentication (PAC) on aarch64.
+    ///
+    /// When enabled the prologue of every compiled function which saves its
+    /// return address on the stack signs it, with `paciasp` or `pacibsp`, and
+    /// the epilogue authenticates it again, with `autiasp` or `autibsp`,
+    /// before returning. This covers both WebAssembly functions and the
+    /// trampolines Wasmtime compiles to enter and exit WebAssembly. That
+    /// ensures frames of WebAssembly code don't weaken the protection against
+    /// return-oriented programming of a host which is itself compiled with
+    /// return address signing.
+    ///
+    /// The instructions used are in the hint space of the aarch64 instruction
+    /// set, so they execute as no-ops on CPUs without pointer authentication
+    /// and compiled code remains compatible with them. On Apple targets the B
+    /// key is used, as their ABI requires, and the A key is used otherwise.
+    ///
+    /// This option has no effect when targeting architectures other than
+    /// aarch64. It is only supported by Cranelift, and enabling it with
+    /// [`Strategy::Winch`] will cause [`Engine::new`] to fail.
+    ///
+    /// By default this is enabled when compiling for the host on macOS, where
+    /// pointer authentication is always available, and disabled otherwise.
+    ///
+    /// [`Engine::new`]: crate::Engine::new
+    pub fn sign_return_address(&mut self, enable: bool) -> &mut Self {
+        self.sign_return_address = Some(enable);
+        self
+    }
+
     /// Configures whether execution of WebAssembly will "consume fuel" to
     /// either halt or yield execution as desired.
     ///
@@ -647,6 +718,31 @@ impl Config {
         self
     }
 
+    /// Configures whether compiled WebAssembly records an execution profile
+    /// for profile-guided optimization.
+    ///
+    /// When enabled, generated code counts how often each function is called
+    /// and which way each `if` and `br_if` goes. The counts are accumulated
+    /// across all instances of a module and can be retrieved with
+    /// [`Module::profile`]. Passing that profile to
+    /// [`CodeBuilder::profile`] when compiling the same module again, for
+    /// example with an engine using optimizations and no instrumentation,
+    /// lets Cranelift tailor inlining decisions and block layout to the
+    /// recorded behavior.
+    ///
+    /// Instrumentation is supported by all compilers, including Winch and
+    /// Cranelift targeting Pulley, so profiles can be recorded cheaply by a
+    /// baseline tier. It does slow down execution noticeably though.
+    ///
+    /// By default this option is `false`.
+    ///
+    /// [`Module::profile`]: crate::Module::profile
+    /// [`CodeBuilder::profile`]: crate::CodeBuilder::profile
+    pub fn profile_instrumentation(&mut self, enable: bool) -> &mut Self {
+        self.tunables.profile_instrumentation = Some(enable);
+        self
+    }
+
     /// Configures the fuel cost of each WebAssembly operator.
     ///
     /// This is only relevant when [`Config::consume_fuel`] is enabled.
@@ -655,6 +751,18 @@ impl Config {
         self
     }
 
+    /// Configures the fuel cost of broad classes of WebAssembly operators,
+    /// such as calls, memory accesses and SIMD operators.
+    ///
+    /// This is a coarser alternative to [`Config::operator_cost`] and the two
+    /// override each other, with whichever is called last taking effect. See
+    /// [`FuelCostTable`] for how operators are assigned to classes.
+    ///
+    /// This is only relevant when [`Config::consume_fuel`] is enabled.
+    pub fn fuel_costs(&mut self, costs: FuelCostTable) -> &mut Self {
+        self.operator_cost(OperatorCost::from(&costs))
+    }
+
     /// Enables epoch-based interruption.
     ///
     /// When executing code in async mode, we sometimes want to
@@ -767,6 +875,27 @@ impl Config {
         self
     }
 
+    /// Configures additional epoch checks within long stretches of
+    /// straight-line code when [`Config::epoch_interruption`] is enabled.
+    ///
+    /// By default epoch checks are only performed on function entry and at
+    /// loop headers. This bounds the time between checks by the length of a
+    /// function body, but very large functions without loops, such as those
+    /// produced by aggressive unrolling, may then run well past their
+    /// deadline. When this is set to `Some(n)` an additional check is inserted
+    /// whenever `n` operators have been compiled in a row without one.
+    ///
+    /// Smaller values tighten the bound on how long guest code may run past
+    /// an epoch deadline at the cost of larger and slower code. Values in the
+    /// thousands keep the overhead negligible for typical code while still
+    /// bounding the latency of huge straight-line functions.
+    ///
+    /// This option is only supported by Cranelift and defaults to `None`.
+    pub fn epoch_interruption_check_interval(&mut self, interval: Option<NonZeroU32>) -> &mut Self {
+        self.tunables.epoch_interruption_check_interval = Some(interval);
+        self
+    }
+
     /// XXX: For internal fuzzing and debugging use only!
     #[doc(hidden)]
     pub fn gc_zeal_alloc_counter(&mut self, counter: Option<NonZeroU32>) -> Result<&mut Self> {
@@ -825,6 +954,12 @@ impl Config {
     /// to `async_stack_size` as doing so may limit how much stack space
     /// is available for host functions.
     ///
+    /// When executing with the Pulley interpreter the interpreter's own stack
+    /// is limited to this size instead. On platforms with virtual memory it's
+    /// reserved up front and only made accessible as guest code uses it, so
+    /// this can be set quite large without consuming memory until deeply
+    /// recursive code actually needs it.
+    ///
     /// By default this option is 512 KiB.
     ///
     /// # Errors
@@ -1413,6 +1548,42 @@ impl Config {
         self
     }
 
+    /// Configures whether background recompilation is enabled.
+    ///
+    /// Winch compiles WebAssembly quickly but produces slower code than
+    /// Cranelift. When background recompilation is enabled an [`Engine`]
+    /// configured with [`Strategy::Winch`] additionally builds a Cranelift
+    /// compiler which is used to recompile modules with optimizations in the
+    /// background. This is used by
+    /// [`RecompilingModule`](crate::RecompilingModule) which makes a
+    /// Winch-compiled module available to instantiate immediately and switches
+    /// to a Cranelift-compiled version of the same module once it's ready.
+    ///
+    /// Instances which are already running Winch-compiled code can be
+    /// switched over to the optimized code with
+    /// [`RecompilingModule::upgrade`](crate::RecompilingModule::upgrade).
+    /// Both compilers use the same calling convention so instances of either
+    /// can be freely linked together within a store.
+    ///
+    /// The default value for this is `false`.
+    ///
+    /// # Errors
+    ///
+    /// Creating an [`Engine`] with this enabled will fail if the compilation
+    /// strategy isn't [`Strategy::Winch`]. Pulley bytecode can't call native
+    /// code directly, so it can't be upgraded in place like Winch code and
+    /// there's no interpreted baseline. This includes platforms where Winch
+    /// isn't supported and Pulley is used instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this configuration's compiler was [disabled][Config::enable_compiler].
+    #[cfg(all(feature = "cranelift", feature = "winch"))]
+    pub fn background_recompilation(&mut self, enable: bool) -> &mut Self {
+        self.compiler_config_mut().background_recompilation = enable;
+        self
+    }
+
     /// Configures which garbage collector will be used for Wasm modules.
     ///
     /// This method can be used to configure which garbage collector
@@ -1717,6 +1888,31 @@ impl Config {
         self
     }
 
+    /// Configures a hook which is invoked as code is loaded and unloaded by
+    /// the [`Engine`].
+    ///
+    /// The hook receives a [`CodeEvent::Mapped`](crate::CodeEvent::Mapped)
+    /// event after the code of a [`Module`](crate::Module) or
+    /// [`Component`](crate::component::Component) has been made executable
+    /// and a [`CodeEvent::Unmapped`](crate::CodeEvent::Unmapped) event just
+    /// before its memory is released. This can be used to feed telemetry about
+    /// JIT code, or to integrate with external profilers and debuggers, in
+    /// addition to the built-in support configured with
+    /// [`Config::profiler`]. All code which is loaded at any point is
+    /// also available through [`Engine::code_regions`].
+    ///
+    /// The hook is invoked on whichever thread loads or unloads code, and
+    /// must not load or unload code itself, for example by dropping a
+    /// [`Module`](crate::Module).
+    #[cfg(feature = "runtime")]
+    pub fn code_event_hook(
+        &mut self,
+        hook: impl Fn(crate::CodeEvent<'_>) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.code_event_hook = Some(Arc::new(hook));
+        self
+    }
+
     /// Sets the instance allocation strategy to use.
     ///
     /// This is notably used in conjunction with
@@ -2154,8 +2350,6 @@ impl Config {
     /// are initialized eagerly during instantiation from any active element
     /// segments that apply to them.
     ///
-    /// **Note** Disabling this option is not compatible with the Winch compiler.
-    ///
     /// ## Default
     ///
     /// This value defaults to `true`.
@@ -2196,6 +2390,59 @@ impl Config {
         self
     }
 
+    /// Configures the number of threads used for parallel compilation.
+    ///
+    /// By default Wasmtime compiles on rayon's global thread pool, which is
+    /// shared with the rest of the process and sized to the number of CPUs.
+    /// Configuring a number of threads instead gives each [`Engine`] created
+    /// from this configuration its own pool with that many threads, which
+    /// bounds how much of the host compilation can occupy at once.
+    ///
+    /// This has no effect if [`Config::parallel_compilation`] is disabled, and
+    /// can't be combined with [`Config::with_compilation_executor`]. Creating
+    /// an [`Engine`] fails if `threads` is zero.
+    #[cfg(feature = "parallel-compilation")]
+    pub fn compilation_threads(&mut self, threads: usize) -> &mut Self {
+        self.compilation_threads = Some(threads);
+        self
+    }
+
+    /// Configures a function to run on each of an [`Engine`]'s compilation
+    /// threads when it starts, before it compiles anything.
+    ///
+    /// The function is passed the index of the thread within the engine's
+    /// compilation thread pool. This is intended for configuring the thread
+    /// itself, for example lowering its priority or pinning it to a set of
+    /// cores, such that compilation stays out of the way of
+    /// latency-sensitive work on the same host.
+    ///
+    /// Configuring this gives each [`Engine`] its own compilation thread pool
+    /// as described in [`Config::compilation_threads`], which it also can't be
+    /// combined with [`Config::with_compilation_executor`].
+    #[cfg(feature = "parallel-compilation")]
+    pub fn compilation_thread_start(
+        &mut self,
+        start: impl Fn(usize) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.compilation_thread_start = Some(Arc::new(start));
+        self
+    }
+
+    /// Configures an executor to run parallel compilation on instead of a
+    /// rayon thread pool.
+    ///
+    /// See [`CompilationExecutor`](crate::CompilationExecutor) for more
+    /// information. This has no effect if [`Config::parallel_compilation`] is
+    /// disabled.
+    #[cfg(feature = "parallel-compilation")]
+    pub fn with_compilation_executor(
+        &mut self,
+        executor: Arc<dyn crate::CompilationExecutor>,
+    ) -> &mut Self {
+        self.compilation_executor = Some(executor);
+        self
+    }
+
     /// Configures whether compiled artifacts will contain information to map
     /// native program addresses back to the original wasm module.
     ///
@@ -2292,6 +2539,11 @@ impl Config {
     /// Configures whether or not a coredump should be generated and attached to
     /// the [`Error`](crate::Error) when a trap is raised.
     ///
+    /// The generated [`WasmCoreDump`](crate::WasmCoreDump) contains the stack
+    /// frames of the trap along with a snapshot of linear memories and
+    /// globals. When [`Config::guest_debug`] is also enabled then the values
+    /// of locals and the operand stack of each frame are recorded as well.
+    ///
     /// This option is disabled by default.
     #[cfg(feature = "coredump")]
     pub fn coredump_on_trap(&mut self, enable: bool) -> &mut Self {
@@ -2299,6 +2551,68 @@ impl Config {
         self
     }
 
+    /// Configures a file that coredumps generated on trap are written to.
+    ///
+    /// This implies [`Config::coredump_on_trap`] and additionally serializes
+    /// the coredump of the first trap of an [`Engine`](crate::Engine) in [the
+    /// standard core dump binary format][spec] to `path`, overwriting any
+    /// previous contents, before the trap is returned to the embedder. Later
+    /// traps still carry a [`WasmCoreDump`](crate::WasmCoreDump) but aren't
+    /// written to `path`, so a guest which traps repeatedly doesn't keep
+    /// rewriting the file and the first failure is preserved. The resulting
+    /// file can be inspected with the `wasmtime coredump explore` subcommand
+    /// or other tools which understand Wasm core dumps.
+    ///
+    /// Failure to write the coredump doesn't affect the trap itself and is
+    /// only logged.
+    ///
+    /// This option is not set by default.
+    ///
+    /// [spec]: https://github.com/WebAssembly/tool-conventions/blob/main/Coredump.md
+    #[cfg(feature = "coredump")]
+    pub fn coredump_on_trap_path(&mut self, path: impl Into<std::path::PathBuf>) -> &mut Self {
+        self.coredump_on_trap = true;
+        self.coredump_on_trap_path = Some(path.into());
+        self
+    }
+
+    /// Configures a file descriptor that a crash dump is written to when the
+    /// host process receives a fatal signal which isn't a WebAssembly trap.
+    ///
+    /// Wasmtime's signal handlers receive all `SIGSEGV`, `SIGILL`, `SIGFPE`
+    /// and `SIGBUS` signals in the process to detect traps in WebAssembly.
+    /// When one of these signals isn't a WebAssembly trap it's forwarded to
+    /// the previously installed signal handler, which typically terminates the
+    /// process. With this option Wasmtime will first write a textual summary
+    /// of its state to `fd` for every such signal, whether it was raised by
+    /// host code or by compiled WebAssembly code, including:
+    ///
+    /// * the signal, program counter and faulting address,
+    /// * the number of live [`Store`](crate::Store)s in the process,
+    /// * the WebAssembly activations on the crashing thread, with the program
+    ///   counter at which each last exited WebAssembly,
+    /// * the program counters of executing Pulley interpreters, when the
+    ///   `profile-pulley` Cargo feature is enabled,
+    /// * the occupancy of pooling allocators.
+    ///
+    /// The dump is written with async-signal-safe code which doesn't allocate
+    /// or take locks, so `fd` should be opened ahead of time, for example to a
+    /// file or a pipe read by a supervisor. At most one dump is written per
+    /// process. Signal handlers are process-wide so the `fd` of the most
+    /// recently created [`Engine`](crate::Engine) with this option is used,
+    /// and it's kept open for the rest of the process's lifetime.
+    ///
+    /// This requires [`Config::signals_based_traps`] and, on macOS,
+    /// [`Config::macos_use_mach_ports`] to be disabled since Mach ports don't
+    /// deliver signals to Wasmtime's handlers.
+    ///
+    /// This option is not set by default.
+    #[cfg(all(feature = "crash-dump", unix))]
+    pub fn crash_dump_fd(&mut self, fd: std::os::fd::OwnedFd) -> &mut Self {
+        self.crash_dump_fd = Some(Arc::new(fd));
+        self
+    }
+
     /// Enables memory error checking for wasm programs.
     ///
     /// This option is disabled by default.
@@ -2371,6 +2685,56 @@ impl Config {
         self
     }
 
+    /// Configures the size, as measured by the compiler, at or below which
+    /// fused adapters between components are inlined into their callers.
+    ///
+    /// When components are composed together, calls from one component into
+    /// another go through an adapter function generated by Wasmtime. These
+    /// adapters often do little more than shuffle arguments around, yet they
+    /// cost a full function call. Adapters no larger than `size` are inlined
+    /// into their callers regardless of the [`Config::compiler_inlining`]
+    /// setting, which reduces the overhead of cross-component calls at the
+    /// cost of some additional compile time.
+    ///
+    /// Like [`Config::compiler_inlining`] this is only supported by
+    /// Cranelift and is ignored by other compilation strategies.
+    ///
+    /// The default value for this is 0, which disables inlining of adapters
+    /// unless [`Config::compiler_inlining`] enables inter-module inlining.
+    pub fn compiler_inlining_adapter_size(&mut self, size: u32) -> &mut Self {
+        self.tunables.inlining_adapter_size = Some(size);
+        self
+    }
+
+    /// Configures how large `br_table` instructions are compiled.
+    ///
+    /// By default each `br_table` is compiled to a single dense jump table
+    /// with one entry per target. Very large tables, such as the dispatch
+    /// tables of interpreters compiled to WebAssembly, often consist of long
+    /// runs of entries that all branch to the same place. With a nonzero
+    /// `threshold`, runs of at least `threshold` entries with the same target
+    /// are instead split out into range checks and the remaining entries are
+    /// emitted as smaller jump tables, selected between with a binary search.
+    /// Those jump tables are further split into two levels, a first-level
+    /// table over chunks of 32 entries and second-level tables for the chunks
+    /// that don't all branch to the same place, when this at least halves the
+    /// number of table entries. Identical chunks share a second-level table.
+    ///
+    /// This reduces the size of the generated code and the time taken to
+    /// compile it, but each dispatch through a split table executes more
+    /// branches. For a hot dispatch loop whose jump table fits in the
+    /// instruction cache this can make dispatch noticeably slower, so the
+    /// setting is best suited to tables which are large or rarely executed.
+    ///
+    /// This is only supported by Cranelift and is ignored by other
+    /// compilation strategies.
+    ///
+    /// The default value for this is 0, which always emits dense jump tables.
+    pub fn compiler_br_table_range_split(&mut self, threshold: u32) -> &mut Self {
+        self.tunables.br_table_range_split = Some(threshold);
+        self
+    }
+
     /// Returns the set of features that the currently selected compiler backend
     /// does not support at all and may panic on.
     ///
@@ -2416,14 +2780,10 @@ impl Config {
         let mut unsupported = !features_known_to_wasmtime;
 
         #[cfg(any(feature = "cranelift", feature = "winch"))]
-        match self.compiler_config.as_ref().and_then(|c| c.strategy) {
+        match self.compiler_strategy() {
             None | Some(Strategy::Cranelift) => {
-                // Pulley at this time fundamentally doesn't support the
-                // `threads` proposal, notably shared memory, because Rust can't
-                // safely implement loads/stores in the face of shared memory.
-                // Stack switching is not implemented, either.
+                // Stack switching is not implemented for Pulley.
                 if self.compiler_target().is_pulley() {
-                    unsupported |= WasmFeatures::THREADS;
                     unsupported |= WasmFeatures::STACK_SWITCHING;
                 }
 
@@ -2450,15 +2810,13 @@ impl Config {
             Some(Strategy::Winch) => {
                 unsupported |= WasmFeatures::GC
                     | WasmFeatures::FUNCTION_REFERENCES
-                    | WasmFeatures::RELAXED_SIMD
-                    | WasmFeatures::TAIL_CALL
                     | WasmFeatures::GC_TYPES
                     | WasmFeatures::EXCEPTIONS
                     | WasmFeatures::LEGACY_EXCEPTIONS
                     | WasmFeatures::STACK_SWITCHING;
                 match self.compiler_target().architecture {
                     target_lexicon::Architecture::Aarch64(_) => {
-                        unsupported |= WasmFeatures::THREADS;
+                        unsupported |= WasmFeatures::THREADS | WasmFeatures::RELAXED_SIMD;
                     }
 
                     // Winch doesn't support other non-x64 architectures at this
@@ -2559,7 +2917,51 @@ impl Config {
     }
 
     /// Returns the configured compiler target for this `Config`.
+    ///
+    /// This is the Pulley target corresponding to the requested target if
+    /// Winch is configured but doesn't support that target, see
+    /// [`Strategy::Winch`].
     pub(crate) fn compiler_target(&self) -> target_lexicon::Triple {
+        let target = self.requested_compiler_target();
+        if self.winch_falls_back_to_pulley(&target) {
+            return pulley_for(&target);
+        }
+        target
+    }
+
+    /// Returns the compilation strategy to use, taking into account the
+    /// fallback from Winch to Pulley.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn compiler_strategy(&self) -> Option<Strategy> {
+        if self.winch_falls_back_to_pulley(&self.requested_compiler_target()) {
+            return Some(Strategy::Cranelift);
+        }
+        self.compiler_config.as_ref().and_then(|c| c.strategy)
+    }
+
+    /// Returns whether Winch was requested for `target` but doesn't support
+    /// it, in which case Cranelift's Pulley backend is used instead so the
+    /// same configuration works across all architectures.
+    fn winch_falls_back_to_pulley(&self, target: &target_lexicon::Triple) -> bool {
+        #[cfg(all(feature = "winch", feature = "cranelift", feature = "pulley"))]
+        if self
+            .compiler_config
+            .as_ref()
+            .is_some_and(|c| c.strategy == Some(Strategy::Winch))
+        {
+            return !target.is_pulley()
+                && !matches!(
+                    target.architecture,
+                    target_lexicon::Architecture::X86_64 | target_lexicon::Architecture::Aarch64(_)
+                );
+        }
+        let _ = target;
+        false
+    }
+
+    /// Returns the target configured for this `Config`, before any compiler
+    /// fallback is applied.
+    fn requested_compiler_target(&self) -> target_lexicon::Triple {
         // If a target is explicitly configured, always use that.
         if let Some(target) = self.target.clone() {
             return target;
@@ -2606,6 +3008,21 @@ impl Config {
             panic!("should have returned an error by now")
         }
 
+        #[cfg(feature = "parallel-compilation")]
+        {
+            if self.compilation_threads == Some(0) {
+                bail!("the number of compilation threads must be non-zero");
+            }
+            if self.compilation_executor.is_some()
+                && (self.compilation_threads.is_some() || self.compilation_thread_start.is_some())
+            {
+                bail!(
+                    "a compilation executor cannot be combined with configuring \
+                     compilation threads"
+                );
+            }
+        }
+
         if self.max_wasm_stack > self.async_stack_size {
             bail!("max_wasm_stack size cannot exceed the async_stack_size");
         }
@@ -2683,12 +3100,13 @@ impl Config {
         // Inlining currently falls over with the `stack_switch` instruction.
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         if features.contains(WasmFeatures::STACK_SWITCHING) {
-            if let Some(inlining) = self.tunables.inlining
-                && inlining != Inlining::No
+            if self.tunables.inlining.is_some_and(|i| i != Inlining::No)
+                || self.tunables.inlining_adapter_size.is_some_and(|s| s > 0)
             {
                 bail!("cannot enable compiler inlining when stack switching is enabled");
             }
             tunables.inlining = Inlining::No;
+            tunables.inlining_adapter_size = 0;
         }
 
         self.tunables.configure(&mut tunables);
@@ -2706,10 +3124,7 @@ impl Config {
         // If we're going to compile with winch, we must use the winch calling convention.
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         {
-            tunables.winch_callable = self
-                .compiler_config
-                .as_ref()
-                .is_some_and(|c| c.strategy == Some(Strategy::Winch));
+            tunables.winch_callable = self.compiler_strategy() == Some(Strategy::Winch);
         }
 
         tunables.collector = if features.gc_types() {
@@ -2729,6 +3144,14 @@ impl Config {
             None
         };
 
+        #[cfg(all(feature = "crash-dump", unix))]
+        if self.crash_dump_fd.is_some() {
+            ensure!(
+                tunables.signals_based_traps,
+                "crash dumps require signals-based traps to be enabled"
+            );
+        }
+
         if tunables.debug_guest {
             ensure!(
                 cfg!(feature = "debug"),
@@ -2905,8 +3328,16 @@ impl Config {
         mut self,
         tunables: &mut Tunables,
         features: WasmFeatures,
-    ) -> Result<(Self, Box<dyn wasmtime_environ::Compiler>)> {
+    ) -> Result<(
+        Self,
+        Box<dyn wasmtime_environ::Compiler>,
+        Option<Box<dyn wasmtime_environ::Compiler>>,
+    )> {
         let target = self.compiler_target();
+        let background_recompilation = self.compiler_config_mut().background_recompilation;
+        if background_recompilation && self.compiler_strategy() != Some(Strategy::Winch) {
+            bail!("background recompilation requires the Winch compilation strategy");
+        }
 
         // The target passed to the builders below is an `Option<Triple>` where
         // `None` represents the current host with CPU features inferred from
@@ -2921,13 +3352,13 @@ impl Config {
                 Some(target.clone())
             };
 
-        let mut compiler = match self.compiler_config_mut().strategy {
+        let mut compiler = match self.compiler_strategy() {
             #[cfg(feature = "cranelift")]
-            Some(Strategy::Cranelift) => wasmtime_cranelift::builder(target_for_builder)?,
+            Some(Strategy::Cranelift) => wasmtime_cranelift::builder(target_for_builder.clone())?,
             #[cfg(not(feature = "cranelift"))]
             Some(Strategy::Cranelift) => bail!("cranelift support not compiled in"),
             #[cfg(feature = "winch")]
-            Some(Strategy::Winch) => wasmtime_winch::builder(target_for_builder)?,
+            Some(Strategy::Winch) => wasmtime_winch::builder(target_for_builder.clone())?,
             #[cfg(not(feature = "winch"))]
             Some(Strategy::Winch) => bail!("winch support not compiled in"),
 
@@ -2989,6 +3420,29 @@ impl Config {
             ("true".into(), UserSpecified::No),
         );
 
+        if let Some(enable) = self.sign_return_address {
+            if let target_lexicon::Architecture::Aarch64(_) = target.architecture {
+                if enable && self.compiler_strategy() == Some(Strategy::Winch) {
+                    bail!("return address signing is not supported by Winch");
+                }
+                if !self
+                    .compiler_config_mut()
+                    .ensure_setting_unset_or_given("sign_return_address", &enable.to_string())
+                {
+                    bail!(
+                        "incompatible settings requested for Cranelift and Wasmtime \
+                         `sign-return-address` settings"
+                    );
+                }
+                if enable && target.vendor == target_lexicon::Vendor::Apple {
+                    self.compiler_config_mut().settings.insert(
+                        "sign_return_address_with_bkey".into(),
+                        ("true".into(), UserSpecified::No),
+                    );
+                }
+            }
+        }
+
         if !tunables.signals_based_traps {
             let mut ok = self
                 .compiler_config_mut()
@@ -3052,7 +3506,28 @@ impl Config {
 
         compiler.wmemcheck(self.compiler_config_mut().wmemcheck);
 
-        Ok((self, compiler.build()?))
+        // With background recompilation a Cranelift compiler is built
+        // alongside Winch with the same tunables and settings so code produced
+        // by either can call the other.
+        #[cfg(all(feature = "cranelift", feature = "winch"))]
+        let recompiler = if background_recompilation {
+            let mut recompiler = wasmtime_cranelift::builder(target_for_builder)?;
+            recompiler.set_tunables(tunables.clone())?;
+            for (k, (v, _)) in self.compiler_config_mut().settings.iter() {
+                recompiler.set(k, v)?;
+            }
+            for (flag, _) in self.compiler_config_mut().flags.iter() {
+                recompiler.enable(flag)?;
+            }
+            recompiler.wmemcheck(self.compiler_config_mut().wmemcheck);
+            Some(recompiler.build()?)
+        } else {
+            None
+        };
+        #[cfg(not(all(feature = "cranelift", feature = "winch")))]
+        let recompiler = None;
+
+        Ok((self, compiler.build()?, recompiler))
     }
 
     /// Internal setting for whether adapter modules for components will have
@@ -3440,6 +3915,8 @@ impl fmt::Debug for Config {
         }
 
         f.field("parallel_compilation", &self.parallel_compilation);
+        #[cfg(feature = "parallel-compilation")]
+        f.field("compilation_threads", &self.compilation_threads);
         #[cfg(any(feature = "cranelift", feature = "winch"))]
         {
             f.field("compiler_config", &self.compiler_config);
@@ -3474,6 +3951,13 @@ pub enum Strategy {
     /// A low-latency baseline compiler for WebAssembly.
     /// For more details regarding ISA support and Wasm proposals support
     /// see <https://docs.wasmtime.dev/stability-tiers.html#current-tier-status>
+    ///
+    /// Winch currently supports x86\_64 and aarch64. On other architectures,
+    /// such as s390x or riscv64, Wasm is instead compiled to Pulley bytecode
+    /// and run in the Pulley interpreter if the `pulley` Cargo feature is
+    /// enabled, so the same configuration can be used everywhere. The
+    /// fallback is reflected by [`Engine::is_pulley`] and
+    /// [`Engine::get_strategy`].
     Winch,
 }
 
@@ -3744,6 +4228,45 @@ pub enum Enabled {
     No,
 }
 
+/// How the pooling allocator returns the memory of deallocated linear
+/// memories, tables and stacks to a pristine state, configured with
+/// [`PoolingAllocationConfig::decommit_policy`].
+///
+/// Memory within the `keep_resident` limits, for example
+/// [`PoolingAllocationConfig::linear_memory_keep_resident`], is always reset
+/// with `memset` and kept resident. This policy applies to the rest of it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum DecommitPolicy {
+    /// Release memory back to the OS immediately, with `madvise(MADV_DONTNEED)`
+    /// on Linux or by remapping it elsewhere.
+    ///
+    /// This minimizes resident memory, but the next instantiation to reuse the
+    /// slot takes a page fault for each page it touches.
+    #[default]
+    Release,
+    /// Zero memory with `memset` and then mark it as lazily freeable with
+    /// `madvise(MADV_FREE)`.
+    ///
+    /// The kernel may reclaim these pages when under memory pressure, but
+    /// otherwise they stay resident and can be reused without page faults.
+    /// Linear memories which are initialized from a copy-on-write image are
+    /// still released with `MADV_DONTNEED` to restore the image.
+    ///
+    /// This is only supported on Linux, and behaves like
+    /// [`DecommitPolicy::Release`] elsewhere.
+    LazyFree,
+    /// Zero memory with `memset` and never return it to the OS.
+    ///
+    /// This is equivalent to setting all `keep_resident` options to
+    /// `usize::MAX`: memory that has been touched remains resident for as long
+    /// as the allocator exists, but reusing slots never takes page faults.
+    ///
+    /// This only applies to async stacks when
+    /// [`Config::async_stack_zeroing`] is enabled, and only to linear memories
+    /// on Linux.
+    Zero,
+}
+
 /// Configuration options used with [`InstanceAllocationStrategy::Pooling`] to
 /// change the behavior of the pooling instance allocator.
 ///
@@ -3844,6 +4367,15 @@ pub struct PoolingAllocationConfig {
     /// immediately flush them, and so we may go over this target size
     /// occasionally.
     pub(crate) decommit_batch_size: usize,
+    /// The target number of bytes to decommit per batch, see
+    /// `decommit_batch_size`.
+    pub(crate) decommit_batch_bytes: usize,
+    /// The maximum amount of time a decommit may be queued for.
+    pub(crate) decommit_max_delay: Option<Duration>,
+    /// How memory is returned to the OS when it's decommitted.
+    pub(crate) decommit_policy: DecommitPolicy,
+    /// Whether deallocated slots are reset on a background thread.
+    pub(crate) background_decommit: bool,
     /// The size, in bytes, of async stacks to allocate (not including the guard
     /// page).
     #[cfg_attr(
@@ -3888,6 +4420,10 @@ impl Default for PoolingAllocationConfig {
         Self {
             max_unused_warm_slots: 100,
             decommit_batch_size: 1,
+            decommit_batch_bytes: usize::MAX,
+            decommit_max_delay: None,
+            decommit_policy: DecommitPolicy::Release,
+            background_decommit: false,
             stack_size: 2 << 20,
             limits: InstanceLimits::default(),
             async_stack_zeroing: false,
@@ -4079,6 +4615,74 @@ impl PoolingAllocationConfig {
         self
     }
 
+    /// The target number of bytes to decommit per batch.
+    ///
+    /// Batches of decommits are flushed once they either contain
+    /// [`decommit_batch_size`](PoolingAllocationConfig::decommit_batch_size)
+    /// regions of memory or add up to this many bytes, whichever comes first.
+    /// This bounds how much memory is kept resident by memories, tables, and
+    /// stacks waiting to be decommitted, which can be monitored with
+    /// [`PoolingAllocatorMetrics::queued_decommit_bytes`](crate::PoolingAllocatorMetrics::queued_decommit_bytes).
+    ///
+    /// Like `decommit_batch_size` this is not precise and applies to each
+    /// shard of the queue independently.
+    ///
+    /// Defaults to `usize::MAX`, meaning batches are only bounded by their
+    /// number of regions.
+    pub fn decommit_batch_bytes(&mut self, bytes: usize) -> &mut Self {
+        self.decommit_batch_bytes = bytes;
+        self
+    }
+
+    /// The maximum amount of time a region of memory may be waiting to be
+    /// decommitted.
+    ///
+    /// With a [`decommit_batch_size`](PoolingAllocationConfig::decommit_batch_size)
+    /// larger than one, decommits are deferred until enough of them have been
+    /// queued. During periods of low churn this may take arbitrarily long,
+    /// keeping memory resident and slots unavailable for reuse. When this is
+    /// set, batches are also flushed once their oldest region has been queued
+    /// for this long: a background thread is spawned for each [`Engine`] to
+    /// enforce the deadline, which is therefore approximate, even when no
+    /// deallocations happen.
+    ///
+    /// Defaults to `None`, meaning decommits may be deferred indefinitely.
+    pub fn decommit_max_delay(&mut self, delay: Option<Duration>) -> &mut Self {
+        self.decommit_max_delay = delay;
+        self
+    }
+
+    /// How memory of deallocated linear memories, tables and stacks is
+    /// returned to the OS.
+    ///
+    /// This trades how much memory stays resident between instantiations
+    /// against how many page faults the next instantiation to reuse a slot
+    /// takes. See [`DecommitPolicy`] for the available policies.
+    ///
+    /// Defaults to [`DecommitPolicy::Release`].
+    pub fn decommit_policy(&mut self, policy: DecommitPolicy) -> &mut Self {
+        self.decommit_policy = policy;
+        self
+    }
+
+    /// Whether to reset deallocated linear memories, tables and stacks on a
+    /// background thread.
+    ///
+    /// By default the thread that deallocates an instance resets its memory
+    /// back to zero, or to its initial image, before returning. When this is
+    /// enabled that work, along with flushing the decommit queue, is instead
+    /// handed off to a background thread spawned for each [`Engine`], taking
+    /// it off the critical path of request handling. Slots become available
+    /// for reuse once the background thread has reset them; if the pool is
+    /// exhausted in the meantime then allocation resets pending slots itself
+    /// instead of failing.
+    ///
+    /// Defaults to `false`.
+    pub fn background_decommit(&mut self, enable: bool) -> &mut Self {
+        self.background_decommit = enable;
+        self
+    }
+
     /// How much memory, in bytes, to keep resident for async stacks allocated
     /// with the pooling allocator.
     ///
@@ -4530,6 +5134,30 @@ impl PoolingAllocationConfig {
         self.decommit_batch_size
     }
 
+    /// Returns the configured
+    /// [`PoolingAllocationConfig::decommit_batch_bytes`] value.
+    pub fn get_decommit_batch_bytes(&self) -> usize {
+        self.decommit_batch_bytes
+    }
+
+    /// Returns the configured
+    /// [`PoolingAllocationConfig::decommit_max_delay`] value.
+    pub fn get_decommit_max_delay(&self) -> Option<Duration> {
+        self.decommit_max_delay
+    }
+
+    /// Returns the configured
+    /// [`PoolingAllocationConfig::decommit_policy`] value.
+    pub fn get_decommit_policy(&self) -> DecommitPolicy {
+        self.decommit_policy
+    }
+
+    /// Returns whether
+    /// [`PoolingAllocationConfig::background_decommit`] is enabled.
+    pub fn get_background_decommit(&self) -> bool {
+        self.background_decommit
+    }
+
     /// Returns the configured
     /// [`PoolingAllocationConfig::max_unused_warm_slots`], if enabled.
     pub fn get_max_unused_warm_slots(&self) -> u32 {
@@ -4670,6 +5298,20 @@ impl PoolingAllocationConfig {
     }
 }
 
+/// Returns the Pulley target with the same pointer width and endianness as
+/// `target`.
+fn pulley_for(target: &target_lexicon::Triple) -> target_lexicon::Triple {
+    use target_lexicon::{Endianness, PointerWidth};
+    let big = target.endianness() == Ok(Endianness::Big);
+    let name = match (target.pointer_width(), big) {
+        (Ok(PointerWidth::U32), false) => "pulley32",
+        (Ok(PointerWidth::U32), true) => "pulley32be",
+        (_, false) => "pulley64",
+        (_, true) => "pulley64be",
+    };
+    name.parse().unwrap()
+}
+
 #[cfg(feature = "std")]
 fn detect_host_feature(feature: &str) -> Option<bool> {
     #[cfg(target_arch = "aarch64")]
@@ -4737,6 +5379,7 @@ fn detect_host_feature(feature: &str) -> Option<bool> {
             "bmi1" => Some(std::is_x86_feature_detected!("bmi1")),
             "bmi2" => Some(std::is_x86_feature_detected!("bmi2")),
             "avx512bitalg" => Some(std::is_x86_feature_detected!("avx512bitalg")),
+            "avx512bw" => Some(std::is_x86_feature_detected!("avx512bw")),
             "avx512dq" => Some(std::is_x86_feature_detected!("avx512dq")),
             "avx512f" => Some(std::is_x86_feature_detected!("avx512f")),
             "avx512vl" => Some(std::is_x86_feature_detected!("avx512vl")),
@@ -4892,7 +5535,7 @@ impl Engine {
     /// Returns the configured [`Config::strategy`] value.
     pub fn get_strategy(&self) -> Option<Strategy> {
         #[cfg(any(feature = "cranelift", feature = "winch"))]
-        return self.config().compiler_config.as_ref()?.strategy;
+        return self.config().compiler_strategy();
         #[cfg(not(any(feature = "cranelift", feature = "winch")))]
         return None;
     }
@@ -4937,11 +5580,34 @@ impl Engine {
         None
     }
 
+    /// Returns the configured [`Config::sign_return_address`] value.
+    ///
+    /// Returns `None` when not targeting aarch64.
+    pub fn get_sign_return_address(&self) -> Option<bool> {
+        #[cfg(any(feature = "cranelift", feature = "winch"))]
+        if let Some(compiler) = self.compiler() {
+            let flags = compiler.isa_flags();
+            let (_, FlagValue::Bool(b)) =
+                flags.iter().find(|(f, _)| *f == "sign_return_address")?
+            else {
+                return None;
+            };
+            return Some(*b);
+        }
+        None
+    }
+
     /// Returns the configured [`Config::parallel_compilation`] value.
     pub fn get_parallel_compilation(&self) -> bool {
         self.config().parallel_compilation
     }
 
+    /// Returns the configured [`Config::compilation_threads`] value, if any.
+    #[cfg(feature = "parallel-compilation")]
+    pub fn get_compilation_threads(&self) -> Option<usize> {
+        self.config().compilation_threads
+    }
+
     /// Returns the configured [`Config::metadata_for_internal_asserts`] value.
     pub fn get_metadata_for_internal_asserts(&self) -> bool {
         self.tunables().metadata_for_internal_asserts
@@ -4995,11 +5661,22 @@ impl Engine {
         self.tunables().epoch_interruption
     }
 
+    /// Returns the configured [`Config::epoch_interruption_check_interval`]
+    /// value.
+    pub fn get_epoch_interruption_check_interval(&self) -> Option<NonZeroU32> {
+        self.tunables().epoch_interruption_check_interval
+    }
+
     /// Returns the configured [`Config::consume_fuel`] value.
     pub fn get_consume_fuel(&self) -> bool {
         self.tunables().consume_fuel
     }
 
+    /// Returns the configured [`Config::profile_instrumentation`] value.
+    pub fn get_profile_instrumentation(&self) -> bool {
+        self.tunables().profile_instrumentation
+    }
+
     /// Returns the configured [`Config::max_wasm_stack`] value.
     pub fn get_max_wasm_stack(&self) -> usize {
         self.config().max_wasm_stack
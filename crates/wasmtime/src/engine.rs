@@ -17,8 +17,14 @@ use std::{fs::File, path::Path};
 use wasmparser::WasmFeatures;
 use wasmtime_environ::{FlagValue, ObjectKind, TripleExt, Tunables};
 
+#[cfg(feature = "parallel-compilation")]
+mod parallel;
 mod serialization;
 
+#[cfg(feature = "parallel-compilation")]
+pub use self::parallel::CompilationExecutor;
+pub use self::serialization::CompatibilityReport;
+
 /// An `Engine` which is a global context for compilation and management of wasm
 /// modules.
 ///
@@ -52,6 +58,9 @@ struct EngineInner {
     tunables: Tunables,
     #[cfg(any(feature = "cranelift", feature = "winch"))]
     compiler: Option<Box<dyn wasmtime_environ::Compiler>>,
+    /// The optimizing compiler used for background recompilation, if enabled.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    recompiler: Option<Box<dyn wasmtime_environ::Compiler>>,
     #[cfg(feature = "runtime")]
     allocator: Box<dyn crate::runtime::vm::InstanceAllocator + Send + Sync>,
     #[cfg(feature = "runtime")]
@@ -62,6 +71,21 @@ struct EngineInner {
     signatures: TypeRegistry,
     #[cfg(all(feature = "runtime", target_has_atomic = "64"))]
     epoch: AtomicU64,
+    #[cfg(feature = "runtime")]
+    counters: crate::runtime::EngineCounters,
+    #[cfg(feature = "runtime")]
+    code_registry: crate::runtime::CodeRegistry,
+    /// Values attached by libraries with [`Engine::get_or_try_init_extension`].
+    #[cfg(feature = "runtime")]
+    extensions: crate::sync::Mutex<
+        crate::hash_map::HashMap<core::any::TypeId, Arc<dyn core::any::Any + Send + Sync>>,
+    >,
+    #[cfg(feature = "parallel-compilation")]
+    compilation_pool: self::parallel::CompilationPool,
+    /// Whether a coredump was already written to
+    /// `Config::coredump_on_trap_path`.
+    #[cfg(feature = "coredump")]
+    coredump_written: core::sync::atomic::AtomicBool,
 
     /// One-time check of whether the compiler's settings, if present, are
     /// compatible with the native host.
@@ -116,6 +140,10 @@ impl Engine {
             // handlers, etc.
             #[cfg(has_native_signals)]
             crate::runtime::vm::init_traps(config.macos_use_mach_ports);
+            #[cfg(all(feature = "crash-dump", unix))]
+            if let Some(fd) = &config.crash_dump_fd {
+                crate::runtime::vm::crash_dump::set_fd(fd.clone());
+            }
             if !cfg!(miri) {
                 #[cfg(all(has_host_compiler_backend, feature = "debug-builtins"))]
                 crate::runtime::vm::debug_builtins::init();
@@ -123,11 +151,11 @@ impl Engine {
         }
 
         #[cfg(any(feature = "cranelift", feature = "winch"))]
-        let (config, compiler) = if config.has_compiler() {
-            let (config, compiler) = config.build_compiler(&mut tunables, features)?;
-            (config, Some(compiler))
+        let (config, compiler, recompiler) = if config.has_compiler() {
+            let (config, compiler, recompiler) = config.build_compiler(&mut tunables, features)?;
+            (config, Some(compiler), recompiler)
         } else {
-            (config.clone(), None)
+            (config.clone(), None, None)
         };
         #[cfg(not(any(feature = "cranelift", feature = "winch")))]
         let _ = &mut tunables;
@@ -137,10 +165,12 @@ impl Engine {
             wasmtime_environ::Module::new(wasmtime_environ::StaticModuleIndex::from_u32(0)),
         )?)?;
 
-        Ok(Engine {
+        let engine = Engine {
             inner: try_new::<Arc<_>>(EngineInner {
                 #[cfg(any(feature = "cranelift", feature = "winch"))]
                 compiler,
+                #[cfg(any(feature = "cranelift", feature = "winch"))]
+                recompiler,
                 #[cfg(feature = "runtime")]
                 allocator: {
                     let allocator = config.build_allocator(&tunables)?;
@@ -161,6 +191,16 @@ impl Engine {
                 signatures: TypeRegistry::new(),
                 #[cfg(all(feature = "runtime", target_has_atomic = "64"))]
                 epoch: AtomicU64::new(0),
+                #[cfg(feature = "runtime")]
+                counters: Default::default(),
+                #[cfg(feature = "runtime")]
+                code_registry: Default::default(),
+                #[cfg(feature = "runtime")]
+                extensions: Default::default(),
+                #[cfg(feature = "parallel-compilation")]
+                compilation_pool: self::parallel::CompilationPool::new(&config)?,
+                #[cfg(feature = "coredump")]
+                coredump_written: Default::default(),
                 compatible_with_native_host: Default::default(),
                 config,
                 tunables,
@@ -168,7 +208,18 @@ impl Engine {
                 #[cfg(feature = "runtime")]
                 empty_module_runtime_info,
             })?,
-        })
+        };
+
+        #[cfg(feature = "pooling-allocator")]
+        if let Some(pool) = engine.allocator().as_pooling() {
+            let delay = pool.config().decommit_max_delay;
+            let signal = pool.decommit_signal();
+            if delay.is_some() || signal.is_some() {
+                crate::runtime::vm::spawn_decommit_thread(&engine, delay, signal);
+            }
+        }
+
+        Ok(engine)
     }
 
     /// Returns the configuration settings that this engine is using.
@@ -195,14 +246,13 @@ impl Engine {
         if self.config().parallel_compilation {
             #[cfg(feature = "parallel-compilation")]
             {
-                use rayon::prelude::*;
                 // If we collect into Result<Vec<B>, E> directly, the returned error is not
                 // deterministic, because any error could be returned early. So we first materialize
                 // all results in order and then return the first error deterministically, or Ok(_).
-                return input
-                    .into_par_iter()
-                    .map(|a| f(a))
-                    .collect::<Vec<Result<B, E>>>()
+                return self
+                    .inner
+                    .compilation_pool
+                    .map(input, f)
                     .into_iter()
                     .collect::<Result<Vec<B>, E>>();
             }
@@ -229,16 +279,15 @@ impl Engine {
         if self.config().parallel_compilation {
             #[cfg(feature = "parallel-compilation")]
             {
-                use rayon::prelude::*;
                 // If we collect into `Result<(), E>` directly, the returned
                 // error is not deterministic, because any error could be
                 // returned early. So we first materialize all results in order
                 // and then return the first error deterministically, or
                 // `Ok(_)`.
-                return input
-                    .into_par_iter()
-                    .map(|a| f(a))
-                    .collect::<Vec<Result<(), E>>>()
+                return self
+                    .inner
+                    .compilation_pool
+                    .map_mut(input, f)
                     .into_iter()
                     .collect::<Result<(), E>>();
             }
@@ -318,6 +367,36 @@ impl Engine {
         serialization::detect_precompiled_file(path)
     }
 
+    /// Explains whether the precompiled artifact `bytes` can be loaded by this
+    /// engine, and if not then every reason why.
+    ///
+    /// Loading an incompatible artifact with, for example,
+    /// [`Module::deserialize`](crate::Module::deserialize) fails with an
+    /// error describing only the first mismatch found. This method instead
+    /// inspects the whole artifact and reports all differences in Wasmtime
+    /// version, target, compiler settings, CPU features, and WebAssembly
+    /// features between the artifact and this engine. This is intended to
+    /// help diagnose why artifacts produced elsewhere fail to load.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// let mut config = Config::new();
+    /// config.consume_fuel(true);
+    /// let bytes = Engine::new(&config)?.precompile_module(b"(module)")?;
+    ///
+    /// let report = Engine::default().check_compatibility(&bytes);
+    /// assert!(!report.is_compatible());
+    /// assert!(report.incompatibilities()[0].contains("fuel support"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_compatibility(&self, bytes: &[u8]) -> CompatibilityReport {
+        serialization::compatibility_report(self, bytes)
+    }
+
     /// Returns the target triple which this engine is compiling code for
     /// and/or running code for.
     pub(crate) fn target(&self) -> target_lexicon::Triple {
@@ -503,6 +582,9 @@ information about this check\
             | "tls_model" // wasmtime doesn't use tls right now
             | "opt_level" // opt level doesn't change semantics
             | "enable_alias_analysis" // alias analysis-based opts don't change semantics
+            | "enable_bounds_check_elimination" // keeps the same trap codes
+            | "enable_switch_recognition" // only restructures branches
+            | "enable_loop_unrolling" // only duplicates code
             | "probestack_size_log2" // probestack above asserted disabled
             | "regalloc" // shouldn't change semantics
             | "enable_incremental_compilation_cache_checks" // shouldn't change semantics
@@ -604,6 +686,7 @@ information about this check\
             "has_bmi1" => "bmi1",
             "has_bmi2" => "bmi2",
             "has_avx512bitalg" => "avx512bitalg",
+            "has_avx512bw" => "avx512bw",
             "has_avx512dq" => "avx512dq",
             "has_avx512f" => "avx512f",
             "has_avx512vl" => "avx512vl",
@@ -615,6 +698,9 @@ information about this check\
             "big_endian" if cfg!(target_endian = "little") => {
                 return Err("wrong host endianness".to_string());
             }
+            // Only affects how bytecode is encoded, not what the host must
+            // support.
+            "enable_peephole" => return Ok(()),
 
             _ => {
                 // FIXME: should enumerate risc-v features and plumb them
@@ -680,6 +766,26 @@ impl Engine {
             .ok_or_else(|| format_err!("Engine was not configured with a compiler"))
     }
 
+    pub(crate) fn try_recompiler(&self) -> Result<&dyn wasmtime_environ::Compiler> {
+        self.inner
+            .recompiler
+            .as_deref()
+            .ok_or_else(|| format_err!("Engine was not configured with background recompilation"))
+    }
+
+    /// Returns the background recompiler if `recompile` is set, or the
+    /// primary compiler otherwise.
+    pub(crate) fn try_compiler_for(
+        &self,
+        recompile: bool,
+    ) -> Result<&dyn wasmtime_environ::Compiler> {
+        if recompile {
+            self.try_recompiler()
+        } else {
+            self.try_compiler()
+        }
+    }
+
     /// Ahead-of-time (AOT) compiles a WebAssembly module.
     ///
     /// The `bytes` provided must be in one of two formats:
@@ -700,6 +806,15 @@ impl Engine {
     /// generation will be skipped and this will improve the performance of constructing
     /// a [`Module`](crate::Module) from the output of this method.
     ///
+    /// # Reproducibility
+    ///
+    /// The output of this method is deterministic: it only depends on `bytes`,
+    /// the [`Config`](crate::Config) of this [`Engine`], and the version of
+    /// Wasmtime. Note though that unless [`Config::target`](crate::Config::target)
+    /// is set the configuration includes the CPU features of the host, which
+    /// are detected at runtime, so the output may then differ between
+    /// machines.
+    ///
     /// [binary]: https://webassembly.github.io/spec/core/binary/index.html
     /// [text]: https://webassembly.github.io/spec/core/text/index.html
     pub fn precompile_module(&self, bytes: &[u8]) -> Result<Vec<u8>> {
@@ -728,6 +843,20 @@ impl Engine {
         Ok(())
     }
 
+    /// Appends `variants` of the artifact being built in `obj`, compiled with
+    /// different ISA-specific flags, to be selected from when it's loaded.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn append_variants(&self, obj: &mut Object<'_>, variants: &[Vec<u8>]) {
+        serialization::append_variants(obj, variants);
+    }
+
+    /// Checks that the artifact `variant` can be embedded as a variant of
+    /// artifacts compiled by this engine.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn check_variant(&self, variant: &[u8], expected: ObjectKind) -> Result<()> {
+        serialization::check_variant(self, variant, expected)
+    }
+
     #[cfg(any(feature = "cranelift", feature = "winch"))]
     pub(crate) fn append_bti(&self, obj: &mut Object<'_>) {
         let section = obj.add_section(
@@ -787,6 +916,67 @@ impl Engine {
         crate::runtime::vm::PoolingAllocatorMetrics::new(self)
     }
 
+    /// Returns an [`EngineMetrics`](crate::EngineMetrics) handle to read
+    /// counters of runtime events, such as instantiations and traps, across
+    /// all stores using this engine.
+    pub fn metrics(&self) -> crate::EngineMetrics {
+        crate::EngineMetrics::new(self)
+    }
+
+    pub(crate) fn counters(&self) -> &crate::runtime::EngineCounters {
+        &self.inner.counters
+    }
+
+    /// Returns a snapshot of the regions of code currently loaded by this
+    /// engine, one for each loaded [`Module`](crate::Module) and
+    /// [`Component`](crate::component::Component).
+    ///
+    /// Code stays loaded for as long as its module or component, or any
+    /// instance of it, is alive. Note that private copies of code made for
+    /// each store when guest debugging is enabled aren't included.
+    pub fn code_regions(&self) -> Vec<crate::CodeRegion> {
+        self.inner.code_registry.regions()
+    }
+
+    /// Returns the total size, in bytes, of the executable machine code
+    /// currently loaded by this engine.
+    ///
+    /// This is the sum of [`CodeRegion::text_size`](crate::CodeRegion::text_size)
+    /// of all [`Engine::code_regions`] which are executable.
+    pub fn executable_code_size(&self) -> usize {
+        self.inner.code_registry.executable_size()
+    }
+
+    /// Returns the value of type `T` attached to this engine, first attaching
+    /// the result of `init` if there isn't one yet.
+    ///
+    /// This lets libraries built on Wasmtime keep state which is shared by
+    /// everything using this engine, such as a pool of threads, without a
+    /// process-wide registry. Attached values are dropped along with the
+    /// engine. Values are keyed by their type, so libraries should use a type
+    /// of their own to avoid clashing with each other.
+    ///
+    /// If `init` returns an error then nothing is attached and the error is
+    /// returned. `init` must not attach values to this engine itself.
+    pub fn get_or_try_init_extension<T, E>(
+        &self,
+        init: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Arc<T>, E>
+    where
+        T: core::any::Any + Send + Sync,
+    {
+        let mut extensions = self.inner.extensions.lock();
+        let value = match extensions.entry(core::any::TypeId::of::<T>()) {
+            crate::hash_map::Entry::Occupied(entry) => entry.get().clone(),
+            crate::hash_map::Entry::Vacant(entry) => entry.insert(Arc::new(init()?)).clone(),
+        };
+        Ok(value.downcast().unwrap())
+    }
+
+    pub(crate) fn code_registry(&self) -> &crate::runtime::CodeRegistry {
+        &self.inner.code_registry
+    }
+
     pub(crate) fn allocator(&self) -> &dyn crate::runtime::vm::InstanceAllocator {
         let r: &(dyn crate::runtime::vm::InstanceAllocator + Send + Sync) =
             self.inner.allocator.as_ref();
@@ -815,6 +1005,21 @@ impl Engine {
         self.config().custom_code_memory.as_ref()
     }
 
+    /// Returns the path that the coredump of a trap should be written to, which
+    /// is only the case for the first trap of this engine.
+    #[cfg(feature = "coredump")]
+    pub(crate) fn take_coredump_path(&self) -> Option<&std::path::Path> {
+        let path = self.config().coredump_on_trap_path.as_deref()?;
+        if self
+            .inner
+            .coredump_written
+            .swap(true, core::sync::atomic::Ordering::Relaxed)
+        {
+            return None;
+        }
+        Some(path)
+    }
+
     #[cfg(target_has_atomic = "64")]
     pub(crate) fn epoch_counter(&self) -> &AtomicU64 {
         &self.inner.epoch
@@ -879,11 +1084,13 @@ impl Engine {
     /// uniquely owned mmap.
     ///
     /// The `expected` marker here is whether the bytes are expected to be a
-    /// precompiled module or a component.
+    /// precompiled module or a component, and `origin` is where they came
+    /// from.
     pub(crate) fn load_code_bytes(
         &self,
         bytes: &[u8],
         expected: ObjectKind,
+        origin: crate::CodeOrigin,
     ) -> Result<Arc<crate::CodeMemory>> {
         self.load_code(
             crate::runtime::vm::MmapVec::from_slice_with_alignment(
@@ -891,6 +1098,7 @@ impl Engine {
                 self.required_code_alignment(),
             )?,
             expected,
+            origin,
         )
     }
 
@@ -916,7 +1124,13 @@ impl Engine {
     ) -> Result<Arc<crate::CodeMemory>> {
         // SAFETY: the contract of this function is the same as that of
         // `from_raw`.
-        unsafe { self.load_code(crate::runtime::vm::MmapVec::from_raw(memory)?, expected) }
+        unsafe {
+            self.load_code(
+                crate::runtime::vm::MmapVec::from_raw(memory)?,
+                expected,
+                crate::CodeOrigin::Deserialized,
+            )
+        }
     }
 
     /// Like `load_code_bytes`, but creates a mmap from a file on disk.
@@ -930,6 +1144,7 @@ impl Engine {
             crate::runtime::vm::MmapVec::from_file(file)
                 .with_context(|| "Failed to create file mapping".to_string())?,
             expected,
+            crate::CodeOrigin::Deserialized,
         )
     }
 
@@ -937,12 +1152,21 @@ impl Engine {
         &self,
         mmap: crate::runtime::vm::MmapVec,
         expected: ObjectKind,
+        origin: crate::CodeOrigin,
     ) -> Result<Arc<crate::CodeMemory>> {
         self.check_compatible_with_native_host()
             .context("compilation settings are not compatible with the native host")?;
 
+        // Load the best variant of the artifact for this host, if it carries
+        // any, in its place.
+        let variant = serialization::select_variant(self, &mmap, expected)?
+            .map(crate::runtime::vm::MmapVec::from_slice)
+            .transpose()?;
+        let mmap = variant.unwrap_or(mmap);
+
         serialization::check_compatible(self, &mmap, expected)?;
         let mut code = crate::CodeMemory::new(self, mmap)?;
+        code.set_origin(origin);
         code.publish()?;
         Ok(try_new(code)?)
     }
@@ -0,0 +1,137 @@
+//! Control over the threads that Wasmtime's parallel compilation runs on.
+
+use crate::Config;
+use crate::prelude::*;
+use alloc::sync::Arc;
+use rayon::prelude::*;
+use std::sync::Mutex;
+
+/// An embedder-provided executor for Wasmtime's parallel compilation.
+///
+/// By default Wasmtime compiles functions in parallel on rayon's global
+/// thread pool, or on a dedicated pool if
+/// [`Config::compilation_threads`] or [`Config::compilation_thread_start`]
+/// are configured. Embedders which need to control exactly where and when
+/// compilation happens, for example to keep it off of cores reserved for
+/// latency-sensitive work, can instead provide their own executor with
+/// [`Config::with_compilation_executor`].
+///
+/// # Example
+///
+/// An executor which runs all compilation tasks on the calling thread:
+///
+/// ```
+/// use std::sync::Arc;
+/// use wasmtime::{CompilationExecutor, Config, Engine, Result};
+///
+/// struct Inline;
+///
+/// impl CompilationExecutor for Inline {
+///     fn run(&self, count: usize, task: &(dyn Fn(usize) + Sync)) {
+///         for i in 0..count {
+///             task(i);
+///         }
+///     }
+/// }
+///
+/// fn main() -> Result<()> {
+///     let mut config = Config::new();
+///     config.with_compilation_executor(Arc::new(Inline));
+///     let engine = Engine::new(&config)?;
+///     # let _ = engine;
+///     Ok(())
+/// }
+/// ```
+pub trait CompilationExecutor: Send + Sync {
+    /// Runs `task` once for each index in `0..count`, returning once all of
+    /// them have finished.
+    ///
+    /// Tasks are independent of each other and may run concurrently on any
+    /// number of threads, including the calling thread. Each index must be
+    /// run exactly once; Wasmtime panics if an index isn't run. A panic in a
+    /// task should be propagated to the caller of this method.
+    fn run(&self, count: usize, task: &(dyn Fn(usize) + Sync));
+}
+
+/// Where an engine runs its parallel compilation work.
+pub(crate) enum CompilationPool {
+    /// rayon's global thread pool.
+    Global,
+    /// A thread pool owned by the engine.
+    Dedicated(rayon::ThreadPool),
+    /// An executor provided by the embedder.
+    Executor(Arc<dyn CompilationExecutor>),
+}
+
+impl CompilationPool {
+    pub(crate) fn new(config: &Config) -> Result<CompilationPool> {
+        if let Some(executor) = &config.compilation_executor {
+            return Ok(CompilationPool::Executor(executor.clone()));
+        }
+        if config.compilation_threads.is_none() && config.compilation_thread_start.is_none() {
+            return Ok(CompilationPool::Global);
+        }
+
+        let mut builder = rayon::ThreadPoolBuilder::new()
+            .thread_name(|i| format!("wasmtime-compile-{i}"))
+            .num_threads(config.compilation_threads.unwrap_or(0));
+        if let Some(start) = config.compilation_thread_start.clone() {
+            builder = builder.start_handler(move |i| start(i));
+        }
+        let pool = builder
+            .build()
+            .map_err(|e| format_err!("failed to create compilation thread pool: {e}"))?;
+        Ok(CompilationPool::Dedicated(pool))
+    }
+
+    /// Maps `f` over `input` in parallel, returning results in order.
+    pub(crate) fn map<A, B, F>(&self, input: Vec<A>, f: F) -> Vec<B>
+    where
+        A: Send,
+        B: Send,
+        F: Fn(A) -> B + Send + Sync,
+    {
+        match self {
+            CompilationPool::Global => input.into_par_iter().map(f).collect(),
+            CompilationPool::Dedicated(pool) => {
+                pool.install(|| input.into_par_iter().map(f).collect())
+            }
+            CompilationPool::Executor(executor) => {
+                let count = input.len();
+                let inputs = input
+                    .into_iter()
+                    .map(|a| Mutex::new(Some(a)))
+                    .collect::<Vec<_>>();
+                let outputs = (0..count).map(|_| Mutex::new(None)).collect::<Vec<_>>();
+                executor.run(count, &|i| {
+                    let a = inputs[i]
+                        .lock()
+                        .unwrap()
+                        .take()
+                        .expect("compilation task run more than once");
+                    *outputs[i].lock().unwrap() = Some(f(a));
+                });
+                outputs
+                    .into_iter()
+                    .map(|b| {
+                        b.into_inner()
+                            .unwrap()
+                            .expect("compilation executor didn't run all tasks")
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Runs `f` over each element of `input` in parallel, returning results
+    /// in order.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    pub(crate) fn map_mut<T, B, F>(&self, input: &mut [T], f: F) -> Vec<B>
+    where
+        T: Send,
+        B: Send,
+        F: Fn(&mut T) -> B + Send + Sync,
+    {
+        self.map(input.iter_mut().collect(), f)
+    }
+}
@@ -47,6 +47,129 @@ const VERSION: u8 = 0;
 /// compiler options, etc. If a mismatch is found and the compilation metadata
 /// specified is incompatible then an error is returned.
 pub fn check_compatible(engine: &Engine, mmap: &[u8], expected: ObjectKind) -> Result<()> {
+    let (version, data) = engine_section(mmap, expected)?;
+    check_version(engine, version)?;
+    postcard::from_bytes::<Metadata<'_>>(data)?.check_compatible(engine)
+}
+
+/// Verifies that `variant` can be embedded as a variant of artifacts compiled
+/// by `engine`.
+///
+/// This is the same as [`check_compatible`] except that ISA-specific flags,
+/// which are what variants are expected to differ in, aren't checked.
+#[cfg(any(feature = "cranelift", feature = "winch"))]
+pub fn check_variant(engine: &Engine, variant: &[u8], expected: ObjectKind) -> Result<()> {
+    let (version, data) = engine_section(variant, expected)?;
+    check_version(engine, version)?;
+    let metadata = postcard::from_bytes::<Metadata<'_>>(data)?;
+    match metadata.incompatibilities(engine, false).into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Returns the first variant embedded in `mmap` which is compatible with
+/// `engine` and the native host, if any.
+///
+/// Variants are complete artifacts compiled with different ISA-specific flags
+/// which are appended to an artifact with [`append_variants`]. Variants that
+/// can't be loaded are skipped, in which case the artifact's own code is used.
+#[cfg(feature = "runtime")]
+pub fn select_variant<'a>(
+    engine: &Engine,
+    mmap: &'a [u8],
+    expected: ObjectKind,
+) -> Result<Option<&'a [u8]>> {
+    let Some(data) = section(mmap, expected, obj::ELF_WASMTIME_VARIANTS)? else {
+        return Ok(None);
+    };
+    let variants = postcard::from_bytes::<Vec<&[u8]>>(data)
+        .context("failed to decode the variants of the artifact")?;
+    Ok(variants
+        .into_iter()
+        .find(|variant| check_compatible(engine, variant, expected).is_ok()))
+}
+
+/// Same as [`check_compatible`] except that instead of stopping at the first
+/// problem this collects every reason that `mmap` can't be loaded into
+/// `engine`.
+pub fn compatibility_report(engine: &Engine, mmap: &[u8]) -> CompatibilityReport {
+    let mut report = CompatibilityReport {
+        kind: detect_precompiled_bytes(mmap),
+        version: None,
+        incompatibilities: Vec::new(),
+    };
+    let expected = match report.kind {
+        Some(Precompiled::Module) => ObjectKind::Module,
+        Some(Precompiled::Component) => ObjectKind::Component,
+        None => {
+            report
+                .incompatibilities
+                .push("input is not a precompiled Wasmtime artifact".to_string());
+            return report;
+        }
+    };
+
+    if let Err(e) = engine.check_compatible_with_native_host() {
+        report.push(
+            e.context("compilation settings of the engine are not compatible with the native host"),
+        );
+    }
+
+    let (version, data) = match engine_section(mmap, expected) {
+        Ok(section) => section,
+        Err(e) => {
+            report.push(e);
+            return report;
+        }
+    };
+    report.version = core::str::from_utf8(version).ok().map(|v| v.to_string());
+    if let Err(e) = check_version(engine, version) {
+        report.push(e);
+    }
+
+    match postcard::from_bytes::<Metadata<'_>>(data) {
+        Ok(metadata) => {
+            for e in metadata.incompatibilities(engine, true) {
+                report.push(e);
+            }
+        }
+        Err(e) => report.push(
+            Error::from(e)
+                .context("failed to decode the artifact's compilation settings, it was likely produced by a different version of Wasmtime"),
+        ),
+    }
+    report
+}
+
+/// Locates the engine section within `mmap`, returning the version string and
+/// the `postcard`-encoded `Metadata` that follows it.
+fn engine_section(mmap: &[u8], expected: ObjectKind) -> Result<(&[u8], &[u8])> {
+    let Some(data) = section(mmap, expected, obj::ELF_WASM_ENGINE)? else {
+        bail!("failed to find section `{}`", obj::ELF_WASM_ENGINE)
+    };
+    let (first, data) = data
+        .split_first()
+        .ok_or_else(|| format_err!("invalid engine section"))?;
+    if *first != VERSION {
+        bail!("mismatched version in engine section");
+    }
+    let (len, data) = data
+        .split_first()
+        .ok_or_else(|| format_err!("invalid engine section"))?;
+    let len = usize::from(*len);
+    let (version, data) = if data.len() < len + 1 {
+        bail!("engine section too small")
+    } else {
+        data.split_at(len)
+    };
+
+    Ok((version, data))
+}
+
+/// Returns the contents of the section called `name` within `mmap`, if it's
+/// present.
+fn section<'a>(mmap: &'a [u8], expected: ObjectKind, name: &str) -> Result<Option<&'a [u8]>> {
     // Parse the input `mmap` as an ELF file and see if the header matches the
     // Wasmtime-generated header. This includes a Wasmtime-specific `os_abi` and
     // the `e_flags` field should indicate whether `expected` matches or not.
@@ -88,33 +211,20 @@ pub fn check_compatible(engine: &Engine, mmap: &[u8], expected: ObjectKind) -> R
 
     let mut section_header = None;
     for s in sections.iter() {
-        let name = s.name(endian, strings)?;
-        if name == obj::ELF_WASM_ENGINE.as_bytes() {
+        if s.name(endian, strings)? == name.as_bytes() {
             section_header = Some(s);
         }
     }
     let Some(section_header) = section_header else {
-        bail!("failed to find section `{}`", obj::ELF_WASM_ENGINE)
+        return Ok(None);
     };
     let data = section_header
         .data(endian, mmap)
         .map_err(obj::ObjectCrateErrorWrapper)?;
-    let (first, data) = data
-        .split_first()
-        .ok_or_else(|| format_err!("invalid engine section"))?;
-    if *first != VERSION {
-        bail!("mismatched version in engine section");
-    }
-    let (len, data) = data
-        .split_first()
-        .ok_or_else(|| format_err!("invalid engine section"))?;
-    let len = usize::from(*len);
-    let (version, data) = if data.len() < len + 1 {
-        bail!("engine section too small")
-    } else {
-        data.split_at(len)
-    };
+    Ok(Some(data))
+}
 
+fn check_version(engine: &Engine, version: &[u8]) -> Result<()> {
     match &engine.config().module_version {
         ModuleVersionStrategy::None => { /* ignore the version info, accept all */ }
         _ => {
@@ -124,7 +234,76 @@ pub fn check_compatible(engine: &Engine, mmap: &[u8], expected: ObjectKind) -> R
             }
         }
     }
-    postcard::from_bytes::<Metadata<'_>>(data)?.check_compatible(engine)
+    Ok(())
+}
+
+/// A report of why a precompiled artifact can or can't be loaded by an
+/// [`Engine`], returned by [`Engine::check_compatibility`].
+///
+/// Unlike the error returned by [`Module::deserialize`](crate::Module::deserialize)
+/// which only describes the first problem encountered, this lists every
+/// difference in target, compilation settings, CPU features, WebAssembly
+/// features, and Wasmtime version which prevents loading the artifact.
+#[derive(Debug, Clone)]
+pub struct CompatibilityReport {
+    kind: Option<Precompiled>,
+    version: Option<String>,
+    incompatibilities: Vec<String>,
+}
+
+impl CompatibilityReport {
+    fn push(&mut self, error: Error) {
+        self.incompatibilities.push(format!("{error:#}"));
+    }
+
+    /// Returns whether the artifact can be loaded by the engine.
+    pub fn is_compatible(&self) -> bool {
+        self.incompatibilities.is_empty()
+    }
+
+    /// Returns what kind of artifact was inspected, or `None` if the input
+    /// wasn't recognized as a precompiled Wasmtime artifact at all.
+    pub fn kind(&self) -> Option<Precompiled> {
+        self.kind
+    }
+
+    /// Returns the Wasmtime version string embedded in the artifact, if it
+    /// could be read.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns a human-readable description of each reason the artifact
+    /// can't be loaded.
+    ///
+    /// This is empty if the artifact is compatible with the engine.
+    pub fn incompatibilities(&self) -> &[String] {
+        &self.incompatibilities
+    }
+}
+
+impl fmt::Display for CompatibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            Some(Precompiled::Module) => "module",
+            Some(Precompiled::Component) => "component",
+            None => "artifact",
+        };
+        if let Some(version) = &self.version {
+            writeln!(
+                f,
+                "precompiled {kind} produced by Wasmtime version '{version}'"
+            )?;
+        }
+        if self.is_compatible() {
+            return write!(f, "the {kind} is compatible with this engine");
+        }
+        write!(f, "the {kind} is not compatible with this engine:")?;
+        for incompatibility in self.incompatibilities.iter() {
+            write!(f, "\n  - {incompatibility}")?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(any(feature = "cranelift", feature = "winch"))]
@@ -148,6 +327,23 @@ pub fn append_compiler_info(engine: &Engine, obj: &mut Object<'_>, metadata: &Me
     obj.set_section_data(section, data, 1);
 }
 
+/// Appends `variants` of the artifact being built in `obj`, each a complete
+/// artifact compiled with different ISA-specific flags, to be selected from
+/// with [`select_variant`] when it's loaded.
+#[cfg(any(feature = "cranelift", feature = "winch"))]
+pub fn append_variants(obj: &mut Object<'_>, variants: &[Vec<u8>]) {
+    if variants.is_empty() {
+        return;
+    }
+    let section = obj.add_section(
+        obj.segment_name(StandardSegment::Data).to_vec(),
+        obj::ELF_WASMTIME_VARIANTS.as_bytes().to_vec(),
+        object::SectionKind::ReadOnlyData,
+    );
+    let variants = variants.iter().map(|v| &v[..]).collect::<Vec<_>>();
+    obj.set_section_data(section, postcard::to_allocvec(&variants).unwrap(), 1);
+}
+
 fn detect_precompiled<'data, R: object::ReadRef<'data>>(
     obj: ElfFile64<'data, Endianness, R>,
 ) -> Option<Precompiled> {
@@ -201,13 +397,27 @@ impl Metadata<'_> {
         })
     }
 
-    fn check_compatible(mut self, engine: &Engine) -> Result<()> {
-        self.check_triple(engine)?;
-        self.check_shared_flags(engine)?;
-        self.check_isa_flags(engine)?;
-        self.check_tunables(&engine.tunables())?;
-        self.check_features(&engine.features())?;
-        Ok(())
+    fn check_compatible(self, engine: &Engine) -> Result<()> {
+        match self.incompatibilities(engine, true).into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns every reason this metadata is incompatible with `engine`, in
+    /// the order that they're checked.
+    ///
+    /// ISA-specific flags are only checked if `isa_flags` is set.
+    fn incompatibilities(mut self, engine: &Engine, isa_flags: bool) -> Vec<Error> {
+        let mut errors = Vec::new();
+        errors.extend(self.check_triple(engine).err());
+        self.check_shared_flags(engine, &mut errors);
+        if isa_flags {
+            self.check_isa_flags(engine, &mut errors);
+        }
+        self.check_tunables(&engine.tunables(), &mut errors);
+        self.check_features(&engine.features(), &mut errors);
+        errors
     }
 
     fn check_triple(&self, engine: &Engine) -> Result<()> {
@@ -232,24 +442,26 @@ impl Metadata<'_> {
         Ok(())
     }
 
-    fn check_shared_flags(&mut self, engine: &Engine) -> Result<()> {
+    fn check_shared_flags(&mut self, engine: &Engine, errors: &mut Vec<Error>) {
         for (name, val) in self.shared_flags.iter() {
-            engine
-                .check_compatible_with_shared_flag(name, val)
-                .map_err(|s| crate::Error::msg(s))
-                .context("compilation settings of module incompatible with native host")?;
+            if let Err(s) = engine.check_compatible_with_shared_flag(name, val) {
+                errors.push(
+                    crate::Error::msg(s)
+                        .context("compilation settings of module incompatible with native host"),
+                );
+            }
         }
-        Ok(())
     }
 
-    fn check_isa_flags(&mut self, engine: &Engine) -> Result<()> {
+    fn check_isa_flags(&mut self, engine: &Engine, errors: &mut Vec<Error>) {
         for (name, val) in self.isa_flags.iter() {
-            engine
-                .check_compatible_with_isa_flag(name, val)
-                .map_err(|s| crate::Error::msg(s))
-                .context("compilation settings of module incompatible with native host")?;
+            if let Err(s) = engine.check_compatible_with_isa_flag(name, val) {
+                errors.push(
+                    crate::Error::msg(s)
+                        .context("compilation settings of module incompatible with native host"),
+                );
+            }
         }
-        Ok(())
     }
 
     fn check_int<T: Eq + fmt::Display>(found: T, expected: T, feature: &str) -> Result<()> {
@@ -291,7 +503,7 @@ impl Metadata<'_> {
         Ok(())
     }
 
-    fn check_tunables(&mut self, other: &Tunables) -> Result<()> {
+    fn check_tunables(&mut self, other: &Tunables, errors: &mut Vec<Error>) {
         let Tunables {
             collector,
             memory_reservation,
@@ -313,8 +525,11 @@ impl Metadata<'_> {
             inlining,
             inlining_small_callee_size,
             inlining_sum_size_threshold,
+            inlining_adapter_size,
+            br_table_range_split,
             concurrency_support,
             recording,
+            profile_instrumentation,
 
             // This doesn't affect compilation, it's just a runtime setting.
             memory_reservation_for_growth: _,
@@ -328,6 +543,10 @@ impl Metadata<'_> {
             // Just a debugging aid, doesn't affect functionality at all.
             debug_adapter_modules: _,
 
+            // Code with additional epoch checks runs the same in any engine
+            // that has epoch interruption enabled.
+            epoch_interruption_check_interval: _,
+
             // This is a runtime GC debugging setting, doesn't affect compilation.
             gc_zeal_alloc_counter: _,
 
@@ -350,111 +569,156 @@ impl Metadata<'_> {
             branch_hinting: _,
         } = self.tunables;
 
-        Self::check_collector(collector, other.collector)?;
-        Self::check_int(
+        let mut check = |result: Result<()>| errors.extend(result.err());
+        check(Self::check_collector(collector, other.collector));
+        check(Self::check_int(
             memory_reservation,
             other.memory_reservation,
             "memory reservation",
-        )?;
-        Self::check_int(
+        ));
+        check(Self::check_int(
             memory_guard_size,
             other.memory_guard_size,
             "memory guard size",
-        )?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
             debug_native,
             other.debug_native,
             "native debug information support",
-        )?;
-        Self::check_bool(debug_guest, other.debug_guest, "guest debug")?;
-        Self::check_bool(debug_symbols, other.debug_symbols, "debug symbols")?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
+            debug_guest,
+            other.debug_guest,
+            "guest debug",
+        ));
+        check(Self::check_bool(
+            debug_symbols,
+            other.debug_symbols,
+            "debug symbols",
+        ));
+        check(Self::check_bool(
             parse_wasm_debuginfo,
             other.parse_wasm_debuginfo,
             "WebAssembly backtrace support",
-        )?;
-        Self::check_bool(consume_fuel, other.consume_fuel, "fuel support")?;
-        Self::check_cost(consume_fuel, operator_cost, &other.operator_cost)?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
+            consume_fuel,
+            other.consume_fuel,
+            "fuel support",
+        ));
+        check(Self::check_cost(
+            consume_fuel,
+            operator_cost,
+            &other.operator_cost,
+        ));
+        check(Self::check_bool(
             epoch_interruption,
             other.epoch_interruption,
             "epoch interruption",
-        )?;
-        Self::check_bool(memory_may_move, other.memory_may_move, "memory may move")?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
+            memory_may_move,
+            other.memory_may_move,
+            "memory may move",
+        ));
+        check(Self::check_bool(
             guard_before_linear_memory,
             other.guard_before_linear_memory,
             "guard before linear memory",
-        )?;
-        Self::check_bool(table_lazy_init, other.table_lazy_init, "table lazy init")?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
+            table_lazy_init,
+            other.table_lazy_init,
+            "table lazy init",
+        ));
+        check(Self::check_bool(
             relaxed_simd_deterministic,
             other.relaxed_simd_deterministic,
             "relaxed simd deterministic semantics",
-        )?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
             winch_callable,
             other.winch_callable,
             "Winch calling convention",
-        )?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
             signals_based_traps,
             other.signals_based_traps,
             "Signals-based traps",
-        )?;
-        Self::check_bool(
+        ));
+        check(Self::check_bool(
             memory_init_cow,
             other.memory_init_cow,
             "memory initialization with CoW",
-        )?;
-        Self::check_int(
+        ));
+        check(Self::check_int(
             inlining_small_callee_size,
             other.inlining_small_callee_size,
             "function inlining small-callee size",
-        )?;
-        Self::check_int(
+        ));
+        check(Self::check_int(
             inlining_sum_size_threshold,
             other.inlining_sum_size_threshold,
             "function inlining sum-size threshold",
-        )?;
-        Self::check_bool(
+        ));
+        check(Self::check_int(
+            inlining_adapter_size,
+            other.inlining_adapter_size,
+            "fused adapter inlining size",
+        ));
+        check(Self::check_int(
+            br_table_range_split,
+            other.br_table_range_split,
+            "br_table range split threshold",
+        ));
+        check(Self::check_bool(
             concurrency_support,
             other.concurrency_support,
             "concurrency support",
-        )?;
-        Self::check_bool(recording, other.recording, "RR recording support")?;
-        Self::check_inlining(inlining, other.inlining)?;
-        Self::check_int(
+        ));
+        check(Self::check_bool(
+            recording,
+            other.recording,
+            "RR recording support",
+        ));
+        check(Self::check_bool(
+            profile_instrumentation,
+            other.profile_instrumentation,
+            "profile instrumentation",
+        ));
+        check(Self::check_inlining(inlining, other.inlining));
+        check(Self::check_int(
             gc_heap_reservation,
             other.gc_heap_reservation,
             "GC heap reservation",
-        )?;
-        Self::check_int(
+        ));
+        check(Self::check_int(
             gc_heap_guard_size,
             other.gc_heap_guard_size,
             "GC heap guard size",
-        )?;
-        Self::check_int(
+        ));
+        check(Self::check_int(
             gc_heap_initial_size,
             other.gc_heap_initial_size,
             "GC heap initial size",
-        )?;
-        Self::check_bool(gc_heap_may_move, other.gc_heap_may_move, "GC heap may move")?;
-
-        Ok(())
+        ));
+        check(Self::check_bool(
+            gc_heap_may_move,
+            other.gc_heap_may_move,
+            "GC heap may move",
+        ));
     }
 
-    fn check_features(&mut self, other: &wasmparser::WasmFeatures) -> Result<()> {
+    fn check_features(&mut self, other: &wasmparser::WasmFeatures, errors: &mut Vec<Error>) {
         let module_features = wasmparser::WasmFeatures::from_bits_truncate(self.features);
         let missing_features = (*other & module_features) ^ module_features;
         for (name, _) in missing_features.iter_names() {
             let name = name.to_ascii_lowercase();
-            bail!(
+            errors.push(format_err!(
                 "Module was compiled with support for WebAssembly feature \
                 `{name}` but it is not enabled for the host",
-            );
+            ));
         }
-        Ok(())
     }
 
     fn check_collector(
@@ -706,6 +970,96 @@ mod test {
         Ok(())
     }
 
+    /// Returns the Cranelift flag for an x86-64 feature the host lacks, if
+    /// any.
+    #[cfg(target_arch = "x86_64")]
+    fn missing_x86_feature() -> Option<&'static str> {
+        if !std::is_x86_feature_detected!("avx512bitalg") {
+            Some("has_avx512bitalg")
+        } else if !std::is_x86_feature_detected!("avx512vbmi") {
+            Some("has_avx512vbmi")
+        } else if !std::is_x86_feature_detected!("avx512vl") {
+            Some("has_avx512vl")
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    fn variants_are_selected_by_host_features() -> Result<()> {
+        let wasm = "(module (func (export \"f\") (param i32) (result i32) local.get 0))";
+        let engine = Engine::default();
+        let supported = Engine::default();
+
+        // A variant which the host supports is loaded instead of the
+        // artifact's own code.
+        let artifact = crate::CodeBuilder::new(&engine)
+            .wasm_binary_or_text(wasm.as_bytes(), None)?
+            .variant(&supported)
+            .compile_module_serialized()?;
+        let expected = supported.precompile_module(wasm.as_bytes())?;
+        let module = unsafe { Module::deserialize(&engine, &artifact)? };
+        assert_eq!(module.serialize()?, expected);
+
+        let Some(flag) = missing_x86_feature() else {
+            return Ok(());
+        };
+        let mut config = Config::new();
+        unsafe {
+            config.cranelift_flag_enable(flag);
+        }
+        let unsupported = Engine::new(&config)?;
+
+        // Variants which the host doesn't support are skipped, falling back
+        // to the artifact's own code if none are left.
+        let artifact = crate::CodeBuilder::new(&engine)
+            .wasm_binary_or_text(wasm.as_bytes(), None)?
+            .variant(&unsupported)
+            .compile_module_serialized()?;
+        let module = unsafe { Module::deserialize(&engine, &artifact)? };
+        assert_eq!(module.serialize()?, artifact);
+
+        let artifact = crate::CodeBuilder::new(&engine)
+            .wasm_binary_or_text(wasm.as_bytes(), None)?
+            .variant(&unsupported)
+            .variant(&supported)
+            .compile_module_serialized()?;
+        let module = unsafe { Module::deserialize(&engine, &artifact)? };
+        assert_eq!(module.serialize()?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn variants_must_match_the_engine() -> Result<()> {
+        let wasm = "(module)";
+        let engine = Engine::default();
+        let mut config = Config::new();
+        config.epoch_interruption(true);
+        let variant = Engine::new(&config)?;
+
+        let err = crate::CodeBuilder::new(&engine)
+            .wasm_binary_or_text(wasm.as_bytes(), None)?
+            .variant(&variant)
+            .compile_module_serialized()
+            .unwrap_err();
+        assert_contains(
+            &err,
+            "variant is incompatible with the engine it's embedded for",
+        );
+
+        let err = crate::CodeBuilder::new(&engine)
+            .wasm_binary_or_text(wasm.as_bytes(), None)?
+            .variant(&variant)
+            .compile_module()
+            .unwrap_err();
+        assert_contains(&err, "can only be used with serialized");
+
+        Ok(())
+    }
+
     #[test]
     fn engine_weak_upgrades() {
         let engine = Engine::default();
@@ -719,6 +1073,38 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn cache_hits_are_cache_code_regions() -> Result<()> {
+        let td = TempDir::new()?;
+        let config_path = td.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            &format!(
+                "
+                    [cache]
+                    directory = '{}'
+                ",
+                td.path().join("cache").display()
+            ),
+        )?;
+        let mut cfg = Config::new();
+        cfg.cache(Some(Cache::from_file(Some(&config_path))?));
+        let engine = Engine::new(&cfg)?;
+        let _compiled = Module::new(&engine, "(module (func))")?;
+        let _cached = Module::new(&engine, "(module (func))")?;
+        let mut origins = engine
+            .code_regions()
+            .iter()
+            .map(|region| (region.id(), region.origin()))
+            .collect::<Vec<_>>();
+        origins.sort_by_key(|(id, _)| *id);
+        assert_eq!(origins.len(), 2);
+        assert_eq!(origins[0].1, crate::CodeOrigin::Compiled);
+        assert_eq!(origins[1].1, crate::CodeOrigin::Cache);
+        Ok(())
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn cache_accounts_for_opt_level() -> Result<()> {
@@ -509,10 +509,12 @@ mod sync_nostd;
 #[cfg(not(feature = "std"))]
 use sync_nostd as sync;
 
-pub use wasmtime_environ::OperatorCost;
+pub use wasmtime_environ::SpectreMitigations;
 pub use wasmtime_environ::ToWasmtimeResult;
 #[doc(inline)]
 pub use wasmtime_environ::error;
+pub use wasmtime_environ::{BranchCounts, FunctionProfile, WasmProfile};
+pub use wasmtime_environ::{FuelCostTable, OperatorCost};
 pub use wasmtime_environ::{FuncIndex, StaticModuleIndex};
 
 // Only for use in `bindgen!`-generated code.
@@ -38,9 +38,11 @@ pub(crate) mod func;
 
 pub(crate) mod code;
 pub(crate) mod code_memory;
+pub(crate) mod code_region;
 #[cfg(feature = "debug")]
 pub(crate) mod debug;
 pub(crate) mod exception;
+pub(crate) mod extensions;
 pub(crate) mod externals;
 #[cfg(feature = "async")]
 pub(crate) mod fiber;
@@ -50,10 +52,13 @@ pub(crate) mod instantiate;
 pub(crate) mod limits;
 pub(crate) mod linker;
 pub(crate) mod memory;
+pub(crate) mod metrics;
 pub(crate) mod module;
 #[cfg(feature = "debug-builtins")]
 pub(crate) mod native_debug;
 pub(crate) mod resources;
+#[cfg(feature = "state-image")]
+pub(crate) mod state_image;
 pub(crate) mod store;
 pub(crate) mod trampoline;
 pub(crate) mod trap;
@@ -89,9 +94,12 @@ cfg_select! {
 pub use bug::WasmtimeBug;
 pub(crate) use bug::{bail_bug, bug};
 pub use code_memory::CodeMemory;
+pub use code_region::{CodeEvent, CodeKind, CodeOrigin, CodeRegion};
+pub(crate) use code_region::{CodeEventHook, CodeRegistration, CodeRegistry};
 #[cfg(feature = "debug")]
 pub use debug::*;
 pub use exception::*;
+pub use extensions::{Extensions, HasExtensions};
 pub use externals::*;
 pub use func::*;
 pub use gc::*;
@@ -100,12 +108,19 @@ pub use instantiate::CompiledModule;
 pub use limits::*;
 pub use linker::*;
 pub use memory::*;
-pub use module::{Module, ModuleExport, ModuleFunction};
+pub(crate) use metrics::EngineCounters;
+pub use metrics::{EngineMetrics, TrapStats};
+#[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+pub use module::RecompilingModule;
+pub use module::{Module, ModuleExport, ModuleFunction, ModuleNames, ModuleStackMap};
 pub use resources::*;
+#[cfg(feature = "state-image")]
+pub use state_image::StateImage;
 #[cfg(all(feature = "async", feature = "call-hook"))]
 pub use store::CallHookHandler;
 pub use store::{
-    AsContext, AsContextMut, CallHook, Store, StoreContext, StoreContextMut, UpdateDeadline,
+    AsContext, AsContextMut, CallHook, Store, StoreContext, StoreContextMut, StoreDataHandle,
+    UpdateDeadline,
 };
 pub use trap::*;
 pub use types::*;
@@ -120,6 +135,8 @@ mod profiling;
 #[cfg(feature = "profiling")]
 pub use profiling::GuestProfiler;
 
+#[cfg(feature = "async")]
+pub use fiber::Cancelled;
 #[cfg(feature = "async")]
 pub(crate) mod stack;
 #[cfg(feature = "async")]
@@ -188,6 +205,8 @@ fn _assertions_runtime() {
     _assert_send_and_sync::<Store<()>>();
     _assert_send_and_sync::<StoreContext<'_, ()>>();
     _assert_send_and_sync::<StoreContextMut<'_, ()>>();
+    _assert_send_and_sync::<StoreDataHandle<()>>();
+    _assert_send_and_sync::<StoreDataHandle<*mut u8>>();
 
     #[cfg(feature = "async")]
     fn _call_async(s: &mut Store<()>, f: Func) {
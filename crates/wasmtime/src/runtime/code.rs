@@ -1,8 +1,9 @@
-use crate::Engine;
 use crate::Module;
 use crate::Result;
 use crate::module::ModuleRegistry;
+use crate::runtime::CodeRegistration;
 use crate::vm::ModuleMemoryImageSource;
+use crate::{CodeKind, Engine};
 use crate::{code_memory::CodeMemory, type_registry::TypeCollection};
 #[cfg(feature = "debug")]
 use alloc::boxed::Box;
@@ -97,6 +98,13 @@ impl StoreCodePC {
 /// instance-specific data and code pointers can be obtained from the
 /// `StoreCode`.
 pub struct EngineCode {
+    /// Registration of this code with the `Engine` that loaded it, for
+    /// `Engine::code_regions`.
+    ///
+    /// This is declared first so it's unregistered before `original_code` is
+    /// possibly released.
+    _registration: CodeRegistration,
+
     /// Actual underlying code which is executable and contains other
     /// compiled information.
     ///
@@ -126,15 +134,21 @@ pub struct EngineCode {
 
 impl EngineCode {
     pub fn new(
+        engine: &Engine,
         mmap: Arc<CodeMemory>,
         signatures: TypeCollection,
         types: Types,
+        kind: CodeKind,
+        name: Option<&str>,
     ) -> Result<EngineCode, OutOfMemory> {
+        let registration = CodeRegistration::new(engine, &mmap, kind, name)?;
+
         // The corresponding unregister for this is below in `Drop for
         // EngineCode`.
         crate::module::register_code(&mmap, mmap.raw_addr_range())?;
 
         Ok(EngineCode {
+            _registration: registration,
             original_code: mmap,
             signatures,
             types,
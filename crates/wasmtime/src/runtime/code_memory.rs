@@ -1,8 +1,8 @@
 //! Memory management for executable code.
 
-use crate::Engine;
 use crate::prelude::*;
 use crate::runtime::vm::MmapVec;
+use crate::{CodeOrigin, Engine};
 use alloc::sync::Arc;
 use core::ops::Range;
 use object::read::elf::SectionTable;
@@ -33,6 +33,7 @@ pub struct CodeMemory {
     #[cfg(feature = "debug-builtins")]
     has_native_debug_info: bool,
     custom_code_memory: Option<Arc<dyn CustomCodeMemory>>,
+    origin: CodeOrigin,
 
     // Ranges within `self.mmap` of where the particular sections lie.
     text: Range<usize>,
@@ -265,6 +266,7 @@ impl CodeMemory {
             #[cfg(feature = "debug-builtins")]
             has_native_debug_info,
             custom_code_memory: engine.custom_code_memory().cloned(),
+            origin: CodeOrigin::Deserialized,
             text,
             unwind,
             trap_data,
@@ -281,6 +283,23 @@ impl CodeMemory {
         })
     }
 
+    /// Returns where this code came from.
+    pub(crate) fn origin(&self) -> CodeOrigin {
+        self.origin
+    }
+
+    /// Records where this code came from, which defaults to
+    /// `CodeOrigin::Deserialized`.
+    pub(crate) fn set_origin(&mut self, origin: CodeOrigin) {
+        self.origin = origin;
+    }
+
+    /// Returns whether the text section of this image is mapped executable
+    /// once published, which isn't the case for Pulley bytecode.
+    pub(crate) fn is_executable(&self) -> bool {
+        self.needs_executable
+    }
+
     /// Returns a reference to the underlying `MmapVec` this memory owns.
     #[inline]
     pub fn mmap(&self) -> &MmapVec {
@@ -631,7 +650,9 @@ impl CodeMemory {
     #[cfg(feature = "debug")]
     pub(crate) fn deep_clone(self: &Arc<Self>, engine: &Engine) -> Result<CodeMemory> {
         let mmap = self.mmap.deep_clone()?;
-        Self::new(engine, mmap)
+        let mut code = Self::new(engine, mmap)?;
+        code.origin = self.origin;
+        Ok(code)
     }
 
     /// Obtain a frame-table parser on this module's frame state slot
@@ -0,0 +1,211 @@
+use crate::Engine;
+use crate::code_memory::CodeMemory;
+use crate::prelude::*;
+use crate::sync::RwLock;
+use alloc::sync::Arc;
+use core::ops::Range;
+use wasmtime_core::error::OutOfMemory;
+
+/// Where the code within a [`CodeRegion`] came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CodeOrigin {
+    /// The code was freshly compiled by the engine.
+    Compiled,
+    /// The code was loaded from the compilation cache configured with
+    /// [`Config::cache`](crate::Config::cache).
+    Cache,
+    /// The code was deserialized from a precompiled artifact, for example with
+    /// [`Module::deserialize`](crate::Module::deserialize).
+    Deserialized,
+}
+
+/// What kind of artifact a [`CodeRegion`] holds the code for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CodeKind {
+    /// The code of a core wasm [`Module`](crate::Module).
+    Module,
+    /// The code of a [`Component`](crate::component::Component), including
+    /// the code of all of its core wasm modules.
+    Component,
+}
+
+/// A region of code loaded by an [`Engine`].
+///
+/// Each region corresponds to one compiled [`Module`](crate::Module) or
+/// [`Component`](crate::component::Component) and stays loaded for as long as
+/// the module or component, or any instance of it, is alive.
+///
+/// Regions can be enumerated with [`Engine::code_regions`] and observed as
+/// they're loaded and unloaded with
+/// [`Config::code_event_hook`](crate::Config::code_event_hook).
+#[derive(Clone, Debug)]
+pub struct CodeRegion {
+    id: u64,
+    kind: CodeKind,
+    name: Option<Arc<str>>,
+    origin: CodeOrigin,
+    text: Range<usize>,
+    image_size: usize,
+    executable: bool,
+}
+
+impl CodeRegion {
+    /// Returns an identifier for this region which is unique among all regions
+    /// loaded by the same engine.
+    ///
+    /// This can be used to correlate [`CodeEvent::Mapped`] and
+    /// [`CodeEvent::Unmapped`] events.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the kind of artifact whose code this region holds.
+    pub fn kind(&self) -> CodeKind {
+        self.kind
+    }
+
+    /// Returns the name of the module, if this is the code of a module with a
+    /// name.
+    ///
+    /// See [`Module::name`](crate::Module::name) for more information.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns where the code in this region came from.
+    pub fn origin(&self) -> CodeOrigin {
+        self.origin
+    }
+
+    /// Returns the range of addresses in the host process of the machine code
+    /// in this region.
+    pub fn text_range(&self) -> Range<usize> {
+        self.text.clone()
+    }
+
+    /// Returns the size, in bytes, of the machine code in this region.
+    pub fn text_size(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns the total size, in bytes, of the loaded image containing this
+    /// region's code, including metadata such as trap and unwind tables.
+    pub fn image_size(&self) -> usize {
+        self.image_size
+    }
+
+    /// Returns whether the machine code in this region is mapped executable.
+    ///
+    /// Apart from the machine code being executable, the whole image is mapped
+    /// read-only. Code compiled for Pulley is interpreted and is never mapped
+    /// executable.
+    pub fn is_executable(&self) -> bool {
+        self.executable
+    }
+}
+
+/// An event delivered to the hook configured with
+/// [`Config::code_event_hook`](crate::Config::code_event_hook).
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum CodeEvent<'a> {
+    /// A region of code was loaded and is now ready to execute.
+    Mapped(&'a CodeRegion),
+    /// A region of code was unloaded and its memory is about to be released.
+    Unmapped(&'a CodeRegion),
+}
+
+/// The hook type configured with
+/// [`Config::code_event_hook`](crate::Config::code_event_hook).
+pub(crate) type CodeEventHook = Arc<dyn Fn(CodeEvent<'_>) + Send + Sync>;
+
+/// The code regions loaded by an [`Engine`], stored within it.
+#[derive(Default)]
+pub(crate) struct CodeRegistry {
+    regions: RwLock<Regions>,
+}
+
+#[derive(Default)]
+struct Regions {
+    next_id: u64,
+    map: TryBTreeMap<u64, CodeRegion>,
+}
+
+impl CodeRegistry {
+    fn register(&self, engine: &Engine, mut region: CodeRegion) -> Result<u64, OutOfMemory> {
+        {
+            let mut regions = self.regions.write();
+            region.id = regions.next_id;
+            let prev = regions.map.insert(region.id, region.clone())?;
+            debug_assert!(prev.is_none());
+            regions.next_id += 1;
+        }
+        if let Some(hook) = &engine.config().code_event_hook {
+            hook(CodeEvent::Mapped(&region));
+        }
+        Ok(region.id)
+    }
+
+    fn unregister(&self, engine: &Engine, id: u64) {
+        let region = self.regions.write().map.remove(id);
+        let region = region.expect("code region should be registered");
+        if let Some(hook) = &engine.config().code_event_hook {
+            hook(CodeEvent::Unmapped(&region));
+        }
+    }
+
+    pub(crate) fn regions(&self) -> Vec<CodeRegion> {
+        self.regions.read().map.values().cloned().collect()
+    }
+
+    pub(crate) fn executable_size(&self) -> usize {
+        self.regions
+            .read()
+            .map
+            .values()
+            .filter(|region| region.executable)
+            .map(|region| region.text_size())
+            .sum()
+    }
+}
+
+/// Registration of a [`CodeRegion`] within its engine's [`CodeRegistry`],
+/// which is unregistered when dropped.
+pub(crate) struct CodeRegistration {
+    engine: Engine,
+    id: u64,
+}
+
+impl CodeRegistration {
+    pub(crate) fn new(
+        engine: &Engine,
+        code: &CodeMemory,
+        kind: CodeKind,
+        name: Option<&str>,
+    ) -> Result<CodeRegistration, OutOfMemory> {
+        let region = CodeRegion {
+            id: 0,
+            kind,
+            name: name.map(Arc::from),
+            origin: code.origin(),
+            text: code.raw_addr_range(),
+            image_size: code.mmap().len(),
+            executable: code.is_executable(),
+        };
+        let id = engine.code_registry().register(engine, region)?;
+        Ok(CodeRegistration {
+            engine: engine.clone(),
+            id,
+        })
+    }
+}
+
+impl Drop for CodeRegistration {
+    fn drop(&mut self) {
+        self.engine
+            .code_registry()
+            .unregister(&self.engine, self.id);
+    }
+}
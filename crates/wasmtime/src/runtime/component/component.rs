@@ -7,10 +7,11 @@ use crate::prelude::*;
 use crate::runtime::vm::open_file_for_mmap;
 use crate::runtime::vm::{CompiledModuleId, VMArrayCallFunction, VMFuncRef, VMWasmCallFunction};
 use crate::{
-    Engine, Module, ResourcesRequired, code::EngineCode, code_memory::CodeMemory,
-    type_registry::TypeCollection,
+    CodeKind, CodeOrigin, Engine, Module, ResourcesRequired, code::EngineCode,
+    code_memory::CodeMemory, type_registry::TypeCollection,
 };
 use crate::{FuncType, ValType};
+use alloc::collections::BTreeSet;
 use alloc::sync::Arc;
 use core::ops::Range;
 use core::ptr::NonNull;
@@ -20,6 +21,7 @@ use wasmtime_environ::component::{
     CompiledComponentInfo, ComponentArtifacts, ComponentTypes, CoreDef, Export, ExportIndex,
     GlobalInitializer, InstantiateModule, NameMapNoIntern, OptionsIndex, StaticModuleIndex,
     TrampolineIndex, TypeComponentIndex, TypeFuncIndex, UnsafeIntrinsic, VMComponentOffsets,
+    package_name_and_version,
 };
 use wasmtime_environ::{Abi, CompiledFunctionsTable, FuncKey, TypeTrace, WasmChecksum};
 use wasmtime_environ::{FunctionLoc, HostPtr, ObjectKind, PrimaryMap};
@@ -220,7 +222,11 @@ impl Component {
     ///
     /// [`Module::deserialize`]: crate::Module::deserialize
     pub unsafe fn deserialize(engine: &Engine, bytes: impl AsRef<[u8]>) -> Result<Component> {
-        let code = engine.load_code_bytes(bytes.as_ref(), ObjectKind::Component)?;
+        let code = engine.load_code_bytes(
+            bytes.as_ref(),
+            ObjectKind::Component,
+            CodeOrigin::Deserialized,
+        )?;
         Component::from_parts(engine, code, None)
     }
 
@@ -266,6 +272,54 @@ impl Component {
         Component::from_parts(engine, code, None)
     }
 
+    /// Returns the WIT packages that this component imports interfaces from,
+    /// along with the version of each package that it was built against.
+    ///
+    /// Each item is a package name such as `wasi:http` and its version, such
+    /// as `0.2.0`, or `None` for interfaces imported without a version. A
+    /// package imported at several versions is returned once per version.
+    /// Imports which aren't interfaces of a WIT package, such as plain names,
+    /// aren't included.
+    ///
+    /// This can be used with [`Linker::alias_package_version`] to provide the
+    /// versions that a component needs.
+    ///
+    /// [`Linker::alias_package_version`]: crate::component::Linker::alias_package_version
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::Engine;
+    /// # use wasmtime::component::Component;
+    /// # fn main() -> wasmtime::Result<()> {
+    /// # let engine = Engine::default();
+    /// let component = Component::new(&engine, r#"
+    ///     (component
+    ///         (import "wasi:http/types@0.2.0" (instance))
+    ///         (import "wasi:http/outgoing-handler@0.2.0" (instance))
+    ///         (import "wasi:cli/stdout@0.2.3" (instance))
+    ///         (import "log" (func))
+    ///     )
+    /// "#)?;
+    ///
+    /// let packages = component.imported_packages().collect::<Vec<_>>();
+    /// assert_eq!(packages, [
+    ///     ("wasi:cli", Some("0.2.3")),
+    ///     ("wasi:http", Some("0.2.0")),
+    /// ]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn imported_packages(&self) -> impl Iterator<Item = (&str, Option<&str>)> + '_ {
+        let packages = self
+            .env_component()
+            .import_types
+            .values()
+            .filter_map(|(name, _)| package_name_and_version(name))
+            .collect::<BTreeSet<_>>();
+        packages.into_iter()
+    }
+
     /// Returns the type of this component as a [`types::Component`].
     ///
     /// This method enables runtime introspection of the type of a component
@@ -446,7 +500,14 @@ impl Component {
         // Assemble the `EngineCode` artifact which is shared by all core wasm
         // modules as well as the final component.
         let types = Arc::new(types);
-        let code = Arc::new(EngineCode::new(code_memory, signatures, types.into())?);
+        let code = Arc::new(EngineCode::new(
+            engine,
+            code_memory,
+            signatures,
+            types.into(),
+            CodeKind::Component,
+            None,
+        )?);
 
         // Convert all information about static core wasm modules into actual
         // `Module` instances by converting each `CompiledModuleInfo`, the
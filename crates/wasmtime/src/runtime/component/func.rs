@@ -682,7 +682,36 @@ impl Func {
         let param_ty = InterfaceType::Tuple(cx.types[ty].params);
         let result = lower(&mut cx, param_ty);
         unsafe { flags.set_may_leave(true) };
-        result
+        result.map_err(|e| ReallocFailure::name_function(e, || self.export_name(store.0)))
+    }
+
+    /// Returns the interface, if any, and name under which this function is
+    /// exported from its component.
+    fn export_name(&self, store: &StoreOpaque) -> Option<(Option<String>, String)> {
+        use wasmtime_environ::collections::TryString;
+        use wasmtime_environ::component::{Component, ComponentExternData, NameMap};
+
+        fn find(
+            component: &Component,
+            exports: &NameMap<TryString, (ExportIndex, ComponentExternData)>,
+            interface: Option<&str>,
+            index: ExportIndex,
+        ) -> Option<(Option<String>, String)> {
+            for (name, (export, _)) in exports.raw_iter() {
+                if *export == index {
+                    return Some((interface.map(str::to_string), name.to_string()));
+                }
+                let item = &component.export_items[*export];
+                if let wasmtime_environ::component::Export::Instance { exports, .. } = item {
+                    if let Some(found) = find(component, exports, Some(name), index) {
+                        return Some(found);
+                    }
+                }
+            }
+            None
+        }
+        let component = self.instance.id().get(store).component().env_component();
+        find(component, &component.exports, None, self.index)
     }
 
     /// Creates a `LiftContext` using the configuration values with this lifted
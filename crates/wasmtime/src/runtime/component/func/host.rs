@@ -2,7 +2,8 @@
 
 #[cfg(feature = "component-model-async")]
 use crate::component::concurrent::{self, Accessor, Status};
-use crate::component::func::{LiftContext, LowerContext};
+use crate::component::func::{LiftContext, LowerContext, ReallocFailure};
+use crate::component::instance::RuntimeImport;
 use crate::component::matching::InstanceType;
 use crate::component::storage::{slice_to_storage, slice_to_storage_mut};
 use crate::component::types::ComponentFunc;
@@ -12,7 +13,7 @@ use crate::runtime::vm::component::{
     ComponentInstance, VMComponentContext, VMLowering, VMLoweringCallee,
 };
 use crate::runtime::vm::{VMOpaqueContext, VMStore};
-use crate::store::Asyncness;
+use crate::store::{Asyncness, StoreOpaque};
 use crate::{AsContextMut, StoreContextMut, ValRaw};
 use alloc::sync::Arc;
 use core::any::Any;
@@ -243,7 +244,7 @@ impl HostFunc {
     }
 
     pub fn lowering(&self) -> VMLowering {
-        let data = NonNull::from(&*self.func).cast();
+        let data = NonNull::from(self).cast();
         VMLowering {
             callee: NonNull::new(self.entrypoint as *mut _).unwrap().into(),
             data: data.into(),
@@ -255,6 +256,37 @@ impl HostFunc {
     }
 }
 
+/// Names the function imported by `instance` in any [`ReallocFailure`] of
+/// `err`, which happened while lowering its results.
+///
+/// The function is identified by the address of its [`HostFunc`], which is
+/// the `data` of its [`VMLowering`] and unique to each `Arc<HostFunc>`.
+fn name_realloc_failure(err: Error, store: &StoreOpaque, instance: Instance, func: usize) -> Error {
+    ReallocFailure::name_function(err, || {
+        let vminstance = instance.id().get(store);
+        let component = vminstance.component().env_component();
+        let (import, path) =
+            component.imports.iter().find_map(|(index, import)| {
+                match vminstance.runtime_import(index) {
+                    RuntimeImport::Func(f) if Arc::as_ptr(f) as usize == func => Some(import),
+                    _ => None,
+                }
+            })?;
+        let (name, _) = &component.import_types[*import];
+        match path.split_last() {
+            None => Some((None, name.clone())),
+            Some((function, rest)) => {
+                let mut interface = name.clone();
+                for segment in rest {
+                    interface.push('/');
+                    interface.push_str(segment);
+                }
+                Some((Some(interface), function.clone()))
+            }
+        }
+    })
+}
+
 /// Argument to [`HostFn::lift_params`]
 enum Source<'a> {
     /// The parameters come from flat wasm arguments which are provided here.
@@ -315,7 +347,7 @@ where
     /// upholds at least these invariants:
     ///
     /// * `cx` is a valid pointer which comes from calling wasm.
-    /// * `data` is a valid pointer to `Self`
+    /// * `data` is a valid pointer to a `HostFunc` whose `func` is `Self`
     /// * `ty` and `options` are valid within the context of `cx`
     /// * `storage` and `storage_len` are valid pointers and correspond to
     ///   correctly initialized wasm arguments/results according to the
@@ -344,8 +376,16 @@ where
                 let ty = TypeFuncIndex::from_u32(ty);
                 let options = OptionsIndex::from_u32(options);
                 let storage = NonNull::slice_from_raw_parts(storage, storage_len).as_mut();
-                let data = data.cast::<Self>().as_ref();
-                data.entrypoint(store.as_context_mut(), instance, ty, options, storage)
+                let host = data.cast::<HostFunc>().as_ref();
+                let func = NonNull::from(&*host.func).cast::<Self>().as_ref();
+                func.entrypoint(
+                    store.as_context_mut(),
+                    instance,
+                    ty,
+                    options,
+                    storage,
+                    data.as_ptr() as usize,
+                )
             })
         }
     }
@@ -359,6 +399,7 @@ where
         ty: TypeFuncIndex,
         options: OptionsIndex,
         storage: &mut [MaybeUninit<ValRaw>],
+        host: usize,
     ) -> Result<()> {
         let vminstance = instance.id().get(store.0);
         let async_ = vminstance.component().env_component().options[options].async_;
@@ -373,7 +414,7 @@ where
         if async_ {
             #[cfg(feature = "component-model-async")]
             {
-                self.call_async_lower(store.as_context_mut(), instance, ty, options, storage)
+                self.call_async_lower(store.as_context_mut(), instance, ty, options, storage, host)
             }
             #[cfg(not(feature = "component-model-async"))]
             unreachable!(
@@ -381,7 +422,7 @@ where
                  when `component-model-async` feature disabled"
             );
         } else {
-            self.call_sync_lower(store.as_context_mut(), instance, ty, options, storage)
+            self.call_sync_lower(store.as_context_mut(), instance, ty, options, storage, host)
         }
     }
 
@@ -399,6 +440,7 @@ where
         ty: TypeFuncIndex,
         options: OptionsIndex,
         storage: &mut [MaybeUninit<ValRaw>],
+        host: usize,
     ) -> Result<()> {
         let entered_host_task = store.0.host_task_create()?;
 
@@ -432,6 +474,7 @@ where
         lower.validate_scope_exit()?;
         lower.store.0.host_task_delete(entered_host_task)?;
         Self::lower_raw(&mut lower, ty, ret, dst)
+            .map_err(|e| name_realloc_failure(e, lower.store.0, instance, host))
     }
 
     /// Implementation of the "async" ABI of the component model.
@@ -447,6 +490,7 @@ where
         ty: TypeFuncIndex,
         options: OptionsIndex,
         storage: &mut [MaybeUninit<ValRaw>],
+        host: usize,
     ) -> Result<()> {
         use wasmtime_environ::component::MAX_FLAT_ASYNC_PARAMS;
 
@@ -485,7 +529,8 @@ where
                 let mut lower = LowerContext::new(store, options, instance);
                 lower.validate_scope_exit()?;
                 lower.store.0.host_task_delete(entered_host_task)?;
-                Self::lower_raw(&mut lower, ty, result, Destination::Memory(retptr))?;
+                Self::lower_raw(&mut lower, ty, result, Destination::Memory(retptr))
+                    .map_err(|e| name_realloc_failure(e, lower.store.0, instance, host))?;
                 Status::Returned.pack(None)
             }
             HostResult::Future(future) => instance.first_poll(
@@ -504,7 +549,8 @@ where
                     // non-immediate exit which is not correct. There's no real
                     // right answer here, hence the upstream issue.
                     if let Some(result) = ret {
-                        Self::lower_raw(&mut lower, ty, result, Destination::Memory(retptr))?;
+                        Self::lower_raw(&mut lower, ty, result, Destination::Memory(retptr))
+                            .map_err(|e| name_realloc_failure(e, lower.store.0, instance, host))?;
                     }
                     Ok(())
                 },
@@ -178,22 +178,44 @@ impl<'a, T: 'static> LowerContext<'a, T> {
 
         // Invoke the wasm malloc function using its raw and statically known
         // signature.
+        let grow_failures = store.memory_grow_failures();
         let result = unsafe {
-            ReallocFunc::call_raw(&mut StoreContextMut(store), &realloc_ty, realloc, params)?
+            ReallocFunc::call_raw(&mut StoreContextMut(store), &realloc_ty, realloc, params)
         };
 
-        if result % old_align != 0 {
-            bail!("realloc return: result not aligned");
-        }
+        // Describe any failure below with as much context as is available to
+        // help distinguish allocator bugs from genuinely running out of
+        // memory.
+        let failure = |cx: &mut Self, kind, returned_ptr| ReallocFailure {
+            kind,
+            old_ptr: old,
+            old_size,
+            align: old_align,
+            new_size,
+            returned_ptr,
+            memory_size: cx.as_slice_mut().len(),
+            memory_grow_failures: cx.store.0.memory_grow_failures() - grow_failures,
+            interface: None,
+            function: None,
+        };
+
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => return Err(e.context(failure(self, ReallocFailureKind::Trapped, None))),
+        };
         let result = usize::try_from(result)?;
 
+        if result % usize::try_from(old_align)? != 0 {
+            bail!(failure(self, ReallocFailureKind::Misaligned, Some(result)));
+        }
+
         if self
             .as_slice_mut()
             .get_mut(result..)
             .and_then(|s| s.get_mut(..new_size))
             .is_none()
         {
-            bail!("realloc return: beyond end of memory")
+            bail!(failure(self, ReallocFailureKind::OutOfBounds, Some(result)));
         }
 
         // Note that this restoration isn't part of a `Drop` guard which works
@@ -347,6 +369,9 @@ pub struct LiftContext<'a> {
     /// This is decremented for strings/lists, for example, to cap the size of
     /// data the host allocates on behalf of the guest.
     hostcall_fuel: usize,
+
+    /// Whether lists of numbers lifted into a `Val` use `Val::PackedList`.
+    packed_lists: bool,
 }
 
 #[doc(hidden)]
@@ -360,6 +385,7 @@ impl<'a> LiftContext<'a> {
     ) -> Result<LiftContext<'a>> {
         let store_id = store.id();
         let hostcall_fuel = store.hostcall_fuel();
+        let packed_lists = store.packed_lists();
         let current_scope_id = store.current_scope_id()?;
         // From `&mut StoreOpaque` provided the goal here is to project out
         // three different disjoint fields owned by the store: memory,
@@ -385,6 +411,7 @@ impl<'a> LiftContext<'a> {
             host_table,
             host_resource_data,
             hostcall_fuel,
+            packed_lists,
         })
     }
 
@@ -519,6 +546,12 @@ impl<'a> LiftContext<'a> {
         Ok(())
     }
 
+    /// Returns whether lists of numbers lifted into a `Val` should use
+    /// `Val::PackedList`.
+    pub(crate) fn packed_lists(&self) -> bool {
+        self.packed_lists
+    }
+
     /// Same as [`Self::consume_fuel`], but safely multiplies `len` and `size`
     /// together before calling that.
     pub fn consume_fuel_array(&mut self, len: usize, size: usize) -> Result<()> {
@@ -529,6 +562,118 @@ impl<'a> LiftContext<'a> {
     }
 }
 
+/// Description of a failed call to a component's `realloc` function made while
+/// lowering values into its linear memory.
+///
+/// This is either the error itself, when `realloc` returned an invalid
+/// pointer, or is attached as context to the error that `realloc` trapped
+/// with. In both cases it can be retrieved with
+/// [`Error::downcast_ref`](crate::Error::downcast_ref).
+#[derive(Debug, Clone)]
+pub struct ReallocFailure {
+    /// How the call to `realloc` failed.
+    pub kind: ReallocFailureKind,
+    /// The original pointer passed to `realloc`.
+    pub old_ptr: usize,
+    /// The original size passed to `realloc`.
+    pub old_size: usize,
+    /// The alignment requested of `realloc`.
+    pub align: u32,
+    /// The number of bytes requested of `realloc`.
+    pub new_size: usize,
+    /// The pointer returned by `realloc`, or `None` if it trapped.
+    pub returned_ptr: Option<usize>,
+    /// The size of linear memory, in bytes, after `realloc` returned.
+    pub memory_size: usize,
+    /// The number of `memory.grow` instructions which failed during the call
+    /// to `realloc`, for example due to a
+    /// [`ResourceLimiter`](crate::ResourceLimiter) denying the growth.
+    ///
+    /// A nonzero value here typically indicates that a memory limit was hit
+    /// rather than that the guest's allocator is misbehaving.
+    pub memory_grow_failures: u64,
+    /// The interface which `function` belongs to, or `None` if it was
+    /// imported or exported on its own rather than as part of an interface.
+    pub interface: Option<String>,
+    /// The name of the function whose parameters or results were being
+    /// lowered, if known.
+    ///
+    /// This is the name of an exported function of the component when its
+    /// parameters are lowered for a call from the host, or the name of an
+    /// imported function when the results of a host function are lowered.
+    pub function: Option<String>,
+}
+
+impl ReallocFailure {
+    /// Names the function being called in the `ReallocFailure` within `err`,
+    /// if there is one which isn't already named.
+    ///
+    /// Names are only looked up through `name` on failure since the lowering
+    /// itself doesn't know which function it's lowering for.
+    pub(crate) fn name_function(
+        mut err: Error,
+        name: impl FnOnce() -> Option<(Option<String>, String)>,
+    ) -> Error {
+        if let Some(failure) = err.downcast_mut::<ReallocFailure>() {
+            if failure.function.is_none() {
+                if let Some((interface, function)) = name() {
+                    failure.interface = interface;
+                    failure.function = Some(function);
+                }
+            }
+        }
+        err
+    }
+}
+
+/// The different ways in which a call to `realloc` can fail, as described by
+/// [`ReallocFailure`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReallocFailureKind {
+    /// The `realloc` function trapped.
+    Trapped,
+    /// The `realloc` function returned a pointer which isn't aligned to the
+    /// requested alignment.
+    Misaligned,
+    /// The `realloc` function returned a pointer such that the requested
+    /// number of bytes doesn't fit within linear memory.
+    OutOfBounds,
+}
+
+impl fmt::Display for ReallocFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ReallocFailureKind::Trapped => write!(f, "realloc trapped")?,
+            ReallocFailureKind::Misaligned => write!(f, "realloc return: result not aligned")?,
+            ReallocFailureKind::OutOfBounds => write!(f, "realloc return: beyond end of memory")?,
+        }
+        match (&self.interface, &self.function) {
+            (Some(interface), Some(function)) => write!(f, " in `{interface}#{function}`")?,
+            (None, Some(function)) => write!(f, " in `{function}`")?,
+            _ => {}
+        }
+        write!(
+            f,
+            " (requested 0x{:x} bytes with alignment {}",
+            self.new_size, self.align
+        )?;
+        if let Some(ptr) = self.returned_ptr {
+            write!(f, ", returned 0x{ptr:x}")?;
+        }
+        write!(f, ", linear memory of size 0x{:x}", self.memory_size)?;
+        if self.memory_grow_failures > 0 {
+            write!(
+                f,
+                ", {} failed memory.grow during the call",
+                self.memory_grow_failures
+            )?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl core::error::Error for ReallocFailure {}
+
 #[derive(Debug)]
 struct HostcallFuelExhausted;
 
@@ -1,3 +1,6 @@
+use crate::HasExtensions;
+use core::any::Any;
+
 /// A trait used as part of [`bindgen!`] to indicate a `Data<'_>` payload that
 /// implements some host bindings traits.
 ///
@@ -303,3 +306,99 @@ pub struct HasSelf<T: ?Sized>(core::marker::PhantomData<T>);
 impl<T: ?Sized + 'static> HasData for HasSelf<T> {
     type Data<'a> = &'a mut T;
 }
+
+/// An implementation of the [`HasData`] trait for state stored in the
+/// [`Extensions`] of a store rather than a field of its `T`.
+///
+/// This is used in conjunction with [`expect_extension`] as the getter passed to
+/// `add_to_linker` functions generated by [`bindgen!`]. This enables
+/// libraries to implement host interfaces using their own state, attached to
+/// the store independently of the embedder's `T`.
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::component::{bindgen, expect_extension, HasExtension, Linker};
+/// use wasmtime::{Engine, Extensions, Result, Store};
+///
+/// bindgen!({
+///     inline: "
+///         package my:counter;
+///         interface counter {
+///             next: func() -> u32;
+///         }
+///         world my-world {
+///             import counter;
+///         }
+///     ",
+/// });
+///
+/// #[derive(Default)]
+/// struct Counter(u32);
+///
+/// impl my::counter::counter::Host for Counter {
+///     fn next(&mut self) -> u32 {
+///         self.0 += 1;
+///         self.0
+///     }
+/// }
+///
+/// fn main() -> Result<()> {
+///     let engine = Engine::default();
+///     let mut linker = Linker::<Extensions>::new(&engine);
+///     my::counter::counter::add_to_linker::<_, HasExtension<Counter>>(
+///         &mut linker,
+///         expect_extension,
+///     )?;
+///
+///     let mut store = Store::new(&engine, Extensions::new());
+///     store.extensions_mut().insert(Counter::default());
+///     // ... instantiate and use components with `linker` and `store` ...
+///     # let _ = store;
+///     Ok(())
+/// }
+/// ```
+///
+/// [`bindgen!`]: super::bindgen
+/// [`Extensions`]: crate::Extensions
+pub struct HasExtension<T: ?Sized>(core::marker::PhantomData<T>);
+
+impl<T: ?Sized + 'static> HasData for HasExtension<T> {
+    type Data<'a> = &'a mut T;
+}
+
+/// Projects from the `T` of a store to the value of type `U` in its
+/// [`Extensions`], if one has been inserted.
+///
+/// See [`expect_extension`] for a version of this which can be used as the
+/// getter of `add_to_linker` functions generated by [`bindgen!`].
+///
+/// [`bindgen!`]: super::bindgen
+/// [`Extensions`]: crate::Extensions
+pub fn get_extension<T, U>(data: &mut T) -> Option<&mut U>
+where
+    T: HasExtensions,
+    U: Any + Send + Sync,
+{
+    data.extensions_mut().get_mut()
+}
+
+/// Projects from the `T` of a store to the value of type `U` in its
+/// [`Extensions`], for use with [`HasExtension`].
+///
+/// # Panics
+///
+/// Panics if no value of type `U` has been inserted into the extensions. When
+/// this getter is used by a host function the panic propagates out of the call
+/// into wasm like any other panic in a host function, so the extension should
+/// be inserted before the store is used to call wasm. Use [`get_extension`] to
+/// check whether an extension is present.
+///
+/// [`Extensions`]: crate::Extensions
+pub fn expect_extension<T, U>(data: &mut T) -> &mut U
+where
+    T: HasExtensions,
+    U: Any + Send + Sync,
+{
+    data.extensions_mut().expect_mut()
+}
@@ -836,8 +836,14 @@ impl<'a> Instantiator<'a> {
                     // if required.
 
                     let i = unsafe {
-                        crate::Instance::new_started(store, module, imports.as_ref(), asyncness)
-                            .await?
+                        crate::Instance::new_started(
+                            store,
+                            module,
+                            imports.as_ref(),
+                            &[],
+                            asyncness,
+                        )
+                        .await?
                     };
 
                     if exit {
@@ -1189,6 +1195,7 @@ impl<T: 'static> InstancePre<T> {
         // Everything has passed, don't decrement the instance count and let the
         // destructor for the `Store` handle that at this point.
         decrement.enabled = false;
+        decrement.store.engine().counters().component_instantiated();
         return Ok(instance);
 
         struct DecrementComponentInstanceCountOnDrop<'a, T: 'static> {
@@ -13,7 +13,7 @@ use alloc::sync::Arc;
 use core::marker;
 #[cfg(feature = "component-model-async")]
 use core::pin::Pin;
-use wasmtime_environ::component::{NameMap, NameMapIntern};
+use wasmtime_environ::component::{NameMap, NameMapIntern, package_name_and_version};
 use wasmtime_environ::{Atom, PrimaryMap, StringPool};
 
 /// A type used to instantiate [`Component`]s.
@@ -164,6 +164,83 @@ impl<T: 'static> Linker<T> {
         self.root().into_instance(name)
     }
 
+    /// Defines every instance of the WIT package `package` at version `from`
+    /// in this linker again at version `to`, backed by the same definitions.
+    ///
+    /// Versions which are semver-compatible already resolve to one another
+    /// automatically, as described in [the documentation of
+    /// `Linker`](Linker#names-and-semver). This is instead intended for
+    /// versions that semver considers incompatible but which one host
+    /// implementation can still serve, such as prereleases. For example
+    /// aliasing `wasi:http` from `0.2.3` to `0.2.0-rc-2023-12-05` defines
+    /// `wasi:http/types@0.2.0-rc-2023-12-05` with the same host functions and
+    /// resource types as `wasi:http/types@0.2.3`, and likewise for every other
+    /// interface of `wasi:http@0.2.3` in this linker. Use
+    /// [`Component::imported_packages`] to find which versions a component
+    /// needs.
+    ///
+    /// The aliased definitions are type-checked against a component's imports
+    /// at instantiation like any other definition. Compatible changes between
+    /// versions, such as functions added in `from` which `to` doesn't have,
+    /// need no adaptation as definitions a component doesn't import are
+    /// ignored. Incompatible changes, such as a function whose signature
+    /// differs, fail instantiation. Resource types are shared between the two
+    /// versions, so resources created through one version may be passed to
+    /// the other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::{Engine, Result, Store};
+    /// # use wasmtime::component::{Component, Linker};
+    /// # fn main() -> Result<()> {
+    /// # let engine = Engine::default();
+    /// let mut linker = Linker::<()>::new(&engine);
+    /// linker
+    ///     .instance("my:pkg/host@1.2.0")?
+    ///     .func_wrap("answer", |_, ()| Ok((42u32,)))?;
+    /// linker.alias_package_version("my:pkg", "1.2.0", "1.0.0-rc.1")?;
+    ///
+    /// let component = Component::new(&engine, r#"
+    ///     (component
+    ///         (import "my:pkg/host@1.0.0-rc.1" (instance
+    ///             (export "answer" (func (result u32)))
+    ///         ))
+    ///     )
+    /// "#)?;
+    /// let mut store = Store::new(&engine, ());
+    /// linker.instantiate(&mut store, &component)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no instances of `package` are defined at version
+    /// `from` in the root of this linker, or if any of the aliased names is
+    /// already defined and shadowing isn't allowed.
+    pub fn alias_package_version(&mut self, package: &str, from: &str, to: &str) -> Result<()> {
+        let mut aliases = Vec::new();
+        for (name, definition) in self.map.raw_iter() {
+            let name = &self.strings[*name];
+            if !matches!(definition, Definition::Instance(_))
+                || package_name_and_version(name) != Some((package, Some(from)))
+            {
+                continue;
+            }
+            let unversioned = &name[..name.len() - from.len() - 1];
+            aliases.push((format!("{unversioned}@{to}"), definition.try_clone()?));
+        }
+        if aliases.is_empty() {
+            bail!("no instances of `{package}@{from}` are defined in this linker");
+        }
+        let mut root = self.root();
+        for (name, definition) in aliases {
+            root.insert(&name, definition)?;
+        }
+        Ok(())
+    }
+
     fn typecheck<'a>(&'a self, component: &'a Component) -> Result<TypeChecker<'a>> {
         let mut cx = TypeChecker {
             engine: &self.engine,
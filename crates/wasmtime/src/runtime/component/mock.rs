@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use alloc::collections::VecDeque;
+use core::fmt;
+
+/// A recording of calls to, and canned results of, a single mocked host
+/// function.
+///
+/// This type is used by the `Mock` types generated by [`bindgen!`] when the
+/// `mocks: true` option is specified. Each imported interface then has a
+/// `Mock` type implementing its `Host` traits with one public `MockFunc` field
+/// per function, named after the function (and prefixed with the resource
+/// name for resource functions such as `x_new` or `x_drop`).
+///
+/// The parameters `P` of each call are recorded as a tuple, and the result of
+/// each call is taken from the front of a queue of values configured with
+/// [`MockFunc::returns`]. Once the queue is empty results are produced by the
+/// function configured with [`MockFunc::returns_with`], if any. A call for
+/// which no result has been configured panics.
+///
+/// ```
+/// use wasmtime::component::MockFunc;
+///
+/// let mut func = MockFunc::<(u32,), u32>::new("double");
+/// func.returns(0).returns_with(|(x,)| x * 2);
+/// assert_eq!(func.call((1,)), 0);
+/// assert_eq!(func.call((2,)), 4);
+/// assert_eq!(func.calls(), [(1,), (2,)]);
+/// ```
+///
+/// [`bindgen!`]: crate::component::bindgen
+pub struct MockFunc<P, R> {
+    name: &'static str,
+    calls: Vec<P>,
+    results: VecDeque<R>,
+    handler: Option<Box<dyn FnMut(&P) -> R + Send>>,
+}
+
+impl<P, R> MockFunc<P, R> {
+    /// Creates a new mock function named `name` with no recorded calls nor
+    /// configured results.
+    ///
+    /// The `name` is only used in the panic message of calls for which no
+    /// result was configured.
+    pub fn new(name: &'static str) -> MockFunc<P, R> {
+        MockFunc {
+            name,
+            calls: Vec::new(),
+            results: VecDeque::new(),
+            handler: None,
+        }
+    }
+
+    /// Queues `result` to be returned by a future call.
+    ///
+    /// Queued results are returned in the order they were queued, and take
+    /// precedence over a function configured with [`MockFunc::returns_with`].
+    pub fn returns(&mut self, result: R) -> &mut Self {
+        self.results.push_back(result);
+        self
+    }
+
+    /// Configures `f` to produce the result of calls once all queued results
+    /// have been returned, replacing any previously configured function.
+    pub fn returns_with(&mut self, f: impl FnMut(&P) -> R + Send + 'static) -> &mut Self {
+        self.handler = Some(Box::new(f));
+        self
+    }
+
+    /// Returns the parameters of all calls recorded so far, in order.
+    pub fn calls(&self) -> &[P] {
+        &self.calls
+    }
+
+    /// Returns the number of calls recorded so far.
+    pub fn call_count(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Removes and returns the parameters of all calls recorded so far.
+    pub fn take_calls(&mut self) -> Vec<P> {
+        core::mem::take(&mut self.calls)
+    }
+
+    /// Records a call with `params` and returns its configured result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is neither a queued result nor a function configured
+    /// with [`MockFunc::returns_with`].
+    pub fn call(&mut self, params: P) -> R {
+        let result = match self.results.pop_front() {
+            Some(result) => result,
+            None => match &mut self.handler {
+                Some(handler) => handler(&params),
+                None => panic!("no result configured for mocked function `{}`", self.name),
+            },
+        };
+        self.calls.push(params);
+        result
+    }
+}
+
+impl<P: fmt::Debug, R> fmt::Debug for MockFunc<P, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockFunc")
+            .field("name", &self.name)
+            .field("calls", &self.calls)
+            .field("queued_results", &self.results.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -110,6 +110,7 @@ mod has_data;
 mod instance;
 mod linker;
 mod matching;
+mod mock;
 mod resource_table;
 mod resources;
 mod storage;
@@ -126,15 +127,19 @@ pub use self::concurrent::{
     VMComponentAsyncStore, VecBuffer, WriteBuffer,
 };
 pub use self::func::{
-    ComponentNamedList, ComponentType, Func, Lift, Lower, TypedFunc, WasmList, WasmStr,
+    ComponentNamedList, ComponentType, Func, Lift, Lower, ReallocFailure, ReallocFailureKind,
+    TypedFunc, WasmList, WasmStr,
 };
 pub use self::has_data::*;
 pub use self::instance::{Instance, InstancePre};
 pub use self::linker::{Linker, LinkerInstance};
+pub use self::mock::MockFunc;
 pub use self::resource_table::{ResourceTable, ResourceTableError};
-pub use self::resources::{Resource, ResourceAny, ResourceDynamic};
+pub use self::resources::{
+    Resource, ResourceAny, ResourceBroker, ResourceDynamic, ResourceTransfer,
+};
 pub use self::types::{ResourceType, Type};
-pub use self::values::Val;
+pub use self::values::{PackedElement, PackedList, Val};
 
 pub(crate) use self::instance::RuntimeImport;
 pub(crate) use self::resources::HostResourceData;
@@ -510,6 +515,34 @@ pub(crate) use self::store::ComponentStoreData;
 ///     // to also be enabled in the `wasmtime` crate.
 ///     anyhow: false,
 ///
+///     // Path to a function of the shape `fn<F: Future>(F) -> F::Output`, such
+///     // as an executor's `block_on`, used to generate blocking versions of
+///     // `async` imports.
+///     //
+///     // For each imported interface whose functions are all `async` (without
+///     // `store`) this generates a synchronous `HostBlocking` trait, and a
+///     // `Host{Resource}Blocking` trait per resource, implemented for all
+///     // implementors of the `async` traits by blocking on their futures.
+///     // Accompanying `add_to_linker_blocking` functions register these with
+///     // a `Linker` as synchronous functions, enabling the same host
+///     // implementation to be used with stores without async support.
+///     //
+///     // The path is resolved relative to the macro invocation, like the paths
+///     // in `with`. By default no blocking wrappers are generated.
+///     blocking_wrappers: path::to::block_on,
+///
+///     // Whether to generate a `Mock` type for each imported interface, for
+///     // use in tests.
+///     //
+///     // A `Mock` implements all of the `Host` traits of its interface and
+///     // has a public `MockFunc` field per function which records the
+///     // parameters of each call and returns configurable canned results.
+///     // Mocks aren't generated for interfaces with functions configured with
+///     // `store` or declared `async` in WIT.
+///     //
+///     // This option defaults to false.
+///     mocks: false,
+///
 ///     // This is an in-source alternative to using `WASMTIME_DEBUG_BINDGEN`.
 ///     //
 ///     // Note that if this option is specified then the compiler will always
@@ -537,7 +570,9 @@ pub use wasmtime_component_macro::bindgen;
 ///
 /// This macro supports a `#[component]` attribute which is used to customize
 /// how the type is bound to the component model. A top-level `#[component]`
-/// attribute is required to specify either `record`, `enum`, or `variant`.
+/// attribute specifies either `record`, `enum`, or `variant`. It may be
+/// omitted for `struct`s, which are bound as `record`s, and for `enum`s with a
+/// payload in at least one case, which are bound as `variant`s.
 ///
 /// ## Records
 ///
@@ -589,6 +624,45 @@ pub use wasmtime_component_macro::bindgen;
 /// Also note that field ordering is significant at this time and must match
 /// WIT.
 ///
+/// ## Naming
+///
+/// By default the names of fields and cases in the component model are the
+/// same as their names in Rust. A top-level `#[component(rename_all =
+/// "kebab-case")]` attribute instead converts Rust `snake_case` and
+/// `UpperCamelCase` names to the `kebab-case` used by WIT. An explicit
+/// `#[component(name = "...")]` on a field or case takes precedence.
+///
+/// ```rust
+/// use wasmtime::component::ComponentType;
+///
+/// // Binds `record request { user-id: u64, max-results: u32 }`.
+/// #[derive(ComponentType)]
+/// #[component(rename_all = "kebab-case")]
+/// struct Request {
+///     user_id: u64,
+///     max_results: u32,
+/// }
+/// ```
+///
+/// ## Borrowed data
+///
+/// Fields and payloads may borrow strings and lists as `&str` and `&[T]`.
+/// Such types implement [`ComponentType`] and [`Lower`], but not [`Lift`], so
+/// they can be passed as parameters to WebAssembly without first being copied
+/// into a `String` or `Vec<T>` on the host:
+///
+/// ```rust
+/// use wasmtime::component::{ComponentType, Lower};
+///
+/// // Binds `record upload { name: string, chunks: list<list<u8>>, checksum: option<u32> }`.
+/// #[derive(ComponentType, Lower)]
+/// struct Upload<'a> {
+///     name: &'a str,
+///     chunks: &'a [&'a [u8]],
+///     checksum: Option<u32>,
+/// }
+/// ```
+///
 /// ## Variants
 ///
 /// `variant`s in the component model correspond to a subset of shapes of a Rust
@@ -626,15 +700,33 @@ pub use wasmtime_component_macro::bindgen;
 /// U)` or `Foo { name: T }` are not supported at this time.
 ///
 /// Note that the order of variants in Rust must match the order of variants in
-/// WIT. Additionally it's likely that `#[component(name = "...")]` is required
-/// on all Rust `enum` variants because the name currently defaults to the Rust
-/// name which is typically UpperCamelCase whereas WIT uses kebab-case.
+/// WIT. Additionally it's likely that either `#[component(name = "...")]` is
+/// required on all Rust `enum` variants or `#[component(rename_all =
+/// "kebab-case")]` is required on the `enum` because the name defaults to the
+/// Rust name which is typically UpperCamelCase whereas WIT uses kebab-case.
+///
+/// Payloads may themselves be any type which implements [`ComponentType`],
+/// including nested `Option`s and `Result`s:
+///
+/// ```rust
+/// use wasmtime::component::{ComponentType, Lift, Lower};
+///
+/// // Binds `variant lookup { missing, found(option<result<u32, string>>) }`.
+/// #[derive(ComponentType, Lift, Lower)]
+/// #[component(rename_all = "kebab-case")]
+/// enum Lookup {
+///     Missing,
+///     Found(Option<Result<u32, String>>),
+/// }
+/// ```
 ///
 /// ## Enums
 ///
 /// `enum`s in the component model correspond to C-like `enum`s in Rust. Note
 /// that a component model `enum` does not allow any payloads so the Rust `enum`
-/// must additionally have no payloads.
+/// must additionally have no payloads. The `#[component(enum)]` attribute is
+/// always required as a Rust `enum` without payloads could also be bound as a
+/// `variant`.
 ///
 /// ```rust
 /// use wasmtime::component::ComponentType;
@@ -663,9 +755,10 @@ pub use wasmtime_component_macro::bindgen;
 /// ```
 ///
 /// Note that the order of variants in Rust must match the order of variants in
-/// WIT. Additionally it's likely that `#[component(name = "...")]` is required
-/// on all Rust `enum` variants because the name currently defaults to the Rust
-/// name which is typically UpperCamelCase whereas WIT uses kebab-case.
+/// WIT. Additionally it's likely that either `#[component(name = "...")]` is
+/// required on all Rust `enum` variants or `#[component(rename_all =
+/// "kebab-case")]` is required on the `enum` because the name defaults to the
+/// Rust name which is typically UpperCamelCase whereas WIT uses kebab-case.
 pub use wasmtime_component_macro::ComponentType;
 
 /// A derive macro for generating implementations of the [`Lift`] trait.
@@ -17,6 +17,9 @@ pub enum ResourceTableError {
     /// Resource cannot be deleted because child resources exist in the table. Consult wit docs for
     /// the particular resource to see which methods may return child resources.
     HasChildren,
+    /// Resource cannot be moved to another table because it is the child of
+    /// another resource in this table.
+    HasParent,
 }
 
 impl fmt::Display for ResourceTableError {
@@ -26,6 +29,7 @@ impl fmt::Display for ResourceTableError {
             Self::NotPresent => write!(f, "resource not present"),
             Self::WrongType => write!(f, "resource is of another type"),
             Self::HasChildren => write!(f, "resource has children"),
+            Self::HasParent => write!(f, "resource has a parent"),
         }
     }
 }
@@ -373,6 +377,22 @@ impl ResourceTable {
         Ok(e)
     }
 
+    /// Checks that the entry at `key` is a `T` which can be removed from this
+    /// table without leaving behind any parent or child relationships.
+    pub(crate) fn check_detachable<T: Any>(&self, key: u32) -> Result<(), ResourceTableError> {
+        let entry = self.occupied(key)?;
+        if !entry.entry.is::<T>() {
+            return Err(ResourceTableError::WrongType);
+        }
+        if !entry.children.is_empty() {
+            return Err(ResourceTableError::HasChildren);
+        }
+        if entry.parent.is_some() {
+            return Err(ResourceTableError::HasParent);
+        }
+        Ok(())
+    }
+
     /// Zip the values of the map with mutable references to table entries corresponding to each
     /// key. As the keys in the `BTreeMap` are unique, this iterator can give mutable references
     /// with the same lifetime as the mutable reference to the [ResourceTable].
@@ -1,4 +1,5 @@
 mod any;
+mod broker;
 mod host;
 mod host_dynamic;
 mod host_static;
@@ -6,6 +7,7 @@ mod host_tables;
 mod ty;
 
 pub use any::*;
+pub use broker::*;
 pub use host_dynamic::*;
 pub use host_static::*;
 pub use host_tables::*;
@@ -0,0 +1,255 @@
+//! This module defines the `ResourceBroker` type in the public API of
+//! Wasmtime, which moves owned host resources from one store to another.
+//!
+//! A `Resource<T>` is only a 32-bit `rep` whose meaning is defined by the
+//! embedder, typically as an index into a `ResourceTable` within a store. The
+//! broker moves both halves of that pair at once: the rep is released from the
+//! sending store, its `T` is taken out of the sending store's table, and on
+//! the receiving end the `T` is placed in the receiving store's table under a
+//! new rep.
+
+use crate::component::{Resource, ResourceTable};
+use crate::prelude::*;
+use crate::{AsContextMut, Engine};
+use alloc::sync::Arc;
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+/// A broker which transfers owned host resources between [`Store`]s of the
+/// same [`Engine`].
+///
+/// Resources in the component model are scoped to a single store, so a
+/// [`Resource<T>`] can't be passed directly to another store even if both use
+/// the same [`Engine`]. Instead [`ResourceBroker::send`] removes a resource,
+/// and its entry in a [`ResourceTable`], from one store and returns a
+/// [`ResourceTransfer`] which can be sent to another thread and later placed
+/// in another store with [`ResourceTransfer::receive`], for example to hand
+/// off a connection to a different worker.
+///
+/// Only owned host-defined resources can be transferred: guest-defined
+/// resources are tied to the component instance which defined them, and
+/// borrowed resources are only valid for the duration of a call.
+///
+/// Brokers are cheap to clone and all clones share the same accounting of
+/// transfers, see [`ResourceBroker::pending`] and
+/// [`ResourceBroker::discarded`].
+///
+/// # Destructors
+///
+/// A transferred resource's `T` is only dropped once, wherever it ends up:
+///
+/// * Once received, the resource is owned by the receiving store like any
+///   other resource. If it's passed to a guest there, then the destructor
+///   configured with [`LinkerInstance::resource`] for the receiving store is
+///   the one invoked when the guest drops it.
+/// * If a [`ResourceTransfer`] is dropped without being received, then its `T`
+///   is dropped along with it and the transfer is counted in
+///   [`ResourceBroker::discarded`].
+///
+/// No destructor runs in the sending store as the resource is no longer owned
+/// by it.
+///
+/// [`Store`]: crate::Store
+/// [`LinkerInstance::resource`]: crate::component::LinkerInstance::resource
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::{Engine, Result, Store};
+/// use wasmtime::component::{ResourceBroker, ResourceTable};
+///
+/// struct Connection {
+///     id: u32,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let engine = Engine::default();
+/// let broker = ResourceBroker::new(&engine);
+///
+/// let mut store1 = Store::new(&engine, ResourceTable::new());
+/// let mut store2 = Store::new(&engine, ResourceTable::new());
+///
+/// let conn = store1.data_mut().push(Connection { id: 42 })?;
+/// let transfer = broker.send(&mut store1, conn, |table| table)?;
+/// assert!(store1.data().is_empty());
+/// assert_eq!(broker.pending(), 1);
+///
+/// // ... `transfer` can be sent to another thread here ...
+///
+/// let conn = transfer.receive(&mut store2, |table| table)?;
+/// assert_eq!(store2.data().get(&conn)?.id, 42);
+/// assert_eq!(broker.pending(), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ResourceBroker {
+    inner: Arc<BrokerInner>,
+}
+
+struct BrokerInner {
+    engine: Engine,
+    pending: AtomicUsize,
+    discarded: AtomicUsize,
+}
+
+impl ResourceBroker {
+    /// Creates a new broker for transferring resources between stores
+    /// belonging to `engine`.
+    pub fn new(engine: &Engine) -> ResourceBroker {
+        ResourceBroker {
+            inner: Arc::new(BrokerInner {
+                engine: engine.clone(),
+                pending: AtomicUsize::new(0),
+                discarded: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Returns the [`Engine`] this broker was created for.
+    pub fn engine(&self) -> &Engine {
+        &self.inner.engine
+    }
+
+    /// Removes the owned `resource` from `store`, and its value from the
+    /// [`ResourceTable`] returned by `table`, for transfer to another store.
+    ///
+    /// The `table` closure is used to project the table that `resource` lives
+    /// in out of the store's data, and may be called more than once.
+    ///
+    /// # Errors
+    ///
+    /// This returns an error, leaving `resource` and its table entry
+    /// untouched, if:
+    ///
+    /// * `store` doesn't belong to this broker's [`Engine`].
+    /// * `resource` isn't present in the table with type `T`.
+    /// * `resource` has children or a parent in the table. Transferring such a
+    ///   resource on its own would break the relationship between the two.
+    /// * `resource` is a borrow, has already been moved into a guest, or is
+    ///   currently borrowed by a guest.
+    ///
+    /// # Panics
+    ///
+    /// This may panic if `resource` was previously used with a different
+    /// store.
+    pub fn send<D, T>(
+        &self,
+        mut store: impl AsContextMut<Data = D>,
+        resource: Resource<T>,
+        mut table: impl FnMut(&mut D) -> &mut ResourceTable,
+    ) -> Result<ResourceTransfer<T>>
+    where
+        D: 'static,
+        T: Send + 'static,
+    {
+        let mut store = store.as_context_mut();
+        if !Engine::same(store.engine(), &self.inner.engine) {
+            bail!("cannot send a resource from a store of a different engine than the broker");
+        }
+        if !resource.owned() {
+            bail!("cannot send a `borrow` resource");
+        }
+        table(store.data_mut()).check_detachable::<T>(resource.rep())?;
+        let rep = resource.release_own(&mut store)?;
+        let value = table(store.data_mut()).delete(Resource::<T>::new_own(rep))?;
+        self.inner.pending.fetch_add(1, Relaxed);
+        Ok(ResourceTransfer {
+            value: Some(value),
+            broker: self.clone(),
+        })
+    }
+
+    /// Returns the number of transfers which have been sent but not yet
+    /// received or discarded.
+    pub fn pending(&self) -> usize {
+        self.inner.pending.load(Relaxed)
+    }
+
+    /// Returns the number of transfers which were dropped, along with the
+    /// value of their resource, without being received.
+    pub fn discarded(&self) -> usize {
+        self.inner.discarded.load(Relaxed)
+    }
+}
+
+impl fmt::Debug for ResourceBroker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceBroker")
+            .field("pending", &self.pending())
+            .field("discarded", &self.discarded())
+            .finish()
+    }
+}
+
+/// An owned host resource in transit between two stores, created with
+/// [`ResourceBroker::send`].
+///
+/// This holds the value removed from the sending store's [`ResourceTable`] and
+/// can be sent to other threads. It's placed in its new store with
+/// [`ResourceTransfer::receive`]. If it's dropped instead then the value is
+/// dropped along with it.
+pub struct ResourceTransfer<T: Send + 'static> {
+    value: Option<T>,
+    broker: ResourceBroker,
+}
+
+impl<T: Send + 'static> ResourceTransfer<T> {
+    /// Places this resource's value in the [`ResourceTable`] returned by
+    /// `table` within `store`, returning the new owned resource for it.
+    ///
+    /// The returned resource has a new `rep` which is unrelated to the `rep`
+    /// the resource had in the sending store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `store` doesn't belong to the broker's [`Engine`]
+    /// or if the table is full. The value is dropped in that case as with
+    /// dropping the transfer.
+    pub fn receive<D>(
+        mut self,
+        mut store: impl AsContextMut<Data = D>,
+        table: impl FnOnce(&mut D) -> &mut ResourceTable,
+    ) -> Result<Resource<T>>
+    where
+        D: 'static,
+    {
+        let mut store = store.as_context_mut();
+        if !Engine::same(store.engine(), &self.broker.inner.engine) {
+            bail!("cannot receive a resource into a store of a different engine than the broker");
+        }
+        let value = self.value.take().unwrap();
+        let inner = &self.broker.inner;
+        inner.pending.fetch_sub(1, Relaxed);
+        match table(store.data_mut()).push(value) {
+            Ok(resource) => Ok(resource),
+            Err(e) => {
+                inner.discarded.fetch_add(1, Relaxed);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Returns the broker this transfer was sent through.
+    pub fn broker(&self) -> &ResourceBroker {
+        &self.broker
+    }
+}
+
+impl<T: Send + 'static> Drop for ResourceTransfer<T> {
+    fn drop(&mut self) {
+        if self.value.take().is_some() {
+            let inner = &self.broker.inner;
+            inner.pending.fetch_sub(1, Relaxed);
+            inner.discarded.fetch_add(1, Relaxed);
+        }
+    }
+}
+
+impl<T: Send + 'static> fmt::Debug for ResourceTransfer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceTransfer")
+            .field("type", &core::any::type_name::<T>())
+            .finish_non_exhaustive()
+    }
+}
@@ -283,6 +283,25 @@ where
         })
     }
 
+    /// Releases this owned resource from any tracking within `store`,
+    /// returning its `rep` and marking this resource as consumed.
+    ///
+    /// This fails for borrowed resources, resources already moved into a
+    /// guest, and resources which are currently borrowed by a guest.
+    pub fn release_own(&self, mut store: impl AsContextMut) -> Result<u32> {
+        let rep = match self.state.get() {
+            ResourceState::Borrow => bail!("cannot release a `borrow` resource"),
+            ResourceState::Taken => bail!("host resource already consumed"),
+            ResourceState::NotInTable => self.rep,
+            ResourceState::Index(idx) => {
+                let store = store.as_context_mut();
+                HostResourceTables::new_host(store.0)?.host_resource_lift_own(idx)?
+            }
+        };
+        self.state.swap(ResourceState::Taken);
+        Ok(rep)
+    }
+
     pub fn try_into_resource_any(self, mut store: impl AsContextMut) -> Result<ResourceAny> {
         let HostResource {
             rep,
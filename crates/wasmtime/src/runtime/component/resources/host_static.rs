@@ -213,6 +213,13 @@ where
     pub fn try_into_resource_any(self, store: impl AsContextMut) -> Result<ResourceAny> {
         self.0.try_into_resource_any(store)
     }
+
+    /// Releases this owned resource from `store`, returning its `rep`.
+    ///
+    /// See [`ResourceBroker::send`](crate::component::ResourceBroker::send).
+    pub(crate) fn release_own(&self, store: impl AsContextMut) -> Result<u32> {
+        self.0.release_own(store)
+    }
 }
 
 unsafe impl<T: 'static> ComponentType for Resource<T> {
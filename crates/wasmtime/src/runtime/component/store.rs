@@ -56,6 +56,14 @@ pub struct ComponentStoreData {
     /// Caps the size of the allocations made on the host to this amount
     /// effectively.
     hostcall_fuel: usize,
+
+    /// Whether lists of primitive numbers lifted into a [`Val`] are
+    /// represented as [`Val::PackedList`] rather than [`Val::List`].
+    ///
+    /// [`Val`]: crate::component::Val
+    /// [`Val::PackedList`]: crate::component::Val::PackedList
+    /// [`Val::List`]: crate::component::Val::List
+    packed_lists: bool,
 }
 
 /// State tracking for tasks within components.
@@ -104,6 +112,7 @@ impl ComponentStoreData {
                 ComponentTaskState::NotConcurrent(Default::default())
             },
             hostcall_fuel: DEFAULT_HOSTCALL_FUEL,
+            packed_lists: false,
         }
     }
 
@@ -447,6 +456,14 @@ impl StoreOpaque {
         self.component_data_mut().hostcall_fuel = fuel;
     }
 
+    pub(crate) fn packed_lists(&self) -> bool {
+        self.component_data().packed_lists
+    }
+
+    pub(crate) fn set_packed_lists(&mut self, enabled: bool) {
+        self.component_data_mut().packed_lists = enabled;
+    }
+
     #[cfg(feature = "component-model-async")]
     fn concurrent_resource_table(&mut self) -> Option<&mut ResourceTable> {
         if self.concurrency_support() {
@@ -510,6 +527,44 @@ impl<T> Store<T> {
         self.as_context_mut().set_hostcall_fuel(fuel)
     }
 
+    /// Returns whether lists of primitive numbers are lifted as
+    /// [`Val::PackedList`].
+    ///
+    /// See [`Store::set_packed_lists`] for more details.
+    ///
+    /// [`Val::PackedList`]: crate::component::Val::PackedList
+    pub fn packed_lists(&self) -> bool {
+        self.as_context().0.packed_lists()
+    }
+
+    /// Configures whether lists of primitive numbers are lifted as
+    /// [`Val::PackedList`] instead of [`Val::List`].
+    ///
+    /// This only affects the dynamic [`Val`] API, for example the results of
+    /// [`Func::call`] or the parameters passed to functions defined with
+    /// [`LinkerInstance::func_new`]. When enabled, a `list<T>` where `T` is an
+    /// integer or float type is lifted into a single contiguous [`PackedList`]
+    /// with a bulk copy out of linear memory, rather than a [`Val::List`] with
+    /// one [`Val`] per element. This is significantly faster, and uses
+    /// significantly less memory, for large lists.
+    ///
+    /// Hosts which enable this must be prepared to receive
+    /// [`Val::PackedList`] anywhere a list of numbers is expected. Both
+    /// [`Val::List`] and [`Val::PackedList`] are always accepted when lowering
+    /// values into a component, regardless of this setting.
+    ///
+    /// The default value for this is `false`.
+    ///
+    /// [`Val`]: crate::component::Val
+    /// [`Val::List`]: crate::component::Val::List
+    /// [`Val::PackedList`]: crate::component::Val::PackedList
+    /// [`PackedList`]: crate::component::PackedList
+    /// [`Func::call`]: crate::component::Func::call
+    /// [`LinkerInstance::func_new`]: crate::component::LinkerInstance::func_new
+    pub fn set_packed_lists(&mut self, enabled: bool) {
+        self.as_context_mut().set_packed_lists(enabled)
+    }
+
     /// Returns the underlying [`ResourceTable`] that the implementation of
     /// concurrency in the component model is using.
     ///
@@ -533,6 +588,16 @@ impl<T> StoreContextMut<'_, T> {
         self.0.set_hostcall_fuel(fuel)
     }
 
+    /// See [`Store::packed_lists`].
+    pub fn packed_lists(&self) -> bool {
+        self.0.packed_lists()
+    }
+
+    /// See [`Store::set_packed_lists`].
+    pub fn set_packed_lists(&mut self, enabled: bool) {
+        self.0.set_packed_lists(enabled)
+    }
+
     /// See [`Store::concurrent_resource_table`].
     #[cfg(feature = "component-model-async")]
     pub fn concurrent_resource_table(&mut self) -> Option<&mut ResourceTable> {
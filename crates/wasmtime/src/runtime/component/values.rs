@@ -1,7 +1,7 @@
 use crate::ValRaw;
 use crate::component::ResourceAny;
 use crate::component::concurrent::{self, ErrorContext, FutureAny, StreamAny};
-use crate::component::func::{Lift, LiftContext, Lower, LowerContext, desc};
+use crate::component::func::{Lift, LiftContext, Lower, LowerContext, WasmList, desc};
 use crate::prelude::*;
 use core::mem::MaybeUninit;
 use core::slice::{Iter, IterMut};
@@ -79,6 +79,13 @@ pub enum Val {
     Char(char),
     String(String),
     List(Vec<Val>),
+    /// A list of integers or floats stored contiguously.
+    ///
+    /// This is a more compact and efficient alternative to [`Val::List`] for
+    /// lists of numbers. It's accepted anywhere a `list<T>` is expected as long
+    /// as its element type matches `T`, and is only produced by Wasmtime when
+    /// [`Store::set_packed_lists`](crate::Store::set_packed_lists) is enabled.
+    PackedList(PackedList),
     /// A map type represented as a list of key-value pairs.
     /// Duplicate keys are allowed and follow "last value wins" semantics.
     Map(Vec<(Val, Val)>),
@@ -461,6 +468,13 @@ impl Val {
                 next_mut(dst).write(ValRaw::i64(len as i64));
                 Ok(())
             }
+            (InterfaceType::List(ty), Val::PackedList(list)) => {
+                let ty = &cx.types[ty];
+                let (ptr, len) = list.lower(cx, ty.element)?;
+                next_mut(dst).write(ValRaw::i64(ptr as i64));
+                next_mut(dst).write(ValRaw::i64(len as i64));
+                Ok(())
+            }
             (InterfaceType::List(_), _) => unexpected(ty, self),
             (InterfaceType::Map(ty), Val::Map(pairs)) => {
                 let map_ty = &cx.types[ty];
@@ -608,6 +622,14 @@ impl Val {
                 *cx.get(offset + 4) = u32::try_from(len).unwrap().to_le_bytes();
                 Ok(())
             }
+            (InterfaceType::List(ty), Val::PackedList(list)) => {
+                let ty = &cx.types[ty];
+                let (ptr, len) = list.lower(cx, ty.element)?;
+                // FIXME(#4311): needs memory64 handling
+                *cx.get(offset + 0) = u32::try_from(ptr).unwrap().to_le_bytes();
+                *cx.get(offset + 4) = u32::try_from(len).unwrap().to_le_bytes();
+                Ok(())
+            }
             (InterfaceType::List(_), _) => unexpected(ty, self),
             (InterfaceType::Map(ty_idx), Val::Map(values)) => {
                 let map_ty = &cx.types[ty_idx];
@@ -740,7 +762,7 @@ impl Val {
             Val::Float32(_) => "f32",
             Val::Float64(_) => "f64",
             Val::Char(_) => "char",
-            Val::List(_) => "list",
+            Val::List(_) | Val::PackedList(_) => "list",
             Val::Map(_) => "map",
             Val::String(_) => "string",
             Val::Record(_) => "record",
@@ -816,7 +838,12 @@ impl PartialEq for Val {
             (Self::String(l), Self::String(r)) => l == r,
             (Self::String(_), _) => false,
             (Self::List(l), Self::List(r)) => l == r,
+            (Self::List(l), Self::PackedList(r)) | (Self::PackedList(r), Self::List(l)) => {
+                l.len() == r.len() && l.iter().zip(r.iter()).all(|(l, r)| *l == r)
+            }
             (Self::List(_), _) => false,
+            (Self::PackedList(l), Self::PackedList(r)) => l == r,
+            (Self::PackedList(_), _) => false,
             (Self::Map(l), Self::Map(r)) => l == r,
             (Self::Map(_), _) => false,
             (Self::Record(l), Self::Record(r)) => l == r,
@@ -1008,6 +1035,11 @@ fn load_flat_pointer_pair(bytes: &[u8]) -> (usize, usize) {
 
 fn load_list(cx: &mut LiftContext<'_>, ty: TypeListIndex, ptr: usize, len: usize) -> Result<Val> {
     let elem = cx.types[ty].element;
+    if cx.packed_lists() {
+        if let Some(list) = PackedList::load(cx, elem, ptr, len)? {
+            return Ok(Val::PackedList(list));
+        }
+    }
     let abi = cx.types.canonical_abi(&elem);
     let element_size = usize::try_from(abi.size32).unwrap();
     let element_alignment = abi.align32;
@@ -1023,17 +1055,12 @@ fn load_list(cx: &mut LiftContext<'_>, ty: TypeListIndex, ptr: usize, len: usize
         bail!("list pointer is not aligned")
     }
 
-    Ok(Val::List(
-        (0..len)
-            .map(|index| {
-                Val::load(
-                    cx,
-                    elem,
-                    &cx.memory()[ptr + (index * element_size)..][..element_size],
-                )
-            })
-            .collect::<Result<_>>()?,
-    ))
+    let mut list = Vec::with_capacity(len);
+    for index in 0..len {
+        let bytes = &cx.memory()[ptr + (index * element_size)..][..element_size];
+        list.push(Val::load(cx, elem, bytes)?);
+    }
+    Ok(Val::List(list))
 }
 
 fn load_map(cx: &mut LiftContext<'_>, ty: TypeMapIndex, ptr: usize, len: usize) -> Result<Val> {
@@ -1350,3 +1377,245 @@ impl From<StreamAny> for Val {
         Val::Stream(i)
     }
 }
+
+impl From<PackedList> for Val {
+    fn from(list: PackedList) -> Self {
+        Val::PackedList(list)
+    }
+}
+
+/// A list of integers or floats stored contiguously, used with
+/// [`Val::PackedList`].
+///
+/// Where a [`Val::List`] stores each element as its own [`Val`], a
+/// `PackedList` stores all of its elements in a single `Vec` of their native
+/// Rust type. Lifting and lowering a `PackedList` is then a bulk copy out of or
+/// into linear memory rather than a per-element traversal.
+///
+/// A `PackedList` is created from a `Vec<T>` where `T` implements
+/// [`PackedElement`], and its contents can be borrowed back with
+/// [`PackedList::as_slice`].
+///
+/// ```
+/// use wasmtime::component::{PackedList, Val};
+///
+/// let list = PackedList::from(vec![1u32, 2, 3]);
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list.get(1), Some(Val::U32(2)));
+/// assert_eq!(list.as_slice::<u32>(), Some(&[1, 2, 3][..]));
+/// assert_eq!(list.as_slice::<i32>(), None);
+/// assert_eq!(Val::from(list), Val::List(vec![Val::U32(1), Val::U32(2), Val::U32(3)]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PackedList(Packed);
+
+#[derive(Debug, Clone)]
+enum Packed {
+    U8(Vec<u8>),
+    S8(Vec<i8>),
+    U16(Vec<u16>),
+    S16(Vec<i16>),
+    U32(Vec<u32>),
+    S32(Vec<i32>),
+    U64(Vec<u64>),
+    S64(Vec<i64>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+}
+
+macro_rules! with_packed {
+    ($packed:expr, $items:ident => $e:expr) => {
+        match $packed {
+            Packed::U8($items) => $e,
+            Packed::S8($items) => $e,
+            Packed::U16($items) => $e,
+            Packed::S16($items) => $e,
+            Packed::U32($items) => $e,
+            Packed::S32($items) => $e,
+            Packed::U64($items) => $e,
+            Packed::S64($items) => $e,
+            Packed::Float32($items) => $e,
+            Packed::Float64($items) => $e,
+        }
+    };
+}
+
+impl PackedList {
+    /// Creates a new list from the provided elements.
+    pub fn new<T: PackedElement>(items: Vec<T>) -> PackedList {
+        T::into_packed(items)
+    }
+
+    /// Returns the number of elements in this list.
+    pub fn len(&self) -> usize {
+        with_packed!(&self.0, items => items.len())
+    }
+
+    /// Returns whether this list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the `index`th element of this list as a [`Val`], or `None` if
+    /// `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Val> {
+        with_packed!(&self.0, items => items.get(index).map(|i| i.into_val()))
+    }
+
+    /// Returns an iterator over the elements of this list as [`Val`]s.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Val> + '_ {
+        (0..self.len()).map(|i| self.get(i).unwrap())
+    }
+
+    /// Returns the elements of this list if they're of type `T`, or `None`
+    /// otherwise.
+    pub fn as_slice<T: PackedElement>(&self) -> Option<&[T]> {
+        T::from_packed(self)
+    }
+
+    /// Returns the elements of this list if they're of type `T`, or returns
+    /// this list back otherwise.
+    pub fn into_vec<T: PackedElement>(self) -> Result<Vec<T>, PackedList> {
+        T::from_packed_vec(self)
+    }
+
+    /// Returns the component model type of this list's elements.
+    fn element_type(&self) -> InterfaceType {
+        match &self.0 {
+            Packed::U8(_) => InterfaceType::U8,
+            Packed::S8(_) => InterfaceType::S8,
+            Packed::U16(_) => InterfaceType::U16,
+            Packed::S16(_) => InterfaceType::S16,
+            Packed::U32(_) => InterfaceType::U32,
+            Packed::S32(_) => InterfaceType::S32,
+            Packed::U64(_) => InterfaceType::U64,
+            Packed::S64(_) => InterfaceType::S64,
+            Packed::Float32(_) => InterfaceType::Float32,
+            Packed::Float64(_) => InterfaceType::Float64,
+        }
+    }
+
+    /// Loads a list of `len` elements of type `elem` at `ptr`, returning
+    /// `None` if `elem` can't be stored in a `PackedList`.
+    fn load(
+        cx: &mut LiftContext<'_>,
+        elem: InterfaceType,
+        ptr: usize,
+        len: usize,
+    ) -> Result<Option<PackedList>> {
+        fn load<T: PackedElement>(
+            cx: &mut LiftContext<'_>,
+            elem: InterfaceType,
+            ptr: usize,
+            len: usize,
+        ) -> Result<PackedList> {
+            let list = WasmList::<T>::new(ptr, len, cx, elem)?;
+            Ok(T::into_packed(T::linear_lift_list_from_memory(cx, &list)?))
+        }
+
+        Ok(Some(match elem {
+            InterfaceType::U8 => load::<u8>(cx, elem, ptr, len)?,
+            InterfaceType::S8 => load::<i8>(cx, elem, ptr, len)?,
+            InterfaceType::U16 => load::<u16>(cx, elem, ptr, len)?,
+            InterfaceType::S16 => load::<i16>(cx, elem, ptr, len)?,
+            InterfaceType::U32 => load::<u32>(cx, elem, ptr, len)?,
+            InterfaceType::S32 => load::<i32>(cx, elem, ptr, len)?,
+            InterfaceType::U64 => load::<u64>(cx, elem, ptr, len)?,
+            InterfaceType::S64 => load::<i64>(cx, elem, ptr, len)?,
+            InterfaceType::Float32 => load::<f32>(cx, elem, ptr, len)?,
+            InterfaceType::Float64 => load::<f64>(cx, elem, ptr, len)?,
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Lowers this list into a list with elements of type `elem`.
+    fn lower<T>(
+        &self,
+        cx: &mut LowerContext<'_, T>,
+        elem: InterfaceType,
+    ) -> Result<(usize, usize)> {
+        let actual = self.element_type();
+        if elem != actual {
+            bail!(
+                "type mismatch: expected {}, found {}",
+                desc(&elem),
+                desc(&actual)
+            )
+        }
+        with_packed!(&self.0, items => crate::component::func::lower_list(cx, elem, items))
+    }
+}
+
+impl<T: PackedElement> From<Vec<T>> for PackedList {
+    fn from(items: Vec<T>) -> PackedList {
+        PackedList::new(items)
+    }
+}
+
+impl PartialEq for PackedList {
+    fn eq(&self, other: &Self) -> bool {
+        // Defer to `Val`'s equality to get the same semantics for floats.
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for PackedList {}
+
+/// Element types which can be stored in a [`PackedList`].
+///
+/// This is implemented for `u8`, `i8`, `u16`, `i16`, `u32`, `i32`, `u64`,
+/// `i64`, `f32`, and `f64`.
+///
+/// Note that this is intended to be a `wasmtime`-sealed trait so it shouldn't
+/// need to be implemented externally.
+pub trait PackedElement: Lift + Lower + Copy {
+    #[doc(hidden)]
+    fn into_packed(items: Vec<Self>) -> PackedList;
+    #[doc(hidden)]
+    fn from_packed(list: &PackedList) -> Option<&[Self]>;
+    #[doc(hidden)]
+    fn from_packed_vec(list: PackedList) -> Result<Vec<Self>, PackedList>;
+    #[doc(hidden)]
+    fn into_val(self) -> Val;
+}
+
+macro_rules! packed_elements {
+    ($($ty:ident = $variant:ident,)*) => {$(
+        impl PackedElement for $ty {
+            fn into_packed(items: Vec<Self>) -> PackedList {
+                PackedList(Packed::$variant(items))
+            }
+
+            fn from_packed(list: &PackedList) -> Option<&[Self]> {
+                match &list.0 {
+                    Packed::$variant(items) => Some(items),
+                    _ => None,
+                }
+            }
+
+            fn from_packed_vec(list: PackedList) -> Result<Vec<Self>, PackedList> {
+                match list.0 {
+                    Packed::$variant(items) => Ok(items),
+                    other => Err(PackedList(other)),
+                }
+            }
+
+            fn into_val(self) -> Val {
+                Val::$variant(self)
+            }
+        }
+    )*};
+}
+
+packed_elements! {
+    u8 = U8,
+    i8 = S8,
+    u16 = U16,
+    i16 = S16,
+    u32 = U32,
+    i32 = S32,
+    u64 = U64,
+    i64 = S64,
+    f32 = Float32,
+    f64 = Float64,
+}
@@ -1,8 +1,10 @@
 use crate::hash_map::HashMap;
 use crate::prelude::*;
+use crate::store::{AutoAssertNoGc, StoreOpaque};
+use crate::vm::CoreDumpFrame;
 use crate::{
-    AsContextMut, FrameInfo, Global, HeapType, Instance, Memory, Module, StoreContextMut, Val,
-    ValType, WasmBacktrace, store::StoreOpaque,
+    AsContextMut, FrameInfo, Global, HeapType, Instance, Memory, MemoryType, Module, Val, ValType,
+    WasmBacktrace,
 };
 use std::fmt;
 
@@ -18,10 +20,13 @@ use std::fmt;
 ///
 /// Note that some state, such as Wasm locals or values on the operand stack,
 /// may be optimized away by the compiler or otherwise not recovered in the
-/// coredump.
+/// coredump. Locals and the operand stack are only recorded when guest
+/// debugging is enabled with `Config::guest_debug`.
 ///
 /// Capturing of wasm coredumps can be configured through the
-/// [`Config::coredump_on_trap`][crate::Config::coredump_on_trap] method.
+/// [`Config::coredump_on_trap`][crate::Config::coredump_on_trap] method, and
+/// [`Config::coredump_on_trap_path`][crate::Config::coredump_on_trap_path] can
+/// be used to have them written to a file as well.
 ///
 /// For more information about errors in wasmtime see the documentation of the
 /// [`Trap`][crate::Trap] type.
@@ -35,10 +40,17 @@ pub struct WasmCoreDump {
     memories: Vec<Memory>,
     globals: Vec<Global>,
     backtrace: WasmBacktrace,
+    /// The locals and operand stack of each frame in `backtrace`, if
+    /// recovered.
+    frame_values: Vec<Option<CoreDumpFrame>>,
 }
 
 impl WasmCoreDump {
-    pub(crate) fn new(store: &mut StoreOpaque, backtrace: WasmBacktrace) -> WasmCoreDump {
+    pub(crate) fn new(
+        store: &mut StoreOpaque,
+        backtrace: WasmBacktrace,
+        frames: Vec<CoreDumpFrame>,
+    ) -> WasmCoreDump {
         let modules = store
             .modules()
             .all_modules()
@@ -51,6 +63,24 @@ impl WasmCoreDump {
         let mut store_globals: Vec<Global> = vec![];
         store.for_each_global(|_store, global| store_globals.push(global));
 
+        // The captured frame values are a subsequence-matched list of the
+        // frames in the backtrace, where frames without values (such as
+        // those of other stores) are missing. Pair them up in order.
+        let mut frames = frames.into_iter().map(Some).collect::<Vec<_>>();
+        let mut next = 0;
+        let frame_values = backtrace
+            .frames()
+            .iter()
+            .map(|frame| {
+                let i = frames[next..].iter().position(|f| {
+                    let f = f.as_ref().unwrap();
+                    f.module == frame.module().id() && f.func_index == frame.func_index()
+                })?;
+                next += i + 1;
+                frames[next - 1].take()
+            })
+            .collect();
+
         WasmCoreDump {
             name: String::from("store_name"),
             modules,
@@ -58,6 +88,7 @@ impl WasmCoreDump {
             memories: store_memories,
             globals: store_globals,
             backtrace,
+            frame_values,
         }
     }
 
@@ -105,10 +136,10 @@ impl WasmCoreDump {
     /// [spec]: https://github.com/WebAssembly/tool-conventions/blob/main/Coredump.md
     pub fn serialize(&self, mut store: impl AsContextMut, name: &str) -> Vec<u8> {
         let store = store.as_context_mut();
-        self._serialize(store, name)
+        self._serialize(store.0, name)
     }
 
-    fn _serialize<T: 'static>(&self, mut store: StoreContextMut<'_, T>, name: &str) -> Vec<u8> {
+    pub(crate) fn _serialize(&self, store: &mut StoreOpaque, name: &str) -> Vec<u8> {
         let mut core_dump = wasm_encoder::Module::new();
 
         core_dump.section(&wasm_encoder::CoreDumpSection::new(name));
@@ -123,10 +154,10 @@ impl WasmCoreDump {
             let mut memories = wasm_encoder::MemorySection::new();
             for mem in self.memories() {
                 let memory_idx = memories.len();
-                memory_to_idx.insert(mem.hash_key(&store.0), memory_idx);
-                let ty = mem.ty(&store);
+                memory_to_idx.insert(mem.hash_key(store), memory_idx);
+                let ty = MemoryType::from_wasmtime_memory(mem.wasmtime_ty(store));
                 memories.memory(wasm_encoder::MemoryType {
-                    minimum: mem.size(&store),
+                    minimum: mem.internal_size(store),
                     maximum: ty.maximum(),
                     memory64: ty.is_64(),
                     shared: ty.is_shared(),
@@ -144,7 +175,7 @@ impl WasmCoreDump {
                 // into reasonably-sized chunks and then trim runs of zeroes
                 // from the start and end of each chunk.
                 const CHUNK_SIZE: usize = 4096;
-                for (i, chunk) in mem.data(&store).chunks_exact(CHUNK_SIZE).enumerate() {
+                for (i, chunk) in mem._data(store).chunks_exact(CHUNK_SIZE).enumerate() {
                     if let Some(start) = chunk.iter().position(|byte| *byte != 0) {
                         let end = chunk.iter().rposition(|byte| *byte != 0).unwrap() + 1;
                         let offset = i * CHUNK_SIZE + start;
@@ -169,8 +200,8 @@ impl WasmCoreDump {
         {
             let mut globals = wasm_encoder::GlobalSection::new();
             for g in self.globals() {
-                global_to_idx.insert(g.hash_key(&store.0), globals.len());
-                let ty = g.ty(&store);
+                global_to_idx.insert(g.hash_key(store), globals.len());
+                let ty = g._ty(store);
                 let mutable = matches!(ty.mutability(), crate::Mutability::Var);
                 let val_type = match ty.content() {
                     ValType::I32 => wasm_encoder::ValType::I32,
@@ -194,7 +225,7 @@ impl WasmCoreDump {
                         ty => unreachable!("not a top type: {ty:?}"),
                     },
                 };
-                let init = match g.get(&mut store) {
+                let init = match g._get(&mut AutoAssertNoGc::new(store)) {
                     Val::I32(x) => wasm_encoder::ConstExpr::i32_const(x),
                     Val::I64(x) => wasm_encoder::ConstExpr::i64_const(x),
                     Val::F32(x) => wasm_encoder::ConstExpr::f32_const(f32::from_bits(x).into()),
@@ -264,27 +295,27 @@ impl WasmCoreDump {
         {
             let mut instances = wasm_encoder::CoreDumpInstancesSection::new();
             for instance in self.instances() {
-                let module = instance.module(&store);
+                let module = instance._module(store);
                 module_to_instance.insert(module.id(), instances.len());
 
                 let module_index = module_to_index[&module.id()];
 
                 let memories = instance
-                    .all_memories(store.0)
+                    .all_memories(store)
                     .filter_map(|(_, m)| m.unshared())
                     .map(|memory| {
                         memory_to_idx
-                            .get(&memory.hash_key(&store.0))
+                            .get(&memory.hash_key(store))
                             .copied()
                             .unwrap_or(u32::MAX)
                     })
                     .collect::<Vec<_>>();
 
                 let globals = instance
-                    .all_globals(store.0)
+                    .all_globals(store)
                     .collect::<Vec<_>>()
                     .into_iter()
-                    .map(|(_i, global)| global_to_idx[&global.hash_key(&store.0)])
+                    .map(|(_i, global)| global_to_idx[&global.hash_key(store)])
                     .collect::<Vec<_>>();
 
                 instances.instance(module_index, memories, globals);
@@ -295,7 +326,7 @@ impl WasmCoreDump {
         {
             let thread_name = "main";
             let mut stack = wasm_encoder::CoreDumpStackSection::new(thread_name);
-            for frame in self.frames() {
+            for (frame, values) in self.frames().iter().zip(&self.frame_values) {
                 // This isn't necessarily the right instance if there are
                 // multiple instances of the same module. See comment above
                 // `module_to_instance` for details.
@@ -308,12 +339,18 @@ impl WasmCoreDump {
                     .and_then(|o| u32::try_from(o).ok())
                     .unwrap_or(0);
 
-                // We can't currently recover locals and the operand stack. We
-                // should eventually be able to do that with Winch though.
-                let locals = [];
-                let operand_stack = [];
+                let (locals, operand_stack) = match values {
+                    Some(values) => (&values.locals[..], &values.operand_stack[..]),
+                    None => (&[][..], &[][..]),
+                };
 
-                stack.frame(instance, func, offset, locals, operand_stack);
+                stack.frame(
+                    instance,
+                    func,
+                    offset,
+                    locals.iter().cloned(),
+                    operand_stack.iter().cloned(),
+                );
             }
             core_dump.section(&stack);
         }
@@ -21,6 +21,10 @@ use core::{ffi::c_void, ptr::NonNull};
 #[cfg(feature = "gc")]
 use wasmtime_environ::FrameTable;
 // Re-export ModulePC so downstream crates can use it.
+#[cfg(feature = "coredump")]
+use crate::vm::CoreDumpFrame;
+#[cfg(feature = "coredump")]
+use wasm_encoder::CoreDumpValue;
 pub use wasmtime_environ::ModulePC;
 use wasmtime_environ::{
     DefinedFuncIndex, EntityIndex, FrameInstPos, FrameStackShape, FrameStateSlot,
@@ -148,6 +152,26 @@ impl StoreOpaque {
             .filter_map(|act| unsafe { FrameHandle::exit_frame(self, act) })
     }
 
+    /// Capture the locals and operand stack of all Wasm frames on the
+    /// stack for a core dump, from innermost to outermost.
+    ///
+    /// Returns an empty list if debug instrumentation is not enabled.
+    #[cfg(feature = "coredump")]
+    pub(crate) fn coredump_frames(&mut self) -> Vec<CoreDumpFrame> {
+        let mut ret = vec![];
+        let exit_frames = self.debug_exit_frames().collect::<Vec<_>>();
+        for frame in exit_frames {
+            let mut frame = Some(frame);
+            while let Some(f) = frame {
+                if let Ok(Some(values)) = f.coredump_frame(self) {
+                    ret.push(values);
+                }
+                frame = f.parent_impl(self).ok().flatten();
+            }
+        }
+        ret
+    }
+
     fn edit_breakpoints<'a>(&'a mut self) -> Option<BreakpointEdit<'a>> {
         if !self.engine().tunables().debug_guest {
             return None;
@@ -460,8 +484,12 @@ impl FrameHandle {
     /// Get a handle to the next frame up the activation (the one that
     /// called this frame), if any.
     pub fn parent(&self, mut store: impl AsContextMut) -> Result<Option<FrameHandle>> {
-        let mut store = store.as_context_mut();
-        if !self.is_valid(&mut store) {
+        let store = store.as_context_mut();
+        self.parent_impl(store.0.as_store_opaque())
+    }
+
+    fn parent_impl(&self, store: &mut StoreOpaque) -> Result<Option<FrameHandle>> {
+        if !self.is_valid_impl(store) {
             crate::error::bail!("Frame handle is no longer valid.");
         }
 
@@ -469,10 +497,7 @@ impl FrameHandle {
         parent.virtual_frame_idx += 1;
 
         while !parent.cursor.done() {
-            let (cache, registry) = store
-                .0
-                .as_store_opaque()
-                .frame_data_cache_mut_and_registry();
+            let (cache, registry) = store.frame_data_cache_mut_and_registry();
             let frames = cache.lookup_or_compute(registry, parent.cursor.frame());
             if parent.virtual_frame_idx < frames.len() {
                 return Ok(Some(parent));
@@ -482,7 +507,7 @@ impl FrameHandle {
             // wrt execution version at the top of this function, and
             // we have not returned since.
             unsafe {
-                parent.cursor.advance(store.0.as_store_opaque().unwinder());
+                parent.cursor.advance(store.unwinder());
             }
         }
 
@@ -597,6 +622,30 @@ impl FrameHandle {
         Ok(unsafe { read_value(store.0.as_store_opaque(), slot_addr, offset, ty) })
     }
 
+    /// Capture the locals and operand stack of this frame for a core
+    /// dump, if this is a Wasm function's frame.
+    #[cfg(feature = "coredump")]
+    fn coredump_frame(&self, store: &mut StoreOpaque) -> Result<Option<CoreDumpFrame>> {
+        let Some(module) = self.raw_instance(store)?.runtime_module() else {
+            return Ok(None);
+        };
+        let module = module.clone();
+        let frame_data = self.frame_data(store)?;
+        let FuncKey::DefinedWasmFunction(_, func) = frame_data.func_key else {
+            return Ok(None);
+        };
+        let slot_addr = frame_data.slot_addr(self.cursor.frame().fp());
+        // SAFETY: the slots are described by compiler-produced metadata
+        // and the frame is live, as checked by `frame_data` above.
+        let read = |&(offset, ty)| unsafe { read_coredump_value(slot_addr, offset, ty) };
+        Ok(Some(CoreDumpFrame {
+            module: module.id(),
+            func_index: module.env_module().func_index(func).as_u32(),
+            locals: frame_data.locals.iter().map(read).collect(),
+            operand_stack: frame_data.stack.iter().map(read).collect(),
+        }))
+    }
+
     /// Get the type and value of the given operand-stack value in
     /// this frame.
     ///
@@ -830,6 +879,47 @@ unsafe fn read_value(
     }
 }
 
+/// Read the value at the given offset for a core dump.
+///
+/// The core dump format can't represent vectors or references, so
+/// these are recorded as missing.
+///
+/// # Safety
+///
+/// Same as [`read_value`].
+#[cfg(feature = "coredump")]
+unsafe fn read_coredump_value(
+    slot_base: *const u8,
+    offset: FrameStateSlotOffset,
+    ty: FrameValType,
+) -> CoreDumpValue {
+    let address = unsafe { slot_base.offset(isize::try_from(offset.offset()).unwrap()) };
+
+    // SAFETY: see `read_value`.
+    match ty {
+        FrameValType::I32 => {
+            CoreDumpValue::I32(unsafe { (address as *const i32).read_unaligned() })
+        }
+        FrameValType::I64 => {
+            CoreDumpValue::I64(unsafe { (address as *const i64).read_unaligned() })
+        }
+        FrameValType::F32 => {
+            let bits = unsafe { (address as *const u32).read_unaligned() };
+            CoreDumpValue::F32(f32::from_bits(bits).into())
+        }
+        FrameValType::F64 => {
+            let bits = unsafe { (address as *const u64).read_unaligned() };
+            CoreDumpValue::F64(f64::from_bits(bits).into())
+        }
+        FrameValType::V128
+        | FrameValType::AnyRef
+        | FrameValType::ExnRef
+        | FrameValType::ExternRef
+        | FrameValType::FuncRef
+        | FrameValType::ContRef => CoreDumpValue::Missing,
+    }
+}
+
 /// Compute raw pointers to all GC refs in the given frame.
 // Note: ideally this would be an impl Iterator, but this is quite
 // awkward because of the locally computed data (FrameStateSlot::parse
@@ -0,0 +1,174 @@
+//! A typed registry of per-store state attached by independent libraries.
+
+use crate::hash_map::HashMap;
+use crate::prelude::*;
+use core::any::{Any, TypeId};
+use core::fmt;
+
+/// A map of values keyed by their type, used to attach state to a
+/// [`Store`](crate::Store) without defining a field for it in the store's
+/// `T`.
+///
+/// As a host grows, the `T` in `Store<T>` tends to accumulate one field per
+/// library that needs per-store state, along with the glue to get at each of
+/// them. An `Extensions` map lets each library instead insert its own state
+/// under its own type, keeping the libraries independent of each other and of
+/// the embedder's `T`.
+///
+/// The `T` of a store opts in to extensions by implementing [`HasExtensions`],
+/// after which they're available through [`Store::extensions`] and similar
+/// methods. [`Extensions`] itself implements [`HasExtensions`] so it can also
+/// be used as the `T` directly. The map is part of `T`, rather than of the
+/// store itself, so that it can be reached by the getters passed to
+/// `add_to_linker` functions, which only receive a `&mut T`.
+///
+/// With the component model the
+/// [`HasExtension`](crate::component::HasExtension) type and
+/// [`expect_extension`](crate::component::expect_extension) function can be
+/// used to fill out a linker using `add_to_linker` functions generated by
+/// [`bindgen!`](crate::component::bindgen) with state stored in extensions.
+///
+/// [`Store::extensions`]: crate::Store::extensions
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::{Engine, Extensions, Store};
+///
+/// struct RequestCounter(u32);
+///
+/// let engine = Engine::default();
+/// let mut store = Store::new(&engine, Extensions::new());
+/// store.extensions_mut().insert(RequestCounter(0));
+///
+/// store.extensions_mut().get_mut::<RequestCounter>().unwrap().0 += 1;
+/// assert_eq!(store.extensions().get::<RequestCounter>().unwrap().0, 1);
+/// ```
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// Creates a new, empty, set of extensions.
+    pub fn new() -> Extensions {
+        Extensions::default()
+    }
+
+    /// Inserts `value` into this map, returning the previous value of the same
+    /// type, if any.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .map(|prev| *prev.downcast().unwrap())
+    }
+
+    /// Returns a reference to the value of type `T` in this map, if any.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .map(|value| value.downcast_ref().unwrap())
+    }
+
+    /// Returns a mutable reference to the value of type `T` in this map, if
+    /// any.
+    pub fn get_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .map(|value| value.downcast_mut().unwrap())
+    }
+
+    /// Returns a mutable reference to the value of type `T` in this map,
+    /// inserting the result of `f` first if there isn't one.
+    pub fn get_or_insert_with<T: Any + Send + Sync>(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(f()))
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// Removes the value of type `T` from this map, returning it if present.
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .map(|value| *value.downcast().unwrap())
+    }
+
+    /// Returns whether this map contains a value of type `T`.
+    pub fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns the number of values in this map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether this map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a mutable reference to the value of type `T`, panicking with a
+    /// descriptive message if it's missing.
+    #[cfg(feature = "component-model")]
+    pub(crate) fn expect_mut<T: Any + Send + Sync>(&mut self) -> &mut T {
+        match self.get_mut() {
+            Some(value) => value,
+            None => panic!(
+                "store extension `{}` was not inserted",
+                core::any::type_name::<T>()
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Implemented by the `T` of a [`Store<T>`](crate::Store) which carries an
+/// [`Extensions`] map.
+///
+/// Typically this is implemented by returning a field of `T`:
+///
+/// ```
+/// use wasmtime::{Extensions, HasExtensions};
+///
+/// struct MyState {
+///     extensions: Extensions,
+///     // ...
+/// }
+///
+/// impl HasExtensions for MyState {
+///     fn extensions(&self) -> &Extensions {
+///         &self.extensions
+///     }
+///
+///     fn extensions_mut(&mut self) -> &mut Extensions {
+///         &mut self.extensions
+///     }
+/// }
+/// ```
+pub trait HasExtensions {
+    /// Returns the extensions of this store data.
+    fn extensions(&self) -> &Extensions;
+
+    /// Returns the extensions of this store data, mutably.
+    fn extensions_mut(&mut self) -> &mut Extensions;
+}
+
+impl HasExtensions for Extensions {
+    fn extensions(&self) -> &Extensions {
+        self
+    }
+
+    fn extensions_mut(&mut self) -> &mut Extensions {
+        self
+    }
+}
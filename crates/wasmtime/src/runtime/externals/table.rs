@@ -338,7 +338,7 @@ impl Table {
         let result = unsafe {
             self.instance
                 .get_mut(store)
-                .defined_table_grow(self.index, limiter, delta)
+                .defined_table_grow(self.instance.store_id(), self.index, limiter, delta)
                 .await?
         };
         let start = match result {
@@ -7,7 +7,7 @@ use core::mem;
 use core::ops::Range;
 use core::pin::Pin;
 use core::ptr::{self, NonNull};
-use core::task::{Context, Poll};
+use core::task::{Context, Poll, Waker};
 use wasmtime_fiber::{Fiber, FiberStack, Suspend};
 #[cfg(all(feature = "component-model-async", feature = "gc"))]
 use wasmtime_unwinder::Unwind;
@@ -97,6 +97,15 @@ pub(crate) struct AsyncState {
     /// yielding) which requires that wasm is executed on a fiber, thus async
     /// entrypoints are required.
     pub(crate) async_required: bool,
+
+    /// Whether the fiber currently executing was cancelled because the future
+    /// driving it was dropped.
+    ///
+    /// This is set while a cancelled fiber is resumed to unwind, and while set
+    /// all attempts to suspend fail immediately with [`Cancelled`]. That
+    /// ensures that the fiber runs to completion rather than being left at a
+    /// suspension point which will never be resumed.
+    cancelled: bool,
 }
 
 // SAFETY: it's known that `std::task::Context` is neither `Send` nor `Sync`,
@@ -116,6 +125,7 @@ impl Default for AsyncState {
             current_future_cx: None,
             last_fiber_stack: None,
             async_required: false,
+            cancelled: false,
         }
     }
 }
@@ -125,13 +135,51 @@ impl AsyncState {
         &mut self.last_fiber_stack
     }
 
+    /// Returns whether the current fiber is unwinding due to its future having
+    /// been dropped.
+    #[inline]
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
     /// Returns whether `block_on` will succeed or panic.
+    ///
+    /// Note that a cancelled fiber can still block, but only on futures which
+    /// are immediately ready, as suspending fails.
     #[inline]
     pub(crate) fn can_block(&mut self) -> bool {
-        self.current_future_cx.is_some()
+        self.current_future_cx.is_some() || (self.cancelled && self.current_suspend.is_some())
     }
 }
 
+/// An error indicating that an asynchronous call into WebAssembly was
+/// cancelled because the future driving it was dropped.
+///
+/// When a future returned by an `*_async` function, such as
+/// [`Func::call_async`](crate::Func::call_async), is dropped before completing
+/// while WebAssembly is suspended, the suspended computation is unwound. The
+/// host future that WebAssembly was suspended on, if any, is dropped, and the
+/// host function awaiting it returns this error, which then propagates through
+/// WebAssembly like a trap. Any other attempt to suspend during this unwinding,
+/// for example another async host call or an epoch or fuel yield, fails
+/// immediately with this error as well.
+///
+/// Host functions can check for this error with
+/// [`Error::is`](crate::Error::is), or query
+/// [`Store::is_cancelled`](crate::Store::is_cancelled), to avoid doing further
+/// work while a call is being cancelled. Host code should propagate this error
+/// rather than handling it.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl core::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("asynchronous call cancelled: its future was dropped")
+    }
+}
+
+impl core::error::Error for Cancelled {}
+
 /// A helper structure used to block a fiber.
 ///
 /// This is acquired via either `StoreContextMut::with_blocking` or
@@ -164,6 +212,11 @@ pub(crate) struct BlockingContext<'a, 'b> {
     /// Cancellation is a case where it isn't passed back and a re-poll is a
     /// case where it's passed back.
     future_cx: Option<&'a mut Context<'b>>,
+
+    /// Whether this fiber has been cancelled, in which case suspending fails.
+    ///
+    /// See `AsyncState::cancelled`.
+    cancelled: bool,
 }
 
 impl<'a, 'b> BlockingContext<'a, 'b> {
@@ -215,12 +268,22 @@ impl<'a, 'b> BlockingContext<'a, 'b> {
         // fiber and for this fiber. The "take" pattern here ensures that if
         // this `BlockingContext` context acquires the pointers then there are
         // no other instances of these pointers in use anywhere else.
-        let future_cx = unsafe { Some(state.current_future_cx.take().unwrap().as_mut()) };
+        //
+        // Note that a cancelled fiber may have no `Context` available as it was
+        // discarded when the fiber was last suspended.
+        let future_cx = state.current_future_cx.take();
+        assert!(future_cx.is_some() || state.cancelled);
+        let future_cx = future_cx.map(|mut cx| unsafe { cx.as_mut() });
         let suspend = unsafe { state.current_suspend.take().unwrap().as_mut() };
+        let cancelled = state.cancelled;
 
         let mut reset = ResetBlockingContext {
             store,
-            cx: BlockingContext { future_cx, suspend },
+            cx: BlockingContext {
+                future_cx,
+                suspend,
+                cancelled,
+            },
         };
         return f(&mut reset.store, &mut reset.cx);
 
@@ -301,7 +364,19 @@ impl<'a, 'b> BlockingContext<'a, 'b> {
     {
         let mut future = core::pin::pin!(future);
         loop {
-            match future.as_mut().poll(self.future_cx.as_mut().unwrap()) {
+            let poll = match &mut self.future_cx {
+                Some(cx) => future.as_mut().poll(cx),
+                // Once cancelled there's no `Context` to poll with, but
+                // futures which are immediately ready may still complete.
+                // Anything else fails to suspend below.
+                None => {
+                    debug_assert!(self.cancelled);
+                    future
+                        .as_mut()
+                        .poll(&mut Context::from_waker(Waker::noop()))
+                }
+            };
+            match poll {
                 Poll::Ready(v) => break Ok(v),
                 Poll::Pending => self.suspend(StoreFiberYield::KeepStore)?,
             }
@@ -314,14 +389,27 @@ impl<'a, 'b> BlockingContext<'a, 'b> {
     /// fiber has resumed. This function return `Ok(())` if the fiber was
     /// resumed to be completed, and `Err(e)` indicates that the fiber has been
     /// cancelled and needs to exit/complete ASAP.
+    ///
+    /// Once a fiber has been cancelled all further attempts to suspend it
+    /// fail immediately, without suspending, with a [`Cancelled`] error.
     pub(crate) fn suspend(&mut self, yield_: StoreFiberYield) -> Result<()> {
+        if self.cancelled {
+            return Err(Cancelled.into());
+        }
+
         // Over a suspension point we're guaranteed that the `Context` provided
         // here is no longer valid, so discard it. If we're supposed to be able
         // to poll afterwards this will be given back as part of the resume
         // value given back.
         self.future_cx.take();
 
-        let mut new_future_cx: NonNull<Context<'static>> = self.suspend.suspend(yield_)?;
+        let mut new_future_cx: NonNull<Context<'static>> = match self.suspend.suspend(yield_) {
+            Ok(cx) => cx,
+            Err(e) => {
+                self.cancelled = true;
+                return Err(e);
+            }
+        };
 
         // SAFETY: this function is unsafe as we're doing "funky" things to the
         // `new_future_cx` we have been given. The safety here relies on the
@@ -452,10 +540,14 @@ impl<'a> StoreFiber<'a> {
         self.fiber.take().map(|f| f.into_inner().0.into_stack())
     }
 
+    /// Cancels this fiber if it's still in progress, resuming it with a
+    /// [`Cancelled`] error so that it unwinds and runs to completion.
     pub(crate) fn dispose(&mut self, store: &mut StoreOpaque) {
         if let Some(fiber) = self.fiber() {
             if !fiber.done() {
-                let result = resume_fiber(store, self, Err(format_err!("future dropped")));
+                let prev = mem::replace(&mut store.fiber_async_state_mut().cancelled, true);
+                let result = resume_fiber(store, self, Err(Cancelled.into()));
+                store.fiber_async_state_mut().cancelled = prev;
                 debug_assert!(result.is_ok());
             }
         }
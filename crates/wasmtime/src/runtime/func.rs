@@ -2012,6 +2012,17 @@ impl<T> Caller<'_, T> {
         }
     }
 
+    /// Returns the context `key` of the calling instance, see
+    /// [`Linker::func_wrap_ctx`](crate::Linker::func_wrap_ctx).
+    pub(crate) fn instance_context<C: core::any::Any + Send + Sync>(
+        &mut self,
+        key: usize,
+        init: impl FnOnce() -> C,
+    ) -> Arc<C> {
+        let instance = self.caller.id.instance();
+        self.store.0.instance_context(instance, key, init)
+    }
+
     /// Looks up an export from the caller's module by the `name` given.
     ///
     /// This is a low-level function that's typically used to implement passing
@@ -2132,6 +2143,41 @@ impl<T> Caller<'_, T> {
         self.store.data_mut()
     }
 
+    /// Returns a handle to the data owned by this `Store`.
+    ///
+    /// Same as [`Store::data_handle`](crate::Store::data_handle)
+    pub fn data_handle(&mut self) -> crate::StoreDataHandle<T> {
+        self.store.data_handle()
+    }
+
+    /// Runs `f` with the data owned by this `Store` after running all work
+    /// queued through its handles.
+    ///
+    /// Same as [`Store::data_scope`](crate::Store::data_scope)
+    pub fn data_scope<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.store.data_scope(f)
+    }
+
+    /// Access the extensions of the data owned by this `Store`.
+    ///
+    /// Same as [`Store::extensions`](crate::Store::extensions)
+    pub fn extensions(&self) -> &crate::Extensions
+    where
+        T: crate::HasExtensions,
+    {
+        self.store.extensions()
+    }
+
+    /// Mutably access the extensions of the data owned by this `Store`.
+    ///
+    /// Same as [`Store::extensions_mut`](crate::Store::extensions_mut)
+    pub fn extensions_mut(&mut self) -> &mut crate::Extensions
+    where
+        T: crate::HasExtensions,
+    {
+        self.store.extensions_mut()
+    }
+
     /// Returns the underlying [`Engine`] this store is connected to.
     pub fn engine(&self) -> &Engine {
         self.store.engine()
@@ -2153,6 +2199,16 @@ impl<T> Caller<'_, T> {
         self.store.0.gc_heap_capacity()
     }
 
+    /// Precisely enumerate the GC references held in the stack slots of the
+    /// active Wasm frames on the current stack.
+    ///
+    /// Same as
+    /// [`StoreContextMut::wasm_stack_gc_roots`](crate::StoreContextMut::wasm_stack_gc_roots).
+    #[cfg(feature = "gc")]
+    pub fn wasm_stack_gc_roots(&mut self) -> Vec<crate::WasmStackGcRoot> {
+        self.store.wasm_stack_gc_roots()
+    }
+
     /// Perform garbage collection asynchronously.
     ///
     /// Same as [`Store::gc_async`](crate::Store::gc_async).
@@ -2164,6 +2220,16 @@ impl<T> Caller<'_, T> {
         self.store.gc_async(why).await
     }
 
+    /// Returns whether the store is currently unwinding an asynchronous call
+    /// which was cancelled.
+    ///
+    /// For more information see
+    /// [`Store::is_cancelled`](crate::Store::is_cancelled)
+    #[cfg(feature = "async")]
+    pub fn is_cancelled(&self) -> bool {
+        self.store.is_cancelled()
+    }
+
     /// Returns the remaining fuel in the store.
     ///
     /// For more information see [`Store::get_fuel`](crate::Store::get_fuel)
@@ -2186,6 +2252,91 @@ impl<T> Caller<'_, T> {
     pub fn fuel_async_yield_interval(&mut self, interval: Option<u64>) -> Result<()> {
         self.store.fuel_async_yield_interval(interval)
     }
+
+    /// Returns the amount of fuel that can be consumed before the store's
+    /// next fuel-based interruption.
+    ///
+    /// This is the smaller of [`Caller::get_fuel`] and the fuel left until the
+    /// next yield configured with [`Caller::fuel_async_yield_interval`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if fuel consumption is not enabled
+    /// via [`Config::consume_fuel`](crate::Config::consume_fuel).
+    pub fn remaining_fuel(&self) -> Result<u64> {
+        self.store.0.remaining_active_fuel()
+    }
+
+    /// Returns the number of epoch ticks left before the store's epoch
+    /// deadline is reached.
+    ///
+    /// Returns `None` if epoch interruption is not enabled via
+    /// [`Config::epoch_interruption`](crate::Config::epoch_interruption).
+    #[cfg(target_has_atomic = "64")]
+    pub fn remaining_epoch_ticks(&self) -> Option<u64> {
+        self.store.0.remaining_epoch_ticks()
+    }
+
+    /// Performs the interruption checks that WebAssembly code performs, on
+    /// behalf of a host function.
+    ///
+    /// Only WebAssembly code checks the store's epoch deadline and fuel, so a
+    /// long-running host function can otherwise overrun them. Calling this
+    /// method periodically from such a function lets the store interrupt it
+    /// the same way it would interrupt WebAssembly:
+    ///
+    /// * If the epoch deadline has been reached, the store's configured
+    ///   deadline behavior is applied. By default this returns a
+    ///   [`Trap::Interrupt`](crate::Trap::Interrupt) error; a store configured
+    ///   with
+    ///   [`Store::epoch_deadline_async_yield_and_update`](crate::Store::epoch_deadline_async_yield_and_update)
+    ///   or
+    ///   [`Store::epoch_deadline_callback`](crate::Store::epoch_deadline_callback)
+    ///   yields and extends the deadline as requested.
+    ///
+    /// * If [`Caller::fuel_async_yield_interval`] is configured and the fuel
+    ///   for the current interval is used up, the store yields after moving
+    ///   more fuel into the next interval, or returns a
+    ///   [`Trap::OutOfFuel`](crate::Trap::OutOfFuel) error if there is none
+    ///   left.
+    ///
+    /// Otherwise this returns immediately. Use [`Caller::remaining_fuel`] and
+    /// [`Caller::remaining_epoch_ticks`] to find out how close the store is to
+    /// its next interruption.
+    #[cfg(feature = "async")]
+    pub async fn yield_if_needed(&mut self) -> Result<()>
+    where
+        T: Send,
+    {
+        #[cfg(target_has_atomic = "64")]
+        if self.remaining_epoch_ticks() == Some(0) {
+            use crate::UpdateDeadline;
+            use crate::runtime::vm::VMStore as _;
+
+            let delta = match self.store.0.new_epoch_updated_deadline()? {
+                UpdateDeadline::Interrupt => return Err(crate::Trap::Interrupt.into()),
+                UpdateDeadline::Continue(delta) => delta,
+                UpdateDeadline::Yield(delta) => {
+                    self.store.0.yield_now().await;
+                    delta
+                }
+                UpdateDeadline::YieldCustom(delta, future) => {
+                    future.await;
+                    delta
+                }
+            };
+            self.store.0.set_epoch_deadline(delta);
+        }
+
+        if self.store.0.fuel_yield_interval.is_some() && self.remaining_fuel()? == 0 {
+            if !self.store.0.refuel() {
+                return Err(crate::Trap::OutOfFuel.into());
+            }
+            self.store.0.yield_now().await;
+        }
+
+        Ok(())
+    }
 }
 
 impl<T: 'static> AsContext for Caller<'_, T> {
@@ -2232,6 +2383,17 @@ pub struct HostFunc {
     // Stored to unregister this function's signature with the engine when this
     // is dropped.
     engine: Engine,
+
+    /// The per-instance context of functions defined with
+    /// `Linker::func_wrap_ctx`, created for each instance importing this
+    /// function when it's instantiated.
+    instance_context: Option<InstanceContext>,
+}
+
+/// Creates the context `key` of each instance importing a [`HostFunc`].
+struct InstanceContext {
+    key: usize,
+    new: Box<dyn Fn() -> Arc<dyn core::any::Any + Send + Sync> + Send + Sync>,
 }
 
 // State stored inside a `VMArrayCallHostFuncContext`.
@@ -2266,6 +2428,28 @@ impl HostFunc {
             ctx,
             engine: engine.clone(),
             asyncness,
+            instance_context: None,
+        }
+    }
+
+    /// Configures this function to create the context `key` of each instance
+    /// importing it with `new` when that instance is created.
+    pub(crate) fn set_instance_context<C: core::any::Any + Send + Sync>(
+        &mut self,
+        key: usize,
+        new: impl Fn() -> C + Send + Sync + 'static,
+    ) {
+        self.instance_context = Some(InstanceContext {
+            key,
+            new: Box::new(move || Arc::new(new())),
+        });
+    }
+
+    /// Creates the context of `instance`, which imports this function, if
+    /// this function has one.
+    pub(crate) fn init_instance_context(&self, store: &mut StoreOpaque, instance: InstanceId) {
+        if let Some(cx) = &self.instance_context {
+            store.set_instance_context(instance, cx.key, (cx.new)());
         }
     }
 
@@ -92,6 +92,10 @@ impl<T: GcRef> Rooted<T> {
         match self.inner {}
     }
 
+    pub fn downgrade(&self, _store: impl AsContextMut) -> Result<WeakRooted<T>> {
+        match self.inner {}
+    }
+
     pub fn rooted_eq(a: Self, _b: Self) -> bool {
         match a.inner {}
     }
@@ -198,3 +202,36 @@ impl<T: GcRef> RootedGcRefImpl<T> for OwnedRooted<T> {
         match self.inner {}
     }
 }
+
+/// This type has been disabled because the `gc` cargo feature was not enabled
+/// at compile time.
+pub struct WeakRooted<T: GcRef> {
+    pub(crate) inner: Infallible,
+    _phantom: marker::PhantomData<T>,
+}
+
+impl<T: GcRef> Clone for WeakRooted<T> {
+    fn clone(&self) -> Self {
+        match self.inner {}
+    }
+}
+
+impl<T: GcRef> Debug for WeakRooted<T> {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner {}
+    }
+}
+
+impl<T: GcRef> WeakRooted<T> {
+    pub fn upgrade(&self, _context: impl AsContextMut) -> Option<Rooted<T>> {
+        match self.inner {}
+    }
+
+    pub fn on_reclaimed(
+        &self,
+        _context: impl AsContextMut,
+        _callback: impl FnOnce() + Send + Sync + 'static,
+    ) {
+        match self.inner {}
+    }
+}
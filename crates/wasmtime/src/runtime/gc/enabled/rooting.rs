@@ -145,6 +145,20 @@
 //! allows us to update the *actual* GC pointers in the `RootSet` and
 //! support moving GCs (again, as mentioned above).
 //!
+//! ## Weak References
+//!
+//! Both flavors of root above are strong: they keep their referent alive. A
+//! `Rooted<T>` can also be downgraded into a `WeakRooted<T>`, which refers to
+//! the same GC object without keeping it alive. Weak roots live in their own
+//! slab in the `RootSet` and are unrooted just like `OwnedRooted<T>`s, via
+//! liveness flags. They are handed to the collector as weak roots during a
+//! GC, and the collector either updates them to point to their referent's new
+//! location or clears them when their referent is reclaimed. Collectors that
+//! don't determine liveness by tracing (i.e. reference counting) instead report
+//! reclaimed weakly-referenced objects to the store, which then clears the
+//! corresponding weak roots. Once a weak root is cleared, any finalizers that
+//! were registered on it are run.
+//!
 //! ## Unrooted References
 //!
 //! We generally don't expose *unrooted* GC references in the Wasmtime API at
@@ -161,9 +175,10 @@
 //! can. However, if you really must, consider also using an `AutoAssertNoGc`
 //! across the block of code that is manipulating raw GC references.
 
-use crate::runtime::vm::{GcRootsList, GcStore, VMGcRef};
+use crate::hash_set::HashSet;
+use crate::runtime::vm::{GcRootsList, GcStore, SendSyncPtr, VMGcRef};
 use crate::{
-    AsContext, AsContextMut, GcRef, Result, RootedGcRef,
+    AsContext, AsContextMut, FrameInfo, GcRef, Ref, Result, RootedGcRef,
     error::OutOfMemory,
     store::{AsStoreOpaque, AutoAssertNoGc, StoreId, StoreOpaque},
 };
@@ -173,6 +188,7 @@ use core::any;
 use core::marker;
 use core::mem::{self, MaybeUninit};
 use core::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
+use core::ptr::NonNull;
 use core::{
     fmt::{self, Debug},
     hash::{Hash, Hasher},
@@ -444,8 +460,8 @@ pub(crate) struct RootSet {
     owned_rooted: Slab<VMGcRef>,
 
     /// List of liveness flags and corresponding `SlabId`s into the
-    /// `owned_rooted` slab.
-    liveness_flags: Vec<(Weak<()>, SlabId)>,
+    /// `owned_rooted` and `weak_rooted` slabs.
+    liveness_flags: Vec<(Weak<()>, LivenessId)>,
 
     /// High-water mark for liveness flag trimming. We use this to
     /// ensure we have amortized constant-time behavior on adding
@@ -459,6 +475,13 @@ pub(crate) struct RootSet {
     /// Generation counter for entries to prevent ABA bugs with `RootScope` and
     /// `Rooted<T>`.
     lifo_generation: u32,
+
+    /// Weak GC roots, for use with `WeakRooted<T>`.
+    ///
+    /// These are unrooted when their liveness flags are cleared, just like
+    /// `owned_rooted`, unless they still have finalizers waiting on their
+    /// referent to be reclaimed.
+    weak_rooted: Slab<WeakRoot>,
 }
 
 #[derive(Debug)]
@@ -467,6 +490,38 @@ struct LifoRoot {
     gc_ref: VMGcRef,
 }
 
+/// The root that a liveness flag in `RootSet::liveness_flags` is guarding.
+#[derive(Clone, Copy, Debug)]
+enum LivenessId {
+    Owned(SlabId),
+    Weak(SlabId),
+}
+
+/// A callback registered with `WeakRooted::on_reclaimed`.
+type Finalizer = Box<dyn FnOnce() + Send + Sync>;
+
+struct WeakRoot {
+    /// The weakly-referenced GC object, or `None` if it has been reclaimed.
+    gc_ref: Option<VMGcRef>,
+
+    /// Callbacks to run once `gc_ref` has been reclaimed.
+    finalizers: Vec<Finalizer>,
+
+    /// Whether every `WeakRooted<T>` for this root has been dropped, in which
+    /// case this entry only remains to run its finalizers.
+    orphaned: bool,
+}
+
+impl Debug for WeakRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakRoot")
+            .field("gc_ref", &self.gc_ref)
+            .field("finalizers", &self.finalizers.len())
+            .field("orphaned", &self.orphaned)
+            .finish()
+    }
+}
+
 impl RootSet {
     pub(crate) fn trace_roots(&mut self, gc_roots_list: &mut GcRootsList) {
         log::trace!("Begin trace user LIFO roots");
@@ -484,6 +539,77 @@ impl RootSet {
             }
         }
         log::trace!("End trace user owned roots");
+
+        log::trace!("Begin trace user weak roots");
+        for (_id, root) in self.weak_rooted.iter_mut() {
+            if root.gc_ref.as_ref().is_some_and(|r| !r.is_i31()) {
+                unsafe {
+                    gc_roots_list.add_weak_root((&mut root.gc_ref).into(), "user weak root");
+                }
+            }
+        }
+        log::trace!("End trace user weak roots");
+    }
+
+    /// Clear the weak roots whose referents `gc_store` reported as reclaimed,
+    /// and take the finalizers of every weak root whose referent has been
+    /// reclaimed.
+    ///
+    /// Weak roots that the collector cleared itself are only examined when
+    /// `after_gc` is true, which avoids scanning every weak root when nothing
+    /// has been reclaimed.
+    ///
+    /// The caller is responsible for running the returned finalizers.
+    pub(crate) fn take_reclaimed_weak_roots(
+        &mut self,
+        gc_store: &mut GcStore,
+        after_gc: bool,
+    ) -> Vec<Finalizer> {
+        let mut reclaimed = Vec::new();
+        gc_store.take_reclaimed_weak_targets(&mut reclaimed);
+        if reclaimed.is_empty() && !after_gc {
+            return Vec::new();
+        }
+        let reclaimed = reclaimed.into_iter().collect::<HashSet<_>>();
+
+        let mut finalizers = Vec::new();
+        let mut orphans = Vec::new();
+        for (id, root) in self.weak_rooted.iter_mut() {
+            if root.gc_ref.as_ref().is_some_and(|r| reclaimed.contains(r)) {
+                root.gc_ref = None;
+            }
+            if root.gc_ref.is_none() {
+                finalizers.append(&mut root.finalizers);
+                if root.orphaned {
+                    orphans.push(id);
+                }
+            }
+        }
+        for id in orphans {
+            self.weak_rooted.dealloc(id);
+        }
+        finalizers
+    }
+
+    /// Get the GC references of all host-held roots, along with what kind of
+    /// root is holding each of them.
+    ///
+    /// Weak roots whose referents have been reclaimed are skipped.
+    pub(crate) fn host_roots(&self) -> impl Iterator<Item = (HostGcRootKind, &VMGcRef)> + '_ {
+        let lifo = self
+            .lifo_roots
+            .iter()
+            .map(|root| (HostGcRootKind::Scoped, &root.gc_ref));
+        let owned = self
+            .owned_rooted
+            .iter()
+            .map(|(_id, gc_ref)| (HostGcRootKind::Owned, gc_ref));
+        let weak = self
+            .weak_rooted
+            .iter()
+            .filter(|(_id, root)| !root.orphaned)
+            .filter_map(|(_id, root)| Some((HostGcRootKind::Weak, root.gc_ref.as_ref()?)));
+        lifo.chain(owned).chain(weak)
     }
 
     /// Enter a LIFO rooting scope.
@@ -711,12 +837,29 @@ impl RootSet {
             return;
         }
 
-        self.liveness_flags.retain(|(flag, index)| {
+        self.liveness_flags.retain(|(flag, id)| {
             if flag.strong_count() == 0 {
-                // No more `OwnedRooted` instances are holding onto
-                // this; dealloc the index and drop our Weak.
-                let gc_ref = self.owned_rooted.dealloc(*index);
-                gc_store.drop_gc_ref(gc_ref);
+                match *id {
+                    // No more `OwnedRooted` instances are holding onto
+                    // this; dealloc the index and drop our Weak.
+                    LivenessId::Owned(index) => {
+                        let gc_ref = self.owned_rooted.dealloc(index);
+                        gc_store.drop_gc_ref(gc_ref);
+                    }
+                    // No more `WeakRooted` instances are holding onto
+                    // this. Weak roots don't own their GC reference, so
+                    // there is nothing to drop, but keep the entry around
+                    // if it has finalizers that still need to run once its
+                    // referent is reclaimed.
+                    LivenessId::Weak(index) => {
+                        let root = &mut self.weak_rooted[index];
+                        if !root.finalizers.is_empty() {
+                            root.orphaned = true;
+                        } else {
+                            self.weak_rooted.dealloc(index);
+                        }
+                    }
+                }
                 // Don't retain in the list.
                 false
             } else {
@@ -1071,6 +1214,56 @@ impl<T: GcRef> Rooted<T> {
         Ok(OwnedRooted::new(&mut store, gc_ref)?)
     }
 
+    /// Create a [`WeakRooted<T>`][crate::WeakRooted] referring to the same GC
+    /// object as `self`, without keeping that object alive.
+    ///
+    /// This does not unroot `self`, and `self` remains valid until its
+    /// associated scope is exited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `self` is used outside of its scope and has
+    /// therefore been unrooted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this object is not associated with the given store.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn _foo() -> Result<()> {
+    /// let mut store = Store::<()>::default();
+    ///
+    /// let weak = {
+    ///     let mut scope = RootScope::new(&mut store);
+    ///     let x = ExternRef::new(&mut scope, "hello!")?;
+    ///     let weak = x.downgrade(&mut scope)?;
+    ///
+    ///     // While `x` is still rooted, the weak reference can be upgraded.
+    ///     assert!(weak.upgrade(&mut scope).is_some());
+    ///     weak
+    /// };
+    ///
+    /// // Once nothing else is holding the object alive, a GC reclaims it and
+    /// // the weak reference can no longer be upgraded.
+    /// store.gc(None)?;
+    /// assert!(weak.upgrade(&mut store).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn downgrade(&self, mut store: impl AsContextMut) -> Result<WeakRooted<T>> {
+        self._downgrade(store.as_context_mut().0)
+    }
+
+    pub(crate) fn _downgrade(&self, store: &mut StoreOpaque) -> Result<WeakRooted<T>> {
+        store.process_reclaimed_weak_roots(false);
+        let mut store = AutoAssertNoGc::new(store);
+        let gc_ref = self.try_gc_ref(&store)?.unchecked_copy();
+        WeakRooted::new(&mut store, gc_ref)
+    }
+
     /// Are these two `Rooted<T>`s the same GC root?
     ///
     /// Note that this function can return `false` even when `a` and `b` are
@@ -1664,7 +1857,7 @@ where
         let liveness_flag = Arc::new(());
         roots
             .liveness_flags
-            .push((Arc::downgrade(&liveness_flag), id));
+            .push((Arc::downgrade(&liveness_flag), LivenessId::Owned(id)));
         Ok(OwnedRooted {
             inner: GcRootIndex {
                 store_id: store.id(),
@@ -1966,6 +2159,287 @@ impl<T: GcRef> RootedGcRefImpl<T> for OwnedRooted<T> {
     }
 }
 
+/// A weak reference to a garbage-collected `T`.
+///
+/// Unlike [`Rooted<T>`][crate::Rooted] and
+/// [`OwnedRooted<T>`][crate::OwnedRooted], a `WeakRooted<T>` does not keep its
+/// referent alive. Once nothing else is keeping the object alive it may be
+/// reclaimed by the collector, after which [`upgrade`][WeakRooted::upgrade]
+/// returns `None`. This is useful for building host-side weak tables and
+/// caches keyed on guest GC objects.
+///
+/// `WeakRooted<T>`s are created with [`Rooted::downgrade`]. Like
+/// `OwnedRooted<T>`, a `WeakRooted<T>` is unregistered from its store some
+/// time after it, and all of its clones, are dropped.
+///
+/// Objects are only reclaimed during a GC, and the null collector never
+/// reclaims any objects. Additionally, `i31ref`s are never reclaimed.
+///
+/// # Example
+///
+/// ```
+/// # use wasmtime::*;
+/// # fn _foo() -> Result<()> {
+/// let mut store = Store::<()>::default();
+///
+/// let mut scope = RootScope::new(&mut store);
+/// let x = ExternRef::new(&mut scope, 42_u32)?;
+/// let weak: WeakRooted<ExternRef> = x.downgrade(&mut scope)?;
+///
+/// // Upgrading creates a new `Rooted<T>` in the current scope.
+/// let y = weak.upgrade(&mut scope).expect("`x` is still alive");
+/// assert!(Rooted::ref_eq(&scope, &x, &y)?);
+/// # Ok(())
+/// # }
+/// ```
+pub struct WeakRooted<T: GcRef> {
+    store_id: StoreId,
+    id: SlabId,
+    liveness_flag: Arc<()>,
+    _phantom: marker::PhantomData<T>,
+}
+
+impl<T: GcRef> Debug for WeakRooted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("WeakRooted<{}>", any::type_name::<T>());
+        f.debug_struct(&name)
+            .field("store_id", &self.store_id)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<T: GcRef> Clone for WeakRooted<T> {
+    fn clone(&self) -> Self {
+        WeakRooted {
+            store_id: self.store_id,
+            id: self.id,
+            liveness_flag: self.liveness_flag.clone(),
+            _phantom: marker::PhantomData,
+        }
+    }
+}
+
+impl<T: GcRef> WeakRooted<T> {
+    /// Construct a new weak GC root.
+    ///
+    /// `gc_ref` should belong to `store`'s heap and should be a GC reference
+    /// pointing to an instance of the GC type that `T` represents. It is not
+    /// consumed, as weak roots do not hold a reference to their referent.
+    fn new(store: &mut AutoAssertNoGc<'_>, gc_ref: VMGcRef) -> Result<Self> {
+        store.trim_gc_liveness_flags(false);
+
+        if !gc_ref.is_i31() {
+            store.require_gc_store_mut()?.note_weak_target(&gc_ref)?;
+        }
+
+        let roots = store.gc_roots_mut();
+        let id = roots.weak_rooted.alloc(WeakRoot {
+            gc_ref: Some(gc_ref),
+            finalizers: Vec::new(),
+            orphaned: false,
+        })?;
+        let liveness_flag = Arc::new(());
+        roots
+            .liveness_flags
+            .push((Arc::downgrade(&liveness_flag), LivenessId::Weak(id)));
+        Ok(WeakRooted {
+            store_id: store.id(),
+            id,
+            liveness_flag,
+            _phantom: marker::PhantomData,
+        })
+    }
+
+    fn assert_same_store(&self, store: &StoreOpaque) {
+        assert!(self.store_id == store.id(), "object used with wrong store");
+    }
+
+    /// Get a [`Rooted<T>`][crate::Rooted] for this weak reference's referent,
+    /// rooted in the given context's scope.
+    ///
+    /// Returns `None` if the referent has been reclaimed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this object is not associated with the given context's store.
+    pub fn upgrade(&self, mut context: impl AsContextMut) -> Option<Rooted<T>> {
+        self._upgrade(context.as_context_mut().0)
+    }
+
+    pub(crate) fn _upgrade(&self, store: &mut StoreOpaque) -> Option<Rooted<T>> {
+        self.assert_same_store(store);
+        store.process_reclaimed_weak_roots(false);
+        let mut store = AutoAssertNoGc::new(store);
+        let gc_ref = store.gc_roots().weak_rooted[self.id]
+            .gc_ref
+            .as_ref()?
+            .unchecked_copy();
+        let gc_ref = store.clone_gc_ref(&gc_ref);
+        Some(Rooted::new(&mut store, gc_ref))
+    }
+
+    /// Register a callback to run once this weak reference's referent has
+    /// been reclaimed.
+    ///
+    /// The callback runs once the collection that reclaimed the object has
+    /// finished, on the thread that performed it. Callbacks do not have access to the
+    /// store; embedders that need to react to reclamation from within the
+    /// store can use the callback to, for example, enqueue work that is
+    /// processed later.
+    ///
+    /// Callbacks remain registered even if this `WeakRooted<T>` and all of its
+    /// clones are dropped. If the referent has already been reclaimed, the
+    /// callback is run immediately. Callbacks are never run for objects that
+    /// are never reclaimed, for example because the store is dropped first,
+    /// the null collector is in use, or the referent is an `i31ref`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this object is not associated with the given context's store.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # use std::sync::Arc;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # fn _foo() -> Result<()> {
+    /// let mut store = Store::<()>::default();
+    /// let reclaimed = Arc::new(AtomicBool::new(false));
+    ///
+    /// {
+    ///     let mut scope = RootScope::new(&mut store);
+    ///     let x = ExternRef::new(&mut scope, "hello!")?;
+    ///     let weak = x.downgrade(&mut scope)?;
+    ///     let flag = reclaimed.clone();
+    ///     weak.on_reclaimed(&mut scope, move || flag.store(true, Ordering::SeqCst));
+    /// }
+    ///
+    /// store.gc(None)?;
+    /// assert!(reclaimed.load(Ordering::SeqCst));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_reclaimed(
+        &self,
+        mut context: impl AsContextMut,
+        callback: impl FnOnce() + Send + Sync + 'static,
+    ) {
+        let store = context.as_context_mut().0;
+        self.assert_same_store(store);
+        store.process_reclaimed_weak_roots(false);
+        let root = &mut store.gc_roots_mut().weak_rooted[self.id];
+        if root.gc_ref.is_some() {
+            root.finalizers.push(Box::new(callback));
+        } else {
+            callback();
+        }
+    }
+}
+
+/// The kind of host-held GC root, as reported by
+/// [`Store::host_gc_roots`][crate::Store::host_gc_roots].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HostGcRootKind {
+    /// A [`Rooted<T>`][crate::Rooted] in a rooting scope that has not exited
+    /// yet.
+    Scoped,
+    /// An [`OwnedRooted<T>`][crate::OwnedRooted].
+    Owned,
+    /// A [`WeakRooted<T>`][crate::WeakRooted] whose referent has not been
+    /// reclaimed.
+    Weak,
+}
+
+/// A GC object that the host is holding onto, as reported by
+/// [`Store::host_gc_roots`][crate::Store::host_gc_roots].
+#[derive(Debug)]
+pub struct HostGcRoot {
+    kind: HostGcRootKind,
+    value: Ref,
+}
+
+impl HostGcRoot {
+    pub(crate) fn new(kind: HostGcRootKind, value: Ref) -> Self {
+        HostGcRoot { kind, value }
+    }
+
+    /// The kind of root that is holding onto this object.
+    pub fn kind(&self) -> HostGcRootKind {
+        self.kind
+    }
+
+    /// The rooted object.
+    ///
+    /// This is an `externref`, `exnref`, or `anyref` depending on the kind of
+    /// the underlying GC object, regardless of the static type of the root
+    /// that is holding onto it. The object is rooted in the scope that was
+    /// active when it was reported.
+    pub fn value(&self) -> &Ref {
+        &self.value
+    }
+}
+
+/// A GC reference held in a stack slot of an active Wasm frame, as reported
+/// by [`StoreContextMut::wasm_stack_gc_roots`][crate::StoreContextMut::wasm_stack_gc_roots].
+#[derive(Debug)]
+pub struct WasmStackGcRoot {
+    frame: FrameInfo,
+    text_offset: usize,
+    slot: SendSyncPtr<u32>,
+    value: Ref,
+}
+
+impl WasmStackGcRoot {
+    pub(crate) fn new(
+        frame: FrameInfo,
+        text_offset: usize,
+        slot: SendSyncPtr<u32>,
+        value: Ref,
+    ) -> Self {
+        WasmStackGcRoot {
+            frame,
+            text_offset,
+            slot,
+            value,
+        }
+    }
+
+    /// The Wasm frame whose stack slot holds this reference.
+    pub fn frame(&self) -> &FrameInfo {
+        &self.frame
+    }
+
+    /// The offset of the frame's current safepoint in the
+    /// [`.text` section][crate::Module::text] of the frame's module.
+    ///
+    /// This matches the [`ModuleStackMap::offset`][crate::ModuleStackMap::offset]
+    /// of the stack map that describes the frame.
+    pub fn text_offset(&self) -> usize {
+        self.text_offset
+    }
+
+    /// The address of the stack slot holding the raw, 32-bit GC reference.
+    ///
+    /// The address is only valid while the frame is active, which is at most
+    /// until the host function that enumerated it returns. Note that
+    /// collections performed by Wasmtime may update the slot, for example
+    /// when a copying collector moves the object.
+    pub fn slot(&self) -> NonNull<u32> {
+        self.slot.as_non_null()
+    }
+
+    /// The referenced object.
+    ///
+    /// This is an `externref`, `exnref`, or `anyref` depending on the kind of
+    /// the underlying GC object, regardless of the static type of the slot. The
+    /// object is rooted in the scope that was active when it was reported.
+    pub fn value(&self) -> &Ref {
+        &self.value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ExternRef;
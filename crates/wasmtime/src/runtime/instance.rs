@@ -124,7 +124,7 @@ impl Instance {
         // `typecheck_externs` above which satisfies the condition that all
         // the imports are valid for this module.
         vm::assert_ready(unsafe {
-            Instance::new_started(&mut store, module, imports.as_ref(), Asyncness::No)
+            Instance::new_started(&mut store, module, imports.as_ref(), &[], Asyncness::No)
         })
     }
 
@@ -205,7 +205,9 @@ impl Instance {
         let mut store = store.as_context_mut();
         let imports = Instance::typecheck_externs(store.0, module, imports)?;
         // See `new` for notes on this unsafety
-        unsafe { Instance::new_started(&mut store, module, imports.as_ref(), Asyncness::Yes).await }
+        unsafe {
+            Instance::new_started(&mut store, module, imports.as_ref(), &[], Asyncness::Yes).await
+        }
     }
 
     fn typecheck_externs(
@@ -247,11 +249,16 @@ impl Instance {
 
     /// Internal function to create an instance and run the start function.
     ///
+    /// The `items` are the linker definitions that `imports` were created
+    /// from, if any, whose per-instance contexts are created before the
+    /// instance is started.
+    ///
     /// This function's unsafety is the same as `Instance::new_raw`.
     pub(crate) async unsafe fn new_started<T>(
         store: &mut StoreContextMut<'_, T>,
         module: &Module,
         imports: Imports<'_>,
+        items: &[Definition],
         asyncness: Asyncness,
     ) -> Result<Instance> {
         let instance = {
@@ -260,6 +267,11 @@ impl Instance {
             // function.
             unsafe { Instance::new_raw(store, limiter.as_mut(), module, imports).await? }
         };
+        for item in items {
+            if let Definition::HostFunc(func) = item {
+                func.init_instance_context(store.0, instance.id.instance());
+            }
+        }
 
         // If this instance requires startup, which is a dynamic decision made
         // at this point in conjunction with analysis at compile time, the
@@ -306,6 +318,29 @@ impl Instance {
         if !Engine::same(store.engine(), module.engine()) {
             bail!("cross-`Engine` instantiation is not currently supported");
         }
+        if let Some(limiter) = limiter.as_deref_mut() {
+            if !limiter
+                .instance_creating(module, store.instance_count())
+                .await?
+            {
+                bail!("resource limiter rejected instantiation of module");
+            }
+        }
+
+        // Prefer to reuse an instance of this module that was previously
+        // marked as recyclable. Such an instance was already accounted for in
+        // resource counts and already has its module registered.
+        //
+        // SAFETY: the contract of `reuse_recyclable_instance` is the same as
+        // this function's.
+        if let Some(id) = unsafe {
+            store
+                .reuse_recyclable_instance(limiter.as_deref_mut(), module, imports)
+                .await?
+        } {
+            return Ok(Instance::from_wasmtime(id, store));
+        }
+
         store.bump_resource_counts(module)?;
 
         // Allocate the GC heap, if necessary.
@@ -363,13 +398,17 @@ impl Instance {
                 .expect("should have a startup function")
         };
         let caller_vmctx = instance.vmctx();
-        unsafe {
+        #[cfg(target_has_atomic = "64")]
+        let prev_deadline = store.0.enter_start_function_deadline();
+        let result = unsafe {
             let funcref = f.vm_func_ref(store.0);
             super::func::invoke_wasm_and_catch_traps(store, |_default_caller, vm| {
                 VMFuncRef::array_call(funcref, vm, caller_vmctx, NonNull::from(&mut []))
-            })?;
-        }
-        Ok(())
+            })
+        };
+        #[cfg(target_has_atomic = "64")]
+        store.0.exit_start_function_deadline(prev_deadline);
+        result
     }
 
     /// Get this instance's module.
@@ -465,7 +504,7 @@ impl Instance {
         Some(self._get_export(store, export.entity))
     }
 
-    fn _get_export(&self, store: &mut StoreOpaque, entity: EntityIndex) -> Extern {
+    pub(crate) fn _get_export(&self, store: &mut StoreOpaque, entity: EntityIndex) -> Extern {
         let id = store.id();
         // SAFETY: the store `id` owns this instance and all exports contained
         // within.
@@ -587,6 +626,108 @@ impl Instance {
         self.get_export(store, name)?.into_tag()
     }
 
+    /// Captures the contents of all linear memories, tables, and mutable
+    /// globals defined by this instance into a
+    /// [`StateImage`](crate::StateImage).
+    ///
+    /// The returned image can later be restored into this instance, or into
+    /// another instance of the same module, with [`Instance::thaw`], for
+    /// example to checkpoint a long-running instance or to migrate it between
+    /// hosts. See [`StateImage`](crate::StateImage) for what is and isn't
+    /// captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this instance defines state which can't be
+    /// captured, such as shared memories, tables or globals of GC reference
+    /// types, or references to functions of other instances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this instance.
+    #[cfg(feature = "state-image")]
+    pub fn freeze(&self, mut store: impl AsContextMut) -> Result<crate::StateImage> {
+        crate::StateImage::freeze(store.as_context_mut(), self)
+    }
+
+    /// Restores the state captured by [`Instance::freeze`] into this instance.
+    ///
+    /// Linear memories and tables are grown as necessary to match the sizes
+    /// in `image`, and their contents, along with those of all mutable
+    /// globals, are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `image` wasn't captured from an instance of the
+    /// same module or is otherwise malformed, if a memory or table is larger
+    /// than in `image`, or if growing a memory or table fails. The image is
+    /// checked in full before anything is modified, so the instance is left
+    /// unchanged on error, except that memories and tables may have been
+    /// grown if growing a later one fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this instance, or if the store has an
+    /// async [`ResourceLimiterAsync`](crate::ResourceLimiterAsync) configured.
+    #[cfg(feature = "state-image")]
+    pub fn thaw(&self, mut store: impl AsContextMut, image: &crate::StateImage) -> Result<()> {
+        image.thaw(store.as_context_mut(), self)
+    }
+
+    /// Marks this instance as recyclable, allowing its resources to be reused
+    /// by a future instantiation of the same module within `store`.
+    ///
+    /// Instantiating a module in a store normally allocates a new instance,
+    /// which is never deallocated until the store itself is dropped. For
+    /// embeddings which create an instance per request that's a growing cost,
+    /// and this method is a way to amortize it: the next instantiation of
+    /// this instance's module in `store`, for example through
+    /// [`Instance::new`], [`Linker::instantiate`](crate::Linker::instantiate),
+    /// or [`InstancePre::instantiate`], will reset and reuse this instance
+    /// rather than allocating a new one. Reuse involves:
+    ///
+    /// * Handing the instance's linear memories and tables back to the
+    ///   engine's instance allocator and allocating fresh ones. With the
+    ///   [pooling allocator](crate::PoolingAllocationConfig) this zeroes
+    ///   memories, or resets their copy-on-write images, and resets tables
+    ///   while keeping affinity between the module and its slots.
+    /// * Resetting globals, passive data and element segments, and imports to
+    ///   their initial state for the new instantiation.
+    /// * Running the module's initialization and `start` function again.
+    ///
+    /// The instance's slot in the store, its module registration, and its
+    /// `VMContext` are all reused as-is, and the reused instance doesn't
+    /// count against the store's instance limit again.
+    ///
+    /// Once an instance is reused, this [`Instance`] handle and all items
+    /// previously exported from it refer to the new instantiation. Embedders
+    /// should therefore only mark an instance as recyclable once they're done
+    /// with it. Until it's reused the instance remains fully usable.
+    ///
+    /// Reuse only happens when no WebAssembly is executing within `store`;
+    /// instantiations performed from within host functions, for example,
+    /// allocate a new instance as usual. If allocating the replacement
+    /// memories or tables fails then the instantiation fails and this
+    /// instance remains recyclable and untouched.
+    ///
+    /// Marking an instance as recyclable more than once has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of this instance's memories has been aliased
+    /// with [`Memory::alias`](crate::Memory::alias), since the alias would
+    /// otherwise outlive the memory. Such instances are also never reused if
+    /// they're aliased after being marked as recyclable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this instance.
+    pub fn mark_recyclable(&self, mut store: impl AsContextMut) -> Result<()> {
+        let store = store.as_context_mut().0;
+        self.id.store_id().assert_belongs_to(store.id());
+        store.mark_instance_recyclable(self.id.instance())
+    }
+
     #[allow(
         dead_code,
         reason = "c-api crate does not yet support exnrefs and causes this method to be dead."
@@ -902,7 +1043,13 @@ impl<T: 'static> InstancePre<T> {
         // constructor of `InstancePre` to assert that all the imports we're passing
         // in match the module we're instantiating.
         vm::assert_ready(unsafe {
-            Instance::new_started(&mut store, &self.module, imports.as_ref(), Asyncness::No)
+            Instance::new_started(
+                &mut store,
+                &self.module,
+                imports.as_ref(),
+                &self.items,
+                Asyncness::No,
+            )
         })
     }
 
@@ -941,7 +1088,14 @@ impl<T: 'static> InstancePre<T> {
         // constructor of `InstancePre` to assert that all the imports we're passing
         // in match the module we're instantiating.
         unsafe {
-            Instance::new_started(&mut store, &self.module, imports.as_ref(), Asyncness::Yes).await
+            Instance::new_started(
+                &mut store,
+                &self.module,
+                imports.as_ref(),
+                &self.items,
+                Asyncness::Yes,
+            )
+            .await
         }
     }
 }
@@ -12,7 +12,8 @@ use core::ops::Range;
 use core::str;
 use wasmtime_environ::{
     CompiledFunctionsTable, CompiledModuleInfo, DefinedFuncIndex, FilePos, FuncIndex, FuncKey,
-    FunctionLoc, FunctionName, Metadata, Module, ModuleInternedTypeIndex, StaticModuleIndex,
+    FunctionLoc, FunctionName, IndirectName, Metadata, Module, ModuleInternedTypeIndex,
+    StaticModuleIndex,
 };
 
 /// A compiled wasm module, ready to be instantiated.
@@ -25,6 +26,10 @@ pub struct CompiledModule {
     index: Arc<CompiledFunctionsTable>,
     /// Sorted list, by function index, of names we have for this module.
     func_names: Vec<FunctionName>,
+    /// Sorted list, by function and local index, of local names.
+    local_names: Vec<IndirectName>,
+    /// Sorted list, by function and label index, of label names.
+    label_names: Vec<IndirectName>,
 }
 
 impl CompiledModule {
@@ -58,6 +63,8 @@ impl CompiledModule {
             meta: info.meta,
             index,
             func_names: info.func_names,
+            local_names: info.local_names,
+            label_names: info.label_names,
         };
         ret.register_profiling(profiler)?;
 
@@ -100,13 +107,68 @@ impl CompiledModule {
         // Find entry for `idx`, if present.
         let i = self.func_names.binary_search_by_key(&idx, |n| n.idx).ok()?;
         let name = &self.func_names[i];
+        Some(self.name_data(name.offset, name.len))
+    }
+
+    /// Returns an iterator over all function names in this module's `name`
+    /// section, sorted by function index.
+    pub fn func_names(&self) -> impl ExactSizeIterator<Item = (FuncIndex, &str)> + '_ {
+        self.func_names
+            .iter()
+            .map(|n| (n.idx, self.name_data(n.offset, n.len)))
+    }
+
+    /// Looks up the `name` section name for local `local` of function `func`,
+    /// if one was specified in the original wasm module.
+    pub fn local_name(&self, func: FuncIndex, local: u32) -> Option<&str> {
+        self.indirect_name(&self.local_names, func, local)
+    }
+
+    /// Returns an iterator over the named locals of function `func`, sorted
+    /// by local index.
+    pub fn local_names(&self, func: FuncIndex) -> impl Iterator<Item = (u32, &str)> + '_ {
+        self.indirect_names(&self.local_names, func)
+    }
+
+    /// Looks up the `name` section name for label `label` of function `func`,
+    /// if one was specified in the original wasm module.
+    pub fn label_name(&self, func: FuncIndex, label: u32) -> Option<&str> {
+        self.indirect_name(&self.label_names, func, label)
+    }
 
+    /// Returns an iterator over the named labels of function `func`, sorted
+    /// by label index.
+    pub fn label_names(&self, func: FuncIndex) -> impl Iterator<Item = (u32, &str)> + '_ {
+        self.indirect_names(&self.label_names, func)
+    }
+
+    fn indirect_name(&self, names: &[IndirectName], func: FuncIndex, index: u32) -> Option<&str> {
+        let i = names
+            .binary_search_by_key(&(func, index), |n| (n.func, n.index))
+            .ok()?;
+        let name = &names[i];
+        Some(self.name_data(name.offset, name.len))
+    }
+
+    fn indirect_names<'a>(
+        &'a self,
+        names: &'a [IndirectName],
+        func: FuncIndex,
+    ) -> impl Iterator<Item = (u32, &'a str)> + 'a {
+        let start = names.partition_point(|n| n.func < func);
+        names[start..]
+            .iter()
+            .take_while(move |n| n.func == func)
+            .map(|n| (n.index, self.name_data(n.offset, n.len)))
+    }
+
+    fn name_data(&self, offset: u32, len: u32) -> &str {
         // Here we `unwrap` the `from_utf8` but this can theoretically be a
         // `from_utf8_unchecked` if we really wanted since this section is
         // guaranteed to only have valid utf-8 data. Until it's a problem it's
         // probably best to double-check this though.
         let data = self.engine_code.func_name_data();
-        Some(str::from_utf8(&data[name.offset as usize..][..name.len as usize]).unwrap())
+        str::from_utf8(&data[offset as usize..][..len as usize]).unwrap()
     }
 
     /// Returns an iterator over all functions defined within this module with
@@ -225,6 +287,12 @@ impl CompiledModule {
         self.meta.has_unparsed_debuginfo
     }
 
+    /// Returns whether this module was compiled by the engine's background
+    /// recompiler rather than its primary compiler.
+    pub fn is_recompiled(&self) -> bool {
+        self.meta.recompiled
+    }
+
     /// Indicates whether this module came with n address map such that lookups
     /// via `wasmtime_environ::lookup_file_pos` will succeed.
     ///
@@ -1,4 +1,6 @@
 use crate::prelude::*;
+use crate::store::{InstanceId, StoreId, StoreInstanceId};
+use crate::{Instance, Module};
 
 /// Value returned by [`ResourceLimiter::instances`] default method
 pub const DEFAULT_INSTANCE_LIMIT: usize = 10000;
@@ -90,6 +92,45 @@ pub trait ResourceLimiter: Send {
         Ok(())
     }
 
+    /// Same as [`ResourceLimiter::memory_growing`], but additionally receives
+    /// a [`ResourceContext`] describing which linear memory is growing and
+    /// which instance it belongs to.
+    ///
+    /// This is the method Wasmtime invokes for the creation and growth of
+    /// linear memories, and its default implementation forwards to
+    /// [`ResourceLimiter::memory_growing`]. Implement it instead of
+    /// `memory_growing` to attribute memory consumption to a particular
+    /// instance or module of a store.
+    fn memory_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let _ = context;
+        self.memory_growing(current, desired, maximum)
+    }
+
+    /// Notifies the resource limiter that the host is creating a linear
+    /// memory, for example with [`Memory::new`](crate::Memory::new) to define
+    /// it in a [`Linker`](crate::Linker).
+    ///
+    /// * `minimum` is the initial size of the linear memory in bytes.
+    /// * `maximum` is the linear memory's maximum in bytes, if any.
+    ///
+    /// Growth of such a memory afterwards is reported to
+    /// [`ResourceLimiter::memory_growing_with_context`] with a context for
+    /// which [`ResourceContext::is_host`] returns `true`. By default this
+    /// method forwards to `memory_growing_with_context` with such a context
+    /// and a `current` size of zero.
+    ///
+    /// See the details on the return values for `memory_growing` for what the
+    /// return value of this function indicates.
+    fn host_memory_creating(&mut self, minimum: usize, maximum: Option<usize>) -> Result<bool> {
+        self.memory_growing_with_context(&ResourceContext::host(), 0, minimum, maximum)
+    }
+
     /// Notifies the resource limiter that an instance's table has been
     /// requested to grow.
     ///
@@ -125,6 +166,42 @@ pub trait ResourceLimiter: Send {
         Ok(())
     }
 
+    /// Same as [`ResourceLimiter::table_growing`], but additionally receives a
+    /// [`ResourceContext`] describing which table is growing and which
+    /// instance it belongs to.
+    ///
+    /// This is the method Wasmtime invokes for the creation and growth of
+    /// tables, and its default implementation forwards to
+    /// [`ResourceLimiter::table_growing`].
+    fn table_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let _ = context;
+        self.table_growing(current, desired, maximum)
+    }
+
+    /// Notifies the resource limiter that `module` is about to be
+    /// instantiated.
+    ///
+    /// * `module` is the module being instantiated.
+    /// * `current` is the number of instances created within the store so
+    ///   far.
+    ///
+    /// This is invoked before any of the instance's memories or tables are
+    /// created. If `Ok(false)` is returned then instantiation fails with an
+    /// error, and if `Err(e)` is returned then instantiation fails with `e`.
+    ///
+    /// This method by default allows all instantiations, subject to the
+    /// limit returned by [`ResourceLimiter::instances`].
+    fn instance_creating(&mut self, module: &Module, current: usize) -> Result<bool> {
+        let _ = (module, current);
+        Ok(true)
+    }
+
     /// The maximum number of instances that can be created for a `Store`.
     ///
     /// Module instantiation will fail if this limit is exceeded.
@@ -185,6 +262,28 @@ pub trait ResourceLimiterAsync: Send {
         Ok(())
     }
 
+    /// Asynchronous version of [`ResourceLimiter::memory_growing_with_context`]
+    async fn memory_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let _ = context;
+        self.memory_growing(current, desired, maximum).await
+    }
+
+    /// Asynchronous version of [`ResourceLimiter::host_memory_creating`]
+    async fn host_memory_creating(
+        &mut self,
+        minimum: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.memory_growing_with_context(&ResourceContext::host(), 0, minimum, maximum)
+            .await
+    }
+
     /// Asynchronous version of [`ResourceLimiter::table_growing`]
     async fn table_growing(
         &mut self,
@@ -199,6 +298,24 @@ pub trait ResourceLimiterAsync: Send {
         Ok(())
     }
 
+    /// Asynchronous version of [`ResourceLimiter::table_growing_with_context`]
+    async fn table_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let _ = context;
+        self.table_growing(current, desired, maximum).await
+    }
+
+    /// Asynchronous version of [`ResourceLimiter::instance_creating`]
+    async fn instance_creating(&mut self, module: &Module, current: usize) -> Result<bool> {
+        let _ = (module, current);
+        Ok(true)
+    }
+
     /// Identical to [`ResourceLimiter::instances`]`
     fn instances(&self) -> usize {
         DEFAULT_INSTANCE_LIMIT
@@ -215,6 +332,90 @@ pub trait ResourceLimiterAsync: Send {
     }
 }
 
+/// Describes the linear memory or table that a [`ResourceLimiter`] or
+/// [`ResourceLimiterAsync`] is being asked about.
+///
+/// Within a store with many instances this can be used to attribute the
+/// growth of memories and tables to the instance, and module, that requested
+/// it. Memories and tables defined by the host, for example with
+/// [`Memory::new`](crate::Memory::new) or [`Table::new`](crate::Table::new),
+/// have no instance or module. Memories created internally by Wasmtime, such
+/// as the GC heap, have no instance or module either and are not considered
+/// to be defined by the host.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceContext {
+    instance: Option<Instance>,
+    module: Option<Module>,
+    index: Option<u32>,
+    host: bool,
+}
+
+impl ResourceContext {
+    /// Context of a memory or table defined by the host.
+    pub(crate) fn host() -> ResourceContext {
+        ResourceContext {
+            host: true,
+            ..ResourceContext::default()
+        }
+    }
+
+    /// Context of the memory or table at `index`, within the memory or table
+    /// index space of `module`, of the instance `id` within the store `store`.
+    ///
+    /// A `module` of `None` indicates an instance created by Wasmtime to hold
+    /// an item defined by the host.
+    pub(crate) fn new(
+        store: StoreId,
+        id: InstanceId,
+        module: Option<&Module>,
+        index: u32,
+    ) -> ResourceContext {
+        match module {
+            Some(module) => ResourceContext {
+                instance: Some(Instance {
+                    id: StoreInstanceId::new(store, id),
+                }),
+                module: Some(module.clone()),
+                index: Some(index),
+                host: false,
+            },
+            None => ResourceContext::host(),
+        }
+    }
+
+    /// Returns the instance which the memory or table belongs to, if any.
+    pub fn instance(&self) -> Option<Instance> {
+        self.instance
+    }
+
+    /// Returns the module of the instance which the memory or table belongs
+    /// to, if any.
+    pub fn module(&self) -> Option<&Module> {
+        self.module.as_ref()
+    }
+
+    /// Returns the name of the module of the instance which the memory or
+    /// table belongs to, if it has one.
+    ///
+    /// See [`Module::name`] for more information.
+    pub fn module_name(&self) -> Option<&str> {
+        self.module.as_ref()?.name()
+    }
+
+    /// Returns the index of the memory or table within the memory or table
+    /// index space of [`ResourceContext::module`], if it belongs to an
+    /// instance.
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    /// Returns whether the memory or table is defined by the host rather than
+    /// by an instance.
+    pub fn is_host(&self) -> bool {
+        self.host
+    }
+}
+
 /// Used to build [`StoreLimits`].
 pub struct StoreLimitsBuilder(StoreLimits);
 
@@ -2,9 +2,11 @@ use crate::error::OutOfMemory;
 use crate::func::HostFunc;
 use crate::instance::InstancePre;
 use crate::store::StoreOpaque;
+use crate::sync::OnceLock;
 use crate::{
-    AsContext, AsContextMut, Caller, Engine, Extern, ExternType, Func, FuncType, ImportType,
-    Instance, IntoFunc, Module, Result, StoreContextMut, Val, ValRaw, prelude::*,
+    AsContext, AsContextMut, Caller, Engine, Extern, ExternType, Func, FuncType, GlobalType,
+    ImportType, Instance, IntoFunc, MemoryType, Module, Result, StoreContextMut, TableType,
+    TagType, Val, ValRaw, prelude::*,
 };
 use alloc::sync::Arc;
 use core::fmt::{self, Debug};
@@ -12,6 +14,7 @@ use core::fmt::{self, Debug};
 use core::future::Future;
 use core::marker;
 use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use log::warn;
 use wasmtime_environ::{Atom, PanicOnOom, StringPool};
 
@@ -85,6 +88,7 @@ pub struct Linker<T> {
     engine: Engine,
     pool: StringPool,
     map: TryHashMap<ImportKey, Definition>,
+    lazy: TryHashMap<Atom, Arc<LazyModule>>,
     allow_shadowing: bool,
     allow_unknown_exports: bool,
     _marker: marker::PhantomData<fn() -> T>,
@@ -102,6 +106,7 @@ impl<T> Clone for Linker<T> {
             engine: self.engine.clone(),
             pool: self.pool.clone_panic_on_oom(),
             map: self.map.clone_panic_on_oom(),
+            lazy: self.lazy.clone_panic_on_oom(),
             allow_shadowing: self.allow_shadowing,
             allow_unknown_exports: self.allow_unknown_exports,
             _marker: self._marker,
@@ -161,6 +166,7 @@ impl<T> Linker<T> {
         Linker {
             engine: engine.clone(),
             map: TryHashMap::new(),
+            lazy: TryHashMap::new(),
             pool: StringPool::new(),
             allow_shadowing: false,
             allow_unknown_exports: false,
@@ -569,6 +575,107 @@ impl<T> Linker<T> {
         self.func_insert(module, name, func.into_func(&self.engine)?)
     }
 
+    /// Creates a [`Func::wrap`]-style host function which additionally
+    /// receives a context specific to the instance calling it.
+    ///
+    /// Each instance importing the function gets its own clone of `ctx`, made
+    /// when that instance is created and kept until the store is dropped or
+    /// the instance is reused after [`Instance::mark_recyclable`]. Calls made
+    /// directly from the host, for example through [`Func::call`], share one
+    /// context per store. This is useful for plugin hosts which need
+    /// per-plugin state alongside the store-wide `T`, without keeping a map
+    /// from instances to their state in `T`. Note that functions defined with
+    /// separate calls to this method have separate contexts, even if `ctx` is
+    /// shared between them through an [`Arc`].
+    ///
+    /// The context is passed by shared reference, so state which is mutated
+    /// by calls must use interior mutability. The arguments of the function
+    /// are passed in a tuple like with [`Linker::func_wrap_async`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `module` and `name` already identify an item
+    /// of the same type as the `item` provided and if shadowing is disallowed.
+    /// For more information see the documentation on [`Linker`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// # fn main() -> Result<()> {
+    /// # let engine = Engine::default();
+    /// #[derive(Default)]
+    /// struct PluginState {
+    ///     calls: AtomicU32,
+    /// }
+    ///
+    /// impl Clone for PluginState {
+    ///     fn clone(&self) -> Self {
+    ///         PluginState::default()
+    ///     }
+    /// }
+    ///
+    /// let mut linker = Linker::new(&engine);
+    /// linker.func_wrap_ctx(
+    ///     "host",
+    ///     "count",
+    ///     PluginState::default(),
+    ///     |_caller: Caller<'_, ()>, state: &PluginState, (): ()| {
+    ///         state.calls.fetch_add(1, Ordering::Relaxed) + 1
+    ///     },
+    /// )?;
+    ///
+    /// let module = Module::new(
+    ///     &engine,
+    ///     r#"(module
+    ///         (import "host" "count" (func $count (result i32)))
+    ///         (func (export "count") (result i32) call $count)
+    ///     )"#,
+    /// )?;
+    ///
+    /// let mut store = Store::new(&engine, ());
+    /// let a = linker.instantiate(&mut store, &module)?;
+    /// let b = linker.instantiate(&mut store, &module)?;
+    /// let count_a = a.get_typed_func::<(), u32>(&mut store, "count")?;
+    /// let count_b = b.get_typed_func::<(), u32>(&mut store, "count")?;
+    /// assert_eq!(count_a.call(&mut store, ())?, 1);
+    /// assert_eq!(count_a.call(&mut store, ())?, 2);
+    /// assert_eq!(count_b.call(&mut store, ())?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn func_wrap_ctx<C, F, Params, Args>(
+        &mut self,
+        module: &str,
+        name: &str,
+        ctx: C,
+        func: F,
+    ) -> Result<&mut Self>
+    where
+        C: Clone + Send + Sync + 'static,
+        F: Fn(Caller<'_, T>, &C, Params) -> Args + Send + Sync + 'static,
+        Params: crate::WasmTyList,
+        Args: crate::WasmRet,
+        T: 'static,
+    {
+        static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+        let key = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+        let ctx = Arc::new(ctx);
+        let mut func = HostFunc::wrap(&self.engine, {
+            let ctx = ctx.clone();
+            move |mut caller: Caller<'_, T>, params: Params| {
+                // Instances importing this function have their context created
+                // when instantiated, so this only creates the store-wide
+                // context used by calls from the host.
+                let cx = caller.instance_context(key, || C::clone(&ctx));
+                func(caller, &cx, params)
+            }
+        })?;
+        func.set_instance_context(key, move || C::clone(&ctx));
+        self.func_insert(module, name, func)
+    }
+
     /// Asynchronous analog of [`Linker::func_wrap`].
     #[cfg(feature = "async")]
     pub fn func_wrap_async<F, Params: crate::WasmTyList, Args: crate::WasmRet>(
@@ -927,6 +1034,225 @@ impl<T> Linker<T> {
         }
     }
 
+    /// Registers a module to be compiled and instantiated on demand under the
+    /// name `module_name`.
+    ///
+    /// Unlike [`Linker::module`], nothing happens when this method is called:
+    /// the module isn't compiled until another module instantiated through
+    /// [`Linker::instantiate`] or [`Linker::instantiate_async`] imports from
+    /// `module_name`. At that point the module is compiled, once, for the
+    /// lifetime of this linker and all of its clones, and then instantiated
+    /// into the store being used, once per store. The imports of a
+    /// lazily-defined module are resolved with this linker too, so its own
+    /// lazily-defined dependencies are instantiated first. This means large
+    /// graphs of plugins only pay for the modules that are actually used.
+    ///
+    /// Definitions in this linker take precedence over lazily-defined
+    /// modules: an import is only satisfied by a lazily-defined module if no
+    /// item with its name has been defined otherwise.
+    ///
+    /// Lazily-defined modules are instantiated as Reactors, meaning their
+    /// `_initialize` function, if any, is called after instantiation. Commands
+    /// are not supported and result in an error at instantiation time.
+    ///
+    /// Note that [`Linker::instantiate_pre`], [`Linker::check`], and
+    /// [`Linker::get`] don't have access to a store to instantiate into and do
+    /// not consider lazily-defined modules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `module_name` was already registered as a
+    /// lazily-defined module and shadowing is disallowed.
+    ///
+    /// Errors compiling or instantiating a lazily-defined module, as well as
+    /// cycles between lazily-defined modules, are reported by the
+    /// instantiation that required them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// # let engine = Engine::default();
+    /// # let mut store = Store::new(&engine, ());
+    /// let mut linker = Linker::new(&engine);
+    ///
+    /// // Neither of these are compiled yet.
+    /// linker.module_lazy(
+    ///     "math",
+    ///     br#"(module (func (export "double") (param i32) (result i32)
+    ///         (i32.add (local.get 0) (local.get 0))))"#,
+    /// )?;
+    /// linker.module_lazy("unused", b"(module)")?;
+    ///
+    /// let wat = r#"
+    ///     (module
+    ///         (import "math" "double" (func $double (param i32) (result i32)))
+    ///         (func (export "run") (result i32)
+    ///             (call $double (i32.const 21))
+    ///         )
+    ///     )
+    /// "#;
+    /// let module = Module::new(&engine, wat)?;
+    ///
+    /// // Only `math` is compiled and instantiated here.
+    /// let instance = linker.instantiate(&mut store, &module)?;
+    /// let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    /// assert_eq!(run.call(&mut store, ())?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn module_lazy(
+        &mut self,
+        module_name: &str,
+        source: impl Into<LazyModuleSource>,
+    ) -> Result<&mut Self> {
+        let key = self.pool.insert(module_name)?;
+        if !self.allow_shadowing && self.lazy.contains_key(&key) {
+            bail!("lazily-defined module `{module_name}` defined twice");
+        }
+        static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+        let lazy = LazyModule {
+            key: NEXT_KEY.fetch_add(1, Ordering::Relaxed),
+            name: module_name.to_string(),
+            source: source.into(),
+            module: OnceLock::new(),
+        };
+        self.lazy.insert(key, try_new::<Arc<_>>(lazy)?)?;
+        Ok(self)
+    }
+
+    /// Returns the lazily-defined modules which `module` transitively depends
+    /// on that still need to be instantiated into `store`, in the order they
+    /// need to be instantiated.
+    fn lazy_dependencies(
+        &self,
+        store: &StoreOpaque,
+        module: &Module,
+    ) -> Result<Vec<Arc<LazyModule>>> {
+        let mut order = Vec::new();
+        if !self.lazy.is_empty() {
+            let mut path = Vec::new();
+            self.visit_lazy_dependencies(store, module, &mut path, &mut order)?;
+        }
+        Ok(order)
+    }
+
+    fn visit_lazy_dependencies(
+        &self,
+        store: &StoreOpaque,
+        module: &Module,
+        path: &mut Vec<Arc<LazyModule>>,
+        order: &mut Vec<Arc<LazyModule>>,
+    ) -> Result<()> {
+        for import in module.imports() {
+            if self._get(import.module(), import.name()).is_some() {
+                continue;
+            }
+            // Imports which can't be satisfied at all are reported when
+            // resolving the imports of `module` later on.
+            let Some(lazy) = self.lazy_module(import.module()) else {
+                continue;
+            };
+            if store.lazy_instance(lazy.key).is_some() || order.iter().any(|l| Arc::ptr_eq(l, lazy))
+            {
+                continue;
+            }
+            if let Some(i) = path.iter().position(|l| Arc::ptr_eq(l, lazy)) {
+                let cycle = path[i..]
+                    .iter()
+                    .chain([lazy])
+                    .map(|l| format!("`{}`", l.name))
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                bail!("cycle detected between lazily-defined modules: {cycle}");
+            }
+
+            let dependency = lazy.module(&self.engine)?;
+            path.push(lazy.clone());
+            self.visit_lazy_dependencies(store, dependency, path, order)
+                .with_context(|| {
+                    format!(
+                        "failed to resolve imports of lazily-defined module `{}`",
+                        lazy.name
+                    )
+                })?;
+            path.pop();
+            order.push(lazy.clone());
+        }
+        Ok(())
+    }
+
+    fn lazy_module(&self, module: &str) -> Option<&Arc<LazyModule>> {
+        self.lazy.get(&self.pool.get_atom(module)?)
+    }
+
+    /// Instantiates the lazily-defined modules that `module` depends on into
+    /// `store`.
+    fn instantiate_lazy_dependencies(
+        &self,
+        store: &mut StoreContextMut<'_, T>,
+        module: &Module,
+    ) -> Result<()>
+    where
+        T: 'static,
+    {
+        for lazy in self.lazy_dependencies(store.0, module)? {
+            let module = lazy.module(&self.engine)?;
+            let instance = self
+                ._instantiate_pre(module, Some(store))
+                .and_then(|pre| {
+                    LazyModule::check_reactor(module)?;
+                    let instance = pre.instantiate(&mut *store)?;
+                    if let Some(Extern::Func(func)) =
+                        instance.get_export(&mut *store, "_initialize")
+                    {
+                        func.typed::<(), ()>(&*store)
+                            .and_then(|f| f.call(&mut *store, ()))
+                            .context("calling the Reactor initialization function")?;
+                    }
+                    Ok(instance)
+                })
+                .with_context(|| lazy.instantiate_error())?;
+            store.0.set_lazy_instance(lazy.key, instance);
+        }
+        Ok(())
+    }
+
+    /// Same as [`Linker::instantiate_lazy_dependencies`], except for async
+    /// `Store`s.
+    #[cfg(feature = "async")]
+    async fn instantiate_lazy_dependencies_async(
+        &self,
+        store: &mut StoreContextMut<'_, T>,
+        module: &Module,
+    ) -> Result<()>
+    where
+        T: Send + 'static,
+    {
+        for lazy in self.lazy_dependencies(store.0, module)? {
+            let instance = async {
+                let module = lazy.module(&self.engine)?;
+                let pre = self._instantiate_pre(module, Some(store))?;
+                LazyModule::check_reactor(module)?;
+                let instance = pre.instantiate_async(&mut *store).await?;
+                if let Some(Extern::Func(func)) = instance.get_export(&mut *store, "_initialize") {
+                    let func = func
+                        .typed::<(), ()>(&*store)
+                        .context("loading the Reactor initialization function")?;
+                    func.call_async(&mut *store, ())
+                        .await
+                        .context("calling the Reactor initialization function")?;
+                }
+                Ok::<_, Error>(instance)
+            }
+            .await
+            .with_context(|| lazy.instantiate_error())?;
+            store.0.set_lazy_instance(lazy.key, instance);
+        }
+        Ok(())
+    }
+
     fn command(
         &mut self,
         mut store: impl AsContextMut<Data = T>,
@@ -1122,7 +1448,9 @@ impl<T> Linker<T> {
     where
         T: 'static,
     {
-        self._instantiate_pre(module, Some(store.as_context_mut().0))?
+        let mut store = store.as_context_mut();
+        self.instantiate_lazy_dependencies(&mut store, module)?;
+        self._instantiate_pre(module, Some(&mut store))?
             .instantiate(store)
     }
 
@@ -1143,7 +1471,10 @@ impl<T> Linker<T> {
     where
         T: Send + 'static,
     {
-        self._instantiate_pre(module, Some(store.as_context_mut().0))?
+        let mut store = store.as_context_mut();
+        self.instantiate_lazy_dependencies_async(&mut store, module)
+            .await?;
+        self._instantiate_pre(module, Some(&mut store))?
             .instantiate_async(store)
             .await
     }
@@ -1206,6 +1537,66 @@ impl<T> Linker<T> {
         self._instantiate_pre(module, None)
     }
 
+    /// Checks whether all imports of `module` can be satisfied by this linker,
+    /// returning a [`LinkError`] for each one which can't.
+    ///
+    /// Unlike [`Linker::instantiate`] and [`Linker::instantiate_pre`], which
+    /// fail on the first import that can't be satisfied, this reports every
+    /// missing import and every import whose definition has an incompatible
+    /// type, in the order they're imported by `module`. An empty list means
+    /// [`Linker::instantiate_pre`] will succeed for `module`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// # let engine = Engine::default();
+    /// let mut linker = Linker::<()>::new(&engine);
+    /// linker.func_wrap("host", "double", |x: i32| x * 2)?;
+    ///
+    /// let wat = r#"
+    ///     (module
+    ///         (import "host" "double" (func (param i64) (result i64)))
+    ///         (import "host" "log" (func (param i32)))
+    ///     )
+    /// "#;
+    /// let module = Module::new(&engine, wat)?;
+    /// let errors = linker.check(&module);
+    /// assert_eq!(errors.len(), 2);
+    ///
+    /// assert_eq!(errors[0].name(), "double");
+    /// assert!(errors[0].actual().is_some());
+    ///
+    /// assert_eq!(errors[1].name(), "log");
+    /// assert!(errors[1].actual().is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check(&self, module: &Module) -> Vec<LinkError> {
+        let cx = crate::types::matching::MatchCx::new(module.engine());
+        let env_module = module.compiled_module().module();
+        env_module
+            .imports()
+            .zip(module.imports())
+            .filter_map(|((_, _, expected), import)| {
+                let error = match self._get_by_import(&import) {
+                    Ok(def) => {
+                        let actual = def.ty();
+                        let reason = cx.definition(&expected, &actual).err()?;
+                        LinkError::new(
+                            &import,
+                            Some(actual.to_extern_type(module.engine())),
+                            Some(format!("{reason:#}")),
+                        )
+                    }
+                    Err(_) => LinkError::new(&import, None, None),
+                };
+                Some(error)
+            })
+            .collect()
+    }
+
     /// This is split out to optionally take a `store` so that when the
     /// `.instantiate` API is used we can get fresh up-to-date type information
     /// for memories and their current size, if necessary.
@@ -1217,26 +1608,47 @@ impl<T> Linker<T> {
     /// This isn't expected to be much of a problem though since
     /// per-store-`Linker` types are likely using `.instantiate(..)` and
     /// per-`Engine` linkers don't have memories/tables in them.
+    ///
+    /// Providing a `store` additionally allows imports to be satisfied by
+    /// lazily-defined modules which were already instantiated into it.
     fn _instantiate_pre(
         &self,
         module: &Module,
-        store: Option<&StoreOpaque>,
+        mut store: Option<&mut StoreContextMut<'_, T>>,
     ) -> Result<InstancePre<T>>
     where
         T: 'static,
     {
         let mut imports: TryVec<_> = module
             .imports()
-            .map(|import| Ok(self._get_by_import(&import)?))
+            .map(|import| match self._get_by_import(&import) {
+                Ok(def) => Ok(def),
+                Err(e) => match &mut store {
+                    Some(store) => self.get_lazy_export(store, &import).ok_or(e.into()),
+                    None => Err(e.into()),
+                },
+            })
             .try_collect::<_, Error>()?;
         if let Some(store) = store {
             for import in imports.iter_mut() {
-                import.update_size(store);
+                import.update_size(store.0);
             }
         }
         unsafe { InstancePre::new(module, imports) }
     }
 
+    fn get_lazy_export(
+        &self,
+        store: &mut StoreContextMut<'_, T>,
+        import: &ImportType,
+    ) -> Option<Definition> {
+        let instance = store
+            .0
+            .lazy_instance(self.lazy_module(import.module())?.key)?;
+        let export = instance.get_export(&mut *store, import.name())?;
+        Some(Definition::new(store.0, export))
+    }
+
     /// Returns an iterator over all items defined in this `Linker`, in
     /// arbitrary order.
     ///
@@ -1491,6 +1903,17 @@ impl DefinitionType {
         }
     }
 
+    /// Returns the public type of this definition.
+    pub(crate) fn to_extern_type(&self, engine: &Engine) -> ExternType {
+        match self {
+            DefinitionType::Func(ty) => FuncType::from_shared_type_index(engine, *ty).into(),
+            DefinitionType::Global(ty) => GlobalType::from_wasmtime_global(engine, ty).into(),
+            DefinitionType::Table(ty, _) => TableType::from_wasmtime_table(engine, ty).into(),
+            DefinitionType::Memory(ty, _) => MemoryType::from_wasmtime_memory(ty).into(),
+            DefinitionType::Tag(ty) => TagType::from_wasmtime_tag(engine, ty).into(),
+        }
+    }
+
     pub(crate) fn desc(&self) -> &'static str {
         match self {
             DefinitionType::Func(_) => "function",
@@ -1502,6 +1925,117 @@ impl DefinitionType {
     }
 }
 
+/// The source of a module registered with [`Linker::module_lazy`].
+#[derive(Clone, Debug)]
+pub enum LazyModuleSource {
+    /// The binary or text format of a WebAssembly module, compiled with
+    /// [`Module::new`] on first use.
+    #[cfg(any(feature = "cranelift", feature = "winch"))]
+    Bytes(Vec<u8>),
+
+    /// A path to a file containing a WebAssembly module, compiled with
+    /// [`Module::from_file`] on first use.
+    #[cfg(all(feature = "std", any(feature = "cranelift", feature = "winch")))]
+    File(std::path::PathBuf),
+
+    /// An already-compiled module, which is only instantiated on first use.
+    Module(Module),
+}
+
+#[cfg(any(feature = "cranelift", feature = "winch"))]
+impl From<Vec<u8>> for LazyModuleSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        LazyModuleSource::Bytes(bytes)
+    }
+}
+
+#[cfg(any(feature = "cranelift", feature = "winch"))]
+impl From<&[u8]> for LazyModuleSource {
+    fn from(bytes: &[u8]) -> Self {
+        LazyModuleSource::Bytes(bytes.to_vec())
+    }
+}
+
+#[cfg(any(feature = "cranelift", feature = "winch"))]
+impl<const N: usize> From<&[u8; N]> for LazyModuleSource {
+    fn from(bytes: &[u8; N]) -> Self {
+        LazyModuleSource::Bytes(bytes.to_vec())
+    }
+}
+
+#[cfg(all(feature = "std", any(feature = "cranelift", feature = "winch")))]
+impl From<std::path::PathBuf> for LazyModuleSource {
+    fn from(path: std::path::PathBuf) -> Self {
+        LazyModuleSource::File(path)
+    }
+}
+
+#[cfg(all(feature = "std", any(feature = "cranelift", feature = "winch")))]
+impl From<&std::path::Path> for LazyModuleSource {
+    fn from(path: &std::path::Path) -> Self {
+        LazyModuleSource::File(path.to_path_buf())
+    }
+}
+
+impl From<Module> for LazyModuleSource {
+    fn from(module: Module) -> Self {
+        LazyModuleSource::Module(module)
+    }
+}
+
+impl From<&Module> for LazyModuleSource {
+    fn from(module: &Module) -> Self {
+        LazyModuleSource::Module(module.clone())
+    }
+}
+
+/// A module registered with [`Linker::module_lazy`], shared between clones of
+/// a linker.
+struct LazyModule {
+    /// Unique key of this module, used to find its instance in a store.
+    key: usize,
+    name: String,
+    source: LazyModuleSource,
+    /// The compiled module, filled in on first use.
+    module: OnceLock<Module>,
+}
+
+impl LazyModule {
+    fn module(&self, engine: &Engine) -> Result<&Module> {
+        self.module.get_or_try_init(|| {
+            let module: Result<Module> = match &self.source {
+                #[cfg(any(feature = "cranelift", feature = "winch"))]
+                LazyModuleSource::Bytes(bytes) => Module::new(engine, bytes),
+                #[cfg(all(feature = "std", any(feature = "cranelift", feature = "winch")))]
+                LazyModuleSource::File(path) => Module::from_file(engine, path),
+                LazyModuleSource::Module(module) => {
+                    assert!(
+                        Engine::same(engine, module.engine()),
+                        "different engines for this linker and the lazily-defined module"
+                    );
+                    Ok(module.clone())
+                }
+            };
+            module
+                .with_context(|| format!("failed to compile lazily-defined module `{}`", self.name))
+        })
+    }
+
+    fn check_reactor(module: &Module) -> Result<()> {
+        match ModuleKind::categorize(module)? {
+            ModuleKind::Reactor => Ok(()),
+            ModuleKind::Command => bail!("lazily-defined modules cannot be Commands"),
+        }
+    }
+
+    fn instantiate_error(&self) -> String {
+        format!(
+            "failed to instantiate lazily-defined module `{}`",
+            self.name
+        )
+    }
+}
+
 /// Modules can be interpreted either as Commands or Reactors.
 enum ModuleKind {
     /// The instance is a Command, meaning an instance is created for each
@@ -1594,3 +2128,70 @@ impl fmt::Display for UnknownImportError {
 }
 
 impl core::error::Error for UnknownImportError {}
+
+/// An import of a module which a [`Linker`] can't satisfy, as reported by
+/// [`Linker::check`].
+///
+/// This is either an import which has no definition in the linker, in which
+/// case [`LinkError::actual`] is `None`, or an import whose definition has an
+/// incompatible type.
+#[derive(Clone, Debug)]
+pub struct LinkError {
+    module: String,
+    name: String,
+    expected: ExternType,
+    actual: Option<ExternType>,
+    reason: Option<String>,
+}
+
+impl LinkError {
+    fn new(import: &ImportType, actual: Option<ExternType>, reason: Option<String>) -> Self {
+        Self {
+            module: import.module().to_string(),
+            name: import.name().to_string(),
+            expected: import.ty(),
+            actual,
+            reason,
+        }
+    }
+
+    /// Returns the module name of the import.
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// Returns the field name of the import.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type the module expects the import to have.
+    pub fn expected(&self) -> &ExternType {
+        &self.expected
+    }
+
+    /// Returns the type of the linker's definition for the import, or `None`
+    /// if the linker has no definition for it.
+    pub fn actual(&self) -> Option<&ExternType> {
+        self.actual.as_ref()
+    }
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            None => write!(
+                f,
+                "unknown import: `{}::{}` has not been defined",
+                self.module, self.name,
+            ),
+            Some(reason) => write!(
+                f,
+                "incompatible import type for `{}::{}`: {reason}",
+                self.module, self.name,
+            ),
+        }
+    }
+}
+
+impl core::error::Error for LinkError {}
@@ -2,7 +2,7 @@ use crate::Trap;
 use crate::prelude::*;
 use crate::runtime::vm::{self, ExportMemory};
 use crate::store::{StoreInstanceId, StoreOpaque, StoreResourceLimiter};
-use crate::trampoline::generate_memory_export;
+use crate::trampoline::{Preallocation, generate_memory_export};
 #[cfg(feature = "async")]
 use crate::vm::VMStore;
 use crate::{AsContext, AsContextMut, Engine, MemoryType, StoreContext, StoreContextMut};
@@ -10,7 +10,7 @@ use core::cell::UnsafeCell;
 use core::fmt;
 use core::slice;
 use core::time::Duration;
-use wasmtime_environ::DefinedMemoryIndex;
+use wasmtime_environ::{DefinedMemoryIndex, Limits};
 
 pub use crate::runtime::vm::WaitResult;
 
@@ -406,10 +406,13 @@ impl Memory {
     ///
     /// Panics if this memory doesn't belong to `store`.
     pub fn data<'a, T: 'static>(&self, store: impl Into<StoreContext<'a, T>>) -> &'a [u8] {
+        self._data(store.into().0)
+    }
+
+    pub(crate) fn _data<'a>(&self, store: &'a StoreOpaque) -> &'a [u8] {
         unsafe {
-            let store = store.into();
             let definition = store[self.instance].memory(self.index);
-            debug_assert!(!self.ty(store).is_shared());
+            debug_assert!(!self.wasmtime_ty(store).shared);
             slice::from_raw_parts(definition.base.as_ptr(), definition.current_length())
         }
     }
@@ -669,7 +672,7 @@ impl Memory {
         let result = self
             .instance
             .get_mut(store)
-            .memory_grow(limiter, self.index, delta)
+            .memory_grow(self.instance.store_id(), limiter, self.index, delta)
             .await?;
         match result {
             Some(size) => {
@@ -680,6 +683,134 @@ impl Memory {
         }
     }
 
+    /// Creates a new memory which aliases `len` bytes of this memory, starting
+    /// at byte `offset`.
+    ///
+    /// The returned [`Memory`] is a view of that range of this memory: reads
+    /// and writes through either one are visible through the other without any
+    /// copies. It can be imported into instances like any other memory, for
+    /// example alongside an instance's own memories with the multi-memory
+    /// proposal. Note that any [`Memory`], including aliases, can also be
+    /// imported into several instances within the same store, so together
+    /// these allow instances to share a buffer or parts of one without
+    /// threads.
+    ///
+    /// The alias's type has both its minimum and maximum size set to `len`,
+    /// so it can't be grown, and accesses beyond its end trap even if they
+    /// would be within this memory. Its page size and index type are the same
+    /// as this memory's.
+    ///
+    /// The instance defining this memory can no longer be recycled with
+    /// [`Instance::mark_recyclable`](crate::Instance::mark_recyclable) once
+    /// an alias of it exists, since recycling would free the aliased memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    ///
+    /// * `offset` or `len` aren't multiples of this memory's page size.
+    /// * The range `offset..offset + len` isn't within this memory's current
+    ///   size.
+    /// * This memory's base pointer may move when it grows, see
+    ///   [`Config::memory_may_move`](crate::Config::memory_may_move).
+    /// * Code accessing the alias could rely on virtual memory guard regions
+    ///   instead of explicit bounds checks, which would let it access memory
+    ///   past the end of the alias. Explicit bounds checks are used when
+    ///   [`Config::signals_based_traps`](crate::Config::signals_based_traps)
+    ///   is disabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this memory doesn't belong to `store`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// let mut config = Config::new();
+    /// config.signals_based_traps(false);
+    /// let engine = Engine::new(&config)?;
+    /// let mut store = Store::new(&engine, ());
+    ///
+    /// let memory = Memory::new(&mut store, MemoryType::new(4, Some(4)))?;
+    /// let scratch = memory.alias(&mut store, 0x10000, 0x10000)?;
+    /// scratch.write(&mut store, 0, b"hello")?;
+    ///
+    /// let mut buf = [0; 5];
+    /// memory.read(&store, 0x10000, &mut buf)?;
+    /// assert_eq!(&buf, b"hello");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn alias(&self, mut store: impl AsContextMut, offset: u64, len: u64) -> Result<Memory> {
+        let store = store.as_context_mut().0;
+        let ty = *self.wasmtime_ty(store);
+        let page_size = ty.page_size();
+        if offset % page_size != 0 || len % page_size != 0 {
+            bail!(
+                "memory alias offset {offset:#x} and length {len:#x} must be \
+                 multiples of the page size ({page_size:#x} bytes)"
+            );
+        }
+        let size = u64::try_from(self.internal_data_size(store)).unwrap();
+        if offset.checked_add(len).is_none_or(|end| end > size) {
+            bail!(
+                "memory alias range of {len:#x} bytes at offset {offset:#x} is out \
+                 of bounds for memory of {size:#x} bytes"
+            );
+        }
+        if store[self.instance]
+            .get_defined_memory(self.index)
+            .memory_may_move()
+        {
+            bail!("cannot alias a memory whose base pointer may move when it grows");
+        }
+
+        let pages = len / page_size;
+        let alias_ty = wasmtime_environ::Memory {
+            limits: Limits {
+                min: pages,
+                max: Some(pages),
+            },
+            shared: false,
+            ..ty
+        };
+        #[cfg(has_virtual_memory)]
+        let host_page_size_log2 = u8::try_from(vm::host_page_size().ilog2()).unwrap();
+        // Conservatively assume any page size can be backed by virtual memory.
+        #[cfg(not(has_virtual_memory))]
+        let host_page_size_log2 = 0;
+        if alias_ty.can_use_virtual_memory(store.engine().tunables(), host_page_size_log2) {
+            bail!(
+                "memory aliases require explicit bounds checks, which are only \
+                 used when signals-based traps are disabled"
+            );
+        }
+
+        let base = store[self.instance].memory(self.index).base.as_non_null();
+        // SAFETY: the range was checked above to be within this memory, which
+        // can't move or shrink and lives as long as the store which the alias
+        // is created in. Its instance is kept from being recycled, which would
+        // free the memory, by `note_memory_alias` below.
+        let base = unsafe { base.add(usize::try_from(offset).unwrap()) };
+        // Note `vm::assert_ready` shouldn't panic here because this isn't
+        // actually allocating any new memory (also no limiter), so resource
+        // limiting shouldn't kick in.
+        let memory = vm::assert_ready(generate_memory_export(
+            store,
+            None,
+            &MemoryType::from_wasmtime_memory(&alias_ty),
+            Some(Preallocation::Alias {
+                base: base.into(),
+                len: usize::try_from(len).unwrap(),
+            }),
+        ))?;
+        let memory = memory.unshared().unwrap();
+        store.note_memory_alias(memory.instance.instance(), self.instance.instance())?;
+        Ok(memory)
+    }
+
     /// Creates a new memory from its raw component parts.
     ///
     /// # Safety
@@ -1074,7 +1205,7 @@ impl SharedMemory {
             store,
             None,
             &self.ty(),
-            Some(&self.vm),
+            Some(Preallocation::Shared(&self.vm)),
         ))
         .unwrap();
         match memory {
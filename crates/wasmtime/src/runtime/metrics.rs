@@ -0,0 +1,200 @@
+use crate::{Engine, OutOfBoundsDetection, Trap, TrapDelivery, TrapDetails};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// `EngineMetrics` provides access to counters of runtime events across all
+/// stores using an [`Engine`].
+///
+/// All counters start at zero when the engine is created and only ever
+/// increase, which makes them suitable for export as monotonic counters to
+/// a metrics system.
+///
+/// This is a cheap cloneable handle which can be obtained with
+/// [`Engine::metrics`].
+#[derive(Clone)]
+pub struct EngineMetrics {
+    engine: Engine,
+}
+
+impl EngineMetrics {
+    pub(crate) fn new(engine: &Engine) -> Self {
+        Self {
+            engine: engine.clone(),
+        }
+    }
+
+    /// Returns the number of core module instances which have been
+    /// successfully instantiated, including those instantiated as part of a
+    /// component.
+    pub fn core_instantiations(&self) -> usize {
+        self.counters().core_instantiations.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of component instances which have been
+    /// successfully instantiated.
+    pub fn component_instantiations(&self) -> usize {
+        self.counters()
+            .component_instantiations
+            .load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of times WebAssembly has trapped with `trap`.
+    ///
+    /// This counts each trap once as it unwinds out of WebAssembly, including
+    /// [`Trap::OutOfFuel`] and [`Trap::Interrupt`] traps raised when fuel is
+    /// exhausted or an epoch deadline is reached.
+    pub fn traps(&self, trap: Trap) -> usize {
+        self.counters().traps[trap as usize].load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of traps for each trap code which has been hit at
+    /// least once.
+    pub fn trap_counts(&self) -> impl Iterator<Item = (Trap, usize)> + '_ {
+        Trap::ALL
+            .iter()
+            .map(|trap| (*trap, self.traps(*trap)))
+            .filter(|(_, n)| *n > 0)
+    }
+
+    /// Returns the number of times WebAssembly has run out of fuel.
+    ///
+    /// This counts each time the fuel in a store was exhausted, whether or not
+    /// it was subsequently refueled with
+    /// [`Store::fuel_async_yield_interval`](crate::Store::fuel_async_yield_interval).
+    pub fn fuel_exhaustions(&self) -> usize {
+        self.counters().fuel_exhaustions.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of times WebAssembly has reached its epoch
+    /// deadline.
+    ///
+    /// This counts each time a deadline was reached regardless of whether the
+    /// store then trapped, yielded or continued executing.
+    pub fn epoch_interruptions(&self) -> usize {
+        self.counters().epoch_interruptions.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of times compiled code was found in the cache
+    /// configured with [`Config::cache`](crate::Config::cache).
+    ///
+    /// This is zero when no cache is configured.
+    pub fn cache_hits(&self) -> usize {
+        #[cfg(all(feature = "cache", any(feature = "cranelift", feature = "winch")))]
+        if let Some(cache) = self.engine.cache() {
+            return cache.cache_hits();
+        }
+        0
+    }
+
+    /// Returns the number of times compiled code was not found in the cache
+    /// configured with [`Config::cache`](crate::Config::cache).
+    ///
+    /// This is zero when no cache is configured.
+    pub fn cache_misses(&self) -> usize {
+        #[cfg(all(feature = "cache", any(feature = "cranelift", feature = "winch")))]
+        if let Some(cache) = self.engine.cache() {
+            return cache.cache_misses();
+        }
+        0
+    }
+
+    fn counters(&self) -> &EngineCounters {
+        self.engine.counters()
+    }
+}
+
+/// Counters of how traps raised by WebAssembly in a [`Store`] were delivered.
+///
+/// This is returned by [`Store::trap_stats`] and can be used to verify that a
+/// configuration catches out-of-bounds accesses with guard regions rather than
+/// explicit bounds checks, for example. Only traps raised by compiled code,
+/// those which carry [`TrapDetails`], are counted.
+///
+/// [`Store`]: crate::Store
+/// [`Store::trap_stats`]: crate::Store::trap_stats
+#[derive(Debug, Clone, Default)]
+pub struct TrapStats {
+    signal: usize,
+    libcall: usize,
+    interpreter: usize,
+    guard_region: usize,
+    bounds_check: usize,
+}
+
+impl TrapStats {
+    /// Returns the number of traps which were delivered with `delivery`.
+    pub fn delivered(&self, delivery: TrapDelivery) -> usize {
+        match delivery {
+            TrapDelivery::Signal => self.signal,
+            TrapDelivery::Libcall => self.libcall,
+            TrapDelivery::Interpreter => self.interpreter,
+        }
+    }
+
+    /// Returns the number of [`Trap::MemoryOutOfBounds`] traps whose
+    /// out-of-bounds access was detected with `detection`.
+    pub fn out_of_bounds(&self, detection: OutOfBoundsDetection) -> usize {
+        match detection {
+            OutOfBoundsDetection::GuardRegion => self.guard_region,
+            OutOfBoundsDetection::BoundsCheck => self.bounds_check,
+        }
+    }
+
+    pub(crate) fn record(&mut self, details: &TrapDetails) {
+        *match details.delivery() {
+            TrapDelivery::Signal => &mut self.signal,
+            TrapDelivery::Libcall => &mut self.libcall,
+            TrapDelivery::Interpreter => &mut self.interpreter,
+        } += 1;
+        match details.out_of_bounds_detection() {
+            Some(OutOfBoundsDetection::GuardRegion) => self.guard_region += 1,
+            Some(OutOfBoundsDetection::BoundsCheck) => self.bounds_check += 1,
+            None => {}
+        }
+    }
+}
+
+/// The counters behind [`EngineMetrics`], stored within an [`Engine`].
+pub(crate) struct EngineCounters {
+    core_instantiations: AtomicUsize,
+    component_instantiations: AtomicUsize,
+    traps: [AtomicUsize; Trap::ALL.len()],
+    fuel_exhaustions: AtomicUsize,
+    epoch_interruptions: AtomicUsize,
+}
+
+impl Default for EngineCounters {
+    fn default() -> EngineCounters {
+        EngineCounters {
+            core_instantiations: AtomicUsize::new(0),
+            component_instantiations: AtomicUsize::new(0),
+            traps: [const { AtomicUsize::new(0) }; Trap::ALL.len()],
+            fuel_exhaustions: AtomicUsize::new(0),
+            epoch_interruptions: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl EngineCounters {
+    pub(crate) fn core_instantiated(&self) {
+        self.core_instantiations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "component-model")]
+    pub(crate) fn component_instantiated(&self) {
+        self.component_instantiations
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn trapped(&self, trap: Trap) {
+        self.traps[trap as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn fuel_exhausted(&self) {
+        self.fuel_exhaustions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(target_has_atomic = "64")]
+    pub(crate) fn epoch_interrupted(&self) {
+        self.epoch_interruptions.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -2,9 +2,9 @@ use crate::prelude::*;
 #[cfg(feature = "std")]
 use crate::runtime::vm::open_file_for_mmap;
 use crate::runtime::vm::{CompiledModuleId, MmapVec, ModuleMemoryImages, VMWasmCallFunction};
-use crate::sync::OnceLock;
+use crate::sync::{Mutex, OnceLock};
 use crate::{
-    Engine,
+    CodeKind, CodeOrigin, Engine, SpectreMitigations, WasmProfile,
     code::EngineCode,
     code_memory::CodeMemory,
     instantiate::CompiledModule,
@@ -19,11 +19,18 @@ use core::ptr::NonNull;
 use std::{fs::File, path::Path};
 use wasmparser::{Parser, ValidPayload, Validator};
 use wasmtime_environ::{
-    CompiledFunctionsTable, CompiledModuleInfo, EntityIndex, FuncKey, HostPtr, ModuleTypes,
-    ObjectKind, StaticModuleIndex, TypeTrace, VMOffsets, VMSharedTypeIndex, WasmChecksum,
+    CompiledFunctionsTable, CompiledModuleInfo, EntityIndex, FuncIndex, FuncKey, HostPtr,
+    ModuleTypes, ObjectKind, StaticModuleIndex, TypeTrace, VMOffsets, VMSharedTypeIndex,
+    WasmChecksum,
 };
+mod names;
+#[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+mod recompiling;
 mod registry;
 
+pub use names::ModuleNames;
+#[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+pub use recompiling::RecompilingModule;
 pub use registry::*;
 
 /// A compiled WebAssembly module, ready to be instantiated.
@@ -160,6 +167,10 @@ struct ModuleInner {
 
     /// The checksum of the source binary from which this module was compiled.
     checksum: WasmChecksum,
+
+    /// The execution profile recorded by instances of this module, if it
+    /// was compiled with `Config::profile_instrumentation`.
+    profile: Option<Mutex<WasmProfile>>,
 }
 
 impl fmt::Debug for Module {
@@ -349,7 +360,7 @@ impl Module {
         let open_file = open_file_for_mmap(file.as_ref())?;
         let mmap = crate::runtime::vm::MmapVec::from_file(open_file)?;
         if &mmap[0..4] == b"\x7fELF" {
-            let code = engine.load_code(mmap, ObjectKind::Module)?;
+            let code = engine.load_code(mmap, ObjectKind::Module, CodeOrigin::Deserialized)?;
             return Module::from_parts(engine, code, None);
         }
 
@@ -407,7 +418,8 @@ impl Module {
     /// memory allocation fails. See the `OutOfMemory` type's documentation for
     /// details on Wasmtime's out-of-memory handling.
     pub unsafe fn deserialize(engine: &Engine, bytes: impl AsRef<[u8]>) -> Result<Module> {
-        let code = engine.load_code_bytes(bytes.as_ref(), ObjectKind::Module)?;
+        let code =
+            engine.load_code_bytes(bytes.as_ref(), ObjectKind::Module, CodeOrigin::Deserialized)?;
         Module::from_parts(engine, code, None)
     }
 
@@ -525,7 +537,14 @@ impl Module {
 
         // Package up all our data into an `EngineCode` and delegate to the final
         // step of module compilation.
-        let code = try_new::<Arc<_>>(EngineCode::new(code_memory, signatures, types.into())?)?;
+        let code = try_new::<Arc<_>>(EngineCode::new(
+            engine,
+            code_memory,
+            signatures,
+            types.into(),
+            CodeKind::Module,
+            info.module.name.map(|name| &info.module.strings[name]),
+        )?)?;
         let index = try_new::<Arc<_>>(index)?;
         Module::from_parts_raw(engine, code, info, index, true)
     }
@@ -558,10 +577,23 @@ impl Module {
                 serializable,
                 offsets,
                 checksum,
+                profile: engine
+                    .tunables()
+                    .profile_instrumentation
+                    .then(|| Mutex::new(WasmProfile::new())),
             })?,
         })
     }
 
+    /// Returns whether this module was compiled by the engine's background
+    /// recompiler, see [`RecompilingModule`](crate::RecompilingModule).
+    ///
+    /// This is recorded in serialized modules, so it's also reported for
+    /// modules created with [`Module::deserialize`].
+    pub(crate) fn is_recompiled(&self) -> bool {
+        self.compiled_module().is_recompiled()
+    }
+
     /// Validates `binary` input data as a WebAssembly binary given the
     /// configuration in `engine`.
     ///
@@ -694,6 +726,115 @@ impl Module {
         Some(&module.strings[name])
     }
 
+    /// Returns the names recorded in this module's `name` custom section.
+    ///
+    /// The returned [`ModuleNames`] can be used to look up function, local,
+    /// and label names of this module. These are available even when the
+    /// module was compiled without DWARF debug information, and are what
+    /// Wasmtime itself uses when symbolizing backtraces and profiles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// # let engine = Engine::default();
+    /// let module = Module::new(
+    ///     &engine,
+    ///     r#"
+    ///         (module $m
+    ///             (func $f (local $x i32)
+    ///                 block $out
+    ///                 end)
+    ///         )
+    ///     "#,
+    /// )?;
+    /// let names = module.names();
+    /// assert_eq!(names.module(), Some("m"));
+    /// assert_eq!(names.func(0), Some("f"));
+    /// assert_eq!(names.local(0, 0), Some("x"));
+    /// assert_eq!(names.label(0, 0), Some("out"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn names(&self) -> ModuleNames<'_> {
+        ModuleNames::new(self)
+    }
+
+    /// Returns the tier of Spectre mitigations this module was compiled with.
+    ///
+    /// This is [`SpectreMitigations::Enabled`] unless the module was compiled
+    /// with [`CodeBuilder::spectre_mitigations`](crate::CodeBuilder::spectre_mitigations).
+    /// The tier is recorded in serialized modules, so this also reports the
+    /// tier of modules created with [`Module::deserialize`].
+    pub fn spectre_mitigations(&self) -> SpectreMitigations {
+        self.compiled_module().module().spectre_mitigations
+    }
+
+    /// Returns the execution profile recorded so far by all instances of this
+    /// module.
+    ///
+    /// This returns `None` unless the module was compiled with
+    /// [`Config::profile_instrumentation`](crate::Config::profile_instrumentation)
+    /// enabled. The returned profile can be passed to
+    /// [`CodeBuilder::profile`](crate::CodeBuilder::profile) to recompile this
+    /// module optimized for the recorded behavior.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// let mut config = Config::new();
+    /// config.profile_instrumentation(true);
+    /// let engine = Engine::new(&config)?;
+    /// let wat = r#"
+    ///     (module
+    ///         (func (export "run") (param i32) (result i32)
+    ///             (if (result i32) (local.get 0)
+    ///                 (then (i32.const 1))
+    ///                 (else (i32.const 2))))
+    ///     )
+    /// "#;
+    /// let module = Module::new(&engine, wat)?;
+    ///
+    /// let mut store = Store::new(&engine, ());
+    /// let instance = Instance::new(&mut store, &module, &[])?;
+    /// let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+    /// for _ in 0..100 {
+    ///     run.call(&mut store, 1)?;
+    /// }
+    /// let profile = module.profile().unwrap();
+    ///
+    /// // Recompile the module, without instrumentation, optimized for the
+    /// // recorded profile.
+    /// let engine = Engine::default();
+    /// let optimized = CodeBuilder::new(&engine)
+    ///     .wasm_binary_or_text(wat.as_bytes(), None)?
+    ///     .profile(profile)
+    ///     .compile_module()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn profile(&self) -> Option<WasmProfile> {
+        Some(self.inner.profile.as_ref()?.lock().clone())
+    }
+
+    /// Records a call to `func` in this module's profile, if it has one.
+    pub(crate) fn record_profile_call(&self, func: FuncIndex) {
+        if let Some(profile) = &self.inner.profile {
+            profile.lock().record_call(func);
+        }
+    }
+
+    /// Records which way the branch at body-relative `offset` in `func` went
+    /// in this module's profile, if it has one.
+    pub(crate) fn record_profile_branch(&self, func: FuncIndex, offset: u32, taken: bool) {
+        if let Some(profile) = &self.inner.profile {
+            profile.lock().record_branch(func, offset, taken);
+        }
+    }
+
     /// Returns the original Wasm bytecode for this module, if it is
     /// available.
     ///
@@ -1083,6 +1224,33 @@ impl Module {
         )
     }
 
+    /// Get the stack maps for this module's compiled code.
+    ///
+    /// Each stack map describes a safepoint in this module's `.text` section,
+    /// which is a location where GC references may be live in stack slots of
+    /// the Wasm frame, such as at the return address of a call. Together with
+    /// the frame pointer of a Wasm frame stopped at a safepoint, a stack map
+    /// precisely identifies every stack slot that holds a GC reference. This
+    /// enables embedders to scan Wasm frames precisely, rather than treating
+    /// the whole stack conservatively.
+    ///
+    /// Stack maps are yielded in order of their offset. Modules that don't use
+    /// any GC references, or that were compiled by a compiler that doesn't
+    /// emit stack maps, don't have any.
+    ///
+    /// See also [`StoreContextMut::wasm_stack_gc_roots`][crate::StoreContextMut::wasm_stack_gc_roots],
+    /// which uses these stack maps to enumerate the GC references in the
+    /// active Wasm frames of a store.
+    pub fn stack_maps(&self) -> impl Iterator<Item = ModuleStackMap<'_>> + '_ {
+        wasmtime_environ::StackMap::iter(self.engine_code().stack_map_data())
+            .into_iter()
+            .flatten()
+            .map(|(offset, map)| ModuleStackMap {
+                offset: offset as usize,
+                map,
+            })
+    }
+
     /// Get this module's code object's `.text` section, containing its compiled
     /// executable code.
     pub fn text(&self) -> &[u8] {
@@ -1103,7 +1271,7 @@ impl Module {
             ModuleFunction {
                 module: module_index,
                 index: idx,
-                name: module.func_name(idx).map(|n| n.to_string()),
+                name: self.names().func(idx.as_u32()).map(|n| n.to_string()),
                 offset: loc.start as usize,
                 len: loc.length as usize,
             }
@@ -1220,6 +1388,52 @@ pub struct ModuleFunction {
     pub len: usize,
 }
 
+/// Describes where GC references are stored in a Wasm stack frame at a
+/// safepoint of a module's compiled code.
+///
+/// Returned by [`Module::stack_maps`].
+pub struct ModuleStackMap<'a> {
+    offset: usize,
+    map: wasmtime_environ::StackMap<'a>,
+}
+
+impl ModuleStackMap<'_> {
+    /// The byte offset of this safepoint in the module's
+    /// [`.text` section][Module::text].
+    ///
+    /// For calls this is the return address of the call, which is the program
+    /// counter observed for the calling frame when walking the stack.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The size, in bytes, of the Wasm frame at this safepoint.
+    ///
+    /// This is the distance from the stack pointer up to the frame pointer of
+    /// the frame, so the stack pointer is the frame pointer minus this size.
+    pub fn frame_size(&self) -> u32 {
+        self.map.frame_size()
+    }
+
+    /// The byte offsets, relative to the stack pointer at this safepoint, of
+    /// each stack slot holding a GC reference.
+    ///
+    /// Each slot holds a 32-bit GC reference, which may be null.
+    pub fn gc_ref_offsets(&self) -> impl Iterator<Item = u32> + '_ {
+        self.map.offsets()
+    }
+}
+
+impl fmt::Debug for ModuleStackMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModuleStackMap")
+            .field("offset", &self.offset)
+            .field("frame_size", &self.frame_size())
+            .field("gc_ref_offsets", &self.gc_ref_offsets().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
 impl Drop for ModuleInner {
     fn drop(&mut self) {
         // When a `Module` is being dropped that means that it's no longer
@@ -0,0 +1,121 @@
+use crate::Module;
+use wasmtime_environ::FuncIndex;
+
+/// Names recorded in the `name` custom section of a WebAssembly module.
+///
+/// This is returned by [`Module::names`] and provides access to function,
+/// local, and label names that were present in the original module. These
+/// names are preserved in compiled artifacts regardless of whether DWARF debug
+/// information was present or enabled, so they're available for symbolizing
+/// modules at runtime and for modules loaded with
+/// [`Module::deserialize`].
+///
+/// All indices here are indices within the respective index spaces of the
+/// original WebAssembly module, for example function indices include imported
+/// functions.
+#[derive(Clone, Copy)]
+pub struct ModuleNames<'a> {
+    module: &'a Module,
+}
+
+impl<'a> ModuleNames<'a> {
+    pub(super) fn new(module: &'a Module) -> ModuleNames<'a> {
+        ModuleNames { module }
+    }
+
+    /// Returns the name of the module itself, if one was specified.
+    ///
+    /// This is the same as [`Module::name`].
+    pub fn module(&self) -> Option<&'a str> {
+        self.module.name()
+    }
+
+    /// Returns the name of the function at index `func`, if one was
+    /// specified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn main() -> Result<()> {
+    /// # let engine = Engine::default();
+    /// let module = Module::new(
+    ///     &engine,
+    ///     r#"
+    ///         (module
+    ///             (func $add (param $lhs i32) (param $rhs i32) (result i32)
+    ///                 local.get $lhs
+    ///                 local.get $rhs
+    ///                 i32.add)
+    ///             (func)
+    ///         )
+    ///     "#,
+    /// )?;
+    /// let names = module.names();
+    /// assert_eq!(names.func(0), Some("add"));
+    /// assert_eq!(names.func(1), None);
+    /// assert_eq!(names.local(0, 1), Some("rhs"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn func(&self, func: u32) -> Option<&'a str> {
+        self.module
+            .compiled_module()
+            .func_name(FuncIndex::from_u32(func))
+    }
+
+    /// Returns an iterator over all named functions in this module, sorted by
+    /// function index.
+    pub fn funcs(&self) -> impl ExactSizeIterator<Item = (u32, &'a str)> + 'a {
+        self.module
+            .compiled_module()
+            .func_names()
+            .map(|(idx, name)| (idx.as_u32(), name))
+    }
+
+    /// Returns the name of local `local` within function `func`, if one was
+    /// specified.
+    ///
+    /// Local indices include the function's parameters.
+    pub fn local(&self, func: u32, local: u32) -> Option<&'a str> {
+        self.module
+            .compiled_module()
+            .local_name(FuncIndex::from_u32(func), local)
+    }
+
+    /// Returns an iterator over the named locals of function `func`, sorted
+    /// by local index.
+    pub fn locals(&self, func: u32) -> impl Iterator<Item = (u32, &'a str)> + 'a {
+        self.module
+            .compiled_module()
+            .local_names(FuncIndex::from_u32(func))
+    }
+
+    /// Returns the name of label `label` within function `func`, if one was
+    /// specified.
+    ///
+    /// Labels are numbered in the order in which their `block`, `loop`, `if`,
+    /// or `try_table` instructions appear within the function body.
+    pub fn label(&self, func: u32, label: u32) -> Option<&'a str> {
+        self.module
+            .compiled_module()
+            .label_name(FuncIndex::from_u32(func), label)
+    }
+
+    /// Returns an iterator over the named labels of function `func`, sorted
+    /// by label index.
+    pub fn labels(&self, func: u32) -> impl Iterator<Item = (u32, &'a str)> + 'a {
+        self.module
+            .compiled_module()
+            .label_names(FuncIndex::from_u32(func))
+    }
+}
+
+impl core::fmt::Debug for ModuleNames<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ModuleNames")
+            .field("module", &self.module())
+            .field("funcs", &self.funcs().collect::<crate::prelude::Vec<_>>())
+            .finish()
+    }
+}
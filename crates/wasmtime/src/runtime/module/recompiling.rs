@@ -0,0 +1,242 @@
+use crate::prelude::*;
+use crate::{AsContextMut, Engine, Instance, Module};
+use alloc::sync::Arc;
+use core::fmt;
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+
+/// A WebAssembly module which is compiled quickly with Winch first, and then
+/// recompiled with Cranelift's optimizations in the background.
+///
+/// Creating a `RecompilingModule` compiles the module with Winch and returns
+/// as soon as that's done, so the module can start running right away. A
+/// background thread then recompiles the same module with Cranelift. Once that
+/// has finished, [`RecompilingModule::module`] returns the optimized module
+/// instead of the Winch-compiled one.
+///
+/// Instances created from the Winch-compiled module can tier up to the
+/// optimized code once it's ready with [`RecompilingModule::upgrade`], which
+/// patches the instance's functions, table entries referring to them, and
+/// imports of them in place. Both compilers share a calling convention, so
+/// instances of either can be linked together and their functions and tables
+/// may be freely mixed within a store.
+///
+/// Background recompilation must be enabled on the [`Engine`] with
+/// [`Config::background_recompilation`](crate::Config::background_recompilation).
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::{Config, Engine, Instance, RecompilingModule, Result, Store, Strategy};
+///
+/// # fn main() -> Result<()> {
+/// # if !cfg!(any(target_arch = "x86_64", target_arch = "aarch64")) { return Ok(()) }
+/// let mut config = Config::new();
+/// config.strategy(Strategy::Winch).background_recompilation(true);
+/// let engine = Engine::new(&config)?;
+///
+/// let module = RecompilingModule::new(&engine, r#"
+///     (module (func (export "answer") (result i32) i32.const 42))
+/// "#)?;
+///
+/// // Instantiate with whichever version is currently available.
+/// let mut store = Store::new(&engine, ());
+/// let instance = Instance::new(&mut store, &module.module(), &[])?;
+/// let answer = instance.get_typed_func::<(), i32>(&mut store, "answer")?;
+/// assert_eq!(answer.call(&mut store, ())?, 42);
+///
+/// // Once optimized code has been compiled the running instance can switch
+/// // over to it, and later instances use it from the start.
+/// module.wait()?;
+/// assert!(module.upgrade(&mut store, &instance)?);
+/// assert_eq!(answer.call(&mut store, ())?, 42);
+/// let instance = Instance::new(&mut store, &module.module(), &[])?;
+/// let answer = instance.get_typed_func::<(), i32>(&mut store, "answer")?;
+/// assert_eq!(answer.call(&mut store, ())?, 42);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RecompilingModule {
+    inner: Arc<RecompilingModuleInner>,
+}
+
+struct RecompilingModuleInner {
+    baseline: Module,
+    optimized: Mutex<Recompile>,
+    finished: Condvar,
+}
+
+enum Recompile {
+    Compiling,
+    Finished(Module),
+    Failed(String),
+}
+
+impl RecompilingModule {
+    /// Compiles `bytes` with Winch, returning once that's complete, and starts
+    /// compiling it with Cranelift in the background.
+    ///
+    /// The `bytes` may be either the binary or text format of a module, as
+    /// with [`Module::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `engine` doesn't have background recompilation enabled or
+    /// if the module fails to compile with Winch. Errors from the background
+    /// compilation are reported by [`RecompilingModule::wait`].
+    pub fn new(engine: &Engine, bytes: impl AsRef<[u8]>) -> Result<RecompilingModule> {
+        crate::CodeBuilder::new(engine)
+            .wasm_binary_or_text(bytes.as_ref(), None)?
+            .compile_recompiling_module()
+    }
+
+    /// Same as [`RecompilingModule::new`] except that the module is read from
+    /// `file`.
+    pub fn from_file(engine: &Engine, file: impl AsRef<Path>) -> Result<RecompilingModule> {
+        crate::CodeBuilder::new(engine)
+            .wasm_binary_or_text_file(file.as_ref())?
+            .compile_recompiling_module()
+    }
+
+    /// Spawns a thread running `compile` to produce the optimized version of
+    /// `baseline`.
+    pub(crate) fn spawn(
+        baseline: Module,
+        compile: impl FnOnce() -> Result<Module> + Send + 'static,
+    ) -> Result<RecompilingModule> {
+        let inner = Arc::new(RecompilingModuleInner {
+            baseline,
+            optimized: Mutex::new(Recompile::Compiling),
+            finished: Condvar::new(),
+        });
+        let weak = Arc::downgrade(&inner);
+        std::thread::Builder::new()
+            .name("wasmtime-recompile".into())
+            .spawn(move || {
+                let result = match compile() {
+                    Ok(module) => Recompile::Finished(module),
+                    Err(e) => {
+                        log::warn!("failed to recompile module with optimizations: {e:?}");
+                        Recompile::Failed(format!("{e:?}"))
+                    }
+                };
+                // If every handle to the module has been dropped in the
+                // meantime then there's nobody left to use the result.
+                if let Some(inner) = weak.upgrade() {
+                    *inner.optimized.lock().unwrap() = result;
+                    inner.finished.notify_all();
+                }
+            })
+            .context("failed to spawn recompilation thread")?;
+        Ok(RecompilingModule { inner })
+    }
+
+    /// Returns the best version of this module that's currently available.
+    ///
+    /// This is the optimized module if its compilation has finished, and
+    /// otherwise the Winch-compiled module.
+    pub fn module(&self) -> Module {
+        self.optimized()
+            .unwrap_or_else(|| self.inner.baseline.clone())
+    }
+
+    /// Returns the Winch-compiled version of this module.
+    pub fn baseline(&self) -> &Module {
+        &self.inner.baseline
+    }
+
+    /// Returns the optimized version of this module if its compilation has
+    /// finished successfully.
+    pub fn optimized(&self) -> Option<Module> {
+        match &*self.inner.optimized.lock().unwrap() {
+            Recompile::Finished(module) => Some(module.clone()),
+            Recompile::Compiling | Recompile::Failed(_) => None,
+        }
+    }
+
+    /// Switches `instance` over to the optimized code of this module if it's
+    /// ready, returning whether `instance` now runs optimized code.
+    ///
+    /// This is the safe point at which a running instance tiers up: every
+    /// entry into `instance` from now on runs Cranelift-compiled code. That
+    /// covers its exports, including [`Func`](crate::Func)s which were
+    /// already looked up, its functions placed in tables, and other instances
+    /// in `store` which import its functions. Optimized code calls other
+    /// optimized code directly, so once entered only optimized code of
+    /// `instance` runs. Frames of `instance` which are already on the stack,
+    /// for example if this is called from a host function, finish running the
+    /// Winch-compiled code.
+    ///
+    /// Returns `false` without changing anything if the optimized module isn't
+    /// ready yet, or if its compilation failed. Instances created from the
+    /// optimized module already run optimized code, and this returns `true`
+    /// for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `instance` wasn't created from this module.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instance` doesn't belong to `store`.
+    pub fn upgrade(&self, mut store: impl AsContextMut, instance: &Instance) -> Result<bool> {
+        let store = store.as_context_mut().0;
+        let Some(optimized) = self.optimized() else {
+            return Ok(false);
+        };
+        let module = instance._module(store);
+        if Module::same(module, &optimized) {
+            return Ok(true);
+        }
+        if !Module::same(module, &self.inner.baseline) {
+            bail!("instance was not created from this module");
+        }
+        let id = instance.id();
+        if store.instance(id).upgraded_code().is_none() {
+            // SAFETY: `optimized` was compiled from the same module as
+            // `instance` for the same engine, as checked above.
+            unsafe {
+                store.upgrade_instance_code(id, &optimized)?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the [`Engine`] this module was compiled for.
+    pub fn engine(&self) -> &Engine {
+        self.inner.baseline.engine()
+    }
+
+    /// Blocks the current thread until the optimized version of this module
+    /// has been compiled, and returns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if compiling the optimized version failed. The
+    /// Winch-compiled module remains usable in that case.
+    pub fn wait(&self) -> Result<Module> {
+        let mut optimized = self.inner.optimized.lock().unwrap();
+        loop {
+            match &*optimized {
+                Recompile::Compiling => optimized = self.inner.finished.wait(optimized).unwrap(),
+                Recompile::Finished(module) => return Ok(module.clone()),
+                Recompile::Failed(e) => bail!("failed to recompile module with optimizations: {e}"),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RecompilingModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match &*self.inner.optimized.lock().unwrap() {
+            Recompile::Compiling => "compiling",
+            Recompile::Finished(_) => "finished",
+            Recompile::Failed(_) => "failed",
+        };
+        f.debug_struct("RecompilingModule")
+            .field("baseline", &self.inner.baseline)
+            .field("optimized", &state)
+            .finish()
+    }
+}
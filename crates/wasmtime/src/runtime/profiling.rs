@@ -271,12 +271,12 @@ fn module_symbols(name: String, module: &Module) -> Option<LibraryInfo> {
                 let key =
                     FuncKey::DefinedWasmFunction(module.env_module().module_index, defined_idx);
                 let loc = compiled.func_loc(key);
-                let func_idx = compiled.module().func_index(defined_idx);
+                let func_idx = compiled.module().func_index(defined_idx).as_u32();
                 let mut name = String::new();
                 demangle_function_name_or_index(
                     &mut name,
-                    compiled.func_name(func_idx),
-                    defined_idx.as_u32() as usize,
+                    module.names().func(func_idx),
+                    func_idx as usize,
                 )
                 .unwrap();
                 Symbol {
@@ -0,0 +1,471 @@
+//! Support for checkpointing the state of an [`Instance`] with
+//! [`Instance::freeze`] and restoring it with [`Instance::thaw`].
+
+use crate::hash_map::HashMap;
+use crate::prelude::*;
+use crate::runtime::vm::VMFuncRef;
+use crate::{Extern, Func, Instance, Ref, StoreContextMut, V128, Val};
+use core::ptr::NonNull;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use wasmtime_environ::{EntityIndex, FuncIndex, Module, PrimaryMap};
+
+/// Prefix of the serialized form of a [`StateImage`], used to reject
+/// arbitrary data passed to [`StateImage::from_bytes`].
+const MAGIC: &[u8] = b"\0wasmtime-state-image-v2";
+
+/// Granularity at which runs of zeros in linear memory are omitted from a
+/// [`StateImage`].
+const CHUNK_SIZE: usize = 64;
+
+/// A snapshot of the mutable state of an [`Instance`], created with
+/// [`Instance::freeze`] and restored with [`Instance::thaw`].
+///
+/// An image contains the contents of all linear memories, tables, and mutable
+/// globals defined by an instance, which is enough to resume execution of the
+/// instance later on, possibly in a different store or on a different host,
+/// without any cooperation from the module itself. Images are converted to and
+/// from a compact binary format with [`StateImage::to_bytes`] and
+/// [`StateImage::from_bytes`].
+///
+/// Function references within tables and globals are recorded by their index
+/// within the instance's module, so they can only refer to functions of the
+/// instance being frozen. Items imported by the instance are not part of the
+/// image and must be checkpointed separately, for example by freezing the
+/// instance which defines them.
+///
+/// An image records a digest of the imports, exports, and item types of the
+/// instance's module, and can only be restored into instances of a module with
+/// the same digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateImage {
+    module: [u8; 32],
+    memories: Vec<MemoryState>,
+    tables: Vec<TableState>,
+    globals: Vec<Option<GlobalState>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct MemoryState {
+    /// Size of the memory, in bytes.
+    size: u64,
+    /// Nonzero contents of the memory as `(offset, bytes)` pairs, all other
+    /// bytes are zero.
+    chunks: Vec<(u64, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TableState {
+    /// Each element of the table as the index of the function it refers to.
+    elements: Vec<Option<u32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum GlobalState {
+    I32(i32),
+    I64(i64),
+    F32(u32),
+    F64(u64),
+    V128(u128),
+    FuncRef(Option<u32>),
+}
+
+impl StateImage {
+    /// Serializes this image into its compact binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(postcard::to_allocvec(self).unwrap());
+        bytes
+    }
+
+    /// Deserializes an image previously produced by [`StateImage::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` isn't a serialized state image.
+    pub fn from_bytes(bytes: &[u8]) -> Result<StateImage> {
+        let bytes = bytes
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| format_err!("bytes are not a serialized state image"))?;
+        Ok(postcard::from_bytes(bytes)?)
+    }
+
+    pub(crate) fn freeze<T>(
+        mut store: StoreContextMut<'_, T>,
+        instance: &Instance,
+    ) -> Result<StateImage> {
+        let funcs = Funcs::new(&mut store, instance);
+        let func_index = |store: &mut StoreContextMut<'_, T>, func: Option<&Func>| match func {
+            Some(func) => {
+                let func_ref = func.vm_func_ref(store.0);
+                match funcs.by_ref.get(&func_ref) {
+                    Some(index) => Ok(Some(*index)),
+                    None => bail!("cannot freeze a reference to a function of another instance"),
+                }
+            }
+            None => Ok(None),
+        };
+
+        let mut image = StateImage {
+            module: module_digest(store.0[instance.id].env_module())?,
+            memories: Vec::new(),
+            tables: Vec::new(),
+            globals: Vec::new(),
+        };
+
+        for item in defined_items(&mut store, instance) {
+            match item {
+                Extern::Memory(memory) => {
+                    let data = memory.data(&store);
+                    let mut chunks: Vec<(u64, Vec<u8>)> = Vec::new();
+                    let mut offset = 0;
+                    for chunk in data.chunks(CHUNK_SIZE) {
+                        if chunk.iter().any(|b| *b != 0) {
+                            match chunks.last_mut() {
+                                Some((start, bytes))
+                                    if usize::try_from(*start)? + bytes.len() == offset =>
+                                {
+                                    bytes.extend_from_slice(chunk);
+                                }
+                                _ => chunks.push((u64::try_from(offset)?, chunk.to_vec())),
+                            }
+                        }
+                        offset += chunk.len();
+                    }
+                    image.memories.push(MemoryState {
+                        size: u64::try_from(data.len())?,
+                        chunks,
+                    });
+                }
+                Extern::SharedMemory(_) => bail!("cannot freeze an instance's shared memory"),
+                Extern::Table(table) => {
+                    if !table.ty(&store).element().heap_type().top().is_func() {
+                        bail!("cannot freeze a table whose elements aren't function references");
+                    }
+                    let mut elements = Vec::new();
+                    for i in 0..table.size(&store) {
+                        let elem = table.get(&mut store, i).unwrap();
+                        elements.push(func_index(&mut store, elem.as_func().unwrap())?);
+                    }
+                    image.tables.push(TableState { elements });
+                }
+                Extern::Global(global) => {
+                    if !global.ty(&store).mutability().is_var() {
+                        image.globals.push(None);
+                        continue;
+                    }
+                    let state = match global.get(&mut store) {
+                        Val::I32(i) => GlobalState::I32(i),
+                        Val::I64(i) => GlobalState::I64(i),
+                        Val::F32(f) => GlobalState::F32(f),
+                        Val::F64(f) => GlobalState::F64(f),
+                        Val::V128(v) => GlobalState::V128(v.as_u128()),
+                        Val::FuncRef(f) => {
+                            GlobalState::FuncRef(func_index(&mut store, f.as_ref())?)
+                        }
+                        _ => bail!("cannot freeze a global of this type"),
+                    };
+                    image.globals.push(Some(state));
+                }
+                Extern::Func(_) | Extern::Tag(_) => unreachable!(),
+            }
+        }
+
+        Ok(image)
+    }
+
+    pub(crate) fn thaw<T>(
+        &self,
+        mut store: StoreContextMut<'_, T>,
+        instance: &Instance,
+    ) -> Result<()> {
+        if module_digest(store.0[instance.id].env_module())? != self.module {
+            bail!("state image does not match the instance's module");
+        }
+        let items = defined_items(&mut store, instance);
+
+        let funcs = Funcs::new(&mut store, instance);
+        let func = |index: Option<u32>| match index {
+            Some(index) => funcs
+                .by_index
+                .get(FuncIndex::from_u32(index))
+                .and_then(|f| *f)
+                .map(Some)
+                .ok_or_else(|| format_err!("state image refers to an unknown function")),
+            None => Ok(None),
+        };
+
+        // Everything in the image is checked against the instance before any
+        // of the instance's state is modified, so that an image which doesn't
+        // fit leaves the instance untouched.
+        let mismatch = || format_err!("state image does not match the instance's module");
+        let mut memory_states = self.memories.iter();
+        let mut table_states = self.tables.iter();
+        let mut global_states = self.globals.iter();
+        let mut memories = Vec::new();
+        let mut tables = Vec::new();
+        let mut globals = Vec::new();
+        for item in items {
+            match item {
+                Extern::Memory(memory) => {
+                    let state = memory_states.next().ok_or_else(mismatch)?;
+                    let current = u64::try_from(memory.data_size(&store))?;
+                    if current > state.size {
+                        bail!("cannot shrink linear memory to the size in the state image");
+                    }
+                    let page_size = memory.page_size(&store);
+                    if state.size % page_size != 0 {
+                        bail!("state image memory size is not a multiple of the page size");
+                    }
+                    let pages = state.size / page_size;
+                    if memory.ty(&store).maximum().is_some_and(|max| pages > max) {
+                        bail!("state image memory is larger than the memory's maximum size");
+                    }
+                    for (offset, bytes) in state.chunks.iter() {
+                        let end = offset.checked_add(u64::try_from(bytes.len())?);
+                        if end.is_none_or(|end| end > state.size) {
+                            bail!("state image memory contents out of bounds");
+                        }
+                    }
+                    memories.push((memory, state, pages - current / page_size));
+                }
+                Extern::SharedMemory(_) => bail!("cannot thaw an instance's shared memory"),
+                Extern::Table(table) => {
+                    let state = table_states.next().ok_or_else(mismatch)?;
+                    let ty = table.ty(&store);
+                    let size = u64::try_from(state.elements.len())?;
+                    if table.size(&store) > size {
+                        bail!("cannot shrink table to the size in the state image");
+                    }
+                    if ty.maximum().is_some_and(|max| size > max) {
+                        bail!("state image table is larger than the table's maximum size");
+                    }
+                    let mut elements = Vec::new();
+                    for elem in state.elements.iter() {
+                        let elem = Ref::Func(func(*elem)?);
+                        if !elem.matches_ty(&store, ty.element())? {
+                            bail!("state image table element does not match the table's type");
+                        }
+                        elements.push(elem);
+                    }
+                    tables.push((table, elements));
+                }
+                Extern::Global(global) => {
+                    let ty = global.ty(&store);
+                    let state = match (
+                        global_states.next().ok_or_else(mismatch)?,
+                        ty.mutability().is_var(),
+                    ) {
+                        (Some(state), true) => state,
+                        (None, false) => continue,
+                        _ => return Err(mismatch()),
+                    };
+                    let val = match *state {
+                        GlobalState::I32(i) => Val::I32(i),
+                        GlobalState::I64(i) => Val::I64(i),
+                        GlobalState::F32(f) => Val::F32(f),
+                        GlobalState::F64(f) => Val::F64(f),
+                        GlobalState::V128(v) => Val::V128(V128::from(v)),
+                        GlobalState::FuncRef(f) => Val::FuncRef(func(f)?),
+                    };
+                    if !val.matches_ty(&store, ty.content())? {
+                        bail!("state image global does not match the global's type");
+                    }
+                    globals.push((global, val));
+                }
+                Extern::Func(_) | Extern::Tag(_) => unreachable!(),
+            }
+        }
+        if memory_states.next().is_some()
+            || table_states.next().is_some()
+            || global_states.next().is_some()
+        {
+            return Err(mismatch());
+        }
+
+        // Growing may still fail, for example when denied by a resource
+        // limiter, so everything is grown before any contents are written.
+        for (memory, _, delta) in memories.iter() {
+            memory.grow(&mut store, *delta)?;
+        }
+        for (table, elements) in tables.iter() {
+            let current = table.size(&store);
+            let size = u64::try_from(elements.len())?;
+            if current < size {
+                // Grow with an element of the image rather than null, which
+                // non-nullable tables would reject.
+                let init = elements[usize::try_from(current)?].clone();
+                table.grow(&mut store, size - current, init)?;
+            }
+        }
+
+        for (memory, state, _) in memories {
+            let data = memory.data_mut(&mut store);
+            data.fill(0);
+            for (offset, bytes) in state.chunks.iter() {
+                let offset = usize::try_from(*offset)?;
+                data[offset..][..bytes.len()].copy_from_slice(bytes);
+            }
+        }
+        for (table, elements) in tables {
+            for (i, elem) in elements.into_iter().enumerate() {
+                table.set(&mut store, u64::try_from(i)?, elem)?;
+            }
+        }
+        for (global, val) in globals {
+            global.set(&mut store, val)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a digest of the parts of `module` which determine the shape of its
+/// instances' state: its imports, exports, and the types of all its items.
+///
+/// Function bodies and initializers aren't included, so modules which differ
+/// only in their code are considered the same.
+fn module_digest(module: &Module) -> Result<[u8; 32]> {
+    let exports = module
+        .exports
+        .iter()
+        .map(|(name, index)| (module.strings.get(*name), *index))
+        .collect::<Vec<_>>();
+    let imports = module.imports().collect::<Vec<_>>();
+    let shape = (
+        imports,
+        exports,
+        &module.functions,
+        &module.tables,
+        &module.memories,
+        &module.globals,
+        &module.tags,
+    );
+    Ok(Sha256::digest(postcard::to_allocvec(&shape)?).into())
+}
+
+/// Returns the memories, tables, and globals defined (not imported) by
+/// `instance`, in that order.
+fn defined_items<T>(store: &mut StoreContextMut<'_, T>, instance: &Instance) -> Vec<Extern> {
+    let module = store.0[instance.id].env_module().clone();
+    let memories = module
+        .memories
+        .keys()
+        .skip(module.num_imported_memories)
+        .map(EntityIndex::Memory);
+    let tables = module
+        .tables
+        .keys()
+        .skip(module.num_imported_tables)
+        .map(EntityIndex::Table);
+    let globals = module
+        .globals
+        .keys()
+        .skip(module.num_imported_globals)
+        .map(EntityIndex::Global);
+    memories
+        .chain(tables)
+        .chain(globals)
+        .map(|entity| instance._get_export(store.0, entity))
+        .collect()
+}
+
+/// The functions of an instance which may be referenced from its tables and
+/// globals.
+struct Funcs {
+    by_index: PrimaryMap<FuncIndex, Option<Func>>,
+    by_ref: HashMap<NonNull<VMFuncRef>, u32>,
+}
+
+impl Funcs {
+    fn new<T>(store: &mut StoreContextMut<'_, T>, instance: &Instance) -> Funcs {
+        let module = store.0[instance.id].env_module().clone();
+        let mut funcs = Funcs {
+            by_index: PrimaryMap::new(),
+            by_ref: HashMap::new(),
+        };
+        for (index, func) in module.functions.iter() {
+            if !func.is_escaping() {
+                funcs.by_index.push(None);
+                continue;
+            }
+            let func = match instance._get_export(store.0, EntityIndex::Function(index)) {
+                Extern::Func(func) => func,
+                _ => unreachable!(),
+            };
+            funcs
+                .by_ref
+                .insert(func.vm_func_ref(store.0), index.as_u32());
+            funcs.by_index.push(Some(func));
+        }
+        funcs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Engine, Module, Store};
+
+    #[test]
+    fn thaw_rejects_malformed_images() -> Result<()> {
+        let wat = r#"
+            (module
+                (memory (export "memory") 1 2)
+                (table 2 funcref)
+                (global (export "counter") (mut i32) (i32.const 0))
+                (global (mut funcref) (ref.null func))
+                (global i32 (i32.const 0))
+                (func $f)
+                (elem declare func $f))
+        "#;
+        let engine = Engine::default();
+        let module = Module::new(&engine, wat)?;
+
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        memory.data_mut(&mut store)[0] = 1;
+        let counter = instance.get_global(&mut store, "counter").unwrap();
+        counter.set(&mut store, Val::I32(1))?;
+        let image = instance.freeze(&mut store)?;
+
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let fresh = instance.freeze(&mut store)?;
+
+        // Memories are restored before globals, so the instance is checked to
+        // be unchanged after each failure.
+        let tampered: [fn(&mut StateImage); 14] = [
+            |image| image.memories.clear(),
+            |image| image.memories.push(image.memories[0].clone()),
+            |image| image.tables.clear(),
+            |image| image.globals.truncate(2),
+            |image| image.globals.push(None),
+            |image| image.globals[0] = None,
+            |image| image.globals[2] = Some(GlobalState::I32(1)),
+            |image| image.globals[0] = Some(GlobalState::I64(1)),
+            |image| image.globals[1] = Some(GlobalState::FuncRef(Some(1000))),
+            |image| image.memories[0].size += 1,
+            |image| image.memories[0].size = 3 << 16,
+            |image| image.memories[0].chunks.push((1 << 16, vec![1])),
+            |image| image.memories[0].chunks.push((u64::MAX, vec![1])),
+            |image| image.tables[0].elements.push(Some(1000)),
+        ];
+        for tamper in tampered {
+            let mut bad = image.clone();
+            tamper(&mut bad);
+            assert!(instance.thaw(&mut store, &bad).is_err());
+            assert_eq!(instance.freeze(&mut store)?, fresh);
+        }
+
+        instance.thaw(&mut store, &image)?;
+        assert_eq!(instance.freeze(&mut store)?, image);
+
+        let bytes = image.to_bytes();
+        for len in 0..bytes.len() {
+            assert!(StateImage::from_bytes(&bytes[..len]).is_err());
+        }
+        Ok(())
+    }
+}
@@ -94,7 +94,9 @@ use crate::trampoline::VMHostGlobalContext;
 #[cfg(feature = "debug")]
 use crate::{BreakpointState, DebugHandler, FrameDataCache};
 use crate::{Engine, Module, Val, ValRaw, module::ModuleRegistry};
-use crate::{Global, Instance, Table};
+use crate::{Global, Instance, ResourceContext, Table};
+use alloc::sync::Arc;
+use core::any::Any;
 use core::convert::Infallible;
 use core::fmt;
 #[cfg(any(feature = "async", feature = "gc"))]
@@ -113,6 +115,9 @@ mod context;
 pub use self::context::*;
 mod data;
 pub use self::data::*;
+mod data_handle;
+use self::data_handle::DataQueue;
+pub use self::data_handle::StoreDataHandle;
 mod func_refs;
 use func_refs::FuncRefs;
 #[cfg(feature = "component-model-async")]
@@ -258,6 +263,10 @@ pub struct StoreInner<T: 'static> {
     /// For comments about `ManuallyDrop`, see `Store::into_data`.
     data_no_provenance: ManuallyDrop<T>,
 
+    /// Closures queued by [`StoreDataHandle`]s to run against `T`, created
+    /// the first time a handle is requested.
+    data_queue: Option<Arc<DataQueue<T>>>,
+
     /// The user's debug handler, if any. See [`crate::DebugHandler`]
     /// for more documentation.
     ///
@@ -317,31 +326,76 @@ enum ResourceLimiterInner<T> {
 /// efficient to pass around (nullable pointer) and it's also notably passed
 /// around as an `Option` to represent how this is optionally specified within a
 /// store.
-pub enum StoreResourceLimiter<'a> {
+///
+/// The limiter additionally carries the [`ResourceContext`] of the memory or
+/// table that's being operated on, which is set with
+/// [`StoreResourceLimiter::set_context`] by callers which know what instance
+/// they're operating on behalf of.
+pub struct StoreResourceLimiter<'a> {
+    kind: StoreResourceLimiterKind<'a>,
+    context: ResourceContext,
+}
+
+enum StoreResourceLimiterKind<'a> {
     Sync(&'a mut dyn crate::ResourceLimiter),
     #[cfg(feature = "async")]
     Async(&'a mut dyn crate::ResourceLimiterAsync),
 }
 
 impl StoreResourceLimiter<'_> {
+    /// Returns whether this is a `ResourceLimiterAsync`.
+    pub(crate) fn is_async(&self) -> bool {
+        match self.kind {
+            StoreResourceLimiterKind::Sync(_) => false,
+            #[cfg(feature = "async")]
+            StoreResourceLimiterKind::Async(_) => true,
+        }
+    }
+
+    /// Sets the context reported for subsequent memory and table operations.
+    pub(crate) fn set_context(&mut self, context: ResourceContext) {
+        self.context = context;
+    }
+
+    pub(crate) async fn memory_creating(
+        &mut self,
+        minimum: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool, Error> {
+        if !self.context.is_host() {
+            return self.memory_growing(0, minimum, maximum).await;
+        }
+        match &mut self.kind {
+            StoreResourceLimiterKind::Sync(s) => s.host_memory_creating(minimum, maximum),
+            #[cfg(feature = "async")]
+            StoreResourceLimiterKind::Async(s) => s.host_memory_creating(minimum, maximum).await,
+        }
+    }
+
     pub(crate) async fn memory_growing(
         &mut self,
         current: usize,
         desired: usize,
         maximum: Option<usize>,
     ) -> Result<bool, Error> {
-        match self {
-            Self::Sync(s) => s.memory_growing(current, desired, maximum),
+        let context = &self.context;
+        match &mut self.kind {
+            StoreResourceLimiterKind::Sync(s) => {
+                s.memory_growing_with_context(context, current, desired, maximum)
+            }
             #[cfg(feature = "async")]
-            Self::Async(s) => s.memory_growing(current, desired, maximum).await,
+            StoreResourceLimiterKind::Async(s) => {
+                s.memory_growing_with_context(context, current, desired, maximum)
+                    .await
+            }
         }
     }
 
     pub(crate) fn memory_grow_failed(&mut self, error: crate::Error) -> Result<()> {
-        match self {
-            Self::Sync(s) => s.memory_grow_failed(error),
+        match &mut self.kind {
+            StoreResourceLimiterKind::Sync(s) => s.memory_grow_failed(error),
             #[cfg(feature = "async")]
-            Self::Async(s) => s.memory_grow_failed(error),
+            StoreResourceLimiterKind::Async(s) => s.memory_grow_failed(error),
         }
     }
 
@@ -351,18 +405,36 @@ impl StoreResourceLimiter<'_> {
         desired: usize,
         maximum: Option<usize>,
     ) -> Result<bool, Error> {
-        match self {
-            Self::Sync(s) => s.table_growing(current, desired, maximum),
+        let context = &self.context;
+        match &mut self.kind {
+            StoreResourceLimiterKind::Sync(s) => {
+                s.table_growing_with_context(context, current, desired, maximum)
+            }
             #[cfg(feature = "async")]
-            Self::Async(s) => s.table_growing(current, desired, maximum).await,
+            StoreResourceLimiterKind::Async(s) => {
+                s.table_growing_with_context(context, current, desired, maximum)
+                    .await
+            }
         }
     }
 
     pub(crate) fn table_grow_failed(&mut self, error: crate::Error) -> Result<()> {
-        match self {
-            Self::Sync(s) => s.table_grow_failed(error),
+        match &mut self.kind {
+            StoreResourceLimiterKind::Sync(s) => s.table_grow_failed(error),
             #[cfg(feature = "async")]
-            Self::Async(s) => s.table_grow_failed(error),
+            StoreResourceLimiterKind::Async(s) => s.table_grow_failed(error),
+        }
+    }
+
+    pub(crate) async fn instance_creating(
+        &mut self,
+        module: &Module,
+        current: usize,
+    ) -> Result<bool, Error> {
+        match &mut self.kind {
+            StoreResourceLimiterKind::Sync(s) => s.instance_creating(module, current),
+            #[cfg(feature = "async")]
+            StoreResourceLimiterKind::Async(s) => s.instance_creating(module, current).await,
         }
     }
 }
@@ -469,6 +541,14 @@ pub struct StoreOpaque {
     continuations: Vec<Box<VMContRef>>,
 
     instances: TryPrimaryMap<InstanceId, StoreInstance>,
+    /// Instances marked with `Instance::mark_recyclable` which are reused,
+    /// instead of allocating a new instance, by the next instantiation of the
+    /// same module in this store.
+    recyclable_instances: TryVec<InstanceId>,
+    /// Pairs of an instance created by `Memory::alias` and the instance
+    /// owning the memory that it aliases. Instances owning aliased memories
+    /// are never recycled since their memories must outlive the aliases.
+    memory_aliases: TryVec<(InstanceId, InstanceId)>,
 
     signal_handler: Option<SignalHandler>,
     modules: ModuleRegistry,
@@ -495,6 +575,19 @@ pub struct StoreOpaque {
     // until the reserve is empty.
     fuel_reserve: u64,
     pub(crate) fuel_yield_interval: Option<NonZeroU64>,
+    /// Number of epoch ticks that start functions are allowed to run for, as
+    /// configured by `Store::start_function_epoch_deadline`, and whether that
+    /// deadline is the one currently in effect.
+    #[cfg(target_has_atomic = "64")]
+    start_function_epoch_deadline: Option<u64>,
+    #[cfg(target_has_atomic = "64")]
+    in_start_function_deadline: bool,
+    /// Number of times a `memory.grow` executed by wasm has failed within this
+    /// store, for example due to a `ResourceLimiter` denying the growth.
+    memory_grow_failures: u64,
+    /// Counters of how traps raised by compiled code in this store were
+    /// delivered.
+    trap_stats: crate::TrapStats,
     /// Indexed data within this `Store`, used to store information about
     /// globals, functions, memories, etc.
     store_data: StoreData,
@@ -549,6 +642,15 @@ pub struct StoreOpaque {
     /// enabled, so the key-space is unique for each store.)
     #[cfg(feature = "debug")]
     frame_data_cache: FrameDataCache,
+
+    /// Contexts of host functions defined with `Linker::func_wrap_ctx`, keyed
+    /// by the calling instance and the definition. These are created on the
+    /// first call from each instance.
+    instance_contexts: crate::hash_map::HashMap<(InstanceId, usize), Arc<dyn Any + Send + Sync>>,
+
+    /// Instances of modules defined with `Linker::module_lazy`, keyed by the
+    /// lazily-defined module.
+    lazy_instances: crate::hash_map::HashMap<usize, Instance>,
 }
 
 /// Self-pointer to `StoreInner<T>` from within a `StoreOpaque` which is chiefly
@@ -731,6 +833,8 @@ impl<T> Store<T> {
             #[cfg(feature = "stack-switching")]
             continuations: Vec::new(),
             instances: TryPrimaryMap::new(),
+            recyclable_instances: TryVec::new(),
+            memory_aliases: TryVec::new(),
             signal_handler: None,
             gc_store: None,
             #[cfg(feature = "gc")]
@@ -748,6 +852,12 @@ impl<T> Store<T> {
             async_state: Default::default(),
             fuel_reserve: 0,
             fuel_yield_interval: None,
+            #[cfg(target_has_atomic = "64")]
+            start_function_epoch_deadline: None,
+            #[cfg(target_has_atomic = "64")]
+            in_start_function_deadline: false,
+            memory_grow_failures: 0,
+            trap_stats: Default::default(),
             store_data,
             traitobj: StorePtr(None),
             default_caller_vmctx: SendSyncPtr::new(NonNull::dangling()),
@@ -759,7 +869,10 @@ impl<T> Store<T> {
             breakpoints: Default::default(),
             #[cfg(feature = "debug")]
             frame_data_cache: FrameDataCache::new(),
+            instance_contexts: Default::default(),
+            lazy_instances: Default::default(),
         };
+        vm::crash_dump::store_created();
         let mut inner = try_new::<Box<_>>(StoreInner {
             inner,
             limiter: None,
@@ -767,6 +880,7 @@ impl<T> Store<T> {
             #[cfg(target_has_atomic = "64")]
             epoch_deadline_behavior: None,
             data_no_provenance: ManuallyDrop::new(data),
+            data_queue: None,
             #[cfg(feature = "debug")]
             debug_handler: None,
         })?;
@@ -833,6 +947,43 @@ impl<T> Store<T> {
         self.inner.data_mut()
     }
 
+    /// Returns a [`StoreDataHandle`] which can be sent to other tasks and
+    /// threads to queue up work against this store's `T` data.
+    ///
+    /// Work queued through the returned handle, and any of its clones, runs
+    /// the next time [`Store::data_scope`] is called.
+    pub fn data_handle(&mut self) -> StoreDataHandle<T> {
+        self.inner.data_handle()
+    }
+
+    /// Runs `f` with the `T` data owned by this `Store` after first running
+    /// all work queued through this store's [`StoreDataHandle`]s.
+    ///
+    /// This is how work done by tasks holding a [`StoreDataHandle`] is
+    /// rejoined with the store. It's otherwise the same as [`Store::data_mut`].
+    pub fn data_scope<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.inner.data_scope(f)
+    }
+
+    /// Access the [`Extensions`](crate::Extensions) of this `Store`'s data.
+    #[inline]
+    pub fn extensions(&self) -> &crate::Extensions
+    where
+        T: crate::HasExtensions,
+    {
+        self.data().extensions()
+    }
+
+    /// Mutably access the [`Extensions`](crate::Extensions) of this `Store`'s
+    /// data.
+    #[inline]
+    pub fn extensions_mut(&mut self) -> &mut crate::Extensions
+    where
+        T: crate::HasExtensions,
+    {
+        self.data_mut().extensions_mut()
+    }
+
     fn run_manual_drop_routines(&mut self) {
         StoreData::run_manual_drop_routines(StoreContextMut(&mut self.inner));
 
@@ -990,6 +1141,17 @@ impl<T> Store<T> {
         self.inner.get_fuel()
     }
 
+    /// Returns counters of how traps raised by WebAssembly in this store were
+    /// delivered.
+    ///
+    /// These counters can be used to check whether out-of-bounds memory
+    /// accesses are caught with guard regions or with explicit bounds checks
+    /// in a particular configuration. For information about an individual
+    /// trap see [`TrapDetails`](crate::TrapDetails).
+    pub fn trap_stats(&self) -> &crate::TrapStats {
+        self.inner.trap_stats()
+    }
+
     /// Set the fuel to this [`Store`] for wasm to consume while executing.
     ///
     /// For this method to work fuel consumption must be enabled via
@@ -1147,6 +1309,30 @@ impl<T> Store<T> {
         self.inner.epoch_deadline_callback(Box::new(callback));
     }
 
+    /// Configures a dedicated epoch deadline for the start functions of
+    /// instances created within this store.
+    ///
+    /// When set to `Some(ticks)` each start function, including those of the
+    /// core instances created while instantiating a component, runs with an
+    /// epoch deadline of `ticks` beyond the epoch at which it starts. Reaching
+    /// that deadline always traps, regardless of the behavior configured with
+    /// [`Store::epoch_deadline_callback`] and friends, and fails
+    /// instantiation. Once the start function finishes the previously
+    /// configured deadline is restored.
+    ///
+    /// This is intended to bound the time spent instantiating modules on
+    /// latency-sensitive paths independently of the deadline used for the
+    /// rest of execution. When set to `None`, the default, start functions run
+    /// with the store's regular deadline.
+    ///
+    /// Note that this requires
+    /// [`Config::epoch_interruption`](crate::Config::epoch_interruption) to be
+    /// enabled to have any effect.
+    #[cfg(target_has_atomic = "64")]
+    pub fn start_function_epoch_deadline(&mut self, ticks_beyond_current: Option<u64>) {
+        self.inner.start_function_epoch_deadline = ticks_beyond_current;
+    }
+
     /// Tests whether there is a pending exception.
     ///
     /// Ordinarily, a pending exception will be set on a store if and
@@ -1266,6 +1452,14 @@ impl<'a, T> StoreContext<'a, T> {
     pub fn get_fuel(&self) -> Result<u64> {
         self.0.get_fuel()
     }
+
+    /// Returns counters of how traps raised by WebAssembly in this store were
+    /// delivered.
+    ///
+    /// For more information see [`Store::trap_stats`].
+    pub fn trap_stats(&self) -> &'a crate::TrapStats {
+        self.0.trap_stats()
+    }
 }
 
 impl<'a, T> StoreContextMut<'a, T> {
@@ -1283,6 +1477,41 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.data_mut()
     }
 
+    /// Returns a handle to the data owned by this `Store`.
+    ///
+    /// Same as [`Store::data_handle`].
+    pub fn data_handle(&mut self) -> StoreDataHandle<T> {
+        self.0.data_handle()
+    }
+
+    /// Runs `f` with the data owned by this `Store` after running all work
+    /// queued through its handles.
+    ///
+    /// Same as [`Store::data_scope`].
+    pub fn data_scope<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.0.data_scope(f)
+    }
+
+    /// Access the extensions of the data owned by this `Store`.
+    ///
+    /// Same as [`Store::extensions`].
+    pub fn extensions(&self) -> &crate::Extensions
+    where
+        T: crate::HasExtensions,
+    {
+        self.data().extensions()
+    }
+
+    /// Mutably access the extensions of the data owned by this `Store`.
+    ///
+    /// Same as [`Store::extensions_mut`].
+    pub fn extensions_mut(&mut self) -> &mut crate::Extensions
+    where
+        T: crate::HasExtensions,
+    {
+        self.data_mut().extensions_mut()
+    }
+
     /// Returns the underlying [`Engine`] this store is connected to.
     pub fn engine(&self) -> &Engine {
         self.0.engine()
@@ -1295,6 +1524,14 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.get_fuel()
     }
 
+    /// Returns counters of how traps raised by WebAssembly in this store were
+    /// delivered.
+    ///
+    /// For more information see [`Store::trap_stats`].
+    pub fn trap_stats(&self) -> &crate::TrapStats {
+        self.0.trap_stats()
+    }
+
     /// Set the amount of fuel in this store.
     ///
     /// For more information see [`Store::set_fuel`]
@@ -1326,6 +1563,14 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.epoch_deadline_trap();
     }
 
+    /// Configures a dedicated epoch deadline for start functions.
+    ///
+    /// For more information see [`Store::start_function_epoch_deadline`].
+    #[cfg(target_has_atomic = "64")]
+    pub fn start_function_epoch_deadline(&mut self, ticks_beyond_current: Option<u64>) {
+        self.0.start_function_epoch_deadline = ticks_beyond_current;
+    }
+
     /// Tests whether there is a pending exception.
     ///
     /// See [`Store::has_pending_exception`] for more details.
@@ -1382,6 +1627,20 @@ impl<T> StoreInner<T> {
         self.data_limiter_and_opaque().0
     }
 
+    fn data_handle(&mut self) -> StoreDataHandle<T> {
+        let queue = self
+            .data_queue
+            .get_or_insert_with(|| Arc::new(DataQueue::new()));
+        StoreDataHandle::new(queue)
+    }
+
+    fn data_scope<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        if let Some(queue) = self.data_queue.clone() {
+            queue.drain(self.data_mut());
+        }
+        f(self.data_mut())
+    }
+
     #[inline]
     pub fn call_hook(&mut self, s: CallHook) -> Result<()> {
         if self.inner.pkey.is_none() && self.call_hook.is_none() {
@@ -1449,7 +1708,7 @@ impl<T> StoreInner<T> {
         &mut self,
     ) -> Result<(Option<StoreResourceLimiter<'_>>, &mut StoreOpaque)> {
         let (limiter, store) = self.resource_limiter_and_store_opaque();
-        if !matches!(limiter, None | Some(StoreResourceLimiter::Sync(_))) {
+        if limiter.as_ref().is_some_and(|l| l.is_async()) {
             bail!(
                 "when using an async resource limiter `*_async` functions must \
              be used instead"
@@ -1504,6 +1763,11 @@ impl StoreOpaque {
         self.store_data.id()
     }
 
+    /// Returns the number of module instances created within this store.
+    pub fn instance_count(&self) -> usize {
+        self.instance_count
+    }
+
     pub fn bump_resource_counts(&mut self, module: &Module) -> Result<()> {
         fn bump(slot: &mut usize, max: usize, amt: usize, desc: &str) -> Result<()> {
             let new = slot.saturating_add(amt);
@@ -1573,6 +1837,45 @@ impl StoreOpaque {
         &self.modules
     }
 
+    /// Sets the context `key` of `instance`, replacing any previous one.
+    pub(crate) fn set_instance_context(
+        &mut self,
+        instance: InstanceId,
+        key: usize,
+        cx: Arc<dyn Any + Send + Sync>,
+    ) {
+        self.instance_contexts.insert((instance, key), cx);
+    }
+
+    /// Returns the context `key` of `instance`, creating it with `init` if
+    /// this is its first use.
+    pub(crate) fn instance_context<C: Any + Send + Sync>(
+        &mut self,
+        instance: InstanceId,
+        key: usize,
+        init: impl FnOnce() -> C,
+    ) -> Arc<C> {
+        let cx = self
+            .instance_contexts
+            .entry((instance, key))
+            .or_insert_with(|| Arc::new(init()));
+        Arc::clone(cx)
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("instance context keys are unique per type"))
+    }
+
+    /// Returns this store's instance of the lazily-defined module `key`, if
+    /// it has been instantiated yet.
+    pub(crate) fn lazy_instance(&self, key: usize) -> Option<Instance> {
+        self.lazy_instances.get(&key).copied()
+    }
+
+    /// Records `instance` as this store's instance of the lazily-defined
+    /// module `key`.
+    pub(crate) fn set_lazy_instance(&mut self, key: usize, instance: Instance) {
+        self.lazy_instances.insert(key, instance);
+    }
+
     #[inline]
     pub(crate) fn modules_and_engine_and_breakpoints_mut(
         &mut self,
@@ -1867,6 +2170,21 @@ impl StoreOpaque {
         Ok(get_fuel(injected_fuel, self.fuel_reserve))
     }
 
+    /// Returns the fuel that wasm may consume before the next fuel-based
+    /// interruption, i.e. before it either runs out of fuel or yields.
+    pub(crate) fn remaining_active_fuel(&self) -> Result<u64> {
+        crate::ensure!(
+            self.engine().tunables().consume_fuel,
+            "fuel is not configured in this store"
+        );
+        let injected_fuel = unsafe { *self.vm_store_context.fuel_consumed.get() };
+        Ok(if injected_fuel < 0 {
+            injected_fuel.unsigned_abs()
+        } else {
+            0
+        })
+    }
+
     pub(crate) fn refuel(&mut self) -> bool {
         let injected_fuel = unsafe { &mut *self.vm_store_context.fuel_consumed.get() };
         refuel(
@@ -2179,6 +2497,7 @@ at https://bytecodealliance.org/security.
 
         let actual = match kind {
             AllocateInstanceKind::Module(module_id) => {
+                self.engine().counters().core_instantiated();
                 log::trace!(
                     "Adding instance to store: store={:?}, module={module_id:?}, instance={id:?}",
                     self.id()
@@ -2211,6 +2530,204 @@ at https://bytecodealliance.org/security.
         Ok(id)
     }
 
+    /// Records that the memory of the instance `alias` aliases a memory
+    /// defined by the instance `of`.
+    pub(crate) fn note_memory_alias(&mut self, alias: InstanceId, of: InstanceId) -> Result<()> {
+        // Aliases of aliases point into the memory of the original owner.
+        let owner = self
+            .memory_aliases
+            .iter()
+            .find(|(a, _)| *a == of)
+            .map_or(of, |(_, owner)| *owner);
+        self.memory_aliases.push((alias, owner))?;
+        Ok(())
+    }
+
+    /// Returns whether a memory defined by the instance `id` is aliased by a
+    /// memory created with `Memory::alias`.
+    fn has_memory_aliases(&self, id: InstanceId) -> bool {
+        self.memory_aliases.iter().any(|(_, owner)| *owner == id)
+    }
+
+    /// Marks the instance `id` as reusable for a future instantiation of the
+    /// same module within this store.
+    pub(crate) fn mark_instance_recyclable(&mut self, id: InstanceId) -> Result<()> {
+        assert!(matches!(
+            self.instances[id].kind,
+            StoreInstanceKind::Real { .. }
+        ));
+        if self.has_memory_aliases(id) {
+            bail!("cannot recycle an instance whose memories are aliased");
+        }
+        if !self.recyclable_instances.contains(&id) {
+            self.recyclable_instances.push(id)?;
+        }
+        Ok(())
+    }
+
+    /// Attempts to reuse an instance previously marked recyclable for a new
+    /// instantiation of `module` with `imports`.
+    ///
+    /// Returns `Ok(None)` if there's no such instance available, in which case
+    /// a new instance should be allocated instead. On success the returned
+    /// instance has been reset to a freshly-allocated state: its memories and
+    /// tables have been handed back to the instance allocator and replaced
+    /// with new ones, and its `VMContext` has been reinitialized. It still
+    /// needs to be started, however, like a freshly-allocated instance.
+    ///
+    /// # Safety
+    ///
+    /// The `imports` provided must be correctly sized/typed for `module`.
+    pub(crate) async unsafe fn reuse_recyclable_instance(
+        &mut self,
+        limiter: Option<&mut StoreResourceLimiter<'_>>,
+        module: &Module,
+        imports: Imports<'_>,
+    ) -> Result<Option<InstanceId>> {
+        // An instance can only be reset if none of its frames are on the
+        // stack. That's conservatively approximated here by not reusing
+        // anything while wasm is executing in this store at all, or while
+        // there are continuations which may have suspended wasm frames.
+        //
+        // SAFETY: the store context is always valid to read from the host.
+        let wasm_active = unsafe { *self.vm_store_context.last_wasm_entry_fp.get() != 0 };
+        #[cfg(feature = "stack-switching")]
+        let wasm_active = wasm_active || !self.continuations.is_empty();
+        if wasm_active {
+            return Ok(None);
+        }
+
+        let Some(id) = self
+            .recyclable_instances
+            .iter()
+            .copied()
+            // Aliases may have been created after the instance was marked
+            // recyclable.
+            .filter(|id| !self.has_memory_aliases(*id))
+            .find(|id| match self.instances[*id].kind {
+                StoreInstanceKind::Real { module_id } => self
+                    .modules()
+                    .module_by_id(module_id)
+                    .is_some_and(|m| Module::same(m, module)),
+                StoreInstanceKind::Dummy => false,
+            })
+        else {
+            return Ok(None);
+        };
+
+        // Allocate the replacement memories and tables before touching the
+        // instance itself so that a failure leaves the instance intact (and
+        // still recyclable). Note that the engine is cloned to decouple the
+        // allocator's borrow from the store's.
+        let engine = self.engine().clone();
+        let runtime_info = ModuleRuntimeInfo::Module(module.clone());
+        let resources = engine
+            .allocator()
+            .allocate_instance_resources(InstanceAllocationRequest {
+                id,
+                runtime_info: &runtime_info,
+                imports,
+                store: self,
+                limiter,
+            })
+            .await?;
+
+        // SAFETY: the imports are valid per this function's contract, the
+        // resources and instance come from the engine's allocator, and no
+        // frames of this instance are active as checked above.
+        unsafe {
+            resources.reinitialize(self.instances[id].handle.get_mut(), imports)?;
+        }
+        self.recyclable_instances.retain(|i| *i != id);
+        self.instance_contexts.retain(|(i, _), _| *i != id);
+
+        engine.counters().core_instantiated();
+        log::trace!(
+            "Reusing recycled instance in store: store={:?}, instance={id:?}",
+            self.id()
+        );
+        Ok(Some(id))
+    }
+
+    /// Switches the instance `id` over to the compiled code of `module`, a
+    /// recompiled version of the module it was instantiated from.
+    ///
+    /// This patches the instance's own `VMFuncRef`s as well as the function
+    /// imports of all other instances in this store which refer to functions
+    /// of this instance.
+    ///
+    /// # Safety
+    ///
+    /// `module` must have been compiled for this store's engine from the same
+    /// WebAssembly module as the instance `id`.
+    #[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+    pub(crate) unsafe fn upgrade_instance_code(
+        &mut self,
+        id: InstanceId,
+        module: &Module,
+    ) -> Result<()> {
+        use crate::code::ModuleWithCode;
+        use crate::vm::VMOpaqueContext;
+        use std::collections::HashMap;
+        use wasmtime_environ::FuncKey;
+
+        let (modules, engine, breakpoints) = self.modules_and_engine_and_breakpoints_mut();
+        modules.register_module(module, engine, breakpoints)?;
+
+        // Other instances import this instance's functions by copying the
+        // code pointers out of its `VMFuncRef`s, so record which new code
+        // replaces each function's current code before switching over.
+        let (instance, registry) = self.instance_and_module_registry_mut(id);
+        let old = instance
+            .upgraded_code()
+            .or(instance.runtime_module())
+            .unwrap();
+        let old = ModuleWithCode::in_store(registry, old).unwrap();
+        let new = ModuleWithCode::in_store(registry, module).unwrap();
+        let env_module = module.env_module();
+        let mut replacements = HashMap::new();
+        for def_index in env_module.defined_func_indices() {
+            let index = env_module.func_index(def_index);
+            if !env_module.functions[index].is_escaping() {
+                continue;
+            }
+            let wasm_key = FuncKey::DefinedWasmFunction(env_module.module_index, def_index);
+            let array_key = FuncKey::ArrayToWasmTrampoline(env_module.module_index, def_index);
+            let array_call = NonNull::from(new.function(array_key)).cast();
+            let wasm_call = NonNull::from(new.function(wasm_key)).cast();
+            replacements.insert(
+                old.function(wasm_key).as_ptr() as usize,
+                (array_call, wasm_call),
+            );
+        }
+
+        let vmctx = VMOpaqueContext::from_vmcontext(instance.vmctx());
+        // SAFETY: `module` is registered above and is otherwise valid for
+        // this instance per this function's contract.
+        unsafe {
+            instance.upgrade_code(registry, module);
+        }
+
+        for (_, other) in self.instances.iter_mut() {
+            let instance = other.handle.get_mut();
+            // SAFETY: the replacement code implements the same functions as
+            // the code it replaces per this function's contract.
+            unsafe {
+                instance.retarget_function_imports(|import| {
+                    if import.vmctx.as_non_null() != vmctx {
+                        return;
+                    }
+                    let old = import.wasm_call.as_ptr() as usize;
+                    if let Some((array_call, wasm_call)) = replacements.get(&old) {
+                        import.array_call = (*array_call).into();
+                        import.wasm_call = (*wasm_call).into();
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(target_has_atomic = "64")]
     pub(crate) fn set_epoch_deadline(&mut self, delta: u64) {
         // Set a new deadline based on the "epoch deadline delta".
@@ -2227,6 +2744,57 @@ at https://bytecodealliance.org/security.
         *self.vm_store_context.epoch_deadline.get_mut()
     }
 
+    /// Returns the number of epoch ticks left before the epoch deadline is
+    /// reached, or `None` if epoch interruption isn't enabled.
+    #[cfg(target_has_atomic = "64")]
+    pub(crate) fn remaining_epoch_ticks(&self) -> Option<u64> {
+        if !self.engine().tunables().epoch_interruption {
+            return None;
+        }
+        let deadline = unsafe { *self.vm_store_context.epoch_deadline.get() };
+        Some(deadline.saturating_sub(self.engine().current_epoch()))
+    }
+
+    /// Returns the number of failed `memory.grow` operations executed by wasm
+    /// within this store so far.
+    #[cfg(feature = "component-model")]
+    pub(crate) fn memory_grow_failures(&self) -> u64 {
+        self.memory_grow_failures
+    }
+
+    /// Records that a `memory.grow` operation executed by wasm failed.
+    pub(crate) fn note_memory_grow_failure(&mut self) {
+        self.memory_grow_failures += 1;
+    }
+
+    pub(crate) fn trap_stats(&self) -> &crate::TrapStats {
+        &self.trap_stats
+    }
+
+    pub(crate) fn trap_stats_mut(&mut self) -> &mut crate::TrapStats {
+        &mut self.trap_stats
+    }
+
+    /// Installs the deadline configured for start functions, if any,
+    /// returning the state it replaced to pass to
+    /// `exit_start_function_deadline` once the start function is done.
+    #[cfg(target_has_atomic = "64")]
+    pub(crate) fn enter_start_function_deadline(&mut self) -> Option<(u64, bool)> {
+        let ticks = self.start_function_epoch_deadline?;
+        let prev = (self.get_epoch_deadline(), self.in_start_function_deadline);
+        self.set_epoch_deadline(ticks);
+        self.in_start_function_deadline = true;
+        Some(prev)
+    }
+
+    #[cfg(target_has_atomic = "64")]
+    pub(crate) fn exit_start_function_deadline(&mut self, prev: Option<(u64, bool)>) {
+        if let Some((deadline, in_start_function_deadline)) = prev {
+            *self.vm_store_context.epoch_deadline.get_mut() = deadline;
+            self.in_start_function_deadline = in_start_function_deadline;
+        }
+    }
+
     #[inline]
     pub(crate) fn validate_sync_call(&self) -> Result<()> {
         #[cfg(feature = "async")]
@@ -2323,10 +2891,13 @@ unsafe impl<T> VMStore for StoreInner<T> {
     ) -> (Option<StoreResourceLimiter<'_>>, &mut StoreOpaque) {
         let (data, limiter, opaque) = self.data_limiter_and_opaque();
 
-        let limiter = limiter.map(|l| match l {
-            ResourceLimiterInner::Sync(s) => StoreResourceLimiter::Sync(s(data)),
-            #[cfg(feature = "async")]
-            ResourceLimiterInner::Async(s) => StoreResourceLimiter::Async(s(data)),
+        let limiter = limiter.map(|l| StoreResourceLimiter {
+            kind: match l {
+                ResourceLimiterInner::Sync(s) => StoreResourceLimiterKind::Sync(s(data)),
+                #[cfg(feature = "async")]
+                ResourceLimiterInner::Async(s) => StoreResourceLimiterKind::Async(s(data)),
+            },
+            context: ResourceContext::default(),
         });
 
         (limiter, opaque)
@@ -2334,8 +2905,14 @@ unsafe impl<T> VMStore for StoreInner<T> {
 
     #[cfg(target_has_atomic = "64")]
     fn new_epoch_updated_deadline(&mut self) -> Result<UpdateDeadline> {
+        self.engine().counters().epoch_interrupted();
+
         // Temporarily take the configured behavior to avoid mutably borrowing
         // multiple times.
+        if self.in_start_function_deadline {
+            return Ok(UpdateDeadline::Interrupt);
+        }
+
         let mut behavior = self.epoch_deadline_behavior.take();
         let update = match &mut behavior {
             Some(callback) => callback((&mut *self).as_context_mut()),
@@ -2438,6 +3015,8 @@ impl Drop for StoreOpaque {
 
             self.store_data.decrement_allocator_resources(allocator);
         }
+
+        vm::crash_dump::store_dropped();
     }
 }
 
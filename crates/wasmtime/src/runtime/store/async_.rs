@@ -5,7 +5,7 @@ use crate::prelude::*;
 #[cfg(feature = "gc")]
 use crate::runtime::vm::VMStore;
 use crate::store::{Asyncness, ResourceLimiterInner, StoreInner, StoreOpaque};
-use crate::{Store, StoreContextMut, UpdateDeadline};
+use crate::{Store, StoreContext, StoreContextMut, UpdateDeadline};
 
 /// An object that can take callbacks when the runtime enters or exits hostcalls.
 #[cfg(feature = "call-hook")]
@@ -131,6 +131,21 @@ impl<T> Store<T> {
     pub fn epoch_deadline_async_yield_and_update(&mut self, delta: u64) {
         self.inner.epoch_deadline_async_yield_and_update(delta);
     }
+
+    /// Returns whether this store is currently unwinding an asynchronous call
+    /// which was cancelled.
+    ///
+    /// When the future of an asynchronous call into WebAssembly, such as
+    /// [`Func::call_async`](crate::Func::call_async), is dropped while
+    /// WebAssembly is suspended, the suspended computation is unwound with a
+    /// [`Cancelled`](crate::Cancelled) error. This method returns `true` for
+    /// the duration of that unwinding, for example within host functions or
+    /// destructors which run as part of it, and `false` otherwise. While this
+    /// returns `true` no asynchronous work can be performed within this store,
+    /// as suspending fails with a [`Cancelled`](crate::Cancelled) error.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
 }
 
 impl<'a, T> StoreContextMut<'a, T> {
@@ -165,6 +180,24 @@ impl<'a, T> StoreContextMut<'a, T> {
     pub fn epoch_deadline_async_yield_and_update(&mut self, delta: u64) {
         self.0.epoch_deadline_async_yield_and_update(delta);
     }
+
+    /// Returns whether this store is currently unwinding an asynchronous call
+    /// which was cancelled.
+    ///
+    /// For more information see [`Store::is_cancelled`].
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+impl<'a, T> StoreContext<'a, T> {
+    /// Returns whether this store is currently unwinding an asynchronous call
+    /// which was cancelled.
+    ///
+    /// For more information see [`Store::is_cancelled`].
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
 }
 
 impl<T> StoreInner<T> {
@@ -193,6 +226,10 @@ impl StoreOpaque {
         *self.async_state.last_fiber_stack() = Some(stack);
     }
 
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.async_state.is_cancelled()
+    }
+
     /// Releases the last fiber stack to the underlying instance allocator, if
     /// present.
     pub fn flush_fiber_stack(&mut self) {
@@ -10,7 +10,7 @@ use core::pin::Pin;
 // it only as `pub(crate)`. This avoids a ton of
 // crate-private-type-in-public-interface errors that aren't really too
 // interesting to deal with.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct InstanceId(u32);
 wasmtime_environ::entity_impl!(InstanceId);
 
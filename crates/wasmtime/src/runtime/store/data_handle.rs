@@ -0,0 +1,232 @@
+//! Deferred access to a store's `T` data from outside of the store.
+//!
+//! A [`StoreDataHandle`] is a cloneable, `Send` and `Sync` handle which
+//! queues up closures to run against the data of a [`Store`](crate::Store).
+//! Those closures are run in order the next time the store's owner rejoins
+//! with [`Store::data_scope`](crate::Store::data_scope) or one of its
+//! equivalents. This lets asynchronous work that doesn't have access to the
+//! store, such as tasks spawned onto an executor by an async host function,
+//! record updates to the store's data without the data itself needing to be
+//! wrapped in something like `Arc<Mutex<...>>`.
+
+use crate::prelude::*;
+use crate::sync::Mutex;
+use alloc::sync::{Arc, Weak};
+use core::fmt;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+type Deferred<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+/// The queue of closures pending for a store, owned by the store itself.
+pub(crate) struct DataQueue<T> {
+    pending: Mutex<Vec<Deferred<T>>>,
+}
+
+impl<T> DataQueue<T> {
+    pub(crate) fn new() -> DataQueue<T> {
+        DataQueue {
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Runs all closures queued for `data`, including any queued while doing
+    /// so, in the order they were queued.
+    pub(crate) fn drain(&self, data: &mut T) {
+        loop {
+            let pending = mem::take(&mut *self.pending.lock());
+            if pending.is_empty() {
+                break;
+            }
+            for f in pending {
+                f(data);
+            }
+        }
+    }
+}
+
+/// A handle to the `T` data of a [`Store`](crate::Store) which can be sent to
+/// other tasks and threads.
+///
+/// Handles are created with [`Store::data_handle`](crate::Store::data_handle)
+/// or one of its equivalents such as
+/// [`Caller::data_handle`](crate::Caller::data_handle). Closures given to
+/// [`StoreDataHandle::defer`] and [`StoreDataHandle::with`] don't run
+/// immediately but instead run, in the order they were queued, the next time
+/// the store's owner calls [`Store::data_scope`](crate::Store::data_scope) or
+/// one of its equivalents. Access to the data is thus always serialized with
+/// the store's owner without any locking of `T` itself.
+///
+/// A handle doesn't keep its store alive. Once the store is dropped, closures
+/// which haven't run yet are dropped without running and further use of the
+/// handle returns an error.
+///
+/// # Example
+///
+/// ```
+/// use wasmtime::*;
+///
+/// # fn main() -> Result<()> {
+/// let engine = Engine::default();
+/// let mut store = Store::new(&engine, 0);
+///
+/// // Handles can be cloned and sent to other threads...
+/// let handle = store.data_handle();
+/// std::thread::spawn(move || {
+///     handle.defer(|count: &mut i32| *count += 1).unwrap();
+/// })
+/// .join()
+/// .unwrap();
+///
+/// // ... and the work they queue is applied when the store rejoins.
+/// assert_eq!(*store.data(), 0);
+/// let count = store.data_scope(|count| *count);
+/// assert_eq!(count, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct StoreDataHandle<T: 'static> {
+    queue: Weak<DataQueue<T>>,
+}
+
+impl<T: 'static> StoreDataHandle<T> {
+    pub(crate) fn new(queue: &Arc<DataQueue<T>>) -> StoreDataHandle<T> {
+        StoreDataHandle {
+            queue: Arc::downgrade(queue),
+        }
+    }
+
+    /// Queues `f` to run against the store's data the next time the store's
+    /// owner rejoins with [`Store::data_scope`](crate::Store::data_scope).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store has been dropped.
+    pub fn defer(&self, f: impl FnOnce(&mut T) + Send + 'static) -> Result<()> {
+        let queue = self.queue.upgrade().ok_or_else(store_dropped)?;
+        queue.pending.lock().push(Box::new(f));
+        Ok(())
+    }
+
+    /// Queues `f` to run against the store's data, returning a future which
+    /// resolves to its result once it has run.
+    ///
+    /// The returned future only resolves once the store's owner rejoins with
+    /// [`Store::data_scope`](crate::Store::data_scope), so it must not be
+    /// awaited by anything that the store's owner is itself waiting on before
+    /// doing so.
+    ///
+    /// The future resolves to an error if the store is dropped before `f` has
+    /// run.
+    pub fn with<R>(
+        &self,
+        f: impl FnOnce(&mut T) -> R + Send + 'static,
+    ) -> impl Future<Output = Result<R>> + Send + 'static
+    where
+        R: Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(Slot::Pending(None)));
+        let completion = Completion(slot.clone());
+        let queued = self.defer(move |data| completion.complete(f(data)));
+        Deferral {
+            slot: queued.ok().map(|()| slot),
+        }
+    }
+
+    /// Returns whether the store this handle refers to has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.queue.strong_count() == 0
+    }
+}
+
+impl<T: 'static> Clone for StoreDataHandle<T> {
+    fn clone(&self) -> Self {
+        StoreDataHandle {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<T: 'static> fmt::Debug for StoreDataHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreDataHandle")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+fn store_dropped() -> Error {
+    format_err!("the store referred to by this handle has been dropped")
+}
+
+enum Slot<R> {
+    /// The closure hasn't run yet, and the future awaiting it last registered
+    /// this waker, if any.
+    Pending(Option<Waker>),
+    /// The closure ran and produced this result which hasn't been taken yet.
+    Done(R),
+    /// The closure was dropped without running.
+    Dropped,
+    /// The result has been taken by the future.
+    Taken,
+}
+
+/// Sending half of a `Slot`, held by the closure queued with the store.
+///
+/// If this is dropped without completing, because the store was dropped
+/// before running it, the waiting future is woken up with an error.
+struct Completion<R>(Arc<Mutex<Slot<R>>>);
+
+impl<R> Completion<R> {
+    fn complete(self, value: R) {
+        self.finish(Slot::Done(value));
+    }
+
+    fn finish(&self, new: Slot<R>) {
+        let prev = {
+            let mut slot = self.0.lock();
+            match &*slot {
+                Slot::Pending(_) => mem::replace(&mut *slot, new),
+                _ => return,
+            }
+        };
+        if let Slot::Pending(Some(waker)) = prev {
+            waker.wake();
+        }
+    }
+}
+
+impl<R> Drop for Completion<R> {
+    fn drop(&mut self) {
+        self.finish(Slot::Dropped);
+    }
+}
+
+/// Future returned by [`StoreDataHandle::with`].
+struct Deferral<R> {
+    /// The slot the result is placed in, or `None` if the store had already
+    /// been dropped when the closure was queued.
+    slot: Option<Arc<Mutex<Slot<R>>>>,
+}
+
+impl<R> Future for Deferral<R> {
+    type Output = Result<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<R>> {
+        let Some(slot) = &self.slot else {
+            return Poll::Ready(Err(store_dropped()));
+        };
+        let mut slot = slot.lock();
+        match mem::replace(&mut *slot, Slot::Taken) {
+            Slot::Pending(_) => {
+                *slot = Slot::Pending(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+            Slot::Done(value) => Poll::Ready(Ok(value)),
+            Slot::Dropped => Poll::Ready(Err(store_dropped())),
+            Slot::Taken => panic!("`StoreDataHandle::with` future polled after completion"),
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::RootSet;
 use crate::error::{Context, ensure};
 use crate::hash_map::HashMap;
 use crate::module::ModuleRegistry;
+use crate::prelude::*;
 use crate::store::{
     Asyncness, AutoAssertNoGc, InstanceId, StoreOpaque, StoreResourceLimiter, yield_now,
 };
@@ -13,15 +14,16 @@ use crate::vm::{
     SendSyncPtr, StoreGcHostAllocTypes, TraceInfo, VMGcRef,
 };
 use crate::{
-    ExnRef, GcHeapOutOfMemory, Result, Rooted, Store, StoreContextMut, ThrownException, bail,
+    AnyRef, ExnRef, ExternRef, GcHeapOutOfMemory, HostGcRoot, Ref, Result, Rooted, Store,
+    StoreContextMut, ThrownException, WasmStackGcRoot, bail,
 };
 use core::fmt;
 use core::mem::ManuallyDrop;
 use core::num::NonZeroU32;
 use core::ops::{Deref, DerefMut};
 use core::ptr::NonNull;
-use wasmtime_environ::DefinedTagIndex;
 use wasmtime_environ::packed_option::ReservedValue;
+use wasmtime_environ::{DefinedTagIndex, VMGcKind};
 
 #[derive(Default)]
 pub(crate) struct StoreGcData {
@@ -94,6 +96,39 @@ impl<T> Store<T> {
         self.inner.gc_heap_capacity()
     }
 
+    /// Enumerate every GC object that the host is currently holding onto in
+    /// this store, for diagnostic purposes.
+    ///
+    /// This reports the referents of all [`Rooted<T>`]s in active rooting
+    /// scopes, all [`OwnedRooted<T>`][crate::OwnedRooted]s that have not been
+    /// dropped, and all [`WeakRooted<T>`][crate::WeakRooted]s whose referents
+    /// have not been reclaimed. Roots held by Wasm itself, such as globals,
+    /// tables, and Wasm stack frames, are not included.
+    ///
+    /// Each reported object is re-rooted in the current rooting scope, so
+    /// consider calling this within a [`RootScope`][crate::RootScope].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn _foo() -> Result<()> {
+    /// let mut store = Store::<()>::default();
+    /// let x = ExternRef::new(&mut store, "hello")?;
+    /// let _owned = x.to_owned_rooted(&mut store)?;
+    ///
+    /// let mut scope = RootScope::new(&mut store);
+    /// let roots = scope.as_context_mut().host_gc_roots();
+    /// assert_eq!(roots.len(), 2);
+    /// assert_eq!(roots[0].kind(), HostGcRootKind::Scoped);
+    /// assert_eq!(roots[1].kind(), HostGcRootKind::Owned);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn host_gc_roots(&mut self) -> Vec<HostGcRoot> {
+        self.inner.host_gc_roots()
+    }
+
     /// Set an exception as the currently pending exception, and
     /// return an error that propagates the throw.
     ///
@@ -165,6 +200,73 @@ impl<'a, T> StoreContextMut<'a, T> {
         Ok(())
     }
 
+    /// Enumerate every GC object that the host is currently holding onto in
+    /// this store, for diagnostic purposes.
+    ///
+    /// Same as [`Store::host_gc_roots`].
+    pub fn host_gc_roots(&mut self) -> Vec<HostGcRoot> {
+        self.0.host_gc_roots()
+    }
+
+    /// Precisely enumerate the GC references held in the stack slots of the
+    /// active Wasm frames on the current stack.
+    ///
+    /// This is intended to be called from within a host function, for example
+    /// via [`Caller::as_context_mut`][crate::AsContextMut::as_context_mut],
+    /// when every Wasm frame on the stack is stopped at a safepoint. The slots
+    /// of each frame are found with the stack maps of its module's compiled
+    /// code, see [`Module::stack_maps`][crate::Module::stack_maps], so unlike
+    /// a conservative scan of the stack only actual GC references are reported.
+    /// Slots holding null references are skipped. Frames are reported from the
+    /// youngest to the oldest.
+    ///
+    /// Frames of suspended fibers and continuations are not included. Each
+    /// reported object is re-rooted in the current rooting scope, so consider
+    /// calling this within a [`RootScope`][crate::RootScope].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmtime::*;
+    /// # fn _foo() -> Result<()> {
+    /// let mut config = Config::new();
+    /// config.wasm_reference_types(true);
+    /// let engine = Engine::new(&config)?;
+    /// let mut store = Store::new(&engine, ());
+    ///
+    /// let host = Func::wrap(&mut store, |mut caller: Caller<'_, ()>| -> Result<()> {
+    ///     let mut scope = RootScope::new(&mut caller);
+    ///     let roots = scope.as_context_mut().wasm_stack_gc_roots();
+    ///     assert_eq!(roots.len(), 1);
+    ///     let externref = roots[0].value().unwrap_extern().unwrap();
+    ///     assert_eq!(externref.data(&scope)?.unwrap().downcast_ref::<u32>(), Some(&42));
+    ///     Ok(())
+    /// });
+    /// let module = Module::new(
+    ///     &engine,
+    ///     r#"
+    ///         (module
+    ///             (import "" "host" (func $host))
+    ///             (func (export "run") (param externref) (result externref)
+    ///                 call $host
+    ///                 local.get 0)
+    ///         )
+    ///     "#,
+    /// )?;
+    /// let instance = Instance::new(&mut store, &module, &[host.into()])?;
+    /// let run = instance.get_typed_func::<Option<Rooted<ExternRef>>, Option<Rooted<ExternRef>>>(
+    ///     &mut store,
+    ///     "run",
+    /// )?;
+    /// let externref = ExternRef::new(&mut store, 42_u32)?;
+    /// run.call(&mut store, Some(externref))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wasm_stack_gc_roots(&mut self) -> Vec<WasmStackGcRoot> {
+        self.0.wasm_stack_gc_roots()
+    }
+
     /// Set an exception as the currently pending exception, and
     /// return an error that propagates the throw.
     ///
@@ -653,6 +755,8 @@ impl StoreOpaque {
         roots.clear();
         self.gc_data.gc_roots_list = roots;
 
+        self.process_reclaimed_weak_roots(true);
+
         if log::log_enabled!(log::Level::Trace) {
             let gc_store = self.gc_store.as_ref().unwrap();
             let capacity = gc_store.gc_heap_capacity();
@@ -726,8 +830,26 @@ impl StoreOpaque {
             .expect("should have store code for Wasm frame");
         let offset = u32::try_from(offset).unwrap();
 
-        let stack_map =
-            wasmtime_environ::StackMap::lookup(offset, store_code.code_memory().stack_map_data());
+        Self::for_each_wasm_stack_frame_gc_slot(
+            store_code.code_memory(),
+            offset,
+            fp,
+            |slot| unsafe {
+                Self::trace_wasm_stack_slot(gc_roots_list, slot);
+            },
+        );
+    }
+
+    /// Call `f` with each stack slot that may hold a GC reference in the Wasm
+    /// frame with frame pointer `fp`, which is stopped at the safepoint with
+    /// the given `text_offset` in `code`.
+    fn for_each_wasm_stack_frame_gc_slot(
+        code: &crate::CodeMemory,
+        text_offset: u32,
+        fp: *mut usize,
+        mut f: impl FnMut(*mut u32),
+    ) {
+        let stack_map = wasmtime_environ::StackMap::lookup(text_offset, code.stack_map_data());
 
         if let Some(stack_map) = stack_map {
             log::trace!(
@@ -737,18 +859,14 @@ impl StoreOpaque {
 
             let sp = unsafe { stack_map.sp(fp) };
             for stack_slot in unsafe { stack_map.live_gc_refs(sp) } {
-                unsafe {
-                    Self::trace_wasm_stack_slot(gc_roots_list, stack_slot);
-                }
+                f(stack_slot);
             }
         }
 
         #[cfg(feature = "debug")]
-        if let Some(frame_table) = store_code.code_memory().frame_table() {
-            for stack_slot in crate::debug::gc_refs_in_frame(frame_table, offset, fp) {
-                unsafe {
-                    Self::trace_wasm_stack_slot(gc_roots_list, stack_slot);
-                }
+        if let Some(frame_table) = code.frame_table() {
+            for stack_slot in crate::debug::gc_refs_in_frame(frame_table, text_offset, fp) {
+                f(stack_slot);
             }
         }
     }
@@ -860,6 +978,94 @@ impl StoreOpaque {
         log::trace!("End trace GC roots :: user");
     }
 
+    /// Clear weak roots whose referents have been reclaimed and run their
+    /// finalizers.
+    ///
+    /// `after_gc` should be true when a collection has just finished. See
+    /// `RootSet::take_reclaimed_weak_roots` for details.
+    pub(crate) fn process_reclaimed_weak_roots(&mut self, after_gc: bool) {
+        let Some(gc_store) = self.gc_store.as_mut() else {
+            return;
+        };
+        let finalizers = self
+            .gc_data
+            .gc_roots
+            .take_reclaimed_weak_roots(gc_store, after_gc);
+        if !finalizers.is_empty() {
+            log::trace!("Running {} weak root finalizers", finalizers.len());
+        }
+        for finalizer in finalizers {
+            finalizer();
+        }
+    }
+
+    pub(crate) fn host_gc_roots(&mut self) -> Vec<HostGcRoot> {
+        self.trim_gc_liveness_flags(true);
+        self.process_reclaimed_weak_roots(false);
+
+        let mut store = AutoAssertNoGc::new(self);
+        let roots = store
+            .gc_roots()
+            .host_roots()
+            .map(|(kind, gc_ref)| (kind, gc_ref.unchecked_copy()))
+            .collect::<Vec<_>>();
+
+        roots
+            .into_iter()
+            .map(|(kind, gc_ref)| HostGcRoot::new(kind, Self::gc_ref_to_ref(&mut store, &gc_ref)))
+            .collect()
+    }
+
+    pub(crate) fn wasm_stack_gc_roots(&mut self) -> Vec<WasmStackGcRoot> {
+        let mut slots = Vec::new();
+        Backtrace::trace(self, |frame| {
+            let fp = frame.fp() as *mut usize;
+            if let Some((store_code, offset)) = self.modules().store_code_by_pc(frame.pc()) {
+                let text_offset = u32::try_from(offset).unwrap();
+                Self::for_each_wasm_stack_frame_gc_slot(
+                    store_code.code_memory(),
+                    text_offset,
+                    fp,
+                    |slot| slots.push((frame.pc(), offset, slot)),
+                );
+            }
+            core::ops::ControlFlow::Continue(())
+        });
+
+        let mut store = AutoAssertNoGc::new(self);
+        slots
+            .into_iter()
+            .filter_map(|(pc, text_offset, slot)| {
+                // SAFETY: the frame containing this slot is still active, since
+                // we're called from a host function on top of it.
+                let raw = unsafe { core::ptr::read(slot) };
+                let gc_ref = VMGcRef::from_raw_u32(raw)?;
+                let (frame, _) = store.modules().lookup_frame_info(pc)?;
+                let value = Self::gc_ref_to_ref(&mut store, &gc_ref);
+                let slot = SendSyncPtr::new(NonNull::new(slot).unwrap());
+                Some(WasmStackGcRoot::new(frame, text_offset, slot, value))
+            })
+            .collect()
+    }
+
+    /// Create a new root for `gc_ref` of the most specific `Ref` kind for its
+    /// object.
+    fn gc_ref_to_ref(store: &mut AutoAssertNoGc<'_>, gc_ref: &VMGcRef) -> Ref {
+        let kind_of_object = if gc_ref.is_i31() {
+            VMGcKind::AnyRef
+        } else {
+            store.unwrap_gc_store().header(gc_ref).unwrap().kind()
+        };
+        let gc_ref = store.clone_gc_ref(gc_ref);
+        if kind_of_object.matches(VMGcKind::ExternRef) {
+            Ref::Extern(Some(ExternRef::from_cloned_gc_ref(store, gc_ref)))
+        } else if kind_of_object.matches(VMGcKind::ExnRef) {
+            Ref::Exn(Some(ExnRef::from_cloned_gc_ref(store, gc_ref)))
+        } else {
+            Ref::Any(Some(AnyRef::from_cloned_gc_ref(store, gc_ref)))
+        }
+    }
+
     fn trace_pending_exception_roots(&mut self, gc_roots_list: &mut GcRootsList) {
         log::trace!("Begin trace GC roots :: pending exception");
         if let Some(pending_exception) = self.gc_data.pending_exception.as_mut() {
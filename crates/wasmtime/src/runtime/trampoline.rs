@@ -6,13 +6,13 @@ mod table;
 mod tag;
 
 pub use self::global::*;
-pub(crate) use memory::MemoryCreatorProxy;
+pub(crate) use memory::{MemoryCreatorProxy, Preallocation};
 
 use self::memory::create_memory;
 use self::table::create_table;
 use self::tag::create_tag;
 use crate::prelude::*;
-use crate::runtime::vm::{ExportMemory, SharedMemory};
+use crate::runtime::vm::ExportMemory;
 use crate::store::{StoreOpaque, StoreResourceLimiter};
 use crate::{MemoryType, TableType, TagType};
 use wasmtime_environ::{MemoryIndex, TableIndex, TagIndex};
@@ -21,7 +21,7 @@ pub async fn generate_memory_export(
     store: &mut StoreOpaque,
     limiter: Option<&mut StoreResourceLimiter<'_>>,
     m: &MemoryType,
-    preallocation: Option<&SharedMemory>,
+    preallocation: Option<Preallocation<'_>>,
 ) -> Result<ExportMemory> {
     let id = store.id();
     let instance = create_memory(store, limiter, m, preallocation).await?;
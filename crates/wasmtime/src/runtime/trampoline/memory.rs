@@ -5,7 +5,7 @@ use crate::runtime::vm::mpk::ProtectionKey;
 use crate::runtime::vm::{
     CompiledModuleId, InstanceAllocationRequest, InstanceAllocator, Memory, MemoryAllocationIndex,
     MemoryBase, ModuleRuntimeInfo, OnDemandInstanceAllocator, RuntimeLinearMemory,
-    RuntimeMemoryCreator, SharedMemory, Table, TableAllocationIndex,
+    RuntimeMemoryCreator, SendSyncPtr, SharedMemory, Table, TableAllocationIndex,
 };
 use crate::store::{AllocateInstanceKind, InstanceId, StoreOpaque, StoreResourceLimiter};
 use alloc::sync::Arc;
@@ -19,6 +19,17 @@ use wasmtime_environ::{
 #[cfg(feature = "component-model")]
 use wasmtime_environ::component::{Component, VMComponentOffsets};
 
+/// Memory that a "frankenstein" instance created by [`create_memory`] uses
+/// instead of allocating its own.
+pub enum Preallocation<'a> {
+    /// A shared memory, which is imported into instances through its own
+    /// single-memory instance.
+    Shared(&'a SharedMemory),
+    /// A range of another memory in the same store, which must stay allocated
+    /// and not move for as long as the instance is alive.
+    Alias { base: SendSyncPtr<u8>, len: usize },
+}
+
 /// Create a "frankenstein" instance with a single memory.
 ///
 /// This separate instance is necessary because Wasm objects in Wasmtime must be
@@ -28,13 +39,13 @@ pub async fn create_memory(
     store: &mut StoreOpaque,
     limiter: Option<&mut StoreResourceLimiter<'_>>,
     memory_ty: &MemoryType,
-    preallocation: Option<&SharedMemory>,
+    preallocation: Option<Preallocation<'_>>,
 ) -> Result<InstanceId> {
     let mut module = Module::new(StaticModuleIndex::from_u32(0));
 
     // Create a memory, though it will never be used for constructing a memory
     // with an allocator: instead the memories are either preallocated (i.e.,
-    // shared memory) or allocated manually below.
+    // shared memory or an alias of another memory) or allocated manually below.
     let memory_id = module.memories.push(*memory_ty.wasmtime_memory())?;
 
     // Since we have only associated a single memory with the "frankenstein"
@@ -124,7 +135,7 @@ impl RuntimeMemoryCreator for MemoryCreatorProxy {
 }
 
 struct SingleMemoryInstance<'a> {
-    preallocation: Option<&'a SharedMemory>,
+    preallocation: Option<Preallocation<'a>>,
     ondemand: OnDemandInstanceAllocator,
 }
 
@@ -186,12 +197,20 @@ unsafe impl InstanceAllocator for SingleMemoryInstance<'_> {
         }
 
         match self.preallocation {
-            Some(shared_memory) => crate::runtime::box_future(async move {
+            Some(Preallocation::Shared(shared_memory)) => crate::runtime::box_future(async move {
                 Ok((
                     MemoryAllocationIndex::default(),
                     shared_memory.clone().as_memory(),
                 ))
             }),
+            Some(Preallocation::Alias { base, len }) => crate::runtime::box_future(async move {
+                // SAFETY: the creator of the alias guarantees that the aliased
+                // range outlives this instance.
+                let memory = unsafe {
+                    Memory::new_alias(ty, request.store.engine(), base.as_non_null(), len)?
+                };
+                Ok((MemoryAllocationIndex::default(), memory))
+            }),
             None => self
                 .ondemand
                 .allocate_memory(request, ty, memory_index, memory_kind),
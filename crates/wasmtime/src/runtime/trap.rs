@@ -1,8 +1,8 @@
 #[cfg(feature = "coredump")]
 use super::coredump::WasmCoreDump;
 use crate::prelude::*;
-use crate::store::StoreOpaque;
-use crate::{AsContext, Module, bug};
+use crate::store::{StoreInstanceId, StoreOpaque};
+use crate::{AsContext, Memory, Module, bug};
 use core::fmt;
 use core::num::NonZeroUsize;
 use wasmtime_core::alloc::TryVec;
@@ -106,8 +106,26 @@ pub(crate) fn from_runtime_box(
             faulting_addr,
             trap,
         } => {
-            let mut err: Error = match trap {
-                CompiledTrap::Normal(trap) => trap.into(),
+            let err = match trap {
+                CompiledTrap::Normal(trap) => {
+                    // If a fault address was present, for example with
+                    // segfaults, then simultaneously assert that it's within a
+                    // known linear memory and additionally translate it to a
+                    // wasm-local address to be recorded in the trap's details.
+                    let fault = faulting_addr.and_then(|addr| store.wasm_fault(pc, addr));
+                    let delivery = if store.engine().is_pulley() {
+                        TrapDelivery::Interpreter
+                    } else {
+                        TrapDelivery::Signal
+                    };
+                    let details = TrapDetails::new(store, trap, pc, delivery, fault.clone());
+                    store.trap_stats_mut().record(&details);
+                    let err = Error::from(trap).context(details);
+                    match fault {
+                        Some(fault) => err.context(fault),
+                        None => err,
+                    }
+                }
                 CompiledTrap::InternalAssert => {
                     bug!("internal assert triggered in compiled code").into()
                 }
@@ -115,18 +133,14 @@ pub(crate) fn from_runtime_box(
                     bug!("gc heap corruption detected in compiled code").into()
                 }
             };
-
-            // If a fault address was present, for example with segfaults,
-            // then simultaneously assert that it's within a known linear memory
-            // and additionally translate it to a wasm-local address to be added
-            // as context to the error.
-            if let Some(fault) = faulting_addr.and_then(|addr| store.wasm_fault(pc, addr)) {
-                err = err.context(fault);
-            }
             (err, Some(pc))
         }
     };
 
+    if let Some(trap) = error.downcast_ref::<Trap>() {
+        store.engine().counters().trapped(*trap);
+    }
+
     if let Some(bt) = backtrace {
         let bt = WasmBacktrace::from_captured(
             store,
@@ -148,7 +162,19 @@ pub(crate) fn from_runtime_box(
             pc,
             store.engine().config().wasm_backtrace_max_frames,
         );
-        let cd = WasmCoreDump::new(store, bt);
+        let cd = WasmCoreDump::new(store, bt, coredump.frames);
+        let engine = store.engine().clone();
+        if let Some(path) = engine.take_coredump_path() {
+            let name = cd
+                .frames()
+                .last()
+                .and_then(|frame| frame.module().name())
+                .unwrap_or("<unknown>");
+            let bytes = cd._serialize(store, name);
+            if let Err(e) = std::fs::write(path, bytes) {
+                log::warn!("failed to write coredump to {}: {e}", path.display());
+            }
+        }
         error = error.context(cd);
     }
 
@@ -478,6 +504,270 @@ impl fmt::Display for WasmBacktrace {
     }
 }
 
+/// Structured information about where and how WebAssembly trapped.
+///
+/// This structure is attached to the [`wasmtime::Error`] returned whenever
+/// compiled WebAssembly code hits a [`Trap`], alongside the [`Trap`] itself.
+/// It can be acquired with the [`Error::downcast_ref`](crate::Error::downcast_ref)
+/// family of methods to programmatically inspect the trap's location instead
+/// of parsing the error's `Display` output. Unlike [`WasmBacktrace`] these
+/// details are recorded regardless of the
+/// [`Config::wasm_backtrace_max_frames`](crate::Config::wasm_backtrace_max_frames)
+/// setting.
+///
+/// Traps raised by the host, for example from host functions returning
+/// errors, don't have these details attached.
+///
+/// [`wasmtime::Error`]: crate::Error
+///
+/// # Examples
+///
+/// ```
+/// # use wasmtime::*;
+/// # fn main() -> Result<()> {
+/// let engine = Engine::default();
+/// let module = Module::new(
+///     &engine,
+///     r#"
+///         (module
+///             (func (export "run")
+///                 unreachable)
+///         )
+///     "#,
+/// )?;
+/// let mut store = Store::new(&engine, ());
+/// let instance = Instance::new(&mut store, &module, &[])?;
+/// let func = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+/// let error = func.call(&mut store, ()).unwrap_err();
+/// let details = error.downcast_ref::<TrapDetails>().unwrap();
+/// assert_eq!(details.trap(), Trap::UnreachableCodeReached);
+/// assert_eq!(details.func_index(), Some(0));
+/// assert!(details.fault_address().is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrapDetails {
+    trap: Trap,
+    backend: TrapBackend,
+    delivery: TrapDelivery,
+    func_index: Option<u32>,
+    module_offset: Option<usize>,
+    func_offset: Option<usize>,
+    fault: Option<crate::runtime::vm::WasmFault>,
+    fault_memory: Option<Memory>,
+}
+
+impl TrapDetails {
+    pub(crate) fn new(
+        store: &StoreOpaque,
+        trap: Trap,
+        pc: usize,
+        delivery: TrapDelivery,
+        fault: Option<crate::runtime::vm::WasmFault>,
+    ) -> TrapDetails {
+        let engine = store.engine();
+        let frame = store.modules().lookup_frame_info(pc);
+        let recompiled = frame
+            .as_ref()
+            .is_some_and(|(_, m)| m.module().is_recompiled());
+        let backend = if engine.is_pulley() {
+            TrapBackend::Pulley
+        } else if engine.tunables().winch_callable && !recompiled {
+            TrapBackend::Winch
+        } else {
+            TrapBackend::Cranelift
+        };
+        let frame = frame.map(|(info, _)| info);
+        // Shared memories are accessed through `SharedMemory` rather than
+        // `Memory`, so there's no handle to report for them.
+        let fault_memory = fault.as_ref().filter(|f| !f.shared).map(|f| {
+            let instance = StoreInstanceId::new(store.id(), f.instance);
+            // SAFETY: the fault was found within this defined memory of this
+            // instance, which belongs to `store`.
+            unsafe { Memory::from_raw(instance, f.memory) }
+        });
+        TrapDetails {
+            trap,
+            backend,
+            delivery,
+            func_index: frame.as_ref().map(|f| f.func_index()),
+            module_offset: frame.as_ref().and_then(|f| f.module_offset()),
+            func_offset: frame.as_ref().and_then(|f| f.func_offset()),
+            fault,
+            fault_memory,
+        }
+    }
+
+    /// Returns the [`Trap`] code that was raised.
+    pub fn trap(&self) -> Trap {
+        self.trap
+    }
+
+    /// Returns which code generator produced the code that trapped.
+    pub fn backend(&self) -> TrapBackend {
+        self.backend
+    }
+
+    /// Returns how this trap was delivered to Wasmtime.
+    ///
+    /// This can be used to check whether a configuration relies on signal
+    /// handlers to catch traps or on explicit checks in compiled code.
+    pub fn delivery(&self) -> TrapDelivery {
+        self.delivery
+    }
+
+    /// For [`Trap::MemoryOutOfBounds`] traps, returns how the out-of-bounds
+    /// access was detected.
+    ///
+    /// This is [`OutOfBoundsDetection::GuardRegion`] when the access itself
+    /// faulted in the guard region of a linear memory, meaning that virtual
+    /// memory was used in place of a bounds check. Otherwise the access was
+    /// caught by a bounds check in compiled code and this is
+    /// [`OutOfBoundsDetection::BoundsCheck`].
+    ///
+    /// Returns `None` for all other traps.
+    pub fn out_of_bounds_detection(&self) -> Option<OutOfBoundsDetection> {
+        if self.trap != Trap::MemoryOutOfBounds {
+            return None;
+        }
+        Some(if self.fault.is_some() {
+            OutOfBoundsDetection::GuardRegion
+        } else {
+            OutOfBoundsDetection::BoundsCheck
+        })
+    }
+
+    /// Returns the index, in the function index space of its module, of the
+    /// WebAssembly function which trapped.
+    ///
+    /// This is `None` if the trap happened outside of a WebAssembly function,
+    /// for example in a trampoline.
+    pub fn func_index(&self) -> Option<u32> {
+        self.func_index
+    }
+
+    /// Returns the offset within the original wasm module of the instruction
+    /// which trapped.
+    ///
+    /// This has the same caveats as [`FrameInfo::module_offset`].
+    pub fn module_offset(&self) -> Option<usize> {
+        self.module_offset
+    }
+
+    /// Returns the offset from the start of the trapping function, within the
+    /// original wasm module, of the instruction which trapped.
+    ///
+    /// This has the same caveats as [`FrameInfo::func_offset`].
+    pub fn func_offset(&self) -> Option<usize> {
+        self.func_offset
+    }
+
+    /// Returns the linear memory address whose access caused this trap.
+    ///
+    /// This is only available for out-of-bounds accesses which were caught
+    /// through a fault of the access itself, such as accesses into the guard
+    /// region of a linear memory. Accesses caught by explicit bounds checks
+    /// return `None`.
+    pub fn fault_address(&self) -> Option<u64> {
+        Some(self.fault.as_ref()?.wasm_address)
+    }
+
+    /// Returns the size, in bytes, of the linear memory that was accessed at
+    /// the time of the trap.
+    ///
+    /// This is available whenever [`TrapDetails::fault_address`] is.
+    pub fn fault_memory_size(&self) -> Option<usize> {
+        Some(self.fault.as_ref()?.memory_size)
+    }
+
+    /// Returns the linear memory whose access caused this trap.
+    ///
+    /// This is available whenever [`TrapDetails::fault_address`] is, except
+    /// for accesses to a [`SharedMemory`](crate::SharedMemory).
+    pub fn fault_memory(&self) -> Option<Memory> {
+        self.fault_memory
+    }
+}
+
+impl fmt::Display for TrapDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The trap itself leads since these details are the outermost context
+        // of a trap's error when backtraces are disabled.
+        write!(f, "{} in ", self.trap)?;
+        match self.func_index {
+            Some(index) => write!(f, "wasm function {index}")?,
+            None => write!(f, "compiled code")?,
+        }
+        if let Some(offset) = self.module_offset {
+            write!(f, " at module offset {offset:#x}")?;
+        }
+        write!(f, " ({})", self.backend)
+    }
+}
+
+/// The code generator which produced code that trapped, as reported by
+/// [`TrapDetails::backend`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapBackend {
+    /// Native code generated by Cranelift.
+    Cranelift,
+    /// Native code generated by Winch.
+    Winch,
+    /// Pulley bytecode run in Wasmtime's interpreter.
+    Pulley,
+}
+
+impl fmt::Display for TrapBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrapBackend::Cranelift => f.write_str("Cranelift"),
+            TrapBackend::Winch => f.write_str("Winch"),
+            TrapBackend::Pulley => f.write_str("Pulley"),
+        }
+    }
+}
+
+/// How a trap was delivered to Wasmtime, as reported by
+/// [`TrapDetails::delivery`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapDelivery {
+    /// A trapping instruction in native code raised a hardware exception,
+    /// such as a segfault or an illegal instruction, which was caught by
+    /// Wasmtime's signal handler (or exception handler, depending on the
+    /// platform).
+    Signal,
+    /// Native code explicitly called into Wasmtime to raise the trap, as is
+    /// done when [`Config::signals_based_traps`](crate::Config::signals_based_traps)
+    /// is disabled.
+    Libcall,
+    /// The trap was raised by Pulley while interpreting bytecode.
+    Interpreter,
+}
+
+impl fmt::Display for TrapDelivery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrapDelivery::Signal => f.write_str("signal"),
+            TrapDelivery::Libcall => f.write_str("libcall"),
+            TrapDelivery::Interpreter => f.write_str("interpreter"),
+        }
+    }
+}
+
+/// How an out-of-bounds linear memory access was detected, as reported by
+/// [`TrapDetails::out_of_bounds_detection`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutOfBoundsDetection {
+    /// The access faulted in the guard region of a linear memory.
+    GuardRegion,
+    /// The access was caught by a bounds check in compiled code.
+    BoundsCheck,
+}
+
 /// Description of a frame in a backtrace for a [`WasmBacktrace`].
 ///
 /// Whenever an error happens while WebAssembly is executing a
@@ -507,7 +797,7 @@ impl FrameInfo {
             wasmtime_environ::lookup_file_pos(module.engine_code().address_map_data(), text_offset);
         let index = compiled_module.module().func_index(index);
         let func_index = index.as_u32();
-        let func_name = compiled_module.func_name(index).map(|s| s.to_string());
+        let func_name = module.names().func(func_index).map(|s| s.to_string());
 
         // In debug mode for now assert that we found a mapping for `pc` within
         // the function, because otherwise something is buggy along the way and
@@ -35,7 +35,7 @@ pub(crate) struct f64x2(core::convert::Infallible);
 
 use crate::StoreContextMut;
 use crate::prelude::*;
-use crate::store::{StoreInner, StoreOpaque, StoreResourceLimiter};
+use crate::store::{InstanceId, StoreInner, StoreOpaque, StoreResourceLimiter};
 use crate::type_registry::RegisteredType;
 use alloc::sync::Arc;
 use core::fmt;
@@ -90,6 +90,13 @@ pub(crate) mod interpreter_disabled;
 #[cfg(not(feature = "pulley"))]
 pub(crate) use interpreter_disabled as interpreter;
 
+#[cfg(all(feature = "crash-dump", unix))]
+pub(crate) mod crash_dump;
+#[cfg(not(all(feature = "crash-dump", unix)))]
+pub(crate) mod crash_dump_disabled;
+#[cfg(not(all(feature = "crash-dump", unix)))]
+pub(crate) use crash_dump_disabled as crash_dump;
+
 #[cfg(feature = "component-model-async")]
 pub(crate) use sys::{component_async_tls_get, component_async_tls_set};
 
@@ -107,6 +114,7 @@ pub use crate::runtime::vm::instance::{
 #[cfg(feature = "pooling-allocator")]
 pub use crate::runtime::vm::instance::{
     PoolConcurrencyLimitError, PoolingAllocatorMetrics, PoolingInstanceAllocator,
+    spawn_decommit_thread,
 };
 pub use crate::runtime::vm::interpreter::*;
 pub use crate::runtime::vm::memory::{
@@ -321,6 +329,14 @@ impl ModuleRuntimeInfo {
         Ok(ModuleRuntimeInfo::Bare(info))
     }
 
+    /// The `Module` this was created from, if any.
+    pub(crate) fn module(&self) -> Option<&crate::Module> {
+        match self {
+            ModuleRuntimeInfo::Module(m) => Some(m),
+            ModuleRuntimeInfo::Bare(_) => None,
+        }
+    }
+
     /// The underlying Module.
     pub(crate) fn env_module(&self) -> &Arc<wasmtime_environ::Module> {
         match self {
@@ -430,8 +446,14 @@ pub enum WaitResult {
 }
 
 /// Description about a fault that occurred in WebAssembly.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WasmFault {
+    /// The instance which defines the linear memory that was accessed.
+    pub instance: InstanceId,
+    /// The index, within `instance`, of the linear memory that was accessed.
+    pub memory: DefinedMemoryIndex,
+    /// Whether the linear memory that was accessed is a shared memory.
+    pub shared: bool,
     /// The size of memory, in bytes, at the time of the fault.
     pub memory_size: usize,
     /// The WebAssembly address at which the fault occurred.
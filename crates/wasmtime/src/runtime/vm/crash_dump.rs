@@ -0,0 +1,469 @@
+//! Best-effort dumps of Wasmtime's state when the host process crashes.
+//!
+//! When configured with [`Config::crash_dump_fd`](crate::Config::crash_dump_fd)
+//! Wasmtime's signal handlers will write a textual summary of the state of
+//! Wasmtime in this process to a pre-opened file descriptor whenever they
+//! receive a signal which isn't a trap in WebAssembly, right before forwarding
+//! it to the previously installed handler. Such signals are typically fatal,
+//! so this is intended to give postmortems of crashes in production some
+//! context about what WebAssembly was doing at the time.
+//!
+//! Everything here which is read from within a signal handler must be
+//! async-signal-safe. This means that no locks are taken and no memory is
+//! allocated while writing a dump: all state is tracked in global atomics
+//! which are updated as stores, interpreters and pooling allocators come and
+//! go, and the dump itself is formatted into a fixed-size buffer on the stack
+//! and written with `write(2)`.
+
+#![cfg_attr(
+    not(has_native_signals),
+    allow(
+        dead_code,
+        reason = "crash dumps are only written by native signal handlers"
+    )
+)]
+
+use crate::runtime::vm::CallThreadState;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering::Relaxed};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::sync::Arc;
+
+/// The file descriptor that crash dumps are written to, or -1 if crash dumps
+/// are disabled.
+static FD: AtomicI32 = AtomicI32::new(-1);
+
+/// Whether a crash dump has already been written by this process.
+static WRITTEN: AtomicBool = AtomicBool::new(false);
+
+/// The number of `Store`s currently alive in this process.
+static LIVE_STORES: AtomicUsize = AtomicUsize::new(0);
+
+/// Configures crash dumps to get written to `fd`.
+///
+/// Signal handlers are process-wide so this replaces any previously
+/// configured file descriptor. The file descriptor is intentionally leaked to
+/// ensure that it's never closed, and possibly reused, while a signal handler
+/// on another thread is writing to it.
+pub fn set_fd(fd: Arc<OwnedFd>) {
+    FD.store(fd.as_raw_fd(), Relaxed);
+    core::mem::forget(fd);
+}
+
+/// Records that a new `Store` has been created.
+pub fn store_created() {
+    LIVE_STORES.fetch_add(1, Relaxed);
+}
+
+/// Records that a `Store` has been destroyed.
+pub fn store_dropped() {
+    LIVE_STORES.fetch_sub(1, Relaxed);
+}
+
+/// Resources of pooling allocators whose occupancy is included in crash
+/// dumps.
+#[cfg(feature = "pooling-allocator")]
+#[derive(Clone, Copy)]
+pub enum PoolResource {
+    ComponentInstance,
+    CoreInstance,
+    Memory,
+    Table,
+    Stack,
+    GcHeap,
+}
+
+#[cfg(feature = "pooling-allocator")]
+impl PoolResource {
+    const ALL: [PoolResource; 6] = [
+        PoolResource::ComponentInstance,
+        PoolResource::CoreInstance,
+        PoolResource::Memory,
+        PoolResource::Table,
+        PoolResource::Stack,
+        PoolResource::GcHeap,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            PoolResource::ComponentInstance => "component instances",
+            PoolResource::CoreInstance => "core instances",
+            PoolResource::Memory => "memories",
+            PoolResource::Table => "tables",
+            PoolResource::Stack => "stacks",
+            PoolResource::GcHeap => "GC heaps",
+        }
+    }
+
+    fn counts(&self) -> &'static PoolCounts {
+        &POOLS[*self as usize]
+    }
+}
+
+/// Occupancy of one kind of resource summed across all pooling allocators in
+/// this process.
+#[cfg(feature = "pooling-allocator")]
+struct PoolCounts {
+    live: AtomicUsize,
+    capacity: AtomicUsize,
+}
+
+#[cfg(feature = "pooling-allocator")]
+static POOLS: [PoolCounts; PoolResource::ALL.len()] = [const {
+    PoolCounts {
+        live: AtomicUsize::new(0),
+        capacity: AtomicUsize::new(0),
+    }
+}; PoolResource::ALL.len()];
+
+/// Records the capacity of a newly created pooling allocator.
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_created(limits: &crate::config::InstanceLimits) {
+    for (resource, capacity) in pool_capacities(limits) {
+        resource.counts().capacity.fetch_add(capacity, Relaxed);
+    }
+}
+
+/// Records that a pooling allocator has been destroyed.
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_destroyed(limits: &crate::config::InstanceLimits) {
+    for (resource, capacity) in pool_capacities(limits) {
+        resource.counts().capacity.fetch_sub(capacity, Relaxed);
+    }
+}
+
+#[cfg(feature = "pooling-allocator")]
+fn pool_capacities(
+    limits: &crate::config::InstanceLimits,
+) -> impl Iterator<Item = (PoolResource, usize)> {
+    [
+        limits.total_component_instances,
+        limits.total_core_instances,
+        limits.total_memories,
+        limits.total_tables,
+        limits.total_stacks,
+        limits.total_gc_heaps,
+    ]
+    .into_iter()
+    .zip(PoolResource::ALL)
+    .map(|(capacity, resource)| (resource, usize::try_from(capacity).unwrap()))
+}
+
+/// Records that a slot of a pooling allocator has been allocated.
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_allocated(resource: PoolResource) {
+    resource.counts().live.fetch_add(1, Relaxed);
+}
+
+/// Records that a slot of a pooling allocator has been deallocated.
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_deallocated(resource: PoolResource) {
+    resource.counts().live.fetch_sub(1, Relaxed);
+}
+
+/// Registers a new Pulley interpreter whose currently executing program
+/// counter is included in crash dumps.
+///
+/// This requires the `profile-pulley` feature as Pulley otherwise doesn't
+/// track its program counter in a way that can be read from a signal handler.
+#[cfg(feature = "pulley")]
+pub fn register_interpreter(interpreter: &crate::runtime::vm::Interpreter) {
+    #[cfg(feature = "profile-pulley")]
+    interpreters::register(interpreter.pulley().executing_pc());
+    #[cfg(not(feature = "profile-pulley"))]
+    let _ = interpreter;
+}
+
+#[cfg(feature = "profile-pulley")]
+mod interpreters {
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{
+        AtomicUsize, Ordering::Acquire, Ordering::Relaxed, Ordering::Release,
+    };
+    use pulley_interpreter::profile::ExecutingPc;
+
+    /// Maximum number of interpreters tracked at any one time.
+    ///
+    /// Interpreters beyond this limit are silently omitted from crash dumps.
+    const MAX: usize = 64;
+
+    /// `Slot::state` of a slot which has never held an interpreter.
+    const EMPTY: usize = 0;
+
+    /// `Slot::state` of a slot which holds an interpreter and isn't being
+    /// accessed. Each reader of the slot adds one to this.
+    const OCCUPIED: usize = 1;
+
+    /// `Slot::state` of a slot which a registration has exclusive access to.
+    const WRITING: usize = usize::MAX;
+
+    /// A slot tracking the program counter of one interpreter.
+    ///
+    /// The `ExecutingPc` in a slot is only replaced while its registration has
+    /// exclusive access to the slot, which it only gets when no signal handler
+    /// is reading the slot. Registrations never wait for readers, they move on
+    /// to the next slot instead, so a signal handler interrupting a
+    /// registration can't deadlock.
+    struct Slot {
+        state: AtomicUsize,
+        pc: UnsafeCell<Option<ExecutingPc>>,
+    }
+
+    // SAFETY: `pc` is only mutated with exclusive access as tracked by `state`.
+    unsafe impl Sync for Slot {}
+
+    static SLOTS: [Slot; MAX] = [const {
+        Slot {
+            state: AtomicUsize::new(EMPTY),
+            pc: UnsafeCell::new(None),
+        }
+    }; MAX];
+
+    pub fn register(pc: &ExecutingPc) {
+        for slot in SLOTS.iter() {
+            // Slots are reclaimed lazily here, rather than when interpreters
+            // are dropped, once their interpreter is done.
+            let state = slot.state.load(Relaxed);
+            if (state != EMPTY && state != OCCUPIED)
+                || slot
+                    .state
+                    .compare_exchange(state, WRITING, Acquire, Relaxed)
+                    .is_err()
+            {
+                continue;
+            }
+            // SAFETY: this registration has exclusive access to the slot.
+            let entry = unsafe { &mut *slot.pc.get() };
+            if entry.as_ref().is_some_and(|pc| !pc.is_done()) {
+                slot.state.store(OCCUPIED, Release);
+                continue;
+            }
+            *entry = Some(pc.clone());
+            slot.state.store(OCCUPIED, Release);
+            return;
+        }
+    }
+
+    /// Yields the program counters of all registered interpreters which are
+    /// currently executing.
+    pub fn executing_pcs() -> impl Iterator<Item = usize> {
+        SLOTS.iter().filter_map(|slot| {
+            let mut state = slot.state.load(Relaxed);
+            loop {
+                if state == EMPTY || state == WRITING {
+                    return None;
+                }
+                match slot
+                    .state
+                    .compare_exchange_weak(state, state + 1, Acquire, Relaxed)
+                {
+                    Ok(_) => break,
+                    Err(actual) => state = actual,
+                }
+            }
+            // SAFETY: the slot can't be written while it's being read.
+            let pc = unsafe { (*slot.pc.get()).as_ref() };
+            let ret = pc.filter(|pc| !pc.is_done()).and_then(|pc| pc.get());
+            slot.state.fetch_sub(1, Release);
+            ret
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::prelude::*;
+        use pulley_interpreter::interp::Vm;
+        use std::sync::atomic::AtomicBool;
+        use std::thread;
+
+        #[test]
+        fn register_from_many_threads() {
+            let stop = AtomicBool::new(false);
+            thread::scope(|s| {
+                let reader = s.spawn(|| {
+                    let mut reads = 0;
+                    while !stop.load(Relaxed) {
+                        reads += executing_pcs().count();
+                    }
+                    reads
+                });
+                let writers = (0..8)
+                    .map(|_| {
+                        s.spawn(|| {
+                            // Keep a few interpreters alive at a time so that
+                            // slots are both filled and reclaimed.
+                            let mut live = Vec::new();
+                            for i in 0..1000 {
+                                let vm = Vm::new().unwrap();
+                                register(vm.executing_pc());
+                                live.push(vm);
+                                if i % 4 == 3 {
+                                    live.clear();
+                                }
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                for writer in writers {
+                    writer.join().unwrap();
+                }
+                stop.store(true, Relaxed);
+                // None of the interpreters ever executed anything.
+                assert_eq!(reader.join().unwrap(), 0);
+            });
+
+            // All of those interpreters are gone, so every slot can be
+            // reclaimed for a new one.
+            let vms = (0..MAX).map(|_| Vm::new().unwrap()).collect::<Vec<_>>();
+            for vm in vms.iter() {
+                register(vm.executing_pc());
+            }
+            for slot in SLOTS.iter() {
+                assert_eq!(slot.state.load(Relaxed), OCCUPIED);
+                let pc = unsafe { (*slot.pc.get()).as_ref().unwrap() };
+                assert!(!pc.is_done());
+            }
+        }
+    }
+}
+
+/// Writes a crash dump, if enabled and not already written, for the signal
+/// `signum` received by the current thread.
+///
+/// `pc` and `faulting_addr` are the program counter and, for memory faults,
+/// the faulting address of the signal. `state` is the innermost activation of
+/// WebAssembly on the current thread, if any.
+///
+/// # Safety
+///
+/// Must only be called from Wasmtime's signal handlers with `state` as read
+/// from TLS.
+pub unsafe fn write(
+    signum: i32,
+    pc: usize,
+    faulting_addr: Option<usize>,
+    state: Option<&CallThreadState>,
+) {
+    let fd = FD.load(Relaxed);
+    if fd < 0 || WRITTEN.swap(true, Relaxed) {
+        return;
+    }
+    let mut out = Output {
+        fd,
+        buf: [0; 512],
+        len: 0,
+    };
+    // Errors here are from `write(2)` failing, in which case there's nothing
+    // else that can be done, so they're ignored.
+    let _ = unsafe { write_dump(&mut out, signum, pc, faulting_addr, state) };
+    out.flush();
+}
+
+unsafe fn write_dump(
+    out: &mut Output,
+    signum: i32,
+    pc: usize,
+    faulting_addr: Option<usize>,
+    state: Option<&CallThreadState>,
+) -> fmt::Result {
+    writeln!(out, "=== wasmtime crash dump ===")?;
+    writeln!(out, "signal: {signum}")?;
+    writeln!(out, "pc: {pc:#x}")?;
+    if let Some(addr) = faulting_addr {
+        writeln!(out, "faulting address: {addr:#x}")?;
+    }
+    writeln!(out, "live stores: {}", LIVE_STORES.load(Relaxed))?;
+
+    writeln!(out, "wasm activations on the crashing thread:")?;
+    match state {
+        Some(head) => {
+            for (i, state) in head.iter().enumerate() {
+                let store = state.vm_store_context.get();
+                // The state of the store for this activation is saved by the
+                // next-innermost activation of the same store, if any, or
+                // otherwise it's the current state of the store.
+                let newer = head
+                    .iter()
+                    .take(i)
+                    .filter(|s| s.vm_store_context.get() == store)
+                    .last();
+                let exit_pc = match newer {
+                    Some(newer) => unsafe { newer.old_last_wasm_exit_pc() },
+                    None => unsafe { *store.as_ref().last_wasm_exit_pc.get() },
+                };
+                writeln!(
+                    out,
+                    "  #{i}: store context {store:p}, last wasm exit pc {exit_pc:#x}"
+                )?;
+            }
+        }
+        None => writeln!(out, "  (none)")?,
+    }
+
+    #[cfg(feature = "profile-pulley")]
+    {
+        writeln!(out, "executing pulley interpreters:")?;
+        for (i, pc) in interpreters::executing_pcs().enumerate() {
+            writeln!(out, "  #{i}: pc {pc:#x}")?;
+        }
+    }
+
+    #[cfg(feature = "pooling-allocator")]
+    if PoolResource::ALL
+        .iter()
+        .any(|r| r.counts().capacity.load(Relaxed) > 0)
+    {
+        writeln!(out, "pooling allocator occupancy:")?;
+        for resource in PoolResource::ALL {
+            let counts = resource.counts();
+            writeln!(
+                out,
+                "  {}: {} of {}",
+                resource.name(),
+                counts.live.load(Relaxed),
+                counts.capacity.load(Relaxed),
+            )?;
+        }
+    }
+
+    writeln!(out, "=== end of wasmtime crash dump ===")
+}
+
+/// A buffered writer to a file descriptor which doesn't allocate.
+struct Output {
+    fd: i32,
+    buf: [u8; 512],
+    len: usize,
+}
+
+impl Output {
+    fn flush(&mut self) -> bool {
+        let mut buf = &self.buf[..self.len];
+        self.len = 0;
+        while !buf.is_empty() {
+            let n = unsafe { libc::write(self.fd, buf.as_ptr().cast(), buf.len()) };
+            match usize::try_from(n) {
+                Ok(n) => buf = &buf[n..],
+                Err(_)
+                    if std::io::Error::last_os_error().kind()
+                        == std::io::ErrorKind::Interrupted => {}
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+}
+
+impl Write for Output {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for chunk in s.as_bytes().chunks(self.buf.len()) {
+            if self.len + chunk.len() > self.buf.len() && !self.flush() {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..][..chunk.len()].copy_from_slice(chunk);
+            self.len += chunk.len();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+//! Stubs for when crash dumps are disabled at compile time, or unsupported on
+//! this platform, in which case none of the state they need is tracked.
+
+#[cfg(feature = "pooling-allocator")]
+use crate::config::InstanceLimits;
+
+pub fn store_created() {}
+
+pub fn store_dropped() {}
+
+#[cfg(feature = "pooling-allocator")]
+#[derive(Clone, Copy)]
+pub enum PoolResource {
+    #[cfg(feature = "component-model")]
+    ComponentInstance,
+    CoreInstance,
+    Memory,
+    Table,
+    #[cfg(feature = "async")]
+    Stack,
+    #[cfg(feature = "gc")]
+    GcHeap,
+}
+
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_created(_limits: &InstanceLimits) {}
+
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_destroyed(_limits: &InstanceLimits) {}
+
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_allocated(_resource: PoolResource) {}
+
+#[cfg(feature = "pooling-allocator")]
+pub fn pool_deallocated(_resource: PoolResource) {}
+
+#[cfg(feature = "pulley")]
+pub fn register_interpreter(_interpreter: &crate::runtime::vm::Interpreter) {}
+
+#[cfg(all(has_native_signals, unix, feature = "std"))]
+pub unsafe fn write(
+    _signum: i32,
+    _pc: usize,
+    _faulting_addr: Option<usize>,
+    _state: Option<&crate::runtime::vm::CallThreadState>,
+) {
+}
@@ -243,6 +243,21 @@ impl GcStore {
         }
     }
 
+    /// Note that the host holds a weak reference to the given GC reference.
+    pub fn note_weak_target(&mut self, gc_ref: &VMGcRef) -> Result<()> {
+        if gc_ref.is_i31() {
+            Ok(())
+        } else {
+            self.gc_heap.note_weak_target(gc_ref)
+        }
+    }
+
+    /// Move every weakly-referenced object that the GC heap has reported as
+    /// reclaimed since the last call into `reclaimed`.
+    pub fn take_reclaimed_weak_targets(&mut self, reclaimed: &mut Vec<VMGcRef>) {
+        self.gc_heap.take_reclaimed_weak_targets(reclaimed);
+    }
+
     /// Hook to call whenever a GC reference is about to be exposed to Wasm.
     ///
     /// Returns the raw representation of this GC ref, ready to be passed to
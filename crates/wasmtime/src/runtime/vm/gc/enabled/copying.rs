@@ -13,7 +13,7 @@
 use super::VMArrayRef;
 use super::trace_infos::TraceInfos;
 use crate::runtime::vm::{
-    ExternRefHostDataId, GarbageCollection, GcHeap, GcHeapObject, GcProgress, GcRootsIter,
+    ExternRefHostDataId, GarbageCollection, GcHeap, GcHeapObject, GcProgress, GcRoot, GcRootsIter,
     GcRuntime, GcStoreTraceState, SendSyncUnsafeCell, TraceInfo, TypedGcRef, VMExternRef,
     VMGcHeader, VMGcRef, VMMemoryDefinition,
 };
@@ -966,6 +966,7 @@ unsafe impl GcHeap for CopyingHeap {
         assert_eq!(self.no_gc_count, 0, "Cannot GC inside a no-GC scope!");
         Box::new(CopyingCollection {
             roots: Some(roots),
+            weak_roots: Vec::new(),
             trace_state,
             heap: self,
             phase: CopyingCollectionPhase::ProcessRoots,
@@ -1030,6 +1031,9 @@ const OBJECTS_PER_INCREMENT: usize = 16_384;
 
 struct CopyingCollection<'a, 'b> {
     roots: Option<GcRootsIter<'a>>,
+    /// Weak roots, set aside while processing roots and updated or cleared
+    /// once the worklist has been drained.
+    weak_roots: Vec<GcRoot<'a>>,
     trace_state: &'a mut GcStoreTraceState<'b>,
     heap: &'a mut CopyingHeap,
     phase: CopyingCollectionPhase,
@@ -1048,6 +1052,10 @@ impl CopyingCollection<'_, '_> {
         log::trace!("Begin processing GC roots");
         let roots = self.roots.take().unwrap();
         for mut root in roots {
+            if root.is_weak() {
+                self.weak_roots.push(root);
+                continue;
+            }
             let gc_ref = root.get()?;
             if gc_ref.is_i31() {
                 continue;
@@ -1114,6 +1122,35 @@ impl CopyingCollection<'_, '_> {
         Ok(GcProgress::Continue)
     }
 
+    /// Update weak roots whose referents survived collection to point to
+    /// their new locations, and clear the rest.
+    ///
+    /// This must happen after the worklist is drained (so that all live
+    /// objects have been copied) but before the idle semi-space is reused.
+    fn process_weak_roots(&mut self) -> Result<()> {
+        log::trace!("Begin processing weak roots");
+        for mut root in self.weak_roots.drain(..) {
+            let gc_ref = root.get()?;
+            if gc_ref.is_i31() {
+                continue;
+            }
+            debug_assert!(self.heap.is_in_idle_space(gc_ref.heap_index()?.get()));
+            match self
+                .heap
+                .index(header_and_forwarding_ref(&gc_ref))?
+                .forwarding_ref()?
+            {
+                Some(new_ref) => root.set(new_ref),
+                None => {
+                    log::trace!("weakly-referenced object {gc_ref:#p} was reclaimed");
+                    root.clear();
+                }
+            }
+        }
+        log::trace!("End processing weak roots");
+        Ok(())
+    }
+
     /// Clean up dead externrefs by iterating the idle semi-space's externref
     /// linked list and deallocating host data for any that were not forwarded.
     fn sweep_extern_refs(&mut self) -> Result<()> {
@@ -1152,6 +1189,7 @@ impl CopyingCollection<'_, '_> {
                 || self.heap.idle_space_end <= self.heap.active_space_start
         );
 
+        self.process_weak_roots()?;
         self.sweep_extern_refs()?;
         self.heap.resize_semi_spaces();
 
@@ -198,6 +198,11 @@ struct DrcHeap {
 
     /// Running total of bytes currently allocated (live objects) in this heap.
     allocated_bytes: usize,
+
+    /// Objects that were weakly referenced by the host and that have since
+    /// been deallocated, waiting to be reported via
+    /// `take_reclaimed_weak_targets`.
+    reclaimed_weak_targets: Vec<VMGcRef>,
 }
 
 struct TracingAllocs {
@@ -235,12 +240,18 @@ impl DrcHeap {
                 to_dealloc: Vec::with_capacity(1),
             }),
             allocated_bytes: 0,
+            reclaimed_weak_targets: Vec::new(),
         })
     }
 
     fn dealloc(&mut self, gc_ref: VMGcRef) -> Result<()> {
         let drc_ref = drc_ref(&gc_ref);
-        let size = self.index(drc_ref)?.object_size;
+        let header = self.index(drc_ref)?;
+        let size = header.object_size;
+        if header.is_weak_target() {
+            log::trace!("reclaimed weakly-referenced object {gc_ref:#p}");
+            self.reclaimed_weak_targets.push(gc_ref.unchecked_copy());
+        }
         let alloc_size = match FreeList::aligned_size(size) {
             Some(size) => size,
             None => bail_bug!("aligned size overflow"),
@@ -555,7 +566,8 @@ impl DrcHeap {
                 // GC references that we do deferred ref counting for
                 // and that get inserted into our activations
                 // table. Other GC roots are managed purely with naive
-                // ref counting.
+                // ref counting. Weak roots don't hold a reference count
+                // at all; see `note_weak_target`.
                 continue;
             }
 
@@ -836,6 +848,20 @@ impl VMDrcHeader {
         }
     }
 
+    /// Is this object weakly referenced by the host?
+    #[inline]
+    fn is_weak_target(&self) -> bool {
+        self.header.reserved_u26() & wasmtime_environ::drc::HEADER_WEAK_TARGET_BIT != 0
+    }
+
+    /// Record that this object is weakly referenced by the host.
+    #[inline]
+    fn set_weak_target(&mut self) {
+        let reserved = self.header.reserved_u26();
+        self.header
+            .set_reserved_u26(reserved | wasmtime_environ::drc::HEADER_WEAK_TARGET_BIT);
+    }
+
     /// Increment the ref count for this object.
     fn inc_ref(&mut self) {
         debug_assert!(self.ref_count > 0);
@@ -925,6 +951,7 @@ unsafe impl GcHeap for DrcHeap {
             vmmemory,
             allocated_bytes,
             trace_infos,
+            reclaimed_weak_targets,
         } = self;
 
         *no_gc_count = 0;
@@ -933,6 +960,7 @@ unsafe impl GcHeap for DrcHeap {
         *vmmemory = None;
         *allocated_bytes = 0;
         trace_infos.clear();
+        reclaimed_weak_targets.clear();
 
         debug_assert!(tracing_allocs.is_some());
         if let Some(allocs) = tracing_allocs {
@@ -1074,6 +1102,16 @@ unsafe impl GcHeap for DrcHeap {
         Ok(self.index(drc_ref(gc_ref))?.object_size())
     }
 
+    fn note_weak_target(&mut self, gc_ref: &VMGcRef) -> Result<()> {
+        debug_assert!(!gc_ref.is_i31());
+        self.index_mut(drc_ref(gc_ref))?.set_weak_target();
+        Ok(())
+    }
+
+    fn take_reclaimed_weak_targets(&mut self, reclaimed: &mut Vec<VMGcRef>) {
+        reclaimed.append(&mut self.reclaimed_weak_targets);
+    }
+
     fn alloc_raw(&mut self, header: VMGcHeader, layout: Layout) -> Result<Result<VMGcRef, u64>> {
         debug_assert!(layout.size() >= core::mem::size_of::<VMDrcHeader>());
         debug_assert!(layout.align() >= core::mem::align_of::<VMDrcHeader>());
@@ -392,6 +392,32 @@ pub unsafe trait GcHeap: 'static + Send + Sync {
     where
         'b: 'a;
 
+    /// Note that the host holds a weak reference to `gc_ref`.
+    ///
+    /// Weak references are passed to `gc` as weak roots, and tracing
+    /// collectors should update or clear them there. Collectors that don't
+    /// determine liveness by tracing (for example, those that reclaim objects
+    /// when their reference count reaches zero) may ignore weak roots, and
+    /// should instead use this hook to remember which objects are weakly
+    /// referenced and then report them from `take_reclaimed_weak_targets` once
+    /// they have been reclaimed.
+    ///
+    /// Callers should pass a valid, non-`i31ref`, `VMGcRef` that belongs to
+    /// the given heap. Failure to do so is memory safe, but may result in
+    /// general failures such as panics or incorrect results.
+    fn note_weak_target(&mut self, gc_ref: &VMGcRef) -> Result<()> {
+        let _ = gc_ref;
+        Ok(())
+    }
+
+    /// Move every weakly-referenced object that this heap has reclaimed since
+    /// the last call into `reclaimed`.
+    ///
+    /// See `note_weak_target` for details.
+    fn take_reclaimed_weak_targets(&mut self, reclaimed: &mut Vec<VMGcRef>) {
+        let _ = reclaimed;
+    }
+
     ////////////////////////////////////////////////////////////////////////////
     // JIT-Code Interaction Methods
 
@@ -592,6 +618,7 @@ enum RawGcRoot {
     Stack(SendSyncPtr<u32>),
     VMGcRef(SendSyncPtr<VMGcRef>),
     ValRaw(SendSyncPtr<ValRaw>),
+    Weak(SendSyncPtr<Option<VMGcRef>>),
 }
 
 #[cfg(feature = "gc")]
@@ -649,6 +676,27 @@ impl GcRootsList {
         self.0.push(RawGcRoot::ValRaw(ptr_to_root))
     }
 
+    /// Add a weak GC root to this list.
+    ///
+    /// Weak roots do not keep their referent alive. See `GcRoot::is_weak` for
+    /// how collectors should handle them.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be to a valid `Some(VMGcRef)` and must remain valid
+    /// while registered within this `GcRootsList`.
+    #[inline]
+    pub unsafe fn add_weak_root(&mut self, ptr_to_root: SendSyncPtr<Option<VMGcRef>>, why: &str) {
+        unsafe {
+            log::trace!(
+                "Adding weak root: {why}: {:#p}",
+                ptr_to_root.as_ref().as_ref().unwrap().unchecked_copy()
+            );
+            debug_assert!(ptr_to_root.as_ref().is_some());
+        }
+        self.0.push(RawGcRoot::Weak(ptr_to_root))
+    }
+
     /// Get an iterator over all roots in this list.
     ///
     /// # Safety
@@ -714,6 +762,18 @@ impl GcRoot<'_> {
         matches!(self.raw, RawGcRoot::Stack(_))
     }
 
+    /// Is this a weak root?
+    ///
+    /// Weak roots must not be traced: they do not keep their referent
+    /// alive. Instead, once a collection has determined which objects are
+    /// live, collectors must either `set` each weak root to its referent's
+    /// (possibly new) location if the referent survived, or `clear` it if the
+    /// referent was reclaimed.
+    #[inline]
+    pub fn is_weak(&self) -> bool {
+        matches!(self.raw, RawGcRoot::Weak(_))
+    }
+
     /// Get this GC root.
     ///
     /// Does NOT run GC barriers.
@@ -721,6 +781,10 @@ impl GcRoot<'_> {
     pub fn get(&self) -> Result<VMGcRef> {
         match self.raw {
             RawGcRoot::VMGcRef(ptr) => Ok(unsafe { ptr::read(ptr.as_ptr()) }),
+            RawGcRoot::Weak(ptr) => match unsafe { ptr::read(ptr.as_ptr()) } {
+                Some(r) => Ok(r),
+                None => bail_bug!("weak root contained null gcref"),
+            },
             RawGcRoot::Stack(ptr) => unsafe {
                 let raw: u32 = ptr::read(ptr.as_ptr());
                 match VMGcRef::from_raw_u32(raw) {
@@ -757,6 +821,25 @@ impl GcRoot<'_> {
                 let val = ValRaw::vmgcref(Some(new_ref));
                 ptr::write(ptr.as_ptr(), val);
             },
+            RawGcRoot::Weak(ptr) => unsafe {
+                ptr::write(ptr.as_ptr(), Some(new_ref));
+            },
+        }
+    }
+
+    /// Clear this weak GC root because its referent was reclaimed.
+    ///
+    /// Does NOT run GC barriers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a weak root.
+    pub fn clear(&mut self) {
+        match self.raw {
+            RawGcRoot::Weak(ptr) => unsafe {
+                ptr::write(ptr.as_ptr(), None);
+            },
+            _ => panic!("only weak GC roots may be cleared"),
         }
     }
 }
@@ -13,7 +13,7 @@ use crate::runtime::vm::vmcontext::{
 /// proposal all `alias` directives should map to imported items. This means
 /// that each of these items aren't necessarily directly imported, but may be
 /// aliased.
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct Imports<'a> {
     /// Resolved addresses for imported functions.
     pub functions: &'a [VMFunctionImport],
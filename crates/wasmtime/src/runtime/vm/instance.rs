@@ -2,6 +2,7 @@
 //! wasm module (except its callstack and register state). An
 //! `InstanceHandle` is a reference-counting handle for an `Instance`.
 
+use crate::ResourceContext;
 use crate::code::ModuleWithCode;
 use crate::module::ModuleRegistry;
 use crate::prelude::*;
@@ -105,6 +106,11 @@ pub struct Instance {
     // borrowing a store mutably at the same time as a contained instance.
     runtime_info: ModuleRuntimeInfo,
 
+    /// A recompiled version of `runtime_info`'s module whose code is used for
+    /// this instance's functions instead, if this instance has been upgraded
+    /// with `upgrade_code`.
+    upgraded_code: Option<crate::Module>,
+
     /// WebAssembly linear memory data.
     ///
     /// This is where all runtime information about defined linear memories in
@@ -170,30 +176,14 @@ impl Instance {
         memories: &mut TryPrimaryMap<DefinedMemoryIndex, (MemoryAllocationIndex, Memory)>,
         tables: &mut TryPrimaryMap<DefinedTableIndex, (TableAllocationIndex, Table)>,
     ) -> Result<InstanceHandle, OutOfMemory> {
-        let module = req.runtime_info.env_module();
-        let memory_tys = &module.memories;
-        let mut passive_elements = TryVec::with_capacity(module.passive_elements.len())?;
+        let passive_elements = Self::new_passive_elements(req.runtime_info.env_module())?;
 
         #[cfg(feature = "wmemcheck")]
         let wmemcheck_state = if req.store.engine().config().wmemcheck {
-            let size = memory_tys
-                .iter()
-                .next()
-                .map(|memory| memory.1.limits.min)
-                .unwrap_or(0)
-                * 64
-                * 1024;
-            Some(Wmemcheck::new(size.try_into().unwrap()))
+            Some(Self::new_wmemcheck_state(req.runtime_info.env_module()))
         } else {
             None
         };
-        #[cfg(not(feature = "wmemcheck"))]
-        let _ = memory_tys;
-
-        for (_, (ty, len)) in req.runtime_info.env_module().passive_elements.iter() {
-            let len = usize::try_from(*len).unwrap();
-            passive_elements.push(PassiveElementSegment::new(*ty, len)?)?;
-        }
 
         // Allocate the instance and its `VMContext` with empty memory and table
         // maps. This is the final fallible allocation in this function; only
@@ -203,6 +193,7 @@ impl Instance {
         let mut ret = OwnedInstance::new(Instance {
             id: req.id,
             runtime_info: req.runtime_info.clone(),
+            upgraded_code: None,
             memories: TryPrimaryMap::default(),
             tables: TryPrimaryMap::default(),
             passive_elements,
@@ -226,6 +217,73 @@ impl Instance {
         Ok(ret)
     }
 
+    /// Re-initializes this instance, in place, for a fresh instantiation of its
+    /// module with `imports`.
+    ///
+    /// The `memories` and `tables` provided are swapped with this instance's
+    /// own, so on success they contain the instance's previous memories and
+    /// tables which the caller is then responsible for deallocating. On
+    /// failure this instance is left untouched.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as `Instance::new` apply for `imports`,
+    /// `memories`, and `tables`. Additionally no frames of this instance may
+    /// be active on the stack, as its state is entirely reset.
+    pub(crate) unsafe fn reinitialize(
+        mut self: Pin<&mut Self>,
+        imports: Imports,
+        memories: &mut TryPrimaryMap<DefinedMemoryIndex, (MemoryAllocationIndex, Memory)>,
+        tables: &mut TryPrimaryMap<DefinedTableIndex, (TableAllocationIndex, Table)>,
+    ) -> Result<(), OutOfMemory> {
+        // Perform the only fallible allocation first so that a failure leaves
+        // this instance as it was.
+        let passive_elements = Self::new_passive_elements(self.env_module())?;
+        *self.as_mut().passive_elements_mut() = passive_elements;
+
+        mem::swap(self.as_mut().memories_mut(), memories);
+        mem::swap(self.as_mut().tables_mut(), tables);
+
+        #[cfg(feature = "wmemcheck")]
+        if self.wmemcheck_state.is_some() {
+            let state = Self::new_wmemcheck_state(self.env_module());
+            *self.as_mut().wmemcheck_state_mut() = Some(state);
+        }
+
+        // SAFETY: this vmctx was previously initialized for this same store
+        // and module, so everything but the store-related fields is
+        // re-initialized here.
+        unsafe {
+            self.initialize_vmctx_state(imports);
+        }
+
+        Ok(())
+    }
+
+    fn new_passive_elements(
+        module: &wasmtime_environ::Module,
+    ) -> Result<TryVec<PassiveElementSegment>, OutOfMemory> {
+        let mut passive_elements = TryVec::with_capacity(module.passive_elements.len())?;
+        for (_, (ty, len)) in module.passive_elements.iter() {
+            let len = usize::try_from(*len).unwrap();
+            passive_elements.push(PassiveElementSegment::new(*ty, len)?)?;
+        }
+        Ok(passive_elements)
+    }
+
+    #[cfg(feature = "wmemcheck")]
+    fn new_wmemcheck_state(module: &wasmtime_environ::Module) -> Wmemcheck {
+        let size = module
+            .memories
+            .iter()
+            .next()
+            .map(|memory| memory.1.limits.min)
+            .unwrap_or(0)
+            * 64
+            * 1024;
+        Wmemcheck::new(size.try_into().unwrap())
+    }
+
     /// Trace element segment GC roots inside this `Instance`.
     ///
     /// # Safety
@@ -398,10 +456,20 @@ impl Instance {
     }
 
     pub(crate) fn runtime_module(&self) -> Option<&crate::Module> {
-        match &self.runtime_info {
-            ModuleRuntimeInfo::Module(m) => Some(m),
-            ModuleRuntimeInfo::Bare(_) => None,
-        }
+        self.runtime_info.module()
+    }
+
+    /// Returns the module whose code this instance was switched over to with
+    /// `upgrade_code`, if any.
+    #[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+    pub(crate) fn upgraded_code(&self) -> Option<&crate::Module> {
+        self.upgraded_code.as_ref()
+    }
+
+    /// Returns the context reported to resource limiters for this instance's
+    /// memory or table at `index`, where this instance lives in `store`.
+    fn resource_context(&self, store: StoreId, index: u32) -> ResourceContext {
+        ResourceContext::new(store, self.id, self.runtime_module(), index)
     }
 
     /// Translate a module-level interned type index into an engine-level
@@ -787,10 +855,15 @@ impl Instance {
     /// successful.
     pub(crate) async fn memory_grow(
         mut self: Pin<&mut Self>,
-        limiter: Option<&mut StoreResourceLimiter<'_>>,
+        store: StoreId,
+        mut limiter: Option<&mut StoreResourceLimiter<'_>>,
         idx: DefinedMemoryIndex,
         delta: u64,
     ) -> Result<Option<usize>, Error> {
+        if let Some(limiter) = limiter.as_deref_mut() {
+            let index = self.env_module().memory_index(idx).as_u32();
+            limiter.set_context(self.resource_context(store, index));
+        }
         let memory = &mut self.as_mut().memories_mut()[idx].1;
 
         // SAFETY: this is the safe wrapper around `Memory::grow` because it
@@ -818,10 +891,15 @@ impl Instance {
     /// elements with an appropriately typed value.
     pub(crate) async unsafe fn defined_table_grow(
         mut self: Pin<&mut Self>,
+        store: StoreId,
         table_index: DefinedTableIndex,
-        limiter: Option<&mut StoreResourceLimiter<'_>>,
+        mut limiter: Option<&mut StoreResourceLimiter<'_>>,
         amt: u64,
     ) -> Result<Option<usize>> {
+        if let Some(limiter) = limiter.as_deref_mut() {
+            let index = self.env_module().table_index(table_index).as_u32();
+            limiter.set_context(self.resource_context(store, index));
+        }
         let table = self.as_mut().get_defined_table(table_index);
         // SAFETY: updating the `VMContext` table pointers and such is done
         // below, and the responsibility of filling in the new table elements
@@ -960,12 +1038,14 @@ impl Instance {
         // it's better for instantiation performance if we don't have to track
         // "is-initialized" state at all!
 
-        let module_with_code = ModuleWithCode::in_store(
-            registry,
-            self.runtime_module()
+        let module = match &self.upgraded_code {
+            Some(module) => module,
+            None => self
+                .runtime_module()
                 .expect("funcref impossible in fake module"),
-        )
-        .expect("module not in store");
+        };
+        let module_with_code =
+            ModuleWithCode::in_store(registry, module).expect("module not in store");
 
         let array_call =
             VmPtr::from(NonNull::from(module_with_code.function(array_to_wasm_key)).cast());
@@ -1001,6 +1081,58 @@ impl Instance {
         Some(func_ref_ptr)
     }
 
+    /// Switches this instance's defined functions over to the compiled code
+    /// of `module`.
+    ///
+    /// All of this instance's `VMFuncRef`s are rewritten to point at the new
+    /// code, so exports and table entries referring to them switch over too,
+    /// as do `VMFuncRef`s which are lazily initialized later on. Frames of
+    /// this instance which are already on the stack keep running the old
+    /// code, which remains registered in the store.
+    ///
+    /// # Safety
+    ///
+    /// `module` must have been compiled for the same engine from the same
+    /// WebAssembly module as this instance's module, and it must be registered
+    /// in `registry`.
+    #[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+    pub(crate) unsafe fn upgrade_code(
+        mut self: Pin<&mut Self>,
+        registry: &ModuleRegistry,
+        module: &crate::Module,
+    ) {
+        // SAFETY: see `store_mut` below.
+        unsafe { self.as_mut().get_unchecked_mut() }.upgraded_code = Some(module.clone());
+        let env_module = self.env_module().clone();
+        for def_index in env_module.defined_func_indices() {
+            let index = env_module.func_index(def_index);
+            if env_module.functions[index].is_escaping() {
+                self.as_mut()
+                    .initialize_defined_funcref(registry, def_index);
+            }
+        }
+    }
+
+    /// Calls `retarget` with each of this instance's function imports so it
+    /// may point them at different code.
+    ///
+    /// # Safety
+    ///
+    /// Any code which `retarget` points an import at must implement the same
+    /// function as the code it replaces.
+    #[cfg(all(feature = "std", feature = "cranelift", feature = "winch"))]
+    pub(crate) unsafe fn retarget_function_imports(
+        mut self: Pin<&mut Self>,
+        mut retarget: impl FnMut(&mut VMFunctionImport),
+    ) {
+        for i in 0..self.env_module().num_imported_funcs {
+            let offset = self.offsets().vmctx_vmfunction_import(FuncIndex::new(i));
+            // SAFETY: the offset is that of an imported function within our
+            // `VMContext`.
+            retarget(unsafe { self.as_mut().vmctx_plus_offset_mut(offset) });
+        }
+    }
+
     /// Get the passive elements segment at the given index.
     pub(crate) fn passive_element_segment(
         self: Pin<&mut Self>,
@@ -1177,23 +1309,32 @@ impl Instance {
     /// The `VMContext` memory is assumed to be uninitialized; any field
     /// that we need in a certain state will be explicitly written by this
     /// function.
-    unsafe fn initialize_vmctx(self: Pin<&mut Self>, store: &StoreOpaque, imports: Imports) {
-        let (module, mut instance) = self.module_and_self();
-
+    unsafe fn initialize_vmctx(mut self: Pin<&mut Self>, store: &StoreOpaque, imports: Imports) {
         // SAFETY: the type of the magic field is indeed `u32` and this function
         // is initializing its value.
         unsafe {
-            let offsets = instance.runtime_info.offsets();
-            instance
-                .vmctx_plus_offset_raw::<u32>(offsets.ptr.vmctx_magic())
+            let offsets = self.runtime_info.offsets();
+            self.vmctx_plus_offset_raw::<u32>(offsets.ptr.vmctx_magic())
                 .write(VMCONTEXT_MAGIC);
         }
 
         // SAFETY: it's up to the caller to provide a valid store pointer here.
         unsafe {
-            instance.as_mut().set_store(store);
+            self.as_mut().set_store(store);
         }
 
+        // SAFETY: the vmctx is being initialized, the contract of which is
+        // the same as this function's.
+        unsafe {
+            self.initialize_vmctx_state(imports);
+        }
+    }
+
+    /// Initialize everything in the VMContext which isn't tied to the store,
+    /// which is everything that's reset when an instance is reinitialized.
+    unsafe fn initialize_vmctx_state(self: Pin<&mut Self>, imports: Imports) {
+        let (module, mut instance) = self.module_and_self();
+
         // Initialize shared types
         //
         // SAFETY: validity of the vmctx means it should be safe to write to it
@@ -1428,13 +1569,16 @@ impl Instance {
     /// returned and `addr` doesn't belong to this instance.
     pub fn wasm_fault(&self, addr: usize) -> Option<WasmFault> {
         let mut fault = None;
-        for (_, (_, memory)) in self.memories.iter() {
+        for (index, (_, memory)) in self.memories.iter() {
             let accessible = memory.wasm_accessible();
             if accessible.start <= addr && addr < accessible.end {
                 // All linear memories should be disjoint so assert that no
                 // prior fault has been found.
                 assert!(fault.is_none());
                 fault = Some(WasmFault {
+                    instance: self.id,
+                    memory: index,
+                    shared: memory.is_shared_memory(),
                     memory_size: memory.byte_size(),
                     wasm_address: u64::try_from(addr - accessible.start).unwrap(),
                 });
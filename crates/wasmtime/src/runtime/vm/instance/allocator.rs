@@ -1,3 +1,4 @@
+use crate::ResourceContext;
 use crate::prelude::*;
 use crate::runtime::vm::imports::Imports;
 use crate::runtime::vm::instance::{Instance, InstanceHandle};
@@ -30,6 +31,7 @@ mod pooling;
 #[cfg(feature = "pooling-allocator")]
 pub use self::pooling::{
     PoolConcurrencyLimitError, PoolingAllocatorMetrics, PoolingInstanceAllocator,
+    spawn_decommit_thread,
 };
 
 /// Represents a request for a new runtime instance.
@@ -55,6 +57,29 @@ pub struct InstanceAllocationRequest<'a, 'b> {
     pub limiter: Option<&'a mut StoreResourceLimiter<'b>>,
 }
 
+impl InstanceAllocationRequest<'_, '_> {
+    /// Reports the memory or table at `index` of the instance being allocated
+    /// to the resource limiter for the allocations which follow.
+    fn set_limiter_context(&mut self, index: u32) {
+        if let Some(limiter) = self.limiter.as_deref_mut() {
+            limiter.set_context(ResourceContext::new(
+                self.store.id(),
+                self.id,
+                self.runtime_info.module(),
+                index,
+            ));
+        }
+    }
+
+    /// Resets the context of the resource limiter once this instance's
+    /// memories or tables have been allocated.
+    fn clear_limiter_context(&mut self) {
+        if let Some(limiter) = self.limiter.as_deref_mut() {
+            limiter.set_context(ResourceContext::default());
+        }
+    }
+}
+
 /// The index of a memory allocation within an `InstanceAllocator`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub struct MemoryAllocationIndex(u32);
@@ -398,6 +423,29 @@ impl dyn InstanceAllocator + '_ {
         self.decrement_core_instance_count();
     }
 
+    /// Allocates a fresh set of memories and tables for the instance described
+    /// by `request`, which is used to reinitialize an existing instance of the
+    /// same module with `InstanceResources::reinitialize`.
+    ///
+    /// Note that unlike `allocate_module` this does not count as a new
+    /// instance within this allocator.
+    pub(crate) async fn allocate_instance_resources(
+        &self,
+        mut request: InstanceAllocationRequest<'_, '_>,
+    ) -> Result<InstanceResources<'_>> {
+        let module = request.runtime_info.env_module();
+        let mut resources = InstanceResources {
+            memories: TryPrimaryMap::with_capacity(module.num_defined_memories())?,
+            tables: TryPrimaryMap::with_capacity(module.num_defined_tables())?,
+            allocator: self,
+        };
+        self.allocate_memories(&mut request, &mut resources.memories)
+            .await?;
+        self.allocate_tables(&mut request, &mut resources.tables)
+            .await?;
+        Ok(resources)
+    }
+
     /// Allocate the memories for the given instance allocation request, pushing
     /// them into `memories`.
     async fn allocate_memories(
@@ -413,6 +461,7 @@ impl dyn InstanceAllocator + '_ {
         }
 
         for (memory_index, ty) in module.memories.iter().skip(module.num_imported_memories) {
+            request.set_limiter_context(memory_index.as_u32());
             let memory_index = module
                 .defined_memory_index(memory_index)
                 .expect("should be a defined memory since we skipped imported ones");
@@ -422,6 +471,7 @@ impl dyn InstanceAllocator + '_ {
                 .await?;
             memories.push(memory)?;
         }
+        request.clear_limiter_context();
 
         Ok(())
     }
@@ -466,6 +516,7 @@ impl dyn InstanceAllocator + '_ {
         }
 
         for (index, table) in module.tables.iter().skip(module.num_imported_tables) {
+            request.set_limiter_context(index.as_u32());
             let def_index = module
                 .defined_table_index(index)
                 .expect("should be a defined table since we skipped imported ones");
@@ -473,6 +524,7 @@ impl dyn InstanceAllocator + '_ {
             let table = self.allocate_table(request, table, def_index).await?;
             tables.push(table)?;
         }
+        request.clear_limiter_context();
 
         Ok(())
     }
@@ -497,6 +549,50 @@ impl dyn InstanceAllocator + '_ {
     }
 }
 
+/// Memories and tables allocated by
+/// `InstanceAllocator::allocate_instance_resources` which are deallocated when
+/// this is dropped.
+pub(crate) struct InstanceResources<'a> {
+    memories: TryPrimaryMap<DefinedMemoryIndex, (MemoryAllocationIndex, Memory)>,
+    tables: TryPrimaryMap<DefinedTableIndex, (TableAllocationIndex, Table)>,
+    allocator: &'a (dyn InstanceAllocator + 'a),
+}
+
+impl InstanceResources<'_> {
+    /// Reinitializes `instance` with these memories and tables, deallocating
+    /// the instance's previous memories and tables.
+    ///
+    /// On failure `instance` is left untouched and these resources are
+    /// deallocated instead.
+    ///
+    /// # Safety
+    ///
+    /// Same as `Instance::reinitialize`, and additionally `instance` must have
+    /// been allocated by the same allocator as these resources.
+    pub(crate) unsafe fn reinitialize(
+        mut self,
+        instance: Pin<&mut Instance>,
+        imports: Imports<'_>,
+    ) -> Result<(), OutOfMemory> {
+        // SAFETY: the contract of `reinitialize` is forwarded to our caller.
+        // Afterwards `self` contains the instance's previous memories and
+        // tables which are deallocated when `self` is dropped.
+        unsafe { instance.reinitialize(imports, &mut self.memories, &mut self.tables) }
+    }
+}
+
+impl Drop for InstanceResources<'_> {
+    fn drop(&mut self) {
+        // SAFETY: these were previously allocated by this allocator, either
+        // in `allocate_instance_resources` or for the instance that was
+        // reinitialized.
+        unsafe {
+            self.allocator.deallocate_memories(&mut self.memories);
+            self.allocator.deallocate_tables(&mut self.tables);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,28 +42,31 @@ cfg_select! {
     }
 }
 
-use self::decommit_queue::DecommitQueue;
+pub use self::decommit_queue::spawn_decommit_thread;
+use self::decommit_queue::{DecommitQueue, DecommitSignal, DecommitStats};
 use self::memory_pool::MemoryPool;
 pub use self::metrics::PoolingAllocatorMetrics;
 use self::table_pool::TablePool;
 use super::{
     InstanceAllocationRequest, InstanceAllocator, MemoryAllocationIndex, TableAllocationIndex,
 };
-use crate::Enabled;
 use crate::config::PoolingAllocationConfig;
 use crate::prelude::*;
 use crate::runtime::vm::{
-    CompiledModuleId, Memory, Table,
+    CompiledModuleId, HostAlignedByteCount, Memory, MemoryImageSlot, Table,
+    crash_dump::{self, PoolResource},
+    host_page_size,
     instance::Instance,
     mpk::{self, ProtectionKey, ProtectionMask},
     sys::vm::PageMap,
 };
+use crate::{DecommitPolicy, Enabled};
 use core::future::Future;
 use core::pin::Pin;
 use core::sync::atomic::AtomicUsize;
 use std::borrow::Cow;
 use std::fmt::Display;
-use std::sync::{Mutex, MutexGuard};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::{
     mem,
     sync::atomic::{AtomicU64, Ordering},
@@ -209,6 +212,10 @@ pub struct PoolingInstanceAllocator {
     /// reaches the configured batch size. Slot-exhaustion paths flush all
     /// shards.
     decommit_queues: Box<[CachePadded<Mutex<DecommitQueue>>]>,
+    decommit_stats: DecommitStats,
+    /// Wakes up the background decommit thread, if background decommit is
+    /// enabled.
+    decommit_signal: Option<Arc<DecommitSignal>>,
 
     memories: MemoryPool,
     live_memories: AtomicUsize,
@@ -232,6 +239,8 @@ pub struct PoolingInstanceAllocator {
 
 impl Drop for PoolingInstanceAllocator {
     fn drop(&mut self) {
+        crash_dump::pool_destroyed(&self.config.limits);
+
         if !cfg!(debug_assertions) {
             return;
         }
@@ -270,12 +279,18 @@ impl Drop for PoolingInstanceAllocator {
 impl PoolingInstanceAllocator {
     /// Creates a new pooling instance allocator with the given strategy and limits.
     pub fn new(config: &PoolingAllocationConfig, tunables: &Tunables) -> Result<Self> {
-        Ok(Self {
+        let ret = Self {
             live_component_instances: AtomicU64::new(0),
             live_core_instances: AtomicU64::new(0),
             decommit_queues: (0..default_shard_count())
                 .map(|_| CachePadded(Mutex::new(DecommitQueue::default())))
                 .try_collect::<Box<[_]>, OutOfMemory>()?,
+            decommit_stats: DecommitStats::default(),
+            decommit_signal: if config.background_decommit {
+                Some(Arc::new(DecommitSignal::default()))
+            } else {
+                None
+            },
             memories: MemoryPool::new(config, tunables)?,
             live_memories: AtomicUsize::new(0),
             tables: TablePool::new(config)?,
@@ -303,7 +318,9 @@ impl PoolingInstanceAllocator {
                 Enabled::No => None,
             },
             config: config.clone(),
-        })
+        };
+        crash_dump::pool_created(&ret.config.limits);
+        Ok(ret)
     }
 
     fn core_instance_size(&self) -> usize {
@@ -417,12 +434,35 @@ impl PoolingInstanceAllocator {
         // contention.
         let queue = mem::take(&mut *locked_queue);
         drop(locked_queue);
+        self.decommit_stats.dequeued(queue.raw_bytes());
         queue.flush(self)
     }
 
+    /// Whether `queue` has reached one of the configured thresholds at which
+    /// it should be flushed.
+    fn should_flush(&self, queue: &DecommitQueue) -> bool {
+        queue.raw_len() >= self.config.decommit_batch_size
+            || queue.raw_bytes() >= self.config.decommit_batch_bytes
+            || self
+                .config
+                .decommit_max_delay
+                .is_some_and(|delay| queue.expired(delay))
+    }
+
+    /// Flush every shard of the decommit queue whose oldest region has been
+    /// queued for at least `delay`.
+    pub(crate) fn flush_expired_decommit_queues(&self, delay: core::time::Duration) {
+        for shard in self.decommit_shard_ids() {
+            let queue = self.decommit_queue(shard).lock().unwrap();
+            if queue.expired(delay) {
+                self.flush_decommit_queue(queue);
+            }
+        }
+    }
+
     /// Flush every shard of the decommit queue, e.g. on allocator drop.
     /// Returns whether any slot was returned to any pool.
-    fn flush_all_decommit_queues(&self) -> bool {
+    pub(crate) fn flush_all_decommit_queues(&self) -> bool {
         let mut any = false;
         for shard in self.decommit_shard_ids() {
             let queue = self.decommit_queue(shard).lock().unwrap();
@@ -462,6 +502,18 @@ impl PoolingInstanceAllocator {
     }
 
     fn merge_or_flush(&self, mut local_queue: DecommitQueue) {
+        // With background decommit everything is handed off to the
+        // background thread to reset and flush.
+        if let Some(signal) = &self.decommit_signal {
+            let shard = thread_shard(self.decommit_queues.len());
+            let mut shared_queue = self.decommit_queue(shard).lock().unwrap();
+            self.decommit_stats.enqueued(local_queue.raw_bytes());
+            shared_queue.append(&mut local_queue);
+            drop(shared_queue);
+            signal.notify();
+            return;
+        }
+
         match local_queue.raw_len() {
             // If we didn't enqueue any regions for decommit, then we must have
             // either memset the whole entity or eagerly remapped it to zero
@@ -471,24 +523,24 @@ impl PoolingInstanceAllocator {
                 local_queue.flush(self);
             }
 
-            // We enqueued at least our batch size of regions for decommit, so
-            // flush the local queue immediately. Don't bother inspecting (or
-            // locking!) the shared queue.
-            n if n >= self.config.decommit_batch_size => {
+            // We enqueued at least our batch size of regions (or bytes) for
+            // decommit, so flush the local queue immediately. Don't bother
+            // inspecting (or locking!) the shared queue.
+            _ if self.should_flush(&local_queue) => {
                 local_queue.flush(self);
             }
 
             // If we enqueued some regions for decommit, but did not reach our
             // batch size, so we don't want to flush it yet, then merge the
             // local queue into this thread's shard of the shared queue.
-            n => {
-                debug_assert!(n < self.config.decommit_batch_size);
+            _ => {
                 let shard = thread_shard(self.decommit_queues.len());
                 let mut shared_queue = self.decommit_queue(shard).lock().unwrap();
+                self.decommit_stats.enqueued(local_queue.raw_bytes());
                 shared_queue.append(&mut local_queue);
-                // And if this shard now has at least as many regions enqueued
-                // for decommit as our batch size, then we can flush it.
-                if shared_queue.raw_len() >= self.config.decommit_batch_size {
+                // And if this shard has now reached our batch size, or has
+                // been waiting for too long, then we can flush it.
+                if self.should_flush(&shared_queue) {
                     self.flush_decommit_queue(shared_queue);
                 }
             }
@@ -498,6 +550,150 @@ impl PoolingInstanceAllocator {
     pub fn config(&self) -> &PoolingAllocationConfig {
         &self.config
     }
+
+    /// Returns the signal used to wake up the background decommit thread, if
+    /// background decommit is enabled.
+    pub(crate) fn decommit_signal(&self) -> Option<Arc<DecommitSignal>> {
+        self.decommit_signal.clone()
+    }
+
+    /// Whether regions of memory should be zeroed and lazily freed rather
+    /// than decommitted, see `DecommitPolicy::LazyFree`.
+    fn lazy_free(&self) -> bool {
+        cfg!(target_os = "linux") && self.config.decommit_policy == DecommitPolicy::LazyFree
+    }
+
+    /// Resets the memory in `image` back to its original state, enqueueing
+    /// any regions to decommit and the memory itself into `queue`.
+    ///
+    /// If the memory can't be reset then its slot is deallocated immediately
+    /// without preserving its image.
+    ///
+    /// # Safety
+    ///
+    /// The memory must no longer be in use and `allocation_index` must come
+    /// from this pool.
+    unsafe fn reset_memory(
+        &self,
+        queue: &mut DecommitQueue,
+        allocation_index: MemoryAllocationIndex,
+        mut image: MemoryImageSlot,
+    ) {
+        // Memories backed by an image must be decommitted to restore the
+        // image, so they can't be lazily freed.
+        let lazy = self.lazy_free() && !image.has_image();
+        let bytes_resident = image.clear_and_remain_ready(
+            self.pagemap.as_ref(),
+            self.memories.keep_resident,
+            |ptr, len| {
+                // SAFETY: the memory in `image` won't be used until this
+                // decommit queue is flushed, and by definition the memory is
+                // not in use when calling this function.
+                unsafe {
+                    queue.push_region(ptr, len, lazy);
+                }
+            },
+        );
+
+        match bytes_resident {
+            Ok(bytes_resident) => {
+                // SAFETY: this image is not in use and its memory regions were enqueued
+                // with `push_region` above.
+                unsafe {
+                    queue.push_memory(allocation_index, image, bytes_resident);
+                }
+            }
+            Err(e) => {
+                log::warn!("ignoring clear_and_remain_ready error {e}");
+                // SAFETY: `allocation_index` comes from this pool, as an unsafe
+                // contract of this function itself, and it's guaranteed to be no
+                // longer in use so safe to deallocate. The slot couldn't be
+                // preserved so it's dropped here.
+                //
+                // Note that at this point it's not clear how many bytes are
+                // resident in memory, so it's inevitably going to leave statistics
+                // a little off. Also note though that non-Linux platforms don't
+                // keep track of resident bytes anyway, and this path is only
+                // reachable on non-Linux platforms because Linux can't return an
+                // error.
+                unsafe {
+                    self.memories.deallocate(allocation_index, None, 0);
+                }
+            }
+        }
+    }
+
+    /// Resets `table` back to zero, enqueueing any regions to decommit and
+    /// the table itself into `queue`.
+    ///
+    /// # Safety
+    ///
+    /// The table must no longer be in use and `allocation_index` must come
+    /// from this pool.
+    unsafe fn reset_table(
+        &self,
+        queue: &mut DecommitQueue,
+        allocation_index: TableAllocationIndex,
+        mut table: Table,
+    ) {
+        let lazy = self.lazy_free();
+        // SAFETY: This table is no longer in use by the allocator when this
+        // method is called and additionally all image ranges are pushed with
+        // the understanding that the memory won't get used until the whole
+        // queue is flushed.
+        let bytes_resident = unsafe {
+            self.tables.reset_table_pages_to_zero(
+                self.pagemap.as_ref(),
+                allocation_index,
+                &mut table,
+                |ptr, len| {
+                    queue.push_region(ptr, len, lazy);
+                },
+            )
+        };
+
+        // SAFETY: the table has had all its memory regions enqueued above.
+        unsafe {
+            queue.push_table(allocation_index, table, bytes_resident);
+        }
+    }
+
+    /// Resets `stack` back to zero, if configured to do so, enqueueing any
+    /// regions to decommit and the stack itself into `queue`.
+    ///
+    /// # Safety
+    ///
+    /// The stack must no longer be in use and must come from this pool.
+    #[cfg(feature = "async")]
+    unsafe fn reset_stack(&self, queue: &mut DecommitQueue, mut stack: wasmtime_fiber::FiberStack) {
+        let lazy = self.lazy_free();
+        // SAFETY: the stack is no longer in use by definition when this
+        // function is called and memory ranges pushed here are otherwise no
+        // longer in use.
+        let bytes_resident = unsafe {
+            self.stacks
+                .zero_stack(&mut stack, |ptr, len| queue.push_region(ptr, len, lazy))
+        };
+        // SAFETY: this stack's memory regions were enqueued above.
+        unsafe {
+            queue.push_stack(stack, bytes_resident);
+        }
+    }
+}
+
+/// Returns how much memory to keep resident in each slot of a pool, given the
+/// `keep_resident` option configured for it.
+fn keep_resident(
+    config: &PoolingAllocationConfig,
+    keep_resident: usize,
+) -> Result<HostAlignedByteCount> {
+    let bytes = match config.decommit_policy {
+        // Everything is kept resident, which is limited to the slot's size
+        // where this is used.
+        DecommitPolicy::Zero => usize::MAX - (host_page_size() - 1),
+        DecommitPolicy::Release | DecommitPolicy::LazyFree => keep_resident,
+    };
+    Ok(HostAlignedByteCount::new_rounded_up(bytes)?)
 }
 
 unsafe impl InstanceAllocator for PoolingInstanceAllocator {
@@ -591,6 +787,7 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
     #[cfg(feature = "component-model")]
     fn increment_component_instance_count(&self) -> Result<()> {
         let old_count = self.live_component_instances.fetch_add(1, Ordering::AcqRel);
+        crash_dump::pool_allocated(PoolResource::ComponentInstance);
         if old_count >= u64::from(self.config.limits.total_component_instances) {
             self.decrement_component_instance_count();
             return Err(PoolConcurrencyLimitError::new(
@@ -605,10 +802,12 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
     #[cfg(feature = "component-model")]
     fn decrement_component_instance_count(&self) {
         self.live_component_instances.fetch_sub(1, Ordering::AcqRel);
+        crash_dump::pool_deallocated(PoolResource::ComponentInstance);
     }
 
     fn increment_core_instance_count(&self) -> Result<()> {
         let old_count = self.live_core_instances.fetch_add(1, Ordering::AcqRel);
+        crash_dump::pool_allocated(PoolResource::CoreInstance);
         if old_count >= u64::from(self.config.limits.total_core_instances) {
             self.decrement_core_instance_count();
             return Err(PoolConcurrencyLimitError::new(
@@ -622,6 +821,7 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
 
     fn decrement_core_instance_count(&self) {
         self.live_core_instances.fetch_sub(1, Ordering::AcqRel);
+        crash_dump::pool_deallocated(PoolResource::CoreInstance);
     }
 
     fn allocate_memory<'a, 'b: 'a, 'c: 'a>(
@@ -660,6 +860,7 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
             .await
             .inspect(|_| {
                 self.live_memories.fetch_add(1, Ordering::Relaxed);
+                crash_dump::pool_allocated(PoolResource::Memory);
             })
         })
     }
@@ -672,53 +873,20 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
     ) {
         let prev = self.live_memories.fetch_sub(1, Ordering::Relaxed);
         debug_assert!(prev > 0);
+        crash_dump::pool_deallocated(PoolResource::Memory);
 
-        // Reset the image slot. Depending on whether this is successful or not
-        // the `image` is preserved for future use. On success it's queued up to
-        // get deallocated later, and on failure the slot is deallocated
-        // immediately without preserving the image.
-        let mut image = memory.unwrap_static_image();
+        let image = memory.unwrap_static_image();
         let mut queue = DecommitQueue::default();
-        let bytes_resident = image.clear_and_remain_ready(
-            self.pagemap.as_ref(),
-            self.memories.keep_resident,
-            |ptr, len| {
-                // SAFETY: the memory in `image` won't be used until this
-                // decommit queue is flushed, and by definition the memory is
-                // not in use when calling this function.
-                unsafe {
-                    queue.push_raw(ptr, len);
-                }
-            },
-        );
-
-        match bytes_resident {
-            Ok(bytes_resident) => {
-                // SAFETY: this image is not in use and its memory regions were enqueued
-                // with `push_raw` above.
-                unsafe {
-                    queue.push_memory(allocation_index, image, bytes_resident);
-                }
-                self.merge_or_flush(queue);
-            }
-            Err(e) => {
-                log::warn!("ignoring clear_and_remain_ready error {e}");
-                // SAFETY: `allocation_index` comes from this pool, as an unsafe
-                // contract of this function itself, and it's guaranteed to be no
-                // longer in use so safe to deallocate. The slot couldn't be
-                // preserved so it's dropped here.
-                //
-                // Note that at this point it's not clear how many bytes are
-                // resident in memory, so it's inevitably going to leave statistics
-                // a little off. Also note though that non-Linux platforms don't
-                // keep track of resident bytes anyway, and this path is only
-                // reachable on non-Linux platforms because Linux can't return an
-                // error.
-                unsafe {
-                    self.memories.deallocate(allocation_index, None, 0);
-                }
-            }
+        if self.decommit_signal.is_some() {
+            // SAFETY: the memory is no longer in use.
+            unsafe { queue.push_dirty_memory(allocation_index, image) };
+        } else {
+            // SAFETY: the memory is no longer in use and `allocation_index`
+            // comes from this pool, as an unsafe contract of this function
+            // itself.
+            unsafe { self.reset_memory(&mut queue, allocation_index, image) };
         }
+        self.merge_or_flush(queue);
     }
 
     fn allocate_table<'a, 'b: 'a, 'c: 'a>(
@@ -755,6 +923,7 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
             .await
             .inspect(|_| {
                 self.live_tables.fetch_add(1, Ordering::Relaxed);
+                crash_dump::pool_allocated(PoolResource::Table);
             })
         })
     }
@@ -763,30 +932,19 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
         &self,
         _table_index: DefinedTableIndex,
         allocation_index: TableAllocationIndex,
-        mut table: Table,
+        table: Table,
     ) {
         let prev = self.live_tables.fetch_sub(1, Ordering::Relaxed);
         debug_assert!(prev > 0);
+        crash_dump::pool_deallocated(PoolResource::Table);
 
         let mut queue = DecommitQueue::default();
-        // SAFETY: This table is no longer in use by the allocator when this
-        // method is called and additionally all image ranges are pushed with
-        // the understanding that the memory won't get used until the whole
-        // queue is flushed.
-        let bytes_resident = unsafe {
-            self.tables.reset_table_pages_to_zero(
-                self.pagemap.as_ref(),
-                allocation_index,
-                &mut table,
-                |ptr, len| {
-                    queue.push_raw(ptr, len);
-                },
-            )
-        };
-
-        // SAFETY: the table has had all its memory regions enqueued above.
-        unsafe {
-            queue.push_table(allocation_index, table, bytes_resident);
+        if self.decommit_signal.is_some() {
+            // SAFETY: the table is no longer in use.
+            unsafe { queue.push_dirty_table(allocation_index, table) };
+        } else {
+            // SAFETY: the table is no longer in use and comes from this pool.
+            unsafe { self.reset_table(&mut queue, allocation_index, table) };
         }
         self.merge_or_flush(queue);
     }
@@ -795,23 +953,21 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
     fn allocate_fiber_stack(&self) -> Result<wasmtime_fiber::FiberStack> {
         let ret = self.with_flush_and_retry(|| self.stacks.allocate())?;
         self.live_stacks.fetch_add(1, Ordering::Relaxed);
+        crash_dump::pool_allocated(PoolResource::Stack);
         Ok(ret)
     }
 
     #[cfg(feature = "async")]
-    unsafe fn deallocate_fiber_stack(&self, mut stack: wasmtime_fiber::FiberStack) {
+    unsafe fn deallocate_fiber_stack(&self, stack: wasmtime_fiber::FiberStack) {
         self.live_stacks.fetch_sub(1, Ordering::Relaxed);
+        crash_dump::pool_deallocated(PoolResource::Stack);
         let mut queue = DecommitQueue::default();
-        // SAFETY: the stack is no longer in use by definition when this
-        // function is called and memory ranges pushed here are otherwise no
-        // longer in use.
-        let bytes_resident = unsafe {
-            self.stacks
-                .zero_stack(&mut stack, |ptr, len| queue.push_raw(ptr, len))
-        };
-        // SAFETY: this stack's memory regions were enqueued above.
-        unsafe {
-            queue.push_stack(stack, bytes_resident);
+        if self.decommit_signal.is_some() {
+            // SAFETY: the stack is no longer in use.
+            unsafe { queue.push_dirty_stack(stack) };
+        } else {
+            // SAFETY: the stack is no longer in use and comes from this pool.
+            unsafe { self.reset_stack(&mut queue, stack) };
         }
         self.merge_or_flush(queue);
     }
@@ -845,6 +1001,7 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
                 .unwrap()
                 .allocate(engine, gc_runtime, memory_alloc_index)?;
         self.live_gc_heaps.fetch_add(1, Ordering::Relaxed);
+        crash_dump::pool_allocated(PoolResource::GcHeap);
         Ok(ret)
     }
 
@@ -856,6 +1013,7 @@ unsafe impl InstanceAllocator for PoolingInstanceAllocator {
     ) -> MemoryAllocationIndex {
         let gc_heaps = self.gc_heaps.as_ref().unwrap();
         self.live_gc_heaps.fetch_sub(1, Ordering::Relaxed);
+        crash_dump::pool_deallocated(PoolResource::GcHeap);
         gc_heaps.deallocate(allocation_index, gc_heap)
     }
 
@@ -7,12 +7,27 @@
 //! Even when batching is "disabled" we still use this queue. Batching is
 //! disabled by specifying a batch size of one, in which case, this queue will
 //! immediately get flushed every time we push onto it.
+//!
+//! Batches are flushed once they reach the configured number of regions or
+//! bytes, or once their oldest region has been queued for longer than the
+//! configured maximum delay. The latter is enforced both when deallocating and
+//! by a background thread, see [`spawn_decommit_thread`].
+//!
+//! With background decommit enabled, entities are enqueued here while still
+//! dirty and are only reset back to zero (or their original image) when the
+//! queue is flushed, which is normally done by the background thread.
 
 use super::PoolingInstanceAllocator;
-use crate::vm::sys::vm::{decommit_pages, iovec};
+use crate::Engine;
+use crate::vm::sys::vm::{decommit_pages, iovec, lazy_free_pages};
 use crate::vm::{MemoryAllocationIndex, MemoryImageSlot, Table, TableAllocationIndex};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use core::time::Duration;
 use smallvec::SmallVec;
 use std::io;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Instant;
 
 #[cfg(feature = "async")]
 use wasmtime_fiber::FiberStack;
@@ -42,10 +57,22 @@ unsafe impl Sync for SendSyncStack {}
 #[derive(Default)]
 pub struct DecommitQueue {
     raw: SmallVec<[IoVec; 2]>,
+    /// Already-zeroed regions to mark as lazily freeable rather than
+    /// decommit, see `DecommitPolicy::LazyFree`.
+    lazy: SmallVec<[IoVec; 2]>,
+    /// The total size, in bytes, of the regions in `raw` and `lazy`.
+    raw_bytes: usize,
+    /// When the oldest region in `raw` or `lazy` was enqueued.
+    oldest: Option<Instant>,
     memories: SmallVec<[(MemoryAllocationIndex, MemoryImageSlot, usize); 1]>,
     tables: SmallVec<[(TableAllocationIndex, Table, usize); 1]>,
     #[cfg(feature = "async")]
     stacks: SmallVec<[(SendSyncStack, usize); 1]>,
+    /// Entities which have yet to be reset, with background decommit.
+    dirty_memories: SmallVec<[(MemoryAllocationIndex, MemoryImageSlot); 1]>,
+    dirty_tables: SmallVec<[(TableAllocationIndex, Table); 1]>,
+    #[cfg(feature = "async")]
+    dirty_stacks: SmallVec<[SendSyncStack; 1]>,
     //
     // TODO: GC heaps are not well-integrated with the pooling allocator
     // yet. Once we better integrate them, we should start (optionally) zeroing
@@ -59,6 +86,9 @@ impl std::fmt::Debug for DecommitQueue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DecommitQueue")
             .field("raw", &self.raw)
+            .field("lazy", &self.lazy)
+            .field("raw_bytes", &self.raw_bytes)
+            .field("oldest", &self.oldest)
             .finish_non_exhaustive()
     }
 }
@@ -69,22 +99,50 @@ impl DecommitQueue {
         &mut self,
         Self {
             raw,
+            lazy,
+            raw_bytes,
+            oldest,
             memories,
             tables,
             #[cfg(feature = "async")]
             stacks,
+            dirty_memories,
+            dirty_tables,
+            #[cfg(feature = "async")]
+            dirty_stacks,
         }: &mut Self,
     ) {
         self.raw.append(raw);
+        self.lazy.append(lazy);
+        self.raw_bytes += mem::take(raw_bytes);
+        self.oldest = match (self.oldest, oldest.take()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
         self.memories.append(memories);
         self.tables.append(tables);
         #[cfg(feature = "async")]
         self.stacks.append(stacks);
+        self.dirty_memories.append(dirty_memories);
+        self.dirty_tables.append(dirty_tables);
+        #[cfg(feature = "async")]
+        self.dirty_stacks.append(dirty_stacks);
     }
 
     /// How many raw memory regions are enqueued for decommit?
     pub fn raw_len(&self) -> usize {
-        self.raw.len()
+        self.raw.len() + self.lazy.len()
+    }
+
+    /// How many bytes of memory are enqueued for decommit?
+    pub fn raw_bytes(&self) -> usize {
+        self.raw_bytes
+    }
+
+    /// Whether the oldest region enqueued for decommit has been waiting for
+    /// at least `delay`.
+    pub fn expired(&self, delay: Duration) -> bool {
+        self.oldest.is_some_and(|oldest| oldest.elapsed() >= delay)
     }
 
     /// Enqueue a region of memory for decommit.
@@ -102,6 +160,71 @@ impl DecommitQueue {
             iov_base: ptr.cast(),
             iov_len: len,
         }));
+        self.raw_bytes += len;
+        self.oldest.get_or_insert_with(Instant::now);
+    }
+
+    /// Enqueue a region of memory to be reset, either by decommitting it or,
+    /// if `lazy` is true, by zeroing it now and marking it as lazily freeable
+    /// when flushed.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`DecommitQueue::push_raw`], and additionally when `lazy` is
+    /// true the region must be safe to write zeros to now.
+    pub unsafe fn push_region(&mut self, ptr: *mut u8, len: usize, lazy: bool) {
+        if !lazy {
+            // SAFETY: forwarded to this function's own contract.
+            unsafe { self.push_raw(ptr, len) };
+            return;
+        }
+        // SAFETY: this function's contract requires that it's safe to zero
+        // this region.
+        unsafe {
+            std::ptr::write_bytes(ptr, 0, len);
+        }
+        self.lazy.push(IoVec(iovec {
+            iov_base: ptr.cast(),
+            iov_len: len,
+        }));
+        self.raw_bytes += len;
+        self.oldest.get_or_insert_with(Instant::now);
+    }
+
+    /// Push a memory which has yet to be reset into the queue.
+    ///
+    /// # Safety
+    ///
+    /// This memory should not be in use.
+    pub unsafe fn push_dirty_memory(
+        &mut self,
+        allocation_index: MemoryAllocationIndex,
+        image: MemoryImageSlot,
+    ) {
+        self.dirty_memories.push((allocation_index, image));
+    }
+
+    /// Push a table which has yet to be reset into the queue.
+    ///
+    /// # Safety
+    ///
+    /// This table should not be in use.
+    pub unsafe fn push_dirty_table(
+        &mut self,
+        allocation_index: TableAllocationIndex,
+        table: Table,
+    ) {
+        self.dirty_tables.push((allocation_index, table));
+    }
+
+    /// Push a stack which has yet to be reset into the queue.
+    ///
+    /// # Safety
+    ///
+    /// This stack should not be in use.
+    #[cfg(feature = "async")]
+    pub unsafe fn push_dirty_stack(&mut self, stack: FiberStack) {
+        self.dirty_stacks.push(SendSyncStack(stack));
     }
 
     /// Push a memory into the queue.
@@ -159,6 +282,18 @@ impl DecommitQueue {
             decommit_pages(iov)?;
         }
         self.raw.clear();
+
+        let lazy: &[IoVec] = self.lazy.as_slice();
+        // SAFETY: same as above.
+        let lazy = unsafe { &*(lazy as *const [IoVec] as *const [iovec]) };
+        // SAFETY: same as above, and `push_region` zeroed these regions
+        // before enqueueing them.
+        unsafe {
+            lazy_free_pages(lazy)?;
+        }
+        self.lazy.clear();
+        self.raw_bytes = 0;
+        self.oldest = None;
         Ok(())
     }
 
@@ -167,8 +302,31 @@ impl DecommitQueue {
     /// Returns `true` if we did any decommits and returned their entities to
     /// the associated free lists; `false` if the queue was empty.
     pub fn flush(mut self, pool: &PoolingInstanceAllocator) -> bool {
-        // First, do the raw decommit syscall(s).
-        let decommit_succeeded = self.decommit_all_raw().is_ok();
+        // First, reset any entities which were enqueued while still dirty,
+        // which enqueues their regions for decommit into this queue.
+        for (allocation_index, image) in mem::take(&mut self.dirty_memories) {
+            // SAFETY: the memory was not in use when it was enqueued.
+            unsafe { pool.reset_memory(&mut self, allocation_index, image) };
+        }
+        for (allocation_index, table) in mem::take(&mut self.dirty_tables) {
+            // SAFETY: the table was not in use when it was enqueued.
+            unsafe { pool.reset_table(&mut self, allocation_index, table) };
+        }
+        #[cfg(feature = "async")]
+        for stack in mem::take(&mut self.dirty_stacks) {
+            // SAFETY: the stack was not in use when it was enqueued.
+            unsafe { pool.reset_stack(&mut self, stack.0) };
+        }
+
+        // Next, do the raw decommit syscall(s).
+        let decommit_succeeded = if self.raw_len() == 0 {
+            true
+        } else {
+            let start = Instant::now();
+            let result = self.decommit_all_raw();
+            pool.decommit_stats.record_flush(start.elapsed());
+            result.is_ok()
+        };
 
         // Second, restore the various entities to their associated pools' free
         // lists. This is safe, and they are ready for reuse, now that their
@@ -232,3 +390,131 @@ impl DecommitQueue {
         deallocated_any
     }
 }
+
+/// Statistics about decommits, reported through
+/// [`PoolingAllocatorMetrics`](super::PoolingAllocatorMetrics).
+#[derive(Debug, Default)]
+pub struct DecommitStats {
+    flushes: AtomicU64,
+    total_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+    queued_bytes: AtomicUsize,
+    max_queued_bytes: AtomicUsize,
+}
+
+impl DecommitStats {
+    /// Records a batch of decommits which took `elapsed`.
+    fn record_flush(&self, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.flushes.fetch_add(1, Ordering::Relaxed);
+        self.total_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// Records that `bytes` were added to a shared decommit queue.
+    pub fn enqueued(&self, bytes: usize) {
+        let queued = self.queued_bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.max_queued_bytes.fetch_max(queued, Ordering::Relaxed);
+    }
+
+    /// Records that `bytes` were taken out of a shared decommit queue.
+    pub fn dequeued(&self, bytes: usize) {
+        self.queued_bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    pub fn flushes(&self) -> u64 {
+        self.flushes.load(Ordering::Relaxed)
+    }
+
+    pub fn total_time(&self) -> Duration {
+        Duration::from_nanos(self.total_nanos.load(Ordering::Relaxed))
+    }
+
+    pub fn max_time(&self) -> Duration {
+        Duration::from_nanos(self.max_nanos.load(Ordering::Relaxed))
+    }
+
+    pub fn queued_bytes(&self) -> usize {
+        self.queued_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn max_queued_bytes(&self) -> usize {
+        self.max_queued_bytes.load(Ordering::Relaxed)
+    }
+}
+
+/// Wakes up the background decommit thread when there's work for it, with
+/// background decommit enabled.
+#[derive(Debug, Default)]
+pub struct DecommitSignal {
+    pending: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl DecommitSignal {
+    /// Signals that there are queued entities for the thread to reset.
+    pub fn notify(&self) {
+        *self.pending.lock().unwrap() = true;
+        self.cond.notify_one();
+    }
+
+    /// Waits for up to `timeout` to be signaled, returning whether it was.
+    fn wait(&self, timeout: Duration) -> bool {
+        let pending = self.pending.lock().unwrap();
+        let (mut pending, _) = self
+            .cond
+            .wait_timeout_while(pending, timeout, |pending| !*pending)
+            .unwrap();
+        mem::take(&mut *pending)
+    }
+}
+
+/// Spawns a thread which flushes the decommit queues of `engine`'s pooling
+/// allocator.
+///
+/// Queues are flushed once their oldest region has been queued for longer than
+/// `delay`, if provided, and whenever `signal` is notified, if provided.
+///
+/// The thread only holds a weak reference to the engine and exits once the
+/// engine has been dropped.
+pub fn spawn_decommit_thread(
+    engine: &Engine,
+    delay: Option<Duration>,
+    signal: Option<Arc<DecommitSignal>>,
+) {
+    let engine = engine.weak();
+    // Even when only woken up by `signal` the thread needs to periodically
+    // check whether the engine is still alive.
+    let interval = delay
+        .map(|delay| (delay / 2).max(Duration::from_millis(1)))
+        .unwrap_or(Duration::from_millis(100));
+    let spawned = std::thread::Builder::new()
+        .name("wasmtime-decommit".into())
+        .spawn(move || {
+            loop {
+                let signaled = match &signal {
+                    Some(signal) => signal.wait(interval),
+                    None => {
+                        std::thread::sleep(interval);
+                        false
+                    }
+                };
+                let Some(engine) = engine.upgrade() else {
+                    break;
+                };
+                let Some(pool) = engine.allocator().as_pooling() else {
+                    break;
+                };
+                if signaled {
+                    pool.flush_all_decommit_queues();
+                } else if let Some(delay) = delay {
+                    pool.flush_expired_decommit_queues(delay);
+                }
+            }
+        });
+    // If the thread can't be spawned then queues are still flushed when
+    // deallocating, just less promptly.
+    if let Err(e) = spawned {
+        log::warn!("failed to spawn decommit thread: {e}");
+    }
+}
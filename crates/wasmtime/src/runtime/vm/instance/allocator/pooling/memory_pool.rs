@@ -127,6 +127,14 @@ pub struct MemoryPool {
     /// NB: this is needed for validation but does not affect the pool's size.
     memories_per_instance: usize,
 
+    /// The configured maximum size, in bytes, of each linear memory.
+    ///
+    /// Slots are sized to `layout.max_memory_bytes`, which is this rounded up
+    /// to the host page size. Memories with custom page sizes can be sized at
+    /// a byte granularity, however, so this is used to limit their size to
+    /// the configured maximum rather than the size of their slot.
+    max_memory_size: usize,
+
     /// How much linear memory, in bytes, to keep resident after resetting for
     /// use with the next instance. This much memory will be `memset` to zero
     /// when a linear memory is deallocated.
@@ -281,9 +289,8 @@ impl MemoryPool {
             image_slots,
             layout,
             memories_per_instance: usize::try_from(config.limits.max_memories_per_module).unwrap(),
-            keep_resident: HostAlignedByteCount::new_rounded_up(
-                config.linear_memory_keep_resident,
-            )?,
+            max_memory_size: config.limits.max_memory_size,
+            keep_resident: super::keep_resident(config, config.linear_memory_keep_resident)?,
             next_available_pkey: AtomicUsize::new(0),
         };
 
@@ -327,11 +334,11 @@ impl MemoryPool {
         let min = memory.minimum_byte_size().with_context(|| {
             format!("memory has a minimum byte size that cannot be represented in a u64",)
         })?;
-        if min > u64::try_from(self.layout.max_memory_bytes.byte_count()).unwrap() {
+        if min > u64::try_from(self.max_memory_size).unwrap() {
             bail!(
                 "memory has a minimum byte size of {} which exceeds the limit of {} bytes",
                 min,
-                self.layout.max_memory_bytes,
+                self.max_memory_size,
             );
         }
         if memory.shared {
@@ -401,7 +408,8 @@ impl MemoryPool {
         );
 
         let base = self.get_base(allocation_index);
-        let base_capacity = self.layout.max_memory_bytes;
+        debug_assert!(self.max_memory_size <= self.layout.max_memory_bytes.byte_count());
+        let base_capacity = self.max_memory_size;
 
         let mut slot = self.take_memory_image_slot(allocation_index)?;
         let image = match memory_index {
@@ -432,7 +440,7 @@ impl MemoryPool {
             ty,
             &memory_tunables,
             MemoryBase::Mmap(base),
-            base_capacity.byte_count(),
+            base_capacity,
             slot,
             request.limiter.as_deref_mut(),
         )
@@ -1,4 +1,5 @@
 use core::sync::atomic::Ordering;
+use core::time::Duration;
 
 use crate::{Engine, vm::PoolingInstanceAllocator};
 
@@ -109,6 +110,38 @@ impl PoolingAllocatorMetrics {
         self.allocator().stacks.unused_bytes_resident()
     }
 
+    /// Returns the number of batches of decommits performed so far.
+    ///
+    /// See [`PoolingAllocationConfig::decommit_batch_size`](crate::PoolingAllocationConfig::decommit_batch_size).
+    pub fn decommit_batches(&self) -> u64 {
+        self.allocator().decommit_stats.flushes()
+    }
+
+    /// Returns the total time spent decommitting memory so far.
+    pub fn decommit_time(&self) -> Duration {
+        self.allocator().decommit_stats.total_time()
+    }
+
+    /// Returns the longest time a single batch of decommits has taken.
+    pub fn max_decommit_latency(&self) -> Duration {
+        self.allocator().decommit_stats.max_time()
+    }
+
+    /// Returns the number of bytes of memory which are no longer in use but
+    /// are still resident because they're queued to be decommitted.
+    ///
+    /// This is the overshoot in RSS caused by batching decommits, which can be
+    /// bounded with
+    /// [`PoolingAllocationConfig::decommit_batch_bytes`](crate::PoolingAllocationConfig::decommit_batch_bytes).
+    pub fn queued_decommit_bytes(&self) -> usize {
+        self.allocator().decommit_stats.queued_bytes()
+    }
+
+    /// Returns the largest value [`Self::queued_decommit_bytes`] has had.
+    pub fn max_queued_decommit_bytes(&self) -> usize {
+        self.allocator().decommit_stats.max_queued_bytes()
+    }
+
     fn allocator(&self) -> &PoolingInstanceAllocator {
         self.engine
             .allocator()
@@ -45,7 +45,7 @@ impl TablePool {
         let mapping = Mmap::accessible_reserved(allocation_size, allocation_size)
             .context("failed to create table pool mapping")?;
 
-        let keep_resident = HostAlignedByteCount::new_rounded_up(config.table_keep_resident)?;
+        let keep_resident = super::keep_resident(config, config.table_keep_resident)?;
 
         Ok(Self {
             index_allocator: SimpleIndexAllocator::new(config.limits.total_tables)?,
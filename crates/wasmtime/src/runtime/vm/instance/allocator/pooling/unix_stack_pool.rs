@@ -80,7 +80,8 @@ impl StackPool {
             max_stacks,
             page_size,
             async_stack_zeroing: config.async_stack_zeroing,
-            async_stack_keep_resident: HostAlignedByteCount::new_rounded_up(
+            async_stack_keep_resident: super::keep_resident(
+                config,
                 config.async_stack_keep_resident,
             )?,
             index_allocator: SimpleIndexAllocator::new(config.limits.total_stacks)?,
@@ -1,11 +1,17 @@
 use crate::error::OutOfMemory;
+#[cfg(has_virtual_memory)]
+use crate::prelude::*;
 use crate::runtime::vm::vmcontext::VMArrayCallNative;
+#[cfg(has_virtual_memory)]
+use crate::runtime::vm::{HostAlignedByteCount, Mmap, mmap::AlignedLength};
 use crate::runtime::vm::{
     StoreBox, TrapRegisters, TrapTest, VMContext, VMOpaqueContext, f32x4, f64x2, i8x16, tls,
 };
 use crate::{Engine, ValRaw};
 use core::marker;
 use core::ptr::NonNull;
+#[cfg(has_virtual_memory)]
+use pulley_interpreter::interp::StackMemory;
 use pulley_interpreter::interp::{DoneReason, RegType, TrapKind, Val, Vm, XRegVal};
 use pulley_interpreter::{Reg, XReg};
 use wasmtime_environ::{BuiltinFunctionIndex, HostCall, Trap};
@@ -61,11 +67,12 @@ impl Interpreter {
     pub fn new(engine: &Engine) -> Result<Interpreter, OutOfMemory> {
         let ret = Interpreter {
             pulley: StoreBox::new(VmState {
-                vm: Vm::with_stack(engine.config().max_wasm_stack)?,
+                vm: new_vm(engine.config().max_wasm_stack)?,
                 resume_at_pc: None,
             })?,
         };
         engine.profiler().register_interpreter(&ret);
+        crate::runtime::vm::crash_dump::register_interpreter(&ret);
         Ok(ret)
     }
 
@@ -89,6 +96,73 @@ impl Interpreter {
     }
 }
 
+/// Creates the Pulley VM for an interpreter, giving it a stack of at most
+/// `max_stack` bytes.
+///
+/// With virtual memory the entire stack is reserved up front but only made
+/// accessible as it's used, so large stack limits don't cost memory unless
+/// guest code actually recurses deeply. Otherwise the stack is allocated in
+/// full.
+#[cfg(has_virtual_memory)]
+fn new_vm(max_stack: usize) -> Result<Vm, OutOfMemory> {
+    let oom = || OutOfMemory::new(max_stack);
+    let size = HostAlignedByteCount::new_rounded_up(max_stack).map_err(|_| oom())?;
+    if size.is_zero() {
+        return Vm::with_stack(max_stack);
+    }
+    let mmap = Mmap::accessible_reserved(HostAlignedByteCount::ZERO, size).map_err(|_| oom())?;
+    Ok(Vm::with_stack_memory(try_new::<Box<MmapStack>>(
+        MmapStack {
+            mmap,
+            accessible: HostAlignedByteCount::ZERO,
+        },
+    )?))
+}
+
+#[cfg(not(has_virtual_memory))]
+fn new_vm(max_stack: usize) -> Result<Vm, OutOfMemory> {
+    Vm::with_stack(max_stack)
+}
+
+/// A Pulley stack reserved in virtual memory and made accessible from the top
+/// down as the interpreter needs it.
+#[cfg(has_virtual_memory)]
+struct MmapStack {
+    mmap: Mmap<AlignedLength>,
+    /// The number of bytes at the top of `mmap` which are accessible.
+    accessible: HostAlignedByteCount,
+}
+
+// SAFETY: the mmap is never moved or resized once created, it's page-aligned
+// (and so 16-byte aligned), and accessible regions stay accessible until it's
+// dropped.
+#[cfg(has_virtual_memory)]
+unsafe impl StackMemory for MmapStack {
+    fn base(&self) -> NonNull<u8> {
+        self.mmap.as_non_null()
+    }
+
+    fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    fn commit(&mut self, size: usize) -> Result<usize, OutOfMemory> {
+        let oom = || OutOfMemory::new(size);
+        let size = HostAlignedByteCount::new_rounded_up(size)
+            .map_err(|_| oom())?
+            .min(self.mmap.len_aligned());
+        if size > self.accessible {
+            let start = self.mmap.len_aligned().saturating_sub(size);
+            let len = size.saturating_sub(self.accessible);
+            // SAFETY: the region being made accessible is beneath everything
+            // handed out to the interpreter so far, so nothing references it.
+            unsafe { self.mmap.make_accessible(start, len) }.map_err(|_| oom())?;
+            self.accessible = size;
+        }
+        Ok(self.accessible.byte_count())
+    }
+}
+
 /// Wrapper around `&mut pulley_interpreter::Vm` to enable compiling this to a
 /// zero-sized structure when pulley is disabled at compile time.
 #[repr(transparent)]
@@ -250,14 +250,18 @@ fn memory_grow(
     let (mut limiter, store) = store.resource_limiter_and_store_opaque();
     let limiter = limiter.as_mut();
     block_on!(store, async |store, _| {
+        let store_id = store.id();
         let instance = store.instance_mut(instance);
         let module = instance.env_module();
         let page_size_log2 = module.memories[module.memory_index(memory_index)].page_size_log2;
 
         let result = instance
-            .memory_grow(limiter, memory_index, delta)
+            .memory_grow(store_id, limiter, memory_index, delta)
             .await?
             .map(|size_in_bytes| AllocationSize(size_in_bytes >> page_size_log2));
+        if result.is_none() {
+            store.note_memory_grow_failure();
+        }
 
         Ok(result)
     })?
@@ -311,9 +315,10 @@ unsafe fn table_grow(
     let (mut limiter, store) = store.resource_limiter_and_store_opaque();
     let limiter = limiter.as_mut();
     block_on!(store, async |store, _| unsafe {
+        let store_id = store.id();
         let result = store
             .instance_mut(instance)
-            .defined_table_grow(defined_table_index, limiter, delta)
+            .defined_table_grow(store_id, defined_table_index, limiter, delta)
             .await?
             .map(AllocationSize);
         Ok(result)
@@ -675,6 +680,7 @@ fn memory_atomic_wait64(
 
 // Hook for when an instance runs out of fuel.
 fn out_of_gas(store: &mut dyn VMStore, _instance: InstanceId) -> Result<()> {
+    store.engine().counters().fuel_exhausted();
     block_on!(store, async |store, _| {
         if !store.refuel() {
             return Err(Trap::OutOfFuel.into());
@@ -687,6 +693,26 @@ fn out_of_gas(store: &mut dyn VMStore, _instance: InstanceId) -> Result<()> {
     })?
 }
 
+// Hook for profile instrumentation when a function is called.
+fn profile_call(store: &mut dyn VMStore, instance: InstanceId, func: u32) {
+    if let Some(module) = store.instance(instance).runtime_module() {
+        module.record_profile_call(FuncIndex::from_u32(func));
+    }
+}
+
+// Hook for profile instrumentation when a conditional branch executes.
+fn profile_branch(
+    store: &mut dyn VMStore,
+    instance: InstanceId,
+    func: u32,
+    offset: u32,
+    taken: u32,
+) {
+    if let Some(module) = store.instance(instance).runtime_module() {
+        module.record_profile_branch(FuncIndex::from_u32(func), offset, taken != 0);
+    }
+}
+
 // Hook for when an instance observes that the epoch has changed.
 #[cfg(target_has_atomic = "64")]
 fn new_epoch(store: &mut dyn VMStore, _instance: InstanceId) -> Result<NextEpoch> {
@@ -1095,9 +1121,18 @@ fn fma_f64x2(
 /// The `Infallible` "ok" type here means that this never returns success, it
 /// only ever returns an error, and this hooks into the machinery to handle
 /// `Result` values to record such trap information.
-fn trap(_store: &mut dyn VMStore, _instance: InstanceId, code: u8) -> Result<Infallible> {
+fn trap(store: &mut dyn VMStore, _instance: InstanceId, code: u8) -> Result<Infallible> {
     match CompiledTrap::from_u8(code).unwrap() {
-        CompiledTrap::Normal(trap) => Err(trap.into()),
+        CompiledTrap::Normal(trap) => {
+            // The exit pc is the return address of this libcall, so subtract
+            // one to describe the call instruction that raised this trap.
+            let store = store.store_opaque_mut();
+            let pc = unsafe { *store.vm_store_context().last_wasm_exit_pc.get() };
+            let details =
+                crate::TrapDetails::new(store, trap, pc - 1, crate::TrapDelivery::Libcall, None);
+            store.trap_stats_mut().record(&details);
+            Err(Error::from(trap).context(details))
+        }
         CompiledTrap::InternalAssert => bail_bug!("internal assert hit in wasm"),
         CompiledTrap::GcHeapCorrupt => bail_bug!("GC heap corruption detected"),
     }
@@ -96,9 +96,7 @@ pub use self::mmap::MmapMemory;
 mod malloc;
 pub use self::malloc::MallocMemory;
 
-#[cfg(feature = "pooling-allocator")]
 mod static_;
-#[cfg(feature = "pooling-allocator")]
 use self::static_::StaticMemory;
 
 #[cfg(feature = "threads")]
@@ -292,6 +290,26 @@ impl Memory {
         })
     }
 
+    /// Create a new memory which aliases `len` bytes of another memory,
+    /// starting at `base`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `base..base + len` stays allocated, and
+    /// doesn't move, for as long as the returned memory is in use.
+    pub unsafe fn new_alias(
+        ty: &wasmtime_environ::Memory,
+        engine: &Engine,
+        base: NonNull<u8>,
+        len: usize,
+    ) -> Result<Self> {
+        let memory_tunables = MemoryTunables::new(engine.tunables(), MemoryKind::LinearMemory);
+        let alias = StaticMemory::new(MemoryBase::Raw(base.into()), len, len, Some(len))?;
+        let allocation = try_new::<Box<_>>(alias)?;
+        let memory = LocalMemory::new(ty, &memory_tunables, allocation, None)?;
+        Ok(Memory::Local(memory))
+    }
+
     /// Calls the `store`'s limiter to optionally prevent a memory from being allocated.
     ///
     /// Returns a tuple of the minimum size, optional maximum size, and log(page
@@ -343,7 +361,7 @@ impl Memory {
         // now the expected uses of limiter means that's ok.
         if let Some(limiter) = limiter {
             if !limiter
-                .memory_growing(0, minimum.unwrap_or(absolute_max), maximum)
+                .memory_creating(minimum.unwrap_or(absolute_max), maximum)
                 .await?
             {
                 bail!(
@@ -385,6 +403,15 @@ impl Memory {
         }
     }
 
+    /// Returns whether the base pointer of this memory may be relocated when
+    /// it grows.
+    pub fn memory_may_move(&self) -> bool {
+        match self {
+            Memory::Local(mem) => mem.memory_may_move,
+            Memory::Shared(_) => false,
+        }
+    }
+
     /// Returns whether or not this memory needs initialization. It
     /// may not if it already has initial content thanks to a CoW
     /// mechanism.
@@ -6,7 +6,8 @@ use crate::runtime::vm::MemoryBase;
 use crate::runtime::vm::memory::RuntimeLinearMemory;
 
 /// A "static" memory where the lifetime of the backing memory is managed
-/// elsewhere. Currently used with the pooling allocator.
+/// elsewhere. Currently used with the pooling allocator and for aliases of
+/// ranges of other memories.
 pub struct StaticMemory {
     /// The base pointer of this static memory, wrapped up in a send/sync
     /// wrapper.
@@ -49,6 +49,10 @@ pub unsafe fn decommit_pages(iov: &[iovec]) -> io::Result<()> {
     Ok(())
 }
 
+pub unsafe fn lazy_free_pages(iov: &[iovec]) -> io::Result<()> {
+    unsafe { decommit_pages(iov) }
+}
+
 pub fn get_page_size() -> usize {
     4096
 }
@@ -1,6 +1,7 @@
 //! Trap handling on Unix based on POSIX signals.
 
 use crate::prelude::*;
+use crate::runtime::vm::crash_dump;
 use crate::runtime::vm::traphandlers::{TrapRegisters, TrapTest, tls};
 use std::cell::RefCell;
 use std::io;
@@ -192,6 +193,17 @@ unsafe extern "C" fn trap_handler(
         return;
     }
 
+    // This signal isn't for wasm and is likely fatal, so record what Wasmtime
+    // was up to, if configured, before forwarding it.
+    unsafe {
+        let faulting_addr = match signum {
+            libc::SIGSEGV | libc::SIGBUS => Some((*siginfo).si_addr() as usize),
+            _ => None,
+        };
+        let regs = get_trap_registers(context, signum);
+        tls::with(|info| crash_dump::write(signum, regs.pc, faulting_addr, info));
+    }
+
     unsafe { delegate_signal_to_previous_handler(previous, signum, siginfo, context) }
 }
 
@@ -89,6 +89,33 @@ pub unsafe fn decommit_pages(iov: &[iovec]) -> io::Result<()> {
     Ok(())
 }
 
+/// Marks the already-zeroed pages in `iov` as lazily freeable.
+///
+/// On Linux this uses `madvise(MADV_FREE)`, which lets the kernel reclaim the
+/// pages under memory pressure while otherwise leaving them resident. Pages
+/// that are reclaimed read as zero when next accessed, so callers must zero
+/// pages before passing them here for their contents to be consistent either
+/// way. Elsewhere this falls back to [`decommit_pages`].
+#[cfg(feature = "pooling-allocator")]
+pub unsafe fn lazy_free_pages(iov: &[iovec]) -> io::Result<()> {
+    cfg_select! {
+        target_os = "linux" => {
+            use rustix::mm::{madvise, Advice};
+
+            for iov in iov {
+                if iov.iov_len == 0 {
+                    continue;
+                }
+                unsafe {
+                    madvise(iov.iov_base, iov.iov_len, Advice::LinuxFree)?;
+                }
+            }
+            Ok(())
+        }
+        _ => unsafe { decommit_pages(iov) },
+    }
+}
+
 // NB: this function is duplicated in `crates/fiber/src/unix.rs` so if this
 // changes that should probably get updated as well.
 pub fn get_page_size() -> usize {
@@ -56,6 +56,11 @@ pub unsafe fn decommit_pages(iov: &[iovec]) -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "pooling-allocator")]
+pub unsafe fn lazy_free_pages(iov: &[iovec]) -> io::Result<()> {
+    unsafe { decommit_pages(iov) }
+}
+
 pub fn get_page_size() -> usize {
     unsafe {
         let mut info = MaybeUninit::uninit();
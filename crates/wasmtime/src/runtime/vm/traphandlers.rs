@@ -39,6 +39,8 @@ pub use self::backtrace::Backtrace;
 #[cfg(feature = "gc")]
 pub use wasmtime_unwinder::Frame;
 
+#[cfg(feature = "coredump")]
+pub use self::coredump::CoreDumpFrame;
 pub use self::coredump::CoreDumpStack;
 pub use self::tls::tls_eager_initialize;
 #[cfg(feature = "async")]
@@ -891,6 +893,15 @@ impl CallThreadState {
                 if !has_backtrace {
                     trap.backtrace = self.capture_backtrace(store.vm_store_context_mut(), None);
                     trap.coredumpstack = self.capture_coredump(store.vm_store_context_mut(), None);
+
+                    // Unlike the backtrace, the values in each frame can only
+                    // be read with the help of the store's debug metadata,
+                    // which is available here since this isn't a signal
+                    // handler.
+                    #[cfg(all(feature = "coredump", feature = "debug"))]
+                    if let Some(coredump) = &mut trap.coredumpstack {
+                        coredump.frames = store.coredump_frames();
+                    }
                 }
             }
 
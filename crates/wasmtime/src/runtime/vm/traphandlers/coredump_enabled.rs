@@ -1,6 +1,6 @@
 use super::CallThreadState;
 use crate::prelude::*;
-use crate::runtime::vm::{Backtrace, VMStoreContext};
+use crate::runtime::vm::{Backtrace, CompiledModuleId, VMStoreContext};
 use wasm_encoder::CoreDumpValue;
 
 /// A WebAssembly Coredump
@@ -9,15 +9,29 @@ pub struct CoreDumpStack {
     /// The backtrace containing the stack frames for the CoreDump
     pub bt: Backtrace,
 
-    /// The locals for each frame in the backtrace.
+    /// The locals and operand stack of Wasm frames on the stack, from
+    /// innermost to outermost.
     ///
-    /// This is not currently implemented.
-    pub locals: Vec<Vec<CoreDumpValue>>,
+    /// These are only recoverable when guest debugging is enabled, and this
+    /// is empty otherwise. Frames are matched up with those in `bt` by their
+    /// module and function index.
+    pub frames: Vec<CoreDumpFrame>,
+}
 
-    /// The operands for each stack frame
-    ///
-    /// This is not currently implemented.
-    pub operand_stack: Vec<Vec<CoreDumpValue>>,
+/// The state of one Wasm frame captured for a core dump.
+#[derive(Debug)]
+pub struct CoreDumpFrame {
+    /// The module that the frame's function is defined in.
+    pub module: CompiledModuleId,
+
+    /// The index of the frame's function within `module`.
+    pub func_index: u32,
+
+    /// The values of the frame's locals.
+    pub locals: Vec<CoreDumpValue>,
+
+    /// The values on the frame's operand stack, from bottom to top.
+    pub operand_stack: Vec<CoreDumpValue>,
 }
 
 impl CallThreadState {
@@ -33,10 +47,6 @@ impl CallThreadState {
             Backtrace::new_with_trap_state(vm_store_context, self.unwinder, self, trap_pc_and_fp)
         };
 
-        Some(CoreDumpStack {
-            bt,
-            locals: vec![],
-            operand_stack: vec![],
-        })
+        Some(CoreDumpStack { bt, frames: vec![] })
     }
 }
@@ -132,7 +132,7 @@ impl WasmValue for component::Val {
             Self::Float64(_) => WasmTypeKind::F64,
             Self::Char(_) => WasmTypeKind::Char,
             Self::String(_) => WasmTypeKind::String,
-            Self::List(_) => WasmTypeKind::List,
+            Self::List(_) | Self::PackedList(_) => WasmTypeKind::List,
             Self::Record(_) => WasmTypeKind::Record,
             Self::Tuple(_) => WasmTypeKind::Tuple,
             Self::Variant(..) => WasmTypeKind::Variant,
@@ -261,6 +261,9 @@ impl WasmValue for component::Val {
         unwrap_val!(self, Self::String, "string").into()
     }
     fn unwrap_list(&self) -> Box<dyn Iterator<Item = Cow<'_, Self>> + '_> {
+        if let Self::PackedList(list) = self {
+            return Box::new(list.iter().map(Cow::Owned));
+        }
         let list = unwrap_val!(self, Self::List, "list");
         Box::new(list.iter().map(cow))
     }
@@ -318,6 +321,12 @@ fn ensure_type_val(ty: &component::Type, val: &component::Val) -> Result<(), Was
                 ensure_type_val(&list_type, val)?;
             }
         }
+        component::Val::PackedList(list) => {
+            let list_type = ty.unwrap_list().ty();
+            for val in list.iter() {
+                ensure_type_val(&list_type, &val)?;
+            }
+        }
         component::Val::Record(vals) => {
             let record_handle = ty.unwrap_record();
             // Check that every non option field type is found in the Vec
@@ -168,6 +168,25 @@ impl<T: Default> Default for RwLock<T> {
     }
 }
 
+/// Mutual exclusion built on `RwLock` which only ever takes write locks.
+#[derive(Debug, Default)]
+pub struct Mutex<T>(RwLock<T>);
+
+// SAFETY: like `std::sync::Mutex` this only ever hands out exclusive access
+// to `T`, so it's safe to share across threads so long as `T` can be sent
+// between them.
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn new(val: T) -> Mutex<T> {
+        Mutex(RwLock::new(val))
+    }
+
+    pub fn lock(&self) -> impl DerefMut<Target = T> + '_ {
+        self.0.write()
+    }
+}
+
 struct RwLockReadGuard<'a, T> {
     lock: &'a RwLock<T>,
 }
@@ -445,6 +464,14 @@ mod tests {
         drop((a, b));
     }
 
+    #[test]
+    fn smoke_mutex() {
+        let lock = Mutex::new(1);
+        assert_eq!(*lock.lock(), 1);
+        *lock.lock() = 4;
+        assert_eq!(*lock.lock(), 4);
+    }
+
     #[test]
     #[should_panic(expected = "concurrent write request")]
     fn rwlock_panic_read_then_write() {
@@ -86,6 +86,12 @@ enum StackOverflow {
     Host,
     HostAsyncStack,
     Wasm,
+    /// Not a stack overflow, but a segfault with a crash dump on stderr.
+    #[cfg_attr(
+        not(all(unix, feature = "crash-dump")),
+        expect(dead_code, reason = "only constructed with crash dumps enabled")
+    )]
+    CrashDump,
 }
 
 fn main() {
@@ -152,6 +158,58 @@ fn main() {
             },
             StackOverflow::No,
         ),
+        #[cfg(all(unix, feature = "crash-dump"))]
+        (
+            "segfault in a host function with a crash dump",
+            || {
+                let engine = Engine::new(&crash_dump_config()).unwrap();
+                let mut store = Store::new(&engine, ());
+                let module = Module::new(&engine, r#"(import "" "" (func)) (start 0)"#).unwrap();
+                let segfault = Func::wrap(&mut store, || -> () { segfault() });
+                Instance::new(&mut store, &module, &[segfault.into()]).unwrap();
+                unreachable!();
+            },
+            StackOverflow::CrashDump,
+        ),
+        #[cfg(all(unix, feature = "crash-dump"))]
+        (
+            "segfault in wasm code with a crash dump",
+            || {
+                let engine = Engine::new(&crash_dump_config()).unwrap();
+                let mut store = Store::new(&engine, ());
+                let module = Module::new(
+                    &engine,
+                    r#"
+                        (import "" "" (func))
+                        (func (export "run") (call 0) (loop br 0))
+                    "#,
+                )
+                .unwrap();
+                // Raise a fault at whichever instruction of the loop the
+                // thread is at, none of which are trap sites. Unlike a real
+                // fault this isn't raised again once the handler returns, so
+                // keep raising it until the default disposition, restored by
+                // the first one, terminates the process.
+                let thread = unsafe { libc::pthread_self() } as usize;
+                let kill = Func::wrap(&mut store, move || {
+                    println!("{CONFIRM}");
+                    io::stdout().flush().unwrap();
+                    std::thread::spawn(move || {
+                        loop {
+                            std::thread::sleep(std::time::Duration::from_millis(100));
+                            unsafe { libc::pthread_kill(thread as libc::pthread_t, libc::SIGSEGV) };
+                        }
+                    });
+                });
+                let instance = Instance::new(&mut store, &module, &[kill.into()]).unwrap();
+                let run = instance
+                    .get_typed_func::<(), ()>(&mut store, "run")
+                    .unwrap();
+                run.call(&mut store, ()).unwrap();
+                unreachable!();
+            },
+            StackOverflow::CrashDump,
+        ),
         (
             "hit async stack guard page",
             || {
@@ -231,6 +289,16 @@ fn main() {
     }
 }
 
+#[cfg(all(unix, feature = "crash-dump"))]
+fn crash_dump_config() -> Config {
+    use std::os::fd::AsFd;
+
+    let mut config = Config::default();
+    config.macos_use_mach_ports(false);
+    config.crash_dump_fd(io::stderr().as_fd().try_clone_to_owned().unwrap());
+    config
+}
+
 fn run_test(name: &str, stack_overflow: StackOverflow) {
     let me = env::current_exe().unwrap();
     let mut cmd = Command::new(me);
@@ -292,6 +360,23 @@ fn run_test(name: &str, stack_overflow: StackOverflow) {
             );
         }
 
+        StackOverflow::CrashDump => {
+            assert!(
+                is_segfault(&output.status) && stdout.trim().ends_with(CONFIRM),
+                "expected a segfault on `{name}`\n{desc}"
+            );
+            assert!(
+                stderr.starts_with("=== wasmtime crash dump ===\n")
+                    && stderr.contains("\nsignal: 11\n")
+                    && stderr.contains("\nlive stores: 1\n")
+                    && stderr.contains("\n  #0: store context ")
+                    && stderr
+                        .trim_end()
+                        .ends_with("=== end of wasmtime crash dump ==="),
+                "expected a crash dump on `{name}`\n{desc}"
+            );
+        }
+
         StackOverflow::No => {
             if is_segfault(&output.status) {
                 assert!(
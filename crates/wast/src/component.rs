@@ -278,7 +278,7 @@ fn mismatch(expected: &ComponentConst<'_>, actual: &Val) -> Result<()> {
         Val::Float64(..) => "f64",
         Val::Char(..) => "char",
         Val::String(..) => "string",
-        Val::List(..) => "list",
+        Val::List(..) | Val::PackedList(..) => "list",
         Val::Record(..) => "record",
         Val::Tuple(..) => "tuple",
         Val::Enum(..) => "enum",
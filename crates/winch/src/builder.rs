@@ -55,22 +55,18 @@ impl CompilerBuilder for Builder {
             bail!("Winch requires the winch calling convention");
         }
 
-        if !tunables.table_lazy_init {
-            bail!("Winch requires the table-lazy-init option to be enabled");
-        }
-
         if !tunables.signals_based_traps {
             bail!("Winch requires the signals-based-traps option to be enabled");
         }
 
-        if tunables.debug_native {
-            bail!("Winch does not currently support generating native debug information");
-        }
-
         if tunables.debug_guest {
             bail!("Winch does not currently support guest-level debugging");
         }
 
+        if tunables.epoch_interruption_check_interval.is_some() {
+            bail!("Winch does not currently support an epoch interruption check interval");
+        }
+
         self.tunables = Some(tunables.clone());
         self.cranelift.set_tunables(tunables)?;
         Ok(())
@@ -1,4 +1,4 @@
-use cranelift_codegen::isa::unwind::UnwindInfoKind;
+use cranelift_codegen::isa::unwind::{UnwindInfo, UnwindInfoKind};
 use object::write::{Object, SymbolId};
 use std::any::Any;
 use std::mem;
@@ -86,6 +86,31 @@ impl Compiler {
 
         Ok(())
     }
+
+    /// Emit the CFA-based unwind information required by DWARF debugging
+    /// support into the [`CompiledFunction`], unless the native unwind
+    /// information is already CFA-based.
+    fn emit_cfa_unwind_info(
+        &self,
+        compiled_function: &mut CompiledFunction,
+    ) -> Result<(), CompileError> {
+        if matches!(
+            compiled_function.metadata().unwind_info,
+            Some(UnwindInfo::SystemV(_))
+        ) {
+            return Ok(());
+        }
+
+        if let Some(UnwindInfo::SystemV(info)) = self
+            .isa
+            .emit_unwind_info(&compiled_function.buffer, UnwindInfoKind::SystemV)
+            .map_err(|e| CompileError::Codegen(format!("{e:?}")))?
+        {
+            compiled_function.set_cfa_unwind_info(info);
+        }
+
+        Ok(())
+    }
 }
 
 fn box_dyn_any_compiled_function(f: CompiledFunction) -> Box<dyn Any + Send + Sync> {
@@ -136,6 +161,7 @@ impl wasmtime_environ::Compiler for Compiler {
         let func = self
             .isa
             .compile_function(
+                index,
                 ty,
                 &body,
                 translation,
@@ -159,6 +185,10 @@ impl wasmtime_environ::Compiler for Compiler {
             self.emit_unwind_info(&mut func)?;
         }
 
+        if self.tunables.debug_native {
+            self.emit_cfa_unwind_info(&mut func)?;
+        }
+
         Ok(CompiledFunctionBody {
             code: box_dyn_any_compiled_function(func),
             // TODO: Winch doesn't support GC objects and stack maps and all that yet.
@@ -208,16 +238,21 @@ impl wasmtime_environ::Compiler for Compiler {
 
     fn append_dwarf<'a>(
         &self,
-        _obj: &mut Object<'_>,
-        _translations: &'a PrimaryMap<StaticModuleIndex, ModuleTranslation<'a>>,
-        _get_func: &'a dyn Fn(
+        obj: &mut Object<'_>,
+        translations: &'a PrimaryMap<StaticModuleIndex, ModuleTranslation<'a>>,
+        get_func: &'a dyn Fn(
             StaticModuleIndex,
             DefinedFuncIndex,
         ) -> (Option<SymbolId>, &'a (dyn Any + Send + Sync)),
-        _dwarf_package_bytes: Option<&'a [u8]>,
-        _tunables: &'a Tunables,
+        dwarf_package_bytes: Option<&'a [u8]>,
+        tunables: &'a Tunables,
     ) -> Result<()> {
-        todo!()
+        // Winch-compiled functions are represented as a `CompiledFunction`
+        // carrying the same metadata (address map, value label ranges and
+        // unwind information) as the ones produced by Cranelift, so the DWARF
+        // transformation is shared.
+        self.trampolines
+            .append_dwarf(obj, translations, get_func, dwarf_package_bytes, tunables)
     }
 
     fn create_systemv_cie(&self) -> Option<gimli::write::CommonInformationEntry> {
@@ -192,6 +192,33 @@ pub struct Opts {
     /// ways), whereas this option lets you specify it on a case-by-case basis.
     pub debug: bool,
 
+    /// Path to a function of the shape `fn<F: Future>(F) -> F::Output` used
+    /// to generate blocking wrappers for `async` imports.
+    ///
+    /// When specified, every imported interface whose functions are all
+    /// `async` without `store` additionally gets a synchronous
+    /// `HostBlocking` trait (and a `Host{Resource}Blocking` trait per
+    /// resource) which is implemented for all implementors of the `async`
+    /// traits by driving their futures to completion with this function.
+    /// These are accompanied by `add_to_linker_blocking` functions which
+    /// register the imports with synchronous functions in a `Linker`, so the
+    /// same host implementation can be used with stores which don't have
+    /// async support.
+    ///
+    /// Like the paths in `with`, this path is relative to the location the
+    /// bindings are generated in.
+    pub blocking_wrappers: Option<String>,
+
+    /// Whether to generate a `Mock` type for each imported interface.
+    ///
+    /// A `Mock` implements all of the `Host` traits of its interface with
+    /// a public `wasmtime::component::MockFunc` field per function, which
+    /// records the parameters of each call and returns configurable canned
+    /// results. This enables testing components and hosts without
+    /// implementing every import by hand. Interfaces with functions that
+    /// have access to the store or which are `async` in WIT don't get mocks.
+    pub mocks: bool,
+
     /// TODO
     pub imports: FunctionConfig,
     /// TODO
@@ -795,10 +822,11 @@ pub fn new<_T>(
                     let resource_name = resolve.types[id].name.as_ref().unwrap();
                     let camel = resource_name.to_upper_camel_case();
                     uwriteln!(generator.src, "impl Guest{camel}<'_> {{");
-                    for method in methods {
+                    for method in methods.iter() {
                         generator.define_rust_guest_export(resolve, Some(name), method);
                     }
                     uwriteln!(generator.src, "}}");
+                    generator.define_rust_guest_export_owned(resolve, name, id, &methods);
                 }
 
                 let module = &generator.src[..];
@@ -1557,11 +1585,18 @@ impl Wasmtime {
             );
             let gate = FeatureGate::open(&mut self.src, &resolve.worlds[world].stability);
             for (ty, _name) in get_world_resources(resolve, world) {
-                self.generate_add_resource_to_linker(None, None, "linker", resolve, ty, false);
+                self.generate_add_resource_to_linker(
+                    None, None, "linker", resolve, ty, false, false,
+                );
             }
             for f in self.world_import_functions.clone() {
                 let mut generator = InterfaceGenerator::new(self, resolve);
-                generator.generate_add_function_to_linker(TypeOwner::World(world), &f, "linker");
+                generator.generate_add_function_to_linker(
+                    TypeOwner::World(world),
+                    &f,
+                    "linker",
+                    false,
+                );
                 let src = String::from(generator.src);
                 self.src.push_str(&src);
                 self.src.push_str("\n");
@@ -1655,6 +1690,7 @@ impl Wasmtime {
         resolve: &Resolve,
         ty: TypeId,
         named: bool,
+        blocking: bool,
     ) {
         let ty = &resolve.types[ty];
         let name = ty.name.as_ref().unwrap();
@@ -1681,7 +1717,7 @@ impl Wasmtime {
         };
 
         let flags = self.opts.imports.resource_drop_flags(resolve, key, name);
-        if flags.contains(FunctionFlags::ASYNC) {
+        if flags.contains(FunctionFlags::ASYNC) && !blocking {
             if flags.contains(FunctionFlags::STORE) {
                 uwriteln!(
                     src,
@@ -1717,7 +1753,9 @@ impl Wasmtime {
                 )
             }
         } else {
-            let (first_arg, trait_suffix) = if flags.contains(FunctionFlags::STORE) {
+            let (first_arg, trait_suffix) = if blocking {
+                ("&mut host_getter(store.data_mut())".to_string(), "Blocking")
+            } else if flags.contains(FunctionFlags::STORE) {
                 (
                     format!("{wt}::component::Access::new(store, host_getter)"),
                     "WithStore::<T>",
@@ -2616,11 +2654,12 @@ impl<'a> InterfaceGenerator<'a> {
                 self.resolve,
                 ty,
                 self.named_import_id.is_some(),
+                false,
             );
         }
 
         for (_, func) in iface.functions.iter() {
-            self.generate_add_function_to_linker(owner, func, "inst");
+            self.generate_add_function_to_linker(owner, func, "inst", false);
         }
         gate.close(&mut self.src);
         uwriteln!(self.src, "Ok(())");
@@ -2689,8 +2728,414 @@ pub fn add_to_linker<T, D>(
 }}
             "
                 );
+
+                if let Some(block_on) = self.generator.opts.blocking_wrappers.clone()
+                    && self.supports_blocking_wrappers(id, generated_trait.all_func_flags)
+                {
+                    // Like paths in `with`, resolve the path relative to the
+                    // root of the generated bindings.
+                    let block_on = format!("{}{block_on}", self.path_to_root());
+                    self.generate_blocking_wrappers(
+                        id,
+                        name,
+                        &block_on,
+                        &sync_bounds,
+                        options_param,
+                        options_param_forward,
+                    );
+                }
+            }
+        }
+
+        if self.generator.opts.mocks
+            && self.named_import_id.is_none()
+            && self.supports_mocks(id, generated_trait.all_func_flags)
+        {
+            self.generate_mock(id, &extra_functions);
+        }
+    }
+
+    /// Returns whether blocking wrappers can be generated for the imported
+    /// interface `id`, which requires all of its functions to be `async` but
+    /// not require access to the store.
+    fn supports_blocking_wrappers(&mut self, id: InterfaceId, flags: FunctionFlags) -> bool {
+        flags.contains(FunctionFlags::ASYNC)
+            && !flags.contains(FunctionFlags::STORE)
+            && self.resolve.interfaces[id]
+                .functions
+                .values()
+                .all(|f| !f.kind.is_async())
+    }
+
+    /// Returns whether a `Mock` can be generated for the imported interface
+    /// `id`, which requires none of its functions to need access to the store
+    /// or to be `async` in WIT.
+    fn supports_mocks(&mut self, id: InterfaceId, flags: FunctionFlags) -> bool {
+        !flags.contains(FunctionFlags::STORE)
+            && self.resolve.interfaces[id]
+                .functions
+                .values()
+                .all(|f| !f.kind.is_async())
+    }
+
+    /// Generates the `Mock` type of the imported interface `id`, which
+    /// implements all of its host traits by recording calls in a
+    /// `MockFunc` per function.
+    fn generate_mock(&mut self, id: InterfaceId, extra_functions: &[ExtraTraitMethod<'_>]) {
+        let wt = self.generator.wasmtime_path();
+        let iface = &self.resolve.interfaces[id];
+
+        // Each trait that `Mock` implements, along with the functions of that
+        // trait as `(field, params, result, function)`.
+        let mut traits = Vec::new();
+        for (ty, resource_name) in get_resources(self.resolve, id).collect::<Vec<_>>() {
+            let camel = resource_name.to_upper_camel_case();
+            let snake = to_rust_ident(resource_name);
+            let mut funcs = Vec::new();
+            for func in get_resource_functions(self.resolve, ty) {
+                let field = format!("{snake}_{}", rust_function_name(func));
+                funcs.push(self.mock_func(field, func));
+            }
+            traits.push((
+                format!("Host{camel}"),
+                Some((resource_name, snake, camel)),
+                funcs,
+            ));
+        }
+        let mut funcs = Vec::new();
+        for func in iface.functions.values() {
+            if func.kind.resource().is_none() {
+                funcs.push(self.mock_func(rust_function_name(func), func));
+            }
+        }
+        traits.push(("Host".to_string(), None, funcs));
+
+        let prev = mem::take(&mut self.src);
+        self.push_wasmtime_or_anyhow_result();
+        let result = String::from(mem::replace(&mut self.src, prev));
+
+        uwriteln!(
+            self.src,
+            "
+/// Implementation of [`Host`] for tests, which records calls and returns
+/// results configured in its [`MockFunc`]({wt}::component::MockFunc) fields.
+pub struct Mock {{"
+        );
+        for (_, resource, funcs) in traits.iter() {
+            for (field, params, ret, _) in funcs {
+                uwriteln!(
+                    self.src,
+                    "pub {field}: {wt}::component::MockFunc<{params}, {ret}>,"
+                );
+            }
+            if let Some((_, snake, camel)) = resource {
+                uwriteln!(
+                    self.src,
+                    "pub {snake}_drop: {wt}::component::MockFunc<({wt}::component::Resource<{camel}>,), {result}<()>>,"
+                );
+            }
+        }
+        for extra in extra_functions {
+            if let ExtraTraitMethod::ErrorConvert { name, id } = extra {
+                let root = self.path_to_root();
+                let custom_name = &self.generator.trappable_errors[id];
+                let snake = name.to_snake_case();
+                let camel = name.to_upper_camel_case();
+                uwriteln!(
+                    self.src,
+                    "pub convert_{snake}: {wt}::component::MockFunc<({root}{custom_name},), {result}<{camel}>>,"
+                );
+            }
+        }
+        uwriteln!(self.src, "}}");
+
+        // Resources are dropped successfully by default, everything else
+        // must be configured.
+        uwriteln!(
+            self.src,
+            "
+impl Default for Mock {{
+    fn default() -> Mock {{
+        Mock {{"
+        );
+        for (_, resource, funcs) in traits.iter() {
+            for (field, _, _, func) in funcs {
+                uwriteln!(
+                    self.src,
+                    "{field}: {wt}::component::MockFunc::new({:?}),",
+                    func.name
+                );
+            }
+            if let Some((name, snake, _)) = resource {
+                uwriteln!(
+                    self.src,
+                    "{snake}_drop: {{
+                        let mut f = {wt}::component::MockFunc::new(\"[resource-drop]{name}\");
+                        f.returns_with(|_| Ok(()));
+                        f
+                    }},"
+                );
+            }
+        }
+        for extra in extra_functions {
+            if let ExtraTraitMethod::ErrorConvert { name, .. } = extra {
+                let snake = name.to_snake_case();
+                uwriteln!(
+                    self.src,
+                    "convert_{snake}: {wt}::component::MockFunc::new(\"convert_{snake}\"),"
+                );
+            }
+        }
+        uwriteln!(self.src, "}}\n}}\n}}");
+
+        for (trait_name, resource, funcs) in traits.iter() {
+            uwriteln!(self.src, "impl {trait_name} for Mock {{");
+            for (field, _, _, func) in funcs {
+                let flags = self.generator.opts.imports.flags(
+                    self.resolve,
+                    self.current_interface.map(|p| p.1),
+                    func,
+                );
+                self.generate_function_trait_sig(func, flags);
+                let mut call = format!("self.{field}.call((");
+                for param in func.params.iter() {
+                    uwrite!(call, "{},", to_rust_ident(&param.name));
+                }
+                call.push_str("))");
+                if flags.contains(FunctionFlags::ASYNC) {
+                    uwriteln!(self.src, "{{ ::core::future::ready({call}) }}");
+                } else {
+                    uwriteln!(self.src, "{{ {call} }}");
+                }
+            }
+            if let Some((name, snake, camel)) = resource {
+                if self
+                    .import_resource_drop_flags(name)
+                    .contains(FunctionFlags::ASYNC)
+                {
+                    self.src.push_str("async ");
+                }
+                uwriteln!(
+                    self.src,
+                    "fn drop(&mut self, rep: {wt}::component::Resource<{camel}>) -> {result}<()> {{
+                        self.{snake}_drop.call((rep,))
+                    }}"
+                );
+            }
+            if resource.is_none() {
+                for extra in extra_functions {
+                    if let ExtraTraitMethod::ErrorConvert { name, id } = extra {
+                        let root = self.path_to_root();
+                        let custom_name = &self.generator.trappable_errors[id];
+                        let snake = name.to_snake_case();
+                        let camel = name.to_upper_camel_case();
+                        uwriteln!(
+                            self.src,
+                            "fn convert_{snake}(&mut self, err: {root}{custom_name}) -> {result}<{camel}> {{
+                                self.convert_{snake}.call((err,))
+                            }}"
+                        );
+                    }
+                }
+            }
+            uwriteln!(self.src, "}}");
+        }
+    }
+
+    /// Returns the `MockFunc` field name, parameter tuple type and result type
+    /// of `func` for its interface's `Mock`.
+    fn mock_func<'b>(
+        &mut self,
+        field: String,
+        func: &'b Function,
+    ) -> (String, String, String, &'b Function) {
+        let flags = self.generator.opts.imports.flags(
+            self.resolve,
+            self.current_interface.map(|p| p.1),
+            func,
+        );
+        let mut params = "(".to_string();
+        for param in func.params.iter() {
+            params.push_str(&self.ty(&param.ty, TypeMode::Owned));
+            params.push_str(",");
+        }
+        params.push(')');
+        let prev = mem::take(&mut self.src);
+        self.generate_function_result(func, flags);
+        let ret = String::from(mem::replace(&mut self.src, prev));
+        (field, params, ret, func)
+    }
+
+    /// Generates the `*Blocking` traits of the imported interface `id`, which
+    /// are implemented in terms of the `async` traits with `block_on`, along
+    /// with `add_to_linker_blocking` functions using them.
+    fn generate_blocking_wrappers(
+        &mut self,
+        id: InterfaceId,
+        name: &str,
+        block_on: &str,
+        sync_bounds: &str,
+        options_param: &str,
+        options_param_forward: &str,
+    ) {
+        let wt = self.generator.wasmtime_path();
+        let iface = &self.resolve.interfaces[id];
+        let owner = TypeOwner::Interface(id);
+
+        let mut supertraits = Vec::new();
+        for (ty, resource_name) in get_resources(self.resolve, id).collect::<Vec<_>>() {
+            let camel = resource_name.to_upper_camel_case();
+            let functions = get_resource_functions(self.resolve, ty);
+            let drop_flags = self.import_resource_drop_flags(resource_name);
+            let mut drop =
+                format!("fn drop(&mut self, rep: {wt}::component::Resource<{camel}>) -> ");
+            drop.push_str(&format!("{wt}::"));
+            if self.generator.opts.anyhow {
+                drop.push_str("anyhow::");
+            }
+            drop.push_str("Result<()>");
+            self.generate_blocking_trait(
+                &format!("Host{camel}"),
+                &functions,
+                &[],
+                Some((&drop, drop_flags)),
+                block_on,
+            );
+            supertraits.push(format!("Host{camel}Blocking"));
+        }
+        let functions = iface
+            .functions
+            .values()
+            .filter(|f| f.kind.resource().is_none())
+            .collect::<Vec<_>>();
+        self.generate_blocking_trait("Host", &functions, &supertraits, None, block_on);
+
+        uwriteln!(
+            self.src,
+            "
+                pub fn add_to_linker_instance_blocking<T, D>(
+                    inst: &mut {wt}::component::LinkerInstance<'_, T>,
+                    {options_param}
+                    host_getter: fn(&mut T) -> D::Data<'_>,
+                ) -> {wt}::Result<()>
+                    where
+                        D: HostWithStore<T>,
+                        for<'a> D::Data<'a>: {sync_bounds},
+                        T: 'static,
+                {{
+            "
+        );
+        let gate = FeatureGate::open(&mut self.src, &iface.stability);
+        for (ty, _name) in get_resources(self.resolve, id) {
+            self.generator.generate_add_resource_to_linker(
+                self.current_interface.map(|p| p.1),
+                Some(&mut self.src),
+                "inst",
+                self.resolve,
+                ty,
+                false,
+                true,
+            );
+        }
+        for (_, func) in iface.functions.iter() {
+            self.generate_add_function_to_linker(owner, func, "inst", true);
+        }
+        gate.close(&mut self.src);
+        uwriteln!(self.src, "Ok(())");
+        uwriteln!(self.src, "}}");
+
+        uwriteln!(
+            self.src,
+            "
+pub fn add_to_linker_blocking<T, D>(
+    linker: &mut {wt}::component::Linker<T>,
+    {options_param}
+    host_getter: fn(&mut T) -> D::Data<'_>,
+) -> {wt}::Result<()>
+    where
+        D: HostWithStore<T>,
+        for<'a> D::Data<'a>: {sync_bounds},
+        T: 'static,
+{{
+    let mut inst = linker.instance(\"{name}\")?;
+    add_to_linker_instance_blocking::<T, D>(&mut inst, {options_param_forward} host_getter)
+}}
+            "
+        );
+    }
+
+    /// Generates the `{trait_name}Blocking` trait with synchronous versions
+    /// of `functions`, and its blanket implementation for all
+    /// `{trait_name}` implementors.
+    fn generate_blocking_trait(
+        &mut self,
+        trait_name: &str,
+        functions: &[&Function],
+        supertraits: &[String],
+        drop: Option<(&str, FunctionFlags)>,
+        block_on: &str,
+    ) {
+        let funcs = functions
+            .iter()
+            .map(|func| {
+                let flags = self.generator.opts.imports.flags(
+                    self.resolve,
+                    self.current_interface.map(|p| p.1),
+                    func,
+                );
+                (*func, flags)
+            })
+            .collect::<Vec<_>>();
+
+        let supertraits = if supertraits.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", supertraits.join(" + "))
+        };
+        uwriteln!(
+            self.src,
+            "
+/// Synchronous version of [`{trait_name}`], implemented for all of its
+/// implementors by blocking on their futures.
+pub trait {trait_name}Blocking {supertraits} {{"
+        );
+        for (func, flags) in funcs.iter() {
+            self.generate_function_trait_sig(func, *flags - FunctionFlags::ASYNC);
+            self.push_str(";\n");
+        }
+        if let Some((drop, _)) = drop {
+            uwriteln!(self.src, "{drop};");
+        }
+        uwriteln!(self.src, "}}");
+
+        uwriteln!(
+            self.src,
+            "impl<_T: {trait_name} + ?Sized> {trait_name}Blocking for _T {{"
+        );
+        for (func, flags) in funcs.iter() {
+            self.generate_function_trait_sig(func, *flags - FunctionFlags::ASYNC);
+            uwrite!(self.src, "{{");
+            let mut call = format!("{trait_name}::{}(self,", rust_function_name(func));
+            for param in func.params.iter() {
+                uwrite!(call, "{},", to_rust_ident(&param.name));
             }
+            call.push(')');
+            if flags.contains(FunctionFlags::ASYNC) {
+                uwriteln!(self.src, "{block_on}({call})");
+            } else {
+                uwriteln!(self.src, "{call}");
+            }
+            uwriteln!(self.src, "}}");
         }
+        if let Some((drop, flags)) = drop {
+            let call = format!("{trait_name}::drop(self, rep)");
+            if flags.contains(FunctionFlags::ASYNC) {
+                uwriteln!(self.src, "{drop} {{ {block_on}({call}) }}");
+            } else {
+                uwriteln!(self.src, "{drop} {{ {call} }}");
+            }
+        }
+        uwriteln!(self.src, "}}");
     }
 
     fn import_resource_drop_flags(&mut self, name: &str) -> FunctionFlags {
@@ -2701,13 +3146,26 @@ pub fn add_to_linker<T, D>(
         )
     }
 
-    fn generate_add_function_to_linker(&mut self, owner: TypeOwner, func: &Function, linker: &str) {
+    fn generate_add_function_to_linker(
+        &mut self,
+        owner: TypeOwner,
+        func: &Function,
+        linker: &str,
+        blocking: bool,
+    ) {
         let flags = self.generator.opts.imports.flags(
             self.resolve,
             self.current_interface.map(|p| p.1),
             func,
         );
         self.all_func_flags |= flags;
+        // Blocking wrappers are registered as synchronous functions which call
+        // into the `*Blocking` traits.
+        let flags = if blocking {
+            flags - FunctionFlags::ASYNC
+        } else {
+            flags
+        };
         let gate = FeatureGate::open(&mut self.src, &func.stability);
         uwrite!(
             self.src,
@@ -2724,7 +3182,7 @@ pub fn add_to_linker<T, D>(
         if self.named_import_id.is_some() {
             self.src.push_str("{ let id = id.clone(); ");
         }
-        self.generate_guest_import_closure(owner, func, flags);
+        self.generate_guest_import_closure(owner, func, flags, blocking);
         if self.named_import_id.is_some() {
             self.src.push_str("}\n");
         }
@@ -2737,6 +3195,7 @@ pub fn add_to_linker<T, D>(
         owner: TypeOwner,
         func: &Function,
         flags: FunctionFlags,
+        blocking: bool,
     ) {
         // Generate the closure that's passed to a `Linker`, the final piece of
         // codegen here.
@@ -2859,6 +3318,11 @@ pub fn add_to_linker<T, D>(
                 format!("Host{resource}")
             }
         };
+        let host_trait = if blocking {
+            format!("{host_trait}Blocking")
+        } else {
+            host_trait
+        };
 
         if flags.contains(FunctionFlags::STORE) {
             uwrite!(
@@ -3180,6 +3644,133 @@ pub fn add_to_linker<T, D>(
         self.src.push_str("}\n");
     }
 
+    /// Generates the `Owned*` RAII wrapper for the exported resource `id`,
+    /// whose methods forward to the `call_*` functions of `Guest*` and whose
+    /// destructor drops the resource within the store it holds.
+    ///
+    /// The wrapper is only generated when all of the resource's functions
+    /// are synchronous since destructors can't be run asynchronously.
+    fn define_rust_guest_export_owned(
+        &mut self,
+        resolve: &Resolve,
+        ns: &WorldKey,
+        id: TypeId,
+        funcs: &[&Function],
+    ) {
+        for func in funcs {
+            let flags = self.generator.opts.exports.flags(resolve, Some(ns), func);
+            if flags.contains(FunctionFlags::ASYNC) {
+                return;
+            }
+        }
+        let wt = self.generator.wasmtime_path();
+        let camel = resolve.types[id]
+            .name
+            .as_ref()
+            .unwrap()
+            .to_upper_camel_case();
+
+        uwriteln!(
+            self.src,
+            "
+                impl<'a> Guest{camel}<'a> {{
+                    /// Wraps the owned `resource` in an [`Owned{camel}`] which
+                    /// drops it within `store` once it goes out of scope.
+                    pub fn owned<S: {wt}::AsContextMut>(
+                        &self,
+                        store: S,
+                        resource: {camel},
+                    ) -> Owned{camel}<'a, S> {{
+                        Owned{camel} {{
+                            funcs: self.funcs,
+                            store,
+                            resource: Some(resource),
+                        }}
+                    }}
+                }}
+
+                /// An owned [`{camel}`] along with the store it lives in.
+                ///
+                /// The resource is dropped, running the guest's destructor,
+                /// when this value goes out of scope. Use
+                /// [`Owned{camel}::resource_drop`] to observe errors of the
+                /// destructor instead.
+                pub struct Owned{camel}<'a, S: {wt}::AsContextMut> {{
+                    funcs: &'a Guest,
+                    store: S,
+                    resource: Option<{camel}>,
+                }}
+
+                impl<S: {wt}::AsContextMut> Owned{camel}<'_, S> {{
+                    /// Returns the underlying resource handle.
+                    pub fn resource(&self) -> {camel} {{
+                        self.resource.unwrap()
+                    }}
+
+                    /// Returns the store this resource lives in.
+                    pub fn store(&mut self) -> &mut S {{
+                        &mut self.store
+                    }}
+
+                    /// Releases ownership of the resource without dropping
+                    /// it, which is then up to the caller.
+                    pub fn into_resource(mut self) -> {camel} {{
+                        self.resource.take().unwrap()
+                    }}
+
+                    /// Drops the resource, returning any error raised by its
+                    /// destructor.
+                    pub fn resource_drop(mut self) -> {wt}::Result<()> {{
+                        self.resource.take().unwrap().resource_drop(&mut self.store)
+                    }}
+            "
+        );
+
+        for func in funcs {
+            if !matches!(
+                func.kind,
+                FunctionKind::Method(_) | FunctionKind::AsyncMethod(_)
+            ) {
+                continue;
+            }
+            let snake = func.item_name().to_snake_case();
+            self.rustdoc(&func.docs);
+            uwrite!(self.src, "pub fn {snake}(&mut self, ");
+            for (i, param) in func.params.iter().enumerate().skip(1) {
+                uwrite!(self.src, "arg{i}: ");
+                self.print_ty(&param.ty, TypeMode::AllBorrowed("'_"));
+                self.push_str(",");
+            }
+            uwrite!(self.src, ") -> {wt}::Result<");
+            self.print_result_ty(func.result, TypeMode::Owned);
+            uwrite!(
+                self.src,
+                "> {{
+                    let funcs = Guest{camel} {{ funcs: self.funcs }};
+                    let resource = self.resource();
+                    funcs.call_{snake}(&mut self.store, resource, "
+            );
+            for i in 1..func.params.len() {
+                uwrite!(self.src, "arg{i}, ");
+            }
+            uwriteln!(self.src, ")\n}}");
+        }
+        uwriteln!(self.src, "}}");
+
+        uwriteln!(
+            self.src,
+            "
+                impl<S: {wt}::AsContextMut> Drop for Owned{camel}<'_, S> {{
+                    fn drop(&mut self) {{
+                        if let Some(resource) = self.resource.take() {{
+                            let _ = resource.resource_drop(&mut self.store);
+                        }}
+                    }}
+                }}
+            "
+        );
+    }
+
     fn rustdoc(&mut self, docs: &Docs) {
         let docs = match &docs.contents {
             Some(docs) => docs,
@@ -0,0 +1,270 @@
+#![no_main]
+
+//! Generates Cranelift functions and checks that they produce the same results
+//! when compiled to Pulley bytecode and run in the Pulley interpreter as when
+//! compiled to native code for the host.
+//!
+//! Functions are generated for the host, so that native results can always be
+//! compared against, and test cases using features that Pulley doesn't support
+//! yet are skipped. Inputs are first run in the Cranelift interpreter, which
+//! filters out those that trap or don't terminate.
+
+use cranelift_codegen::CodegenError;
+use cranelift_codegen::data_value::DataValue;
+use cranelift_codegen::ir::{ExternalName, Function, Signature, UserExternalName, UserFuncName};
+use cranelift_codegen::isa;
+use cranelift_codegen::settings::Configurable;
+use cranelift_control::ControlPlane;
+use cranelift_filetests::function_runner::{CompiledTestFile, TestFileCompiler};
+use cranelift_fuzzgen::*;
+use cranelift_interpreter::environment::{FuncIndex, FunctionStore};
+use cranelift_interpreter::interpreter::{Interpreter, InterpreterError, InterpreterState};
+use cranelift_interpreter::step::ControlFlow;
+use cranelift_native::builder_with_options;
+use libfuzzer_sys::arbitrary::{self, Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const INTERPRETER_FUEL: u64 = 4096;
+
+/// The Pulley target matching the pointer width and endianness of the host,
+/// which is required to run Pulley bytecode in-process.
+const PULLEY_TARGET: &str = match (
+    cfg!(target_pointer_width = "64"),
+    cfg!(target_endian = "little"),
+) {
+    (true, true) => "pulley64",
+    (true, false) => "pulley64be",
+    (false, true) => "pulley32",
+    (false, false) => "pulley32be",
+};
+
+/// Gather statistics about the fuzzer executions
+struct Statistics {
+    /// Inputs that fuzzgen can build a function with.
+    valid_inputs: AtomicU64,
+    /// Test cases using a feature not supported by Pulley.
+    unsupported: AtomicU64,
+    /// Runs compared between both backends.
+    compared_runs: AtomicU64,
+    /// Runs skipped because they trapped or timed out in the interpreter.
+    skipped_runs: AtomicU64,
+}
+
+impl Statistics {
+    fn print(&self, valid_inputs: u64) {
+        println!("== Pulley FuzzGen Statistics ====================");
+        println!("Valid Inputs: {valid_inputs}");
+        println!(
+            "Unsupported Inputs: {}",
+            self.unsupported.load(Ordering::SeqCst)
+        );
+        println!(
+            "Compared Runs: {}",
+            self.compared_runs.load(Ordering::SeqCst)
+        );
+        println!("Skipped Runs: {}", self.skipped_runs.load(Ordering::SeqCst));
+    }
+}
+
+static STATISTICS: Statistics = Statistics {
+    valid_inputs: AtomicU64::new(0),
+    unsupported: AtomicU64::new(0),
+    compared_runs: AtomicU64::new(0),
+    skipped_runs: AtomicU64::new(0),
+};
+
+pub struct TestCase {
+    /// TargetIsa of the host.
+    pub native: isa::OwnedTargetIsa,
+    /// Pulley TargetIsa with the same shared flags as `native`.
+    pub pulley: isa::OwnedTargetIsa,
+    /// Functions under test
+    /// By convention the first function is the main function.
+    pub functions: Vec<Function>,
+    /// The same functions, as compiled for Pulley.
+    pub pulley_functions: Vec<Function>,
+    /// Control planes for function compilation, one per function.
+    pub ctrl_planes: Vec<ControlPlane>,
+    /// Inputs to run the main function with.
+    pub inputs: Vec<TestCaseInput>,
+}
+
+impl fmt::Debug for TestCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, ";; Compared against {}", self.native.triple())?;
+        PrintableTestCase::run(&self.pulley, &self.pulley_functions, &self.inputs).fmt(f)
+    }
+}
+
+impl<'a> Arbitrary<'a> for TestCase {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let _ = env_logger::try_init();
+        Self::generate(u).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+impl TestCase {
+    pub fn generate(u: &mut Unstructured) -> anyhow::Result<Self> {
+        let mut generator = FuzzGen::new(u);
+
+        // Shared flags are generated for the host so that they're all
+        // supported there, Pulley ignores those which don't apply to it.
+        let mut builder =
+            builder_with_options(true).expect("Unable to build a TargetIsa for the current host");
+        let flags = generator.generate_flags(builder.triple().architecture)?;
+        generator.set_isa_flags(&mut builder, IsaFlagGen::Host)?;
+        let native = builder.finish(flags.clone())?;
+
+        let mut builder = isa::lookup_by_name(PULLEY_TARGET)?;
+        if bool::arbitrary(generator.u)? {
+            builder.enable("enable_peephole")?;
+        }
+        let pulley = builder.finish(flags)?;
+
+        // As in `cranelift-fuzzgen` each function may only call those
+        // generated before it. Libcalls aren't supported as Pulley would need
+        // to call native code for them.
+        let func_count = generator
+            .u
+            .int_in_range(generator.config.testcase_funcs.clone())?;
+        let mut functions: Vec<Function> = Vec::with_capacity(func_count);
+        let mut ctrl_planes: Vec<ControlPlane> = Vec::with_capacity(func_count);
+        for i in (0..func_count).rev() {
+            let fname = UserFuncName::user(1, i as u32);
+            let usercalls: Vec<(UserExternalName, Signature)> = functions
+                .iter()
+                .map(|f| {
+                    (
+                        f.name.get_user().unwrap().clone(),
+                        f.stencil.signature.clone(),
+                    )
+                })
+                .collect();
+            let func = generator.generate_func(fname, native.clone(), usercalls, Vec::new())?;
+            functions.push(func);
+            ctrl_planes.push(ControlPlane::arbitrary(generator.u)?);
+        }
+        functions.reverse();
+
+        // Pulley lowers calls to functions which aren't colocated as calls to
+        // native host functions, so calls between the functions under test
+        // must be colocated there. They can't be on the host as cranelift-jit
+        // puts every function in its own mmap.
+        let mut pulley_functions = functions.clone();
+        for func in pulley_functions.iter_mut() {
+            for ext_func in func.dfg.ext_funcs.values_mut() {
+                if let ExternalName::User(_) = ext_func.name {
+                    ext_func.colocated = true;
+                }
+            }
+        }
+
+        let inputs = generator.generate_test_inputs(&functions[0].signature)?;
+
+        Ok(TestCase {
+            native,
+            pulley,
+            functions,
+            pulley_functions,
+            ctrl_planes,
+            inputs,
+        })
+    }
+
+    /// Compiles `functions` for `isa`, returning `None` if they use a feature
+    /// the backend doesn't support yet.
+    fn compile(
+        &self,
+        isa: &isa::OwnedTargetIsa,
+        functions: &[Function],
+    ) -> Option<CompiledTestFile> {
+        let mut compiler = TestFileCompiler::new(isa.clone());
+        if let Err(e) = compiler.add_functions(functions, self.ctrl_planes.clone()) {
+            let unsupported = e.chain().any(|e| {
+                matches!(
+                    e.downcast_ref::<CodegenError>(),
+                    Some(CodegenError::Unsupported(_))
+                )
+            });
+            if unsupported {
+                log::debug!("unsupported on {}: {e:?}", isa.triple());
+                return None;
+            }
+            panic!("failed to compile for {}: {e:?}", isa.triple());
+        }
+        Some(compiler.compile().unwrap())
+    }
+}
+
+/// Runs the main function of `testcase` in the Cranelift interpreter,
+/// returning `None` if it traps or runs out of fuel.
+fn run_in_interpreter(testcase: &TestCase, args: &[DataValue]) -> Option<Vec<DataValue>> {
+    let mut env = FunctionStore::default();
+    for func in testcase.functions.iter() {
+        env.add(func.name.to_string(), func);
+    }
+    let state = InterpreterState::default().with_function_store(env);
+    let mut interpreter = Interpreter::new(state).with_fuel(Some(INTERPRETER_FUEL));
+    match interpreter.call_by_index(FuncIndex::from_u32(0), args) {
+        Ok(ControlFlow::Return(results)) => Some(results.to_vec()),
+        Ok(ControlFlow::Trap(_)) | Err(InterpreterError::FuelExhausted) => None,
+        Ok(cf) => panic!("Unrecognized exit ControlFlow: {cf:?}"),
+        Err(e) => panic!("interpreter failed: {e:?}"),
+    }
+}
+
+/// Compares results bitwise, which is sound for floats as NaNs are
+/// canonicalized by both the generated functions and the compiler.
+fn same_results(a: &[DataValue], b: &[DataValue]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.bitwise_eq(b))
+}
+
+fuzz_target!(|testcase: TestCase| {
+    // Differential results are only meaningful if NaNs are canonicalized.
+    assert!(testcase.native.flags().enable_nan_canonicalization());
+    assert!(testcase.pulley.flags().enable_nan_canonicalization());
+    assert!(testcase.pulley.flags().enable_verifier());
+
+    let valid_inputs = STATISTICS.valid_inputs.fetch_add(1, Ordering::SeqCst);
+    if valid_inputs != 0 && valid_inputs % 10000 == 0 {
+        STATISTICS.print(valid_inputs);
+    }
+
+    let Some(pulley) = testcase.compile(&testcase.pulley, &testcase.pulley_functions) else {
+        STATISTICS.unsupported.fetch_add(1, Ordering::SeqCst);
+        return;
+    };
+    let native = testcase
+        .compile(&testcase.native, &testcase.functions)
+        .expect("functions are generated for the host");
+    let native_trampoline = native.get_trampoline(&testcase.functions[0]).unwrap();
+    let pulley_trampoline = pulley
+        .get_trampoline(&testcase.pulley_functions[0])
+        .unwrap();
+
+    for args in &testcase.inputs {
+        let Some(expected) = run_in_interpreter(&testcase, args) else {
+            STATISTICS.skipped_runs.fetch_add(1, Ordering::SeqCst);
+            continue;
+        };
+        STATISTICS.compared_runs.fetch_add(1, Ordering::SeqCst);
+
+        let native_results = native_trampoline.call(&native, args);
+        let pulley_results = pulley_trampoline.call(&pulley, args);
+        assert!(
+            same_results(&native_results, &pulley_results),
+            "native and Pulley results differ for {args:?}:\n\
+             native: {native_results:?}\n\
+             pulley: {pulley_results:?}\n\
+             interpreter: {expected:?}"
+        );
+        assert!(
+            same_results(&expected, &pulley_results),
+            "interpreter and Pulley results differ for {args:?}:\n\
+             interpreter: {expected:?}\n\
+             pulley: {pulley_results:?}"
+        );
+    }
+});
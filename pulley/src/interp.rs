@@ -5,29 +5,42 @@ use crate::encode::Encode;
 use crate::imms::*;
 use crate::profile::{ExecutingPc, ExecutingPcRef};
 use crate::regs::*;
+use alloc::boxed::Box;
 use alloc::string::ToString;
 use core::fmt;
+use core::marker;
 use core::mem;
 use core::ops::ControlFlow;
 use core::ops::{Index, IndexMut};
 use core::ptr::NonNull;
+use core::sync::atomic::{self, Ordering::*};
 use pulley_macros::interp_disable_if_cfg;
-use wasmtime_core::alloc::TryVec;
+use wasmtime_core::alloc::{TryVec, try_new};
 use wasmtime_core::error::OutOfMemory;
 use wasmtime_core::math::{WasmFloat, f32_cvt_to_int_bounds, f64_cvt_to_int_bounds};
 
+mod checkpoint;
 mod debug;
+mod host_call;
 #[cfg(all(not(pulley_tail_calls), not(pulley_assume_llvm_makes_tail_calls)))]
 mod match_loop;
 #[cfg(any(pulley_tail_calls, pulley_assume_llvm_makes_tail_calls))]
 mod tail_loop;
 
+pub use checkpoint::Checkpoint;
+use host_call::RegisteredHostCall;
+pub use host_call::{HostCallReturn, HostCallSignature, HostCallValue, HostFn};
+
 const DEFAULT_STACK_SIZE: usize = 1 << 20; // 1 MiB
 
+/// The minimum number of bytes a [`StackMemory`]-backed stack grows by at once.
+const MIN_STACK_GROWTH: usize = 64 << 10; // 64 KiB
+
 /// A virtual machine for interpreting Pulley bytecode.
 pub struct Vm {
     state: MachineState,
     executing_pc: ExecutingPc,
+    host_calls: TryVec<Option<RegisteredHostCall>>,
 }
 
 impl Vm {
@@ -41,9 +54,24 @@ impl Vm {
         Ok(Self {
             state: MachineState::with_stack(stack_size)?,
             executing_pc: ExecutingPc::default(),
+            host_calls: TryVec::new(),
         })
     }
 
+    /// Create a new virtual machine whose stack lives in `memory`.
+    ///
+    /// Unlike [`Vm::with_stack`] the stack isn't made usable all at once.
+    /// Instead it grows on demand, through [`StackMemory::commit`], as bytecode
+    /// uses more of it. Stack overflow is only raised once the entire region
+    /// reserved by `memory` is in use, or if committing more of it fails.
+    pub fn with_stack_memory(memory: Box<dyn StackMemory>) -> Self {
+        Self {
+            state: MachineState::new(Stack::with_memory(memory)),
+            executing_pc: ExecutingPc::default(),
+            host_calls: TryVec::new(),
+        }
+    }
+
     /// Get a shared reference to this VM's machine state.
     pub fn state(&self) -> &MachineState {
         &self.state
@@ -54,6 +82,51 @@ impl Vm {
         &mut self.state
     }
 
+    /// Registers `func` to handle `call_indirect_host` instructions with the
+    /// given `id`.
+    ///
+    /// When bytecode executes `call_indirect_host` with this `id` the
+    /// interpreter calls `func` directly instead of stopping with
+    /// [`DoneReason::CallIndirectHost`], then resumes execution after the
+    /// instruction. The function's arguments are read from, and its result
+    /// written to, registers according to Pulley's calling convention as
+    /// described by [`HostCallValue`], meaning that `call_indirect_host` can
+    /// be treated by the bytecode producer like a `call` to a function with
+    /// the signature [`HostFn::SIGNATURE`].
+    ///
+    /// Any function previously registered with `id` is replaced.
+    pub fn register_host_call<F, P, R>(&mut self, id: u8, mut func: F) -> Result<(), OutOfMemory>
+    where
+        F: HostFn<P, R>,
+    {
+        let signature = F::SIGNATURE;
+        let func: Box<_> = try_new(move |state: &mut MachineState| func.call(state))?;
+        let index = usize::from(id);
+        if self.host_calls.len() <= index {
+            self.host_calls.resize_with(index + 1, || None)?;
+        }
+        self.host_calls[index] = Some(RegisteredHostCall { signature, func });
+        Ok(())
+    }
+
+    /// Removes the host call registered with [`Vm::register_host_call`] for
+    /// `id`, returning whether there was one.
+    ///
+    /// Afterwards `call_indirect_host` instructions with this `id` stop
+    /// execution with [`DoneReason::CallIndirectHost`] again.
+    pub fn unregister_host_call(&mut self, id: u8) -> bool {
+        self.host_calls
+            .get_mut(usize::from(id))
+            .and_then(|call| call.take())
+            .is_some()
+    }
+
+    /// Returns the signature of the host call registered for `id`, if any.
+    pub fn host_call_signature(&self, id: u8) -> Option<HostCallSignature> {
+        let call = self.host_calls.get(usize::from(id))?.as_ref()?;
+        Some(call.signature)
+    }
+
     /// Call a bytecode function.
     ///
     /// The given `func` must point to the beginning of a valid Pulley bytecode
@@ -140,15 +213,31 @@ impl Vm {
     /// In addition to all the invariants documented for `call`, you
     /// may only invoke `call_run` after invoking `call_start` to
     /// initialize this call's arguments.
-    pub unsafe fn call_run(&mut self, pc: NonNull<u8>) -> DoneReason<()> {
-        self.state.debug_assert_done_reason_none();
-        let interpreter = Interpreter {
-            state: &mut self.state,
-            pc: unsafe { UnsafeBytecodeStream::new(pc) },
-            executing_pc: self.executing_pc.as_ref(),
-        };
-        let done = interpreter.run();
-        self.state.done_decode(done)
+    pub unsafe fn call_run(&mut self, mut pc: NonNull<u8>) -> DoneReason<()> {
+        loop {
+            self.state.debug_assert_done_reason_none();
+            let interpreter = Interpreter {
+                state: &mut self.state,
+                pc: unsafe { UnsafeBytecodeStream::new(pc) },
+                executing_pc: self.executing_pc.as_ref(),
+            };
+            let done = interpreter.run();
+            match self.state.done_decode(done) {
+                // Host calls registered with `register_host_call` are handled
+                // here, resuming execution afterwards, while all others are
+                // left to the embedder.
+                DoneReason::CallIndirectHost { id, resume } => {
+                    match self.host_calls.get_mut(usize::from(id)) {
+                        Some(Some(call)) => {
+                            (call.func)(&mut self.state);
+                            pc = resume;
+                        }
+                        _ => return DoneReason::CallIndirectHost { id, resume },
+                    }
+                }
+                done => return done,
+            }
+        }
     }
 
     /// Performs the tail end of [`Vm::call`] by returning the values as
@@ -214,6 +303,38 @@ impl Vm {
         self.state.lr = lr;
     }
 
+    /// Returns an iterator over the frames of bytecode functions on this VM's
+    /// stack, from youngest to oldest.
+    ///
+    /// This is intended to be used once execution has stopped, for example
+    /// with [`DoneReason::Trap`], to produce a backtrace of the bytecode that
+    /// was executing. The `pc` provided is the program counter of the
+    /// youngest frame, such as the `pc` of [`DoneReason::Trap`]. All older
+    /// frames are found by following the saved `fp`/`lr` pairs pushed by
+    /// `push_frame` and `push_frame_save`, meaning that their program counters
+    /// are return addresses pointing just after the call instruction in that
+    /// frame.
+    ///
+    /// Iteration stops at the frame which was called from the host, i.e. the
+    /// oldest frame of the current call into this VM.
+    ///
+    /// # Unsafety
+    ///
+    /// This requires that the VM's current `fp` register points to the frame
+    /// of the function containing `pc`, that is, the youngest function has
+    /// already executed its `push_frame`. Every older bytecode function on the
+    /// stack must have set up a frame as well. If these invariants are not
+    /// upheld then iteration may read arbitrary memory.
+    pub unsafe fn frames(&self, pc: NonNull<u8>) -> Frames<'_> {
+        Frames {
+            next: Some(Frame {
+                pc,
+                fp: self.state.fp,
+            }),
+            _vm: marker::PhantomData,
+        }
+    }
+
     /// Gets a handle to the currently executing program counter for this
     /// interpreter which can be read from other threads.
     //
@@ -232,8 +353,73 @@ impl Drop for Vm {
     }
 }
 
+/// A frame of a bytecode function on a [`Vm`]'s stack, as yielded by
+/// [`Vm::frames`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pc: NonNull<u8>,
+    fp: *mut u8,
+}
+
+impl Frame {
+    /// Returns the program counter that this frame is executing at.
+    ///
+    /// For all frames but the youngest this is the return address of the call
+    /// into the next younger frame.
+    pub fn pc(&self) -> NonNull<u8> {
+        self.pc
+    }
+
+    /// Returns this frame's frame pointer.
+    ///
+    /// This points to the saved `fp` of the next older frame, and the saved
+    /// return address is stored just above it.
+    pub fn fp(&self) -> *mut u8 {
+        self.fp
+    }
+}
+
+/// An iterator over the frames on a [`Vm`]'s stack, created with
+/// [`Vm::frames`].
+pub struct Frames<'a> {
+    next: Option<Frame>,
+    _vm: marker::PhantomData<&'a Vm>,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        let frame = self.next.take()?;
+
+        // If no frame has been pushed at all then there's nothing older to
+        // find.
+        if frame.fp == HOST_RETURN_ADDR {
+            return Some(frame);
+        }
+
+        // SAFETY: `Vm::frames` requires that `fp` points to a frame created
+        // by `push_frame`, which contains the older `fp` followed by the
+        // return address. The lifetime of this iterator additionally prevents
+        // the VM from executing while frames are being walked.
+        let (older_fp, lr) = unsafe {
+            let fp = frame.fp.cast::<*mut u8>();
+            (*fp, *fp.add(1))
+        };
+
+        // The sentinel return address indicates that this frame was called by
+        // the host, meaning it's the oldest one.
+        if lr != HOST_RETURN_ADDR {
+            if let Some(pc) = NonNull::new(lr) {
+                self.next = Some(Frame { pc, fp: older_fp });
+            }
+        }
+        Some(frame)
+    }
+}
+
 /// The type of a register in the Pulley machine state.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RegType {
     /// An `x` register: integers.
     XReg,
@@ -750,6 +936,41 @@ pub struct MachineState {
 unsafe impl Send for MachineState {}
 unsafe impl Sync for MachineState {}
 
+/// A contiguous region of memory, reserved up front, which backs a [`Vm`]'s
+/// stack and is made usable on demand.
+///
+/// Pulley's stack grows down, from the top of the reserved region towards its
+/// base, and it can't be moved once execution has started as bytecode is free
+/// to hold pointers into it. Embedders can implement this trait to reserve the
+/// maximum size of the stack ahead of time, for example as inaccessible
+/// virtual memory, and only make it accessible once the interpreter needs it.
+///
+/// # Safety
+///
+/// The values returned by [`StackMemory::base`] and [`StackMemory::len`] must
+/// not change for the lifetime of this object. The base must be 16-byte
+/// aligned and the length must be a multiple of 16.
+///
+/// After [`StackMemory::commit`] returns `Ok(n)` the top `n` bytes of the
+/// region must be valid for reads and writes, and remain so for the lifetime
+/// of this object.
+pub unsafe trait StackMemory: Send + Sync {
+    /// Returns a pointer to the base of the reserved region (its lowest
+    /// address).
+    fn base(&self) -> NonNull<u8>;
+
+    /// Returns the size, in bytes, of the reserved region.
+    fn len(&self) -> usize;
+
+    /// Makes at least the top `size` bytes of the reserved region usable.
+    ///
+    /// The `size` requested is never larger than [`StackMemory::len`]. Returns
+    /// the number of bytes at the top of the region which are now usable,
+    /// which may be more than `size` if the embedder rounds up, for example to
+    /// a page size.
+    fn commit(&mut self, size: usize) -> Result<usize, OutOfMemory>;
+}
+
 /// Helper structure to store the state of the Pulley stack.
 ///
 /// The Pulley stack notably needs to be a 16-byte aligned allocation on the
@@ -757,7 +978,17 @@ unsafe impl Sync for MachineState {}
 /// done with a custom `Vec<T>` internally where `T` has size and align of 16.
 /// This is manually done with a helper `Align16` type below.
 struct Stack {
-    storage: TryVec<Align16>,
+    storage: StackStorage,
+    /// The lowest address of the stack that's currently usable. Moving the
+    /// stack pointer beneath this requires growing the stack.
+    limit: usize,
+}
+
+enum StackStorage {
+    /// A stack allocated in full when it's created.
+    Heap(TryVec<Align16>),
+    /// A stack reserved by the embedder and committed on demand.
+    Reserved(Box<dyn StackMemory>),
 }
 
 /// Helper type used with `Stack` above.
@@ -780,7 +1011,56 @@ impl Stack {
         // intentional as pulley bytecode should always initialize the stack
         // before use.
         storage.reserve_exact(size.checked_next_multiple_of(16).unwrap_or(usize::MAX) / 16)?;
-        Ok(Stack { storage })
+        let mut stack = Stack {
+            storage: StackStorage::Heap(storage),
+            limit: 0,
+        };
+        stack.limit = stack.base() as usize;
+        Ok(stack)
+    }
+
+    /// Creates a new stack backed by `memory`, none of which is usable yet.
+    fn with_memory(memory: Box<dyn StackMemory>) -> Stack {
+        let mut stack = Stack {
+            storage: StackStorage::Reserved(memory),
+            limit: 0,
+        };
+        stack.limit = stack.top() as usize;
+        stack
+    }
+
+    /// Attempts to grow this stack such that `sp` is usable.
+    ///
+    /// The stack is grown by at least double its current size to amortize the
+    /// cost of growth. Returns `false` if `sp` is beneath the base of this
+    /// stack, if this stack can't grow, or if committing memory failed.
+    #[cold]
+    fn grow(&mut self, sp: usize) -> bool {
+        let top = self.top() as usize;
+        let base = self.base() as usize;
+        let StackStorage::Reserved(memory) = &mut self.storage else {
+            return false;
+        };
+        if sp < base {
+            return false;
+        }
+        let needed = top - sp;
+        let committed = top - self.limit;
+        let size = needed
+            .max(committed.saturating_mul(2))
+            .max(MIN_STACK_GROWTH)
+            .min(memory.len());
+        match memory.commit(size) {
+            Ok(size) => {
+                debug_assert!(size >= needed && size <= memory.len());
+                self.limit = top - size;
+                true
+            }
+            Err(e) => {
+                log::trace!("failed to grow pulley stack to {size} bytes: {e}");
+                false
+            }
+        }
     }
 
     /// Returns a pointer to the top of the stack (the highest address).
@@ -797,12 +1077,21 @@ impl Stack {
     /// Note that the returned pointer has provenance for the entire stack
     /// allocation, however, not just the top.
     fn base(&mut self) -> *mut u8 {
-        self.storage.as_mut_ptr().cast::<u8>()
+        match &mut self.storage {
+            StackStorage::Heap(storage) => storage.as_mut_ptr().cast::<u8>(),
+            StackStorage::Reserved(memory) => memory.base().as_ptr(),
+        }
     }
 
     /// Returns the length, in bytes, of this stack allocation.
+    ///
+    /// For a stack which grows on demand this is the size of the entire
+    /// reservation, not just the part which is currently usable.
     fn len(&self) -> usize {
-        self.storage.capacity() * mem::size_of::<Align16>()
+        match &self.storage {
+            StackStorage::Heap(storage) => storage.capacity() * mem::size_of::<Align16>(),
+            StackStorage::Reserved(memory) => memory.len(),
+        }
     }
 }
 
@@ -892,12 +1181,16 @@ const HOST_RETURN_ADDR: *mut u8 = usize::MAX as *mut u8;
 
 impl MachineState {
     fn with_stack(stack_size: usize) -> Result<Self, OutOfMemory> {
+        Ok(Self::new(Stack::new(stack_size)?))
+    }
+
+    fn new(stack: Stack) -> Self {
         let mut state = Self {
             x_regs: [Default::default(); XReg::RANGE.end as usize],
             f_regs: Default::default(),
             #[cfg(not(pulley_disable_interp_simd))]
             v_regs: Default::default(),
-            stack: Stack::new(stack_size)?,
+            stack,
             done_reason: None,
             fp: HOST_RETURN_ADDR,
             lr: HOST_RETURN_ADDR,
@@ -906,7 +1199,7 @@ impl MachineState {
         let sp = state.stack.top();
         state[XReg::sp] = XRegVal::new_ptr(sp);
 
-        Ok(state)
+        state
     }
 }
 
@@ -934,7 +1227,9 @@ mod done {
             /// The kind of trap being raised, if known.
             kind: Option<TrapKind>,
         },
-        /// The `call_indirect_host` instruction was executed.
+        /// The `call_indirect_host` instruction was executed with an `id` that
+        /// has no function registered with
+        /// [`Vm::register_host_call`](super::Vm::register_host_call).
         CallIndirectHost {
             /// The payload of `call_indirect_host`.
             id: u8,
@@ -1048,8 +1343,7 @@ impl Interpreter<'_> {
     /// is generated.
     #[must_use]
     fn push<I: Encode, T>(&mut self, val: T) -> ControlFlow<Done> {
-        let new_sp = self.state[XReg::sp].get_ptr::<T>().wrapping_sub(1);
-        self.set_sp::<I>(new_sp.cast())?;
+        let new_sp = self.decrement_sp::<I>(size_of::<T>())?.cast::<T>();
         unsafe {
             new_sp.write_unaligned(val);
         }
@@ -1064,33 +1358,42 @@ impl Interpreter<'_> {
         val
     }
 
-    /// Sets the stack pointer to the `sp` provided.
+    /// Decrements the stack pointer by `amt` bytes, returning the new stack
+    /// pointer.
     ///
-    /// Returns a trap if this would result in stack overflow, or if `sp` is
-    /// beneath the base pointer of `self.state.stack`.
+    /// Returns a trap if this would result in stack overflow, or if the new
+    /// stack pointer is beneath the base pointer of `self.state.stack`. If the
+    /// stack is able to grow on demand then it's grown first, and a trap is
+    /// only returned if that fails. Note that the decrement itself is checked
+    /// as well since on 32-bit hosts a large `amt` could otherwise wrap around
+    /// the address space and look like an in-bounds stack pointer.
     ///
     /// The `I` parameter here is the instruction that is setting the stack
     /// pointer and is used to calculate this instruction's own `pc` if this
     /// instruction traps.
     #[must_use]
-    fn set_sp<I: Encode>(&mut self, sp: *mut u8) -> ControlFlow<Done> {
-        let sp_raw = sp as usize;
-        let base_raw = self.state.stack.base() as usize;
-        if sp_raw < base_raw {
-            return self.done_trap_kind::<I>(Some(TrapKind::StackOverflow));
+    fn decrement_sp<I: Encode>(&mut self, amt: usize) -> ControlFlow<Done, *mut u8> {
+        let sp = self.state[XReg::sp].get_ptr::<u8>();
+        let Some(sp_raw) = (sp as usize).checked_sub(amt) else {
+            self.done_trap_kind::<I>(Some(TrapKind::StackOverflow))?;
+            unreachable!();
+        };
+        if sp_raw < self.state.stack.limit && !self.state.stack.grow(sp_raw) {
+            self.done_trap_kind::<I>(Some(TrapKind::StackOverflow))?;
+            unreachable!();
         }
-        self.set_sp_unchecked(sp);
-        ControlFlow::Continue(())
+        let new_sp = sp.wrapping_sub(amt);
+        self.set_sp_unchecked(new_sp);
+        ControlFlow::Continue(new_sp)
     }
 
-    /// Same as `set_sp` but does not check to see if `sp` is in-bounds. Should
+    /// Same as `decrement_sp` but does not check to see if `sp` is in-bounds. Should
     /// only be used with stack increment operations such as `pop`.
     fn set_sp_unchecked<T>(&mut self, sp: *mut T) {
         if cfg!(debug_assertions) {
             let sp_raw = sp as usize;
-            let base = self.state.stack.base() as usize;
-            let end = base + self.state.stack.len();
-            assert!(base <= sp_raw && sp_raw <= end);
+            let end = self.state.stack.top() as usize;
+            assert!(self.state.stack.limit <= sp_raw && sp_raw <= end);
         }
         self.state[XReg::sp].set_ptr(sp);
     }
@@ -1134,6 +1437,91 @@ impl Interpreter<'_> {
         unsafe { addr.store_ne::<T, I>(self, val) }
     }
 
+    /// Same as [`Interpreter::load_ne`] except that the address must be
+    /// naturally aligned for `T`.
+    ///
+    /// # Unsafety
+    ///
+    /// Same as [`Interpreter::load_ne`], and loading from an unaligned address
+    /// is undefined behavior.
+    #[must_use]
+    unsafe fn load_ne_aligned<T, I: Encode>(
+        &mut self,
+        addr: impl AddressingMode,
+    ) -> ControlFlow<Done, T> {
+        unsafe { addr.load_ne_aligned::<T, I>(self) }
+    }
+
+    /// Same as [`Interpreter::store_ne`] except that the address must be
+    /// naturally aligned for `T`.
+    ///
+    /// # Unsafety
+    ///
+    /// Same as [`Interpreter::store_ne`], and storing to an unaligned address
+    /// is undefined behavior.
+    #[must_use]
+    unsafe fn store_ne_aligned<T, I: Encode>(
+        &mut self,
+        addr: impl AddressingMode,
+        val: T,
+    ) -> ControlFlow<Done> {
+        unsafe { addr.store_ne_aligned::<T, I>(self, val) }
+    }
+
+    /// Atomically loads a `T` from the `addr` specified.
+    ///
+    /// Like [`Interpreter::load_ne`] the `I` type parameter is the instruction
+    /// issuing this load, used in case of traps. The address must be naturally
+    /// aligned for `T`.
+    #[must_use]
+    unsafe fn atomic_load<T: AtomicInt, I: Encode>(&mut self, addr: AddrZ) -> ControlFlow<Done, T> {
+        let ptr = unsafe { addr.addr::<T, I>(self)? };
+        ControlFlow::Continue(unsafe { T::atomic_load(ptr) })
+    }
+
+    /// Atomically stores `val` to the `addr` specified.
+    ///
+    /// See [`Interpreter::atomic_load`] for more information.
+    #[must_use]
+    unsafe fn atomic_store<T: AtomicInt, I: Encode>(
+        &mut self,
+        addr: AddrZ,
+        val: T,
+    ) -> ControlFlow<Done> {
+        let ptr = unsafe { addr.addr::<T, I>(self)? };
+        unsafe { T::atomic_store(ptr, val) };
+        ControlFlow::Continue(())
+    }
+
+    /// Atomically replaces the `T` at `addr` with `f` applied to it, returning
+    /// the previous value.
+    ///
+    /// See [`Interpreter::atomic_load`] for more information.
+    #[must_use]
+    unsafe fn atomic_rmw<T: AtomicInt, I: Encode>(
+        &mut self,
+        addr: AddrZ,
+        f: impl Fn(T) -> T,
+    ) -> ControlFlow<Done, T> {
+        let ptr = unsafe { addr.addr::<T, I>(self)? };
+        ControlFlow::Continue(unsafe { T::atomic_rmw(ptr, f) })
+    }
+
+    /// Atomically replaces the `T` at `addr` with `replacement` if it's equal
+    /// to `expected`, returning the previous value.
+    ///
+    /// See [`Interpreter::atomic_load`] for more information.
+    #[must_use]
+    unsafe fn atomic_cmpxchg<T: AtomicInt, I: Encode>(
+        &mut self,
+        addr: AddrZ,
+        expected: T,
+        replacement: T,
+    ) -> ControlFlow<Done, T> {
+        let ptr = unsafe { addr.addr::<T, I>(self)? };
+        ControlFlow::Continue(unsafe { T::atomic_cmpxchg(ptr, expected, replacement) })
+    }
+
     fn check_xnn_from_f32<I: Encode>(
         &mut self,
         val: f32,
@@ -1194,8 +1582,8 @@ trait AddressingMode: Sized {
     /// For more information see [`Interpreter::load_ne`].
     #[must_use]
     unsafe fn load_ne<T, I: Encode>(self, i: &mut Interpreter<'_>) -> ControlFlow<Done, T> {
-        let ret = unsafe { self.addr::<T, I>(i)?.read_unaligned() };
-        ControlFlow::Continue(ret)
+        let ptr = unsafe { self.addr::<T, I>(i)? };
+        ControlFlow::Continue(unsafe { racy_load(ptr, racy_width::<T>(ptr as usize)) })
     }
 
     /// Stores a `val` to this address, using native-endian byte order.
@@ -1203,10 +1591,125 @@ trait AddressingMode: Sized {
     /// For more information see [`Interpreter::store_ne`].
     #[must_use]
     unsafe fn store_ne<T, I: Encode>(self, i: &mut Interpreter<'_>, val: T) -> ControlFlow<Done> {
+        let ptr = unsafe { self.addr::<T, I>(i)? };
+        unsafe { racy_store(ptr, val, racy_width::<T>(ptr as usize)) };
+        ControlFlow::Continue(())
+    }
+
+    /// Same as [`AddressingMode::load_ne`] but for naturally aligned
+    /// addresses.
+    ///
+    /// For more information see [`Interpreter::load_ne_aligned`].
+    #[must_use]
+    unsafe fn load_ne_aligned<T, I: Encode>(self, i: &mut Interpreter<'_>) -> ControlFlow<Done, T> {
+        let ptr = unsafe { self.addr::<T, I>(i)? };
+        ControlFlow::Continue(unsafe { racy_load(ptr, racy_width::<T>(0)) })
+    }
+
+    /// Same as [`AddressingMode::store_ne`] but for naturally aligned
+    /// addresses.
+    ///
+    /// For more information see [`Interpreter::store_ne_aligned`].
+    #[must_use]
+    unsafe fn store_ne_aligned<T, I: Encode>(
+        self,
+        i: &mut Interpreter<'_>,
+        val: T,
+    ) -> ControlFlow<Done> {
+        let ptr = unsafe { self.addr::<T, I>(i)? };
+        unsafe { racy_store(ptr, val, racy_width::<T>(0)) };
+        ControlFlow::Continue(())
+    }
+}
+
+/// Widest access, in bytes, performed by [`racy_load`] and [`racy_store`].
+#[cfg(target_has_atomic = "64")]
+const MAX_RACY_WIDTH: usize = 8;
+#[cfg(not(target_has_atomic = "64"))]
+const MAX_RACY_WIDTH: usize = 4;
+
+/// Returns the width of the individual accesses used to access a `T` at
+/// `addr` with [`racy_load`] or [`racy_store`].
+///
+/// This is the largest power of two, up to [`MAX_RACY_WIDTH`], which both
+/// the size of `T` and `addr` are multiples of. Naturally aligned accesses
+/// pass an `addr` of zero so that this is a constant.
+#[inline(always)]
+fn racy_width<T>(addr: usize) -> usize {
+    1 << (addr | mem::size_of::<T>() | MAX_RACY_WIDTH).trailing_zeros()
+}
+
+/// Loads a `T` from `ptr` with relaxed atomic accesses of `width` bytes each.
+///
+/// Memory accessed by bytecode may be a shared wasm memory which other threads
+/// concurrently access, and plain loads and stores racing with those would be
+/// undefined behavior in Rust. All memory accesses performed on behalf of
+/// bytecode therefore use atomic accesses instead, which on most hosts compile
+/// to the same instructions as plain accesses. Accesses which are wider than
+/// [`MAX_RACY_WIDTH`] or not naturally aligned are split into several atomic
+/// accesses, and may tear, which wasm permits for its non-atomic accesses.
+///
+/// # Unsafety
+///
+/// `ptr` must be valid for reads of a `T` and be a multiple of `width`, which
+/// must come from [`racy_width`].
+#[inline(always)]
+unsafe fn racy_load<T>(ptr: *const T, width: usize) -> T {
+    let src = ptr.cast::<u8>().cast_mut();
+    let mut ret = mem::MaybeUninit::<T>::uninit();
+    let dst = ret.as_mut_ptr().cast::<u8>();
+    for offset in (0..mem::size_of::<T>()).step_by(width) {
         unsafe {
-            self.addr::<T, I>(i)?.write_unaligned(val);
+            let src = src.add(offset);
+            let dst = dst.add(offset);
+            match width {
+                1 => dst.write(atomic::AtomicU8::from_ptr(src).load(Relaxed)),
+                2 => dst
+                    .cast::<u16>()
+                    .write_unaligned(atomic::AtomicU16::from_ptr(src.cast()).load(Relaxed)),
+                4 => dst
+                    .cast::<u32>()
+                    .write_unaligned(atomic::AtomicU32::from_ptr(src.cast()).load(Relaxed)),
+                #[cfg(target_has_atomic = "64")]
+                8 => dst
+                    .cast::<u64>()
+                    .write_unaligned(atomic::AtomicU64::from_ptr(src.cast()).load(Relaxed)),
+                _ => unreachable!(),
+            }
+        }
+    }
+    unsafe { ret.assume_init() }
+}
+
+/// Stores `val` to `ptr` with relaxed atomic accesses of `width` bytes each.
+///
+/// See [`racy_load`] for more information.
+///
+/// # Unsafety
+///
+/// `ptr` must be valid for writes of a `T` and be a multiple of `width`, which
+/// must come from [`racy_width`].
+#[inline(always)]
+unsafe fn racy_store<T>(ptr: *mut T, val: T, width: usize) {
+    let val = mem::ManuallyDrop::new(val);
+    let src = (&raw const *val).cast::<u8>();
+    let dst = ptr.cast::<u8>();
+    for offset in (0..mem::size_of::<T>()).step_by(width) {
+        unsafe {
+            let src = src.add(offset);
+            let dst = dst.add(offset);
+            match width {
+                1 => atomic::AtomicU8::from_ptr(dst).store(src.read(), Relaxed),
+                2 => atomic::AtomicU16::from_ptr(dst.cast())
+                    .store(src.cast::<u16>().read_unaligned(), Relaxed),
+                4 => atomic::AtomicU32::from_ptr(dst.cast())
+                    .store(src.cast::<u32>().read_unaligned(), Relaxed),
+                #[cfg(target_has_atomic = "64")]
+                8 => atomic::AtomicU64::from_ptr(dst.cast())
+                    .store(src.cast::<u64>().read_unaligned(), Relaxed),
+                _ => unreachable!(),
+            }
         }
-        ControlFlow::Continue(())
     }
 }
 
@@ -1266,10 +1769,13 @@ impl AddressingMode for AddrG32 {
 impl AddressingMode for AddrG32Bne {
     unsafe fn addr<T, I: Encode>(self, i: &mut Interpreter<'_>) -> ControlFlow<Done, *mut T> {
         // Same as `AddrG32` above except that the bound is loaded from memory.
+        // The bound of a shared memory may be concurrently updated as it
+        // grows, so it's loaded atomically.
         let bound = unsafe {
-            *i.state[self.host_heap_bound_addr]
+            let ptr = i.state[self.host_heap_bound_addr]
                 .get_ptr::<usize>()
-                .byte_add(usize::from(self.host_heap_bound_offset))
+                .byte_add(usize::from(self.host_heap_bound_offset));
+            atomic::AtomicUsize::from_ptr(ptr).load(Relaxed)
         };
         let wasm_addr = i.state[self.wasm_addr].get_u32() as usize;
         let offset = usize::from(self.offset);
@@ -1287,6 +1793,116 @@ impl AddressingMode for AddrG32Bne {
     }
 }
 
+/// Helper trait for the integer types that Pulley's atomic instructions
+/// operate on.
+///
+/// All operations here are sequentially consistent and operate on
+/// little-endian values in memory, and `ptr` must be valid and naturally
+/// aligned.
+trait AtomicInt: Copy {
+    unsafe fn atomic_load(ptr: *mut Self) -> Self;
+    unsafe fn atomic_store(ptr: *mut Self, val: Self);
+    unsafe fn atomic_rmw(ptr: *mut Self, f: impl Fn(Self) -> Self) -> Self;
+    unsafe fn atomic_cmpxchg(ptr: *mut Self, expected: Self, replacement: Self) -> Self;
+}
+
+macro_rules! atomic_int {
+    ($($ty:ident = $atomic:ident,)*) => {$(
+        impl AtomicInt for $ty {
+            unsafe fn atomic_load(ptr: *mut $ty) -> $ty {
+                let atomic = unsafe { atomic::$atomic::from_ptr(ptr) };
+                $ty::from_le(atomic.load(SeqCst))
+            }
+
+            unsafe fn atomic_store(ptr: *mut $ty, val: $ty) {
+                let atomic = unsafe { atomic::$atomic::from_ptr(ptr) };
+                atomic.store(val.to_le(), SeqCst);
+            }
+
+            unsafe fn atomic_rmw(ptr: *mut $ty, f: impl Fn($ty) -> $ty) -> $ty {
+                let atomic = unsafe { atomic::$atomic::from_ptr(ptr) };
+                let old = atomic.fetch_update(SeqCst, SeqCst, |old| {
+                    Some(f($ty::from_le(old)).to_le())
+                });
+                match old {
+                    Ok(old) | Err(old) => $ty::from_le(old),
+                }
+            }
+
+            unsafe fn atomic_cmpxchg(ptr: *mut $ty, expected: $ty, replacement: $ty) -> $ty {
+                let atomic = unsafe { atomic::$atomic::from_ptr(ptr) };
+                let old = atomic.compare_exchange(
+                    expected.to_le(),
+                    replacement.to_le(),
+                    SeqCst,
+                    SeqCst,
+                );
+                match old {
+                    Ok(old) | Err(old) => $ty::from_le(old),
+                }
+            }
+        }
+    )*};
+}
+
+atomic_int! {
+    u8 = AtomicU8,
+    u16 = AtomicU16,
+    u32 = AtomicU32,
+}
+
+#[cfg(target_has_atomic = "64")]
+atomic_int! {
+    u64 = AtomicU64,
+}
+
+/// Hosts without native 64-bit atomics emulate them with a global spin lock.
+///
+/// Note that this means 64-bit atomic operations are only atomic with respect
+/// to each other, not to narrower atomic operations on overlapping memory.
+#[cfg(not(target_has_atomic = "64"))]
+impl AtomicInt for u64 {
+    unsafe fn atomic_load(ptr: *mut u64) -> u64 {
+        with_u64_lock(|| u64::from_le(unsafe { racy_load(ptr, 4) }))
+    }
+
+    unsafe fn atomic_store(ptr: *mut u64, val: u64) {
+        with_u64_lock(|| unsafe { racy_store(ptr, val.to_le(), 4) })
+    }
+
+    unsafe fn atomic_rmw(ptr: *mut u64, f: impl Fn(u64) -> u64) -> u64 {
+        with_u64_lock(|| unsafe {
+            let old = u64::from_le(racy_load(ptr, 4));
+            racy_store(ptr, f(old).to_le(), 4);
+            old
+        })
+    }
+
+    unsafe fn atomic_cmpxchg(ptr: *mut u64, expected: u64, replacement: u64) -> u64 {
+        with_u64_lock(|| unsafe {
+            let old = u64::from_le(racy_load(ptr, 4));
+            if old == expected {
+                racy_store(ptr, replacement.to_le(), 4);
+            }
+            old
+        })
+    }
+}
+
+#[cfg(not(target_has_atomic = "64"))]
+fn with_u64_lock<R>(f: impl FnOnce() -> R) -> R {
+    static LOCK: atomic::AtomicBool = atomic::AtomicBool::new(false);
+    while LOCK
+        .compare_exchange_weak(false, true, Acquire, Relaxed)
+        .is_err()
+    {
+        core::hint::spin_loop();
+    }
+    let ret = f();
+    LOCK.store(false, Release);
+    ret
+}
+
 #[test]
 fn simple_push_pop() {
     let mut state = MachineState::with_stack(16).unwrap();
@@ -1330,6 +1946,24 @@ macro_rules! br_if_imm {
     )*};
 }
 
+macro_rules! xatomic_rmw {
+    ($(
+        fn $snake:ident = $camel:ident / $ty:ident / $set:ident / |$old:ident, $src:ident| $op:expr;
+    )*) => {$(
+        #[allow(
+            trivial_numeric_casts,
+            clippy::useless_conversion,
+            reason = "macro-generated code"
+        )]
+        fn $snake(&mut self, dst: XReg, addr: AddrZ, src: XReg) -> ControlFlow<Done> {
+            let $src = self.state[src].get_u64() as $ty;
+            let old = unsafe { self.atomic_rmw::<$ty, crate::$camel>(addr, |$old| $op)? };
+            self.state[dst].$set(old.into());
+            ControlFlow::Continue(())
+        }
+    )*};
+}
+
 impl OpVisitor for Interpreter<'_> {
     type BytecodeStream = UnsafeBytecodeStream;
     type Return = ControlFlow<Done>;
@@ -2009,8 +2643,7 @@ impl OpVisitor for Interpreter<'_> {
         // fp/lr.
         let ptr_size = size_of::<usize>();
         let full_amt = usize::from(amt) + 2 * ptr_size;
-        let new_sp = self.state[XReg::sp].get_ptr::<u8>().wrapping_sub(full_amt);
-        self.set_sp::<crate::PushFrameSave>(new_sp)?;
+        self.decrement_sp::<crate::PushFrameSave>(full_amt)?;
 
         unsafe {
             // Emulate `push_frame` by placing `lr` and `fp` onto the stack, in
@@ -2094,8 +2727,7 @@ impl OpVisitor for Interpreter<'_> {
 
     fn stack_alloc32(&mut self, amt: u32) -> ControlFlow<Done> {
         let amt = usize::try_from(amt).unwrap();
-        let new_sp = self.state[XReg::sp].get_ptr::<u8>().wrapping_sub(amt);
-        self.set_sp::<crate::StackAlloc32>(new_sp)?;
+        self.decrement_sp::<crate::StackAlloc32>(amt)?;
         ControlFlow::Continue(())
     }
 
@@ -2611,6 +3243,34 @@ impl OpVisitor for Interpreter<'_> {
         ControlFlow::Continue(())
     }
 
+    fn xload32le_aligned_o32(&mut self, dst: XReg, addr: AddrO32) -> ControlFlow<Done> {
+        let result = unsafe { self.load_ne_aligned::<i32, crate::XLoad32LeAlignedO32>(addr)? };
+        self.state[dst].set_i32(i32::from_le(result));
+        ControlFlow::Continue(())
+    }
+
+    fn xload64le_aligned_o32(&mut self, dst: XReg, addr: AddrO32) -> ControlFlow<Done> {
+        let result = unsafe { self.load_ne_aligned::<i64, crate::XLoad64LeAlignedO32>(addr)? };
+        self.state[dst].set_i64(i64::from_le(result));
+        ControlFlow::Continue(())
+    }
+
+    fn xstore32le_aligned_o32(&mut self, addr: AddrO32, val: XReg) -> ControlFlow<Done> {
+        let val = self.state[val].get_u32();
+        unsafe {
+            self.store_ne_aligned::<u32, crate::XStore32LeAlignedO32>(addr, val.to_le())?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn xstore64le_aligned_o32(&mut self, addr: AddrO32, val: XReg) -> ControlFlow<Done> {
+        let val = self.state[val].get_u64();
+        unsafe {
+            self.store_ne_aligned::<u64, crate::XStore64LeAlignedO32>(addr, val.to_le())?;
+        }
+        ControlFlow::Continue(())
+    }
+
     // =========================================================================
     // g32 addressing modes
 
@@ -2994,6 +3654,34 @@ impl ExtendedOpVisitor for Interpreter<'_> {
         ControlFlow::Continue(())
     }
 
+    fn fload32le_aligned_o32(&mut self, dst: FReg, addr: AddrO32) -> ControlFlow<Done> {
+        let val = unsafe { self.load_ne_aligned::<u32, crate::Fload32LeAlignedO32>(addr)? };
+        self.state[dst].set_f32(f32::from_bits(u32::from_le(val)));
+        ControlFlow::Continue(())
+    }
+
+    fn fload64le_aligned_o32(&mut self, dst: FReg, addr: AddrO32) -> ControlFlow<Done> {
+        let val = unsafe { self.load_ne_aligned::<u64, crate::Fload64LeAlignedO32>(addr)? };
+        self.state[dst].set_f64(f64::from_bits(u64::from_le(val)));
+        ControlFlow::Continue(())
+    }
+
+    fn fstore32le_aligned_o32(&mut self, addr: AddrO32, src: FReg) -> ControlFlow<Done> {
+        let val = self.state[src].get_f32();
+        unsafe {
+            self.store_ne_aligned::<u32, crate::Fstore32LeAlignedO32>(addr, val.to_bits().to_le())?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn fstore64le_aligned_o32(&mut self, addr: AddrO32, src: FReg) -> ControlFlow<Done> {
+        let val = self.state[src].get_f64();
+        unsafe {
+            self.store_ne_aligned::<u64, crate::Fstore64LeAlignedO32>(addr, val.to_bits().to_le())?;
+        }
+        ControlFlow::Continue(())
+    }
+
     // =========================================================================
     // o32 addressing modes for big-endian F-registers
 
@@ -4275,17 +4963,245 @@ impl ExtendedOpVisitor for Interpreter<'_> {
     }
 
     #[interp_disable_if_cfg(pulley_disable_interp_simd)]
-    fn vf32x4_from_i32x4_s(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
-        let a = self.state[src].get_i32x4();
-        self.state[dst].set_f32x4(a.map(|i| i as f32));
-        ControlFlow::Continue(())
+    fn br_if_vanytrue(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u128();
+        if a != 0 {
+            self.pc_rel_jump::<crate::BrIfVanytrue>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
     }
 
     #[interp_disable_if_cfg(pulley_disable_interp_simd)]
-    fn vf32x4_from_i32x4_u(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
-        let a = self.state[src].get_u32x4();
-        self.state[dst].set_f32x4(a.map(|i| i as f32));
-        ControlFlow::Continue(())
+    fn br_if_not_vanytrue(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u128();
+        if a == 0 {
+            self.pc_rel_jump::<crate::BrIfNotVanytrue>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_valltrue8x16(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u8x16();
+        if a.iter().all(|a| *a != 0) {
+            self.pc_rel_jump::<crate::BrIfValltrue8x16>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_valltrue16x8(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u16x8();
+        if a.iter().all(|a| *a != 0) {
+            self.pc_rel_jump::<crate::BrIfValltrue16x8>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_valltrue32x4(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u32x4();
+        if a.iter().all(|a| *a != 0) {
+            self.pc_rel_jump::<crate::BrIfValltrue32x4>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_valltrue64x2(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u64x2();
+        if a.iter().all(|a| *a != 0) {
+            self.pc_rel_jump::<crate::BrIfValltrue64x2>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_not_valltrue8x16(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u8x16();
+        if a.iter().any(|a| *a == 0) {
+            self.pc_rel_jump::<crate::BrIfNotValltrue8x16>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_not_valltrue16x8(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u16x8();
+        if a.iter().any(|a| *a == 0) {
+            self.pc_rel_jump::<crate::BrIfNotValltrue16x8>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_not_valltrue32x4(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u32x4();
+        if a.iter().any(|a| *a == 0) {
+            self.pc_rel_jump::<crate::BrIfNotValltrue32x4>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn br_if_not_valltrue64x2(&mut self, src: VReg, offset: PcRelOffset) -> ControlFlow<Done> {
+        let a = self.state[src].get_u64x2();
+        if a.iter().any(|a| *a == 0) {
+            self.pc_rel_jump::<crate::BrIfNotValltrue64x2>(offset)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreduceaddi8x16(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u8x16();
+        let result = a.into_iter().fold(0, u8::wrapping_add);
+        self.state[dst].set_u32(u32::from(result));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreduceaddi16x8(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u16x8();
+        let result = a.into_iter().fold(0, u16::wrapping_add);
+        self.state[dst].set_u32(u32::from(result));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreduceaddi32x4(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u32x4();
+        let result = a.into_iter().fold(0, u32::wrapping_add);
+        self.state[dst].set_u32(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreduceaddi64x2(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u64x2();
+        let result = a.into_iter().fold(0, u64::wrapping_add);
+        self.state[dst].set_u64(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemin8x16_s(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i8x16();
+        let result = a.into_iter().min().unwrap();
+        self.state[dst].set_u32(u32::from(result as u8));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemin8x16_u(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u8x16();
+        let result = a.into_iter().min().unwrap();
+        self.state[dst].set_u32(u32::from(result));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemin16x8_s(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i16x8();
+        let result = a.into_iter().min().unwrap();
+        self.state[dst].set_u32(u32::from(result as u16));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemin16x8_u(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u16x8();
+        let result = a.into_iter().min().unwrap();
+        self.state[dst].set_u32(u32::from(result));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemin32x4_s(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i32x4();
+        let result = a.into_iter().min().unwrap();
+        self.state[dst].set_i32(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemin32x4_u(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u32x4();
+        let result = a.into_iter().min().unwrap();
+        self.state[dst].set_u32(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemax8x16_s(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i8x16();
+        let result = a.into_iter().max().unwrap();
+        self.state[dst].set_u32(u32::from(result as u8));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemax8x16_u(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u8x16();
+        let result = a.into_iter().max().unwrap();
+        self.state[dst].set_u32(u32::from(result));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemax16x8_s(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i16x8();
+        let result = a.into_iter().max().unwrap();
+        self.state[dst].set_u32(u32::from(result as u16));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemax16x8_u(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u16x8();
+        let result = a.into_iter().max().unwrap();
+        self.state[dst].set_u32(u32::from(result));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemax32x4_s(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i32x4();
+        let result = a.into_iter().max().unwrap();
+        self.state[dst].set_i32(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreducemax32x4_u(&mut self, dst: XReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u32x4();
+        let result = a.into_iter().max().unwrap();
+        self.state[dst].set_u32(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vf32x4_from_i32x4_s(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_i32x4();
+        self.state[dst].set_f32x4(a.map(|i| i as f32));
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vf32x4_from_i32x4_u(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
+        let a = self.state[src].get_u32x4();
+        self.state[dst].set_f32x4(a.map(|i| i as f32));
+        ControlFlow::Continue(())
     }
 
     #[interp_disable_if_cfg(pulley_disable_interp_simd)]
@@ -5387,6 +6303,162 @@ impl ExtendedOpVisitor for Interpreter<'_> {
         ControlFlow::Continue(())
     }
 
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavelo8x16(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u8x16();
+        let b = self.state[operands.src2].get_u8x16();
+        let result = core::array::from_fn(|i| {
+            let lane = 0 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u8x16(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavehi8x16(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u8x16();
+        let b = self.state[operands.src2].get_u8x16();
+        let result = core::array::from_fn(|i| {
+            let lane = 8 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u8x16(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavelo16x8(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u16x8();
+        let b = self.state[operands.src2].get_u16x8();
+        let result = core::array::from_fn(|i| {
+            let lane = 0 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u16x8(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavehi16x8(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u16x8();
+        let b = self.state[operands.src2].get_u16x8();
+        let result = core::array::from_fn(|i| {
+            let lane = 4 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u16x8(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavelo32x4(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u32x4();
+        let b = self.state[operands.src2].get_u32x4();
+        let result = core::array::from_fn(|i| {
+            let lane = 0 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u32x4(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavehi32x4(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u32x4();
+        let b = self.state[operands.src2].get_u32x4();
+        let result = core::array::from_fn(|i| {
+            let lane = 2 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u32x4(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavelo64x2(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u64x2();
+        let b = self.state[operands.src2].get_u64x2();
+        let result = core::array::from_fn(|i| {
+            let lane = 0 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u64x2(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vinterleavehi64x2(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
+        let a = self.state[operands.src1].get_u64x2();
+        let b = self.state[operands.src2].get_u64x2();
+        let result = core::array::from_fn(|i| {
+            let lane = 1 + i / 2;
+            if i % 2 == 0 { a[lane] } else { b[lane] }
+        });
+        self.state[operands.dst].set_u64x2(result);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vbroadcast8x16(&mut self, dst: VReg, src: VReg, lane: u8) -> ControlFlow<Done> {
+        let a = unsafe { *self.state[src].get_u8x16().get_unchecked(usize::from(lane)) };
+        self.state[dst].set_u8x16([a; 16]);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vbroadcast16x8(&mut self, dst: VReg, src: VReg, lane: u8) -> ControlFlow<Done> {
+        let a = unsafe { *self.state[src].get_u16x8().get_unchecked(usize::from(lane)) };
+        self.state[dst].set_u16x8([a; 8]);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vbroadcast32x4(&mut self, dst: VReg, src: VReg, lane: u8) -> ControlFlow<Done> {
+        let a = unsafe { *self.state[src].get_u32x4().get_unchecked(usize::from(lane)) };
+        self.state[dst].set_u32x4([a; 4]);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vbroadcast64x2(&mut self, dst: VReg, src: VReg, lane: u8) -> ControlFlow<Done> {
+        let a = unsafe { *self.state[src].get_u64x2().get_unchecked(usize::from(lane)) };
+        self.state[dst].set_u64x2([a; 2]);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreverse8x16(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
+        let mut a = self.state[src].get_u8x16();
+        a.reverse();
+        self.state[dst].set_u8x16(a);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreverse16x8(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
+        let mut a = self.state[src].get_u16x8();
+        a.reverse();
+        self.state[dst].set_u16x8(a);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreverse32x4(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
+        let mut a = self.state[src].get_u32x4();
+        a.reverse();
+        self.state[dst].set_u32x4(a);
+        ControlFlow::Continue(())
+    }
+
+    #[interp_disable_if_cfg(pulley_disable_interp_simd)]
+    fn vreverse64x2(&mut self, dst: VReg, src: VReg) -> ControlFlow<Done> {
+        let mut a = self.state[src].get_u64x2();
+        a.reverse();
+        self.state[dst].set_u64x2(a);
+        ControlFlow::Continue(())
+    }
+
     #[interp_disable_if_cfg(pulley_disable_interp_simd)]
     fn vswizzlei8x16(&mut self, operands: BinaryOperands<VReg>) -> ControlFlow<Done> {
         let src1 = self.state[operands.src1].get_i8x16();
@@ -5722,4 +6794,174 @@ impl ExtendedOpVisitor for Interpreter<'_> {
         }
         ControlFlow::Continue(())
     }
+
+    fn fence(&mut self) -> ControlFlow<Done> {
+        atomic::fence(SeqCst);
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_load8_u32_z(&mut self, dst: XReg, addr: AddrZ) -> ControlFlow<Done> {
+        let val = unsafe { self.atomic_load::<u8, crate::XAtomicLoad8U32Z>(addr)? };
+        self.state[dst].set_u32(val.into());
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_load16le_u32_z(&mut self, dst: XReg, addr: AddrZ) -> ControlFlow<Done> {
+        let val = unsafe { self.atomic_load::<u16, crate::XAtomicLoad16LeU32Z>(addr)? };
+        self.state[dst].set_u32(val.into());
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_load32le_z(&mut self, dst: XReg, addr: AddrZ) -> ControlFlow<Done> {
+        let val = unsafe { self.atomic_load::<u32, crate::XAtomicLoad32LeZ>(addr)? };
+        self.state[dst].set_u32(val);
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_load64le_z(&mut self, dst: XReg, addr: AddrZ) -> ControlFlow<Done> {
+        let val = unsafe { self.atomic_load::<u64, crate::XAtomicLoad64LeZ>(addr)? };
+        self.state[dst].set_u64(val);
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_store8_z(&mut self, addr: AddrZ, src: XReg) -> ControlFlow<Done> {
+        let val = self.state[src].get_u32() as u8;
+        unsafe { self.atomic_store::<u8, crate::XAtomicStore8Z>(addr, val) }
+    }
+
+    fn xatomic_store16le_z(&mut self, addr: AddrZ, src: XReg) -> ControlFlow<Done> {
+        let val = self.state[src].get_u32() as u16;
+        unsafe { self.atomic_store::<u16, crate::XAtomicStore16LeZ>(addr, val) }
+    }
+
+    fn xatomic_store32le_z(&mut self, addr: AddrZ, src: XReg) -> ControlFlow<Done> {
+        let val = self.state[src].get_u32();
+        unsafe { self.atomic_store::<u32, crate::XAtomicStore32LeZ>(addr, val) }
+    }
+
+    fn xatomic_store64le_z(&mut self, addr: AddrZ, src: XReg) -> ControlFlow<Done> {
+        let val = self.state[src].get_u64();
+        unsafe { self.atomic_store::<u64, crate::XAtomicStore64LeZ>(addr, val) }
+    }
+
+    xatomic_rmw! {
+        fn xatomic_add8_u32_z = XAtomicAdd8U32Z / u8 / set_u32 / |old, src| old.wrapping_add(src);
+        fn xatomic_add16le_u32_z = XAtomicAdd16LeU32Z / u16 / set_u32 / |old, src| old.wrapping_add(src);
+        fn xatomic_add32le_z = XAtomicAdd32LeZ / u32 / set_u32 / |old, src| old.wrapping_add(src);
+        fn xatomic_add64le_z = XAtomicAdd64LeZ / u64 / set_u64 / |old, src| old.wrapping_add(src);
+
+        fn xatomic_sub8_u32_z = XAtomicSub8U32Z / u8 / set_u32 / |old, src| old.wrapping_sub(src);
+        fn xatomic_sub16le_u32_z = XAtomicSub16LeU32Z / u16 / set_u32 / |old, src| old.wrapping_sub(src);
+        fn xatomic_sub32le_z = XAtomicSub32LeZ / u32 / set_u32 / |old, src| old.wrapping_sub(src);
+        fn xatomic_sub64le_z = XAtomicSub64LeZ / u64 / set_u64 / |old, src| old.wrapping_sub(src);
+
+        fn xatomic_and8_u32_z = XAtomicAnd8U32Z / u8 / set_u32 / |old, src| old & src;
+        fn xatomic_and16le_u32_z = XAtomicAnd16LeU32Z / u16 / set_u32 / |old, src| old & src;
+        fn xatomic_and32le_z = XAtomicAnd32LeZ / u32 / set_u32 / |old, src| old & src;
+        fn xatomic_and64le_z = XAtomicAnd64LeZ / u64 / set_u64 / |old, src| old & src;
+
+        fn xatomic_nand8_u32_z = XAtomicNand8U32Z / u8 / set_u32 / |old, src| !(old & src);
+        fn xatomic_nand16le_u32_z = XAtomicNand16LeU32Z / u16 / set_u32 / |old, src| !(old & src);
+        fn xatomic_nand32le_z = XAtomicNand32LeZ / u32 / set_u32 / |old, src| !(old & src);
+        fn xatomic_nand64le_z = XAtomicNand64LeZ / u64 / set_u64 / |old, src| !(old & src);
+
+        fn xatomic_or8_u32_z = XAtomicOr8U32Z / u8 / set_u32 / |old, src| old | src;
+        fn xatomic_or16le_u32_z = XAtomicOr16LeU32Z / u16 / set_u32 / |old, src| old | src;
+        fn xatomic_or32le_z = XAtomicOr32LeZ / u32 / set_u32 / |old, src| old | src;
+        fn xatomic_or64le_z = XAtomicOr64LeZ / u64 / set_u64 / |old, src| old | src;
+
+        fn xatomic_xor8_u32_z = XAtomicXor8U32Z / u8 / set_u32 / |old, src| old ^ src;
+        fn xatomic_xor16le_u32_z = XAtomicXor16LeU32Z / u16 / set_u32 / |old, src| old ^ src;
+        fn xatomic_xor32le_z = XAtomicXor32LeZ / u32 / set_u32 / |old, src| old ^ src;
+        fn xatomic_xor64le_z = XAtomicXor64LeZ / u64 / set_u64 / |old, src| old ^ src;
+
+        fn xatomic_xchg8_u32_z = XAtomicXchg8U32Z / u8 / set_u32 / |_old, src| src;
+        fn xatomic_xchg16le_u32_z = XAtomicXchg16LeU32Z / u16 / set_u32 / |_old, src| src;
+        fn xatomic_xchg32le_z = XAtomicXchg32LeZ / u32 / set_u32 / |_old, src| src;
+        fn xatomic_xchg64le_z = XAtomicXchg64LeZ / u64 / set_u64 / |_old, src| src;
+
+        fn xatomic_umin8_u32_z = XAtomicUmin8U32Z / u8 / set_u32 / |old, src| old.min(src);
+        fn xatomic_umin16le_u32_z = XAtomicUmin16LeU32Z / u16 / set_u32 / |old, src| old.min(src);
+        fn xatomic_umin32le_z = XAtomicUmin32LeZ / u32 / set_u32 / |old, src| old.min(src);
+        fn xatomic_umin64le_z = XAtomicUmin64LeZ / u64 / set_u64 / |old, src| old.min(src);
+
+        fn xatomic_umax8_u32_z = XAtomicUmax8U32Z / u8 / set_u32 / |old, src| old.max(src);
+        fn xatomic_umax16le_u32_z = XAtomicUmax16LeU32Z / u16 / set_u32 / |old, src| old.max(src);
+        fn xatomic_umax32le_z = XAtomicUmax32LeZ / u32 / set_u32 / |old, src| old.max(src);
+        fn xatomic_umax64le_z = XAtomicUmax64LeZ / u64 / set_u64 / |old, src| old.max(src);
+
+        fn xatomic_smin8_u32_z = XAtomicSmin8U32Z / u8 / set_u32 / |old, src| (old as i8).min(src as i8) as u8;
+        fn xatomic_smin16le_u32_z = XAtomicSmin16LeU32Z / u16 / set_u32 / |old, src| (old as i16).min(src as i16) as u16;
+        fn xatomic_smin32le_z = XAtomicSmin32LeZ / u32 / set_u32 / |old, src| (old as i32).min(src as i32) as u32;
+        fn xatomic_smin64le_z = XAtomicSmin64LeZ / u64 / set_u64 / |old, src| (old as i64).min(src as i64) as u64;
+
+        fn xatomic_smax8_u32_z = XAtomicSmax8U32Z / u8 / set_u32 / |old, src| (old as i8).max(src as i8) as u8;
+        fn xatomic_smax16le_u32_z = XAtomicSmax16LeU32Z / u16 / set_u32 / |old, src| (old as i16).max(src as i16) as u16;
+        fn xatomic_smax32le_z = XAtomicSmax32LeZ / u32 / set_u32 / |old, src| (old as i32).max(src as i32) as u32;
+        fn xatomic_smax64le_z = XAtomicSmax64LeZ / u64 / set_u64 / |old, src| (old as i64).max(src as i64) as u64;
+    }
+
+    fn xatomic_cmpxchg8_u32_z(
+        &mut self,
+        dst: XReg,
+        addr: AddrZ,
+        expected: XReg,
+        replacement: XReg,
+    ) -> ControlFlow<Done> {
+        let expected = self.state[expected].get_u32() as u8;
+        let replacement = self.state[replacement].get_u32() as u8;
+        let old = unsafe {
+            self.atomic_cmpxchg::<u8, crate::XAtomicCmpxchg8U32Z>(addr, expected, replacement)?
+        };
+        self.state[dst].set_u32(old.into());
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_cmpxchg16le_u32_z(
+        &mut self,
+        dst: XReg,
+        addr: AddrZ,
+        expected: XReg,
+        replacement: XReg,
+    ) -> ControlFlow<Done> {
+        let expected = self.state[expected].get_u32() as u16;
+        let replacement = self.state[replacement].get_u32() as u16;
+        let old = unsafe {
+            self.atomic_cmpxchg::<u16, crate::XAtomicCmpxchg16LeU32Z>(addr, expected, replacement)?
+        };
+        self.state[dst].set_u32(old.into());
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_cmpxchg32le_z(
+        &mut self,
+        dst: XReg,
+        addr: AddrZ,
+        expected: XReg,
+        replacement: XReg,
+    ) -> ControlFlow<Done> {
+        let expected = self.state[expected].get_u32();
+        let replacement = self.state[replacement].get_u32();
+        let old = unsafe {
+            self.atomic_cmpxchg::<u32, crate::XAtomicCmpxchg32LeZ>(addr, expected, replacement)?
+        };
+        self.state[dst].set_u32(old);
+        ControlFlow::Continue(())
+    }
+
+    fn xatomic_cmpxchg64le_z(
+        &mut self,
+        dst: XReg,
+        addr: AddrZ,
+        expected: XReg,
+        replacement: XReg,
+    ) -> ControlFlow<Done> {
+        let expected = self.state[expected].get_u64();
+        let replacement = self.state[replacement].get_u64();
+        let old = unsafe {
+            self.atomic_cmpxchg::<u64, crate::XAtomicCmpxchg64LeZ>(addr, expected, replacement)?
+        };
+        self.state[dst].set_u64(old);
+        ControlFlow::Continue(())
+    }
 }
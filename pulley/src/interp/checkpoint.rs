@@ -0,0 +1,194 @@
+//! Snapshots of a [`Vm`]'s machine state which can be restored later.
+
+#[cfg(not(pulley_disable_interp_simd))]
+use super::VRegVal;
+use super::{FRegVal, HOST_RETURN_ADDR, Vm, XRegVal};
+use crate::regs::*;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ptr::{self, NonNull};
+use wasmtime_core::alloc::TryVec;
+use wasmtime_core::error::OutOfMemory;
+
+/// A snapshot of a [`Vm`]'s registers and stack, created with
+/// [`Vm::checkpoint`].
+///
+/// A checkpoint owns a copy of the live part of the stack, everything between
+/// the stack pointer and the top of the stack, so it stays valid while the VM
+/// it was taken from continues executing. It can be restored with
+/// [`Vm::restore`] into the same VM, to go back to an earlier point of
+/// execution, or into a different VM.
+pub struct Checkpoint {
+    x_regs: [XRegVal; XReg::RANGE.end as usize],
+    f_regs: [FRegVal; FReg::RANGE.end as usize],
+    #[cfg(not(pulley_disable_interp_simd))]
+    v_regs: [VRegVal; VReg::RANGE.end as usize],
+    fp: *mut u8,
+    lr: *mut u8,
+    pc: NonNull<u8>,
+    /// The contents of the stack from the stack pointer to its top.
+    stack: TryVec<MaybeUninit<u8>>,
+    /// The address of the top of the stack this checkpoint was taken from.
+    stack_top: usize,
+}
+
+unsafe impl Send for Checkpoint {}
+unsafe impl Sync for Checkpoint {}
+
+impl Checkpoint {
+    /// Returns the program counter at which execution resumes once this
+    /// checkpoint is restored.
+    pub fn pc(&self) -> NonNull<u8> {
+        self.pc
+    }
+
+    /// Returns the number of bytes of stack saved in this checkpoint.
+    pub fn stack_size(&self) -> usize {
+        self.stack.len()
+    }
+}
+
+impl fmt::Debug for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkpoint")
+            .field("pc", &self.pc)
+            .field("fp", &self.fp)
+            .field("lr", &self.lr)
+            .field("stack_size", &self.stack.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Vm {
+    /// Takes a snapshot of this VM's machine state.
+    ///
+    /// This saves every register, including `fp` and `lr`, along with the
+    /// contents of the stack between the stack pointer and the top of the
+    /// stack. The `pc` provided is the program counter at which execution
+    /// resumes after the checkpoint is restored, for example the `resume`
+    /// address of [`DoneReason::CallIndirectHost`](super::DoneReason).
+    ///
+    /// Memory outside of this VM's stack, such as any linear memory accessed
+    /// by bytecode, is not part of the checkpoint.
+    pub fn checkpoint(&mut self, pc: NonNull<u8>) -> Result<Checkpoint, OutOfMemory> {
+        let state = &mut self.state;
+        let top = state.stack.top();
+        let sp = state[XReg::sp].get_ptr::<u8>();
+        let len = (top as usize)
+            .checked_sub(sp as usize)
+            .filter(|_| sp as usize >= state.stack.limit)
+            .expect("stack pointer should be within the usable part of the stack");
+
+        let mut stack = TryVec::new();
+        stack.resize_with(len, MaybeUninit::uninit)?;
+        // SAFETY: `sp..top` was checked above to be within the usable part of
+        // the stack, and `MaybeUninit` permits copying bytes which bytecode
+        // never initialized.
+        unsafe {
+            ptr::copy_nonoverlapping(sp.cast::<MaybeUninit<u8>>(), stack.as_mut_ptr(), len);
+        }
+
+        Ok(Checkpoint {
+            x_regs: state.x_regs,
+            f_regs: state.f_regs,
+            #[cfg(not(pulley_disable_interp_simd))]
+            v_regs: state.v_regs,
+            fp: state.fp,
+            lr: state.lr,
+            pc,
+            stack,
+            stack_top: top as usize,
+        })
+    }
+
+    /// Restores the machine state saved in `checkpoint`, returning the program
+    /// counter at which to resume execution with [`Vm::call_run`].
+    ///
+    /// All registers are overwritten and the saved stack contents are copied
+    /// back to the same distance from the top of this VM's stack. If this VM's
+    /// stack is at a different address than the stack the checkpoint was taken
+    /// from, for example when restoring into a different [`Vm`], the registers
+    /// known to point into the stack are rebased onto this VM's stack: the
+    /// stack pointer, `fp`, and the saved frame pointers of every frame
+    /// reachable from `fp`. `lr` and the saved return addresses are bytecode
+    /// addresses and are restored unchanged.
+    ///
+    /// Returns an error if this VM's stack is too small to hold the saved
+    /// stack contents, or if growing it failed.
+    ///
+    /// # Unsafety
+    ///
+    /// Only the registers listed above are rebased. Other `x` registers are
+    /// restored as-is since they can't be told apart from integers which
+    /// happen to look like stack addresses, so bytecode which holds pointers
+    /// to its own stack in other registers or in stack slots, for example the
+    /// address of a stack slot computed from `sp`, will observe stale pointers
+    /// after being moved to a new stack. Any memory outside of the stack that
+    /// the bytecode accesses must also still be valid.
+    pub unsafe fn restore(&mut self, checkpoint: &Checkpoint) -> Result<NonNull<u8>, OutOfMemory> {
+        let state = &mut self.state;
+        let len = checkpoint.stack.len();
+        if len > state.stack.len() {
+            return Err(OutOfMemory::new(len));
+        }
+        let top = state.stack.top();
+        let sp = top.wrapping_sub(len);
+        if (sp as usize) < state.stack.limit && !state.stack.grow(sp as usize) {
+            return Err(OutOfMemory::new(len));
+        }
+        // SAFETY: `sp..top` is within the usable part of the stack as checked
+        // and grown above.
+        unsafe {
+            ptr::copy_nonoverlapping(checkpoint.stack.as_ptr(), sp.cast(), len);
+        }
+
+        // Pointers into the saved part of the old stack are moved to the same
+        // distance from the top of this stack, retaining the provenance of
+        // this stack's allocation.
+        let old_top = checkpoint.stack_top;
+        let old_sp = old_top - len;
+        let rebase = |ptr: *mut u8| {
+            let addr = ptr as usize;
+            if (old_sp..=old_top).contains(&addr) {
+                top.wrapping_sub(old_top - addr)
+            } else {
+                ptr
+            }
+        };
+
+        state.x_regs = checkpoint.x_regs;
+        state.f_regs = checkpoint.f_regs;
+        #[cfg(not(pulley_disable_interp_simd))]
+        {
+            state.v_regs = checkpoint.v_regs;
+        }
+        state[XReg::sp].set_ptr(sp);
+        state.fp = rebase(checkpoint.fp);
+        state.lr = checkpoint.lr;
+
+        if old_top != top as usize {
+            // Each frame holds the older frame's `fp` followed by the return
+            // address. Frames only ever get older going up the stack, so stop
+            // as soon as the chain leaves the restored part of the stack.
+            let mut fp = state.fp;
+            while fp != HOST_RETURN_ADDR
+                && fp as usize >= sp as usize
+                && (fp as usize) + 2 * size_of::<*mut u8>() <= top as usize
+            {
+                let slot = fp.cast::<*mut u8>();
+                // SAFETY: the frame is within the restored part of the stack
+                // as checked by the loop condition.
+                let older = unsafe { rebase(*slot) };
+                unsafe {
+                    *slot = older;
+                }
+                if older as usize <= fp as usize {
+                    break;
+                }
+                fp = older;
+            }
+        }
+
+        Ok(checkpoint.pc)
+    }
+}
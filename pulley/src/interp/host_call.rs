@@ -0,0 +1,272 @@
+//! Typed host calls dispatched from the `call_indirect_host` instruction.
+//!
+//! By default `call_indirect_host` stops the interpreter and reports
+//! [`DoneReason::CallIndirectHost`](super::DoneReason::CallIndirectHost) to
+//! the embedder, which is then responsible for decoding arguments from, and
+//! storing results into, the machine's registers. Functions registered with
+//! [`Vm::register_host_call`](super::Vm::register_host_call) are instead
+//! invoked directly by the interpreter with their arguments and results
+//! marshalled according to Pulley's calling convention.
+
+#[cfg(not(pulley_disable_interp_simd))]
+use super::VRegVal;
+use super::{FRegVal, MachineState, RegType, XRegVal};
+#[cfg(not(pulley_disable_interp_simd))]
+use crate::regs::VReg;
+use crate::regs::{FReg, Reg, XReg};
+use alloc::boxed::Box;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A value which can be passed to or returned from a host call.
+///
+/// Each value is passed in a single register, and the register class used is
+/// described by [`HostCallValue::REG_TYPE`]. Arguments are assigned to
+/// successive registers of each class, starting at `x0`, `f0` and `v0`
+/// respectively, and a result is returned in `x0`, `f0` or `v0`. This matches
+/// the calling convention for Pulley bytecode functions.
+///
+/// This trait is sealed and is implemented for integers, `bool`, raw
+/// pointers, floats and the raw register value types.
+pub trait HostCallValue: sealed::Sealed + Sized + 'static {
+    /// The class of register this value is passed in.
+    const REG_TYPE: RegType;
+
+    #[doc(hidden)]
+    fn read(state: &MachineState, reg: u8) -> Self;
+
+    #[doc(hidden)]
+    fn write(self, state: &mut MachineState);
+}
+
+macro_rules! x_values {
+    ($($ty:ty => |$r:ident| $get:expr, |$v:ident, $w:ident| $set:expr;)*) => {$(
+        impl sealed::Sealed for $ty {}
+
+        impl HostCallValue for $ty {
+            const REG_TYPE: RegType = RegType::XReg;
+
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_possible_wrap,
+                clippy::cast_sign_loss,
+                reason = "host call values are truncated to their declared type"
+            )]
+            fn read(state: &MachineState, reg: u8) -> Self {
+                let $r = &state[XReg::new(reg).unwrap()];
+                $get
+            }
+
+            fn write(self, state: &mut MachineState) {
+                let $v = self;
+                let $w = &mut state[XReg::x0];
+                $set
+            }
+        }
+    )*};
+}
+
+x_values! {
+    bool => |r| r.get_u32() != 0, |v, w| w.set_u32(u32::from(v));
+    u8 => |r| r.get_u32() as u8, |v, w| w.set_u32(u32::from(v));
+    i8 => |r| r.get_i32() as i8, |v, w| w.set_i32(i32::from(v));
+    u16 => |r| r.get_u32() as u16, |v, w| w.set_u32(u32::from(v));
+    i16 => |r| r.get_i32() as i16, |v, w| w.set_i32(i32::from(v));
+    u32 => |r| r.get_u32(), |v, w| w.set_u32(v);
+    i32 => |r| r.get_i32(), |v, w| w.set_i32(v);
+    u64 => |r| r.get_u64(), |v, w| w.set_u64(v);
+    i64 => |r| r.get_i64(), |v, w| w.set_i64(v);
+    usize => |r| r.get_ptr::<u8>() as usize, |v, w| w.set_ptr(v as *mut u8);
+    isize => |r| r.get_ptr::<u8>() as isize, |v, w| w.set_ptr(v as *mut u8);
+    XRegVal => |r| *r, |v, w| *w = v;
+}
+
+impl<T: 'static> sealed::Sealed for *mut T {}
+
+impl<T: 'static> HostCallValue for *mut T {
+    const REG_TYPE: RegType = RegType::XReg;
+
+    fn read(state: &MachineState, reg: u8) -> Self {
+        state[XReg::new(reg).unwrap()].get_ptr()
+    }
+
+    fn write(self, state: &mut MachineState) {
+        state[XReg::x0].set_ptr(self);
+    }
+}
+
+impl<T: 'static> sealed::Sealed for *const T {}
+
+impl<T: 'static> HostCallValue for *const T {
+    const REG_TYPE: RegType = RegType::XReg;
+
+    fn read(state: &MachineState, reg: u8) -> Self {
+        state[XReg::new(reg).unwrap()].get_ptr::<T>().cast_const()
+    }
+
+    fn write(self, state: &mut MachineState) {
+        state[XReg::x0].set_ptr(self.cast_mut());
+    }
+}
+
+macro_rules! f_values {
+    ($($ty:ty => |$r:ident| $get:expr, |$v:ident, $w:ident| $set:expr;)*) => {$(
+        impl sealed::Sealed for $ty {}
+
+        impl HostCallValue for $ty {
+            const REG_TYPE: RegType = RegType::FReg;
+
+            fn read(state: &MachineState, reg: u8) -> Self {
+                let $r = &state[FReg::new(reg).unwrap()];
+                $get
+            }
+
+            fn write(self, state: &mut MachineState) {
+                let $v = self;
+                let $w = &mut state[FReg::f0];
+                $set
+            }
+        }
+    )*};
+}
+
+f_values! {
+    f32 => |r| r.get_f32(), |v, w| w.set_f32(v);
+    f64 => |r| r.get_f64(), |v, w| w.set_f64(v);
+    FRegVal => |r| *r, |v, w| *w = v;
+}
+
+#[cfg(not(pulley_disable_interp_simd))]
+impl sealed::Sealed for VRegVal {}
+
+#[cfg(not(pulley_disable_interp_simd))]
+impl HostCallValue for VRegVal {
+    const REG_TYPE: RegType = RegType::VReg;
+
+    fn read(state: &MachineState, reg: u8) -> Self {
+        state[VReg::new(reg).unwrap()]
+    }
+
+    fn write(self, state: &mut MachineState) {
+        state[VReg::v0] = self;
+    }
+}
+
+/// A value which can be returned from a host call: either `()` for no result
+/// or a single [`HostCallValue`].
+pub trait HostCallReturn: sealed::Sealed + 'static {
+    /// The class of register the result is returned in, if any.
+    const REG_TYPE: Option<RegType>;
+
+    #[doc(hidden)]
+    fn write(self, state: &mut MachineState);
+}
+
+impl sealed::Sealed for () {}
+
+impl HostCallReturn for () {
+    const REG_TYPE: Option<RegType> = None;
+
+    fn write(self, _state: &mut MachineState) {}
+}
+
+impl<T: HostCallValue> HostCallReturn for T {
+    const REG_TYPE: Option<RegType> = Some(T::REG_TYPE);
+
+    fn write(self, state: &mut MachineState) {
+        HostCallValue::write(self, state)
+    }
+}
+
+/// The signature of a host call, describing which registers its parameters
+/// and result are passed in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HostCallSignature {
+    /// The register class of each parameter, in order.
+    pub params: &'static [RegType],
+    /// The register class of the result, if there is one.
+    pub result: Option<RegType>,
+}
+
+/// A Rust function which can be registered as a host call with
+/// [`Vm::register_host_call`](super::Vm::register_host_call).
+///
+/// This is implemented for closures taking up to eight [`HostCallValue`]
+/// parameters and returning a [`HostCallReturn`]. The `Params` type parameter
+/// is the tuple of the closure's parameter types and only exists to allow
+/// these implementations to coexist.
+pub trait HostFn<Params, Result>: Send + Sync + 'static {
+    /// The signature of this function.
+    const SIGNATURE: HostCallSignature;
+
+    #[doc(hidden)]
+    fn call(&mut self, state: &mut MachineState);
+}
+
+/// Assigns argument registers to parameters in order.
+#[derive(Default)]
+struct ArgRegs {
+    x: u8,
+    f: u8,
+    #[cfg(not(pulley_disable_interp_simd))]
+    v: u8,
+}
+
+impl ArgRegs {
+    fn next(&mut self, ty: RegType) -> u8 {
+        let reg = match ty {
+            RegType::XReg => &mut self.x,
+            RegType::FReg => &mut self.f,
+            #[cfg(not(pulley_disable_interp_simd))]
+            RegType::VReg => &mut self.v,
+            #[cfg(pulley_disable_interp_simd)]
+            RegType::VReg => unreachable!(),
+        };
+        let ret = *reg;
+        *reg += 1;
+        ret
+    }
+}
+
+macro_rules! host_fns {
+    ($(($($param:ident)*))*) => {$(
+        impl<F, R, $($param,)*> HostFn<($($param,)*), R> for F
+        where
+            F: FnMut($($param),*) -> R + Send + Sync + 'static,
+            R: HostCallReturn,
+            $($param: HostCallValue,)*
+        {
+            const SIGNATURE: HostCallSignature = HostCallSignature {
+                params: &[$($param::REG_TYPE),*],
+                result: R::REG_TYPE,
+            };
+
+            #[allow(unused, reason = "macro-generated code")]
+            fn call(&mut self, state: &mut MachineState) {
+                let mut regs = ArgRegs::default();
+                let result = self($($param::read(state, regs.next($param::REG_TYPE))),*);
+                result.write(state);
+            }
+        }
+    )*};
+}
+
+host_fns! {
+    ()
+    (A1)
+    (A1 A2)
+    (A1 A2 A3)
+    (A1 A2 A3 A4)
+    (A1 A2 A3 A4 A5)
+    (A1 A2 A3 A4 A5 A6)
+    (A1 A2 A3 A4 A5 A6 A7)
+    (A1 A2 A3 A4 A5 A6 A7 A8)
+}
+
+/// A host call registered with a [`Vm`](super::Vm).
+pub(super) struct RegisteredHostCall {
+    pub(super) signature: HostCallSignature,
+    pub(super) func: Box<dyn FnMut(&mut MachineState) + Send + Sync>,
+}
@@ -486,6 +486,21 @@ macro_rules! for_each_op {
             /// `*addr = src`
             xstore64le_g32bne = XStore64LeG32Bne { addr: AddrG32Bne, src: XReg };
 
+            // Variants of the `o32` loads/stores above for addresses which are
+            // known to be naturally aligned, for example those of stack slots
+            // or `VMContext` fields. These can be serviced with aligned host
+            // accesses which are cheaper than unaligned ones on some hosts.
+            // Executing them with an unaligned address is undefined behavior.
+
+            /// `low32(dst) = *addr`
+            xload32le_aligned_o32 = XLoad32LeAlignedO32 { dst: XReg, addr: AddrO32 };
+            /// `dst = *addr`
+            xload64le_aligned_o32 = XLoad64LeAlignedO32 { dst: XReg, addr: AddrO32 };
+            /// `*addr = low32(src)`
+            xstore32le_aligned_o32 = XStore32LeAlignedO32 { addr: AddrO32, src: XReg };
+            /// `*addr = src`
+            xstore64le_aligned_o32 = XStore64LeAlignedO32 { addr: AddrO32, src: XReg };
+
 
             /// `push lr; push fp; fp = sp`
             push_frame = PushFrame ;
@@ -632,7 +647,10 @@ macro_rules! for_each_extended_op {
             /// This opcode results in `DoneReason::CallIndirectHost` where the
             /// `id` here is shepherded along to the embedder. It's up to the
             /// embedder to determine what to do with the `id` and the current
-            /// state of registers and the stack.
+            /// state of registers and the stack. Alternatively embedders can
+            /// register a typed Rust function for an `id` with
+            /// `Vm::register_host_call`, in which case it's called directly
+            /// and execution continues after this instruction.
             ///
             /// In Wasmtime this is used to implement interpreter-to-host calls.
             /// This is modeled as a `call` instruction where the first
@@ -720,6 +738,15 @@ macro_rules! for_each_extended_op {
             /// `*addr = src`
             fstore64le_o32 = Fstore64LeO32 { addr: AddrO32, src: FReg };
 
+            /// Same as `fload32le_o32` but `addr` must be naturally aligned.
+            fload32le_aligned_o32 = Fload32LeAlignedO32 { dst: FReg, addr: AddrO32 };
+            /// Same as `fload64le_o32` but `addr` must be naturally aligned.
+            fload64le_aligned_o32 = Fload64LeAlignedO32 { dst: FReg, addr: AddrO32 };
+            /// Same as `fstore32le_o32` but `addr` must be naturally aligned.
+            fstore32le_aligned_o32 = Fstore32LeAlignedO32 { addr: AddrO32, src: FReg };
+            /// Same as `fstore64le_o32` but `addr` must be naturally aligned.
+            fstore64le_aligned_o32 = Fstore64LeAlignedO32 { addr: AddrO32, src: FReg };
+
             /// `low32(dst) = zext(*addr)`
             fload32le_z = Fload32LeZ { dst: FReg, addr: AddrZ };
             /// `dst = *addr`
@@ -1060,6 +1087,60 @@ macro_rules! for_each_extended_op {
             /// Store whether any lanes are nonzero in `dst`.
             vanytrue64x2 = Vanytrue64x2 { dst: XReg, src: VReg };
 
+            /// Branch if any lane of `src` is nonzero.
+            br_if_vanytrue = BrIfVanytrue { src: VReg, offset: PcRelOffset };
+            /// Branch if all lanes of `src` are zero.
+            br_if_not_vanytrue = BrIfNotVanytrue { src: VReg, offset: PcRelOffset };
+            /// Branch if all lanes of `src` are nonzero.
+            br_if_valltrue8x16 = BrIfValltrue8x16 { src: VReg, offset: PcRelOffset };
+            /// Branch if all lanes of `src` are nonzero.
+            br_if_valltrue16x8 = BrIfValltrue16x8 { src: VReg, offset: PcRelOffset };
+            /// Branch if all lanes of `src` are nonzero.
+            br_if_valltrue32x4 = BrIfValltrue32x4 { src: VReg, offset: PcRelOffset };
+            /// Branch if all lanes of `src` are nonzero.
+            br_if_valltrue64x2 = BrIfValltrue64x2 { src: VReg, offset: PcRelOffset };
+            /// Branch if any lane of `src` is zero.
+            br_if_not_valltrue8x16 = BrIfNotValltrue8x16 { src: VReg, offset: PcRelOffset };
+            /// Branch if any lane of `src` is zero.
+            br_if_not_valltrue16x8 = BrIfNotValltrue16x8 { src: VReg, offset: PcRelOffset };
+            /// Branch if any lane of `src` is zero.
+            br_if_not_valltrue32x4 = BrIfNotValltrue32x4 { src: VReg, offset: PcRelOffset };
+            /// Branch if any lane of `src` is zero.
+            br_if_not_valltrue64x2 = BrIfNotValltrue64x2 { src: VReg, offset: PcRelOffset };
+
+            /// `low8(dst) = src[0] + src[1] + ... + src[15]` (wrapping)
+            vreduceaddi8x16 = VReduceAddI8x16 { dst: XReg, src: VReg };
+            /// `low16(dst) = src[0] + src[1] + ... + src[7]` (wrapping)
+            vreduceaddi16x8 = VReduceAddI16x8 { dst: XReg, src: VReg };
+            /// `low32(dst) = src[0] + src[1] + src[2] + src[3]` (wrapping)
+            vreduceaddi32x4 = VReduceAddI32x4 { dst: XReg, src: VReg };
+            /// `dst = src[0] + src[1]` (wrapping)
+            vreduceaddi64x2 = VReduceAddI64x2 { dst: XReg, src: VReg };
+            /// `low8(dst) = min(src[0], ..., src[15])` (signed)
+            vreducemin8x16_s = VReduceMin8x16S { dst: XReg, src: VReg };
+            /// `low8(dst) = min(src[0], ..., src[15])` (unsigned)
+            vreducemin8x16_u = VReduceMin8x16U { dst: XReg, src: VReg };
+            /// `low16(dst) = min(src[0], ..., src[7])` (signed)
+            vreducemin16x8_s = VReduceMin16x8S { dst: XReg, src: VReg };
+            /// `low16(dst) = min(src[0], ..., src[7])` (unsigned)
+            vreducemin16x8_u = VReduceMin16x8U { dst: XReg, src: VReg };
+            /// `low32(dst) = min(src[0], ..., src[3])` (signed)
+            vreducemin32x4_s = VReduceMin32x4S { dst: XReg, src: VReg };
+            /// `low32(dst) = min(src[0], ..., src[3])` (unsigned)
+            vreducemin32x4_u = VReduceMin32x4U { dst: XReg, src: VReg };
+            /// `low8(dst) = max(src[0], ..., src[15])` (signed)
+            vreducemax8x16_s = VReduceMax8x16S { dst: XReg, src: VReg };
+            /// `low8(dst) = max(src[0], ..., src[15])` (unsigned)
+            vreducemax8x16_u = VReduceMax8x16U { dst: XReg, src: VReg };
+            /// `low16(dst) = max(src[0], ..., src[7])` (signed)
+            vreducemax16x8_s = VReduceMax16x8S { dst: XReg, src: VReg };
+            /// `low16(dst) = max(src[0], ..., src[7])` (unsigned)
+            vreducemax16x8_u = VReduceMax16x8U { dst: XReg, src: VReg };
+            /// `low32(dst) = max(src[0], ..., src[3])` (signed)
+            vreducemax32x4_s = VReduceMax32x4S { dst: XReg, src: VReg };
+            /// `low32(dst) = max(src[0], ..., src[3])` (unsigned)
+            vreducemax32x4_u = VReduceMax32x4U { dst: XReg, src: VReg };
+
             /// Int-to-float conversion (same as `f32_from_x32_s`)
             vf32x4_from_i32x4_s = VF32x4FromI32x4S { dst: VReg, src: VReg };
             /// Int-to-float conversion (same as `f32_from_x32_u`)
@@ -1314,6 +1395,38 @@ macro_rules! for_each_extended_op {
 
             /// `dst = shuffle(src1, src2, mask)`
             vshuffle = VShuffle { dst: VReg, src1: VReg, src2: VReg, mask: u128 };
+            /// `dst = [src1[0], src2[0], src1[1], src2[1], ..., src1[7], src2[7]]`
+            vinterleavelo8x16 = VInterleaveLo8x16 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[0], src2[0], src1[1], src2[1], src1[2], src2[2], src1[3], src2[3]]`
+            vinterleavelo16x8 = VInterleaveLo16x8 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[0], src2[0], src1[1], src2[1]]`
+            vinterleavelo32x4 = VInterleaveLo32x4 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[0], src2[0]]`
+            vinterleavelo64x2 = VInterleaveLo64x2 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[8], src2[8], src1[9], src2[9], ..., src1[15], src2[15]]`
+            vinterleavehi8x16 = VInterleaveHi8x16 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[4], src2[4], src1[5], src2[5], src1[6], src2[6], src1[7], src2[7]]`
+            vinterleavehi16x8 = VInterleaveHi16x8 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[2], src2[2], src1[3], src2[3]]`
+            vinterleavehi32x4 = VInterleaveHi32x4 { operands: BinaryOperands<VReg> };
+            /// `dst = [src1[1], src2[1]]`
+            vinterleavehi64x2 = VInterleaveHi64x2 { operands: BinaryOperands<VReg> };
+            /// `dst = splat(src[lane])`
+            vbroadcast8x16 = VBroadcast8x16 { dst: VReg, src: VReg, lane: u8 };
+            /// `dst = splat(src[lane])`
+            vbroadcast16x8 = VBroadcast16x8 { dst: VReg, src: VReg, lane: u8 };
+            /// `dst = splat(src[lane])`
+            vbroadcast32x4 = VBroadcast32x4 { dst: VReg, src: VReg, lane: u8 };
+            /// `dst = splat(src[lane])`
+            vbroadcast64x2 = VBroadcast64x2 { dst: VReg, src: VReg, lane: u8 };
+            /// `dst = [src[15], src[14], ..., src[0]]`
+            vreverse8x16 = VReverse8x16 { dst: VReg, src: VReg };
+            /// `dst = [src[7], src[6], ..., src[0]]`
+            vreverse16x8 = VReverse16x8 { dst: VReg, src: VReg };
+            /// `dst = [src[3], src[2], src[1], src[0]]`
+            vreverse32x4 = VReverse32x4 { dst: VReg, src: VReg };
+            /// `dst = [src[1], src[0]]`
+            vreverse64x2 = VReverse64x2 { dst: VReg, src: VReg };
 
             /// `dst = swizzle(src1, src2)`
             vswizzlei8x16 = Vswizzlei8x16 { operands: BinaryOperands<VReg> };
@@ -1407,6 +1520,157 @@ macro_rules! for_each_extended_op {
             vload128be_z = VLoad128BeZ { dst: VReg, addr: AddrZ };
             /// `*(ptr + offset) = src`
             vstore128be_z = Vstore128BeZ { addr: AddrZ, src: VReg };
+
+            // Atomic memory operations. These all use the "z" addressing mode,
+            // are sequentially consistent, and require the address to be
+            // naturally aligned for the width of the access. Note that only
+            // little-endian variants exist.
+
+            /// Sequentially consistent memory fence.
+            fence = Fence;
+
+            /// `low32(dst) = zext_8_32(atomic_load(addr))`
+            xatomic_load8_u32_z = XAtomicLoad8U32Z { dst: XReg, addr: AddrZ };
+            /// `low32(dst) = zext_16_32(atomic_load(addr))`
+            xatomic_load16le_u32_z = XAtomicLoad16LeU32Z { dst: XReg, addr: AddrZ };
+            /// `low32(dst) = atomic_load(addr)`
+            xatomic_load32le_z = XAtomicLoad32LeZ { dst: XReg, addr: AddrZ };
+            /// `dst = atomic_load(addr)`
+            xatomic_load64le_z = XAtomicLoad64LeZ { dst: XReg, addr: AddrZ };
+            /// `atomic_store(addr, low8(src))`
+            xatomic_store8_z = XAtomicStore8Z { addr: AddrZ, src: XReg };
+            /// `atomic_store(addr, low16(src))`
+            xatomic_store16le_z = XAtomicStore16LeZ { addr: AddrZ, src: XReg };
+            /// `atomic_store(addr, low32(src))`
+            xatomic_store32le_z = XAtomicStore32LeZ { addr: AddrZ, src: XReg };
+            /// `atomic_store(addr, src)`
+            xatomic_store64le_z = XAtomicStore64LeZ { addr: AddrZ, src: XReg };
+
+            // Atomic read-modify-write operations. Each of these atomically
+            // replaces `old`, the value at `addr`, with the result of the
+            // operation and returns `old` in `dst`.
+
+            /// `low32(dst) = zext_8_32(old); *addr = old + low8(src)`
+            xatomic_add8_u32_z = XAtomicAdd8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = old + low16(src)`
+            xatomic_add16le_u32_z = XAtomicAdd16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = old + low32(src)`
+            xatomic_add32le_z = XAtomicAdd32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = old + src`
+            xatomic_add64le_z = XAtomicAdd64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = old - low8(src)`
+            xatomic_sub8_u32_z = XAtomicSub8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = old - low16(src)`
+            xatomic_sub16le_u32_z = XAtomicSub16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = old - low32(src)`
+            xatomic_sub32le_z = XAtomicSub32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = old - src`
+            xatomic_sub64le_z = XAtomicSub64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = old & low8(src)`
+            xatomic_and8_u32_z = XAtomicAnd8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = old & low16(src)`
+            xatomic_and16le_u32_z = XAtomicAnd16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = old & low32(src)`
+            xatomic_and32le_z = XAtomicAnd32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = old & src`
+            xatomic_and64le_z = XAtomicAnd64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = !(old & low8(src))`
+            xatomic_nand8_u32_z = XAtomicNand8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = !(old & low16(src))`
+            xatomic_nand16le_u32_z = XAtomicNand16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = !(old & low32(src))`
+            xatomic_nand32le_z = XAtomicNand32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = !(old & src)`
+            xatomic_nand64le_z = XAtomicNand64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = old | low8(src)`
+            xatomic_or8_u32_z = XAtomicOr8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = old | low16(src)`
+            xatomic_or16le_u32_z = XAtomicOr16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = old | low32(src)`
+            xatomic_or32le_z = XAtomicOr32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = old | src`
+            xatomic_or64le_z = XAtomicOr64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = old ^ low8(src)`
+            xatomic_xor8_u32_z = XAtomicXor8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = old ^ low16(src)`
+            xatomic_xor16le_u32_z = XAtomicXor16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = old ^ low32(src)`
+            xatomic_xor32le_z = XAtomicXor32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = old ^ src`
+            xatomic_xor64le_z = XAtomicXor64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = low8(src)`
+            xatomic_xchg8_u32_z = XAtomicXchg8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = low16(src)`
+            xatomic_xchg16le_u32_z = XAtomicXchg16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = low32(src)`
+            xatomic_xchg32le_z = XAtomicXchg32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = src`
+            xatomic_xchg64le_z = XAtomicXchg64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = umin(old, low8(src))`
+            xatomic_umin8_u32_z = XAtomicUmin8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = umin(old, low16(src))`
+            xatomic_umin16le_u32_z = XAtomicUmin16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = umin(old, low32(src))`
+            xatomic_umin32le_z = XAtomicUmin32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = umin(old, src)`
+            xatomic_umin64le_z = XAtomicUmin64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = umax(old, low8(src))`
+            xatomic_umax8_u32_z = XAtomicUmax8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = umax(old, low16(src))`
+            xatomic_umax16le_u32_z = XAtomicUmax16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = umax(old, low32(src))`
+            xatomic_umax32le_z = XAtomicUmax32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = umax(old, src)`
+            xatomic_umax64le_z = XAtomicUmax64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = smin(old, low8(src))`
+            xatomic_smin8_u32_z = XAtomicSmin8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = smin(old, low16(src))`
+            xatomic_smin16le_u32_z = XAtomicSmin16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = smin(old, low32(src))`
+            xatomic_smin32le_z = XAtomicSmin32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = smin(old, src)`
+            xatomic_smin64le_z = XAtomicSmin64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_8_32(old); *addr = smax(old, low8(src))`
+            xatomic_smax8_u32_z = XAtomicSmax8U32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = zext_16_32(old); *addr = smax(old, low16(src))`
+            xatomic_smax16le_u32_z = XAtomicSmax16LeU32Z { dst: XReg, addr: AddrZ, src: XReg };
+            /// `low32(dst) = old; *addr = smax(old, low32(src))`
+            xatomic_smax32le_z = XAtomicSmax32LeZ { dst: XReg, addr: AddrZ, src: XReg };
+            /// `dst = old; *addr = smax(old, src)`
+            xatomic_smax64le_z = XAtomicSmax64LeZ { dst: XReg, addr: AddrZ, src: XReg };
+
+            // Atomic compare-and-exchange operations. Each of these atomically
+            // replaces `old`, the value at `addr`, with `replacement` if it's
+            // equal to `expected` and returns `old` in `dst`.
+
+            /// `low32(dst) = zext_8_32(old); if old == low8(expected) { *addr = low8(replacement) }`
+            xatomic_cmpxchg8_u32_z = XAtomicCmpxchg8U32Z {
+                dst: XReg,
+                addr: AddrZ,
+                expected: XReg,
+                replacement: XReg
+            };
+            /// `low32(dst) = zext_16_32(old); if old == low16(expected) { *addr = low16(replacement) }`
+            xatomic_cmpxchg16le_u32_z = XAtomicCmpxchg16LeU32Z {
+                dst: XReg,
+                addr: AddrZ,
+                expected: XReg,
+                replacement: XReg
+            };
+            /// `low32(dst) = old; if old == low32(expected) { *addr = low32(replacement) }`
+            xatomic_cmpxchg32le_z = XAtomicCmpxchg32LeZ {
+                dst: XReg,
+                addr: AddrZ,
+                expected: XReg,
+                replacement: XReg
+            };
+            /// `dst = old; if old == expected { *addr = replacement }`
+            xatomic_cmpxchg64le_z = XAtomicCmpxchg64LeZ {
+                dst: XReg,
+                addr: AddrZ,
+                expected: XReg,
+                replacement: XReg
+            };
         }
     };
 }
@@ -1423,6 +1687,8 @@ pub mod interp;
 pub mod profile;
 #[cfg(all(not(feature = "profile"), feature = "interp"))]
 mod profile_disabled;
+#[cfg(feature = "verify")]
+pub mod verify;
 #[cfg(all(not(feature = "profile"), feature = "interp"))]
 use profile_disabled as profile;
 
@@ -0,0 +1,500 @@
+//! Verification of untrusted pulley bytecode.
+//!
+//! The interpreter assumes that the bytecode it executes is valid: see the
+//! safety contract of `UnsafeBytecodeStream`. Bytecode produced by Cranelift
+//! in the same process satisfies this contract by construction, but bytecode
+//! produced elsewhere (e.g. loaded from disk or received over the network)
+//! does not. The [`Verifier`] in this module checks a function's bytecode for
+//! the structural properties that the interpreter relies on:
+//!
+//! * Every instruction decodes successfully, which includes all opcodes and
+//!   register operands being valid.
+//! * Every branch, including `jump` and the entries of `br_table32`, targets
+//!   the start of an instruction within the function.
+//! * Execution never falls through past the end of the function.
+//! * Frames are balanced: every `pop_frame` has a matching `push_frame`, all
+//!   paths reaching an instruction do so with the same number of frames
+//!   pushed, and no frame is left pushed upon `ret`.
+//!
+//! A `jump` whose target lies outside of the function is a tail call to another
+//! function. Such a `jump` must target one of the function entries given to
+//! [`Verifier::with_entries`], and like `xjump` it requires that no frame is
+//! left pushed. The targets of `call` and `xpcadd` are not verified since they
+//! refer to code outside of the function.
+//!
+//! Note that verification doesn't make arbitrary bytecode safe to execute: for
+//! example loads and stores are not bounds-checked by the interpreter. It
+//! only rules out bytecode that would make the interpreter itself misbehave
+//! while decoding and dispatching instructions.
+
+use crate::decode::*;
+use crate::imms::*;
+use crate::opcode::*;
+use crate::regs::*;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// An error found while verifying pulley bytecode.
+pub enum VerifyError {
+    /// The bytecode failed to decode.
+    Decoding(DecodingError),
+
+    /// A branch targets an offset outside of the function, which for `jump`
+    /// isn't the entry of another function either.
+    BranchOutOfBounds {
+        /// The position of the branching instruction.
+        position: usize,
+        /// The offset targeted by the branch.
+        target: isize,
+    },
+
+    /// A branch targets an offset that is not the start of an instruction.
+    BranchIntoInstruction {
+        /// The position of the branching instruction.
+        position: usize,
+        /// The offset targeted by the branch.
+        target: usize,
+    },
+
+    /// A `br_table32` instruction has no targets.
+    EmptyBrTable {
+        /// The position of the `br_table32` instruction.
+        position: usize,
+    },
+
+    /// Execution falls through past the end of the function.
+    FallthroughPastEnd {
+        /// The position of the last instruction of the function.
+        position: usize,
+    },
+
+    /// A frame is popped when no frame is pushed.
+    FrameUnderflow {
+        /// The position of the instruction popping the frame.
+        position: usize,
+    },
+
+    /// The function is exited while a frame is still pushed.
+    FrameNotPopped {
+        /// The position of the instruction exiting the function.
+        position: usize,
+    },
+
+    /// An instruction is reachable with different numbers of frames pushed.
+    FrameMismatch {
+        /// The position of the instruction.
+        position: usize,
+    },
+}
+
+impl From<DecodingError> for VerifyError {
+    fn from(error: DecodingError) -> Self {
+        Self::Decoding(error)
+    }
+}
+
+impl core::fmt::Debug for VerifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+impl core::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Decoding(e) => core::fmt::Display::fmt(e, f),
+            Self::BranchOutOfBounds { position, target } => write!(
+                f,
+                "branch at bytecode offset {position:#x} targets out-of-bounds offset {target:#x}"
+            ),
+            Self::BranchIntoInstruction { position, target } => write!(
+                f,
+                "branch at bytecode offset {position:#x} targets the middle of an \
+                 instruction at offset {target:#x}"
+            ),
+            Self::EmptyBrTable { position } => {
+                write!(f, "empty `br_table32` at bytecode offset {position:#x}")
+            }
+            Self::FallthroughPastEnd { position } => write!(
+                f,
+                "execution falls through past the end of the function at bytecode \
+                 offset {position:#x}"
+            ),
+            Self::FrameUnderflow { position } => write!(
+                f,
+                "frame popped without a pushed frame at bytecode offset {position:#x}"
+            ),
+            Self::FrameNotPopped { position } => write!(
+                f,
+                "function exited with a pushed frame at bytecode offset {position:#x}"
+            ),
+            Self::FrameMismatch { position } => write!(
+                f,
+                "bytecode offset {position:#x} is reachable with mismatched frames"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+/// How an instruction transfers control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Flow {
+    /// Falls through to the next instruction.
+    Next,
+    /// Pushes a frame and falls through to the next instruction.
+    PushFrame,
+    /// Pops a frame and falls through to the next instruction.
+    PopFrame,
+    /// Conditionally branches to its targets or falls through.
+    Branch,
+    /// Unconditionally branches to its targets.
+    Jump,
+    /// Unconditionally branches to its single target, which may be the entry
+    /// of another function if it is out of bounds.
+    MaybeTailJump,
+    /// Exits the function, either returning or tail calling.
+    Exit,
+    /// Doesn't transfer control anywhere.
+    Trap,
+}
+
+/// A decoded instruction.
+struct Inst {
+    start: usize,
+    flow: Flow,
+    targets: Vec<isize>,
+}
+
+/// A Pulley bytecode verifier.
+///
+/// This is implemented as an `OpVisitor`, where a `Verifier` is passed to a
+/// `Decoder` to collect the instructions of a function, which are checked
+/// by [`Verifier::finish`].
+///
+/// Alternatively, the `Verifier::verify` method can be used to verify a
+/// complete function.
+pub struct Verifier<'a> {
+    bytecode: SafeBytecodeStream<'a>,
+    raw_bytecode: &'a [u8],
+    entries: &'a [isize],
+    start: usize,
+    targets: Vec<isize>,
+    insts: Vec<Inst>,
+    error: Option<VerifyError>,
+}
+
+impl<'a> Verifier<'a> {
+    /// Verify the bytecode of a single function which doesn't tail call other
+    /// functions with `jump`.
+    pub fn verify(bytecode: &'a [u8]) -> Result<(), VerifyError> {
+        let mut verifier = Self::new(bytecode);
+        Decoder::decode_all(&mut verifier)?;
+        verifier.finish()
+    }
+
+    /// Create a new `Verifier` for the given function bytecode.
+    pub fn new(bytecode: &'a [u8]) -> Self {
+        Self {
+            bytecode: SafeBytecodeStream::new(bytecode),
+            raw_bytecode: bytecode,
+            entries: &[],
+            start: 0,
+            targets: Vec::new(),
+            insts: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Allow the function to tail call the functions starting at `entries`
+    /// with `jump`.
+    ///
+    /// The entries are offsets relative to the start of the function's
+    /// bytecode, so they're negative for functions preceding it, and must be
+    /// sorted.
+    pub fn with_entries(mut self, entries: &'a [isize]) -> Self {
+        self.entries = entries;
+        self
+    }
+
+    /// Check all the instructions decoded so far as a complete function.
+    pub fn finish(self) -> Result<(), VerifyError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let len = self.raw_bytecode.len();
+        if self.insts.is_empty() {
+            return Err(VerifyError::FallthroughPastEnd { position: 0 });
+        }
+
+        // Map each bytecode offset that starts an instruction to the index of
+        // that instruction.
+        let mut inst_at = vec![u32::MAX; len];
+        for (i, inst) in self.insts.iter().enumerate() {
+            inst_at[inst.start] = u32::try_from(i).unwrap();
+        }
+        let resolve = |position: usize, target: isize| -> Result<Option<usize>, VerifyError> {
+            let Ok(offset) = usize::try_from(target) else {
+                return Ok(None);
+            };
+            match inst_at.get(offset) {
+                None => Ok(None),
+                Some(&u32::MAX) => Err(VerifyError::BranchIntoInstruction {
+                    position,
+                    target: offset,
+                }),
+                Some(&i) => Ok(Some(i as usize)),
+            }
+        };
+
+        // Propagate the number of pushed frames along all control flow edges,
+        // starting from the function entry with no frame pushed.
+        let mut frames: Vec<Option<u32>> = vec![None; self.insts.len()];
+        let mut worklist = vec![0];
+        frames[0] = Some(0);
+        while let Some(i) = worklist.pop() {
+            let inst = &self.insts[i];
+            let position = inst.start;
+            let mut depth = frames[i].unwrap();
+            let mut succs = Vec::new();
+
+            match inst.flow {
+                Flow::Next | Flow::PushFrame | Flow::PopFrame | Flow::Branch => {
+                    if inst.flow == Flow::PushFrame {
+                        depth += 1;
+                    }
+                    if inst.flow == Flow::PopFrame {
+                        depth = depth
+                            .checked_sub(1)
+                            .ok_or(VerifyError::FrameUnderflow { position })?;
+                    }
+                    if i + 1 == self.insts.len() {
+                        return Err(VerifyError::FallthroughPastEnd { position });
+                    }
+                    succs.push(i + 1);
+                }
+                Flow::Jump | Flow::MaybeTailJump => {}
+                Flow::Exit => {
+                    if depth != 0 {
+                        return Err(VerifyError::FrameNotPopped { position });
+                    }
+                }
+                Flow::Trap => {}
+            }
+
+            for &target in &inst.targets {
+                let absolute = (position as isize).wrapping_add(target);
+                match resolve(position, absolute)? {
+                    Some(succ) => succs.push(succ),
+                    None if inst.flow == Flow::MaybeTailJump
+                        && self.entries.binary_search(&absolute).is_ok() =>
+                    {
+                        if depth != 0 {
+                            return Err(VerifyError::FrameNotPopped { position });
+                        }
+                    }
+                    None => {
+                        return Err(VerifyError::BranchOutOfBounds {
+                            position,
+                            target: absolute,
+                        });
+                    }
+                }
+            }
+
+            for succ in succs {
+                match frames[succ] {
+                    Some(d) if d == depth => {}
+                    Some(_) => {
+                        return Err(VerifyError::FrameMismatch {
+                            position: self.insts[succ].start,
+                        });
+                    }
+                    None => {
+                        frames[succ] = Some(depth);
+                        worklist.push(succ);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Classifies the instruction starting at `self.start`.
+    fn flow(&self) -> Flow {
+        let bytes = &self.raw_bytecode[self.start..];
+        match Opcode::new(bytes[0]).unwrap() {
+            Opcode::Ret | Opcode::XJump => Flow::Exit,
+            Opcode::Jump => Flow::MaybeTailJump,
+            Opcode::BrTable32 => Flow::Jump,
+            Opcode::PushFrame | Opcode::PushFrameSave => Flow::PushFrame,
+            Opcode::PopFrame | Opcode::PopFrameRestore => Flow::PopFrame,
+            // The targets of calls are other functions, so they aren't
+            // branches within this function.
+            Opcode::Call | Opcode::Call1 | Opcode::Call2 | Opcode::Call3 | Opcode::Call4 => {
+                Flow::Next
+            }
+            Opcode::ExtendedOp => {
+                let code = u16::from_le_bytes([bytes[1], bytes[2]]);
+                match ExtendedOpcode::new(code).unwrap() {
+                    ExtendedOpcode::Trap => Flow::Trap,
+                    _ => Flow::Next,
+                }
+            }
+            _ if !self.targets.is_empty() => Flow::Branch,
+            _ => Flow::Next,
+        }
+    }
+
+    fn verify_op(&mut self, operands: &[&dyn Verify]) {
+        for operand in operands {
+            operand.verify(self);
+        }
+        let flow = self.flow();
+        let targets = match flow {
+            Flow::Branch | Flow::MaybeTailJump => core::mem::take(&mut self.targets),
+            _ => {
+                // Operands of other instructions, such as `xpcadd`, are not
+                // branch targets.
+                self.targets.clear();
+                Vec::new()
+            }
+        };
+        self.insts.push(Inst {
+            start: self.start,
+            flow,
+            targets,
+        });
+    }
+
+    fn verify_br_table32(&mut self, _idx: XReg, amt: u32) {
+        let start = self.start;
+        let mut targets = Vec::new();
+        for _ in 0..amt {
+            // Each entry is relative to its own position, but the targets of
+            // an instruction are relative to its start.
+            let entry = self.bytecode.position();
+            match PcRelOffset::decode(&mut self.bytecode) {
+                Ok(offset) => {
+                    let rel = (entry - start) as isize;
+                    targets.push(rel + i32::from(offset) as isize);
+                }
+                Err(e) => {
+                    self.error.get_or_insert(e.into());
+                    return;
+                }
+            }
+        }
+        if amt == 0 {
+            self.error
+                .get_or_insert(VerifyError::EmptyBrTable { position: start });
+        }
+        self.insts.push(Inst {
+            start,
+            flow: Flow::Jump,
+            targets,
+        });
+    }
+}
+
+/// Anything inside an instruction that may need verification.
+trait Verify {
+    fn verify(&self, verifier: &mut Verifier<'_>);
+}
+
+impl Verify for PcRelOffset {
+    fn verify(&self, verifier: &mut Verifier<'_>) {
+        verifier.targets.push(i32::from(*self) as isize);
+    }
+}
+
+macro_rules! impl_verify_nop {
+    ($($ty:ty),*) => {
+        $(
+            impl Verify for $ty {
+                fn verify(&self, _verifier: &mut Verifier<'_>) {}
+            }
+        )*
+    };
+}
+
+impl_verify_nop!(
+    XReg, FReg, VReg, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, U6, AddrO32, AddrZ,
+    AddrG32, AddrG32Bne
+);
+
+impl<D, S1, S2> Verify for BinaryOperands<D, S1, S2> {
+    fn verify(&self, _verifier: &mut Verifier<'_>) {}
+}
+
+impl<R> Verify for UpperRegSet<R> {
+    fn verify(&self, _verifier: &mut Verifier<'_>) {}
+}
+
+macro_rules! impl_verify {
+    (
+        $(
+            $( #[$attr:meta] )*
+                $snake_name:ident = $name:ident $( {
+                $(
+                    $( #[$field_attr:meta] )*
+                    $field:ident : $field_ty:ty
+                ),*
+            } )? ;
+        )*
+    ) => {
+        $(
+            impl_verify!(@one $snake_name = $name $( { $($field: $field_ty),* } )?);
+        )*
+    };
+
+    // Verifying `br_table` is a bit special as it has trailing branch targets
+    // after the opcode of the branch table itself.
+    (
+        @one br_table32 = BrTable32 $( {
+            $(
+                $field:ident : $field_ty:ty
+            ),*
+        } )?
+    ) => {
+        fn br_table32(&mut self $( $( , $field : $field_ty )* )? ) {
+            self.verify_br_table32($($($field),*)?)
+        }
+    };
+
+    // All other opcodes other than `br_table` are handled in the same manner.
+    (
+        @one $snake_name:ident = $name:ident $( {
+            $(
+                $field:ident : $field_ty:ty
+            ),*
+        } )?
+    ) => {
+        fn $snake_name(&mut self $( $( , $field : $field_ty )* )? ) {
+            self.verify_op(&[$($(&$field),*)?])
+        }
+    };
+}
+
+impl<'a> OpVisitor for Verifier<'a> {
+    type BytecodeStream = SafeBytecodeStream<'a>;
+
+    fn bytecode(&mut self) -> &mut Self::BytecodeStream {
+        &mut self.bytecode
+    }
+
+    type Return = ();
+
+    fn before_visit(&mut self) {
+        self.start = self.bytecode.position();
+    }
+
+    for_each_op!(impl_verify);
+}
+
+impl ExtendedOpVisitor for Verifier<'_> {
+    for_each_extended_op!(impl_verify);
+}
@@ -2,10 +2,13 @@
 
 use interp::Val;
 use pulley_interpreter::{
-    interp::{DoneReason, Vm},
+    interp::{DoneReason, StackMemory, TrapKind, Vm},
     *,
 };
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use std::{cell::UnsafeCell, fmt::Debug, ptr::NonNull};
+use wasmtime_core::error::OutOfMemory;
 
 fn encoded(ops: &[Op]) -> Vec<u8> {
     let mut encoded = vec![];
@@ -681,6 +684,87 @@ fn xstore64_le_o32() {
     assert_eq!(c, expected);
 }
 
+#[test]
+fn xload_aligned_o32() {
+    let a = UnsafeCell::new([11u64.to_le(), (-1i64 as u64).to_le()]);
+
+    for (expected, offset) in [(11, 0), (u64::MAX, 8)] {
+        unsafe {
+            assert_one(
+                [
+                    (x(0), Val::from(0x1234567812345678u64)),
+                    (x(1), Val::from(a.get())),
+                ],
+                XLoad64LeAlignedO32 {
+                    dst: x(0),
+                    addr: AddrO32 { addr: x(1), offset },
+                },
+                x(0),
+                expected,
+            );
+        }
+    }
+
+    for (expected, offset) in [(11, 0), (0, 4), (u32::MAX, 12)] {
+        let init = 0x1234567812345678u64;
+        let expected = init & !u64::from(u32::MAX) | u64::from(expected);
+        unsafe {
+            assert_one(
+                [(x(0), Val::from(init)), (x(1), Val::from(a.get()))],
+                XLoad32LeAlignedO32 {
+                    dst: x(0),
+                    addr: AddrO32 { addr: x(1), offset },
+                },
+                x(0),
+                expected,
+            );
+        }
+    }
+}
+
+#[test]
+fn xstore_aligned_o32() {
+    let a = UnsafeCell::new([0x1234567812345678u64, 0x1234567812345678]);
+
+    unsafe {
+        assert_one(
+            [
+                (x(0), Val::from(a.get())),
+                (x(1), Val::from(0x1111111111111111u64)),
+            ],
+            XStore64LeAlignedO32 {
+                addr: AddrO32 {
+                    addr: x(0),
+                    offset: 0,
+                },
+                src: x(1),
+            },
+            x(1),
+            0x1111111111111111,
+        );
+        assert_one(
+            [(x(0), Val::from(a.get())), (x(1), Val::from(0x22222222u64))],
+            XStore32LeAlignedO32 {
+                addr: AddrO32 {
+                    addr: x(0),
+                    offset: 8,
+                },
+                src: x(1),
+            },
+            x(1),
+            0x22222222,
+        );
+    }
+
+    let [a, b] = a.into_inner();
+    assert_eq!(a, 0x1111111111111111u64.to_le());
+    assert_eq!(b.to_ne_bytes()[..4], 0x22222222u32.to_le_bytes());
+    assert_eq!(
+        b.to_ne_bytes()[4..],
+        0x1234567812345678u64.to_ne_bytes()[4..]
+    );
+}
+
 #[test]
 fn bitcast_int_from_float_32() {
     for val in [
@@ -816,3 +900,475 @@ fn trap() {
     // `dst` should not have been written to the second time.
     assert_eq!(vm.state()[dst].get_u32(), 1);
 }
+
+#[test]
+fn xatomic_rmw() {
+    let a = UnsafeCell::new([0x1234u16.to_le(), 0xff00u16.to_le()]);
+    let b = UnsafeCell::new(0x1111_2222_3333_4444u64.to_le());
+
+    unsafe {
+        assert_one(
+            [
+                (x(0), Val::from(0x1234567812345678u64)),
+                (x(1), Val::from(a.get())),
+                (x(2), Val::from(0x0001u64)),
+            ],
+            XAtomicAdd16LeU32Z {
+                dst: x(0),
+                addr: AddrZ {
+                    addr: x(1),
+                    offset: 2,
+                },
+                src: x(2),
+            },
+            x(0),
+            0x12345678_0000ff00,
+        );
+        assert_one(
+            [
+                (x(0), Val::from(0u64)),
+                (x(1), Val::from(a.get())),
+                (x(2), Val::from(0xfffe_u64)),
+            ],
+            XAtomicSmin16LeU32Z {
+                dst: x(0),
+                addr: AddrZ {
+                    addr: x(1),
+                    offset: 0,
+                },
+                src: x(2),
+            },
+            x(0),
+            0x1234,
+        );
+        assert_one(
+            [
+                (x(0), Val::from(0u64)),
+                (x(1), Val::from(b.get())),
+                (x(2), Val::from(0x5555_5555_5555_5555u64)),
+            ],
+            XAtomicXchg64LeZ {
+                dst: x(0),
+                addr: AddrZ {
+                    addr: x(1),
+                    offset: 0,
+                },
+                src: x(2),
+            },
+            x(0),
+            0x1111_2222_3333_4444,
+        );
+    }
+
+    let [a0, a1] = a.into_inner().map(u16::from_le);
+    assert_eq!(a0, 0xfffe);
+    assert_eq!(a1, 0xff01);
+    assert_eq!(u64::from_le(b.into_inner()), 0x5555_5555_5555_5555);
+}
+
+#[test]
+fn xatomic_cmpxchg() {
+    let a = UnsafeCell::new(11u32.to_le());
+
+    for (expected, replacement, old, new) in [(22, 33, 11, 11), (11, 33, 11, 33)] {
+        unsafe {
+            assert_one(
+                [
+                    (x(0), Val::from(0u64)),
+                    (x(1), Val::from(a.get())),
+                    (x(2), Val::from(expected)),
+                    (x(3), Val::from(replacement)),
+                ],
+                XAtomicCmpxchg32LeZ {
+                    dst: x(0),
+                    addr: AddrZ {
+                        addr: x(1),
+                        offset: 0,
+                    },
+                    expected: x(2),
+                    replacement: x(3),
+                },
+                x(0),
+                old,
+            );
+        }
+        assert_eq!(u32::from_le(unsafe { *a.get() }), new);
+    }
+}
+
+#[test]
+fn xatomic_null_traps() {
+    let mut vm = Vm::new().unwrap();
+    vm.state_mut()[x(1)] = interp::XRegVal::new_u64(0);
+
+    unsafe {
+        run(
+            &mut vm,
+            &[
+                Op::from(XAtomicStore32LeZ {
+                    addr: AddrZ {
+                        addr: x(1),
+                        offset: 0,
+                    },
+                    src: x(0),
+                }),
+                Op::Ret(Ret {}),
+            ],
+        )
+        .unwrap_err();
+    }
+}
+
+#[test]
+fn frames() {
+    let mut vm = Vm::new().unwrap();
+
+    // A function which calls another function that traps, both of which set
+    // up a frame.
+    let caller = |offset: i32| {
+        [
+            Op::PushFrame(PushFrame {}),
+            Op::Call(Call {
+                offset: PcRelOffset::from(offset),
+            }),
+            Op::PopFrame(PopFrame {}),
+            Op::Ret(Ret {}),
+        ]
+    };
+    let call_offset = encoded(&caller(0)[..1]).len();
+    let callee_offset = encoded(&caller(0)).len();
+    let return_offset = encoded(&caller(0)[..2]).len();
+    let mut ops = caller((callee_offset - call_offset) as i32).to_vec();
+    ops.push(Op::PushFrame(PushFrame {}));
+    let trap_offset = encoded(&ops).len();
+    ops.push(Op::ExtendedOp(ExtendedOp::Trap(Trap {})));
+    let bytecode = encoded(&ops);
+    let start = NonNull::from(&bytecode[..]).cast::<u8>();
+
+    let pc = match unsafe { vm.call(start, &[], []) } {
+        DoneReason::Trap { pc, .. } => pc,
+        _ => panic!("expected a trap"),
+    };
+    assert_eq!(pc, unsafe { start.add(trap_offset) });
+
+    let frames = unsafe { vm.frames(pc) }.collect::<Vec<_>>();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].pc(), pc);
+    assert_eq!(frames[0].fp(), vm.fp());
+    assert_eq!(frames[1].pc(), unsafe { start.add(return_offset) });
+    assert!(frames[1].fp() > frames[0].fp());
+}
+
+/// A `StackMemory` which is allocated in full up front, but which records how
+/// much of itself has been committed.
+struct TrackedStack {
+    storage: Vec<u128>,
+    committed: Arc<AtomicUsize>,
+}
+
+unsafe impl StackMemory for TrackedStack {
+    fn base(&self) -> NonNull<u8> {
+        NonNull::from(&self.storage[..]).cast()
+    }
+
+    fn len(&self) -> usize {
+        self.storage.len() * 16
+    }
+
+    fn commit(&mut self, size: usize) -> Result<usize, OutOfMemory> {
+        assert!(size <= self.len());
+        self.committed.fetch_max(size, SeqCst);
+        Ok(size)
+    }
+}
+
+#[test]
+fn stack_grows_on_demand() {
+    let committed = Arc::new(AtomicUsize::new(0));
+    let mut vm = Vm::with_stack_memory(Box::new(TrackedStack {
+        storage: vec![0; (1 << 20) / 16],
+        committed: committed.clone(),
+    }));
+
+    // Small allocations use the minimum growth.
+    unsafe {
+        run(
+            &mut vm,
+            &[
+                Op::PushFrame(PushFrame {}),
+                Op::StackAlloc32(StackAlloc32 { amt: 16 }),
+                Op::StackFree32(StackFree32 { amt: 16 }),
+                Op::PopFrame(PopFrame {}),
+                Op::Ret(Ret {}),
+            ],
+        )
+        .unwrap();
+    }
+    assert_eq!(committed.load(SeqCst), 64 << 10);
+
+    // Larger allocations grow the stack further.
+    unsafe {
+        run(
+            &mut vm,
+            &[
+                Op::StackAlloc32(StackAlloc32 { amt: 300 << 10 }),
+                Op::StackFree32(StackFree32 { amt: 300 << 10 }),
+                Op::Ret(Ret {}),
+            ],
+        )
+        .unwrap();
+    }
+    assert_eq!(committed.load(SeqCst), 300 << 10);
+
+    // Going beyond the reservation is a stack overflow.
+    let ops = [
+        Op::StackAlloc32(StackAlloc32 {
+            amt: (1 << 20) + 16,
+        }),
+        Op::Ret(Ret {}),
+    ];
+    let bytecode = encoded(&ops);
+    let start = NonNull::from(&bytecode[..]).cast::<u8>();
+    match unsafe { vm.call(start, &[], []) } {
+        DoneReason::Trap {
+            pc,
+            kind: Some(TrapKind::StackOverflow),
+        } => assert_eq!(pc, start),
+        _ => panic!("expected a stack overflow"),
+    }
+    assert_eq!(committed.load(SeqCst), 300 << 10);
+}
+
+#[test]
+fn huge_stack_alloc_overflows() {
+    // On 32-bit hosts this allocation would wrap around the address space if
+    // the stack pointer decrement weren't checked.
+    let mut vm = Vm::with_stack(64 << 10).unwrap();
+    let ops = [
+        Op::StackAlloc32(StackAlloc32 { amt: u32::MAX }),
+        Op::Ret(Ret {}),
+    ];
+    let bytecode = encoded(&ops);
+    let start = NonNull::from(&bytecode[..]).cast::<u8>();
+    match unsafe { vm.call(start, &[], []) } {
+        DoneReason::Trap {
+            pc,
+            kind: Some(TrapKind::StackOverflow),
+        } => assert_eq!(pc, start),
+        _ => panic!("expected a stack overflow"),
+    }
+}
+
+#[test]
+fn registered_host_calls() {
+    use pulley_interpreter::interp::{HostCallSignature, RegType};
+
+    let mut vm = Vm::new().unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls2 = calls.clone();
+    vm.register_host_call(1, move |a: u32, scale: f64, b: i64| -> u64 {
+        calls2.fetch_add(1, SeqCst);
+        (f64::from(a) * scale) as u64 + b as u64
+    })
+    .unwrap();
+    vm.register_host_call(2, |x: f64| x * 2.0).unwrap();
+    assert_eq!(
+        vm.host_call_signature(1),
+        Some(HostCallSignature {
+            params: &[RegType::XReg, RegType::FReg, RegType::XReg],
+            result: Some(RegType::XReg),
+        })
+    );
+    assert_eq!(vm.host_call_signature(3), None);
+
+    // Arguments are assigned to registers per class, so `b` is in `x1`, and
+    // the result replaces `x0`. Execution resumes after each host call.
+    let ops = [
+        Op::Xconst8(Xconst8 { dst: x(0), imm: 20 }),
+        Op::Xconst8(Xconst8 { dst: x(1), imm: 2 }),
+        Op::ExtendedOp(ExtendedOp::CallIndirectHost(CallIndirectHost { id: 1 })),
+        Op::Xconst8(Xconst8 { dst: x(1), imm: 3 }),
+        Op::ExtendedOp(ExtendedOp::CallIndirectHost(CallIndirectHost { id: 1 })),
+        Op::ExtendedOp(ExtendedOp::CallIndirectHost(CallIndirectHost { id: 2 })),
+        Op::Ret(Ret {}),
+    ];
+    vm.state_mut()[f(0)] = interp::FRegVal::new_f64(0.5);
+    unsafe {
+        run(&mut vm, &ops).unwrap();
+    }
+    assert_eq!(calls.load(SeqCst), 2);
+    assert_eq!(vm.state()[x(0)].get_u64(), 9);
+    assert_eq!(vm.state()[f(0)].get_f64(), 1.0);
+
+    // Ids without a registered function are still left to the embedder.
+    assert!(vm.unregister_host_call(1));
+    assert!(!vm.unregister_host_call(1));
+    let bytecode = encoded(&ops);
+    let start = NonNull::from(&bytecode[..]).cast::<u8>();
+    match unsafe { vm.call(start, &[], []) } {
+        DoneReason::CallIndirectHost { id: 1, .. } => {}
+        _ => panic!("expected an unhandled host call"),
+    }
+    assert_eq!(calls.load(SeqCst), 2);
+}
+
+#[test]
+fn checkpoint_and_restore() {
+    use pulley_interpreter::interp::XRegVal;
+
+    // A function which calls another function that spills `x0` to its stack,
+    // stops for the host, and then adds the spilled value back into `x0`. The
+    // stack slot is addressed through `sp`, which is rebased when the stack
+    // moves, while `x2` holds a copy of `sp` which isn't.
+    let slot = AddrO32 {
+        addr: XReg::sp,
+        offset: 0,
+    };
+    let caller = |offset: i32| {
+        [
+            Op::PushFrame(PushFrame {}),
+            Op::Call(Call {
+                offset: PcRelOffset::from(offset),
+            }),
+            Op::PopFrame(PopFrame {}),
+            Op::Ret(Ret {}),
+        ]
+    };
+    let call_offset = encoded(&caller(0)[..1]).len();
+    let callee_offset = encoded(&caller(0)).len();
+    let mut ops = caller((callee_offset - call_offset) as i32).to_vec();
+    ops.extend([
+        Op::PushFrame(PushFrame {}),
+        Op::StackAlloc32(StackAlloc32 { amt: 16 }),
+        Op::Xmov(Xmov {
+            dst: x(2),
+            src: XReg::sp,
+        }),
+        Op::XStore64LeO32(XStore64LeO32 {
+            addr: slot,
+            src: x(0),
+        }),
+        Op::ExtendedOp(ExtendedOp::CallIndirectHost(CallIndirectHost { id: 0 })),
+        Op::XLoad64LeO32(XLoad64LeO32 {
+            dst: x(1),
+            addr: slot,
+        }),
+        Op::Xadd64(Xadd64 {
+            operands: BinaryOperands::new(x(0), x(0), x(1)),
+        }),
+        Op::XStore64LeO32(XStore64LeO32 {
+            addr: slot,
+            src: x(0),
+        }),
+        Op::StackFree32(StackFree32 { amt: 16 }),
+        Op::PopFrame(PopFrame {}),
+        Op::Ret(Ret {}),
+    ]);
+    let bytecode = encoded(&ops);
+    let start = NonNull::from(&bytecode[..]).cast::<u8>();
+
+    let mut vm = Vm::new().unwrap();
+    vm.state_mut()[x(0)] = XRegVal::new_u64(7);
+    let lr = unsafe { vm.call_start(&[]) };
+    let resume = match unsafe { vm.call_run(start) } {
+        DoneReason::CallIndirectHost { id: 0, resume } => resume,
+        _ => panic!("expected an unhandled host call"),
+    };
+    let checkpoint = vm.checkpoint(resume).unwrap();
+    assert_eq!(checkpoint.pc(), resume);
+    let frames = unsafe { vm.frames(resume) }
+        .map(|frame| frame.fp() as usize)
+        .collect::<Vec<_>>();
+    assert_eq!(frames.len(), 2);
+
+    let finish = |vm: &mut Vm, pc: NonNull<u8>| {
+        match unsafe { vm.call_run(pc) } {
+            DoneReason::ReturnToHost(()) => {}
+            _ => panic!("expected a return to the host"),
+        }
+        unsafe { vm.call_end(lr, []) }.for_each(drop);
+        vm.state()[x(0)].get_u64()
+    };
+
+    // Continuing with a different `x0` overwrites the spilled value with the
+    // sum.
+    vm.state_mut()[x(0)] = XRegVal::new_u64(1);
+    assert_eq!(finish(&mut vm, resume), 8);
+
+    // Restoring goes back to the state at the host call, stack included.
+    let pc = unsafe { vm.restore(&checkpoint) }.unwrap();
+    assert_eq!(pc, resume);
+    assert_eq!(finish(&mut vm, pc), 14);
+
+    // Restoring into a VM with a different stack rebases pointers into the
+    // stack, including the chain of frame pointers.
+    let mut other = Vm::with_stack(64 << 10).unwrap();
+    let pc = unsafe { other.restore(&checkpoint) }.unwrap();
+    let x2 = vm.state()[x(2)].get_u64();
+    assert_ne!(other.state()[XReg::sp].get_u64(), x2);
+    assert_eq!(other.state()[x(2)].get_u64(), x2);
+    let other_frames = unsafe { other.frames(pc) }
+        .map(|frame| frame.fp() as usize)
+        .collect::<Vec<_>>();
+    assert_eq!(other_frames.len(), 2);
+    let delta = frames[0].wrapping_sub(other_frames[0]);
+    assert_ne!(delta, 0);
+    assert_eq!(frames[1].wrapping_sub(other_frames[1]), delta);
+    drop(vm);
+    assert_eq!(finish(&mut other, pc), 14);
+
+    // A stack which is too small for the checkpoint can't be restored into.
+    let mut small = Vm::with_stack(16).unwrap();
+    assert!(unsafe { small.restore(&checkpoint) }.is_err());
+}
+
+#[test]
+fn restore_onto_different_stack_and_return() {
+    // A caller with a frame calls a leaf function which stops for the host
+    // and then returns through `lr` without having saved it on the stack.
+    let caller = |offset: i32| {
+        [
+            Op::PushFrame(PushFrame {}),
+            Op::Call(Call {
+                offset: PcRelOffset::from(offset),
+            }),
+            Op::Xadd64(Xadd64 {
+                operands: BinaryOperands::new(x(0), x(0), x(0)),
+            }),
+            Op::PopFrame(PopFrame {}),
+            Op::Ret(Ret {}),
+        ]
+    };
+    let call_offset = encoded(&caller(0)[..1]).len();
+    let callee_offset = encoded(&caller(0)).len();
+    let mut ops = caller((callee_offset - call_offset) as i32).to_vec();
+    ops.extend([
+        Op::ExtendedOp(ExtendedOp::CallIndirectHost(CallIndirectHost { id: 0 })),
+        Op::Ret(Ret {}),
+    ]);
+    let bytecode = encoded(&ops);
+    let start = NonNull::from(&bytecode[..]).cast::<u8>();
+
+    let mut vm = Vm::new().unwrap();
+    vm.state_mut()[x(0)] = interp::XRegVal::new_u64(21);
+    let host_lr = unsafe { vm.call_start(&[]) };
+    let resume = match unsafe { vm.call_run(start) } {
+        DoneReason::CallIndirectHost { id: 0, resume } => resume,
+        _ => panic!("expected an unhandled host call"),
+    };
+    let checkpoint = vm.checkpoint(resume).unwrap();
+    let lr = vm.lr();
+    drop(vm);
+
+    // `lr` is a bytecode address, so it's restored as-is even though the
+    // stack moved.
+    let mut other = Vm::with_stack(64 << 10).unwrap();
+    let pc = unsafe { other.restore(&checkpoint) }.unwrap();
+    assert_eq!(other.lr(), lr);
+    match unsafe { other.call_run(pc) } {
+        DoneReason::ReturnToHost(()) => {}
+        _ => panic!("expected a return to the host"),
+    }
+    unsafe { other.call_end(host_lr, []) }.for_each(drop);
+    assert_eq!(other.state()[x(0)].get_u64(), 42);
+}
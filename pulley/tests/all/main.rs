@@ -4,6 +4,9 @@ mod disas;
 #[cfg(feature = "interp")]
 mod interp;
 
+#[cfg(all(feature = "verify", feature = "encode"))]
+mod verify;
+
 // Test the property relied on by `crates/cranelift/src/obj.rs` when filling in
 // the `PulleyHostcall` relocation.
 #[test]
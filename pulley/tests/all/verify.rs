@@ -0,0 +1,266 @@
+//! Bytecode verifier tests.
+
+use pulley_interpreter::decode::Decoder;
+use pulley_interpreter::verify::{Verifier, VerifyError};
+use pulley_interpreter::*;
+
+fn encoded(ops: &[Op]) -> Vec<u8> {
+    let mut encoded = vec![];
+    for op in ops {
+        op.encode(&mut encoded);
+    }
+    log::trace!("encoded: {encoded:?}");
+    encoded
+}
+
+fn verify(ops: &[Op]) -> Result<(), VerifyError> {
+    Verifier::verify(&encoded(ops))
+}
+
+fn xadd32() -> Op {
+    Op::Xadd32(Xadd32 {
+        operands: BinaryOperands {
+            dst: XReg::x0,
+            src1: XReg::x0,
+            src2: XReg::x1,
+        },
+    })
+}
+
+fn br_if(offset: i32) -> Op {
+    Op::BrIf(BrIf {
+        cond: XReg::x0,
+        offset: PcRelOffset::from(offset),
+    })
+}
+
+#[test]
+fn simple() {
+    verify(&[
+        Op::PushFrame(PushFrame {}),
+        xadd32(),
+        Op::PopFrame(PopFrame {}),
+        Op::Ret(Ret {}),
+    ])
+    .unwrap();
+}
+
+#[test]
+fn leaf_and_trap() {
+    verify(&[xadd32(), Op::Ret(Ret {})]).unwrap();
+    verify(&[Op::ExtendedOp(ExtendedOp::Trap(Trap {}))]).unwrap();
+}
+
+#[test]
+fn loop_back_edge() {
+    verify(&[
+        // 0
+        Op::PushFrame(PushFrame {}),
+        // 1
+        Op::Nop(Nop {}),
+        // 2, branch back to the `nop`.
+        br_if(-1),
+        // 8
+        Op::PopFrame(PopFrame {}),
+        // 9
+        Op::Ret(Ret {}),
+    ])
+    .unwrap();
+}
+
+#[test]
+fn branch_out_of_bounds() {
+    let err = verify(&[br_if(100), Op::Ret(Ret {})]).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            VerifyError::BranchOutOfBounds {
+                position: 0,
+                target: 100
+            }
+        ),
+        "{err}"
+    );
+
+    let err = verify(&[Op::Nop(Nop {}), br_if(-2), Op::Ret(Ret {})]).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            VerifyError::BranchOutOfBounds {
+                position: 1,
+                target: -1
+            }
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn branch_into_instruction() {
+    let err = verify(&[xadd32(), br_if(-2), Op::Ret(Ret {})]).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            VerifyError::BranchIntoInstruction {
+                position: 3,
+                target: 1
+            }
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn tail_calls() {
+    let with_entries = |ops: &[Op], entries: &[isize]| {
+        let bytecode = encoded(ops);
+        let mut verifier = Verifier::new(&bytecode).with_entries(entries);
+        Decoder::decode_all(&mut verifier)?;
+        verifier.finish()
+    };
+
+    // Jumps out of the function are tail calls to the entries of other
+    // functions and must not leave a frame pushed.
+    let tail_call = [Op::Jump(Jump {
+        offset: PcRelOffset::from(-100),
+    })];
+    with_entries(&tail_call, &[-100, 50]).unwrap();
+    verify(&[Op::XJump(XJump { reg: XReg::x1 })]).unwrap();
+
+    // Jumps out of the function anywhere else are rejected.
+    let err = verify(&tail_call).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            VerifyError::BranchOutOfBounds {
+                position: 0,
+                target: -100
+            }
+        ),
+        "{err}"
+    );
+    let err = with_entries(&tail_call, &[-99]).unwrap_err();
+    assert!(
+        matches!(err, VerifyError::BranchOutOfBounds { .. }),
+        "{err}"
+    );
+
+    let err = with_entries(
+        &[
+            Op::PushFrame(PushFrame {}),
+            Op::Jump(Jump {
+                offset: PcRelOffset::from(100),
+            }),
+        ],
+        &[101],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, VerifyError::FrameNotPopped { position: 1 }),
+        "{err}"
+    );
+}
+
+#[test]
+fn frames() {
+    let err = verify(&[Op::PushFrame(PushFrame {}), Op::Ret(Ret {})]).unwrap_err();
+    assert!(
+        matches!(err, VerifyError::FrameNotPopped { position: 1 }),
+        "{err}"
+    );
+
+    let err = verify(&[Op::PopFrame(PopFrame {}), Op::Ret(Ret {})]).unwrap_err();
+    assert!(
+        matches!(err, VerifyError::FrameUnderflow { position: 0 }),
+        "{err}"
+    );
+
+    // The `trap` is reachable both with and without the frame pushed.
+    let err = verify(&[
+        // 0
+        Op::PushFrame(PushFrame {}),
+        // 1
+        br_if(7),
+        // 7
+        Op::PopFrame(PopFrame {}),
+        // 8
+        Op::ExtendedOp(ExtendedOp::Trap(Trap {})),
+    ])
+    .unwrap_err();
+    assert!(
+        matches!(err, VerifyError::FrameMismatch { position: 8 }),
+        "{err}"
+    );
+}
+
+#[test]
+fn fallthrough_past_end() {
+    let err = verify(&[Op::Nop(Nop {}), xadd32()]).unwrap_err();
+    assert!(
+        matches!(err, VerifyError::FallthroughPastEnd { position: 1 }),
+        "{err}"
+    );
+
+    let err = Verifier::verify(&[]).unwrap_err();
+    assert!(
+        matches!(err, VerifyError::FallthroughPastEnd { position: 0 }),
+        "{err}"
+    );
+}
+
+#[test]
+fn br_table() {
+    let mut bytecode = Vec::new();
+    BrTable32 {
+        idx: XReg::x1,
+        amt: 2,
+    }
+    .encode(&mut bytecode);
+    // Both entries target the `ret` just after the table at offset 14.
+    bytecode.extend_from_slice(&8_i32.to_le_bytes());
+    bytecode.extend_from_slice(&4_i32.to_le_bytes());
+    Ret {}.encode(&mut bytecode);
+    Verifier::verify(&bytecode).unwrap();
+
+    // An entry targeting the table itself is out of the instruction stream.
+    bytecode[10..14].copy_from_slice(&0_i32.to_le_bytes());
+    let err = Verifier::verify(&bytecode).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            VerifyError::BranchIntoInstruction {
+                position: 0,
+                target: 10
+            }
+        ),
+        "{err}"
+    );
+
+    let mut bytecode = Vec::new();
+    BrTable32 {
+        idx: XReg::x1,
+        amt: 0,
+    }
+    .encode(&mut bytecode);
+    Ret {}.encode(&mut bytecode);
+    let err = Verifier::verify(&bytecode).unwrap_err();
+    assert!(
+        matches!(err, VerifyError::EmptyBrTable { position: 0 }),
+        "{err}"
+    );
+}
+
+#[test]
+fn invalid_encoding() {
+    let err = Verifier::verify(&[0xff]).unwrap_err();
+    assert!(matches!(err, VerifyError::Decoding(_)), "{err}");
+
+    // `xadd32` with an operand whose register is out of range.
+    let mut bytecode = encoded(&[Op::Call1(Call1 {
+        arg1: XReg::x0,
+        offset: PcRelOffset::from(0),
+    })]);
+    bytecode[1] = 0xff;
+    let err = Verifier::verify(&bytecode).unwrap_err();
+    assert!(matches!(err, VerifyError::Decoding(_)), "{err}");
+}
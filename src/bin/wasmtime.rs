@@ -90,12 +90,24 @@ enum Subcommand {
     #[cfg(feature = "objdump")]
     Objdump(wasmtime_cli::commands::ObjdumpCommand),
 
+    /// Inspects Wasm core dumps
+    #[cfg(feature = "coredump")]
+    Coredump(wasmtime_cli::commands::CoredumpCommand),
+
     /// Profile a Wasm module's execution and print the hottest basic blocks.
     #[cfg(all(feature = "hot-blocks", target_os = "linux"))]
     HotBlocks(wasmtime_cli::commands::HotBlocksCommand),
 
     #[cfg(feature = "wizer")]
     Wizer(wasmtime_cli::commands::WizerCommand),
+
+    /// Benchmarks a function exported by a WebAssembly module or component.
+    #[cfg(feature = "bench")]
+    Bench(wasmtime_cli::commands::BenchCommand),
+
+    /// Interactively invokes functions exported by a WebAssembly component.
+    #[cfg(feature = "repl")]
+    Repl(wasmtime_cli::commands::ReplCommand),
 }
 
 impl Wasmtime {
@@ -134,11 +146,20 @@ impl Wasmtime {
             #[cfg(feature = "objdump")]
             Subcommand::Objdump(c) => c.execute(),
 
+            #[cfg(feature = "coredump")]
+            Subcommand::Coredump(c) => c.execute(),
+
             #[cfg(all(feature = "hot-blocks", target_os = "linux"))]
             Subcommand::HotBlocks(c) => c.execute(),
 
             #[cfg(feature = "wizer")]
             Subcommand::Wizer(c) => c.execute(),
+
+            #[cfg(feature = "bench")]
+            Subcommand::Bench(c) => c.execute(),
+
+            #[cfg(feature = "repl")]
+            Subcommand::Repl(c) => c.execute(),
         }
     }
 }
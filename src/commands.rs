@@ -35,6 +35,11 @@ mod settings;
 #[cfg(feature = "cranelift")]
 pub use self::settings::*;
 
+#[cfg(feature = "coredump")]
+mod coredump;
+#[cfg(feature = "coredump")]
+pub use self::coredump::*;
+
 #[cfg(feature = "objdump")]
 mod objdump;
 #[cfg(feature = "objdump")]
@@ -49,3 +54,13 @@ pub use self::hot_blocks::*;
 mod wizer;
 #[cfg(feature = "wizer")]
 pub use self::wizer::*;
+
+#[cfg(feature = "bench")]
+mod bench;
+#[cfg(feature = "bench")]
+pub use self::bench::*;
+
+#[cfg(feature = "repl")]
+mod repl;
+#[cfg(feature = "repl")]
+pub use self::repl::*;
@@ -0,0 +1,374 @@
+//! Implementation of the `wasmtime bench` subcommand.
+
+#![cfg_attr(
+    not(feature = "component-model"),
+    allow(irrefutable_let_patterns, unreachable_patterns)
+)]
+
+use crate::commands::{CliLinker, Host, RunCommand};
+use crate::common::RunTarget;
+use clap::Parser;
+use std::fmt;
+use std::iter;
+use std::mem;
+use std::time::{Duration, Instant};
+use wasmtime::{Result, Store, Val, bail, error::Context as _, format_err};
+
+/// Benchmarks a function exported by a WebAssembly module or component.
+///
+/// The module or component is instantiated once and the function named by
+/// `--invoke` is then called repeatedly within that instance: first a number
+/// of warmup calls which aren't measured, followed by the measured calls. The
+/// mean time per call is reported along with its 95% confidence interval, as
+/// well as the median, 99th percentile, and extremes of the measurements.
+///
+/// With `--compare` the same benchmark is additionally run on a second engine
+/// configuration, for example to compare Winch against Cranelift or Pulley
+/// against native code, and the difference between the two configurations is
+/// reported along with whether it is statistically significant.
+///
+/// All options of `wasmtime run`, such as `--dir` and `--env`, may be used to
+/// configure the benchmarked instance.
+#[derive(Parser)]
+pub struct BenchCommand {
+    /// Number of calls to make before measuring, to warm up caches and such.
+    #[arg(long, value_name = "N", default_value = "10")]
+    warmup: usize,
+
+    /// Number of calls to measure.
+    #[arg(long, value_name = "N", default_value = "100")]
+    iterations: usize,
+
+    /// Additionally benchmark an engine configured with these options and
+    /// compare it with the baseline configuration.
+    ///
+    /// The options are separated by whitespace and are applied on top of the
+    /// options of the baseline, for example `--compare "-Ccompiler=winch"` or
+    /// `--compare "--target pulley64"`. An option given here replaces all
+    /// occurrences of the same option in the baseline, so `-C` options of the
+    /// baseline are not inherited if `-C` is given here.
+    #[arg(long, value_name = "OPTIONS", allow_hyphen_values = true)]
+    compare: Option<String>,
+
+    #[command(flatten)]
+    #[expect(missing_docs, reason = "don't want to mess with clap doc-strings")]
+    pub run: RunCommand,
+}
+
+/// A function, and the arguments to call it with, being benchmarked.
+enum Target {
+    Core {
+        func: wasmtime::Func,
+        params: Vec<Val>,
+        results: Vec<Val>,
+    },
+    #[cfg(feature = "component-model")]
+    Component {
+        func: wasmtime::component::Func,
+        params: Vec<wasmtime::component::Val>,
+        results: Vec<wasmtime::component::Val>,
+    },
+}
+
+/// Summary statistics of the measured calls of one configuration, in
+/// nanoseconds.
+struct Summary {
+    samples: usize,
+    mean: f64,
+    stddev: f64,
+    /// Half the width of the 95% confidence interval of `mean`.
+    ci: f64,
+    median: f64,
+    p99: f64,
+    min: f64,
+    max: f64,
+}
+
+impl BenchCommand {
+    /// Executes the command.
+    pub fn execute(mut self) -> Result<()> {
+        if self.run.invoke.is_none() {
+            bail!("`--invoke` must be used to name the function to benchmark");
+        }
+        if self.iterations < 2 {
+            bail!("--iterations must be at least 2");
+        }
+
+        // Parse the options of the comparison before the baseline's options
+        // are configured below, as configuring consumes the raw options.
+        let compare = match &self.compare {
+            Some(options) => {
+                let mut common = self.run.run.common.clone();
+                common
+                    .try_update_from(iter::once("--compare").chain(options.split_whitespace()))
+                    .with_context(|| format!("failed to parse `--compare` options `{options}`"))?;
+                Some((options.clone(), common))
+            }
+            None => None,
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_time()
+            .enable_io()
+            .build()?;
+
+        runtime.block_on(async {
+            self.run.run.common.init_logging()?;
+
+            println!(
+                "benchmarking `{}` in {}: {} warmup and {} measured calls",
+                self.run.invoke.as_deref().unwrap(),
+                self.run.module_and_args[0].to_string_lossy(),
+                self.warmup,
+                self.iterations,
+            );
+
+            let baseline = self.measure().await?;
+            println!();
+            println!("baseline:");
+            print!("{baseline}");
+
+            if let Some((options, common)) = compare {
+                let prev = mem::replace(&mut self.run.run.common, common);
+                let comparison = self.measure().await;
+                self.run.run.common = prev;
+                let comparison = comparison?;
+
+                println!();
+                println!("comparison ({options}):");
+                print!("{comparison}");
+                println!();
+                println!("{}", compare_summaries(&baseline, &comparison));
+            }
+            Ok(())
+        })
+    }
+
+    /// Instantiates the module or component with the current configuration
+    /// and measures the calls to the benchmarked function.
+    async fn measure(&mut self) -> Result<Summary> {
+        let engine = self.run.new_engine()?;
+        let main = self
+            .run
+            .run
+            .load_module(&engine, self.run.module_and_args[0].as_ref(), None)?;
+        let (mut store, mut linker) = self.run.new_store_and_linker(&engine, &main)?;
+        let mut target = self.instantiate(&mut store, &mut linker, &main).await?;
+
+        for _ in 0..self.warmup {
+            self.call(&mut store, &mut target).await?;
+        }
+
+        let mut samples = Vec::with_capacity(self.iterations);
+        for _ in 0..self.iterations {
+            let start = Instant::now();
+            self.call(&mut store, &mut target).await?;
+            samples.push(start.elapsed());
+        }
+        Ok(Summary::new(&samples))
+    }
+
+    async fn instantiate(
+        &self,
+        store: &mut Store<Host>,
+        linker: &mut CliLinker,
+        main: &RunTarget,
+    ) -> Result<Target> {
+        self.run.define_unknown_imports(store, linker, main)?;
+        let name = self.run.invoke.as_deref().unwrap();
+
+        match linker {
+            CliLinker::Core(linker) => {
+                let instance = linker
+                    .instantiate_async(&mut *store, main.unwrap_core())
+                    .await
+                    .with_context(|| {
+                        format!("failed to instantiate {:?}", self.run.module_and_args[0])
+                    })?;
+
+                // If `_initialize` is present, meaning a reactor, then invoke
+                // the function.
+                if let Some(func) = instance.get_func(&mut *store, "_initialize") {
+                    func.typed::<(), ()>(&store)?
+                        .call_async(&mut *store, ())
+                        .await?;
+                }
+
+                let func = instance
+                    .get_func(&mut *store, name)
+                    .ok_or_else(|| format_err!("no func export named `{name}` found"))?;
+                let ty = func.ty(&store);
+                let params = self.run.core_func_params(&ty)?;
+                let results = vec![Val::null_func_ref(); ty.results().len()];
+                Ok(Target::Core {
+                    func,
+                    params,
+                    results,
+                })
+            }
+            #[cfg(feature = "component-model")]
+            CliLinker::Component(linker) => {
+                let component = main.unwrap_component();
+                let (export, ty, params) = self.run.component_invoke_params(store, component)?;
+                let instance = linker.instantiate_async(&mut *store, component).await?;
+                let func = instance
+                    .get_func(&mut *store, export)
+                    .expect("found export index");
+                let results = vec![wasmtime::component::Val::Bool(false); ty.results().len()];
+                Ok(Target::Component {
+                    func,
+                    params,
+                    results,
+                })
+            }
+        }
+    }
+
+    async fn call(&self, store: &mut Store<Host>, target: &mut Target) -> Result<()> {
+        let result = match target {
+            Target::Core {
+                func,
+                params,
+                results,
+            } => func.call_async(&mut *store, params, results).await,
+            #[cfg(feature = "component-model")]
+            Target::Component {
+                func,
+                params,
+                results,
+            } => {
+                self.run
+                    .call_component_func(store, params, *func, results)
+                    .await
+            }
+        };
+        result.with_context(|| format!("failed to invoke `{}`", self.run.invoke.as_ref().unwrap()))
+    }
+}
+
+impl Summary {
+    fn new(samples: &[Duration]) -> Summary {
+        let mut samples = samples
+            .iter()
+            .map(|d| d.as_nanos() as f64)
+            .collect::<Vec<_>>();
+        samples.sort_by(f64::total_cmp);
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let stddev = variance.sqrt();
+
+        Summary {
+            samples: samples.len(),
+            mean,
+            stddev,
+            ci: t_critical_95(n - 1.0) * stddev / n.sqrt(),
+            median: percentile(&samples, 50.0),
+            p99: percentile(&samples, 99.0),
+            min: samples[0],
+            max: samples[samples.len() - 1],
+        }
+    }
+
+    /// The variance of the mean of this summary.
+    fn mean_variance(&self) -> f64 {
+        self.stddev.powi(2) / self.samples as f64
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  mean:    {} ± {} (95% CI)",
+            Nanos(self.mean),
+            Nanos(self.ci)
+        )?;
+        writeln!(f, "  median:  {}", Nanos(self.median))?;
+        writeln!(f, "  p99:     {}", Nanos(self.p99))?;
+        writeln!(f, "  min:     {}", Nanos(self.min))?;
+        writeln!(f, "  max:     {}", Nanos(self.max))?;
+        writeln!(f, "  std dev: {}", Nanos(self.stddev))
+    }
+}
+
+/// Describes how the mean of `comparison` differs from the mean of
+/// `baseline`, using Welch's t-test to determine whether the difference is
+/// significant.
+fn compare_summaries(baseline: &Summary, comparison: &Summary) -> String {
+    let diff = comparison.mean - baseline.mean;
+    let (va, vb) = (baseline.mean_variance(), comparison.mean_variance());
+    let se = (va + vb).sqrt();
+
+    // The Welch–Satterthwaite approximation of the degrees of freedom.
+    let df = (va + vb).powi(2)
+        / (va.powi(2) / (baseline.samples as f64 - 1.0)
+            + vb.powi(2) / (comparison.samples as f64 - 1.0));
+    let half = if se > 0.0 {
+        t_critical_95(df) * se
+    } else {
+        0.0
+    };
+
+    let percent = |ns: f64| ns / baseline.mean * 100.0;
+    let (lo, hi) = (percent(diff - half), percent(diff + half));
+    if lo <= 0.0 && hi >= 0.0 {
+        format!(
+            "no significant difference between comparison and baseline: \
+             {:+.1}% (95% CI: {lo:+.1}% .. {hi:+.1}%)",
+            percent(diff)
+        )
+    } else {
+        let (lo, hi) = (lo.abs().min(hi.abs()), lo.abs().max(hi.abs()));
+        format!(
+            "comparison is {:.1}% {} than baseline (95% CI: {lo:.1}% .. {hi:.1}%)",
+            percent(diff).abs(),
+            if diff > 0.0 { "slower" } else { "faster" },
+        )
+    }
+}
+
+/// Returns the `p`th percentile of the sorted `samples`, using the
+/// nearest-rank method.
+fn percentile(samples: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * samples.len() as f64).ceil() as usize;
+    samples[rank.clamp(1, samples.len()) - 1]
+}
+
+/// Returns the two-sided 95% critical value of Student's t-distribution with
+/// `df` degrees of freedom.
+fn t_critical_95(df: f64) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    // Round fractional degrees of freedom down, which errs on the side of a
+    // wider interval.
+    match df.floor() as usize {
+        0 => f64::INFINITY,
+        df @ 1..=30 => TABLE[df - 1],
+        31..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+/// A duration in nanoseconds, displayed with an appropriate unit.
+struct Nanos(f64);
+
+impl fmt::Display for Nanos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ns = self.0;
+        if ns < 1e3 {
+            write!(f, "{ns:.1} ns")
+        } else if ns < 1e6 {
+            write!(f, "{:.3} µs", ns / 1e3)
+        } else if ns < 1e9 {
+            write!(f, "{:.3} ms", ns / 1e6)
+        } else {
+            write!(f, "{:.3} s", ns / 1e9)
+        }
+    }
+}
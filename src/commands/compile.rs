@@ -1,9 +1,14 @@
 //! The module that implements the `wasmtime compile` command.
 
 use clap::Parser;
+use object::write::{Object, StandardSection, Symbol, SymbolSection};
+use object::{Object as _, ObjectSection as _, SymbolFlags, SymbolKind, SymbolScope};
+use std::fmt::Write as _;
 use std::fs;
-use std::path::PathBuf;
-use wasmtime::{CodeBuilder, CodeHint, Engine, Result, bail, error::Context as _};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use target_lexicon::Triple;
+use wasmtime::{CodeBuilder, CodeHint, Engine, Result, bail, error::Context as _, format_err};
 use wasmtime_cli_flags::CommonOptions;
 
 const AFTER_HELP: &str =
@@ -21,7 +26,15 @@ const AFTER_HELP: &str =
         \n\
         Compiling for a specific platform (Linux) and CPU preset (Skylake):\n\
         \n  \
-        wasmtime compile --target x86_64-unknown-linux -Ccranelift-skylake foo.wasm\n";
+        wasmtime compile --target x86_64-unknown-linux -Ccranelift-skylake foo.wasm\n\
+        \n\
+        Emitting an object file and C header to statically link into an embedder:\n\
+        \n  \
+        wasmtime compile --emit obj -o foo.o foo.wasm\n\
+        \n\
+        Explaining why a precompiled module can't be loaded with the given options:\n\
+        \n  \
+        wasmtime compile --explain-compat -Wepoch-interruption foo.cwasm\n";
 
 /// Compiles a WebAssembly module.
 #[derive(Parser)]
@@ -42,19 +55,70 @@ pub struct CompileCommand {
     #[arg(long = "emit-clif", value_name = "PATH")]
     pub emit_clif: Option<PathBuf>,
 
+    /// The kind of output to produce.
+    ///
+    /// `cwasm` writes a precompiled module which can be loaded at runtime with
+    /// `Module::deserialize_file`. `obj` instead wraps it in a relocatable
+    /// object file, defaulting to `<MODULE>.o`, alongside a C header with the
+    /// same name and an `.h` extension. The object can be statically linked
+    /// into an embedder and loaded with `Module::deserialize_raw` without
+    /// any file I/O at runtime.
+    #[arg(long, default_value = "cwasm", value_name = "KIND")]
+    pub emit: Emit,
+
+    /// The name of the symbol holding the precompiled module with `--emit
+    /// obj`; defaults to the output's file name with a `_cwasm` suffix.
+    #[arg(long, value_name = "NAME")]
+    pub symbol: Option<String>,
+
+    /// Produce byte-for-byte reproducible output.
+    ///
+    /// Without `--target` the host's CPU features are otherwise enabled during
+    /// compilation, making the output depend on the machine it's compiled on.
+    /// With this flag the output only depends on the input, the options
+    /// passed, and the version of Wasmtime.
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Instead of compiling `MODULE`, treat it as a previously precompiled
+    /// artifact and explain whether it can be loaded with the configuration
+    /// given by the other options.
+    ///
+    /// Every difference in Wasmtime version, target, compiler settings, CPU
+    /// features, and WebAssembly features which prevents loading the
+    /// artifact is reported, and the command fails if there are any.
+    #[arg(long)]
+    pub explain_compat: bool,
+
     /// The path of the WebAssembly to compile
     #[arg(index = 1, value_name = "MODULE")]
     pub module: PathBuf,
 }
 
+/// The kinds of output of `wasmtime compile`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Emit {
+    /// A precompiled module or component.
+    Cwasm,
+    /// A relocatable object file containing a precompiled module or
+    /// component, plus a C header declaring its symbols.
+    Obj,
+}
+
 impl CompileCommand {
     /// Executes the command.
     pub fn execute(mut self) -> Result<()> {
         self.common.init_logging()?;
 
+        // An explicit target disables the inference of the host's CPU
+        // features.
+        if self.deterministic && self.common.target.is_none() {
+            self.common.target = Some(target_lexicon::HOST.to_string());
+        }
+
         let mut config = self.common.config(None)?;
 
-        if let Some(path) = self.emit_clif {
+        if let Some(path) = self.emit_clif.take() {
             if !path.exists() {
                 std::fs::create_dir(&path)?;
             }
@@ -71,6 +135,17 @@ impl CompileCommand {
 
         let engine = Engine::new(&config)?;
 
+        if self.explain_compat {
+            let bytes = fs::read(&self.module)
+                .with_context(|| format!("failed to read {}", self.module.display()))?;
+            let report = engine.check_compatibility(&bytes);
+            if !report.is_compatible() {
+                bail!("{report}");
+            }
+            println!("{report}");
+            return Ok(());
+        }
+
         if self.module.file_name().is_none() {
             bail!(
                 "'{}' is not a valid input module path",
@@ -83,11 +158,15 @@ impl CompileCommand {
 
         let output = self.output.take().unwrap_or_else(|| {
             let mut output: PathBuf = self.module.file_name().unwrap().into();
-            output.set_extension("cwasm");
+            output.set_extension(match self.emit {
+                Emit::Cwasm => "cwasm",
+                Emit::Obj => "o",
+            });
             output
         });
 
-        let output_bytes = match code.hint() {
+        let hint = code.hint();
+        let output_bytes = match hint {
             #[cfg(feature = "component-model")]
             Some(CodeHint::Component) => code.compile_component_serialized()?,
             #[cfg(not(feature = "component-model"))]
@@ -96,11 +175,202 @@ impl CompileCommand {
             }
             Some(CodeHint::Module) | None => code.compile_module_serialized()?,
         };
-        fs::write(&output, output_bytes)
-            .with_context(|| format!("failed to write output: {}", output.display()))?;
+
+        match self.emit {
+            Emit::Cwasm => fs::write(&output, output_bytes)
+                .with_context(|| format!("failed to write output: {}", output.display()))?,
+            Emit::Obj => {
+                let symbol = match self.symbol.take() {
+                    Some(symbol) => symbol,
+                    None => default_symbol(&output)?,
+                };
+                if !is_c_identifier(&symbol) {
+                    bail!("symbol name `{symbol}` is not a valid C identifier");
+                }
+                let kind = match hint {
+                    Some(CodeHint::Component) => "component",
+                    Some(CodeHint::Module) | None => "module",
+                };
+                let obj = self.emit_obj(&symbol, &output_bytes)?;
+                fs::write(&output, obj)
+                    .with_context(|| format!("failed to write output: {}", output.display()))?;
+
+                let header_path = output.with_extension("h");
+                let header = emit_header(&symbol, kind, &self.module, &output)?;
+                fs::write(&header_path, header).with_context(|| {
+                    format!("failed to write header: {}", header_path.display())
+                })?;
+            }
+        }
 
         Ok(())
     }
+
+    /// Wraps the precompiled `artifact` in an object file for the target being
+    /// compiled to, exporting it as `symbol` and its length as `symbol_len`.
+    fn emit_obj(&self, symbol: &str, artifact: &[u8]) -> Result<Vec<u8>> {
+        let mut triple = match &self.common.target {
+            Some(target) => Triple::from_str(target).map_err(|e| format_err!(e))?,
+            None => target_lexicon::HOST,
+        };
+        // Pulley bytecode is interpreted by the embedder, so the object is
+        // linked into a binary for the host.
+        if matches!(
+            triple.architecture,
+            target_lexicon::Architecture::Pulley32
+                | target_lexicon::Architecture::Pulley64
+                | target_lexicon::Architecture::Pulley32be
+                | target_lexicon::Architecture::Pulley64be
+        ) {
+            triple = target_lexicon::HOST;
+        }
+
+        let format = match triple.binary_format {
+            target_lexicon::BinaryFormat::Elf => object::BinaryFormat::Elf,
+            target_lexicon::BinaryFormat::Macho => object::BinaryFormat::MachO,
+            target_lexicon::BinaryFormat::Coff => object::BinaryFormat::Coff,
+            format => bail!("cannot emit an object file in the {format} format"),
+        };
+        let architecture = match triple.architecture {
+            target_lexicon::Architecture::X86_32(_) => object::Architecture::I386,
+            target_lexicon::Architecture::X86_64 => object::Architecture::X86_64,
+            target_lexicon::Architecture::Arm(_) => object::Architecture::Arm,
+            target_lexicon::Architecture::Aarch64(_) => object::Architecture::Aarch64,
+            target_lexicon::Architecture::S390x => object::Architecture::S390x,
+            target_lexicon::Architecture::Riscv64(_) => object::Architecture::Riscv64,
+            architecture => bail!("cannot emit an object file for {architecture}"),
+        };
+        let endian = match triple.endianness() {
+            Ok(target_lexicon::Endianness::Big) => object::Endianness::Big,
+            _ => object::Endianness::Little,
+        };
+        let pointer_width = triple
+            .pointer_width()
+            .map_err(|()| format_err!("unknown pointer width of {triple}"))?
+            .bytes();
+
+        // The sections of the precompiled artifact must stay aligned when it's
+        // used in place, so align the whole artifact to the largest of them.
+        let align = object::File::parse(artifact)
+            .context("failed to parse the precompiled artifact")?
+            .sections()
+            .map(|s| s.align())
+            .max()
+            .unwrap_or(1)
+            .max(16);
+
+        let mut obj = Object::new(format, architecture, endian);
+        let section = obj.section_id(StandardSection::ReadOnlyData);
+        let mut add = |name: String, data: &[u8], align: u64| {
+            let size = u64::try_from(data.len()).unwrap();
+            let symbol = obj.add_symbol(Symbol {
+                name: name.into_bytes(),
+                value: 0,
+                size,
+                kind: SymbolKind::Data,
+                scope: SymbolScope::Linkage,
+                weak: false,
+                section: SymbolSection::Undefined,
+                flags: SymbolFlags::None,
+            });
+            obj.add_symbol_data(symbol, section, data, align);
+        };
+        add(symbol.to_string(), artifact, align);
+        let len = u64::try_from(artifact.len())?;
+        let len = match (pointer_width, endian) {
+            (4, object::Endianness::Little) => u32::try_from(len)?.to_le_bytes().to_vec(),
+            (4, object::Endianness::Big) => u32::try_from(len)?.to_be_bytes().to_vec(),
+            (_, object::Endianness::Little) => len.to_le_bytes().to_vec(),
+            (_, object::Endianness::Big) => len.to_be_bytes().to_vec(),
+        };
+        add(format!("{symbol}_len"), &len, u64::from(pointer_width));
+
+        obj.write().context("failed to write object file")
+    }
+}
+
+/// Returns the default symbol name for the object file at `output`.
+fn default_symbol(output: &Path) -> Result<String> {
+    let stem = output.file_stem().and_then(|s| s.to_str()).ok_or_else(|| {
+        format_err!(
+            "cannot derive a symbol name from '{}', pass `--symbol`",
+            output.display()
+        )
+    })?;
+    let mut symbol = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if symbol.starts_with(|c: char| c.is_ascii_digit()) {
+        symbol.insert(0, '_');
+    }
+    symbol.push_str("_cwasm");
+    Ok(symbol)
+}
+
+fn is_c_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Generates the C header declaring the symbols of an object file emitted by
+/// `CompileCommand::emit_obj`.
+fn emit_header(symbol: &str, kind: &str, module: &Path, output: &Path) -> Result<String> {
+    let guard = format!("{}_H", symbol.to_ascii_uppercase());
+    let object = output
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let mut header = String::new();
+    writeln!(
+        header,
+        "// Generated by `wasmtime compile --emit obj` from `{}`.",
+        module.display()
+    )?;
+    writeln!(header, "//")?;
+    writeln!(
+        header,
+        "// Link with `{object}`, which contains the precompiled {kind}, and load it"
+    )?;
+    writeln!(
+        header,
+        "// in place with `{}::deserialize_raw` using an engine configured",
+        match kind {
+            "component" => "Component",
+            _ => "Module",
+        }
+    )?;
+    writeln!(
+        header,
+        "// identically to the one which compiled it. Native code also requires"
+    )?;
+    writeln!(
+        header,
+        "// `Config::with_custom_code_memory` to make the code executable."
+    )?;
+    writeln!(header)?;
+    writeln!(header, "#ifndef {guard}")?;
+    writeln!(header, "#define {guard}")?;
+    writeln!(header)?;
+    writeln!(header, "#include <stddef.h>")?;
+    writeln!(header, "#include <stdint.h>")?;
+    writeln!(header)?;
+    writeln!(header, "#ifdef __cplusplus")?;
+    writeln!(header, "extern \"C\" {{")?;
+    writeln!(header, "#endif")?;
+    writeln!(header)?;
+    writeln!(header, "// The precompiled {kind}.")?;
+    writeln!(header, "extern const uint8_t {symbol}[];")?;
+    writeln!(header)?;
+    writeln!(header, "// The size, in bytes, of `{symbol}`.")?;
+    writeln!(header, "extern const size_t {symbol}_len;")?;
+    writeln!(header)?;
+    writeln!(header, "#ifdef __cplusplus")?;
+    writeln!(header, "}} // extern \"C\"")?;
+    writeln!(header, "#endif")?;
+    writeln!(header)?;
+    writeln!(header, "#endif // {guard}")?;
+    Ok(header)
 }
 
 #[cfg(all(test, not(miri)))]
@@ -141,6 +411,145 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_deterministic_compile() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut outputs = Vec::new();
+        for name in ["a", "b"] {
+            let input_path = dir.path().join(format!("{name}.wat"));
+            std::fs::write(
+                &input_path,
+                "(module (func (export \"f\") (param i32) (result i32) local.get 0))",
+            )?;
+            let output_path = dir.path().join(format!("{name}.cwasm"));
+
+            let command = CompileCommand::try_parse_from(vec![
+                "compile",
+                "-Dlogging=n",
+                "-Ddebug-info",
+                "--deterministic",
+                "-o",
+                output_path.to_str().unwrap(),
+                input_path.to_str().unwrap(),
+            ])?;
+            command.execute()?;
+
+            outputs.push(std::fs::read(output_path)?);
+        }
+        assert!(outputs[0] == outputs[1], "compiled outputs differ");
+
+        let engine = Engine::new(wasmtime::Config::new().debug_info(true))?;
+        let module = unsafe { Module::deserialize(&engine, &outputs[0])? };
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let f = instance.get_typed_func::<i32, i32>(&mut store, "f")?;
+        assert_eq!(f.call(&mut store, 1234).unwrap(), 1234);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_compat() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input_path = dir.path().join("a.wat");
+        std::fs::write(&input_path, "(module)")?;
+        let output_path = dir.path().join("a.cwasm");
+        let output = output_path.to_str().unwrap();
+
+        CompileCommand::try_parse_from(vec![
+            "compile",
+            "-Dlogging=n",
+            "-o",
+            output,
+            input_path.to_str().unwrap(),
+        ])?
+        .execute()?;
+
+        // Same options as the artifact was compiled with.
+        CompileCommand::try_parse_from(vec!["compile", "-Dlogging=n", "--explain-compat", output])?
+            .execute()?;
+
+        // Every difference is reported, not just the first one.
+        let err = CompileCommand::try_parse_from(vec![
+            "compile",
+            "-Dlogging=n",
+            "-Ddebug-info",
+            "-Wepoch-interruption",
+            "--explain-compat",
+            output,
+        ])?
+        .execute()
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("is not compatible"), "{err}");
+        assert!(err.contains("native debug information support"), "{err}");
+        assert!(err.contains("epoch interruption"), "{err}");
+
+        // Inputs which aren't precompiled artifacts at all are rejected.
+        let err = CompileCommand::try_parse_from(vec![
+            "compile",
+            "-Dlogging=n",
+            "--explain-compat",
+            input_path.to_str().unwrap(),
+        ])?
+        .execute()
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("not a precompiled Wasmtime artifact"), "{err}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_obj_compile() -> Result<()> {
+        use object::{Object as _, ObjectSymbol as _};
+
+        let (mut input, input_path) = NamedTempFile::new()?.into_parts();
+        input.write_all(
+            "(module (func (export \"f\") (param i32) (result i32) local.get 0))".as_bytes(),
+        )?;
+        drop(input);
+
+        let dir = tempfile::tempdir()?;
+        let output_path = dir.path().join("my-module.o");
+
+        let command = CompileCommand::try_parse_from(vec![
+            "compile",
+            "-Dlogging=n",
+            "--emit",
+            "obj",
+            "-o",
+            output_path.to_str().unwrap(),
+            input_path.to_str().unwrap(),
+        ])?;
+
+        command.execute()?;
+
+        let header = std::fs::read_to_string(dir.path().join("my-module.h"))?;
+        assert!(header.contains("extern const uint8_t my_module_cwasm[];"));
+        assert!(header.contains("extern const size_t my_module_cwasm_len;"));
+
+        let contents = std::fs::read(&output_path)?;
+        let obj = object::File::parse(&contents[..])?;
+        let symbol = obj
+            .symbols()
+            .find(|s| s.name() == Ok("my_module_cwasm") || s.name() == Ok("_my_module_cwasm"))
+            .expect("missing symbol");
+        let section = obj.section_by_index(symbol.section_index().unwrap())?;
+        let start = usize::try_from(symbol.address() - section.address())?;
+        let len = usize::try_from(symbol.size())?;
+        let artifact = &section.data()?[start..][..len];
+
+        let engine = Engine::default();
+        let module = unsafe { Module::deserialize(&engine, artifact)? };
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let f = instance.get_typed_func::<i32, i32>(&mut store, "f")?;
+        assert_eq!(f.call(&mut store, 1234).unwrap(), 1234);
+
+        Ok(())
+    }
+
     #[cfg(target_arch = "x86_64")]
     #[test]
     fn test_x64_flags_compile() -> Result<()> {
@@ -0,0 +1,367 @@
+//! The module that implements the `wasmtime coredump` command.
+
+use clap::{Parser, Subcommand};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use wasmparser::{
+    BinaryReader, CoreDumpInstancesSection, CoreDumpModulesSection, CoreDumpSection,
+    CoreDumpStackSection, CoreDumpValue, Encoding, KnownCustom, Name, Parser as WasmParser,
+    Payload, TypeRef,
+};
+use wasmtime::{Result, bail, error::Context as _, format_err};
+
+/// Inspects Wasm core dumps
+#[derive(Parser, PartialEq)]
+pub struct CoredumpCommand {
+    #[command(subcommand)]
+    subcommand: CoredumpSubcommand,
+}
+
+#[derive(Subcommand, PartialEq)]
+enum CoredumpSubcommand {
+    /// Prints the stack of a core dump, symbolized with the debug information
+    /// of the original modules
+    Explore(CoredumpExploreCommand),
+}
+
+impl CoredumpCommand {
+    /// Executes the command.
+    pub fn execute(self) -> Result<()> {
+        match self.subcommand {
+            CoredumpSubcommand::Explore(c) => c.execute(),
+        }
+    }
+}
+
+/// Prints the stack of a core dump, symbolized with the debug information of
+/// the original modules
+#[derive(Parser, PartialEq)]
+pub struct CoredumpExploreCommand {
+    /// The path of the core dump, as written by `wasmtime run -D coredump=...`
+    /// or `Config::coredump_on_trap_path`.
+    #[arg(index = 1, value_name = "COREDUMP")]
+    coredump: PathBuf,
+
+    /// The original Wasm module or component that was executing.
+    ///
+    /// This may be passed multiple times if multiple modules were instantiated,
+    /// in which case they're matched up in order with the modules listed in
+    /// the core dump, with components contributing all of their core modules.
+    /// Function names and DWARF debug information from these modules are used
+    /// to symbolize stack frames.
+    #[arg(long = "module", value_name = "MODULE")]
+    modules: Vec<PathBuf>,
+
+    /// Whether or not to print the locals and operand stack of each frame.
+    #[arg(long)]
+    values: bool,
+}
+
+impl CoredumpExploreCommand {
+    /// Executes the command.
+    pub fn execute(self) -> Result<()> {
+        let bytes = std::fs::read(&self.coredump)
+            .with_context(|| format!("failed to read {}", self.coredump.display()))?;
+        let coredump = CoreDump::parse(&bytes)?;
+
+        let wasms = self
+            .modules
+            .iter()
+            .map(|path| {
+                std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut modules = Vec::new();
+        for (path, wasm) in self.modules.iter().zip(&wasms) {
+            let infos = ModuleInfo::parse(wasm)
+                .with_context(|| format!("failed to parse {}", path.display()))?;
+            modules.extend(infos);
+        }
+        if modules.len() > coredump.modules.len() {
+            bail!(
+                "{} modules were passed but the core dump only contains {}",
+                modules.len(),
+                coredump.modules.len(),
+            );
+        }
+
+        print!("{}", coredump.render(&modules, self.values)?);
+        Ok(())
+    }
+}
+
+/// The relevant contents of a serialized core dump.
+struct CoreDump<'a> {
+    name: &'a str,
+    modules: Vec<&'a str>,
+    /// The module index of each instance.
+    instances: Vec<u32>,
+    stacks: Vec<CoreDumpStackSection<'a>>,
+    /// The size, in bytes, of each memory.
+    memories: Vec<u64>,
+}
+
+impl<'a> CoreDump<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<CoreDump<'a>> {
+        let mut name = None;
+        let mut modules = Vec::new();
+        let mut instances = Vec::new();
+        let mut stacks = Vec::new();
+        let mut memories = Vec::new();
+        for payload in WasmParser::new(0).parse_all(bytes) {
+            match payload.context("failed to parse core dump")? {
+                Payload::CustomSection(section) => {
+                    let reader = BinaryReader::new(section.data(), section.data_offset());
+                    match section.name() {
+                        "core" => name = Some(CoreDumpSection::new(reader)?.name),
+                        "coremodules" => modules = CoreDumpModulesSection::new(reader)?.modules,
+                        "coreinstances" => {
+                            instances = CoreDumpInstancesSection::new(reader)?
+                                .instances
+                                .iter()
+                                .map(|i| i.module_index)
+                                .collect();
+                        }
+                        "corestack" => stacks.push(CoreDumpStackSection::new(reader)?),
+                        _ => {}
+                    }
+                }
+                Payload::MemorySection(reader) => {
+                    for ty in reader {
+                        let ty = ty?;
+                        let size = 1u64
+                            .checked_shl(ty.page_size_log2.unwrap_or(16))
+                            .and_then(|page_size| ty.initial.checked_mul(page_size))
+                            .context("malformed core dump: memory size overflows")?;
+                        memories.push(size);
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some(name) = name else {
+            bail!("not a Wasm core dump: missing `core` custom section");
+        };
+        Ok(CoreDump {
+            name,
+            modules,
+            instances,
+            stacks,
+            memories,
+        })
+    }
+
+    fn render(&self, modules: &[ModuleInfo<'_>], values: bool) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "core dump of `{}`", self.name)?;
+
+        writeln!(out, "modules:")?;
+        for (i, name) in self.modules.iter().enumerate() {
+            writeln!(out, "  {i}: {name}")?;
+        }
+
+        writeln!(out, "memories:")?;
+        for (i, size) in self.memories.iter().enumerate() {
+            writeln!(out, "  {i}: {size} bytes")?;
+        }
+
+        let contexts = modules
+            .iter()
+            .map(|m| m.addr2line())
+            .collect::<Result<Vec<_>>>()?;
+        for stack in self.stacks.iter() {
+            writeln!(out, "thread `{}`:", stack.name)?;
+            for (i, frame) in stack.frames.iter().enumerate() {
+                let module_index = *self
+                    .instances
+                    .get(usize::try_from(frame.instanceidx)?)
+                    .ok_or_else(|| format_err!("frame refers to unknown instance"))?;
+                let module_index = usize::try_from(module_index)?;
+                let module_name = self
+                    .modules
+                    .get(module_index)
+                    .copied()
+                    .unwrap_or("<unknown>");
+                let module = modules.get(module_index);
+
+                let func_name = module
+                    .and_then(|m| m.func_names.get(&frame.funcidx))
+                    .map(|name| format!("!{name}"))
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    "  {i:>2}: {module_name}!<wasm function {}>{func_name} @ {:#x}",
+                    frame.funcidx, frame.codeoffset,
+                )?;
+
+                if let Some(module) = module {
+                    let cx = &contexts[module_index];
+                    module.symbolize(cx, frame.funcidx, frame.codeoffset, &mut out)?;
+                }
+
+                if values {
+                    writeln!(out, "        locals: {}", render_values(&frame.locals))?;
+                    writeln!(out, "        stack: {}", render_values(&frame.stack))?;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+fn render_values(values: &[CoreDumpValue]) -> String {
+    let values = values
+        .iter()
+        .map(|v| match v {
+            CoreDumpValue::Missing => "<missing>".to_string(),
+            CoreDumpValue::I32(x) => format!("i32 {x}"),
+            CoreDumpValue::I64(x) => format!("i64 {x}"),
+            CoreDumpValue::F32(x) => format!("f32 {}", f32::from_bits(x.bits())),
+            CoreDumpValue::F64(x) => format!("f64 {}", f64::from_bits(x.bits())),
+        })
+        .collect::<Vec<_>>();
+    format!("[{}]", values.join(", "))
+}
+
+type Addr2LineContext<'a> = addr2line::Context<gimli::EndianSlice<'a, gimli::LittleEndian>>;
+
+/// The parts of an original Wasm module needed to symbolize frames of a core
+/// dump.
+struct ModuleInfo<'a> {
+    /// Offset of the code section's contents within the module, which DWARF
+    /// addresses are relative to.
+    code_section_offset: usize,
+    /// Number of functions seen so far while parsing.
+    num_funcs: u32,
+    /// Offset of each function body, indexed by function index.
+    func_offsets: HashMap<u32, usize>,
+    /// Names of functions from the `name` section.
+    func_names: HashMap<u32, String>,
+    /// DWARF custom sections, keyed by name.
+    dwarf: HashMap<&'a str, &'a [u8]>,
+}
+
+impl<'a> ModuleInfo<'a> {
+    /// Parses `wasm`, returning the info of the module itself or, if `wasm`
+    /// is a component, of all of its core modules in order.
+    fn parse(wasm: &'a [u8]) -> Result<Vec<ModuleInfo<'a>>> {
+        let mut ret = Vec::new();
+
+        // The modules currently being parsed, or `None` for components, as
+        // nested modules and components are inlined into the payloads.
+        let mut stack: Vec<Option<ModuleInfo<'a>>> = Vec::new();
+
+        for payload in WasmParser::new(0).parse_all(wasm) {
+            let payload = payload?;
+            match &payload {
+                Payload::Version { encoding, .. } => {
+                    stack.push(match encoding {
+                        Encoding::Module => Some(ModuleInfo {
+                            code_section_offset: 0,
+                            num_funcs: 0,
+                            func_offsets: HashMap::new(),
+                            func_names: HashMap::new(),
+                            dwarf: HashMap::new(),
+                        }),
+                        Encoding::Component => None,
+                    });
+                    continue;
+                }
+                Payload::End(_) => {
+                    if let Some(info) = stack.pop().flatten() {
+                        ret.push(info);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let Some(Some(info)) = stack.last_mut() else {
+                continue;
+            };
+            match payload {
+                Payload::ImportSection(reader) => {
+                    for import in reader.into_imports() {
+                        if let TypeRef::Func(_) = import?.ty {
+                            info.num_funcs += 1;
+                        }
+                    }
+                }
+                Payload::CodeSectionStart { range, .. } => info.code_section_offset = range.start,
+                Payload::CodeSectionEntry(body) => {
+                    info.func_offsets.insert(info.num_funcs, body.range().start);
+                    info.num_funcs += 1;
+                }
+                Payload::CustomSection(section) => match section.as_known() {
+                    KnownCustom::Name(reader) => {
+                        for name in reader {
+                            let Ok(Name::Function(names)) = name else {
+                                continue;
+                            };
+                            for naming in names.into_iter().flatten() {
+                                info.func_names
+                                    .insert(naming.index, naming.name.to_string());
+                            }
+                        }
+                    }
+                    _ if section.name().starts_with(".debug_") => {
+                        info.dwarf.insert(section.name(), section.data());
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        Ok(ret)
+    }
+
+    fn addr2line(&self) -> Result<Addr2LineContext<'_>> {
+        let dwarf = gimli::Dwarf::load(|id| -> Result<_> {
+            let data = self.dwarf.get(id.name()).copied().unwrap_or(&[]);
+            Ok(gimli::EndianSlice::new(data, gimli::LittleEndian))
+        })?;
+        addr2line::Context::from_dwarf(dwarf)
+            .context("failed to create addr2line dwarf mapping context")
+    }
+
+    /// Writes the source locations of the instruction at `offset` within
+    /// function `func` to `out`, if the module has DWARF for it.
+    fn symbolize(
+        &self,
+        cx: &Addr2LineContext<'_>,
+        func: u32,
+        offset: u32,
+        out: &mut String,
+    ) -> Result<()> {
+        let Some(start) = self.func_offsets.get(&func) else {
+            return Ok(());
+        };
+        let addr = start + usize::try_from(offset)? - self.code_section_offset;
+        let Ok(mut frames) = cx.find_frames(u64::try_from(addr)?).skip_all_loads() else {
+            return Ok(());
+        };
+        while let Ok(Some(frame)) = frames.next() {
+            let name = frame
+                .function
+                .as_ref()
+                .and_then(|f| f.raw_name().ok())
+                .unwrap_or("<unknown>".into());
+            write!(out, "        at {name}")?;
+            if let Some(location) = &frame.location {
+                if let Some(file) = location.file {
+                    write!(out, " ({file}")?;
+                    if let Some(line) = location.line {
+                        write!(out, ":{line}")?;
+                        if let Some(column) = location.column {
+                            write!(out, ":{column}")?;
+                        }
+                    }
+                    write!(out, ")")?;
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
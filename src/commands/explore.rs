@@ -20,6 +20,15 @@ pub struct ExploreCommand {
     /// provided)
     #[arg(short, long)]
     output: Option<PathBuf>,
+
+    /// Include a control-flow graph of each function's CLIF in the output
+    #[arg(long)]
+    cfg: bool,
+
+    /// Include the registers used, defined and live at each machine
+    /// instruction, and which instructions spill to or reload from the stack
+    #[arg(long)]
+    regalloc: bool,
 }
 
 impl ExploreCommand {
@@ -59,6 +68,10 @@ impl ExploreCommand {
             &config,
             self.common.target.as_deref(),
             clif_dir.as_ref().map(|tmp_dir| tmp_dir.path()),
+            wasmtime_explorer::Views {
+                cfg: self.cfg,
+                regalloc: self.regalloc,
+            },
             &bytes,
             &mut output_file,
         )?;
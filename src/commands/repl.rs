@@ -0,0 +1,217 @@
+//! Implementation of the `wasmtime repl` subcommand.
+
+use crate::commands::{CliLinker, Host, RunCommand};
+use crate::common::RunTarget;
+use clap::Parser;
+use std::io::{self, BufRead, Write};
+use wasmtime::component::types::{ComponentFunc, ComponentItem};
+use wasmtime::component::wasm_wave::wasm::{DisplayFunc, DisplayFuncResults};
+use wasmtime::component::wit_parser::ItemName;
+use wasmtime::component::{Component, Instance, Val};
+use wasmtime::{Engine, Result, Store, Trap, bail, error::Context as _};
+
+/// Interactively invokes functions exported by a WebAssembly component.
+///
+/// The component is instantiated and its exported functions are listed along
+/// with their types. Each line read from stdin is then a WAVE-encoded function
+/// call, such as `greet("world")` or `my:pkg/iface.add(1, 2)`, whose results
+/// or trap are printed. Bare function names are searched for in all exported
+/// instances, as with `wasmtime run --invoke`.
+///
+/// Lines starting with `:` are commands to the REPL itself, see `:help`. If
+/// the component traps it's instantiated again for the following calls.
+///
+/// All options of `wasmtime run`, such as `--dir` and `--env`, may be used to
+/// configure the instance.
+#[derive(Parser)]
+pub struct ReplCommand {
+    #[command(flatten)]
+    #[expect(missing_docs, reason = "don't want to mess with clap doc-strings")]
+    pub run: RunCommand,
+}
+
+/// The component being explored along with its current instance.
+struct Session {
+    engine: Engine,
+    component: Component,
+    store: Store<Host>,
+    instance: Instance,
+}
+
+impl ReplCommand {
+    /// Executes the command.
+    pub fn execute(mut self) -> Result<()> {
+        if self.run.invoke.is_some() {
+            bail!("`--invoke` cannot be used with `wasmtime repl`");
+        }
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_time()
+            .enable_io()
+            .build()?;
+
+        runtime.block_on(async {
+            self.run.run.common.init_logging()?;
+
+            let engine = self.run.new_engine()?;
+            let main =
+                self.run
+                    .run
+                    .load_module(&engine, self.run.module_and_args[0].as_ref(), None)?;
+            let component = match main {
+                RunTarget::Component(component) => component,
+                RunTarget::Core(_) => bail!("`wasmtime repl` only supports components"),
+            };
+            let (store, instance) = self.instantiate(&engine, &component).await?;
+            let mut session = Session {
+                engine,
+                component,
+                store,
+                instance,
+            };
+
+            print_exports(&session);
+            println!();
+            println!("type `:help` for help, or `:quit` to exit");
+
+            let mut lines = io::stdin().lock().lines();
+            loop {
+                print!("> ");
+                io::stdout().flush()?;
+                let Some(line) = lines.next() else {
+                    println!();
+                    break;
+                };
+                let line = line?;
+                match line.trim() {
+                    "" => {}
+                    ":quit" | ":q" | ":exit" => break,
+                    ":help" | ":h" => print_help(),
+                    ":list" | ":l" => print_exports(&session),
+                    ":reset" => {
+                        self.reset(&mut session).await?;
+                        println!("instantiated the component again");
+                    }
+                    cmd if cmd.starts_with(':') => {
+                        println!("unknown command `{cmd}`, type `:help` for help");
+                    }
+                    call => {
+                        if let Err(e) = self.call(&mut session, call).await {
+                            println!("error: {e:#}");
+                            if e.downcast_ref::<Trap>().is_some() {
+                                self.reset(&mut session).await?;
+                                println!("the component trapped and was instantiated again");
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Creates a new store, and instance of `component` within it, with the
+    /// configuration of this command.
+    async fn instantiate(
+        &mut self,
+        engine: &Engine,
+        component: &Component,
+    ) -> Result<(Store<Host>, Instance)> {
+        let main = RunTarget::Component(component.clone());
+        let (mut store, mut linker) = self.run.new_store_and_linker(engine, &main)?;
+        self.run
+            .define_unknown_imports(&mut store, &mut linker, &main)?;
+        let CliLinker::Component(linker) = linker else {
+            unreachable!()
+        };
+        let instance = linker
+            .instantiate_async(&mut store, component)
+            .await
+            .with_context(|| format!("failed to instantiate {:?}", self.run.module_and_args[0]))?;
+        Ok((store, instance))
+    }
+
+    /// Replaces the instance of `session` with a fresh one.
+    async fn reset(&mut self, session: &mut Session) -> Result<()> {
+        let (store, instance) = self
+            .instantiate(&session.engine, &session.component)
+            .await?;
+        session.store = store;
+        session.instance = instance;
+        Ok(())
+    }
+
+    /// Invokes the WAVE-encoded function call `call` and prints its results.
+    async fn call(&self, session: &mut Session, call: &str) -> Result<()> {
+        let (export, ty, params) =
+            RunCommand::parse_component_func_call(&mut session.store, &session.component, call)?;
+        let func = session
+            .instance
+            .get_func(&mut session.store, export)
+            .expect("found export index");
+        let mut results = vec![Val::Bool(false); ty.results().len()];
+        self.run
+            .call_component_func(&mut session.store, &params, func, &mut results)
+            .await?;
+        println!("{}", DisplayFuncResults(&results));
+        Ok(())
+    }
+}
+
+fn print_help() {
+    println!("enter a WAVE-encoded function call to invoke it, for example:");
+    println!();
+    println!("  greet(\"world\")");
+    println!("  my:pkg/iface.add(1, 2)");
+    println!();
+    println!("commands:");
+    println!("  :list   list the functions exported by the component");
+    println!("  :reset  instantiate the component again");
+    println!("  :help   show this help");
+    println!("  :quit   exit the REPL");
+}
+
+/// Prints the functions exported by the component of `session`, both at the
+/// root and within exported instances, along with their types.
+fn print_exports(session: &Session) {
+    let exports = component_funcs(&session.engine, &session.component);
+    if exports.is_empty() {
+        println!("the component doesn't export any functions");
+        return;
+    }
+    println!("exported functions:");
+    for (name, ty) in exports {
+        println!("  {name}: {}", DisplayFunc(ty));
+    }
+}
+
+/// Returns the names, as used in a WAVE-encoded function call, and types of
+/// the functions exported by `component`.
+fn component_funcs(engine: &Engine, component: &Component) -> Vec<(String, ComponentFunc)> {
+    let mut funcs = Vec::new();
+    for (name, item) in component.component_type().exports(engine) {
+        match item.ty {
+            ComponentItem::ComponentFunc(func) => funcs.push((name.to_string(), func)),
+            ComponentItem::ComponentInstance(instance) => {
+                for (func_name, item) in instance.exports(engine) {
+                    let ComponentItem::ComponentFunc(func) = item.ty else {
+                        continue;
+                    };
+                    // Format as an `ItemName`, as `run --invoke` does, to get
+                    // the package version in the right place.
+                    let name = match name.parse::<ItemName>() {
+                        Ok(mut item_name) => {
+                            item_name.interface = Some(item_name.name.clone());
+                            item_name.name = func_name.to_string();
+                            item_name.to_string()
+                        }
+                        Err(_) => format!("{name}.{func_name}"),
+                    };
+                    funcs.push((name, func));
+                }
+            }
+            _ => {}
+        }
+    }
+    funcs
+}
@@ -13,7 +13,7 @@ use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::thread;
 use wasmtime::{
-    Engine, Error, Func, Module, Result, Store, StoreLimits, Val, ValType, bail,
+    Engine, Error, Func, FuncType, Module, Result, Store, StoreLimits, Val, ValType, bail,
     error::Context as _, format_err,
 };
 use wasmtime_wasi::{WasiCtxView, WasiView};
@@ -681,13 +681,14 @@ impl RunCommand {
         }))
     }
 
-    async fn load_main_module(
+    /// Defines the unknown imports of `main_target` in `linker` as configured
+    /// by `-Wunknown-imports-trap` and `-Wunknown-imports-default`.
+    pub(crate) fn define_unknown_imports(
         &self,
         store: &mut Store<Host>,
         linker: &mut CliLinker,
         main_target: &RunTarget,
-        profiled_modules: Vec<(String, Module)>,
-    ) -> Result<CliInstance> {
+    ) -> Result<()> {
         // The main module might be allowed to have unknown imports, which
         // should be defined as traps:
         if self.run.common.wasm.unknown_imports_trap == Some(true) {
@@ -715,6 +716,18 @@ impl RunCommand {
             }
         }
 
+        Ok(())
+    }
+
+    async fn load_main_module(
+        &self,
+        store: &mut Store<Host>,
+        linker: &mut CliLinker,
+        main_target: &RunTarget,
+        profiled_modules: Vec<(String, Module)>,
+    ) -> Result<CliInstance> {
+        self.define_unknown_imports(store, linker, main_target)?;
+
         let finish_epoch_handler =
             self.setup_epoch_handler(store, main_target, profiled_modules)?;
 
@@ -781,16 +794,56 @@ impl RunCommand {
         component: &wasmtime::component::Component,
         linker: &mut wasmtime::component::Linker<Host>,
     ) -> Result<wasmtime::component::Instance> {
-        use wasmtime::component::{
-            Val,
-            wasm_wave::{
-                untyped::UntypedFuncCall,
-                wasm::{DisplayFuncResults, WasmFunc},
-            },
-        };
+        use wasmtime::component::{Val, wasm_wave::wasm::DisplayFuncResults};
+
+        let (export, func_type, params) = self.component_invoke_params(store, component)?;
+
+        let instance = linker.instantiate_async(&mut *store, component).await?;
+
+        let func = instance
+            .get_func(&mut *store, export)
+            .expect("found export index");
+
+        let mut results = vec![Val::Bool(false); func_type.results().len()];
+        self.call_component_func(store, &params, func, &mut results)
+            .await?;
+
+        println!("{}", DisplayFuncResults(&results));
+        Ok(instance)
+    }
 
+    /// Parses the wave-encoded `--invoke` function call into the export it
+    /// refers to in `component`, along with that export's type and the
+    /// parameters to pass to it.
+    #[cfg(feature = "component-model")]
+    pub(crate) fn component_invoke_params(
+        &self,
+        store: &mut Store<Host>,
+        component: &wasmtime::component::Component,
+    ) -> Result<(
+        wasmtime::component::ComponentExportIndex,
+        wasmtime::component::types::ComponentFunc,
+        Vec<wasmtime::component::Val>,
+    )> {
         // Check if the invoke string is present
         let invoke: &String = self.invoke.as_ref().unwrap();
+        Self::parse_component_func_call(store, component, invoke)
+    }
+
+    /// Parses the wave-encoded function call `invoke` into the export it refers
+    /// to in `component`, along with that export's type and the parameters to
+    /// pass to it.
+    #[cfg(feature = "component-model")]
+    pub(crate) fn parse_component_func_call(
+        store: &mut Store<Host>,
+        component: &wasmtime::component::Component,
+        invoke: &str,
+    ) -> Result<(
+        wasmtime::component::ComponentExportIndex,
+        wasmtime::component::types::ComponentFunc,
+        Vec<wasmtime::component::Val>,
+    )> {
+        use wasmtime::component::wasm_wave::{untyped::UntypedFuncCall, wasm::WasmFunc};
 
         let untyped_call = UntypedFuncCall::parse(invoke).with_context(|| {
                 format!(
@@ -812,22 +865,11 @@ impl RunCommand {
             .to_wasm_params(&param_types)
             .with_context(|| format!("while interpreting parameters in invoke \"{invoke}\""))?;
 
-        let instance = linker.instantiate_async(&mut *store, component).await?;
-
-        let func = instance
-            .get_func(&mut *store, export)
-            .expect("found export index");
-
-        let mut results = vec![Val::Bool(false); func_type.results().len()];
-        self.call_component_func(store, &params, func, &mut results)
-            .await?;
-
-        println!("{}", DisplayFuncResults(&results));
-        Ok(instance)
+        Ok((export, func_type, params))
     }
 
     #[cfg(feature = "component-model")]
-    async fn call_component_func(
+    pub(crate) async fn call_component_func(
         &self,
         store: &mut Store<Host>,
         params: &[wasmtime::component::Val],
@@ -936,7 +978,7 @@ impl RunCommand {
     }
 
     #[cfg(feature = "component-model")]
-    fn search_component_funcs(
+    pub(crate) fn search_component_funcs(
         store: &mut Store<Host>,
         component: &wasmtime::component::Component,
         item_name: &wasmtime::component::wit_parser::ItemName,
@@ -1023,14 +1065,9 @@ impl RunCommand {
         }
     }
 
-    async fn invoke_func(&self, store: &mut Store<Host>, func: Func) -> Result<()> {
-        let ty = func.ty(&store);
-        if ty.params().len() > 0 {
-            eprintln!(
-                "warning: using `--invoke` with a function that takes arguments \
-                 is experimental and may break in the future"
-            );
-        }
+    /// Parses the arguments following the module into parameters for a core
+    /// wasm function of type `ty`.
+    pub(crate) fn core_func_params(&self, ty: &FuncType) -> Result<Vec<Val>> {
         let mut args = self.module_and_args.iter().skip(1);
         let mut values = Vec::new();
         for ty in ty.params() {
@@ -1064,6 +1101,18 @@ impl RunCommand {
                 t => bail!("unsupported argument type {t:?}"),
             });
         }
+        Ok(values)
+    }
+
+    async fn invoke_func(&self, store: &mut Store<Host>, func: Func) -> Result<()> {
+        let ty = func.ty(&store);
+        if ty.params().len() > 0 {
+            eprintln!(
+                "warning: using `--invoke` with a function that takes arguments \
+                 is experimental and may break in the future"
+            );
+        }
+        let values = self.core_func_params(&ty)?;
 
         // Invoke the function and then afterwards print all the results that came
         // out, if there are any.
@@ -1241,6 +1290,10 @@ impl RunCommand {
             }
         }
 
+        if self.run.common.wasi.correlation == Some(true) {
+            bail!("wasi-correlation is only supported by `wasmtime serve`");
+        }
+
         if self.run.common.wasi.keyvalue == Some(true) {
             #[cfg(not(feature = "wasi-keyvalue"))]
             {
@@ -1292,7 +1345,10 @@ impl RunCommand {
                         bail!("Cannot enable wasi-http for core wasm modules");
                     }
                     CliLinker::Component(linker) => {
-                        wasmtime_wasi_http::p2::add_only_http_to_linker_async(linker)?;
+                        wasmtime_wasi_http::p2::add_only_http_to_linker_with_options_async(
+                            linker,
+                            &self.run.wasi_http_link_options(),
+                        )?;
                         #[cfg(feature = "component-model-async")]
                         if self.run.common.wasi.p3.unwrap_or(crate::common::P3_DEFAULT) {
                             wasmtime_wasi_http::p3::add_to_linker(linker)?;
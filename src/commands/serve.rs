@@ -10,14 +10,14 @@ use std::net::SocketAddr;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
         atomic::{AtomicBool, AtomicU64, Ordering},
     },
     time::{Duration, Instant},
 };
-use tokio::io::{self, AsyncWrite};
+use tokio::io::{self, AsyncRead, AsyncWrite};
 use tokio::sync::{Notify, Semaphore};
 use wasmtime::component::{Component, GuestTaskId, Linker};
 use wasmtime::error::Context as _;
@@ -29,16 +29,20 @@ use wasmtime_wasi::p2::{StreamError, StreamResult};
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 use wasmtime_wasi_http::WasiHttpCtx;
 use wasmtime_wasi_http::handler::{
-    HandlerState, Instance, Prepared, Proxy, ProxyHandler, ProxyPre, ShouldAccept,
+    HandlerState, Instance, Prepared, Proxy, ProxyPre, ReloadableProxyHandler, ShouldAccept,
     WorkerExpiration, WorkerState, WorkerStatus,
 };
 use wasmtime_wasi_http::io::TokioIo;
+use wasmtime_wasi_http::p2::types::OnInformational;
+use wasmtime_wasi_http::router::{Route, Router};
 
 #[cfg(feature = "debug")]
 use crate::commands::run::RunCommand;
 
 #[cfg(feature = "wasi-config")]
 use wasmtime_wasi_config::{WasiConfig, WasiConfigVariables};
+#[cfg(feature = "wasi-correlation")]
+use wasmtime_wasi_correlation::{Correlation, CorrelationContext, WasiCorrelation};
 #[cfg(feature = "wasi-keyvalue")]
 use wasmtime_wasi_keyvalue::{WasiKeyValue, WasiKeyValueCtx, WasiKeyValueCtxBuilder};
 #[cfg(feature = "wasi-nn")]
@@ -62,6 +66,9 @@ struct Host {
     #[cfg(feature = "wasi-config")]
     wasi_config: Option<WasiConfigVariables>,
 
+    #[cfg(feature = "wasi-correlation")]
+    correlation: Option<Correlation>,
+
     #[cfg(feature = "wasi-keyvalue")]
     wasi_keyvalue: Option<WasiKeyValueCtx>,
 
@@ -116,14 +123,60 @@ pub struct ServeCommand {
     #[arg(long, value_name = "SOCKADDR")]
     shutdown_addr: Option<SocketAddr>,
 
+    /// Socket address for a health endpoint to bind to.
+    ///
+    /// HTTP requests to this address are answered with a JSON document
+    /// describing the status of the server, including the outcome of the most
+    /// recent reload of the component.
+    #[arg(long, value_name = "SOCKADDR")]
+    health_addr: Option<SocketAddr>,
+
+    /// Socket address for a metrics endpoint to bind to.
+    ///
+    /// HTTP requests to this address are answered with metrics about the
+    /// server in the Prometheus text format, including request latencies,
+    /// instantiation and trap counts, fuel and epoch interruptions, pooling
+    /// allocator occupancy, and compilation cache hits.
+    #[arg(long, value_name = "SOCKADDR")]
+    metrics_addr: Option<SocketAddr>,
+
+    /// Time to wait for in-flight requests to finish on the previous version
+    /// of the component when it is reloaded, after which they are abandoned.
+    ///
+    /// On Unix the component is recompiled from its original path and reloaded
+    /// without downtime when the server receives `SIGHUP`. Uses the same
+    /// format as `--idle-instance-timeout`.
+    #[arg(long, default_value = "30s", value_parser = parse_duration)]
+    drain_timeout: Duration,
+
     /// Disable log prefixes of wasi-http handlers.
     /// if unspecified, logs will be prefixed with 'stdout|stderr [{req_id}] :: '
     #[arg(long)]
     no_logging_prefix: bool,
 
     /// The WebAssembly component to run.
-    #[arg(value_name = "WASM", required = true)]
-    component: PathBuf,
+    #[arg(
+        value_name = "WASM",
+        required_unless_present = "routes",
+        conflicts_with = "routes"
+    )]
+    component: Option<PathBuf>,
+
+    /// Serve several components, routing each request to one of them by its
+    /// path or `Host` header as listed in the given file.
+    ///
+    /// Each line of the file has the form `ROUTE = WASM`, where `ROUTE` is a
+    /// path prefix such as `/api`, a host such as `example.com`, or both such
+    /// as `example.com/api`, and `WASM` is the component handling requests
+    /// matching it, relative to the file. Empty lines and lines starting with
+    /// `#` are ignored. Routes limited to a host take precedence, followed by
+    /// the longest matching path prefix, and requests matching no route are
+    /// answered with a 404 response.
+    ///
+    /// Each component has its own pool of instances, all compiled and run
+    /// with the same engine and configuration.
+    #[arg(long, value_name = "FILE")]
+    routes: Option<PathBuf>,
 
     /// Maximum number of requests to send to a single component instance before
     /// dropping it.
@@ -311,13 +364,15 @@ impl ServeCommand {
         self,
         mut debug_run: RunCommand,
         linker: Linker<Host>,
-        component: Component,
+        components: Vec<(Route, PathBuf, Component)>,
     ) -> Result<()> {
         let mut debuggee_store = self.new_store(linker.engine(), None)?;
 
         // Pre-register component modules so the debugger can see
         // them and set breakpoints at the initial stop.
-        debuggee_store.debug_register_component(&component)?;
+        for (_, _, component) in &components {
+            debuggee_store.debug_register_component(component)?;
+        }
 
         let debug_engine = debug_run.new_engine()?;
         let debug_main = debug_run.run.load_module(
@@ -345,7 +400,7 @@ impl ServeCommand {
                 &debug_component,
                 &mut debug_linker,
                 debuggee_store,
-                move |store| Box::pin(self.serve_maybe_debug(linker, component, Some(store))),
+                move |store| Box::pin(self.serve_maybe_debug(linker, components, Some(store))),
             )
             .await
     }
@@ -389,6 +444,8 @@ impl ServeCommand {
             nn: None,
             #[cfg(feature = "wasi-config")]
             wasi_config: None,
+            #[cfg(feature = "wasi-correlation")]
+            correlation: None,
             #[cfg(feature = "wasi-keyvalue")]
             wasi_keyvalue: None,
             #[cfg(feature = "profiling")]
@@ -427,6 +484,15 @@ impl ServeCommand {
             }
         }
 
+        if self.run.common.wasi.correlation == Some(true) {
+            #[cfg(feature = "wasi-correlation")]
+            {
+                let correlation = Correlation::new();
+                host.http.set_correlation(correlation.clone());
+                host.correlation.replace(correlation);
+            }
+        }
+
         if self.run.common.wasi.keyvalue == Some(true) {
             #[cfg(feature = "wasi-keyvalue")]
             {
@@ -474,15 +540,23 @@ impl ServeCommand {
         // If `-Scli` isn't passed then use the `add_to_linker_async`
         // bindings which adds just those interfaces that the proxy interface
         // uses.
+        let http_options = self.run.wasi_http_link_options();
         if cli == Some(true) {
             self.run.add_wasmtime_wasi_to_linker(linker)?;
-            wasmtime_wasi_http::p2::add_only_http_to_linker_async(linker)?;
+            wasmtime_wasi_http::p2::add_only_http_to_linker_with_options_async(
+                linker,
+                &http_options,
+            )?;
             #[cfg(feature = "component-model-async")]
             if self.run.common.wasi.p3.unwrap_or(crate::common::P3_DEFAULT) {
                 wasmtime_wasi_http::p3::add_to_linker(linker)?;
             }
         } else {
-            wasmtime_wasi_http::p2::add_to_linker_async(linker)?;
+            wasmtime_wasi::p2::add_to_linker_proxy_interfaces_async(linker)?;
+            wasmtime_wasi_http::p2::add_only_http_to_linker_with_options_async(
+                linker,
+                &http_options,
+            )?;
             #[cfg(feature = "component-model-async")]
             if self.run.common.wasi.p3.unwrap_or(crate::common::P3_DEFAULT) {
                 wasmtime_wasi_http::p3::add_to_linker(linker)?;
@@ -519,6 +593,19 @@ impl ServeCommand {
             }
         }
 
+        if self.run.common.wasi.correlation == Some(true) {
+            #[cfg(not(feature = "wasi-correlation"))]
+            {
+                bail!("support for wasi-correlation was disabled at compile time");
+            }
+            #[cfg(feature = "wasi-correlation")]
+            {
+                wasmtime_wasi_correlation::add_to_linker(linker, |h| {
+                    WasiCorrelation::from(h.correlation.as_ref().unwrap())
+                })?;
+            }
+        }
+
         if self.run.common.wasi.keyvalue == Some(true) {
             #[cfg(not(feature = "wasi-keyvalue"))]
             {
@@ -572,37 +659,77 @@ impl ServeCommand {
 
         self.add_to_linker(&mut linker)?;
 
-        let component = match self.run.load_module(&engine, &self.component, None)? {
-            RunTarget::Core(_) => bail!("The serve command currently requires a component"),
-            RunTarget::Component(c) => c,
-        };
+        let mut components = Vec::new();
+        for (route, path) in self.routes()? {
+            let component = self.load_component(&engine, &path)?;
+            components.push((route, path, component));
+        }
 
         #[cfg(feature = "debug")]
         if let Some(debug_run) = debug_run {
             return self
-                .serve_under_debugger(debug_run, linker, component)
+                .serve_under_debugger(debug_run, linker, components)
                 .await;
         }
 
-        self.serve_maybe_debug(linker, component, None).await
+        self.serve_maybe_debug(linker, components, None).await
+    }
+
+    /// Returns the path of each component to serve along with the requests
+    /// routed to it, read from `--routes` if specified.
+    fn routes(&self) -> Result<Vec<(Route, PathBuf)>> {
+        let Some(file) = &self.routes else {
+            let component = self.component.clone().unwrap();
+            return Ok(vec![(Route::new(None, "/")?, component)]);
+        };
+        let contents = std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read routes file `{}`", Path::display(file)))?;
+        let dir = file.parent().unwrap_or(Path::new(""));
+        let mut routes: Vec<(Route, PathBuf)> = Vec::new();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (route, wasm) = line
+                .split_once('=')
+                .with_context(|| format!("route `{line}` is missing `=`"))?;
+            let route = route
+                .trim()
+                .parse::<Route>()
+                .with_context(|| format!("invalid route `{line}`"))?;
+            if routes.iter().any(|(r, _)| *r == route) {
+                bail!("route `{route}` is listed more than once");
+            }
+            routes.push((route, dir.join(wasm.trim())));
+        }
+        if routes.is_empty() {
+            bail!("no routes listed in `{}`", Path::display(file));
+        }
+        Ok(routes)
+    }
+
+    fn load_component(&self, engine: &Engine, path: &Path) -> Result<Component> {
+        match self.run.load_module(engine, path, None)? {
+            RunTarget::Core(_) => bail!("The serve command currently requires a component"),
+            RunTarget::Component(c) => Ok(c),
+        }
     }
 
     async fn serve_maybe_debug(
         self,
         linker: Linker<Host>,
-        component: Component,
+        components: Vec<(Route, PathBuf, Component)>,
         mut debuggee_store: Option<&mut Store<Host>>,
     ) -> Result<()> {
         let engine = linker.engine();
         let request_headers = RequestHeaders::parse(&self.headers)?;
-        let instance = linker.instantiate_pre(&component)?;
-        #[cfg(feature = "component-model-async")]
-        let instance = match wasmtime_wasi_http::p3::bindings::ServicePre::new(instance.clone()) {
-            Ok(pre) => ProxyPre::P3(pre),
-            Err(_) => ProxyPre::P2(wasmtime_wasi_http::p2::bindings::ProxyPre::new(instance)?),
-        };
-        #[cfg(not(feature = "component-model-async"))]
-        let instance = ProxyPre::P2(wasmtime_wasi_http::p2::bindings::ProxyPre::new(instance)?);
+        let components = components
+            .into_iter()
+            .map(|(route, path, component)| {
+                let instance = proxy_pre(&linker, &component)?;
+                Ok((route, path, component, instance))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         // Spawn background task(s) waiting for graceful shutdown signals. This
         // always listens for ctrl-c but additionally can listen for a TCP
@@ -628,6 +755,21 @@ impl ServeCommand {
             });
         }
 
+        // Reloading the component isn't supported under a debugger since all
+        // requests are handled by the single debuggee store.
+        let reload_requested = Arc::new(Notify::new());
+        #[cfg(unix)]
+        if debuggee_store.is_none() {
+            use tokio::signal::unix::{SignalKind, signal};
+            let mut hangup = signal(SignalKind::hangup())?;
+            let reload_requested = reload_requested.clone();
+            tokio::task::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    reload_requested.notify_one();
+                }
+            });
+        }
+
         let socket = match &self.addr {
             SocketAddr::V4(_) => tokio::net::TcpSocket::new_v4()?,
             SocketAddr::V6(_) => tokio::net::TcpSocket::new_v6()?,
@@ -659,21 +801,6 @@ impl ServeCommand {
         };
         let _epoch_thread = epoch_interval.map(|t| EpochThread::spawn(t, engine.clone()));
 
-        let max_instance_reuse_count = self.max_instance_reuse_count.unwrap_or_else(|| {
-            if let ProxyPre::P3(_) = &instance {
-                DEFAULT_WASIP3_MAX_INSTANCE_REUSE_COUNT
-            } else {
-                DEFAULT_WASIP2_MAX_INSTANCE_REUSE_COUNT
-            }
-        });
-
-        let max_instance_concurrent_reuse_count = if let ProxyPre::P3(_) = &instance {
-            self.max_instance_concurrent_reuse_count
-                .unwrap_or(DEFAULT_WASIP3_MAX_INSTANCE_CONCURRENT_REUSE_COUNT)
-        } else {
-            1
-        };
-
         let max_concurrent_connections = self
             .max_concurrent_connections
             .unwrap_or(if debuggee_store.is_some() { 1 } else { 1000 });
@@ -688,28 +815,86 @@ impl ServeCommand {
         }
 
         let sem_connections = Arc::new(Semaphore::new(max_concurrent_connections));
+        let health_addr = self.health_addr;
+        let metrics_addr = self.metrics_addr;
 
-        let handler = ProxyHandler::new(HostHandlerState {
+        let server = Arc::new(HostServer {
             sem_requests: Semaphore::new(max_concurrent_requests),
             cmd: self,
-            component,
+            linker,
             request_headers,
-            max_instance_reuse_count,
-            max_instance_concurrent_reuse_count,
-            instance,
             next_instance_id: AtomicU64::default(),
             next_request_id: AtomicU64::default(),
-            // Give one shutdown guard to this handler which will track the
-            // full lifetime of any instances spawned.
-            _shutdown_guard: Box::new(shutdown.clone().increment()),
+            reload_error: Mutex::new(None),
+            request_latency: LatencyHistogram::default(),
+        });
+        let mut router = Router::new();
+        for (route, path, component, instance) in components {
+            let state = HostHandlerState::new(
+                server.clone(),
+                path,
+                component,
+                instance,
+                // Give one shutdown guard to each handler which will track
+                // the full lifetime of any instances spawned.
+                Box::new(shutdown.clone().increment()),
+            );
+            router.add(route, ReloadableProxyHandler::new(state));
+        }
+        let routes = Arc::new(HostRouter {
+            server: server.clone(),
+            router,
         });
 
+        // Serve health checks in the background. This task is aborted once the
+        // server starts shutting down to release its reference to `routes`.
+        let mut health_task = None;
+        if let Some(addr) = health_addr {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            eprintln!(
+                "Serving health checks on http://{}/",
+                listener.local_addr()?
+            );
+            let routes = routes.clone();
+            health_task = Some(tokio::task::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::task::spawn(handle_health_client(stream, routes.clone()));
+                }
+            }));
+        }
+
+        // Serve metrics in the background, aborted at shutdown like health
+        // checks above.
+        let mut metrics_task = None;
+        if let Some(addr) = metrics_addr {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            eprintln!("Serving metrics on http://{}/", listener.local_addr()?);
+            let routes = routes.clone();
+            metrics_task = Some(tokio::task::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::task::spawn(handle_metrics_client(stream, routes.clone()));
+                }
+            }));
+        }
+
         loop {
             // Wait for a socket, but also "race" against shutdown to break out
             // of this loop. Once the graceful shutdown signal is received then
             // this loop exits immediately.
             let (connection_permit, stream) = tokio::select! {
                 _ = shutdown.requested.notified() => break,
+                _ = reload_requested.notified() => {
+                    // Give the reload a shutdown guard per route to ensure
+                    // that shutdown waits for it, and subsequently the new
+                    // generation of instances, to finish.
+                    let shutdown_guards = routes
+                        .router
+                        .iter()
+                        .map(|_| Box::new(shutdown.clone().increment()) as ShutdownGuard)
+                        .collect();
+                    tokio::task::spawn(reload(routes.clone(), shutdown_guards));
+                    continue;
+                }
                 v = async {
                     let permit = sem_connections.clone().acquire_owned().await?;
                     let (stream, _) = listener.accept().await?;
@@ -734,12 +919,12 @@ impl ServeCommand {
             // task to handle this client.
             match &mut debuggee_store {
                 Some(store) => {
-                    handle_client(stream, &handler, Some(store)).await;
+                    handle_client(stream, &routes, Some(store)).await;
                 }
                 None => {
-                    let handler = handler.clone();
+                    let routes = routes.clone();
                     tokio::task::spawn(async move {
-                        handle_client(stream, &handler, None).await;
+                        handle_client(stream, &routes, None).await;
                         drop(shutdown_guard);
                         drop(connection_permit);
                     });
@@ -748,9 +933,12 @@ impl ServeCommand {
         }
 
         // Don't allow any further requests to get picked up.
-        handler.state().sem_requests.close();
+        server.sem_requests.close();
 
-        drop(handler);
+        for task in [health_task, metrics_task].into_iter().flatten() {
+            task.abort();
+        }
+        drop(routes);
 
         // Upon exiting the loop we'll no longer process any more incoming
         // connections but there may still be outstanding connections
@@ -817,7 +1005,7 @@ struct HostWorkerState {
 
 impl WorkerState for HostWorkerState {
     type StoreData = Host;
-    type RequestId = u64;
+    type RequestId = RequestInfo;
 
     fn should_accept_request(&self, concurrent_count: usize, total_count: usize) -> ShouldAccept {
         if total_count >= self.max_instance_reuse_count {
@@ -831,14 +1019,16 @@ impl WorkerState for HostWorkerState {
 
     fn on_request_start(
         &self,
-        _store: StoreContextMut<Host>,
-        request_id: u64,
+        mut store: StoreContextMut<Host>,
+        request: RequestInfo,
         _task_id: GuestTaskId,
     ) -> Pin<Box<dyn Future<Output = ()> + 'static + Send + Sync>> {
         log::info!(
-            "Instance {} handling request {request_id}",
+            "Instance {} handling request {}",
             self.instance_id,
+            request.id,
         );
+        store.data_mut().start_request(request);
 
         Box::pin(tokio::time::sleep(self.request_timeout))
     }
@@ -856,22 +1046,83 @@ impl WorkerState for HostWorkerState {
     }
 }
 
-struct HostHandlerState {
+/// State shared by all generations of `HostHandlerState` across reloads.
+struct HostServer {
     cmd: ServeCommand,
-    component: Component,
+    linker: Linker<Host>,
     request_headers: RequestHeaders,
-    max_instance_reuse_count: usize,
-    max_instance_concurrent_reuse_count: usize,
-    instance: ProxyPre<Host>,
     next_instance_id: AtomicU64,
     next_request_id: AtomicU64,
     sem_requests: Semaphore,
-    _shutdown_guard: Box<dyn std::any::Any + Send + Sync>,
+    /// The error from the most recent reload, if it failed.
+    reload_error: Mutex<Option<String>>,
+    /// Latencies of requests handled so far, reported by `--metrics-addr`.
+    request_latency: LatencyHistogram,
+}
+
+/// Type-erased guard delaying graceful shutdown, see `GracefulShutdown`.
+type ShutdownGuard = Box<dyn std::any::Any + Send + Sync>;
+
+/// The components served by `wasmtime serve`, one per route, and the state
+/// they share.
+struct HostRouter {
+    server: Arc<HostServer>,
+    router: Router<ReloadableProxyHandler<HostHandlerState>>,
+}
+
+struct HostHandlerState {
+    server: Arc<HostServer>,
+    /// The path `component` was loaded from, and is reloaded from.
+    component_path: PathBuf,
+    component: Component,
+    max_instance_reuse_count: usize,
+    max_instance_concurrent_reuse_count: usize,
+    instance: ProxyPre<Host>,
+    _shutdown_guard: ShutdownGuard,
 }
 
 impl HostHandlerState {
+    fn new(
+        server: Arc<HostServer>,
+        component_path: PathBuf,
+        component: Component,
+        instance: ProxyPre<Host>,
+        shutdown_guard: ShutdownGuard,
+    ) -> Self {
+        let cmd = &server.cmd;
+        let max_instance_reuse_count = cmd.max_instance_reuse_count.unwrap_or_else(|| {
+            if let ProxyPre::P3(_) = &instance {
+                DEFAULT_WASIP3_MAX_INSTANCE_REUSE_COUNT
+            } else {
+                DEFAULT_WASIP2_MAX_INSTANCE_REUSE_COUNT
+            }
+        });
+
+        // The correlation context is stored per-instance, so when it's enabled
+        // each instance may only handle a single request at a time.
+        let max_instance_concurrent_reuse_count = if let ProxyPre::P3(_) = &instance
+            && cmd.run.common.wasi.correlation != Some(true)
+        {
+            cmd.max_instance_concurrent_reuse_count
+                .unwrap_or(DEFAULT_WASIP3_MAX_INSTANCE_CONCURRENT_REUSE_COUNT)
+        } else {
+            1
+        };
+
+        HostHandlerState {
+            server,
+            component_path,
+            component,
+            max_instance_reuse_count,
+            max_instance_concurrent_reuse_count,
+            instance,
+            _shutdown_guard: shutdown_guard,
+        }
+    }
+
     async fn instantiate_into(&self, store: &mut Store<Host>) -> Result<Proxy> {
-        let write_profile = setup_epoch_handler(&self.cmd, &mut *store, self.component.clone())?;
+        let write_profile =
+            setup_epoch_handler(&self.server.cmd, &mut *store, self.component.clone())?;
         store.data_mut().write_profile = Some(write_profile);
         self.instance.instantiate_async(&mut *store).await
     }
@@ -885,10 +1136,9 @@ impl HandlerState for HostHandlerState {
     async fn instantiate(
         &self,
     ) -> Result<Instance<Self::StoreData, Self::WorkerExpiration, Self::WorkerState>> {
-        let instance_id = self.next_instance_id.fetch_add(1, Ordering::Relaxed);
-        let mut store = self
-            .cmd
-            .new_store(self.component.engine(), Some(instance_id))?;
+        let cmd = &self.server.cmd;
+        let instance_id = self.server.next_instance_id.fetch_add(1, Ordering::Relaxed);
+        let mut store = cmd.new_store(self.component.engine(), Some(instance_id))?;
         let proxy = self.instantiate_into(&mut store).await?;
 
         Ok(Instance {
@@ -896,20 +1146,287 @@ impl HandlerState for HostHandlerState {
             proxy,
             view: wasmtime_wasi_http::WasiHttpView::http,
             expiration: HostWorkerExpiration {
-                idle_timeout: self.cmd.idle_instance_timeout,
-                request_timeout: self.cmd.run.common.wasm.timeout.unwrap_or(Duration::MAX),
+                idle_timeout: cmd.idle_instance_timeout,
+                request_timeout: cmd.run.common.wasm.timeout.unwrap_or(Duration::MAX),
                 sleep: tokio::time::sleep(Duration::MAX),
             },
             state: HostWorkerState {
                 max_instance_reuse_count: self.max_instance_reuse_count,
                 max_instance_concurrent_reuse_count: self.max_instance_concurrent_reuse_count,
                 instance_id,
-                request_timeout: self.cmd.run.common.wasm.timeout.unwrap_or(Duration::MAX),
+                request_timeout: cmd.run.common.wasm.timeout.unwrap_or(Duration::MAX),
             },
         })
     }
 }
 
+/// Creates the pre-instance used to serve requests with `component`.
+fn proxy_pre(linker: &Linker<Host>, component: &Component) -> Result<ProxyPre<Host>> {
+    let instance = linker.instantiate_pre(component)?;
+    #[cfg(feature = "component-model-async")]
+    let instance = match wasmtime_wasi_http::p3::bindings::ServicePre::new(instance.clone()) {
+        Ok(pre) => ProxyPre::P3(pre),
+        Err(_) => ProxyPre::P2(wasmtime_wasi_http::p2::bindings::ProxyPre::new(instance)?),
+    };
+    #[cfg(not(feature = "component-model-async"))]
+    let instance = ProxyPre::P2(wasmtime_wasi_http::p2::bindings::ProxyPre::new(instance)?);
+    Ok(instance)
+}
+
+/// Recompiles each component from its original path and switches its route
+/// over to it, draining requests in flight on the previous version.
+async fn reload(routes: Arc<HostRouter>, shutdown_guards: Vec<ShutdownGuard>) {
+    let server = &routes.server;
+    let mut errors = Vec::new();
+    for ((_, handler), shutdown_guard) in routes.router.iter().zip(shutdown_guards) {
+        let path = handler.current().state().component_path.clone();
+        eprintln!("Reloading {}", Path::display(&path));
+
+        let compile = tokio::task::spawn_blocking({
+            let server = server.clone();
+            let path = path.clone();
+            move || {
+                let component = server.cmd.load_component(server.linker.engine(), &path)?;
+                let instance = proxy_pre(&server.linker, &component)?;
+                Ok((component, instance))
+            }
+        });
+        let (component, instance) =
+            match compile.await.map_err(wasmtime::Error::from).and_then(|r| r) {
+                Ok(pair) => pair,
+                Err(e) => {
+                    eprintln!("error: failed to reload component: {e:?}");
+                    errors.push(format!("{e:#}"));
+                    continue;
+                }
+            };
+
+        let state =
+            HostHandlerState::new(server.clone(), path, component, instance, shutdown_guard);
+        let report = handler.reload(state, server.cmd.drain_timeout).await;
+        if report.abandoned > 0 {
+            eprintln!(
+                "Reloaded component (generation {}), abandoned {} in-flight request(s) after {:?}",
+                report.generation, report.abandoned, report.drain_time,
+            );
+        } else {
+            eprintln!(
+                "Reloaded component (generation {}), drained previous generation in {:?}",
+                report.generation, report.drain_time,
+            );
+        }
+    }
+    *server.reload_error.lock().unwrap() = if errors.is_empty() {
+        None
+    } else {
+        Some(errors.join("\n"))
+    };
+}
+
+/// Answers requests to the `--health-addr` endpoint with the server status.
+///
+/// With `--routes` the status of each route is listed in `routes`, otherwise
+/// the status of the only component is reported at the top level.
+async fn handle_health_client(client: tokio::net::TcpStream, routes: Arc<HostRouter>) {
+    let service = hyper::service::service_fn(move |_req| {
+        let route_status = |handler: &ReloadableProxyHandler<HostHandlerState>| {
+            let status = handler.status();
+            serde_json::json!({
+                "generation": status.generation,
+                "draining": status.draining,
+                "last_reload": status.last_reload.map(|r| serde_json::json!({
+                    "generation": r.generation,
+                    "drain_time_ms": r.drain_time.as_millis(),
+                    "abandoned": r.abandoned,
+                })),
+            })
+        };
+        let mut body = if routes.server.cmd.routes.is_some() {
+            let statuses = routes
+                .router
+                .iter()
+                .map(|(route, handler)| {
+                    let mut status = route_status(handler);
+                    status["route"] = route.to_string().into();
+                    status["component"] = Path::display(&handler.current().state().component_path)
+                        .to_string()
+                        .into();
+                    status
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "routes": statuses })
+        } else {
+            let (_, handler) = routes.router.iter().next().unwrap();
+            route_status(handler)
+        };
+        body["reload_error"] = routes.server.reload_error.lock().unwrap().clone().into();
+        async move {
+            Response::builder()
+                .header("Content-Type", "application/json")
+                .body(Full::new(bytes::Bytes::from(body.to_string())))
+        }
+    });
+    if let Err(e) = http1::Builder::new()
+        .keep_alive(false)
+        .serve_connection(TokioIo::new(client), service)
+        .await
+    {
+        eprintln!("error: {e:?}");
+    }
+}
+
+/// Answers requests to the `--metrics-addr` endpoint with server metrics in
+/// the Prometheus text exposition format.
+async fn handle_metrics_client(client: tokio::net::TcpStream, routes: Arc<HostRouter>) {
+    let service = hyper::service::service_fn(move |_req| {
+        let server = &routes.server;
+        let body = render_metrics(server.linker.engine(), &server.request_latency);
+        async move {
+            Response::builder()
+                .header("Content-Type", "text/plain; version=0.0.4")
+                .body(Full::new(bytes::Bytes::from(body)))
+        }
+    });
+    if let Err(e) = http1::Builder::new()
+        .keep_alive(false)
+        .serve_connection(TokioIo::new(client), service)
+        .await
+    {
+        eprintln!("error: {e:?}");
+    }
+}
+
+/// Renders the metrics of `engine` and the request latencies in `latency` in
+/// the Prometheus text exposition format.
+fn render_metrics(engine: &Engine, latency: &LatencyHistogram) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+        writeln!(out, "# HELP {name} {help}").unwrap();
+        writeln!(out, "# TYPE {name} {kind}").unwrap();
+        for (suffix, value) in samples {
+            writeln!(out, "{name}{suffix} {value}").unwrap();
+        }
+    };
+    let count = |n: usize| n as f64;
+
+    let mut buckets = Vec::new();
+    let mut cumulative = 0;
+    for (bound, n) in LATENCY_BUCKETS_SECONDS.iter().zip(&latency.buckets) {
+        cumulative += n.load(Ordering::Relaxed);
+        buckets.push((format!("_bucket{{le=\"{bound}\"}}"), cumulative as f64));
+    }
+    let total = latency.count.load(Ordering::Relaxed) as f64;
+    buckets.push(("_bucket{le=\"+Inf\"}".to_string(), total));
+    let sum_micros = latency.sum_micros.load(Ordering::Relaxed) as f64;
+    buckets.push(("_sum".to_string(), sum_micros / 1e6));
+    buckets.push(("_count".to_string(), total));
+    metric(
+        "wasmtime_serve_request_duration_seconds",
+        "histogram",
+        "Time taken to produce the response head of each request.",
+        &buckets,
+    );
+
+    let metrics = engine.metrics();
+    metric(
+        "wasmtime_core_instantiations_total",
+        "counter",
+        "Core module instances created.",
+        &[(String::new(), count(metrics.core_instantiations()))],
+    );
+    metric(
+        "wasmtime_component_instantiations_total",
+        "counter",
+        "Component instances created.",
+        &[(String::new(), count(metrics.component_instantiations()))],
+    );
+    let traps = metrics
+        .trap_counts()
+        .map(|(trap, n)| (format!("{{trap=\"{trap:?}\"}}"), count(n)))
+        .collect::<Vec<_>>();
+    metric(
+        "wasmtime_traps_total",
+        "counter",
+        "WebAssembly traps, by trap code.",
+        &traps,
+    );
+    metric(
+        "wasmtime_fuel_exhaustions_total",
+        "counter",
+        "Times WebAssembly ran out of fuel.",
+        &[(String::new(), count(metrics.fuel_exhaustions()))],
+    );
+    metric(
+        "wasmtime_epoch_interruptions_total",
+        "counter",
+        "Times WebAssembly reached its epoch deadline.",
+        &[(String::new(), count(metrics.epoch_interruptions()))],
+    );
+    metric(
+        "wasmtime_cache_hits_total",
+        "counter",
+        "Compilations served from the compilation cache.",
+        &[(String::new(), count(metrics.cache_hits()))],
+    );
+    metric(
+        "wasmtime_cache_misses_total",
+        "counter",
+        "Compilations not found in the compilation cache.",
+        &[(String::new(), count(metrics.cache_misses()))],
+    );
+
+    if let Some(pool) = engine.pooling_allocator_metrics() {
+        metric(
+            "wasmtime_pooling_allocator_slots",
+            "gauge",
+            "Pooling allocator slots currently in use, by kind.",
+            &[
+                (
+                    "{kind=\"core_instance\"}".to_string(),
+                    pool.core_instances() as f64,
+                ),
+                (
+                    "{kind=\"component_instance\"}".to_string(),
+                    pool.component_instances() as f64,
+                ),
+                ("{kind=\"memory\"}".to_string(), count(pool.memories())),
+                ("{kind=\"table\"}".to_string(), count(pool.tables())),
+            ],
+        );
+    }
+
+    out
+}
+
+/// Upper bounds, in seconds, of the buckets of [`LatencyHistogram`].
+const LATENCY_BUCKETS_SECONDS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0,
+];
+
+/// A histogram of request latencies exported by `--metrics-addr`.
+#[derive(Default)]
+struct LatencyHistogram {
+    /// Number of requests in each of `LATENCY_BUCKETS_SECONDS`, not including
+    /// smaller buckets.
+    buckets: [AtomicU64; LATENCY_BUCKETS_SECONDS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl LatencyHistogram {
+    fn record(&self, latency: Duration) {
+        let secs = latency.as_secs_f64();
+        if let Some(i) = LATENCY_BUCKETS_SECONDS.iter().position(|b| secs <= *b) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+    }
+}
+
 /// Helper structure to manage graceful shutdown int he accept loop above.
 #[derive(Default)]
 struct GracefulShutdown {
@@ -1093,7 +1610,7 @@ type Request = hyper::Request<hyper::body::Incoming>;
 
 async fn handle_client(
     client: tokio::net::TcpStream,
-    handler: &ProxyHandler<HostHandlerState>,
+    routes: &HostRouter,
     debuggee_store: Option<&mut Store<Host>>,
 ) {
     // Hyper's `service_fn` takes an `Fn` closure, so to bridge the need to
@@ -1101,22 +1618,39 @@ async fn handle_client(
     // used. The tokio mutex is required as the returned future must also be
     // `Send`.
     let lock = &debuggee_store.map(tokio::sync::Mutex::new);
+    let informational = Arc::new(Informational::default());
+    let client = InformationalIo {
+        io: client,
+        informational: informational.clone(),
+    };
 
     if let Err(e) = http1::Builder::new()
         .keep_alive(true)
         .serve_connection(
             TokioIo::new(client),
-            hyper::service::service_fn(move |req| async move {
-                let mut debuggee_store = match &lock {
-                    Some(store) => Some(store.lock().await),
-                    None => None,
-                };
-                let debuggee_store = debuggee_store.as_mut().map(|s| &mut ***s);
-                match handle_request(handler, debuggee_store, req).await {
-                    Ok(r) => Ok::<_, Infallible>(r),
-                    Err(e) => {
-                        eprintln!("error: {e:?}");
-                        let error_html = "\
+            hyper::service::service_fn(move |mut req| {
+                let informational = informational.clone();
+                async move {
+                    informational.idle().await;
+                    // HTTP/1.0 clients don't understand informational
+                    // responses, so they're only forwarded to newer ones.
+                    if req.version() >= http::Version::HTTP_11 {
+                        let informational = informational.clone();
+                        req.extensions_mut().insert(OnInformational::new(
+                            move |status, headers| informational.send(status, &headers),
+                        ));
+                    }
+
+                    let mut debuggee_store = match &lock {
+                        Some(store) => Some(store.lock().await),
+                        None => None,
+                    };
+                    let debuggee_store = debuggee_store.as_mut().map(|s| &mut ***s);
+                    let response = match handle_request(routes, debuggee_store, req).await {
+                        Ok(r) => r,
+                        Err(e) => {
+                            eprintln!("error: {e:?}");
+                            let error_html = "\
 <!doctype html>
 <html>
 <head>
@@ -1130,16 +1664,28 @@ async fn handle_client(
     </center>
 </body>
 </html>";
-                        Ok(Response::builder()
-                            .status(StatusCode::INTERNAL_SERVER_ERROR)
-                            .header("Content-Type", "text/html; charset=UTF-8")
-                            .body(
-                                Full::new(bytes::Bytes::from(error_html))
-                                    .map_err(|_| unreachable!())
-                                    .boxed_unsync(),
-                            )
-                            .unwrap())
-                    }
+                            Response::builder()
+                                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                .header("Content-Type", "text/html; charset=UTF-8")
+                                .body(
+                                    Full::new(bytes::Bytes::from(error_html))
+                                        .map_err(|_| unreachable!())
+                                        .boxed_unsync(),
+                                )
+                                .unwrap()
+                        }
+                    };
+
+                    // The guard is moved into the body so it's dropped once
+                    // hyper is done with the body.
+                    let guard = informational.respond();
+                    Ok::<_, Infallible>(response.map(|body| {
+                        body.map_frame(move |frame| {
+                            let _ = &guard;
+                            frame
+                        })
+                        .boxed_unsync()
+                    }))
                 }
             }),
         )
@@ -1149,36 +1695,219 @@ async fn handle_client(
     }
 }
 
+/// State shared between a client connection and the requests handled on it
+/// to forward informational (1xx) responses, such as `103 Early Hints`, sent
+/// by guests.
+///
+/// Hyper's server can't send informational responses other than
+/// `100 Continue` itself, so they're serialized here and written to the
+/// connection by [`InformationalIo`] ahead of whatever hyper writes next. To
+/// keep them from being interleaved with an earlier response on the same
+/// connection, pipelined requests aren't handled until the previous response
+/// has been completely written.
+#[derive(Default)]
+struct Informational {
+    state: Mutex<InformationalState>,
+    idle: Notify,
+}
+
+#[derive(Default)]
+struct InformationalState {
+    /// Serialized informational responses which haven't been written yet.
+    pending: Vec<u8>,
+    /// Waker of the task driving the connection, woken when `pending` grows.
+    waker: Option<std::task::Waker>,
+    /// Whether a final response is being written to the connection.
+    responding: bool,
+    /// Whether hyper is done with the body of the final response, which
+    /// means it's been written once hyper next flushes the connection.
+    body_done: bool,
+}
+
+impl Informational {
+    /// Waits until no final response is being written to the connection.
+    async fn idle(&self) {
+        loop {
+            let notified = self.idle.notified();
+            if !self.state.lock().unwrap().responding {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Queues an informational response to be written to the connection.
+    fn send(&self, status: StatusCode, headers: &HeaderMap) {
+        let mut state = self.state.lock().unwrap();
+        // Informational responses can't follow the final response, and hyper
+        // wouldn't know about a protocol switch, so neither is forwarded.
+        if state.responding || status == StatusCode::SWITCHING_PROTOCOLS {
+            return;
+        }
+        let reason = status.canonical_reason().unwrap_or("");
+        let pending = &mut state.pending;
+        pending.extend_from_slice(format!("HTTP/1.1 {} {reason}\r\n", status.as_u16()).as_bytes());
+        for (name, value) in headers {
+            pending.extend_from_slice(name.as_str().as_bytes());
+            pending.extend_from_slice(b": ");
+            pending.extend_from_slice(value.as_bytes());
+            pending.extend_from_slice(b"\r\n");
+        }
+        pending.extend_from_slice(b"\r\n");
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Marks the start of a final response, returning a guard to drop once
+    /// hyper is done with its body.
+    fn respond(self: &Arc<Self>) -> ResponseGuard {
+        self.state.lock().unwrap().responding = true;
+        ResponseGuard(self.clone())
+    }
+}
+
+/// Returned by [`Informational::respond`].
+struct ResponseGuard(Arc<Informational>);
+
+impl Drop for ResponseGuard {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().body_done = true;
+    }
+}
+
+pin_project! {
+    /// A client connection which writes the informational responses queued
+    /// in an [`Informational`] ahead of hyper's own writes.
+    struct InformationalIo<T> {
+        #[pin]
+        io: T,
+        informational: Arc<Informational>,
+    }
+}
+
+impl<T: AsyncWrite> InformationalIo<T> {
+    /// Writes any queued informational responses to the connection.
+    fn poll_informational(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut me = self.project();
+        let mut state = me.informational.state.lock().unwrap();
+        state.waker = Some(cx.waker().clone());
+        while !state.pending.is_empty() {
+            let n = std::task::ready!(me.io.as_mut().poll_write(cx, &state.pending))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            state.pending.drain(..n);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncRead> AsyncRead for InformationalIo<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.project().io.poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite> AsyncWrite for InformationalIo<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        std::task::ready!(self.as_mut().poll_informational(cx))?;
+        self.project().io.poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        std::task::ready!(self.as_mut().poll_informational(cx))?;
+        self.project().io.poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        std::task::ready!(self.as_mut().poll_informational(cx))?;
+        let me = self.project();
+        std::task::ready!(me.io.poll_flush(cx))?;
+
+        // Hyper only flushes the connection once everything it has buffered
+        // is written, so if it's done with the body of the final response
+        // then the response has been written completely.
+        let mut state = me.informational.state.lock().unwrap();
+        if state.body_done {
+            state.body_done = false;
+            state.responding = false;
+            drop(state);
+            me.informational.idle.notify_waiters();
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().io.poll_shutdown(cx)
+    }
+}
+
 async fn handle_request(
-    handler: &ProxyHandler<HostHandlerState>,
+    routes: &HostRouter,
     debuggee_store: Option<&mut Store<Host>>,
     mut req: Request,
 ) -> Result<hyper::Response<wasmtime_wasi_http::WasiBody>> {
+    let server = &routes.server;
+
     // This is used to throttle the maximum number of concurrent requests that
     // can be processed at any one point in time before delegating to
     // `handler.handle(...)` below.
-    let _request_permit = handler.state().sem_requests.acquire().await?;
-
-    handler.state().request_headers.apply(req.headers_mut());
-
-    let request_id = handler
-        .state()
-        .next_request_id
-        .fetch_add(1, Ordering::Relaxed);
+    let _request_permit = server.sem_requests.acquire().await?;
+
+    server.request_headers.apply(req.headers_mut());
+
+    let request = RequestInfo {
+        id: server.next_request_id.fetch_add(1, Ordering::Relaxed),
+        #[cfg(feature = "wasi-correlation")]
+        trace_id: req
+            .headers()
+            .get(wasmtime_wasi_correlation::TRACE_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string()),
+    };
     log::info!(
-        "Received request {request_id}: {} {}",
+        "Received request {}: {} {}",
+        request.id,
         req.method(),
         req.uri()
     );
 
+    let Some(handler) = routes.router.route(&req) else {
+        log::info!("No route for request {}", request.id);
+        return Ok(Response::builder().status(StatusCode::NOT_FOUND).body(
+            Full::new(bytes::Bytes::from_static(b"no route for this request\n"))
+                .map_err(|_| unreachable!())
+                .boxed_unsync(),
+        )?);
+    };
+
     let req = req.map(|body| body.map_err(|e| e.into()).boxed_unsync());
 
-    match debuggee_store {
+    let start = Instant::now();
+    let response = match debuggee_store {
         // For debugging go ahead and synchronously execute the instance here
         // in a single instance. This is debugging-specific to use the store
         // passed in.
         Some(store) => {
-            let instance = handler.state().instantiate_into(store).await?;
+            let instance = handler.current().state().instantiate_into(store).await?;
+            store.data_mut().start_request(request);
             let (tx, rx) = futures::channel::oneshot::channel();
             let prepared = Prepared::new(
                 store.as_context_mut(),
@@ -1194,7 +1923,32 @@ async fn handle_request(
         }
 
         // For when debugging is disabled delegate to the default handling path.
-        None => handler.handle(request_id, req).await,
+        None => handler.handle(request, req).await,
+    };
+    server.request_latency.record(start.elapsed());
+    response
+}
+
+/// Identifies a request handled by `wasmtime serve`.
+struct RequestInfo {
+    id: u64,
+    /// The trace ID propagated by the client, if any.
+    #[cfg(feature = "wasi-correlation")]
+    trace_id: Option<String>,
+}
+
+impl Host {
+    /// Updates the state of this store for handling `request`.
+    fn start_request(&mut self, request: RequestInfo) {
+        #[cfg(feature = "wasi-correlation")]
+        if let Some(correlation) = &self.correlation {
+            correlation.set(CorrelationContext {
+                request_id: request.id,
+                trace_id: request.trace_id,
+            });
+        }
+        #[cfg(not(feature = "wasi-correlation"))]
+        let _ = request;
     }
 }
 
@@ -85,8 +85,28 @@ pub struct RunCommon {
     /// host is made available within the guest. If specified as `HOST::GUEST`
     /// then the `HOST` directory is opened and made available as the name
     /// `GUEST` in the guest.
-    #[arg(long = "dir", value_name = "HOST_DIR[::GUEST_DIR]", value_parser = parse_dirs)]
-    pub dirs: Vec<(String, String)>,
+    ///
+    /// Either form can be followed by `:OPTIONS`, a comma-separated list of:
+    ///
+    /// * `ro` - the guest can only read the directory and files within it.
+    /// * `rw` - the guest can also modify the directory (the default).
+    /// * `create` - the guest can create new files and directories, even if
+    ///   the directory is otherwise read-only with `ro`. Files the guest
+    ///   creates can be written through the descriptor which created them.
+    /// * `nofollow` - symlinks at the end of paths are never followed.
+    /// * `follow` - symlinks are followed if requested (the default).
+    /// * `mkdir` - create `HOST_DIR`, and any missing parents, on the host if
+    ///   it doesn't already exist.
+    ///
+    /// For example `--dir ./data::/data:ro,nofollow,create`. A final `:` is
+    /// only treated as the start of `:OPTIONS` if everything after it is a
+    /// known option, so paths like `./a:b` are still interpreted as paths.
+    #[arg(
+        long = "dir",
+        value_name = "HOST_DIR[::GUEST_DIR][:OPTIONS]",
+        value_parser = DirMapping::parse,
+    )]
+    pub dirs: Vec<DirMapping>,
 
     /// Pass an environment variable to the program.
     ///
@@ -117,14 +137,119 @@ fn parse_env_var(s: &str) -> Result<(String, Option<String>)> {
     ))
 }
 
-fn parse_dirs(s: &str) -> Result<(String, String)> {
-    let mut parts = s.split("::");
-    let host = parts.next().unwrap();
-    let guest = match parts.next() {
-        Some(guest) => guest,
-        None => host,
-    };
-    Ok((host.into(), guest.into()))
+/// A host directory made available to the guest with `--dir`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirMapping {
+    /// The directory on the host to open.
+    pub host: String,
+    /// The name of the directory within the guest.
+    pub guest: String,
+    /// Whether the guest may only read from the directory.
+    pub read_only: bool,
+    /// Whether the guest may create new entries even if `read_only`.
+    pub create: bool,
+    /// Whether symlinks within the directory are followed.
+    pub follow_symlinks: bool,
+    /// Whether `host` is created on the host if it doesn't exist.
+    pub mkdir: bool,
+}
+
+impl DirMapping {
+    const OPTIONS: &[&str] = &["ro", "rw", "create", "nofollow", "follow", "mkdir"];
+
+    /// Parse the `--dir` argument of the `run` or `serve` commands.
+    pub fn parse(s: &str) -> Result<DirMapping> {
+        // A trailing `:OPTIONS` is only recognized if every option in it is
+        // known, so host paths which contain a `:`, such as `C:\foo` on
+        // Windows or `./a:b`, are still interpreted as paths.
+        let (dirs, options) = match s.rsplit_once(':') {
+            Some((dirs, options))
+                if !dirs.is_empty()
+                    && !dirs.ends_with(':')
+                    && options.split(',').all(|o| Self::OPTIONS.contains(&o)) =>
+            {
+                (dirs, options)
+            }
+            _ => (s, ""),
+        };
+        let (host, guest) = dirs.split_once("::").unwrap_or((dirs, dirs));
+        let mut mapping = DirMapping {
+            host: host.to_string(),
+            guest: guest.to_string(),
+            read_only: false,
+            create: false,
+            follow_symlinks: true,
+            mkdir: false,
+        };
+        for option in options.split(',').filter(|o| !o.is_empty()) {
+            match option {
+                "ro" => mapping.read_only = true,
+                "rw" => mapping.read_only = false,
+                "create" => mapping.create = true,
+                "nofollow" => mapping.follow_symlinks = false,
+                "follow" => mapping.follow_symlinks = true,
+                "mkdir" => mapping.mkdir = true,
+                _ => unreachable!(),
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// Opens this directory within `builder`, creating it first if requested.
+    fn preopen(&self, builder: &mut WasiCtxBuilder) -> Result<()> {
+        use wasmtime_wasi::{DirPerms, FilePerms, SymlinkPolicy};
+
+        if self.mkdir {
+            std::fs::create_dir_all(&self.host)
+                .with_context(|| format!("failed to create directory `{}`", self.host))?;
+        }
+        let (mut dir_perms, file_perms) = if self.read_only {
+            (DirPerms::READ, FilePerms::READ)
+        } else {
+            (DirPerms::all(), FilePerms::all())
+        };
+        if self.create {
+            dir_perms |= DirPerms::CREATE;
+        }
+        let symlinks = if self.follow_symlinks {
+            SymlinkPolicy::Follow
+        } else {
+            SymlinkPolicy::NoFollow
+        };
+        builder
+            .preopened_dir_with_symlink_policy(
+                &self.host,
+                &self.guest,
+                dir_perms,
+                file_perms,
+                symlinks,
+            )
+            .with_context(|| format!("failed to open directory `{}`", self.host))?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for DirMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}::{}", self.host, self.guest)?;
+        let mut options = Vec::new();
+        if self.read_only {
+            options.push("ro");
+        }
+        if self.create {
+            options.push("create");
+        }
+        if !self.follow_symlinks {
+            options.push("nofollow");
+        }
+        if self.mkdir {
+            options.push("mkdir");
+        }
+        if !options.is_empty() {
+            write!(f, ":{}", options.join(","))?;
+        }
+        Ok(())
+    }
 }
 
 impl std::fmt::Display for RunCommon {
@@ -136,8 +261,8 @@ impl std::fmt::Display for RunCommon {
         if let Some(profile) = &self.profile {
             write!(f, "--profile={profile} ")?;
         }
-        for (host, guest) in &self.dirs {
-            write!(f, "--dir={host}::{guest} ")?;
+        for dir in &self.dirs {
+            write!(f, "--dir={dir} ")?;
         }
         for (key, value) in &self.vars {
             match value {
@@ -343,13 +468,8 @@ impl RunCommon {
             builder.env(key, &value);
         }
 
-        for (host, guest) in self.dirs.iter() {
-            builder.preopened_dir(
-                host,
-                guest,
-                wasmtime_wasi::DirPerms::all(),
-                wasmtime_wasi::FilePerms::all(),
-            )?;
+        for dir in self.dirs.iter() {
+            dir.preopen(builder)?;
         }
         if let Some(cwd) = &self.common.wasi.cwd {
             builder.initial_cwd(cwd);
@@ -393,6 +513,18 @@ impl RunCommon {
         Ok(http)
     }
 
+    #[cfg(all(feature = "wasi-http", feature = "component-model"))]
+    pub fn wasi_http_link_options(&self) -> wasmtime_wasi_http::p2::bindings::LinkOptions {
+        let mut options = wasmtime_wasi_http::p2::bindings::LinkOptions::default();
+        options.informational_outbound_responses(
+            self.common
+                .wasi
+                .http_informational_responses
+                .unwrap_or(false),
+        );
+        options
+    }
+
     #[cfg(feature = "wasi-http")]
     pub fn wasi_http_hooks(&self) -> HttpHooks {
         HttpHooks {
@@ -291,6 +291,87 @@ async fn cancel_during_run() {
     }
 }
 
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn cancel_unwinds_guest() -> Result<()> {
+    #[derive(Default)]
+    struct State {
+        cancelled_in_drop: Option<bool>,
+        cancelled_in_hook: bool,
+        resumed_after_cancel: bool,
+    }
+
+    struct Hook;
+
+    #[async_trait::async_trait]
+    impl CallHookHandler<State> for Hook {
+        async fn handle_call_event(
+            &self,
+            mut store: StoreContextMut<'_, State>,
+            ch: CallHook,
+        ) -> Result<()> {
+            if matches!(ch, CallHook::ReturningFromHost) && store.is_cancelled() {
+                store.data_mut().cancelled_in_hook = true;
+                // Suspending while cancelled fails, dropping this future,
+                // rather than leaving the guest suspended.
+                tokio::task::yield_now().await;
+                store.data_mut().resumed_after_cancel = true;
+            }
+            Ok(())
+        }
+    }
+
+    struct RecordOnDrop<'a>(Caller<'a, State>);
+
+    impl Drop for RecordOnDrop<'_> {
+        fn drop(&mut self) {
+            let cancelled = self.0.is_cancelled();
+            self.0.data_mut().cancelled_in_drop = Some(cancelled);
+        }
+    }
+
+    let mut store = Store::new(&Engine::default(), State::default());
+    store.call_hook_async(Hook);
+    let module = Module::new(
+        store.engine(),
+        r#"
+            (module
+                (import "" "pending" (func $pending))
+                (func (export "run") call $pending unreachable)
+                (func (export "ok") (result i32) i32.const 42)
+            )
+        "#,
+    )?;
+    let ty = FuncType::new(store.engine(), None, None);
+    let pending = Func::new_async(&mut store, ty, |caller, _params, _results| {
+        let guard = RecordOnDrop(caller);
+        Box::new(async move {
+            let _ = &guard;
+            std::future::pending::<()>().await;
+            Ok(())
+        })
+    });
+    let instance = Instance::new_async(&mut store, &module, &[pending.into()]).await?;
+    let run = instance.get_func(&mut store, "run").unwrap();
+    let ok = instance.get_typed_func::<(), i32>(&mut store, "ok")?;
+
+    let future = Box::pin(run.call_async(&mut store, &[], &mut []));
+    let future = PollOnce::new(future).await;
+    assert!(future.is_err());
+
+    // Dropping the future unwinds the guest, dropping the host future along
+    // the way and failing any further attempts to suspend.
+    drop(future);
+    assert_eq!(store.data().cancelled_in_drop, Some(true));
+    assert!(store.data().cancelled_in_hook);
+    assert!(!store.data().resumed_after_cancel);
+    assert!(!store.is_cancelled());
+
+    // The store remains usable afterwards.
+    assert_eq!(ok.call_async(&mut store, ()).await?, 42);
+    Ok(())
+}
+
 #[tokio::test]
 #[cfg_attr(miri, ignore)]
 async fn iloop_with_fuel() {
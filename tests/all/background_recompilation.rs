@@ -0,0 +1,201 @@
+use wasmtime::*;
+use wasmtime_test_macros::wasmtime_test;
+
+const WAT: &str = r#"
+(module
+  (import "" "table" (table 3 funcref))
+  (import "" "add" (func $imported_add (param i32 i32) (result i32)))
+  (type $binary (func (param i32 i32) (result i32)))
+
+  (func $add (export "add") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+  (func $mul (export "mul") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.mul)
+  (func (export "sum") (param $n i32) (result i32) (local $acc i32)
+    (loop $l
+      (local.set $acc (call $imported_add (local.get $acc) (local.get $n)))
+      (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if $l (local.get $n)))
+    local.get $acc)
+  (func (export "call-indirect") (param i32 i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    call_indirect (type $binary))
+  (func (export "trap")
+    unreachable)
+  (func (export "fail") (param i32 i32) (result i32)
+    unreachable)
+)
+"#;
+
+#[wasmtime_test(strategies(only(Winch)))]
+fn baseline_and_optimized_can_be_mixed(config: &mut Config) -> Result<()> {
+    config.background_recompilation(true);
+    let engine = Engine::new(config)?;
+    let module = RecompilingModule::new(&engine, WAT)?;
+    let baseline = module.baseline().clone();
+    let optimized = module.wait()?;
+    assert!(!Module::same(&baseline, &optimized));
+    assert!(Module::same(&module.module(), &optimized));
+
+    let mut store = Store::new(&engine, ());
+    let ty = TableType::new(RefType::FUNCREF, 3, None);
+    let table = Table::new(&mut store, ty, Ref::Func(None))?;
+    let host_add = Func::wrap(&mut store, |a: i32, b: i32| a + b);
+
+    // Chain together instances of each version through their imports.
+    let a = Instance::new(&mut store, &baseline, &[table.into(), host_add.into()])?;
+    let a_add = a.get_func(&mut store, "add").unwrap();
+    let b = Instance::new(&mut store, &optimized, &[table.into(), a_add.into()])?;
+    let b_add = b.get_func(&mut store, "add").unwrap();
+    let c = Instance::new(&mut store, &baseline, &[table.into(), b_add.into()])?;
+    for instance in [a, b, c] {
+        let sum = instance.get_typed_func::<i32, i32>(&mut store, "sum")?;
+        assert_eq!(sum.call(&mut store, 100)?, 5050);
+    }
+
+    // Fill a shared table with functions from each version.
+    let a_mul = a.get_func(&mut store, "mul").unwrap();
+    let b_mul = b.get_func(&mut store, "mul").unwrap();
+    table.set(&mut store, 0, a_mul.into())?;
+    table.set(&mut store, 1, b_mul.into())?;
+    table.set(&mut store, 2, b_add.into())?;
+    for instance in [a, b] {
+        let f = instance.get_typed_func::<(i32, i32, i32), i32>(&mut store, "call-indirect")?;
+        assert_eq!(f.call(&mut store, (6, 7, 0))?, 42);
+        assert_eq!(f.call(&mut store, (6, 7, 1))?, 42);
+        assert_eq!(f.call(&mut store, (6, 7, 2))?, 13);
+    }
+
+    // Traps are attributed to the compiler which produced the code.
+    for (instance, backend) in [(a, TrapBackend::Winch), (b, TrapBackend::Cranelift)] {
+        let trap = instance.get_typed_func::<(), ()>(&mut store, "trap")?;
+        let err = trap.call(&mut store, ()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<TrapDetails>().unwrap().backend(),
+            backend
+        );
+    }
+    Ok(())
+}
+
+#[wasmtime_test(strategies(only(Winch)))]
+fn running_instances_are_upgraded(config: &mut Config) -> Result<()> {
+    config.background_recompilation(true);
+    let engine = Engine::new(config)?;
+    let module = RecompilingModule::new(&engine, WAT)?;
+    let baseline = module.baseline().clone();
+
+    let mut store = Store::new(&engine, ());
+    let ty = TableType::new(RefType::FUNCREF, 3, None);
+    let table = Table::new(&mut store, ty, Ref::Func(None))?;
+    let host_add = Func::wrap(&mut store, |a: i32, b: i32| a + b);
+    let a = Instance::new(&mut store, &baseline, &[table.into(), host_add.into()])?;
+
+    // Reach `a`'s failing function through an export looked up before the
+    // upgrade, through a table, and through another instance's import.
+    let a_fail = a.get_func(&mut store, "fail").unwrap();
+    table.set(&mut store, 0, a_fail.into())?;
+    let b = Instance::new(&mut store, &baseline, &[table.into(), a_fail.into()])?;
+    let fail = a_fail.typed::<(i32, i32), i32>(&store)?;
+    let call_indirect = b.get_typed_func::<(i32, i32, i32), i32>(&mut store, "call-indirect")?;
+    let sum = b.get_typed_func::<i32, i32>(&mut store, "sum")?;
+    let backends = |store: &mut Store<()>| -> Result<Vec<TrapBackend>> {
+        let errors = [
+            fail.call(&mut *store, (1, 2)).unwrap_err(),
+            call_indirect.call(&mut *store, (1, 2, 0)).unwrap_err(),
+            sum.call(&mut *store, 3).unwrap_err(),
+        ];
+        Ok(errors
+            .iter()
+            .map(|e| e.downcast_ref::<TrapDetails>().unwrap().backend())
+            .collect())
+    };
+    assert_eq!(backends(&mut store)?, [TrapBackend::Winch; 3]);
+
+    module.wait()?;
+    assert!(module.upgrade(&mut store, &a)?);
+    assert_eq!(backends(&mut store)?, [TrapBackend::Cranelift; 3]);
+
+    // Upgrading is idempotent, and `b` itself is still running Winch code.
+    assert!(module.upgrade(&mut store, &a)?);
+    let b_fail = b.get_typed_func::<(i32, i32), i32>(&mut store, "fail")?;
+    let err = b_fail.call(&mut store, (1, 2)).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<TrapDetails>().unwrap().backend(),
+        TrapBackend::Winch
+    );
+
+    // Instances of the optimized module don't need an upgrade, and instances
+    // of other modules can't be upgraded.
+    let c = Instance::new(
+        &mut store,
+        &module.module(),
+        &[table.into(), host_add.into()],
+    )?;
+    assert!(module.upgrade(&mut store, &c)?);
+    let other = Module::new(&engine, "(module)")?;
+    let d = Instance::new(&mut store, &other, &[])?;
+    assert!(module.upgrade(&mut store, &d).is_err());
+    Ok(())
+}
+
+#[wasmtime_test(strategies(only(Winch)))]
+fn recompiled_modules_can_be_serialized(config: &mut Config) -> Result<()> {
+    config.background_recompilation(true);
+    let engine = Engine::new(config)?;
+    let module = RecompilingModule::new(&engine, WAT)?;
+    let optimized = module.wait()?;
+
+    // Which compiler produced the code survives a round trip through
+    // serialization.
+    for (module, backend) in [
+        (module.baseline(), TrapBackend::Winch),
+        (&optimized, TrapBackend::Cranelift),
+    ] {
+        let bytes = module.serialize()?;
+        let module = unsafe { Module::deserialize(&engine, &bytes)? };
+        let mut store = Store::new(&engine, ());
+        let ty = TableType::new(RefType::FUNCREF, 3, None);
+        let table = Table::new(&mut store, ty, Ref::Func(None))?;
+        let host_add = Func::wrap(&mut store, |a: i32, b: i32| a + b);
+        let instance = Instance::new(&mut store, &module, &[table.into(), host_add.into()])?;
+        let trap = instance.get_typed_func::<(), ()>(&mut store, "trap")?;
+        let err = trap.call(&mut store, ()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<TrapDetails>().unwrap().backend(),
+            backend
+        );
+    }
+    Ok(())
+}
+
+#[wasmtime_test(strategies(only(Winch)))]
+fn requires_background_recompilation(config: &mut Config) -> Result<()> {
+    let engine = Engine::new(config)?;
+    let err = RecompilingModule::new(&engine, "(module)").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Engine was not configured with background recompilation"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn requires_winch() {
+    let mut config = Config::new();
+    config
+        .strategy(Strategy::Cranelift)
+        .background_recompilation(true);
+    let err = Engine::new(&config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "background recompilation requires the Winch compilation strategy"
+    );
+}
@@ -109,6 +109,68 @@ fn run_coredump_smoketest() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn coredump_explore_smoketest() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/coredump_smoketest.wat")?;
+    let coredump_file = NamedTempFile::new()?;
+    let coredump_arg = format!("-Dcoredump={}", coredump_file.path().display());
+    run_wasmtime(&[
+        "run",
+        "--invoke",
+        "a",
+        "-Ccache=n",
+        &coredump_arg,
+        wasm.path().to_str().unwrap(),
+    ])
+    .unwrap_err();
+
+    let output = run_wasmtime(&[
+        "coredump",
+        "explore",
+        "--module",
+        wasm.path().to_str().unwrap(),
+        coredump_file.path().to_str().unwrap(),
+    ])?;
+    let frames = output
+        .lines()
+        .skip_while(|line| !line.starts_with("thread"))
+        .skip(1)
+        .map(|line| line.split(" @ ").next().unwrap().trim())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        frames,
+        [
+            "0: <anonymous-module-0>!<wasm function 2>!c",
+            "1: <anonymous-module-0>!<wasm function 1>!b",
+            "2: <anonymous-module-0>!<wasm function 0>",
+        ],
+        "unexpected output:\n{output}"
+    );
+    Ok(())
+}
+
+#[test]
+fn coredump_explore_rejects_oversized_memory() -> Result<()> {
+    let coredump = wat::parse_str(
+        r#"(module
+            (@custom "core" "\00\04test")
+            (memory i64 0x1_0000_0000_0000))"#,
+    )?;
+    let mut coredump_file = NamedTempFile::new()?;
+    coredump_file.write_all(&coredump)?;
+    let output = get_wasmtime_command()?
+        .args(&[
+            "coredump",
+            "explore",
+            coredump_file.path().to_str().unwrap(),
+        ])
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("memory size overflows"), "{stderr}");
+    Ok(())
+}
+
 // Running simple wat
 #[test]
 fn run_wasmtime_simple_wat() -> Result<()> {
@@ -290,6 +352,144 @@ fn exit126_wasi_snapshot1() -> Result<()> {
     Ok(())
 }
 
+// Preopen directories with and without the `ro` option.
+#[test]
+fn dir_read_only() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/dir_create_file.wat")?;
+    let dir = tempfile::tempdir()?;
+    let host = dir.path().to_str().unwrap();
+
+    let output = run_wasmtime_for_output(
+        &[
+            "run",
+            "-Ccache=n",
+            &format!("--dir={host}::/:ro"),
+            wasm.path().to_str().unwrap(),
+        ],
+        None,
+    )?;
+    assert_eq!(output.status.code().unwrap(), 63); // errno `perm`
+    assert!(!dir.path().join("file.txt").exists());
+
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--dir={host}::/:rw"),
+        wasm.path().to_str().unwrap(),
+    ])?;
+    assert!(dir.path().join("file.txt").exists());
+    Ok(())
+}
+
+// Preopen a directory that doesn't exist yet with the `mkdir` option.
+#[test]
+fn dir_mkdir() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/dir_create_file.wat")?;
+    let dir = tempfile::tempdir()?;
+    let host = dir.path().join("a").join("b");
+    let host = host.to_str().unwrap();
+
+    let output = run_wasmtime_for_output(
+        &[
+            "run",
+            "-Ccache=n",
+            &format!("--dir={host}::/"),
+            wasm.path().to_str().unwrap(),
+        ],
+        None,
+    )?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to open directory"));
+
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--dir={host}::/:mkdir"),
+        wasm.path().to_str().unwrap(),
+    ])?;
+    assert!(dir.path().join("a/b/file.txt").exists());
+    Ok(())
+}
+
+// Preopen a read-only directory with the `create` option.
+#[test]
+fn dir_create() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/dir_create_file.wat")?;
+    let dir = tempfile::tempdir()?;
+    let host = dir.path().to_str().unwrap();
+
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--dir={host}::/:ro,nofollow,create"),
+        wasm.path().to_str().unwrap(),
+    ])?;
+    assert!(dir.path().join("file.txt").exists());
+
+    // Existing files still can't be written to.
+    let output = run_wasmtime_for_output(
+        &[
+            "run",
+            "-Ccache=n",
+            &format!("--dir={host}::/:ro,create"),
+            wasm.path().to_str().unwrap(),
+        ],
+        None,
+    )?;
+    assert_eq!(output.status.code().unwrap(), 63); // errno `perm`
+    Ok(())
+}
+
+// A trailing `:` followed by something other than options is part of the path.
+#[cfg(unix)]
+#[test]
+fn dir_path_with_colon() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/dir_create_file.wat")?;
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("a:b"))?;
+    let host = dir.path().join("a:b");
+    let host = host.to_str().unwrap();
+
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--dir={host}"),
+        wasm.path().to_str().unwrap(),
+    ])?;
+    assert!(dir.path().join("a:b/file.txt").exists());
+    Ok(())
+}
+
+// Open a symlink within directories preopened with and without `nofollow`.
+#[cfg(unix)]
+#[test]
+fn dir_nofollow() -> Result<()> {
+    let wasm = build_wasm("tests/all/cli_tests/dir_open_link.wat")?;
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("file.txt"), b"hello")?;
+    std::os::unix::fs::symlink("file.txt", dir.path().join("link"))?;
+    let host = dir.path().to_str().unwrap();
+
+    run_wasmtime(&[
+        "run",
+        "-Ccache=n",
+        &format!("--dir={host}::/"),
+        wasm.path().to_str().unwrap(),
+    ])?;
+
+    let output = run_wasmtime_for_output(
+        &[
+            "run",
+            "-Ccache=n",
+            &format!("--dir={host}::/:ro,nofollow"),
+            wasm.path().to_str().unwrap(),
+        ],
+        None,
+    )?;
+    assert_eq!(output.status.code().unwrap(), 32); // errno `loop`
+    Ok(())
+}
+
 // Run a minimal command program.
 #[test]
 fn minimal_command() -> Result<()> {
@@ -1086,6 +1286,7 @@ mod test_programs {
     use std::net::SocketAddr;
     use std::process::{Child, Command, Stdio};
     use std::thread::{self, JoinHandle};
+    use tempfile::NamedTempFile;
     use test_programs_artifacts::*;
     use tokio::net::TcpStream;
     use wasmtime::{Result, bail, error::Context as _, format_err};
@@ -1322,6 +1523,32 @@ mod test_programs {
         Ok(())
     }
 
+    #[test]
+    fn p2_cli_exit_panic_coredump_explore() -> Result<()> {
+        let coredump_file = NamedTempFile::new()?;
+        let coredump_arg = format!("-Dcoredump={}", coredump_file.path().display());
+        let output = get_wasmtime_command()?
+            .args(&[
+                "run",
+                "-Wcomponent-model",
+                &coredump_arg,
+                P2_CLI_EXIT_PANIC_COMPONENT,
+            ])
+            .output()?;
+        assert!(!output.status.success());
+
+        // The guest's DWARF is used to find the panicking source file.
+        let output = run_wasmtime(&[
+            "coredump",
+            "explore",
+            "--module",
+            P2_CLI_EXIT_PANIC_COMPONENT,
+            coredump_file.path().to_str().unwrap(),
+        ])?;
+        assert!(output.contains("p2_cli_exit_panic.rs:2"), "{output}");
+        Ok(())
+    }
+
     #[test]
     fn p2_cli_directory_list() -> Result<()> {
         let dir = tempfile::tempdir()?;
@@ -1497,6 +1724,8 @@ mod test_programs {
         stderr: Option<JoinHandle<io::Result<Vec<u8>>>>,
         addr: SocketAddr,
         shutdown_addr: SocketAddr,
+        health_addr: Option<SocketAddr>,
+        metrics_addr: Option<SocketAddr>,
     }
 
     impl WasmtimeServe {
@@ -1515,6 +1744,13 @@ mod test_programs {
         }
 
         fn spawn(cmd: &mut Command) -> Result<WasmtimeServe> {
+            let has_health_addr = cmd
+                .get_args()
+                .any(|arg| arg.to_str().is_some_and(|a| a.starts_with("--health-addr")));
+            let has_metrics_addr = cmd.get_args().any(|arg| {
+                arg.to_str()
+                    .is_some_and(|a| a.starts_with("--metrics-addr"))
+            });
             cmd.arg("--shutdown-addr=127.0.0.1:0");
             cmd.stdin(Stdio::null());
             cmd.stdout(Stdio::piped());
@@ -1525,7 +1761,8 @@ mod test_programs {
             // it's listening on. The first line is the shutdown line (with
             // `--shutdown-addr`) and the second is what `--addr` was bound to.
             // This is done to figure out what `:0` was bound to in the child
-            // process.
+            // process. With `--health-addr` the next line is what that was
+            // bound to, followed by the same for `--metrics-addr`.
             let mut line = String::new();
             let mut stderr = BufReader::new(child.stderr.take().unwrap());
             let mut read_addr_from_line = |prefix: &str| -> Result<SocketAddr> {
@@ -1548,17 +1785,31 @@ mod test_programs {
             };
             let shutdown_addr = read_addr_from_line("Listening for shutdown");
             let addr = read_addr_from_line("Serving HTTP on");
-            let (shutdown_addr, addr) = match (shutdown_addr, addr) {
-                (Ok(a), Ok(b)) => (a, b),
-                // If either failed kill the child and otherwise try to shepherd
-                // along any contextual information we have.
-                (Err(a), _) | (_, Err(a)) => {
-                    child.kill()?;
-                    child.wait()?;
-                    stderr.read_to_string(&mut line)?;
-                    return Err(a.context(line));
-                }
+            let health_addr = if has_health_addr {
+                read_addr_from_line("Serving health checks on").map(Some)
+            } else {
+                Ok(None)
             };
+            let metrics_addr = if has_metrics_addr {
+                read_addr_from_line("Serving metrics on").map(Some)
+            } else {
+                Ok(None)
+            };
+            let (shutdown_addr, addr, health_addr, metrics_addr) =
+                match (shutdown_addr, addr, health_addr, metrics_addr) {
+                    (Ok(a), Ok(b), Ok(c), Ok(d)) => (a, b, c, d),
+                    // If any failed kill the child and otherwise try to shepherd
+                    // along any contextual information we have.
+                    (Err(a), _, _, _)
+                    | (_, Err(a), _, _)
+                    | (_, _, Err(a), _)
+                    | (_, _, _, Err(a)) => {
+                        child.kill()?;
+                        child.wait()?;
+                        stderr.read_to_string(&mut line)?;
+                        return Err(a.context(line));
+                    }
+                };
             let mut stdout = child.stdout.take().unwrap();
             Ok(WasmtimeServe {
                 stdout: Some(thread::spawn(move || {
@@ -1574,6 +1825,8 @@ mod test_programs {
                 child: Some(child),
                 addr,
                 shutdown_addr,
+                health_addr,
+                metrics_addr,
             })
         }
 
@@ -1642,6 +1895,35 @@ mod test_programs {
             Ok(http::Response::from_parts(parts, body))
         }
 
+        /// Fetch the JSON status document from the `--health-addr` endpoint.
+        async fn health(&self) -> Result<serde_json::Value> {
+            let body = Self::get(self.health_addr.unwrap()).await?;
+            Ok(serde_json::from_str(&body)?)
+        }
+
+        /// Fetch the Prometheus metrics from the `--metrics-addr` endpoint.
+        async fn metrics(&self) -> Result<String> {
+            Self::get(self.metrics_addr.unwrap()).await
+        }
+
+        async fn get(addr: SocketAddr) -> Result<String> {
+            let tcp = TcpStream::connect(addr)
+                .await
+                .context("failed to connect")?;
+            let tcp = wasmtime_wasi_http::io::TokioIo::new(tcp);
+            let (mut send, conn) = hyper::client::conn::http1::handshake(tcp)
+                .await
+                .context("failed http handshake")?;
+            let conn_task = tokio::task::spawn(conn);
+            let req = hyper::Request::builder()
+                .uri("http://localhost/")
+                .body(String::new())?;
+            let response = Self::send_request_with(&mut send, req).await?;
+            drop(send);
+            conn_task.await??;
+            Ok(response.into_body())
+        }
+
         async fn start_requests(
             &self,
         ) -> Result<(
@@ -1730,6 +2012,52 @@ mod test_programs {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn p2_cli_serve_early_hints() -> Result<()> {
+        let server = WasmtimeServe::new(P2_CLI_SERVE_EARLY_HINTS_COMPONENT, |cmd| {
+            cmd.arg("-Scli");
+            cmd.arg("-Shttp-informational-responses");
+        })?;
+
+        // Send two requests on one connection to ensure that early hints for
+        // the second request don't get mixed up with the first response.
+        let (mut send, conn_task) = server.start_requests().await?;
+        for _ in 0..2 {
+            let hints = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+            let mut req = hyper::Request::builder()
+                .uri("http://localhost/")
+                .body(String::new())
+                .context("failed to make request")?;
+            hyper::ext::on_informational(&mut req, {
+                let hints = hints.clone();
+                move |res| {
+                    hints
+                        .lock()
+                        .unwrap()
+                        .push((res.status(), res.headers().get("link").cloned()))
+                }
+            });
+            let resp = WasmtimeServe::send_request_with(&mut send, req).await?;
+
+            assert!(resp.status().is_success());
+            assert_eq!(resp.body(), "Hello, WASI!");
+            assert_eq!(
+                *hints.lock().unwrap(),
+                [(
+                    hyper::StatusCode::from_u16(103)?,
+                    Some(HeaderValue::from_static(
+                        "</style.css>; rel=preload; as=style"
+                    )),
+                )]
+            );
+        }
+        drop(send);
+        conn_task.await??;
+
+        server.finish()?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn p2_cli_serve_header_replaces_request_header() -> Result<()> {
         let server = WasmtimeServe::new(P2_CLI_SERVE_ECHO_ENV_COMPONENT, |cmd| {
@@ -1994,6 +2322,141 @@ stderr [1] :: start a print 1234
         Ok(())
     }
 
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn p2_cli_serve_reload_on_sighup() -> Result<()> {
+        let server = WasmtimeServe::new(P2_CLI_SERVE_WITH_PRINT_COMPONENT, |cmd| {
+            cmd.arg("-Scli");
+            cmd.arg("--health-addr=127.0.0.1:0");
+        })?;
+        let request = || {
+            hyper::Request::builder()
+                .uri("http://localhost/")
+                .body(String::new())
+                .context("failed to make request")
+        };
+
+        let resp = server.send_request(request()?).await?;
+        assert!(resp.status().is_success());
+        let health = server.health().await?;
+        assert_eq!(health["generation"], 0);
+        assert!(health["last_reload"].is_null());
+
+        let pid = rustix::process::Pid::from_child(server.child.as_ref().unwrap());
+        rustix::process::kill_process(pid, rustix::process::Signal::HUP)?;
+
+        let health = loop {
+            let health = server.health().await?;
+            if !health["last_reload"].is_null() {
+                break health;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        };
+        assert_eq!(health["generation"], 1);
+        assert_eq!(health["last_reload"]["generation"], 1);
+        assert_eq!(health["last_reload"]["abandoned"], 0);
+        assert!(health["reload_error"].is_null());
+
+        let resp = server.send_request(request()?).await?;
+        assert!(resp.status().is_success());
+
+        let (_, err) = server.finish()?;
+        assert!(err.contains("Reloaded component (generation 1)"), "{err}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn p2_cli_serve_metrics() -> Result<()> {
+        let server = WasmtimeServe::new(P2_CLI_SERVE_WITH_PRINT_COMPONENT, |cmd| {
+            cmd.arg("-Scli");
+            cmd.arg("--metrics-addr=127.0.0.1:0");
+        })?;
+
+        for _ in 0..2 {
+            let resp = server
+                .send_request(
+                    hyper::Request::builder()
+                        .uri("http://localhost/")
+                        .body(String::new())
+                        .context("failed to make request")?,
+                )
+                .await?;
+            assert!(resp.status().is_success());
+        }
+
+        let metrics = server.metrics().await?;
+        assert!(
+            metrics.contains("wasmtime_serve_request_duration_seconds_count 2\n"),
+            "{metrics}"
+        );
+        assert!(
+            metrics.contains("wasmtime_component_instantiations_total 2\n"),
+            "{metrics}"
+        );
+        assert!(
+            metrics.contains("# TYPE wasmtime_traps_total counter\n"),
+            "{metrics}"
+        );
+
+        server.finish()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn p2_cli_serve_routes() -> Result<()> {
+        let mut routes = NamedTempFile::new()?;
+        writeln!(
+            routes,
+            "# Each line maps a route to the component serving it."
+        )?;
+        writeln!(routes, "/env = {P2_CLI_SERVE_ECHO_ENV_COMPONENT}")?;
+        writeln!(routes, "example.com = {P2_CLI_SERVE_WITH_PRINT_COMPONENT}")?;
+        let server = WasmtimeServe::spawn(
+            super::get_wasmtime_command()?
+                .arg("serve")
+                .arg("--addr=127.0.0.1:0")
+                .arg("--health-addr=127.0.0.1:0")
+                .arg("--env=FOO=bar")
+                .arg("-Scli")
+                .arg("--routes")
+                .arg(routes.path()),
+        )?;
+        let request = |host: &str, path: &str| {
+            hyper::Request::builder()
+                .uri(format!("http://{host}{path}"))
+                .header("host", host)
+                .header("env", "FOO")
+                .body(String::new())
+                .context("failed to make request")
+        };
+
+        let resp = server
+            .send_request(request("localhost", "/env/foo")?)
+            .await?;
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get("env").unwrap(), "bar");
+
+        // Routes limited to a host take precedence over path prefixes.
+        let resp = server
+            .send_request(request("example.com:8080", "/env")?)
+            .await?;
+        assert!(resp.status().is_success());
+        assert!(resp.headers().get("env").is_none());
+
+        let resp = server.send_request(request("localhost", "/other")?).await?;
+        assert_eq!(resp.status(), hyper::StatusCode::NOT_FOUND);
+
+        let health = server.health().await?;
+        let routes = health["routes"].as_array().unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0]["route"], "/env");
+        assert_eq!(routes[1]["route"], "example.com/");
+        assert_eq!(routes[1]["generation"], 0);
+
+        server.finish()?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn p2_cli_serve_with_print_no_prefix() -> Result<()> {
         let server = WasmtimeServe::new(P2_CLI_SERVE_WITH_PRINT_COMPONENT, |cmd| {
@@ -3590,3 +4053,120 @@ fn hot_blocks_fib() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn bench_core_and_compare() -> Result<()> {
+    let stdout = run_wasmtime(&[
+        "bench",
+        "-Ccache=n",
+        "--invoke",
+        "simple",
+        "--warmup",
+        "1",
+        "--iterations",
+        "5",
+        "--compare",
+        "-Oopt-level=0",
+        "tests/all/cli_tests/simple.wat",
+        "4",
+    ])?;
+    assert!(stdout.contains("1 warmup and 5 measured calls"), "{stdout}");
+    assert!(stdout.contains("baseline:"), "{stdout}");
+    assert!(stdout.contains("comparison (-Oopt-level=0):"), "{stdout}");
+    assert!(stdout.contains("(95% CI)"), "{stdout}");
+    // Whether the difference is significant varies between runs.
+    let verdict = stdout.lines().last().unwrap();
+    assert!(verdict.contains("baseline"), "{stdout}");
+    assert!(verdict.contains("(95% CI: "), "{stdout}");
+
+    // The benchmarked function is required, and its arguments are checked.
+    let output = run_wasmtime_for_output(&["bench", "tests/all/cli_tests/simple.wat"], None)?;
+    assert!(!output.status.success());
+    let output = run_wasmtime_for_output(
+        &[
+            "bench",
+            "--invoke",
+            "simple",
+            "tests/all/cli_tests/simple.wat",
+        ],
+        None,
+    )?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("not enough arguments"),
+        "{output:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn bench_component() -> Result<()> {
+    let stdout = run_wasmtime(&[
+        "bench",
+        "-Ccache=n",
+        "--invoke",
+        "run()",
+        "--iterations",
+        "3",
+        "tests/all/cli_tests/component-basic.wat",
+    ])?;
+    assert!(stdout.contains("benchmarking `run()`"), "{stdout}");
+    assert!(stdout.contains("median:"), "{stdout}");
+    assert!(!stdout.contains("comparison"), "{stdout}");
+    Ok(())
+}
+
+#[test]
+fn repl_component() -> Result<()> {
+    let mut input = NamedTempFile::new()?;
+    writeln!(input, "add(1, 2)")?;
+    writeln!(input, "my:pkg/counter.count@0.1.0()")?;
+    writeln!(input, "count()")?;
+    writeln!(input, "boom()")?;
+    writeln!(input, "count()")?;
+    writeln!(input, "add(1)")?;
+    writeln!(input, ":bogus")?;
+    writeln!(input, ":quit")?;
+    writeln!(input, "add(3, 4)")?;
+    input.flush()?;
+
+    let output = run_wasmtime_for_output(
+        &[
+            "repl",
+            "-Ccache=n",
+            "tests/all/cli_tests/component-repl.wat",
+        ],
+        Some(input.path()),
+    )?;
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains("  add: func(u32, u32) -> u32\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("  my:pkg/counter.count@0.1.0: func() -> u32\n"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("> 3\n> 1\n> 2\n"), "{stdout}");
+    assert!(
+        stdout.contains("wasm `unreachable` instruction executed"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("the component trapped and was instantiated again\n> 1\n"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("missing required param"), "{stdout}");
+    assert!(stdout.contains("unknown command `:bogus`"), "{stdout}");
+    assert!(!stdout.contains("> 7"), "{stdout}");
+
+    // Only components are supported.
+    let output = run_wasmtime_for_output(&["repl", "tests/all/cli_tests/simple.wat"], None)?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("only supports components"),
+        "{output:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,23 @@
+(component
+  (core module $m
+    (global $count (mut i32) (i32.const 0))
+    (func (export "add") (param i32 i32) (result i32)
+      (i32.add (local.get 0) (local.get 1)))
+    (func (export "count") (result i32)
+      (global.set $count (i32.add (global.get $count) (i32.const 1)))
+      (global.get $count))
+    (func (export "boom")
+      unreachable)
+  )
+  (core instance $i (instantiate $m))
+  (func (export "add") (param "a" u32) (param "b" u32) (result u32)
+    (canon lift (core func $i "add")))
+  (func $count (result u32)
+    (canon lift (core func $i "count")))
+  (func $boom
+    (canon lift (core func $i "boom")))
+
+  (instance (export (interface "my:pkg/counter@0.1.0"))
+    (export "count" (func $count))
+    (export "boom" (func $boom)))
+)
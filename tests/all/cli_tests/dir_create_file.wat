@@ -0,0 +1,23 @@
+;; Creates `file.txt` in the first preopened directory and exits with the
+;; resulting errno.
+(module
+  (import "wasi_snapshot_preview1" "path_open"
+    (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "proc_exit"
+    (func $proc_exit (param i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 16) "file.txt")
+  (func (export "_start")
+    (call $proc_exit
+      (call $path_open
+        (i32.const 3)   ;; fd
+        (i32.const 0)   ;; dirflags
+        (i32.const 16)  ;; path
+        (i32.const 8)   ;; path_len
+        (i32.const 1)   ;; oflags: creat
+        (i64.const 64)  ;; fs_rights_base: fd_write
+        (i64.const 0)   ;; fs_rights_inheriting
+        (i32.const 0)   ;; fdflags
+        (i32.const 0))) ;; opened fd
+  )
+)
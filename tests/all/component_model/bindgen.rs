@@ -198,6 +198,102 @@ mod one_import {
     }
 }
 
+mod extension_import {
+    use super::*;
+    use wasmtime::component::{HasExtension, expect_extension, get_extension};
+    use wasmtime::{Extensions, HasExtensions};
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo;
+
+            world one-import {
+                import foo: interface {
+                    foo: func();
+                }
+
+                export bar: func();
+            }
+        ",
+    });
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import "foo" (instance $i
+                        (export "foo" (func))
+                    ))
+                    (core module $m
+                        (import "" "" (func))
+                        (export "" (func 0))
+                    )
+                    (core func $f (canon lower (func $i "foo")))
+                    (core instance $i (instantiate $m
+                        (with "" (instance (export "" (func $f))))
+                    ))
+
+                    (func $f (export "bar") (canon lift (core func $i "")))
+                )
+            "#,
+        )?;
+
+        #[derive(Default)]
+        struct MyImports {
+            hits: u32,
+        }
+
+        impl foo::Host for MyImports {
+            fn foo(&mut self) {
+                self.hits += 1;
+            }
+        }
+
+        struct MyState {
+            extensions: Extensions,
+        }
+
+        impl HasExtensions for MyState {
+            fn extensions(&self) -> &Extensions {
+                &self.extensions
+            }
+
+            fn extensions_mut(&mut self) -> &mut Extensions {
+                &mut self.extensions
+            }
+        }
+
+        let mut linker = Linker::new(&engine);
+        foo::add_to_linker::<_, HasExtension<MyImports>>(&mut linker, expect_extension)?;
+        let mut store = Store::new(
+            &engine,
+            MyState {
+                extensions: Extensions::new(),
+            },
+        );
+        store.extensions_mut().insert(MyImports::default());
+        let one_import = OneImport::instantiate(&mut store, &component, &linker)?;
+        one_import.call_bar(&mut store)?;
+        one_import.call_bar(&mut store)?;
+        assert_eq!(store.extensions().get::<MyImports>().unwrap().hits, 2);
+        let imports = get_extension::<_, MyImports>(store.data_mut());
+        assert_eq!(imports.map(|i| i.hits), Some(2));
+
+        // Calling the import without the extension in place panics.
+        store.extensions_mut().remove::<MyImports>();
+        assert!(get_extension::<_, MyImports>(store.data_mut()).is_none());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            one_import.call_bar(&mut store)
+        }));
+        assert!(result.is_err());
+        Ok(())
+    }
+}
+
 mod one_import_concurrent {
     use super::*;
     use wasmtime::component::{Accessor, HasData};
@@ -488,6 +584,241 @@ mod resources_at_interface_level {
     }
 }
 
+mod blocking_wrappers {
+    use super::*;
+    use wasmtime::component::{HasSelf, Resource};
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo;
+
+            interface def {
+                resource x {
+                    constructor();
+                }
+            }
+
+            interface user {
+                use def.{x};
+
+                y: func(x: x);
+            }
+
+            world resources {
+                export user;
+            }
+        ",
+        imports: { default: async },
+        blocking_wrappers: block_on,
+    });
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import (interface "foo:foo/def") (instance $i
+                        (export "x" (type $x (sub resource)))
+                        (export "[constructor]x" (func (result (own $x))))
+                    ))
+                    (alias export $i "x" (type $x))
+                    (core func $dtor (canon resource.drop $x))
+                    (core func $ctor (canon lower (func $i "[constructor]x")))
+
+                    (core module $m
+                        (import "" "ctor" (func $ctor (result i32)))
+                        (import "" "dtor" (func $dtor (param i32)))
+
+                        (func (export "x") (param i32)
+                            (call $dtor (local.get 0))
+                            (call $dtor (call $ctor))
+                        )
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "ctor" (func $ctor))
+                            (export "dtor" (func $dtor))
+                        ))
+                    ))
+                    (func $y (param "x" (own $x))
+                        (canon lift (core func $i "x")))
+
+                    (instance (export (interface "foo:foo/user"))
+                        (export "y" (func $y))
+                    )
+                )
+            "#,
+        )?;
+
+        #[derive(Default)]
+        struct MyImports {
+            ctor_hit: bool,
+            drops: usize,
+        }
+
+        use foo::foo::def::X;
+
+        impl foo::foo::def::HostX for MyImports {
+            async fn new(&mut self) -> Resource<X> {
+                tokio::task::yield_now().await;
+                self.ctor_hit = true;
+                Resource::new_own(80)
+            }
+
+            async fn drop(&mut self, val: Resource<X>) -> Result<()> {
+                tokio::task::yield_now().await;
+                match self.drops {
+                    0 => assert_eq!(val.rep(), 40),
+                    1 => assert_eq!(val.rep(), 80),
+                    _ => unreachable!(),
+                }
+                self.drops += 1;
+                Ok(())
+            }
+        }
+
+        impl foo::foo::def::Host for MyImports {}
+
+        // The `async` host implementation is usable with a store that doesn't
+        // support async through the blocking wrappers.
+        let mut linker = Linker::new(&engine);
+        foo::foo::def::add_to_linker_blocking::<_, HasSelf<_>>(&mut linker, |f| f)?;
+        let mut store = Store::new(&engine, MyImports::default());
+        let one_import = Resources::instantiate(&mut store, &component, &linker)?;
+        one_import
+            .foo_foo_user()
+            .call_y(&mut store, Resource::new_own(40))?;
+        assert!(store.data().ctor_hit);
+        assert_eq!(store.data().drops, 2);
+
+        // The blocking traits can also be called directly.
+        let mut imports = MyImports::default();
+        let x = foo::foo::def::HostXBlocking::new(&mut imports);
+        foo::foo::def::HostXBlocking::drop(&mut imports, Resource::new_own(40))?;
+        foo::foo::def::HostXBlocking::drop(&mut imports, x)?;
+        assert_eq!(imports.drops, 2);
+        Ok(())
+    }
+}
+
+mod mocks {
+    use super::*;
+    use wasmtime::component::{HasSelf, Resource};
+
+    wasmtime::component::bindgen!({
+        inline: "
+            package foo:foo;
+
+            interface def {
+                resource x {
+                    constructor();
+                }
+
+                add: func(a: u32, b: u32) -> u32;
+            }
+
+            interface user {
+                use def.{x};
+
+                y: func(x: x) -> u32;
+            }
+
+            world mocks {
+                export user;
+            }
+        ",
+        mocks: true,
+    });
+
+    #[test]
+    fn run() -> Result<()> {
+        let engine = engine();
+
+        let component = Component::new(
+            &engine,
+            r#"
+                (component
+                    (import (interface "foo:foo/def") (instance $i
+                        (export "x" (type $x (sub resource)))
+                        (export "[constructor]x" (func (result (own $x))))
+                        (export "add" (func (param "a" u32) (param "b" u32) (result u32)))
+                    ))
+                    (alias export $i "x" (type $x))
+                    (core func $dtor (canon resource.drop $x))
+                    (core func $ctor (canon lower (func $i "[constructor]x")))
+                    (core func $add (canon lower (func $i "add")))
+
+                    (core module $m
+                        (import "" "ctor" (func $ctor (result i32)))
+                        (import "" "dtor" (func $dtor (param i32)))
+                        (import "" "add" (func $add (param i32 i32) (result i32)))
+
+                        (func (export "x") (param i32) (result i32)
+                            (call $dtor (local.get 0))
+                            (call $dtor (call $ctor))
+                            (i32.add
+                                (call $add (i32.const 1) (i32.const 2))
+                                (call $add (i32.const 3) (i32.const 4)))
+                        )
+                    )
+                    (core instance $i (instantiate $m
+                        (with "" (instance
+                            (export "ctor" (func $ctor))
+                            (export "dtor" (func $dtor))
+                            (export "add" (func $add))
+                        ))
+                    ))
+                    (func $y (param "x" (own $x)) (result u32)
+                        (canon lift (core func $i "x")))
+
+                    (instance (export (interface "foo:foo/user"))
+                        (export "y" (func $y))
+                    )
+                )
+            "#,
+        )?;
+
+        let mut mock = foo::foo::def::Mock::default();
+        mock.x_new.returns(Resource::new_own(80));
+        mock.add.returns(10).returns_with(|(a, b)| a + b);
+
+        let mut linker = Linker::new(&engine);
+        Mocks::add_to_linker::<_, HasSelf<_>>(&mut linker, |f| f)?;
+        let mut store = Store::new(&engine, mock);
+        let mocks = Mocks::instantiate(&mut store, &component, &linker)?;
+        let ret = mocks
+            .foo_foo_user()
+            .call_y(&mut store, Resource::new_own(40))?;
+        assert_eq!(ret, 17);
+
+        let mock = store.data_mut();
+        assert_eq!(mock.x_new.call_count(), 1);
+        assert_eq!(mock.add.calls(), [(1, 2), (3, 4)]);
+        let drops = mock.x_drop.take_calls();
+        assert_eq!(drops.len(), 2);
+        assert_eq!(drops[0].0.rep(), 40);
+        assert_eq!(drops[1].0.rep(), 80);
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "no result configured for mocked function `add`")]
+    fn unconfigured_result_panics() {
+        let mut mock = foo::foo::def::Mock::default();
+        foo::foo::def::Host::add(&mut mock, 1, 2);
+    }
+}
+
 mod async_config {
     use super::*;
 
@@ -797,6 +1128,30 @@ mod exported_resources {
             mem::take(&mut store.data_mut().hostcalls),
             [Hostcall::DropAX(0)],
         );
+
+        // the `Owned*` wrapper drops the resource once it goes out of scope
+        let b_x = b.x().call_constructor(&mut store, Resource::new_own(6))?;
+        let mut b_x = b.x().owned(&mut store, b_x);
+        b_x.foo()?;
+        assert_eq!(
+            mem::take(&mut b_x.store().data_mut().hostcalls),
+            [Hostcall::DropAX(6), Hostcall::NewA]
+        );
+        drop(b_x);
+        assert_eq!(
+            mem::take(&mut store.data_mut().hostcalls),
+            [Hostcall::DropAX(1)],
+        );
+
+        // ... unless it's released from the wrapper
+        let b_x = b.x().call_constructor(&mut store, Resource::new_own(7))?;
+        let b_x = b.x().owned(&mut store, b_x).into_resource();
+        mem::take(&mut store.data_mut().hostcalls);
+        b.x().owned(&mut store, b_x).resource_drop()?;
+        assert_eq!(
+            mem::take(&mut store.data_mut().hostcalls),
+            [Hostcall::DropAX(2)],
+        );
         Ok(())
     }
 }
@@ -3,7 +3,7 @@
 use super::{Param, Type, make_echo_component, make_echo_component_with_params};
 use wasmtime::Result;
 use wasmtime::component::types::{self, Case, ComponentItem, Field};
-use wasmtime::component::{Component, Linker, ResourceType, Val};
+use wasmtime::component::{Component, Linker, PackedList, ResourceType, Val};
 use wasmtime::{Module, Store};
 use wasmtime_component_util::REALLOC_AND_FREE;
 
@@ -136,6 +136,59 @@ fn lists() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn packed_lists() -> Result<()> {
+    let engine = super::engine();
+    let mut store = Store::new(&engine, ());
+
+    let component = Component::new(&engine, make_echo_component("(list u32)", 8))?;
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let func = instance.get_func(&mut store, "echo").unwrap();
+    let mut output = [Val::Bool(false)];
+
+    // Packed lists are always accepted, but lifted as `Val::List` by default.
+    let input = Val::PackedList(PackedList::from(vec![32343u32, 79023439, 2084037802]));
+    func.call(&mut store, &[input.clone()], &mut output)?;
+    assert!(matches!(&output[0], Val::List(_)));
+    assert_eq!(input, output[0]);
+
+    store.set_packed_lists(true);
+    let list = Val::List(vec![Val::U32(1), Val::U32(2), Val::U32(3)]);
+    func.call(&mut store, &[list.clone()], &mut output)?;
+    match &output[0] {
+        Val::PackedList(packed) => assert_eq!(packed.as_slice::<u32>(), Some(&[1, 2, 3][..])),
+        other => panic!("expected a packed list, got {other:?}"),
+    }
+    assert_eq!(list, output[0]);
+
+    let large = (0..100_000).collect::<Vec<u32>>();
+    func.call(
+        &mut store,
+        &[Val::PackedList(large.clone().into())],
+        &mut output,
+    )?;
+    let Val::PackedList(packed) = &output[0] else {
+        panic!("expected a packed list");
+    };
+    assert_eq!(packed.as_slice::<u32>(), Some(&large[..]));
+
+    // Sad path: type mismatch
+
+    let err = Val::PackedList(PackedList::from(vec![1u64, 2, 3]));
+    let err = func.call(&mut store, &[err], &mut output).unwrap_err();
+    assert!(err.to_string().contains("type mismatch"), "{err}");
+
+    // Lists of types which can't be packed are still lifted as `Val::List`.
+    let component = Component::new(&engine, make_echo_component("(list bool)", 8))?;
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let func = instance.get_func(&mut store, "echo").unwrap();
+    let input = Val::List(vec![Val::Bool(true), Val::Bool(false)]);
+    func.call(&mut store, &[input.clone()], &mut output)?;
+    assert_eq!(input, output[0]);
+
+    Ok(())
+}
+
 #[test]
 fn maps() -> Result<()> {
     let engine = super::map_engine();
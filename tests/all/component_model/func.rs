@@ -2402,6 +2402,211 @@ impl<'a> SliceExt<'a> for &'a [u8] {
     }
 }
 
+#[test]
+fn realloc_failure_diagnostics() -> Result<()> {
+    let component = r#"(component
+        (core module $m
+            (memory (export "memory") 1)
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                (local $page i32)
+                (local.set $page (memory.grow (i32.const 1)))
+                (if (i32.eq (local.get $page) (i32.const -1))
+                    (then unreachable))
+                (i32.mul (local.get $page) (i32.const 65536)))
+            (func (export "take-string") (param i32 i32))
+        )
+        (core instance $i (instantiate $m))
+
+        (func (export "take-string") (param "s" string)
+            (canon lift
+                (core func $i "take-string")
+                (memory $i "memory")
+                (realloc (func $i "realloc"))
+            )
+        )
+    )"#;
+
+    let engine = super::engine();
+    let component = Component::new(&engine, component)?;
+    let mut store = Store::new(
+        &engine,
+        wasmtime::StoreLimitsBuilder::new()
+            .memory_size(1 << 16)
+            .build(),
+    );
+    store.limiter(|limits| limits);
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+
+    let err = instance
+        .get_typed_func::<(&str,), ()>(&mut store, "take-string")?
+        .call(&mut store, ("hello",))
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Trap>(),
+        Some(&Trap::UnreachableCodeReached)
+    );
+    let failure = err.downcast_ref::<ReallocFailure>().unwrap();
+    assert_eq!(failure.kind, ReallocFailureKind::Trapped);
+    assert_eq!(failure.align, 1);
+    assert_eq!(failure.new_size, 5);
+    assert_eq!(failure.returned_ptr, None);
+    assert_eq!(failure.memory_size, 1 << 16);
+    assert_eq!(failure.memory_grow_failures, 1);
+    assert_eq!(failure.interface, None);
+    assert_eq!(failure.function.as_deref(), Some("take-string"));
+    assert!(
+        failure.to_string().contains("in `take-string`"),
+        "{failure}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn realloc_failure_names_interface_functions() -> Result<()> {
+    let component = r#"(component
+        (import "host:test/strings" (instance $host
+            (export "get" (func (result string)))
+        ))
+        (core module $libc
+            (memory (export "memory") 1)
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                unreachable)
+        )
+        (core instance $libc (instantiate $libc))
+        (core func $get (canon lower (func $host "get")
+            (memory $libc "memory")
+            (realloc (func $libc "realloc"))
+        ))
+        (core module $m
+            (import "host" "get" (func $get (param i32)))
+            (func (export "take-string") (param i32 i32))
+            (func (export "call-get") (call $get (i32.const 8)))
+        )
+        (core instance $i (instantiate $m
+            (with "host" (instance (export "get" (func $get))))
+        ))
+
+        (func $take-string (param "s" string)
+            (canon lift
+                (core func $i "take-string")
+                (memory $libc "memory")
+                (realloc (func $libc "realloc"))
+            )
+        )
+        (func $call-get (canon lift (core func $i "call-get")))
+        (instance (export "guest:test/api")
+            (export "take-string" (func $take-string))
+            (export "call-get" (func $call-get))
+        )
+    )"#;
+
+    let engine = super::engine();
+    let component = Component::new(&engine, component)?;
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    linker
+        .instance("host:test/strings")?
+        .func_wrap("get", |_, ()| Ok(("hello".to_string(),)))?;
+    let instance = linker.instantiate(&mut store, &component)?;
+    let api = instance
+        .get_export_index(&mut store, None, "guest:test/api")
+        .unwrap();
+
+    let take_string = instance
+        .get_export_index(&mut store, Some(&api), "take-string")
+        .unwrap();
+    let err = instance
+        .get_typed_func::<(&str,), ()>(&mut store, &take_string)?
+        .call(&mut store, ("hello",))
+        .unwrap_err();
+    let failure = err.downcast_ref::<ReallocFailure>().unwrap();
+    assert_eq!(failure.interface.as_deref(), Some("guest:test/api"));
+    assert_eq!(failure.function.as_deref(), Some("take-string"));
+    assert!(
+        failure
+            .to_string()
+            .contains("in `guest:test/api#take-string`"),
+        "{failure}"
+    );
+
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &component)?;
+    let call_get = instance
+        .get_export_index(&mut store, Some(&api), "call-get")
+        .unwrap();
+    let err = instance
+        .get_typed_func::<(), ()>(&mut store, &call_get)?
+        .call(&mut store, ())
+        .unwrap_err();
+    let failure = err.downcast_ref::<ReallocFailure>().unwrap();
+    assert_eq!(failure.kind, ReallocFailureKind::Trapped);
+    assert_eq!(failure.interface.as_deref(), Some("host:test/strings"));
+    assert_eq!(failure.function.as_deref(), Some("get"));
+
+    Ok(())
+}
+
+#[test]
+fn realloc_failure_names_capture_less_imports() -> Result<()> {
+    let component = r#"(component
+        (import "host:test/strings" (instance $host
+            (export "first" (func (result string)))
+            (export "second" (func (result string)))
+        ))
+        (core module $libc
+            (memory (export "memory") 1)
+            (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                unreachable)
+        )
+        (core instance $libc (instantiate $libc))
+        (core func $first (canon lower (func $host "first")
+            (memory $libc "memory")
+            (realloc (func $libc "realloc"))
+        ))
+        (core func $second (canon lower (func $host "second")
+            (memory $libc "memory")
+            (realloc (func $libc "realloc"))
+        ))
+        (core module $m
+            (import "host" "first" (func $first (param i32)))
+            (import "host" "second" (func $second (param i32)))
+            (func (export "call-first") (call $first (i32.const 8)))
+            (func (export "call-second") (call $second (i32.const 8)))
+        )
+        (core instance $i (instantiate $m
+            (with "host" (instance
+                (export "first" (func $first))
+                (export "second" (func $second))
+            ))
+        ))
+
+        (func (export "call-first") (canon lift (core func $i "call-first")))
+        (func (export "call-second") (canon lift (core func $i "call-second")))
+    )"#;
+
+    let engine = super::engine();
+    let component = Component::new(&engine, component)?;
+    let mut linker = Linker::new(&engine);
+    let mut host = linker.instance("host:test/strings")?;
+    host.func_wrap("first", |_, ()| Ok(("first".to_string(),)))?;
+    host.func_wrap("second", |_, ()| Ok(("second".to_string(),)))?;
+
+    for name in ["first", "second"] {
+        let mut store = Store::new(&engine, ());
+        let instance = linker.instantiate(&mut store, &component)?;
+        let err = instance
+            .get_typed_func::<(), ()>(&mut store, &format!("call-{name}"))?
+            .call(&mut store, ())
+            .unwrap_err();
+        let failure = err.downcast_ref::<ReallocFailure>().unwrap();
+        assert_eq!(failure.interface.as_deref(), Some("host:test/strings"));
+        assert_eq!(failure.function.as_deref(), Some(name));
+    }
+
+    Ok(())
+}
+
 #[test]
 fn invalid_alignment() -> Result<()> {
     let component = format!(
@@ -2475,6 +2680,10 @@ fn invalid_alignment() -> Result<()> {
         "{}",
         err
     );
+    let failure = err.downcast_ref::<ReallocFailure>().unwrap();
+    assert_eq!(failure.kind, ReallocFailureKind::Misaligned);
+    assert_eq!(failure.returned_ptr, Some(1));
+    assert_eq!(failure.memory_grow_failures, 0);
 
     let err = instance(&mut store)?
         .get_typed_func::<(), (WasmStr,)>(&mut store, "string-ret")?
@@ -228,3 +228,87 @@ fn open_instance_twice() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn alias_package_version() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+
+    let ty = ResourceType::host::<u32>();
+    let mut i = linker.instance("a:b/c@0.2.3")?;
+    i.resource("r", ty, |_, _| Ok(()))?;
+    i.func_wrap("f", |_, ()| Ok((1u32,)))?;
+    i.func_wrap("g", |_, ()| Ok(()))?;
+    linker
+        .instance("a:b/d@0.2.3")?
+        .func_wrap("h", |_, ()| Ok(()))?;
+    linker
+        .instance("a:x/y@0.2.3")?
+        .func_wrap("h", |_, ()| Ok(()))?;
+    linker.alias_package_version("a:b", "0.2.3", "0.2.0-rc.1")?;
+
+    // An older prerelease which lacks `g` can use the aliased definitions, and
+    // shares resource types with the version it's aliased from.
+    let component = Component::new(
+        &engine,
+        r#"(component
+            (import "a:b/c@0.2.0-rc.1" (instance $old
+                (export "r" (type (sub resource)))
+                (export "f" (func (result u32)))
+            ))
+            (import "a:b/d@0.2.0-rc.1" (instance))
+            (import "a:b/c@0.2.3" (instance $new
+                (export "r" (type (sub resource)))
+            ))
+            (alias export $old "r" (type $r1))
+            (alias export $new "r" (type $r2))
+            (export "r1" (type $r1))
+            (export "r2" (type $r2))
+        )"#,
+    )?;
+    assert_eq!(
+        component.imported_packages().collect::<Vec<_>>(),
+        [("a:b", Some("0.2.0-rc.1")), ("a:b", Some("0.2.3"))],
+    );
+    let mut store = Store::new(&engine, ());
+    let i = linker.instantiate(&mut store, &component)?;
+    assert_eq!(i.get_resource(&mut store, "r1"), Some(ty));
+    assert_eq!(i.get_resource(&mut store, "r2"), Some(ty));
+
+    // Other packages aren't aliased.
+    let component = Component::new(
+        &engine,
+        r#"(component
+            (import "a:x/y@0.2.0-rc.1" (instance
+                (export "h" (func))
+            ))
+        )"#,
+    )?;
+    assert!(linker.instantiate(&mut store, &component).is_err());
+
+    // Incompatible signatures are still rejected.
+    let component = Component::new(
+        &engine,
+        r#"(component
+            (import "a:b/c@0.2.0-rc.1" (instance
+                (export "f" (func (result string)))
+            ))
+        )"#,
+    )?;
+    assert!(linker.instantiate(&mut store, &component).is_err());
+
+    // Aliasing a version which isn't defined, or aliasing onto names which
+    // already exist, fails.
+    assert!(
+        linker
+            .alias_package_version("a:b", "0.1.0", "0.1.1")
+            .is_err()
+    );
+    assert!(
+        linker
+            .alias_package_version("a:b", "0.2.3", "0.2.0-rc.1")
+            .is_err()
+    );
+
+    Ok(())
+}
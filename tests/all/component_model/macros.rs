@@ -691,3 +691,116 @@ fn flags() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn inferred_style_and_rename_all() -> Result<()> {
+    #[derive(ComponentType, Lift, Lower, PartialEq, Eq, Debug, Copy, Clone)]
+    #[component(rename_all = "kebab-case")]
+    struct Status {
+        http_status: u32,
+        #[component(name = "retries")]
+        retry_count: u32,
+    }
+
+    #[derive(ComponentType, Lift, Lower, PartialEq, Eq, Debug, Copy, Clone)]
+    #[component(rename_all = "kebab-case")]
+    enum Reply {
+        NotFound,
+        TooLarge(u32),
+        HTTPLookup(Option<Result<u32, u32>>),
+    }
+
+    let engine = super::engine();
+    let mut store = Store::new(&engine, ());
+
+    let component = Component::new(
+        &engine,
+        make_echo_component(
+            r#"(record (field "http-status" u32) (field "retries" u32))"#,
+            8,
+        ),
+    )?;
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let input = Status {
+        http_status: 404,
+        retry_count: 3,
+    };
+    let output = instance
+        .get_typed_func::<(Status,), (Status,)>(&mut store, "echo")?
+        .call(&mut store, (input,))?;
+    assert_eq!((input,), output);
+
+    let component = Component::new(
+        &engine,
+        make_echo_component(
+            r#"
+                (variant
+                    (case "not-found")
+                    (case "too-large" u32)
+                    (case "http-lookup" (option (result u32 (error u32)))))
+            "#,
+            16,
+        ),
+    )?;
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let func = instance.get_typed_func::<(Reply,), (Reply,)>(&mut store, "echo")?;
+    for &input in &[
+        Reply::NotFound,
+        Reply::TooLarge(1 << 20),
+        Reply::HTTPLookup(None),
+        Reply::HTTPLookup(Some(Ok(200))),
+        Reply::HTTPLookup(Some(Err(500))),
+    ] {
+        let output = func.call(&mut store, (input,))?;
+        assert_eq!((input,), output);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn borrowed_lower() -> Result<()> {
+    #[derive(ComponentType, Lower)]
+    struct Request<'a> {
+        name: &'a str,
+        data: &'a [u8],
+    }
+
+    #[derive(ComponentType, Lift, PartialEq, Eq, Debug)]
+    struct Response {
+        name: String,
+        data: Vec<u8>,
+    }
+
+    let engine = super::engine();
+    let mut store = Store::new(&engine, ());
+
+    let component = Component::new(
+        &engine,
+        make_echo_component(
+            r#"(record (field "name" string) (field "data" (list u8)))"#,
+            16,
+        ),
+    )?;
+    let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+    let func = instance.get_typed_func::<(Request<'_>,), (Response,)>(&mut store, "echo")?;
+
+    let name = String::from("hello");
+    let data = [1, 2, 3, 4];
+    let output = func.call(
+        &mut store,
+        (Request {
+            name: &name,
+            data: &data,
+        },),
+    )?;
+    assert_eq!(
+        output,
+        (Response {
+            name: "hello".to_string(),
+            data: data.to_vec(),
+        },)
+    );
+
+    Ok(())
+}
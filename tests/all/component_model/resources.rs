@@ -1803,3 +1803,154 @@ async fn drop_after_sync_lowered_async_host_function() -> Result<()> {
     call.call_async(&mut store, (r,)).await?;
     Ok(())
 }
+
+#[test]
+fn broker_transfer_between_stores() -> Result<()> {
+    let engine = super::engine();
+    let c = Component::new(
+        &engine,
+        r#"
+            (component
+                (import "t" (type $t (sub resource)))
+
+                (core func $drop (canon resource.drop $t))
+
+                (core module $m
+                    (import "" "drop" (func $drop (param i32)))
+                    (func (export "consume") (param i32)
+                        (call $drop (local.get 0)))
+                    (func (export "inspect") (param i32)
+                        (call $drop (local.get 0)))
+                )
+                (core instance $i (instantiate $m
+                    (with "" (instance (export "drop" (func $drop))))
+                ))
+
+                (func (export "consume") (param "x" (own $t))
+                    (canon lift (core func $i "consume")))
+                (func (export "inspect") (param "x" (borrow $t))
+                    (canon lift (core func $i "inspect")))
+            )
+        "#,
+    )?;
+
+    struct Connection(&'static str);
+
+    #[derive(Default)]
+    struct Data {
+        table: ResourceTable,
+        dropped: Vec<&'static str>,
+    }
+
+    let mut linker = Linker::<Data>::new(&engine);
+    linker
+        .root()
+        .resource("t", ResourceType::host::<Connection>(), |mut store, rep| {
+            let data = store.data_mut();
+            let conn = data.table.delete(Resource::<Connection>::new_own(rep))?;
+            data.dropped.push(conn.0);
+            Ok(())
+        })?;
+
+    let broker = ResourceBroker::new(&engine);
+    let mut store1 = Store::new(&engine, Data::default());
+    let mut store2 = Store::new(&engine, Data::default());
+    let i1 = linker.instantiate(&mut store1, &c)?;
+    let i2 = linker.instantiate(&mut store2, &c)?;
+    let consume1 = i1.get_typed_func::<(&Resource<Connection>,), ()>(&mut store1, "consume")?;
+    let inspect1 = i1.get_typed_func::<(&Resource<Connection>,), ()>(&mut store1, "inspect")?;
+    let consume2 = i2.get_typed_func::<(Resource<Connection>,), ()>(&mut store2, "consume")?;
+
+    // Occupy a slot in the second store's table so the transferred resource
+    // gets a different rep there.
+    store2.data_mut().table.push(Connection("other"))?;
+
+    let a = store1.data_mut().table.push(Connection("a"))?;
+    let transfer = broker.send(&mut store1, a, |d| &mut d.table)?;
+    assert!(store1.data().table.is_empty());
+    assert_eq!(broker.pending(), 1);
+
+    // Transfers can move across threads.
+    let transfer = std::thread::spawn(move || transfer).join().unwrap();
+
+    let a = transfer.receive(&mut store2, |d| &mut d.table)?;
+    assert_eq!(a.rep(), 1);
+    assert_eq!(store2.data().table.get(&a)?.0, "a");
+    assert_eq!(broker.pending(), 0);
+    assert_eq!(broker.discarded(), 0);
+
+    // Giving the resource to a guest in the second store runs the destructor
+    // of that store, and none in the first.
+    consume2.call(&mut store2, (a,))?;
+    assert_eq!(store2.data().dropped, ["a"]);
+    assert!(store1.data().dropped.is_empty());
+
+    // Resources which have been lent to a guest in the first store, and are
+    // therefore tracked by it, can be transferred once the borrow has ended.
+    let b = store1.data_mut().table.push(Connection("b"))?;
+    inspect1.call(&mut store1, (&b,))?;
+    let transfer = broker.send(&mut store1, b, |d| &mut d.table)?;
+    let b = transfer.receive(&mut store2, |d| &mut d.table)?;
+    consume2.call(&mut store2, (b,))?;
+    assert_eq!(store2.data().dropped, ["a", "b"]);
+
+    // Resources already given to a guest can't be transferred.
+    let c = store1.data_mut().table.push(Connection("c"))?;
+    consume1.call(&mut store1, (&c,))?;
+    assert_eq!(store1.data().dropped, ["c"]);
+    let err = broker.send(&mut store1, c, |d| &mut d.table).unwrap_err();
+    assert!(format!("{err:?}").contains("not present"), "{err:?}");
+
+    // Dropping a transfer drops its value without running any destructor.
+    let d = store1.data_mut().table.push(Connection("d"))?;
+    let transfer = broker.send(&mut store1, d, |d| &mut d.table)?;
+    drop(transfer);
+    assert_eq!(broker.pending(), 0);
+    assert_eq!(broker.discarded(), 1);
+    assert!(store1.data().table.is_empty());
+    assert_eq!(store1.data().dropped, ["c"]);
+    Ok(())
+}
+
+#[test]
+fn broker_send_errors() -> Result<()> {
+    struct Connection;
+    struct Stream;
+
+    let engine = super::engine();
+    let broker = ResourceBroker::new(&engine);
+    let mut store = Store::new(&engine, ResourceTable::new());
+
+    // Borrows can't be sent.
+    let conn = store.data_mut().push(Connection)?;
+    let borrow = Resource::<Connection>::new_borrow(conn.rep());
+    let err = broker.send(&mut store, borrow, |t| t).unwrap_err();
+    assert!(format!("{err:?}").contains("borrow"), "{err:?}");
+
+    // Nor can resources with children or parents.
+    let stream = store.data_mut().push_child(Stream, &conn)?;
+    let err = broker.send(&mut store, stream, |t| t).unwrap_err();
+    assert!(format!("{err:?}").contains("parent"), "{err:?}");
+    let conn = Resource::<Connection>::new_own(conn.rep());
+    let err = broker.send(&mut store, conn, |t| t).unwrap_err();
+    assert!(format!("{err:?}").contains("children"), "{err:?}");
+
+    // Type mismatches are caught.
+    let conn = Resource::<Stream>::new_own(0);
+    let err = broker.send(&mut store, conn, |t| t).unwrap_err();
+    assert!(format!("{err:?}").contains("another type"), "{err:?}");
+    assert_eq!(broker.pending(), 0);
+
+    // Stores of other engines can't send or receive.
+    let other = Engine::default();
+    let mut other_store = Store::new(&other, ResourceTable::new());
+    let conn = other_store.data_mut().push(Connection)?;
+    assert!(broker.send(&mut other_store, conn, |t| t).is_err());
+
+    let conn = store.data_mut().push(Connection)?;
+    let transfer = broker.send(&mut store, conn, |t| t)?;
+    assert!(transfer.receive(&mut other_store, |t| t).is_err());
+    assert_eq!(broker.pending(), 0);
+    assert_eq!(broker.discarded(), 1);
+    Ok(())
+}
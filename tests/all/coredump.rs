@@ -1,6 +1,20 @@
+use wasmparser::{BinaryReader, CoreDumpStackSection, CoreDumpValue, Parser, Payload};
 use wasmtime::bail;
 use wasmtime::*;
 
+/// Parses the frames out of the stack section of a serialized core dump.
+fn serialized_frames(core_dump: &[u8]) -> Result<Vec<wasmparser::CoreDumpStackFrame>> {
+    for payload in Parser::new(0).parse_all(core_dump) {
+        if let Payload::CustomSection(section) = payload? {
+            if section.name() == "corestack" {
+                let reader = BinaryReader::new(section.data(), section.data_offset());
+                return Ok(CoreDumpStackSection::new(reader)?.frames);
+            }
+        }
+    }
+    bail!("core dump has no stack section")
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn coredump_attached_to_error() -> Result<()> {
@@ -293,3 +307,111 @@ fn core_dump_with_shared_memory() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn coredump_written_to_path() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("trap.coredump");
+    let mut config = Config::default();
+    config.coredump_on_trap_path(&path);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::<()>::new(&engine, ());
+
+    let wat = r#"
+      (module $trapper
+          (memory 1)
+          (data (i32.const 0) "hello")
+          (func $a (export "a")
+              call $b
+          )
+          (func $b
+              unreachable
+          )
+      )
+    "#;
+
+    let module = Module::new(store.engine(), wat)?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let a_func = instance.get_typed_func::<(), ()>(&mut store, "a")?;
+
+    let e = a_func.call(&mut store, ()).unwrap_err();
+    assert!(e.downcast_ref::<WasmCoreDump>().is_some());
+
+    let core_dump = std::fs::read(&path)?;
+    let frames = serialized_frames(&core_dump)?;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].funcidx, 1);
+    assert_eq!(frames[1].funcidx, 0);
+
+    // The contents of linear memory are part of the file.
+    assert!(core_dump.windows(5).any(|w| w == b"hello"));
+
+    // Only the first trap is written to the file.
+    std::fs::remove_file(&path)?;
+    let e = a_func.call(&mut store, ()).unwrap_err();
+    assert!(e.downcast_ref::<WasmCoreDump>().is_some());
+    assert!(!path.exists());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn coredump_has_locals_with_guest_debug() -> Result<()> {
+    let mut config = Config::default();
+    config.coredump_on_trap(true).guest_debug(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::<()>::new(&engine, ());
+
+    let wat = r#"
+      (module
+          (func (export "a") (param i32)
+              i32.const 5
+              (call $b (i64.const 42) (local.get 0))
+              drop
+          )
+          (func $b (param i64 i32) (local f32)
+              (local.set 2 (f32.const 1.5))
+              i32.const 7
+              local.get 1
+              i32.div_u
+              drop
+          )
+      )
+    "#;
+
+    let module = Module::new(store.engine(), wat)?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let a_func = instance.get_typed_func::<i32, ()>(&mut store, "a")?;
+
+    let e = a_func.call(&mut store, 0).unwrap_err();
+    assert_eq!(e.downcast_ref::<Trap>(), Some(&Trap::IntegerDivisionByZero));
+    let cd = e.downcast_ref::<WasmCoreDump>().unwrap();
+    let frames = serialized_frames(&cd.serialize(&mut store, "locals"))?;
+    assert_eq!(frames.len(), 2);
+
+    let b = &frames[0];
+    assert_eq!(b.funcidx, 1);
+    assert!(matches!(
+        b.locals[..],
+        [
+            CoreDumpValue::I64(42),
+            CoreDumpValue::I32(0),
+            CoreDumpValue::F32(f),
+        ] if f32::from_bits(f.bits()) == 1.5
+    ));
+
+    let a = &frames[1];
+    assert_eq!(a.funcidx, 0);
+    assert!(matches!(a.locals[..], [CoreDumpValue::I32(0)]));
+    // The operand stack of a caller still includes the arguments of the call.
+    assert!(matches!(
+        a.stack[..],
+        [
+            CoreDumpValue::I32(5),
+            CoreDumpValue::I64(42),
+            CoreDumpValue::I32(0),
+        ]
+    ));
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::sync::Arc;
 use wasmtime::*;
 
 #[test]
@@ -12,3 +13,208 @@ fn engine_without_compiler_cannot_compile() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn engine_metrics() -> Result<()> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+    let metrics = engine.metrics();
+    assert_eq!(metrics.core_instantiations(), 0);
+    assert_eq!(metrics.trap_counts().count(), 0);
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (func (export "unreachable") unreachable)
+                (func (export "loop") (loop br 0))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    store.set_fuel(10_000)?;
+    store.set_epoch_deadline(1);
+    let instance = Instance::new(&mut store, &module, &[])?;
+    assert_eq!(metrics.core_instantiations(), 1);
+
+    let unreachable = instance.get_typed_func::<(), ()>(&mut store, "unreachable")?;
+    assert!(unreachable.call(&mut store, ()).is_err());
+    assert!(unreachable.call(&mut store, ()).is_err());
+    assert_eq!(metrics.traps(Trap::UnreachableCodeReached), 2);
+
+    let looping = instance.get_typed_func::<(), ()>(&mut store, "loop")?;
+    let trap = looping.call(&mut store, ()).unwrap_err();
+    assert_eq!(trap.downcast::<Trap>()?, Trap::OutOfFuel);
+    assert_eq!(metrics.fuel_exhaustions(), 1);
+    assert_eq!(metrics.traps(Trap::OutOfFuel), 1);
+
+    store.set_fuel(u64::MAX)?;
+    engine.increment_epoch();
+    let trap = looping.call(&mut store, ()).unwrap_err();
+    assert_eq!(trap.downcast::<Trap>()?, Trap::Interrupt);
+    assert_eq!(metrics.epoch_interruptions(), 1);
+
+    assert_eq!(
+        metrics.trap_counts().collect::<Vec<_>>(),
+        [
+            (Trap::UnreachableCodeReached, 2),
+            (Trap::Interrupt, 1),
+            (Trap::OutOfFuel, 1),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn code_regions() -> Result<()> {
+    use std::sync::Mutex;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut config = Config::new();
+    config.code_event_hook({
+        let events = events.clone();
+        move |event| {
+            let event = match event {
+                CodeEvent::Mapped(region) => (true, region.clone()),
+                CodeEvent::Unmapped(region) => (false, region.clone()),
+                _ => unreachable!(),
+            };
+            events.lock().unwrap().push(event);
+        }
+    });
+    let engine = Engine::new(&config)?;
+    assert!(engine.code_regions().is_empty());
+    assert_eq!(engine.executable_code_size(), 0);
+
+    let module = Module::new(&engine, r#"(module $m (func (export "f")))"#)?;
+    let regions = engine.code_regions();
+    assert_eq!(regions.len(), 1);
+    let compiled = &regions[0];
+    assert_eq!(compiled.kind(), CodeKind::Module);
+    assert_eq!(compiled.name(), Some("m"));
+    assert_eq!(compiled.origin(), CodeOrigin::Compiled);
+    assert!(compiled.text_size() > 0);
+    assert!(compiled.image_size() > compiled.text_size());
+    if compiled.is_executable() {
+        assert_eq!(engine.executable_code_size(), compiled.text_size());
+    } else {
+        assert_eq!(engine.executable_code_size(), 0);
+    }
+
+    let serialized = module.serialize()?;
+    let deserialized = unsafe { Module::deserialize(&engine, &serialized)? };
+    let regions = engine.code_regions();
+    assert_eq!(regions.len(), 2);
+    let loaded = regions.iter().find(|r| r.id() != compiled.id()).unwrap();
+    assert_eq!(loaded.origin(), CodeOrigin::Deserialized);
+    assert_eq!(loaded.name(), Some("m"));
+    assert_eq!(loaded.text_size(), compiled.text_size());
+    assert_ne!(loaded.text_range(), compiled.text_range());
+
+    drop(module);
+    drop(deserialized);
+    assert!(engine.code_regions().is_empty());
+    assert_eq!(engine.executable_code_size(), 0);
+
+    let events = events.lock().unwrap();
+    let events = events
+        .iter()
+        .map(|(mapped, region)| (*mapped, region.id(), region.origin()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            (true, compiled.id(), CodeOrigin::Compiled),
+            (true, loaded.id(), CodeOrigin::Deserialized),
+            (false, compiled.id(), CodeOrigin::Compiled),
+            (false, loaded.id(), CodeOrigin::Deserialized),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn compilation_threads() -> Result<()> {
+    use std::sync::Mutex;
+
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let mut config = Config::new();
+    config.compilation_threads(2).compilation_thread_start({
+        let started = started.clone();
+        move |i| {
+            let name = std::thread::current().name().map(|s| s.to_string());
+            started.lock().unwrap().push((i, name));
+        }
+    });
+    let engine = Engine::new(&config)?;
+    assert_eq!(engine.get_compilation_threads(), Some(2));
+    Module::new(
+        &engine,
+        r#"(module (func (export "a")) (func (export "b")) (func (export "c")))"#,
+    )?;
+
+    // Threads are started in the background, so not all of them have
+    // necessarily started yet.
+    let started = started.lock().unwrap();
+    assert!(started.len() <= 2);
+    for (i, name) in started.iter() {
+        assert!(*i < 2);
+        assert_eq!(name.as_deref(), Some(&*format!("wasmtime-compile-{i}")));
+    }
+    drop(started);
+
+    config.compilation_threads(0);
+    Engine::new(&config)
+        .err()
+        .unwrap()
+        .assert_contains("the number of compilation threads must be non-zero");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn compilation_executor() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Scoped {
+        tasks: AtomicUsize,
+    }
+
+    impl CompilationExecutor for Scoped {
+        fn run(&self, count: usize, task: &(dyn Fn(usize) + Sync)) {
+            self.tasks.fetch_add(count, Ordering::SeqCst);
+            std::thread::scope(|s| {
+                for i in 0..count {
+                    s.spawn(move || task(i));
+                }
+            });
+        }
+    }
+
+    let executor = Arc::new(Scoped::default());
+    let mut config = Config::new();
+    config.with_compilation_executor(executor.clone());
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"(module (func (export "f") (result i32) i32.const 42))"#,
+    )?;
+    assert!(executor.tasks.load(Ordering::SeqCst) > 0);
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let f = instance.get_typed_func::<(), i32>(&mut store, "f")?;
+    assert_eq!(f.call(&mut store, ())?, 42);
+
+    config.compilation_threads(1);
+    Engine::new(&config).err().unwrap().assert_contains(
+        "a compilation executor cannot be combined with configuring compilation threads",
+    );
+    Ok(())
+}
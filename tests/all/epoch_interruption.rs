@@ -129,6 +129,34 @@ async fn epoch_yield_at_loop_header(config: &mut Config) -> Result<()> {
     Ok(())
 }
 
+#[wasmtime_test(strategies(not(Winch)))]
+async fn epoch_yield_in_straight_line_code(config: &mut Config) -> Result<()> {
+    // Without loops or calls there's nowhere to check the epoch after it's
+    // bumped, unless additional checks are requested.
+    let wasm = "
+        (module
+            (import \"\" \"bump_epoch\" (func $bump))
+            (func (export \"run\")
+                (local $x i32)
+                call $bump  ;; bump epoch
+                (local.set $x (i32.add (local.get $x) (i32.const 1)))
+                (local.set $x (i32.add (local.get $x) (i32.const 1)))
+                (local.set $x (i32.add (local.get $x) (i32.const 1)))
+                (local.set $x (i32.add (local.get $x) (i32.const 1)))))
+    ";
+    assert_eq!(
+        Some((0, 0)),
+        run_and_count_yields_or_trap(config, wasm, 1, InterruptMode::Yield(1), |_| {}).await?
+    );
+
+    config.epoch_interruption_check_interval(core::num::NonZeroU32::new(4));
+    assert_eq!(
+        Some((1, 0)),
+        run_and_count_yields_or_trap(config, wasm, 1, InterruptMode::Yield(1), |_| {}).await?
+    );
+    Ok(())
+}
+
 #[wasmtime_test]
 async fn epoch_yield_immediate(config: &mut Config) -> Result<()> {
     // We should see one yield immediately when the initial deadline
@@ -496,3 +524,100 @@ async fn drop_future_on_epoch_yield(config: &mut Config) -> Result<()> {
     assert_eq!(true, alive_flag.load(Ordering::Acquire));
     Ok(())
 }
+
+#[wasmtime_test]
+async fn epoch_start_function_deadline(config: &mut Config) -> Result<()> {
+    let engine = build_engine(config)?;
+    let linker = make_env::<usize>(&engine);
+    let mut store = Store::new(&engine, 0);
+    store.set_epoch_deadline(100);
+    store.epoch_deadline_callback(|mut cx| {
+        *cx.data_mut() += 1;
+        Ok(UpdateDeadline::Continue(1))
+    });
+    store.start_function_epoch_deadline(Some(1));
+
+    // The start function reaches its own deadline, which traps even though
+    // the store's callback would otherwise let it continue.
+    let module = Module::new(
+        &engine,
+        "
+        (module
+            (import \"\" \"bump_epoch\" (func $bump))
+            (func $start
+                call $bump
+                call $subfunc)
+            (func $subfunc)
+            (start $start))
+        ",
+    )?;
+    let err = linker
+        .instantiate_async(&mut store, &module)
+        .await
+        .unwrap_err();
+    assert_eq!(err.downcast::<Trap>()?, Trap::Interrupt);
+    assert_eq!(*store.data(), 0);
+
+    // Afterwards the store's own deadline is back in effect.
+    let module = Module::new(
+        &engine,
+        "
+        (module
+            (import \"\" \"bump_epoch\" (func $bump))
+            (func (export \"run\")
+                call $bump
+                call $subfunc)
+            (func $subfunc))
+        ",
+    )?;
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let f = instance.get_func(&mut store, "run").unwrap();
+    f.call_async(&mut store, &[], &mut []).await?;
+    assert_eq!(*store.data(), 0);
+    Ok(())
+}
+
+#[wasmtime_test]
+async fn epoch_yield_from_host(config: &mut Config) -> Result<()> {
+    let engine = build_engine(config)?;
+    let module = Module::new(
+        &engine,
+        "
+        (module
+            (import \"\" \"work\" (func $work))
+            (func (export \"run\") call $work))
+        ",
+    )?;
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap_async("", "work", |mut caller: Caller<'_, usize>, ()| {
+        Box::new(async move {
+            assert_eq!(caller.remaining_epoch_ticks(), Some(2));
+            caller.yield_if_needed().await?;
+            for _ in 0..3 {
+                caller.engine().increment_epoch();
+                caller.yield_if_needed().await?;
+                *caller.data_mut() += 1;
+            }
+            assert_eq!(caller.remaining_epoch_ticks(), Some(1));
+            Ok(())
+        })
+    })?;
+
+    let mut store = Store::new(&engine, 0);
+    store.set_epoch_deadline(2);
+    store.epoch_deadline_async_yield_and_update(1);
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let f = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    let (result, yields) = CountPending::new(Box::pin(f.call_async(&mut store, ()))).await;
+    result?;
+    assert_eq!(yields, 2);
+    assert_eq!(*store.data(), 3);
+
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_trap();
+    engine.increment_epoch();
+    let err = f.call_async(&mut store, ()).await.unwrap_err();
+    assert_eq!(err.downcast::<Trap>()?, Trap::Interrupt);
+    assert_eq!(*store.data(), 3);
+    Ok(())
+}
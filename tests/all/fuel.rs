@@ -498,6 +498,7 @@ fn host_function_consumes_all(config: &mut Config) -> Result<()> {
     let func = Func::wrap(&mut store, |mut caller: Caller<'_, ()>| {
         let remaining = caller.get_fuel().unwrap();
         assert_eq!(remaining, FUEL - 2);
+        assert_eq!(caller.remaining_fuel().unwrap(), FUEL - 2);
         assert!(caller.set_fuel(1).is_ok());
     });
 
@@ -693,3 +694,85 @@ fn custom_operator_cost(config: &mut Config) -> Result<()> {
 
     Ok(())
 }
+
+#[wasmtime_test]
+#[cfg_attr(miri, ignore)]
+fn custom_fuel_costs(config: &mut Config) -> Result<()> {
+    config.consume_fuel(true);
+    let mut costs = FuelCostTable::new();
+    costs.call(10).memory(5).other(2);
+    config.fuel_costs(costs);
+    let engine = Engine::new(config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+              (memory 1)
+              (func $f)
+              (func (export "main")
+                call $f
+                (i32.store (i32.const 0) (i32.const 1))
+                (drop (i32.load (i32.const 0)))
+                (drop (memory.size))
+              )
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    store.set_fuel(10_000)?;
+
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let main = instance.get_typed_func::<(), ()>(&mut store, "main")?;
+
+    let initial_fuel = store.get_fuel()?;
+    main.call(&mut store, ())?;
+    // One call, three memory operators and three constants, plus one for
+    // entering each of the two functions.
+    let cost_of_execution = 10 + 3 * 5 + 3 * 2 + 2;
+    assert_eq!(store.get_fuel()?, initial_fuel - cost_of_execution);
+
+    Ok(())
+}
+
+#[wasmtime_test]
+#[cfg_attr(miri, ignore)]
+async fn host_function_yields_if_needed(config: &mut Config) -> Result<()> {
+    config.consume_fuel(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "" (func))
+                (func (export "") call 0))
+        "#,
+    )?;
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap_async("", "", |mut caller: Caller<'_, ()>, ()| {
+        Box::new(async move {
+            // Calling the host function used up the first interval.
+            assert_eq!(caller.remaining_fuel()?, 0);
+            assert_eq!(caller.get_fuel()?, 8);
+            caller.yield_if_needed().await?;
+            assert_eq!(caller.remaining_fuel()?, 2);
+            assert_eq!(caller.get_fuel()?, 8);
+
+            // Nothing to do until more fuel is used.
+            caller.yield_if_needed().await?;
+
+            caller.set_fuel(0)?;
+            caller.yield_if_needed().await
+        })
+    })?;
+
+    let mut store = Store::new(&engine, ());
+    store.set_fuel(10)?;
+    store.fuel_async_yield_interval(Some(2))?;
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let func = instance.get_typed_func::<(), ()>(&mut store, "")?;
+    let (result, yields) =
+        crate::async_functions::CountPending::new(Box::pin(func.call_async(&mut store, ()))).await;
+    assert_eq!(yields, 1);
+    assert_eq!(result.unwrap_err().downcast::<Trap>()?, Trap::OutOfFuel);
+    Ok(())
+}
@@ -3788,3 +3788,331 @@ fn initial_size_larger_than_reservation() -> Result<()> {
 
     Ok(())
 }
+
+fn weak_rooted_store(collector: Collector) -> Result<Store<()>> {
+    let _ = env_logger::try_init();
+    let mut config = Config::new();
+    config.wasm_function_references(true);
+    config.wasm_gc(true);
+    config.collector(collector);
+    let engine = Engine::new(&config)?;
+    Ok(Store::new(&engine, ()))
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn weak_rooted_cleared_after_collection() -> Result<()> {
+    for collector in [Collector::DeferredReferenceCounting, Collector::Copying] {
+        let mut store = weak_rooted_store(collector)?;
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let weak = {
+            let mut scope = RootScope::new(&mut store);
+            let x = ExternRef::new(&mut scope, SetFlagOnDrop(flag.clone()))?;
+            let weak = x.downgrade(&mut scope)?;
+
+            scope.as_context_mut().gc(None)?;
+            let y = weak.upgrade(&mut scope).expect("still rooted");
+            assert!(Rooted::ref_eq(&scope, &x, &y)?);
+            weak
+        };
+
+        store.gc(None)?;
+        assert!(
+            flag.load(SeqCst),
+            "{collector:?}: weak roots don't keep objects alive"
+        );
+        assert!(weak.upgrade(&mut store).is_none(), "{collector:?}");
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn weak_rooted_follows_moved_objects() -> Result<()> {
+    let (mut store, engine) = copying_store_with_gc_zeal(1)?;
+    let ty = StructType::new(
+        &engine,
+        [FieldType::new(
+            Mutability::Var,
+            StorageType::ValType(ValType::I32),
+        )],
+    )?;
+    let pre = StructRefPre::new(&mut store, ty);
+
+    let (owned, weak) = {
+        let mut scope = RootScope::new(&mut store);
+        // Allocate some garbage so that the live object moves on collection.
+        for _ in 0..10 {
+            StructRef::new(&mut scope, &pre, &[Val::I32(0)])?;
+        }
+        let s = StructRef::new(&mut scope, &pre, &[Val::I32(42)])?;
+        (s.to_owned_rooted(&mut scope)?, s.downgrade(&mut scope)?)
+    };
+
+    for _ in 0..3 {
+        store.gc(None)?;
+        let mut scope = RootScope::new(&mut store);
+        let s = weak.upgrade(&mut scope).expect("still owned");
+        assert!(Rooted::ref_eq(&scope, &s, &owned)?);
+        assert_eq!(s.field(&mut scope, 0)?.unwrap_i32(), 42);
+    }
+
+    drop(owned);
+    store.gc(None)?;
+    assert!(weak.upgrade(&mut store).is_none());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn drc_weak_rooted_cleared_by_ref_counting() -> Result<()> {
+    let mut store = weak_rooted_store(Collector::DeferredReferenceCounting)?;
+    let engine = store.engine().clone();
+    let ty = StructType::new(
+        &engine,
+        [FieldType::new(
+            Mutability::Var,
+            StorageType::ValType(ValType::ANYREF),
+        )],
+    )?;
+    let pre = StructRefPre::new(&mut store, ty);
+
+    let (outer, weak) = {
+        let mut scope = RootScope::new(&mut store);
+        let inner = StructRef::new(&mut scope, &pre, &[Val::AnyRef(None)])?;
+        let outer = StructRef::new(&mut scope, &pre, &[Val::AnyRef(Some(inner.to_anyref()))])?;
+        (
+            outer.to_owned_rooted(&mut scope)?,
+            inner.downgrade(&mut scope)?,
+        )
+    };
+
+    // `inner` is only kept alive by `outer`'s reference to it.
+    store.gc(None)?;
+    assert!(weak.upgrade(&mut RootScope::new(&mut store)).is_some());
+
+    // Reclaiming `outer` decrements `inner`'s reference count to zero, which
+    // reclaims it too.
+    drop(outer);
+    store.gc(None)?;
+    assert!(weak.upgrade(&mut store).is_none());
+
+    // Reusing the reclaimed objects' memory doesn't resurrect the weak root.
+    let mut scope = RootScope::new(&mut store);
+    for _ in 0..2 {
+        StructRef::new(&mut scope, &pre, &[Val::AnyRef(None)])?;
+    }
+    assert!(weak.upgrade(&mut scope).is_none());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn weak_rooted_on_reclaimed() -> Result<()> {
+    for collector in [Collector::DeferredReferenceCounting, Collector::Copying] {
+        let mut store = weak_rooted_store(collector)?;
+        let count = Arc::new(AtomicUsize::new(0));
+
+        {
+            let mut scope = RootScope::new(&mut store);
+            let x = ExternRef::new(&mut scope, "hello")?;
+            let weak = x.downgrade(&mut scope)?;
+            for _ in 0..2 {
+                let count = count.clone();
+                weak.on_reclaimed(&mut scope, move || {
+                    count.fetch_add(1, SeqCst);
+                });
+            }
+            scope.as_context_mut().gc(None)?;
+            assert_eq!(count.load(SeqCst), 0, "{collector:?}: still rooted");
+
+            // Finalizers outlive the `WeakRooted` they were registered on.
+            drop(weak);
+        }
+
+        store.gc(None)?;
+        assert_eq!(count.load(SeqCst), 2, "{collector:?}");
+
+        // Finalizers run exactly once.
+        store.gc(None)?;
+        assert_eq!(count.load(SeqCst), 2, "{collector:?}");
+
+        // Registering a finalizer on an already-reclaimed object runs it
+        // immediately.
+        let weak = {
+            let mut scope = RootScope::new(&mut store);
+            ExternRef::new(&mut scope, "goodbye")?.downgrade(&mut scope)?
+        };
+        store.gc(None)?;
+        let count2 = count.clone();
+        weak.on_reclaimed(&mut store, move || {
+            count2.fetch_add(1, SeqCst);
+        });
+        assert_eq!(count.load(SeqCst), 3, "{collector:?}");
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn null_collector_weak_rooted_never_cleared() -> Result<()> {
+    let mut store = weak_rooted_store(Collector::Null)?;
+    let weak = {
+        let mut scope = RootScope::new(&mut store);
+        ExternRef::new(&mut scope, 1234_u32)?.downgrade(&mut scope)?
+    };
+    store.gc(None)?;
+    assert!(weak.upgrade(&mut store).is_some());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn weak_rooted_i31ref() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let i31 = AnyRef::from_i31(&mut store, I31::wrapping_u32(42));
+    let weak = i31.downgrade(&mut store)?;
+    store.gc(None)?;
+    let i31 = weak
+        .upgrade(&mut store)
+        .expect("i31refs are never reclaimed");
+    assert_eq!(i31.unwrap_i31(&store)?.get_u32(), 42);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn host_gc_roots() -> Result<()> {
+    let mut store = weak_rooted_store(Collector::DeferredReferenceCounting)?;
+    let mut scope = RootScope::new(&mut store);
+
+    let scoped = ExternRef::new(&mut scope, "scoped")?;
+    let i31 = AnyRef::from_i31(&mut scope, I31::wrapping_u32(7));
+    let (owned, weak) = {
+        let mut scope = RootScope::new(&mut scope);
+        let r = ExternRef::new(&mut scope, "owned")?;
+        (r.to_owned_rooted(&mut scope)?, r.downgrade(&mut scope)?)
+    };
+
+    let mut nested = RootScope::new(&mut scope);
+    let roots = nested.as_context_mut().host_gc_roots();
+    let kinds = roots.iter().map(|r| r.kind()).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            HostGcRootKind::Scoped,
+            HostGcRootKind::Scoped,
+            HostGcRootKind::Owned,
+            HostGcRootKind::Weak,
+        ]
+    );
+
+    let Ref::Extern(Some(r)) = roots[0].value() else {
+        panic!("expected an externref, found {:?}", roots[0].value());
+    };
+    assert!(Rooted::ref_eq(&nested, r, &scoped)?);
+    let Ref::Any(Some(r)) = roots[1].value() else {
+        panic!("expected an anyref, found {:?}", roots[1].value());
+    };
+    assert!(Rooted::ref_eq(&nested, r, &i31)?);
+    for root in &roots[2..] {
+        let Ref::Extern(Some(r)) = root.value() else {
+            panic!("expected an externref, found {:?}", root.value());
+        };
+        assert!(Rooted::ref_eq(&nested, r, &owned)?);
+    }
+    drop(nested);
+
+    drop(owned);
+    scope.as_context_mut().gc(None)?;
+    assert!(weak.upgrade(&mut scope).is_none());
+    let kinds = scope
+        .as_context_mut()
+        .host_gc_roots()
+        .iter()
+        .map(|r| r.kind())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [HostGcRootKind::Scoped, HostGcRootKind::Scoped]);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn wasm_stack_gc_roots() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_reference_types(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "host" (func $host))
+                (func $inner (param externref) (result externref)
+                    call $host
+                    local.get 0
+                )
+                (func (export "run") (param externref externref externref)
+                    (result externref externref)
+                    (call $inner (local.get 1))
+                    local.get 0
+                )
+            )
+        "#,
+    )?;
+    let stack_maps = module.stack_maps().collect::<Vec<_>>();
+    assert_eq!(stack_maps.len(), 2);
+    for map in &stack_maps {
+        assert!(map.offset() < module.text().len());
+        assert_eq!(map.gc_ref_offsets().count(), 1, "{map:?}");
+    }
+
+    let host = Func::wrap(&mut store, |mut caller: Caller<'_, ()>| -> Result<()> {
+        let mut scope = RootScope::new(&mut caller);
+        let roots = scope.as_context_mut().wasm_stack_gc_roots();
+
+        let summary = roots
+            .iter()
+            .map(|root| {
+                assert!(
+                    root.frame()
+                        .module()
+                        .stack_maps()
+                        .any(|m| m.offset() == root.text_offset())
+                );
+                let Ref::Extern(Some(externref)) = root.value() else {
+                    panic!("expected an externref, found {:?}", root.value());
+                };
+                let data = externref.data(&scope)?.unwrap();
+                let data = *data.downcast_ref::<&str>().unwrap();
+                let raw = unsafe { root.slot().read() };
+                assert_ne!(raw, 0);
+                Ok((root.frame().func_index(), data))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The youngest frame comes first, and the third argument to `run` is
+        // dead across the call to `$inner`.
+        assert_eq!(summary, [(1, "b"), (2, "a")]);
+        Ok(())
+    });
+
+    let instance = Instance::new(&mut store, &module, &[host.into()])?;
+    let run = instance.get_typed_func::<(
+        Option<Rooted<ExternRef>>,
+        Option<Rooted<ExternRef>>,
+        Option<Rooted<ExternRef>>,
+    ), (Option<Rooted<ExternRef>>, Option<Rooted<ExternRef>>)>(
+        &mut store, "run"
+    )?;
+    let a = ExternRef::new(&mut store, "a")?;
+    let b = ExternRef::new(&mut store, "b")?;
+    let c = ExternRef::new(&mut store, "c")?;
+    run.call(&mut store, (Some(a), Some(b), Some(c)))?;
+
+    // Outside of a host call there are no Wasm frames.
+    assert!(store.as_context_mut().wasm_stack_gc_roots().is_empty());
+    Ok(())
+}
@@ -79,3 +79,117 @@ fn linear_memory_limits() -> Result<()> {
         Ok(())
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn freeze_and_thaw() -> Result<()> {
+    let wat = r#"
+        (module
+            (memory (export "memory") 1)
+            (table (export "table") 2 funcref)
+            (global $counter (export "counter") (mut i32) (i32.const 0))
+            (global $callback (export "callback") (mut funcref) (ref.null func))
+            (elem declare func $one $two)
+
+            (func $one (result i32) i32.const 1)
+            (func $two (result i32) i32.const 2)
+
+            (func (export "step")
+                (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+                (i32.store (i32.const 70000) (global.get $counter))
+                (drop (memory.grow (i32.const 1)))
+                (table.set (i32.const 1) (ref.func $two))
+                (global.set $callback (ref.func $one)))
+        )"#;
+    let engine = Engine::default();
+    let module = Module::new(&engine, wat)?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let step = instance.get_typed_func::<(), ()>(&mut store, "step")?;
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    memory.grow(&mut store, 1)?;
+    step.call(&mut store, ())?;
+    step.call(&mut store, ())?;
+    memory.data_mut(&mut store)[100] = 42;
+
+    let bytes = instance.freeze(&mut store)?.to_bytes();
+    let image = StateImage::from_bytes(&bytes)?;
+    assert!(bytes.len() < 1024, "image is {} bytes", bytes.len());
+
+    // Restore into a fresh instance in a fresh store.
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance.thaw(&mut store, &image)?;
+
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    assert_eq!(memory.size(&store), 4);
+    assert_eq!(memory.data(&store)[100], 42);
+    assert_eq!(memory.data(&store)[70000], 2);
+    let counter = instance.get_global(&mut store, "counter").unwrap();
+    assert_eq!(counter.get(&mut store).unwrap_i32(), 2);
+    let table = instance.get_table(&mut store, "table").unwrap();
+    assert!(table.get(&mut store, 0).unwrap().unwrap_func().is_none());
+    let two = *table.get(&mut store, 1).unwrap().unwrap_func().unwrap();
+    assert_eq!(two.typed::<(), i32>(&store)?.call(&mut store, ())?, 2);
+    let callback = instance.get_global(&mut store, "callback").unwrap();
+    let one = *callback.get(&mut store).unwrap_funcref().unwrap();
+    assert_eq!(one.typed::<(), i32>(&store)?.call(&mut store, ())?, 1);
+    assert_eq!(instance.freeze(&mut store)?, image);
+
+    // Memories which are larger than the image can't be restored.
+    memory.grow(&mut store, 1)?;
+    assert!(instance.thaw(&mut store, &image).is_err());
+
+    // Images only apply to instances of the same module.
+    let other = Module::new(&engine, "(module (memory 1))")?;
+    let other = Instance::new(&mut store, &other, &[])?;
+    assert!(other.thaw(&mut store, &image).is_err());
+    let other = Module::new(
+        &engine,
+        r#"(module
+            (memory 1)
+            (table 2 funcref)
+            (global (mut i64) (i64.const 0))
+            (global (mut funcref) (ref.null func)))"#,
+    )?;
+    let other = Instance::new(&mut store, &other, &[])?;
+    assert!(other.thaw(&mut store, &image).is_err());
+    assert!(StateImage::from_bytes(b"not an image").is_err());
+
+    // References to functions of other instances can't be frozen.
+    let func = Func::wrap(&mut store, || 3i32);
+    table.set(&mut store, 0, func.into())?;
+    assert!(instance.freeze(&mut store).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn thaw_non_nullable_table() -> Result<()> {
+    let wat = r#"
+        (module
+            (type $f (func))
+            (func $a)
+            (func $b)
+            (table $t (export "table") 1 (ref $f) (ref.func $a))
+            (elem declare func $b)
+            (func (export "grow")
+                (drop (table.grow $t (ref.func $b) (i32.const 2)))))"#;
+    let engine = Engine::default();
+    let module = Module::new(&engine, wat)?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let grow = instance.get_typed_func::<(), ()>(&mut store, "grow")?;
+    grow.call(&mut store, ())?;
+    let image = instance.freeze(&mut store)?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance.thaw(&mut store, &image)?;
+    let table = instance.get_table(&mut store, "table").unwrap();
+    assert_eq!(table.size(&store), 3);
+    assert_eq!(instance.freeze(&mut store)?, image);
+    Ok(())
+}
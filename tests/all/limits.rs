@@ -1164,3 +1164,203 @@ fn growth_trap() -> Result<()> {
 
     Ok(())
 }
+
+#[derive(Default)]
+struct ContextRecorder {
+    /// Module name, index, and desired size of each memory and table request
+    memories: Vec<(Option<String>, Option<u32>, usize)>,
+    tables: Vec<(Option<String>, Option<u32>, usize)>,
+    /// Minimum size of each host-created memory
+    host_memories: Vec<usize>,
+    /// Module name and current instance count of each instantiation
+    instances: Vec<(Option<String>, usize)>,
+    max_instances: usize,
+}
+
+impl ResourceLimiter for ContextRecorder {
+    fn memory_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+        unreachable!()
+    }
+    fn memory_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        assert_eq!(context.instance().is_some(), !context.is_host());
+        let name = context.module_name().map(|s| s.to_string());
+        self.memories.push((name, context.index(), desired));
+        Ok(true)
+    }
+    fn host_memory_creating(&mut self, minimum: usize, _maximum: Option<usize>) -> Result<bool> {
+        self.host_memories.push(minimum);
+        Ok(true)
+    }
+    fn table_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+        unreachable!()
+    }
+    fn table_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        let name = context.module_name().map(|s| s.to_string());
+        self.tables.push((name, context.index(), desired));
+        Ok(true)
+    }
+    fn instance_creating(&mut self, module: &Module, current: usize) -> Result<bool> {
+        self.instances
+            .push((module.name().map(|s| s.to_string()), current));
+        Ok(current < self.max_instances)
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn custom_limiter_receives_context() -> Result<()> {
+    let engine = Engine::default();
+    let a = Module::new(
+        &engine,
+        r#"(module $a
+            (import "host" "m" (memory 1))
+            (memory $m (export "m") 1)
+            (table (export "t") 2 funcref)
+            (func (export "grow") (param i32) (result i32)
+              (memory.grow $m (local.get 0)))
+           )"#,
+    )?;
+    let b = Module::new(
+        &engine,
+        r#"(module $b
+            (table 1 funcref)
+            (table (export "t") 3 funcref)
+            (func (export "grow") (param i32) (result i32)
+              (table.grow 1 (ref.null func) (local.get 0)))
+           )"#,
+    )?;
+
+    let mut store = Store::new(
+        &engine,
+        ContextRecorder {
+            max_instances: 2,
+            ..ContextRecorder::default()
+        },
+    );
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+
+    let host = Memory::new(&mut store, MemoryType::new(1, None))?;
+    assert_eq!(store.data().host_memories, [WASM_PAGE_SIZE]);
+    host.grow(&mut store, 1)?;
+    assert_eq!(store.data().memories, [(None, None, 2 * WASM_PAGE_SIZE)]);
+
+    let mut linker = Linker::new(&engine);
+    linker.define(&store, "host", "m", host)?;
+    let instance_a = linker.instantiate(&mut store, &a)?;
+    let instance_b = linker.instantiate(&mut store, &b)?;
+    let err = linker.instantiate(&mut store, &b).unwrap_err();
+    assert!(
+        err.to_string().contains("resource limiter rejected"),
+        "{err:?}"
+    );
+    assert_eq!(
+        store.data().instances,
+        [
+            (Some("a".to_string()), 0),
+            (Some("b".to_string()), 1),
+            (Some("b".to_string()), 2),
+        ]
+    );
+
+    let grow = instance_a.get_typed_func::<i32, i32>(&mut store, "grow")?;
+    grow.call(&mut store, 2)?;
+    let grow = instance_b.get_typed_func::<i32, i32>(&mut store, "grow")?;
+    grow.call(&mut store, 4)?;
+    let a = Some("a".to_string());
+    let b = Some("b".to_string());
+    assert_eq!(
+        store.data().memories[1..],
+        [
+            (a.clone(), Some(1), WASM_PAGE_SIZE),
+            (a.clone(), Some(1), 3 * WASM_PAGE_SIZE),
+        ]
+    );
+    assert_eq!(
+        store.data().tables,
+        [
+            (a.clone(), Some(0), 2),
+            (b.clone(), Some(0), 1),
+            (b.clone(), Some(1), 3),
+            (b.clone(), Some(1), 7),
+        ]
+    );
+
+    // Growth through the embedder API reports the owning instance too.
+    let table = instance_a.get_table(&mut store, "t").unwrap();
+    table.grow(&mut store, 1, Ref::Func(None))?;
+    assert_eq!(store.data().tables.last(), Some(&(a, Some(0), 3)));
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl ResourceLimiterAsync for ContextRecorder {
+    async fn memory_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+        unreachable!()
+    }
+    async fn memory_growing_with_context(
+        &mut self,
+        context: &ResourceContext,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        tokio::task::yield_now().await;
+        ResourceLimiter::memory_growing_with_context(self, context, current, desired, maximum)
+    }
+    async fn table_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+        unreachable!()
+    }
+    async fn instance_creating(&mut self, module: &Module, current: usize) -> Result<bool> {
+        tokio::task::yield_now().await;
+        ResourceLimiter::instance_creating(self, module, current)
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(miri, ignore)]
+async fn custom_limiter_async_receives_context() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, r#"(module $m (memory 0) (memory 1))"#)?;
+
+    let mut store = Store::new(
+        &engine,
+        ContextRecorder {
+            max_instances: 1,
+            ..ContextRecorder::default()
+        },
+    );
+    store.limiter_async(|s| s as &mut dyn ResourceLimiterAsync);
+
+    // Host memories default to `memory_growing_with_context` with a host
+    // context.
+    Memory::new_async(&mut store, MemoryType::new(2, None)).await?;
+    Instance::new_async(&mut store, &module, &[]).await?;
+    assert!(Instance::new_async(&mut store, &module, &[]).await.is_err());
+
+    let m = Some("m".to_string());
+    assert_eq!(
+        store.data().memories,
+        [
+            (None, None, 2 * WASM_PAGE_SIZE),
+            (m.clone(), Some(0), 0),
+            (m, Some(1), WASM_PAGE_SIZE),
+        ]
+    );
+    assert_eq!(store.data().instances.len(), 2);
+    assert!(store.data().host_memories.is_empty());
+
+    Ok(())
+}
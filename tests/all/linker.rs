@@ -42,6 +42,76 @@ fn test_unknown_import_error() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn check_reports_all_link_errors() -> Result<()> {
+    let mut store = Store::<()>::default();
+    let mut linker = Linker::new(store.engine());
+    linker.func_wrap("host", "f", |x: i32| x)?;
+    let g = Global::new(
+        &mut store,
+        GlobalType::new(ValType::I32, Mutability::Const),
+        Val::I32(0),
+    )?;
+    linker.define(&mut store, "host", "g", g)?;
+    let module = Module::new(
+        store.engine(),
+        r#"
+            (module
+                (import "host" "f" (func (param i32) (result i32)))
+                (import "host" "g" (global i64))
+                (import "host" "missing" (memory 1))
+                (import "host" "f" (table 1 funcref))
+            )
+        "#,
+    )?;
+
+    let errors = linker.check(&module);
+    assert_eq!(errors.len(), 3);
+
+    assert_eq!(errors[0].module(), "host");
+    assert_eq!(errors[0].name(), "g");
+    errors[0].expected().unwrap_global();
+    let actual = errors[0].actual().unwrap().unwrap_global();
+    assert!(actual.content().matches(&ValType::I32));
+    assert!(
+        errors[0]
+            .to_string()
+            .starts_with("incompatible import type for `host::g`"),
+        "{}",
+        errors[0]
+    );
+
+    assert_eq!(errors[1].name(), "missing");
+    errors[1].expected().unwrap_memory();
+    assert!(errors[1].actual().is_none());
+    assert_eq!(
+        errors[1].to_string(),
+        "unknown import: `host::missing` has not been defined"
+    );
+
+    assert_eq!(errors[2].name(), "f");
+    errors[2].expected().unwrap_table();
+    errors[2].actual().unwrap().unwrap_func();
+    assert!(
+        errors[2]
+            .to_string()
+            .contains("expected table, but found function"),
+        "{}",
+        errors[2]
+    );
+
+    // Once everything is defined there's nothing to report, and
+    // instantiation succeeds.
+    let module = Module::new(
+        store.engine(),
+        r#"(module (import "host" "f" (func (param i32) (result i32))))"#,
+    )?;
+    assert!(linker.check(&module).is_empty());
+    linker.instantiate(&mut store, &module)?;
+    Ok(())
+}
+
 #[test]
 fn link_twice_bad() -> Result<()> {
     let mut store = Store::<()>::default();
@@ -370,6 +440,121 @@ fn instance_pre() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn func_wrap_ctx() -> Result<()> {
+    // Clones start counting from zero, so each instance counts its own calls.
+    struct Counter(AtomicUsize);
+
+    impl Clone for Counter {
+        fn clone(&self) -> Counter {
+            Counter(AtomicUsize::new(0))
+        }
+    }
+
+    let engine = Engine::default();
+    let mut linker = Linker::<usize>::new(&engine);
+    for name in ["a", "b"] {
+        linker.func_wrap_ctx(
+            "host",
+            name,
+            Counter(AtomicUsize::new(0)),
+            |mut caller: Caller<'_, usize>, counter: &Counter, (n,): (u32,)| {
+                *caller.data_mut() += 1;
+                counter.0.fetch_add(usize::try_from(n).unwrap(), SeqCst) as u32 + n
+            },
+        )?;
+    }
+
+    let module = Module::new(
+        &engine,
+        r#"(module
+            (import "host" "a" (func $a (param i32) (result i32)))
+            (import "host" "b" (func $b (param i32) (result i32)))
+            (func (export "a") (param i32) (result i32) (call $a (local.get 0)))
+            (func (export "b") (param i32) (result i32) (call $b (local.get 0)))
+        )"#,
+    )?;
+
+    let mut store = Store::new(&engine, 0);
+    let i1 = linker.instantiate(&mut store, &module)?;
+    let i2 = linker.instantiate(&mut store, &module)?;
+    let a1 = i1.get_typed_func::<u32, u32>(&mut store, "a")?;
+    let b1 = i1.get_typed_func::<u32, u32>(&mut store, "b")?;
+    let a2 = i2.get_typed_func::<u32, u32>(&mut store, "a")?;
+    assert_eq!(a1.call(&mut store, 1)?, 1);
+    assert_eq!(a1.call(&mut store, 2)?, 3);
+    assert_eq!(b1.call(&mut store, 5)?, 5);
+    assert_eq!(a2.call(&mut store, 10)?, 10);
+    assert_eq!(a1.call(&mut store, 1)?, 4);
+    assert_eq!(*store.data(), 5);
+
+    // Instances in other stores get their own contexts as well.
+    let mut store = Store::new(&engine, 0);
+    let i3 = linker.instantiate(&mut store, &module)?;
+    let a3 = i3.get_typed_func::<u32, u32>(&mut store, "a")?;
+    assert_eq!(a3.call(&mut store, 7)?, 7);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn func_wrap_ctx_cloned_at_instantiation() -> Result<()> {
+    #[derive(Default)]
+    struct Ctx {
+        clones: Arc<AtomicUsize>,
+        calls: AtomicUsize,
+    }
+
+    impl Clone for Ctx {
+        fn clone(&self) -> Ctx {
+            self.clones.fetch_add(1, SeqCst);
+            Ctx {
+                clones: self.clones.clone(),
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+    let ctx = Ctx::default();
+    let clones = ctx.clones.clone();
+    linker.func_wrap_ctx(
+        "host",
+        "count",
+        ctx,
+        |_caller: Caller<'_, ()>, ctx: &Ctx, (): ()| ctx.calls.fetch_add(1, SeqCst) as u32 + 1,
+    )?;
+    let module = Module::new(
+        &engine,
+        r#"(module
+            (import "host" "count" (func $count (result i32)))
+            (global $calls (mut i32) (i32.const 0))
+            (func $start (global.set $calls (call $count)))
+            (start $start)
+            (func (export "count") (result i32) call $count)
+        )"#,
+    )?;
+
+    // The context is cloned when instantiating, before the start function
+    // runs, and not again when the instance calls the function.
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &module)?;
+    assert_eq!(clones.load(SeqCst), 1);
+    let count = instance.get_typed_func::<(), u32>(&mut store, "count")?;
+    assert_eq!(count.call(&mut store, ())?, 2);
+    assert_eq!(clones.load(SeqCst), 1);
+
+    // Recycled instances start over with a fresh context.
+    instance.mark_recyclable(&mut store)?;
+    let instance = linker.instantiate(&mut store, &module)?;
+    assert_eq!(clones.load(SeqCst), 2);
+    let count = instance.get_typed_func::<(), u32>(&mut store, "count")?;
+    assert_eq!(count.call(&mut store, ())?, 2);
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn test_trapping_unknown_import() -> Result<()> {
@@ -808,3 +993,124 @@ fn linker_clone_drop_original_then_instantiate() -> Result<()> {
     assert_eq!(f.call(&mut store, ())?, 42);
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn module_lazy() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+
+    // `counter` is shared between its importers within a store, and
+    // `broken` is never compiled since nothing imports from it.
+    linker.module_lazy(
+        "counter",
+        br#"(module
+            (global $count (mut i32) (i32.const 0))
+            (func (export "_initialize")
+                (global.set $count (i32.const 100)))
+            (func (export "next") (result i32)
+                (global.set $count (i32.add (global.get $count) (i32.const 1)))
+                (global.get $count))
+        )"#,
+    )?;
+    linker.module_lazy(
+        "wrapper",
+        br#"(module
+            (import "counter" "next" (func $next (result i32)))
+            (func (export "next2") (result i32)
+                (drop (call $next))
+                (call $next))
+        )"#,
+    )?;
+    linker.module_lazy("broken", b"(module")?;
+    assert!(linker.module_lazy("broken", b"(module)").is_err());
+
+    let module = Module::new(
+        &engine,
+        r#"(module
+            (import "wrapper" "next2" (func $next2 (result i32)))
+            (import "counter" "next" (func $next (result i32)))
+            (func (export "run") (result i32)
+                (drop (call $next2))
+                (call $next))
+        )"#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 103);
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 106);
+
+    // A new store gets new instances of the lazily-defined modules.
+    let mut store = Store::new(&engine, ());
+    let instance = linker.clone().instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 103);
+
+    // Definitions in the linker take precedence.
+    linker.func_wrap("counter", "next", || 0)?;
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn module_lazy_errors() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+    linker.module_lazy("a", br#"(module (import "b" "f" (func)))"#)?;
+    linker.module_lazy("b", br#"(module (import "c" "f" (func)))"#)?;
+    linker.module_lazy(
+        "c",
+        br#"(module (import "a" "f" (func)) (func (export "f")))"#,
+    )?;
+    linker.module_lazy("invalid", b"(module")?;
+    linker.module_lazy("missing", br#"(module (import "x" "y" (func)))"#)?;
+
+    let mut store = Store::new(&engine, ());
+    let module = Module::new(&engine, r#"(module (import "a" "f" (func)))"#)?;
+    let err = linker.instantiate(&mut store, &module).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "cycle detected between lazily-defined modules: `a` -> `b` -> `c` -> `a`",
+    );
+
+    let module = Module::new(&engine, r#"(module (import "invalid" "f" (func)))"#)?;
+    let err = linker.instantiate(&mut store, &module).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to compile lazily-defined module `invalid`"
+    );
+
+    let module = Module::new(&engine, r#"(module (import "missing" "f" (func)))"#)?;
+    let err = linker.instantiate(&mut store, &module).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to instantiate lazily-defined module `missing`"
+    );
+    let unknown_import: UnknownImportError = err.downcast()?;
+    assert_eq!(unknown_import.module(), "x");
+
+    // The lazily-defined module doesn't export the item.
+    linker.module_lazy("empty", b"(module)")?;
+    let module = Module::new(&engine, r#"(module (import "empty" "f" (func)))"#)?;
+    let err = linker.instantiate(&mut store, &module).unwrap_err();
+    let unknown_import: UnknownImportError = err.downcast()?;
+    assert_eq!(unknown_import.name(), "f");
+
+    linker.module_lazy("command", br#"(module (func (export "_start")))"#)?;
+    let module = Module::new(&engine, r#"(module (import "command" "_start" (func)))"#)?;
+    let err = linker.instantiate(&mut store, &module).unwrap_err();
+    assert_eq!(
+        format!("{err:#}"),
+        "failed to instantiate lazily-defined module `command`: \
+         lazily-defined modules cannot be Commands"
+    );
+    Ok(())
+}
@@ -4,6 +4,7 @@ use wasmtime::Result;
 
 mod arrays;
 mod async_functions;
+mod background_recompilation;
 mod call_hook;
 mod cli_tests;
 mod compile_time_builtins;
@@ -46,6 +47,7 @@ mod native_debug;
 mod noextern;
 mod piped_tests;
 mod pooling_allocator;
+mod profile_guided;
 mod profiling;
 mod pulley;
 mod relocs;
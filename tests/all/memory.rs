@@ -825,6 +825,37 @@ fn get_memory_type_with_custom_page_size_from_wasm(config: &mut Config) -> Resul
     Ok(())
 }
 
+#[wasmtime_test]
+#[cfg_attr(miri, ignore)]
+fn custom_page_size_larger_than_static_reservation(config: &mut Config) -> Result<()> {
+    config.wasm_custom_page_sizes(true);
+    config.memory_may_move(false);
+    config.memory_reservation(0);
+    config.memory_guard_size(0);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+
+    // The memory's minimum size exceeds the reservation, so accesses beyond
+    // the reservation are not statically out of bounds.
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory 13 (pagesize 1))
+                (func (export "load") (param i32) (result i32)
+                    (i32.load8_u offset=4 (local.get 0)))
+            )
+        "#,
+    )?;
+
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let load = instance.get_typed_func::<u32, u32>(&mut store, "load")?;
+    assert_eq!(load.call(&mut store, 8)?, 0);
+    assert!(load.call(&mut store, 9).is_err());
+
+    Ok(())
+}
+
 #[wasmtime_test]
 fn configure_zero(config: &mut Config) -> Result<()> {
     config.guard_before_linear_memory(false);
@@ -876,3 +907,97 @@ fn atomic_wait_massive_timeout() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn memory_alias_shared_between_instances() -> Result<()> {
+    let mut config = Config::new();
+    config.signals_based_traps(false);
+    config.wasm_multi_memory(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+
+    let memory = Memory::new(&mut store, MemoryType::new(4, Some(4)))?;
+    let scratch = memory.alias(&mut store, 0x20000, 0x10000)?;
+    assert_eq!(scratch.size(&store), 1);
+    assert_eq!(scratch.ty(&store).maximum(), Some(1));
+    assert!(scratch.grow(&mut store, 1).is_err());
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "own" (memory 1))
+                (import "" "scratch" (memory $scratch 1 1))
+                (func (export "store") (param i32 i32)
+                    (i32.store $scratch (local.get 0) (local.get 1)))
+                (func (export "load") (param i32) (result i32)
+                    (i32.load $scratch (local.get 0)))
+            )
+        "#,
+    )?;
+    let own1 = Memory::new(&mut store, MemoryType::new(1, None))?;
+    let own2 = Memory::new(&mut store, MemoryType::new(1, None))?;
+    let producer = Instance::new(&mut store, &module, &[own1.into(), scratch.into()])?;
+    let consumer = Instance::new(&mut store, &module, &[own2.into(), scratch.into()])?;
+
+    let store_i32 = producer.get_typed_func::<(u32, u32), ()>(&mut store, "store")?;
+    let load_i32 = consumer.get_typed_func::<u32, u32>(&mut store, "load")?;
+    store_i32.call(&mut store, (8, 0xdeadbeef))?;
+    assert_eq!(load_i32.call(&mut store, 8)?, 0xdeadbeef);
+    assert_eq!(
+        memory.data(&store)[0x20008..0x2000c],
+        0xdeadbeefu32.to_le_bytes()
+    );
+
+    memory.data_mut(&mut store)[0x20010] = 42;
+    assert_eq!(load_i32.call(&mut store, 0x10)?, 42);
+
+    // Accesses past the end of the alias trap instead of reaching the rest of
+    // the aliased memory.
+    let trap = load_i32.call(&mut store, 0x10000).unwrap_err();
+    assert_eq!(trap.downcast::<Trap>()?, Trap::MemoryOutOfBounds);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn memory_alias_errors() -> Result<()> {
+    let mut config = Config::new();
+    config.signals_based_traps(false);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(2, Some(2)))?;
+
+    assert!(memory.alias(&mut store, 1, 0x10000).is_err());
+    assert!(memory.alias(&mut store, 0, 0x100).is_err());
+    assert!(memory.alias(&mut store, 0x10000, 0x20000).is_err());
+    assert!(
+        memory
+            .alias(&mut store, u64::MAX - 0xffff, 0x10000)
+            .is_err()
+    );
+    memory.alias(&mut store, 0x20000, 0)?;
+
+    // Memories which may move when grown can't be aliased.
+    let growable = Memory::new(&mut store, MemoryType::new(2, None))?;
+    let mut config = Config::new();
+    config.signals_based_traps(false);
+    config.memory_reservation(0);
+    let engine = Engine::new(&config)?;
+    let mut other = Store::new(&engine, ());
+    let movable = Memory::new(&mut other, MemoryType::new(2, None))?;
+    assert!(movable.alias(&mut other, 0, 0x10000).is_err());
+    growable.alias(&mut store, 0, 0x10000)?;
+
+    // Aliases require explicit bounds checks.
+    let mut config = Config::new();
+    config.signals_based_traps(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new(2, Some(2)))?;
+    assert!(memory.alias(&mut store, 0, 0x10000).is_err());
+
+    Ok(())
+}
@@ -650,3 +650,69 @@ fn deserialize_raw_fails_for_native() {
         );
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn spectre_mitigations_per_module() -> Result<()> {
+    // Force explicit bounds checks so that mitigations show up in the
+    // generated code.
+    let mut config = Config::new();
+    config.memory_reservation(0);
+    config.memory_guard_size(0);
+    let engine = Engine::new(&config)?;
+    let wat = r#"
+        (module
+            (memory 1)
+            (table 1 funcref)
+            (func (export "load") (param i32) (result i32)
+                (i32.load (local.get 0)))
+            (func (export "get") (param i32) (result funcref)
+                (table.get (local.get 0)))
+        )
+    "#;
+    let compile = |mitigations| -> Result<Module> {
+        let mut builder = CodeBuilder::new(&engine);
+        builder.wasm_binary_or_text(wat.as_bytes(), None)?;
+        unsafe {
+            builder.spectre_mitigations(mitigations);
+        }
+        builder.compile_module()
+    };
+
+    let module = Module::new(&engine, wat)?;
+    assert_eq!(module.spectre_mitigations(), SpectreMitigations::Enabled);
+
+    let tiers = [
+        SpectreMitigations::Enabled,
+        SpectreMitigations::TablesOnly,
+        SpectreMitigations::Disabled,
+    ];
+    let mut texts = Vec::new();
+    for mitigations in tiers {
+        let module = compile(mitigations)?;
+        assert_eq!(module.spectre_mitigations(), mitigations);
+        texts.push(module.text().to_vec());
+
+        let serialized = module.serialize()?;
+        let module = unsafe { Module::deserialize(&engine, &serialized)? };
+        assert_eq!(module.spectre_mitigations(), mitigations);
+
+        // Out-of-bounds accesses trap regardless of the tier.
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let load = instance.get_typed_func::<u32, i32>(&mut store, "load")?;
+        assert_eq!(load.call(&mut store, 0)?, 0);
+        let trap = load.call(&mut store, 65536).unwrap_err();
+        assert_eq!(trap.downcast::<Trap>()?, Trap::MemoryOutOfBounds);
+        let get = instance.get_typed_func::<u32, Option<Func>>(&mut store, "get")?;
+        assert!(get.call(&mut store, 0)?.is_none());
+        let trap = get.call(&mut store, 1).unwrap_err();
+        assert_eq!(trap.downcast::<Trap>()?, Trap::TableOutOfBounds);
+    }
+
+    // Each tier removes mitigations from the generated code.
+    assert_ne!(texts[0], texts[1]);
+    assert_ne!(texts[1], texts[2]);
+
+    Ok(())
+}
@@ -125,6 +125,43 @@ fn test_deserialize_from_file() -> Result<()> {
     }
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn name_section_survives_serialization() -> Result<()> {
+    let engine = Engine::default();
+    let buffer = serialize(
+        &engine,
+        r#"
+            (module $m
+                (func)
+                (func $f (param $a i32) (local i64) (local $c f32)
+                    block $outer
+                        loop
+                            block $inner
+                            end
+                        end
+                    end)
+                (func $g)
+            )
+        "#,
+    )?;
+    let module = unsafe { Module::deserialize(&engine, &buffer)? };
+    let names = module.names();
+    assert_eq!(names.module(), Some("m"));
+    assert_eq!(names.func(0), None);
+    assert_eq!(names.func(1), Some("f"));
+    assert_eq!(names.funcs().collect::<Vec<_>>(), [(1, "f"), (2, "g")]);
+    assert_eq!(names.locals(1).collect::<Vec<_>>(), [(0, "a"), (2, "c")]);
+    assert_eq!(names.local(1, 1), None);
+    assert_eq!(names.locals(2).count(), 0);
+    assert_eq!(
+        names.labels(1).collect::<Vec<_>>(),
+        [(0, "outer"), (2, "inner")]
+    );
+    assert_eq!(names.label(1, 1), None);
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn deserialize_from_serialized() -> Result<()> {
@@ -154,3 +191,66 @@ fn detect_precompiled() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn precompile_is_deterministic() -> Result<()> {
+    let wat = r#"
+        (module
+            (memory 1)
+            (func $f (param i32) (result i32) local.get 0)
+            (func (export "a") (result i32) (call $f (i32.const 1)))
+            (func (export "b") (result i32) (call $f (i32.load (i32.const 0))))
+        )
+    "#;
+    let precompile = || -> Result<Vec<u8>> {
+        let mut config = Config::new();
+        config.target(&target_lexicon::HOST.to_string())?;
+        config.debug_info(true);
+        let engine = Engine::new(&config)?;
+        engine.precompile_module(wat.as_bytes())
+    };
+    let first = precompile()?;
+    let second = precompile()?;
+    assert!(first == second, "precompiled modules differ");
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn native_unwind_info_disabled_omits_unwind_tables() -> Result<()> {
+    use object::Object;
+
+    let wat = r#"
+        (module
+            (func $f (param i32) (result i32) local.get 0)
+            (func (export "a") (result i32) (call $f (i32.const 1)))
+        )
+    "#;
+    for target in [
+        "x86_64-unknown-linux-gnu",
+        "aarch64-unknown-linux-gnu",
+        "riscv64gc-unknown-linux-gnu",
+    ] {
+        let precompile = |unwind_info: bool| -> Result<Vec<u8>> {
+            let mut config = Config::new();
+            config.target(target)?;
+            config.native_unwind_info(unwind_info);
+            Engine::new(&config)?.precompile_module(wat.as_bytes())
+        };
+        let with = precompile(true)?;
+        let without = precompile(false)?;
+        assert!(
+            object::File::parse(&with[..])?
+                .section_by_name(".eh_frame")
+                .is_some()
+        );
+        assert!(
+            object::File::parse(&without[..])?
+                .section_by_name(".eh_frame")
+                .is_none()
+        );
+        assert!(without.len() < with.len(), "{target}");
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use super::{ErrorExt, skip_pooling_allocator_tests};
+use std::time::Duration;
 use wasmtime::*;
 
 #[test]
@@ -1256,6 +1257,126 @@ fn decommit_batching() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(any(miri, not(target_os = "linux")), ignore)]
+fn decommit_batch_bytes() -> Result<()> {
+    let mut pool = crate::small_pool_config();
+    pool.decommit_batch_size(1000)
+        .decommit_batch_bytes(1)
+        .memory_protection_keys(Enabled::No);
+    let mut config = Config::new();
+    config.allocation_strategy(pool);
+
+    let engine = Engine::new(&config)?;
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    let module = Module::new(&engine, "(module (memory 1 1))")?;
+
+    // Any decommit reaches the byte threshold, so nothing is ever left queued.
+    let mut store = Store::new(&engine, ());
+    Instance::new(&mut store, &module, &[])?;
+    drop(store);
+    assert_eq!(metrics.queued_decommit_bytes(), 0);
+    assert_eq!(metrics.max_queued_decommit_bytes(), 0);
+    assert!(metrics.decommit_batches() > 0);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(any(miri, not(target_os = "linux")), ignore)]
+fn decommit_max_delay() -> Result<()> {
+    let mut pool = crate::small_pool_config();
+    pool.decommit_batch_size(1000)
+        .decommit_max_delay(Some(Duration::from_millis(10)))
+        .memory_protection_keys(Enabled::No);
+    let mut config = Config::new();
+    config.allocation_strategy(pool);
+
+    let engine = Engine::new(&config)?;
+    let metrics = engine.pooling_allocator_metrics().unwrap();
+    let module = Module::new(&engine, "(module (memory 1 1))")?;
+
+    let mut store = Store::new(&engine, ());
+    Instance::new(&mut store, &module, &[])?;
+    drop(store);
+    assert!(metrics.max_queued_decommit_bytes() > 0);
+
+    // Without any further deallocations the background thread should flush
+    // the queue shortly.
+    let start = std::time::Instant::now();
+    while metrics.queued_decommit_bytes() > 0 {
+        assert!(start.elapsed() < Duration::from_secs(10));
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert!(metrics.decommit_batches() > 0);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn decommit_policies_reset_slots() -> Result<()> {
+    for policy in [
+        DecommitPolicy::Release,
+        DecommitPolicy::LazyFree,
+        DecommitPolicy::Zero,
+    ] {
+        for background in [false, true] {
+            let mut pool = crate::small_pool_config();
+            pool.decommit_policy(policy).background_decommit(background);
+            let mut config = Config::new();
+            config.allocation_strategy(pool);
+            let engine = Engine::new(&config)?;
+
+            let with_image = Module::new(
+                &engine,
+                r#"(module (memory (export "m") 1) (data (i32.const 0) "hello"))"#,
+            )?;
+            let without_image = Module::new(
+                &engine,
+                r#"
+                    (module
+                        (memory (export "m") 1)
+                        (table (export "t") 1 funcref)
+                        (func (export "f"))
+                    )
+                "#,
+            )?;
+
+            for module in [&with_image, &without_image] {
+                for _ in 0..3 {
+                    let mut store = Store::new(&engine, ());
+                    let instance = Instance::new(&mut store, module, &[])?;
+                    let memory = instance.get_memory(&mut store, "m").unwrap();
+                    let data = memory.data_mut(&mut store);
+                    if Module::same(module, &with_image) {
+                        assert_eq!(&data[..5], b"hello", "{policy:?} {background}");
+                    } else {
+                        assert_eq!(data[0], 0, "{policy:?} {background}");
+                    }
+                    assert!(data[5..].iter().all(|b| *b == 0), "{policy:?} {background}");
+                    data.fill(0xff);
+
+                    if let Some(table) = instance.get_table(&mut store, "t") {
+                        assert!(
+                            table
+                                .get(&mut store, 0)
+                                .unwrap()
+                                .as_func()
+                                .unwrap()
+                                .is_none()
+                        );
+                        let f = instance.get_func(&mut store, "f").unwrap();
+                        table.set(&mut store, 0, f.into())?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn tricky_empty_table_with_empty_virtual_memory_alloc() -> Result<()> {
@@ -1400,6 +1521,44 @@ fn instantiate_non_page_aligned_sizes() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn custom_page_sizes_respect_max_memory_size() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_custom_page_sizes(true);
+    let mut cfg = crate::small_pool_config();
+    cfg.total_memories(1);
+    cfg.max_memory_size(0x1001);
+    config.allocation_strategy(InstanceAllocationStrategy::Pooling(cfg));
+    let engine = Engine::new(&config)?;
+
+    // The slot is rounded up to the host page size, but memories may not grow
+    // beyond the configured maximum size.
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory (export "m") 0x1000 (pagesize 1))
+                (func (export "grow") (param i32) (result i32)
+                    (memory.grow (local.get 0)))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let grow = instance.get_typed_func::<i32, i32>(&mut store, "grow")?;
+    assert_eq!(grow.call(&mut store, 1)?, 0x1000);
+    assert_eq!(grow.call(&mut store, 1)?, -1);
+    let memory = instance.get_memory(&mut store, "m").unwrap();
+    assert!(memory.grow(&mut store, 1).is_err());
+    assert_eq!(memory.data_size(&store), 0x1001);
+
+    // Memories whose minimum size exceeds the maximum are rejected.
+    let err = Module::new(&engine, "(module (memory 0x1002 (pagesize 1)))").unwrap_err();
+    err.assert_contains("minimum byte size of 4098 which exceeds the limit of 4097 bytes");
+    Ok(())
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn pagemap_scan_enabled_or_disabled() -> Result<()> {
@@ -1556,3 +1715,109 @@ fn purge_module_with_mpk() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn recycle_instances() -> Result<()> {
+    let mut pool = crate::small_pool_config();
+    pool.total_core_instances(1)
+        .total_memories(2)
+        .total_tables(2)
+        .max_memory_size(2 << 16);
+    let mut config = Config::new();
+    config.allocation_strategy(pool);
+    let engine = Engine::new(&config)?;
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "f" (func $f (result i32)))
+                (memory (export "m") 1 2)
+                (data (i32.const 0) "\01")
+                (table (export "t") 1 funcref)
+                (global $g (export "g") (mut i32) (i32.const 0))
+                (func $start (global.set $g (call $f)))
+                (start $start)
+            )
+        "#,
+    )?;
+    let other = Module::new(&engine, "(module)")?;
+
+    let mut store = Store::new(&engine, ());
+    let f1 = Func::wrap(&mut store, || 1_i32);
+    let f2 = Func::wrap(&mut store, || 2_i32);
+
+    let first = Instance::new(&mut store, &module, &[f1.into()])?;
+    let memory = first.get_memory(&mut store, "m").unwrap();
+    let global = first.get_global(&mut store, "g").unwrap();
+    let table = first.get_table(&mut store, "t").unwrap();
+    assert_eq!(global.get(&mut store).unwrap_i32(), 1);
+    memory.data_mut(&mut store)[0] = 42;
+    memory.grow(&mut store, 1)?;
+    table.set(&mut store, 0, f1.into())?;
+
+    // The pool only has room for a single instance.
+    assert!(Instance::new(&mut store, &module, &[f2.into()]).is_err());
+
+    // Marking the instance as recyclable allows it to be reused, and only by
+    // the same module.
+    first.mark_recyclable(&mut store)?;
+    first.mark_recyclable(&mut store)?;
+    assert!(Instance::new(&mut store, &other, &[]).is_err());
+    let second = Instance::new(&mut store, &module, &[f2.into()])?;
+
+    // The reused instance is freshly initialized with its new imports.
+    let memory = second.get_memory(&mut store, "m").unwrap();
+    assert_eq!(memory.size(&store), 1);
+    assert_eq!(memory.data(&store)[0], 1);
+    let global = second.get_global(&mut store, "g").unwrap();
+    assert_eq!(global.get(&mut store).unwrap_i32(), 2);
+    let table = second.get_table(&mut store, "t").unwrap();
+    assert!(table.get(&mut store, 0).unwrap().unwrap_func().is_none());
+
+    // Old handles now alias the new instantiation.
+    let global = first.get_global(&mut store, "g").unwrap();
+    assert_eq!(global.get(&mut store).unwrap_i32(), 2);
+
+    // The instance is no longer recyclable once reused.
+    assert!(Instance::new(&mut store, &module, &[f1.into()]).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn recycle_instances_with_aliased_memories() -> Result<()> {
+    let mut pool = crate::small_pool_config();
+    pool.total_core_instances(1)
+        .total_memories(2)
+        .max_memory_size(2 << 16);
+    let mut config = Config::new();
+    config.allocation_strategy(pool);
+    config.signals_based_traps(false);
+    let engine = Engine::new(&config)?;
+
+    let module = Module::new(&engine, r#"(module (memory (export "m") 1 2))"#)?;
+
+    let mut store = Store::new(&engine, ());
+    let first = Instance::new(&mut store, &module, &[])?;
+    let memory = first.get_memory(&mut store, "m").unwrap();
+
+    // An instance marked recyclable before its memory is aliased isn't reused
+    // once the alias exists.
+    first.mark_recyclable(&mut store)?;
+    let alias = memory.alias(&mut store, 0, 0x10000)?;
+    let alias_of_alias = alias.alias(&mut store, 0, 0x10000)?;
+    assert!(Instance::new(&mut store, &module, &[]).is_err());
+
+    // The aliased memory is still intact.
+    alias.data_mut(&mut store)[0] = 42;
+    assert_eq!(memory.data(&store)[0], 42);
+    assert_eq!(alias_of_alias.data(&store)[0], 42);
+
+    // And it can't be marked recyclable again.
+    assert!(first.mark_recyclable(&mut store).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use wasmtime::*;
+use wasmtime_test_macros::wasmtime_test;
+
+const WAT: &str = r#"
+(module
+  (func $classify (export "classify") (param i32) (result i32)
+    (if (result i32) (local.get 0)
+      (then (i32.const 1))
+      (else (i32.const 2))))
+  (func $never (export "never"))
+  (func (export "count") (param $n i32) (result i32) (local $acc i32)
+    (loop $l
+      (local.set $acc (i32.add (local.get $acc) (call $classify (local.get $n))))
+      (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+      (br_if $l (local.get $n)))
+    local.get $acc)
+)
+"#;
+
+fn run(engine: &Engine, module: &Module) -> Result<()> {
+    let mut store = Store::new(engine, ());
+    let instance = Instance::new(&mut store, module, &[])?;
+    let classify = instance.get_typed_func::<i32, i32>(&mut store, "classify")?;
+    let count = instance.get_typed_func::<i32, i32>(&mut store, "count")?;
+    assert_eq!(classify.call(&mut store, 0)?, 2);
+    assert_eq!(classify.call(&mut store, 7)?, 1);
+    assert_eq!(count.call(&mut store, 5)?, 5);
+    Ok(())
+}
+
+#[wasmtime_test]
+fn records_calls_and_branches(config: &mut Config) -> Result<()> {
+    config.profile_instrumentation(true);
+    let engine = Engine::new(config)?;
+    let module = Module::new(&engine, WAT)?;
+    run(&engine, &module)?;
+
+    let profile = module.profile().unwrap();
+    let classify = FuncIndex::from_u32(0);
+    let never = FuncIndex::from_u32(1);
+    let count = FuncIndex::from_u32(2);
+    assert_eq!(profile.calls(classify), 7);
+    assert_eq!(profile.calls(never), 0);
+    assert_eq!(profile.calls(count), 1);
+
+    // The `if` follows an empty locals vector and a `local.get 0`.
+    let branches = profile.function(classify).unwrap();
+    assert_eq!(branches.branches().len(), 1);
+    assert_eq!(
+        branches.branch(3),
+        Some(BranchCounts {
+            taken: 6,
+            not_taken: 1,
+        })
+    );
+
+    let branches = profile.function(count).unwrap();
+    assert_eq!(branches.branches().len(), 1);
+    let (_, counts) = branches.branches().next().unwrap();
+    assert_eq!(
+        counts,
+        BranchCounts {
+            taken: 4,
+            not_taken: 1,
+        }
+    );
+
+    // Profiles accumulate across instances of the same module.
+    run(&engine, &module)?;
+    assert_eq!(module.profile().unwrap().calls(classify), 14);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn no_profile_without_instrumentation() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, WAT)?;
+    run(&engine, &module)?;
+    assert!(module.profile().is_none());
+    Ok(())
+}
+
+#[wasmtime_test]
+fn recompile_with_profile(config: &mut Config) -> Result<()> {
+    config.profile_instrumentation(true);
+    let engine = Engine::new(config)?;
+    let module = Module::new(&engine, WAT)?;
+    run(&engine, &module)?;
+    let profile = module.profile().unwrap();
+    let profile = WasmProfile::deserialize(&profile.serialize())?;
+
+    let mut config = Config::new();
+    config.compiler_inlining(Inlining::InterModule);
+    let engine = Engine::new(&config)?;
+    let optimized = CodeBuilder::new(&engine)
+        .wasm_binary_or_text(WAT.as_bytes(), None)?
+        .profile(profile.clone())
+        .compile_module()?;
+    run(&engine, &optimized)?;
+
+    let err = CodeBuilder::new(&engine)
+        .wasm_binary_or_text(b"(component)", None)?
+        .profile(profile)
+        .compile_component()
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("can only be used with modules"),
+        "{err:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn instrumentation_must_match_to_deserialize() -> Result<()> {
+    let mut config = Config::new();
+    config.profile_instrumentation(true);
+    let engine = Engine::new(&config)?;
+    let serialized = Module::new(&engine, WAT)?.serialize()?;
+
+    let engine = Engine::default();
+    let err = unsafe { Module::deserialize(&engine, &serialized) }.unwrap_err();
+    assert!(
+        format!("{err:?}").contains("profile instrumentation"),
+        "{err:?}"
+    );
+    Ok(())
+}
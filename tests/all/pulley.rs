@@ -2,7 +2,8 @@ use std::ptr::NonNull;
 use wasmtime::Result;
 use wasmtime::component::{self, Component};
 use wasmtime::{
-    Caller, Config, Engine, Func, FuncType, Instance, Module, Store, Trap, Val, ValType,
+    Caller, Config, Engine, Func, FuncType, Instance, Module, OutOfBoundsDetection, Store, Trap,
+    TrapDelivery, TrapDetails, Val, ValType,
 };
 use wasmtime_environ::TripleExt;
 
@@ -515,3 +516,112 @@ fn decode_unaligned() -> Result<()> {
 
     Ok(())
 }
+
+// Winch doesn't support riscv64, so requesting it there should compile to and
+// run with Pulley instead.
+#[test]
+#[cfg(all(
+    feature = "winch",
+    feature = "cranelift",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+#[cfg_attr(miri, ignore)]
+fn winch_falls_back_to_pulley() -> Result<()> {
+    let mut config = Config::new();
+    config.strategy(wasmtime::Strategy::Winch);
+    config.target("riscv64gc-unknown-linux-gnu")?;
+    let engine = Engine::new(&config)?;
+    assert!(engine.is_pulley());
+    assert_eq!(engine.get_strategy(), Some(wasmtime::Strategy::Cranelift));
+
+    let module = Module::new(
+        &engine,
+        r#"(module (func (export "add") (param i32 i32) (result i32)
+            local.get 0 local.get 1 i32.add))"#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let add = instance.get_typed_func::<(i32, i32), i32>(&mut store, "add")?;
+    assert_eq!(add.call(&mut store, (1, 2))?, 3);
+    Ok(())
+}
+
+// Pulley's stack is reserved up front but only made usable as it's needed, so
+// a large `max_wasm_stack` is usable for deep recursion, and overflowing it is
+// still reported as a stack overflow.
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stack_grows_on_demand() -> Result<()> {
+    let wat = r#"
+        (module
+            (func $recurse (export "recurse") (param i32) (result i32)
+                local.get 0
+                i32.eqz
+                if (result i32)
+                    i32.const 0
+                else
+                    local.get 0
+                    i32.const 1
+                    i32.sub
+                    call $recurse
+                    i32.const 1
+                    i32.add
+                end))
+    "#;
+
+    let mut config = pulley_config();
+    config.max_wasm_stack(64 << 20).async_stack_size(usize::MAX);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, wat)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let recurse = instance.get_typed_func::<i32, i32>(&mut store, "recurse")?;
+    assert_eq!(recurse.call(&mut store, 500_000)?, 500_000);
+    assert_eq!(recurse.call(&mut store, 10)?, 10);
+
+    let mut config = pulley_config();
+    config.max_wasm_stack(64 << 10);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, wat)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let recurse = instance.get_typed_func::<i32, i32>(&mut store, "recurse")?;
+    let err = recurse.call(&mut store, 500_000).unwrap_err();
+    assert_eq!(err.downcast::<Trap>()?, Trap::StackOverflow);
+    assert_eq!(recurse.call(&mut store, 10)?, 10);
+    Ok(())
+}
+
+#[test]
+fn traps_delivered_by_interpreter() -> Result<()> {
+    let engine = Engine::new(&pulley_config())?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory 1)
+                (func (export "load") (param i32) (result i32)
+                    (i32.load (local.get 0)))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let load = instance.get_typed_func::<i32, i32>(&mut store, "load")?;
+
+    let err = load.call(&mut store, 0x10000).unwrap_err();
+    let details = err.downcast_ref::<TrapDetails>().unwrap();
+    assert_eq!(details.trap(), Trap::MemoryOutOfBounds);
+    assert_eq!(details.delivery(), TrapDelivery::Interpreter);
+    assert_eq!(
+        details.out_of_bounds_detection(),
+        Some(OutOfBoundsDetection::BoundsCheck)
+    );
+    assert!(details.fault_memory().is_none());
+
+    let stats = store.trap_stats();
+    assert_eq!(stats.delivered(TrapDelivery::Interpreter), 1);
+    assert_eq!(stats.out_of_bounds(OutOfBoundsDetection::BoundsCheck), 1);
+    Ok(())
+}
@@ -20,3 +20,103 @@ fn into_inner() {
     Store::new(&engine, A).into_data();
     assert_eq!(HITS.load(SeqCst), 2);
 }
+
+#[test]
+fn data_handle_defer() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, Vec::new());
+
+    let handle = store.data_handle();
+    let threads = (0..4)
+        .map(|i| {
+            let handle = handle.clone();
+            std::thread::spawn(move || handle.defer(move |v: &mut Vec<i32>| v.push(i)).unwrap())
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    // Nothing runs until the store rejoins.
+    assert!(store.data().is_empty());
+    let mut seen = store.data_scope(|v| v.clone());
+    seen.sort();
+    assert_eq!(seen, [0, 1, 2, 3]);
+
+    // Closures queued while rejoining run as part of the same rejoin, in
+    // order.
+    let inner = handle.clone();
+    handle
+        .defer(move |v: &mut Vec<i32>| {
+            v.clear();
+            v.push(10);
+            inner.defer(|v| v.push(11)).unwrap();
+        })
+        .unwrap();
+    store.data_scope(|v| v.push(12));
+    assert_eq!(store.data(), &[10, 11, 12]);
+}
+
+#[tokio::test]
+async fn data_handle_with() -> wasmtime::Result<()> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, 1);
+
+    let handle = store.data_handle();
+    let task = tokio::spawn(async move {
+        let a = handle.with(|n: &mut i32| *n * 10).await?;
+        let b = handle.with(|n: &mut i32| std::mem::replace(n, 5)).await?;
+        wasmtime::Result::<_>::Ok((a, b))
+    });
+
+    while !task.is_finished() {
+        store.data_scope(|_| {});
+        tokio::task::yield_now().await;
+    }
+    assert_eq!(task.await??, (10, 1));
+    assert_eq!(*store.data(), 5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn data_handle_store_dropped() {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, 0);
+    let handle = store.data_handle();
+
+    // Work still pending when the store is dropped never runs.
+    let pending = handle.with(|n: &mut i32| *n);
+    assert!(!handle.is_closed());
+    drop(store);
+    assert!(handle.is_closed());
+    assert!(pending.await.is_err());
+
+    assert!(handle.defer(|_| {}).is_err());
+    assert!(handle.with(|n| *n).await.is_err());
+}
+
+#[tokio::test]
+async fn data_handle_from_async_host_func() -> wasmtime::Result<()> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, 0);
+
+    let func = wasmtime::Func::wrap_async(&mut store, |mut caller, (x,): (i32,)| {
+        Box::new(async move {
+            // Hand work off to another task without borrowing the store
+            // across the `await`...
+            let handle = caller.data_handle();
+            tokio::spawn(async move {
+                handle.defer(move |n: &mut i32| *n += x).unwrap();
+            })
+            .await
+            .unwrap();
+
+            // ... and then rejoin to see its results.
+            Ok(caller.data_scope(|n| *n))
+        })
+    });
+    let func = func.typed::<i32, i32>(&store)?;
+    assert_eq!(func.call_async(&mut store, 2).await?, 2);
+    assert_eq!(func.call_async(&mut store, 3).await?, 5);
+    Ok(())
+}
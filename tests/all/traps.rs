@@ -209,6 +209,42 @@ fn test_trap_through_host() -> Result<()> {
     Ok(())
 }
 
+// Backtraces and panics only rely on frame pointers, not native unwind info.
+#[test]
+#[cfg_attr(windows, ignore)]
+fn test_trap_without_native_unwind_info() -> Result<()> {
+    let mut config = Config::default();
+    config.native_unwind_info(false);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::<()>::new(&engine, ());
+    let wat = r#"
+        (module $hello_mod
+            (import "" "" (func $panic))
+            (func (export "run") (call $hello))
+            (func $hello (unreachable))
+            (func (export "panic") (call $panic))
+        )
+    "#;
+
+    let module = Module::new(&engine, wat)?;
+    let panic = Func::wrap(&mut store, || -> () { panic!("this is a panic") });
+    let instance = Instance::new(&mut store, &module, &[panic.into()])?;
+    let run_func = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+
+    let e = run_func.call(&mut store, ()).unwrap_err();
+    let trace = e.downcast_ref::<WasmBacktrace>().unwrap().frames();
+    assert_eq!(trace.len(), 2);
+    assert_eq!(trace[0].func_name(), Some("hello"));
+    assert_eq!(trace[1].func_index(), 1);
+    assert_eq!(e.downcast::<Trap>()?, Trap::UnreachableCodeReached);
+
+    let panic_func = instance.get_typed_func::<(), ()>(&mut store, "panic")?;
+    let err = panic::catch_unwind(AssertUnwindSafe(|| drop(panic_func.call(&mut store, ()))))
+        .unwrap_err();
+    assert_eq!(err.downcast_ref::<&'static str>(), Some(&"this is a panic"));
+    Ok(())
+}
+
 #[test]
 fn test_trap_backtrace_disabled() -> Result<()> {
     let mut config = Config::default();
@@ -1327,15 +1363,18 @@ fn wasm_fault_address_reported_by_default() -> Result<()> {
     };
     let err = Instance::new(&mut store, &module, &[]).unwrap_err();
 
-    // NB: at this time there's no programmatic access to the fault address
-    // because it's not always available for load/store traps. Only static
-    // memories on 32-bit have this information, but bounds-checked memories use
-    // manual trapping instructions and otherwise don't have a means of
-    // communicating the faulting address at this time.
+    // NB: the fault address isn't always available for load/store traps.
+    // Only memories relying on guard regions have this information, while
+    // bounds-checked memories use manual trapping instructions and otherwise
+    // don't have a means of communicating the faulting address at this time.
     //
     // It looks like the exact reported fault address may not be deterministic,
     // so assert that we have the right error message, but not the exact
     // address.
+    let details = err.downcast_ref::<TrapDetails>().unwrap();
+    assert_eq!(details.trap(), Trap::MemoryOutOfBounds);
+    assert!(details.fault_address().is_some());
+    assert_eq!(details.fault_memory_size(), Some(0x10000));
     let err = format!("{err:?}");
     assert!(
         err.contains("memory fault at wasm address ")
@@ -1345,6 +1384,75 @@ fn wasm_fault_address_reported_by_default() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn trap_delivery_reported() -> Result<()> {
+    const WAT: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (func (export "load") (param i32) (result i32)
+                (i32.load (local.get 0)))
+            (func (export "unreachable")
+                unreachable)
+        )
+    "#;
+
+    for signals_based_traps in [true, false] {
+        let mut config = Config::new();
+        config.signals_based_traps(signals_based_traps);
+        let engine = Engine::new(&config)?;
+        let Ok(module) = Module::new(&engine, WAT) else {
+            // Signals-based traps aren't supported on all hosts, so skip that
+            // configuration if it's unavailable.
+            continue;
+        };
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let load = instance.get_typed_func::<i32, i32>(&mut store, "load")?;
+        let unreachable = instance.get_typed_func::<(), ()>(&mut store, "unreachable")?;
+
+        let delivery = if signals_based_traps {
+            TrapDelivery::Signal
+        } else {
+            TrapDelivery::Libcall
+        };
+        // By default 64-bit hosts reserve enough virtual memory to catch all
+        // out-of-bounds accesses with guard regions.
+        let detection = if signals_based_traps && cfg!(target_pointer_width = "64") {
+            OutOfBoundsDetection::GuardRegion
+        } else {
+            OutOfBoundsDetection::BoundsCheck
+        };
+
+        let err = load.call(&mut store, 0x10000).unwrap_err();
+        let details = err.downcast_ref::<TrapDetails>().unwrap();
+        assert_eq!(details.trap(), Trap::MemoryOutOfBounds);
+        assert_eq!(details.delivery(), delivery);
+        assert_eq!(details.func_index(), Some(0));
+        assert_eq!(details.out_of_bounds_detection(), Some(detection));
+        match detection {
+            OutOfBoundsDetection::GuardRegion => {
+                let fault_memory = details.fault_memory().unwrap();
+                assert_eq!(fault_memory.data_ptr(&store), memory.data_ptr(&store));
+            }
+            _ => assert!(details.fault_memory().is_none()),
+        }
+
+        let err = unreachable.call(&mut store, ()).unwrap_err();
+        let details = err.downcast_ref::<TrapDetails>().unwrap();
+        assert_eq!(details.trap(), Trap::UnreachableCodeReached);
+        assert_eq!(details.delivery(), delivery);
+        assert_eq!(details.func_index(), Some(1));
+        assert_eq!(details.out_of_bounds_detection(), None);
+
+        let stats = store.trap_stats();
+        assert_eq!(stats.delivered(delivery), 2);
+        assert_eq!(stats.delivered(TrapDelivery::Interpreter), 0);
+        assert_eq!(stats.out_of_bounds(detection), 1);
+    }
+    Ok(())
+}
+
 #[cfg(target_arch = "x86_64")]
 #[test]
 fn wasm_fault_address_reported_from_mpk_protected_memory() -> Result<()> {
@@ -1877,3 +1985,67 @@ fn test_wasm_backtrace_max_frames() -> Result<()> {
 
     Ok(())
 }
+
+#[wasmtime_test]
+fn trap_details(config: &mut Config) -> Result<()> {
+    config.wasm_backtrace_max_frames(None);
+    let engine = Engine::new(config)?;
+    let mut store = Store::new(&engine, ());
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (func (export "run") (param i32) (result i32)
+                    (call $div (local.get 0)))
+                (func $div (param i32) (result i32)
+                    (i32.div_u (i32.const 1) (local.get 0)))
+            )
+        "#,
+    )?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let run = instance.get_typed_func::<i32, i32>(&mut store, "run")?;
+    let err = run.call(&mut store, 0).unwrap_err();
+    assert!(err.downcast_ref::<WasmBacktrace>().is_none());
+
+    let details = err.downcast_ref::<TrapDetails>().unwrap();
+    assert_eq!(details.trap(), Trap::IntegerDivisionByZero);
+    assert_eq!(details.func_index(), Some(1));
+    let module_offset = details.module_offset().unwrap();
+    let func_offset = details.func_offset().unwrap();
+    assert!(func_offset > 0 && func_offset < module_offset);
+    assert!(details.fault_address().is_none());
+
+    let expected = if engine.is_pulley() {
+        TrapBackend::Pulley
+    } else if engine.get_strategy() == Some(Strategy::Winch) {
+        TrapBackend::Winch
+    } else {
+        TrapBackend::Cranelift
+    };
+    assert_eq!(details.backend(), expected);
+    // With backtraces disabled the details are the outermost context, so the
+    // trap's own message still leads the error's `Display`.
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{} in wasm function 1 at module offset {module_offset:#x} ({expected})",
+            Trap::IntegerDivisionByZero,
+        )
+    );
+    assert!(
+        err.to_string()
+            .starts_with("wasm trap: integer divide by zero")
+    );
+    assert_eq!(
+        *err.root_cause().downcast_ref::<Trap>().unwrap(),
+        Trap::IntegerDivisionByZero
+    );
+
+    // Traps raised by the host don't carry any details.
+    let func = Func::wrap(&mut store, || -> Result<()> {
+        Err(Trap::HeapMisaligned.into())
+    });
+    let err = func.call(&mut store, &[], &mut []).unwrap_err();
+    assert!(err.downcast_ref::<TrapDetails>().is_none());
+    Ok(())
+}
@@ -3,22 +3,43 @@ use wasmtime_test_macros::wasmtime_test;
 
 #[wasmtime_test(strategies(only(Winch)))]
 #[cfg_attr(miri, ignore)]
-fn ensure_compatibility_between_winch_and_table_lazy_init(config: &mut Config) -> Result<()> {
+fn table_eager_init(config: &mut Config) -> Result<()> {
     config.table_lazy_init(false);
-    let result = Engine::new(&config);
-    match result {
-        Ok(_) => {
-            wasmtime::bail!(
-                "Expected incompatibility between the `table_lazy_init` option and Winch"
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $t (func (result i32)))
+                (table $table 3 funcref)
+                (elem (table $table) (i32.const 0) func $one $two)
+                (func $one (result i32) i32.const 1)
+                (func $two (result i32) i32.const 2)
+
+                (func (export "call") (param i32) (result i32)
+                    (call_indirect (type $t) (local.get 0)))
+                (func (export "copy") (param i32 i32)
+                    (table.set $table (local.get 1) (table.get $table (local.get 0))))
+                (func (export "is-null") (param i32) (result i32)
+                    (ref.is_null (table.get $table (local.get 0))))
             )
-        }
-        Err(e) => {
-            assert_eq!(
-                e.to_string(),
-                "Winch requires the table-lazy-init option to be enabled"
-            );
-        }
-    }
+        "#,
+    )?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let call = instance.get_typed_func::<u32, i32>(&mut store, "call")?;
+    let copy = instance.get_typed_func::<(u32, u32), ()>(&mut store, "copy")?;
+    let is_null = instance.get_typed_func::<u32, i32>(&mut store, "is-null")?;
+
+    assert_eq!(call.call(&mut store, 0)?, 1);
+    assert_eq!(call.call(&mut store, 1)?, 2);
+    assert_eq!(is_null.call(&mut store, 2)?, 1);
+    assert!(call.call(&mut store, 2).is_err());
+
+    copy.call(&mut store, (1, 2))?;
+    assert_eq!(is_null.call(&mut store, 2)?, 0);
+    assert_eq!(call.call(&mut store, 2)?, 2);
 
     Ok(())
 }
@@ -47,20 +68,61 @@ fn ensure_compatibility_between_winch_and_signals_based_traps(config: &mut Confi
 
 #[wasmtime_test(strategies(only(Winch)))]
 #[cfg_attr(miri, ignore)]
-fn ensure_compatibility_between_winch_and_debug_native(config: &mut Config) -> Result<()> {
-    config.debug_info(true);
-    let result = Engine::new(&config);
-    match result {
+fn ensure_compatibility_between_winch_and_epoch_check_interval(config: &mut Config) -> Result<()> {
+    config.epoch_interruption(true);
+    config.epoch_interruption_check_interval(Some(std::num::NonZeroU32::new(1000).unwrap()));
+    match Engine::new(&config) {
         Ok(_) => {
-            wasmtime::bail!("Expected incompatibility between the `debug_native` option and Winch")
+            wasmtime::bail!(
+                "Expected incompatibility between the epoch interruption check interval and Winch"
+            )
         }
         Err(e) => {
             assert_eq!(
                 e.to_string(),
-                "Winch does not currently support generating native debug information"
+                "Winch does not currently support an epoch interruption check interval"
             );
         }
     }
 
+    // The default of no interval remains supported.
+    config.epoch_interruption_check_interval(None);
+    Engine::new(&config)?;
+
+    Ok(())
+}
+
+#[wasmtime_test(strategies(only(Winch)))]
+#[cfg_attr(miri, ignore)]
+fn emits_native_debug_info(config: &mut Config) -> Result<()> {
+    use object::{Object, ObjectSection};
+
+    config.debug_info(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (func (export "add") (param i32 i64) (result i64)
+                    (local f32 f64)
+                    local.get 1
+                    local.get 0
+                    i64.extend_i32_u
+                    i64.add))
+        "#,
+    )?;
+
+    let bytes = module.serialize()?;
+    let obj = object::File::parse(&bytes[..])?;
+    let debug_info = obj
+        .section_by_name(".debug_info")
+        .expect("missing .debug_info section");
+    assert!(!debug_info.data()?.is_empty());
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let add = instance.get_typed_func::<(i32, i64), i64>(&mut store, "add")?;
+    assert_eq!(add.call(&mut store, (1, 2))?, 3);
+
     Ok(())
 }
@@ -0,0 +1,118 @@
+;;! target = "aarch64"
+;;! test = "compile"
+;;! flags = "-C sign-return-address"
+;;! objdump = "--funcs all --traps=false"
+
+(module
+  (import "" "f" (func $f))
+  (func (export "g") call $f))
+
+;; wasm[0]::function[1]:
+;;       paciaz
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       ldur    x16, [x2, #8]
+;;       ldur    x16, [x16, #0x18]
+;;       add     x16, x16, #0x10
+;;       cmp     sp, x16
+;;       b.lo    #0x40
+;;   20: ldr     x4, [x2, #0x38]
+;;       mov     x6, x2
+;;       ldr     x2, [x6, #0x48]
+;;       mov     x3, x6
+;;       blr     x4
+;;   34: ldp     x29, x30, [sp], #0x10
+;;       autiaz
+;;       ret
+;;   40: udf     #0xc11f
+;;
+;; wasm[0]::array_to_wasm_trampoline[1]:
+;;       paciasp
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       stp     x27, x28, [sp, #-0x10]!
+;;       stp     x25, x26, [sp, #-0x10]!
+;;       stp     x23, x24, [sp, #-0x10]!
+;;       stp     x21, x22, [sp, #-0x10]!
+;;       stp     x19, x20, [sp, #-0x10]!
+;;       stp     d14, d15, [sp, #-0x10]!
+;;       stp     d12, d13, [sp, #-0x10]!
+;;       stp     d10, d11, [sp, #-0x10]!
+;;       stp     d8, d9, [sp, #-0x10]!
+;;       sub     sp, sp, #0x10
+;;       mov     x3, x1
+;;       mov     x12, x29
+;;       ldr     x15, [x0, #8]
+;;       str     x12, [x15, #0x48]
+;;       mov     x13, sp
+;;       str     x13, [x15, #0x40]
+;;       adr     x14, #0xdc
+;;       str     x14, [x15, #0x50]
+;;       mov     x2, x0
+;;       stur    x15, [sp]
+;;       bl      #0
+;;       ├─╼ exception frame offset: SP = FP - 0xa0
+;;       ╰─╼ exception handler: default handler, no dynamic context, handler=0xdc
+;;   a4: mov     w0, #1
+;;       add     sp, sp, #0x10
+;;       ldp     d8, d9, [sp], #0x10
+;;       ldp     d10, d11, [sp], #0x10
+;;       ldp     d12, d13, [sp], #0x10
+;;       ldp     d14, d15, [sp], #0x10
+;;       ldp     x19, x20, [sp], #0x10
+;;       ldp     x21, x22, [sp], #0x10
+;;       ldp     x23, x24, [sp], #0x10
+;;       ldp     x25, x26, [sp], #0x10
+;;       ldp     x27, x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       autiasp
+;;       ret
+;;   dc: mov     x0, #1
+;;   e0: ldur    x15, [sp]
+;;   e4: str     x0, [x15, #0x88]
+;;   e8: mov     w0, #0
+;;   ec: add     sp, sp, #0x10
+;;   f0: ldp     d8, d9, [sp], #0x10
+;;   f4: ldp     d10, d11, [sp], #0x10
+;;   f8: ldp     d12, d13, [sp], #0x10
+;;   fc: ldp     d14, d15, [sp], #0x10
+;;  100: ldp     x19, x20, [sp], #0x10
+;;  104: ldp     x21, x22, [sp], #0x10
+;;  108: ldp     x23, x24, [sp], #0x10
+;;  10c: ldp     x25, x26, [sp], #0x10
+;;  110: ldp     x27, x28, [sp], #0x10
+;;  114: ldp     x29, x30, [sp], #0x10
+;;  118: autiasp
+;;  11c: ret
+;;
+;; signatures[0]::wasm_to_array_trampoline:
+;;       paciaz
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       str     x19, [sp, #-0x10]!
+;;       mov     x19, x3
+;;       mov     x12, x29
+;;       ldr     x11, [x3, #8]
+;;       str     x12, [x11, #0x30]
+;;       ldur    x30, [x29, #8]
+;;       xpaclri
+;;       mov     x14, x30
+;;       str     x14, [x11, #0x38]
+;;       ldr     x15, [x2, #8]
+;;       mov     x0, x2
+;;       mov     x2, sp
+;;       mov     x3, #0
+;;       mov     x1, x19
+;;       blr     x15
+;;  168: tst     w0, #0xff
+;;       b.eq    #0x180
+;;  170: ldr     x19, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       autiaz
+;;       ret
+;;  180: mov     x3, x19
+;;  184: ldr     x0, [x3, #0x10]
+;;  188: ldr     x1, [x0, #0x148]
+;;  18c: mov     x0, x3
+;;  190: blr     x1
+;;  194: udf     #0xc11f
@@ -0,0 +1,61 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! flags = [
+;;!   "-Ccranelift-enable-bounds-check-elimination",
+;;!   "-Ccranelift-enable-heap-access-spectre-mitigation",
+;;!   "-Oopt-level=2",
+;;!   "-Omemory-reservation=0",
+;;!   "-Omemory-guard-size=0",
+;;! ]
+
+;; Neighboring loads from the same index share a single bounds check.
+
+(module
+  (memory 1)
+  (func (param i32) (result i32)
+    (i32.add
+      (i32.add (i32.load offset=0 (local.get 0)) (i32.load offset=4 (local.get 0)))
+      (i32.add (i32.load offset=8 (local.get 0)) (i32.load offset=12 (local.get 0)))))
+)
+;; function u0:0(i64 vmctx, i64, i32) -> i32 tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     region2 = 603979776 "VMMemoryDefinition+0x0"
+;;     region3 = 603979784 "VMMemoryDefinition+0x8"
+;;     region4 = 201326592 "DefinedMemory(StaticModuleIndex(0), DefinedMemoryIndex(0))"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0020                               v3 = uextend.i64 v2
+;; @0020                               v4 = iconst.i64 4
+;; @0020                               v52 = iconst.i64 16
+;; @0020                               v53 = uadd_overflow_trap v3, v52, heap_oob  ; v52 = 16
+;; @0020                               v6 = load.i64 notrap aligned region3 v0+64
+;; @0020                               v8 = load.i64 notrap aligned can_move region2 v0+56
+;; @0020                               v10 = iconst.i64 0
+;; @0020                               v9 = iadd v8, v3
+;; @0020                               v54 = icmp ugt v53, v6
+;; @0020                               v55 = select_spectre_guard v54, v10, v9  ; v10 = 0
+;; @0020                               v12 = load.i32 little region4 v55
+;; @0025                               v14 = iconst.i64 8
+;; @0025                               v21 = iadd v9, v4  ; v4 = 4
+;; @0025                               v56 = select_spectre_guard v54, v10, v21  ; v10 = 0
+;; @0025                               v24 = load.i32 little region4 v56
+;; @002b                               v27 = iconst.i64 12
+;; @002b                               v34 = iadd v9, v14  ; v14 = 8
+;; @002b                               v57 = select_spectre_guard v54, v10, v34  ; v10 = 0
+;; @002b                               v37 = load.i32 little region4 v57
+;; @0030                               v46 = iadd v9, v27  ; v27 = 12
+;; @0030                               v58 = select_spectre_guard v54, v10, v46  ; v10 = 0
+;; @0030                               v49 = load.i32 little region4 v58
+;; @0035                               jump block1
+;;
+;;                                 block1:
+;; @0028                               v25 = iadd.i32 v12, v24
+;; @0033                               v50 = iadd.i32 v37, v49
+;; @0034                               v51 = iadd v25, v50
+;; @0035                               return v51
+;; }
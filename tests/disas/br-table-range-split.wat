@@ -0,0 +1,81 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! flags = "-C cranelift-wasmtime_br_table_range_split=8"
+
+;; A `br_table` shaped like an interpreter's dispatch table, where a long run of
+;; entries all branch to the same target. That run is split out into a range
+;; check and the remaining entries form two small jump tables.
+(module
+  (func (param i32) (result i32)
+    (block $default
+      (block $d
+        (block $c
+          (block $b
+            (block $a
+              (br_table $a $b $c $a $b $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $a $b $default $c $default (local.get 0))
+            )
+            (return (i32.const 0))
+          )
+          (return (i32.const 1))
+        )
+        (return (i32.const 2))
+      )
+      (return (i32.const 3))
+    )
+    (i32.const -1)
+  )
+)
+;; function u0:0(i64 vmctx, i64, i32) -> i32 tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0025                               v3 = iconst.i32 37
+;; @0025                               v4 = icmp uge v2, v3  ; v3 = 37
+;; @0025                               brif v4, block8, block7
+;;
+;;                                 block8:
+;; @0025                               v5 = iconst.i32 -37
+;; @0025                               v6 = iadd.i32 v2, v5  ; v5 = -37
+;; @0025                               br_table v6, block2, [block6, block5, block2, block4]
+;;
+;;                                 block7:
+;; @0025                               v7 = iconst.i32 5
+;; @0025                               v8 = icmp.i32 uge v2, v7  ; v7 = 5
+;; @0025                               brif v8, block10, block9
+;;
+;;                                 block10:
+;; @0025                               v9 = iconst.i32 36
+;; @0025                               v10 = icmp.i32 ule v2, v9  ; v9 = 36
+;; @0025                               brif v10, block3, block2
+;;
+;;                                 block9:
+;; @0025                               br_table v2, block2, [block6, block5, block4, block6, block5]
+;;
+;;                                 block6:
+;; @0052                               v11 = iconst.i32 0
+;; @0054                               return v11  ; v11 = 0
+;;
+;;                                 block5:
+;; @0056                               v12 = iconst.i32 1
+;; @0058                               return v12  ; v12 = 1
+;;
+;;                                 block4:
+;; @005a                               v13 = iconst.i32 2
+;; @005c                               return v13  ; v13 = 2
+;;
+;;                                 block3:
+;; @005e                               v14 = iconst.i32 3
+;; @0060                               return v14  ; v14 = 3
+;;
+;;                                 block2:
+;; @0064                               jump block1
+;;
+;;                                 block1:
+;; @0062                               v15 = iconst.i32 -1
+;; @0064                               return v15  ; v15 = -1
+;; }
@@ -0,0 +1,114 @@
+;;! target = "x86_64"
+;;! test = "compile"
+;;! flags = "-C cranelift-wasmtime_br_table_range_split=64"
+
+;; A large `br_table` with no runs long enough to be split out, but whose
+;; 32-entry chunks either all branch to one target or repeat the same pattern.
+;; It is emitted as a first-level jump table over the chunks and a single
+;; shared second-level jump table.
+(module
+  (func (param i32) (result i32)
+    (block $default
+      (block $d
+        (block $c
+          (block $b
+            (block $a
+              (br_table $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $c $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $a $b $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $d $default (local.get 0))
+            )
+            (return (i32.const 0))
+          )
+          (return (i32.const 1))
+        )
+        (return (i32.const 2))
+      )
+      (return (i32.const 3))
+    )
+    (i32.const -1)
+  )
+)
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    %rdx, %rax
+;;       shrl    $5, %eax
+;;       movl    $4, %ecx
+;;       cmpl    %ecx, %eax
+;;       cmovbl  %eax, %ecx
+;;       leaq    9(%rip), %rsi
+;;       movslq  (%rsi, %rcx, 4), %rax
+;;       addq    %rax, %rsi
+;;       jmpq    *%rsi
+;;   24: subl    $0x23000000, %eax
+;;       addb    %al, (%rax)
+;;       addb    %ch, 0x19000000(%rip)
+;;       addb    %al, (%rax)
+;;       addb    %ah, %ah
+;;       addb    %al, (%rax)
+;;       addb    %ch, %cl
+;;       lretl
+;;   3a: addb    %al, (%rax)
+;;   3c: addb    %bh, 3(%rax)
+;;   42: movq    %rbp, %rsp
+;;   45: popq    %rbp
+;;   46: retq
+;;   47: movl    $2, %eax
+;;   4c: movq    %rbp, %rsp
+;;   4f: popq    %rbp
+;;   50: retq
+;;   51: andl    $0x1f, %edx
+;;   54: movl    $0x20, %eax
+;;   59: cmpl    %eax, %edx
+;;   5b: cmovbl  %edx, %eax
+;;   5e: leaq    9(%rip), %rcx
+;;   65: movslq  (%rcx, %rax, 4), %rax
+;;   69: addq    %rax, %rcx
+;;   6c: jmpq    *%rcx
+;;   6e: xchgl   %eax, %ebx
+;;   6f: addb    %al, (%rax)
+;;   71: addb    %cl, -0x6d000000(%rcx)
+;;   77: addb    %al, (%rax)
+;;   79: addb    %cl, -0x6d000000(%rcx)
+;;   7f: addb    %al, (%rax)
+;;   81: addb    %cl, -0x6d000000(%rcx)
+;;   87: addb    %al, (%rax)
+;;   89: addb    %cl, -0x6d000000(%rcx)
+;;   8f: addb    %al, (%rax)
+;;   91: addb    %cl, -0x6d000000(%rcx)
+;;   97: addb    %al, (%rax)
+;;   99: addb    %cl, -0x6d000000(%rcx)
+;;   9f: addb    %al, (%rax)
+;;   a1: addb    %cl, -0x6d000000(%rcx)
+;;   a7: addb    %al, (%rax)
+;;   a9: addb    %cl, -0x6d000000(%rcx)
+;;   af: addb    %al, (%rax)
+;;   b1: addb    %cl, -0x6d000000(%rcx)
+;;   b7: addb    %al, (%rax)
+;;   b9: addb    %cl, -0x6d000000(%rcx)
+;;   bf: addb    %al, (%rax)
+;;   c1: addb    %cl, -0x6d000000(%rcx)
+;;   c7: addb    %al, (%rax)
+;;   c9: addb    %cl, -0x6d000000(%rcx)
+;;   cf: addb    %al, (%rax)
+;;   d1: addb    %cl, -0x6d000000(%rcx)
+;;   d7: addb    %al, (%rax)
+;;   d9: addb    %cl, -0x6d000000(%rcx)
+;;   df: addb    %al, (%rax)
+;;   e1: addb    %cl, -0x6d000000(%rcx)
+;;   e7: addb    %al, (%rax)
+;;   e9: addb    %cl, -0x66000000(%rcx)
+;;   ef: addb    %al, (%rax)
+;;   f1: addb    %ch, %cl
+;;   f3: adcl    %eax, (%rax)
+;;   f5: addb    %al, (%rax)
+;;   f7: movl    $1, %eax
+;;   fc: movq    %rbp, %rsp
+;;   ff: popq    %rbp
+;;  100: retq
+;;  101: xorl    %eax, %eax
+;;  103: movq    %rbp, %rsp
+;;  106: popq    %rbp
+;;  107: retq
+;;  108: movl    $0xffffffff, %eax
+;;  10d: movq    %rbp, %rsp
+;;  110: popq    %rbp
+;;  111: retq
@@ -0,0 +1,134 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! filter = "wasm[1]--function"
+;;! flags = "-C inlining=n -C cranelift-wasmtime_inlining_adapter_size=1000 -Wconcurrency-support=n"
+
+;; Same as `direct-adapter-calls.wat`, except that small fused adapters are
+;; inlined. General inlining is disabled, so the adapter should be inlined into
+;; `g'` but the adapter's call to `f'` should remain.
+
+(component
+  (component $A
+    (core module $M
+      (func (export "f'") (param i32) (result i32)
+        (i32.add (local.get 0) (i32.const 42))
+      )
+    )
+
+    (core instance $m (instantiate $M))
+
+    (func (export "f") (param "x" u32) (result u32)
+      (canon lift (core func $m "f'"))
+    )
+  )
+
+  (component $B
+    (import "f" (func $f (param "x" u32) (result u32)))
+
+    (core func $f' (canon lower (func $f)))
+
+    (core module $N
+      (import "" "f'" (func $f' (param i32) (result i32)))
+      (func (export "g'") (result i32)
+        (call $f' (i32.const 1234))
+      )
+    )
+
+    (core instance $n
+      (instantiate $N
+        (with "" (instance (export "f'" (func $f'))))
+      )
+    )
+
+    (func (export "g") (result u32)
+      (canon lift (core func $n "g'"))
+    )
+  )
+
+  (instance $a (instantiate $A))
+  (instance $b
+    (instantiate $B
+      (with "f" (func $a "f"))
+    )
+  )
+
+  (export "g" (func $b "g"))
+)
+;; function u1:0(i64 vmctx, i64) -> i32 tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     region2 = 1207959576 "VMFunctionImport+0x18"
+;;     region3 = 1476395008 "VMGlobalImport+0x0"
+;;     region4 = 402653184 "PublicGlobal"
+;;     region5 = 1207959560 "VMFunctionImport+0x8"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     gv3 = vmctx
+;;     gv4 = load.i64 notrap aligned readonly can_move region0 gv3+8
+;;     gv5 = load.i64 notrap aligned region1 gv4+24
+;;     sig0 = (i64 vmctx, i64, i32) -> i32 tail
+;;     sig1 = (i64 vmctx, i64, i32) tail
+;;     sig2 = (i64 vmctx, i64, i32) -> i32 tail
+;;     fn0 = colocated u2:0 sig0
+;;     fn1 = colocated u0:0 sig2
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64):
+;; @00ee                               jump block2
+;;
+;;                                 block2:
+;;                                     jump block6
+;;
+;;                                 block8(v5: i64):
+;;                                     jump block5
+;;
+;;                                 block6:
+;; @00ee                               v3 = load.i64 notrap aligned readonly can_move region2 v0+72
+;;                                     v9 = load.i64 notrap aligned readonly can_move region3 v3+136
+;;                                     v10 = load.i32 notrap aligned region4 v9
+;;                                     brif v10, block9, block10
+;;
+;;                                 block10:
+;;                                     v26 = load.i64 notrap aligned readonly can_move region5 v3+88
+;;                                     v25 = load.i64 notrap aligned readonly can_move region2 v3+104
+;;                                     v24 = iconst.i32 23
+;;                                     try_call_indirect v26(v25, v3, v24), sig1, block11, [ context v3, default: block8(exn0) ]  ; v24 = 23
+;;
+;;                                 block11:
+;;                                     trap user12
+;;
+;;                                 block9:
+;;                                     v11 = load.i64 notrap aligned readonly can_move region3 v3+112
+;;                                     v12 = load.i32 notrap aligned region4 v11
+;;                                     store notrap aligned region4 v12, v11
+;;                                     v16 = load.i64 notrap aligned readonly can_move region2 v3+72
+;; @00eb                               v2 = iconst.i32 1234
+;;                                     try_call fn1(v16, v3, v2), sig2, block12(ret0), [ context v3, default: block8(exn0) ]  ; v2 = 1234
+;;
+;;                                 block12(v6: i32):
+;;                                     store.i32 notrap aligned region4 v10, v9
+;;                                     jump block7
+;;
+;;                                 block7:
+;;                                     jump block4
+;;
+;;                                 block5:
+;;                                     v27 = load.i64 notrap aligned readonly can_move region5 v3+88
+;;                                     v28 = load.i64 notrap aligned readonly can_move region2 v3+104
+;;                                     v18 = iconst.i32 49
+;;                                     call_indirect sig1, v27(v28, v3, v18)  ; v18 = 49
+;;                                     trap user12
+;;
+;;                                 block4:
+;;                                     jump block3
+;;
+;;                                 block3:
+;;                                     jump block13(v6)
+;;
+;;                                 block13(v7: i32):
+;; @00f0                               jump block1
+;;
+;;                                 block1:
+;; @00f0                               return v7
+;; }
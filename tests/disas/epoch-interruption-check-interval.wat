@@ -0,0 +1,71 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! flags = ["-Wepoch-interruption=y", "-Wepoch-interruption-check-interval=2"]
+
+(module
+  (func (param i32) (result i32)
+    (i32.add (local.get 0) (i32.const 1))
+    (i32.mul (i32.const 3))))
+;; function u0:0(i64 vmctx, i64, i32) -> i32 tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     region2 = 24 "VMContext+0x18"
+;;     region3 = 1744830464 "EpochCounter+0x0"
+;;     region4 = 67108872 "VMStoreContext+0x8"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     sig0 = (i64 vmctx) -> i64 tail
+;;     fn0 = colocated u805306368:13 sig0
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0018                               v3 = load.i64 notrap aligned region2 v0+24
+;; @0018                               v4 = load.i64 notrap aligned region3 v3
+;; @0018                               v5 = load.i64 notrap aligned readonly can_move region0 v0+8
+;; @0018                               v6 = load.i64 notrap aligned region4 v5+8
+;; @0018                               v7 = icmp uge v4, v6
+;; @0018                               brif v7, block3, block2(v6)
+;;
+;;                                 block3 cold:
+;; @0018                               v8 = call fn0(v0)
+;; @0018                               jump block2(v8)
+;;
+;;                                 block2(v12: i64):
+;; @001b                               v11 = load.i64 notrap aligned region3 v3
+;; @001b                               v13 = icmp uge v11, v12
+;; @001b                               brif v13, block5, block4(v12)
+;;
+;;                                 block5 cold:
+;; @001b                               v15 = load.i64 notrap aligned region4 v5+8
+;; @001b                               v16 = icmp.i64 uge v11, v15
+;; @001b                               brif v16, block6, block4(v15)
+;;
+;;                                 block6 cold:
+;; @001b                               v17 = call fn0(v0)
+;; @001b                               jump block4(v17)
+;;
+;;                                 block4(v22: i64):
+;; @001e                               v21 = load.i64 notrap aligned region3 v3
+;; @001e                               v23 = icmp uge v21, v22
+;; @001e                               brif v23, block8, block7
+;;
+;;                                 block8 cold:
+;; @001e                               v25 = load.i64 notrap aligned region4 v5+8
+;; @001e                               v26 = icmp.i64 uge v21, v25
+;; @001e                               brif v26, block9, block7
+;;
+;;                                 block9 cold:
+;; @001e                               v27 = call fn0(v0)
+;; @001e                               jump block7
+;;
+;;                                 block7:
+;; @0021                               jump block1
+;;
+;;                                 block1:
+;; @001b                               v18 = iconst.i32 1
+;; @001d                               v19 = iadd.i32 v2, v18  ; v18 = 1
+;; @001e                               v28 = iconst.i32 3
+;; @0020                               v29 = imul v19, v28  ; v28 = 3
+;; @0021                               return v29
+;; }
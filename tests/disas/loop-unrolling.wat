@@ -0,0 +1,72 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! flags = ["-Ccranelift-enable-loop-unrolling", "-Oopt-level=2"]
+
+;; A loop over the four lanes of a vector stored in memory is fully unrolled,
+;; leaving four loads from constant offsets of the parameter.
+
+(module
+  (memory 1)
+  (func (param i32) (result i32)
+    (local $i i32)
+    (local $sum i32)
+    (loop $lanes
+      (local.set $sum
+        (i32.add
+          (local.get $sum)
+          (i32.load
+            (i32.add (local.get 0) (i32.shl (local.get $i) (i32.const 2))))))
+      (br_if $lanes
+        (i32.lt_u
+          (local.tee $i (i32.add (local.get $i) (i32.const 1)))
+          (i32.const 4))))
+    (local.get $sum))
+)
+;; function u0:0(i64 vmctx, i64, i32) -> i32 tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     region2 = 603979776 "VMMemoryDefinition+0x0"
+;;     region3 = 603979784 "VMMemoryDefinition+0x8"
+;;     region4 = 201326592 "DefinedMemory(StaticModuleIndex(0), DefinedMemoryIndex(0))"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0020                               jump block2
+;;
+;;                                 block2:
+;; @002c                               v11 = load.i64 notrap aligned readonly can_move region2 v0+56
+;; @002c                               v10 = uextend.i64 v2
+;; @002c                               v12 = iadd v11, v10
+;; @002c                               v13 = load.i32 little region4 v12
+;; @0039                               v17 = iconst.i32 4
+;; @002b                               v22 = iadd.i32 v2, v17  ; v17 = 4
+;; @002c                               v23 = uextend.i64 v22
+;; @002c                               v25 = iadd v11, v23
+;; @002c                               v26 = load.i32 little region4 v25
+;;                                     v81 = iconst.i32 8
+;; @002b                               v35 = iadd.i32 v2, v81  ; v81 = 8
+;; @002c                               v36 = uextend.i64 v35
+;; @002c                               v38 = iadd v11, v36
+;; @002c                               v39 = load.i32 little region4 v38
+;;                                     v93 = iconst.i32 12
+;; @002b                               v48 = iadd.i32 v2, v93  ; v93 = 12
+;; @002c                               v49 = uextend.i64 v48
+;; @002c                               v51 = iadd v11, v49
+;; @002c                               v52 = load.i32 little region4 v51
+;; @003c                               jump block4
+;;
+;;                                 block4:
+;; @003e                               jump block3
+;;
+;;                                 block3:
+;; @0041                               jump block1
+;;
+;;                                 block1:
+;; @002f                               v27 = iadd.i32 v13, v26
+;; @002f                               v40 = iadd v27, v39
+;; @002f                               v53 = iadd v40, v52
+;; @0041                               return v53
+;; }
@@ -54,128 +54,128 @@
 )
 ;; wasm[0]::function[0]::load8:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload8_u32_g32 x0, x6, x5, x2, 0
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[1]::load16:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload16le_u32_g32 x0, x6, x5, x2, 0
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[2]::load32:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload32le_g32 x0, x6, x5, x2, 0
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[3]::load64:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload64le_g32 x0, x6, x5, x2, 0
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[4]::store8:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore8_g32 x6, x5, x2, 0, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[5]::store16:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore16le_g32 x6, x5, x2, 0, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[6]::store32:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore32le_g32 x6, x5, x2, 0, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[7]::store64:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore64le_g32 x6, x5, x2, 0, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[8]::load8_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload8_u32_g32 x0, x6, x5, x2, 32
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[9]::load16_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload16le_u32_g32 x0, x6, x5, x2, 32
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[10]::load32_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload32le_g32 x0, x6, x5, x2, 32
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[11]::load64_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xload64le_g32 x0, x6, x5, x2, 32
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[12]::store8_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore8_g32 x6, x5, x2, 8, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[13]::store16_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore16le_g32 x6, x5, x2, 8, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[14]::store32_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore32le_g32 x6, x5, x2, 8, x3
 ;;       pop_frame
 ;;       ret
 ;;
 ;; wasm[0]::function[15]::store64_offset:
 ;;       push_frame
-;;       xload32le_o32 x5, x0, 32
-;;       xload32le_o32 x6, x0, 28
+;;       xload32le_aligned_o32 x5, x0, 32
+;;       xload32le_aligned_o32 x6, x0, 28
 ;;       xstore64le_g32 x6, x5, x2, 8, x3
 ;;       pop_frame
 ;;       ret
@@ -0,0 +1,64 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! flags = ["-Ccranelift-enable-switch-recognition", "-Oopt-level=2"]
+
+;; A chain of `br_if`s comparing the same local against constants, including
+;; through `i32.eqz`, is dispatched through a single jump table.
+
+(module
+  (func (param i32) (result i32)
+    (block $four
+      (block $three
+        (block $two
+          (block $one
+            (block $zero
+              (br_if $one (i32.eq (local.get 0) (i32.const 1)))
+              (br_if $zero (i32.eqz (local.get 0)))
+              (br_if $three (i32.eqz (i32.ne (local.get 0) (i32.const 3))))
+              (br_if $two (i32.eq (i32.const 2) (local.get 0)))
+              (br_if $four (i32.eq (local.get 0) (i32.const 4)))
+              (return (i32.const -1)))
+            (return (i32.const 100)))
+          (return (i32.const 101)))
+        (return (i32.const 102)))
+      (return (i32.const 103)))
+    (i32.const 104))
+)
+;; function u0:0(i64 vmctx, i64, i32) -> i32 tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32):
+;; @0028                               br_table v2, block11, [block6, block5, block4, block3, block2]
+;;
+;;                                 block11:
+;; @0045                               v21 = iconst.i32 -1
+;; @0047                               return v21  ; v21 = -1
+;;
+;;                                 block6:
+;; @0049                               v22 = iconst.i32 100
+;; @004c                               return v22  ; v22 = 100
+;;
+;;                                 block5:
+;; @004e                               v23 = iconst.i32 101
+;; @0051                               return v23  ; v23 = 101
+;;
+;;                                 block4:
+;; @0053                               v24 = iconst.i32 102
+;; @0056                               return v24  ; v24 = 102
+;;
+;;                                 block3:
+;; @0058                               v25 = iconst.i32 103
+;; @005b                               return v25  ; v25 = 103
+;;
+;;                                 block2:
+;; @0060                               jump block1
+;;
+;;                                 block1:
+;; @005d                               v26 = iconst.i32 104
+;; @0060                               return v26  ; v26 = 104
+;; }
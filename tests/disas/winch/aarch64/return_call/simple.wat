@@ -0,0 +1,59 @@
+;;! target = "aarch64"
+;;! test = "winch"
+;;! flags = "-Wtail-call"
+
+(module
+  (func $count (param i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 0))
+      (else (return_call $count (i64.sub (local.get 0) (i64.const 1))))
+    )
+  )
+)
+;; wasm[0]::function[0]::count:
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       str     x28, [sp, #-0x10]!
+;;       mov     x28, sp
+;;       ldur    x16, [x0, #8]
+;;       ldur    x16, [x16, #0x18]
+;;       mov     x17, #0
+;;       movk    x17, #0x20
+;;       add     x16, x16, x17
+;;       cmp     sp, x16
+;;       b.lo    #0xb4
+;;   2c: mov     x9, x0
+;;       sub     x28, x28, #0x18
+;;       mov     sp, x28
+;;       stur    x0, [x28, #0x10]
+;;       stur    x1, [x28, #8]
+;;       stur    x2, [x28]
+;;       ldur    x0, [x28]
+;;       cmp     x0, #0
+;;       cset    x0, eq
+;;       tst     w0, w0
+;;       b.eq    #0x64
+;;       b       #0x5c
+;;   5c: ldur    x0, [x28]
+;;       b       #0x9c
+;;   64: ldur    x0, [x28]
+;;       sub     x0, x0, #1
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x0, [x28]
+;;       ldur    x2, [x28]
+;;       mov     x0, x9
+;;       mov     x1, x9
+;;       add     x28, x28, #0x20
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       b       #0
+;;   9c: add     x28, x28, #0x18
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       ret
+;;   b4: udf     #0xc11f
@@ -0,0 +1,140 @@
+;;! target = "aarch64"
+;;! test = "winch"
+;;! flags = "-Wtail-call"
+
+(module
+  (func $callee (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64) (result i64)
+    (local.get 9)
+  )
+
+  (func (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64) (result i64)
+    (return_call $callee
+      (local.get 9) (local.get 8) (local.get 7) (local.get 6) (local.get 5)
+      (local.get 4) (local.get 3) (local.get 2) (local.get 1) (local.get 0)
+    )
+  )
+)
+;; wasm[0]::function[0]::callee:
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       str     x28, [sp, #-0x10]!
+;;       mov     x28, sp
+;;       ldur    x16, [x0, #8]
+;;       ldur    x16, [x16, #0x18]
+;;       mov     x17, #0
+;;       movk    x17, #0x40
+;;       add     x16, x16, x17
+;;       cmp     sp, x16
+;;       b.lo    #0x74
+;;   2c: mov     x9, x0
+;;       sub     x28, x28, #0x40
+;;       mov     sp, x28
+;;       stur    x0, [x28, #0x38]
+;;       stur    x1, [x28, #0x30]
+;;       stur    x2, [x28, #0x28]
+;;       stur    x3, [x28, #0x20]
+;;       stur    x4, [x28, #0x18]
+;;       stur    x5, [x28, #0x10]
+;;       stur    x6, [x28, #8]
+;;       stur    x7, [x28]
+;;       ldur    x0, [x29, #0x28]
+;;       add     x28, x28, #0x40
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       ret
+;;   74: udf     #0xc11f
+;;
+;; wasm[0]::function[1]:
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       str     x28, [sp, #-0x10]!
+;;       mov     x28, sp
+;;       ldur    x16, [x0, #8]
+;;       ldur    x16, [x16, #0x18]
+;;       mov     x17, #0
+;;       movk    x17, #0x90
+;;       add     x16, x16, x17
+;;       cmp     sp, x16
+;;       b.lo    #0x1e8
+;;   ac: mov     x9, x0
+;;       sub     x28, x28, #0x40
+;;       mov     sp, x28
+;;       stur    x0, [x28, #0x38]
+;;       stur    x1, [x28, #0x30]
+;;       stur    x2, [x28, #0x28]
+;;       stur    x3, [x28, #0x20]
+;;       stur    x4, [x28, #0x18]
+;;       stur    x5, [x28, #0x10]
+;;       stur    x6, [x28, #8]
+;;       stur    x7, [x28]
+;;       ldur    x16, [x29, #0x28]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x29, #0x20]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x29, #0x18]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x29, #0x10]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x28, #0x20]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x28, #0x30]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x28, #0x40]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x28, #0x50]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x28, #0x60]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x16, [x28, #0x70]
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x16, [x28]
+;;       ldur    x2, [x28, #0x48]
+;;       ldur    x3, [x28, #0x40]
+;;       ldur    x4, [x28, #0x38]
+;;       ldur    x5, [x28, #0x30]
+;;       ldur    x6, [x28, #0x28]
+;;       ldur    x7, [x28, #0x20]
+;;       ldur    x16, [x28, #0x18]
+;;       stur    x16, [x29, #0x10]
+;;       ldur    x16, [x28, #0x10]
+;;       stur    x16, [x29, #0x18]
+;;       ldur    x16, [x28, #8]
+;;       stur    x16, [x29, #0x20]
+;;       ldur    x16, [x28]
+;;       stur    x16, [x29, #0x28]
+;;       mov     x0, x9
+;;       mov     x1, x9
+;;       add     x28, x28, #0x90
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       b       #0
+;;  1d0: add     x28, x28, #0x40
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       ret
+;;  1e8: udf     #0xc11f
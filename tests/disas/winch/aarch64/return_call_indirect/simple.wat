@@ -0,0 +1,102 @@
+;;! target = "aarch64"
+;;! test = "winch"
+;;! flags = "-Wtail-call"
+
+(module
+  (type $t (func (param i32) (result i32)))
+  (table funcref (elem $f))
+
+  (func $f (type $t)
+    (return_call_indirect (type $t) (local.get 0) (i32.const 0))
+  )
+)
+;; wasm[0]::function[0]::f:
+;;       stp     x29, x30, [sp, #-0x10]!
+;;       mov     x29, sp
+;;       str     x28, [sp, #-0x10]!
+;;       mov     x28, sp
+;;       ldur    x16, [x0, #8]
+;;       ldur    x16, [x16, #0x18]
+;;       mov     x17, #0
+;;       movk    x17, #0x24
+;;       add     x16, x16, x17
+;;       cmp     sp, x16
+;;       b.lo    #0x154
+;;   2c: mov     x9, x0
+;;       sub     x28, x28, #0x18
+;;       mov     sp, x28
+;;       stur    x0, [x28, #0x10]
+;;       stur    x1, [x28, #8]
+;;       stur    w2, [x28, #4]
+;;       ldur    w16, [x28, #4]
+;;       sub     x28, x28, #4
+;;       mov     sp, x28
+;;       stur    w16, [x28]
+;;       mov     x1, #0
+;;       mov     x2, x9
+;;       ldur    x3, [x2, #0x38]
+;;       cmp     x1, x3, uxtx
+;;       sub     sp, x28, #4
+;;       b.hs    #0x158
+;;   6c: mov     sp, x28
+;;       mov     x16, x1
+;;       mov     x17, #8
+;;       mul     x16, x16, x17
+;;       ldur    x2, [x2, #0x30]
+;;       mov     x4, x2
+;;       add     x2, x2, x16, uxtx
+;;       cmp     x1, x3, uxtx
+;;       csel    x2, x4, x2, hs
+;;       ldur    x0, [x2]
+;;       tst     x0, x0
+;;       b.ne    #0xcc
+;;       b       #0xa0
+;;   a0: sub     x28, x28, #4
+;;       mov     sp, x28
+;;       stur    w1, [x28]
+;;       mov     x0, x9
+;;       mov     x1, #0
+;;       ldur    w2, [x28]
+;;       bl      #0x2c4
+;;   bc: add     x28, x28, #4
+;;       mov     sp, x28
+;;       ldur    x9, [x28, #0x14]
+;;       b       #0xd0
+;;   cc: and     x0, x0, #0xfffffffffffffffe
+;;       sub     sp, x28, #4
+;;       cbz     x0, #0x15c
+;;   d8: mov     sp, x28
+;;       ldur    x16, [x9, #0x28]
+;;       ldur    w1, [x16]
+;;       ldur    w2, [x0, #0x10]
+;;       cmp     w1, w2, uxtx
+;;       sub     sp, x28, #4
+;;       b.ne    #0x160
+;;   f4: mov     sp, x28
+;;       sub     x28, x28, #8
+;;       mov     sp, x28
+;;       stur    x0, [x28]
+;;       ldur    x3, [x28]
+;;       add     x28, x28, #8
+;;       mov     sp, x28
+;;       ldur    x5, [x3, #0x18]
+;;       ldur    x4, [x3, #8]
+;;       ldur    w2, [x28]
+;;       mov     x0, x5
+;;       mov     x1, x9
+;;       add     x28, x28, #0x1c
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       br      x4
+;;  13c: add     x28, x28, #0x18
+;;       mov     sp, x28
+;;       mov     sp, x28
+;;       ldr     x28, [sp], #0x10
+;;       ldp     x29, x30, [sp], #0x10
+;;       ret
+;;  154: udf     #0xc11f
+;;  158: udf     #0xc11f
+;;  15c: udf     #0xc11f
+;;  160: udf     #0xc11f
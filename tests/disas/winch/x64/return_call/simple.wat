@@ -0,0 +1,46 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = "-Wtail-call"
+
+(module
+  (func $count (param i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 0))
+      (else (return_call $count (i64.sub (local.get 0) (i64.const 1))))
+    )
+  )
+)
+;; wasm[0]::function[0]::count:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x28, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x84
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x20, %rsp
+;;       movq    %rdi, 0x18(%rsp)
+;;       movq    %rsi, 0x10(%rsp)
+;;       movq    %rdx, 8(%rsp)
+;;       movq    8(%rsp), %rax
+;;       cmpq    $0, %rax
+;;       movl    $0, %eax
+;;       sete    %al
+;;       testl   %eax, %eax
+;;       je      0x58
+;;   4e: movq    8(%rsp), %rax
+;;       jmp     0x7b
+;;   58: movq    8(%rsp), %rax
+;;       subq    $1, %rax
+;;       pushq   %rax
+;;       movq    (%rsp), %rdx
+;;       movq    %r14, %rdi
+;;       movq    %r14, %rsi
+;;       addq    $0x28, %rsp
+;;       popq    %rbp
+;;       jmp     0
+;;   7b: addq    $0x20, %rsp
+;;       popq    %rbp
+;;       retq
+;;   84: ud2
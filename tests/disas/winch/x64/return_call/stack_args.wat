@@ -0,0 +1,99 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = "-Wtail-call"
+
+(module
+  (func $callee (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64) (result i64)
+    (local.get 9)
+  )
+
+  (func (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64) (result i64)
+    (return_call $callee
+      (local.get 9) (local.get 8) (local.get 7) (local.get 6) (local.get 5)
+      (local.get 4) (local.get 3) (local.get 2) (local.get 1) (local.get 0)
+    )
+  )
+)
+;; wasm[0]::function[0]::callee:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x30, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x50
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x30, %rsp
+;;       movq    %rdi, 0x28(%rsp)
+;;       movq    %rsi, 0x20(%rsp)
+;;       movq    %rdx, 0x18(%rsp)
+;;       movq    %rcx, 0x10(%rsp)
+;;       movq    %r8, 8(%rsp)
+;;       movq    %r9, (%rsp)
+;;       movq    0x38(%rbp), %rax
+;;       addq    $0x30, %rsp
+;;       popq    %rbp
+;;       retq
+;;   50: ud2
+;;
+;; wasm[0]::function[1]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x80, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x148
+;;   7c: movq    %rdi, %r14
+;;       subq    $0x30, %rsp
+;;       movq    %rdi, 0x28(%rsp)
+;;       movq    %rsi, 0x20(%rsp)
+;;       movq    %rdx, 0x18(%rsp)
+;;       movq    %rcx, 0x10(%rsp)
+;;       movq    %r8, 8(%rsp)
+;;       movq    %r9, (%rsp)
+;;       movq    0x38(%rbp), %r11
+;;       pushq   %r11
+;;       movq    0x30(%rbp), %r11
+;;       pushq   %r11
+;;       movq    0x28(%rbp), %r11
+;;       pushq   %r11
+;;       movq    0x20(%rbp), %r11
+;;       pushq   %r11
+;;       movq    0x18(%rbp), %r11
+;;       pushq   %r11
+;;       movq    0x10(%rbp), %r11
+;;       pushq   %r11
+;;       movq    0x30(%rsp), %r11
+;;       pushq   %r11
+;;       movq    0x40(%rsp), %r11
+;;       pushq   %r11
+;;       movq    0x50(%rsp), %r11
+;;       pushq   %r11
+;;       movq    0x60(%rsp), %r11
+;;       pushq   %r11
+;;       movq    0x48(%rsp), %rdx
+;;       movq    0x40(%rsp), %rcx
+;;       movq    0x38(%rsp), %r8
+;;       movq    0x30(%rsp), %r9
+;;       movq    0x28(%rsp), %r11
+;;       movq    %r11, 0x10(%rbp)
+;;       movq    0x20(%rsp), %r11
+;;       movq    %r11, 0x18(%rbp)
+;;       movq    0x18(%rsp), %r11
+;;       movq    %r11, 0x20(%rbp)
+;;       movq    0x10(%rsp), %r11
+;;       movq    %r11, 0x28(%rbp)
+;;       movq    8(%rsp), %r11
+;;       movq    %r11, 0x30(%rbp)
+;;       movq    (%rsp), %r11
+;;       movq    %r11, 0x38(%rbp)
+;;       movq    %r14, %rdi
+;;       movq    %r14, %rsi
+;;       addq    $0x80, %rsp
+;;       popq    %rbp
+;;       jmp     0
+;;  13f: addq    $0x30, %rsp
+;;       popq    %rbp
+;;       retq
+;;  148: ud2
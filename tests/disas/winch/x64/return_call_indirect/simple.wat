@@ -0,0 +1,79 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = "-Wtail-call"
+
+(module
+  (type $t (func (param i32) (result i32)))
+  (table funcref (elem $f))
+
+  (func $f (type $t)
+    (return_call_indirect (type $t) (local.get 0) (i32.const 0))
+  )
+)
+;; wasm[0]::function[0]::f:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x30, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x101
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x20, %rsp
+;;       movq    %rdi, 0x18(%rsp)
+;;       movq    %rsi, 0x10(%rsp)
+;;       movl    %edx, 0xc(%rsp)
+;;       movl    0xc(%rsp), %r11d
+;;       subq    $4, %rsp
+;;       movl    %r11d, (%rsp)
+;;       movl    $0, %ecx
+;;       movq    %r14, %rdx
+;;       movq    0x38(%rdx), %rbx
+;;       cmpq    %rbx, %rcx
+;;       jae     0x103
+;;   59: movq    %rcx, %r11
+;;       imulq   $8, %r11, %r11
+;;       movq    0x30(%rdx), %rdx
+;;       movq    %rdx, %rsi
+;;       addq    %r11, %rdx
+;;       cmpq    %rbx, %rcx
+;;       cmovaeq %rsi, %rdx
+;;       movq    (%rdx), %rax
+;;       testq   %rax, %rax
+;;       jne     0xba
+;;   80: subq    $4, %rsp
+;;       movl    %ecx, (%rsp)
+;;       subq    $8, %rsp
+;;       movq    %r14, %rdi
+;;       movl    $0, %esi
+;;       movl    8(%rsp), %edx
+;;       callq   0x21b
+;;       addq    $8, %rsp
+;;       addq    $4, %rsp
+;;       movq    0x1c(%rsp), %r14
+;;       jmp     0xc0
+;;   ba: andq    $0xfffffffffffffffe, %rax
+;;       testq   %rax, %rax
+;;       je      0x105
+;;   c9: movq    0x28(%r14), %r11
+;;       movl    (%r11), %ecx
+;;       movl    0x10(%rax), %edx
+;;       cmpl    %edx, %ecx
+;;       jne     0x107
+;;   db: pushq   %rax
+;;       popq    %rcx
+;;       movq    0x18(%rcx), %r8
+;;       movq    8(%rcx), %rbx
+;;       movl    (%rsp), %edx
+;;       movq    %r8, %rdi
+;;       movq    %r14, %rsi
+;;       addq    $0x24, %rsp
+;;       popq    %rbp
+;;       jmpq    *%rbx
+;;   f8: addq    $0x20, %rsp
+;;       popq    %rbp
+;;       retq
+;;  101: ud2
+;;  103: ud2
+;;  105: ud2
+;;  107: ud2
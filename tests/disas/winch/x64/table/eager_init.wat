@@ -0,0 +1,100 @@
+;;! target = "x86_64"
+;;! test = "winch"
+;;! flags = [ "-Otable-lazy-init=n" ]
+
+(module
+  (table $t 2 funcref)
+  (elem (table $t) (i32.const 1) func $dummy)
+  (func $dummy)
+
+  (func (export "get-funcref") (param $i i32) (result funcref)
+    (table.get $t (local.get $i))
+  )
+  (func (export "set-funcref") (param $i i32) (param $r funcref)
+    (table.set $t (local.get $i) (local.get $r))
+  )
+)
+
+;; wasm[0]::function[0]::dummy:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x10, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x38
+;;   1c: movq    %rdi, %r14
+;;       subq    $0x10, %rsp
+;;       movq    %rdi, 8(%rsp)
+;;       movq    %rsi, (%rsp)
+;;       addq    $0x10, %rsp
+;;       popq    %rbp
+;;       retq
+;;   38: ud2
+;;
+;; wasm[0]::function[1]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x20, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0xb2
+;;   5c: movq    %rdi, %r14
+;;       subq    $0x20, %rsp
+;;       movq    %rdi, 0x18(%rsp)
+;;       movq    %rsi, 0x10(%rsp)
+;;       movl    %edx, 0xc(%rsp)
+;;       movl    0xc(%rsp), %eax
+;;       movq    %r14, %rcx
+;;       movq    0x38(%rcx), %rdx
+;;       cmpq    %rdx, %rax
+;;       jae     0xb4
+;;   88: movq    %rax, %r11
+;;       imulq   $8, %r11, %r11
+;;       movq    0x30(%rcx), %rcx
+;;       movq    %rcx, %rbx
+;;       addq    %r11, %rcx
+;;       cmpq    %rdx, %rax
+;;       cmovaeq %rbx, %rcx
+;;       movq    (%rcx), %rcx
+;;       movq    %rcx, %rax
+;;       addq    $0x20, %rsp
+;;       popq    %rbp
+;;       retq
+;;   b2: ud2
+;;   b4: ud2
+;;
+;; wasm[0]::function[2]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       movq    8(%rdi), %r11
+;;       movq    0x18(%r11), %r11
+;;       addq    $0x20, %r11
+;;       cmpq    %rsp, %r11
+;;       ja      0x137
+;;   dc: movq    %rdi, %r14
+;;       subq    $0x20, %rsp
+;;       movq    %rdi, 0x18(%rsp)
+;;       movq    %rsi, 0x10(%rsp)
+;;       movl    %edx, 0xc(%rsp)
+;;       movq    %rcx, (%rsp)
+;;       movq    (%rsp), %rax
+;;       movl    0xc(%rsp), %ecx
+;;       movq    %r14, %rdx
+;;       movq    0x38(%rdx), %rbx
+;;       cmpq    %rbx, %rcx
+;;       jae     0x139
+;;  110: movq    %rcx, %r11
+;;       imulq   $8, %r11, %r11
+;;       movq    0x30(%rdx), %rdx
+;;       movq    %rdx, %rsi
+;;       addq    %r11, %rdx
+;;       cmpq    %rbx, %rcx
+;;       cmovaeq %rsi, %rdx
+;;       movq    %rax, (%rdx)
+;;       addq    $0x20, %rsp
+;;       popq    %rbp
+;;       retq
+;;  137: ud2
+;;  139: ud2
@@ -0,0 +1,33 @@
+;;! target = "x86_64"
+;;! test = "compile"
+;;! flags = "-Ccranelift-has-avx512vl -Ccranelift-has-avx512f"
+
+;; With AVX512VL and AVX512F the unsigned relaxed truncations lower to a single
+;; `vcvttp{s,d}2udq`, which returns `u32::MAX` for NaN and out-of-range lanes.
+(module
+  (func (param v128) (result v128)
+    local.get 0
+    i32x4.relaxed_trunc_f32x4_u
+  )
+
+  (func (param v128) (result v128)
+    local.get 0
+    i32x4.relaxed_trunc_f64x2_u_zero
+  )
+)
+
+;; wasm[0]::function[0]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       vcvttps2udq %xmm0, %xmm0
+;;       movq    %rbp, %rsp
+;;       popq    %rbp
+;;       retq
+;;
+;; wasm[0]::function[1]:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       vcvttpd2udq %xmm0, %xmm0
+;;       movq    %rbp, %rsp
+;;       popq    %rbp
+;;       retq
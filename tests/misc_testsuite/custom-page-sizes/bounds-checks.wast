@@ -0,0 +1,137 @@
+;;! custom_page_sizes = true
+;;! memory64 = true
+;;! multi_memory = true
+;;! bulk_memory = true
+
+;; Bounds checks of memories with a page size of 1 whose byte sizes are not a
+;; multiple of the host page size, so out-of-bounds accesses can't be caught
+;; with guard pages.
+
+;; A memory whose size can change.
+(module
+  (memory 13 (pagesize 1))
+  (func (export "grow") (param i32) (result i32)
+    (memory.grow (local.get 0)))
+  (func (export "size") (result i32)
+    memory.size)
+  (func (export "load8") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+  (func (export "load16") (param i32) (result i32)
+    (i32.load16_u (local.get 0)))
+  (func (export "load32") (param i32) (result i32)
+    (i32.load (local.get 0)))
+  (func (export "load64") (param i32) (result i64)
+    (i64.load (local.get 0)))
+  (func (export "load64-offset") (param i32) (result i64)
+    (i64.load offset=4 (local.get 0)))
+  (func (export "load8-big-offset") (param i32) (result i32)
+    (i32.load8_u offset=0xffff_fff0 (local.get 0)))
+  (func (export "store64") (param i32 i64)
+    (i64.store (local.get 0) (local.get 1)))
+)
+
+(assert_return (invoke "size") (i32.const 13))
+(assert_return (invoke "load8" (i32.const 12)) (i32.const 0))
+(assert_trap (invoke "load8" (i32.const 13)) "out of bounds memory access")
+(assert_return (invoke "load16" (i32.const 11)) (i32.const 0))
+(assert_trap (invoke "load16" (i32.const 12)) "out of bounds memory access")
+(assert_return (invoke "load32" (i32.const 9)) (i32.const 0))
+(assert_trap (invoke "load32" (i32.const 10)) "out of bounds memory access")
+(assert_return (invoke "load64" (i32.const 5)) (i64.const 0))
+(assert_trap (invoke "load64" (i32.const 6)) "out of bounds memory access")
+(assert_return (invoke "load64-offset" (i32.const 1)) (i64.const 0))
+(assert_trap (invoke "load64-offset" (i32.const 2)) "out of bounds memory access")
+(assert_trap (invoke "load64" (i32.const -1)) "out of bounds memory access")
+(assert_trap (invoke "load8-big-offset" (i32.const 0)) "out of bounds memory access")
+(assert_trap (invoke "load8-big-offset" (i32.const 0x10)) "out of bounds memory access")
+(assert_trap (invoke "store64" (i32.const 6) (i64.const -1)) "out of bounds memory access")
+(assert_return (invoke "load8" (i32.const 12)) (i32.const 0))
+
+(assert_return (invoke "grow" (i32.const 3)) (i32.const 13))
+(assert_return (invoke "size") (i32.const 16))
+(assert_return (invoke "store64" (i32.const 8) (i64.const -1)))
+(assert_return (invoke "load64" (i32.const 8)) (i64.const -1))
+(assert_return (invoke "load8" (i32.const 15)) (i32.const 0xff))
+(assert_trap (invoke "load8" (i32.const 16)) "out of bounds memory access")
+(assert_trap (invoke "load64" (i32.const 9)) "out of bounds memory access")
+
+;; Grow across a host page boundary.
+(assert_return (invoke "grow" (i32.const 0x1001)) (i32.const 16))
+(assert_return (invoke "size") (i32.const 0x1011))
+(assert_return (invoke "load8" (i32.const 0x1010)) (i32.const 0))
+(assert_trap (invoke "load8" (i32.const 0x1011)) "out of bounds memory access")
+(assert_return (invoke "load64" (i32.const 0x1009)) (i64.const 0))
+(assert_trap (invoke "load64" (i32.const 0x100a)) "out of bounds memory access")
+
+;; A memory whose size is fixed.
+(module
+  (memory 7 7 (pagesize 1))
+  (func (export "grow") (param i32) (result i32)
+    (memory.grow (local.get 0)))
+  (func (export "load8") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+  (func (export "load32") (param i32) (result i32)
+    (i32.load (local.get 0)))
+  (func (export "load32-offset") (param i32) (result i32)
+    (i32.load offset=3 (local.get 0)))
+  (func (export "load64") (param i32) (result i64)
+    (i64.load (local.get 0)))
+)
+
+(assert_return (invoke "load8" (i32.const 6)) (i32.const 0))
+(assert_trap (invoke "load8" (i32.const 7)) "out of bounds memory access")
+(assert_return (invoke "load32" (i32.const 3)) (i32.const 0))
+(assert_trap (invoke "load32" (i32.const 4)) "out of bounds memory access")
+(assert_return (invoke "load32-offset" (i32.const 0)) (i32.const 0))
+(assert_trap (invoke "load32-offset" (i32.const 1)) "out of bounds memory access")
+(assert_trap (invoke "load64" (i32.const 0)) "out of bounds memory access")
+(assert_return (invoke "grow" (i32.const 1)) (i32.const -1))
+
+;; An empty memory whose size is fixed.
+(module
+  (memory 0 0 (pagesize 1))
+  (func (export "load8") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+)
+
+(assert_trap (invoke "load8" (i32.const 0)) "out of bounds memory access")
+
+;; A 64-bit memory.
+(module
+  (memory i64 5 (pagesize 1))
+  (func (export "grow") (param i64) (result i64)
+    (memory.grow (local.get 0)))
+  (func (export "size") (result i64)
+    memory.size)
+  (func (export "load8") (param i64) (result i32)
+    (i32.load8_u (local.get 0)))
+  (func (export "load32") (param i64) (result i32)
+    (i32.load (local.get 0)))
+  (func (export "load32-offset") (param i64) (result i32)
+    (i32.load offset=0x1_0000_0000 (local.get 0)))
+)
+
+(assert_return (invoke "size") (i64.const 5))
+(assert_return (invoke "load8" (i64.const 4)) (i32.const 0))
+(assert_trap (invoke "load8" (i64.const 5)) "out of bounds memory access")
+(assert_return (invoke "load32" (i64.const 1)) (i32.const 0))
+(assert_trap (invoke "load32" (i64.const 2)) "out of bounds memory access")
+(assert_trap (invoke "load32" (i64.const -1)) "out of bounds memory access")
+(assert_trap (invoke "load32-offset" (i64.const 0)) "out of bounds memory access")
+(assert_return (invoke "grow" (i64.const 2)) (i64.const 5))
+(assert_return (invoke "load32" (i64.const 3)) (i32.const 0))
+(assert_trap (invoke "load32" (i64.const 4)) "out of bounds memory access")
+
+;; Bulk memory operations respect byte granularity bounds.
+(module
+  (memory 10 (pagesize 1))
+  (func (export "fill") (param i32 i32 i32)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "load8") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+)
+
+(assert_return (invoke "fill" (i32.const 7) (i32.const 0xaa) (i32.const 3)))
+(assert_return (invoke "load8" (i32.const 9)) (i32.const 0xaa))
+(assert_trap (invoke "fill" (i32.const 8) (i32.const 0xbb) (i32.const 3)) "out of bounds memory access")
+(assert_return (invoke "load8" (i32.const 8)) (i32.const 0xaa))
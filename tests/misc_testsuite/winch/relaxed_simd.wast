@@ -0,0 +1,203 @@
+;;! simd = true
+;;! relaxed_simd = true
+
+(module
+  (func (export "i8x16.relaxed_swizzle") (param v128 v128) (result v128)
+    (i8x16.relaxed_swizzle (local.get 0) (local.get 1)))
+
+  (func (export "i32x4.relaxed_trunc_f32x4_s") (param v128) (result v128)
+    (i32x4.relaxed_trunc_f32x4_s (local.get 0)))
+  (func (export "i32x4.relaxed_trunc_f32x4_u") (param v128) (result v128)
+    (i32x4.relaxed_trunc_f32x4_u (local.get 0)))
+  (func (export "i32x4.relaxed_trunc_f64x2_s_zero") (param v128) (result v128)
+    (i32x4.relaxed_trunc_f64x2_s_zero (local.get 0)))
+  (func (export "i32x4.relaxed_trunc_f64x2_u_zero") (param v128) (result v128)
+    (i32x4.relaxed_trunc_f64x2_u_zero (local.get 0)))
+
+  (func (export "f32x4.relaxed_madd") (param v128 v128 v128) (result v128)
+    (f32x4.relaxed_madd (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "f32x4.relaxed_nmadd") (param v128 v128 v128) (result v128)
+    (f32x4.relaxed_nmadd (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "f64x2.relaxed_madd") (param v128 v128 v128) (result v128)
+    (f64x2.relaxed_madd (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "f64x2.relaxed_nmadd") (param v128 v128 v128) (result v128)
+    (f64x2.relaxed_nmadd (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "i8x16.relaxed_laneselect") (param v128 v128 v128) (result v128)
+    (i8x16.relaxed_laneselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i16x8.relaxed_laneselect") (param v128 v128 v128) (result v128)
+    (i16x8.relaxed_laneselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i32x4.relaxed_laneselect") (param v128 v128 v128) (result v128)
+    (i32x4.relaxed_laneselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i64x2.relaxed_laneselect") (param v128 v128 v128) (result v128)
+    (i64x2.relaxed_laneselect (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "f32x4.relaxed_min") (param v128 v128) (result v128)
+    (f32x4.relaxed_min (local.get 0) (local.get 1)))
+  (func (export "f32x4.relaxed_max") (param v128 v128) (result v128)
+    (f32x4.relaxed_max (local.get 0) (local.get 1)))
+  (func (export "f64x2.relaxed_min") (param v128 v128) (result v128)
+    (f64x2.relaxed_min (local.get 0) (local.get 1)))
+  (func (export "f64x2.relaxed_max") (param v128 v128) (result v128)
+    (f64x2.relaxed_max (local.get 0) (local.get 1)))
+
+  (func (export "i16x8.relaxed_q15mulr_s") (param v128 v128) (result v128)
+    (i16x8.relaxed_q15mulr_s (local.get 0) (local.get 1)))
+
+  (func (export "i16x8.relaxed_dot_i8x16_i7x16_s") (param v128 v128) (result v128)
+    (i16x8.relaxed_dot_i8x16_i7x16_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.relaxed_dot_i8x16_i7x16_add_s") (param v128 v128 v128) (result v128)
+    (i32x4.relaxed_dot_i8x16_i7x16_add_s (local.get 0) (local.get 1) (local.get 2)))
+)
+
+(assert_return
+  (invoke "i8x16.relaxed_swizzle"
+    (v128.const i8x16 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)
+    (v128.const i8x16 15 14 13 12 11 10 9 8 7 6 5 4 3 2 1 0))
+  (v128.const i8x16 15 14 13 12 11 10 9 8 7 6 5 4 3 2 1 0))
+(assert_return
+  (invoke "i8x16.relaxed_swizzle"
+    (v128.const i8x16 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)
+    (v128.const i8x16 -128 -1 16 0 1 2 3 4 5 6 7 8 9 10 11 12))
+  (either
+    (v128.const i8x16 0 0 0 0 1 2 3 4 5 6 7 8 9 10 11 12)
+    (v128.const i8x16 0 15 0 0 1 2 3 4 5 6 7 8 9 10 11 12)))
+
+(assert_return
+  (invoke "i32x4.relaxed_trunc_f32x4_s" (v128.const f32x4 -1.5 1.5 100.9 -100.9))
+  (v128.const i32x4 -1 1 100 -100))
+(assert_return
+  (invoke "i32x4.relaxed_trunc_f32x4_s" (v128.const f32x4 nan -nan 1e10 -1e10))
+  (either
+    (v128.const i32x4 0 0 0x7fffffff 0x80000000)
+    (v128.const i32x4 0x80000000 0x80000000 0x80000000 0x80000000)))
+(assert_return
+  (invoke "i32x4.relaxed_trunc_f32x4_u" (v128.const f32x4 1.5 3000000000.0 0 42.9))
+  (v128.const i32x4 1 3000000000 0 42))
+(assert_return
+  (invoke "i32x4.relaxed_trunc_f64x2_s_zero" (v128.const f64x2 -1.5 100.9))
+  (v128.const i32x4 -1 100 0 0))
+(assert_return
+  (invoke "i32x4.relaxed_trunc_f64x2_u_zero" (v128.const f64x2 1.5 3000000000.0))
+  (v128.const i32x4 1 3000000000 0 0))
+
+(assert_return
+  (invoke "f32x4.relaxed_madd"
+    (v128.const f32x4 1 2 3 4)
+    (v128.const f32x4 5 6 7 8)
+    (v128.const f32x4 0.5 -0.5 1 -1))
+  (v128.const f32x4 5.5 11.5 22 31))
+(assert_return
+  (invoke "f32x4.relaxed_nmadd"
+    (v128.const f32x4 1 2 3 4)
+    (v128.const f32x4 5 6 7 8)
+    (v128.const f32x4 0.5 -0.5 1 -1))
+  (v128.const f32x4 -4.5 -12.5 -20 -33))
+(assert_return
+  (invoke "f64x2.relaxed_madd"
+    (v128.const f64x2 1.5 -2)
+    (v128.const f64x2 2 3)
+    (v128.const f64x2 1 1))
+  (v128.const f64x2 4 -5))
+(assert_return
+  (invoke "f64x2.relaxed_nmadd"
+    (v128.const f64x2 1.5 -2)
+    (v128.const f64x2 2 3)
+    (v128.const f64x2 1 1))
+  (v128.const f64x2 -2 7))
+;; Results differ depending on whether the multiply is rounded separately.
+(assert_return
+  (invoke "f32x4.relaxed_madd"
+    (v128.const f32x4 0x1.000002p+0 0x1.000002p+0 0x1.000002p+0 0x1.000002p+0)
+    (v128.const f32x4 0x1.fffffcp-1 0x1.fffffcp-1 0x1.fffffcp-1 0x1.fffffcp-1)
+    (v128.const f32x4 -0x1p+0 -0x1p+0 -0x1p+0 -0x1p+0))
+  (either
+    (v128.const f32x4 -0x1p-46 -0x1p-46 -0x1p-46 -0x1p-46)
+    (v128.const f32x4 0 0 0 0)))
+
+(assert_return
+  (invoke "i8x16.relaxed_laneselect"
+    (v128.const i8x16 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)
+    (v128.const i8x16 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31)
+    (v128.const i8x16 -1 0 -1 0 -1 0 -1 0 -1 0 -1 0 -1 0 -1 0))
+  (v128.const i8x16 0 17 2 19 4 21 6 23 8 25 10 27 12 29 14 31))
+(assert_return
+  (invoke "i16x8.relaxed_laneselect"
+    (v128.const i16x8 0 1 2 3 4 5 6 7)
+    (v128.const i16x8 8 9 10 11 12 13 14 15)
+    (v128.const i16x8 -1 0 -1 0 -1 0 -1 0))
+  (v128.const i16x8 0 9 2 11 4 13 6 15))
+(assert_return
+  (invoke "i32x4.relaxed_laneselect"
+    (v128.const i32x4 0 1 2 3)
+    (v128.const i32x4 4 5 6 7)
+    (v128.const i32x4 -1 0 -1 0))
+  (v128.const i32x4 0 5 2 7))
+(assert_return
+  (invoke "i32x4.relaxed_laneselect"
+    (v128.const i32x4 0x12345678 0x12345678 0 0)
+    (v128.const i32x4 0x87654321 0x87654321 0 0)
+    (v128.const i32x4 0x80000000 0x0000ffff 0 0))
+  (either
+    (v128.const i32x4 0x12345678 0x87654321 0 0)
+    (v128.const i32x4 0x07654321 0x87655678 0 0)))
+(assert_return
+  (invoke "i64x2.relaxed_laneselect"
+    (v128.const i64x2 0 1)
+    (v128.const i64x2 2 3)
+    (v128.const i64x2 0 -1))
+  (v128.const i64x2 2 1))
+
+(assert_return
+  (invoke "f32x4.relaxed_min" (v128.const f32x4 1 -2 3 -4) (v128.const f32x4 -1 2 -3 4))
+  (v128.const f32x4 -1 -2 -3 -4))
+(assert_return
+  (invoke "f32x4.relaxed_max" (v128.const f32x4 1 -2 3 -4) (v128.const f32x4 -1 2 -3 4))
+  (v128.const f32x4 1 2 3 4))
+(assert_return
+  (invoke "f64x2.relaxed_min" (v128.const f64x2 1 -2) (v128.const f64x2 -1 2))
+  (v128.const f64x2 -1 -2))
+(assert_return
+  (invoke "f64x2.relaxed_max" (v128.const f64x2 1 -2) (v128.const f64x2 -1 2))
+  (v128.const f64x2 1 2))
+(assert_return
+  (invoke "f32x4.relaxed_min" (v128.const f32x4 nan 0 -0 1) (v128.const f32x4 1 -0 0 nan))
+  (either
+    (v128.const f32x4 nan:canonical -0 -0 nan:canonical)
+    (v128.const f32x4 1 -0 0 nan:canonical)
+    (v128.const f32x4 nan:canonical 0 -0 1)
+    (v128.const f32x4 1 0 -0 1)))
+
+(assert_return
+  (invoke "i16x8.relaxed_q15mulr_s"
+    (v128.const i16x8 0x4000 0x7fff -0x8000 1 0 0 0 0)
+    (v128.const i16x8 0x4000 0x7fff 0x4000 1 0 0 0 0))
+  (v128.const i16x8 0x2000 0x7ffe -0x4000 0 0 0 0 0))
+(assert_return
+  (invoke "i16x8.relaxed_q15mulr_s"
+    (v128.const i16x8 -0x8000 0 0 0 0 0 0 0)
+    (v128.const i16x8 -0x8000 0 0 0 0 0 0 0))
+  (either
+    (v128.const i16x8 0x7fff 0 0 0 0 0 0 0)
+    (v128.const i16x8 -0x8000 0 0 0 0 0 0 0)))
+
+(assert_return
+  (invoke "i16x8.relaxed_dot_i8x16_i7x16_s"
+    (v128.const i8x16 0 1 2 3 4 5 6 7 -8 -9 -10 11 12 13 14 15)
+    (v128.const i8x16 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 127))
+  (v128.const i16x8 2 18 50 98 -162 22 338 2115))
+(assert_return
+  (invoke "i16x8.relaxed_dot_i8x16_i7x16_s"
+    (v128.const i8x16 -128 -128 127 127 0 0 0 0 0 0 0 0 0 0 0 0)
+    (v128.const i8x16 -128 -128 -128 -128 0 0 0 0 0 0 0 0 0 0 0 0))
+  (either
+    (v128.const i16x8 -32768 -32512 0 0 0 0 0 0)
+    (v128.const i16x8 32767 -32512 0 0 0 0 0 0)
+    (v128.const i16x8 -32768 32512 0 0 0 0 0 0)
+    (v128.const i16x8 32767 32512 0 0 0 0 0 0)))
+(assert_return
+  (invoke "i32x4.relaxed_dot_i8x16_i7x16_add_s"
+    (v128.const i8x16 0 1 2 3 4 5 6 7 -8 -9 -10 11 12 13 14 15)
+    (v128.const i8x16 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 127)
+    (v128.const i32x4 1 2 3 -4))
+  (v128.const i32x4 21 150 -137 2449))
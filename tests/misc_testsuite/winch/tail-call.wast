@@ -0,0 +1,154 @@
+;;! tail_call = true
+
+;; Tail calls in Winch reuse the frame of the caller, so the following
+;; recursions must not exhaust the stack.
+
+(module
+  (type $t (func (param i64 i64) (result i64)))
+
+  (table funcref (elem $fac-acc $even $odd))
+
+  (func $fac-acc (export "fac-acc") (type $t)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 1))
+      (else
+        (return_call $fac-acc
+          (i64.sub (local.get 0) (i64.const 1))
+          (i64.mul (local.get 0) (local.get 1))
+        )
+      )
+    )
+  )
+
+  (func $count (export "count") (param i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 0))
+      (else (return_call $count (i64.sub (local.get 0) (i64.const 1))))
+    )
+  )
+
+  (func $even (export "even") (param i64 i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 44))
+      (else
+        (return_call_indirect (type $t)
+          (i64.sub (local.get 0) (i64.const 1))
+          (local.get 1)
+          (i32.const 2)
+        )
+      )
+    )
+  )
+
+  (func $odd (param i64 i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 99))
+      (else
+        (return_call_indirect (type $t)
+          (i64.sub (local.get 0) (i64.const 1))
+          (local.get 1)
+          (i32.const 1)
+        )
+      )
+    )
+  )
+
+  (func (export "null") (result i64)
+    (return_call_indirect (type $t) (i64.const 0) (i64.const 0) (i32.const 3))
+  )
+
+  (func (export "bad-sig") (result i64)
+    (return_call_indirect (param i64) (result i64) (i64.const 0) (i32.const 0))
+  )
+)
+
+(assert_return (invoke "fac-acc" (i64.const 0) (i64.const 1)) (i64.const 1))
+(assert_return (invoke "fac-acc" (i64.const 5) (i64.const 1)) (i64.const 120))
+(assert_return
+  (invoke "fac-acc" (i64.const 25) (i64.const 1))
+  (i64.const 7034535277573963776)
+)
+(assert_return (invoke "count" (i64.const 1_000_000)) (i64.const 0))
+(assert_return (invoke "even" (i64.const 0) (i64.const 0)) (i64.const 44))
+(assert_return (invoke "even" (i64.const 1_000_001) (i64.const 0)) (i64.const 99))
+(assert_return (invoke "even" (i64.const 1_000_000) (i64.const 0)) (i64.const 44))
+(assert_trap (invoke "null") "undefined element")
+(assert_trap (invoke "bad-sig") "indirect call type mismatch")
+
+;; Arguments passed on the stack and results returned through the results
+;; area.
+(module
+  (func $sum (export "sum")
+    (param i64 i64 i64 i64 i64 i64 i64 i64 i64 i64 i64 i64)
+    (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then
+        (i64.add (local.get 1)
+          (i64.add (local.get 2)
+            (i64.add (local.get 3)
+              (i64.add (local.get 4)
+                (i64.add (local.get 5)
+                  (i64.add (local.get 6)
+                    (i64.add (local.get 7)
+                      (i64.add (local.get 8)
+                        (i64.add (local.get 9)
+                          (i64.add (local.get 10) (local.get 11)))))))))))
+      )
+      (else
+        (return_call $sum
+          (i64.sub (local.get 0) (i64.const 1))
+          (local.get 2) (local.get 3) (local.get 4) (local.get 5)
+          (local.get 6) (local.get 7) (local.get 8) (local.get 9)
+          (local.get 10) (local.get 11) (local.get 1)
+        )
+      )
+    )
+  )
+
+  (func $multi (export "multi") (param i32 i64)
+    (result i64 i64 i64 i64 i64 i64 i64 i64 i64 i64)
+    (if (result i64 i64 i64 i64 i64 i64 i64 i64 i64 i64) (i32.eqz (local.get 0))
+      (then
+        (local.get 1) (local.get 1) (local.get 1) (local.get 1) (local.get 1)
+        (local.get 1) (local.get 1) (local.get 1) (local.get 1) (local.get 1)
+      )
+      (else
+        (return_call $multi
+          (i32.sub (local.get 0) (i32.const 1))
+          (i64.add (local.get 1) (i64.const 2))
+        )
+      )
+    )
+  )
+)
+
+(assert_return
+  (invoke "sum"
+    (i64.const 100_000) (i64.const 1) (i64.const 2) (i64.const 3)
+    (i64.const 4) (i64.const 5) (i64.const 6) (i64.const 7)
+    (i64.const 8) (i64.const 9) (i64.const 10) (i64.const 11))
+  (i64.const 66)
+)
+(assert_return
+  (invoke "multi" (i32.const 100_000) (i64.const 0))
+  (i64.const 200_000) (i64.const 200_000) (i64.const 200_000) (i64.const 200_000)
+  (i64.const 200_000) (i64.const 200_000) (i64.const 200_000) (i64.const 200_000)
+  (i64.const 200_000) (i64.const 200_000)
+)
+
+;; Tail calls to imported functions.
+(module $host
+  (func (export "add") (param i32 i32) (result i32)
+    (i32.add (local.get 0) (local.get 1))
+  )
+)
+(register "host" $host)
+
+(module
+  (import "host" "add" (func $add (param i32 i32) (result i32)))
+  (func (export "add") (param i32) (result i32)
+    (return_call $add (local.get 0) (i32.const 1))
+  )
+)
+
+(assert_return (invoke "add" (i32.const 41)) (i32.const 42))
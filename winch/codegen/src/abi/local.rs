@@ -1,3 +1,4 @@
+use crate::abi::ABI;
 use wasmtime_environ::WasmValType;
 
 /// Base register used to address the local slot.
@@ -79,4 +80,22 @@ impl LocalSlot {
     pub fn addressed_from_sp(&self) -> bool {
         self.base == Base::SP
     }
+
+    /// Returns the offset of this slot relative to the canonical frame
+    /// address (CFA); that is, the value of the stack pointer right before
+    /// the call instruction in the caller.
+    ///
+    /// This offset is only meaningful once the frame has been fully set up
+    /// and it's used to describe the location of locals in native debug
+    /// information.
+    pub fn cfa_offset<A: ABI>(&self) -> i64 {
+        match self.base {
+            // Stack arguments are addressed from the frame pointer, and their
+            // offset already accounts for the argument base.
+            Base::FP => i64::from(self.offset) - i64::from(<A as ABI>::arg_base_offset()),
+            // Slots addressed from the stack pointer grow downwards from the
+            // end of the initial frame.
+            Base::SP => -(i64::from(self.offset) + i64::from(<A as ABI>::initial_frame_size())),
+        }
+    }
 }
@@ -29,6 +29,16 @@
 //! at once when cleaning up the stack after the call has been
 //! emitted.
 //!
+//! Tail calls (`return_call` and `return_call_indirect`) are emitted through
+//! [`FnCall::emit_return_call`], which reuses the frame of the current
+//! function: arguments passed on the stack are written to the current
+//! function's own incoming argument area, the current frame is torn down and
+//! control is transferred to the callee, which returns directly to the caller
+//! of the current function. Given that Winch's calling convention requires the
+//! caller to clean up the stack arguments, tail calls are only supported if
+//! the callee's stack arguments fit in the current function's incoming
+//! argument area.
+//!
 //! The machine stack throughout the function call is as follows:
 //! ┌──────────────────────────────────────────────────┐
 //! │                                                  │
@@ -60,7 +70,7 @@
 
 use crate::{
     FuncEnv, Result,
-    abi::{ABIOperand, ABISig, RetArea, vmctx},
+    abi::{ABI, ABIOperand, ABISig, LocalSlot, RetArea, vmctx},
     codegen::{BuiltinFunction, BuiltinType, Callee, CodeGenContext, CodeGenError, Emission},
     ensure,
     masm::{
@@ -112,6 +122,56 @@ impl FnCall {
         )
     }
 
+    /// Orchestrates the emission of a tail call:
+    /// 1. Resolves the [`Callee`] through the given callback.
+    /// 2. Lowers the resolved [`Callee`] to a ([`CalleeKind`], [ContextArgs])
+    /// 3. Spills the value stack.
+    /// 4. Assigns the arguments, reusing the incoming argument area of the
+    ///    current function for stack arguments.
+    /// 5. Frees all the stack space of the current function and emits the
+    ///    tail call.
+    ///
+    /// `caller_sig` is the signature of the function being compiled.
+    pub fn emit_return_call<M: MacroAssembler>(
+        env: &mut FuncEnv<M::Ptr>,
+        masm: &mut M,
+        context: &mut CodeGenContext<Emission>,
+        callee: Callee,
+        caller_sig: &ABISig,
+    ) -> Result<()> {
+        let (kind, callee_context) = Self::lower(env, context.vmoffsets, &callee, context, masm)?;
+
+        let sig = env.callee_sig::<M::ABI>(&callee)?;
+        ensure!(
+            sig.params_stack_size() <= caller_sig.params_stack_size(),
+            CodeGenError::unsupported_tail_call_stack_args()
+        );
+        context.spill(masm)?;
+        Self::assign_tail(sig, &callee_context, context, masm)?;
+
+        // Free any registers holding the callee or its context arguments;
+        // their values are already in place.
+        match kind {
+            CalleeKind::Indirect(r) => context.free_reg(r),
+            _ => {}
+        }
+        for loc in callee_context.as_slice() {
+            match loc {
+                VMContextLoc::Reg(r) => context.free_reg(*r),
+                _ => {}
+            }
+        }
+
+        // Free all the stack space of the current function, including its
+        // locals, before tearing down the frame. The stack pointer offset is
+        // restored afterwards given that the code following the tail call is
+        // unreachable and the control frames expect it to be unchanged.
+        let sp_offset = masm.sp_offset()?;
+        masm.free_stack(sp_offset.as_u32())?;
+        masm.return_call(kind)?;
+        masm.reset_stack_pointer(sp_offset)
+    }
+
     /// Calculates the return area for the callee, if any.
     fn make_ret_area<M: MacroAssembler>(
         callee_sig: &ABISig,
@@ -362,6 +422,110 @@ impl FnCall {
         Ok(())
     }
 
+    /// Assign arguments for a tail call.
+    ///
+    /// Register arguments are assigned as in [`Self::assign`]; stack arguments
+    /// are stored in the incoming argument area of the current function, which
+    /// is addressed from the frame pointer. This assumes that the value stack
+    /// has been spilled, so that none of the values to be assigned reference
+    /// the incoming argument area.
+    fn assign_tail<M: MacroAssembler>(
+        sig: &ABISig,
+        callee_context: &ContextArgs,
+        context: &mut CodeGenContext<Emission>,
+        masm: &mut M,
+    ) -> Result<()> {
+        let arg_base_offset = u32::from(<M::ABI as ABI>::arg_base_offset());
+        let arg_count = sig.params.len_without_retptr();
+        debug_assert!(arg_count >= callee_context.len());
+
+        // Forward the address of the current function's results area, given
+        // that the callee's results are the current function's results. This
+        // is done first, as the slot holding the address might be part of the
+        // incoming argument area, which is overwritten by the stack arguments.
+        if sig.has_stack_results() {
+            let operand = sig.params.unwrap_results_area_operand();
+            let base = context.frame.results_base_slot.as_ref().unwrap();
+            let addr = masm.local_address(base)?;
+            match operand {
+                &ABIOperand::Reg { ty, reg, .. } => {
+                    masm.load(addr, writable!(reg), ty.try_into()?)?;
+                }
+                &ABIOperand::Stack { ty, offset, .. } => {
+                    let slot = LocalSlot::stack_arg(ty, offset + arg_base_offset);
+                    masm.with_scratch::<IntScratch, _>(|masm, scratch| {
+                        masm.load(addr, scratch.writable(), ty.try_into()?)?;
+                        let dst = masm.local_address(&slot)?;
+                        masm.store(scratch.inner().into(), dst, ty.try_into()?)
+                    })?;
+                }
+            }
+        }
+
+        let stack_values = context.stack.peekn(arg_count - callee_context.len());
+        for (arg, val) in sig
+            .params_without_retptr()
+            .iter()
+            .skip(callee_context.len())
+            .zip(stack_values)
+        {
+            match arg {
+                &ABIOperand::Reg { reg, .. } => {
+                    context.move_val_to_reg(&val, reg, masm)?;
+                }
+                &ABIOperand::Stack { ty, offset, .. } => {
+                    let slot = LocalSlot::stack_arg(ty, offset + arg_base_offset);
+                    let size: OperandSize = ty.try_into()?;
+                    masm.with_scratch_for(ty, |masm, scratch| {
+                        context.move_val_to_reg(val, scratch.inner(), masm)?;
+                        let addr = masm.local_address(&slot)?;
+                        masm.store(scratch.inner().into(), addr, size)
+                    })?;
+                }
+            }
+        }
+
+        // The context arguments are assigned last, as they may need to be
+        // stored in the incoming argument area too.
+        if callee_context.len() > 0 {
+            Self::assign_tail_context_args(sig, callee_context, arg_base_offset, masm)?;
+        }
+        Ok(())
+    }
+
+    /// Materializes any [ContextArgs] as a tail call argument.
+    fn assign_tail_context_args<M: MacroAssembler>(
+        sig: &ABISig,
+        context: &ContextArgs,
+        arg_base_offset: u32,
+        masm: &mut M,
+    ) -> Result<()> {
+        for (context_arg, operand) in context
+            .as_slice()
+            .iter()
+            .zip(sig.params_without_retptr().iter().take(context.len()))
+        {
+            let src = match (context_arg, operand) {
+                (VMContextLoc::Pinned, _) => vmctx!(M),
+                (VMContextLoc::Reg(src), _) => *src,
+                (VMContextLoc::OffsetFromPinned(_), _) => {
+                    crate::bail!("unimplemented tail call with vmctx offset argument");
+                }
+            };
+            match operand {
+                ABIOperand::Reg { ty, reg, .. } => {
+                    masm.mov(writable!(*reg), src.into(), (*ty).try_into()?)?;
+                }
+                ABIOperand::Stack { ty, offset, .. } => {
+                    let slot = LocalSlot::stack_arg(*ty, *offset + arg_base_offset);
+                    let addr = masm.local_address(&slot)?;
+                    masm.store(src.into(), addr, (*ty).try_into()?)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Cleanup stack space, handle multiple results, and free registers after
     /// emitting the call.
     fn cleanup<M: MacroAssembler>(
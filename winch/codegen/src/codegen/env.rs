@@ -133,6 +133,10 @@ pub struct FuncEnv<'a, 'translation: 'a, 'data: 'translation, P: PtrSize> {
     table_access_spectre_mitigation: bool,
     /// Size of pages on the compilation target.
     pub page_size_log2: u8,
+    /// The index of the function being compiled.
+    pub func_index: FuncIndex,
+    /// The offset of the start of the function's body within the module.
+    pub func_body_offset: usize,
     name_map: PrimaryMap<UserExternalNameRef, UserExternalName>,
     name_intern: HashMap<UserExternalName, UserExternalNameRef>,
 }
@@ -152,6 +156,8 @@ impl<'a, 'translation, 'data, P: PtrSize> FuncEnv<'a, 'translation, 'data, P> {
         builtins: &'translation mut BuiltinFunctions,
         isa: &dyn TargetIsa,
         ptr_type: WasmValType,
+        func_index: FuncIndex,
+        func_body_offset: usize,
     ) -> Self {
         Self {
             vmoffsets,
@@ -163,9 +169,13 @@ impl<'a, 'translation, 'data, P: PtrSize> FuncEnv<'a, 'translation, 'data, P> {
             resolved_sigs: HashMap::new(),
             resolved_globals: HashMap::new(),
             ptr_type,
-            heap_access_spectre_mitigation: isa.flags().enable_heap_access_spectre_mitigation(),
-            table_access_spectre_mitigation: isa.flags().enable_table_access_spectre_mitigation(),
+            heap_access_spectre_mitigation: isa.flags().enable_heap_access_spectre_mitigation()
+                && translation.module.spectre_mitigations.memory(),
+            table_access_spectre_mitigation: isa.flags().enable_table_access_spectre_mitigation()
+                && translation.module.spectre_mitigations.tables(),
             page_size_log2: isa.page_size_align_log2(),
+            func_index,
+            func_body_offset,
             builtins,
             name_map: Default::default(),
             name_intern: Default::default(),
@@ -32,9 +32,10 @@ pub(crate) enum CodeGenError {
     /// Unimplemented due to requiring AVX512DQ.
     #[error("Instruction not implemented for CPUs without AVX512DQ support")]
     UnimplementedForNoAvx512DQ,
-    /// Unsupported eager initialization of tables.
-    #[error("Unsupported eager initialization of tables")]
-    UnsupportedTableEagerInit,
+    /// Unsupported tail call, in which the callee requires more stack space
+    /// for its arguments than the caller.
+    #[error("Unsupported tail call with larger stack arguments than the caller")]
+    UnsupportedTailCallStackArgs,
     /// An internal error.
     ///
     /// This error means that an internal invariant was not met and usually
@@ -104,8 +105,8 @@ impl CodeGenError {
         Self::UnsupportedWasmType
     }
 
-    pub(crate) const fn unsupported_table_eager_init() -> Self {
-        Self::UnsupportedTableEagerInit
+    pub(crate) const fn unsupported_tail_call_stack_args() -> Self {
+        Self::UnsupportedTailCallStackArgs
     }
 
     pub(crate) const fn unimplemented_wasm_instruction() -> Self {
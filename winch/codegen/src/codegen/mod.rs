@@ -13,11 +13,13 @@ use crate::{
     stack::{TypedReg, Val},
 };
 use cranelift_codegen::{
+    ValueLabelsRanges,
     binemit::CodeOffset,
     ir::{RelSourceLoc, SourceLoc},
 };
 use smallvec::SmallVec;
 use std::marker::PhantomData;
+use std::ops::Range;
 use wasmparser::{
     BinaryReader, FuncValidator, MemArg, Operator, OperatorsReader, ValidatorResources,
     VisitOperator, VisitSimdOperator,
@@ -118,6 +120,10 @@ where
 
     /// Local counter to track fuel consumption.
     pub fuel_consumed: i64,
+
+    /// The range of machine code offsets in which the frame is fully set
+    /// up; from the end of the prologue to the start of the epilogue.
+    pub frame_body_range: Range<CodeOffset>,
     phase: PhantomData<P>,
 }
 
@@ -142,6 +148,7 @@ where
             control_frames: Default::default(),
             // Empty functions should consume at least 1 fuel unit.
             fuel_consumed: 1,
+            frame_body_range: 0..0,
             phase: PhantomData,
         }
     }
@@ -192,6 +199,7 @@ where
         }
 
         self.masm.end_source_loc()?;
+        let body_start = self.masm.current_code_offset()?;
 
         Ok(CodeGen {
             sig: self.sig,
@@ -202,6 +210,7 @@ where
             source_location: self.source_location,
             control_frames: self.control_frames,
             fuel_consumed: self.fuel_consumed,
+            frame_body_range: body_start..body_start,
             phase: PhantomData,
         })
     }
@@ -253,6 +262,14 @@ where
         Ok(())
     }
 
+    /// Describes the location of the WebAssembly locals for native debug
+    /// information.
+    pub fn value_labels_ranges(&self) -> ValueLabelsRanges {
+        self.context
+            .frame
+            .value_labels_ranges::<M::ABI>(self.frame_body_range.clone())
+    }
+
     /// Pops a control frame from the control frame stack.
     pub fn pop_control_frame(&mut self) -> Result<ControlStackFrame> {
         self.control_frames
@@ -322,6 +339,8 @@ where
 
         self.maybe_emit_epoch_check()?;
 
+        self.maybe_emit_profile_call()?;
+
         // Once we have emitted the epilogue and reserved stack space for the locals, we push the
         // base control flow block.
         self.control_frames.push(ControlStackFrame::block(
@@ -415,6 +434,14 @@ where
                 if self.tunables.consume_fuel {
                     self.fuel_before_visit_op(operator)?;
                 }
+
+                // Handle profile instrumentation.
+                if self.tunables.profile_instrumentation
+                    && self.context.reachable
+                    && matches!(operator, Operator::If { .. } | Operator::BrIf { .. })
+                {
+                    self.emit_profile_branch(offset)?;
+                }
                 Ok(())
             }
 
@@ -528,6 +555,7 @@ where
             self.context.stack.len() == 0,
             CodeGenError::unexpected_value_in_value_stack()
         );
+        self.frame_body_range.end = self.masm.current_code_offset()?;
         self.masm.free_stack(self.context.frame.locals_size)?;
         self.masm.epilogue()?;
         self.masm.end_source_loc()?;
@@ -575,12 +603,24 @@ where
             heap_type == WasmHeapType::Func,
             CodeGenError::unsupported_wasm_type()
         );
-        ensure!(
-            self.tunables.table_lazy_init,
-            CodeGenError::unsupported_table_eager_init()
-        );
         let table_data = self.env.resolve_table_data(table_index);
         let ptr_type = self.env.ptr_type();
+
+        // Without lazy initialization every element is initialized during
+        // instantiation and stored without the initialized bit, so the element
+        // can be loaded as-is.
+        if !self.tunables.table_lazy_init {
+            let index = self.context.pop_to_reg(self.masm, None)?;
+            let base = self.context.any_gpr(self.masm)?;
+            let elem_addr = self.emit_compute_table_elem_addr(index.into(), base, &table_data)?;
+            self.masm.load_ptr(elem_addr, writable!(base))?;
+            self.context.free_reg(index);
+            self.context
+                .stack
+                .push(TypedReg::new(ptr_type, base).into());
+            return Ok(());
+        }
+
         let builtin = self.env.builtins.table_get_lazy_init_func_ref::<M::ABI>()?;
 
         // Request the builtin's result register and use it to hold the table
@@ -667,23 +707,21 @@ where
             table.ref_type.heap_type == WasmHeapType::Func,
             CodeGenError::unsupported_wasm_type()
         );
-        ensure!(
-            self.tunables.table_lazy_init,
-            CodeGenError::unsupported_table_eager_init()
-        );
         let ptr_type = self.env.ptr_type();
         let table_data = self.env.resolve_table_data(table_index);
         let value = self.context.pop_to_reg(self.masm, None)?;
         let index = self.context.pop_to_reg(self.masm, None)?;
         let base = self.context.any_gpr(self.masm)?;
         let elem_addr = self.emit_compute_table_elem_addr(index.into(), base, &table_data)?;
-        // Set the initialized bit.
-        self.masm.or(
-            writable!(value.into()),
-            value.into(),
-            RegImm::i64(FUNCREF_INIT_BIT as i64),
-            ptr_type.try_into()?,
-        )?;
+        // Set the initialized bit, which is only used with lazy initialization.
+        if self.tunables.table_lazy_init {
+            self.masm.or(
+                writable!(value.into()),
+                value.into(),
+                RegImm::i64(FUNCREF_INIT_BIT as i64),
+                ptr_type.try_into()?,
+            )?;
+        }
 
         self.masm.store_ptr(value.into(), elem_addr)?;
 
@@ -1104,9 +1142,16 @@ where
             .memory
             .can_elide_bounds_check(&memory_tunables, self.env.page_size_log2);
 
+        // If this memory can't move then it never grows beyond its
+        // reservation, unless its minimum size already exceeds the reservation.
+        // Note that this is a property of the memory, not just of the
+        // configuration, as memories with a fixed size never move for example.
+        let memory_reservation = memory_tunables.reservation();
+        let fits_in_reservation = !heap.memory.memory_may_move(&memory_tunables)
+            && heap.memory.minimum_byte_size().unwrap_or(u64::MAX) <= memory_reservation;
+
         let addr = if offset_with_access_size > heap.memory.maximum_byte_size().unwrap_or(u64::MAX)
-            || (!self.tunables.memory_may_move
-                && offset_with_access_size > self.tunables.memory_reservation)
+            || (fits_in_reservation && offset_with_access_size > memory_reservation)
         {
             // Detect at compile time if the access is out of bounds.
             // Doing so will put the compiler in an unreachable code state,
@@ -1147,8 +1192,7 @@ where
         // [bounds::ensure_index_and_offset].
         } else if can_elide_bounds_check
             && u64::from(u32::MAX)
-                <= self.tunables.memory_reservation + self.tunables.memory_guard_size
-                    - offset_with_access_size
+                <= memory_reservation + memory_tunables.guard_size() - offset_with_access_size
         {
             assert!(can_elide_bounds_check);
             assert!(heap.index_type() == WasmValType::I32);
@@ -2095,6 +2139,52 @@ where
         Ok(())
     }
 
+    /// Checks if profile instrumentation is enabled and emits a call
+    /// recording the invocation of the current function.
+    pub fn maybe_emit_profile_call(&mut self) -> Result<()> {
+        if !self.tunables.profile_instrumentation {
+            return Ok(());
+        }
+
+        let builtin = self.env.builtins.profile_call::<M::ABI>()?;
+        self.context
+            .stack
+            .extend([self.env.func_index.as_u32().try_into()?]);
+        FnCall::emit::<M>(
+            &mut self.env,
+            self.masm,
+            &mut self.context,
+            Callee::Builtin(builtin),
+        )
+    }
+
+    /// Emits a call recording which way the `if` or `br_if` at `offset`
+    /// goes, given its condition at the top of the value stack.
+    fn emit_profile_branch(&mut self, offset: usize) -> Result<()> {
+        let builtin = self.env.builtins.profile_branch::<M::ABI>()?;
+        // Branches are keyed by body-relative offset, like branch hints.
+        let offset = u32::try_from(offset - self.env.func_body_offset)?;
+
+        // The condition is consumed by the branch itself, so pass a copy of
+        // it to the builtin.
+        let cond = self.context.pop_to_reg(self.masm, None)?;
+        let taken = self.context.any_gpr(self.masm)?;
+        self.masm
+            .mov(writable!(taken), RegImm::reg(cond.reg), OperandSize::S32)?;
+        self.context.stack.push(cond.into());
+        self.context.stack.extend([
+            self.env.func_index.as_u32().try_into()?,
+            offset.try_into()?,
+            TypedReg::i32(taken).into(),
+        ]);
+        FnCall::emit::<M>(
+            &mut self.env,
+            self.masm,
+            &mut self.context,
+            Callee::Builtin(builtin),
+        )
+    }
+
     /// Emits a series of instructions that load the `fuel_consumed` field from
     /// `VMStoreContext`.
     fn emit_load_fuel_consumed(&mut self, fuel_reg: Reg) -> Result<()> {
@@ -4,6 +4,9 @@ use crate::{
     codegen::{CodeGenPhase, Emission, Prologue},
     masm::MacroAssembler,
 };
+use cranelift_codegen::{
+    LabelValueLoc, ValueLabelsRanges, ValueLocRange, binemit::CodeOffset, ir::ValueLabel,
+};
 use smallvec::SmallVec;
 use std::marker::PhantomData;
 use std::ops::Range;
@@ -279,6 +282,37 @@ impl Frame<Emission> {
         self.get_special_local(0)
     }
 
+    /// Describes the location of each WebAssembly local and of the
+    /// `VMContext` as an offset from the canonical frame address, valid for
+    /// the given range of machine code offsets.
+    ///
+    /// Winch keeps every local in its stack slot, so each label has a single
+    /// location throughout the function body.
+    pub fn value_labels_ranges<A: ABI>(&self, range: Range<CodeOffset>) -> ValueLabelsRanges {
+        let loc_range = |slot: &LocalSlot| {
+            vec![ValueLocRange {
+                loc: LabelValueLoc::CFAOffset(slot.cfa_offset::<A>()),
+                start: range.start,
+                end: range.end,
+            }]
+        };
+
+        let mut ranges = ValueLabelsRanges::default();
+        if range.is_empty() {
+            return ranges;
+        }
+
+        ranges.insert(
+            wasmtime_cranelift::get_vmctx_value_label(),
+            loc_range(self.vmctx_slot()),
+        );
+        for (index, slot) in self.wasm_locals.iter().enumerate() {
+            let index = u32::try_from(index).unwrap();
+            ranges.insert(ValueLabel::from_u32(index), loc_range(slot));
+        }
+        ranges
+    }
+
     /// Returns the address of the local at the given index.
     ///
     /// # Panics
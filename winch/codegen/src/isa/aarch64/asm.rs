@@ -13,7 +13,9 @@ use crate::{
 };
 
 use cranelift_codegen::isa::aarch64;
-use cranelift_codegen::isa::aarch64::inst::emit::{enc_arith_rrr, enc_move_wide, enc_movk};
+use cranelift_codegen::isa::aarch64::inst::emit::{
+    enc_arith_rrr, enc_jump26, enc_move_wide, enc_movk,
+};
 use cranelift_codegen::isa::aarch64::inst::{
     ASIMDFPModImm, FpuToIntOp, MoveWideConst, NZCV, UImm5, VecALUModOp, VecExtendOp, VecRRNarrowOp,
     VecRRPairLongOp, VecRRRLongOp,
@@ -21,6 +23,7 @@ use cranelift_codegen::isa::aarch64::inst::{
 use cranelift_codegen::{
     Final, MachBuffer, MachBufferFinalized, MachInst, MachInstEmit, MachInstEmitState, MachLabel,
     Writable,
+    binemit::Reloc,
     ir::{ExternalName, MemFlagsData, SourceLoc, TrapCode, UserExternalNameRef},
     isa::aarch64::inst::{
         self, ALUOp, ALUOp3, AMode, BitOp, BranchTarget, Cond, CondBrKind, ExtendOp,
@@ -138,6 +141,13 @@ impl Assembler {
     }
 
     fn emit_with_island(&mut self, inst: Inst, needed_space: u32) {
+        self.maybe_emit_island(needed_space);
+        inst.emit(&mut self.buffer, &self.emit_info, &mut self.emit_state);
+    }
+
+    /// Emits an island, jumping over it, if one is needed before emitting
+    /// `needed_space` bytes.
+    fn maybe_emit_island(&mut self, needed_space: u32) {
         if self.buffer.island_needed(needed_space) {
             let label = self.buffer.get_label();
             let jmp = Inst::Jump {
@@ -149,7 +159,6 @@ impl Assembler {
             self.buffer
                 .bind_label(label, self.emit_state.ctrl_plane_mut());
         }
-        inst.emit(&mut self.buffer, &self.emit_info, &mut self.emit_state);
     }
 
     /// Adds a constant to the constant pool, returning its address.
@@ -1387,6 +1396,25 @@ impl Assembler {
         })
     }
 
+    /// Emit a tail call, i.e. a branch, to a locally defined function through
+    /// an index.
+    pub fn return_call_with_name(&mut self, name: UserExternalNameRef) {
+        self.maybe_emit_island(4);
+        self.buffer
+            .add_reloc(Reloc::Arm64Call, &ExternalName::user(name), 0);
+        // The branch offset is patched through the relocation.
+        self.buffer.put4(enc_jump26(0b000101, 0));
+    }
+
+    /// Emit a tail call, i.e. an indirect branch, to the address stored in
+    /// the `callee` register.
+    pub fn return_call_with_reg(&mut self, callee: Reg) {
+        self.emit(Inst::IndirectBr {
+            rn: callee.into(),
+            targets: vec![],
+        });
+    }
+
     /// Load the min value for an integer of size out_size, as a floating-point
     /// of size `in-size`, into register `rd`.
     fn min_fp_value(
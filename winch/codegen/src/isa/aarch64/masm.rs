@@ -19,11 +19,12 @@ use crate::{
     masm::{
         CalleeKind, DivKind, Extend, ExtendKind, ExtractLaneKind, FloatCmpKind, FloatScratch,
         Imm as I, IntCmpKind, IntScratch, LoadKind, MacroAssembler as Masm, MulWideKind,
-        OperandSize, RegImm, RemKind, ReplaceLaneKind, RmwOp, RoundingMode, SPOffset, Scratch,
-        ScratchType, ShiftKind, SplatKind, StackSlot, StoreKind, TRUSTED_FLAGS, TrapCode,
-        TruncKind, UNTRUSTED_FLAGS, V128AbsKind, V128AddKind, V128ConvertKind, V128ExtAddKind,
-        V128ExtMulKind, V128ExtendKind, V128MaxKind, V128MinKind, V128MulKind, V128NarrowKind,
-        V128NegKind, V128SubKind, V128TruncKind, VectorCompareKind, VectorEqualityKind, Zero,
+        OperandSize, RegImm, RelaxedSimdSemantics, RemKind, ReplaceLaneKind, RmwOp, RoundingMode,
+        SPOffset, Scratch, ScratchType, ShiftKind, SplatKind, StackSlot, StoreKind, TRUSTED_FLAGS,
+        TrapCode, TruncKind, UNTRUSTED_FLAGS, V128AbsKind, V128AddKind, V128ConvertKind,
+        V128ExtAddKind, V128ExtMulKind, V128ExtendKind, V128MaddKind, V128MaxKind, V128MinKind,
+        V128MulKind, V128NarrowKind, V128NegKind, V128RelaxedDotKind, V128SubKind, V128TruncKind,
+        VectorCompareKind, VectorEqualityKind, Zero,
     },
     stack::{TypedReg, Val},
 };
@@ -217,36 +218,7 @@ impl Masm for MacroAssembler {
     }
 
     fn frame_restore(&mut self) -> Result<()> {
-        debug_assert_eq!(self.sp_offset, 0);
-
-        // Sync the real stack pointer with the value of the shadow stack
-        // pointer.
-        self.move_shadow_sp_to_sp();
-
-        // Pop the shadow stack pointer. It's assumed that at this point
-        // `sp_offset` is 0 and therefore the real stack pointer should be
-        // 16-byte aligned.
-        let offset = SImm9::maybe_from_i64(SHADOW_STACK_POINTER_SLOT_SIZE as i64)
-            .expect("Shadow stack pointer slot size is valid for single addressing");
-        let addr = Address::post_indexed_from_sp(offset);
-        addr.to_addressing_mode(self, OperandSize::S64, |masm, mem| {
-            masm.asm.uload(
-                mem,
-                writable!(regs::shadow_sp()),
-                OperandSize::S64,
-                TRUSTED_FLAGS,
-            );
-            Ok(())
-        })?;
-
-        // Restore the link register and frame pointer.
-        let lr = regs::lr();
-        let fp = regs::fp();
-        let offset = SImm7Scaled::maybe_from_i64(16, types::I64)
-            .expect("Frame pointer offset 16 is valid for pair addressing");
-        let addr = Address::post_indexed_from_sp_for_pair(offset);
-
-        self.asm.ldp(fp, lr, addr.to_pair_addressing_mode());
+        self.frame_teardown()?;
         self.asm.ret();
         Ok(())
     }
@@ -430,6 +402,18 @@ impl Masm for MacroAssembler {
         Ok(total_stack)
     }
 
+    fn return_call(&mut self, callee: CalleeKind) -> Result<()> {
+        // Tear down the frame as in the epilogue, restoring the link register
+        // so that the callee returns directly to the caller of the current
+        // function.
+        self.frame_teardown()?;
+        match callee {
+            CalleeKind::Indirect(reg) => self.asm.return_call_with_reg(reg),
+            CalleeKind::Direct(idx) => self.asm.return_call_with_name(idx),
+        }
+        Ok(())
+    }
+
     fn load(&mut self, src: Address, dst: WritableReg, size: OperandSize) -> Result<()> {
         src.to_addressing_mode(self, size, |masm, mem| {
             Ok(masm.asm.uload(mem, dst, size, TRUSTED_FLAGS))
@@ -2162,6 +2146,56 @@ impl Masm for MacroAssembler {
     ) -> Result<()> {
         bail!(CodeGenError::unimplemented_masm_instruction())
     }
+
+    fn v128_relaxed_swizzle(&mut self, _dst: WritableReg, _lhs: Reg, _rhs: Reg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_relaxed_laneselect(
+        &mut self,
+        _src1: Reg,
+        _src2: Reg,
+        _mask: Reg,
+        _dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_relaxed_trunc_s(
+        &mut self,
+        _src: Reg,
+        _dst: WritableReg,
+        _size: OperandSize,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_relaxed_q15mulr_s(&mut self, _lhs: Reg, _rhs: Reg, _dst: WritableReg) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_relaxed_dot(
+        &mut self,
+        _context: &mut CodeGenContext<Emission>,
+        _kind: V128RelaxedDotKind,
+        _semantics: RelaxedSimdSemantics,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
+
+    fn v128_relaxed_madd<
+        F: FnMut(&mut FuncEnv<Self::Ptr>, &mut CodeGenContext<Emission>, &mut Self) -> Result<()>,
+    >(
+        &mut self,
+        _env: &mut FuncEnv<Self::Ptr>,
+        _context: &mut CodeGenContext<Emission>,
+        _kind: V128MaddKind,
+        _semantics: RelaxedSimdSemantics,
+        _fallback: F,
+    ) -> Result<()> {
+        bail!(CodeGenError::unimplemented_masm_instruction())
+    }
 }
 
 impl MacroAssembler {
@@ -2178,6 +2212,44 @@ impl MacroAssembler {
         self.sp_offset -= bytes;
     }
 
+    /// Restores the caller's frame, leaving the stack pointer and the link
+    /// register as they were at function entry.
+    ///
+    /// Used by both the epilogue and tail calls.
+    fn frame_teardown(&mut self) -> Result<()> {
+        debug_assert_eq!(self.sp_offset, 0);
+
+        // Sync the real stack pointer with the value of the shadow stack
+        // pointer.
+        self.move_shadow_sp_to_sp();
+
+        // Pop the shadow stack pointer. It's assumed that at this point
+        // `sp_offset` is 0 and therefore the real stack pointer should be
+        // 16-byte aligned.
+        let offset = SImm9::maybe_from_i64(SHADOW_STACK_POINTER_SLOT_SIZE as i64)
+            .expect("Shadow stack pointer slot size is valid for single addressing");
+        let addr = Address::post_indexed_from_sp(offset);
+        addr.to_addressing_mode(self, OperandSize::S64, |masm, mem| {
+            masm.asm.uload(
+                mem,
+                writable!(regs::shadow_sp()),
+                OperandSize::S64,
+                TRUSTED_FLAGS,
+            );
+            Ok(())
+        })?;
+
+        // Restore the link register and frame pointer.
+        let lr = regs::lr();
+        let fp = regs::fp();
+        let offset = SImm7Scaled::maybe_from_i64(16, types::I64)
+            .expect("Frame pointer offset 16 is valid for pair addressing");
+        let addr = Address::post_indexed_from_sp_for_pair(offset);
+
+        self.asm.ldp(fp, lr, addr.to_pair_addressing_mode());
+        Ok(())
+    }
+
     // Copies the value of the stack pointer to the shadow stack
     // pointer: mov x28, sp
 
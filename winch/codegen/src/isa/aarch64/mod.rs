@@ -16,7 +16,9 @@ use masm::MacroAssembler as Aarch64Masm;
 use target_lexicon::Triple;
 use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
 use wasmtime_cranelift::CompiledFunction;
-use wasmtime_environ::{ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType};
+use wasmtime_environ::{
+    FuncIndex, ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType,
+};
 
 mod abi;
 mod address;
@@ -81,6 +83,7 @@ impl TargetIsa for Aarch64 {
 
     fn compile_function(
         &self,
+        index: FuncIndex,
         sig: &WasmFuncType,
         body: &FunctionBody,
         translation: &ModuleTranslation,
@@ -107,6 +110,8 @@ impl TargetIsa for Aarch64 {
             builtins,
             self,
             abi::Aarch64ABI::ptr_type(),
+            index,
+            body.original_position(),
         );
         let type_converter = TypeConverter::new(env.translation, env.types);
         let defined_locals =
@@ -118,13 +123,17 @@ impl TargetIsa for Aarch64 {
 
         let mut body_codegen = codegen.emit_prologue()?;
         body_codegen.emit(body, validator)?;
+        let value_labels_ranges = tunables
+            .debug_native
+            .then(|| body_codegen.value_labels_ranges());
         let names = body_codegen.env.take_name_map();
         let base = body_codegen.source_location.base;
-        Ok(CompiledFunction::new(
-            masm.finalize(base)?,
-            names,
-            self.function_alignment(),
-        ))
+        let mut func =
+            CompiledFunction::new(masm.finalize(base)?, names, self.function_alignment());
+        if let Some(ranges) = value_labels_ranges {
+            func.set_value_labels_ranges(ranges);
+        }
+        Ok(func)
     }
 
     fn text_section_builder(&self, num_funcs: usize) -> Box<dyn TextSectionBuilder> {
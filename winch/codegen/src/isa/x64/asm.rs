@@ -12,6 +12,7 @@ use crate::{
 use cranelift_codegen::{
     CallInfo, Final, MachBuffer, MachBufferFinalized, MachInst, MachInstEmit, MachInstEmitState,
     MachLabel, PatchRegion, Writable,
+    binemit::Reloc,
     ir::{ExternalName, MemFlagsData, SourceLoc, TrapCode, Type, UserExternalNameRef, types},
     isa::{
         unwind::UnwindInst,
@@ -1618,6 +1619,29 @@ impl Assembler {
         });
     }
 
+    /// Emit a tail call, i.e. a jump, to the address stored in the `callee`
+    /// register.
+    pub fn return_call_with_reg(&mut self, callee: Reg) {
+        let inst = asm::inst::jmpq_m::new(callee).into();
+        self.emit(Inst::External { inst });
+    }
+
+    /// Emit a tail call, i.e. a jump, to a locally defined function through an
+    /// index.
+    pub fn return_call_with_name(&mut self, name: UserExternalNameRef) {
+        let inst = asm::inst::jmp_d32::new(0).into();
+        self.emit(Inst::External { inst });
+        // The addend adjusts for the difference between the end of the
+        // instruction and the beginning of the immediate field.
+        let offset = self.buffer.cur_offset();
+        self.buffer.add_reloc_at_offset(
+            offset - 4,
+            Reloc::X86CallPCRel4,
+            &ExternalName::user(name),
+            -4,
+        );
+    }
+
     /// Emits a conditional jump to the given label.
     pub fn jmp_if(&mut self, cc: impl Into<CC>, taken: MachLabel) {
         self.emit(Inst::WinchJmpIf {
@@ -2760,6 +2784,60 @@ impl Assembler {
         let inst = asm::inst::vpmaddwd_b::new(dst, src1, src2).into();
         self.emit(Inst::External { inst });
     }
+
+    /// Add adjacent pairs of integers in `src1` and `src2`, putting the sums
+    /// of pairs from `src1` in the lower half of `dst` and those from `src2`
+    /// in the upper half.
+    pub fn xmm_vphadd_rrr(&mut self, src1: Reg, src2: Reg, dst: WritableReg, size: OperandSize) {
+        let dst: WritableXmm = dst.map(|r| r.into());
+        let inst = match size {
+            OperandSize::S16 => asm::inst::vphaddw_b::new(dst, src1, src2).into(),
+            OperandSize::S32 => asm::inst::vphaddd_b::new(dst, src1, src2).into(),
+            _ => unimplemented!(),
+        };
+        self.emit(Inst::External { inst });
+    }
+
+    /// Selects lanes from `src2` where the most significant bit of the
+    /// corresponding lane in `mask` is set and from `src1` otherwise.
+    pub fn xmm_vpblendv_rrrr(
+        &mut self,
+        src1: Reg,
+        src2: Reg,
+        mask: Reg,
+        dst: WritableReg,
+        size: OperandSize,
+    ) {
+        let dst: WritableXmm = dst.map(|r| r.into());
+        let inst = match size {
+            OperandSize::S8 => asm::inst::vpblendvb_rvmr::new(dst, src1, src2, mask).into(),
+            OperandSize::S32 => asm::inst::vblendvps_rvmr::new(dst, src1, src2, mask).into(),
+            OperandSize::S64 => asm::inst::vblendvpd_rvmr::new(dst, src1, src2, mask).into(),
+            _ => unimplemented!(),
+        };
+        self.emit(Inst::External { inst });
+    }
+
+    /// Fused multiply-add of floats, computing `src1 * dst + src2` into
+    /// `dst`, or `-(src1 * dst) + src2` if `negate` is set.
+    pub fn xmm_vfmadd213p_rrr(
+        &mut self,
+        src1: Reg,
+        src2: Reg,
+        dst: WritableReg,
+        size: OperandSize,
+        negate: bool,
+    ) {
+        let dst = pair_xmm(dst);
+        let inst = match (size, negate) {
+            (OperandSize::S32, false) => asm::inst::vfmadd213ps_a::new(dst, src1, src2).into(),
+            (OperandSize::S64, false) => asm::inst::vfmadd213pd_a::new(dst, src1, src2).into(),
+            (OperandSize::S32, true) => asm::inst::vfnmadd213ps_a::new(dst, src1, src2).into(),
+            (OperandSize::S64, true) => asm::inst::vfnmadd213pd_a::new(dst, src1, src2).into(),
+            _ => unimplemented!(),
+        };
+        self.emit(Inst::External { inst });
+    }
 }
 
 /// Captures the region in a MachBuffer where an add-with-immediate instruction would be emitted,
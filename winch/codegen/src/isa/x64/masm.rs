@@ -8,11 +8,11 @@ use super::{
 use crate::masm::{
     DivKind, Extend, ExtendKind, ExtractLaneKind, FloatCmpKind, FloatScratch, Imm as I, IntCmpKind,
     IntScratch, LaneSelector, LoadKind, MacroAssembler as Masm, MulWideKind, OperandSize, RegImm,
-    RemKind, ReplaceLaneKind, RmwOp, RoundingMode, Scratch, ScratchType, ShiftKind, SplatKind,
-    StoreKind, TRUSTED_FLAGS, TrapCode, TruncKind, UNTRUSTED_FLAGS, V128AbsKind, V128AddKind,
-    V128ConvertKind, V128ExtAddKind, V128ExtMulKind, V128ExtendKind, V128MaxKind, V128MinKind,
-    V128MulKind, V128NarrowKind, V128NegKind, V128SubKind, V128TruncKind, VectorCompareKind,
-    VectorEqualityKind, Zero,
+    RelaxedSimdSemantics, RemKind, ReplaceLaneKind, RmwOp, RoundingMode, Scratch, ScratchType,
+    ShiftKind, SplatKind, StoreKind, TRUSTED_FLAGS, TrapCode, TruncKind, UNTRUSTED_FLAGS,
+    V128AbsKind, V128AddKind, V128ConvertKind, V128ExtAddKind, V128ExtMulKind, V128ExtendKind,
+    V128MaddKind, V128MaxKind, V128MinKind, V128MulKind, V128NarrowKind, V128NegKind,
+    V128RelaxedDotKind, V128SubKind, V128TruncKind, VectorCompareKind, VectorEqualityKind, Zero,
 };
 use crate::{
     Result,
@@ -342,6 +342,19 @@ impl Masm for MacroAssembler {
         Ok(total_stack)
     }
 
+    fn return_call(&mut self, callee: CalleeKind) -> Result<()> {
+        // Tear down the frame as in the epilogue, but jump to the callee
+        // rather than returning; the return address of the current function
+        // is left in place, so that the callee returns directly to it.
+        debug_assert_eq!(self.sp_offset, 0);
+        self.asm.pop_r(writable!(rbp()));
+        match callee {
+            CalleeKind::Indirect(reg) => self.asm.return_call_with_reg(reg),
+            CalleeKind::Direct(idx) => self.asm.return_call_with_name(idx),
+        }
+        Ok(())
+    }
+
     fn load_ptr(&mut self, src: Self::Address, dst: WritableReg) -> Result<()> {
         self.load(src, dst, self.ptr_size)
     }
@@ -3282,6 +3295,182 @@ impl Masm for MacroAssembler {
         self.asm.xmm_vmaxp_rrr(rhs, lhs, dst, size);
         Ok(())
     }
+
+    fn v128_relaxed_swizzle(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg) -> Result<()> {
+        self.ensure_has_avx()?;
+        // `vpshufb` selects 0 for lanes of `rhs` with their most significant
+        // bit set and otherwise only uses the least significant 4 bits, which
+        // is a valid result for all out-of-range lanes.
+        self.asm.xmm_vpshufb_rrr(dst, lhs, rhs);
+        Ok(())
+    }
+
+    fn v128_relaxed_laneselect(
+        &mut self,
+        src1: Reg,
+        src2: Reg,
+        mask: Reg,
+        dst: WritableReg,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+        match size {
+            // There's no variant of `vpblendv` which only looks at the most
+            // significant bit of 16-bit lanes.
+            OperandSize::S16 => self.v128_bitselect(src1, src2, mask, dst)?,
+            _ => self.asm.xmm_vpblendv_rrrr(src2, src1, mask, dst, size),
+        }
+        Ok(())
+    }
+
+    fn v128_relaxed_trunc_s(
+        &mut self,
+        src: Reg,
+        dst: WritableReg,
+        size: OperandSize,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+        // Out-of-range and NaN lanes are converted to `0x80000000`.
+        let kind = match size {
+            OperandSize::S32 => VcvtKind::F32ToI32,
+            OperandSize::S64 => VcvtKind::F64ToI32,
+            _ => bail!(CodeGenError::unexpected_operand_size()),
+        };
+        self.asm.xmm_vcvt_rr(src, dst, kind);
+        Ok(())
+    }
+
+    fn v128_relaxed_q15mulr_s(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg) -> Result<()> {
+        self.ensure_has_avx()?;
+        self.asm.xmm_vpmulhrs_rrr(lhs, rhs, dst, OperandSize::S16);
+        Ok(())
+    }
+
+    fn v128_relaxed_dot(
+        &mut self,
+        context: &mut CodeGenContext<Emission>,
+        kind: V128RelaxedDotKind,
+        semantics: RelaxedSimdSemantics,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+
+        let acc = match kind {
+            V128RelaxedDotKind::I16x8 => None,
+            V128RelaxedDotKind::I32x4Add => Some(context.pop_to_reg(self, None)?),
+        };
+        let rhs = context.pop_to_reg(self, None)?;
+        let lhs = context.pop_to_reg(self, None)?;
+        let dst = writable!(lhs.reg);
+
+        // A vector of 16-bit 1's, used with `vpmaddwd` to sign extend and add
+        // adjacent 16-bit lanes into 32-bit lanes.
+        let ones = [1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0];
+
+        match semantics {
+            RelaxedSimdSemantics::Native => {
+                // `vpmaddubsw` treats its first operand as unsigned and its
+                // second operand as signed, so pass `rhs` first as its lanes
+                // are expected to fit in 7 bits.
+                self.asm.xmm_vpmaddubsw_rrr(rhs.reg, lhs.reg, dst);
+                if let Some(acc) = acc {
+                    let ones = self.asm.add_constant(&ones);
+                    self.asm.xmm_vpmaddwd_rmr(lhs.reg, &ones, dst);
+                    self.asm
+                        .xmm_vpadd_rrr(lhs.reg, acc.reg, dst, OperandSize::S32);
+                }
+            }
+            RelaxedSimdSemantics::Deterministic => {
+                // Treat both operands as signed: sign extend the low and high
+                // halves of each to 16 bits and multiply them, which can't
+                // overflow, and then add adjacent products together.
+                let hi = writable!(context.any_fpr(self)?);
+                self.v128_extend(lhs.reg, hi, V128ExtendKind::HighI8x16S)?;
+                self.v128_extend(lhs.reg, dst, V128ExtendKind::LowI8x16S)?;
+                self.with_scratch::<FloatScratch, _>(|masm, scratch| {
+                    masm.v128_extend(rhs.reg, scratch.writable(), V128ExtendKind::HighI8x16S)?;
+                    masm.asm
+                        .xmm_vpmull_rrr(hi.to_reg(), scratch.inner(), hi, OperandSize::S16);
+                    wasmtime_environ::error::Ok(())
+                })?;
+                self.v128_extend(rhs.reg, writable!(rhs.reg), V128ExtendKind::LowI8x16S)?;
+                self.asm
+                    .xmm_vpmull_rrr(lhs.reg, rhs.reg, dst, OperandSize::S16);
+
+                match acc {
+                    None => {
+                        self.asm
+                            .xmm_vphadd_rrr(lhs.reg, hi.to_reg(), dst, OperandSize::S16);
+                    }
+                    Some(acc) => {
+                        // Sum the products in 32 bits to avoid wrapping.
+                        let ones = self.asm.add_constant(&ones);
+                        self.asm.xmm_vpmaddwd_rmr(lhs.reg, &ones, dst);
+                        self.asm.xmm_vpmaddwd_rmr(hi.to_reg(), &ones, hi);
+                        self.asm
+                            .xmm_vphadd_rrr(lhs.reg, hi.to_reg(), dst, OperandSize::S32);
+                        self.asm
+                            .xmm_vpadd_rrr(lhs.reg, acc.reg, dst, OperandSize::S32);
+                    }
+                }
+                context.free_reg(hi.to_reg());
+            }
+        }
+
+        if let Some(acc) = acc {
+            context.free_reg(acc);
+        }
+        context.free_reg(rhs);
+        context.stack.push(TypedReg::v128(lhs.reg).into());
+        Ok(())
+    }
+
+    fn v128_relaxed_madd<
+        F: FnMut(&mut FuncEnv<Self::Ptr>, &mut CodeGenContext<Emission>, &mut Self) -> Result<()>,
+    >(
+        &mut self,
+        env: &mut FuncEnv<Self::Ptr>,
+        context: &mut CodeGenContext<Emission>,
+        kind: V128MaddKind,
+        semantics: RelaxedSimdSemantics,
+        mut fallback: F,
+    ) -> Result<()> {
+        self.ensure_has_avx()?;
+
+        let size = kind.lane_size();
+        let fused = self.flags.has_fma();
+        if !fused && semantics == RelaxedSimdSemantics::Deterministic {
+            if kind.is_negated() {
+                let c = context.pop_to_reg(self, None)?;
+                let b = context.pop_to_reg(self, None)?;
+                let a = context.pop_to_reg(self, None)?;
+                self.v128_neg(writable!(a.reg), kind.into())?;
+                context.stack.push(a.into());
+                context.stack.push(b.into());
+                context.stack.push(c.into());
+            }
+            return fallback(env, context, self);
+        }
+
+        let c = context.pop_to_reg(self, None)?;
+        let b = context.pop_to_reg(self, None)?;
+        let a = context.pop_to_reg(self, None)?;
+        let dst = writable!(a.reg);
+        if fused {
+            self.asm
+                .xmm_vfmadd213p_rrr(b.reg, c.reg, dst, size, kind.is_negated());
+        } else {
+            self.asm.xmm_vmulp_rrr(a.reg, b.reg, dst, size);
+            if kind.is_negated() {
+                self.asm.xmm_vsubp_rrr(c.reg, a.reg, dst, size);
+            } else {
+                self.asm.xmm_vaddp_rrr(a.reg, c.reg, dst, size);
+            }
+        }
+        context.free_reg(b);
+        context.free_reg(c);
+        context.stack.push(a.into());
+        Ok(())
+    }
 }
 
 impl MacroAssembler {
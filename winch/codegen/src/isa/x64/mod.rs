@@ -15,7 +15,9 @@ use cranelift_codegen::{MachTextSectionBuilder, TextSectionBuilder};
 use target_lexicon::Triple;
 use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
 use wasmtime_cranelift::CompiledFunction;
-use wasmtime_environ::{ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType};
+use wasmtime_environ::{
+    FuncIndex, ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType,
+};
 
 use self::regs::{fpr_bit_set, gpr_bit_set};
 
@@ -84,6 +86,7 @@ impl TargetIsa for X64 {
 
     fn compile_function(
         &self,
+        index: FuncIndex,
         sig: &WasmFuncType,
         body: &FunctionBody,
         translation: &ModuleTranslation,
@@ -112,6 +115,8 @@ impl TargetIsa for X64 {
             builtins,
             self,
             abi::X64ABI::ptr_type(),
+            index,
+            body.original_position(),
         );
         let type_converter = TypeConverter::new(env.translation, env.types);
         let defined_locals =
@@ -126,12 +131,17 @@ impl TargetIsa for X64 {
         body_codegen.emit(body, validator)?;
         let base = body_codegen.source_location.base;
 
+        let value_labels_ranges = tunables
+            .debug_native
+            .then(|| body_codegen.value_labels_ranges());
+
         let names = body_codegen.env.take_name_map();
-        Ok(CompiledFunction::new(
-            masm.finalize(base)?,
-            names,
-            self.function_alignment(),
-        ))
+        let mut func =
+            CompiledFunction::new(masm.finalize(base)?, names, self.function_alignment());
+        if let Some(ranges) = value_labels_ranges {
+            func.set_value_labels_ranges(ranges);
+        }
+        Ok(func)
     }
 
     fn text_section_builder(&self, num_funcs: usize) -> Box<dyn TextSectionBuilder> {
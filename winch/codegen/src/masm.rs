@@ -1042,6 +1042,66 @@ impl From<V128ExtMulKind> for V128MulKind {
     }
 }
 
+/// How instructions from the relaxed SIMD proposal are lowered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum RelaxedSimdSemantics {
+    /// Produce the same results on all hosts, as configured with
+    /// `Config::relaxed_simd_deterministic`.
+    Deterministic,
+    /// Produce whichever of the results permitted by the proposal is cheapest
+    /// to compute on the target.
+    Native,
+}
+
+/// Kinds of relaxed multiply-add supported by WebAssembly.
+#[derive(Copy, Clone)]
+pub(crate) enum V128MaddKind {
+    /// `f32x4.relaxed_madd`.
+    F32x4Madd,
+    /// `f32x4.relaxed_nmadd`.
+    F32x4Nmadd,
+    /// `f64x2.relaxed_madd`.
+    F64x2Madd,
+    /// `f64x2.relaxed_nmadd`.
+    F64x2Nmadd,
+}
+
+impl V128MaddKind {
+    /// The size of the lanes.
+    pub(crate) fn lane_size(&self) -> OperandSize {
+        match self {
+            Self::F32x4Madd | Self::F32x4Nmadd => OperandSize::S32,
+            Self::F64x2Madd | Self::F64x2Nmadd => OperandSize::S64,
+        }
+    }
+
+    /// Whether the product is negated before the addition.
+    pub(crate) fn is_negated(&self) -> bool {
+        match self {
+            Self::F32x4Nmadd | Self::F64x2Nmadd => true,
+            Self::F32x4Madd | Self::F64x2Madd => false,
+        }
+    }
+}
+
+impl From<V128MaddKind> for V128NegKind {
+    fn from(value: V128MaddKind) -> Self {
+        match value {
+            V128MaddKind::F32x4Madd | V128MaddKind::F32x4Nmadd => Self::F32x4,
+            V128MaddKind::F64x2Madd | V128MaddKind::F64x2Nmadd => Self::F64x2,
+        }
+    }
+}
+
+/// Kinds of relaxed dot products supported by WebAssembly.
+#[derive(Copy, Clone)]
+pub(crate) enum V128RelaxedDotKind {
+    /// `i16x8.relaxed_dot_i8x16_i7x16_s`.
+    I16x8,
+    /// `i32x4.relaxed_dot_i8x16_i7x16_add_s`.
+    I32x4Add,
+}
+
 /// Operand size, in bits.
 #[derive(Copy, Debug, Clone, Eq, PartialEq)]
 pub(crate) enum OperandSize {
@@ -1456,6 +1516,15 @@ pub(crate) trait MacroAssembler {
         f: impl FnMut(&mut Self) -> Result<(CalleeKind, CallingConvention)>,
     ) -> Result<u32>;
 
+    /// Emit a tail call to either a local or external function.
+    ///
+    /// All the stack space of the current function, including its locals,
+    /// must have been freed before emitting a tail call. The current frame is
+    /// torn down in the same way as in [`Self::epilogue`] and control is
+    /// transferred to the callee, which returns directly to the caller of the
+    /// current function.
+    fn return_call(&mut self, callee: CalleeKind) -> Result<()>;
+
     /// Acquire a scratch register and execute the given callback.
     fn with_scratch<T: ScratchType, R>(&mut self, f: impl FnOnce(&mut Self, Scratch) -> R) -> R;
 
@@ -2359,4 +2428,73 @@ pub(crate) trait MacroAssembler {
 
     /// Lane-wise maximum value defined as `lhs < rhs ? rhs : lhs`.
     fn v128_pmax(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg, size: OperandSize) -> Result<()>;
+
+    /// Performs a relaxed swizzle between two 128-bit vectors into a 128-bit
+    /// result.
+    ///
+    /// Unlike [`MacroAssembler::swizzle`], lanes of `rhs` with an index of 16
+    /// or greater may select any lane of `lhs` or 0.
+    fn v128_relaxed_swizzle(&mut self, dst: WritableReg, lhs: Reg, rhs: Reg) -> Result<()>;
+
+    /// Selects lanes of `size` from `src1` where the corresponding lane of
+    /// `mask` is all ones, and from `src2` where it's all zeros.
+    ///
+    /// Unlike [`MacroAssembler::v128_bitselect`], lanes of `mask` which are
+    /// neither all ones nor all zeros may select either lane, or a mix of
+    /// their bits.
+    fn v128_relaxed_laneselect(
+        &mut self,
+        src1: Reg,
+        src2: Reg,
+        mask: Reg,
+        dst: WritableReg,
+        size: OperandSize,
+    ) -> Result<()>;
+
+    /// Lane-wise relaxed truncation of floats with lanes of `size` in `src`
+    /// to signed 32-bit integers in `dst`.
+    ///
+    /// Unlike [`MacroAssembler::v128_trunc`], the result of lanes which are
+    /// NaN or out of the range of the destination type is unspecified.
+    fn v128_relaxed_trunc_s(&mut self, src: Reg, dst: WritableReg, size: OperandSize)
+    -> Result<()>;
+
+    /// Perform a relaxed integer q-format rounding multiplication.
+    ///
+    /// Unlike [`MacroAssembler::v128_q15mulr_sat_s`], the result of
+    /// multiplying -1 by -1 (`0x8000` in Q15 format) may be `0x8000` instead
+    /// of saturating to `0x7FFF`.
+    fn v128_relaxed_q15mulr_s(&mut self, lhs: Reg, rhs: Reg, dst: WritableReg) -> Result<()>;
+
+    /// Performs a relaxed dot product of the two 8-bit integer vectors on the
+    /// top of the stack, and for [`V128RelaxedDotKind::I32x4Add`] adds the
+    /// 32-bit integer vector below them to the result.
+    ///
+    /// The result is only fully specified when the lanes of the second
+    /// operand fit in 7 bits, unless `semantics` is
+    /// [`RelaxedSimdSemantics::Deterministic`].
+    fn v128_relaxed_dot(
+        &mut self,
+        context: &mut CodeGenContext<Emission>,
+        kind: V128RelaxedDotKind,
+        semantics: RelaxedSimdSemantics,
+    ) -> Result<()>;
+
+    /// Performs a relaxed multiply-add `a * b + c` of the three vectors on the
+    /// top of the stack, negating the product for nmadd.
+    ///
+    /// The multiply-add may or may not be fused, unless `semantics` is
+    /// [`RelaxedSimdSemantics::Deterministic`] in which case it must be. If
+    /// the target can't emit a fused multiply-add then `fallback` is invoked
+    /// to compute it instead.
+    fn v128_relaxed_madd<
+        F: FnMut(&mut FuncEnv<Self::Ptr>, &mut CodeGenContext<Emission>, &mut Self) -> Result<()>,
+    >(
+        &mut self,
+        env: &mut FuncEnv<Self::Ptr>,
+        context: &mut CodeGenContext<Emission>,
+        kind: V128MaddKind,
+        semantics: RelaxedSimdSemantics,
+        fallback: F,
+    ) -> Result<()>;
 }
@@ -11,11 +11,12 @@ use crate::codegen::{
 };
 use crate::masm::{
     AtomicWaitKind, DivKind, Extend, ExtractLaneKind, FloatCmpKind, IntCmpKind, LoadKind,
-    MacroAssembler, MulWideKind, OperandSize, RegImm, RemKind, ReplaceLaneKind, RmwOp,
-    RoundingMode, SPOffset, ShiftKind, Signed, SplatKind, SplatLoadKind, StoreKind, TruncKind,
-    V128AbsKind, V128AddKind, V128ConvertKind, V128ExtAddKind, V128ExtMulKind, V128ExtendKind,
-    V128LoadExtendKind, V128MaxKind, V128MinKind, V128MulKind, V128NarrowKind, V128NegKind,
-    V128SubKind, V128TruncKind, VectorCompareKind, VectorEqualityKind, Zero,
+    MacroAssembler, MulWideKind, OperandSize, RegImm, RelaxedSimdSemantics, RemKind,
+    ReplaceLaneKind, RmwOp, RoundingMode, SPOffset, ShiftKind, Signed, SplatKind, SplatLoadKind,
+    StoreKind, TruncKind, V128AbsKind, V128AddKind, V128ConvertKind, V128ExtAddKind,
+    V128ExtMulKind, V128ExtendKind, V128LoadExtendKind, V128MaddKind, V128MaxKind, V128MinKind,
+    V128MulKind, V128NarrowKind, V128NegKind, V128RelaxedDotKind, V128SubKind, V128TruncKind,
+    VectorCompareKind, VectorEqualityKind, Zero,
 };
 use crate::reg::{Reg, writable};
 use crate::stack::{TypedReg, Val};
@@ -211,6 +212,8 @@ macro_rules! def_unsupported {
     (emit Drop $($rest:tt)*) => {};
     (emit BrTable $($rest:tt)*) => {};
     (emit CallIndirect $($rest:tt)*) => {};
+    (emit ReturnCall $($rest:tt)*) => {};
+    (emit ReturnCallIndirect $($rest:tt)*) => {};
     (emit TableInit $($rest:tt)*) => {};
     (emit TableCopy $($rest:tt)*) => {};
     (emit TableGet $($rest:tt)*) => {};
@@ -562,6 +565,26 @@ macro_rules! def_unsupported {
     (emit F64x2Min $($rest:tt)*) => {};
     (emit F32x4Max $($rest:tt)*) => {};
     (emit F64x2Max $($rest:tt)*) => {};
+    (emit I8x16RelaxedSwizzle $($rest:tt)*) => {};
+    (emit I32x4RelaxedTruncF32x4S $($rest:tt)*) => {};
+    (emit I32x4RelaxedTruncF32x4U $($rest:tt)*) => {};
+    (emit I32x4RelaxedTruncF64x2SZero $($rest:tt)*) => {};
+    (emit I32x4RelaxedTruncF64x2UZero $($rest:tt)*) => {};
+    (emit F32x4RelaxedMadd $($rest:tt)*) => {};
+    (emit F32x4RelaxedNmadd $($rest:tt)*) => {};
+    (emit F64x2RelaxedMadd $($rest:tt)*) => {};
+    (emit F64x2RelaxedNmadd $($rest:tt)*) => {};
+    (emit I8x16RelaxedLaneselect $($rest:tt)*) => {};
+    (emit I16x8RelaxedLaneselect $($rest:tt)*) => {};
+    (emit I32x4RelaxedLaneselect $($rest:tt)*) => {};
+    (emit I64x2RelaxedLaneselect $($rest:tt)*) => {};
+    (emit F32x4RelaxedMin $($rest:tt)*) => {};
+    (emit F32x4RelaxedMax $($rest:tt)*) => {};
+    (emit F64x2RelaxedMin $($rest:tt)*) => {};
+    (emit F64x2RelaxedMax $($rest:tt)*) => {};
+    (emit I16x8RelaxedQ15mulrS $($rest:tt)*) => {};
+    (emit I16x8RelaxedDotI8x16I7x16S $($rest:tt)*) => {};
+    (emit I32x4RelaxedDotI8x16I7x16AddS $($rest:tt)*) => {};
 
     (emit $unsupported:tt $($rest:tt)*) => {$($rest)*};
 }
@@ -1715,6 +1738,48 @@ where
         Ok(())
     }
 
+    fn visit_return_call(&mut self, index: u32) -> Self::Output {
+        let callee = self.env.callee_from_index(FuncIndex::from_u32(index));
+        FnCall::emit_return_call::<M>(
+            &mut self.env,
+            self.masm,
+            &mut self.context,
+            callee,
+            &self.sig,
+        )?;
+        self.emit_return_call_end()
+    }
+
+    fn visit_return_call_indirect(&mut self, type_index: u32, table_index: u32) -> Self::Output {
+        // Spill now for the same reasons as in `visit_call_indirect`.
+        self.context.spill(self.masm)?;
+
+        let type_index = TypeIndex::from_u32(type_index);
+        let table_index = TableIndex::from_u32(table_index);
+
+        self.emit_table_get(table_index)?;
+
+        let funcref_ptr = self
+            .context
+            .stack
+            .peek()
+            .map(|v| v.unwrap_reg())
+            .ok_or_else(|| CodeGenError::missing_values_in_stack())?;
+        self.masm
+            .trapz(funcref_ptr.into(), TRAP_INDIRECT_CALL_TO_NULL)?;
+        self.emit_typecheck_funcref(funcref_ptr.into(), type_index)?;
+
+        let callee = self.env.funcref(type_index);
+        FnCall::emit_return_call::<M>(
+            &mut self.env,
+            self.masm,
+            &mut self.context,
+            callee,
+            &self.sig,
+        )?;
+        self.emit_return_call_end()
+    }
+
     fn visit_table_init(&mut self, elem: u32, table: u32) -> Self::Output {
         self.emit_table_init(ElemIndex::from_u32(elem), TableIndex::from_u32(table))
     }
@@ -4583,6 +4648,128 @@ where
             })
     }
 
+    fn visit_i8x16_relaxed_swizzle(&mut self) -> Self::Output {
+        if self.relaxed_simd_semantics() == RelaxedSimdSemantics::Deterministic {
+            return self.visit_i8x16_swizzle();
+        }
+        self.context
+            .binop(self.masm, OperandSize::S8, |masm, dst, src, _size| {
+                masm.v128_relaxed_swizzle(writable!(dst), dst, src)?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i32x4_relaxed_trunc_f32x4_s(&mut self) -> Self::Output {
+        if self.relaxed_simd_semantics() == RelaxedSimdSemantics::Deterministic {
+            return self.visit_i32x4_trunc_sat_f32x4_s();
+        }
+        self.context.unop(self.masm, |masm, reg| {
+            masm.v128_relaxed_trunc_s(reg, writable!(reg), OperandSize::S32)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_i32x4_relaxed_trunc_f32x4_u(&mut self) -> Self::Output {
+        self.visit_i32x4_trunc_sat_f32x4_u()
+    }
+
+    fn visit_i32x4_relaxed_trunc_f64x2_s_zero(&mut self) -> Self::Output {
+        if self.relaxed_simd_semantics() == RelaxedSimdSemantics::Deterministic {
+            return self.visit_i32x4_trunc_sat_f64x2_s_zero();
+        }
+        self.context.unop(self.masm, |masm, reg| {
+            masm.v128_relaxed_trunc_s(reg, writable!(reg), OperandSize::S64)?;
+            Ok(TypedReg::v128(reg))
+        })
+    }
+
+    fn visit_i32x4_relaxed_trunc_f64x2_u_zero(&mut self) -> Self::Output {
+        self.visit_i32x4_trunc_sat_f64x2_u_zero()
+    }
+
+    fn visit_f32x4_relaxed_madd(&mut self) -> Self::Output {
+        self.relaxed_madd(V128MaddKind::F32x4Madd)
+    }
+
+    fn visit_f32x4_relaxed_nmadd(&mut self) -> Self::Output {
+        self.relaxed_madd(V128MaddKind::F32x4Nmadd)
+    }
+
+    fn visit_f64x2_relaxed_madd(&mut self) -> Self::Output {
+        self.relaxed_madd(V128MaddKind::F64x2Madd)
+    }
+
+    fn visit_f64x2_relaxed_nmadd(&mut self) -> Self::Output {
+        self.relaxed_madd(V128MaddKind::F64x2Nmadd)
+    }
+
+    fn visit_i8x16_relaxed_laneselect(&mut self) -> Self::Output {
+        self.relaxed_laneselect(OperandSize::S8)
+    }
+
+    fn visit_i16x8_relaxed_laneselect(&mut self) -> Self::Output {
+        self.relaxed_laneselect(OperandSize::S16)
+    }
+
+    fn visit_i32x4_relaxed_laneselect(&mut self) -> Self::Output {
+        self.relaxed_laneselect(OperandSize::S32)
+    }
+
+    fn visit_i64x2_relaxed_laneselect(&mut self) -> Self::Output {
+        self.relaxed_laneselect(OperandSize::S64)
+    }
+
+    fn visit_f32x4_relaxed_min(&mut self) -> Self::Output {
+        match self.relaxed_simd_semantics() {
+            RelaxedSimdSemantics::Deterministic => self.visit_f32x4_min(),
+            RelaxedSimdSemantics::Native => self.visit_f32x4_pmin(),
+        }
+    }
+
+    fn visit_f32x4_relaxed_max(&mut self) -> Self::Output {
+        match self.relaxed_simd_semantics() {
+            RelaxedSimdSemantics::Deterministic => self.visit_f32x4_max(),
+            RelaxedSimdSemantics::Native => self.visit_f32x4_pmax(),
+        }
+    }
+
+    fn visit_f64x2_relaxed_min(&mut self) -> Self::Output {
+        match self.relaxed_simd_semantics() {
+            RelaxedSimdSemantics::Deterministic => self.visit_f64x2_min(),
+            RelaxedSimdSemantics::Native => self.visit_f64x2_pmin(),
+        }
+    }
+
+    fn visit_f64x2_relaxed_max(&mut self) -> Self::Output {
+        match self.relaxed_simd_semantics() {
+            RelaxedSimdSemantics::Deterministic => self.visit_f64x2_max(),
+            RelaxedSimdSemantics::Native => self.visit_f64x2_pmax(),
+        }
+    }
+
+    fn visit_i16x8_relaxed_q15mulr_s(&mut self) -> Self::Output {
+        if self.relaxed_simd_semantics() == RelaxedSimdSemantics::Deterministic {
+            return self.visit_i16x8_q15mulr_sat_s();
+        }
+        self.context
+            .binop(self.masm, OperandSize::S16, |masm, dst, src, _size| {
+                masm.v128_relaxed_q15mulr_s(dst, src, writable!(dst))?;
+                Ok(TypedReg::v128(dst))
+            })
+    }
+
+    fn visit_i16x8_relaxed_dot_i8x16_i7x16_s(&mut self) -> Self::Output {
+        let semantics = self.relaxed_simd_semantics();
+        self.masm
+            .v128_relaxed_dot(&mut self.context, V128RelaxedDotKind::I16x8, semantics)
+    }
+
+    fn visit_i32x4_relaxed_dot_i8x16_i7x16_add_s(&mut self) -> Self::Output {
+        let semantics = self.relaxed_simd_semantics();
+        self.masm
+            .v128_relaxed_dot(&mut self.context, V128RelaxedDotKind::I32x4Add, semantics)
+    }
+
     wasmparser::for_each_visit_simd_operator!(def_unsupported);
 }
 
@@ -4604,6 +4791,67 @@ where
                 Ok(TypedReg::i32(dst)) // Return value for comparisons is an `i32`.
             })
     }
+
+    /// Marks the code following a tail call as unreachable.
+    fn emit_return_call_end(&mut self) -> Result<()> {
+        self.context.reachable = false;
+        // As in `visit_unreachable`, set the implicit outermost frame as
+        // target to perform the necessary stack clean up.
+        let outermost = &mut self.control_frames[0];
+        outermost.set_as_target();
+        Ok(())
+    }
+
+    fn relaxed_simd_semantics(&self) -> RelaxedSimdSemantics {
+        if self.tunables.relaxed_simd_deterministic {
+            RelaxedSimdSemantics::Deterministic
+        } else {
+            RelaxedSimdSemantics::Native
+        }
+    }
+
+    fn relaxed_laneselect(&mut self, size: OperandSize) -> Result<()> {
+        if self.relaxed_simd_semantics() == RelaxedSimdSemantics::Deterministic {
+            return self.visit_v128_bitselect();
+        }
+        let mask = self.context.pop_to_reg(self.masm, None)?;
+        let op2 = self.context.pop_to_reg(self.masm, None)?;
+        let op1 = self.context.pop_to_reg(self.masm, None)?;
+        let dst = self.context.any_fpr(self.masm)?;
+
+        self.masm
+            .v128_relaxed_laneselect(op1.reg, op2.reg, mask.reg, writable!(dst), size)?;
+
+        self.context
+            .stack
+            .push(TypedReg::new(WasmValType::V128, dst).into());
+        self.context.free_reg(op1);
+        self.context.free_reg(op2);
+        self.context.free_reg(mask);
+        Ok(())
+    }
+
+    fn relaxed_madd(&mut self, kind: V128MaddKind) -> Result<()> {
+        let semantics = self.relaxed_simd_semantics();
+        self.masm.v128_relaxed_madd(
+            &mut self.env,
+            &mut self.context,
+            kind,
+            semantics,
+            |env, cx, masm| {
+                let builtin = match kind.lane_size() {
+                    OperandSize::S32 => env.builtins.fma_f32x4::<M::ABI>()?,
+                    _ => env.builtins.fma_f64x2::<M::ABI>()?,
+                };
+                FnCall::emit::<M>(env, masm, cx, Callee::Builtin(builtin))
+            },
+        )?;
+        let result = self.context.pop_to_reg(self.masm, None)?;
+        self.masm
+            .maybe_canonicalize_v128_nan(writable!(result.into()), kind.lane_size())?;
+        self.context.stack.push(result.into());
+        Ok(())
+    }
 }
 
 impl TryFrom<WasmValType> for OperandSize {